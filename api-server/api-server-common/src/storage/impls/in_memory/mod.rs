@@ -15,10 +15,13 @@
 
 pub mod transactional;
 
+#[cfg(test)]
+mod tests;
+
 use crate::storage::storage_api::{
     block_aux_data::{BlockAuxData, BlockWithExtraData},
     ApiServerStorageError, BlockInfo, CoinOrTokenStatistic, Delegation, FungibleTokenData,
-    LockedUtxo, PoolBlockStats, TransactionInfo, Utxo, UtxoLock, UtxoWithExtraInfo,
+    LockedUtxo, PoolBlockStats, TokenInfo, TransactionInfo, Utxo, UtxoLock, UtxoWithExtraInfo,
 };
 use common::{
     chain::{
@@ -36,12 +39,18 @@ use std::{
     sync::Arc,
 };
 
+use serialization::Encode;
+
 use super::CURRENT_STORAGE_VERSION;
 
+/// The number of entries of each table that are measured to estimate the average entry size
+const MEMORY_USAGE_SAMPLE_SIZE: usize = 32;
+
 #[derive(Debug, Clone)]
 struct ApiServerInMemoryStorage {
     block_table: BTreeMap<Id<Block>, BlockWithExtraData>,
     block_aux_data_table: BTreeMap<Id<Block>, BlockAuxData>,
+    block_transaction_count_table: BTreeMap<Id<Block>, u32>,
     address_balance_table: BTreeMap<String, BTreeMap<(CoinOrTokenId, BlockHeight), Amount>>,
     address_locked_balance_table: BTreeMap<String, BTreeMap<(CoinOrTokenId, BlockHeight), Amount>>,
     address_transactions_table: BTreeMap<String, BTreeMap<BlockHeight, Vec<Id<Transaction>>>>,
@@ -67,6 +76,7 @@ impl ApiServerInMemoryStorage {
         let mut result = Self {
             block_table: BTreeMap::new(),
             block_aux_data_table: BTreeMap::new(),
+            block_transaction_count_table: BTreeMap::new(),
             address_balance_table: BTreeMap::new(),
             address_locked_balance_table: BTreeMap::new(),
             address_transactions_table: BTreeMap::new(),
@@ -99,6 +109,78 @@ impl ApiServerInMemoryStorage {
         Ok(true)
     }
 
+    /// Estimate the memory used by the stored data.
+    ///
+    /// This is an estimate, not an exact measurement: the average entry size of each table is
+    /// computed from a sample of its entries and multiplied by the number of entries. Only the
+    /// entries themselves are counted, not the overhead of the maps; the heap data of blocks and
+    /// transactions is approximated by their encoded size.
+    pub fn memory_usage_bytes(&self) -> usize {
+        let address_size = |address: &String| std::mem::size_of_val(address) + address.len();
+
+        [
+            estimate_table_size(&self.block_table, |id, block| {
+                entry_size(id, block) + block.encoded_size()
+            }),
+            estimate_table_size(&self.block_aux_data_table, entry_size),
+            estimate_table_size(&self.block_transaction_count_table, entry_size),
+            estimate_table_size(&self.address_balance_table, |address, balances| {
+                address_size(address) + nested_table_size(balances)
+            }),
+            estimate_table_size(&self.address_locked_balance_table, |address, balances| {
+                address_size(address) + nested_table_size(balances)
+            }),
+            estimate_table_size(&self.address_transactions_table, |address, txs| {
+                address_size(address)
+                    + std::mem::size_of_val(txs)
+                    + estimate_table_size(txs, |height, tx_ids| {
+                        std::mem::size_of_val(height) + std::mem::size_of_val(tx_ids.as_slice())
+                    })
+            }),
+            estimate_table_size(&self.delegation_table, |id, delegations| {
+                std::mem::size_of_val(id) + nested_table_size(delegations)
+            }),
+            estimate_table_size(&self.main_chain_blocks_table, entry_size),
+            estimate_table_size(&self.pool_data_table, |id, pools| {
+                std::mem::size_of_val(id) + nested_table_size(pools)
+            }),
+            estimate_table_size(&self.transaction_table, |id, tx| {
+                entry_size(id, tx) + tx.1.encoded_size()
+            }),
+            estimate_table_size(&self.utxo_table, |outpoint, utxos| {
+                std::mem::size_of_val(outpoint) + nested_table_size(utxos)
+            }),
+            estimate_table_size(&self.address_utxos, |address, outpoints| {
+                address_size(address)
+                    + std::mem::size_of_val(outpoints)
+                    + outpoints.len() * std::mem::size_of::<UtxoOutPoint>()
+            }),
+            estimate_table_size(&self.locked_utxo_table, |outpoint, utxos| {
+                std::mem::size_of_val(outpoint) + nested_table_size(utxos)
+            }),
+            estimate_table_size(&self.address_locked_utxos, |address, outpoints| {
+                address_size(address)
+                    + std::mem::size_of_val(outpoints)
+                    + outpoints.len() * std::mem::size_of::<UtxoOutPoint>()
+            }),
+            estimate_table_size(&self.fungible_token_issuances, |id, issuances| {
+                std::mem::size_of_val(id) + nested_table_size(issuances)
+            }),
+            estimate_table_size(&self.nft_token_issuances, |id, issuances| {
+                std::mem::size_of_val(id) + nested_table_size(issuances)
+            }),
+            estimate_table_size(&self.statistics, |statistic, by_coin| {
+                std::mem::size_of_val(statistic)
+                    + std::mem::size_of_val(by_coin)
+                    + estimate_table_size(by_coin, |coin_or_token_id, amounts| {
+                        std::mem::size_of_val(coin_or_token_id) + nested_table_size(amounts)
+                    })
+            }),
+        ]
+        .into_iter()
+        .sum()
+    }
+
     fn get_address_balance(
         &self,
         address: &str,
@@ -143,6 +225,35 @@ impl ApiServerInMemoryStorage {
             }))
     }
 
+    fn get_address_transactions_page(
+        &self,
+        address: &str,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<Id<Transaction>>, ApiServerStorageError> {
+        Ok(self
+            .address_transactions_table
+            .get(address)
+            .map_or_else(Vec::new, |transactions| {
+                transactions
+                    .values()
+                    .rev()
+                    .flat_map(|txs| txs.iter())
+                    .skip(offset as usize)
+                    .take(len as usize)
+                    .cloned()
+                    .collect()
+            }))
+    }
+
+    fn get_address_transaction_count(&self, address: &str) -> Result<u64, ApiServerStorageError> {
+        Ok(
+            self.address_transactions_table.get(address).map_or(0, |transactions| {
+                transactions.values().map(|txs| txs.len() as u64).sum()
+            }),
+        )
+    }
+
     fn get_block(&self, block_id: Id<Block>) -> Result<Option<BlockInfo>, ApiServerStorageError> {
         let block_result = self.block_table.get(&block_id);
         let block = match block_result {
@@ -254,6 +365,13 @@ impl ApiServerInMemoryStorage {
         Ok(Some(*block_aux_data))
     }
 
+    fn get_block_transaction_count(
+        &self,
+        block_id: Id<Block>,
+    ) -> Result<Option<u32>, ApiServerStorageError> {
+        Ok(self.block_transaction_count_table.get(&block_id).copied())
+    }
+
     fn get_block_range_from_time_range(
         &self,
         time_range: (BlockTimestamp, BlockTimestamp),
@@ -520,6 +638,15 @@ impl ApiServerInMemoryStorage {
             .map(|by_height| by_height.values().last().cloned().expect("not empty")))
     }
 
+    fn get_token_info(
+        &self,
+        token_id: TokenId,
+    ) -> Result<Option<TokenInfo>, ApiServerStorageError> {
+        Ok(self
+            .get_fungible_token_issuance(token_id)?
+            .map(|data| data.into_token_info(token_id)))
+    }
+
     fn get_nft_token_issuance(
         &self,
         token_id: TokenId,
@@ -647,6 +774,7 @@ impl ApiServerInMemoryStorage {
     ) -> Result<(), ApiServerStorageError> {
         self.block_table.clear();
         self.block_aux_data_table.clear();
+        self.block_transaction_count_table.clear();
         self.address_balance_table.clear();
         self.address_locked_balance_table.clear();
         self.address_transactions_table.clear();
@@ -821,6 +949,15 @@ impl ApiServerInMemoryStorage {
         Ok(())
     }
 
+    fn set_block_transaction_count(
+        &mut self,
+        block_id: Id<Block>,
+        transaction_count: u32,
+    ) -> Result<(), ApiServerStorageError> {
+        self.block_transaction_count_table.insert(block_id, transaction_count);
+        Ok(())
+    }
+
     fn del_main_chain_blocks_above_height(
         &mut self,
         block_height: BlockHeight,
@@ -986,3 +1123,28 @@ impl ApiServerInMemoryStorage {
         Ok(())
     }
 }
+
+/// The size of an entry, not counting any heap data it owns
+fn entry_size<K, V>(key: &K, value: &V) -> usize {
+    std::mem::size_of_val(key) + std::mem::size_of_val(value)
+}
+
+/// The estimated size of a table stored in an entry of another table
+fn nested_table_size<K, V>(table: &BTreeMap<K, V>) -> usize {
+    std::mem::size_of_val(table) + estimate_table_size(table, entry_size)
+}
+
+/// Estimate the memory used by the entries of `table` by measuring a sample of them with
+/// `entry_size` and extrapolating the average to the whole table
+fn estimate_table_size<K, V>(
+    table: &BTreeMap<K, V>,
+    entry_size: impl Fn(&K, &V) -> usize,
+) -> usize {
+    let sample_len = std::cmp::min(table.len(), MEMORY_USAGE_SAMPLE_SIZE);
+    if sample_len == 0 {
+        return 0;
+    }
+
+    let sample_size: usize = table.iter().take(sample_len).map(|(k, v)| entry_size(k, v)).sum();
+    sample_size * table.len() / sample_len
+}
@@ -0,0 +1,148 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    chain::{
+        block::{BlockReward, ConsensusData},
+        config::create_unit_test_config,
+        output_value::OutputValue,
+        signature::inputsig::InputWitness,
+        OutPointSourceId, SignedTransaction, TxInput, TxOutput,
+    },
+    primitives::{Idable, H256},
+};
+
+use crate::storage::storage_api::{TxAdditionalInfo, UtxoWithExtraInfo};
+
+use super::*;
+
+const RECORD_COUNT: u64 = 100;
+
+const ADDRESS: &str = "address";
+
+fn make_storage() -> ApiServerInMemoryStorage {
+    ApiServerInMemoryStorage::new(&create_unit_test_config())
+}
+
+fn make_transaction(index: u64) -> SignedTransaction {
+    let input = TxInput::from_utxo(
+        OutPointSourceId::Transaction(Id::new(H256::from_low_u64_be(index))),
+        0,
+    );
+    let output = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(index.into())),
+        Destination::AnyoneCanSpend,
+    );
+    SignedTransaction::new(
+        Transaction::new(0, vec![input], vec![output]).unwrap(),
+        vec![InputWitness::NoSignature(None)],
+    )
+    .unwrap()
+}
+
+/// Add `RECORD_COUNT` records twice and check that the estimate grows by the same amount both
+/// times, within a 20% tolerance
+fn check_proportional_growth(add_record: impl Fn(&mut ApiServerInMemoryStorage, u64)) {
+    let mut storage = make_storage();
+    let initial_usage = storage.memory_usage_bytes();
+
+    for index in 0..RECORD_COUNT {
+        add_record(&mut storage, index);
+    }
+    let first_growth = storage.memory_usage_bytes() - initial_usage;
+
+    for index in RECORD_COUNT..2 * RECORD_COUNT {
+        add_record(&mut storage, index);
+    }
+    let total_growth = storage.memory_usage_bytes() - initial_usage;
+
+    assert!(first_growth > 0);
+    let expected_growth = 2 * first_growth;
+    assert!(
+        total_growth.abs_diff(expected_growth) * 5 <= expected_growth,
+        "estimate grew by {total_growth}, expected about {expected_growth}"
+    );
+}
+
+#[test]
+fn memory_usage_blocks() {
+    let chain_config = create_unit_test_config();
+    check_proportional_growth(|storage, index| {
+        let block = Block::new(
+            vec![make_transaction(index)],
+            chain_config.genesis_block_id(),
+            BlockTimestamp::from_int_seconds(index),
+            ConsensusData::None,
+            BlockReward::new(vec![]),
+        )
+        .unwrap();
+        let block = BlockWithExtraData {
+            block,
+            tx_additional_infos: vec![],
+        };
+        storage
+            .set_mainchain_block(block.block.get_id(), BlockHeight::new(index + 1), &block)
+            .unwrap();
+    });
+}
+
+#[test]
+fn memory_usage_transactions() {
+    check_proportional_growth(|storage, index| {
+        let tx = make_transaction(index);
+        let tx_info = TransactionInfo {
+            tx: tx.clone(),
+            additinal_info: TxAdditionalInfo {
+                fee: Amount::from_atoms(index.into()),
+                input_utxos: vec![None],
+                token_decimals: BTreeMap::new(),
+            },
+        };
+        storage.set_transaction(tx.transaction().get_id(), None, &tx_info).unwrap();
+    });
+}
+
+#[test]
+fn memory_usage_token_balances() {
+    check_proportional_growth(|storage, index| {
+        let token_id = TokenId::new(H256::from_low_u64_be(index));
+        storage
+            .set_address_balance_at_height(
+                &format!("{ADDRESS}{index}"),
+                Amount::from_atoms(index.into()),
+                CoinOrTokenId::TokenId(token_id),
+                BlockHeight::new(index),
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn memory_usage_utxos() {
+    check_proportional_growth(|storage, index| {
+        let outpoint = UtxoOutPoint::new(
+            OutPointSourceId::Transaction(Id::new(H256::from_low_u64_be(index))),
+            0,
+        );
+        let output = TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(index.into())),
+            Destination::AnyoneCanSpend,
+        );
+        let utxo = Utxo::new_with_info(UtxoWithExtraInfo::new(output, None), false);
+        storage
+            .set_utxo_at_height(outpoint, utxo, ADDRESS, BlockHeight::new(index))
+            .unwrap();
+    });
+}
@@ -48,13 +48,16 @@ impl<'t> ApiServerTransactionRo for ApiServerInMemoryStorageTransactionalRo<'t>
 
 pub struct ApiServerInMemoryStorageTransactionalRw<'t> {
     transaction: RwLockWriteGuard<'t, ApiServerInMemoryStorage>,
-    initial_data_before_tx: ApiServerInMemoryStorage,
+    // Note: This is None once the transaction is committed, so that Drop knows whether
+    //       the changes have to be reverted
+    initial_data_before_tx: Option<ApiServerInMemoryStorage>,
 }
 
 impl<'t> ApiServerInMemoryStorageTransactionalRw<'t> {
-    async fn new(storage: &'t mut TransactionalApiServerInMemoryStorage) -> Self {
+    /// Begin a transaction, remembering the data as it was before it started
+    async fn begin(storage: &'t mut TransactionalApiServerInMemoryStorage) -> Self {
         let transaction = storage.tx_rw().await;
-        let initial_data_before_tx = transaction.clone();
+        let initial_data_before_tx = Some(transaction.clone());
         Self {
             transaction,
             initial_data_before_tx,
@@ -62,15 +65,25 @@ impl<'t> ApiServerInMemoryStorageTransactionalRw<'t> {
     }
 }
 
+impl<'t> Drop for ApiServerInMemoryStorageTransactionalRw<'t> {
+    fn drop(&mut self) {
+        // A transaction that was not committed is rolled back, so that none of its writes are
+        // visible, no matter where it was abandoned
+        if let Some(initial_data_before_tx) = self.initial_data_before_tx.take() {
+            *self.transaction = initial_data_before_tx;
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl<'t> ApiServerTransactionRw for ApiServerInMemoryStorageTransactionalRw<'t> {
-    async fn commit(self) -> Result<(), crate::storage::storage_api::ApiServerStorageError> {
+    async fn commit(mut self) -> Result<(), crate::storage::storage_api::ApiServerStorageError> {
+        self.initial_data_before_tx = None;
         Ok(())
     }
 
-    async fn rollback(mut self) -> Result<(), crate::storage::storage_api::ApiServerStorageError> {
-        // We restore the original data that was there when the transaction started
-        std::mem::swap(&mut *self.transaction, &mut self.initial_data_before_tx);
+    async fn rollback(self) -> Result<(), crate::storage::storage_api::ApiServerStorageError> {
+        // The original data that was there when the transaction started is restored on drop
         Ok(())
     }
 }
@@ -99,8 +112,23 @@ impl TransactionalApiServerInMemoryStorage {
             storage: self.storage.read().await.clone().into(),
         }
     }
+
+    /// Copy the whole state of the storage, so that it can be restored later
+    pub async fn snapshot(&self) -> ApiServerInMemoryStorageSnapshot {
+        ApiServerInMemoryStorageSnapshot(self.storage.read().await.clone())
+    }
+
+    /// Replace the whole state of the storage with the snapshot
+    pub async fn restore(&mut self, snapshot: ApiServerInMemoryStorageSnapshot) {
+        *self.tx_rw().await = snapshot.0;
+    }
 }
 
+/// The state of [TransactionalApiServerInMemoryStorage] at some point, e.g. a chain built once
+/// and shared by multiple tests
+#[derive(Debug, Clone)]
+pub struct ApiServerInMemoryStorageSnapshot(ApiServerInMemoryStorage);
+
 #[async_trait::async_trait]
 impl<'tx> Transactional<'tx> for TransactionalApiServerInMemoryStorage {
     type TransactionRo = ApiServerInMemoryStorageTransactionalRo<'tx>;
@@ -116,7 +144,7 @@ impl<'tx> Transactional<'tx> for TransactionalApiServerInMemoryStorage {
     async fn transaction_rw<'db: 'tx>(
         &'db mut self,
     ) -> Result<Self::TransactionRw, ApiServerStorageError> {
-        Ok(ApiServerInMemoryStorageTransactionalRw::new(self).await)
+        Ok(ApiServerInMemoryStorageTransactionalRw::begin(self).await)
     }
 }
 
@@ -27,8 +27,8 @@ use pos_accounting::PoolData;
 
 use crate::storage::storage_api::{
     block_aux_data::BlockAuxData, ApiServerStorageError, ApiServerStorageRead, BlockInfo,
-    CoinOrTokenStatistic, Delegation, FungibleTokenData, PoolBlockStats, TransactionInfo, Utxo,
-    UtxoWithExtraInfo,
+    CoinOrTokenStatistic, Delegation, FungibleTokenData, PoolBlockStats, TokenInfo,
+    TransactionInfo, Utxo, UtxoWithExtraInfo,
 };
 
 use super::ApiServerInMemoryStorageTransactionalRo;
@@ -62,6 +62,22 @@ impl<'t> ApiServerStorageRead for ApiServerInMemoryStorageTransactionalRo<'t> {
         self.transaction.get_address_transactions(address)
     }
 
+    async fn get_address_transactions_page(
+        &self,
+        address: &str,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<Id<Transaction>>, ApiServerStorageError> {
+        self.transaction.get_address_transactions_page(address, len, offset)
+    }
+
+    async fn get_address_transaction_count(
+        &self,
+        address: &str,
+    ) -> Result<u64, ApiServerStorageError> {
+        self.transaction.get_address_transaction_count(address)
+    }
+
     async fn get_block(
         &self,
         block_id: Id<Block>,
@@ -140,6 +156,10 @@ impl<'t> ApiServerStorageRead for ApiServerInMemoryStorageTransactionalRo<'t> {
         Ok(Some(self.transaction.get_storage_version()?))
     }
 
+    fn estimate_memory_usage(&self) -> usize {
+        self.transaction.memory_usage_bytes()
+    }
+
     async fn get_latest_blocktimestamps(
         &self,
     ) -> Result<Vec<BlockTimestamp>, ApiServerStorageError> {
@@ -157,6 +177,13 @@ impl<'t> ApiServerStorageRead for ApiServerInMemoryStorageTransactionalRo<'t> {
         self.transaction.get_block_aux_data(block_id)
     }
 
+    async fn get_block_transaction_count(
+        &self,
+        block_id: Id<Block>,
+    ) -> Result<Option<u32>, ApiServerStorageError> {
+        self.transaction.get_block_transaction_count(block_id)
+    }
+
     async fn get_main_chain_block_id(
         &self,
         block_height: BlockHeight,
@@ -214,6 +241,13 @@ impl<'t> ApiServerStorageRead for ApiServerInMemoryStorageTransactionalRo<'t> {
         self.transaction.get_fungible_token_issuance(token_id)
     }
 
+    async fn get_token_info(
+        &self,
+        token_id: TokenId,
+    ) -> Result<Option<TokenInfo>, ApiServerStorageError> {
+        self.transaction.get_token_info(token_id)
+    }
+
     async fn get_nft_token_issuance(
         &self,
         token_id: TokenId,
@@ -28,7 +28,7 @@ use pos_accounting::PoolData;
 use crate::storage::storage_api::{
     block_aux_data::{BlockAuxData, BlockWithExtraData},
     ApiServerStorageError, ApiServerStorageRead, ApiServerStorageWrite, BlockInfo,
-    CoinOrTokenStatistic, Delegation, FungibleTokenData, LockedUtxo, PoolBlockStats,
+    CoinOrTokenStatistic, Delegation, FungibleTokenData, LockedUtxo, PoolBlockStats, TokenInfo,
     TransactionInfo, Utxo, UtxoWithExtraInfo,
 };
 
@@ -140,6 +140,14 @@ impl<'t> ApiServerStorageWrite for ApiServerInMemoryStorageTransactionalRw<'t> {
         self.transaction.set_block_aux_data(block_id, block_aux_data)
     }
 
+    async fn set_block_transaction_count(
+        &mut self,
+        block_id: Id<Block>,
+        transaction_count: u32,
+    ) -> Result<(), ApiServerStorageError> {
+        self.transaction.set_block_transaction_count(block_id, transaction_count)
+    }
+
     async fn del_main_chain_blocks_above_height(
         &mut self,
         block_height: BlockHeight,
@@ -266,6 +274,10 @@ impl<'t> ApiServerStorageRead for ApiServerInMemoryStorageTransactionalRw<'t> {
         Ok(Some(self.transaction.get_storage_version()?))
     }
 
+    fn estimate_memory_usage(&self) -> usize {
+        self.transaction.memory_usage_bytes()
+    }
+
     async fn get_address_balance(
         &self,
         address: &str,
@@ -289,6 +301,22 @@ impl<'t> ApiServerStorageRead for ApiServerInMemoryStorageTransactionalRw<'t> {
         self.transaction.get_address_transactions(address)
     }
 
+    async fn get_address_transactions_page(
+        &self,
+        address: &str,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<Id<Transaction>>, ApiServerStorageError> {
+        self.transaction.get_address_transactions_page(address, len, offset)
+    }
+
+    async fn get_address_transaction_count(
+        &self,
+        address: &str,
+    ) -> Result<u64, ApiServerStorageError> {
+        self.transaction.get_address_transaction_count(address)
+    }
+
     async fn get_latest_blocktimestamps(
         &self,
     ) -> Result<Vec<BlockTimestamp>, ApiServerStorageError> {
@@ -313,6 +341,13 @@ impl<'t> ApiServerStorageRead for ApiServerInMemoryStorageTransactionalRw<'t> {
         self.transaction.get_block_aux_data(block_id)
     }
 
+    async fn get_block_transaction_count(
+        &self,
+        block_id: Id<Block>,
+    ) -> Result<Option<u32>, ApiServerStorageError> {
+        self.transaction.get_block_transaction_count(block_id)
+    }
+
     async fn get_block_range_from_time_range(
         &self,
         time_range: (BlockTimestamp, BlockTimestamp),
@@ -437,6 +472,13 @@ impl<'t> ApiServerStorageRead for ApiServerInMemoryStorageTransactionalRw<'t> {
         self.transaction.get_fungible_token_issuance(token_id)
     }
 
+    async fn get_token_info(
+        &self,
+        token_id: TokenId,
+    ) -> Result<Option<TokenInfo>, ApiServerStorageError> {
+        self.transaction.get_token_info(token_id)
+    }
+
     async fn get_nft_token_issuance(
         &self,
         token_id: TokenId,
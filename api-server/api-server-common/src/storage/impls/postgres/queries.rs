@@ -39,7 +39,7 @@ use crate::storage::{
     storage_api::{
         block_aux_data::{BlockAuxData, BlockWithExtraData},
         ApiServerStorageError, BlockInfo, CoinOrTokenStatistic, Delegation, FungibleTokenData,
-        LockedUtxo, PoolBlockStats, TransactionInfo, Utxo, UtxoWithExtraInfo,
+        LockedUtxo, PoolBlockStats, TokenInfo, TransactionInfo, Utxo, UtxoWithExtraInfo,
     },
 };
 
@@ -324,6 +324,65 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
         Ok(transaction_ids)
     }
 
+    pub async fn get_address_transactions_page(
+        &self,
+        address: &str,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<Id<Transaction>>, ApiServerStorageError> {
+        let len = len as i64;
+        let offset = offset as i64;
+        let rows = self
+            .tx
+            .query(
+                r#"
+                    SELECT transaction_id
+                    FROM ml.address_transactions
+                    WHERE address = $1
+                    ORDER BY block_height DESC, transaction_id
+                    OFFSET $2
+                    LIMIT $3;
+                "#,
+                &[&address, &offset, &len],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                let transaction_id: Vec<u8> = row.get(0);
+                Id::<Transaction>::decode_all(&mut transaction_id.as_slice()).map_err(|e| {
+                    ApiServerStorageError::DeserializationError(format!(
+                        "Transaction id deserialization failed: {}",
+                        e
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    pub async fn get_address_transaction_count(
+        &self,
+        address: &str,
+    ) -> Result<u64, ApiServerStorageError> {
+        let row = self
+            .tx
+            .query_one(
+                r#"
+                    SELECT COUNT(*)
+                    FROM ml.address_transactions
+                    WHERE address = $1;
+                "#,
+                &[&address],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        let count: i64 = row.get(0);
+
+        Ok(count as u64)
+    }
+
     pub async fn del_address_transactions_above_height(
         &mut self,
         block_height: BlockHeight,
@@ -490,7 +549,8 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
                 block_id bytea PRIMARY KEY,
                 block_height bigint,
                 block_timestamp bigint NOT NULL,
-                block_data bytea NOT NULL
+                block_data bytea NOT NULL,
+                transaction_count integer
             );",
         )
         .await?;
@@ -1794,6 +1854,16 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
         Ok(Some(issuance))
     }
 
+    pub async fn get_token_info(
+        &self,
+        token_id: TokenId,
+    ) -> Result<Option<TokenInfo>, ApiServerStorageError> {
+        Ok(self
+            .get_fungible_token_issuance(token_id)
+            .await?
+            .map(|data| data.into_token_info(token_id)))
+    }
+
     pub async fn get_token_num_decimals(
         &self,
         token_id: TokenId,
@@ -2124,6 +2194,49 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
         Ok(Some(block_aux_data))
     }
 
+    pub async fn get_block_transaction_count(
+        &mut self,
+        block_id: Id<Block>,
+    ) -> Result<Option<u32>, ApiServerStorageError> {
+        let row = self
+            .tx
+            .query_opt(
+                "SELECT transaction_count FROM ml.blocks WHERE block_id = $1;",
+                &[&block_id.encode()],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        let transaction_count: Option<i32> = match row {
+            Some(row) => row.get(0),
+            None => return Ok(None),
+        };
+
+        Ok(transaction_count.map(|count| count as u32))
+    }
+
+    pub async fn set_block_transaction_count(
+        &mut self,
+        block_id: Id<Block>,
+        transaction_count: u32,
+    ) -> Result<(), ApiServerStorageError> {
+        logging::log::debug!(
+            "Setting the transaction count of block {} to {}",
+            block_id,
+            transaction_count
+        );
+
+        self.tx
+            .execute(
+                "UPDATE ml.blocks SET transaction_count = $2 WHERE block_id = $1;",
+                &[&block_id.encode(), &(transaction_count as i32)],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        Ok(())
+    }
+
     pub async fn set_block_aux_data(
         &mut self,
         block_id: Id<Block>,
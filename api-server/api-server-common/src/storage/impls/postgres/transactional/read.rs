@@ -26,8 +26,8 @@ use crate::storage::{
     impls::postgres::queries::QueryFromConnection,
     storage_api::{
         block_aux_data::BlockAuxData, ApiServerStorageError, ApiServerStorageRead, BlockInfo,
-        CoinOrTokenStatistic, Delegation, FungibleTokenData, PoolBlockStats, TransactionInfo, Utxo,
-        UtxoWithExtraInfo,
+        CoinOrTokenStatistic, Delegation, FungibleTokenData, PoolBlockStats, TokenInfo,
+        TransactionInfo, Utxo, UtxoWithExtraInfo,
     },
 };
 use std::collections::BTreeMap;
@@ -53,6 +53,11 @@ impl<'a> ApiServerStorageRead for ApiServerPostgresTransactionalRo<'a> {
         Ok(res)
     }
 
+    fn estimate_memory_usage(&self) -> usize {
+        // The data is kept by the database server
+        0
+    }
+
     async fn get_address_balance(
         &self,
         address: &str,
@@ -85,6 +90,28 @@ impl<'a> ApiServerStorageRead for ApiServerPostgresTransactionalRo<'a> {
         Ok(res)
     }
 
+    async fn get_address_transactions_page(
+        &self,
+        address: &str,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<Id<Transaction>>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_address_transactions_page(address, len, offset).await?;
+
+        Ok(res)
+    }
+
+    async fn get_address_transaction_count(
+        &self,
+        address: &str,
+    ) -> Result<u64, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_address_transaction_count(address).await?;
+
+        Ok(res)
+    }
+
     async fn get_latest_blocktimestamps(
         &self,
     ) -> Result<Vec<BlockTimestamp>, ApiServerStorageError> {
@@ -124,6 +151,16 @@ impl<'a> ApiServerStorageRead for ApiServerPostgresTransactionalRo<'a> {
         Ok(res)
     }
 
+    async fn get_block_transaction_count(
+        &self,
+        block_id: Id<common::chain::Block>,
+    ) -> Result<Option<u32>, ApiServerStorageError> {
+        let mut conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_block_transaction_count(block_id).await?;
+
+        Ok(res)
+    }
+
     async fn get_block_range_from_time_range(
         &self,
         time_range: (BlockTimestamp, BlockTimestamp),
@@ -302,6 +339,16 @@ impl<'a> ApiServerStorageRead for ApiServerPostgresTransactionalRo<'a> {
         Ok(res)
     }
 
+    async fn get_token_info(
+        &self,
+        token_id: TokenId,
+    ) -> Result<Option<TokenInfo>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_token_info(token_id).await?;
+
+        Ok(res)
+    }
+
     async fn get_nft_token_issuance(
         &self,
         token_id: TokenId,
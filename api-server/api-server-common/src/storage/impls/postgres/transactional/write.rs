@@ -30,7 +30,7 @@ use crate::storage::{
     storage_api::{
         block_aux_data::{BlockAuxData, BlockWithExtraData},
         ApiServerStorageError, ApiServerStorageRead, ApiServerStorageWrite, BlockInfo,
-        CoinOrTokenStatistic, Delegation, FungibleTokenData, LockedUtxo, PoolBlockStats,
+        CoinOrTokenStatistic, Delegation, FungibleTokenData, LockedUtxo, PoolBlockStats, TokenInfo,
         TransactionInfo, Utxo, UtxoWithExtraInfo,
     },
 };
@@ -168,6 +168,17 @@ impl<'a> ApiServerStorageWrite for ApiServerPostgresTransactionalRw<'a> {
         Ok(())
     }
 
+    async fn set_block_transaction_count(
+        &mut self,
+        block_id: Id<Block>,
+        transaction_count: u32,
+    ) -> Result<(), ApiServerStorageError> {
+        let mut conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        conn.set_block_transaction_count(block_id, transaction_count).await?;
+
+        Ok(())
+    }
+
     async fn del_main_chain_blocks_above_height(
         &mut self,
         block_height: BlockHeight,
@@ -341,6 +352,11 @@ impl<'a> ApiServerStorageRead for ApiServerPostgresTransactionalRw<'a> {
         Ok(res)
     }
 
+    fn estimate_memory_usage(&self) -> usize {
+        // The data is kept by the database server
+        0
+    }
+
     async fn get_address_balance(
         &self,
         address: &str,
@@ -373,6 +389,28 @@ impl<'a> ApiServerStorageRead for ApiServerPostgresTransactionalRw<'a> {
         Ok(res)
     }
 
+    async fn get_address_transactions_page(
+        &self,
+        address: &str,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<Id<Transaction>>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_address_transactions_page(address, len, offset).await?;
+
+        Ok(res)
+    }
+
+    async fn get_address_transaction_count(
+        &self,
+        address: &str,
+    ) -> Result<u64, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_address_transaction_count(address).await?;
+
+        Ok(res)
+    }
+
     async fn get_latest_blocktimestamps(
         &self,
     ) -> Result<Vec<BlockTimestamp>, ApiServerStorageError> {
@@ -409,6 +447,16 @@ impl<'a> ApiServerStorageRead for ApiServerPostgresTransactionalRw<'a> {
         Ok(res)
     }
 
+    async fn get_block_transaction_count(
+        &self,
+        block_id: Id<Block>,
+    ) -> Result<Option<u32>, ApiServerStorageError> {
+        let mut conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_block_transaction_count(block_id).await?;
+
+        Ok(res)
+    }
+
     async fn get_block_range_from_time_range(
         &self,
         time_range: (BlockTimestamp, BlockTimestamp),
@@ -586,6 +634,16 @@ impl<'a> ApiServerStorageRead for ApiServerPostgresTransactionalRw<'a> {
         Ok(res)
     }
 
+    async fn get_token_info(
+        &self,
+        token_id: TokenId,
+    ) -> Result<Option<TokenInfo>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_token_info(token_id).await?;
+
+        Ok(res)
+    }
+
     async fn get_nft_token_issuance(
         &self,
         token_id: TokenId,
@@ -66,6 +66,10 @@ pub enum ApiServerStorageError {
     AddressableError,
     #[error("Block timestamp to high {0}")]
     TimestampToHigh(BlockTimestamp),
+    #[error("Amount overflow: {0}")]
+    AmountOverflow(String),
+    #[error("Amount underflow: {0}")]
+    AmountUnderflow(String),
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -309,6 +313,11 @@ pub struct FungibleTokenData {
     pub is_locked: bool,
     pub frozen: IsTokenFrozen,
     pub authority: Destination,
+    pub minted_amount: Amount,
+    pub burned_amount: Amount,
+    pub holder_count: u64,
+    pub issuance_block_height: BlockHeight,
+    pub issuance_tx_id: Id<Transaction>,
 }
 
 impl FungibleTokenData {
@@ -326,14 +335,73 @@ impl FungibleTokenData {
         }
     }
 
-    pub fn mint_tokens(mut self, amount: Amount) -> Self {
-        self.circulating_supply = (self.circulating_supply + amount).expect("no overflow");
-        self
+    pub fn into_token_info(self, token_id: TokenId) -> TokenInfo {
+        TokenInfo {
+            token_id,
+            ticker: self.token_ticker,
+            decimals: self.number_of_decimals,
+            metadata_uri: self.metadata_uri,
+            total_supply: self.total_supply,
+            minted_amount: self.minted_amount,
+            burned_amount: self.burned_amount,
+            circulating_supply: self.circulating_supply,
+            holder_count: self.holder_count,
+            issuance_block_height: self.issuance_block_height,
+            issuance_tx_id: self.issuance_tx_id,
+            frozen: self.frozen,
+            supply_locked: self.is_locked,
+            authority: self.authority,
+        }
     }
 
-    pub fn unmint_tokens(mut self, amount: Amount) -> Self {
-        self.circulating_supply = (self.circulating_supply - amount).expect("no underflow");
-        self
+    pub fn mint_tokens(mut self, amount: Amount) -> Result<Self, ApiServerStorageError> {
+        self.circulating_supply = (self.circulating_supply + amount).ok_or_else(|| {
+            ApiServerStorageError::AmountOverflow(format!(
+                "token circulating supply {:?} + {amount:?}",
+                self.circulating_supply
+            ))
+        })?;
+        self.minted_amount = (self.minted_amount + amount).ok_or_else(|| {
+            ApiServerStorageError::AmountOverflow(format!(
+                "token minted amount {:?} + {amount:?}",
+                self.minted_amount
+            ))
+        })?;
+        Ok(self)
+    }
+
+    pub fn unmint_tokens(mut self, amount: Amount) -> Result<Self, ApiServerStorageError> {
+        self.circulating_supply = (self.circulating_supply - amount).ok_or_else(|| {
+            ApiServerStorageError::AmountUnderflow(format!(
+                "token circulating supply {:?} - {amount:?}",
+                self.circulating_supply
+            ))
+        })?;
+        Ok(self)
+    }
+
+    pub fn burn_tokens(mut self, amount: Amount) -> Result<Self, ApiServerStorageError> {
+        self.burned_amount = (self.burned_amount + amount).ok_or_else(|| {
+            ApiServerStorageError::AmountOverflow(format!(
+                "token burned amount {:?} + {amount:?}",
+                self.burned_amount
+            ))
+        })?;
+        Ok(self)
+    }
+
+    pub fn add_holder(mut self) -> Result<Self, ApiServerStorageError> {
+        self.holder_count = self.holder_count.checked_add(1).ok_or_else(|| {
+            ApiServerStorageError::AmountOverflow("token holder count".to_owned())
+        })?;
+        Ok(self)
+    }
+
+    pub fn remove_holder(mut self) -> Result<Self, ApiServerStorageError> {
+        self.holder_count = self.holder_count.checked_sub(1).ok_or_else(|| {
+            ApiServerStorageError::AmountUnderflow("token holder count".to_owned())
+        })?;
+        Ok(self)
     }
 
     pub fn freeze(mut self, is_token_unfreezable: IsTokenUnfreezable) -> Self {
@@ -362,6 +430,29 @@ impl FungibleTokenData {
     }
 }
 
+/// Aggregated information about a fungible token, as maintained by the scanner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenInfo {
+    pub token_id: TokenId,
+    pub ticker: Vec<u8>,
+    pub decimals: u8,
+    pub metadata_uri: Vec<u8>,
+    pub total_supply: TokenTotalSupply,
+    /// Total amount of tokens ever minted
+    pub minted_amount: Amount,
+    /// Total amount of tokens sent to burn outputs, including the unminted ones
+    pub burned_amount: Amount,
+    /// Minted minus unminted, same as the node reports it
+    pub circulating_supply: Amount,
+    /// Number of addresses with a non-zero spendable balance of the token
+    pub holder_count: u64,
+    pub issuance_block_height: BlockHeight,
+    pub issuance_tx_id: Id<Transaction>,
+    pub frozen: IsTokenFrozen,
+    pub supply_locked: bool,
+    pub authority: Destination,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
 pub struct TxAdditionalInfo {
     pub fee: Amount,
@@ -391,6 +482,10 @@ pub trait ApiServerStorageRead: Sync {
 
     async fn get_storage_version(&self) -> Result<Option<u32>, ApiServerStorageError>;
 
+    /// An estimate of the memory used by the stored data, for capacity planning.
+    /// Backends that don't keep the data in the memory of this process return 0.
+    fn estimate_memory_usage(&self) -> usize;
+
     async fn get_address_balance(
         &self,
         address: &str,
@@ -408,6 +503,20 @@ pub trait ApiServerStorageRead: Sync {
         address: &str,
     ) -> Result<Vec<Id<Transaction>>, ApiServerStorageError>;
 
+    /// Get up to `len` transactions of the address starting from `offset`, the newest first.
+    async fn get_address_transactions_page(
+        &self,
+        address: &str,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<Id<Transaction>>, ApiServerStorageError>;
+
+    /// Get the total number of transactions of the address.
+    async fn get_address_transaction_count(
+        &self,
+        address: &str,
+    ) -> Result<u64, ApiServerStorageError>;
+
     async fn get_best_block(&self) -> Result<BlockAuxData, ApiServerStorageError>;
 
     async fn get_latest_blocktimestamps(
@@ -424,6 +533,12 @@ pub trait ApiServerStorageRead: Sync {
         block_id: Id<Block>,
     ) -> Result<Option<BlockAuxData>, ApiServerStorageError>;
 
+    /// Get the number of transactions in a block without loading the block itself
+    async fn get_block_transaction_count(
+        &self,
+        block_id: Id<Block>,
+    ) -> Result<Option<u32>, ApiServerStorageError>;
+
     async fn get_block_range_from_time_range(
         &self,
         time_range: (BlockTimestamp, BlockTimestamp),
@@ -514,6 +629,11 @@ pub trait ApiServerStorageRead: Sync {
         token_id: TokenId,
     ) -> Result<Option<FungibleTokenData>, ApiServerStorageError>;
 
+    async fn get_token_info(
+        &self,
+        token_id: TokenId,
+    ) -> Result<Option<TokenInfo>, ApiServerStorageError>;
+
     async fn get_nft_token_issuance(
         &self,
         token_id: TokenId,
@@ -621,6 +741,12 @@ pub trait ApiServerStorageWrite: ApiServerStorageRead {
         block_aux_data: &BlockAuxData,
     ) -> Result<(), ApiServerStorageError>;
 
+    async fn set_block_transaction_count(
+        &mut self,
+        block_id: Id<Block>,
+        transaction_count: u32,
+    ) -> Result<(), ApiServerStorageError>;
+
     async fn del_main_chain_blocks_above_height(
         &mut self,
         block_height: BlockHeight,
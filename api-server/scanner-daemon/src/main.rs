@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use api_blockchain_scanner_lib::blockchain_state::BlockchainState;
 use api_server_common::storage::{
@@ -31,6 +31,9 @@ use rpc::RpcAuthData;
 use utils::{cookie::COOKIE_FILENAME, default_data_dir::default_data_dir_for_chain};
 mod config;
 
+/// The delay before syncing again after a sync error
+const SYNC_ERROR_DELAY: Duration = Duration::from_secs(10);
+
 pub async fn make_postgres_storage(
     postgres_host: String,
     postgres_port: u16,
@@ -118,14 +121,31 @@ pub async fn run<S: ApiServerStorage>(
         }
     };
 
+    let mut node_unavailable = false;
     loop {
         let sync_result =
             api_blockchain_scanner_lib::sync::sync_once(chain_config, rpc_client, &mut local_block)
                 .await;
 
         match sync_result {
-            Ok(_) => (),
-            Err(err) => logging::log::error!("Scanner sync error: {}", err),
+            Ok(_) => {
+                if node_unavailable {
+                    logging::log::info!("Node is available again, resuming scanner sync");
+                    node_unavailable = false;
+                }
+            }
+            Err(err) => {
+                // Report an unreachable node once and keep waiting for it quietly
+                if err.is_remote_node_unavailable() {
+                    if !node_unavailable {
+                        logging::log::warn!("Waiting for the node to become available: {}", err);
+                        node_unavailable = true;
+                    }
+                } else {
+                    logging::log::error!("Scanner sync error: {}", err);
+                }
+                tokio::time::sleep(SYNC_ERROR_DELAY).await;
+            }
         }
     }
 }
@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use api_server_common::storage::storage_api::Delegation;
+use api_server_common::storage::storage_api::{ApiServerStorageError, Delegation};
 use common::chain::{DelegationId, Destination, PoolId, UtxoOutPoint};
 use common::primitives::Amount;
 use pos_accounting::{
@@ -35,14 +35,14 @@ impl PoSAdapter {
         pool_id: PoolId,
         pool_data: PoolData,
         delegations: &BTreeMap<DelegationId, Delegation>,
-    ) -> Self {
-        let pool_balances = (pool_data.staker_balance().expect("cannot fail")
-            + delegations
-                .iter()
-                .map(|(_, d)| *d.balance())
-                .sum::<Option<Amount>>()
-                .expect("cannot fail"))
-        .expect("cannot fail");
+    ) -> Result<Self, ApiServerStorageError> {
+        let staker_balance = pool_data.staker_balance().map_err(|e| {
+            ApiServerStorageError::AmountOverflow(format!("staker balance of pool: {e}"))
+        })?;
+        let pool_balances = Amount::sum_checked(
+            std::iter::once(staker_balance).chain(delegations.values().map(|d| *d.balance())),
+        )
+        .ok_or_else(|| ApiServerStorageError::AmountOverflow("balance of pool".to_owned()))?;
 
         let mut pool_delegation_shares = BTreeMap::<(PoolId, DelegationId), Amount>::new();
         let mut delegation_balances = BTreeMap::<DelegationId, Amount>::new();
@@ -65,11 +65,11 @@ impl PoSAdapter {
             delegation_data,
         );
 
-        Self {
+        Ok(Self {
             storage,
             delegation_rewards: Default::default(),
             pool_rewards: Default::default(),
-        }
+        })
     }
 
     pub fn rewards_per_delegation(&self) -> &Vec<(DelegationId, Amount)> {
@@ -52,11 +52,15 @@ use tx_verifier::transaction_verifier::{
 use self::adapter::PoSAdapter;
 
 mod adapter;
+#[cfg(test)]
+mod tests;
 
 #[derive(Debug, thiserror::Error)]
 pub enum BlockchainStateError {
     #[error("Unexpected storage error: {0}")]
     StorageError(#[from] ApiServerStorageError),
+    #[error("Cannot roll back {0} blocks from height {1}")]
+    RollbackBelowGenesis(u32, BlockHeight),
 }
 
 pub struct BlockchainState<S: ApiServerStorage> {
@@ -106,6 +110,26 @@ impl<S: ApiServerStorage + Send + Sync> LocalBlockchainState for BlockchainState
         Ok((best_block.block_height(), best_block.block_id()))
     }
 
+    async fn rollback_blocks(&mut self, n: u32) -> Result<(), Self::Error> {
+        let mut db_tx = self.storage.transaction_rw().await?;
+
+        let best_block_height = db_tx.get_best_block().await?.block_height();
+        utils::ensure!(
+            u64::from(n) <= best_block_height.into_int(),
+            BlockchainStateError::RollbackBelowGenesis(n, best_block_height)
+        );
+
+        let new_best_block_height = best_block_height
+            .checked_sub(n.into())
+            .expect("checked above to not go below genesis");
+        disconnect_tables_above_height(&mut db_tx, new_best_block_height).await?;
+        logging::log::info!("Rolled back {n} blocks from height {best_block_height}");
+
+        db_tx.commit().await?;
+
+        Ok(())
+    }
+
     async fn scan_blocks(
         &mut self,
         common_block_height: BlockHeight,
@@ -158,6 +182,13 @@ impl<S: ApiServerStorage + Send + Sync> LocalBlockchainState for BlockchainState
                 .await
                 .expect("Unable to set block aux data");
 
+            let transaction_count = u32::try_from(block_with_extras.block.transactions().len())
+                .expect("Block transaction count must fit into u32");
+            db_tx
+                .set_block_transaction_count(block_id, transaction_count)
+                .await
+                .expect("Unable to set block transaction count");
+
             let BlockWithExtraData {
                 block,
                 tx_additional_infos,
@@ -229,7 +260,7 @@ async fn update_locked_amounts_for_current_block<T: ApiServerStorageWrite>(
                             CoinOrTokenId::Coin,
                             block_height,
                         )
-                        .await;
+                        .await?;
                         decrease_address_locked_amount(
                             db_tx,
                             address,
@@ -237,7 +268,7 @@ async fn update_locked_amounts_for_current_block<T: ApiServerStorageWrite>(
                             CoinOrTokenId::Coin,
                             block_height,
                         )
-                        .await;
+                        .await?;
                     }
                     OutputValue::TokenV0(_) => {}
                     OutputValue::TokenV1(token_id, amount) => {
@@ -248,7 +279,7 @@ async fn update_locked_amounts_for_current_block<T: ApiServerStorageWrite>(
                             CoinOrTokenId::TokenId(*token_id),
                             block_height,
                         )
-                        .await;
+                        .await?;
                         decrease_address_locked_amount(
                             db_tx,
                             address,
@@ -256,7 +287,7 @@ async fn update_locked_amounts_for_current_block<T: ApiServerStorageWrite>(
                             CoinOrTokenId::TokenId(*token_id),
                             block_height,
                         )
-                        .await;
+                        .await?;
                     }
                 }
             }
@@ -293,60 +324,18 @@ async fn disconnect_tables_above_height<T: ApiServerStorageWrite>(
     block_height: BlockHeight,
 ) -> Result<(), ApiServerStorageError> {
     logging::log::info!("Disconnecting blocks above: {:?}", block_height);
-    db_tx
-        .del_address_balance_above_height(block_height)
-        .await
-        .expect("Unable to disconnect address balance");
-
-    db_tx
-        .del_address_locked_balance_above_height(block_height)
-        .await
-        .expect("Unable to disconnect address balance");
-
-    db_tx
-        .del_address_transactions_above_height(block_height)
-        .await
-        .expect("Unable to disconnect address transactions");
-
-    db_tx
-        .del_utxo_above_height(block_height)
-        .await
-        .expect("Unable to disconnect UTXOs");
-
-    db_tx
-        .del_locked_utxo_above_height(block_height)
-        .await
-        .expect("Unable to disconnect locked UTXOs");
-
-    db_tx
-        .del_delegations_above_height(block_height)
-        .await
-        .expect("Unable to disconnect address transactions");
-
-    db_tx
-        .del_pools_above_height(block_height)
-        .await
-        .expect("Unable to disconnect pool data");
-
-    db_tx
-        .del_token_issuance_above_height(block_height)
-        .await
-        .expect("Unable to disconnect token issuances");
 
-    db_tx
-        .del_nft_issuance_above_height(block_height)
-        .await
-        .expect("Unable to disconnect nft issuances");
-
-    db_tx
-        .del_main_chain_blocks_above_height(block_height)
-        .await
-        .expect("Unable to disconnect block");
-
-    db_tx
-        .del_statistics_above_height(block_height)
-        .await
-        .expect("Unable to disconnect block");
+    db_tx.del_address_balance_above_height(block_height).await?;
+    db_tx.del_address_locked_balance_above_height(block_height).await?;
+    db_tx.del_address_transactions_above_height(block_height).await?;
+    db_tx.del_utxo_above_height(block_height).await?;
+    db_tx.del_locked_utxo_above_height(block_height).await?;
+    db_tx.del_delegations_above_height(block_height).await?;
+    db_tx.del_pools_above_height(block_height).await?;
+    db_tx.del_token_issuance_above_height(block_height).await?;
+    db_tx.del_nft_issuance_above_height(block_height).await?;
+    db_tx.del_main_chain_blocks_above_height(block_height).await?;
+    db_tx.del_statistics_above_height(block_height).await?;
 
     Ok(())
 }
@@ -435,7 +424,7 @@ async fn update_tables_from_block_reward<T: ApiServerStorageWrite>(
                     CoinOrTokenId::Coin,
                     block_height,
                 )
-                .await;
+                .await?;
                 increase_statistic_amount(
                     db_tx,
                     CoinOrTokenStatistic::CirculatingSupply,
@@ -443,7 +432,7 @@ async fn update_tables_from_block_reward<T: ApiServerStorageWrite>(
                     CoinOrTokenId::Coin,
                     block_height,
                 )
-                .await;
+                .await?;
                 increase_statistic_amount(
                     db_tx,
                     CoinOrTokenStatistic::Staked,
@@ -451,7 +440,7 @@ async fn update_tables_from_block_reward<T: ApiServerStorageWrite>(
                     CoinOrTokenId::Coin,
                     block_height,
                 )
-                .await;
+                .await?;
             }
             TxOutput::Transfer(output_value, destination)
             | TxOutput::LockThenTransfer(output_value, destination, _) => {
@@ -467,7 +456,7 @@ async fn update_tables_from_block_reward<T: ApiServerStorageWrite>(
                             CoinOrTokenId::TokenId(*token_id),
                             block_height,
                         )
-                        .await;
+                        .await?;
                         increase_statistic_amount(
                             db_tx,
                             CoinOrTokenStatistic::Preminted,
@@ -475,7 +464,7 @@ async fn update_tables_from_block_reward<T: ApiServerStorageWrite>(
                             CoinOrTokenId::TokenId(*token_id),
                             block_height,
                         )
-                        .await;
+                        .await?;
                         increase_statistic_amount(
                             db_tx,
                             CoinOrTokenStatistic::CirculatingSupply,
@@ -483,7 +472,7 @@ async fn update_tables_from_block_reward<T: ApiServerStorageWrite>(
                             CoinOrTokenId::TokenId(*token_id),
                             block_height,
                         )
-                        .await;
+                        .await?;
                         Some(token_decimals(*token_id, &BTreeMap::new(), db_tx).await?.1)
                     }
                     OutputValue::Coin(amount) => {
@@ -494,7 +483,7 @@ async fn update_tables_from_block_reward<T: ApiServerStorageWrite>(
                             CoinOrTokenId::Coin,
                             block_height,
                         )
-                        .await;
+                        .await?;
                         increase_statistic_amount(
                             db_tx,
                             CoinOrTokenStatistic::Preminted,
@@ -502,7 +491,7 @@ async fn update_tables_from_block_reward<T: ApiServerStorageWrite>(
                             CoinOrTokenId::Coin,
                             block_height,
                         )
-                        .await;
+                        .await?;
                         increase_statistic_amount(
                             db_tx,
                             CoinOrTokenStatistic::CirculatingSupply,
@@ -510,7 +499,7 @@ async fn update_tables_from_block_reward<T: ApiServerStorageWrite>(
                             CoinOrTokenId::Coin,
                             block_height,
                         )
-                        .await;
+                        .await?;
                         None
                     }
                 };
@@ -583,7 +572,9 @@ async fn calculate_fees<T: ApiServerStorageWrite>(
     let mut tx_aditional_infos = vec![];
     for tx in block.transactions().iter() {
         let fee = tx_fees(chain_config, block_height, tx, db_tx, &new_outputs).await?;
-        total_fees = total_fees.combine(fee.clone()).expect("no overflow");
+        total_fees = total_fees.combine(fee.clone()).map_err(|e| {
+            ApiServerStorageError::AmountOverflow(format!("total fees of block: {e}"))
+        })?;
 
         let input_tasks: FuturesOrdered<_> =
             tx.inputs().iter().map(|input| fetch_utxo(input, &new_outputs, db_tx)).collect();
@@ -631,14 +622,18 @@ async fn calculate_fees<T: ApiServerStorageWrite>(
         let token_decimals: BTreeMap<TokenId, u8> = token_tasks.try_collect().await?;
 
         let tx_info = TxAdditionalInfo {
-            fee: fee.map_into_block_fees(chain_config, block_height).expect("no overflow").0,
+            fee: fee
+                .map_into_block_fees(chain_config, block_height)
+                .map_err(|e| ApiServerStorageError::AmountOverflow(format!("tx fee: {e}")))?
+                .0,
             input_utxos,
             token_decimals,
         };
         tx_aditional_infos.push(tx_info);
     }
-    let total_fees =
-        total_fees.map_into_block_fees(chain_config, block_height).expect("no overflow");
+    let total_fees = total_fees
+        .map_into_block_fees(chain_config, block_height)
+        .map_err(|e| ApiServerStorageError::AmountOverflow(format!("total fees of block: {e}")))?;
 
     Ok((total_fees, tx_aditional_infos))
 }
@@ -878,8 +873,12 @@ async fn update_tables_from_consensus_data<T: ApiServerStorageWrite>(
 
             let block_subsidy = chain_config.as_ref().block_subsidy_at_height(&block_height);
 
-            let total_reward = (block_subsidy + total_tx_fees.0)
-                .expect("Block subsidy and fees should not overflow");
+            let total_reward = (block_subsidy + total_tx_fees.0).ok_or_else(|| {
+                ApiServerStorageError::AmountOverflow(format!(
+                    "block subsidy {block_subsidy:?} + fees {:?}",
+                    total_tx_fees.0
+                ))
+            })?;
 
             let pool_id = *pos_data.stake_pool_id();
             let pool_data = db_tx
@@ -889,7 +888,7 @@ async fn update_tables_from_consensus_data<T: ApiServerStorageWrite>(
                 .expect("Pool should exist");
 
             let delegation_shares = db_tx.get_pool_delegations(pool_id).await?;
-            let mut adapter = PoSAdapter::new(pool_id, pool_data, &delegation_shares);
+            let mut adapter = PoSAdapter::new(pool_id, pool_data, &delegation_shares)?;
 
             let reward_distribution_version = chain_config
                 .as_ref()
@@ -913,7 +912,7 @@ async fn update_tables_from_consensus_data<T: ApiServerStorageWrite>(
                 CoinOrTokenId::Coin,
                 block_height,
             )
-            .await;
+            .await?;
             increase_statistic_amount(
                 db_tx,
                 CoinOrTokenStatistic::CirculatingSupply,
@@ -921,7 +920,7 @@ async fn update_tables_from_consensus_data<T: ApiServerStorageWrite>(
                 CoinOrTokenId::Coin,
                 block_height,
             )
-            .await;
+            .await?;
 
             for (delegation_id, rewards) in adapter.rewards_per_delegation() {
                 let delegation = delegation_shares.get(delegation_id).expect("must exist").clone();
@@ -988,7 +987,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                     let issuance =
                         db_tx.get_fungible_token_issuance(*token_id).await?.expect("must exist");
 
-                    let issuance = issuance.mint_tokens(*amount);
+                    let issuance = issuance.mint_tokens(*amount)?;
                     db_tx.set_fungible_token_issuance(*token_id, block_height, issuance).await?;
                     increase_statistic_amount(
                         db_tx,
@@ -997,7 +996,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                         CoinOrTokenId::TokenId(*token_id),
                         block_height,
                     )
-                    .await;
+                    .await?;
                     let amount = chain_config.token_supply_change_fee(block_height);
                     increase_statistic_amount(
                         db_tx,
@@ -1006,7 +1005,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                         CoinOrTokenId::Coin,
                         block_height,
                     )
-                    .await;
+                    .await?;
                     decrease_statistic_amount(
                         db_tx,
                         CoinOrTokenStatistic::CirculatingSupply,
@@ -1014,16 +1013,18 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                         CoinOrTokenId::Coin,
                         block_height,
                     )
-                    .await;
+                    .await?;
                 }
                 AccountCommand::UnmintTokens(token_id) => {
                     let total_burned =
-                        calculate_tokens_burned_in_outputs(tx, token_id).expect("no overflow");
+                        calculate_tokens_burned_in_outputs(tx, token_id).map_err(|e| {
+                            ApiServerStorageError::AmountOverflow(format!("tokens burned: {e}"))
+                        })?;
 
                     let issuance =
                         db_tx.get_fungible_token_issuance(*token_id).await?.expect("must exist");
 
-                    let issuance = issuance.unmint_tokens(total_burned);
+                    let issuance = issuance.unmint_tokens(total_burned)?;
                     db_tx.set_fungible_token_issuance(*token_id, block_height, issuance).await?;
                     let amount = chain_config.token_supply_change_fee(block_height);
                     increase_statistic_amount(
@@ -1033,7 +1034,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                         CoinOrTokenId::Coin,
                         block_height,
                     )
-                    .await;
+                    .await?;
                     decrease_statistic_amount(
                         db_tx,
                         CoinOrTokenStatistic::CirculatingSupply,
@@ -1041,7 +1042,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                         CoinOrTokenId::Coin,
                         block_height,
                     )
-                    .await;
+                    .await?;
                 }
                 AccountCommand::FreezeToken(token_id, is_unfreezable) => {
                     let issuance =
@@ -1057,7 +1058,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                         CoinOrTokenId::Coin,
                         block_height,
                     )
-                    .await;
+                    .await?;
                     decrease_statistic_amount(
                         db_tx,
                         CoinOrTokenStatistic::CirculatingSupply,
@@ -1065,7 +1066,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                         CoinOrTokenId::Coin,
                         block_height,
                     )
-                    .await;
+                    .await?;
                 }
                 AccountCommand::UnfreezeToken(token_id) => {
                     let issuance =
@@ -1081,7 +1082,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                         CoinOrTokenId::Coin,
                         block_height,
                     )
-                    .await;
+                    .await?;
                     decrease_statistic_amount(
                         db_tx,
                         CoinOrTokenStatistic::CirculatingSupply,
@@ -1089,7 +1090,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                         CoinOrTokenId::Coin,
                         block_height,
                     )
-                    .await;
+                    .await?;
                 }
                 AccountCommand::LockTokenSupply(token_id) => {
                     let issuance =
@@ -1105,7 +1106,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                         CoinOrTokenId::Coin,
                         block_height,
                     )
-                    .await;
+                    .await?;
                     decrease_statistic_amount(
                         db_tx,
                         CoinOrTokenStatistic::CirculatingSupply,
@@ -1113,7 +1114,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                         CoinOrTokenId::Coin,
                         block_height,
                     )
-                    .await;
+                    .await?;
                 }
                 AccountCommand::ChangeTokenAuthority(token_id, destination) => {
                     let issuance =
@@ -1129,7 +1130,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                         CoinOrTokenId::Coin,
                         block_height,
                     )
-                    .await;
+                    .await?;
                     decrease_statistic_amount(
                         db_tx,
                         CoinOrTokenStatistic::CirculatingSupply,
@@ -1137,7 +1138,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                         CoinOrTokenId::Coin,
                         block_height,
                     )
-                    .await;
+                    .await?;
                 }
                 AccountCommand::ChangeTokenMetadataUri(token_id, metadata_uri) => {
                     let issuance =
@@ -1153,7 +1154,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                         CoinOrTokenId::Coin,
                         block_height,
                     )
-                    .await;
+                    .await?;
                     decrease_statistic_amount(
                         db_tx,
                         CoinOrTokenStatistic::CirculatingSupply,
@@ -1161,7 +1162,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                         CoinOrTokenId::Coin,
                         block_height,
                     )
-                    .await;
+                    .await?;
                 }
                 AccountCommand::ConcludeOrder(_) | AccountCommand::FillOrder(_, _, _) => {
                     // TODO(orders)
@@ -1192,7 +1193,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                             CoinOrTokenId::Coin,
                             block_height,
                         )
-                        .await;
+                        .await?;
                     }
                 }
             }
@@ -1251,7 +1252,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                                 CoinOrTokenId::Coin,
                                 block_height,
                             )
-                            .await;
+                            .await?;
                         }
                     }
                 }
@@ -1294,7 +1295,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                                 CoinOrTokenId::Coin,
                                 block_height,
                             )
-                            .await;
+                            .await?;
                         }
                         TxOutput::IssueNft(token_id, _, destination) => {
                             let address = Address::<Destination>::new(&chain_config, destination)
@@ -1312,7 +1313,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                                 CoinOrTokenId::TokenId(token_id),
                                 block_height,
                             )
-                            .await;
+                            .await?;
                         }
                         TxOutput::Htlc(_, _) => {} // TODO(HTLC)
                         TxOutput::LockThenTransfer(output_value, destination, _)
@@ -1335,7 +1336,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                                         CoinOrTokenId::TokenId(token_id),
                                         block_height,
                                     )
-                                    .await;
+                                    .await?;
                                 }
                                 OutputValue::Coin(amount) => {
                                     decrease_address_amount(
@@ -1345,7 +1346,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                                         CoinOrTokenId::Coin,
                                         block_height,
                                     )
-                                    .await;
+                                    .await?;
                                 }
                             }
                         }
@@ -1395,6 +1396,14 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                         continue;
                     }
                     OutputValue::TokenV1(token_id, amount) => {
+                        // Unminted tokens are burned via these outputs too
+                        if let Some(issuance) = db_tx.get_fungible_token_issuance(*token_id).await?
+                        {
+                            let issuance = issuance.burn_tokens(*amount)?;
+                            db_tx
+                                .set_fungible_token_issuance(*token_id, block_height, issuance)
+                                .await?;
+                        }
                         (CoinOrTokenId::TokenId(*token_id), amount)
                     }
                 };
@@ -1406,7 +1415,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                     coin_or_token_id,
                     block_height,
                 )
-                .await;
+                .await?;
                 decrease_statistic_amount(
                     db_tx,
                     CoinOrTokenStatistic::CirculatingSupply,
@@ -1414,7 +1423,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                     coin_or_token_id,
                     block_height,
                 )
-                .await;
+                .await?;
             }
             TxOutput::DataDeposit(_) => {
                 let amount = chain_config.data_deposit_fee(block_height);
@@ -1425,7 +1434,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                     CoinOrTokenId::Coin,
                     block_height,
                 )
-                .await;
+                .await?;
                 decrease_statistic_amount(
                     db_tx,
                     CoinOrTokenStatistic::CirculatingSupply,
@@ -1433,7 +1442,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                     CoinOrTokenId::Coin,
                     block_height,
                 )
-                .await;
+                .await?;
             }
             TxOutput::IssueFungibleToken(issuance) => {
                 let token_id = make_token_id(inputs).expect("should not fail");
@@ -1447,6 +1456,11 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                         is_locked: false,
                         frozen: IsTokenFrozen::No(issuance.is_freezable),
                         authority: issuance.authority.clone(),
+                        minted_amount: Amount::ZERO,
+                        burned_amount: Amount::ZERO,
+                        holder_count: 0,
+                        issuance_block_height: block_height,
+                        issuance_tx_id: transaction_id,
                     },
                 };
                 db_tx.set_fungible_token_issuance(token_id, block_height, issuance).await?;
@@ -1458,7 +1472,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                     CoinOrTokenId::Coin,
                     block_height,
                 )
-                .await;
+                .await?;
                 decrease_statistic_amount(
                     db_tx,
                     CoinOrTokenStatistic::CirculatingSupply,
@@ -1466,7 +1480,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                     CoinOrTokenId::Coin,
                     block_height,
                 )
-                .await;
+                .await?;
             }
             TxOutput::IssueNft(token_id, issuance, destination) => {
                 let address = Address::<Destination>::new(&chain_config, destination.clone())
@@ -1480,7 +1494,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                     CoinOrTokenId::TokenId(*token_id),
                     block_height,
                 )
-                .await;
+                .await?;
                 increase_statistic_amount(
                     db_tx,
                     CoinOrTokenStatistic::CirculatingSupply,
@@ -1488,7 +1502,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                     CoinOrTokenId::TokenId(*token_id),
                     block_height,
                 )
-                .await;
+                .await?;
                 let amount = chain_config.nft_issuance_fee(block_height);
                 increase_statistic_amount(
                     db_tx,
@@ -1497,7 +1511,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                     CoinOrTokenId::Coin,
                     block_height,
                 )
-                .await;
+                .await?;
                 decrease_statistic_amount(
                     db_tx,
                     CoinOrTokenStatistic::CirculatingSupply,
@@ -1505,7 +1519,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                     CoinOrTokenId::Coin,
                     block_height,
                 )
-                .await;
+                .await?;
 
                 db_tx.set_nft_token_issuance(*token_id, block_height, *issuance.clone()).await?;
                 set_utxo(
@@ -1554,7 +1568,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                     CoinOrTokenId::Coin,
                     block_height,
                 )
-                .await;
+                .await?;
                 set_utxo(
                     outpoint,
                     output,
@@ -1599,7 +1613,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                     CoinOrTokenId::Coin,
                     block_height,
                 )
-                .await;
+                .await?;
 
                 let address = Address::<Destination>::new(
                     &chain_config,
@@ -1624,7 +1638,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                             CoinOrTokenId::TokenId(*token_id),
                             block_height,
                         )
-                        .await;
+                        .await?;
                         Some(token_decimals(*token_id, &BTreeMap::new(), db_tx).await?.1)
                     }
                     OutputValue::Coin(amount) => {
@@ -1635,7 +1649,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                             CoinOrTokenId::Coin,
                             block_height,
                         )
-                        .await;
+                        .await?;
                         None
                     }
                 };
@@ -1676,7 +1690,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                                 CoinOrTokenId::Coin,
                                 block_height,
                             )
-                            .await;
+                            .await?;
                         } else {
                             increase_locked_address_amount(
                                 db_tx,
@@ -1685,7 +1699,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                                 CoinOrTokenId::Coin,
                                 block_height,
                             )
-                            .await;
+                            .await?;
                         }
                         None
                     }
@@ -1699,7 +1713,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                                 CoinOrTokenId::TokenId(*token_id),
                                 block_height,
                             )
-                            .await;
+                            .await?;
                         } else {
                             increase_locked_address_amount(
                                 db_tx,
@@ -1708,7 +1722,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                                 CoinOrTokenId::TokenId(*token_id),
                                 block_height,
                             )
-                            .await;
+                            .await?;
                         }
                         Some(token_decimals(*token_id, &BTreeMap::new(), db_tx).await?.1)
                     }
@@ -1760,19 +1774,25 @@ async fn increase_statistic_amount<T: ApiServerStorageWrite>(
     amount: &Amount,
     coin_or_token_id: CoinOrTokenId,
     block_height: BlockHeight,
-) {
+) -> Result<(), ApiServerStorageError> {
     let current_balance = db_tx
         .get_statistic(statistic, coin_or_token_id)
         .await
         .expect("Unable to get statistic")
         .unwrap_or(Amount::ZERO);
 
-    let new_amount = current_balance.add(*amount).expect("Balance should not overflow");
+    let new_amount = current_balance.add(*amount).ok_or_else(|| {
+        ApiServerStorageError::AmountOverflow(format!(
+            "statistic {statistic:?} {coin_or_token_id:?} {current_balance:?} + {amount:?}"
+        ))
+    })?;
 
     db_tx
         .set_statistic(statistic, coin_or_token_id, block_height, new_amount)
         .await
-        .expect("Unable to update statistic")
+        .expect("Unable to update statistic");
+
+    Ok(())
 }
 
 async fn decrease_statistic_amount<T: ApiServerStorageWrite>(
@@ -1781,19 +1801,25 @@ async fn decrease_statistic_amount<T: ApiServerStorageWrite>(
     amount: &Amount,
     coin_or_token_id: CoinOrTokenId,
     block_height: BlockHeight,
-) {
+) -> Result<(), ApiServerStorageError> {
     let current_balance = db_tx
         .get_statistic(statistic, coin_or_token_id)
         .await
         .expect("Unable to get statistic")
         .unwrap_or(Amount::ZERO);
 
-    let new_amount = current_balance.sub(*amount).expect("Balance should not underflow");
+    let new_amount = current_balance.sub(*amount).ok_or_else(|| {
+        ApiServerStorageError::AmountUnderflow(format!(
+            "statistic {statistic:?} {coin_or_token_id:?} {current_balance:?} - {amount:?}"
+        ))
+    })?;
 
     db_tx
         .set_statistic(statistic, coin_or_token_id, block_height, new_amount)
         .await
-        .expect("Unable to update statistic")
+        .expect("Unable to update statistic");
+
+    Ok(())
 }
 
 async fn increase_address_amount<T: ApiServerStorageWrite>(
@@ -1802,19 +1828,32 @@ async fn increase_address_amount<T: ApiServerStorageWrite>(
     amount: &Amount,
     coin_or_token_id: CoinOrTokenId,
     block_height: BlockHeight,
-) {
+) -> Result<(), ApiServerStorageError> {
     let current_balance = db_tx
         .get_address_balance(address.as_str(), coin_or_token_id)
         .await
         .expect("Unable to get balance")
         .unwrap_or(Amount::ZERO);
 
-    let new_amount = current_balance.add(*amount).expect("Balance should not overflow");
+    let new_amount = current_balance.add(*amount).ok_or_else(|| {
+        ApiServerStorageError::AmountOverflow(format!(
+            "balance of {address} {coin_or_token_id:?} {current_balance:?} + {amount:?}"
+        ))
+    })?;
 
     db_tx
         .set_address_balance_at_height(address.as_str(), new_amount, coin_or_token_id, block_height)
         .await
-        .expect("Unable to update balance")
+        .expect("Unable to update balance");
+
+    if current_balance == Amount::ZERO && new_amount != Amount::ZERO {
+        update_token_holder_count(db_tx, coin_or_token_id, block_height, |data| {
+            data.add_holder()
+        })
+        .await?;
+    }
+
+    Ok(())
 }
 
 async fn increase_locked_address_amount<T: ApiServerStorageWrite>(
@@ -1823,14 +1862,18 @@ async fn increase_locked_address_amount<T: ApiServerStorageWrite>(
     amount: &Amount,
     coin_or_token_id: CoinOrTokenId,
     block_height: BlockHeight,
-) {
+) -> Result<(), ApiServerStorageError> {
     let current_balance = db_tx
         .get_address_locked_balance(address.as_str(), coin_or_token_id)
         .await
         .expect("Unable to get balance")
         .unwrap_or(Amount::ZERO);
 
-    let new_amount = current_balance.add(*amount).expect("Balance should not overflow");
+    let new_amount = current_balance.add(*amount).ok_or_else(|| {
+        ApiServerStorageError::AmountOverflow(format!(
+            "balance of {address} {coin_or_token_id:?} {current_balance:?} + {amount:?}"
+        ))
+    })?;
 
     db_tx
         .set_address_locked_balance_at_height(
@@ -1840,7 +1883,9 @@ async fn increase_locked_address_amount<T: ApiServerStorageWrite>(
             block_height,
         )
         .await
-        .expect("Unable to update balance")
+        .expect("Unable to update balance");
+
+    Ok(())
 }
 
 async fn decrease_address_amount<T: ApiServerStorageWrite>(
@@ -1849,24 +1894,54 @@ async fn decrease_address_amount<T: ApiServerStorageWrite>(
     amount: &Amount,
     coin_or_token_id: CoinOrTokenId,
     block_height: BlockHeight,
-) {
+) -> Result<(), ApiServerStorageError> {
     let current_balance = db_tx
         .get_address_balance(address.as_str(), coin_or_token_id)
         .await
         .expect("Unable to get balance")
         .unwrap_or(Amount::ZERO);
 
-    let new_amount = current_balance.sub(*amount).unwrap_or_else(|| {
-        panic!(
-            "Balance should not overflow {:?} {:?} {:?}",
-            coin_or_token_id, current_balance, *amount
-        )
-    });
+    let new_amount = current_balance.sub(*amount).ok_or_else(|| {
+        ApiServerStorageError::AmountUnderflow(format!(
+            "balance of {address} {coin_or_token_id:?} {current_balance:?} - {amount:?}"
+        ))
+    })?;
 
     db_tx
         .set_address_balance_at_height(address.as_str(), new_amount, coin_or_token_id, block_height)
         .await
-        .expect("Unable to update balance")
+        .expect("Unable to update balance");
+
+    if current_balance != Amount::ZERO && new_amount == Amount::ZERO {
+        update_token_holder_count(db_tx, coin_or_token_id, block_height, |data| {
+            data.remove_holder()
+        })
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Apply a change of the number of holders to the token data, if this is a fungible token.
+async fn update_token_holder_count<T: ApiServerStorageWrite>(
+    db_tx: &mut T,
+    coin_or_token_id: CoinOrTokenId,
+    block_height: BlockHeight,
+    update: impl FnOnce(FungibleTokenData) -> Result<FungibleTokenData, ApiServerStorageError>,
+) -> Result<(), ApiServerStorageError> {
+    let token_id = match coin_or_token_id {
+        CoinOrTokenId::Coin => return Ok(()),
+        CoinOrTokenId::TokenId(token_id) => token_id,
+    };
+
+    // NFTs don't have any fungible token data
+    if let Some(token_data) = db_tx.get_fungible_token_issuance(token_id).await? {
+        db_tx
+            .set_fungible_token_issuance(token_id, block_height, update(token_data)?)
+            .await?;
+    }
+
+    Ok(())
 }
 
 async fn decrease_address_locked_amount<T: ApiServerStorageWrite>(
@@ -1875,19 +1950,18 @@ async fn decrease_address_locked_amount<T: ApiServerStorageWrite>(
     amount: &Amount,
     coin_or_token_id: CoinOrTokenId,
     block_height: BlockHeight,
-) {
+) -> Result<(), ApiServerStorageError> {
     let current_balance = db_tx
         .get_address_locked_balance(address.as_str(), coin_or_token_id)
         .await
         .expect("Unable to get balance")
         .unwrap_or(Amount::ZERO);
 
-    let new_amount = current_balance.sub(*amount).unwrap_or_else(|| {
-        panic!(
-            "Balance should not overflow {:?} {:?} {:?}",
-            coin_or_token_id, current_balance, *amount
-        )
-    });
+    let new_amount = current_balance.sub(*amount).ok_or_else(|| {
+        ApiServerStorageError::AmountUnderflow(format!(
+            "balance of {address} {coin_or_token_id:?} {current_balance:?} - {amount:?}"
+        ))
+    })?;
 
     db_tx
         .set_address_locked_balance_at_height(
@@ -1897,7 +1971,9 @@ async fn decrease_address_locked_amount<T: ApiServerStorageWrite>(
             block_height,
         )
         .await
-        .expect("Unable to update balance")
+        .expect("Unable to update balance");
+
+    Ok(())
 }
 
 async fn set_utxo<T: ApiServerStorageWrite>(
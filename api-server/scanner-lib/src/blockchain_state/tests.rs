@@ -0,0 +1,189 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api_server_common::storage::{
+    impls::in_memory::transactional::TransactionalApiServerInMemoryStorage,
+    storage_api::Transactional,
+};
+use common::chain::config::create_unit_test_config;
+
+use super::*;
+
+#[tokio::test]
+async fn address_balance_overflow_and_underflow() {
+    let chain_config = create_unit_test_config();
+    let mut storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+    let mut db_tx = storage.transaction_rw().await.unwrap();
+    db_tx.reinitialize_storage(&chain_config).await.unwrap();
+
+    let address = Address::new(&chain_config, Destination::AnyoneCanSpend).unwrap();
+    let coin = CoinOrTokenId::Coin;
+    let height = BlockHeight::new(1);
+
+    let almost_max = (Amount::MAX - Amount::from_atoms(1)).unwrap();
+    increase_address_amount(&mut db_tx, &address, &almost_max, coin, height)
+        .await
+        .unwrap();
+    increase_address_amount(&mut db_tx, &address, &Amount::from_atoms(1), coin, height)
+        .await
+        .unwrap();
+    assert_eq!(
+        db_tx.get_address_balance(address.as_str(), coin).await.unwrap(),
+        Some(Amount::MAX)
+    );
+
+    let result =
+        increase_address_amount(&mut db_tx, &address, &Amount::from_atoms(1), coin, height).await;
+    assert!(matches!(
+        result,
+        Err(ApiServerStorageError::AmountOverflow(_))
+    ));
+    assert_eq!(
+        db_tx.get_address_balance(address.as_str(), coin).await.unwrap(),
+        Some(Amount::MAX)
+    );
+
+    decrease_address_amount(&mut db_tx, address.clone(), &Amount::MAX, coin, height)
+        .await
+        .unwrap();
+    let result = decrease_address_amount(
+        &mut db_tx,
+        address.clone(),
+        &Amount::from_atoms(1),
+        coin,
+        height,
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(ApiServerStorageError::AmountUnderflow(_))
+    ));
+
+    increase_locked_address_amount(&mut db_tx, &address, &Amount::MAX, coin, height)
+        .await
+        .unwrap();
+    let result =
+        increase_locked_address_amount(&mut db_tx, &address, &Amount::from_atoms(1), coin, height)
+            .await;
+    assert!(matches!(
+        result,
+        Err(ApiServerStorageError::AmountOverflow(_))
+    ));
+}
+
+#[tokio::test]
+async fn statistic_overflow_and_underflow() {
+    let chain_config = create_unit_test_config();
+    let mut storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+    let mut db_tx = storage.transaction_rw().await.unwrap();
+    db_tx.reinitialize_storage(&chain_config).await.unwrap();
+
+    let statistic = CoinOrTokenStatistic::Burned;
+    let coin = CoinOrTokenId::Coin;
+    let height = BlockHeight::new(1);
+
+    increase_statistic_amount(&mut db_tx, statistic, &Amount::MAX, coin, height)
+        .await
+        .unwrap();
+    let result =
+        increase_statistic_amount(&mut db_tx, statistic, &Amount::from_atoms(1), coin, height)
+            .await;
+    assert!(matches!(
+        result,
+        Err(ApiServerStorageError::AmountOverflow(_))
+    ));
+
+    decrease_statistic_amount(&mut db_tx, statistic, &Amount::MAX, coin, height)
+        .await
+        .unwrap();
+    let result =
+        decrease_statistic_amount(&mut db_tx, statistic, &Amount::from_atoms(1), coin, height)
+            .await;
+    assert!(matches!(
+        result,
+        Err(ApiServerStorageError::AmountUnderflow(_))
+    ));
+}
+
+// A bookkeeping mismatch in the token data, e.g. after a rollback, must result in an error
+// instead of a panic.
+#[tokio::test]
+async fn token_data_overflow_and_underflow() {
+    let chain_config = create_unit_test_config();
+    let mut storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+    let mut db_tx = storage.transaction_rw().await.unwrap();
+    db_tx.reinitialize_storage(&chain_config).await.unwrap();
+
+    let token_id = TokenId::new(H256::zero());
+    let coin_or_token_id = CoinOrTokenId::TokenId(token_id);
+    let address = Address::new(&chain_config, Destination::AnyoneCanSpend).unwrap();
+    let height = BlockHeight::new(1);
+
+    let token_data = FungibleTokenData {
+        token_ticker: b"TKN".to_vec(),
+        number_of_decimals: 2,
+        metadata_uri: Vec::new(),
+        circulating_supply: Amount::ZERO,
+        total_supply: common::chain::tokens::TokenTotalSupply::Unlimited,
+        is_locked: false,
+        frozen: IsTokenFrozen::No(common::chain::tokens::IsTokenFreezable::No),
+        authority: Destination::AnyoneCanSpend,
+        minted_amount: Amount::ZERO,
+        burned_amount: Amount::ZERO,
+        holder_count: 0,
+        issuance_block_height: height,
+        issuance_tx_id: Id::new(H256::zero()),
+    };
+
+    let minted = token_data.clone().mint_tokens(Amount::MAX).unwrap();
+    assert!(matches!(
+        minted.mint_tokens(Amount::from_atoms(1)),
+        Err(ApiServerStorageError::AmountOverflow(_))
+    ));
+    assert!(matches!(
+        token_data.clone().unmint_tokens(Amount::from_atoms(1)),
+        Err(ApiServerStorageError::AmountUnderflow(_))
+    ));
+    let burned = token_data.clone().burn_tokens(Amount::MAX).unwrap();
+    assert!(matches!(
+        burned.burn_tokens(Amount::from_atoms(1)),
+        Err(ApiServerStorageError::AmountOverflow(_))
+    ));
+
+    // The address has a balance, but the token data doesn't count it as a holder.
+    db_tx.set_fungible_token_issuance(token_id, height, token_data).await.unwrap();
+    db_tx
+        .set_address_balance_at_height(
+            address.as_str(),
+            Amount::from_atoms(1),
+            coin_or_token_id,
+            height,
+        )
+        .await
+        .unwrap();
+
+    let result = decrease_address_amount(
+        &mut db_tx,
+        address.clone(),
+        &Amount::from_atoms(1),
+        coin_or_token_id,
+        height,
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(ApiServerStorageError::AmountUnderflow(_))
+    ));
+}
@@ -27,6 +27,10 @@ pub trait LocalBlockchainState {
     /// Returns the current best known block (may be genesis)
     async fn best_block(&self) -> Result<(BlockHeight, Id<GenBlock>), Self::Error>;
 
+    /// Remove the top `n` blocks, e.g. the ones that are no longer in the main chain
+    /// of the remote node after a reorg. The blocks are removed atomically.
+    async fn rollback_blocks(&mut self, n: u32) -> Result<(), Self::Error>;
+
     /// Scan new blocks:
     /// 1. Reset local blocks to the common block height
     /// (it will be lower than the current block height in case of reorg).
@@ -28,6 +28,8 @@ const MAX_FETCH_BLOCK_COUNT: usize = 100;
 pub enum SyncError {
     #[error("Unexpected remote node error: {0}")]
     RemoteNode(String),
+    #[error("Remote node is unavailable: {0}")]
+    RemoteNodeUnavailable(String),
     #[error("Unexpected local node error: {0}")]
     LocalNode(String),
     #[error("No new blocks found")]
@@ -50,6 +52,22 @@ struct FetchedBlocks {
     common_block_height: BlockHeight,
 }
 
+impl SyncError {
+    /// Whether the sync failed because the remote node couldn't be reached, e.g. while it's
+    /// restarting, so it's worth waiting for the node rather than reporting an error
+    pub fn is_remote_node_unavailable(&self) -> bool {
+        matches!(self, Self::RemoteNodeUnavailable(_))
+    }
+}
+
+fn remote_node_error<N: RemoteNode>(rpc_client: &N, error: N::Error) -> SyncError {
+    if rpc_client.is_node_unavailable(&error) {
+        SyncError::RemoteNodeUnavailable(error.to_string())
+    } else {
+        SyncError::RemoteNode(error.to_string())
+    }
+}
+
 /// Sync the local node to the current block height of the remote node.
 /// This should be run periodically.
 pub async fn sync_once(
@@ -58,10 +76,8 @@ pub async fn sync_once(
     local_state: &mut impl LocalBlockchainState,
 ) -> Result<(), SyncError> {
     loop {
-        let chain_info = rpc_client
-            .chainstate()
-            .await
-            .map_err(|e| SyncError::RemoteNode(e.to_string()))?;
+        let chain_info =
+            rpc_client.chainstate().await.map_err(|e| remote_node_error(rpc_client, e))?;
 
         let (best_block_height, best_block_id) = local_state
             .best_block()
@@ -72,6 +88,13 @@ pub async fn sync_once(
             return Ok(());
         }
 
+        // The blocks that are no longer in the main chain of the node must be removed first
+        if chain_info.best_block_height >= best_block_height
+            && rollback_to_common_ancestor(rpc_client, local_state).await?
+        {
+            continue;
+        }
+
         logging::log::info!(
             "Found a new best block in node: ({}, {})",
             best_block_height,
@@ -90,6 +113,46 @@ pub async fn sync_once(
     }
 }
 
+/// Roll back the local best block until it's in the main chain of the remote node.
+/// Returns whether any blocks were rolled back.
+async fn rollback_to_common_ancestor(
+    rpc_client: &impl RemoteNode,
+    local_state: &mut impl LocalBlockchainState,
+) -> Result<bool, SyncError> {
+    let mut rolled_back = false;
+
+    loop {
+        let (block_height, block_id) = local_state
+            .best_block()
+            .await
+            .map_err(|e| SyncError::BestBlockRetrievalError(e.to_string()))?;
+
+        if block_height == BlockHeight::zero() {
+            return Ok(rolled_back);
+        }
+
+        let remote_block_id = rpc_client
+            .block_id_at_height(block_height)
+            .await
+            .map_err(|e| remote_node_error(rpc_client, e))?;
+        if remote_block_id == Some(block_id) {
+            return Ok(rolled_back);
+        }
+
+        logging::log::info!(
+            "Block ({}, {}) is not in the main chain of the node, rolling it back",
+            block_height,
+            block_id
+        );
+
+        local_state
+            .rollback_blocks(1)
+            .await
+            .map_err(|e| SyncError::LocalNode(e.to_string()))?;
+        rolled_back = true;
+    }
+}
+
 async fn fetch_and_sync(
     chain_info: chainstate::ChainInfo,
     best_block_id: Id<GenBlock>,
@@ -144,7 +207,7 @@ async fn fetch_new_blocks(
     let blocks = rpc_client
         .mainchain_blocks(common_block_height.next_height(), MAX_FETCH_BLOCK_COUNT)
         .await
-        .map_err(|e| SyncError::RemoteNode(e.to_string()))?;
+        .map_err(|e| remote_node_error(rpc_client, e))?;
     match blocks.first() {
         Some(block) => utils::ensure!(
             *block.header().prev_block_id() == common_block_id,
@@ -172,7 +235,7 @@ async fn get_common_block_info(
     let common_block_opt = rpc_client
         .last_common_ancestor(best_block_id, chain_info.best_block_id)
         .await
-        .map_err(|e| SyncError::RemoteNode(e.to_string()))?;
+        .map_err(|e| remote_node_error(rpc_client, e))?;
 
     let (common_block_id, common_block_height) = match common_block_opt {
         // Common branch is found
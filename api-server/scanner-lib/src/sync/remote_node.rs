@@ -40,8 +40,17 @@ pub trait RemoteNode {
         from: BlockHeight,
         max_count: usize,
     ) -> Result<Vec<Block>, Self::Error>;
+    async fn block_id_at_height(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Option<Id<GenBlock>>, Self::Error>;
 
     async fn mempool_feerate_points(&self) -> Result<Vec<(usize, FeeRate)>, Self::Error>;
+
+    /// Whether the error means that the node couldn't be reached, e.g. because it's restarting
+    fn is_node_unavailable(&self, _error: &Self::Error) -> bool {
+        false
+    }
 }
 
 #[async_trait::async_trait]
@@ -67,7 +76,18 @@ impl RemoteNode for NodeRpcClient {
         self.get_mainchain_blocks(from, max_count).await
     }
 
+    async fn block_id_at_height(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Option<Id<GenBlock>>, Self::Error> {
+        self.get_block_id_at_height(height).await
+    }
+
     async fn mempool_feerate_points(&self) -> Result<Vec<(usize, FeeRate)>, Self::Error> {
         self.mempool_get_fee_rate_points().await
     }
+
+    fn is_node_unavailable(&self, error: &Self::Error) -> bool {
+        error.is_node_unavailable()
+    }
 }
@@ -98,6 +98,17 @@ impl LocalBlockchainState for MockLocalState {
         Ok((self.get_block_height(), self.get_best_block_id()))
     }
 
+    async fn rollback_blocks(&mut self, n: u32) -> Result<(), Self::Error> {
+        let n = n as usize;
+        assert!(
+            n <= self.blocks.len(),
+            "Invalid rollback: {n}, max: {}",
+            self.blocks.len()
+        );
+        self.blocks.truncate(self.blocks.len() - n);
+        Ok(())
+    }
+
     async fn scan_blocks(
         &mut self,
         common_block_height: BlockHeight,
@@ -174,6 +185,13 @@ impl RemoteNode for MockRemoteNode {
             .unwrap())
     }
 
+    async fn block_id_at_height(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Option<Id<GenBlock>>, Self::Error> {
+        Ok(self.tf.lock().unwrap().chainstate.get_block_id_from_height(&height).unwrap())
+    }
+
     async fn mempool_feerate_points(&self) -> Result<Vec<(usize, FeeRate)>, Self::Error> {
         Ok(vec![(
             1,
@@ -268,6 +286,94 @@ async fn restart_from_genesis(#[case] seed: Seed) {
     wait_new_tip(&node, &mut new_tip_rx).await;
 }
 
+async fn make_blockchain_state(
+    chain_config: &Arc<ChainConfig>,
+) -> BlockchainState<TransactionalApiServerInMemoryStorage> {
+    let mut storage = TransactionalApiServerInMemoryStorage::new(chain_config);
+
+    let mut db_tx = storage.transaction_rw().await.unwrap();
+    db_tx.reinitialize_storage(chain_config).await.unwrap();
+    db_tx.commit().await.unwrap();
+
+    let mut local_state = BlockchainState::new(Arc::clone(chain_config), storage);
+    local_state.scan_genesis(chain_config.genesis_block().as_ref()).await.unwrap();
+    local_state
+}
+
+#[rstest]
+#[trace]
+#[case(test_utils::random::Seed::from_entropy())]
+#[tokio::test]
+async fn reorg_rollback(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let node = MockRemoteNode::new(&mut rng);
+    let chain_config = Arc::clone(node.tf.lock().unwrap().chainstate.get_chain_config());
+
+    let mut local_state = make_blockchain_state(&chain_config).await;
+    create_chain(&node, &mut rng, 0, 5);
+    sync_once(&chain_config, &node, &mut local_state).await.unwrap();
+    assert_eq!(
+        local_state.best_block().await.unwrap().0,
+        BlockHeight::new(5)
+    );
+
+    // Replace the top 3 blocks with a longer branch
+    create_chain(&node, &mut rng, 2, 4);
+    let node_block_ids = (0..=6)
+        .map(|height| {
+            node.tf
+                .lock()
+                .unwrap()
+                .chainstate
+                .get_block_id_from_height(&BlockHeight::new(height))
+                .unwrap()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    sync_once(&chain_config, &node, &mut local_state).await.unwrap();
+    assert_eq!(
+        local_state.best_block().await.unwrap(),
+        (BlockHeight::new(6), node_block_ids[6])
+    );
+
+    // The storage must be the same as if the new chain was synced from scratch
+    let mut expected_state = make_blockchain_state(&chain_config).await;
+    sync_once(&chain_config, &node, &mut expected_state).await.unwrap();
+
+    let db_tx = local_state.storage().transaction_ro().await.unwrap();
+    let expected_db_tx = expected_state.storage().transaction_ro().await.unwrap();
+
+    for (height, node_block_id) in node_block_ids.iter().enumerate().skip(1) {
+        let height = BlockHeight::new(height as u64);
+        let block_id = db_tx.get_main_chain_block_id(height).await.unwrap();
+        assert_eq!(block_id.map(Into::into), Some(*node_block_id));
+    }
+    assert_eq!(
+        db_tx.get_best_block().await.unwrap(),
+        expected_db_tx.get_best_block().await.unwrap()
+    );
+
+    let address = Address::new(&chain_config, Destination::AnyoneCanSpend).unwrap();
+    assert_eq!(
+        db_tx.get_address_balance(address.as_str(), CoinOrTokenId::Coin).await.unwrap(),
+        expected_db_tx
+            .get_address_balance(address.as_str(), CoinOrTokenId::Coin)
+            .await
+            .unwrap()
+    );
+    let utxos = db_tx.get_address_available_utxos(address.as_str()).await.unwrap();
+    let expected_utxos =
+        expected_db_tx.get_address_available_utxos(address.as_str()).await.unwrap();
+    assert_eq!(
+        utxos.into_iter().map(|(outpoint, _)| outpoint).collect::<Vec<_>>(),
+        expected_utxos.into_iter().map(|(outpoint, _)| outpoint).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        db_tx.get_all_statistic(CoinOrTokenId::Coin).await.unwrap(),
+        expected_db_tx.get_all_statistic(CoinOrTokenId::Coin).await.unwrap()
+    );
+}
+
 #[rstest]
 #[trace]
 #[case(test_utils::random::Seed::from_entropy())]
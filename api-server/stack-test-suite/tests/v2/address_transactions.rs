@@ -0,0 +1,209 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+
+use common::primitives::H256;
+
+use super::*;
+
+fn random_address(rng: &mut impl Rng) -> Address<Destination> {
+    let chain_config = create_unit_test_config();
+    let (_, public_key) = PrivateKey::new_from_rng(rng, KeyKind::Secp256k1Schnorr);
+    let destination = Destination::PublicKeyHash(PublicKeyHash::from(&public_key));
+    Address::<Destination>::new(&chain_config, destination).unwrap()
+}
+
+#[tokio::test]
+async fn invalid_address() {
+    let (task, response) = spawn_webserver("/api/v2/address/invalid-address/transactions").await;
+
+    assert_eq!(response.status(), 400);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(body["error"].as_str().unwrap(), "Invalid address");
+
+    task.abort();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn invalid_offset(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let address = random_address(&mut rng);
+
+    let (task, response) = spawn_webserver(&format!(
+        "/api/v2/address/{}/transactions?offset=asd",
+        address.as_str()
+    ))
+    .await;
+
+    assert_eq!(response.status(), 400);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(body["error"].as_str().unwrap(), "Invalid offset");
+
+    task.abort();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn invalid_num_items_max(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let address = random_address(&mut rng);
+    let more_than_max = rng.gen_range(101..1000);
+
+    let (task, response) = spawn_webserver(&format!(
+        "/api/v2/address/{}/transactions?items={more_than_max}",
+        address.as_str()
+    ))
+    .await;
+
+    assert_eq!(response.status(), 400);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(body["error"].as_str().unwrap(), "Invalid number of items");
+
+    task.abort();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn address_not_found(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let address = random_address(&mut rng);
+
+    let (task, response) = spawn_webserver(&format!(
+        "/api/v2/address/{}/transactions",
+        address.as_str()
+    ))
+    .await;
+
+    assert_eq!(response.status(), 404);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(body["error"].as_str().unwrap(), "Address not found");
+
+    task.abort();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn total_count(#[case] seed: Seed) {
+    const TX_COUNT: usize = 50;
+
+    let mut rng = make_seedable_rng(seed);
+    let address = random_address(&mut rng);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    // The transactions at a higher height come first
+    let tx_ids: Vec<_> = (0..TX_COUNT)
+        .map(|_| Id::<Transaction>::new(H256::random_using(&mut rng)))
+        .collect();
+
+    let task = tokio::spawn({
+        let address = address.clone();
+        let tx_ids = tx_ids.clone();
+
+        async move {
+            let chain_config = Arc::new(create_unit_test_config());
+
+            let mut storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+            let mut db_tx = storage.transaction_rw().await.unwrap();
+            db_tx.reinitialize_storage(&chain_config).await.unwrap();
+            for (i, tx_id) in tx_ids.into_iter().enumerate() {
+                db_tx
+                    .set_address_transactions_at_height(
+                        address.as_str(),
+                        BTreeSet::from([tx_id]),
+                        BlockHeight::new((TX_COUNT - i) as u64),
+                    )
+                    .await
+                    .unwrap();
+            }
+            db_tx.commit().await.unwrap();
+
+            let web_server_state = ApiServerWebServerState {
+                db: Arc::new(storage),
+                chain_config: Arc::clone(&chain_config),
+                rpc: Arc::new(DummyRPC {}),
+                cached_values: Arc::new(CachedValues {
+                    feerate_points: RwLock::new((get_time(), vec![])),
+                }),
+                time_getter: Default::default(),
+            };
+
+            web_server(listener, web_server_state, true).await
+        }
+    });
+
+    let mut queries = vec![(None, None), (Some(0), Some(100)), (Some(TX_COUNT), Some(10))];
+    for _ in 0..5 {
+        let offset = rng.gen_range(0..=TX_COUNT + 10);
+        let items = rng.gen_range(0..=100);
+        queries.push((Some(offset), Some(items)));
+    }
+
+    for (offset, items) in queries {
+        let mut url = format!(
+            "http://{}:{}/api/v2/address/{}/transactions?",
+            addr.ip(),
+            addr.port(),
+            address.as_str()
+        );
+        if let Some(offset) = offset {
+            url += &format!("offset={offset}&");
+        }
+        if let Some(items) = items {
+            url += &format!("items={items}");
+        }
+
+        let response = reqwest::get(url).await.unwrap();
+        assert_eq!(response.status(), 200);
+
+        let body = response.text().await.unwrap();
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+        assert_eq!(body["total_count"].as_u64().unwrap(), TX_COUNT as u64);
+
+        let expected_txs = tx_ids
+            .iter()
+            .skip(offset.unwrap_or(0))
+            .take(items.unwrap_or(10))
+            .map(|tx_id| json!(tx_id))
+            .collect::<Vec<_>>();
+        assert_eq!(body["transactions"], serde_json::Value::Array(expected_txs));
+    }
+
+    task.abort();
+}
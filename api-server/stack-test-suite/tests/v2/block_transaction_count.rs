@@ -0,0 +1,163 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::RwLock;
+
+use api_web_server::CachedValues;
+use common::primitives::time::get_time;
+
+use crate::DummyRPC;
+
+use super::*;
+
+#[tokio::test]
+async fn invalid_block_id() {
+    let (task, response) =
+        spawn_webserver("/api/v2/block/invalid-block-id/transaction-count").await;
+
+    assert_eq!(response.status(), 400);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(body["error"].as_str().unwrap(), "Invalid block Id");
+
+    task.abort();
+}
+
+#[tokio::test]
+async fn block_not_found() {
+    let (task, response) = spawn_webserver(
+    "/api/v2/block/0000000000000000000000000000000000000000000000000000000000000001/transaction-count").await;
+
+    assert_eq!(response.status(), 404);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(body["error"].as_str().unwrap(), "Block not found");
+
+    task.abort();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn ok(#[case] seed: Seed) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    let task = tokio::spawn({
+        async move {
+            let web_server_state = {
+                let mut rng = make_seedable_rng(seed);
+                let block_height = rng.gen_range(1..50);
+                let n_blocks = rng.gen_range(block_height..100);
+
+                let chain_config = create_unit_test_config();
+
+                let chainstate_blocks = {
+                    let mut tf = TestFramework::builder(&mut rng)
+                        .with_chain_config(chain_config.clone())
+                        .build();
+
+                    let chainstate_block_ids = tf
+                        .create_chain_return_ids(&tf.genesis().get_id().into(), n_blocks, &mut rng)
+                        .unwrap();
+
+                    // Need the "- 1" to account for the genesis block not in the vec
+                    let block_id = chainstate_block_ids[block_height - 1];
+
+                    let expected_transaction_ids = tf
+                        .block(tf.to_chain_block_id(&block_id))
+                        .transactions()
+                        .iter()
+                        .map(|tx| tx.transaction().get_id())
+                        .collect::<Vec<_>>();
+
+                    _ = tx.send((
+                        block_id.to_hash().encode_hex::<String>(),
+                        json!(expected_transaction_ids),
+                    ));
+
+                    chainstate_block_ids
+                        .iter()
+                        .map(|id| tf.block(tf.to_chain_block_id(id)))
+                        .collect::<Vec<_>>()
+                };
+
+                let storage = {
+                    let mut storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+
+                    let mut db_tx = storage.transaction_rw().await.unwrap();
+                    db_tx.reinitialize_storage(&chain_config).await.unwrap();
+                    db_tx.commit().await.unwrap();
+
+                    storage
+                };
+
+                let chain_config = Arc::new(chain_config);
+                let mut local_node = BlockchainState::new(Arc::clone(&chain_config), storage);
+                local_node.scan_genesis(chain_config.genesis_block()).await.unwrap();
+                local_node.scan_blocks(BlockHeight::new(0), chainstate_blocks).await.unwrap();
+
+                ApiServerWebServerState {
+                    db: Arc::new(local_node.storage().clone_storage().await),
+                    chain_config: Arc::clone(&chain_config),
+                    rpc: Arc::new(DummyRPC {}),
+                    cached_values: Arc::new(CachedValues {
+                        feerate_points: RwLock::new((get_time(), vec![])),
+                    }),
+                    time_getter: Default::default(),
+                }
+            };
+
+            web_server(listener, web_server_state, true).await
+        }
+    });
+
+    let (block_id, expected_transaction_ids) = rx.await.unwrap();
+
+    // Given that the listener port is open, this will block until a
+    // response is made (by the web server, which takes the listener
+    // over)
+    let url = format!("/api/v2/block/{block_id}/transaction-ids");
+    let response = reqwest::get(format!("http://{}:{}{url}", addr.ip(), addr.port()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body = response.text().await.unwrap();
+    let transaction_ids: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(transaction_ids, expected_transaction_ids);
+
+    let url = format!("/api/v2/block/{block_id}/transaction-count");
+    let response = reqwest::get(format!("http://{}:{}{url}", addr.ip(), addr.port()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    // The count matches the transaction ids of the same block
+    assert_eq!(
+        body["transaction_count"].as_u64().unwrap(),
+        transaction_ids.as_array().unwrap().len() as u64
+    );
+
+    task.abort();
+}
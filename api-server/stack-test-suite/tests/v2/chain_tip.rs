@@ -97,48 +97,23 @@ async fn height_n(#[case] seed: Seed) {
             let n_blocks = rng.gen_range(1..100);
 
             let web_server_state = {
-                let chain_config = create_unit_test_config();
-
-                let chainstate_blocks = {
-                    let mut tf = TestFramework::builder(&mut rng)
-                        .with_chain_config(chain_config.clone())
-                        .build();
-
-                    let chainstate_block_ids = tf
-                        .create_chain_return_ids(&tf.genesis().get_id().into(), n_blocks, &mut rng)
-                        .unwrap();
-
-                    // Need the "- 1" to account for the genesis block not in the vec
-                    let expected_block_id = chainstate_block_ids[n_blocks - 1];
-
-                    _ = tx.send(json!({
-                    "block_height": n_blocks,
-                    "block_id": expected_block_id,
-                    }));
-
-                    chainstate_block_ids
-                        .iter()
-                        .map(|id| tf.block(tf.to_chain_block_id(id)))
-                        .collect::<Vec<_>>()
-                };
+                let chain_config = Arc::new(create_unit_test_config());
 
-                let storage = {
-                    let mut storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+                let (snapshot, chainstate_block_ids) = common_test_setup(&mut rng, n_blocks).await;
 
-                    let mut db_tx = storage.transaction_rw().await.unwrap();
-                    db_tx.reinitialize_storage(&chain_config).await.unwrap();
-                    db_tx.commit().await.unwrap();
+                // Need the "- 1" to account for the genesis block not in the vec
+                let expected_block_id = chainstate_block_ids[n_blocks - 1];
 
-                    storage
-                };
+                _ = tx.send(json!({
+                "block_height": n_blocks,
+                "block_id": expected_block_id,
+                }));
 
-                let chain_config = Arc::new(chain_config);
-                let mut local_node = BlockchainState::new(Arc::clone(&chain_config), storage);
-                local_node.scan_genesis(chain_config.genesis_block()).await.unwrap();
-                local_node.scan_blocks(BlockHeight::new(0), chainstate_blocks).await.unwrap();
+                let mut storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+                storage.restore(snapshot).await;
 
                 ApiServerWebServerState {
-                    db: Arc::new(local_node.storage().clone_storage().await),
+                    db: Arc::new(storage),
                     chain_config: Arc::clone(&chain_config),
                     rpc: Arc::new(DummyRPC {}),
                     cached_values: Arc::new(CachedValues {
@@ -0,0 +1,156 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::RwLock;
+
+use api_web_server::{api::json_helpers::amount_to_json, CachedValues, TxSubmitClient};
+use common::{chain::ChainConfig, primitives::time::Time};
+use mempool::{FeeRate, MempoolConfig};
+use node_comm::rpc_client::NodeRpcError;
+use serialization::hex::HexError;
+
+use super::*;
+
+fn in_top_x_mb(chain_config: &ChainConfig, blocks: usize) -> usize {
+    (blocks * chain_config.max_block_size_from_std_scripts()).div_ceil(1_000_000)
+}
+
+fn feerate_to_json(chain_config: &ChainConfig, atoms_per_kb: u128) -> serde_json::Value {
+    amount_to_json(
+        Amount::from_atoms(atoms_per_kb),
+        chain_config.coin_decimals(),
+    )
+}
+
+async fn get_fee_estimates(rpc: impl TxSubmitClient + Send + Sync + 'static) -> serde_json::Value {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let task = tokio::spawn(async move {
+        let web_server_state = {
+            let chain_config = Arc::new(create_unit_test_config());
+            let storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+
+            ApiServerWebServerState {
+                db: Arc::new(storage),
+                chain_config: Arc::clone(&chain_config),
+                rpc: Arc::new(rpc),
+                cached_values: Arc::new(CachedValues {
+                    // Outdated, so the feerate points are requested from the node
+                    feerate_points: RwLock::new((Time::from_secs_since_epoch(0), vec![])),
+                }),
+                time_getter: Default::default(),
+            }
+        };
+
+        web_server(listener, web_server_state, true).await.unwrap();
+    });
+
+    let response = reqwest::get(format!(
+        "http://{}:{}/api/v2/mempool/fee-estimates",
+        addr.ip(),
+        addr.port()
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let body = response.text().await.unwrap();
+    task.abort();
+
+    serde_json::from_str(&body).unwrap()
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn ok(#[case] seed: Seed) {
+    struct MockRPC {
+        feerate_points: Vec<(usize, FeeRate)>,
+    }
+
+    #[async_trait::async_trait]
+    impl TxSubmitClient for MockRPC {
+        async fn submit_tx(&self, _: SignedTransaction) -> Result<(), NodeRpcError> {
+            Ok(())
+        }
+
+        async fn get_feerate_points(&self) -> Result<Vec<(usize, FeeRate)>, NodeRpcError> {
+            Ok(self.feerate_points.clone())
+        }
+    }
+
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = create_unit_test_config();
+
+    let fast = rng.gen_range(2000..3000);
+    let medium = rng.gen_range(1000..2000);
+    let slow = rng.gen_range(1..1000);
+    let feerate_points = [(1, 10_000), (2, fast), (5, medium), (10, slow), (100, 0)]
+        .into_iter()
+        .map(|(blocks, atoms_per_kb)| {
+            (
+                in_top_x_mb(&chain_config, blocks),
+                FeeRate::from_atoms_per_kb(atoms_per_kb),
+            )
+        })
+        .collect();
+
+    let body = get_fee_estimates(MockRPC { feerate_points }).await;
+
+    assert_eq!(
+        body,
+        json!({
+            "slow": feerate_to_json(&chain_config, slow),
+            "medium": feerate_to_json(&chain_config, medium),
+            "fast": feerate_to_json(&chain_config, fast),
+        })
+    );
+}
+
+#[rstest]
+#[trace]
+#[tokio::test]
+async fn node_unavailable() {
+    struct FailingRPC {}
+
+    #[async_trait::async_trait]
+    impl TxSubmitClient for FailingRPC {
+        async fn submit_tx(&self, _: SignedTransaction) -> Result<(), NodeRpcError> {
+            Ok(())
+        }
+
+        async fn get_feerate_points(&self) -> Result<Vec<(usize, FeeRate)>, NodeRpcError> {
+            Err(NodeRpcError::DecodingError(HexError::HexDecodeError(
+                hex::FromHexError::OddLength,
+            )))
+        }
+    }
+
+    let chain_config = create_unit_test_config();
+    let min_relay_feerate = MempoolConfig::new().min_tx_relay_fee_rate.atoms_per_kb();
+
+    let body = get_fee_estimates(FailingRPC {}).await;
+
+    assert_eq!(
+        body,
+        json!({
+            "slow": feerate_to_json(&chain_config, min_relay_feerate),
+            "medium": feerate_to_json(&chain_config, min_relay_feerate * 2),
+            "fast": feerate_to_json(&chain_config, min_relay_feerate * 5),
+        })
+    );
+}
@@ -17,12 +17,15 @@ mod address;
 mod address_all_utxos;
 mod address_delegations;
 mod address_spendable_utxos;
+mod address_transactions;
 mod block;
 mod block_header;
 mod block_reward;
+mod block_transaction_count;
 mod block_transaction_ids;
 mod chain_at_height;
 mod chain_tip;
+mod fee_estimates;
 mod feerate;
 mod helpers;
 mod nft;
@@ -43,8 +46,12 @@ use api_blockchain_scanner_lib::{
     blockchain_state::BlockchainState, sync::local_state::LocalBlockchainState,
 };
 use api_server_common::storage::{
-    impls::in_memory::transactional::TransactionalApiServerInMemoryStorage,
-    storage_api::{ApiServerStorageWrite, ApiServerTransactionRw, Transactional},
+    impls::in_memory::transactional::{
+        ApiServerInMemoryStorageSnapshot, TransactionalApiServerInMemoryStorage,
+    },
+    storage_api::{
+        ApiServerStorageRead, ApiServerStorageWrite, ApiServerTransactionRw, Transactional,
+    },
 };
 use api_web_server::{
     api::{
@@ -65,7 +72,8 @@ use common::{
             sighash::sighashtype::SigHashType,
         },
         transaction::output::timelock::OutputTimeLock,
-        Destination, OutPointSourceId, SignedTransaction, Transaction, TxInput, TxOutput,
+        Block, Destination, GenBlock, OutPointSourceId, SignedTransaction, Transaction, TxInput,
+        TxOutput,
     },
     primitives::{time::get_time, Amount, BlockHeight, Id, Idable},
 };
@@ -77,7 +85,122 @@ use std::{
     collections::BTreeMap,
     sync::{Arc, RwLock},
 };
-use test_utils::random::{make_seedable_rng, Rng, Seed};
+use test_utils::random::{make_seedable_rng, CryptoRng, Rng, Seed};
+
+/// Build a chain of `n_blocks` blocks and scan it into the storage.
+///
+/// Returns a snapshot of the storage, which the tests restore instead of scanning the chain
+/// themselves, along with the ids of the blocks built by the test framework.
+async fn common_test_setup(
+    rng: &mut (impl Rng + CryptoRng),
+    n_blocks: usize,
+) -> (ApiServerInMemoryStorageSnapshot, Vec<Id<Block>>) {
+    let chain_config = create_unit_test_config();
+
+    let (chainstate_block_ids, chainstate_blocks) = {
+        let mut tf = TestFramework::builder(rng).with_chain_config(chain_config.clone()).build();
+
+        let chainstate_block_ids = tf
+            .create_chain_return_ids(&tf.genesis().get_id().into(), n_blocks, rng)
+            .unwrap()
+            .into_iter()
+            .map(|id| tf.to_chain_block_id(&id))
+            .collect::<Vec<_>>();
+
+        let chainstate_blocks =
+            chainstate_block_ids.iter().map(|id| tf.block(*id)).collect::<Vec<_>>();
+
+        (chainstate_block_ids, chainstate_blocks)
+    };
+
+    let storage = {
+        let mut storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+
+        let mut db_tx = storage.transaction_rw().await.unwrap();
+        db_tx.reinitialize_storage(&chain_config).await.unwrap();
+        db_tx.commit().await.unwrap();
+
+        storage
+    };
+
+    let chain_config = Arc::new(chain_config);
+    let mut local_node = BlockchainState::new(Arc::clone(&chain_config), storage);
+    local_node.scan_genesis(chain_config.genesis_block()).await.unwrap();
+    local_node.scan_blocks(BlockHeight::new(0), chainstate_blocks).await.unwrap();
+
+    (local_node.storage().snapshot().await, chainstate_block_ids)
+}
+
+/// Check that the main chains stored in both storages are the same, block by block
+async fn assert_same_main_chain(
+    storage1: &TransactionalApiServerInMemoryStorage,
+    storage2: &TransactionalApiServerInMemoryStorage,
+) {
+    let db_tx1 = storage1.transaction_ro().await.unwrap();
+    let db_tx2 = storage2.transaction_ro().await.unwrap();
+
+    let best_block = db_tx1.get_best_block().await.unwrap();
+    assert_eq!(best_block, db_tx2.get_best_block().await.unwrap());
+
+    for height in 0..=best_block.block_height().into_int() {
+        let block_id1 = db_tx1.get_main_chain_block_id(BlockHeight::new(height)).await.unwrap();
+        let block_id2 = db_tx2.get_main_chain_block_id(BlockHeight::new(height)).await.unwrap();
+        assert_eq!(block_id1, block_id2);
+
+        if let Some(block_id) = block_id1 {
+            assert_eq!(
+                db_tx1.get_block(block_id).await.unwrap(),
+                db_tx2.get_block(block_id).await.unwrap()
+            );
+            assert_eq!(
+                db_tx1.get_block_aux_data(block_id).await.unwrap(),
+                db_tx2.get_block_aux_data(block_id).await.unwrap()
+            );
+        }
+    }
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn restore_common_setup(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let n_blocks = rng.gen_range(1..100);
+
+    let chain_config = create_unit_test_config();
+    let (snapshot, block_ids) = common_test_setup(&mut rng, n_blocks).await;
+
+    let mut storage1 = TransactionalApiServerInMemoryStorage::new(&chain_config);
+    storage1.restore(snapshot.clone()).await;
+    let mut storage2 = TransactionalApiServerInMemoryStorage::new(&chain_config);
+    storage2.restore(snapshot.clone()).await;
+
+    assert_same_main_chain(&storage1, &storage2).await;
+
+    let best_block = storage1.transaction_ro().await.unwrap().get_best_block().await.unwrap();
+    assert_eq!(best_block.block_height(), BlockHeight::new(n_blocks as u64));
+    assert_eq!(
+        best_block.block_id(),
+        Id::<GenBlock>::from(block_ids[n_blocks - 1])
+    );
+
+    // The changes to one restored storage don't leak into the others or into the snapshot
+    let mut db_tx = storage1.transaction_rw().await.unwrap();
+    db_tx.reinitialize_storage(&chain_config).await.unwrap();
+    db_tx.commit().await.unwrap();
+
+    let best_block = storage1.transaction_ro().await.unwrap().get_best_block().await.unwrap();
+    assert_eq!(best_block.block_height(), BlockHeight::new(0));
+
+    let mut storage3 = TransactionalApiServerInMemoryStorage::new(&chain_config);
+    storage3.restore(snapshot).await;
+
+    assert_same_main_chain(&storage2, &storage3).await;
+
+    let best_block = storage2.transaction_ro().await.unwrap().get_best_block().await.unwrap();
+    assert_eq!(best_block.block_height(), BlockHeight::new(n_blocks as u64));
+}
 
 #[tokio::test]
 async fn chain_genesis() {
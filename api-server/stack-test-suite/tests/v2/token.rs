@@ -16,12 +16,16 @@
 use api_server_common::storage::storage_api::FungibleTokenData;
 use api_web_server::api::json_helpers::{amount_to_json, to_json_string};
 use common::{
-    chain::tokens::{
-        make_token_id, IsTokenFreezable, IsTokenFrozen, TokenId, TokenIssuance, TokenIssuanceV1,
-        TokenTotalSupply,
+    chain::{
+        tokens::{
+            make_token_id, IsTokenFreezable, IsTokenFrozen, TokenId, TokenIssuance,
+            TokenIssuanceV1, TokenTotalSupply,
+        },
+        AccountCommand, AccountNonce, Block, ChainConfig,
     },
     primitives::H256,
 };
+use randomness::CryptoRng;
 
 use crate::DummyRPC;
 
@@ -131,6 +135,11 @@ async fn ok(#[case] seed: Seed) {
                     is_locked: false,
                     frozen: IsTokenFrozen::No(token_issuance.is_freezable),
                     authority: token_issuance.authority.clone(),
+                    minted_amount: Amount::ZERO,
+                    burned_amount: Amount::ZERO,
+                    holder_count: 0,
+                    issuance_block_height: BlockHeight::new(1),
+                    issuance_tx_id: transaction.transaction().get_id(),
                 };
 
                 _ = tx.send([(
@@ -141,6 +150,9 @@ async fn ok(#[case] seed: Seed) {
                         ).as_str(),
                         "is_locked": token_data.is_locked,
                         "circulating_supply": amount_to_json(token_data.circulating_supply, token_data.number_of_decimals),
+                        "minted_amount": amount_to_json(Amount::ZERO, token_data.number_of_decimals),
+                        "burned_amount": amount_to_json(token_data.burned_amount, token_data.number_of_decimals),
+                        "holder_count": token_data.holder_count,
                         "token_ticker": to_json_string(&token_data.token_ticker),
                         "metadata_uri": to_json_string(&token_data.metadata_uri),
                         "number_of_decimals": token_data.number_of_decimals,
@@ -148,6 +160,8 @@ async fn ok(#[case] seed: Seed) {
                         "frozen": false,
                         "is_token_freezable": false,
                         "is_token_unfreezable": None::<bool>,
+                        "issuance_block_height": token_data.issuance_block_height,
+                        "issuance_tx_id": token_data.issuance_tx_id,
                     }),
                 )]);
 
@@ -213,3 +227,332 @@ async fn ok(#[case] seed: Seed) {
 
     task.abort();
 }
+
+async fn get_token_json(
+    local_node: &BlockchainState<TransactionalApiServerInMemoryStorage>,
+    chain_config: &Arc<ChainConfig>,
+    token_id: TokenId,
+) -> serde_json::Value {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let web_server_state = ApiServerWebServerState {
+        db: Arc::new(local_node.storage().clone_storage().await),
+        chain_config: Arc::clone(chain_config),
+        rpc: Arc::new(DummyRPC {}),
+        cached_values: Arc::new(CachedValues {
+            feerate_points: RwLock::new((get_time(), vec![])),
+        }),
+        time_getter: Default::default(),
+    };
+    let task = tokio::spawn(async move { web_server(listener, web_server_state, false).await });
+
+    let token_id = Address::new(chain_config, token_id).unwrap();
+    let url = format!("/api/v2/token/{token_id}");
+    let response = reqwest::get(format!("http://{}:{}{url}", addr.ip(), addr.port()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let body = response.text().await.unwrap();
+    task.abort();
+
+    serde_json::from_str(&body).unwrap()
+}
+
+fn sign_input(
+    rng: &mut (impl Rng + CryptoRng),
+    sk: &PrivateKey,
+    destination: &Destination,
+    tx: &SignedTransaction,
+    input_utxos: &[Option<&TxOutput>],
+    input_index: usize,
+) -> InputWitness {
+    InputWitness::Standard(
+        StandardInputSignature::produce_uniparty_signature_for_input(
+            sk,
+            SigHashType::try_from(SigHashType::ALL).unwrap(),
+            destination.clone(),
+            tx,
+            input_utxos,
+            input_index,
+            rng,
+        )
+        .unwrap(),
+    )
+}
+
+fn process_block(
+    tf: &mut TestFramework,
+    rng: &mut (impl Rng + CryptoRng),
+    tx: SignedTransaction,
+) -> Block {
+    let block_id = *tf
+        .make_block_builder()
+        .add_transaction(tx)
+        .build_and_process(rng)
+        .unwrap()
+        .unwrap()
+        .block_id();
+    tf.block(tf.to_chain_block_id(&block_id.into()))
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn token_lifecycle(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut tf = TestFramework::builder(&mut rng)
+        .with_chain_config(chain_config.as_ref().clone())
+        .build();
+
+    let storage = {
+        let mut storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+
+        let mut db_tx = storage.transaction_rw().await.unwrap();
+        db_tx.reinitialize_storage(&chain_config).await.unwrap();
+        db_tx.commit().await.unwrap();
+
+        storage
+    };
+    let mut local_node = BlockchainState::new(Arc::clone(&chain_config), storage);
+    local_node.scan_genesis(chain_config.genesis_block()).await.unwrap();
+
+    let (alice_sk, alice_pk) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+    let alice_destination = Destination::PublicKeyHash(PublicKeyHash::from(&alice_pk));
+    let (bob_sk, bob_pk) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+    let bob_destination = Destination::PublicKeyHash(PublicKeyHash::from(&bob_pk));
+
+    let token_decimals = rng.gen_range(1..18);
+    let token_issuance = TokenIssuanceV1 {
+        token_ticker: "XXXX".as_bytes().to_vec(),
+        number_of_decimals: token_decimals,
+        metadata_uri: "http://uri".as_bytes().to_vec(),
+        total_supply: TokenTotalSupply::Lockable,
+        authority: alice_destination.clone(),
+        is_freezable: IsTokenFreezable::Yes,
+    };
+    let supply_change_fee = chain_config.token_supply_change_fee(BlockHeight::zero());
+
+    // Issue the token, leaving enough coins to pay for the supply changes
+    let fee_coins = (supply_change_fee * 3).unwrap();
+    let issue_tx = TransactionBuilder::new()
+        .add_input(
+            TxInput::from_utxo(
+                OutPointSourceId::BlockReward(tf.genesis().get_id().into()),
+                0,
+            ),
+            InputWitness::NoSignature(None),
+        )
+        .add_output(TxOutput::Transfer(
+            OutputValue::Coin(fee_coins),
+            Destination::AnyoneCanSpend,
+        ))
+        .add_output(TxOutput::IssueFungibleToken(Box::new(TokenIssuance::V1(
+            token_issuance.clone(),
+        ))))
+        .build();
+    let token_id = make_token_id(issue_tx.inputs()).unwrap();
+
+    let mut expected = json!({
+        "authority": Address::new(&chain_config, alice_destination.clone()).unwrap().as_str(),
+        "is_locked": false,
+        "circulating_supply": amount_to_json(Amount::ZERO, token_decimals),
+        "minted_amount": amount_to_json(Amount::ZERO, token_decimals),
+        "burned_amount": amount_to_json(Amount::ZERO, token_decimals),
+        "holder_count": 0,
+        "token_ticker": to_json_string(&token_issuance.token_ticker),
+        "metadata_uri": to_json_string(&token_issuance.metadata_uri),
+        "number_of_decimals": token_decimals,
+        "total_supply": TokenTotalSupply::Lockable,
+        "frozen": false,
+        "is_token_unfreezable": None::<bool>,
+        "is_token_freezable": true,
+        "issuance_block_height": BlockHeight::new(1),
+        "issuance_tx_id": issue_tx.transaction().get_id(),
+    });
+
+    let block = process_block(&mut tf, &mut rng, issue_tx.clone());
+    local_node.scan_blocks(BlockHeight::new(0), vec![block]).await.unwrap();
+    assert_eq!(
+        get_token_json(&local_node, &chain_config, token_id).await,
+        expected
+    );
+
+    // Mint the tokens to Alice and Bob
+    let alice_amount = Amount::from_atoms(rng.gen_range(2..1000));
+    let bob_amount = Amount::from_atoms(rng.gen_range(1..1000));
+    let minted = (alice_amount + bob_amount).unwrap();
+    let mint_tx = TransactionBuilder::new()
+        .add_input(
+            TxInput::from_utxo(issue_tx.transaction().get_id().into(), 0),
+            InputWitness::NoSignature(None),
+        )
+        .add_input(
+            TxInput::from_command(
+                AccountNonce::new(0),
+                AccountCommand::MintTokens(token_id, minted),
+            ),
+            InputWitness::NoSignature(None),
+        )
+        .add_output(TxOutput::Transfer(
+            OutputValue::Coin((supply_change_fee * 2).unwrap()),
+            Destination::AnyoneCanSpend,
+        ))
+        .add_output(TxOutput::Transfer(
+            OutputValue::TokenV1(token_id, alice_amount),
+            alice_destination.clone(),
+        ))
+        .add_output(TxOutput::Transfer(
+            OutputValue::TokenV1(token_id, bob_amount),
+            bob_destination.clone(),
+        ))
+        .build();
+    let authority_witness = sign_input(
+        &mut rng,
+        &alice_sk,
+        &alice_destination,
+        &mint_tx,
+        &[Some(&issue_tx.outputs()[0]), None],
+        1,
+    );
+    let mint_tx = SignedTransaction::new(
+        mint_tx.transaction().clone(),
+        vec![InputWitness::NoSignature(None), authority_witness],
+    )
+    .unwrap();
+
+    let block = process_block(&mut tf, &mut rng, mint_tx.clone());
+    local_node.scan_blocks(BlockHeight::new(1), vec![block]).await.unwrap();
+    expected["circulating_supply"] = amount_to_json(minted, token_decimals);
+    expected["minted_amount"] = amount_to_json(minted, token_decimals);
+    expected["holder_count"] = 2.into();
+    assert_eq!(
+        get_token_json(&local_node, &chain_config, token_id).await,
+        expected
+    );
+
+    // Unmint all of Bob's tokens
+    let unmint_tx = TransactionBuilder::new()
+        .add_input(
+            TxInput::from_utxo(mint_tx.transaction().get_id().into(), 0),
+            InputWitness::NoSignature(None),
+        )
+        .add_input(
+            TxInput::from_utxo(mint_tx.transaction().get_id().into(), 2),
+            InputWitness::NoSignature(None),
+        )
+        .add_input(
+            TxInput::from_command(AccountNonce::new(1), AccountCommand::UnmintTokens(token_id)),
+            InputWitness::NoSignature(None),
+        )
+        .add_output(TxOutput::Transfer(
+            OutputValue::Coin(supply_change_fee),
+            Destination::AnyoneCanSpend,
+        ))
+        .add_output(TxOutput::Burn(OutputValue::TokenV1(token_id, bob_amount)))
+        .build();
+    let input_utxos = [Some(&mint_tx.outputs()[0]), Some(&mint_tx.outputs()[2]), None];
+    let bob_witness = sign_input(
+        &mut rng,
+        &bob_sk,
+        &bob_destination,
+        &unmint_tx,
+        &input_utxos,
+        1,
+    );
+    let authority_witness = sign_input(
+        &mut rng,
+        &alice_sk,
+        &alice_destination,
+        &unmint_tx,
+        &input_utxos,
+        2,
+    );
+    let unmint_tx = SignedTransaction::new(
+        unmint_tx.transaction().clone(),
+        vec![InputWitness::NoSignature(None), bob_witness, authority_witness],
+    )
+    .unwrap();
+
+    let block = process_block(&mut tf, &mut rng, unmint_tx.clone());
+    local_node.scan_blocks(BlockHeight::new(2), vec![block]).await.unwrap();
+    expected["circulating_supply"] = amount_to_json(alice_amount, token_decimals);
+    expected["burned_amount"] = amount_to_json(bob_amount, token_decimals);
+    expected["holder_count"] = 1.into();
+    assert_eq!(
+        get_token_json(&local_node, &chain_config, token_id).await,
+        expected
+    );
+
+    // Alice burns part of her tokens without unminting them
+    let alice_burned = Amount::from_atoms(rng.gen_range(1..alice_amount.into_atoms()));
+    let burn_tx = TransactionBuilder::new()
+        .add_input(
+            TxInput::from_utxo(mint_tx.transaction().get_id().into(), 1),
+            InputWitness::NoSignature(None),
+        )
+        .add_output(TxOutput::Burn(OutputValue::TokenV1(token_id, alice_burned)))
+        .add_output(TxOutput::Transfer(
+            OutputValue::TokenV1(token_id, (alice_amount - alice_burned).unwrap()),
+            alice_destination.clone(),
+        ))
+        .build();
+    let alice_witness = sign_input(
+        &mut rng,
+        &alice_sk,
+        &alice_destination,
+        &burn_tx,
+        &[Some(&mint_tx.outputs()[1])],
+        0,
+    );
+    let burn_tx =
+        SignedTransaction::new(burn_tx.transaction().clone(), vec![alice_witness]).unwrap();
+
+    let block = process_block(&mut tf, &mut rng, burn_tx);
+    local_node.scan_blocks(BlockHeight::new(3), vec![block]).await.unwrap();
+    expected["burned_amount"] =
+        amount_to_json((bob_amount + alice_burned).unwrap(), token_decimals);
+    assert_eq!(
+        get_token_json(&local_node, &chain_config, token_id).await,
+        expected
+    );
+
+    // Lock the supply
+    let lock_tx = TransactionBuilder::new()
+        .add_input(
+            TxInput::from_utxo(unmint_tx.transaction().get_id().into(), 0),
+            InputWitness::NoSignature(None),
+        )
+        .add_input(
+            TxInput::from_command(
+                AccountNonce::new(2),
+                AccountCommand::LockTokenSupply(token_id),
+            ),
+            InputWitness::NoSignature(None),
+        )
+        .build();
+    let authority_witness = sign_input(
+        &mut rng,
+        &alice_sk,
+        &alice_destination,
+        &lock_tx,
+        &[Some(&unmint_tx.outputs()[0]), None],
+        1,
+    );
+    let lock_tx = SignedTransaction::new(
+        lock_tx.transaction().clone(),
+        vec![InputWitness::NoSignature(None), authority_witness],
+    )
+    .unwrap();
+
+    let block = process_block(&mut tf, &mut rng, lock_tx);
+    local_node.scan_blocks(BlockHeight::new(4), vec![block]).await.unwrap();
+    expected["is_locked"] = true.into();
+    assert_eq!(
+        get_token_json(&local_node, &chain_config, token_id).await,
+        expected
+    );
+}
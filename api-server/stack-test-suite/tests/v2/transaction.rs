@@ -13,7 +13,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use api_web_server::api::json_helpers::tx_input_to_json;
+use api_web_server::api::json_helpers::{amount_to_json, outputvalue_to_json, tx_input_to_json};
+use chainstate_test_framework::{anyonecanspend_address, get_output_value, output_value_amount};
 
 use super::*;
 
@@ -48,6 +49,26 @@ async fn transaction_not_found() {
     task.abort();
 }
 
+#[tokio::test]
+async fn invalid_resolve_inputs() {
+    let (task, response) = spawn_webserver(
+        "/api/v2/transaction/0000000000000000000000000000000000000000000000000000000000000001?resolve_inputs=maybe",
+    )
+    .await;
+
+    assert_eq!(response.status(), 400);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(
+        body["error"].as_str().unwrap(),
+        "Invalid resolve inputs query parameter"
+    );
+
+    task.abort();
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -430,3 +451,167 @@ async fn ok(#[case] seed: Seed) {
 
     task.abort();
 }
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn resolve_inputs(#[case] seed: Seed) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    let task = tokio::spawn(async move {
+        let web_server_state = {
+            let mut rng = make_seedable_rng(seed);
+            let chain_config = create_unit_test_config();
+
+            let chainstate_blocks = {
+                let mut tf = TestFramework::builder(&mut rng)
+                    .with_chain_config(chain_config.clone())
+                    .build();
+
+                // The first transaction spends the genesis output, the second one spends the
+                // output of the first one, both with a known fee
+                let genesis_output = tf.genesis().utxos()[0].clone();
+                let genesis_amount =
+                    output_value_amount(&get_output_value(&genesis_output).unwrap());
+
+                let fee1 = Amount::from_atoms(rng.gen_range(0..1000));
+                let output1 = TxOutput::Transfer(
+                    OutputValue::Coin((genesis_amount - fee1).unwrap()),
+                    anyonecanspend_address(),
+                );
+                let tx1 = TransactionBuilder::new()
+                    .add_input(
+                        TxInput::from_utxo(
+                            OutPointSourceId::BlockReward(tf.genesis().get_id().into()),
+                            0,
+                        ),
+                        InputWitness::NoSignature(None),
+                    )
+                    .add_output(output1.clone())
+                    .build();
+
+                let fee2 = Amount::from_atoms(rng.gen_range(0..1000));
+                let amount2 = Amount::from_atoms(rng.gen_range(1..1000));
+                let change2 =
+                    (((genesis_amount - fee1).unwrap() - fee2).unwrap() - amount2).unwrap();
+                let tx2 = TransactionBuilder::new()
+                    .add_input(
+                        TxInput::from_utxo(
+                            OutPointSourceId::Transaction(tx1.transaction().get_id()),
+                            0,
+                        ),
+                        InputWitness::NoSignature(None),
+                    )
+                    .add_output(TxOutput::Transfer(
+                        OutputValue::Coin(amount2),
+                        anyonecanspend_address(),
+                    ))
+                    .add_output(TxOutput::Transfer(
+                        OutputValue::Coin(change2),
+                        anyonecanspend_address(),
+                    ))
+                    .build();
+
+                let block1_id = *tf
+                    .make_block_builder()
+                    .add_transaction(tx1.clone())
+                    .build_and_process(&mut rng)
+                    .unwrap()
+                    .unwrap()
+                    .block_id();
+                let block2_id = *tf
+                    .make_block_builder()
+                    .add_transaction(tx2.clone())
+                    .build_and_process(&mut rng)
+                    .unwrap()
+                    .unwrap()
+                    .block_id();
+
+                _ = tx.send([
+                    (tx1.transaction().get_id(), fee1, genesis_output),
+                    (tx2.transaction().get_id(), fee2, output1),
+                ]);
+
+                vec![tf.block(block1_id), tf.block(block2_id)]
+            };
+
+            let storage = {
+                let mut storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+
+                let mut db_tx = storage.transaction_rw().await.unwrap();
+                db_tx.reinitialize_storage(&chain_config).await.unwrap();
+                db_tx.commit().await.unwrap();
+
+                storage
+            };
+
+            let chain_config = Arc::new(chain_config);
+            let mut local_node = BlockchainState::new(Arc::clone(&chain_config), storage);
+            local_node.scan_genesis(chain_config.genesis_block()).await.unwrap();
+            local_node.scan_blocks(BlockHeight::new(0), chainstate_blocks).await.unwrap();
+
+            ApiServerWebServerState {
+                db: Arc::new(local_node.storage().clone_storage().await),
+                chain_config: Arc::clone(&chain_config),
+                rpc: Arc::new(DummyRPC {}),
+                cached_values: Arc::new(CachedValues {
+                    feerate_points: RwLock::new((get_time(), vec![])),
+                }),
+                time_getter: Default::default(),
+            }
+        };
+
+        web_server(listener, web_server_state, true).await
+    });
+
+    let chain_config = create_unit_test_config();
+    let transactions = rx.await.unwrap();
+
+    for (transaction_id, fee, spent_output) in transactions {
+        let transaction_id = transaction_id.to_hash().encode_hex::<String>();
+        let url = format!("/api/v2/transaction/{transaction_id}?resolve_inputs=true");
+
+        // Given that the listener port is open, this will block until a
+        // response is made (by the web server, which takes the listener
+        // over)
+        let response = reqwest::get(format!("http://{}:{}{url}", addr.ip(), addr.port()))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+
+        let body = response.text().await.unwrap();
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let body = body.as_object().unwrap();
+
+        assert_eq!(
+            body.get("fee").unwrap(),
+            &amount_to_json(fee, chain_config.coin_decimals())
+        );
+
+        let (spent_value, spent_destination) = match &spent_output {
+            TxOutput::Transfer(value, destination) => (value, destination),
+            _ => panic!("unexpected spent output"),
+        };
+        let inputs = body.get("inputs").unwrap().as_array().unwrap();
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(
+            inputs[0]["utxo"],
+            txoutput_to_json(&spent_output, &chain_config, &TokenDecimals::Single(None))
+        );
+        assert_eq!(
+            inputs[0]["value"],
+            outputvalue_to_json(spent_value, &chain_config, &TokenDecimals::Single(None))
+        );
+        assert_eq!(
+            inputs[0]["destination"],
+            Address::new(&chain_config, spent_destination.clone()).unwrap().as_str()
+        );
+    }
+
+    task.abort();
+}
@@ -14,7 +14,10 @@
 // limitations under the License.
 
 use serialization::extras::non_empty_vec::DataOrNoVec;
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
 
 use crate::helpers::make_trial;
 use crate::make_test;
@@ -389,6 +392,28 @@ where
         db_tx.commit().await.unwrap();
     }
 
+    // Test setting/getting block transaction count
+    {
+        let mut db_tx = storage.transaction_rw().await.unwrap();
+
+        let random_block_id: Id<Block> = Id::<Block>::new(H256::random_using(&mut rng));
+        let count = db_tx.get_block_transaction_count(random_block_id).await.unwrap();
+        assert_eq!(count, None);
+
+        let count = rng.gen_range(0..i32::MAX as u32);
+        db_tx.set_block_transaction_count(block_id, count).await.unwrap();
+        let retrieved_count = db_tx.get_block_transaction_count(block_id).await.unwrap();
+        assert_eq!(retrieved_count, Some(count));
+
+        db_tx.commit().await.unwrap();
+
+        let db_tx = storage.transaction_ro().await.unwrap();
+        let retrieved_count = db_tx.get_block_transaction_count(block_id).await.unwrap();
+        assert_eq!(retrieved_count, Some(count));
+        let count = db_tx.get_block_transaction_count(random_block_id).await.unwrap();
+        assert_eq!(count, None);
+    }
+
     // Test setting/getting address spendable utxos
     {
         let db_tx = storage.transaction_ro().await.unwrap();
@@ -1124,6 +1149,11 @@ where
             is_locked: false,
             frozen: IsTokenFrozen::No(IsTokenFreezable::Yes),
             authority: random_destination,
+            minted_amount: Amount::ZERO,
+            burned_amount: Amount::ZERO,
+            holder_count: 0,
+            issuance_block_height: BlockHeight::new(rng.gen_range(1..100)),
+            issuance_tx_id: Id::new(H256::random_using(&mut rng)),
         };
 
         let mut db_tx = storage.transaction_rw().await.unwrap();
@@ -1142,6 +1172,7 @@ where
         let locked_token_data = token_data
             .clone()
             .mint_tokens(Amount::from_atoms(rng.gen_range(1..1000)))
+            .unwrap()
             .lock();
 
         db_tx
@@ -1158,6 +1189,44 @@ where
 
         assert_eq!(returned_token, locked_token_data);
 
+        let token_info = db_tx.get_token_info(random_token_id).await.unwrap().unwrap();
+        assert_eq!(
+            token_info,
+            locked_token_data.clone().into_token_info(random_token_id)
+        );
+        assert_eq!(
+            token_info.minted_amount,
+            locked_token_data.circulating_supply
+        );
+        assert_eq!(token_info.burned_amount, Amount::ZERO);
+        assert!(token_info.supply_locked);
+
+        // unminting burns the tokens via a burn output
+        let unminted_token_data = locked_token_data
+            .clone()
+            .unmint_tokens(Amount::from_atoms(1))
+            .unwrap()
+            .burn_tokens(Amount::from_atoms(1))
+            .unwrap();
+        let token_info = unminted_token_data.into_token_info(random_token_id);
+        assert_eq!(token_info.minted_amount, locked_token_data.minted_amount);
+        assert_eq!(token_info.burned_amount, Amount::from_atoms(1));
+        assert_eq!(
+            token_info.circulating_supply,
+            (locked_token_data.circulating_supply - Amount::from_atoms(1)).unwrap()
+        );
+
+        // tokens sent to a burn output don't change the circulating supply
+        let burned_token_data =
+            locked_token_data.clone().burn_tokens(Amount::from_atoms(1)).unwrap();
+        let token_info = burned_token_data.into_token_info(random_token_id);
+        assert_eq!(token_info.minted_amount, locked_token_data.minted_amount);
+        assert_eq!(token_info.burned_amount, Amount::from_atoms(1));
+        assert_eq!(
+            token_info.circulating_supply,
+            locked_token_data.circulating_supply
+        );
+
         // after reorg go back to the previous token data
         db_tx.del_token_issuance_above_height(block_height).await.unwrap();
         let returned_token =
@@ -1172,6 +1241,8 @@ where
 
         let token = db_tx.get_fungible_token_issuance(random_token_id).await.unwrap();
         assert!(token.is_none());
+        let token_info = db_tx.get_token_info(random_token_id).await.unwrap();
+        assert!(token_info.is_none());
 
         db_tx.commit().await.unwrap();
     }
@@ -1192,6 +1263,11 @@ where
             is_locked: false,
             frozen: IsTokenFrozen::No(IsTokenFreezable::Yes),
             authority: random_destination,
+            minted_amount: Amount::ZERO,
+            burned_amount: Amount::ZERO,
+            holder_count: 0,
+            issuance_block_height: BlockHeight::new(rng.gen_range(1..100)),
+            issuance_tx_id: Id::new(H256::random_using(&mut rng)),
         };
 
         let block_height = BlockHeight::new(rng.gen_range(1..100));
@@ -1366,6 +1442,221 @@ where
     Ok(())
 }
 
+pub async fn uncommitted_rw_tx_discarded<S, Fut, F>(
+    storage_maker: Arc<F>,
+    seed_maker: Box<dyn Fn() -> Seed + Send>,
+) -> Result<(), Failed>
+where
+    S: ApiServerStorage,
+    Fut: Future<Output = S> + Send + 'static,
+    F: Fn() -> Fut,
+{
+    let seed = seed_maker();
+
+    let mut rng = make_seedable_rng(seed);
+
+    let mut test_framework = TestFramework::builder(&mut rng).build();
+    let chain_config = test_framework.chain_config().clone();
+
+    let mut storage = storage_maker().await;
+    let mut db_tx = storage.transaction_rw().await.unwrap();
+    db_tx.reinitialize_storage(&chain_config).await.unwrap();
+    db_tx.commit().await.unwrap();
+
+    let genesis_id = chain_config.genesis_block_id();
+    test_framework
+        .create_chain_return_ids_with_advancing_time(&genesis_id, 1, &mut rng)
+        .unwrap();
+    let block_id = test_framework.block_id(1).classify(&chain_config).chain_block_id().unwrap();
+    let block = test_framework.block(block_id);
+    let block_height = BlockHeight::new(1);
+
+    let (_, pk) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+    let destination = Destination::PublicKeyHash(PublicKeyHash::from(&pk));
+    let address = Address::<Destination>::new(&chain_config, destination).unwrap();
+    let amount = Amount::from_atoms(rng.gen_range(1..1000));
+    let tx_id = Id::<Transaction>::new(H256::random_using(&mut rng));
+
+    // Write to several tables and abandon the transaction part way through
+    {
+        let mut db_tx = storage.transaction_rw().await.unwrap();
+
+        let block_with_extras = BlockWithExtraData {
+            block: block.clone(),
+            tx_additional_infos: vec![],
+        };
+        db_tx
+            .set_mainchain_block(block_id, block_height, &block_with_extras)
+            .await
+            .unwrap();
+        db_tx
+            .set_block_aux_data(
+                block_id,
+                &BlockAuxData::new(block_id.into(), block_height, block.timestamp()),
+            )
+            .await
+            .unwrap();
+        db_tx
+            .set_address_balance_at_height(
+                address.as_str(),
+                amount,
+                CoinOrTokenId::Coin,
+                block_height,
+            )
+            .await
+            .unwrap();
+
+        if rng.gen_bool(0.5) {
+            db_tx
+                .set_address_transactions_at_height(
+                    address.as_str(),
+                    [tx_id].into_iter().collect(),
+                    block_height,
+                )
+                .await
+                .unwrap();
+        }
+
+        drop(db_tx);
+    }
+
+    // None of the writes are visible, the storage is as it was before the transaction
+    {
+        let db_tx = storage.transaction_ro().await.unwrap();
+
+        let best_block = db_tx.get_best_block().await.unwrap();
+        assert_eq!(best_block.block_height(), BlockHeight::new(0));
+        assert_eq!(best_block.block_id(), genesis_id);
+
+        assert_eq!(db_tx.get_block(block_id).await.unwrap(), None);
+        assert_eq!(
+            db_tx.get_main_chain_block_id(block_height).await.unwrap(),
+            None
+        );
+        assert_eq!(db_tx.get_block_aux_data(block_id).await.unwrap(), None);
+        assert_eq!(
+            db_tx.get_address_balance(address.as_str(), CoinOrTokenId::Coin).await.unwrap(),
+            None
+        );
+        assert!(db_tx.get_address_transactions(address.as_str()).await.unwrap().is_empty());
+    }
+
+    // The same writes are visible once committed
+    {
+        let mut db_tx = storage.transaction_rw().await.unwrap();
+        db_tx
+            .set_address_balance_at_height(
+                address.as_str(),
+                amount,
+                CoinOrTokenId::Coin,
+                block_height,
+            )
+            .await
+            .unwrap();
+        db_tx.commit().await.unwrap();
+
+        let db_tx = storage.transaction_ro().await.unwrap();
+        assert_eq!(
+            db_tx.get_address_balance(address.as_str(), CoinOrTokenId::Coin).await.unwrap(),
+            Some(amount)
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn address_transactions_pagination<S, Fut, F>(
+    storage_maker: Arc<F>,
+    seed_maker: Box<dyn Fn() -> Seed + Send>,
+) -> Result<(), Failed>
+where
+    S: ApiServerStorage,
+    Fut: Future<Output = S> + Send + 'static,
+    F: Fn() -> Fut,
+{
+    let seed = seed_maker();
+
+    let mut rng = make_seedable_rng(seed);
+
+    let chain_config = create_unit_test_config();
+
+    let mut storage = storage_maker().await;
+    let mut db_tx = storage.transaction_rw().await.unwrap();
+    db_tx.reinitialize_storage(&chain_config).await.unwrap();
+    db_tx.commit().await.unwrap();
+
+    let make_address = |rng: &mut _| {
+        let (_, public_key) = PrivateKey::new_from_rng(rng, KeyKind::Secp256k1Schnorr);
+        let destination = Destination::PublicKeyHash(PublicKeyHash::from(&public_key));
+        Address::<Destination>::new(&chain_config, destination).unwrap()
+    };
+    let address = make_address(&mut rng);
+    let other_address = make_address(&mut rng);
+
+    // Insert 50 transactions spread over several blocks, the newest first in the expected order
+    const TX_COUNT: usize = 50;
+    let mut expected_tx_ids = Vec::new();
+    {
+        let mut db_tx = storage.transaction_rw().await.unwrap();
+        let mut height = 1;
+        while expected_tx_ids.len() < TX_COUNT {
+            let block_tx_count = rng.gen_range(1..=TX_COUNT - expected_tx_ids.len());
+            let tx_ids: BTreeSet<_> = (0..block_tx_count)
+                .map(|_| Id::<Transaction>::new(H256::random_using(&mut rng)))
+                .collect();
+            db_tx
+                .set_address_transactions_at_height(
+                    address.as_str(),
+                    tx_ids.clone(),
+                    BlockHeight::new(height),
+                )
+                .await
+                .unwrap();
+
+            expected_tx_ids.splice(0..0, tx_ids);
+            height += 1;
+        }
+        db_tx.commit().await.unwrap();
+    }
+
+    let db_tx = storage.transaction_ro().await.unwrap();
+
+    assert_eq!(
+        db_tx.get_address_transaction_count(address.as_str()).await.unwrap(),
+        TX_COUNT as u64
+    );
+    assert_eq!(
+        db_tx.get_address_transaction_count(other_address.as_str()).await.unwrap(),
+        0
+    );
+    assert!(db_tx
+        .get_address_transactions_page(other_address.as_str(), 10, 0)
+        .await
+        .unwrap()
+        .is_empty());
+
+    for _ in 0..10 {
+        let len = rng.gen_range(0..=TX_COUNT + 10);
+        let offset = rng.gen_range(0..=TX_COUNT + 10);
+
+        let page = db_tx
+            .get_address_transactions_page(address.as_str(), len as u32, offset as u32)
+            .await
+            .unwrap();
+        let expected_page =
+            expected_tx_ids.iter().skip(offset).take(len).copied().collect::<Vec<_>>();
+        assert_eq!(page, expected_page);
+
+        // The count doesn't depend on the page
+        assert_eq!(
+            db_tx.get_address_transaction_count(address.as_str()).await.unwrap(),
+            TX_COUNT as u64
+        );
+    }
+
+    Ok(())
+}
+
 pub fn build_tests<S, Fut, F: Fn() -> Fut + Send + Sync + 'static>(
     storage_maker: Arc<F>,
 ) -> impl Iterator<Item = libtest_mimic::Trial>
@@ -1375,7 +1666,9 @@ where
 {
     vec![
         make_test!(initialization, storage_maker.clone()),
-        make_test!(set_get, storage_maker),
+        make_test!(set_get, storage_maker.clone()),
+        make_test!(uncommitted_rw_tx_discarded, storage_maker.clone()),
+        make_test!(address_transactions_pagination, storage_maker),
     ]
     .into_iter()
 }
@@ -395,6 +395,68 @@ pub fn tx_to_json(
     })
 }
 
+/// Inputs of the transaction, each one with the value and the destination of the output it spends
+/// next to the output itself. The fields are null if the input does not spend an output or the
+/// spent output is not known.
+pub fn resolved_inputs_to_json(
+    tx: &Transaction,
+    additional_info: &TxAdditionalInfo,
+    chain_config: &ChainConfig,
+) -> serde_json::Value {
+    let token_decimals: TokenDecimals = (&additional_info.token_decimals).into();
+
+    tx.inputs()
+        .iter()
+        .zip(additional_info.input_utxos.iter().map(Option::as_ref))
+        .map(|(inp, utxo)| {
+            let value = utxo.and_then(spent_output_value);
+            let destination = utxo.and_then(spent_output_destination);
+            json!({
+                "input": tx_input_to_json(inp, chain_config, &token_decimals),
+                "utxo": utxo.map(|txo| txoutput_to_json(txo, chain_config, &token_decimals)),
+                "value": value.map(|value| outputvalue_to_json(&value, chain_config, &token_decimals)),
+                "destination": destination.map(|dest| Address::new(chain_config, dest.clone()).expect("no error").to_string()),
+            })
+        })
+        .collect()
+}
+
+fn spent_output_value(out: &TxOutput) -> Option<OutputValue> {
+    match out {
+        TxOutput::Transfer(v, _) | TxOutput::LockThenTransfer(v, _, _) | TxOutput::Htlc(v, _) => {
+            Some(v.clone())
+        }
+        TxOutput::CreateStakePool(_, data) => Some(OutputValue::Coin(data.pledge())),
+        TxOutput::IssueNft(token_id, _, _) => {
+            Some(OutputValue::TokenV1(*token_id, Amount::from_atoms(1)))
+        }
+        TxOutput::ProduceBlockFromStake(_, _)
+        | TxOutput::Burn(_)
+        | TxOutput::CreateDelegationId(_, _)
+        | TxOutput::DelegateStaking(_, _)
+        | TxOutput::IssueFungibleToken(_)
+        | TxOutput::DataDeposit(_)
+        | TxOutput::AnyoneCanTake(_) => None,
+    }
+}
+
+fn spent_output_destination(out: &TxOutput) -> Option<&Destination> {
+    match out {
+        TxOutput::Transfer(_, d)
+        | TxOutput::LockThenTransfer(_, d, _)
+        | TxOutput::IssueNft(_, _, d)
+        | TxOutput::ProduceBlockFromStake(d, _) => Some(d),
+        TxOutput::CreateStakePool(_, data) => Some(data.decommission_key()),
+        TxOutput::Htlc(_, _)
+        | TxOutput::Burn(_)
+        | TxOutput::CreateDelegationId(_, _)
+        | TxOutput::DelegateStaking(_, _)
+        | TxOutput::IssueFungibleToken(_)
+        | TxOutput::DataDeposit(_)
+        | TxOutput::AnyoneCanTake(_) => None,
+    }
+}
+
 pub fn to_tx_json_with_block_info(
     tx: &TransactionInfo,
     chain_config: &ChainConfig,
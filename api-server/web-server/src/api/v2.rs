@@ -15,8 +15,8 @@
 
 use crate::{
     api::json_helpers::{
-        amount_to_json, block_header_to_json, to_tx_json_with_block_info, tx_to_json,
-        txoutput_to_json, utxo_outpoint_to_json, TokenDecimals,
+        amount_to_json, block_header_to_json, resolved_inputs_to_json, to_tx_json_with_block_info,
+        tx_to_json, txoutput_to_json, utxo_outpoint_to_json, TokenDecimals,
     },
     error::{
         ApiServerWebServerClientError, ApiServerWebServerError, ApiServerWebServerForbiddenError,
@@ -44,6 +44,8 @@ use common::{
     primitives::{Amount, BlockHeight, CoinOrTokenId, Id, Idable, H256},
 };
 use hex::ToHex;
+use mempool::{FeeRate, MempoolConfig};
+use node_comm::rpc_client::NodeRpcError;
 use serde::Deserialize;
 use serde_json::json;
 use serialization::hex_encoded::HexEncoded;
@@ -75,7 +77,8 @@ pub fn routes<
         .route("/block/:id", get(block))
         .route("/block/:id/header", get(block_header))
         .route("/block/:id/reward", get(block_reward))
-        .route("/block/:id/transaction-ids", get(block_transaction_ids));
+        .route("/block/:id/transaction-ids", get(block_transaction_ids))
+        .route("/block/:id/transaction-count", get(block_transaction_count));
 
     let router = if enable_post_routes {
         router.route(
@@ -86,7 +89,9 @@ pub fn routes<
         router.route("/transaction", post(forbidden_request))
     };
 
-    let router = router.route("/feerate", get(feerate));
+    let router = router
+        .route("/feerate", get(feerate))
+        .route("/mempool/fee-estimates", get(fee_estimates));
 
     let router = router
         .route("/transaction", get(transactions))
@@ -95,6 +100,7 @@ pub fn routes<
 
     let router = router
         .route("/address/:address", get(address))
+        .route("/address/:address/transactions", get(address_transactions))
         .route("/address/:address/all-utxos", get(all_address_utxos))
         .route("/address/:address/spendable-utxos", get(address_utxos))
         .route("/address/:address/delegations", get(address_delegations));
@@ -222,6 +228,37 @@ pub async fn block_transaction_ids<T: ApiServerStorage>(
     Ok(Json(json!(transaction_ids)))
 }
 
+pub async fn block_transaction_count<T: ApiServerStorage>(
+    Path(block_id): Path<String>,
+    State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
+) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    let block_id: Id<Block> = H256::from_str(&block_id)
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidBlockId)
+        })?
+        .into();
+
+    let transaction_count = state
+        .db
+        .transaction_ro()
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?
+        .get_block_transaction_count(block_id)
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?
+        .ok_or(ApiServerWebServerError::NotFound(
+            ApiServerWebServerNotFoundError::BlockNotFound,
+        ))?;
+
+    Ok(Json(json!({ "transaction_count": transaction_count })))
+}
+
 //
 // chain/
 //
@@ -339,46 +376,34 @@ async fn get_transaction(
         ))
 }
 
-pub async fn feerate<T: ApiServerStorage>(
-    Query(params): Query<BTreeMap<String, String>>,
-    State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
-) -> Result<impl IntoResponse, ApiServerWebServerError> {
+/// Get the mempool feerate points from the node, they are cached for `REFRESH_INTERVAL_SEC`
+async fn cached_feerate_points<T>(
+    state: &ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>,
+) -> Result<BTreeMap<usize, FeeRate>, NodeRpcError> {
     const REFRESH_INTERVAL_SEC: Duration = Duration::from_secs(30);
-    const IN_TOP_X_MB: &str = "in_top_x_mb";
-    const DEFAULT_IN_TOP_X_MB: usize = 5;
-    let in_top_x_mb = params
-        .get(IN_TOP_X_MB)
-        .map(|str| usize::from_str(str))
-        .transpose()
-        .map_err(|_| ApiServerWebServerClientError::InvalidInTopX)?
-        .unwrap_or(DEFAULT_IN_TOP_X_MB);
 
     let feerate_points = &state.cached_values.feerate_points;
 
-    let feerate_points: BTreeMap<_, _> = {
-        let current_time = state.time_getter.get_time();
-        let last_cache_time = feerate_points.read().expect("should not fail normally").0;
-
-        if (last_cache_time + REFRESH_INTERVAL_SEC).expect("no overflow") < current_time {
-            let new_feerate_points = {
-                state.rpc.get_feerate_points().await.map_err(|e| {
-                    logging::log::error!("internal error: {e}");
-                    ApiServerWebServerError::ServerError(
-                        ApiServerWebServerServerError::InternalServerError,
-                    )
-                })?
-            };
+    let current_time = state.time_getter.get_time();
+    let last_cache_time = feerate_points.read().expect("should not fail normally").0;
 
-            let mut guard = feerate_points.write().expect("should not fail normally");
-            guard.0 = current_time;
-            guard.1 = new_feerate_points;
-            guard.1.iter().map(|(size, feerate)| (*size, *feerate)).collect()
-        } else {
-            let guard = feerate_points.read().expect("should not fail normally");
-            guard.1.iter().map(|(size, feerate)| (*size, *feerate)).collect()
-        }
-    };
+    if (last_cache_time + REFRESH_INTERVAL_SEC).expect("no overflow") < current_time {
+        let new_feerate_points = state.rpc.get_feerate_points().await?;
+
+        let mut guard = feerate_points.write().expect("should not fail normally");
+        guard.0 = current_time;
+        guard.1 = new_feerate_points;
+        Ok(guard.1.iter().map(|(size, feerate)| (*size, *feerate)).collect())
+    } else {
+        let guard = feerate_points.read().expect("should not fail normally");
+        Ok(guard.1.iter().map(|(size, feerate)| (*size, *feerate)).collect())
+    }
+}
 
+fn feerate_in_top_x_mb(
+    feerate_points: &BTreeMap<usize, FeeRate>,
+    in_top_x_mb: usize,
+) -> Result<FeeRate, ApiServerWebServerError> {
     let (min_size, max_feerate) = feerate_points.first_key_value().expect("not empty");
     let (max_size, min_feerate) = feerate_points.last_key_value().expect("not empty");
     let feerate = if in_top_x_mb <= *min_size {
@@ -386,7 +411,7 @@ pub async fn feerate<T: ApiServerStorage>(
     } else if in_top_x_mb >= *max_size {
         *min_feerate
     } else {
-        mempool::find_interpolated_value(&feerate_points, in_top_x_mb).ok_or_else(|| {
+        mempool::find_interpolated_value(feerate_points, in_top_x_mb).ok_or_else(|| {
             logging::log::error!(
                 "internal error: could not calculate feerate {in_top_x_mb} {feerate_points:?}"
             );
@@ -394,11 +419,77 @@ pub async fn feerate<T: ApiServerStorage>(
         })?
     };
 
+    Ok(feerate)
+}
+
+pub async fn feerate<T: ApiServerStorage>(
+    Query(params): Query<BTreeMap<String, String>>,
+    State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
+) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    const IN_TOP_X_MB: &str = "in_top_x_mb";
+    const DEFAULT_IN_TOP_X_MB: usize = 5;
+    let in_top_x_mb = params
+        .get(IN_TOP_X_MB)
+        .map(|str| usize::from_str(str))
+        .transpose()
+        .map_err(|_| ApiServerWebServerClientError::InvalidInTopX)?
+        .unwrap_or(DEFAULT_IN_TOP_X_MB);
+
+    let feerate_points = cached_feerate_points(&state).await.map_err(|e| {
+        logging::log::error!("internal error: {e}");
+        ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+    })?;
+
+    let feerate = feerate_in_top_x_mb(&feerate_points, in_top_x_mb)?;
+
     Ok(Json(
         serde_json::to_value(feerate.atoms_per_kb().to_string()).expect("should not fail"),
     ))
 }
 
+/// Fee rates, per kB, to get a transaction confirmed within 10 (slow), 5 (medium) and 2 (fast)
+/// blocks, estimated from the mempool transactions that would be included before it.
+///
+/// If the node can't be reached, multiples of the default minimum relay fee rate are returned.
+pub async fn fee_estimates<T: ApiServerStorage>(
+    State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
+) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    const SLOW_BLOCKS: usize = 10;
+    const MEDIUM_BLOCKS: usize = 5;
+    const FAST_BLOCKS: usize = 2;
+
+    let (slow, medium, fast) = match cached_feerate_points(&state).await {
+        Ok(feerate_points) => {
+            let max_block_size = state.chain_config.max_block_size_from_std_scripts();
+            let within_blocks = |blocks: usize| {
+                let in_top_x_mb = (blocks * max_block_size).div_ceil(1_000_000);
+                feerate_in_top_x_mb(&feerate_points, in_top_x_mb)
+            };
+            (
+                within_blocks(SLOW_BLOCKS)?,
+                within_blocks(MEDIUM_BLOCKS)?,
+                within_blocks(FAST_BLOCKS)?,
+            )
+        }
+        Err(e) => {
+            logging::log::warn!("Failed to get the feerate points, using the fallback: {e}");
+            let min_relay_feerate = *MempoolConfig::new().min_tx_relay_fee_rate;
+            let times = |n: u128| FeeRate::from_atoms_per_kb(min_relay_feerate.atoms_per_kb() * n);
+            (min_relay_feerate, times(2), times(5))
+        }
+    };
+
+    let decimals = state.chain_config.coin_decimals();
+    let to_json =
+        |feerate: FeeRate| amount_to_json(Amount::from_atoms(feerate.atoms_per_kb()), decimals);
+
+    Ok(Json(json!({
+        "slow": to_json(slow),
+        "medium": to_json(medium),
+        "fast": to_json(fast),
+    })))
+}
+
 pub async fn submit_transaction<T: ApiServerStorage>(
     State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
     body: String,
@@ -479,8 +570,18 @@ pub async fn transactions<T: ApiServerStorage>(
 
 pub async fn transaction<T: ApiServerStorage>(
     Path(transaction_id): Path<String>,
+    Query(params): Query<BTreeMap<String, String>>,
     State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
 ) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    const RESOLVE_INPUTS: &str = "resolve_inputs";
+
+    let resolve_inputs = params
+        .get(RESOLVE_INPUTS)
+        .map(|resolve| bool::from_str(resolve))
+        .transpose()
+        .map_err(|_| ApiServerWebServerClientError::InvalidResolveInputs)?
+        .unwrap_or(false);
+
     let (block, TransactionInfo { tx, additinal_info }) =
         get_transaction(&transaction_id, &state).await?;
 
@@ -493,6 +594,13 @@ pub async fn transaction<T: ApiServerStorage>(
     let mut json = tx_to_json(tx.transaction(), &additinal_info, &state.chain_config);
     let obj = json.as_object_mut().expect("object");
 
+    if resolve_inputs {
+        obj.insert(
+            "inputs".into(),
+            resolved_inputs_to_json(tx.transaction(), &additinal_info, &state.chain_config),
+        );
+    }
+
     obj.insert(
         "block_id".into(),
         block
@@ -637,6 +745,73 @@ pub async fn address<T: ApiServerStorage>(
     })))
 }
 
+pub async fn address_transactions<T: ApiServerStorage>(
+    Path(address): Path<String>,
+    Query(params): Query<BTreeMap<String, String>>,
+    State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
+) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    const OFFSET: &str = "offset";
+    const ITEMS: &str = "items";
+    const DEFAULT_NUM_ITEMS: u32 = 10;
+    const MAX_NUM_ITEMS: u32 = 100;
+
+    let address =
+        Address::<Destination>::from_string(&state.chain_config, &address).map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidAddress)
+        })?;
+
+    let offset = params
+        .get(OFFSET)
+        .map(|offset| u32::from_str(offset))
+        .transpose()
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidOffset)
+        })?
+        .unwrap_or_default();
+
+    let items = params
+        .get(ITEMS)
+        .map(|items| u32::from_str(items))
+        .transpose()
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidNumItems)
+        })?
+        .unwrap_or(DEFAULT_NUM_ITEMS);
+    ensure!(
+        items <= MAX_NUM_ITEMS,
+        ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidNumItems)
+    );
+
+    let tx = state.db.transaction_ro().await.map_err(|e| {
+        logging::log::error!("internal error: {e}");
+        ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+    })?;
+
+    let total_count = tx.get_address_transaction_count(address.as_str()).await.map_err(|e| {
+        logging::log::error!("internal error: {e}");
+        ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+    })?;
+
+    // if there is no transaction history then return not found
+    ensure!(
+        total_count != 0,
+        ApiServerWebServerError::NotFound(ApiServerWebServerNotFoundError::AddressNotFound)
+    );
+
+    let transactions = tx
+        .get_address_transactions_page(address.as_str(), items, offset)
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?;
+
+    Ok(Json(json!({
+    "transactions": transactions,
+    "total_count": total_count,
+    })))
+}
+
 pub async fn address_utxos<T: ApiServerStorage>(
     Path(address): Path<String>,
     State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
@@ -1046,7 +1221,7 @@ pub async fn token<T: ApiServerStorage>(
             logging::log::error!("internal error: {e}");
             ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
         })?
-        .get_fungible_token_issuance(token_id)
+        .get_token_info(token_id)
         .await
         .map_err(|e| {
             logging::log::error!("internal error: {e}");
@@ -1077,15 +1252,20 @@ pub async fn token<T: ApiServerStorage>(
         "authority": Address::new(&state.chain_config, token.authority).expect(
             "no error in encoding"
         ).as_str(),
-        "is_locked": token.is_locked,
-        "circulating_supply": amount_to_json(token.circulating_supply, token.number_of_decimals),
-        "token_ticker": to_json_string(&token.token_ticker),
+        "is_locked": token.supply_locked,
+        "circulating_supply": amount_to_json(token.circulating_supply, token.decimals),
+        "minted_amount": amount_to_json(token.minted_amount, token.decimals),
+        "burned_amount": amount_to_json(token.burned_amount, token.decimals),
+        "holder_count": token.holder_count,
+        "token_ticker": to_json_string(&token.ticker),
         "metadata_uri": to_json_string(&token.metadata_uri),
-        "number_of_decimals": token.number_of_decimals,
+        "number_of_decimals": token.decimals,
         "total_supply": token.total_supply,
         "frozen": frozen,
         "is_token_unfreezable": unfreezable,
         "is_token_freezable": freezable,
+        "issuance_block_height": token.issuance_block_height,
+        "issuance_tx_id": token.issuance_tx_id,
     })))
 }
 
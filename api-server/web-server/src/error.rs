@@ -101,6 +101,8 @@ pub enum ApiServerWebServerClientError {
     InvalidNftId,
     #[error("Invalid in top X MB query parameter")]
     InvalidInTopX,
+    #[error("Invalid resolve inputs query parameter")]
+    InvalidResolveInputs,
 }
 
 #[allow(dead_code)]
@@ -0,0 +1,106 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp;
+
+use chainstate::chainstate_interface::ChainstateInterface;
+use common::{
+    chain::{block::timestamp::BlockTimestamp, ChainConfig, GenBlock, RequiredConsensus},
+    primitives::{BlockHeight, Id},
+};
+use consensus::ConsensusCreationError;
+
+use crate::BlockProductionError;
+
+use super::utils::{
+    calculate_median_time_past, get_best_block_index, make_ancestor_getter, timestamp_add_secs,
+};
+
+/// The values that the header of a new block built on top of the current best block
+/// must have in order to pass the header checks.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    rpc::description::HasValueHint,
+)]
+pub struct BlockTemplateHeaderInfo {
+    pub prev_block_id: Id<GenBlock>,
+    pub height: BlockHeight,
+    /// The compact target (PoS) or bits (PoW) of the block; `None` if consensus is ignored.
+    pub target_bits: Option<u32>,
+    pub min_timestamp: BlockTimestamp,
+    /// The maximum timestamp accepted at the moment; it grows as the time goes on.
+    pub max_timestamp: BlockTimestamp,
+}
+
+pub fn get_block_template_header_info<CS: ChainstateInterface + ?Sized>(
+    chain_config: &ChainConfig,
+    chainstate: &CS,
+    current_time: BlockTimestamp,
+) -> Result<BlockTemplateHeaderInfo, BlockProductionError> {
+    let best_block_index = get_best_block_index(chainstate)?;
+    let prev_block_id = best_block_index.block_id();
+    let height = best_block_index.block_height().next_height();
+
+    let median_time_past = calculate_median_time_past(chainstate, &prev_block_id)?;
+
+    let (target_bits, min_timestamp) =
+        match chain_config.consensus_upgrades().consensus_status(height) {
+            RequiredConsensus::PoW(pow_status) => {
+                // Note: the PoW target may depend on the block timestamp; the current time is
+                // used here, same as when the block is generated.
+                let target = consensus::calculate_work_required(
+                    chain_config,
+                    &best_block_index,
+                    current_time,
+                    &pow_status,
+                    make_ancestor_getter(chainstate),
+                )
+                .map_err(ConsensusCreationError::MiningError)?;
+
+                (Some(target.0), median_time_past)
+            }
+            RequiredConsensus::PoS(pos_status) => {
+                let target = consensus::calculate_target_required_from_block_index(
+                    chain_config,
+                    &pos_status,
+                    &best_block_index,
+                    make_ancestor_getter(chainstate),
+                )
+                .map_err(ConsensusCreationError::StakingError)?;
+
+                // PoS blocks must also have a timestamp greater than the parent's one.
+                let after_parent = timestamp_add_secs(best_block_index.block_timestamp(), 1)?;
+
+                (Some(target.0), cmp::max(median_time_past, after_parent))
+            }
+            RequiredConsensus::IgnoreConsensus => (None, median_time_past),
+        };
+
+    let max_future_offset = chain_config.max_future_block_time_offset(height).as_secs();
+    let max_timestamp = timestamp_add_secs(current_time, max_future_offset)?;
+
+    Ok(BlockTemplateHeaderInfo {
+        prev_block_id,
+        height,
+        target_bits,
+        min_timestamp,
+        max_timestamp,
+    })
+}
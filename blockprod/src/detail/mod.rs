@@ -13,7 +13,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod block_template;
 pub mod job_manager;
+pub mod reorg_simulation;
 pub mod timestamp_searcher;
 pub mod utils;
 
@@ -44,6 +46,7 @@ use consensus::{
     PoSGenerateBlockInputData,
 };
 use crypto::ephemeral_e2e::{self, EndToEndPrivateKey};
+use logging::log;
 use mempool::{tx_accumulator::PackingStrategy, MempoolHandle};
 use p2p::P2pHandle;
 use randomness::{make_true_rng, Rng};
@@ -64,6 +67,7 @@ use crate::{
 };
 
 use self::{
+    block_template::{get_block_template_header_info, BlockTemplateHeaderInfo},
     timestamp_searcher::TimestampSearchData,
     utils::{
         calculate_median_time_past, get_best_block_index, get_pool_staker_balance,
@@ -491,7 +495,16 @@ impl BlockProduction {
         .await?
         .ok_or(BlockProductionError::RecoverableMempoolError)?;
 
-        let block_body = BlockBody::new(block_reward, collected_transactions);
+        log::debug!(
+            "Collected {} transactions for a block on top of {}, expected total fees: {}",
+            collected_transactions.transactions.len(),
+            current_tip_index.block_id(),
+            collected_transactions
+                .total_fees
+                .into_fixedpoint_str(self.chain_config.coin_decimals()),
+        );
+
+        let block_body = BlockBody::new(block_reward, collected_transactions.transactions);
 
         // A synchronous channel that sends only when the mining/staking is done
         let (ended_sender, ended_receiver) = mpsc::channel::<()>();
@@ -632,6 +645,19 @@ impl BlockProduction {
         )
         .await
     }
+
+    pub async fn get_block_template_header_info_impl(
+        &self,
+    ) -> Result<BlockTemplateHeaderInfo, BlockProductionError> {
+        let current_time = BlockTimestamp::from_time(self.time_getter().get_time());
+
+        self.chainstate_handle
+            .call({
+                let chain_config = Arc::clone(&self.chain_config);
+                move |cs| get_block_template_header_info(&chain_config, cs, current_time)
+            })
+            .await?
+    }
 }
 
 fn generate_finalize_block_data_pos(
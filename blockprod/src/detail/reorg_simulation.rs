@@ -0,0 +1,226 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Regtest-only helpers that trigger controlled reorgs on a single node.
+
+use std::time::Duration;
+
+use chainstate::BlockSource;
+use common::{
+    chain::{config::ChainType, Block, GenBlock},
+    primitives::{BlockHeight, Id, Idable},
+};
+use consensus::GenerateBlockInputData;
+use logging::log;
+use mempool::tx_accumulator::PackingStrategy;
+use utils::ensure;
+
+use crate::BlockProductionError;
+
+use super::BlockProduction;
+
+/// How long to wait for the mempool to process a new tip before giving up
+const MEMPOOL_TIP_SYNC_TIMEOUT: Duration = Duration::from_secs(30);
+const MEMPOOL_TIP_SYNC_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The result of a simulated reorg.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    rpc::description::HasValueHint,
+)]
+pub struct SimulatedReorgInfo {
+    /// The ids of the generated blocks, starting from the one right above the fork point
+    pub new_block_ids: Vec<Id<Block>>,
+    pub best_block_id: Id<GenBlock>,
+    pub best_block_height: BlockHeight,
+    /// Whether the generated blocks ended up in the main chain
+    pub is_new_chain_best: bool,
+}
+
+impl BlockProduction {
+    /// Replace the top `depth` main chain blocks with `count` newly generated ones.
+    ///
+    /// The replaced blocks are only invalidated while the new ones are generated, so if
+    /// the new chain has less trust than the old one, the old chain becomes the best again.
+    pub async fn invalidate_tip_and_remine_impl(
+        &self,
+        input_data: GenerateBlockInputData,
+        depth: u32,
+        count: u32,
+    ) -> Result<SimulatedReorgInfo, BlockProductionError> {
+        self.ensure_regtest()?;
+
+        let best_block_height =
+            self.chainstate_handle.call(|cs| cs.get_best_block_height()).await??;
+
+        let fork_height = best_block_height
+            .into_int()
+            .checked_sub(depth.into())
+            .filter(|_| depth > 0)
+            .map(BlockHeight::new)
+            .ok_or(BlockProductionError::InvalidReorgDepth(
+                depth,
+                best_block_height,
+            ))?;
+
+        self.mine_side_chain(input_data, fork_height, count).await
+    }
+
+    /// Generate a side chain of `count` blocks on top of the main chain block at `height`.
+    ///
+    /// The side chain becomes the main chain if it has more trust than the current one.
+    pub async fn fork_at_impl(
+        &self,
+        input_data: GenerateBlockInputData,
+        height: BlockHeight,
+        count: u32,
+    ) -> Result<SimulatedReorgInfo, BlockProductionError> {
+        self.ensure_regtest()?;
+        self.mine_side_chain(input_data, height, count).await
+    }
+
+    fn ensure_regtest(&self) -> Result<(), BlockProductionError> {
+        let chain_type = *self.chain_config.chain_type();
+        ensure!(
+            chain_type == ChainType::Regtest,
+            BlockProductionError::ReorgSimulationNotAllowed(chain_type.name().to_owned())
+        );
+        Ok(())
+    }
+
+    async fn mine_side_chain(
+        &self,
+        input_data: GenerateBlockInputData,
+        fork_height: BlockHeight,
+        count: u32,
+    ) -> Result<SimulatedReorgInfo, BlockProductionError> {
+        ensure!(count > 0, BlockProductionError::ZeroReorgBlockCount);
+
+        let best_block_height =
+            self.chainstate_handle.call(|cs| cs.get_best_block_height()).await??;
+        ensure!(
+            fork_height < best_block_height,
+            BlockProductionError::InvalidForkHeight(fork_height, best_block_height)
+        );
+
+        let first_replaced_block_id = self
+            .chainstate_handle
+            .call(move |cs| cs.get_block_id_from_height(&fork_height.next_height()))
+            .await??
+            .and_then(|id| id.classify(&self.chain_config).chain_block_id())
+            .ok_or(BlockProductionError::NoBlockForHeight(
+                fork_height.next_height(),
+            ))?;
+
+        // Invalidating the first block above the fork point makes the fork point the tip,
+        // so that the new blocks are generated on top of it.
+        self.chainstate_handle
+            .call_mut(move |cs| cs.invalidate_block(&first_replaced_block_id))
+            .await??;
+
+        let mine_result = self.mine_blocks(input_data, count).await;
+
+        // The flags are cleared even if mining has failed, so that the node state is clean
+        self.chainstate_handle
+            .call_mut(move |cs| cs.reset_block_failure_flags(&first_replaced_block_id))
+            .await??;
+
+        let new_block_ids = mine_result?;
+        let last_new_block_id = *new_block_ids.last().expect("count is not zero");
+
+        let (best_block_id, best_block_height, is_new_chain_best) = self
+            .chainstate_handle
+            .call(move |cs| -> Result<_, chainstate::ChainstateError> {
+                Ok((
+                    cs.get_best_block_id()?,
+                    cs.get_best_block_height()?,
+                    cs.is_block_in_main_chain(&last_new_block_id.into())?,
+                ))
+            })
+            .await??;
+
+        self.wait_for_mempool_tip(best_block_id).await?;
+
+        log::info!(
+            "Generated {} blocks on top of height {}, new chain is best: {}",
+            new_block_ids.len(),
+            fork_height,
+            is_new_chain_best
+        );
+
+        Ok(SimulatedReorgInfo {
+            new_block_ids,
+            best_block_id,
+            best_block_height,
+            is_new_chain_best,
+        })
+    }
+
+    /// Generate and process blocks on top of the current tip.
+    ///
+    /// The blocks are left empty, so that the transactions of the disconnected blocks stay
+    /// in the mempool.
+    async fn mine_blocks(
+        &self,
+        input_data: GenerateBlockInputData,
+        count: u32,
+    ) -> Result<Vec<Id<Block>>, BlockProductionError> {
+        let mut block_ids = Vec::with_capacity(count as usize);
+
+        for _ in 0..count {
+            // The mempool refuses to collect transactions until it has caught up with the tip
+            let tip_id = self.chainstate_handle.call(|cs| cs.get_best_block_id()).await??;
+            self.wait_for_mempool_tip(tip_id).await?;
+
+            let (block, job_finished_receiver) = self
+                .produce_block(
+                    input_data.clone(),
+                    vec![],
+                    vec![],
+                    PackingStrategy::LeaveEmptySpace,
+                )
+                .await?;
+
+            // The only error that can happen is if the channel is closed. We don't care about that here.
+            let _finished = job_finished_receiver.await;
+
+            let block_id = block.get_id();
+            self.chainstate_handle
+                .call_mut(move |cs| cs.process_block(block, BlockSource::Local))
+                .await??;
+            block_ids.push(block_id);
+        }
+
+        Ok(block_ids)
+    }
+
+    async fn wait_for_mempool_tip(&self, tip_id: Id<GenBlock>) -> Result<(), BlockProductionError> {
+        let wait = async {
+            while self.mempool_handle.call(|m| m.best_block_id()).await? != tip_id {
+                tokio::time::sleep(MEMPOOL_TIP_SYNC_POLL_INTERVAL).await;
+            }
+            Ok::<_, BlockProductionError>(())
+        };
+
+        tokio::time::timeout(MEMPOOL_TIP_SYNC_TIMEOUT, wait)
+            .await
+            .map_err(|_| BlockProductionError::MempoolTipSyncTimeout(tip_id))?
+    }
+}
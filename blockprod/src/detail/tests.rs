@@ -26,15 +26,15 @@ use chainstate::{ChainstateError, ChainstateHandle, GenBlockIndex, PropertyQuery
 use chainstate_test_framework::TransactionBuilder;
 use common::{
     chain::{
-        block::timestamp::BlockTimestamp,
+        block::{timestamp::BlockTimestamp, ConsensusData},
         config::{create_unit_test_config, Builder, ChainType},
         output_value::OutputValue,
         signature::inputsig::InputWitness,
         stakelock::StakePoolData,
         timelock::OutputTimeLock,
         transaction::TxInput,
-        CoinUnit, ConsensusUpgrade, Destination, Genesis, NetUpgrades, OutPointSourceId, PoolId,
-        RequiredConsensus, TxOutput,
+        Block, CoinUnit, ConsensusUpgrade, Destination, Genesis, NetUpgrades, OutPointSourceId,
+        PoolId, RequiredConsensus, Transaction, TxOutput,
     },
     primitives::{per_thousand::PerThousand, Amount, BlockHeight, Id, Idable, H256},
     time_getter::TimeGetter,
@@ -49,14 +49,11 @@ use crypto::{
     vrf::{VRFKeyKind, VRFPrivateKey},
 };
 use mempool::{
-    error::{BlockConstructionError, TxValidationError},
-    tx_accumulator::{DefaultTxAccumulator, PackingStrategy},
-    tx_origin::LocalTxOrigin,
+    error::BlockConstructionError, tx_accumulator::PackingStrategy, tx_origin::LocalTxOrigin,
     TxOptions,
 };
 use mocks::{MockChainstateInterface, MockMempoolInterface};
 use randomness::Rng;
-use subsystem::error::ResponseError;
 use test_utils::{
     mock_time_getter::mocked_time_getter_seconds,
     random::{make_seedable_rng, Seed},
@@ -87,37 +84,29 @@ mod collect_transactions {
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn collect_txs_failed() {
-        let (mut manager, chain_config, _chainstate, _mempool, _p2p) =
+        let (manager, chain_config, _chainstate, mempool, _p2p) =
             setup_blockprod_test(None, TimeGetter::default());
 
-        let mut mock_mempool = MockMempoolInterface::default();
-        mock_mempool.expect_collect_txs().return_once(|_, _, _| {
-            Err(BlockConstructionError::Validity(
-                TxValidationError::CallError(ResponseError::NoResponse.into()),
-            ))
-        });
-
-        let mock_mempool_subsystem = manager.add_subsystem("mock-mempool", mock_mempool);
-
-        let current_tip = Id::new(H256::zero());
+        let current_tip = chain_config.genesis_block_id();
+        let missing_tx_id = Id::<Transaction>::new(H256::zero());
 
         let shutdown = manager.make_shutdown_trigger();
         let tester = tokio::spawn(async move {
             let transactions = collect_transactions(
-                &mock_mempool_subsystem,
+                &mempool,
                 &chain_config,
                 current_tip,
                 DUMMY_TIMESTAMP,
                 vec![],
-                vec![],
+                vec![missing_tx_id],
                 PackingStrategy::FillSpaceFromMempool,
             )
             .await;
 
             match transactions {
                 Err(BlockProductionError::MempoolBlockConstruction(
-                    BlockConstructionError::Validity(TxValidationError::CallError(_)),
-                )) => {}
+                    BlockConstructionError::TxNotFound(tx_id),
+                )) => assert_eq!(tx_id, missing_tx_id),
                 _ => panic!("Expected collect_tx() to fail"),
             };
 
@@ -173,25 +162,10 @@ mod collect_transactions {
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn succeeded() {
-        let (mut manager, chain_config, _chainstate, _mempool, _p2p) =
+        let (manager, chain_config, _chainstate, mempool, _p2p) =
             setup_blockprod_test(None, TimeGetter::default());
 
-        let mut mock_mempool = MockMempoolInterface::default();
-
-        mock_mempool
-            .expect_collect_txs()
-            .returning(|_, _, _| {
-                Ok(Some(Box::new(DefaultTxAccumulator::new(
-                    usize::default(),
-                    Id::new(H256::zero()),
-                    DUMMY_TIMESTAMP,
-                ))))
-            })
-            .times(1);
-
-        let mock_mempool_subsystem = manager.add_subsystem("mock-mempool", mock_mempool);
-
-        let current_tip = Id::new(H256::zero());
+        let current_tip = chain_config.genesis_block_id();
 
         let join_handle = tokio::spawn({
             let shutdown_trigger = manager.make_shutdown_trigger();
@@ -202,7 +176,7 @@ mod collect_transactions {
                 });
 
                 let transactions = collect_transactions(
-                    &mock_mempool_subsystem,
+                    &mempool,
                     &chain_config,
                     current_tip,
                     DUMMY_TIMESTAMP,
@@ -217,10 +191,12 @@ mod collect_transactions {
                     "Expected collect_transactions() to succeed"
                 );
 
+                let transactions = transactions.unwrap();
                 assert!(
-                    transactions.unwrap().is_some(),
+                    transactions.is_some(),
                     "Expected collect_transactions() to return Some"
                 );
+                assert_eq!(transactions.unwrap().total_fees, Amount::ZERO);
             }
         });
 
@@ -819,18 +795,10 @@ mod produce_block {
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn transaction_source_mempool_error() {
-        let (mut manager, chain_config, chainstate, _mempool, p2p) =
+        let (manager, chain_config, chainstate, mempool, p2p) =
             setup_blockprod_test(None, TimeGetter::default());
 
-        let mut mock_mempool = MockMempoolInterface::default();
-
-        mock_mempool.expect_collect_txs().return_once(|_, _, _| {
-            Err(BlockConstructionError::Validity(
-                TxValidationError::CallError(ResponseError::NoResponse.into()),
-            ))
-        });
-
-        let mempool_subsystem = manager.add_subsystem("mock-mempool", mock_mempool);
+        let missing_tx_id = Id::<Transaction>::new(H256::zero());
 
         let join_handle = tokio::spawn({
             let shutdown_trigger = manager.make_shutdown_trigger();
@@ -844,7 +812,7 @@ mod produce_block {
                     chain_config,
                     Arc::new(test_blockprod_config()),
                     chainstate.clone(),
-                    mempool_subsystem,
+                    mempool,
                     p2p,
                     Default::default(),
                     prepare_thread_pool(1),
@@ -855,15 +823,15 @@ mod produce_block {
                     .produce_block(
                         GenerateBlockInputData::None,
                         vec![],
-                        vec![],
+                        vec![missing_tx_id],
                         PackingStrategy::FillSpaceFromMempool,
                     )
                     .await;
 
                 match result {
                     Err(BlockProductionError::MempoolBlockConstruction(
-                        BlockConstructionError::Validity(TxValidationError::CallError(_)),
-                    )) => {}
+                        BlockConstructionError::TxNotFound(tx_id),
+                    )) => assert_eq!(tx_id, missing_tx_id),
                     _ => panic!("Unexpected return value: {result:?}"),
                 }
             }
@@ -1878,6 +1846,293 @@ mod produce_block {
     }
 }
 
+mod block_template_header_info {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn matches_produced_block() {
+        let override_chain_config = {
+            let net_upgrades = NetUpgrades::initialize(vec![(
+                BlockHeight::new(0),
+                ConsensusUpgrade::PoW {
+                    initial_difficulty: Uint256::MAX.into(),
+                },
+            )])
+            .expect("Net upgrade is valid");
+
+            Builder::new(ChainType::Regtest).consensus_upgrades(net_upgrades).build()
+        };
+
+        let (manager, chain_config, chainstate, mempool, p2p) =
+            setup_blockprod_test(Some(override_chain_config), TimeGetter::default());
+
+        let join_handle = tokio::spawn({
+            let shutdown_trigger = manager.make_shutdown_trigger();
+            async move {
+                // Ensure a shutdown signal will be sent by the end of the scope
+                let _shutdown_signal = OnceDestructor::new(move || {
+                    shutdown_trigger.initiate();
+                });
+
+                let block_production = BlockProduction::new(
+                    chain_config,
+                    Arc::new(test_blockprod_config()),
+                    chainstate.clone(),
+                    mempool.clone(),
+                    p2p,
+                    Default::default(),
+                    prepare_thread_pool(1),
+                )
+                .expect("Error initializing blockprod");
+
+                let info = block_production.get_block_template_header_info_impl().await.unwrap();
+
+                let (new_block, job_finished_receiver) = block_production
+                    .produce_block(
+                        GenerateBlockInputData::PoW(Box::new(PoWGenerateBlockInputData::new(
+                            Destination::AnyoneCanSpend,
+                        ))),
+                        vec![],
+                        vec![],
+                        PackingStrategy::LeaveEmptySpace,
+                    )
+                    .await
+                    .expect("Failed to produce a block: {:?}");
+
+                job_finished_receiver.await.expect("Job finished receiver closed");
+
+                assert_eq!(info.prev_block_id, *new_block.header().prev_block_id());
+                assert_eq!(info.height, BlockHeight::new(1));
+                assert!(info.min_timestamp <= new_block.timestamp());
+                assert!(new_block.timestamp() <= info.max_timestamp);
+                match new_block.consensus_data() {
+                    ConsensusData::PoW(pow_data) => {
+                        assert_eq!(info.target_bits, Some(pow_data.bits().0))
+                    }
+                    ConsensusData::None | ConsensusData::PoS(_) => {
+                        panic!("Unexpected consensus data")
+                    }
+                }
+
+                let header_check = chainstate
+                    .call({
+                        let header = new_block.header().clone();
+                        move |this| this.check_block_header(&header)
+                    })
+                    .await
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(header_check.height, info.height);
+                assert!(header_check.extends_best_chain);
+
+                assert_process_block(&chainstate, &mempool, new_block).await;
+            }
+        });
+
+        manager.main().await;
+        join_handle.await.unwrap();
+    }
+}
+
+mod reorg_simulation {
+    use super::*;
+
+    fn pow_chain_config(chain_type: ChainType) -> ChainConfig {
+        let net_upgrades = NetUpgrades::initialize(vec![(
+            BlockHeight::new(0),
+            ConsensusUpgrade::PoW {
+                initial_difficulty: Uint256::MAX.into(),
+            },
+        )])
+        .expect("Net upgrade is valid");
+
+        Builder::new(chain_type).consensus_upgrades(net_upgrades).build()
+    }
+
+    fn pow_input_data() -> GenerateBlockInputData {
+        GenerateBlockInputData::PoW(Box::new(PoWGenerateBlockInputData::new(
+            Destination::AnyoneCanSpend,
+        )))
+    }
+
+    async fn is_in_main_chain(chainstate: &ChainstateHandle, block_id: Id<Block>) -> bool {
+        chainstate
+            .call(move |cs| cs.is_block_in_main_chain(&block_id.into()))
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn reorgs() {
+        let (manager, chain_config, chainstate, mempool, p2p) = setup_blockprod_test(
+            Some(pow_chain_config(ChainType::Regtest)),
+            TimeGetter::default(),
+        );
+
+        let join_handle = tokio::spawn({
+            let shutdown_trigger = manager.make_shutdown_trigger();
+            async move {
+                // Ensure a shutdown signal will be sent by the end of the scope
+                let _shutdown_signal = OnceDestructor::new(move || {
+                    shutdown_trigger.initiate();
+                });
+
+                let block_production = BlockProduction::new(
+                    chain_config,
+                    Arc::new(test_blockprod_config()),
+                    chainstate.clone(),
+                    mempool.clone(),
+                    p2p,
+                    Default::default(),
+                    prepare_thread_pool(1),
+                )
+                .expect("Error initializing blockprod");
+
+                let mut old_block_ids = Vec::new();
+                for _ in 0..5 {
+                    let (block, job_finished_receiver) = block_production
+                        .produce_block(
+                            pow_input_data(),
+                            vec![],
+                            vec![],
+                            PackingStrategy::LeaveEmptySpace,
+                        )
+                        .await
+                        .unwrap();
+                    job_finished_receiver.await.expect("Job finished receiver closed");
+                    old_block_ids.push(block.get_id());
+                    assert_process_block(&chainstate, &mempool, block).await;
+                }
+
+                // Invalid depths and fork heights are rejected
+                for depth in [0, 6] {
+                    assert_eq!(
+                        block_production
+                            .invalidate_tip_and_remine_impl(pow_input_data(), depth, 1)
+                            .await,
+                        Err(BlockProductionError::InvalidReorgDepth(
+                            depth,
+                            BlockHeight::new(5)
+                        ))
+                    );
+                }
+                assert_eq!(
+                    block_production.fork_at_impl(pow_input_data(), BlockHeight::new(5), 1).await,
+                    Err(BlockProductionError::InvalidForkHeight(
+                        BlockHeight::new(5),
+                        BlockHeight::new(5)
+                    ))
+                );
+                assert_eq!(
+                    block_production.invalidate_tip_and_remine_impl(pow_input_data(), 1, 0).await,
+                    Err(BlockProductionError::ZeroReorgBlockCount)
+                );
+
+                // Replace the top 3 blocks with 4 new ones
+                let info = block_production
+                    .invalidate_tip_and_remine_impl(pow_input_data(), 3, 4)
+                    .await
+                    .unwrap();
+                assert_eq!(info.new_block_ids.len(), 4);
+                assert_eq!(info.best_block_height, BlockHeight::new(6));
+                assert_eq!(
+                    info.best_block_id,
+                    (*info.new_block_ids.last().unwrap()).into()
+                );
+                assert!(info.is_new_chain_best);
+                for block_id in &old_block_ids[2..] {
+                    assert!(!is_in_main_chain(&chainstate, *block_id).await);
+                }
+                for block_id in &info.new_block_ids {
+                    assert!(is_in_main_chain(&chainstate, *block_id).await);
+                }
+
+                // The old blocks aren't marked as invalid anymore
+                let old_tip_id = *old_block_ids.last().unwrap();
+                let old_tip_status = chainstate
+                    .call(move |cs| cs.get_block_index_for_persisted_block(&old_tip_id))
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .unwrap()
+                    .status();
+                assert!(old_tip_status.is_ok());
+
+                // A shorter side chain doesn't become the best one
+                let best_block_id = info.best_block_id;
+                let info = block_production
+                    .fork_at_impl(pow_input_data(), BlockHeight::new(3), 1)
+                    .await
+                    .unwrap();
+                assert_eq!(info.new_block_ids.len(), 1);
+                assert_eq!(info.best_block_id, best_block_id);
+                assert_eq!(info.best_block_height, BlockHeight::new(6));
+                assert!(!info.is_new_chain_best);
+                assert!(!is_in_main_chain(&chainstate, info.new_block_ids[0]).await);
+
+                // A longer side chain becomes the best one
+                let info = block_production
+                    .fork_at_impl(pow_input_data(), BlockHeight::new(5), 2)
+                    .await
+                    .unwrap();
+                assert_eq!(info.best_block_height, BlockHeight::new(7));
+                assert!(info.is_new_chain_best);
+                assert_eq!(
+                    mempool.call(|m| m.best_block_id()).await.unwrap(),
+                    info.best_block_id
+                );
+            }
+        });
+
+        manager.main().await;
+        join_handle.await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn not_regtest() {
+        let (manager, chain_config, chainstate, mempool, p2p) = setup_blockprod_test(
+            Some(pow_chain_config(ChainType::Testnet)),
+            TimeGetter::default(),
+        );
+
+        let join_handle = tokio::spawn({
+            let shutdown_trigger = manager.make_shutdown_trigger();
+            async move {
+                // Ensure a shutdown signal will be sent by the end of the scope
+                let _shutdown_signal = OnceDestructor::new(move || {
+                    shutdown_trigger.initiate();
+                });
+
+                let block_production = BlockProduction::new(
+                    chain_config,
+                    Arc::new(test_blockprod_config()),
+                    chainstate.clone(),
+                    mempool.clone(),
+                    p2p,
+                    Default::default(),
+                    prepare_thread_pool(1),
+                )
+                .expect("Error initializing blockprod");
+
+                let expected_error =
+                    BlockProductionError::ReorgSimulationNotAllowed("testnet".to_owned());
+                assert_eq!(
+                    block_production.invalidate_tip_and_remine_impl(pow_input_data(), 1, 1).await,
+                    Err(expected_error.clone())
+                );
+                assert_eq!(
+                    block_production.fork_at_impl(pow_input_data(), BlockHeight::new(0), 1).await,
+                    Err(expected_error)
+                );
+            }
+        });
+
+        manager.main().await;
+        join_handle.await.unwrap();
+    }
+}
+
 mod process_block_with_custom_id {
     use super::*;
 
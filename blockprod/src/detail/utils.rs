@@ -287,6 +287,14 @@ pub fn timestamp_add_secs(
     Ok(timestamp)
 }
 
+/// The transactions collected for a new block
+pub struct CollectedTransactions {
+    pub transactions: Vec<SignedTransaction>,
+    /// The expected total fee of the collected transactions. The fees of the transactions
+    /// passed explicitly are not known at this point, so they are not included.
+    pub total_fees: Amount,
+}
+
 /// Collect transactions from the mempool.
 /// Ok(None) means that a recoverable error happened (such as that the mempool tip moved).
 pub async fn collect_transactions(
@@ -297,7 +305,7 @@ pub async fn collect_transactions(
     transactions: Vec<SignedTransaction>,
     transaction_ids: Vec<Id<Transaction>>,
     packing_strategy: PackingStrategy,
-) -> Result<Option<Vec<SignedTransaction>>, BlockProductionError> {
+) -> Result<Option<CollectedTransactions>, BlockProductionError> {
     let mut accumulator = Box::new(DefaultTxAccumulator::new(
         chain_config.max_block_size_from_std_scripts(),
         current_tip,
@@ -312,12 +320,28 @@ pub async fn collect_transactions(
             .map_err(|err| BlockProductionError::FailedToAddTransaction(transaction_id, err))?
     }
 
-    let returned_accumulator = mempool_handle
-        .call(move |mempool| mempool.collect_txs(accumulator, transaction_ids, packing_strategy))
-        .await??;
+    // Collect the transactions from a mempool snapshot, so the mempool can keep accepting
+    // transactions while the block is being assembled.
+    let mempool_snapshot = mempool_handle.call(|mempool| mempool.snapshot()).await?;
+    let task_join_result = tokio::task::spawn_blocking(move || {
+        mempool_snapshot.collect_txs(accumulator, transaction_ids, packing_strategy)
+    })
+    .await;
+
+    let returned_accumulator = match task_join_result {
+        Ok(result) => result?,
+        Err(join_err) => {
+            if join_err.is_panic() {
+                std::panic::resume_unwind(join_err.into_panic());
+            }
+            return Err(BlockProductionError::Cancelled);
+        }
+    };
 
-    let transactions = returned_accumulator
-        .map(|returned_accumulator| returned_accumulator.transactions().to_vec());
+    let transactions = returned_accumulator.map(|returned_accumulator| CollectedTransactions {
+        transactions: returned_accumulator.transactions().to_vec(),
+        total_fees: returned_accumulator.total_fees().into(),
+    });
 
     Ok(transactions)
 }
@@ -13,7 +13,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{detail::job_manager::JobKey, BlockProductionError, TimestampSearchData};
+use crate::{
+    detail::job_manager::JobKey, BlockProductionError, BlockTemplateHeaderInfo, SimulatedReorgInfo,
+    TimestampSearchData,
+};
 use common::{
     chain::{Block, PoolId, SignedTransaction, Transaction},
     primitives::{BlockHeight, Id},
@@ -73,4 +76,28 @@ pub trait BlockProductionInterface: Send + Sync {
         seconds_to_check_for_height: u64,
         check_all_timestamps_between_blocks: bool,
     ) -> Result<TimestampSearchData, BlockProductionError>;
+
+    /// Get the values that the header of a new block built on top of the current best block
+    /// must have: the previous block id, the expected target and the allowed timestamp range.
+    async fn get_block_template_header_info(
+        &self,
+    ) -> Result<BlockTemplateHeaderInfo, BlockProductionError>;
+
+    /// Regtest only: replace the top `depth` main chain blocks with `count` newly generated
+    /// empty blocks and clear the invalidation flags of the replaced blocks afterwards.
+    async fn invalidate_tip_and_remine(
+        &mut self,
+        input_data: GenerateBlockInputData,
+        depth: u32,
+        count: u32,
+    ) -> Result<SimulatedReorgInfo, BlockProductionError>;
+
+    /// Regtest only: generate a side chain of `count` empty blocks on top of the main chain
+    /// block at `height`.
+    async fn fork_at(
+        &mut self,
+        input_data: GenerateBlockInputData,
+        height: BlockHeight,
+        count: u32,
+    ) -> Result<SimulatedReorgInfo, BlockProductionError>;
 }
@@ -15,7 +15,7 @@
 
 use crate::{
     detail::{job_manager::JobKey, BlockProduction},
-    BlockProductionError, TimestampSearchData,
+    BlockProductionError, BlockTemplateHeaderInfo, SimulatedReorgInfo, TimestampSearchData,
 };
 use common::{
     chain::{Block, PoolId, SignedTransaction, Transaction},
@@ -90,6 +90,30 @@ impl BlockProductionInterface for BlockProduction {
         )
         .await
     }
+
+    async fn get_block_template_header_info(
+        &self,
+    ) -> Result<BlockTemplateHeaderInfo, BlockProductionError> {
+        self.get_block_template_header_info_impl().await
+    }
+
+    async fn invalidate_tip_and_remine(
+        &mut self,
+        input_data: GenerateBlockInputData,
+        depth: u32,
+        count: u32,
+    ) -> Result<SimulatedReorgInfo, BlockProductionError> {
+        self.invalidate_tip_and_remine_impl(input_data, depth, count).await
+    }
+
+    async fn fork_at(
+        &mut self,
+        input_data: GenerateBlockInputData,
+        height: BlockHeight,
+        count: u32,
+    ) -> Result<SimulatedReorgInfo, BlockProductionError> {
+        self.fork_at_impl(input_data, height, count).await
+    }
 }
 
 impl subsystem::Subsystem for Box<dyn BlockProductionInterface> {
@@ -41,7 +41,11 @@ use mempool::{tx_accumulator::TxAccumulatorError, MempoolHandle};
 use p2p::P2pHandle;
 use subsystem::error::CallError;
 
-pub use detail::timestamp_searcher::{find_timestamps_for_staking, TimestampSearchData};
+pub use detail::{
+    block_template::BlockTemplateHeaderInfo,
+    reorg_simulation::SimulatedReorgInfo,
+    timestamp_searcher::{find_timestamps_for_staking, TimestampSearchData},
+};
 
 #[derive(thiserror::Error, Debug, PartialEq, Eq)]
 pub enum BlockProductionError {
@@ -101,6 +105,18 @@ pub enum BlockProductionError {
     RecoverableMempoolError,
     #[error("Task exited prematurely")]
     TaskExitedPrematurely,
+    #[error("Chainstate operation failed: {0}")]
+    ChainstateOperationError(#[from] chainstate::ChainstateError),
+    #[error("Reorg simulation is only allowed on regtest, the current chain is {0}")]
+    ReorgSimulationNotAllowed(String),
+    #[error("The number of blocks to generate in a reorg simulation must not be zero")]
+    ZeroReorgBlockCount,
+    #[error("Invalid reorg depth {0}, the best block height is {1}")]
+    InvalidReorgDepth(u32, BlockHeight),
+    #[error("Fork height {0} must be below the best block height {1}")]
+    InvalidForkHeight(BlockHeight, BlockHeight),
+    #[error("Mempool did not catch up with the tip {0} in time")]
+    MempoolTipSyncTimeout(Id<GenBlock>),
 }
 
 pub type BlockProductionSubsystem = Box<dyn BlockProductionInterface>;
@@ -150,7 +166,7 @@ pub fn test_blockprod_config() -> BlockProdConfig {
 #[cfg(test)]
 mod tests {
     use std::{
-        net::{Ipv4Addr, SocketAddrV4},
+        net::{Ipv4Addr, SocketAddr, SocketAddrV4},
         time::Duration,
     };
 
@@ -209,7 +225,8 @@ mod tests {
                                 tip_sx.send(()).unwrap();
                             }
                         }
-                        mempool::event::MempoolEvent::TransactionProcessed(_) => (),
+                        mempool::event::MempoolEvent::TransactionProcessed(_)
+                        | mempool::event::MempoolEvent::TransactionConflict(_) => (),
                     }
                 }))
             })
@@ -274,6 +291,12 @@ mod tests {
             max_db_commit_attempts: Default::default(),
             max_orphan_blocks: Default::default(),
             min_max_bootstrap_import_buffer_sizes: Default::default(),
+            max_block_size_histogram_range: Default::default(),
+            ibd_write_batching: Default::default(),
+            max_write_batch_blocks: Default::default(),
+            max_write_batch_size: Default::default(),
+            storage_sync_policy: Default::default(),
+            upgrade_warning_window: Default::default(),
         };
 
         let mempool_config = MempoolConfig::new();
@@ -299,7 +322,8 @@ mod tests {
         let mempool = manager.add_custom_subsystem("mempool", |hdl| mempool.init(hdl));
 
         let mut p2p_config = test_p2p_config();
-        p2p_config.bind_addresses = vec![SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0).into()];
+        p2p_config.bind_addresses =
+            vec![SocketAddr::from(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0)).into()];
 
         let p2p = p2p::make_p2p(
             true,
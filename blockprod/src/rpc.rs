@@ -25,7 +25,9 @@ use mempool::tx_accumulator::PackingStrategy;
 use rpc::RpcResult;
 use serialization::hex_encoded::HexEncoded;
 
-use crate::{detail::job_manager::JobKey, TimestampSearchData};
+use crate::{
+    detail::job_manager::JobKey, BlockTemplateHeaderInfo, SimulatedReorgInfo, TimestampSearchData,
+};
 
 #[rpc::describe]
 #[rpc::rpc(server, client, namespace = "blockprod")]
@@ -92,6 +94,47 @@ trait BlockProductionRpc {
         seconds_to_check_for_height: u64,
         all_timestamps_between_blocks: bool,
     ) -> RpcResult<HexEncoded<TimestampSearchData>>;
+
+    /// Get the values that the header of a new block built on top of the current best block
+    /// must have in order to be accepted by the node.
+    ///
+    /// The previous block id, the height, the expected compact target (PoS) or bits (PoW)
+    /// and the allowed timestamp range are returned. The maximum timestamp depends on the
+    /// current time, so it grows as the time goes on.
+    #[method(name = "get_block_template_header_info")]
+    async fn get_block_template_header_info(&self) -> RpcResult<BlockTemplateHeaderInfo>;
+
+    /// Replace the top `depth` main chain blocks with `count` newly generated empty blocks.
+    ///
+    /// Only allowed on regtest. The replaced blocks are invalidated while the new blocks are
+    /// generated and their invalidation flags are cleared afterwards, so if the new chain has
+    /// less trust than the old one, the old chain becomes the best again.
+    /// The transactions of the disconnected blocks are returned to the mempool.
+    ///
+    /// Parameters:
+    /// - `input_data`: The input data for block generation, same as in `generate_block`.
+    /// - `depth`: The number of the top main chain blocks to replace.
+    /// - `count`: The number of the new blocks to generate.
+    #[method(name = "invalidate_tip_and_remine")]
+    async fn invalidate_tip_and_remine(
+        &self,
+        input_data: HexEncoded<GenerateBlockInputData>,
+        depth: u32,
+        count: u32,
+    ) -> RpcResult<SimulatedReorgInfo>;
+
+    /// Generate a side chain of `count` empty blocks branching from the main chain block
+    /// at the given height.
+    ///
+    /// Only allowed on regtest. The side chain becomes the main chain if it has more trust
+    /// than the current one, which is reported in the result.
+    #[method(name = "fork_at")]
+    async fn fork_at(
+        &self,
+        input_data: HexEncoded<GenerateBlockInputData>,
+        height: BlockHeight,
+        count: u32,
+    ) -> RpcResult<SimulatedReorgInfo>;
 }
 
 #[async_trait::async_trait]
@@ -190,4 +233,34 @@ impl BlockProductionRpcServer for super::BlockProductionHandle {
 
         Ok(search_data.into())
     }
+
+    async fn get_block_template_header_info(&self) -> RpcResult<BlockTemplateHeaderInfo> {
+        rpc::handle_result(self.call_async(move |this| this.get_block_template_header_info()).await)
+    }
+
+    async fn invalidate_tip_and_remine(
+        &self,
+        input_data: HexEncoded<GenerateBlockInputData>,
+        depth: u32,
+        count: u32,
+    ) -> RpcResult<SimulatedReorgInfo> {
+        rpc::handle_result(
+            self.call_async_mut(move |this| {
+                this.invalidate_tip_and_remine(input_data.take(), depth, count)
+            })
+            .await,
+        )
+    }
+
+    async fn fork_at(
+        &self,
+        input_data: HexEncoded<GenerateBlockInputData>,
+        height: BlockHeight,
+        count: u32,
+    ) -> RpcResult<SimulatedReorgInfo> {
+        rpc::handle_result(
+            self.call_async_mut(move |this| this.fork_at(input_data.take(), height, count))
+                .await,
+        )
+    }
 }
@@ -20,7 +20,7 @@ mod storage_compatibility;
 
 use std::sync::Arc;
 
-use chainstate::InitializationError;
+use chainstate::{InitializationError, ReindexError};
 use chainstate_storage::Transactional;
 use storage_lmdb::resize_callback::MapResizeCallback;
 
@@ -35,6 +35,9 @@ pub use config::{ChainstateLauncherConfig, StorageBackendConfig};
 /// Subdirectory under `datadir` where LMDB chainstate database is placed
 pub const SUBDIRECTORY_LMDB: &str = "chainstate-lmdb";
 
+/// Subdirectory under `datadir` where the old LMDB chainstate database is kept during reindexing
+pub const SUBDIRECTORY_LMDB_REINDEX: &str = "chainstate-lmdb-reindex";
+
 fn make_chainstate_and_storage_impl<B: storage::Backend + 'static>(
     storage_backend: B,
     chain_config: Arc<ChainConfig>,
@@ -62,34 +65,128 @@ fn make_chainstate_and_storage_impl<B: storage::Backend + 'static>(
     Ok(chainstate)
 }
 
+/// Move the existing database aside, so that it can be used as the source of blocks for
+/// reindexing. Return true if there is something to reindex.
+///
+/// If the reindex directory already exists, a previous reindex has been interrupted; in this case
+/// the partially rebuilt database is discarded and the reindex is resumed, even if it
+/// hasn't been requested this time.
+fn prepare_reindex(
+    db_dir: &std::path::Path,
+    reindex_dir: &std::path::Path,
+    reindex: bool,
+) -> Result<bool, ReindexError> {
+    if reindex_dir.exists() {
+        logging::log::warn!("Resuming an interrupted chainstate reindex");
+        if db_dir.exists() {
+            std::fs::remove_dir_all(db_dir)?;
+        }
+        return Ok(true);
+    }
+
+    if !reindex {
+        return Ok(false);
+    }
+
+    if !db_dir.exists() {
+        logging::log::warn!("There is no chainstate database to reindex");
+        return Ok(false);
+    }
+
+    std::fs::rename(db_dir, reindex_dir)?;
+    Ok(true)
+}
+
+fn open_reindex_source<B: storage::Backend>(
+    storage_backend: B,
+    chain_config: &ChainConfig,
+) -> Result<chainstate_storage::Store<B>, Error> {
+    let storage = chainstate_storage::Store::from_backend(storage_backend)
+        .map_err(|e| Error::FailedToInitializeChainstate(e.into()))?;
+
+    let db_tx = storage
+        .transaction_ro()
+        .map_err(|e| Error::FailedToInitializeChainstate(e.into()))?;
+
+    storage_compatibility::check_storage_compatibility(&db_tx, chain_config)
+        .map_err(InitializationError::StorageCompatibilityCheckError)?;
+    drop(db_tx);
+
+    Ok(storage)
+}
+
 /// Create chainstate together with its storage
+///
+/// If `reindex` is set, the chainstate is rebuilt from the blocks stored in the existing
+/// database before this function returns.
 pub fn make_chainstate(
     datadir: &std::path::Path,
     chain_config: Arc<ChainConfig>,
     config: ChainstateLauncherConfig,
+    reindex: bool,
 ) -> Result<ChainstateSubsystem, Error> {
     let ChainstateLauncherConfig {
         storage_backend,
         chainstate_config,
     } = config;
 
-    let lmdb_resize_callback = MapResizeCallback::new(Box::new(|resize_info| {
-        logging::log::info!("Lmdb resize happened: {:?}", resize_info)
-    }));
+    let make_lmdb = |path| {
+        let lmdb_resize_callback = MapResizeCallback::new(Box::new(|resize_info| {
+            logging::log::info!("Lmdb resize happened: {:?}", resize_info)
+        }));
+        storage_lmdb::Lmdb::new(
+            path,
+            Default::default(),
+            Default::default(),
+            lmdb_resize_callback,
+        )
+    };
+
+    // The chainstate takes care of flushing the storage if the durability is relaxed
+    let sync_on_commit = !chainstate_config.is_durability_relaxed();
+    let write_batching = *chainstate_config.ibd_write_batching;
 
     // There is some code duplication because `make_chainstate_and_storage_impl` is called with
     // a different set of generic parameters in each case.
     match storage_backend {
         StorageBackendConfig::Lmdb => {
-            let storage = storage_lmdb::Lmdb::new(
-                datadir.join(SUBDIRECTORY_LMDB),
-                Default::default(),
-                Default::default(),
-                lmdb_resize_callback,
-            );
-            make_chainstate_and_storage_impl(storage, chain_config, chainstate_config)
+            let db_dir = datadir.join(SUBDIRECTORY_LMDB);
+            let reindex_dir = datadir.join(SUBDIRECTORY_LMDB_REINDEX);
+            let reindex = prepare_reindex(&db_dir, &reindex_dir, reindex)?;
+
+            let lmdb = if sync_on_commit {
+                make_lmdb(db_dir)
+            } else {
+                make_lmdb(db_dir).with_no_sync()
+            };
+
+            let mut chainstate = if write_batching {
+                make_chainstate_and_storage_impl(
+                    storage::WriteBatching::new(lmdb),
+                    Arc::clone(&chain_config),
+                    chainstate_config,
+                )?
+            } else {
+                make_chainstate_and_storage_impl(
+                    lmdb,
+                    Arc::clone(&chain_config),
+                    chainstate_config,
+                )?
+            };
+
+            if reindex {
+                let source = open_reindex_source(make_lmdb(reindex_dir.clone()), &chain_config)?;
+                chainstate::reindex_from_storage(&source, chainstate.as_mut())?;
+                drop(source);
+                std::fs::remove_dir_all(&reindex_dir).map_err(ReindexError::from)?;
+            }
+
+            Ok(chainstate)
         }
         StorageBackendConfig::InMemory => {
+            if reindex {
+                logging::log::warn!("Reindexing is not applicable to in-memory storage, ignoring");
+            }
             let storage = storage_inmemory::InMemory::new();
             make_chainstate_and_storage_impl(storage, chain_config, chainstate_config)
         }
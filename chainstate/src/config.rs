@@ -32,6 +32,28 @@ make_config_setting!(
     )
 );
 make_config_setting!(MaxTipAge, Duration, Duration::from_secs(60 * 60 * 24));
+make_config_setting!(MaxBlockSizeHistogramRange, usize, 1000);
+make_config_setting!(EnableIbdWriteBatching, bool, false);
+make_config_setting!(MaxWriteBatchBlocks, usize, 1000);
+make_config_setting!(MaxWriteBatchSize, usize, 1 << 28); // 256 MB
+make_config_setting!(UpgradeWarningWindow, u64, 10_080); // about 2 weeks of blocks
+
+/// Determines when the changes committed to the chainstate storage are made durable.
+///
+/// With any policy other than [StorageSyncPolicy::Always], a crash may lose the most recently
+/// processed blocks; they are downloaded and processed again after the restart. The blocks
+/// processed since the storage was last made durable are re-validated on startup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StorageSyncPolicy {
+    /// Every commit is durable.
+    #[default]
+    Always,
+    /// The storage is made durable at the given interval, on shutdown and whenever a write batch
+    /// is written out.
+    Periodic(Duration),
+    /// The storage is made durable on shutdown and whenever a write batch is written out.
+    OnShutdown,
+}
 
 /// The chainstate subsystem configuration.
 #[derive(Debug, Clone, Default)]
@@ -49,6 +71,20 @@ pub struct ChainstateConfig {
     /// If true, additional computationally-expensive consistency checks will be performed by
     /// the chainstate. The default value depends on the chain type.
     pub enable_heavy_checks: Option<bool>,
+    /// The maximum number of blocks that can be included in a single block size histogram query.
+    pub max_block_size_histogram_range: MaxBlockSizeHistogramRange,
+    /// If true, consecutive blocks are written to the storage in batches during the initial block
+    /// download. Batching is turned off once the initial block download is finished.
+    pub ibd_write_batching: EnableIbdWriteBatching,
+    /// The maximum number of blocks in a write batch.
+    pub max_write_batch_blocks: MaxWriteBatchBlocks,
+    /// The maximum total size of the blocks in a write batch, in bytes.
+    pub max_write_batch_size: MaxWriteBatchSize,
+    /// When the changes committed to the storage are made durable.
+    pub storage_sync_policy: StorageSyncPolicy,
+    /// The number of blocks before the activation of a network upgrade unknown to this build
+    /// at which the node starts warning that it should be updated.
+    pub upgrade_warning_window: UpgradeWarningWindow,
 }
 
 impl ChainstateConfig {
@@ -75,6 +111,42 @@ impl ChainstateConfig {
         self
     }
 
+    pub fn with_max_block_size_histogram_range(mut self, max_range: usize) -> Self {
+        self.max_block_size_histogram_range = max_range.into();
+        self
+    }
+
+    pub fn with_ibd_write_batching(mut self, enable: bool) -> Self {
+        self.ibd_write_batching = enable.into();
+        self
+    }
+
+    pub fn with_max_write_batch_blocks(mut self, max_blocks: usize) -> Self {
+        self.max_write_batch_blocks = max_blocks.into();
+        self
+    }
+
+    pub fn with_max_write_batch_size(mut self, max_size: usize) -> Self {
+        self.max_write_batch_size = max_size.into();
+        self
+    }
+
+    pub fn with_storage_sync_policy(mut self, policy: StorageSyncPolicy) -> Self {
+        self.storage_sync_policy = policy;
+        self
+    }
+
+    pub fn with_upgrade_warning_window(mut self, window: u64) -> Self {
+        self.upgrade_warning_window = window.into();
+        self
+    }
+
+    /// Whether committed changes may be lost on a crash, so that the storage has to be flushed
+    /// explicitly and the blocks processed since then re-validated on startup.
+    pub fn is_durability_relaxed(&self) -> bool {
+        *self.ibd_write_batching || self.storage_sync_policy != StorageSyncPolicy::Always
+    }
+
     pub fn with_heavy_checks_enabled(mut self, enable: bool) -> Self {
         self.enable_heavy_checks = Some(enable);
         self
@@ -80,8 +80,10 @@ impl BanScore for BlockError {
             BlockError::InvariantErrorTotalPoolBalanceLessThanStakers { .. } => 0,
             BlockError::InvariantErrorPoolBalancePresentDataMissing(_, _) => 0,
             BlockError::InvariantErrorPoolDataPresentBalanceMissing(_, _) => 0,
+            BlockError::InvariantErrorTotalPoolBalancesOverflow(_) => 0,
 
             BlockError::UnexpectedHeightRange(_, _) => 0,
+            BlockError::TargetCalculationFailed(_, _) => 0,
 
             BlockError::TokensAccountingError(err) => err.ban_score(),
             BlockError::OrdersAccountingError(err) => err.ban_score(),
@@ -548,6 +550,9 @@ impl BanScore for ChainstateError {
             ChainstateError::FailedToReadProperty(_) => 0,
             ChainstateError::BootstrapError(_) => 0,
             ChainstateError::BlockInvalidatorError(_) => 0,
+            ChainstateError::ReindexError(_) => 0,
+            ChainstateError::TransactionDecodingError(_) => 0,
+            ChainstateError::ProofOfReservesError(_) => 0,
         }
     }
 }
@@ -13,15 +13,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::Chainstate;
-use crate::{BlockError, TransactionVerificationStrategy};
+use serde::{Deserialize, Serialize};
+
+use super::{get_block_index, Chainstate};
+use crate::{BlockError, CheckBlockError, TransactionVerificationStrategy};
 use chainstate_storage::BlockchainStorage;
 use common::{
     chain::{block::signed_block_header::SignedBlockHeader, Block},
-    primitives::id::WithId,
+    primitives::{id::WithId, BlockHeight, Id},
 };
 use utils::tap_log::TapLog;
 
+/// The information about a block header that has passed the header checks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, rpc::description::HasValueHint)]
+pub struct BlockHeaderCheckInfo {
+    pub block_id: Id<Block>,
+    /// The height the block would be attached at.
+    pub height: BlockHeight,
+    /// Whether the block would be attached on top of the current best block.
+    pub extends_best_chain: bool,
+}
+
 pub struct BlockChecker<'a, S, V> {
     chainstate: &'a Chainstate<S, V>,
 }
@@ -53,4 +65,42 @@ impl<'a, S: BlockchainStorage, V: TransactionVerificationStrategy> BlockChecker<
 
         Ok(())
     }
+
+    /// Perform the checks that `process_block` performs before looking at the block body.
+    pub fn check_block_header(
+        &self,
+        header: &SignedBlockHeader,
+    ) -> Result<BlockHeaderCheckInfo, BlockError> {
+        let chainstate_ref = self.chainstate.make_db_tx_ro().map_err(BlockError::from)?;
+        let block_id = header.block_id();
+
+        // Note: the order of the checks is the same as in `attempt_to_process_block`, so that
+        // the header is rejected for the same reason as the full block would be.
+        let parent_block_index = chainstate_ref
+            .get_gen_block_index(header.prev_block_id())
+            .map_err(BlockError::PropertyQueryError)?
+            .ok_or(CheckBlockError::ParentBlockMissing {
+                block_id,
+                parent_block_id: *header.prev_block_id(),
+            })?;
+
+        if let Some(block_index) = get_block_index(&chainstate_ref, &block_id)? {
+            return if block_index.status().is_ok() {
+                Err(BlockError::BlockAlreadyProcessed(block_id))
+            } else {
+                Err(BlockError::InvalidBlockAlreadyProcessed(block_id))
+            };
+        }
+
+        chainstate_ref.check_block_header(header).log_err()?;
+
+        let best_block_id =
+            chainstate_ref.get_best_block_id().map_err(BlockError::BestBlockIdQueryError)?;
+
+        Ok(BlockHeaderCheckInfo {
+            block_id,
+            height: parent_block_index.block_height().next_height(),
+            extends_best_chain: parent_block_index.block_id() == best_block_id,
+        })
+    }
 }
@@ -0,0 +1,224 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use common::{
+    chain::GenBlock,
+    primitives::{time::Time, BlockHeight, Id},
+};
+use utils::sync::Mutex;
+
+/// The width of a histogram bucket, in KiB.
+pub const BLOCK_SIZE_HISTOGRAM_BUCKET_WIDTH_KB: u32 = 10;
+
+/// How long a computed histogram is reused for the same height range and tip.
+const CACHED_HISTOGRAM_LIFETIME: Duration = Duration::from_secs(60);
+
+/// The maximum number of histograms kept in the cache.
+const MAX_CACHED_HISTOGRAMS: usize = 100;
+
+/// Distribution of the sizes of the mainchain blocks in a range of heights.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, rpc::description::HasValueHint)]
+pub struct BlockSizeHistogram {
+    /// Pairs of (the lower bound of the bucket in KiB, the number of blocks in the bucket),
+    /// sorted by the bucket size. Empty buckets are omitted.
+    pub buckets: Vec<(u32, u32)>,
+}
+
+impl BlockSizeHistogram {
+    pub fn from_block_sizes(block_sizes: impl IntoIterator<Item = usize>) -> Self {
+        let bucket_width = BLOCK_SIZE_HISTOGRAM_BUCKET_WIDTH_KB as usize * 1024;
+
+        let mut buckets = BTreeMap::<u32, u32>::new();
+        for size in block_sizes {
+            let bucket_index = u32::try_from(size / bucket_width).unwrap_or(u32::MAX);
+            let size_kb = bucket_index.saturating_mul(BLOCK_SIZE_HISTOGRAM_BUCKET_WIDTH_KB);
+            *buckets.entry(size_kb).or_default() += 1;
+        }
+
+        Self {
+            buckets: buckets.into_iter().collect(),
+        }
+    }
+}
+
+/// Recently computed histograms, keyed by the requested height range and the tip they were
+/// computed for.
+pub struct BlockSizeHistogramCache {
+    entries: Mutex<BTreeMap<HistogramCacheKey, (Time, BlockSizeHistogram)>>,
+}
+
+type HistogramCacheKey = (BlockHeight, BlockHeight, Id<GenBlock>);
+
+impl BlockSizeHistogramCache {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn get(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+        tip_id: Id<GenBlock>,
+        now: Time,
+    ) -> Option<BlockSizeHistogram> {
+        let entries = self.entries.lock().expect("poisoned mutex");
+        entries
+            .get(&(from_height, to_height, tip_id))
+            .filter(|(computed_at, _)| !Self::is_expired(*computed_at, now))
+            .map(|(_, histogram)| histogram.clone())
+    }
+
+    pub fn insert(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+        tip_id: Id<GenBlock>,
+        now: Time,
+        histogram: BlockSizeHistogram,
+    ) {
+        let mut entries = self.entries.lock().expect("poisoned mutex");
+
+        // Entries computed for a different tip will never be hit again
+        entries.retain(|(_, _, entry_tip_id), (computed_at, _)| {
+            *entry_tip_id == tip_id && !Self::is_expired(*computed_at, now)
+        });
+
+        if entries.len() >= MAX_CACHED_HISTOGRAMS {
+            let oldest_key = entries
+                .iter()
+                .min_by_key(|(_, (computed_at, _))| *computed_at)
+                .map(|(key, _)| *key);
+            if let Some(oldest_key) = oldest_key {
+                entries.remove(&oldest_key);
+            }
+        }
+
+        entries.insert((from_height, to_height, tip_id), (now, histogram));
+    }
+
+    fn is_expired(computed_at: Time, now: Time) -> bool {
+        computed_at.saturating_duration_add(CACHED_HISTOGRAM_LIFETIME) <= now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::primitives::H256;
+
+    #[test]
+    fn buckets_from_sizes() {
+        assert_eq!(
+            BlockSizeHistogram::from_block_sizes([]),
+            BlockSizeHistogram { buckets: vec![] }
+        );
+
+        let sizes = [0, 100, 10 * 1024 - 1, 10 * 1024, 25 * 1024, 29 * 1024, 1024 * 1024];
+        assert_eq!(
+            BlockSizeHistogram::from_block_sizes(sizes),
+            BlockSizeHistogram {
+                buckets: vec![(0, 3), (10, 1), (20, 2), (1020, 1)]
+            }
+        );
+    }
+
+    #[test]
+    fn cache_expiry() {
+        let cache = BlockSizeHistogramCache::new();
+        let histogram = BlockSizeHistogram {
+            buckets: vec![(0, 1)],
+        };
+        let (from, to) = (BlockHeight::new(1), BlockHeight::new(10));
+        let tip_id = Id::new(H256::from_low_u64_be(1));
+        let start = Time::from_secs_since_epoch(1000);
+        let after = |secs| start.saturating_duration_add(Duration::from_secs(secs));
+
+        assert_eq!(cache.get(from, to, tip_id, start), None);
+
+        cache.insert(from, to, tip_id, start, histogram.clone());
+        assert_eq!(cache.get(from, to, tip_id, start), Some(histogram.clone()));
+        assert_eq!(
+            cache.get(from, to, tip_id, after(59)),
+            Some(histogram.clone())
+        );
+        assert_eq!(cache.get(from, BlockHeight::new(11), tip_id, start), None);
+        assert_eq!(cache.get(from, to, tip_id, after(60)), None);
+
+        // Expired entries are dropped when a new one is added
+        cache.insert(from, BlockHeight::new(11), tip_id, after(61), histogram);
+        assert_eq!(cache.entries.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn cache_tip_change() {
+        let cache = BlockSizeHistogramCache::new();
+        let histogram = BlockSizeHistogram {
+            buckets: vec![(0, 1)],
+        };
+        let (from, to) = (BlockHeight::new(1), BlockHeight::new(10));
+        let old_tip_id = Id::new(H256::from_low_u64_be(1));
+        let new_tip_id = Id::new(H256::from_low_u64_be(2));
+        let now = Time::from_secs_since_epoch(1000);
+
+        cache.insert(from, to, old_tip_id, now, histogram.clone());
+        assert_eq!(cache.get(from, to, new_tip_id, now), None);
+
+        // Entries for the old tip are dropped when one for the new tip is added
+        cache.insert(from, to, new_tip_id, now, histogram.clone());
+        assert_eq!(cache.get(from, to, old_tip_id, now), None);
+        assert_eq!(cache.get(from, to, new_tip_id, now), Some(histogram));
+        assert_eq!(cache.entries.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn cache_size_limit() {
+        let cache = BlockSizeHistogramCache::new();
+        let histogram = BlockSizeHistogram {
+            buckets: vec![(0, 1)],
+        };
+        let tip_id = Id::new(H256::from_low_u64_be(1));
+        let start = Time::from_secs_since_epoch(1000);
+        let after = |secs| start.saturating_duration_add(Duration::from_millis(secs));
+
+        for i in 0..MAX_CACHED_HISTOGRAMS as u64 + 1 {
+            cache.insert(
+                BlockHeight::new(1),
+                BlockHeight::new(i + 1),
+                tip_id,
+                after(i),
+                histogram.clone(),
+            );
+        }
+
+        // The oldest entry has been evicted
+        assert_eq!(cache.entries.lock().unwrap().len(), MAX_CACHED_HISTOGRAMS);
+        let last_time = after(MAX_CACHED_HISTOGRAMS as u64);
+        assert_eq!(
+            cache.get(BlockHeight::new(1), BlockHeight::new(1), tip_id, last_time),
+            None
+        );
+        assert_eq!(
+            cache.get(BlockHeight::new(1), BlockHeight::new(2), tip_id, last_time),
+            Some(histogram)
+        );
+    }
+}
@@ -22,15 +22,15 @@ mod tx_verifier_storage;
 use itertools::Itertools;
 use serialization::{Decode, Encode};
 use std::{
-    cmp::max,
+    cmp::{max, min},
     collections::{BTreeMap, BTreeSet},
 };
 use thiserror::Error;
 
 use chainstate_storage::{BlockchainStorageRead, BlockchainStorageWrite, TransactionRw};
 use chainstate_types::{
-    block_index_ancestor_getter, get_skip_height, BlockIndex, BlockIndexHandle, BlockStatus,
-    BlockValidationStage, EpochData, EpochDataCache, GenBlockIndex, GetAncestorError,
+    block_index_ancestor_getter, get_skip_height, BlockFeesInfo, BlockIndex, BlockIndexHandle,
+    BlockStatus, BlockValidationStage, EpochData, EpochDataCache, GenBlockIndex, GetAncestorError,
     PropertyQueryError, TipStorageTag,
 };
 use common::{
@@ -41,8 +41,8 @@ use common::{
         },
         config::EpochIndex,
         tokens::{TokenAuxiliaryData, TokenId},
-        AccountNonce, AccountType, Block, ChainConfig, GenBlock, GenBlockId, PoolId, Transaction,
-        TxOutput, UtxoOutPoint,
+        AccountNonce, AccountType, Block, ChainConfig, GenBlock, GenBlockId, OutPointSourceId,
+        PoSConsensusVersion, PoolId, RequiredConsensus, Transaction, TxOutput, UtxoOutPoint,
     },
     primitives::{
         id::WithId, time::Time, Amount, BlockCount, BlockDistance, BlockHeight, Id, Idable,
@@ -66,9 +66,12 @@ use self::{
 };
 
 use super::{
-    median_time::calculate_median_time_past, transaction_verifier::flush::flush_to_storage,
-    tx_verification_strategy::TransactionVerificationStrategy, BlockSizeError, CheckBlockError,
-    CheckBlockTransactionsError,
+    consensus_info::{calculate_difficulty, BlockConsensusInfo, ConsensusInfo},
+    median_time::calculate_median_time_past,
+    stake_pool_info::StakePoolInfo,
+    transaction_verifier::flush::flush_to_storage,
+    tx_verification_strategy::TransactionVerificationStrategy,
+    BlockSizeError, CheckBlockError, CheckBlockTransactionsError,
 };
 
 pub use epoch_seal::EpochSealError;
@@ -256,6 +259,18 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         self.db_tx.get_block_id_by_height(height).map_err(PropertyQueryError::from)
     }
 
+    /// Get the mainchain block ids with heights in `from_height..=to_height`, ordered by height.
+    #[log_error]
+    pub fn get_block_ids_by_height_range(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Result<Vec<(BlockHeight, Id<GenBlock>)>, PropertyQueryError> {
+        self.db_tx
+            .get_block_ids_by_height_range(from_height, to_height)
+            .map_err(PropertyQueryError::from)
+    }
+
     #[log_error]
     pub fn get_existing_block_id_by_height(
         &self,
@@ -399,6 +414,66 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         Ok(self.db_tx.get_block_reward(block_index)?)
     }
 
+    /// Find an output created by the mainchain block at the given height,
+    /// regardless of whether it has been spent.
+    #[log_error]
+    pub fn get_mainchain_output(
+        &self,
+        outpoint: &UtxoOutPoint,
+        source_block_height: BlockHeight,
+    ) -> Result<Option<TxOutput>, PropertyQueryError> {
+        let block_id = match self.get_block_id_by_height(&source_block_height)? {
+            Some(block_id) => block_id,
+            None => return Ok(None),
+        };
+        let output_at =
+            |outputs: &[TxOutput]| outputs.get(outpoint.output_index() as usize).cloned();
+
+        let output = match (outpoint.source_id(), block_id.classify(self.chain_config)) {
+            (OutPointSourceId::BlockReward(reward_block_id), _) if reward_block_id != block_id => {
+                None
+            }
+            (OutPointSourceId::BlockReward(_), GenBlockId::Genesis(_)) => {
+                output_at(self.chain_config.genesis_block().utxos())
+            }
+            (OutPointSourceId::BlockReward(_), GenBlockId::Block(id)) => {
+                match self.get_block_index(&id)? {
+                    Some(block_index) => self
+                        .get_block_reward(&block_index)?
+                        .and_then(|reward| output_at(reward.outputs())),
+                    None => None,
+                }
+            }
+            (OutPointSourceId::Transaction(_), GenBlockId::Genesis(_)) => None,
+            (OutPointSourceId::Transaction(tx_id), GenBlockId::Block(id)) => {
+                self.get_block(id)?.and_then(|block| {
+                    block
+                        .transactions()
+                        .iter()
+                        .find(|tx| tx.transaction().get_id() == tx_id)
+                        .and_then(|tx| output_at(tx.transaction().outputs()))
+                })
+            }
+        };
+        Ok(output)
+    }
+
+    /// Return the fees of a mainchain block, or None if the block is not on the mainchain.
+    #[log_error]
+    pub fn get_block_fees(
+        &self,
+        block_id: &Id<Block>,
+    ) -> Result<Option<BlockFeesInfo>, PropertyQueryError> {
+        // The fees of the disconnected blocks are removed, so the stored ones are all mainchain
+        if let Some(block_fees) = self.db_tx.get_block_fees(*block_id)? {
+            return Ok(Some(BlockFeesInfo::Recorded(block_fees)));
+        }
+
+        Ok(self
+            .is_block_in_main_chain(&(*block_id).into())?
+            .then_some(BlockFeesInfo::NotRecorded))
+    }
+
     #[log_error]
     pub fn get_epoch_data(
         &self,
@@ -1085,6 +1160,291 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         Ok(height_map)
     }
 
+    /// The balances of all the pools that currently have a non-zero staker balance.
+    #[log_error]
+    pub fn get_all_stake_pool_balances(
+        &self,
+    ) -> Result<BTreeMap<PoolId, NonZeroPoolBalances>, BlockError> {
+        let best_block_height = self
+            .get_best_block_index()
+            .map_err(BlockError::PropertyQueryError)?
+            .block_height();
+        let pool_ids = self.db_tx.get_pool_balances_tip_map()?.into_keys().collect::<Vec<_>>();
+        let pos_db = PoSAccountingDB::new(&self);
+
+        Self::collect_pool_balances(pool_ids.iter(), &pos_db, best_block_height)
+    }
+
+    /// The current data of all the pools, along with the number of blocks each of them
+    /// produced among the latest `blocks_window` mainchain blocks.
+    #[log_error]
+    pub fn get_all_pools(&self, blocks_window: usize) -> Result<Vec<StakePoolInfo>, BlockError> {
+        let best_block_height = self
+            .get_best_block_index()
+            .map_err(BlockError::PropertyQueryError)?
+            .block_height();
+        let from_height = BlockHeight::new(
+            best_block_height
+                .into_int()
+                .saturating_sub(blocks_window as u64)
+                .saturating_add(1),
+        );
+
+        let mut blocks_produced = BTreeMap::<PoolId, u64>::new();
+        if from_height <= best_block_height {
+            for (_, block_id) in
+                self.db_tx.get_block_ids_by_height_range(from_height, best_block_height)?
+            {
+                let block_id = match block_id.classify(self.chain_config) {
+                    GenBlockId::Block(id) => id,
+                    GenBlockId::Genesis(_) => continue,
+                };
+                let block_index = self
+                    .get_existing_block_index(&block_id)
+                    .map_err(BlockError::PropertyQueryError)?;
+                match block_index.block_header().consensus_data() {
+                    ConsensusData::PoS(pos_data) => {
+                        *blocks_produced.entry(*pos_data.stake_pool_id()).or_default() += 1;
+                    }
+                    ConsensusData::None | ConsensusData::PoW(_) => {}
+                }
+            }
+        }
+
+        let pos_db = PoSAccountingDB::new(&self);
+        self.db_tx
+            .get_pool_balances_tip_map()?
+            .into_iter()
+            .filter_map(|(pool_id, total_stake)| {
+                pos_db
+                    .get_pool_data(pool_id)
+                    .map_err(BlockError::from)
+                    .map(|pool_data| {
+                        pool_data.map(|pool_data| StakePoolInfo {
+                            pool_id,
+                            pledge: pool_data.pledge_amount(),
+                            total_stake,
+                            cost_per_block: pool_data.cost_per_block(),
+                            margin_ratio_per_thousand: pool_data.margin_ratio_per_thousand(),
+                            blocks_produced: blocks_produced.get(&pool_id).copied().unwrap_or(0),
+                        })
+                    })
+                    .transpose()
+            })
+            .collect()
+    }
+
+    /// The expected delay before the next block is produced, based on the current PoS target
+    /// and the balances of all the pools.
+    ///
+    /// Returns `None` if the next block is not a PoS one or if the estimate can't be made
+    /// from the available pool data, e.g. when there are no pools.
+    #[log_error]
+    pub fn estimate_next_block_delay(&self) -> Result<Option<std::time::Duration>, BlockError> {
+        let best_block_index =
+            self.get_best_block_index().map_err(BlockError::PropertyQueryError)?;
+        let next_height = best_block_index.block_height().next_height();
+
+        let pos_status = match self.chain_config.consensus_upgrades().consensus_status(next_height)
+        {
+            RequiredConsensus::PoS(pos_status) => pos_status,
+            RequiredConsensus::PoW(_) | RequiredConsensus::IgnoreConsensus => return Ok(None),
+        };
+        let final_supply = match self.chain_config.final_supply() {
+            Some(final_supply) => final_supply.to_amount_atoms(),
+            None => return Ok(None),
+        };
+
+        let target = match consensus::calculate_target_required(
+            self.chain_config,
+            &pos_status,
+            best_block_index.block_id(),
+            self,
+        )
+        .and_then(consensus::compact_target_to_target)
+        {
+            Ok(target) => target,
+            Err(err) => {
+                log::debug!("Cannot obtain the target for the next block: {err}");
+                return Ok(None);
+            }
+        };
+
+        let consensus_version = pos_status.get_chain_config().consensus_version();
+        let mut total_effective_balance = Amount::ZERO;
+        for (pool_id, balances) in self.get_all_stake_pool_balances()? {
+            let effective_balance = if consensus_version == PoSConsensusVersion::V0 {
+                balances.total_balance()
+            } else {
+                match consensus::calculate_effective_pool_balance(
+                    balances.staker_balance(),
+                    balances.total_balance(),
+                    final_supply,
+                ) {
+                    Ok(effective_balance) => effective_balance,
+                    Err(err) => {
+                        log::debug!("Cannot obtain the effective balance of pool {pool_id}: {err}");
+                        return Ok(None);
+                    }
+                }
+            };
+
+            total_effective_balance = (total_effective_balance + effective_balance).ok_or(
+                BlockError::InvariantErrorTotalPoolBalancesOverflow(
+                    best_block_index.block_height(),
+                ),
+            )?;
+        }
+
+        Ok(consensus::expected_block_time(
+            &target,
+            total_effective_balance,
+        ))
+    }
+
+    /// Collect the consensus-related values of the mainchain blocks with heights in the range
+    /// `from_height..=to_height`, re-deriving the retarget algorithm's inputs from the stored
+    /// headers.
+    ///
+    /// Genesis and the heights above the best block are skipped.
+    #[log_error]
+    pub fn get_consensus_info(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+        max_range: usize,
+    ) -> Result<ConsensusInfo, BlockError> {
+        ensure!(
+            from_height <= to_height,
+            BlockError::PropertyQueryError(PropertyQueryError::InvalidBlockHeightRange {
+                start: from_height,
+                end: to_height
+            })
+        );
+        let range_len = (to_height.into_int() - from_height.into_int()).saturating_add(1);
+        ensure!(
+            range_len <= max_range as u64,
+            BlockError::PropertyQueryError(PropertyQueryError::BlockHeightRangeTooLarge {
+                start: from_height,
+                end: to_height,
+                max_range,
+            })
+        );
+
+        let best_block_index =
+            self.get_best_block_index().map_err(BlockError::BestBlockIndexQueryError)?;
+        let from_height = max(from_height, BlockHeight::one());
+        let to_height = min(to_height, best_block_index.block_height());
+
+        let mut blocks = Vec::new();
+        if from_height <= to_height {
+            let prev_height =
+                from_height.prev_height().expect("The height is known to be non-zero");
+            let prev_block_id = self
+                .get_existing_block_id_by_height(&prev_height)
+                .map_err(BlockError::PropertyQueryError)?;
+            let mut prev_block_index = self
+                .get_existing_gen_block_index(&prev_block_id)
+                .map_err(BlockError::PropertyQueryError)?;
+
+            for height in from_height.iter_up_to_including(to_height) {
+                let block_id = self
+                    .get_existing_block_id_by_height(&height)
+                    .map_err(BlockError::PropertyQueryError)?
+                    .classify(self.chain_config)
+                    .chain_block_id()
+                    .unwrap_or_else(|| {
+                        panic!("genesis block received at non-zero height {height}")
+                    });
+                let block_index = self
+                    .get_existing_block_index(&block_id)
+                    .map_err(BlockError::PropertyQueryError)?;
+
+                blocks.push(self.get_block_consensus_info(&block_index, &prev_block_index)?);
+                prev_block_index = block_index.into_gen_block_index();
+            }
+        }
+
+        let next_block_target = self
+            .calculate_target_adjustment(
+                &best_block_index,
+                best_block_index.block_height().next_height(),
+            )?
+            .map(|adjustment| adjustment.target.0);
+
+        Ok(ConsensusInfo {
+            blocks,
+            next_block_target,
+        })
+    }
+
+    fn get_block_consensus_info(
+        &self,
+        block_index: &BlockIndex,
+        prev_block_index: &GenBlockIndex,
+    ) -> Result<BlockConsensusInfo, BlockError> {
+        let height = block_index.block_height();
+
+        let (compact_target, difficulty) = match block_index.block_header().consensus_data() {
+            ConsensusData::None => (None, None),
+            ConsensusData::PoW(pow_data) => (Some(pow_data.bits()), None),
+            ConsensusData::PoS(pos_data) => {
+                let difficulty =
+                    match self.chain_config.consensus_upgrades().consensus_status(height) {
+                        RequiredConsensus::PoS(pos_status) => calculate_difficulty(
+                            pos_status.get_chain_config().target_limit(),
+                            pos_data.compact_target(),
+                        ),
+                        RequiredConsensus::PoW(_) | RequiredConsensus::IgnoreConsensus => None,
+                    };
+                (Some(pos_data.compact_target()), difficulty)
+            }
+        };
+
+        let average_block_time = self
+            .calculate_target_adjustment(prev_block_index, height)?
+            .and_then(|adjustment| adjustment.average_block_time);
+
+        Ok(BlockConsensusInfo {
+            height,
+            block_id: *block_index.block_id(),
+            timestamp: block_index.block_timestamp(),
+            block_interval: block_index
+                .block_timestamp()
+                .as_int_seconds()
+                .checked_sub(prev_block_index.block_timestamp().as_int_seconds()),
+            compact_target: compact_target.map(|compact| compact.0),
+            difficulty,
+            average_block_time,
+        })
+    }
+
+    /// Run the PoS retarget algorithm for the block at the specified height on top
+    /// of `prev_block_index`; `None` is returned if the block is not a PoS one.
+    fn calculate_target_adjustment(
+        &self,
+        prev_block_index: &GenBlockIndex,
+        height: BlockHeight,
+    ) -> Result<Option<consensus::TargetAdjustment>, BlockError> {
+        let pos_status = match self.chain_config.consensus_upgrades().consensus_status(height) {
+            RequiredConsensus::PoS(pos_status) => pos_status,
+            RequiredConsensus::PoW(_) | RequiredConsensus::IgnoreConsensus => return Ok(None),
+        };
+
+        let get_ancestor = |block_index: &BlockIndex, ancestor_height: BlockHeight| {
+            consensus::get_ancestor_from_block_index_handle(self, block_index, ancestor_height)
+        };
+
+        consensus::calculate_target_adjustment_from_block_index(
+            self.chain_config,
+            &pos_status,
+            prev_block_index,
+            get_ancestor,
+        )
+        .map(Some)
+        .map_err(|err| BlockError::TargetCalculationFailed(prev_block_index.block_id(), err))
+    }
+
     #[log_error]
     fn collect_pool_balances<'b>(
         pool_ids: impl Iterator<Item = &'b PoolId>,
@@ -1182,7 +1542,7 @@ impl<'a, S: BlockchainStorageWrite, V: TransactionVerificationStrategy> Chainsta
         // The comparison for timelock is done with median_time_past based on BIP-113, i.e., the median time instead of the block timestamp
         let median_time_past = calculate_median_time_past(self, &block.prev_block_id());
 
-        let connected_txs = self
+        let (connected_txs, block_fees) = self
             .tx_verification_strategy
             .connect_block(
                 TransactionVerifier::new,
@@ -1197,6 +1557,8 @@ impl<'a, S: BlockchainStorageWrite, V: TransactionVerificationStrategy> Chainsta
         let consumed = connected_txs.consume()?;
         flush_to_storage(self, consumed)?;
 
+        self.db_tx.set_block_fees(block.get_id(), &block_fees)?;
+
         Ok(())
     }
 
@@ -1211,6 +1573,8 @@ impl<'a, S: BlockchainStorageWrite, V: TransactionVerificationStrategy> Chainsta
         let cached_inputs = cached_inputs.consume()?;
         flush_to_storage(self, cached_inputs)?;
 
+        self.db_tx.del_block_fees(block.get_id())?;
+
         Ok(())
     }
 
@@ -0,0 +1,84 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+use common::{
+    chain::{block::timestamp::BlockTimestamp, Block},
+    primitives::{BlockHeight, Compact, Id},
+    Uint256,
+};
+
+/// The maximum number of blocks that can be included in a single consensus info query.
+pub const MAX_CONSENSUS_INFO_RANGE: usize = 1000;
+
+/// Consensus-related values of a mainchain block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, rpc::description::HasValueHint)]
+pub struct BlockConsensusInfo {
+    pub height: BlockHeight,
+    pub block_id: Id<Block>,
+    pub timestamp: BlockTimestamp,
+    /// The number of seconds since the previous block; `None` if the timestamps are not monotonic.
+    pub block_interval: Option<u64>,
+    /// The compact target (PoS) or bits (PoW) of the block.
+    pub compact_target: Option<u32>,
+    /// The ratio between the target limit and the block's target, for PoS blocks.
+    pub difficulty: Option<u128>,
+    /// The average block time (in seconds) that the retarget algorithm used when calculating
+    /// the block's target; `None` if the target didn't depend on it.
+    pub average_block_time: Option<u64>,
+}
+
+/// Consensus-related values of a range of mainchain blocks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, rpc::description::HasValueHint)]
+pub struct ConsensusInfo {
+    pub blocks: Vec<BlockConsensusInfo>,
+    /// The compact target required for the block following the current tip, if it's a PoS one.
+    pub next_block_target: Option<u32>,
+}
+
+/// Calculate the ratio between the target limit and the target, saturating at `u128::MAX`.
+pub fn calculate_difficulty(target_limit: Uint256, compact_target: Compact) -> Option<u128> {
+    let target = Uint256::try_from(compact_target).ok()?;
+    let difficulty = (target_limit / target)?;
+    Some(u128::try_from(difficulty).unwrap_or(u128::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn difficulty() {
+        let target_limit = Uint256::from_u64(1_000_000);
+
+        assert_eq!(
+            calculate_difficulty(target_limit, Compact::from(target_limit)),
+            Some(1)
+        );
+        assert_eq!(
+            calculate_difficulty(target_limit, Compact::from(Uint256::from_u64(1000))),
+            Some(1000)
+        );
+        assert_eq!(
+            calculate_difficulty(Uint256::MAX, Compact::from(Uint256::ONE)),
+            Some(u128::MAX)
+        );
+        assert_eq!(
+            calculate_difficulty(target_limit, Compact::from(Uint256::ZERO)),
+            None
+        );
+    }
+}
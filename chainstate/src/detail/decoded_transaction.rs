@@ -0,0 +1,261 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use chainstate_types::PropertyQueryError;
+use common::{
+    address::{Address, AddressError},
+    chain::{
+        output_value::OutputValue, signature::inputsig::InputWitness, timelock::OutputTimeLock,
+        ChainConfig, Destination, GenBlock, OutPointSourceId, SignedTransaction, Transaction,
+        TxInput, TxOutput,
+    },
+    primitives::{Amount, Id, Idable},
+};
+use serialization::Encode;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TransactionDecodingError {
+    #[error("Invalid transaction hex: {0}")]
+    InvalidHex(String),
+    #[error("Address encoding error: {0}")]
+    AddressError(#[from] AddressError),
+    #[error("Failed to read a spent output: {0}")]
+    PropertyQueryError(#[from] PropertyQueryError),
+}
+
+/// The value carried by a transaction output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, rpc::description::HasValueHint)]
+#[serde(tag = "type", content = "content")]
+pub enum DecodedOutputValue {
+    Coin { amount: Amount },
+    Token { token_id: String, amount: Amount },
+}
+
+/// A transaction output with its destination and token ids encoded as addresses.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, rpc::description::HasValueHint)]
+pub struct DecodedTxOutput {
+    /// The name of the output variant, e.g. "Transfer" or "CreateStakePool".
+    pub output_type: String,
+    /// The coins or tokens locked in the output; for a stake pool this is the pledge and
+    /// for an order the given value.
+    pub value: Option<DecodedOutputValue>,
+    /// The destination that controls the output, if any.
+    pub destination: Option<String>,
+    /// The timelock of the output; for an HTLC this is the refund timelock.
+    pub timelock: Option<OutputTimeLock>,
+}
+
+/// What a transaction input spends.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, rpc::description::HasValueHint)]
+#[serde(tag = "type", content = "content")]
+pub enum DecodedInputSource {
+    /// An output of a transaction
+    Transaction { tx_id: Id<Transaction>, index: u32 },
+    /// An output of a block reward
+    BlockReward { block_id: Id<GenBlock>, index: u32 },
+    /// An account, e.g. a delegation balance
+    Account { nonce: u64 },
+    /// A command on an account, e.g. minting tokens
+    AccountCommand { nonce: u64 },
+}
+
+/// The kind of the witness of a transaction input.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, rpc::description::HasValueHint)]
+#[serde(tag = "type", content = "content")]
+pub enum DecodedWitnessType {
+    NoSignature,
+    Standard {
+        sighash_type: u8,
+    },
+    /// The transaction has fewer witnesses than inputs
+    Missing,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, rpc::description::HasValueHint)]
+pub struct DecodedTxInput {
+    pub source: DecodedInputSource,
+    pub witness_type: DecodedWitnessType,
+    /// The output being spent; `None` if it's not in the current utxo set
+    /// (e.g. it doesn't exist or is already spent) or if the input spends from an account.
+    pub spent_output: Option<DecodedTxOutput>,
+}
+
+/// A human-readable representation of a signed transaction.
+///
+/// Note that transactions have no lock time of their own; the lock conditions are specified
+/// per output, see [DecodedTxOutput::timelock].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, rpc::description::HasValueHint)]
+pub struct DecodedTransaction {
+    pub tx_id: Id<Transaction>,
+    pub version: u8,
+    pub flags: u128,
+    pub size_bytes: u64,
+    pub inputs: Vec<DecodedTxInput>,
+    pub outputs: Vec<DecodedTxOutput>,
+}
+
+impl DecodedTransaction {
+    /// Decode the transaction; `spent_outputs` are the outputs spent by the inputs,
+    /// if they are known.
+    pub fn new(
+        chain_config: &ChainConfig,
+        tx: &SignedTransaction,
+        spent_outputs: Vec<Option<TxOutput>>,
+    ) -> Result<Self, TransactionDecodingError> {
+        let inputs = tx
+            .transaction()
+            .inputs()
+            .iter()
+            .enumerate()
+            .zip(spent_outputs)
+            .map(|((index, input), spent_output)| {
+                Ok(DecodedTxInput {
+                    source: decode_input_source(input),
+                    witness_type: tx
+                        .signatures()
+                        .get(index)
+                        .map_or(DecodedWitnessType::Missing, decode_witness_type),
+                    spent_output: spent_output
+                        .map(|output| decode_output(chain_config, &output))
+                        .transpose()?,
+                })
+            })
+            .collect::<Result<Vec<_>, TransactionDecodingError>>()?;
+
+        let outputs = tx
+            .transaction()
+            .outputs()
+            .iter()
+            .map(|output| decode_output(chain_config, output))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            tx_id: tx.transaction().get_id(),
+            version: tx.transaction().version_byte(),
+            flags: tx.transaction().flags(),
+            size_bytes: tx.encoded_size() as u64,
+            inputs,
+            outputs,
+        })
+    }
+}
+
+fn decode_input_source(input: &TxInput) -> DecodedInputSource {
+    match input {
+        TxInput::Utxo(outpoint) => match outpoint.source_id() {
+            OutPointSourceId::Transaction(tx_id) => DecodedInputSource::Transaction {
+                tx_id,
+                index: outpoint.output_index(),
+            },
+            OutPointSourceId::BlockReward(block_id) => DecodedInputSource::BlockReward {
+                block_id,
+                index: outpoint.output_index(),
+            },
+        },
+        TxInput::Account(outpoint) => DecodedInputSource::Account {
+            nonce: outpoint.nonce().value(),
+        },
+        TxInput::AccountCommand(nonce, _) => DecodedInputSource::AccountCommand {
+            nonce: nonce.value(),
+        },
+    }
+}
+
+fn decode_witness_type(witness: &InputWitness) -> DecodedWitnessType {
+    match witness {
+        InputWitness::NoSignature(_) => DecodedWitnessType::NoSignature,
+        InputWitness::Standard(sig) => DecodedWitnessType::Standard {
+            sighash_type: sig.sighash_type().get(),
+        },
+    }
+}
+
+pub(super) fn decode_value(
+    chain_config: &ChainConfig,
+    value: &OutputValue,
+) -> Result<Option<DecodedOutputValue>, TransactionDecodingError> {
+    let result = match value {
+        OutputValue::Coin(amount) => Some(DecodedOutputValue::Coin { amount: *amount }),
+        // Deprecated, can't appear in new transactions
+        OutputValue::TokenV0(_) => None,
+        OutputValue::TokenV1(token_id, amount) => Some(DecodedOutputValue::Token {
+            token_id: Address::new(chain_config, *token_id)?.into_string(),
+            amount: *amount,
+        }),
+    };
+    Ok(result)
+}
+
+fn decode_output(
+    chain_config: &ChainConfig,
+    output: &TxOutput,
+) -> Result<DecodedTxOutput, TransactionDecodingError> {
+    let coin = |amount: &Amount| Some(DecodedOutputValue::Coin { amount: *amount });
+
+    let (output_type, value, destination, timelock): (_, _, Option<&Destination>, _) = match output
+    {
+        TxOutput::Transfer(value, dest) => (
+            "Transfer",
+            decode_value(chain_config, value)?,
+            Some(dest),
+            None,
+        ),
+        TxOutput::LockThenTransfer(value, dest, timelock) => (
+            "LockThenTransfer",
+            decode_value(chain_config, value)?,
+            Some(dest),
+            Some(*timelock),
+        ),
+        TxOutput::Burn(value) => ("Burn", decode_value(chain_config, value)?, None, None),
+        TxOutput::CreateStakePool(_, data) => (
+            "CreateStakePool",
+            coin(&data.pledge()),
+            Some(data.staker()),
+            None,
+        ),
+        TxOutput::ProduceBlockFromStake(dest, _) => {
+            ("ProduceBlockFromStake", None, Some(dest), None)
+        }
+        TxOutput::CreateDelegationId(dest, _) => ("CreateDelegationId", None, Some(dest), None),
+        TxOutput::DelegateStaking(amount, _) => ("DelegateStaking", coin(amount), None, None),
+        TxOutput::IssueFungibleToken(_) => ("IssueFungibleToken", None, None, None),
+        TxOutput::IssueNft(_, _, dest) => ("IssueNft", None, Some(dest), None),
+        TxOutput::DataDeposit(_) => ("DataDeposit", None, None, None),
+        TxOutput::Htlc(value, htlc) => (
+            "Htlc",
+            decode_value(chain_config, value)?,
+            Some(&htlc.spend_key),
+            Some(htlc.refund_timelock),
+        ),
+        TxOutput::AnyoneCanTake(order) => (
+            "AnyoneCanTake",
+            decode_value(chain_config, order.give())?,
+            Some(order.conclude_key()),
+            None,
+        ),
+    };
+
+    Ok(DecodedTxOutput {
+        output_type: output_type.to_owned(),
+        value,
+        destination: destination
+            .map(|dest| Address::new(chain_config, dest.clone()).map(Address::into_string))
+            .transpose()?,
+        timelock,
+    })
+}
@@ -34,7 +34,7 @@ use common::{
     },
     primitives::{Amount, BlockHeight, Id},
 };
-use consensus::ConsensusVerificationError;
+use consensus::{ConsensusPoSError, ConsensusVerificationError};
 
 #[derive(Error, Debug, PartialEq, Eq, Clone)]
 pub enum BlockError {
@@ -110,9 +110,14 @@ pub enum BlockError {
     InvariantErrorPoolBalancePresentDataMissing(PoolId, BlockHeight),
     #[error("Pool {0} balance missing while pool data is present; best block height =  {1}")]
     InvariantErrorPoolDataPresentBalanceMissing(PoolId, BlockHeight),
+    #[error("The sum of all pool balances overflows; best block height = {0}")]
+    InvariantErrorTotalPoolBalancesOverflow(BlockHeight),
 
     #[error("Unexpected block height range: first = {0}, second = {1}")]
     UnexpectedHeightRange(BlockHeight, BlockHeight),
+
+    #[error("Failed to calculate the target for the block following {0}: {1}")]
+    TargetCalculationFailed(Id<GenBlock>, ConsensusPoSError),
 }
 
 // Note: this enum isn't supposed to represent a complete error; this is why its elements
@@ -218,6 +223,14 @@ pub enum InitializationError {
     StorageCompatibilityCheckError(#[from] StorageCompatibilityCheckError),
     #[error("Error initializing best chain candidates: {0}")]
     BestChainCandidatesError(#[from] BestChainCandidatesError),
+    #[error("Failed to re-validate the blocks above height {clean_height} up to tip {tip_id}: {error}. The chainstate storage is not made durable on every commit, so a crash may require replaying the blocks processed since it was last flushed; reindex the chainstate if the error persists")]
+    BlockRevalidationFailed {
+        clean_height: BlockHeight,
+        tip_id: Id<Block>,
+        error: Box<BlockError>,
+    },
+    #[error("No mainchain block at the last flushed height {0} below the tip {1}, the chainstate storage is inconsistent; reindex the chainstate")]
+    CleanBlockMissing(BlockHeight, Id<Block>),
 }
 
 #[derive(Error, Debug, PartialEq, Eq, Clone)]
@@ -98,6 +98,7 @@ impl BlockProcessingErrorClassification for BlockError {
             | BlockError::InvariantErrorTotalPoolBalanceLessThanStakers { .. }
             | BlockError::InvariantErrorPoolBalancePresentDataMissing(_, _)
             | BlockError::InvariantErrorPoolDataPresentBalanceMissing(_, _)
+            | BlockError::InvariantErrorTotalPoolBalancesOverflow(_)
             | BlockError::UnexpectedHeightRange(_, _)
             | BlockError::DbCommitError(_, _, _)
             | BlockError::BlockAlreadyExists(_)
@@ -137,6 +138,7 @@ impl BlockProcessingErrorClassification for BlockError {
             BlockError::StateUpdateFailed(err) => err.classify(),
             BlockError::PropertyQueryError(err) => err.classify(),
             BlockError::InMemoryReorgFailed(err) => err.classify(),
+            BlockError::TargetCalculationFailed(_, err) => err.classify(),
         }
     }
 }
@@ -426,7 +428,9 @@ impl BlockProcessingErrorClassification for PropertyQueryError {
             | PropertyQueryError::BlockForHeightNotFound(_)
             | PropertyQueryError::GenesisHeaderRequested
             | PropertyQueryError::InvalidStartingBlockHeightForMainchainBlocks(_)
-            | PropertyQueryError::InvalidBlockHeightRange { .. } => {
+            | PropertyQueryError::InvalidBlockHeightRange { .. }
+            | PropertyQueryError::BlockHeightRangeTooLarge { .. }
+            | PropertyQueryError::AncestorsDepthTooLarge { .. } => {
                 BlockProcessingErrorClass::General
             }
             // Note: these errors are strange - sometimes they don't look like General, judging
@@ -0,0 +1,217 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use common::{
+    chain::{Block, GenBlock},
+    primitives::{time::Time, Id},
+};
+
+/// The node's tip is different from the tips reported by a number of its peers, which may mean
+/// that the node is on a minority fork.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, rpc::description::HasValueHint)]
+pub struct PossibleFork {
+    /// The node's own tip.
+    pub local_tip: Id<GenBlock>,
+    /// The tips reported by the peers that disagree with the node, with the number of peers
+    /// reporting each of them.
+    pub peer_tips: BTreeMap<Id<Block>, u32>,
+}
+
+struct ReportedTip {
+    block_id: Id<Block>,
+    /// When the peer started to report this block as its tip.
+    reported_since: Time,
+}
+
+/// Tracks the best blocks reported by peers and detects the situation when too many of them
+/// disagree with the node's own tip for too long.
+pub struct ForkDetector<P> {
+    reported_tips: BTreeMap<P, ReportedTip>,
+    /// The last detected possible fork; it's reset once the disagreement goes away.
+    possible_fork: Option<PossibleFork>,
+}
+
+impl<P: Ord> ForkDetector<P> {
+    pub fn new() -> Self {
+        Self {
+            reported_tips: BTreeMap::new(),
+            possible_fork: None,
+        }
+    }
+
+    /// Remember the best block reported by a peer.
+    pub fn report_best_block(&mut self, peer: P, block_id: Id<Block>, now: Time) {
+        match self.reported_tips.get_mut(&peer) {
+            Some(reported) if reported.block_id == block_id => {}
+            Some(reported) => {
+                *reported = ReportedTip {
+                    block_id,
+                    reported_since: now,
+                };
+            }
+            None => {
+                self.reported_tips.insert(
+                    peer,
+                    ReportedTip {
+                        block_id,
+                        reported_since: now,
+                    },
+                );
+            }
+        }
+    }
+
+    pub fn remove_peer(&mut self, peer: &P) {
+        self.reported_tips.remove(peer);
+    }
+
+    /// The last detected possible fork, if the disagreement is still in place.
+    pub fn possible_fork(&self) -> Option<&PossibleFork> {
+        self.possible_fork.as_ref()
+    }
+
+    /// Check whether at least `threshold` peers have been reporting tips different from
+    /// `local_tip` for at least `min_duration`.
+    ///
+    /// Returns the possible fork only if it's different from the one returned previously,
+    /// so that the same situation is reported once.
+    pub fn check(
+        &mut self,
+        local_tip: Id<GenBlock>,
+        threshold: usize,
+        min_duration: Duration,
+        now: Time,
+    ) -> Option<PossibleFork> {
+        let mut peer_tips = BTreeMap::<Id<Block>, u32>::new();
+        for reported in self.reported_tips.values() {
+            if Id::<GenBlock>::from(reported.block_id) != local_tip
+                && reported.reported_since.saturating_duration_add(min_duration) <= now
+            {
+                *peer_tips.entry(reported.block_id).or_default() += 1;
+            }
+        }
+
+        let peer_count = peer_tips.values().map(|count| *count as usize).sum::<usize>();
+        if threshold == 0 || peer_count < threshold {
+            self.possible_fork = None;
+            return None;
+        }
+
+        let possible_fork = PossibleFork {
+            local_tip,
+            peer_tips,
+        };
+        if self.possible_fork.as_ref() == Some(&possible_fork) {
+            return None;
+        }
+
+        self.possible_fork = Some(possible_fork.clone());
+        Some(possible_fork)
+    }
+}
+
+impl<P: Ord> Default for ForkDetector<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::primitives::H256;
+
+    const DURATION: Duration = Duration::from_secs(60);
+
+    fn block_id(n: u64) -> Id<Block> {
+        Id::new(H256::from_low_u64_be(n))
+    }
+
+    #[test]
+    fn alert_after_duration() {
+        let mut detector = ForkDetector::<u32>::new();
+        let local_tip: Id<GenBlock> = block_id(1).into();
+        let start = Time::from_secs_since_epoch(1000);
+        let after = |secs| start.saturating_duration_add(Duration::from_secs(secs));
+
+        detector.report_best_block(1, block_id(1), start);
+        detector.report_best_block(2, block_id(2), start);
+        detector.report_best_block(3, block_id(2), start);
+        detector.report_best_block(4, block_id(3), start);
+
+        // The peers haven't been disagreeing long enough.
+        assert_eq!(detector.check(local_tip, 3, DURATION, after(59)), None);
+        assert_eq!(detector.possible_fork(), None);
+
+        let expected = PossibleFork {
+            local_tip,
+            peer_tips: BTreeMap::from([(block_id(2), 2), (block_id(3), 1)]),
+        };
+        assert_eq!(
+            detector.check(local_tip, 3, DURATION, after(60)),
+            Some(expected.clone())
+        );
+        assert_eq!(detector.possible_fork(), Some(&expected));
+
+        // The same situation is not reported again.
+        assert_eq!(detector.check(local_tip, 3, DURATION, after(120)), None);
+        assert_eq!(detector.possible_fork(), Some(&expected));
+
+        // A peer switching to another tip restarts its timer, so the fork is gone for now.
+        detector.report_best_block(4, block_id(4), after(120));
+        assert_eq!(detector.check(local_tip, 3, DURATION, after(150)), None);
+        assert_eq!(detector.possible_fork(), None);
+
+        // And reported again once the peer has been disagreeing long enough.
+        let expected = PossibleFork {
+            local_tip,
+            peer_tips: BTreeMap::from([(block_id(2), 2), (block_id(4), 1)]),
+        };
+        assert_eq!(
+            detector.check(local_tip, 3, DURATION, after(180)),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn no_alert_when_tips_agree() {
+        let mut detector = ForkDetector::<u32>::new();
+        let start = Time::from_secs_since_epoch(1000);
+        let later = start.saturating_duration_add(DURATION);
+
+        for peer in 0..4 {
+            detector.report_best_block(peer, block_id(2), start);
+        }
+        assert!(detector.check(block_id(1).into(), 3, DURATION, later).is_some());
+
+        // The node has caught up with the peers.
+        assert_eq!(detector.check(block_id(2).into(), 3, DURATION, later), None);
+        assert_eq!(detector.possible_fork(), None);
+
+        // Disconnected peers are not taken into account.
+        for peer in 0..2 {
+            detector.remove_peer(&peer);
+        }
+        assert_eq!(detector.check(block_id(1).into(), 3, DURATION, later), None);
+
+        // Zero threshold disables the detection.
+        assert_eq!(detector.check(block_id(1).into(), 0, DURATION, later), None);
+    }
+}
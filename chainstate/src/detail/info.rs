@@ -27,4 +27,6 @@ pub struct ChainInfo {
     pub best_block_timestamp: BlockTimestamp,
     pub median_time: BlockTimestamp,
     pub is_initial_block_download: bool,
+    /// True if a network upgrade unknown to this node activates soon, so the node should be updated.
+    pub update_recommended: bool,
 }
@@ -19,12 +19,21 @@ mod error_classification;
 mod info;
 mod median_time;
 mod orphan_blocks;
+mod storage_flush;
 
 pub mod ban_score;
 pub mod block_checking;
 pub mod block_invalidation;
+pub mod block_size_histogram;
 pub mod bootstrap;
+pub mod consensus_info;
+pub mod decoded_transaction;
+pub mod fork_detector;
+pub mod network_upgrades;
+pub mod proof_of_reserves;
 pub mod query;
+pub mod reindex;
+pub mod stake_pool_info;
 pub mod tx_verification_strategy;
 
 use std::{collections::VecDeque, sync::Arc};
@@ -35,21 +44,32 @@ use utils_networking::broadcaster;
 
 use self::{
     block_invalidation::BlockInvalidator,
+    block_size_histogram::{BlockSizeHistogram, BlockSizeHistogramCache},
+    consensus_info::{ConsensusInfo, MAX_CONSENSUS_INFO_RANGE},
+    decoded_transaction::{DecodedTransaction, TransactionDecodingError},
+    network_upgrades::{NetworkUpgradeInfo, UpgradeWarning},
     orphan_blocks::{OrphanBlocksMut, OrphansProxy},
+    proof_of_reserves::{ProofOfReservesError, ProofOfReservesReport},
     query::ChainstateQuery,
+    stake_pool_info::{StakePoolInfo, STAKE_POOL_BLOCKS_WINDOW},
+    storage_flush::StorageFlushTracker,
     tx_verification_strategy::TransactionVerificationStrategy,
 };
-use crate::{BlockInvalidatorError, ChainstateConfig, ChainstateEvent};
+use crate::{metrics::metrics, BlockInvalidatorError, ChainstateConfig, ChainstateEvent};
 use chainstate_storage::{
     BlockchainStorage, BlockchainStorageRead, BlockchainStorageWrite, TransactionRw, Transactional,
 };
 use chainstate_types::{
     pos_randomness::PoSRandomness, BlockIndex, BlockStatus, BlockValidationStage, EpochData,
-    EpochStorageWrite, PropertyQueryError, SealedStorageTag, TipStorageTag,
+    EpochStorageWrite, GenBlockIndex, PropertyQueryError, SealedStorageTag, TipStorageTag,
 };
 use chainstateref::{ChainstateRef, ReorgError};
 use common::{
-    chain::{block::timestamp::BlockTimestamp, config::ChainConfig, Block, GenBlock, TxOutput},
+    chain::{
+        block::timestamp::BlockTimestamp, config::ChainConfig,
+        signature::inputsig::arbitrary_message::proof_of_reserves::ProofOfReserves, Block,
+        GenBlock, SignedTransaction, TxInput, TxOutput,
+    },
     primitives::{id::WithId, BlockHeight, Compact, Id, Idable},
     time_getter::TimeGetter,
     Uint256,
@@ -59,6 +79,7 @@ use pos_accounting::{
     FlushablePoSAccountingView, PoSAccountingDB, PoSAccountingDelta, PoSAccountingOperations,
     PoSAccountingUndo,
 };
+use serialization::{hex::HexDecode, Encode};
 use tx_verifier::transaction_verifier;
 use utils::{
     const_value::ConstValue,
@@ -68,7 +89,7 @@ use utils::{
     set_flag::SetFlag,
     tap_log::TapLog,
 };
-use utxo::UtxosDB;
+use utxo::{UtxosDB, UtxosView};
 
 pub use self::{
     error::*, info::ChainInfo, median_time::calculate_median_time_past,
@@ -106,6 +127,9 @@ pub struct Chainstate<S, V> {
     rpc_events: broadcaster::Broadcaster<ChainstateEvent>,
     time_getter: TimeGetter,
     is_initial_block_download_finished: SetFlag,
+    block_size_histogram_cache: BlockSizeHistogramCache,
+    storage_flush_tracker: StorageFlushTracker,
+    upgrade_warning: UpgradeWarning,
 }
 
 #[derive(Copy, Clone, Eq, Debug, PartialEq)]
@@ -154,6 +178,153 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
         self.make_db_tx_ro().map(ChainstateQuery::new).map_err(PropertyQueryError::from)
     }
 
+    /// Compute the histogram of the sizes of the mainchain blocks with heights in the range
+    /// `from_height..=to_height`; histograms recently computed for the current tip are reused.
+    #[log_error]
+    pub fn get_block_size_histogram(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Result<BlockSizeHistogram, PropertyQueryError> {
+        let query = self.query()?;
+        let tip_id = query.get_best_block_id()?;
+        let now = self.time_getter.get_time();
+        if let Some(histogram) =
+            self.block_size_histogram_cache.get(from_height, to_height, tip_id, now)
+        {
+            return Ok(histogram);
+        }
+
+        let histogram = query.get_block_size_histogram(
+            from_height,
+            to_height,
+            *self.chainstate_config.max_block_size_histogram_range,
+        )?;
+        self.block_size_histogram_cache.insert(
+            from_height,
+            to_height,
+            tip_id,
+            now,
+            histogram.clone(),
+        );
+
+        Ok(histogram)
+    }
+
+    /// Estimate the timestamp of the next block from the current PoS target and the balances
+    /// of the pools; if there is no data to base the estimate on, the target block spacing
+    /// is used instead.
+    #[log_error]
+    pub fn estimate_next_block_timestamp(&self) -> Result<BlockTimestamp, BlockError> {
+        let delay = self
+            .make_db_tx_ro()?
+            .estimate_next_block_delay()?
+            .unwrap_or_else(|| self.chain_config.target_block_spacing());
+        let now = self.time_getter.get_time();
+
+        Ok(BlockTimestamp::from_time(
+            now.saturating_duration_add(delay),
+        ))
+    }
+
+    /// List the network upgrades of the chain relative to the given tip height.
+    pub fn get_network_upgrades(&self, tip_height: BlockHeight) -> Vec<NetworkUpgradeInfo> {
+        network_upgrades::network_upgrades(&self.chain_config, tip_height)
+    }
+
+    /// Whether an upgrade unknown to this build activates within the warning window.
+    pub fn is_update_recommended(&self, tip_height: BlockHeight) -> bool {
+        self.upgrade_warning.is_update_recommended(&self.chain_config, tip_height)
+    }
+
+    /// Collect the consensus-related values of the mainchain blocks with heights in the range
+    /// `from_height..=to_height`.
+    #[log_error]
+    pub fn get_consensus_info(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Result<ConsensusInfo, BlockError> {
+        self.make_db_tx_ro()?
+            .get_consensus_info(from_height, to_height, MAX_CONSENSUS_INFO_RANGE)
+    }
+
+    /// Collect the current data of all the stake pools, along with the number of blocks
+    /// each of them produced among the latest `STAKE_POOL_BLOCKS_WINDOW` mainchain blocks.
+    #[log_error]
+    pub fn get_all_pools(&self) -> Result<Vec<StakePoolInfo>, BlockError> {
+        self.make_db_tx_ro()?.get_all_pools(STAKE_POOL_BLOCKS_WINDOW)
+    }
+
+    /// Decode a hex-encoded signed transaction, looking up the outputs it spends
+    /// in the current utxo set.
+    #[log_error]
+    pub fn decode_transaction(
+        &self,
+        tx_hex: &str,
+    ) -> Result<DecodedTransaction, TransactionDecodingError> {
+        let tx = SignedTransaction::hex_decode_all(tx_hex)
+            .map_err(|e| TransactionDecodingError::InvalidHex(e.to_string()))?;
+
+        let chainstate_ref = self.make_db_tx_ro().map_err(PropertyQueryError::from)?;
+        let utxo_view = chainstate_ref.make_utxo_view();
+        let spent_outputs = tx
+            .transaction()
+            .inputs()
+            .iter()
+            .map(|input| match input {
+                TxInput::Utxo(outpoint) => utxo_view
+                    .utxo(outpoint)
+                    .map(|utxo| utxo.map(|utxo| utxo.take_output()))
+                    .map_err(PropertyQueryError::from),
+                TxInput::Account(_) | TxInput::AccountCommand(_, _) => Ok(None),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        DecodedTransaction::new(&self.chain_config, &tx, spent_outputs)
+    }
+
+    /// Check a hex-encoded proof of reserves against the mainchain.
+    ///
+    /// The utxos of the proof are looked up in the blocks that created them, so that
+    /// the ones spent since the block of the proof can still be verified.
+    #[log_error]
+    pub fn verify_proof_of_reserves(
+        &self,
+        proof_hex: &str,
+    ) -> Result<ProofOfReservesReport, ProofOfReservesError> {
+        let proof = ProofOfReserves::hex_decode_all(proof_hex)
+            .map_err(|e| ProofOfReservesError::InvalidHex(e.to_string()))?;
+
+        let chainstate_ref = self.make_db_tx_ro().map_err(PropertyQueryError::from)?;
+        ensure!(
+            chainstate_ref.is_block_in_main_chain(proof.block_id())?,
+            ProofOfReservesError::BlockNotInMainchain(*proof.block_id())
+        );
+        let block_height = chainstate_ref
+            .get_gen_block_index(proof.block_id())?
+            .ok_or(ProofOfReservesError::BlockNotInMainchain(*proof.block_id()))?
+            .block_height();
+
+        let utxo_view = chainstate_ref.make_utxo_view();
+        let utxos = proof
+            .entries()
+            .iter()
+            .map(|entry| -> Result<_, ProofOfReservesError> {
+                if entry.source_block_height() > block_height {
+                    return Ok(None);
+                }
+                let output = chainstate_ref
+                    .get_mainchain_output(entry.outpoint(), entry.source_block_height())?;
+                let spent =
+                    utxo_view.utxo(entry.outpoint()).map_err(PropertyQueryError::from)?.is_none();
+                Ok(output.map(|output| (output, spent)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        ProofOfReservesReport::new(&self.chain_config, &proof, block_height, utxos)
+    }
+
     pub fn subscribe_to_events(&mut self, handler: ChainstateEventHandler) {
         self.subsystem_events.subscribe_to_events(handler);
     }
@@ -195,14 +366,28 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
             chainstate.process_genesis().map_err(ChainstateError::ProcessBlockError)?;
         } else {
             chainstate.check_genesis().map_err(crate::ChainstateError::from)?;
+            chainstate.revalidate_blocks_above_clean_height()?;
         }
 
         chainstate.update_initial_block_download_flag()?;
 
+        chainstate
+            .init_storage_durability()
+            .map_err(ChainstateError::ProcessBlockError)?;
+
         chainstate
             .check_consistency()
             .map_err(|e| ChainstateError::FailedToInitializeChainstate(e.into()))?;
 
+        let best_block_index = chainstate
+            .query()
+            .and_then(|query| query.get_best_block_index())
+            .map_err(ChainstateError::FailedToReadProperty)?;
+        metrics().set_tip(
+            best_block_index.block_height(),
+            best_block_index.block_timestamp(),
+        );
+
         Ok(chainstate)
     }
 
@@ -217,6 +402,9 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
         let orphan_blocks = OrphansProxy::new(*chainstate_config.max_orphan_blocks);
         let subsystem_events = EventsController::new();
         let rpc_events = broadcaster::Broadcaster::new();
+        let storage_flush_tracker = StorageFlushTracker::new(time_getter.get_time());
+        let upgrade_warning =
+            UpgradeWarning::new(&chain_config, *chainstate_config.upgrade_warning_window);
         Self {
             chain_config,
             chainstate_config: chainstate_config.into(),
@@ -228,6 +416,9 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
             rpc_events,
             time_getter,
             is_initial_block_download_finished: SetFlag::new(),
+            block_size_histogram_cache: BlockSizeHistogramCache::new(),
+            storage_flush_tracker,
+            upgrade_warning,
         }
     }
 
@@ -259,6 +450,135 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
         Ok(())
     }
 
+    /// If the storage may have been written out only partially after its last flush, disconnect
+    /// the blocks above the height of the tip at the time of the flush and connect them again,
+    /// so that they are re-validated.
+    #[log_error]
+    fn revalidate_blocks_above_clean_height(&mut self) -> Result<(), InitializationError> {
+        let clean_height =
+            match self.chainstate_storage.transaction_ro()?.get_clean_block_height()? {
+                Some(clean_height) => clean_height,
+                None => return Ok(()),
+            };
+
+        let tip_index = match self.make_db_tx_ro()?.get_best_block_index()? {
+            GenBlockIndex::Block(tip_index) if tip_index.block_height() > clean_height => tip_index,
+            GenBlockIndex::Block(_) | GenBlockIndex::Genesis(_) => return Ok(()),
+        };
+        let tip_id = *tip_index.block_id();
+
+        let clean_block_id = self
+            .make_db_tx_ro()?
+            .get_block_id_by_height(&clean_height)?
+            .ok_or(InitializationError::CleanBlockMissing(clean_height, tip_id))?;
+
+        log::warn!(
+            "Chainstate storage was not flushed after height {}, re-validating the blocks up to height {}",
+            clean_height,
+            tip_index.block_height()
+        );
+
+        self.with_rw_tx(
+            |chainstate_ref| {
+                chainstate_ref.disconnect_until(&tip_id, &clean_block_id)?;
+                chainstate_ref.activate_best_chain(&tip_index).map_err(|err| match err {
+                    ReorgError::ConnectTipFailed(_, err) | ReorgError::OtherError(err) => err,
+                })?;
+                Ok(())
+            },
+            |attempt_number| {
+                log::info!(
+                    "Re-validating the blocks above height {clean_height}, attempt #{attempt_number}"
+                );
+            },
+            |attempts_count, db_err| {
+                BlockError::DbCommitError(attempts_count, db_err, DbCommittingContext::Block(tip_id))
+            },
+        )
+        .map_err(|error| InitializationError::BlockRevalidationFailed {
+            clean_height,
+            tip_id,
+            error: Box::new(error),
+        })
+    }
+
+    /// Set up the storage according to the configured durability.
+    ///
+    /// Must be called once everything up to the current tip is known to be valid.
+    #[log_error]
+    fn init_storage_durability(&mut self) -> Result<(), BlockError> {
+        if !self.chainstate_config.is_durability_relaxed() {
+            // Every commit is durable, so there is no need to keep the clean height
+            if self.chainstate_storage.transaction_ro()?.get_clean_block_height()?.is_some() {
+                let mut db_tx = self.chainstate_storage.transaction_rw(None)?;
+                db_tx.del_clean_block_height()?;
+                db_tx.commit()?;
+            }
+            return Ok(());
+        }
+
+        self.flush_storage()?;
+
+        if *self.chainstate_config.ibd_write_batching && self.is_initial_block_download() {
+            log::info!("Enabling chainstate storage write batching for the initial block download");
+            self.chainstate_storage.set_write_batching(true)?;
+            self.storage_flush_tracker.set_batching_enabled(true);
+        }
+
+        Ok(())
+    }
+
+    /// Write out the batched changes, if any, and make the storage durable.
+    ///
+    /// If the durability is relaxed, the current tip height is recorded as the clean height;
+    /// the blocks above it are re-validated on startup if the node crashes before the next flush.
+    #[log_error]
+    pub fn flush_storage(&mut self) -> Result<(), BlockError> {
+        if self.chainstate_config.is_durability_relaxed() {
+            let tip_height = self
+                .make_db_tx_ro()?
+                .get_best_block_index()
+                .map_err(BlockError::BestBlockIndexQueryError)?
+                .block_height();
+
+            let mut db_tx = self.chainstate_storage.transaction_rw(None)?;
+            db_tx.set_clean_block_height(tip_height)?;
+            db_tx.commit()?;
+        }
+
+        self.chainstate_storage.flush()?;
+        self.storage_flush_tracker.on_flushed(self.time_getter.get_time());
+
+        Ok(())
+    }
+
+    /// Flush the storage if a write batch is full or the sync interval has elapsed; stop batching
+    /// the writes once the initial block download is finished.
+    #[log_error]
+    fn flush_storage_if_needed(&mut self, processed_block_size: usize) -> Result<(), BlockError> {
+        if !self.chainstate_config.is_durability_relaxed() {
+            return Ok(());
+        }
+
+        self.storage_flush_tracker.on_block_processed(processed_block_size);
+
+        if self.storage_flush_tracker.batching_enabled() && !self.is_initial_block_download() {
+            log::info!(
+                "Initial block download is finished, disabling chainstate storage write batching"
+            );
+            self.chainstate_storage.set_write_batching(false)?;
+            self.storage_flush_tracker.set_batching_enabled(false);
+            return self.flush_storage();
+        }
+
+        let now = self.time_getter.get_time();
+        if self.storage_flush_tracker.should_flush(&self.chainstate_config, now) {
+            self.flush_storage()?;
+        }
+
+        Ok(())
+    }
+
     fn broadcast_new_tip_event(&mut self, new_block_index: &Option<BlockIndex>) {
         match new_block_index {
             Some(ref new_block_index) => {
@@ -266,6 +586,7 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
                 let new_id = *new_block_index.block_id();
                 let event = ChainstateEvent::NewTip(new_id, new_height);
 
+                metrics().set_tip(new_height, new_block_index.block_timestamp());
                 self.rpc_events.broadcast(&event);
                 self.subsystem_events.broadcast(event);
             }
@@ -633,6 +954,8 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
 
             self.update_initial_block_download_flag()
                 .map_err(BlockError::BestBlockIdQueryError)?;
+
+            self.upgrade_warning.check_new_tip(&self.chain_config, bi.block_height());
         }
 
         Ok(result)
@@ -645,11 +968,15 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
         block: WithId<Block>,
         block_source: BlockSource,
     ) -> Result<Option<BlockIndex>, BlockError> {
+        let block_size = block.encoded_size();
+        let start_time = std::time::Instant::now();
         let result = self.process_block_and_related_orphans(block, block_source);
+        metrics().block_processing_time.observe(start_time.elapsed().as_secs_f64());
         // Note: we don't ignore the result of check_consistency even though we may already have
         // an error to return (if the checks are enabled but couldn't be done for some reason,
         // we don't want to miss this).
         self.check_consistency()?;
+        self.flush_storage_if_needed(block_size)?;
         result
     }
 
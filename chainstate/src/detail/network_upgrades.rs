@@ -0,0 +1,326 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use common::{
+    chain::{ChainConfig, ChainstateUpgrade, ConsensusUpgrade, PoSConsensusVersion},
+    primitives::BlockHeight,
+};
+use logging::log;
+
+/// The height up to which this build knows about the network upgrades, can be set at build time.
+///
+/// If not set, the latest activation height in the default upgrade schedule of the chain is used.
+const KNOWN_UPGRADES_HEIGHT: Option<&str> = option_env!("ML_KNOWN_UPGRADES_HEIGHT");
+
+/// A consensus or chainstate upgrade defined in the chain config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, rpc::description::HasValueHint)]
+pub struct NetworkUpgradeInfo {
+    pub name: String,
+    pub activation_height: BlockHeight,
+    /// Whether the upgrade is active at the current tip.
+    pub is_active: bool,
+    /// The number of blocks until the activation height; zero for the active upgrades.
+    pub blocks_until_activation: u64,
+}
+
+fn consensus_upgrade_name(upgrade: &ConsensusUpgrade) -> String {
+    match upgrade {
+        ConsensusUpgrade::PoW {
+            initial_difficulty: _,
+        } => "Consensus: PoW".to_owned(),
+        ConsensusUpgrade::PoS {
+            initial_difficulty: _,
+            config,
+        } => {
+            let version = config.consensus_version();
+            if version == PoSConsensusVersion::V0 {
+                "Consensus: PoS V0".to_owned()
+            } else if version == PoSConsensusVersion::V1 {
+                "Consensus: PoS V1".to_owned()
+            } else {
+                format!("Consensus: PoS {version:?}")
+            }
+        }
+        ConsensusUpgrade::IgnoreConsensus => "Consensus: IgnoreConsensus".to_owned(),
+    }
+}
+
+/// The name of a chainstate upgrade lists the versions that it changes.
+fn chainstate_upgrade_name(
+    upgrade: &ChainstateUpgrade,
+    prev: Option<&ChainstateUpgrade>,
+) -> String {
+    let prev = match prev {
+        Some(prev) => prev,
+        None => return "Chainstate: initial rules".to_owned(),
+    };
+
+    let mut changes = Vec::new();
+    macro_rules! push_if_changed {
+        ($getter:ident, $type_name:literal) => {
+            if upgrade.$getter() != prev.$getter() {
+                changes.push(format!("{}::{:?}", $type_name, upgrade.$getter()));
+            }
+        };
+    }
+    push_if_changed!(token_issuance_version, "TokenIssuanceVersion");
+    push_if_changed!(reward_distribution_version, "RewardDistributionVersion");
+    push_if_changed!(tokens_fee_version, "TokensFeeVersion");
+    push_if_changed!(data_deposit_fee_version, "DataDepositFeeVersion");
+    push_if_changed!(
+        change_token_metadata_uri_activated,
+        "ChangeTokenMetadataUriActivated"
+    );
+    push_if_changed!(htlc_activated, "HtlcActivated");
+    push_if_changed!(orders_activated, "OrdersActivated");
+
+    if changes.is_empty() {
+        "Chainstate: no changes".to_owned()
+    } else {
+        format!("Chainstate: {}", changes.join(", "))
+    }
+}
+
+/// List the consensus and chainstate upgrades of the chain ordered by their activation heights,
+/// relative to the given tip height.
+pub fn network_upgrades(
+    chain_config: &ChainConfig,
+    tip_height: BlockHeight,
+) -> Vec<NetworkUpgradeInfo> {
+    let consensus_upgrades = chain_config
+        .consensus_upgrades()
+        .iter()
+        .map(|(height, upgrade)| (height, consensus_upgrade_name(upgrade)));
+
+    let mut prev_chainstate_upgrade = None;
+    let chainstate_upgrades = chain_config.chainstate_upgrades().iter().map(|(height, upgrade)| {
+        let name = chainstate_upgrade_name(upgrade, prev_chainstate_upgrade);
+        prev_chainstate_upgrade = Some(upgrade);
+        (height, name)
+    });
+
+    let mut upgrades = consensus_upgrades
+        .chain(chainstate_upgrades)
+        .map(|(activation_height, name)| NetworkUpgradeInfo {
+            name,
+            activation_height,
+            is_active: activation_height <= tip_height,
+            blocks_until_activation: u64::from(activation_height)
+                .saturating_sub(u64::from(tip_height)),
+        })
+        .collect::<Vec<_>>();
+    // The sort is stable, so the consensus upgrades go first at the same height
+    upgrades.sort_by_key(|upgrade| upgrade.activation_height);
+    upgrades
+}
+
+/// Warns about the upcoming upgrades that this build doesn't know about, i.e. the ones
+/// activating above the known upgrades height, once they are within the warning window.
+pub struct UpgradeWarning {
+    known_upgrades_height: BlockHeight,
+    window: u64,
+    /// The activation heights and names of the upgrades that were already warned about
+    warned_upgrades: BTreeSet<(BlockHeight, String)>,
+}
+
+impl UpgradeWarning {
+    pub fn new(chain_config: &ChainConfig, window: u64) -> Self {
+        let known_upgrades_height = KNOWN_UPGRADES_HEIGHT
+            .and_then(|height| height.parse().ok())
+            .unwrap_or_else(|| chain_config.chain_type().default_latest_upgrade_height());
+        Self::with_known_upgrades_height(known_upgrades_height, window)
+    }
+
+    fn with_known_upgrades_height(known_upgrades_height: BlockHeight, window: u64) -> Self {
+        Self {
+            known_upgrades_height,
+            window,
+            warned_upgrades: BTreeSet::new(),
+        }
+    }
+
+    /// The upgrades unknown to this build that activate within the warning window
+    pub fn unknown_upcoming_upgrades(
+        &self,
+        chain_config: &ChainConfig,
+        tip_height: BlockHeight,
+    ) -> Vec<NetworkUpgradeInfo> {
+        network_upgrades(chain_config, tip_height)
+            .into_iter()
+            .filter(|upgrade| {
+                !upgrade.is_active
+                    && upgrade.activation_height > self.known_upgrades_height
+                    && upgrade.blocks_until_activation <= self.window
+            })
+            .collect()
+    }
+
+    /// Whether the node should be updated before an upcoming upgrade
+    pub fn is_update_recommended(
+        &self,
+        chain_config: &ChainConfig,
+        tip_height: BlockHeight,
+    ) -> bool {
+        !self.unknown_upcoming_upgrades(chain_config, tip_height).is_empty()
+    }
+
+    /// Log a warning for each unknown upcoming upgrade, once per upgrade
+    pub fn check_new_tip(&mut self, chain_config: &ChainConfig, tip_height: BlockHeight) {
+        for upgrade in self.unknown_upcoming_upgrades(chain_config, tip_height) {
+            if self.warned_upgrades.insert((upgrade.activation_height, upgrade.name.clone())) {
+                log::warn!(
+                    "!!! The network upgrade \"{}\" activates at height {} (in {} blocks), but this node only knows about the upgrades up to height {}. Please update the node !!!",
+                    upgrade.name,
+                    upgrade.activation_height,
+                    upgrade.blocks_until_activation,
+                    self.known_upgrades_height,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::chain::{
+        config::{Builder, ChainType},
+        ChangeTokenMetadataUriActivated, DataDepositFeeVersion, HtlcActivated, NetUpgrades,
+        OrdersActivated, RewardDistributionVersion, TokenIssuanceVersion, TokensFeeVersion,
+    };
+
+    fn make_chainstate_upgrade(
+        token_issuance_version: TokenIssuanceVersion,
+        htlc_activated: HtlcActivated,
+        orders_activated: OrdersActivated,
+    ) -> ChainstateUpgrade {
+        ChainstateUpgrade::new(
+            token_issuance_version,
+            RewardDistributionVersion::V1,
+            TokensFeeVersion::V1,
+            DataDepositFeeVersion::V1,
+            ChangeTokenMetadataUriActivated::Yes,
+            htlc_activated,
+            orders_activated,
+        )
+    }
+
+    fn make_regtest_config() -> ChainConfig {
+        Builder::new(ChainType::Regtest)
+            .chainstate_upgrades(
+                NetUpgrades::initialize(vec![
+                    (
+                        BlockHeight::zero(),
+                        make_chainstate_upgrade(
+                            TokenIssuanceVersion::V0,
+                            HtlcActivated::No,
+                            OrdersActivated::No,
+                        ),
+                    ),
+                    (
+                        BlockHeight::new(10),
+                        make_chainstate_upgrade(
+                            TokenIssuanceVersion::V1,
+                            HtlcActivated::Yes,
+                            OrdersActivated::No,
+                        ),
+                    ),
+                    (
+                        BlockHeight::new(100),
+                        make_chainstate_upgrade(
+                            TokenIssuanceVersion::V1,
+                            HtlcActivated::Yes,
+                            OrdersActivated::Yes,
+                        ),
+                    ),
+                ])
+                .unwrap(),
+            )
+            .build()
+    }
+
+    #[test]
+    fn regtest_schedule() {
+        let chain_config = make_regtest_config();
+
+        let upgrades = network_upgrades(&chain_config, BlockHeight::new(5));
+        assert_eq!(
+            serde_json::to_value(upgrades).unwrap(),
+            serde_json::json!([
+                {
+                    "name": "Consensus: IgnoreConsensus",
+                    "activation_height": 0,
+                    "is_active": true,
+                    "blocks_until_activation": 0,
+                },
+                {
+                    "name": "Chainstate: initial rules",
+                    "activation_height": 0,
+                    "is_active": true,
+                    "blocks_until_activation": 0,
+                },
+                {
+                    "name": "Consensus: PoW",
+                    "activation_height": 1,
+                    "is_active": true,
+                    "blocks_until_activation": 0,
+                },
+                {
+                    "name": "Chainstate: TokenIssuanceVersion::V1, HtlcActivated::Yes",
+                    "activation_height": 10,
+                    "is_active": false,
+                    "blocks_until_activation": 5,
+                },
+                {
+                    "name": "Chainstate: OrdersActivated::Yes",
+                    "activation_height": 100,
+                    "is_active": false,
+                    "blocks_until_activation": 95,
+                },
+            ])
+        );
+
+        let upgrades = network_upgrades(&chain_config, BlockHeight::new(100));
+        assert!(upgrades.iter().all(|upgrade| upgrade.is_active));
+        assert!(upgrades.iter().all(|upgrade| upgrade.blocks_until_activation == 0));
+    }
+
+    #[test]
+    fn warning_window() {
+        let chain_config = make_regtest_config();
+        let warning = UpgradeWarning::with_known_upgrades_height(BlockHeight::new(10), 20);
+
+        // The upgrade at height 10 is known, the one at 100 is outside the window
+        assert!(!warning.is_update_recommended(&chain_config, BlockHeight::new(5)));
+        assert!(!warning.is_update_recommended(&chain_config, BlockHeight::new(79)));
+
+        let upgrades = warning.unknown_upcoming_upgrades(&chain_config, BlockHeight::new(80));
+        assert_eq!(upgrades.len(), 1);
+        assert_eq!(upgrades[0].activation_height, BlockHeight::new(100));
+        assert!(warning.is_update_recommended(&chain_config, BlockHeight::new(99)));
+
+        // Once activated, the upgrade is not upcoming anymore
+        assert!(!warning.is_update_recommended(&chain_config, BlockHeight::new(100)));
+
+        // Nothing to warn about if the build knows about all the upgrades
+        let warning = UpgradeWarning::with_known_upgrades_height(BlockHeight::new(100), 1000);
+        assert!(!warning.is_update_recommended(&chain_config, BlockHeight::zero()));
+    }
+}
@@ -0,0 +1,183 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use chainstate_types::PropertyQueryError;
+use common::{
+    address::{Address, AddressError},
+    chain::{
+        signature::{
+            inputsig::arbitrary_message::proof_of_reserves::ProofOfReserves, DestinationSigError,
+        },
+        ChainConfig, GenBlock, TxOutput,
+    },
+    primitives::{Amount, BlockHeight, Id},
+};
+use rpc::types::RpcHexString;
+
+use crate::rpc::RpcUtxoOutpoint;
+
+use super::decoded_transaction::{decode_value, DecodedOutputValue};
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ProofOfReservesError {
+    #[error("Invalid proof hex: {0}")]
+    InvalidHex(String),
+    #[error("The block {0} of the proof is not in the mainchain")]
+    BlockNotInMainchain(Id<GenBlock>),
+    #[error("Address encoding error: {0}")]
+    AddressError(#[from] AddressError),
+    #[error("Failed to read a utxo: {0}")]
+    PropertyQueryError(#[from] PropertyQueryError),
+    #[error("Amount overflow")]
+    AmountOverflow,
+}
+
+/// The result of checking the signature of a single utxo of a proof of reserves.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, rpc::description::HasValueHint)]
+#[serde(tag = "type", content = "content")]
+pub enum ReserveProofEntryStatus {
+    Valid,
+    /// All the signatures of a multisig utxo are valid, but there are fewer of them
+    /// than the multisig requires.
+    PartialMultisig {
+        num_signatures: u8,
+        required_signatures: u8,
+    },
+    InvalidSignature,
+    /// The utxo wasn't created in the mainchain at or below the block of the proof,
+    /// or it's not a transfer output.
+    UtxoNotFound,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, rpc::description::HasValueHint)]
+pub struct ReserveProofEntryReport {
+    pub outpoint: RpcUtxoOutpoint,
+    pub status: ReserveProofEntryStatus,
+    /// The coins or tokens locked in the utxo, if it was found.
+    pub value: Option<DecodedOutputValue>,
+    /// The destination that controls the utxo, if it was found.
+    pub destination: Option<String>,
+    /// Whether the utxo has been spent since the block of the proof.
+    pub spent: bool,
+}
+
+/// The result of checking a proof of reserves against the mainchain.
+#[derive(Debug, Clone, Serialize, Deserialize, rpc::description::HasValueHint)]
+pub struct ProofOfReservesReport {
+    pub message: RpcHexString,
+    pub block_id: Id<GenBlock>,
+    pub block_height: BlockHeight,
+    pub entries: Vec<ReserveProofEntryReport>,
+    /// The coins in the utxos with valid signatures, including the ones spent since.
+    pub provable_coins: Amount,
+    /// The tokens in the utxos with valid signatures by token id, including the ones
+    /// spent since.
+    pub provable_tokens: BTreeMap<String, Amount>,
+}
+
+impl ProofOfReservesReport {
+    /// Check the signatures of the proof; `utxos` are the outputs referenced by the entries
+    /// of the proof, if they were found, along with the flags telling whether they have been
+    /// spent since.
+    pub fn new(
+        chain_config: &ChainConfig,
+        proof: &ProofOfReserves,
+        block_height: BlockHeight,
+        utxos: Vec<Option<(TxOutput, bool)>>,
+    ) -> Result<Self, ProofOfReservesError> {
+        let mut provable_coins = Amount::ZERO;
+        let mut provable_tokens = BTreeMap::<String, Amount>::new();
+
+        let entries = proof
+            .entries()
+            .iter()
+            .zip(utxos)
+            .map(|(entry, utxo)| {
+                let outpoint = RpcUtxoOutpoint::new(entry.outpoint().clone());
+                let (value, destination, spent) = match utxo {
+                    Some((TxOutput::Transfer(value, destination), spent))
+                    | Some((TxOutput::LockThenTransfer(value, destination, _), spent)) => {
+                        (value, destination, spent)
+                    }
+                    Some(_) | None => {
+                        return Ok(ReserveProofEntryReport {
+                            outpoint,
+                            status: ReserveProofEntryStatus::UtxoNotFound,
+                            value: None,
+                            destination: None,
+                            spent: false,
+                        })
+                    }
+                };
+
+                let status = match entry.verify_signature(
+                    chain_config,
+                    proof.message(),
+                    proof.block_id(),
+                    &destination,
+                ) {
+                    Ok(()) => ReserveProofEntryStatus::Valid,
+                    Err(DestinationSigError::IncompleteClassicalMultisigSignature(
+                        required_signatures,
+                        num_signatures,
+                    )) => ReserveProofEntryStatus::PartialMultisig {
+                        num_signatures,
+                        required_signatures,
+                    },
+                    Err(_) => ReserveProofEntryStatus::InvalidSignature,
+                };
+
+                let value = decode_value(chain_config, &value)?;
+                if status == ReserveProofEntryStatus::Valid {
+                    let total = match &value {
+                        Some(DecodedOutputValue::Coin { amount }) => {
+                            Some((&mut provable_coins, *amount))
+                        }
+                        Some(DecodedOutputValue::Token { token_id, amount }) => Some((
+                            provable_tokens.entry(token_id.clone()).or_insert(Amount::ZERO),
+                            *amount,
+                        )),
+                        None => None,
+                    };
+                    if let Some((total, amount)) = total {
+                        *total = (*total + amount).ok_or(ProofOfReservesError::AmountOverflow)?;
+                    }
+                }
+
+                Ok(ReserveProofEntryReport {
+                    outpoint,
+                    status,
+                    value,
+                    destination: Some(Address::new(chain_config, destination)?.into_string()),
+                    spent,
+                })
+            })
+            .collect::<Result<Vec<_>, ProofOfReservesError>>()?;
+
+        Ok(Self {
+            message: RpcHexString::from_bytes(proof.message().to_vec()),
+            block_id: *proof.block_id(),
+            block_height,
+            entries,
+            provable_coins,
+            provable_tokens,
+        })
+    }
+}
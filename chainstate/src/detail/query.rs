@@ -29,10 +29,17 @@ use common::{
     primitives::{Amount, BlockDistance, BlockHeight, Id, Idable},
 };
 use orders_accounting::OrdersAccountingStorageRead;
+use serialization::Encode;
 use tokens_accounting::TokensAccountingStorageRead;
 use utils::ensure;
 
-use super::{chainstateref, tx_verification_strategy::TransactionVerificationStrategy};
+use super::{
+    block_size_histogram::BlockSizeHistogram, chainstateref,
+    tx_verification_strategy::TransactionVerificationStrategy,
+};
+
+/// The maximum number of ancestors that can be requested by `get_ancestors`
+pub const MAX_ANCESTORS_DEPTH: u32 = 2016;
 
 pub fn locator_tip_distances() -> impl Iterator<Item = BlockDistance> {
     itertools::iterate(0, |&i| std::cmp::max(1, i * 2)).map(BlockDistance::new)
@@ -125,6 +132,48 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         Ok(res)
     }
 
+    /// Return the ids of up to `depth` ancestors of the specified block, starting from its parent.
+    ///
+    /// Fewer ids are returned if genesis is reached, which is not included in the result.
+    /// Only the block indices are loaded, so the blocks don't have to be on the mainchain.
+    pub fn get_ancestors(
+        &self,
+        block_id: &Id<Block>,
+        depth: u32,
+    ) -> Result<Vec<Id<Block>>, PropertyQueryError> {
+        ensure!(
+            depth <= MAX_ANCESTORS_DEPTH,
+            PropertyQueryError::AncestorsDepthTooLarge {
+                depth,
+                max_depth: MAX_ANCESTORS_DEPTH
+            }
+        );
+
+        let mut block_index = self
+            .chainstate_ref
+            .get_block_index(block_id)?
+            .ok_or(PropertyQueryError::BlockIndexNotFound((*block_id).into()))?;
+
+        let mut ancestors = Vec::with_capacity(depth as usize);
+        while ancestors.len() < depth as usize {
+            let prev_block_id =
+                match block_index.prev_block_id().classify(self.chainstate_ref.chain_config()) {
+                    common::chain::GenBlockId::Genesis(_) => break,
+                    common::chain::GenBlockId::Block(id) => id,
+                };
+
+            block_index = self.chainstate_ref.get_block_index(&prev_block_id)?.ok_or(
+                PropertyQueryError::PrevBlockIndexNotFound {
+                    block_id: *block_index.block_id(),
+                    prev_block_id: prev_block_id.into(),
+                },
+            )?;
+            ancestors.push(prev_block_id);
+        }
+
+        Ok(ancestors)
+    }
+
     pub fn get_block_index_for_persisted_block(
         &self,
         id: &Id<Block>,
@@ -222,6 +271,49 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         itertools::process_results(iter, |iter| iter.collect::<Vec<_>>())
     }
 
+    pub fn get_block_size_histogram(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+        max_range: usize,
+    ) -> Result<BlockSizeHistogram, PropertyQueryError> {
+        ensure!(
+            from_height <= to_height,
+            PropertyQueryError::InvalidBlockHeightRange {
+                start: from_height,
+                end: to_height
+            }
+        );
+        let range_len = (to_height.into_int() - from_height.into_int()).saturating_add(1);
+        ensure!(
+            range_len <= max_range as u64,
+            PropertyQueryError::BlockHeightRangeTooLarge {
+                start: from_height,
+                end: to_height,
+                max_range,
+            }
+        );
+
+        // Genesis has no body, so it's never counted; heights above the tip are ignored.
+        let best_block_height = self.chainstate_ref.get_best_block_index()?.block_height();
+        let from_height = std::cmp::max(from_height, BlockHeight::one());
+        let to_height = std::cmp::min(to_height, best_block_height);
+
+        let block_ids =
+            self.chainstate_ref.get_block_ids_by_height_range(from_height, to_height)?;
+        let block_sizes = block_ids.into_iter().map(|(height, block_id)| {
+            let block_id = block_id
+                .classify(self.chainstate_ref.chain_config())
+                .chain_block_id()
+                .unwrap_or_else(|| panic!("genesis block received at non-zero height {height}"));
+            Ok(self.get_existing_block(block_id)?.encoded_size())
+        });
+
+        itertools::process_results(block_sizes, |sizes| {
+            BlockSizeHistogram::from_block_sizes(sizes)
+        })
+    }
+
     pub fn is_block_in_main_chain(&self, id: &Id<GenBlock>) -> Result<bool, PropertyQueryError> {
         self.chainstate_ref.is_block_in_main_chain(id)
     }
@@ -0,0 +1,167 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use chainstate_storage::{BlockchainStorage, BlockchainStorageRead, TransactionRo};
+use common::{
+    chain::{Block, ChainConfig, GenBlock, GenBlockId},
+    primitives::Id,
+};
+use logging::log;
+
+use crate::{chainstate_interface::ChainstateInterface, BlockSource, ChainstateError};
+
+use super::error_classification::BlockProcessingErrorClassification;
+
+/// Log the reindexing progress after this many blocks
+const PROGRESS_LOG_INTERVAL: usize = 1000;
+
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+pub enum ReindexError {
+    #[error("Block storage error: `{0}`")]
+    StorageError(#[from] chainstate_storage::Error),
+    #[error("File system error: {0}")]
+    FileSystem(String),
+    #[error("Block {0} is missing in the storage")]
+    BlockMissing(Id<Block>),
+    #[error("Block {0} failed to process during reindex, the stored blocks may be corrupted: {1}")]
+    BlockProcessing(Id<Block>, Box<ChainstateError>),
+}
+
+impl From<std::io::Error> for ReindexError {
+    fn from(error: std::io::Error) -> Self {
+        Self::FileSystem(error.to_string())
+    }
+}
+
+/// Process all blocks stored in `source` by `chainstate`, in height order.
+///
+/// Only the block bodies are taken from `source`, everything else (block indices, the utxo set,
+/// accounting data) is rebuilt by `chainstate` through the normal block processing.
+/// Blocks that are not connected to genesis are skipped.
+///
+/// The storage may contain side-branch blocks that were found invalid during a reorg, so
+/// an invalid block is skipped along with its descendants, unless it's on the chain that ends
+/// at the stored best block, in which case the reindex fails.
+pub fn reindex_from_storage<S: BlockchainStorage>(
+    source: &S,
+    chainstate: &mut dyn ChainstateInterface,
+) -> Result<(), ReindexError> {
+    let db_tx = source.transaction_ro()?;
+    let StoredBlocks {
+        block_ids,
+        best_chain,
+    } = stored_blocks_in_height_order(&db_tx, chainstate.get_chain_config())?;
+
+    log::info!("Reindexing {} stored blocks", block_ids.len());
+
+    let mut failed_blocks = BTreeSet::<Id<GenBlock>>::new();
+    for (idx, block_id) in block_ids.iter().enumerate() {
+        let block = db_tx.get_block(*block_id)?.ok_or(ReindexError::BlockMissing(*block_id))?;
+
+        if failed_blocks.contains(&block.prev_block_id()) {
+            failed_blocks.insert((*block_id).into());
+        } else if let Err(err) = chainstate.process_block(block, BlockSource::Local) {
+            let is_invalid_side_block = !best_chain.contains(block_id)
+                && match &err {
+                    ChainstateError::ProcessBlockError(err) => {
+                        err.classify().block_should_be_invalidated()
+                    }
+                    _ => false,
+                };
+            if !is_invalid_side_block {
+                return Err(ReindexError::BlockProcessing(*block_id, Box::new(err)));
+            }
+
+            log::warn!(
+                "Skipping stored side-branch block {block_id} that failed to process: {err}"
+            );
+            failed_blocks.insert((*block_id).into());
+        }
+
+        let processed_count = idx + 1;
+        if processed_count % PROGRESS_LOG_INTERVAL == 0 {
+            log::info!(
+                "Reindexing: {processed_count} of {} blocks processed",
+                block_ids.len()
+            );
+        }
+    }
+
+    db_tx.close();
+
+    log::info!(
+        "Reindexing finished, {} blocks processed, {} side-branch blocks skipped",
+        block_ids.len() - failed_blocks.len(),
+        failed_blocks.len()
+    );
+
+    Ok(())
+}
+
+struct StoredBlocks {
+    /// The ids of the stored blocks connected to genesis, ordered by height
+    block_ids: Vec<Id<Block>>,
+    /// The ids of the blocks on the chain that ends at the stored best block
+    best_chain: BTreeSet<Id<Block>>,
+}
+
+/// Return the ids of the stored blocks ordered by height, based on their parent ids.
+fn stored_blocks_in_height_order(
+    db_tx: &impl BlockchainStorageRead,
+    chain_config: &ChainConfig,
+) -> Result<StoredBlocks, ReindexError> {
+    let mut parents = BTreeMap::<Id<Block>, Id<GenBlock>>::new();
+    let mut children = BTreeMap::<Id<GenBlock>, Vec<Id<Block>>>::new();
+    for block_id in db_tx.get_block_map_keys()? {
+        // The block index is derived data itself, so the parent ids are taken from the blocks.
+        let block = db_tx.get_block(block_id)?.ok_or(ReindexError::BlockMissing(block_id))?;
+        parents.insert(block_id, block.prev_block_id());
+        children.entry(block.prev_block_id()).or_default().push(block_id);
+    }
+
+    let mut best_chain = BTreeSet::new();
+    let mut best_chain_block_id = db_tx.get_best_block_id()?;
+    while let Some(block_id) = best_chain_block_id {
+        best_chain_block_id = match block_id.classify(chain_config) {
+            GenBlockId::Genesis(_) => None,
+            GenBlockId::Block(block_id) => {
+                best_chain.insert(block_id);
+                parents.get(&block_id).copied()
+            }
+        };
+    }
+
+    // Walking the block tree breadth-first from genesis visits the blocks in height order.
+    let mut result = Vec::new();
+    let mut queue = VecDeque::from([chain_config.genesis_block_id()]);
+    while let Some(parent_id) = queue.pop_front() {
+        for block_id in children.remove(&parent_id).unwrap_or_default() {
+            result.push(block_id);
+            queue.push_back(block_id.into());
+        }
+    }
+
+    let skipped_count = children.values().map(Vec::len).sum::<usize>();
+    if skipped_count != 0 {
+        log::warn!("Skipping {skipped_count} stored blocks that are not connected to genesis");
+    }
+
+    Ok(StoredBlocks {
+        block_ids: result,
+        best_chain,
+    })
+}
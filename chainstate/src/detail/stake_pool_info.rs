@@ -0,0 +1,37 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    chain::PoolId,
+    primitives::{per_thousand::PerThousand, Amount},
+};
+
+/// The number of the latest mainchain blocks in which the blocks produced by each pool
+/// are counted.
+pub const STAKE_POOL_BLOCKS_WINDOW: usize = 1000;
+
+/// The current state of a stake pool along with its recent activity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StakePoolInfo {
+    pub pool_id: PoolId,
+    pub pledge: Amount,
+    /// The balance of the pool, including the delegations and the staker's rewards.
+    pub total_stake: Amount,
+    pub cost_per_block: Amount,
+    pub margin_ratio_per_thousand: PerThousand,
+    /// The number of blocks produced by the pool among the latest `STAKE_POOL_BLOCKS_WINDOW`
+    /// mainchain blocks.
+    pub blocks_produced: u64,
+}
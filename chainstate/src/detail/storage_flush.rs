@@ -0,0 +1,131 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::primitives::time::Time;
+
+use crate::{config::StorageSyncPolicy, ChainstateConfig};
+
+/// Tracks the blocks processed since the chainstate storage was last flushed, to decide when
+/// it has to be flushed next.
+pub struct StorageFlushTracker {
+    /// Whether the storage currently batches the committed blocks
+    batching_enabled: bool,
+    /// The number of blocks processed since the last flush
+    pending_blocks: usize,
+    /// The total size of the blocks processed since the last flush
+    pending_size: usize,
+    last_flush_time: Time,
+}
+
+impl StorageFlushTracker {
+    pub fn new(now: Time) -> Self {
+        Self {
+            batching_enabled: false,
+            pending_blocks: 0,
+            pending_size: 0,
+            last_flush_time: now,
+        }
+    }
+
+    pub fn batching_enabled(&self) -> bool {
+        self.batching_enabled
+    }
+
+    pub fn set_batching_enabled(&mut self, enabled: bool) {
+        self.batching_enabled = enabled;
+    }
+
+    pub fn on_block_processed(&mut self, block_size: usize) {
+        self.pending_blocks += 1;
+        self.pending_size = self.pending_size.saturating_add(block_size);
+    }
+
+    /// Whether the write batch is full or the sync interval has elapsed
+    pub fn should_flush(&self, config: &ChainstateConfig, now: Time) -> bool {
+        let batch_full = self.batching_enabled
+            && (self.pending_blocks >= *config.max_write_batch_blocks
+                || self.pending_size >= *config.max_write_batch_size);
+
+        let interval_elapsed = match config.storage_sync_policy {
+            StorageSyncPolicy::Periodic(interval) => {
+                self.pending_blocks > 0
+                    && (self.last_flush_time + interval).is_some_and(|deadline| now >= deadline)
+            }
+            StorageSyncPolicy::Always | StorageSyncPolicy::OnShutdown => false,
+        };
+
+        batch_full || interval_elapsed
+    }
+
+    pub fn on_flushed(&mut self, now: Time) {
+        self.pending_blocks = 0;
+        self.pending_size = 0;
+        self.last_flush_time = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn batch_limits() {
+        let config = ChainstateConfig::new()
+            .with_ibd_write_batching(true)
+            .with_max_write_batch_blocks(3)
+            .with_max_write_batch_size(1000);
+        let now = Time::from_secs_since_epoch(1000);
+
+        let mut tracker = StorageFlushTracker::new(now);
+        tracker.on_block_processed(100);
+        tracker.on_block_processed(100);
+        tracker.on_block_processed(100);
+        // Without batching, a full batch doesn't matter
+        assert!(!tracker.should_flush(&config, now));
+
+        tracker.set_batching_enabled(true);
+        assert!(tracker.should_flush(&config, now));
+
+        tracker.on_flushed(now);
+        assert!(!tracker.should_flush(&config, now));
+        tracker.on_block_processed(1000);
+        assert!(tracker.should_flush(&config, now));
+    }
+
+    #[test]
+    fn periodic_sync() {
+        let config = ChainstateConfig::new()
+            .with_storage_sync_policy(StorageSyncPolicy::Periodic(Duration::from_secs(60)));
+        let now = Time::from_secs_since_epoch(1000);
+        let later = (now + Duration::from_secs(60)).unwrap();
+
+        let mut tracker = StorageFlushTracker::new(now);
+        // Nothing to flush yet
+        assert!(!tracker.should_flush(&config, later));
+
+        tracker.on_block_processed(100);
+        assert!(!tracker.should_flush(&config, now));
+        assert!(tracker.should_flush(&config, later));
+
+        tracker.on_flushed(later);
+        tracker.on_block_processed(100);
+        assert!(!tracker.should_flush(&config, later));
+
+        let config = config.with_storage_sync_policy(StorageSyncPolicy::OnShutdown);
+        assert!(!tracker.should_flush(&config, Time::from_secs_since_epoch(1_000_000)));
+    }
+}
@@ -13,14 +13,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::TransactionVerificationStrategy;
+use super::{make_block_fees, TransactionVerificationStrategy};
 use crate::TransactionVerifierMakerFn;
-use chainstate_types::BlockIndex;
+use chainstate_types::{BlockFees, BlockIndex};
 use common::{
     chain::{block::timestamp::BlockTimestamp, Block, ChainConfig},
     primitives::{id::WithId, Idable},
 };
-use constraints_value_accumulator::AccumulatedFee;
 use orders_accounting::OrdersAccountingView;
 use pos_accounting::PoSAccountingView;
 use tokens_accounting::TokensAccountingView;
@@ -57,7 +56,7 @@ impl TransactionVerificationStrategy for DefaultTransactionVerificationStrategy
         block_index: &BlockIndex,
         block: &WithId<Block>,
         median_time_past: BlockTimestamp,
-    ) -> Result<TransactionVerifier<C, S, U, A, T, O>, ConnectTransactionError>
+    ) -> Result<(TransactionVerifier<C, S, U, A, T, O>, BlockFees), ConnectTransactionError>
     where
         C: AsRef<ChainConfig> + ShallowClone,
         S: TransactionVerifierStorageRef,
@@ -70,32 +69,22 @@ impl TransactionVerificationStrategy for DefaultTransactionVerificationStrategy
     {
         let mut tx_verifier = tx_verifier_maker(storage_backend, chain_config.shallow_clone());
 
-        let total_fees = block
+        let tx_fees = block
             .transactions()
             .iter()
-            .try_fold(AccumulatedFee::new(), |total, tx| {
-                let fee = tx_verifier
-                    .connect_transaction(
-                        &TransactionSourceForConnect::Chain {
-                            new_block_index: block_index,
-                        },
-                        tx,
-                        &median_time_past,
-                    )
-                    .log_err()?;
-                total
-                    .combine(fee)
-                    .map_err(|_| ConnectTransactionError::FailedToAddAllFeesOfBlock(block.get_id()))
+            .map(|tx| {
+                tx_verifier.connect_transaction(
+                    &TransactionSourceForConnect::Chain {
+                        new_block_index: block_index,
+                    },
+                    tx,
+                    &median_time_past,
+                )
             })
+            .collect::<Result<Vec<_>, _>>()
             .log_err()?;
-        let total_fees = total_fees
-            .map_into_block_fees(chain_config.as_ref(), block_index.block_height())
-            .map_err(|err| {
-                ConnectTransactionError::ConstrainedValueAccumulatorError(
-                    err,
-                    block.get_id().into(),
-                )
-            })?;
+        let (total_fees, block_fees) =
+            make_block_fees(chain_config.as_ref(), block_index, block, tx_fees).log_err()?;
 
         tx_verifier
             .check_block_reward(block, total_fees, block_index.block_height())
@@ -112,7 +101,7 @@ impl TransactionVerificationStrategy for DefaultTransactionVerificationStrategy
 
         tx_verifier.set_best_block(block.get_id().into());
 
-        Ok(tx_verifier)
+        Ok((tx_verifier, block_fees))
     }
 
     fn disconnect_block<C, S, M, U, A, T, O>(
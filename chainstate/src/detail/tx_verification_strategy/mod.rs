@@ -16,11 +16,12 @@
 pub mod default_strategy;
 pub use default_strategy::DefaultTransactionVerificationStrategy;
 
-use chainstate_types::BlockIndex;
+use chainstate_types::{BlockFees, BlockIndex};
 use common::{
     chain::{block::timestamp::BlockTimestamp, Block, ChainConfig},
-    primitives::id::WithId,
+    primitives::{id::WithId, Fee, Idable},
 };
+use constraints_value_accumulator::AccumulatedFee;
 use orders_accounting::OrdersAccountingView;
 use pos_accounting::PoSAccountingView;
 use tokens_accounting::TokensAccountingView;
@@ -49,7 +50,8 @@ impl<C, S, U, A, T, O, F> TransactionVerifierMakerFn<C, S, U, A, T, O> for F whe
 pub trait TransactionVerificationStrategy: Sized + Send {
     /// Connect the transactions given by block and block_index,
     /// and return a TransactionVerifier with an internal state
-    /// that represents them being connected.
+    /// that represents them being connected, along with the fees
+    /// of the block.
     /// Notice that this doesn't modify the internal database/storage
     /// state. It just returns a TransactionVerifier that can be
     /// used to update the database/storage state.
@@ -62,7 +64,7 @@ pub trait TransactionVerificationStrategy: Sized + Send {
         block_index: &BlockIndex,
         block: &WithId<Block>,
         median_time_past: BlockTimestamp,
-    ) -> Result<TransactionVerifier<C, S, U, A, T, O>, ConnectTransactionError>
+    ) -> Result<(TransactionVerifier<C, S, U, A, T, O>, BlockFees), ConnectTransactionError>
     where
         S: TransactionVerifierStorageRef<Error = TransactionVerifierStorageError>,
         U: UtxosView,
@@ -96,3 +98,37 @@ pub trait TransactionVerificationStrategy: Sized + Send {
         M: TransactionVerifierMakerFn<C, S, U, A, T, O>,
         <S as utxo::UtxosStorageRead>::Error: From<U::Error>;
 }
+
+/// Make the block fees from the fees of the individual transactions of the block,
+/// given in the order of the transactions.
+///
+/// Returns the total fee, which is used to check the block reward, along with the fees.
+pub fn make_block_fees(
+    chain_config: &ChainConfig,
+    block_index: &BlockIndex,
+    block: &WithId<Block>,
+    tx_fees: Vec<AccumulatedFee>,
+) -> Result<(Fee, BlockFees), ConnectTransactionError> {
+    let block_height = block_index.block_height();
+    let to_block_fee = |fee: AccumulatedFee| {
+        fee.map_into_block_fees(chain_config, block_height).map_err(|err| {
+            ConnectTransactionError::ConstrainedValueAccumulatorError(err, block.get_id().into())
+        })
+    };
+
+    let total_fees = tx_fees
+        .iter()
+        .cloned()
+        .try_fold(AccumulatedFee::new(), |total, fee| total.combine(fee))
+        .map_err(|_| ConnectTransactionError::FailedToAddAllFeesOfBlock(block.get_id()))?;
+    let total_fees = to_block_fee(total_fees)?;
+
+    let tx_fees = block
+        .transactions()
+        .iter()
+        .zip(tx_fees)
+        .map(|(tx, fee)| Ok((tx.transaction().get_id(), to_block_fee(fee)?.0)))
+        .collect::<Result<Vec<_>, ConnectTransactionError>>()?;
+
+    Ok((total_fees, BlockFees::new(total_fees.0, tx_fees)))
+}
@@ -16,10 +16,11 @@
 use std::{collections::BTreeMap, num::NonZeroUsize, sync::Arc};
 
 use crate::{
-    detail::BlockSource, ChainInfo, ChainstateConfig, ChainstateError, ChainstateEvent,
-    NonZeroPoolBalances,
+    detail::BlockSource, BlockHeaderCheckInfo, BlockSizeHistogram, ChainInfo, ChainstateConfig,
+    ChainstateError, ChainstateEvent, ConsensusInfo, DecodedTransaction, NetworkUpgradeInfo,
+    NonZeroPoolBalances, ProofOfReservesReport, StakePoolInfo,
 };
-use chainstate_types::{BlockIndex, EpochData, GenBlockIndex, Locator};
+use chainstate_types::{BlockFeesInfo, BlockIndex, EpochData, GenBlockIndex, Locator};
 use common::{
     chain::{
         block::{
@@ -50,6 +51,8 @@ pub trait ChainstateInterface: Send + Sync {
         block: Block,
         source: BlockSource,
     ) -> Result<Option<BlockIndex>, ChainstateError>;
+    /// Write out the batched blocks, if any, and make the storage durable.
+    fn flush_storage(&mut self) -> Result<(), ChainstateError>;
     fn invalidate_block(&mut self, block_id: &Id<Block>) -> Result<(), ChainstateError>;
     fn reset_block_failure_flags(&mut self, block_id: &Id<Block>) -> Result<(), ChainstateError>;
     fn preliminary_block_check(&self, block: Block) -> Result<Block, ChainstateError>;
@@ -63,6 +66,16 @@ pub trait ChainstateInterface: Send + Sync {
         headers: &[SignedBlockHeader],
     ) -> Result<(), ChainstateError>;
 
+    /// Perform the checks that a new block goes through before its body is looked at
+    /// (the parent block, the timestamp rules and the consensus data) using only its header.
+    ///
+    /// If the header is rejected, submitting the full block would fail for the same reason;
+    /// if it passes, the full block can still be rejected, but only because of its body.
+    fn check_block_header(
+        &self,
+        header: &SignedBlockHeader,
+    ) -> Result<BlockHeaderCheckInfo, ChainstateError>;
+
     fn get_best_block_id(&self) -> Result<Id<GenBlock>, ChainstateError>;
     fn is_block_in_main_chain(&self, block_id: &Id<GenBlock>) -> Result<bool, ChainstateError>;
     fn get_min_height_with_allowed_reorg(&self) -> Result<BlockHeight, ChainstateError>;
@@ -87,6 +100,16 @@ pub trait ChainstateInterface: Send + Sync {
         block_id: Id<Block>,
     ) -> Result<Option<SignedBlockHeader>, ChainstateError>;
 
+    /// Returns the ids of up to `depth` ancestors of the specified block, starting from its
+    /// parent and walking back towards genesis, which is not included.
+    ///
+    /// The depth is limited by `MAX_ANCESTORS_DEPTH`.
+    fn get_ancestors(
+        &self,
+        block_id: Id<Block>,
+        depth: u32,
+    ) -> Result<Vec<Id<Block>>, ChainstateError>;
+
     /// Returns a list of block headers whose heights distances increase exponentially starting
     /// from the current tip.
     ///
@@ -312,6 +335,64 @@ pub trait ChainstateInterface: Send + Sync {
     /// Returns information about the chain.
     fn info(&self) -> Result<ChainInfo, ChainstateError>;
 
+    /// Get the consensus and chainstate upgrades defined in the chain config, ordered by their
+    /// activation heights, along with their status at the current tip.
+    fn get_network_upgrades(&self) -> Result<Vec<NetworkUpgradeInfo>, ChainstateError>;
+
+    /// Get the histogram of the sizes of the mainchain blocks with heights in the range
+    /// `from_height..=to_height`.
+    ///
+    /// Genesis and the heights above the best block are not counted. The number of heights
+    /// in the range is limited by `ChainstateConfig::max_block_size_histogram_range`.
+    fn get_block_size_histogram(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Result<BlockSizeHistogram, ChainstateError>;
+
+    /// Estimate the timestamp of the next block from the current PoS difficulty and the balances
+    /// of all the stake pools. If the estimate can't be made (e.g. the next block is not a PoS one
+    /// or there are no pools), the target block spacing is assumed.
+    fn estimate_next_block_timestamp(&self) -> Result<BlockTimestamp, ChainstateError>;
+
+    /// Get the consensus-related values (targets, difficulty, the average block time used
+    /// by the retarget algorithm) of the mainchain blocks with heights in the range
+    /// `from_height..=to_height`, along with the target required for the next block.
+    ///
+    /// Genesis and the heights above the best block are skipped. The number of heights
+    /// in the range is limited by `MAX_CONSENSUS_INFO_RANGE`.
+    fn get_consensus_info(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Result<ConsensusInfo, ChainstateError>;
+
+    /// Get the current data of all the stake pools, along with the number of blocks each
+    /// of them produced among the latest `STAKE_POOL_BLOCKS_WINDOW` mainchain blocks.
+    fn get_all_pools(&self) -> Result<Vec<StakePoolInfo>, ChainstateError>;
+
+    /// Decode a hex-encoded signed transaction into a human-readable form, with destinations
+    /// and token ids encoded as addresses. The outputs spent by the transaction are looked up
+    /// in the current utxo set; the ones that are not found are reported as unknown.
+    fn decode_transaction(&self, tx_hex: String) -> Result<DecodedTransaction, ChainstateError>;
+
+    /// Check a hex-encoded proof of reserves produced by a wallet. The signature of every utxo
+    /// is checked against the destination of the utxo as created in the mainchain, and the utxos
+    /// that are no longer in the current utxo set are flagged as spent.
+    fn verify_proof_of_reserves(
+        &self,
+        proof_hex: String,
+    ) -> Result<ProofOfReservesReport, ChainstateError>;
+
+    /// Get the total and per-transaction coin fees of a mainchain block, or None if the block
+    /// is not on the mainchain.
+    /// The fees of the blocks connected by older versions of the node are reported as not
+    /// recorded; they are not recalculated.
+    fn get_block_fees(
+        &self,
+        block_id: &Id<Block>,
+    ) -> Result<Option<BlockFeesInfo>, ChainstateError>;
+
     /// Returns account nonce for the account
     fn get_account_nonce_count(
         &self,
@@ -25,14 +25,18 @@ use crate::{
         tx_verification_strategy::TransactionVerificationStrategy,
         BlockSource, OrphanBlocksRef,
     },
-    ChainInfo, ChainstateConfig, ChainstateError, ChainstateEvent, ChainstateInterface, Locator,
-    NonZeroPoolBalances,
+    BlockHeaderCheckInfo, BlockSizeHistogram, ChainInfo, ChainstateConfig, ChainstateError,
+    ChainstateEvent, ChainstateInterface, ConsensusInfo, DecodedTransaction, Locator,
+    NetworkUpgradeInfo, NonZeroPoolBalances, ProofOfReservesReport, StakePoolInfo,
 };
 use chainstate_storage::BlockchainStorage;
-use chainstate_types::{BlockIndex, EpochData, GenBlockIndex, PropertyQueryError};
+use chainstate_types::{BlockFeesInfo, BlockIndex, EpochData, GenBlockIndex, PropertyQueryError};
 use common::{
     chain::{
-        block::{signed_block_header::SignedBlockHeader, Block, BlockReward, GenBlock},
+        block::{
+            signed_block_header::SignedBlockHeader, timestamp::BlockTimestamp, Block, BlockReward,
+            GenBlock,
+        },
         config::ChainConfig,
         tokens::{RPCTokenInfo, TokenAuxiliaryData, TokenId},
         AccountNonce, AccountType, DelegationId, OrderData, OrderId, PoolId, Transaction, TxInput,
@@ -81,6 +85,10 @@ where
             .map_err(ChainstateError::ProcessBlockError)
     }
 
+    fn flush_storage(&mut self) -> Result<(), ChainstateError> {
+        self.chainstate.flush_storage().map_err(ChainstateError::ProcessBlockError)
+    }
+
     #[tracing::instrument(skip_all, fields(block_id = %block_id))]
     fn invalidate_block(&mut self, block_id: &Id<Block>) -> Result<(), ChainstateError> {
         self.chainstate
@@ -108,6 +116,16 @@ where
             .map_err(ChainstateError::ProcessBlockError)
     }
 
+    #[tracing::instrument(skip_all, fields(block_id = %header.get_id()))]
+    fn check_block_header(
+        &self,
+        header: &SignedBlockHeader,
+    ) -> Result<BlockHeaderCheckInfo, ChainstateError> {
+        BlockChecker::new(&self.chainstate)
+            .check_block_header(header)
+            .map_err(ChainstateError::ProcessBlockError)
+    }
+
     #[tracing::instrument(skip_all, fields(block_id = %block.get_id()))]
     fn preliminary_block_check(&self, block: Block) -> Result<Block, ChainstateError> {
         let block = BlockChecker::new(&self.chainstate)
@@ -189,6 +207,19 @@ where
             .map_err(ChainstateError::FailedToReadProperty)
     }
 
+    #[tracing::instrument(skip_all, fields(block_id = %block_id, depth = depth))]
+    fn get_ancestors(
+        &self,
+        block_id: Id<Block>,
+        depth: u32,
+    ) -> Result<Vec<Id<Block>>, ChainstateError> {
+        self.chainstate
+            .query()
+            .map_err(ChainstateError::from)?
+            .get_ancestors(&block_id, depth)
+            .map_err(ChainstateError::FailedToReadProperty)
+    }
+
     #[tracing::instrument(skip_all, fields(block_id = %block_id))]
     fn get_block_header(
         &self,
@@ -382,7 +413,7 @@ where
     fn calculate_median_time_past(
         &self,
         starting_block: &Id<GenBlock>,
-    ) -> Result<common::chain::block::timestamp::BlockTimestamp, ChainstateError> {
+    ) -> Result<BlockTimestamp, ChainstateError> {
         let err_f = |e| ChainstateError::FailedToReadProperty(PropertyQueryError::from(e));
         let dbtx = self.chainstate.make_db_tx_ro().map_err(err_f)?;
         Ok(calculate_median_time_past(&dbtx, starting_block))
@@ -719,15 +750,87 @@ where
 
         let is_initial_block_download = self.is_initial_block_download();
 
+        let update_recommended = self.chainstate.is_update_recommended(best_block_height);
+
         Ok(ChainInfo {
             best_block_height,
             best_block_id,
             best_block_timestamp,
             median_time,
             is_initial_block_download,
+            update_recommended,
         })
     }
 
+    #[tracing::instrument(skip_all)]
+    fn get_network_upgrades(&self) -> Result<Vec<NetworkUpgradeInfo>, ChainstateError> {
+        let best_block_height = self.get_best_block_index()?.block_height();
+        Ok(self.chainstate.get_network_upgrades(best_block_height))
+    }
+
+    #[tracing::instrument(skip_all, fields(from_height = %from_height, to_height = %to_height))]
+    fn get_block_size_histogram(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Result<BlockSizeHistogram, ChainstateError> {
+        self.chainstate
+            .get_block_size_histogram(from_height, to_height)
+            .map_err(ChainstateError::FailedToReadProperty)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn estimate_next_block_timestamp(&self) -> Result<BlockTimestamp, ChainstateError> {
+        self.chainstate
+            .estimate_next_block_timestamp()
+            .map_err(ChainstateError::ProcessBlockError)
+    }
+
+    #[tracing::instrument(skip_all, fields(from_height = %from_height, to_height = %to_height))]
+    fn get_consensus_info(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Result<ConsensusInfo, ChainstateError> {
+        self.chainstate
+            .get_consensus_info(from_height, to_height)
+            .map_err(ChainstateError::ProcessBlockError)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn get_all_pools(&self) -> Result<Vec<StakePoolInfo>, ChainstateError> {
+        self.chainstate.get_all_pools().map_err(ChainstateError::ProcessBlockError)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn decode_transaction(&self, tx_hex: String) -> Result<DecodedTransaction, ChainstateError> {
+        self.chainstate
+            .decode_transaction(&tx_hex)
+            .map_err(ChainstateError::TransactionDecodingError)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn verify_proof_of_reserves(
+        &self,
+        proof_hex: String,
+    ) -> Result<ProofOfReservesReport, ChainstateError> {
+        self.chainstate
+            .verify_proof_of_reserves(&proof_hex)
+            .map_err(ChainstateError::ProofOfReservesError)
+    }
+
+    #[tracing::instrument(skip_all, fields(block_id = %block_id))]
+    fn get_block_fees(
+        &self,
+        block_id: &Id<Block>,
+    ) -> Result<Option<BlockFeesInfo>, ChainstateError> {
+        self.chainstate
+            .make_db_tx_ro()
+            .map_err(|e| ChainstateError::FailedToReadProperty(e.into()))?
+            .get_block_fees(block_id)
+            .map_err(ChainstateError::FailedToReadProperty)
+    }
+
     #[tracing::instrument(skip_all)]
     fn get_account_nonce_count(
         &self,
@@ -834,6 +937,7 @@ fn get_output_coin_amount(
     Ok(amount)
 }
 
+#[async_trait::async_trait]
 impl subsystem::Subsystem for Box<dyn ChainstateInterface> {
     type Interface = dyn ChainstateInterface;
 
@@ -844,4 +948,10 @@ impl subsystem::Subsystem for Box<dyn ChainstateInterface> {
     fn interface_mut(&mut self) -> &mut Self::Interface {
         self.as_mut()
     }
+
+    async fn shutdown(mut self) {
+        if let Err(err) = self.flush_storage() {
+            logging::log::error!("Failed to flush the chainstate storage on shutdown: {err}");
+        }
+    }
 }
@@ -20,7 +20,7 @@ use std::{
     sync::Arc,
 };
 
-use chainstate_types::{BlockIndex, EpochData, GenBlockIndex, Locator};
+use chainstate_types::{BlockFeesInfo, BlockIndex, EpochData, GenBlockIndex, Locator};
 use common::{
     chain::{
         block::{signed_block_header::SignedBlockHeader, timestamp::BlockTimestamp, BlockReward},
@@ -37,8 +37,10 @@ use utils_networking::broadcaster;
 use utxo::Utxo;
 
 use crate::{
-    chainstate_interface::ChainstateInterface, BlockSource, ChainInfo, ChainstateConfig,
-    ChainstateError, ChainstateEvent, NonZeroPoolBalances,
+    chainstate_interface::ChainstateInterface, BlockHeaderCheckInfo, BlockSizeHistogram,
+    BlockSource, ChainInfo, ChainstateConfig, ChainstateError, ChainstateEvent, ConsensusInfo,
+    DecodedTransaction, NetworkUpgradeInfo, NonZeroPoolBalances, ProofOfReservesReport,
+    StakePoolInfo,
 };
 
 impl<T: Deref + DerefMut + Send + Sync> ChainstateInterface for T
@@ -64,6 +66,10 @@ where
         self.deref_mut().process_block(block, source)
     }
 
+    fn flush_storage(&mut self) -> Result<(), ChainstateError> {
+        self.deref_mut().flush_storage()
+    }
+
     fn invalidate_block(&mut self, block_id: &Id<Block>) -> Result<(), ChainstateError> {
         self.deref_mut().invalidate_block(block_id)
     }
@@ -83,6 +89,13 @@ where
         self.deref().preliminary_headers_check(headers)
     }
 
+    fn check_block_header(
+        &self,
+        header: &SignedBlockHeader,
+    ) -> Result<BlockHeaderCheckInfo, ChainstateError> {
+        self.deref().check_block_header(header)
+    }
+
     fn get_best_block_id(&self) -> Result<Id<GenBlock>, ChainstateError> {
         self.deref().get_best_block_id()
     }
@@ -129,6 +142,14 @@ where
         self.deref().get_mainchain_blocks(from, max_count)
     }
 
+    fn get_ancestors(
+        &self,
+        block_id: Id<Block>,
+        depth: u32,
+    ) -> Result<Vec<Id<Block>>, ChainstateError> {
+        self.deref().get_ancestors(block_id, depth)
+    }
+
     fn get_locator(&self) -> Result<Locator, ChainstateError> {
         self.deref().get_locator()
     }
@@ -382,6 +403,52 @@ where
         self.deref().info()
     }
 
+    fn get_network_upgrades(&self) -> Result<Vec<NetworkUpgradeInfo>, ChainstateError> {
+        self.deref().get_network_upgrades()
+    }
+
+    fn get_block_size_histogram(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Result<BlockSizeHistogram, ChainstateError> {
+        self.deref().get_block_size_histogram(from_height, to_height)
+    }
+
+    fn estimate_next_block_timestamp(&self) -> Result<BlockTimestamp, ChainstateError> {
+        self.deref().estimate_next_block_timestamp()
+    }
+
+    fn get_consensus_info(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Result<ConsensusInfo, ChainstateError> {
+        self.deref().get_consensus_info(from_height, to_height)
+    }
+
+    fn get_all_pools(&self) -> Result<Vec<StakePoolInfo>, ChainstateError> {
+        self.deref().get_all_pools()
+    }
+
+    fn decode_transaction(&self, tx_hex: String) -> Result<DecodedTransaction, ChainstateError> {
+        self.deref().decode_transaction(tx_hex)
+    }
+
+    fn verify_proof_of_reserves(
+        &self,
+        proof_hex: String,
+    ) -> Result<ProofOfReservesReport, ChainstateError> {
+        self.deref().verify_proof_of_reserves(proof_hex)
+    }
+
+    fn get_block_fees(
+        &self,
+        block_id: &Id<Block>,
+    ) -> Result<Option<BlockFeesInfo>, ChainstateError> {
+        self.deref().get_block_fees(block_id)
+    }
+
     fn get_block_header(
         &self,
         block_id: Id<Block>,
@@ -472,6 +539,12 @@ mod tests {
                 min_max_bootstrap_import_buffer_sizes: Default::default(),
                 max_tip_age: Default::default(),
                 enable_heavy_checks: Some(true),
+                max_block_size_histogram_range: Default::default(),
+                ibd_write_batching: Default::default(),
+                max_write_batch_blocks: Default::default(),
+                max_write_batch_size: Default::default(),
+                storage_sync_policy: Default::default(),
+                upgrade_warning_window: Default::default(),
             };
             let chainstate_storage = Store::new_empty().unwrap();
 
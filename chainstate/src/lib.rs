@@ -17,6 +17,7 @@ mod config;
 mod detail;
 mod interface;
 
+pub mod metrics;
 pub mod rpc;
 
 use std::sync::Arc;
@@ -32,19 +33,38 @@ use detail::{bootstrap::BootstrapError, Chainstate};
 use interface::chainstate_interface_impl;
 
 pub use crate::{
-    config::{ChainstateConfig, MaxTipAge},
+    config::{ChainstateConfig, MaxTipAge, StorageSyncPolicy},
     detail::{
-        ban_score, block_invalidation::BlockInvalidatorError, calculate_median_time_past,
-        calculate_median_time_past_from_blocktimestamps, BlockError, BlockProcessingErrorClass,
-        BlockProcessingErrorClassification, BlockSource, ChainInfo, CheckBlockError,
-        CheckBlockTransactionsError, ConnectTransactionError, IOPolicyError, InitializationError,
-        Locator, NonZeroPoolBalances, OrphanCheckError, SpendStakeError,
-        StorageCompatibilityCheckError, TokenIssuanceError, TokensError,
+        ban_score,
+        block_checking::BlockHeaderCheckInfo,
+        block_invalidation::BlockInvalidatorError,
+        block_size_histogram::{BlockSizeHistogram, BLOCK_SIZE_HISTOGRAM_BUCKET_WIDTH_KB},
+        calculate_median_time_past, calculate_median_time_past_from_blocktimestamps,
+        consensus_info::{BlockConsensusInfo, ConsensusInfo, MAX_CONSENSUS_INFO_RANGE},
+        decoded_transaction::{
+            DecodedInputSource, DecodedOutputValue, DecodedTransaction, DecodedTxInput,
+            DecodedTxOutput, DecodedWitnessType, TransactionDecodingError,
+        },
+        fork_detector::{ForkDetector, PossibleFork},
+        network_upgrades::NetworkUpgradeInfo,
+        proof_of_reserves::{
+            ProofOfReservesError, ProofOfReservesReport, ReserveProofEntryReport,
+            ReserveProofEntryStatus,
+        },
+        query::MAX_ANCESTORS_DEPTH,
+        stake_pool_info::{StakePoolInfo, STAKE_POOL_BLOCKS_WINDOW},
+        BlockError, BlockProcessingErrorClass, BlockProcessingErrorClassification, BlockSource,
+        ChainInfo, CheckBlockError, CheckBlockTransactionsError, ConnectTransactionError,
+        IOPolicyError, InitializationError, Locator, NonZeroPoolBalances, OrphanCheckError,
+        SpendStakeError, StorageCompatibilityCheckError, TokenIssuanceError, TokensError,
         TransactionVerifierStorageError, MEDIAN_TIME_SPAN,
     },
 };
-pub use chainstate_types::{BlockIndex, GenBlockIndex, PropertyQueryError};
+pub use chainstate_types::{
+    BlockFees, BlockFeesInfo, BlockIndex, GenBlockIndex, PropertyQueryError,
+};
 pub use constraints_value_accumulator;
+pub use detail::reindex::{reindex_from_storage, ReindexError};
 pub use detail::tx_verification_strategy::*;
 pub use interface::{chainstate_interface, chainstate_interface_impl_delegation};
 pub use tx_verifier;
@@ -81,6 +101,60 @@ pub enum ChainstateError {
     BootstrapError(#[from] BootstrapError),
     #[error("Error invoking block invalidator: {0}")]
     BlockInvalidatorError(#[from] BlockInvalidatorError),
+    #[error("Reindex error: {0}")]
+    ReindexError(#[from] ReindexError),
+    #[error("Transaction decoding error: {0}")]
+    TransactionDecodingError(#[from] TransactionDecodingError),
+    #[error("Proof of reserves error: {0}")]
+    ProofOfReservesError(#[from] ProofOfReservesError),
+}
+
+impl ChainstateError {
+    /// The HTTP-style code of the error when it's returned by an RPC call
+    pub fn to_rpc_error_code(&self) -> i32 {
+        match self {
+            ChainstateError::FailedToReadProperty(
+                PropertyQueryError::BlockNotFound(_)
+                | PropertyQueryError::BlockIndexNotFound(_)
+                | PropertyQueryError::BlockForHeightNotFound(_),
+            )
+            | ChainstateError::ProofOfReservesError(ProofOfReservesError::BlockNotInMainchain(_)) => {
+                ::rpc::NOT_FOUND_CODE
+            }
+            ChainstateError::TransactionDecodingError(TransactionDecodingError::InvalidHex(_))
+            | ChainstateError::ProofOfReservesError(ProofOfReservesError::InvalidHex(_)) => {
+                ::rpc::INVALID_INPUT_CODE
+            }
+            ChainstateError::FailedToInitializeChainstate(_)
+            | ChainstateError::ProcessBlockError(_)
+            | ChainstateError::FailedToReadProperty(_)
+            | ChainstateError::BootstrapError(_)
+            | ChainstateError::BlockInvalidatorError(_)
+            | ChainstateError::ReindexError(_)
+            | ChainstateError::TransactionDecodingError(_)
+            | ChainstateError::ProofOfReservesError(_) => ::rpc::GENERIC_ERROR_CODE,
+        }
+    }
+
+    /// The name of the error variant, used as the machine-readable type of the RPC errors
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            ChainstateError::FailedToInitializeChainstate(_) => "FailedToInitializeChainstate",
+            ChainstateError::ProcessBlockError(_) => "ProcessBlockError",
+            ChainstateError::FailedToReadProperty(_) => "FailedToReadProperty",
+            ChainstateError::BootstrapError(_) => "BootstrapError",
+            ChainstateError::BlockInvalidatorError(_) => "BlockInvalidatorError",
+            ChainstateError::ReindexError(_) => "ReindexError",
+            ChainstateError::TransactionDecodingError(_) => "TransactionDecodingError",
+            ChainstateError::ProofOfReservesError(_) => "ProofOfReservesError",
+        }
+    }
+}
+
+impl From<ChainstateError> for ::rpc::Error {
+    fn from(e: ChainstateError) -> Self {
+        ::rpc::categorized_error(e.to_rpc_error_code(), &e, e.variant_name())
+    }
 }
 
 pub type ChainstateSubsystem = Box<dyn ChainstateInterface>;
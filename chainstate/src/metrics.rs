@@ -0,0 +1,77 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::{Arc, OnceLock};
+
+use common::{
+    chain::block::timestamp::BlockTimestamp,
+    primitives::{time::get_time, BlockHeight},
+};
+use utils::metrics::{registry, Gauge, Histogram, DEFAULT_DURATION_BUCKETS};
+
+pub struct ChainstateMetrics {
+    pub best_block_height: Arc<Gauge>,
+    pub best_block_timestamp: Arc<Gauge>,
+    pub block_processing_time: Arc<Histogram>,
+}
+
+impl ChainstateMetrics {
+    pub fn set_tip(&self, height: BlockHeight, timestamp: BlockTimestamp) {
+        self.best_block_height.set(height.into_int() as i64);
+        self.best_block_timestamp.set(timestamp.as_int_seconds() as i64);
+    }
+}
+
+pub fn metrics() -> &'static ChainstateMetrics {
+    static METRICS: OnceLock<ChainstateMetrics> = OnceLock::new();
+    METRICS.get_or_init(|| {
+        let best_block_timestamp = registry().gauge(
+            "chainstate_best_block_timestamp_seconds",
+            "The timestamp of the best block",
+            &[],
+        );
+
+        let tip_timestamp = Arc::clone(&best_block_timestamp);
+        registry().gauge_fn(
+            "chainstate_tip_age_seconds",
+            "The number of seconds since the timestamp of the best block",
+            &[],
+            Box::new(move || {
+                let now = get_time().as_secs_since_epoch() as i64;
+                now.saturating_sub(tip_timestamp.get())
+            }),
+        );
+
+        ChainstateMetrics {
+            best_block_height: registry().gauge(
+                "chainstate_best_block_height",
+                "The height of the best block",
+                &[],
+            ),
+            best_block_timestamp,
+            block_processing_time: registry().histogram(
+                "chainstate_block_processing_seconds",
+                "The time it takes to validate and process a block",
+                &[],
+                &DEFAULT_DURATION_BUCKETS,
+            ),
+        }
+    })
+}
+
+/// Register the metrics, so that they are exported even before they are first updated
+pub fn register() {
+    let _ = metrics();
+}
@@ -25,20 +25,28 @@ use std::{
 };
 
 use self::types::{block::RpcBlock, event::RpcEvent};
-use crate::{Block, BlockSource, ChainInfo, GenBlock};
-use chainstate_types::BlockIndex;
+use crate::{
+    Block, BlockHeaderCheckInfo, BlockSizeHistogram, BlockSource, ChainInfo, ChainstateError,
+    ConsensusInfo, DecodedTransaction, GenBlock, NetworkUpgradeInfo, ProofOfReservesReport,
+};
+use chainstate_types::{BlockFeesInfo, BlockIndex};
 use common::{
     address::{dehexify::to_dehexified_json, Address},
     chain::{
+        block::{signed_block_header::SignedBlockHeader, timestamp::BlockTimestamp},
         tokens::{RPCTokenInfo, TokenId},
         ChainConfig, DelegationId, PoolId, TxOutput,
     },
     primitives::{Amount, BlockHeight, Id},
 };
 use rpc::{subscription, RpcResult};
-use serialization::hex_encoded::HexEncoded;
+use serialization::hex_encoded::{HexEncoded, StrictHexEncoded};
 pub use types::{
-    input::RpcUtxoOutpoint, output::RpcTxOutput, signed_transaction::RpcSignedTransaction,
+    block_submission::{BlockRejectionCategory, BlockSubmissionError},
+    input::RpcUtxoOutpoint,
+    output::RpcTxOutput,
+    signed_transaction::RpcSignedTransaction,
+    stake_pool_info::RpcStakePoolInfo,
 };
 
 #[rpc::describe]
@@ -85,6 +93,13 @@ trait ChainstateRpc {
         step: NonZeroUsize,
     ) -> RpcResult<Vec<(BlockHeight, Id<GenBlock>)>>;
 
+    /// Returns the ids of up to `depth` ancestors of the given block, starting from its parent.
+    ///
+    /// Fewer ids are returned if genesis is reached; genesis itself is not included.
+    /// The depth is limited to 2016 blocks.
+    #[method(name = "get_ancestors")]
+    async fn get_ancestors(&self, block_id: Id<Block>, depth: u32) -> RpcResult<Vec<Id<Block>>>;
+
     /// Returns the TxOutput for a specified UtxoOutPoint.
     /// Returns `None` (null) if the UtxoOutPoint is not found or is already spent.
     #[method(name = "get_utxo")]
@@ -94,8 +109,25 @@ trait ChainstateRpc {
     ///
     /// Note that the submission does not circumvent any validation process.
     /// This function is used by the wallet to submit valid blocks after successful staking.
+    ///
+    /// If the block is rejected, the error data contains the rejection category
+    /// (e.g. "StaleParent", "Duplicate", "InvalidTransactions"), the offending transaction id
+    /// (if known) and a hint on how to proceed.
     #[method(name = "submit_block")]
-    async fn submit_block(&self, block_hex: HexEncoded<Block>) -> RpcResult<()>;
+    async fn submit_block(&self, block_hex: StrictHexEncoded<Block>) -> RpcResult<()>;
+
+    /// Check a hex-encoded serialized block header without the block body.
+    ///
+    /// The header goes through the same checks that a submitted block goes through before its
+    /// body is looked at: the parent block, the timestamp rules and the consensus data.
+    /// If the header is rejected, the error data is the same as `submit_block` would return for
+    /// the full block. Otherwise, the height the block would be attached at is returned, along
+    /// with whether it would extend the current best chain.
+    #[method(name = "check_block_header")]
+    async fn check_block_header(
+        &self,
+        header_hex: StrictHexEncoded<SignedBlockHeader>,
+    ) -> RpcResult<BlockHeaderCheckInfo>;
 
     /// Invalidate the specified block and its descendants.
     ///
@@ -171,6 +203,77 @@ trait ChainstateRpc {
     #[method(name = "info")]
     async fn info(&self) -> RpcResult<ChainInfo>;
 
+    /// Return the consensus and chainstate upgrades defined for the network, ordered by
+    /// their activation heights.
+    ///
+    /// For each upgrade, its name, activation height, whether it is active at the current tip
+    /// and the number of blocks remaining until its activation are returned.
+    #[method(name = "get_network_upgrades")]
+    async fn get_network_upgrades(&self) -> RpcResult<Vec<NetworkUpgradeInfo>>;
+
+    /// Return the histogram of the sizes of the mainchain blocks with heights in the range
+    /// from_height..=to_height.
+    ///
+    /// Each bucket is a pair of its lower bound in KiB and the number of blocks in it;
+    /// the buckets are 10 KiB wide and empty buckets are omitted.
+    /// The number of heights in the range is limited (1000 by default).
+    #[method(name = "block_size_histogram")]
+    async fn block_size_histogram(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> RpcResult<BlockSizeHistogram>;
+
+    /// Return the estimated timestamp of the next block.
+    ///
+    /// The estimate is based on the current PoS difficulty and the balances of all the stake pools;
+    /// if it can't be made, the target block spacing is added to the current time.
+    #[method(name = "estimate_next_block_timestamp")]
+    async fn estimate_next_block_timestamp(&self) -> RpcResult<BlockTimestamp>;
+
+    /// Return the consensus-related values of the mainchain blocks with heights in the range
+    /// from_height..=to_height, along with the target required for the next block.
+    ///
+    /// For each block, its compact target, difficulty (for PoS blocks) and the average block time
+    /// the retarget algorithm used when calculating the target are returned.
+    /// Genesis and the heights above the best block are skipped.
+    /// The number of heights in the range is limited to 1000.
+    #[method(name = "consensus_info")]
+    async fn consensus_info(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> RpcResult<ConsensusInfo>;
+
+    /// Return the total coin fee of a mainchain block along with the fee of each of its
+    /// transactions, or None if the block is not on the mainchain.
+    /// The fees of the blocks connected by older versions of the node are reported as not
+    /// recorded.
+    #[method(name = "block_fees")]
+    async fn block_fees(&self, id: Id<Block>) -> RpcResult<Option<BlockFeesInfo>>;
+
+    /// Return all the stake pools with their pledge, total stake, cost per block, margin ratio
+    /// and the number of blocks each of them produced among the latest 1000 mainchain blocks.
+    #[method(name = "all_pools")]
+    async fn all_pools(&self) -> RpcResult<Vec<RpcStakePoolInfo>>;
+
+    /// Decode a hex-encoded signed transaction.
+    ///
+    /// Returns the transaction id, flags, size and the inputs and outputs with destinations
+    /// encoded as addresses. The outputs spent by the inputs are included if they are
+    /// in the current utxo set.
+    #[method(name = "decode_transaction")]
+    async fn decode_transaction(&self, tx_hex: String) -> RpcResult<DecodedTransaction>;
+
+    /// Verify a hex-encoded proof of reserves produced by a wallet.
+    ///
+    /// Every utxo of the proof is checked against its destination as created in the mainchain.
+    /// Returns the status of each utxo, flagging the ones spent since the block of the proof,
+    /// and the total amounts with valid signatures.
+    #[method(name = "verify_proof_of_reserves")]
+    async fn verify_proof_of_reserves(&self, proof_hex: String)
+        -> RpcResult<ProofOfReservesReport>;
+
     /// Subscribe to chainstate events, such as new tip.
     ///
     /// After a successful subscription, the node will message the subscriber with a message on every event.
@@ -252,6 +355,10 @@ impl ChainstateRpcServer for super::ChainstateHandle {
         )
     }
 
+    async fn get_ancestors(&self, block_id: Id<Block>, depth: u32) -> RpcResult<Vec<Id<Block>>> {
+        rpc::handle_result(self.call(move |this| this.get_ancestors(block_id, depth)).await)
+    }
+
     async fn get_utxo(&self, outpoint: RpcUtxoOutpoint) -> RpcResult<Option<TxOutput>> {
         let outpoint = outpoint.into_outpoint();
         rpc::handle_result(
@@ -262,13 +369,39 @@ impl ChainstateRpcServer for super::ChainstateHandle {
         )
     }
 
-    async fn submit_block(&self, block: HexEncoded<Block>) -> RpcResult<()> {
+    async fn submit_block(&self, block: StrictHexEncoded<Block>) -> RpcResult<()> {
+        let block = block.take();
         let res = self
-            .call_mut(move |this| this.process_block(block.take(), BlockSource::Local))
+            .call_mut({
+                let block = block.clone();
+                move |this| this.process_block(block, BlockSource::Local)
+            })
             .await;
-        // remove the block index from the return value
-        let res = res.map(|v| v.map(|_bi| ()));
-        rpc::handle_result(res)
+
+        match res {
+            Ok(Err(err)) => {
+                let err = BlockSubmissionError::new(&block, &err);
+                Err(rpc::error_with_data(&err, &err))
+            }
+            // remove the block index from the return value
+            res => rpc::handle_result(res.map(|v| v.map(|_bi| ()))),
+        }
+    }
+
+    async fn check_block_header(
+        &self,
+        header: StrictHexEncoded<SignedBlockHeader>,
+    ) -> RpcResult<BlockHeaderCheckInfo> {
+        let header = header.take();
+        let res = self.call(move |this| this.check_block_header(&header)).await;
+
+        match res {
+            Ok(Err(err)) => {
+                let err = BlockSubmissionError::for_header(&err);
+                Err(rpc::error_with_data(&err, &err))
+            }
+            res => rpc::handle_result(res),
+        }
     }
 
     async fn invalidate_block(&self, id: Id<Block>) -> RpcResult<()> {
@@ -405,12 +538,83 @@ impl ChainstateRpcServer for super::ChainstateHandle {
         rpc::handle_result(self.call(move |this| this.info()).await)
     }
 
+    async fn get_network_upgrades(&self) -> RpcResult<Vec<NetworkUpgradeInfo>> {
+        rpc::handle_result(self.call(move |this| this.get_network_upgrades()).await)
+    }
+
+    async fn block_size_histogram(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> RpcResult<BlockSizeHistogram> {
+        rpc::handle_result(
+            self.call(move |this| this.get_block_size_histogram(from_height, to_height))
+                .await,
+        )
+    }
+
+    async fn estimate_next_block_timestamp(&self) -> RpcResult<BlockTimestamp> {
+        rpc::handle_result(self.call(move |this| this.estimate_next_block_timestamp()).await)
+    }
+
+    async fn consensus_info(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> RpcResult<ConsensusInfo> {
+        rpc::handle_result(
+            self.call(move |this| this.get_consensus_info(from_height, to_height)).await,
+        )
+    }
+
+    async fn block_fees(&self, id: Id<Block>) -> RpcResult<Option<BlockFeesInfo>> {
+        rpc::handle_result(self.call(move |this| this.get_block_fees(&id)).await)
+    }
+
+    async fn all_pools(&self) -> RpcResult<Vec<RpcStakePoolInfo>> {
+        rpc::handle_result(
+            self.call(move |this| {
+                let chain_config = this.get_chain_config();
+                dynamize_err(this.get_all_pools()).and_then(|pools| {
+                    pools
+                        .iter()
+                        .map(|info| dynamize_err(RpcStakePoolInfo::new(chain_config, info)))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+            })
+            .await,
+        )
+    }
+
+    async fn decode_transaction(&self, tx_hex: String) -> RpcResult<DecodedTransaction> {
+        handle_chainstate_result(self.call(move |this| this.decode_transaction(tx_hex)).await)
+    }
+
+    async fn verify_proof_of_reserves(
+        &self,
+        proof_hex: String,
+    ) -> RpcResult<ProofOfReservesReport> {
+        handle_chainstate_result(
+            self.call(move |this| this.verify_proof_of_reserves(proof_hex)).await,
+        )
+    }
+
     async fn subscribe_events(&self, pending: subscription::Pending) -> subscription::Reply {
         let event_rx = self.call_mut(move |this| this.subscribe_to_rpc_events()).await?;
         rpc::subscription::connect_broadcast_map(event_rx, pending, RpcEvent::from_event).await
     }
 }
 
+/// Convert the result of a chainstate call, keeping the code and the type of the chainstate error
+fn handle_chainstate_result<T>(
+    res: Result<Result<T, ChainstateError>, subsystem::error::CallError>,
+) -> RpcResult<T> {
+    match res {
+        Ok(res) => res.map_err(Into::into),
+        Err(e) => Err(rpc::service_unavailable_error(e)),
+    }
+}
+
 fn dynamize_err<T, E: std::error::Error + Send + Sync>(
     o: Result<T, E>,
 ) -> Result<T, Box<dyn std::error::Error + Send + Sync>>
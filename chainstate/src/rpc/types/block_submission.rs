@@ -0,0 +1,329 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Classification of the errors returned when a block is submitted via RPC, so that mining
+//! and staking software can react to a rejection without parsing the error message.
+
+use common::{
+    chain::{Block, OutPointSourceId, Transaction, TxInput},
+    primitives::{Id, Idable},
+};
+use tx_verifier::CheckTransactionError;
+
+use crate::{
+    BlockError, BlockProcessingErrorClass, BlockProcessingErrorClassification, ChainstateError,
+    CheckBlockError, CheckBlockTransactionsError, ConnectTransactionError, OrphanCheckError,
+};
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, derive_more::Display,
+)]
+pub enum BlockRejectionCategory {
+    /// The parent block is unknown, invalid or too deep in the chain
+    #[display(fmt = "stale parent")]
+    StaleParent,
+    /// The block has already been submitted
+    #[display(fmt = "duplicate")]
+    Duplicate,
+    /// The consensus data (PoW/PoS data, block reward, signature) is invalid
+    #[display(fmt = "invalid consensus data")]
+    InvalidConsensus,
+    /// One of the transactions is invalid
+    #[display(fmt = "invalid transactions")]
+    InvalidTransactions,
+    /// The block timestamp is too far in the future or too far in the past
+    #[display(fmt = "timestamp")]
+    Timestamp,
+    /// The block is too big
+    #[display(fmt = "size")]
+    Size,
+    /// The block is invalid for some other reason
+    #[display(fmt = "invalid block")]
+    InvalidBlock,
+    /// The node failed to process the block, e.g. because of a storage error
+    #[display(fmt = "internal error")]
+    Internal,
+}
+
+impl BlockRejectionCategory {
+    pub fn hint(&self) -> &'static str {
+        match self {
+            BlockRejectionCategory::StaleParent => {
+                "Fetch the current best block and build the new block on top of it"
+            }
+            BlockRejectionCategory::Duplicate => {
+                "The block is already known to the node, there is no need to submit it again"
+            }
+            BlockRejectionCategory::InvalidConsensus => {
+                "Check the consensus data and the block reward against the parent block"
+            }
+            BlockRejectionCategory::InvalidTransactions => {
+                "Remove the offending transaction from the block and build it again"
+            }
+            BlockRejectionCategory::Timestamp => {
+                "Check that the system clock is synchronized and build the block again"
+            }
+            BlockRejectionCategory::Size => "Include fewer transactions into the block",
+            BlockRejectionCategory::InvalidBlock => {
+                "The block is malformed, resubmitting it won't help"
+            }
+            BlockRejectionCategory::Internal => {
+                "The node failed to process the block, check the node's logs"
+            }
+        }
+    }
+}
+
+/// The reason why a submitted block was rejected.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[error("Block rejected ({category}): {message}")]
+pub struct BlockSubmissionError {
+    pub category: BlockRejectionCategory,
+    /// The transaction that made the block invalid, if it could be determined
+    pub tx_id: Option<Id<Transaction>>,
+    pub hint: String,
+    pub message: String,
+}
+
+impl BlockSubmissionError {
+    pub fn new(block: &Block, error: &ChainstateError) -> Self {
+        let category = chainstate_error_category(error);
+        let tx_id = match category {
+            BlockRejectionCategory::InvalidTransactions => offending_tx_id(block, error),
+            BlockRejectionCategory::StaleParent
+            | BlockRejectionCategory::Duplicate
+            | BlockRejectionCategory::InvalidConsensus
+            | BlockRejectionCategory::Timestamp
+            | BlockRejectionCategory::Size
+            | BlockRejectionCategory::InvalidBlock
+            | BlockRejectionCategory::Internal => None,
+        };
+
+        Self {
+            category,
+            tx_id,
+            hint: category.hint().to_owned(),
+            message: error.to_string(),
+        }
+    }
+
+    /// Same as `new`, but for a rejected block header; the transaction id is never known here.
+    pub fn for_header(error: &ChainstateError) -> Self {
+        let category = chainstate_error_category(error);
+
+        Self {
+            category,
+            tx_id: None,
+            hint: category.hint().to_owned(),
+            message: error.to_string(),
+        }
+    }
+}
+
+// Note: the matches below intentionally have no wildcards, so that adding a new error variant
+// requires deciding which category it belongs to. The deeper errors are not matched directly;
+// the existing block processing classification is used to tell the internal errors apart.
+
+fn chainstate_error_category(error: &ChainstateError) -> BlockRejectionCategory {
+    match error {
+        ChainstateError::ProcessBlockError(err) => block_error_category(err),
+
+        ChainstateError::FailedToInitializeChainstate(_)
+        | ChainstateError::FailedToReadProperty(_)
+        | ChainstateError::BootstrapError(_)
+        | ChainstateError::BlockInvalidatorError(_)
+        | ChainstateError::ReindexError(_)
+        | ChainstateError::TransactionDecodingError(_)
+        | ChainstateError::ProofOfReservesError(_) => BlockRejectionCategory::Internal,
+    }
+}
+
+/// Return `category` unless the error is classified as a general (i.e. not block related) one.
+fn category_unless_general(
+    err: &impl BlockProcessingErrorClassification,
+    category: BlockRejectionCategory,
+) -> BlockRejectionCategory {
+    match err.classify() {
+        BlockProcessingErrorClass::General => BlockRejectionCategory::Internal,
+        BlockProcessingErrorClass::BadBlock | BlockProcessingErrorClass::TemporarilyBadBlock => {
+            category
+        }
+    }
+}
+
+fn block_error_category(error: &BlockError) -> BlockRejectionCategory {
+    use BlockRejectionCategory as C;
+
+    match error {
+        BlockError::OrphanCheckFailed(err) => orphan_check_error_category(err),
+        BlockError::CheckBlockFailed(err) => check_block_error_category(err),
+
+        BlockError::PrevBlockNotFoundForNewBlock(_) => C::StaleParent,
+
+        BlockError::BlockAlreadyExists(_)
+        | BlockError::BlockIndexAlreadyExists(_)
+        | BlockError::BlockAlreadyProcessed(_)
+        | BlockError::InvalidBlockAlreadyProcessed(_) => C::Duplicate,
+
+        BlockError::BlockProofCalculationError(_) => C::InvalidConsensus,
+        BlockError::EpochSealError(err) => category_unless_general(err, C::InvalidConsensus),
+
+        BlockError::StateUpdateFailed(err) => category_unless_general(err, C::InvalidTransactions),
+        BlockError::TransactionVerifierError(err) => {
+            category_unless_general(err, C::InvalidTransactions)
+        }
+        BlockError::PoSAccountingError(err) => category_unless_general(err, C::InvalidTransactions),
+        BlockError::TokensAccountingError(err) => {
+            category_unless_general(err, C::InvalidTransactions)
+        }
+        BlockError::OrdersAccountingError(err) => {
+            category_unless_general(err, C::InvalidTransactions)
+        }
+
+        BlockError::StorageError(err) => category_unless_general(err, C::InvalidBlock),
+        BlockError::PropertyQueryError(err) => category_unless_general(err, C::InvalidBlock),
+        BlockError::InMemoryReorgFailed(err) => category_unless_general(err, C::InvalidBlock),
+        BlockError::BestChainCandidatesAccessorError(err) => {
+            category_unless_general(err, C::InvalidBlock)
+        }
+
+        BlockError::DbCommitError(_, _, _)
+        | BlockError::BlockDataMissingForValidBlockIndex(_)
+        | BlockError::BestBlockIdQueryError(_)
+        | BlockError::BestBlockIndexQueryError(_)
+        | BlockError::BlockIndexQueryError(_, _)
+        | BlockError::IsBlockInMainChainQueryError(_, _)
+        | BlockError::MinHeightForReorgQueryError(_)
+        | BlockError::InvariantErrorFailedToFindNewChainPath(_, _, _)
+        | BlockError::InvariantErrorInvalidTip(_)
+        | BlockError::InvariantErrorAttemptToConnectInvalidBlock(_)
+        | BlockError::InvariantErrorDisconnectedHeaders
+        | BlockError::InvariantErrorTotalPoolBalanceLessThanStakers { .. }
+        | BlockError::InvariantErrorPoolBalancePresentDataMissing(_, _)
+        | BlockError::InvariantErrorPoolDataPresentBalanceMissing(_, _)
+        | BlockError::InvariantErrorTotalPoolBalancesOverflow(_)
+        | BlockError::UnexpectedHeightRange(_, _)
+        | BlockError::TargetCalculationFailed(_, _) => C::Internal,
+    }
+}
+
+fn orphan_check_error_category(error: &OrphanCheckError) -> BlockRejectionCategory {
+    match error {
+        OrphanCheckError::LocalOrphan => BlockRejectionCategory::StaleParent,
+
+        OrphanCheckError::StorageError(_) | OrphanCheckError::PropertyQueryError(_) => {
+            BlockRejectionCategory::Internal
+        }
+    }
+}
+
+fn check_block_error_category(error: &CheckBlockError) -> BlockRejectionCategory {
+    use BlockRejectionCategory as C;
+
+    match error {
+        CheckBlockError::ParentBlockMissing { .. }
+        | CheckBlockError::InvalidParent { .. }
+        | CheckBlockError::CheckpointMismatch(_, _)
+        | CheckBlockError::ParentCheckpointMismatch(_, _, _)
+        | CheckBlockError::AttemptedToAddBlockBeforeReorgLimit(_, _, _) => C::StaleParent,
+
+        CheckBlockError::BlockTimeOrderInvalid(_, _) | CheckBlockError::BlockFromTheFuture(_) => {
+            C::Timestamp
+        }
+
+        CheckBlockError::BlockSizeError(_) => C::Size,
+
+        CheckBlockError::InvalidBlockRewardOutputType(_) => C::InvalidConsensus,
+        CheckBlockError::ConsensusVerificationFailed(err) => {
+            category_unless_general(err, C::InvalidConsensus)
+        }
+        CheckBlockError::BlockRewardMaturityError(err) => {
+            category_unless_general(err, C::InvalidConsensus)
+        }
+        CheckBlockError::EpochSealError(err) => category_unless_general(err, C::InvalidConsensus),
+
+        CheckBlockError::CheckTransactionFailed(err) => {
+            category_unless_general(err, C::InvalidTransactions)
+        }
+        CheckBlockError::TransactionVerifierError(err) => {
+            category_unless_general(err, C::InvalidTransactions)
+        }
+
+        CheckBlockError::MerkleRootMismatch => C::InvalidBlock,
+        CheckBlockError::MerkleRootCalculationFailed(_, err) => {
+            category_unless_general(err, C::InvalidBlock)
+        }
+        CheckBlockError::StorageError(err) => category_unless_general(err, C::InvalidBlock),
+        CheckBlockError::PropertyQueryError(err) => category_unless_general(err, C::InvalidBlock),
+        CheckBlockError::GetAncestorError(err) => category_unless_general(err, C::InvalidBlock),
+        CheckBlockError::InMemoryReorgFailed(err) => category_unless_general(err, C::InvalidBlock),
+    }
+}
+
+/// Find the transaction that made the block invalid.
+///
+/// Most transaction errors don't mention the transaction id; if the error refers to a missing
+/// utxo, the transaction spending it is looked up in the block instead.
+fn offending_tx_id(block: &Block, error: &ChainstateError) -> Option<Id<Transaction>> {
+    let connect_tx_error = match error {
+        ChainstateError::ProcessBlockError(BlockError::StateUpdateFailed(err)) => err,
+        ChainstateError::ProcessBlockError(BlockError::CheckBlockFailed(
+            CheckBlockError::CheckTransactionFailed(
+                CheckBlockTransactionsError::CheckTransactionError(err),
+            ),
+        )) => return check_tx_error_tx_id(err),
+        _ => return None,
+    };
+
+    match connect_tx_error {
+        ConnectTransactionError::BurnAmountSumError(tx_id)
+        | ConnectTransactionError::NotEnoughPledgeToCreateStakePool(tx_id, _, _) => Some(*tx_id),
+        ConnectTransactionError::IOPolicyError(_, source_id)
+        | ConnectTransactionError::ConstrainedValueAccumulatorError(_, source_id) => {
+            match source_id {
+                OutPointSourceId::Transaction(tx_id) => Some(*tx_id),
+                OutPointSourceId::BlockReward(_) => None,
+            }
+        }
+        ConnectTransactionError::CheckTransactionError(err) => check_tx_error_tx_id(err),
+        ConnectTransactionError::MissingOutputOrSpent(outpoint) => block
+            .transactions()
+            .iter()
+            .find(|tx| {
+                tx.inputs().iter().any(|input| match input {
+                    TxInput::Utxo(tx_outpoint) => tx_outpoint == outpoint,
+                    TxInput::Account(_) | TxInput::AccountCommand(_, _) => false,
+                })
+            })
+            .map(|tx| tx.transaction().get_id()),
+        _ => None,
+    }
+}
+
+fn check_tx_error_tx_id(error: &CheckTransactionError) -> Option<Id<Transaction>> {
+    match error {
+        CheckTransactionError::DuplicateInputInTransaction(tx_id)
+        | CheckTransactionError::InvalidWitnessCount(tx_id)
+        | CheckTransactionError::EmptyInputsInTransaction(tx_id)
+        | CheckTransactionError::NoSignatureDataSizeTooLarge(_, _, tx_id)
+        | CheckTransactionError::NoSignatureDataNotAllowed(tx_id)
+        | CheckTransactionError::DataDepositMaxSizeExceeded(_, _, tx_id)
+        | CheckTransactionError::TxSizeTooLarge(tx_id, _, _)
+        | CheckTransactionError::DeprecatedTokenOperationVersion(_, tx_id)
+        | CheckTransactionError::OrdersAreNotActivated(tx_id)
+        | CheckTransactionError::OrdersCurrenciesMustBeDifferent(tx_id) => Some(*tx_id),
+        _ => None,
+    }
+}
@@ -16,9 +16,11 @@
 pub mod account;
 pub mod block;
 pub mod block_reward;
+pub mod block_submission;
 pub mod consensus_data;
 pub mod event;
 pub mod input;
 pub mod output;
 pub mod signed_transaction;
+pub mod stake_pool_info;
 pub mod token;
@@ -27,7 +27,7 @@ use rpc::types::RpcHexString;
 
 use super::token::{RpcNftIssuance, RpcTokenIssuance};
 
-#[derive(Debug, Clone, serde::Serialize, rpc_description::HasValueHint)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
 #[serde(tag = "type", content = "content")]
 pub enum RpcOutputValue {
     Coin {
@@ -55,7 +55,7 @@ impl RpcOutputValue {
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize, rpc_description::HasValueHint)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
 pub struct RpcStakePoolData {
     pledge: RpcAmountOut,
     staker: RpcAddress<Destination>,
@@ -82,7 +82,7 @@ impl RpcStakePoolData {
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize, rpc_description::HasValueHint)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
 pub struct RpcHashedTimelockContract {
     secret_hash: RpcHexString,
     spend_key: RpcAddress<Destination>,
@@ -105,7 +105,7 @@ impl RpcHashedTimelockContract {
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize, rpc_description::HasValueHint)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
 #[serde(tag = "type", content = "content")]
 pub enum RpcTxOutput {
     Transfer {
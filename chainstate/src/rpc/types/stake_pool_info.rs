@@ -0,0 +1,61 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    address::{AddressError, RpcAddress},
+    chain::{ChainConfig, PoolId},
+    primitives::{amount::RpcAmountOut, per_thousand::PerThousand},
+};
+
+use crate::StakePoolInfo;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
+pub struct RpcStakePoolInfo {
+    pub pool_id: RpcAddress<PoolId>,
+    pub pledge: RpcAmountOut,
+    pub total_stake: RpcAmountOut,
+    pub cost_per_block: RpcAmountOut,
+    pub margin_ratio_per_thousand: PerThousand,
+    pub blocks_produced: u64,
+}
+
+impl RpcStakePoolInfo {
+    pub fn new(chain_config: &ChainConfig, info: &StakePoolInfo) -> Result<Self, AddressError> {
+        let decimals = chain_config.coin_decimals();
+        let result = Self {
+            pool_id: RpcAddress::new(chain_config, info.pool_id)?,
+            pledge: RpcAmountOut::from_amount(info.pledge, decimals),
+            total_stake: RpcAmountOut::from_amount(info.total_stake, decimals),
+            cost_per_block: RpcAmountOut::from_amount(info.cost_per_block, decimals),
+            margin_ratio_per_thousand: info.margin_ratio_per_thousand,
+            blocks_produced: info.blocks_produced,
+        };
+        Ok(result)
+    }
+
+    pub fn into_stake_pool_info(
+        self,
+        chain_config: &ChainConfig,
+    ) -> Result<StakePoolInfo, AddressError> {
+        Ok(StakePoolInfo {
+            pool_id: self.pool_id.decode_object(chain_config)?,
+            pledge: self.pledge.amount(),
+            total_stake: self.total_stake.amount(),
+            cost_per_block: self.cost_per_block.amount(),
+            margin_ratio_per_thousand: self.margin_ratio_per_thousand,
+            blocks_produced: self.blocks_produced,
+        })
+    }
+}
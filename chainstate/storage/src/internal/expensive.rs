@@ -33,7 +33,7 @@ impl<B: storage::Backend> StoreTxRo<'_, B> {
     pub fn read_utxo_set(&self) -> crate::Result<BTreeMap<UtxoOutPoint, Utxo>> {
         self.0
             .get::<db::DBUtxo, _>()
-            .prefix_iter_decoded(&())
+            .iter_decoded()
             .map(Iterator::collect)
             .map_err(crate::Error::from)
     }
@@ -44,31 +44,31 @@ impl<B: storage::Backend> StoreTxRo<'_, B> {
         let pool_data = self
             .0
             .get::<db::DBAccountingPoolDataTip, _>()
-            .prefix_iter_decoded(&())?
+            .iter_decoded()?
             .collect::<BTreeMap<_, _>>();
 
         let pool_balances = self
             .0
             .get::<db::DBAccountingPoolBalancesTip, _>()
-            .prefix_iter_decoded(&())?
+            .iter_decoded()?
             .collect::<BTreeMap<_, _>>();
 
         let delegation_data = self
             .0
             .get::<db::DBAccountingDelegationDataTip, _>()
-            .prefix_iter_decoded(&())?
+            .iter_decoded()?
             .collect::<BTreeMap<_, _>>();
 
         let delegation_balances = self
             .0
             .get::<db::DBAccountingDelegationBalancesTip, _>()
-            .prefix_iter_decoded(&())?
+            .iter_decoded()?
             .collect::<BTreeMap<_, _>>();
 
         let pool_delegation_shares = self
             .0
             .get::<db::DBAccountingPoolDelegationSharesTip, _>()
-            .prefix_iter_decoded(&())?
+            .iter_decoded()?
             .collect::<BTreeMap<_, _>>();
 
         Ok(pos_accounting::PoSAccountingData {
@@ -88,31 +88,31 @@ impl<B: storage::Backend> StoreTxRo<'_, B> {
         let pool_data = self
             .0
             .get::<db::DBAccountingPoolDataSealed, _>()
-            .prefix_iter_decoded(&())?
+            .iter_decoded()?
             .collect::<BTreeMap<_, _>>();
 
         let pool_balances = self
             .0
             .get::<db::DBAccountingPoolBalancesSealed, _>()
-            .prefix_iter_decoded(&())?
+            .iter_decoded()?
             .collect::<BTreeMap<_, _>>();
 
         let delegation_data = self
             .0
             .get::<db::DBAccountingDelegationDataSealed, _>()
-            .prefix_iter_decoded(&())?
+            .iter_decoded()?
             .collect::<BTreeMap<_, _>>();
 
         let delegation_balances = self
             .0
             .get::<db::DBAccountingDelegationBalancesSealed, _>()
-            .prefix_iter_decoded(&())?
+            .iter_decoded()?
             .collect::<BTreeMap<_, _>>();
 
         let pool_delegation_shares = self
             .0
             .get::<db::DBAccountingPoolDelegationSharesSealed, _>()
-            .prefix_iter_decoded(&())?
+            .iter_decoded()?
             .collect::<BTreeMap<_, _>>();
 
         Ok(pos_accounting::PoSAccountingData {
@@ -128,16 +128,13 @@ impl<B: storage::Backend> StoreTxRo<'_, B> {
     pub fn read_tokens_accounting_data(
         &self,
     ) -> crate::Result<tokens_accounting::TokensAccountingData> {
-        let token_data = self
-            .0
-            .get::<db::DBTokensData, _>()
-            .prefix_iter_decoded(&())?
-            .collect::<BTreeMap<_, _>>();
+        let token_data =
+            self.0.get::<db::DBTokensData, _>().iter_decoded()?.collect::<BTreeMap<_, _>>();
 
         let circulating_supply = self
             .0
             .get::<db::DBTokensCirculatingSupply, _>()
-            .prefix_iter_decoded(&())?
+            .iter_decoded()?
             .collect::<BTreeMap<_, _>>();
 
         Ok(tokens_accounting::TokensAccountingData {
@@ -150,22 +147,19 @@ impl<B: storage::Backend> StoreTxRo<'_, B> {
     pub fn read_orders_accounting_data(
         &self,
     ) -> crate::Result<orders_accounting::OrdersAccountingData> {
-        let order_data = self
-            .0
-            .get::<db::DBOrdersData, _>()
-            .prefix_iter_decoded(&())?
-            .collect::<BTreeMap<_, _>>();
+        let order_data =
+            self.0.get::<db::DBOrdersData, _>().iter_decoded()?.collect::<BTreeMap<_, _>>();
 
         let ask_balances = self
             .0
             .get::<db::DBOrdersAskBalances, _>()
-            .prefix_iter_decoded(&())?
+            .iter_decoded()?
             .collect::<BTreeMap<_, _>>();
 
         let give_balances = self
             .0
             .get::<db::DBOrdersGiveBalances, _>()
-            .prefix_iter_decoded(&())?
+            .iter_decoded()?
             .collect::<BTreeMap<_, _>>();
 
         Ok(orders_accounting::OrdersAccountingData {
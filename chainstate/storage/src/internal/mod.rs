@@ -111,7 +111,17 @@ impl<'tx, B: storage::Backend + 'tx> Transactional<'tx> for Store<B> {
     }
 }
 
-impl<B: storage::Backend + 'static> BlockchainStorage for Store<B> {}
+impl<B: storage::Backend + 'static> BlockchainStorage for Store<B> {
+    #[log_error]
+    fn set_write_batching(&self, enabled: bool) -> crate::Result<()> {
+        self.0.set_write_batching(enabled).map_err(crate::Error::from)
+    }
+
+    #[log_error]
+    fn flush(&self) -> crate::Result<()> {
+        self.0.flush().map_err(crate::Error::from)
+    }
+}
 
 impl<B: storage::Backend> PoSAccountingStorageRead<TipStorageTag> for Store<B> {
     type Error = crate::Error;
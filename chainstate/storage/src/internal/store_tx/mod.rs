@@ -54,6 +54,7 @@ mod well_known {
     declare_entry!(MagicBytes: chain::config::MagicBytes);
     declare_entry!(ChainType: String);
     declare_entry!(MinHeightForReorg: BlockHeight);
+    declare_entry!(CleanBlockHeight: BlockHeight);
 }
 
 /// Read-only chainstate storage transaction
@@ -206,6 +207,11 @@ impl<'st, B: storage::Backend> StoreTxRw<'st, B> {
     {
         self.track_error(|tx| Ok(tx.get_mut::<DbMap, I>().del(key)?))
     }
+
+    // Delete the value of a well-known entry
+    fn del_value<E: well_known::Entry>(&mut self) -> crate::Result<()> {
+        self.del::<db::DBValue, _, _>(E::KEY)
+    }
 }
 
 impl<'st, B: storage::Backend> crate::TransactionRo for StoreTxRo<'st, B> {
@@ -16,7 +16,9 @@
 use std::collections::{BTreeMap, BTreeSet};
 
 use super::db;
-use chainstate_types::{BlockIndex, EpochData, EpochStorageRead, SealedStorageTag, TipStorageTag};
+use chainstate_types::{
+    BlockFees, BlockIndex, EpochData, EpochStorageRead, SealedStorageTag, TipStorageTag,
+};
 use common::{
     chain::{
         block::{signed_block_header::SignedBlockHeader, BlockReward},
@@ -25,7 +27,7 @@ use common::{
         AccountNonce, AccountType, Block, DelegationId, GenBlock, OrderData, OrderId, PoolId,
         Transaction, UtxoOutPoint,
     },
-    primitives::{Amount, BlockHeight, Id, H256},
+    primitives::{Amount, BlockHeight, Id},
 };
 use orders_accounting::{OrdersAccountingStorageRead, OrdersAccountingUndo};
 use pos_accounting::{
@@ -66,17 +68,10 @@ mod private {
         }
     }
 
-    pub fn filter_delegation_shares_for_poolid(
-        pool_id: PoolId,
+    pub fn collect_delegation_shares(
         iter: impl Iterator<Item = ((PoolId, DelegationId), Amount)>,
     ) -> crate::Result<Option<BTreeMap<DelegationId, Amount>>> {
-        let range_start = (pool_id, DelegationId::new(H256::zero()));
-        let range_end = (pool_id, DelegationId::new(H256::repeat_byte(0xFF)));
-        let range = range_start..=range_end;
-
-        let shares = iter.filter(|(k, _)| range.contains(k));
-
-        let result = shares.map(|((_pool_id, del_id), v)| (del_id, v)).collect::<BTreeMap<_, _>>();
+        let result = iter.map(|((_pool_id, del_id), v)| (del_id, v)).collect::<BTreeMap<_, _>>();
         if result.is_empty() {
             Ok(None)
         } else {
@@ -141,9 +136,26 @@ impl<'st, B: storage::Backend> BlockchainStorageRead for super::StoreTxRo<'st, B
         self.read_value::<well_known::MinHeightForReorg>()
     }
 
+    #[log_error]
+    fn get_clean_block_height(&self) -> crate::Result<Option<BlockHeight>> {
+        self.read_value::<well_known::CleanBlockHeight>()
+    }
+
     #[log_error]
     fn get_block_id_by_height(&self, height: &BlockHeight) -> crate::Result<Option<Id<GenBlock>>> {
-        self.read::<db::DBBlockByHeight, _, _>(height)
+        self.read::<db::DBBlockByHeight, _, _>(&db::block_height_key(*height))
+    }
+
+    #[log_error]
+    fn get_block_ids_by_height_range(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> crate::Result<Vec<(BlockHeight, Id<GenBlock>)>> {
+        let map = self.0.get::<db::DBBlockByHeight, _>();
+        let range = db::block_height_key(from_height)..=db::block_height_key(to_height);
+        let items = map.range_decoded(range)?;
+        Ok(items.map(|(height, id)| (BlockHeight::new(height.inner()), id)).collect())
     }
 
     #[log_error]
@@ -151,6 +163,11 @@ impl<'st, B: storage::Backend> BlockchainStorageRead for super::StoreTxRo<'st, B
         self.read::<db::DBUtxosBlockUndo, _, _>(id)
     }
 
+    #[log_error]
+    fn get_block_fees(&self, id: Id<Block>) -> crate::Result<Option<BlockFees>> {
+        self.read::<db::DBBlockFees, _, _>(id)
+    }
+
     #[log_error]
     fn get_token_aux_data(&self, token_id: &TokenId) -> crate::Result<Option<TokenAuxiliaryData>> {
         self.read::<db::DBTokensAuxData, _, _>(&token_id)
@@ -183,7 +200,7 @@ impl<'st, B: storage::Backend> BlockchainStorageRead for super::StoreTxRo<'st, B
         start_from: BlockHeight,
     ) -> crate::Result<BTreeMap<BlockHeight, Vec<Id<Block>>>> {
         let map = self.0.get::<db::DBBlockIndex, _>();
-        let items = map.prefix_iter_decoded(&())?;
+        let items = map.iter_decoded()?;
 
         let mut result = BTreeMap::<BlockHeight, Vec<Id<Block>>>::new();
         for (_, bi) in items {
@@ -227,21 +244,28 @@ impl<'st, B: storage::Backend> BlockchainStorageRead for super::StoreTxRo<'st, B
     #[log_error]
     fn get_block_map_keys(&self) -> crate::Result<BTreeSet<Id<Block>>> {
         let map = self.0.get::<db::DBBlock, _>();
-        let items = map.prefix_iter_keys(&())?;
+        let items = map.iter_keys()?;
         Ok(items.collect::<BTreeSet<_>>())
     }
 
     #[log_error]
     fn get_block_index_map(&self) -> crate::Result<BTreeMap<Id<Block>, BlockIndex>> {
         let map = self.0.get::<db::DBBlockIndex, _>();
-        let items = map.prefix_iter_decoded(&())?;
+        let items = map.iter_decoded()?;
         Ok(items.collect::<BTreeMap<_, _>>())
     }
 
     #[log_error]
     fn get_block_by_height_map(&self) -> crate::Result<BTreeMap<BlockHeight, Id<GenBlock>>> {
         let map = self.0.get::<db::DBBlockByHeight, _>();
-        let items = map.prefix_iter_decoded(&())?;
+        let items = map.iter_decoded()?;
+        Ok(items.map(|(height, id)| (BlockHeight::new(height.inner()), id)).collect())
+    }
+
+    #[log_error]
+    fn get_pool_balances_tip_map(&self) -> crate::Result<BTreeMap<PoolId, Amount>> {
+        let map = self.0.get::<db::DBAccountingPoolBalancesTip, _>();
+        let items = map.iter_decoded()?;
         Ok(items.collect::<BTreeMap<_, _>>())
     }
 }
@@ -302,8 +326,8 @@ impl<'st, B: storage::Backend> PoSAccountingStorageRead<TipStorageTag>
         pool_id: PoolId,
     ) -> crate::Result<Option<BTreeMap<DelegationId, Amount>>> {
         let db_map = self.0.get::<db::DBAccountingPoolDelegationSharesTip, _>();
-        let shares_iter = db_map.prefix_iter_decoded(&())?;
-        private::filter_delegation_shares_for_poolid(pool_id, shares_iter)
+        let shares_iter = db_map.prefix_iter_decoded(&(pool_id,))?;
+        private::collect_delegation_shares(shares_iter)
     }
 
     #[log_error]
@@ -350,8 +374,8 @@ impl<'st, B: storage::Backend> PoSAccountingStorageRead<SealedStorageTag>
         pool_id: PoolId,
     ) -> crate::Result<Option<BTreeMap<DelegationId, Amount>>> {
         let db_map = self.0.get::<db::DBAccountingPoolDelegationSharesSealed, _>();
-        let shares_iter = db_map.prefix_iter_decoded(&())?;
-        private::filter_delegation_shares_for_poolid(pool_id, shares_iter)
+        let shares_iter = db_map.prefix_iter_decoded(&(pool_id,))?;
+        private::collect_delegation_shares(shares_iter)
     }
 
     #[log_error]
@@ -453,9 +477,26 @@ impl<'st, B: storage::Backend> BlockchainStorageRead for super::StoreTxRw<'st, B
         self.read_value::<well_known::MinHeightForReorg>()
     }
 
+    #[log_error]
+    fn get_clean_block_height(&self) -> crate::Result<Option<BlockHeight>> {
+        self.read_value::<well_known::CleanBlockHeight>()
+    }
+
     #[log_error]
     fn get_block_id_by_height(&self, height: &BlockHeight) -> crate::Result<Option<Id<GenBlock>>> {
-        self.read::<db::DBBlockByHeight, _, _>(height)
+        self.read::<db::DBBlockByHeight, _, _>(&db::block_height_key(*height))
+    }
+
+    #[log_error]
+    fn get_block_ids_by_height_range(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> crate::Result<Vec<(BlockHeight, Id<GenBlock>)>> {
+        let map = self.get_map::<db::DBBlockByHeight, _>()?;
+        let range = db::block_height_key(from_height)..=db::block_height_key(to_height);
+        let items = map.range_decoded(range)?;
+        Ok(items.map(|(height, id)| (BlockHeight::new(height.inner()), id)).collect())
     }
 
     #[log_error]
@@ -463,6 +504,11 @@ impl<'st, B: storage::Backend> BlockchainStorageRead for super::StoreTxRw<'st, B
         self.read::<db::DBUtxosBlockUndo, _, _>(id)
     }
 
+    #[log_error]
+    fn get_block_fees(&self, id: Id<Block>) -> crate::Result<Option<BlockFees>> {
+        self.read::<db::DBBlockFees, _, _>(id)
+    }
+
     #[log_error]
     fn get_token_aux_data(&self, token_id: &TokenId) -> crate::Result<Option<TokenAuxiliaryData>> {
         self.read::<db::DBTokensAuxData, _, _>(&token_id)
@@ -495,7 +541,7 @@ impl<'st, B: storage::Backend> BlockchainStorageRead for super::StoreTxRw<'st, B
         start_from: BlockHeight,
     ) -> crate::Result<BTreeMap<BlockHeight, Vec<Id<Block>>>> {
         let map = self.get_map::<db::DBBlockIndex, _>()?;
-        let items = map.prefix_iter_decoded(&())?;
+        let items = map.iter_decoded()?;
 
         let mut result = BTreeMap::<BlockHeight, Vec<Id<Block>>>::new();
         for (_, bi) in items {
@@ -542,7 +588,7 @@ impl<'st, B: storage::Backend> BlockchainStorageRead for super::StoreTxRw<'st, B
     #[log_error]
     fn get_block_map_keys(&self) -> crate::Result<BTreeSet<Id<Block>>> {
         let map = self.get_map::<db::DBBlock, _>()?;
-        let items = map.prefix_iter_keys(&())?;
+        let items = map.iter_keys()?;
         Ok(items.collect::<BTreeSet<_>>())
     }
 
@@ -550,7 +596,7 @@ impl<'st, B: storage::Backend> BlockchainStorageRead for super::StoreTxRw<'st, B
     #[log_error]
     fn get_block_index_map(&self) -> crate::Result<BTreeMap<Id<Block>, BlockIndex>> {
         let map = self.get_map::<db::DBBlockIndex, _>()?;
-        let items = map.prefix_iter_decoded(&())?;
+        let items = map.iter_decoded()?;
         Ok(items.collect::<BTreeMap<_, _>>())
     }
 
@@ -558,7 +604,15 @@ impl<'st, B: storage::Backend> BlockchainStorageRead for super::StoreTxRw<'st, B
     #[log_error]
     fn get_block_by_height_map(&self) -> crate::Result<BTreeMap<BlockHeight, Id<GenBlock>>> {
         let map = self.get_map::<db::DBBlockByHeight, _>()?;
-        let items = map.prefix_iter_decoded(&())?;
+        let items = map.iter_decoded()?;
+        Ok(items.map(|(height, id)| (BlockHeight::new(height.inner()), id)).collect())
+    }
+
+    // TODO: same as above.
+    #[log_error]
+    fn get_pool_balances_tip_map(&self) -> crate::Result<BTreeMap<PoolId, Amount>> {
+        let map = self.get_map::<db::DBAccountingPoolBalancesTip, _>()?;
+        let items = map.iter_decoded()?;
         Ok(items.collect::<BTreeMap<_, _>>())
     }
 }
@@ -619,8 +673,8 @@ impl<'st, B: storage::Backend> PoSAccountingStorageRead<TipStorageTag>
         pool_id: PoolId,
     ) -> crate::Result<Option<BTreeMap<DelegationId, Amount>>> {
         let db_map = self.get_map::<db::DBAccountingPoolDelegationSharesTip, _>()?;
-        let shares_iter = db_map.prefix_iter_decoded(&())?;
-        private::filter_delegation_shares_for_poolid(pool_id, shares_iter)
+        let shares_iter = db_map.prefix_iter_decoded(&(pool_id,))?;
+        private::collect_delegation_shares(shares_iter)
     }
 
     #[log_error]
@@ -667,8 +721,8 @@ impl<'st, B: storage::Backend> PoSAccountingStorageRead<SealedStorageTag>
         pool_id: PoolId,
     ) -> crate::Result<Option<BTreeMap<DelegationId, Amount>>> {
         let db_map = self.get_map::<db::DBAccountingPoolDelegationSharesSealed, _>()?;
-        let shares_iter = db_map.prefix_iter_decoded(&())?;
-        private::filter_delegation_shares_for_poolid(pool_id, shares_iter)
+        let shares_iter = db_map.prefix_iter_decoded(&(pool_id,))?;
+        private::collect_delegation_shares(shares_iter)
     }
 
     #[log_error]
@@ -15,7 +15,7 @@
 
 use super::{well_known, StoreTxRw};
 use crate::{BlockchainStorageWrite, ChainstateStorageVersion, SealedStorageTag, TipStorageTag};
-use chainstate_types::{BlockIndex, EpochData, EpochStorageWrite};
+use chainstate_types::{BlockFees, BlockIndex, EpochData, EpochStorageWrite};
 use common::{
     chain::{
         config::{EpochIndex, MagicBytes},
@@ -82,18 +82,28 @@ impl<'st, B: storage::Backend> BlockchainStorageWrite for StoreTxRw<'st, B> {
         self.write_value::<well_known::MinHeightForReorg>(&height)
     }
 
+    #[log_error]
+    fn set_clean_block_height(&mut self, height: BlockHeight) -> crate::Result<()> {
+        self.write_value::<well_known::CleanBlockHeight>(&height)
+    }
+
+    #[log_error]
+    fn del_clean_block_height(&mut self) -> crate::Result<()> {
+        self.del_value::<well_known::CleanBlockHeight>()
+    }
+
     #[log_error]
     fn set_block_id_at_height(
         &mut self,
         height: &BlockHeight,
         block_id: &Id<GenBlock>,
     ) -> crate::Result<()> {
-        self.write::<db::DBBlockByHeight, _, _, _>(height, block_id)
+        self.write::<db::DBBlockByHeight, _, _, _>(&db::block_height_key(*height), block_id)
     }
 
     #[log_error]
     fn del_block_id_at_height(&mut self, height: &BlockHeight) -> crate::Result<()> {
-        self.del::<db::DBBlockByHeight, _, _>(height)
+        self.del::<db::DBBlockByHeight, _, _>(&db::block_height_key(*height))
     }
 
     #[log_error]
@@ -106,6 +116,16 @@ impl<'st, B: storage::Backend> BlockchainStorageWrite for StoreTxRw<'st, B> {
         self.del::<db::DBUtxosBlockUndo, _, _>(id)
     }
 
+    #[log_error]
+    fn set_block_fees(&mut self, id: Id<Block>, fees: &BlockFees) -> crate::Result<()> {
+        self.write::<db::DBBlockFees, _, _, _>(id, fees)
+    }
+
+    #[log_error]
+    fn del_block_fees(&mut self, id: Id<Block>) -> crate::Result<()> {
+        self.del::<db::DBBlockFees, _, _>(id)
+    }
+
     #[log_error]
     fn set_token_aux_data(
         &mut self,
@@ -16,7 +16,7 @@
 use super::*;
 use common::chain::output_value::OutputValue;
 use common::chain::transaction::signed_transaction::SignedTransaction;
-use common::chain::{Block, Destination, OutPointSourceId, TxOutput, UtxoOutPoint};
+use common::chain::{Block, Destination, GenBlock, OutPointSourceId, TxOutput, UtxoOutPoint};
 use common::primitives::Id;
 use common::primitives::{Amount, BlockHeight, Idable, H256};
 use crypto::key::{KeyKind, PrivateKey};
@@ -473,3 +473,59 @@ fn utxo_db_impl_test(#[case] seed: Seed) {
     assert!(db_tx.set_best_block_for_utxos(&block_id.into()).is_ok());
     db_tx.commit().expect("commit cannot fail");
 }
+
+// Heights that differ only in their higher bytes must still be visited in the height order,
+// e.g. 256 comes after 255, though its little-endian encoding is lexicographically smaller.
+#[cfg(not(loom))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn block_ids_by_height_range_ordering(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let store = TestStore::new_empty().unwrap();
+
+    let mut heights = vec![0, 1, 255, 256, 257, 65535, 65536, u32::MAX as u64 + 1];
+    heights.extend((0..100).map(|_| rng.gen_range(0..1_000_000)));
+    let blocks = heights
+        .iter()
+        .map(|height| {
+            let block_id: Id<GenBlock> = Id::new(H256::random_using(&mut rng));
+            (BlockHeight::new(*height), block_id)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let mut db_tx = store.transaction_rw(None).unwrap();
+    for (height, block_id) in &blocks {
+        db_tx.set_block_id_at_height(height, block_id).unwrap();
+    }
+
+    fn check_range(
+        db_tx: &impl BlockchainStorageRead,
+        blocks: &BTreeMap<BlockHeight, Id<GenBlock>>,
+        from: u64,
+        to: u64,
+    ) {
+        let (from, to) = (BlockHeight::new(from), BlockHeight::new(to));
+        let expected = blocks.range(from..=to).map(|(h, id)| (*h, *id)).collect::<Vec<_>>();
+        assert_eq!(
+            db_tx.get_block_ids_by_height_range(from, to).unwrap(),
+            expected
+        );
+    }
+
+    check_range(&db_tx, &blocks, 0, u64::MAX);
+    db_tx.commit().unwrap();
+
+    let db_tx = store.transaction_ro().unwrap();
+    check_range(&db_tx, &blocks, 0, u64::MAX);
+    check_range(&db_tx, &blocks, 255, 256);
+    check_range(&db_tx, &blocks, 256, 65536);
+    check_range(&db_tx, &blocks, 2, 254);
+    for _ in 0..10 {
+        let from = rng.gen_range(0..1_000_000);
+        let to = rng.gen_range(from..1_000_000);
+        check_range(&db_tx, &blocks, from, to);
+    }
+
+    assert_eq!(db_tx.get_block_by_height_map().unwrap(), blocks);
+}
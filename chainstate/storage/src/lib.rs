@@ -24,7 +24,7 @@ pub mod schema;
 use std::collections::{BTreeMap, BTreeSet};
 
 use chainstate_types::{
-    BlockIndex, EpochStorageRead, EpochStorageWrite, SealedStorageTag, TipStorageTag,
+    BlockFees, BlockIndex, EpochStorageRead, EpochStorageWrite, SealedStorageTag, TipStorageTag,
 };
 use common::{
     chain::{
@@ -32,9 +32,9 @@ use common::{
         config::{EpochIndex, MagicBytes},
         tokens::{TokenAuxiliaryData, TokenId},
         transaction::Transaction,
-        AccountNonce, AccountType, Block, GenBlock,
+        AccountNonce, AccountType, Block, GenBlock, PoolId,
     },
-    primitives::{BlockHeight, Id},
+    primitives::{Amount, BlockHeight, Id},
 };
 use orders_accounting::{
     OrdersAccountingStorageRead, OrdersAccountingStorageWrite, OrdersAccountingUndo,
@@ -100,11 +100,26 @@ pub trait BlockchainStorageRead:
     /// Get the height below which reorgs should not be allowed.
     fn get_min_height_with_allowed_reorg(&self) -> crate::Result<Option<BlockHeight>>;
 
+    /// Get the height of the best block at the time the storage was last flushed.
+    ///
+    /// Blocks above it may have to be re-validated if the storage isn't flushed on every commit.
+    fn get_clean_block_height(&self) -> crate::Result<Option<BlockHeight>>;
+
     /// Get mainchain block by its height
     fn get_block_id_by_height(&self, height: &BlockHeight) -> crate::Result<Option<Id<GenBlock>>>;
 
+    /// Get the mainchain blocks with heights in `from_height..=to_height`, ordered by height
+    fn get_block_ids_by_height_range(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> crate::Result<Vec<(BlockHeight, Id<GenBlock>)>>;
+
     fn get_undo_data(&self, id: Id<Block>) -> crate::Result<Option<UtxosBlockUndo>>;
 
+    /// Get the fees of a block, recorded when it was connected
+    fn get_block_fees(&self, id: Id<Block>) -> crate::Result<Option<BlockFees>>;
+
     /// Get token creation tx
     fn get_token_aux_data(&self, token_id: &TokenId) -> crate::Result<Option<TokenAuxiliaryData>>;
 
@@ -159,6 +174,8 @@ pub trait BlockchainStorageRead:
     /// Get the entire mainchain-block-by-height map as BTreeMap. This is used in the chainstate's
     /// "heavy" consistency checks.
     fn get_block_by_height_map(&self) -> crate::Result<BTreeMap<BlockHeight, Id<GenBlock>>>;
+    /// Get the balances of all the pools from the tip accounting data.
+    fn get_pool_balances_tip_map(&self) -> crate::Result<BTreeMap<PoolId, Amount>>;
 }
 
 /// Modifying operations on persistent blockchain data
@@ -198,6 +215,12 @@ pub trait BlockchainStorageWrite:
     /// Set the height below which reorgs should not be allowed.
     fn set_min_height_with_allowed_reorg(&mut self, height: BlockHeight) -> crate::Result<()>;
 
+    /// Set the height of the best block at the time the storage is flushed.
+    fn set_clean_block_height(&mut self, height: BlockHeight) -> crate::Result<()>;
+
+    /// Remove the clean block height, if the storage is flushed on every commit.
+    fn del_clean_block_height(&mut self) -> crate::Result<()>;
+
     /// Set the mainchain block at given height to be given block.
     fn set_block_id_at_height(
         &mut self,
@@ -211,6 +234,9 @@ pub trait BlockchainStorageWrite:
     fn set_undo_data(&mut self, id: Id<Block>, undo: &UtxosBlockUndo) -> Result<()>;
     fn del_undo_data(&mut self, id: Id<Block>) -> Result<()>;
 
+    fn set_block_fees(&mut self, id: Id<Block>, fees: &BlockFees) -> Result<()>;
+    fn del_block_fees(&mut self, id: Id<Block>) -> Result<()>;
+
     /// Set data associated with token issuance (and ACL changes in the future)
     fn set_token_aux_data(&mut self, token_id: &TokenId, data: &TokenAuxiliaryData) -> Result<()>;
 
@@ -310,4 +336,10 @@ pub trait Transactional<'t> {
     fn transaction_rw<'s: 't>(&'s self, size: Option<usize>) -> Result<Self::TransactionRw>;
 }
 
-pub trait BlockchainStorage: for<'tx> Transactional<'tx> + Send {}
+pub trait BlockchainStorage: for<'tx> Transactional<'tx> + Send {
+    /// Allow multiple committed transactions to be written out to the backend at once.
+    fn set_write_batching(&self, enabled: bool) -> Result<()>;
+
+    /// Make sure all the committed transactions are written out and durable.
+    fn flush(&self) -> Result<()>;
+}
@@ -17,7 +17,7 @@
 
 use std::collections::{BTreeMap, BTreeSet};
 
-use chainstate_types::{BlockIndex, EpochData, EpochStorageRead, EpochStorageWrite};
+use chainstate_types::{BlockFees, BlockIndex, EpochData, EpochStorageRead, EpochStorageWrite};
 use common::{
     chain::{
         block::{signed_block_header::SignedBlockHeader, BlockReward},
@@ -63,13 +63,21 @@ mockall::mock! {
         fn get_block_header(&self, id: Id<Block>) -> crate::Result<Option<SignedBlockHeader>>;
 
         fn get_min_height_with_allowed_reorg(&self) -> crate::Result<Option<BlockHeight>>;
+        fn get_clean_block_height(&self) -> crate::Result<Option<BlockHeight>>;
 
         fn get_block_id_by_height(
             &self,
             height: &BlockHeight,
         ) -> crate::Result<Option<Id<GenBlock>>>;
 
+        fn get_block_ids_by_height_range(
+            &self,
+            from_height: BlockHeight,
+            to_height: BlockHeight,
+        ) -> crate::Result<Vec<(BlockHeight, Id<GenBlock>)>>;
+
         fn get_undo_data(&self, id: Id<Block>) -> crate::Result<Option<UtxosBlockUndo>>;
+        fn get_block_fees(&self, id: Id<Block>) -> crate::Result<Option<BlockFees>>;
 
         fn get_token_aux_data(&self, token_id: &TokenId) -> crate::Result<Option<TokenAuxiliaryData>>;
 
@@ -107,6 +115,7 @@ mockall::mock! {
         fn get_block_map_keys(&self) -> crate::Result<BTreeSet<Id<Block>>>;
         fn get_block_index_map(&self) -> crate::Result<BTreeMap<Id<Block>, BlockIndex>>;
         fn get_block_by_height_map(&self) -> crate::Result<BTreeMap<BlockHeight, Id<GenBlock>>>;
+        fn get_pool_balances_tip_map(&self) -> crate::Result<BTreeMap<PoolId, Amount>>;
     }
 
     impl EpochStorageRead for Store {
@@ -187,6 +196,8 @@ mockall::mock! {
         fn del_block(&mut self, id: Id<Block>) -> crate::Result<()>;
 
         fn set_min_height_with_allowed_reorg(&mut self, height: BlockHeight) -> crate::Result<()>;
+        fn set_clean_block_height(&mut self, height: BlockHeight) -> crate::Result<()>;
+        fn del_clean_block_height(&mut self) -> crate::Result<()>;
 
         fn set_block_id_at_height(
             &mut self,
@@ -199,6 +210,9 @@ mockall::mock! {
         fn set_undo_data(&mut self, id: Id<Block>, undo: &UtxosBlockUndo) -> crate::Result<()>;
         fn del_undo_data(&mut self, id: Id<Block>) -> crate::Result<()>;
 
+        fn set_block_fees(&mut self, id: Id<Block>, fees: &BlockFees) -> crate::Result<()>;
+        fn del_block_fees(&mut self, id: Id<Block>) -> crate::Result<()>;
+
         fn set_token_aux_data(&mut self, token_id: &TokenId, data: &TokenAuxiliaryData) -> crate::Result<()>;
         fn del_token_aux_data(&mut self, token_id: &TokenId) -> crate::Result<()>;
         fn set_token_id(&mut self, issuance_tx_id: &Id<Transaction>, token_id: &TokenId) -> crate::Result<()>;
@@ -349,7 +363,10 @@ mockall::mock! {
         fn transaction_rw<'st>(&'st self, size: Option<usize>) -> crate::Result<MockStoreTxRw> where 'st: 'tx;
     }
 
-    impl crate::BlockchainStorage for Store {}
+    impl crate::BlockchainStorage for Store {
+        fn set_write_batching(&self, enabled: bool) -> crate::Result<()>;
+        fn flush(&self) -> crate::Result<()>;
+    }
 }
 
 mockall::mock! {
@@ -368,13 +385,21 @@ mockall::mock! {
         fn get_block_header(&self, id: Id<Block>) -> crate::Result<Option<SignedBlockHeader>>;
 
         fn get_min_height_with_allowed_reorg(&self) -> crate::Result<Option<BlockHeight>>;
+        fn get_clean_block_height(&self) -> crate::Result<Option<BlockHeight>>;
 
         fn get_block_id_by_height(
             &self,
             height: &BlockHeight,
         ) -> crate::Result<Option<Id<GenBlock>>>;
 
+        fn get_block_ids_by_height_range(
+            &self,
+            from_height: BlockHeight,
+            to_height: BlockHeight,
+        ) -> crate::Result<Vec<(BlockHeight, Id<GenBlock>)>>;
+
         fn get_undo_data(&self, id: Id<Block>) -> crate::Result<Option<UtxosBlockUndo>>;
+        fn get_block_fees(&self, id: Id<Block>) -> crate::Result<Option<BlockFees>>;
 
         fn get_token_aux_data(&self, token_id: &TokenId) -> crate::Result<Option<TokenAuxiliaryData>>;
         fn get_token_id(&self, tx_id: &Id<Transaction>) -> crate::Result<Option<TokenId>>;
@@ -404,6 +429,7 @@ mockall::mock! {
         fn get_block_map_keys(&self) -> crate::Result<BTreeSet<Id<Block>>>;
         fn get_block_index_map(&self) -> crate::Result<BTreeMap<Id<Block>, BlockIndex>>;
         fn get_block_by_height_map(&self) -> crate::Result<BTreeMap<BlockHeight, Id<GenBlock>>>;
+        fn get_pool_balances_tip_map(&self) -> crate::Result<BTreeMap<PoolId, Amount>>;
     }
 
     impl EpochStorageRead for StoreTxRo {
@@ -495,13 +521,21 @@ mockall::mock! {
         fn get_block_header(&self, id: Id<Block>) -> crate::Result<Option<SignedBlockHeader>>;
 
         fn get_min_height_with_allowed_reorg(&self) -> crate::Result<Option<BlockHeight>>;
+        fn get_clean_block_height(&self) -> crate::Result<Option<BlockHeight>>;
 
         fn get_block_id_by_height(
             &self,
             height: &BlockHeight,
         ) -> crate::Result<Option<Id<GenBlock>>>;
 
+        fn get_block_ids_by_height_range(
+            &self,
+            from_height: BlockHeight,
+            to_height: BlockHeight,
+        ) -> crate::Result<Vec<(BlockHeight, Id<GenBlock>)>>;
+
         fn get_undo_data(&self, id: Id<Block>) -> crate::Result<Option<UtxosBlockUndo>>;
+        fn get_block_fees(&self, id: Id<Block>) -> crate::Result<Option<BlockFees>>;
 
         fn get_token_aux_data(&self, token_id: &TokenId) -> crate::Result<Option<TokenAuxiliaryData>>;
         fn get_token_id(&self, tx_id: &Id<Transaction>) -> crate::Result<Option<TokenId>>;
@@ -530,6 +564,7 @@ mockall::mock! {
         fn get_block_map_keys(&self) -> crate::Result<BTreeSet<Id<Block>>>;
         fn get_block_index_map(&self) -> crate::Result<BTreeMap<Id<Block>, BlockIndex>>;
         fn get_block_by_height_map(&self) -> crate::Result<BTreeMap<BlockHeight, Id<GenBlock>>>;
+        fn get_pool_balances_tip_map(&self) -> crate::Result<BTreeMap<PoolId, Amount>>;
     }
 
     impl EpochStorageRead for StoreTxRw {
@@ -610,6 +645,8 @@ mockall::mock! {
         fn del_block(&mut self, id: Id<Block>) -> crate::Result<()>;
 
         fn set_min_height_with_allowed_reorg(&mut self, height: BlockHeight) -> crate::Result<()>;
+        fn set_clean_block_height(&mut self, height: BlockHeight) -> crate::Result<()>;
+        fn del_clean_block_height(&mut self) -> crate::Result<()>;
 
         fn set_block_id_at_height(
             &mut self,
@@ -620,6 +657,9 @@ mockall::mock! {
         fn set_undo_data(&mut self, id: Id<Block>, undo: &UtxosBlockUndo) -> crate::Result<()>;
         fn del_undo_data(&mut self, id: Id<Block>) -> crate::Result<()>;
 
+        fn set_block_fees(&mut self, id: Id<Block>, fees: &BlockFees) -> crate::Result<()>;
+        fn del_block_fees(&mut self, id: Id<Block>) -> crate::Result<()>;
+
         fn del_block_id_at_height(&mut self, height: &BlockHeight) -> crate::Result<()>;
         fn set_token_aux_data(&mut self, token_id: &TokenId, data: &TokenAuxiliaryData) -> crate::Result<()>;
         fn del_token_aux_data(&mut self, token_id: &TokenId) -> crate::Result<()>;
@@ -15,7 +15,7 @@
 
 //! Chainstate database schema
 
-use chainstate_types::{BlockIndex, EpochData};
+use chainstate_types::{BlockFees, BlockIndex, EpochData};
 use common::{
     chain::{
         config::EpochIndex,
@@ -29,9 +29,18 @@ use orders_accounting::OrdersAccountingUndo;
 use pos_accounting::{
     DelegationData, DeltaMergeUndo, PoSAccountingDeltaData, PoSAccountingUndo, PoolData,
 };
+use storage::OrderPreservingValue;
 use tokens_accounting::TokenAccountingUndo;
 use utxo::{Utxo, UtxosBlockUndo};
 
+/// The key of `DBBlockByHeight`; the height is encoded so that the entries of the map are ordered
+/// by height.
+pub type BlockHeightKey = OrderPreservingValue<u64>;
+
+pub fn block_height_key(height: BlockHeight) -> BlockHeightKey {
+    OrderPreservingValue::new(height.into_int())
+}
+
 storage::decl_schema! {
     /// Database schema for blockchain storage
     pub Schema {
@@ -42,11 +51,13 @@ storage::decl_schema! {
         /// Store tag for blocks indexes.
         pub DBBlockIndex: Map<Id<Block>, BlockIndex>,
         /// Storage for block IDs indexed by block height.
-        pub DBBlockByHeight: Map<BlockHeight, Id<GenBlock>>,
+        pub DBBlockByHeight: Map<BlockHeightKey, Id<GenBlock>>,
         /// Store for Utxo Entries
         pub DBUtxo: Map<UtxoOutPoint, Utxo>,
         /// Store for utxo BlockUndo
         pub DBUtxosBlockUndo: Map<Id<Block>, UtxosBlockUndo>,
+        /// Store for the fees of the mainchain blocks
+        pub DBBlockFees: Map<Id<Block>, BlockFees>,
         /// Store for EpochData
         pub DBEpochData: Map<EpochIndex, EpochData>,
         /// Store for token's info; created on issuance
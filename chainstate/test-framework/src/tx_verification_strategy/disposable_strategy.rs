@@ -14,14 +14,14 @@
 // limitations under the License.
 
 use chainstate::{
-    TransactionVerificationStrategy, TransactionVerifierMakerFn, TransactionVerifierStorageError,
+    make_block_fees, TransactionVerificationStrategy, TransactionVerifierMakerFn,
+    TransactionVerifierStorageError,
 };
-use chainstate_types::BlockIndex;
+use chainstate_types::{BlockFees, BlockIndex};
 use common::{
     chain::{block::timestamp::BlockTimestamp, Block, ChainConfig},
     primitives::{id::WithId, Idable},
 };
-use constraints_value_accumulator::AccumulatedFee;
 use orders_accounting::OrdersAccountingView;
 use pos_accounting::PoSAccountingView;
 use tokens_accounting::TokensAccountingView;
@@ -61,7 +61,7 @@ impl TransactionVerificationStrategy for DisposableTransactionVerificationStrate
         block_index: &BlockIndex,
         block: &WithId<Block>,
         median_time_past: BlockTimestamp,
-    ) -> Result<TransactionVerifier<C, S, U, A, T, O>, ConnectTransactionError>
+    ) -> Result<(TransactionVerifier<C, S, U, A, T, O>, BlockFees), ConnectTransactionError>
     where
         C: AsRef<ChainConfig> + ShallowClone,
         S: TransactionVerifierStorageRef<Error = TransactionVerifierStorageError>,
@@ -74,10 +74,10 @@ impl TransactionVerificationStrategy for DisposableTransactionVerificationStrate
     {
         let mut base_tx_verifier = tx_verifier_maker(storage_backend, chain_config.shallow_clone());
 
-        let total_fees = block
+        let tx_fees = block
             .transactions()
             .iter()
-            .try_fold(AccumulatedFee::new(), |total, tx| {
+            .map(|tx| {
                 let mut tx_verifier = base_tx_verifier.derive_child();
                 let fee = tx_verifier
                     .connect_transaction(
@@ -91,19 +91,12 @@ impl TransactionVerificationStrategy for DisposableTransactionVerificationStrate
                 let consumed_cache = tx_verifier.consume()?;
                 flush_to_storage(&mut base_tx_verifier, consumed_cache).log_err()?;
 
-                total
-                    .combine(fee)
-                    .map_err(|_| ConnectTransactionError::FailedToAddAllFeesOfBlock(block.get_id()))
+                Ok(fee)
             })
+            .collect::<Result<Vec<_>, ConnectTransactionError>>()
             .log_err()?;
-        let total_fees = total_fees
-            .map_into_block_fees(chain_config.as_ref(), block_index.block_height())
-            .map_err(|err| {
-                ConnectTransactionError::ConstrainedValueAccumulatorError(
-                    err,
-                    block.get_id().into(),
-                )
-            })?;
+        let (total_fees, block_fees) =
+            make_block_fees(chain_config.as_ref(), block_index, block, tx_fees).log_err()?;
 
         base_tx_verifier
             .check_block_reward(block, total_fees, block_index.block_height())
@@ -120,7 +113,7 @@ impl TransactionVerificationStrategy for DisposableTransactionVerificationStrate
 
         base_tx_verifier.set_best_block(block.get_id().into());
 
-        Ok(base_tx_verifier)
+        Ok((base_tx_verifier, block_fees))
     }
 
     fn disconnect_block<C, S, M, U, A, T, O>(
@@ -14,9 +14,10 @@
 // limitations under the License.
 
 use chainstate::{
-    TransactionVerificationStrategy, TransactionVerifierMakerFn, TransactionVerifierStorageError,
+    make_block_fees, TransactionVerificationStrategy, TransactionVerifierMakerFn,
+    TransactionVerifierStorageError,
 };
-use chainstate_types::BlockIndex;
+use chainstate_types::{BlockFees, BlockIndex};
 use common::{
     chain::{block::timestamp::BlockTimestamp, Block, ChainConfig},
     primitives::{id::WithId, Idable},
@@ -73,7 +74,7 @@ impl TransactionVerificationStrategy for RandomizedTransactionVerificationStrate
         block_index: &BlockIndex,
         block: &WithId<Block>,
         median_time_past: BlockTimestamp,
-    ) -> Result<TransactionVerifier<C, S, U, A, T, O>, ConnectTransactionError>
+    ) -> Result<(TransactionVerifier<C, S, U, A, T, O>, BlockFees), ConnectTransactionError>
     where
         C: AsRef<ChainConfig> + ShallowClone,
         S: TransactionVerifierStorageRef<Error = TransactionVerifierStorageError>,
@@ -84,7 +85,7 @@ impl TransactionVerificationStrategy for RandomizedTransactionVerificationStrate
         M: TransactionVerifierMakerFn<C, S, U, A, T, O>,
         <S as utxo::UtxosStorageRead>::Error: From<U::Error>,
     {
-        let mut tx_verifier = self
+        let (mut tx_verifier, block_fees) = self
             .connect_with_base(
                 tx_verifier_maker,
                 storage_backend,
@@ -97,7 +98,7 @@ impl TransactionVerificationStrategy for RandomizedTransactionVerificationStrate
 
         tx_verifier.set_best_block(block.get_id().into());
 
-        Ok(tx_verifier)
+        Ok((tx_verifier, block_fees))
     }
 
     fn disconnect_block<C, S, M, U, A, T, O>(
@@ -136,7 +137,7 @@ impl RandomizedTransactionVerificationStrategy {
         block_index: &BlockIndex,
         block: &WithId<Block>,
         median_time_past: &BlockTimestamp,
-    ) -> Result<TransactionVerifier<C, S, U, A, T, O>, ConnectTransactionError>
+    ) -> Result<(TransactionVerifier<C, S, U, A, T, O>, BlockFees), ConnectTransactionError>
     where
         C: AsRef<ChainConfig> + ShallowClone,
         S: TransactionVerifierStorageRef<Error = TransactionVerifierStorageError>,
@@ -149,12 +150,12 @@ impl RandomizedTransactionVerificationStrategy {
     {
         let mut tx_verifier = tx_verifier_maker(storage_backend, chain_config.shallow_clone());
 
-        let mut total_fees = AccumulatedFee::new();
+        let mut tx_fees = Vec::with_capacity(block.transactions().len());
         let mut tx_num = 0usize;
         while tx_num < block.transactions().len() {
             if self.rng.lock().unwrap().gen::<bool>() {
                 // derive a new cache
-                let (consumed_cache, fee, new_tx_index) = self.connect_with_derived(
+                let (consumed_cache, fees, new_tx_index) = self.connect_with_derived(
                     &tx_verifier,
                     block,
                     block_index,
@@ -162,9 +163,7 @@ impl RandomizedTransactionVerificationStrategy {
                     tx_num,
                 )?;
 
-                total_fees = total_fees.combine(fee).map_err(|_| {
-                    ConnectTransactionError::FailedToAddAllFeesOfBlock(block.get_id())
-                })?;
+                tx_fees.extend(fees);
 
                 flush_to_storage(&mut tx_verifier, consumed_cache)
                     .map_err(ConnectTransactionError::from)?;
@@ -180,22 +179,13 @@ impl RandomizedTransactionVerificationStrategy {
                     median_time_past,
                 )?;
 
-                total_fees = total_fees.combine(fee).map_err(|_| {
-                    ConnectTransactionError::FailedToAddAllFeesOfBlock(block.get_id())
-                })?;
-
+                tx_fees.push(fee);
                 tx_num += 1;
             }
         }
 
-        let total_fees = total_fees
-            .map_into_block_fees(chain_config.as_ref(), block_index.block_height())
-            .map_err(|err| {
-                ConnectTransactionError::ConstrainedValueAccumulatorError(
-                    err,
-                    block.get_id().into(),
-                )
-            })?;
+        let (total_fees, block_fees) =
+            make_block_fees(chain_config.as_ref(), block_index, block, tx_fees)?;
 
         tx_verifier
             .check_block_reward(block, total_fees, block_index.block_height())
@@ -210,7 +200,7 @@ impl RandomizedTransactionVerificationStrategy {
             )
             .log_err()?;
 
-        Ok(tx_verifier)
+        Ok((tx_verifier, block_fees))
     }
 
     fn connect_with_derived<C, S, U, A, T, O>(
@@ -220,7 +210,7 @@ impl RandomizedTransactionVerificationStrategy {
         block_index: &BlockIndex,
         median_time_past: &BlockTimestamp,
         mut tx_num: usize,
-    ) -> Result<(TransactionVerifierDelta, AccumulatedFee, usize), ConnectTransactionError>
+    ) -> Result<(TransactionVerifierDelta, Vec<AccumulatedFee>, usize), ConnectTransactionError>
     where
         C: AsRef<ChainConfig>,
         U: UtxosView,
@@ -231,7 +221,7 @@ impl RandomizedTransactionVerificationStrategy {
         <S as utxo::UtxosStorageRead>::Error: From<U::Error>,
     {
         let mut tx_verifier = base_tx_verifier.derive_child();
-        let mut total_fees = AccumulatedFee::new();
+        let mut tx_fees = Vec::new();
         while tx_num < block.transactions().len() {
             if self.rng.lock().unwrap().gen::<bool>() {
                 // break the loop, which effectively would flush current state to the parent
@@ -246,14 +236,12 @@ impl RandomizedTransactionVerificationStrategy {
                     median_time_past,
                 )?;
 
-                total_fees = total_fees.combine(fee).map_err(|_| {
-                    ConnectTransactionError::FailedToAddAllFeesOfBlock(block.get_id())
-                })?;
+                tx_fees.push(fee);
                 tx_num += 1;
             }
         }
         let cache = tx_verifier.consume()?;
-        Ok((cache, total_fees, tx_num))
+        Ok((cache, tx_fees, tx_num))
     }
 
     fn disconnect_with_base<C, S, M, U, A, T, O>(
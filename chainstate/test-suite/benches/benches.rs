@@ -13,9 +13,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
+use chainstate::{
+    make_chainstate, BlockSource, ChainstateConfig, DefaultTransactionVerificationStrategy,
+    StorageSyncPolicy,
+};
 use chainstate_test_framework::TestFramework;
 use common::{
-    chain::{config::create_unit_test_config, stakelock::StakePoolData, Destination, PoolId},
+    chain::{
+        config::create_unit_test_config, stakelock::StakePoolData, Block, ChainConfig, Destination,
+        PoolId,
+    },
     primitives::{per_thousand::PerThousand, Amount, BlockDistance, Idable, H256},
 };
 use crypto::{
@@ -114,5 +123,84 @@ pub fn pos_reorg(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, pow_reorg, pos_reorg);
+fn connect_blocks<B: storage::Backend + 'static>(
+    backend: B,
+    chain_config: &Arc<ChainConfig>,
+    chainstate_config: ChainstateConfig,
+    blocks: &[Block],
+) {
+    let storage = chainstate_storage::Store::new(backend, chain_config).unwrap();
+    let mut chainstate = make_chainstate(
+        Arc::clone(chain_config),
+        chainstate_config,
+        storage,
+        DefaultTransactionVerificationStrategy::new(),
+        None,
+        Default::default(),
+    )
+    .unwrap();
+
+    for block in blocks {
+        chainstate.process_block(block.clone(), BlockSource::Peer).unwrap();
+    }
+    chainstate.flush_storage().unwrap();
+}
+
+pub fn ibd_write_batching(c: &mut Criterion) {
+    const BLOCK_COUNT: u64 = 10_000;
+
+    let mut rng = make_seedable_rng(1111.into());
+    let mut tf = TestFramework::builder(&mut rng).build();
+    tf.create_chain(
+        &tf.genesis().get_id().into(),
+        BLOCK_COUNT as usize,
+        &mut rng,
+    )
+    .unwrap();
+    let blocks: Vec<Block> = (1..=BLOCK_COUNT)
+        .map(|height| tf.block(tf.to_chain_block_id(&tf.block_id(height))))
+        .collect();
+    let chain_config = Arc::clone(tf.chainstate.get_chain_config());
+
+    let make_lmdb = |db_dir: &tempfile::TempDir| {
+        storage_lmdb::Lmdb::new(
+            db_dir.path().to_path_buf(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+    };
+
+    let mut group = c.benchmark_group("IBD into LMDB");
+    group.sample_size(10);
+
+    group.bench_function("sync on every commit", |b| {
+        b.iter(|| {
+            let db_dir = tempfile::TempDir::new().unwrap();
+            let chainstate_config = ChainstateConfig::new().with_heavy_checks_enabled(false);
+            connect_blocks(
+                make_lmdb(&db_dir),
+                &chain_config,
+                chainstate_config,
+                &blocks,
+            );
+        })
+    });
+
+    group.bench_function("write batching", |b| {
+        b.iter(|| {
+            let db_dir = tempfile::TempDir::new().unwrap();
+            let chainstate_config = ChainstateConfig::new()
+                .with_heavy_checks_enabled(false)
+                .with_ibd_write_batching(true)
+                .with_storage_sync_policy(StorageSyncPolicy::OnShutdown);
+            let backend = storage::WriteBatching::new(make_lmdb(&db_dir).with_no_sync());
+            connect_blocks(backend, &chain_config, chainstate_config, &blocks);
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, pow_reorg, pos_reorg, ibd_write_batching);
 criterion_main!(benches);
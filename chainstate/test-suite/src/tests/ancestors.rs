@@ -0,0 +1,119 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chainstate::{ChainstateError, PropertyQueryError, MAX_ANCESTORS_DEPTH};
+use chainstate_test_framework::TestFramework;
+use common::{
+    chain::Block,
+    primitives::{Id, Idable, H256},
+};
+use randomness::Rng;
+use rstest::rstest;
+use test_utils::random::{make_seedable_rng, Seed};
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn ancestors_of_tip(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        let genesis_id = tf.genesis().get_id().into();
+        tf.create_chain(&genesis_id, 50, &mut rng).unwrap();
+        let tip_id = tf.to_chain_block_id(&tf.best_block_id());
+
+        let ancestors = tf.chainstate.get_ancestors(tip_id, 10).unwrap();
+        let expected = (40..50).rev().map(|height| tf.to_chain_block_id(&tf.block_id(height)));
+        assert_eq!(ancestors, expected.collect::<Vec<_>>());
+
+        // Genesis is reached before the requested depth
+        let ancestors = tf.chainstate.get_ancestors(tip_id, MAX_ANCESTORS_DEPTH).unwrap();
+        let expected = (1..50).rev().map(|height| tf.to_chain_block_id(&tf.block_id(height)));
+        assert_eq!(ancestors, expected.collect::<Vec<_>>());
+
+        // A random block in the middle of the chain
+        let height = rng.gen_range(1..=50);
+        let depth = rng.gen_range(0..60);
+        let block_id = tf.to_chain_block_id(&tf.block_id(height));
+        let ancestors = tf.chainstate.get_ancestors(block_id, depth).unwrap();
+        let expected = (1..height)
+            .rev()
+            .take(depth as usize)
+            .map(|height| tf.to_chain_block_id(&tf.block_id(height)));
+        assert_eq!(ancestors, expected.collect::<Vec<_>>());
+    });
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn ancestors_of_stale_block(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        let genesis_id = tf.genesis().get_id().into();
+        tf.create_chain(&genesis_id, 10, &mut rng).unwrap();
+
+        // A shorter branch that forks off at height 5
+        let fork_point = tf.block_id(5);
+        let branch = tf.create_chain_return_ids(&fork_point, 3, &mut rng).unwrap();
+        let branch_tip = tf.to_chain_block_id(branch.last().unwrap());
+        assert_ne!(tf.best_block_id(), *branch.last().unwrap());
+
+        let ancestors = tf.chainstate.get_ancestors(branch_tip, 6).unwrap();
+        let expected = branch[..2]
+            .iter()
+            .rev()
+            .chain([fork_point, tf.block_id(4), tf.block_id(3), tf.block_id(2)].iter())
+            .map(|id| tf.to_chain_block_id(id))
+            .collect::<Vec<_>>();
+        assert_eq!(ancestors, expected);
+    });
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn ancestors_invalid_input(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        let genesis_id = tf.genesis().get_id().into();
+        tf.create_chain(&genesis_id, 5, &mut rng).unwrap();
+        let tip_id = tf.to_chain_block_id(&tf.best_block_id());
+
+        let depth = rng.gen_range(MAX_ANCESTORS_DEPTH + 1..=u32::MAX);
+        assert_eq!(
+            tf.chainstate.get_ancestors(tip_id, depth),
+            Err(ChainstateError::FailedToReadProperty(
+                PropertyQueryError::AncestorsDepthTooLarge {
+                    depth,
+                    max_depth: MAX_ANCESTORS_DEPTH
+                }
+            ))
+        );
+
+        let unknown_block_id = Id::<Block>::new(H256::random_using(&mut rng));
+        assert_eq!(
+            tf.chainstate.get_ancestors(unknown_block_id, 1),
+            Err(ChainstateError::FailedToReadProperty(
+                PropertyQueryError::BlockIndexNotFound(unknown_block_id.into())
+            ))
+        );
+    });
+}
@@ -0,0 +1,165 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chainstate::{BlockFees, BlockFeesInfo};
+use chainstate_storage::{
+    BlockchainStorageRead, BlockchainStorageWrite, TransactionRw, Transactional,
+};
+use chainstate_test_framework::{anyonecanspend_address, TestFramework, TransactionBuilder};
+use common::{
+    chain::{
+        output_value::OutputValue, signature::inputsig::InputWitness, Block, GenBlock,
+        OutPointSourceId, SignedTransaction, TxInput, TxOutput,
+    },
+    primitives::{Amount, Id, Idable, H256},
+};
+use randomness::{CryptoRng, Rng};
+use rstest::rstest;
+use test_utils::random::{make_seedable_rng, Seed};
+
+/// Make a chain of transactions spending the genesis output, each paying a random fee.
+/// Return the transactions and the expected block fees.
+fn make_txs(
+    tf: &TestFramework,
+    rng: &mut (impl Rng + CryptoRng),
+) -> (Vec<SignedTransaction>, BlockFees) {
+    let genesis_id: Id<GenBlock> = tf.genesis().get_id().into();
+    let mut source_id = OutPointSourceId::BlockReward(genesis_id);
+    let mut amount = match tf.genesis().utxos()[0] {
+        TxOutput::Transfer(OutputValue::Coin(amount), _) => amount,
+        _ => panic!("coin transfer expected"),
+    };
+
+    let mut txs = Vec::new();
+    let mut tx_fees = Vec::new();
+    for _ in 0..rng.gen_range(1..5) {
+        let fee = Amount::from_atoms(rng.gen_range(0..1_000_000));
+        amount = (amount - fee).unwrap();
+        let tx = TransactionBuilder::new()
+            .add_input(
+                TxInput::from_utxo(source_id, 0),
+                InputWitness::NoSignature(None),
+            )
+            .add_output(TxOutput::Transfer(
+                OutputValue::Coin(amount),
+                anyonecanspend_address(),
+            ))
+            .build();
+        source_id = tx.transaction().get_id().into();
+        tx_fees.push((tx.transaction().get_id(), fee));
+        txs.push(tx);
+    }
+
+    let total_fees = tx_fees.iter().map(|(_, fee)| *fee).sum::<Option<Amount>>().unwrap();
+    (txs, BlockFees::new(total_fees, tx_fees))
+}
+
+fn stored_block_fees(tf: &TestFramework, block_id: Id<Block>) -> Option<BlockFees> {
+    tf.storage.transaction_ro().unwrap().get_block_fees(block_id).unwrap()
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn fees_of_mainchain_block(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        let (txs, expected_fees) = make_txs(&tf, &mut rng);
+        let block = tf.make_block_builder().with_transactions(txs).build(&mut rng);
+        let block_id = block.get_id();
+        tf.process_block(block, chainstate::BlockSource::Local).unwrap();
+
+        assert_eq!(
+            stored_block_fees(&tf, block_id),
+            Some(expected_fees.clone())
+        );
+        assert_eq!(
+            tf.chainstate.get_block_fees(&block_id).unwrap(),
+            Some(expected_fees.clone())
+        );
+
+        // A block without transactions has no fees
+        let empty_block_id = tf.create_chain(&block_id.into(), 1, &mut rng).unwrap();
+        let empty_block_id = tf.to_chain_block_id(&empty_block_id);
+        assert_eq!(
+            tf.chainstate.get_block_fees(&empty_block_id).unwrap(),
+            Some(BlockFeesInfo::Recorded(BlockFees::new(
+                Amount::ZERO,
+                Vec::new()
+            )))
+        );
+
+        // The fees missing in the db, e.g. for blocks connected by an older version,
+        // are reported as not recorded rather than as a block that is not on the mainchain
+        {
+            let mut db_tx = tf.storage.transaction_rw(None).unwrap();
+            db_tx.del_block_fees(block_id).unwrap();
+            db_tx.commit().unwrap();
+        }
+        assert_eq!(
+            tf.chainstate.get_block_fees(&block_id).unwrap(),
+            Some(BlockFeesInfo::NotRecorded)
+        );
+        assert_eq!(stored_block_fees(&tf, block_id), None);
+    });
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn fees_after_reorg(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+        let genesis_id: Id<GenBlock> = tf.genesis().get_id().into();
+
+        let (txs, expected_fees) = make_txs(&tf, &mut rng);
+        let block = tf.make_block_builder().with_transactions(txs).build(&mut rng);
+        let block_id = block.get_id();
+        tf.process_block(block, chainstate::BlockSource::Local).unwrap();
+        assert_eq!(
+            stored_block_fees(&tf, block_id),
+            Some(expected_fees.clone())
+        );
+
+        // Reorg to a longer chain whose first block spends the genesis output differently
+        let (alt_txs, alt_expected_fees) = make_txs(&tf, &mut rng);
+        let alt_block = tf
+            .make_block_builder()
+            .with_parent(genesis_id)
+            .with_transactions(alt_txs)
+            .build(&mut rng);
+        let alt_block_id = alt_block.get_id();
+        tf.process_block(alt_block, chainstate::BlockSource::Local).unwrap();
+        tf.create_chain(&alt_block_id.into(), 1, &mut rng).unwrap();
+        assert!(!tf.is_block_in_main_chain(&block_id));
+
+        // The fees of the disconnected block are removed and can't be queried
+        assert_eq!(stored_block_fees(&tf, block_id), None);
+        assert_eq!(tf.chainstate.get_block_fees(&block_id).unwrap(), None);
+        assert_eq!(
+            tf.chainstate.get_block_fees(&alt_block_id).unwrap(),
+            Some(BlockFeesInfo::Recorded(alt_expected_fees))
+        );
+
+        // Unknown blocks have no fees either
+        assert_eq!(
+            tf.chainstate.get_block_fees(&Id::new(H256::random_using(&mut rng))).unwrap(),
+            None
+        );
+    });
+}
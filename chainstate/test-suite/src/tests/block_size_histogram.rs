@@ -0,0 +1,267 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use chainstate::{
+    BlockSizeHistogram, ChainstateConfig, ChainstateError, PropertyQueryError,
+    BLOCK_SIZE_HISTOGRAM_BUCKET_WIDTH_KB,
+};
+use chainstate_test_framework::{
+    anyonecanspend_address, get_output_value, TestFramework, TransactionBuilder,
+};
+use common::{
+    chain::{
+        output_value::OutputValue, signature::inputsig::InputWitness, GenBlockId, OutPointSourceId,
+        TxInput, TxOutput,
+    },
+    primitives::{Amount, BlockHeight, Idable},
+};
+use randomness::{CryptoRng, Rng};
+use rstest::rstest;
+use serialization::Encode;
+use test_utils::random::{make_seedable_rng, Seed};
+
+// Each padding output takes a few bytes, so these counts produce blocks from a few KiB
+// up to a few dozens of KiB.
+const MAX_PADDING_OUTPUTS: usize = 10_000;
+
+/// Create a mainchain block for each of the specified padding output counts and return
+/// the sizes of the blocks.
+fn make_blocks(
+    tf: &mut TestFramework,
+    padding_outputs: &[usize],
+    rng: &mut (impl Rng + CryptoRng),
+) -> Vec<usize> {
+    let mut sizes = Vec::new();
+
+    for padding_outputs in padding_outputs {
+        let best_block_id = tf.best_block_id();
+        let (source_id, output) = match best_block_id.classify(tf.chain_config()) {
+            GenBlockId::Genesis(_) => (
+                OutPointSourceId::BlockReward(best_block_id),
+                tf.genesis().utxos()[0].clone(),
+            ),
+            GenBlockId::Block(id) => {
+                let tx = tf.block(id).transactions()[0].clone();
+                (tx.transaction().get_id().into(), tx.outputs()[0].clone())
+            }
+        };
+        let OutputValue::Coin(amount) =
+            get_output_value(&output).expect("output must have a value")
+        else {
+            panic!("coin output expected");
+        };
+
+        let padding_amount = Amount::from_atoms(1);
+        let change = (amount - (padding_amount * *padding_outputs as u128).unwrap()).unwrap();
+        let tx = (0..*padding_outputs)
+            .fold(
+                TransactionBuilder::new()
+                    .add_input(
+                        TxInput::from_utxo(source_id, 0),
+                        InputWitness::NoSignature(None),
+                    )
+                    .add_output(TxOutput::Transfer(
+                        OutputValue::Coin(change),
+                        anyonecanspend_address(),
+                    )),
+                |builder, _| {
+                    builder.add_output(TxOutput::Transfer(
+                        OutputValue::Coin(padding_amount),
+                        anyonecanspend_address(),
+                    ))
+                },
+            )
+            .build();
+
+        let block = tf.make_block_builder().add_transaction(tx).build(rng);
+        sizes.push(block.encoded_size());
+        tf.process_block(block, chainstate::BlockSource::Local).unwrap();
+    }
+
+    sizes
+}
+
+fn expected_histogram(block_sizes: &[usize]) -> BlockSizeHistogram {
+    let mut buckets = BTreeMap::<u32, u32>::new();
+    for size in block_sizes {
+        let size_kb = (size / 1024) as u32;
+        *buckets
+            .entry(size_kb - size_kb % BLOCK_SIZE_HISTOGRAM_BUCKET_WIDTH_KB)
+            .or_default() += 1;
+    }
+
+    BlockSizeHistogram {
+        buckets: buckets.into_iter().collect(),
+    }
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn histogram_matches_block_sizes(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        // Make sure that the blocks don't all end up in the same bucket
+        let mut padding_outputs = vec![0, 3_000, 6_000, MAX_PADDING_OUTPUTS];
+        padding_outputs
+            .extend((0..rng.gen_range(5..15)).map(|_| rng.gen_range(0..MAX_PADDING_OUTPUTS)));
+        let block_sizes = make_blocks(&mut tf, &padding_outputs, &mut rng);
+        let tip_height = block_sizes.len() as u64;
+
+        let expected = expected_histogram(&block_sizes);
+        assert!(expected.buckets.len() >= 3);
+        assert_eq!(
+            expected.buckets.iter().map(|(_, count)| *count as usize).sum::<usize>(),
+            block_sizes.len()
+        );
+
+        // The whole chain; genesis is not counted
+        let histogram = tf
+            .chainstate
+            .get_block_size_histogram(BlockHeight::zero(), BlockHeight::new(tip_height))
+            .unwrap();
+        assert_eq!(histogram, expected);
+
+        // A random sub-range
+        let from = rng.gen_range(1..=tip_height);
+        let to = rng.gen_range(from..=tip_height);
+        let histogram = tf
+            .chainstate
+            .get_block_size_histogram(BlockHeight::new(from), BlockHeight::new(to))
+            .unwrap();
+        assert_eq!(
+            histogram,
+            expected_histogram(&block_sizes[(from - 1) as usize..to as usize])
+        );
+
+        // Heights above the tip are ignored
+        let histogram = tf
+            .chainstate
+            .get_block_size_histogram(
+                BlockHeight::new(tip_height),
+                BlockHeight::new(tip_height + 100),
+            )
+            .unwrap();
+        assert_eq!(
+            histogram,
+            expected_histogram(&block_sizes[block_sizes.len() - 1..])
+        );
+
+        // Only genesis
+        let histogram = tf
+            .chainstate
+            .get_block_size_histogram(BlockHeight::zero(), BlockHeight::zero())
+            .unwrap();
+        assert_eq!(histogram, BlockSizeHistogram { buckets: vec![] });
+    });
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn invalid_ranges(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let max_range = rng.gen_range(1..10);
+        let mut tf = TestFramework::builder(&mut rng)
+            .with_chainstate_config(
+                ChainstateConfig::new().with_max_block_size_histogram_range(max_range),
+            )
+            .build();
+        tf.create_chain(&tf.genesis().get_id().into(), 2, &mut rng).unwrap();
+
+        let from = BlockHeight::new(rng.gen_range(1..100));
+        let to = from.checked_add(max_range as u64 - 1).unwrap();
+        assert!(tf.chainstate.get_block_size_histogram(from, to).is_ok());
+
+        let too_far = to.next_height();
+        assert_eq!(
+            tf.chainstate.get_block_size_histogram(from, too_far),
+            Err(ChainstateError::FailedToReadProperty(
+                PropertyQueryError::BlockHeightRangeTooLarge {
+                    start: from,
+                    end: too_far,
+                    max_range,
+                }
+            ))
+        );
+
+        let before = from.prev_height().unwrap();
+        assert_eq!(
+            tf.chainstate.get_block_size_histogram(from, before),
+            Err(ChainstateError::FailedToReadProperty(
+                PropertyQueryError::InvalidBlockHeightRange {
+                    start: from,
+                    end: before,
+                }
+            ))
+        );
+    });
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn cached_histogram(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        let block_sizes = make_blocks(&mut tf, &[0, 3_000], &mut rng);
+        let (from, to) = (BlockHeight::new(1), BlockHeight::new(100));
+
+        let histogram = tf.chainstate.get_block_size_histogram(from, to).unwrap();
+        assert_eq!(histogram, expected_histogram(&block_sizes));
+
+        // The cached result is reused while the tip stays the same
+        tf.progress_time_seconds_since_epoch(59);
+        assert_eq!(
+            tf.chainstate.get_block_size_histogram(from, to).unwrap(),
+            histogram
+        );
+
+        // A new block is seen immediately
+        let new_block_sizes = make_blocks(&mut tf, &[6_000], &mut rng);
+        let all_block_sizes = [block_sizes, new_block_sizes].concat();
+        assert_eq!(
+            tf.chainstate.get_block_size_histogram(from, to).unwrap(),
+            expected_histogram(&all_block_sizes)
+        );
+        assert_eq!(
+            tf.chainstate.get_block_size_histogram(from, to.next_height()).unwrap(),
+            expected_histogram(&all_block_sizes)
+        );
+
+        // After a reorg the blocks of the new mainchain are counted
+        let reorg_block_ids =
+            tf.create_chain_return_ids(&tf.genesis().get_id().into(), 4, &mut rng).unwrap();
+        assert_eq!(tf.best_block_id(), *reorg_block_ids.last().unwrap());
+        let reorg_block_sizes = reorg_block_ids
+            .iter()
+            .map(|id| match id.classify(tf.chain_config()) {
+                GenBlockId::Block(id) => tf.block(id).encoded_size(),
+                GenBlockId::Genesis(_) => panic!("genesis can't be created"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tf.chainstate.get_block_size_histogram(from, to).unwrap(),
+            expected_histogram(&reorg_block_sizes)
+        );
+    });
+}
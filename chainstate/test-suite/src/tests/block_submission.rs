@@ -0,0 +1,282 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chainstate::{
+    rpc::{BlockRejectionCategory, BlockSubmissionError},
+    BlockHeaderCheckInfo, BlockSource,
+};
+use chainstate_test_framework::{
+    anyonecanspend_address, empty_witness, get_output_value, TestFramework,
+};
+use common::{
+    chain::{
+        block::timestamp::BlockTimestamp, config::Builder as ConfigBuilder,
+        output_value::OutputValue, signed_transaction::SignedTransaction, Block, Destination,
+        Transaction, TxInput, TxOutput,
+    },
+    primitives::{Amount, BlockHeight, Idable},
+};
+use crypto::key::{KeyKind, PrivateKey};
+use rstest::rstest;
+use test_utils::random::{make_seedable_rng, Seed};
+
+fn submit(tf: &mut TestFramework, block: Block) -> BlockSubmissionError {
+    let err = tf.process_block(block.clone(), BlockSource::Local).unwrap_err();
+    BlockSubmissionError::new(&block, &err)
+}
+
+fn check_header(
+    tf: &TestFramework,
+    block: &Block,
+) -> Result<BlockHeaderCheckInfo, BlockSubmissionError> {
+    tf.chainstate
+        .check_block_header(block.header())
+        .map_err(|err| BlockSubmissionError::for_header(&err))
+}
+
+fn assert_rejection(rejection: &BlockSubmissionError, category: BlockRejectionCategory) {
+    assert_eq!(rejection.category, category);
+    assert_eq!(rejection.hint, category.hint());
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn stale_parent(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        let missing_block = tf
+            .make_block_builder()
+            .add_test_transaction_from_best_block(&mut rng)
+            .build(&mut rng);
+        let block = tf
+            .make_block_builder()
+            .with_parent(missing_block.get_id().into())
+            .add_test_transaction_from_block(&missing_block, &mut rng)
+            .build(&mut rng);
+
+        let header_rejection = check_header(&tf, &block).unwrap_err();
+        assert_rejection(&header_rejection, BlockRejectionCategory::StaleParent);
+
+        let rejection = submit(&mut tf, block);
+        assert_rejection(&rejection, BlockRejectionCategory::StaleParent);
+        assert_eq!(rejection.tx_id, None);
+    });
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn duplicate(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        let block = tf
+            .make_block_builder()
+            .add_test_transaction_from_best_block(&mut rng)
+            .build(&mut rng);
+        tf.process_block(block.clone(), BlockSource::Local).unwrap();
+
+        let header_rejection = check_header(&tf, &block).unwrap_err();
+        assert_rejection(&header_rejection, BlockRejectionCategory::Duplicate);
+
+        let rejection = submit(&mut tf, block);
+        assert_rejection(&rejection, BlockRejectionCategory::Duplicate);
+        assert_eq!(rejection.tx_id, None);
+    });
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn invalid_consensus(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        // The block reward of a PoW block must be locked
+        let destination =
+            Destination::PublicKey(PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr).1);
+        let block = tf
+            .make_block_builder()
+            .with_reward(vec![TxOutput::Transfer(
+                OutputValue::Coin(Amount::from_atoms(10)),
+                destination,
+            )])
+            .add_test_transaction_from_best_block(&mut rng)
+            .build(&mut rng);
+
+        // The block reward is a part of the body, so the header alone is fine
+        assert!(check_header(&tf, &block).is_ok());
+
+        let rejection = submit(&mut tf, block);
+        assert_rejection(&rejection, BlockRejectionCategory::InvalidConsensus);
+        assert_eq!(rejection.tx_id, None);
+    });
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn invalid_transactions(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        let tx1 = SignedTransaction::new(
+            Transaction::new(
+                0,
+                vec![TxInput::from_utxo(tf.genesis().get_id().into(), 0)],
+                vec![TxOutput::Transfer(
+                    get_output_value(&tf.genesis().utxos()[0]).unwrap(),
+                    anyonecanspend_address(),
+                )],
+            )
+            .unwrap(),
+            vec![empty_witness(&mut rng)],
+        )
+        .unwrap();
+        let tx2 = SignedTransaction::new(
+            Transaction::new(
+                0,
+                vec![TxInput::from_utxo(tx1.transaction().get_id().into(), 0)],
+                vec![TxOutput::Transfer(
+                    get_output_value(&tx1.transaction().outputs()[0]).unwrap(),
+                    anyonecanspend_address(),
+                )],
+            )
+            .unwrap(),
+            vec![empty_witness(&mut rng)],
+        )
+        .unwrap();
+        let tx2_id = tx2.transaction().get_id();
+
+        // tx2 spends an output of tx1, which comes after it
+        let block = tf
+            .make_block_builder()
+            .add_transaction(tx2)
+            .add_transaction(tx1)
+            .build(&mut rng);
+
+        assert!(check_header(&tf, &block).is_ok());
+
+        let rejection = submit(&mut tf, block);
+        assert_rejection(&rejection, BlockRejectionCategory::InvalidTransactions);
+        assert_eq!(rejection.tx_id, Some(tx2_id));
+    });
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn timestamp(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        let max_future_offset =
+            tf.chain_config().max_future_block_time_offset(BlockHeight::zero()).as_secs();
+        let timestamp = BlockTimestamp::from_int_seconds(
+            tf.current_time().as_secs_since_epoch() + max_future_offset + 1,
+        );
+        let block = tf.make_block_builder().with_timestamp(timestamp).build(&mut rng);
+
+        let header_rejection = check_header(&tf, &block).unwrap_err();
+        assert_rejection(&header_rejection, BlockRejectionCategory::Timestamp);
+
+        let rejection = submit(&mut tf, block);
+        assert_rejection(&rejection, BlockRejectionCategory::Timestamp);
+        assert_eq!(rejection.tx_id, None);
+    });
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn size(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let chain_config = ConfigBuilder::test_chain().max_block_size_with_standard_txs(1).build();
+        let mut tf = TestFramework::builder(&mut rng).with_chain_config(chain_config).build();
+
+        let block = tf
+            .make_block_builder()
+            .add_test_transaction_from_best_block(&mut rng)
+            .build(&mut rng);
+
+        assert!(check_header(&tf, &block).is_ok());
+
+        let rejection = submit(&mut tf, block);
+        assert_rejection(&rejection, BlockRejectionCategory::Size);
+        assert_eq!(rejection.tx_id, None);
+    });
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn header_check_info(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        let block1 = tf
+            .make_block_builder()
+            .add_test_transaction_from_best_block(&mut rng)
+            .build(&mut rng);
+        let block1_id = block1.get_id();
+        assert_eq!(
+            check_header(&tf, &block1),
+            Ok(BlockHeaderCheckInfo {
+                block_id: block1_id,
+                height: BlockHeight::new(1),
+                extends_best_chain: true,
+            })
+        );
+        tf.process_block(block1, BlockSource::Local).unwrap();
+
+        // A sibling of the best block doesn't extend the best chain
+        let genesis_id = tf.genesis().get_id();
+        let block2 = tf
+            .make_block_builder()
+            .with_parent(genesis_id.into())
+            .add_test_transaction_with_parent(genesis_id.into(), &mut rng)
+            .build(&mut rng);
+        assert_eq!(
+            check_header(&tf, &block2),
+            Ok(BlockHeaderCheckInfo {
+                block_id: block2.get_id(),
+                height: BlockHeight::new(1),
+                extends_best_chain: false,
+            })
+        );
+
+        let block3 = tf
+            .make_block_builder()
+            .add_test_transaction_from_best_block(&mut rng)
+            .build(&mut rng);
+        assert_eq!(
+            check_header(&tf, &block3).map(|info| (info.height, info.extends_best_chain)),
+            Ok((BlockHeight::new(2), true))
+        );
+
+        // Passing the header check doesn't change anything
+        assert_eq!(tf.best_block_id(), block1_id.into());
+        tf.process_block(block3, BlockSource::Local).unwrap();
+    });
+}
@@ -0,0 +1,206 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chainstate::{
+    BlockError, ChainstateError, ConsensusInfo, PropertyQueryError, MAX_CONSENSUS_INFO_RANGE,
+};
+use chainstate_test_framework::TestFramework;
+use common::{
+    chain::{block::ConsensusData, RequiredConsensus},
+    primitives::BlockHeight,
+    Uint256,
+};
+use crypto::{
+    key::{KeyKind, PrivateKey},
+    vrf::{VRFKeyKind, VRFPrivateKey},
+};
+use randomness::Rng;
+use rstest::rstest;
+use test_utils::random::{make_seedable_rng, Seed};
+
+use super::helpers::pos::calculate_new_target;
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn consensus_info_matches_headers(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let (vrf_sk, vrf_pk) = VRFPrivateKey::new_from_rng(&mut rng, VRFKeyKind::Schnorrkel);
+        let (staking_sk, staking_pk) =
+            PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+
+        let (chain_config_builder, genesis_pool_id) =
+            chainstate_test_framework::create_chain_config_with_default_staking_pool(
+                &mut rng, staking_pk, vrf_pk,
+            );
+        let chain_config = chain_config_builder.build();
+
+        let target_block_time = chain_config.target_block_spacing();
+        let mut tf = TestFramework::builder(&mut rng).with_chain_config(chain_config).build();
+        tf.progress_time_seconds_since_epoch(target_block_time.as_secs());
+
+        let block_count = rng.gen_range(5..20);
+        for _ in 0..block_count {
+            tf.progress_time_seconds_since_epoch(rng.gen_range(1..100));
+            tf.make_pos_block_builder()
+                .with_stake_pool_id(genesis_pool_id)
+                .with_stake_spending_key(staking_sk.clone())
+                .with_vrf_key(vrf_sk.clone())
+                .build_and_process(&mut rng)
+                .unwrap();
+        }
+
+        // The whole chain; genesis is skipped
+        let info = tf
+            .chainstate
+            .get_consensus_info(BlockHeight::zero(), BlockHeight::new(block_count))
+            .unwrap();
+        assert_eq!(info.blocks.len(), block_count as usize);
+
+        for (block_info, height) in info.blocks.iter().zip(1..) {
+            assert_eq!(block_info.height, BlockHeight::new(height));
+
+            let block_id = tf.to_chain_block_id(&tf.block_id(height));
+            assert_eq!(block_info.block_id, block_id);
+
+            let block_index = tf.block_index(&block_id);
+            let prev_block_index = tf.gen_block_index(block_index.prev_block_id());
+            assert_eq!(block_info.timestamp, block_index.block_timestamp());
+            assert_eq!(
+                block_info.block_interval,
+                Some(
+                    block_index.block_timestamp().as_int_seconds()
+                        - prev_block_index.block_timestamp().as_int_seconds()
+                )
+            );
+
+            let compact_target = match block_index.block_header().consensus_data() {
+                ConsensusData::PoS(pos_data) => pos_data.compact_target(),
+                ConsensusData::None | ConsensusData::PoW(_) => panic!("PoS block expected"),
+            };
+            assert_eq!(block_info.compact_target, Some(compact_target.0));
+
+            let target_limit = match tf
+                .chain_config()
+                .consensus_upgrades()
+                .consensus_status(BlockHeight::new(height))
+            {
+                RequiredConsensus::PoS(pos_status) => pos_status.get_chain_config().target_limit(),
+                RequiredConsensus::PoW(_) | RequiredConsensus::IgnoreConsensus => {
+                    panic!("PoS consensus expected")
+                }
+            };
+            let target = Uint256::try_from(compact_target).unwrap();
+            let expected_difficulty = u128::try_from((target_limit / target).unwrap()).unwrap();
+            assert_eq!(block_info.difficulty, Some(expected_difficulty));
+        }
+
+        // The first PoS block and the one following it use the initial difficulty,
+        // the others are based on the averaged block times
+        assert!(info.blocks[..2].iter().all(|block| block.average_block_time.is_none()));
+        assert!(info.blocks[2..].iter().all(|block| block.average_block_time.is_some()));
+
+        let next_height = BlockHeight::new(block_count + 1);
+        assert_eq!(
+            info.next_block_target,
+            Some(calculate_new_target(&tf, next_height).unwrap().0)
+        );
+
+        // A random sub-range
+        let from = rng.gen_range(1..=block_count);
+        let to = rng.gen_range(from..=block_count);
+        let sub_info = tf
+            .chainstate
+            .get_consensus_info(BlockHeight::new(from), BlockHeight::new(to))
+            .unwrap();
+        assert_eq!(
+            sub_info,
+            ConsensusInfo {
+                blocks: info.blocks[(from - 1) as usize..to as usize].to_vec(),
+                next_block_target: info.next_block_target,
+            }
+        );
+
+        // Heights above the tip are ignored
+        let sub_info = tf
+            .chainstate
+            .get_consensus_info(
+                BlockHeight::new(block_count),
+                BlockHeight::new(block_count + 100),
+            )
+            .unwrap();
+        assert_eq!(sub_info.blocks, info.blocks[info.blocks.len() - 1..]);
+
+        // Only genesis
+        let sub_info = tf
+            .chainstate
+            .get_consensus_info(BlockHeight::zero(), BlockHeight::zero())
+            .unwrap();
+        assert_eq!(sub_info.blocks, vec![]);
+        assert_eq!(sub_info.next_block_target, info.next_block_target);
+    });
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn invalid_ranges(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+        tf.create_chain(&tf.genesis().get_id().into(), 2, &mut rng).unwrap();
+
+        // Not a PoS chain
+        let info = tf
+            .chainstate
+            .get_consensus_info(BlockHeight::zero(), BlockHeight::new(2))
+            .unwrap();
+        assert_eq!(info.blocks.len(), 2);
+        assert!(
+            info.blocks.iter().all(|block| block.compact_target.is_none()
+                && block.difficulty.is_none()
+                && block.average_block_time.is_none())
+        );
+        assert_eq!(info.next_block_target, None);
+
+        let from = BlockHeight::new(rng.gen_range(1..100));
+        let to = from.checked_add(MAX_CONSENSUS_INFO_RANGE as u64 - 1).unwrap();
+        assert!(tf.chainstate.get_consensus_info(from, to).is_ok());
+
+        let too_far = to.next_height();
+        assert_eq!(
+            tf.chainstate.get_consensus_info(from, too_far),
+            Err(ChainstateError::ProcessBlockError(
+                BlockError::PropertyQueryError(PropertyQueryError::BlockHeightRangeTooLarge {
+                    start: from,
+                    end: too_far,
+                    max_range: MAX_CONSENSUS_INFO_RANGE,
+                })
+            ))
+        );
+
+        let before = from.prev_height().unwrap();
+        assert_eq!(
+            tf.chainstate.get_consensus_info(from, before),
+            Err(ChainstateError::ProcessBlockError(
+                BlockError::PropertyQueryError(PropertyQueryError::InvalidBlockHeightRange {
+                    start: from,
+                    end: before,
+                })
+            ))
+        );
+    });
+}
@@ -0,0 +1,180 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chainstate::{
+    ChainstateError, DecodedInputSource, DecodedOutputValue, DecodedTxInput, DecodedTxOutput,
+    DecodedWitnessType, TransactionDecodingError,
+};
+use chainstate_test_framework::{TestFramework, TransactionBuilder};
+use common::{
+    address::{pubkeyhash::PublicKeyHash, Address},
+    chain::{
+        output_value::OutputValue,
+        signature::{
+            inputsig::{standard_signature::StandardInputSignature, InputWitness},
+            sighash::sighashtype::SigHashType,
+        },
+        timelock::OutputTimeLock,
+        Destination, GenBlock, OutPointSourceId, TxInput, TxOutput,
+    },
+    primitives::{Amount, Id, Idable, H256},
+};
+use randomness::Rng;
+use rstest::rstest;
+use serialization::{hex::HexEncode, Encode};
+use test_utils::random::{make_seedable_rng, Seed};
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn decode_transaction(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let tf = TestFramework::builder(&mut rng).build();
+        let chain_config = tf.chain_config().as_ref().clone();
+
+        let genesis_id: Id<GenBlock> = tf.genesis().get_id().into();
+        let genesis_output = tf.genesis().utxos()[0].clone();
+        let genesis_amount = match &genesis_output {
+            TxOutput::Transfer(OutputValue::Coin(amount), _) => *amount,
+            _ => panic!("coin transfer expected"),
+        };
+        let genesis_destination = match &genesis_output {
+            TxOutput::Transfer(_, destination) => destination.clone(),
+            _ => unreachable!(),
+        };
+
+        let unknown_tx_id = Id::new(H256::random_using(&mut rng));
+        let unknown_index = rng.gen_range(0..10);
+        let destination = Destination::PublicKeyHash(PublicKeyHash::random());
+        let transfer_amount = Amount::from_atoms(rng.gen_range(1..1_000_000));
+        let locked_amount = Amount::from_atoms(rng.gen_range(1..1_000_000));
+        let burn_amount = Amount::from_atoms(rng.gen_range(1..1_000_000));
+        let timelock = OutputTimeLock::ForBlockCount(rng.gen_range(1..100));
+        let sighash_type = SigHashType::try_from(SigHashType::ALL).unwrap();
+
+        let tx = TransactionBuilder::new()
+            .add_input(
+                TxInput::from_utxo(OutPointSourceId::BlockReward(genesis_id), 0),
+                InputWitness::NoSignature(None),
+            )
+            .add_input(
+                TxInput::from_utxo(OutPointSourceId::Transaction(unknown_tx_id), unknown_index),
+                InputWitness::Standard(StandardInputSignature::new(sighash_type, vec![1, 2, 3])),
+            )
+            .add_output(TxOutput::Transfer(
+                OutputValue::Coin(transfer_amount),
+                destination.clone(),
+            ))
+            .add_output(TxOutput::LockThenTransfer(
+                OutputValue::Coin(locked_amount),
+                destination.clone(),
+                timelock,
+            ))
+            .add_output(TxOutput::Burn(OutputValue::Coin(burn_amount)))
+            .build();
+
+        let decoded = tf.chainstate.decode_transaction(tx.hex_encode()).unwrap();
+
+        let encode_address =
+            |dest: &Destination| Address::new(&chain_config, dest.clone()).unwrap().into_string();
+
+        assert_eq!(decoded.tx_id, tx.transaction().get_id());
+        assert_eq!(decoded.version, tx.transaction().version_byte());
+        assert_eq!(decoded.flags, tx.transaction().flags());
+        assert_eq!(decoded.size_bytes, tx.encoded_size() as u64);
+
+        assert_eq!(
+            decoded.inputs,
+            vec![
+                DecodedTxInput {
+                    source: DecodedInputSource::BlockReward {
+                        block_id: genesis_id,
+                        index: 0,
+                    },
+                    witness_type: DecodedWitnessType::NoSignature,
+                    spent_output: Some(DecodedTxOutput {
+                        output_type: "Transfer".to_owned(),
+                        value: Some(DecodedOutputValue::Coin {
+                            amount: genesis_amount
+                        }),
+                        destination: Some(encode_address(&genesis_destination)),
+                        timelock: None,
+                    }),
+                },
+                // The output spent by this input doesn't exist
+                DecodedTxInput {
+                    source: DecodedInputSource::Transaction {
+                        tx_id: unknown_tx_id,
+                        index: unknown_index,
+                    },
+                    witness_type: DecodedWitnessType::Standard {
+                        sighash_type: SigHashType::ALL,
+                    },
+                    spent_output: None,
+                },
+            ]
+        );
+
+        assert_eq!(
+            decoded.outputs,
+            vec![
+                DecodedTxOutput {
+                    output_type: "Transfer".to_owned(),
+                    value: Some(DecodedOutputValue::Coin {
+                        amount: transfer_amount
+                    }),
+                    destination: Some(encode_address(&destination)),
+                    timelock: None,
+                },
+                DecodedTxOutput {
+                    output_type: "LockThenTransfer".to_owned(),
+                    value: Some(DecodedOutputValue::Coin {
+                        amount: locked_amount
+                    }),
+                    destination: Some(encode_address(&destination)),
+                    timelock: Some(timelock),
+                },
+                DecodedTxOutput {
+                    output_type: "Burn".to_owned(),
+                    value: Some(DecodedOutputValue::Coin {
+                        amount: burn_amount
+                    }),
+                    destination: None,
+                    timelock: None,
+                },
+            ]
+        );
+    });
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn decode_invalid_transaction(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let tf = TestFramework::builder(&mut rng).build();
+
+        for tx_hex in ["zz".to_owned(), "00".to_owned(), hex::encode([0xff; 10])] {
+            assert!(matches!(
+                tf.chainstate.decode_transaction(tx_hex),
+                Err(ChainstateError::TransactionDecodingError(
+                    TransactionDecodingError::InvalidHex(_)
+                ))
+            ));
+        }
+    });
+}
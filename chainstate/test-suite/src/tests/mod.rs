@@ -26,13 +26,19 @@ use randomness::Rng;
 use rstest::rstest;
 use test_utils::random::{make_seedable_rng, Seed};
 
+mod ancestors;
 mod basic_tests;
+mod block_fees;
 mod block_invalidation;
+mod block_size_histogram;
 mod block_status;
+mod block_submission;
 mod bootstrap;
 mod chainstate_accounting_storage_tests;
 mod chainstate_storage_tests;
+mod consensus_info;
 mod data_deposit;
+mod decode_transaction;
 mod delegation_tests;
 mod double_spend_tests;
 mod events_tests;
@@ -45,6 +51,8 @@ mod homomorphism;
 mod htlc;
 mod initialization;
 mod mempool_output_timelock;
+mod network_upgrades;
+mod next_block_timestamp;
 mod nft_burn;
 mod nft_issuance;
 mod nft_reorgs;
@@ -56,9 +64,11 @@ mod pos_maturity_settings;
 mod pos_processing_tests;
 mod pos_retargeting_tests;
 mod processing_tests;
+mod reindex;
 mod reorgs_tests;
 mod signature_tests;
 mod stake_pool_tests;
+mod storage_write_batching;
 mod syncing_tests;
 mod tx_fee;
 mod tx_verification_simulation;
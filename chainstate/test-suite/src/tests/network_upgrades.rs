@@ -0,0 +1,106 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use chainstate::{ChainstateConfig, NetworkUpgradeInfo};
+use chainstate_test_framework::TestFramework;
+use common::{
+    chain::{
+        config::{Builder, ChainType},
+        ChainConfig, ChainstateUpgrade, ChangeTokenMetadataUriActivated, DataDepositFeeVersion,
+        Destination, HtlcActivated, NetUpgrades, OrdersActivated, RewardDistributionVersion,
+        TokenIssuanceVersion, TokensFeeVersion,
+    },
+    primitives::BlockHeight,
+};
+use rstest::rstest;
+use test_utils::random::{make_seedable_rng, Seed};
+
+const ORDERS_FORK_HEIGHT: BlockHeight = BlockHeight::new(20);
+const WARNING_WINDOW: u64 = 5;
+
+fn make_chainstate_upgrade(orders_activated: OrdersActivated) -> ChainstateUpgrade {
+    ChainstateUpgrade::new(
+        TokenIssuanceVersion::V1,
+        RewardDistributionVersion::V1,
+        TokensFeeVersion::V1,
+        DataDepositFeeVersion::V1,
+        ChangeTokenMetadataUriActivated::Yes,
+        HtlcActivated::Yes,
+        orders_activated,
+    )
+}
+
+// A regtest config with an upgrade that is not in the default regtest schedule, so the node
+// doesn't know about it.
+fn make_chain_config() -> ChainConfig {
+    Builder::new(ChainType::Regtest)
+        .consensus_upgrades(NetUpgrades::unit_tests())
+        .chainstate_upgrades(
+            NetUpgrades::initialize(vec![
+                (
+                    BlockHeight::zero(),
+                    make_chainstate_upgrade(OrdersActivated::No),
+                ),
+                (
+                    ORDERS_FORK_HEIGHT,
+                    make_chainstate_upgrade(OrdersActivated::Yes),
+                ),
+            ])
+            .unwrap(),
+        )
+        .genesis_unittest(Destination::AnyoneCanSpend)
+        .checkpoints(BTreeMap::new())
+        .build()
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn update_recommended_before_unknown_upgrade(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng)
+            .with_chain_config(make_chain_config())
+            .with_chainstate_config(
+                ChainstateConfig::new().with_upgrade_warning_window(WARNING_WINDOW),
+            )
+            .build();
+
+        let fork_height = u64::from(ORDERS_FORK_HEIGHT);
+        for height in 0..fork_height + 2 {
+            let info = tf.chainstate.info().unwrap();
+            assert_eq!(info.best_block_height, BlockHeight::new(height));
+
+            let expected_update_recommended =
+                height < fork_height && fork_height - height <= WARNING_WINDOW;
+            assert_eq!(info.update_recommended, expected_update_recommended);
+
+            let upgrades = tf.chainstate.get_network_upgrades().unwrap();
+            assert_eq!(
+                upgrades.last().unwrap(),
+                &NetworkUpgradeInfo {
+                    name: "Chainstate: OrdersActivated::Yes".to_owned(),
+                    activation_height: ORDERS_FORK_HEIGHT,
+                    is_active: height >= fork_height,
+                    blocks_until_activation: fork_height.saturating_sub(height),
+                }
+            );
+
+            tf.create_chain(&tf.best_block_id(), 1, &mut rng).unwrap();
+        }
+    });
+}
@@ -0,0 +1,189 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use chainstate_test_framework::{
+    create_stake_pool_data_with_all_reward_to_staker, empty_witness, TestFramework,
+    TransactionBuilder,
+};
+use common::{
+    chain::{
+        block::timestamp::BlockTimestamp, config::Builder as ConfigBuilder, ConsensusUpgrade,
+        NetUpgrades, OutPointSourceId, PoSChainConfigBuilder, PoSConsensusVersion, TxOutput,
+        UtxoOutPoint,
+    },
+    primitives::{Amount, BlockHeight, Idable},
+    Uint256,
+};
+use crypto::vrf::{VRFKeyKind, VRFPrivateKey};
+use randomness::{CryptoRng, Rng};
+use rstest::rstest;
+use test_utils::random::{make_seedable_rng, Seed};
+
+/// Make a chain where the next block is the first PoS one, which has the specified target;
+/// if `pledge` is set, the current tip contains a stake pool with this pledge.
+fn make_pos_chain(
+    rng: &mut (impl Rng + CryptoRng),
+    target: Uint256,
+    consensus_version: PoSConsensusVersion,
+    pledge: Option<Amount>,
+) -> TestFramework {
+    let upgrades = vec![
+        (BlockHeight::new(0), ConsensusUpgrade::IgnoreConsensus),
+        (
+            BlockHeight::new(2),
+            ConsensusUpgrade::PoS {
+                initial_difficulty: Some(target.into()),
+                config: PoSChainConfigBuilder::new_for_unit_test()
+                    .consensus_version(consensus_version)
+                    .build(),
+            },
+        ),
+    ];
+    let chain_config = ConfigBuilder::test_chain()
+        .consensus_upgrades(NetUpgrades::initialize(upgrades).unwrap())
+        .build();
+    let mut tf = TestFramework::builder(rng).with_chain_config(chain_config).build();
+
+    let stake_pool_tx = pledge.map(|pledge| {
+        let genesis_outpoint = UtxoOutPoint::new(
+            OutPointSourceId::BlockReward(tf.genesis().get_id().into()),
+            0,
+        );
+        let pool_id = pos_accounting::make_pool_id(&genesis_outpoint);
+        let (vrf_sk, _) = VRFPrivateKey::new_from_rng(rng, VRFKeyKind::Schnorrkel);
+        let (stake_pool_data, _) =
+            create_stake_pool_data_with_all_reward_to_staker(rng, pledge, vrf_sk.to_public_key());
+        TransactionBuilder::new()
+            .add_input(genesis_outpoint.into(), empty_witness(rng))
+            .add_output(TxOutput::CreateStakePool(
+                pool_id,
+                Box::new(stake_pool_data),
+            ))
+            .build()
+    });
+    tf.make_block_builder()
+        .with_transactions(stake_pool_tx.into_iter().collect())
+        .build_and_process(rng)
+        .unwrap();
+
+    tf
+}
+
+fn estimated_delay(tf: &TestFramework) -> Duration {
+    let estimate = tf.chainstate.estimate_next_block_timestamp().unwrap();
+    estimate.as_duration_since_epoch() - tf.current_time().as_duration_since_epoch()
+}
+
+// Targets are small enough for the delays not to be clamped to the minimum of 1 second.
+// Compact targets are lossy, so use the value that the chain will actually have.
+fn make_target(rng: &mut impl Rng) -> Uint256 {
+    let target = Uint256::ONE << rng.gen_range(170..185);
+    consensus::compact_target_to_target(target.into()).unwrap()
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn estimate_is_proportional_to_stake(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let target = make_target(&mut rng);
+
+        let min_pledge = ConfigBuilder::test_chain().build().min_stake_pool_pledge();
+        let pledge = (min_pledge * rng.gen_range(1..5)).unwrap();
+        let factor = rng.gen_range(2..5);
+        let bigger_pledge = (pledge * factor).unwrap();
+
+        let tf = make_pos_chain(&mut rng, target, PoSConsensusVersion::V0, Some(pledge));
+        let delay = estimated_delay(&tf);
+        assert_eq!(Some(delay), consensus::expected_block_time(&target, pledge));
+
+        let tf = make_pos_chain(
+            &mut rng,
+            target,
+            PoSConsensusVersion::V0,
+            Some(bigger_pledge),
+        );
+        let shorter_delay = estimated_delay(&tf);
+        let factor = factor as u32;
+        assert!(shorter_delay * factor <= delay);
+        assert!(delay < (shorter_delay + Duration::from_secs(1)) * factor);
+
+        // A bigger target means an easier difficulty
+        let tf = make_pos_chain(
+            &mut rng,
+            (target * Uint256::from_u64(2)).unwrap(),
+            PoSConsensusVersion::V0,
+            Some(pledge),
+        );
+        let easier_delay = estimated_delay(&tf);
+        assert!(easier_delay * 2 <= delay && delay < (easier_delay + Duration::from_secs(1)) * 2);
+    });
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn estimate_uses_effective_balance(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let target = make_target(&mut rng);
+
+        let chain_config = ConfigBuilder::test_chain().build();
+        let pledge = (chain_config.min_stake_pool_pledge() * rng.gen_range(1..5)).unwrap();
+        let effective_balance = consensus::calculate_effective_pool_balance(
+            pledge,
+            pledge,
+            chain_config.final_supply().unwrap().to_amount_atoms(),
+        )
+        .unwrap();
+
+        let tf = make_pos_chain(&mut rng, target, PoSConsensusVersion::V1, Some(pledge));
+        assert_eq!(
+            Some(estimated_delay(&tf)),
+            consensus::expected_block_time(&target, effective_balance)
+        );
+    });
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn fallback_to_target_block_spacing(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+
+        // Not a PoS chain
+        let mut tf = TestFramework::builder(&mut rng).build();
+        tf.create_chain(&tf.genesis().get_id().into(), 2, &mut rng).unwrap();
+        let target_block_spacing = tf.chain_config().target_block_spacing();
+        assert_eq!(
+            tf.chainstate.estimate_next_block_timestamp().unwrap(),
+            BlockTimestamp::from_time(
+                tf.current_time().saturating_duration_add(target_block_spacing)
+            )
+        );
+
+        // A PoS chain without pools
+        let target = make_target(&mut rng);
+        let tf = make_pos_chain(&mut rng, target, PoSConsensusVersion::V1, None);
+        assert_eq!(
+            estimated_delay(&tf),
+            tf.chain_config().target_block_spacing()
+        );
+    });
+}
@@ -0,0 +1,178 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chainstate::{reindex_from_storage, BlockSource, ChainstateError, ReindexError};
+use chainstate_storage::{BlockchainStorageWrite, TransactionRw, Transactional};
+use chainstate_test_framework::{TestFramework, TestStore, TransactionBuilder};
+use common::{
+    chain::{
+        output_value::OutputValue, signature::inputsig::InputWitness, Destination,
+        OutPointSourceId, TxInput, TxOutput,
+    },
+    primitives::{Amount, Id, Idable, H256},
+};
+use randomness::Rng;
+use rstest::rstest;
+use test_utils::random::{make_seedable_rng, Seed};
+use utxo::UtxosStorageWrite;
+
+// Build a chain with transactions and a stale branch.
+fn make_chain(tf: &mut TestFramework, rng: &mut (impl Rng + randomness::CryptoRng)) {
+    let genesis_id = tf.genesis().get_id();
+
+    for _ in 0..rng.gen_range(5..15) {
+        tf.make_block_builder()
+            .add_test_transaction_from_best_block(rng)
+            .build_and_process(rng)
+            .unwrap();
+    }
+
+    tf.create_chain(&genesis_id.into(), 2, rng).unwrap();
+}
+
+// Build a chain, delete an entry from the utxo set directly in the storage, reindex the storage
+// into a fresh chainstate; the tip and the utxo set must be restored.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn reindex_restores_derived_state(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+        make_chain(&mut tf, &mut rng);
+
+        let best_block_id = tf.best_block_id();
+        let utxo_set = tf.storage.transaction_ro().unwrap().read_utxo_set().unwrap();
+
+        let storage: TestStore = tf.storage;
+        let mut db_tx = storage.transaction_rw(None).unwrap();
+        let (outpoint, _) = utxo_set.first_key_value().unwrap();
+        db_tx.del_utxo(outpoint).unwrap();
+        db_tx.commit().unwrap();
+        assert_ne!(
+            storage.transaction_ro().unwrap().read_utxo_set().unwrap(),
+            utxo_set
+        );
+
+        let mut tf = TestFramework::builder(&mut rng).build();
+        reindex_from_storage(&storage, tf.chainstate.as_mut()).unwrap();
+
+        assert_eq!(tf.best_block_id(), best_block_id);
+        assert_eq!(
+            tf.storage.transaction_ro().unwrap().read_utxo_set().unwrap(),
+            utxo_set
+        );
+    });
+}
+
+// A stored block on the best chain that fails validation stops the reindex, the error names
+// the block.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn reindex_stops_on_invalid_block(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+        make_chain(&mut tf, &mut rng);
+
+        let tx = TransactionBuilder::new()
+            .add_input(
+                TxInput::from_utxo(
+                    OutPointSourceId::Transaction(Id::new(H256::random_using(&mut rng))),
+                    0,
+                ),
+                InputWitness::NoSignature(None),
+            )
+            .add_output(TxOutput::Transfer(
+                OutputValue::Coin(Amount::from_atoms(1)),
+                Destination::AnyoneCanSpend,
+            ))
+            .build();
+        let bad_block = tf.make_block_builder().add_transaction(tx).build(&mut rng);
+        let bad_block_id = bad_block.get_id();
+
+        let storage: TestStore = tf.storage;
+        let mut db_tx = storage.transaction_rw(None).unwrap();
+        db_tx.add_block(&bad_block).unwrap();
+        db_tx.set_best_block_id(&bad_block_id.into()).unwrap();
+        db_tx.commit().unwrap();
+
+        let mut tf = TestFramework::builder(&mut rng).build();
+        let err = reindex_from_storage(&storage, tf.chainstate.as_mut()).unwrap_err();
+
+        assert!(matches!(
+            err,
+            ReindexError::BlockProcessing(block_id, err)
+                if block_id == bad_block_id
+                    && matches!(*err, ChainstateError::ProcessBlockError(_))
+        ));
+    });
+}
+
+// A stale branch that was found invalid during a reorg stays in the storage; it's skipped during
+// the reindex along with its descendants.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn reindex_skips_invalid_stale_branch(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+        make_chain(&mut tf, &mut rng);
+
+        let best_block_id = tf.best_block_id();
+        let best_block_height = tf.best_block_index().block_height().into_int() as usize;
+
+        // The bad block spends a non-existent utxo; it passes the checks as a side-branch block,
+        // but fails to connect once its branch becomes the heaviest one.
+        let tx = TransactionBuilder::new()
+            .add_input(
+                TxInput::from_utxo(
+                    OutPointSourceId::Transaction(Id::new(H256::random_using(&mut rng))),
+                    0,
+                ),
+                InputWitness::NoSignature(None),
+            )
+            .add_output(TxOutput::Transfer(
+                OutputValue::Coin(Amount::from_atoms(1)),
+                Destination::AnyoneCanSpend,
+            ))
+            .build();
+        let bad_block = tf
+            .make_block_builder()
+            .with_parent(tf.genesis().get_id().into())
+            .add_transaction(tx)
+            .build(&mut rng);
+        let bad_block_id = bad_block.get_id();
+        tf.process_block(bad_block, BlockSource::Local).unwrap();
+
+        // Extend the stale branch until it's heavier than the main chain, the reorg fails
+        tf.create_chain(&bad_block_id.into(), best_block_height, &mut rng).unwrap_err();
+        assert_eq!(tf.best_block_id(), best_block_id);
+        assert!(!tf.block_index(&bad_block_id).status().is_ok());
+        let utxo_set = tf.storage.transaction_ro().unwrap().read_utxo_set().unwrap();
+
+        let storage: TestStore = tf.storage;
+        let mut tf = TestFramework::builder(&mut rng).build();
+        reindex_from_storage(&storage, tf.chainstate.as_mut()).unwrap();
+
+        assert_eq!(tf.best_block_id(), best_block_id);
+        assert_eq!(
+            tf.storage.transaction_ro().unwrap().read_utxo_set().unwrap(),
+            utxo_set
+        );
+    });
+}
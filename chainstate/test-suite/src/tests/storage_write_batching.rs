@@ -0,0 +1,296 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use chainstate::{
+    make_chainstate, ChainstateConfig, ChainstateError, ChainstateSubsystem,
+    DefaultTransactionVerificationStrategy, InitializationError, StorageSyncPolicy,
+};
+use chainstate_storage::{
+    BlockchainStorageRead, BlockchainStorageWrite, Store, TransactionRw, Transactional,
+};
+use common::{
+    chain::{config::create_unit_test_config, ChainConfig},
+    primitives::Idable,
+    time_getter::TimeGetter,
+};
+use storage_core::{adaptor::WriteBatching, backend::Backend, DbDesc};
+use storage_inmemory::InMemory;
+use test_utils::mock_time_getter::mocked_time_getter_seconds;
+use utils::atomics::SeqCstAtomicU64;
+
+/// In-memory backend that can be opened again after the chainstate using it has "crashed".
+///
+/// Only the changes written out to the in-memory database survive, the changes batched on top
+/// of it are lost with the chainstate.
+#[derive(Clone, Default)]
+struct ReopenableInMemory(Arc<Mutex<Option<<InMemory as Backend>::Impl>>>);
+
+impl Backend for ReopenableInMemory {
+    type Impl = <InMemory as Backend>::Impl;
+
+    fn open(self, desc: DbDesc) -> storage_core::Result<Self::Impl> {
+        let mut db = self.0.lock().unwrap();
+        if db.is_none() {
+            *db = Some(InMemory::new().open(desc)?);
+        }
+        Ok(db.as_ref().expect("db to be open").clone())
+    }
+}
+
+/// Create a chain of the given length and return its blocks, ordered by height
+fn make_blocks(rng: &mut (impl Rng + randomness::CryptoRng), count: usize) -> Vec<Block> {
+    let mut tf = TestFramework::builder(rng).build();
+    tf.create_chain(&tf.genesis().get_id().into(), count, rng).unwrap();
+    (1..=count as u64)
+        .map(|height| tf.block(tf.to_chain_block_id(&tf.block_id(height))))
+        .collect()
+}
+
+fn try_open_chainstate(
+    chain_config: &ChainConfig,
+    chainstate_config: ChainstateConfig,
+    backend: &ReopenableInMemory,
+    time_getter: TimeGetter,
+) -> Result<ChainstateSubsystem, ChainstateError> {
+    let storage = Store::from_backend(WriteBatching::new(backend.clone())).unwrap();
+    make_chainstate(
+        Arc::new(chain_config.clone()),
+        chainstate_config.with_heavy_checks_enabled(true),
+        storage,
+        DefaultTransactionVerificationStrategy::new(),
+        None,
+        time_getter,
+    )
+}
+
+fn open_chainstate(
+    chain_config: &ChainConfig,
+    chainstate_config: ChainstateConfig,
+    backend: &ReopenableInMemory,
+    time_getter: TimeGetter,
+) -> ChainstateSubsystem {
+    try_open_chainstate(chain_config, chainstate_config, backend, time_getter).unwrap()
+}
+
+fn clean_block_height(backend: &ReopenableInMemory) -> Option<BlockHeight> {
+    let storage = Store::from_backend(backend.clone()).unwrap();
+    let clean_height = storage.transaction_ro().unwrap().get_clean_block_height().unwrap();
+    clean_height
+}
+
+// Blocks batched since the last flush are lost in a crash, the chainstate comes back at the
+// height of the last flush
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn crash_loses_unflushed_batch(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = create_unit_test_config();
+    let chainstate_config = ChainstateConfig::new()
+        .with_ibd_write_batching(true)
+        .with_max_write_batch_blocks(5)
+        .with_storage_sync_policy(StorageSyncPolicy::OnShutdown);
+    let blocks = make_blocks(&mut rng, 30);
+    let processed_count = rng.gen_range(1..blocks.len());
+
+    let backend = ReopenableInMemory::default();
+    let mut chainstate = open_chainstate(
+        &chain_config,
+        chainstate_config.clone(),
+        &backend,
+        TimeGetter::default(),
+    );
+    assert!(chainstate.is_initial_block_download());
+    for block in &blocks[..processed_count] {
+        chainstate.process_block(block.clone(), BlockSource::Local).unwrap();
+    }
+    assert_eq!(
+        chainstate.get_best_block_height().unwrap(),
+        BlockHeight::new(processed_count as u64)
+    );
+
+    // Simulate a crash, the batched changes are never written out
+    std::mem::forget(chainstate);
+
+    let clean_height = BlockHeight::new((processed_count / 5 * 5) as u64);
+    assert_eq!(clean_block_height(&backend), Some(clean_height));
+
+    let mut chainstate = open_chainstate(
+        &chain_config,
+        chainstate_config,
+        &backend,
+        TimeGetter::default(),
+    );
+    assert_eq!(chainstate.get_best_block_height().unwrap(), clean_height);
+
+    // The lost blocks can be processed again
+    for block in &blocks[clean_height.into_int() as usize..] {
+        chainstate.process_block(block.clone(), BlockSource::Local).unwrap();
+    }
+    assert_eq!(
+        chainstate.get_best_block_id().unwrap(),
+        blocks.last().unwrap().get_id().into()
+    );
+}
+
+// Without batching, the blocks above the clean height survive a crash but are re-validated
+// on the next startup
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn blocks_above_clean_height_revalidated(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = create_unit_test_config();
+    let chainstate_config =
+        ChainstateConfig::new().with_storage_sync_policy(StorageSyncPolicy::OnShutdown);
+    let block_count = rng.gen_range(1..20);
+    let blocks = make_blocks(&mut rng, block_count);
+
+    let backend = ReopenableInMemory::default();
+    let mut chainstate = open_chainstate(
+        &chain_config,
+        chainstate_config.clone(),
+        &backend,
+        TimeGetter::default(),
+    );
+    for block in &blocks {
+        chainstate.process_block(block.clone(), BlockSource::Local).unwrap();
+    }
+    std::mem::forget(chainstate);
+
+    assert_eq!(clean_block_height(&backend), Some(BlockHeight::zero()));
+
+    let chainstate = open_chainstate(
+        &chain_config,
+        chainstate_config,
+        &backend,
+        TimeGetter::default(),
+    );
+    let tip_height = BlockHeight::new(blocks.len() as u64);
+    assert_eq!(chainstate.get_best_block_height().unwrap(), tip_height);
+    assert_eq!(
+        chainstate.get_best_block_id().unwrap(),
+        blocks.last().unwrap().get_id().into()
+    );
+
+    // The re-validated tip is now clean
+    assert_eq!(clean_block_height(&backend), Some(tip_height));
+}
+
+// An inconsistent storage, where the height of the last flush has no mainchain block, results
+// in an initialization error instead of a panic
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn clean_height_without_mainchain_block(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = create_unit_test_config();
+    let chainstate_config =
+        ChainstateConfig::new().with_storage_sync_policy(StorageSyncPolicy::OnShutdown);
+    let block_count = rng.gen_range(2..20);
+    let blocks = make_blocks(&mut rng, block_count);
+
+    let backend = ReopenableInMemory::default();
+    let mut chainstate = open_chainstate(
+        &chain_config,
+        chainstate_config.clone(),
+        &backend,
+        TimeGetter::default(),
+    );
+    for block in &blocks {
+        chainstate.process_block(block.clone(), BlockSource::Local).unwrap();
+    }
+    std::mem::forget(chainstate);
+
+    // Point the clean height marker to a height whose mainchain entry is missing
+    let clean_height = BlockHeight::new(rng.gen_range(1..block_count) as u64);
+    {
+        let storage = Store::from_backend(backend.clone()).unwrap();
+        let mut db_tx = storage.transaction_rw(None).unwrap();
+        db_tx.set_clean_block_height(clean_height).unwrap();
+        db_tx.del_block_id_at_height(&clean_height).unwrap();
+        db_tx.commit().unwrap();
+    }
+
+    let result = try_open_chainstate(
+        &chain_config,
+        chainstate_config,
+        &backend,
+        TimeGetter::default(),
+    );
+    assert_eq!(
+        result.err(),
+        Some(ChainstateError::FailedToInitializeChainstate(
+            InitializationError::CleanBlockMissing(clean_height, blocks.last().unwrap().get_id())
+        ))
+    );
+}
+
+// Once the initial block download is finished, the batch is written out and the following
+// blocks are not batched anymore
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn batching_disabled_after_initial_block_download(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = create_unit_test_config();
+    let chainstate_config = ChainstateConfig::new()
+        .with_ibd_write_batching(true)
+        .with_storage_sync_policy(StorageSyncPolicy::OnShutdown);
+    let blocks = make_blocks(&mut rng, 20);
+    let ibd_end_count = rng.gen_range(1..blocks.len());
+
+    // The blocks are too old to end the initial block download until the time is turned back
+    let genesis_time = chain_config.genesis_block().timestamp().as_int_seconds();
+    let time_value = Arc::new(SeqCstAtomicU64::new(genesis_time + 30 * 24 * 60 * 60));
+    let time_getter = mocked_time_getter_seconds(Arc::clone(&time_value));
+
+    let backend = ReopenableInMemory::default();
+    let mut chainstate = open_chainstate(
+        &chain_config,
+        chainstate_config.clone(),
+        &backend,
+        time_getter,
+    );
+    for block in &blocks[..ibd_end_count - 1] {
+        chainstate.process_block(block.clone(), BlockSource::Local).unwrap();
+    }
+    assert!(chainstate.is_initial_block_download());
+    assert_eq!(clean_block_height(&backend), Some(BlockHeight::zero()));
+
+    time_value.store(genesis_time);
+    for block in &blocks[ibd_end_count - 1..] {
+        chainstate.process_block(block.clone(), BlockSource::Local).unwrap();
+    }
+    assert!(!chainstate.is_initial_block_download());
+    assert_eq!(
+        clean_block_height(&backend),
+        Some(BlockHeight::new(ibd_end_count as u64))
+    );
+
+    std::mem::forget(chainstate);
+
+    let chainstate = open_chainstate(
+        &chain_config,
+        chainstate_config,
+        &backend,
+        TimeGetter::default(),
+    );
+    assert_eq!(
+        chainstate.get_best_block_id().unwrap(),
+        blocks.last().unwrap().get_id().into()
+    );
+}
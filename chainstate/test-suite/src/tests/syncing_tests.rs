@@ -640,6 +640,12 @@ fn initial_block_download(#[case] seed: Seed) {
                 min_max_bootstrap_import_buffer_sizes: Default::default(),
                 max_tip_age: Duration::from_secs(1).into(),
                 enable_heavy_checks: Some(true),
+                max_block_size_histogram_range: Default::default(),
+                ibd_write_batching: Default::default(),
+                max_write_batch_blocks: Default::default(),
+                max_write_batch_size: Default::default(),
+                storage_sync_policy: Default::default(),
+                upgrade_warning_window: Default::default(),
             })
             .with_initial_time_since_genesis(2)
             .build();
@@ -84,3 +84,87 @@ pub fn combine<T: Debug + PartialEq>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn any_operation() -> impl Strategy<Value = CachedOperation<u64>> {
+        prop_oneof![
+            any::<u64>().prop_map(CachedOperation::Write),
+            any::<u64>().prop_map(CachedOperation::Read),
+            Just(CachedOperation::Erase),
+        ]
+    }
+
+    // Turn random operations into a sequence that can happen on a single key: nothing is read
+    // after the data has been modified or erased and the data is never overwritten with
+    // a different value without being erased first. `None` means that the key wasn't touched.
+    fn valid_sequence() -> impl Strategy<Value = Vec<Option<CachedOperation<u64>>>> {
+        prop::collection::vec(prop::option::of(any_operation()), 0..20).prop_map(|ops| {
+            let mut last_modification: Option<CachedOperation<u64>> = None;
+            ops.into_iter()
+                .map(|op| {
+                    let op = match (op?, &last_modification) {
+                        (op, None) => op,
+                        (CachedOperation::Read(_), Some(CachedOperation::Write(v))) => {
+                            CachedOperation::Write(*v)
+                        }
+                        (CachedOperation::Read(_), Some(_)) => CachedOperation::Erase,
+                        (CachedOperation::Write(_), Some(CachedOperation::Write(v))) => {
+                            CachedOperation::Write(*v)
+                        }
+                        (op, Some(_)) => op,
+                    };
+                    if !matches!(op, CachedOperation::Read(_)) {
+                        last_modification = Some(op.clone());
+                    }
+                    Some(op)
+                })
+                .collect()
+        })
+    }
+
+    // The expected outcome of a valid sequence: the last modification if there is one,
+    // otherwise the last read.
+    fn expected_result(ops: &[Option<CachedOperation<u64>>]) -> Option<CachedOperation<u64>> {
+        let ops = ops.iter().flatten();
+        ops.clone()
+            .filter(|op| !matches!(op, CachedOperation::Read(_)))
+            .last()
+            .or_else(|| ops.last())
+            .cloned()
+    }
+
+    proptest! {
+        #[test]
+        fn combine_with_none(op in any_operation()) {
+            prop_assert_eq!(combine(Some(op.clone()), None), Some(op.clone()));
+            prop_assert_eq!(combine(None, Some(op.clone())), Some(op));
+            prop_assert_eq!(combine::<u64>(None, None), None);
+        }
+
+        #[test]
+        fn combine_valid_sequence(ops in valid_sequence()) {
+            let folded = ops.iter().cloned().fold(None, combine);
+            prop_assert_eq!(&folded, &expected_result(&ops));
+
+            // Applying the operations one by one gives the same result as the fold
+            let mut result = None;
+            for op in ops.iter().cloned() {
+                result = combine(result, op);
+            }
+            prop_assert_eq!(result, folded);
+        }
+
+        #[test]
+        fn combine_valid_pair(ops in valid_sequence().prop_filter("need 2 operations", |ops| ops.len() >= 2)) {
+            // Any two consecutive operations of a valid sequence can be combined on their own
+            for pair in ops.windows(2) {
+                let combined = combine(pair[0].clone(), pair[1].clone());
+                prop_assert_eq!(combined, expected_result(pair));
+            }
+        }
+    }
+}
@@ -60,27 +60,24 @@ pub fn calculate_tokens_burned_in_outputs(
     tx: &Transaction,
     token_id: &TokenId,
 ) -> Result<Amount, ConnectTransactionError> {
-    tx.outputs()
-        .iter()
-        .filter_map(|output| match output {
-            TxOutput::Burn(output_value) => match output_value {
-                OutputValue::Coin(_) | OutputValue::TokenV0(_) => None,
-                OutputValue::TokenV1(id, amount) => (id == token_id).then_some(*amount),
-            },
-            TxOutput::Transfer(_, _)
-            | TxOutput::LockThenTransfer(_, _, _)
-            | TxOutput::CreateStakePool(_, _)
-            | TxOutput::ProduceBlockFromStake(_, _)
-            | TxOutput::CreateDelegationId(_, _)
-            | TxOutput::DelegateStaking(_, _)
-            | TxOutput::IssueFungibleToken(_)
-            | TxOutput::IssueNft(_, _, _)
-            | TxOutput::DataDeposit(_)
-            | TxOutput::Htlc(_, _)
-            | TxOutput::AnyoneCanTake(_) => None,
-        })
-        .sum::<Option<Amount>>()
-        .ok_or(ConnectTransactionError::BurnAmountSumError(tx.get_id()))
+    let burned = tx.outputs().iter().filter_map(|output| match output {
+        TxOutput::Burn(output_value) => match output_value {
+            OutputValue::Coin(_) | OutputValue::TokenV0(_) => None,
+            OutputValue::TokenV1(id, amount) => (id == token_id).then_some(*amount),
+        },
+        TxOutput::Transfer(_, _)
+        | TxOutput::LockThenTransfer(_, _, _)
+        | TxOutput::CreateStakePool(_, _)
+        | TxOutput::ProduceBlockFromStake(_, _)
+        | TxOutput::CreateDelegationId(_, _)
+        | TxOutput::DelegateStaking(_, _)
+        | TxOutput::IssueFungibleToken(_)
+        | TxOutput::IssueNft(_, _, _)
+        | TxOutput::DataDeposit(_)
+        | TxOutput::Htlc(_, _)
+        | TxOutput::AnyoneCanTake(_) => None,
+    });
+    Amount::sum_checked(burned).ok_or(ConnectTransactionError::BurnAmountSumError(tx.get_id()))
 }
 
 pub fn check_reward_inputs_outputs_policy(
@@ -219,24 +216,21 @@ fn check_issuance_fee_burn_v0(
     // Check if the fee is enough for issuance
     let issuance_count = get_tokens_issuance_count(tx.outputs());
     if issuance_count > 0 {
-        let total_burned = tx
-            .outputs()
-            .iter()
-            .filter_map(|output| match output {
-                TxOutput::Burn(v) => v.coin_amount(),
-                TxOutput::Transfer(_, _)
-                | TxOutput::LockThenTransfer(_, _, _)
-                | TxOutput::CreateStakePool(_, _)
-                | TxOutput::ProduceBlockFromStake(_, _)
-                | TxOutput::CreateDelegationId(_, _)
-                | TxOutput::IssueFungibleToken(_)
-                | TxOutput::IssueNft(_, _, _)
-                | TxOutput::DataDeposit(_)
-                | TxOutput::DelegateStaking(_, _)
-                | TxOutput::Htlc(_, _)
-                | TxOutput::AnyoneCanTake(_) => None,
-            })
-            .sum::<Option<Amount>>()
+        let burned = tx.outputs().iter().filter_map(|output| match output {
+            TxOutput::Burn(v) => v.coin_amount(),
+            TxOutput::Transfer(_, _)
+            | TxOutput::LockThenTransfer(_, _, _)
+            | TxOutput::CreateStakePool(_, _)
+            | TxOutput::ProduceBlockFromStake(_, _)
+            | TxOutput::CreateDelegationId(_, _)
+            | TxOutput::IssueFungibleToken(_)
+            | TxOutput::IssueNft(_, _, _)
+            | TxOutput::DataDeposit(_)
+            | TxOutput::DelegateStaking(_, _)
+            | TxOutput::Htlc(_, _)
+            | TxOutput::AnyoneCanTake(_) => None,
+        });
+        let total_burned = Amount::sum_checked(burned)
             .ok_or_else(|| ConnectTransactionError::BurnAmountSumError(tx.get_id()))?;
 
         if total_burned < chain_config.fungible_token_issuance_fee() {
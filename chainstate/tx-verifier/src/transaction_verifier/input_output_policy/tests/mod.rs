@@ -25,6 +25,8 @@ use common::{
 use crypto::vrf::{transcript::no_rng::VRFTranscript, VRFKeyKind, VRFPrivateKey};
 use itertools::Itertools;
 use randomness::{seq::IteratorRandom, Rng};
+use rstest::rstest;
+use test_utils::random::{make_seedable_rng, Seed};
 use utxo::{Utxo, UtxosDBInMemoryImpl};
 
 use super::*;
@@ -141,3 +143,24 @@ fn prepare_utxos_and_tx_with_random_combinations(
 
     prepare_utxos_and_tx(rng, input_utxos, outputs)
 }
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn tokens_burned_sum_overflow(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let token_id = TokenId::new(H256::random_using(&mut rng));
+    let burn = |atoms| TxOutput::Burn(OutputValue::TokenV1(token_id, Amount::from_atoms(atoms)));
+
+    let tx = Transaction::new(0, vec![], vec![burn(u128::MAX - 1), burn(1)]).unwrap();
+    assert_eq!(
+        calculate_tokens_burned_in_outputs(&tx, &token_id),
+        Ok(Amount::MAX)
+    );
+
+    let tx = Transaction::new(0, vec![], vec![burn(u128::MAX), burn(1)]).unwrap();
+    assert_eq!(
+        calculate_tokens_burned_in_outputs(&tx, &token_id),
+        Err(ConnectTransactionError::BurnAmountSumError(tx.get_id()))
+    );
+}
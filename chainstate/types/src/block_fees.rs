@@ -0,0 +1,67 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use common::{
+    chain::Transaction,
+    primitives::{Amount, Id},
+};
+use serialization::{Decode, Encode};
+
+/// The coin fees paid by the transactions of a block, as computed when the block was connected.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Encode,
+    Decode,
+    serde::Serialize,
+    serde::Deserialize,
+    rpc_description::HasValueHint,
+)]
+pub struct BlockFees {
+    /// The total fee, which is added to the block reward
+    total_fees: Amount,
+    /// The fee of each transaction, in the order of the transactions in the block
+    tx_fees: Vec<(Id<Transaction>, Amount)>,
+}
+
+impl BlockFees {
+    pub fn new(total_fees: Amount, tx_fees: Vec<(Id<Transaction>, Amount)>) -> Self {
+        Self {
+            total_fees,
+            tx_fees,
+        }
+    }
+
+    pub fn total_fees(&self) -> Amount {
+        self.total_fees
+    }
+
+    pub fn tx_fees(&self) -> &[(Id<Transaction>, Amount)] {
+        &self.tx_fees
+    }
+}
+
+/// The fees of a mainchain block as known to the chainstate
+#[derive(
+    Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint,
+)]
+#[serde(tag = "type", content = "content")]
+pub enum BlockFeesInfo {
+    /// The fees recorded when the block was connected
+    Recorded(BlockFees),
+    /// The block was connected before the fees started being recorded
+    NotRecorded,
+}
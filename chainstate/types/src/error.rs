@@ -56,6 +56,14 @@ pub enum PropertyQueryError {
         start: BlockHeight,
         end: BlockHeight,
     },
+    #[error("Block height range {start}..={end} exceeds the maximum of {max_range} blocks")]
+    BlockHeightRangeTooLarge {
+        start: BlockHeight,
+        end: BlockHeight,
+        max_range: usize,
+    },
+    #[error("Ancestors depth {depth} exceeds the maximum of {max_depth}")]
+    AncestorsDepthTooLarge { depth: u32, max_depth: u32 },
 }
 
 #[derive(Error, Debug, PartialEq, Eq, Clone)]
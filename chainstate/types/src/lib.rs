@@ -20,6 +20,7 @@ pub mod vrf_tools;
 pub use crate::{
     ancestor::block_index_ancestor_getter,
     ancestor::gen_block_index_getter,
+    block_fees::{BlockFees, BlockFeesInfo},
     block_index::BlockIndex,
     block_index_handle::BlockIndexHandle,
     block_index_history_iter::BlockIndexHistoryIterator,
@@ -36,6 +37,7 @@ pub use crate::{
 };
 
 mod ancestor;
+mod block_fees;
 mod block_index;
 mod block_index_handle;
 mod block_index_history_iter;
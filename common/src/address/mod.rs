@@ -15,6 +15,7 @@
 
 pub mod dehexify;
 pub mod hexified;
+pub mod payment_uri;
 pub mod pubkeyhash;
 pub mod rpc;
 pub mod traits;
@@ -0,0 +1,350 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Payment URIs, modeled after BIP-21:
+//!
+//! `mintlayer:<address>[?amount=<decimal amount>][&label=<label>][&message=<message>]`
+//!
+//! The label and the message are percent-encoded. Unknown parameters are ignored, unless their
+//! name starts with `req-`, in which case the URI is rejected.
+
+use std::fmt::Display;
+
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+use crate::{
+    chain::{ChainConfig, Destination},
+    primitives::{amount::DecimalAmount, Amount},
+};
+
+use super::{Address, AddressError};
+
+pub const PAYMENT_URI_SCHEME: &str = "mintlayer";
+
+const AMOUNT_PARAM: &str = "amount";
+const LABEL_PARAM: &str = "label";
+const MESSAGE_PARAM: &str = "message";
+
+/// Parameters with this prefix must be understood by the parser
+const REQUIRED_PARAM_PREFIX: &str = "req-";
+
+/// The unreserved characters of RFC 3986 are left as is, everything else is percent-encoded
+const PARAM_VALUE_ENCODE_SET: &AsciiSet =
+    &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum PaymentUriError {
+    #[error("The payment URI must start with `{PAYMENT_URI_SCHEME}:`")]
+    InvalidScheme,
+    #[error("The payment URI doesn't contain an address")]
+    MissingAddress,
+    #[error("Invalid address in the payment URI: {0}")]
+    InvalidAddress(AddressError),
+    #[error("The address prefix `{0}` doesn't match the current network")]
+    WrongNetwork(String),
+    #[error("Invalid amount `{0}` in the payment URI")]
+    InvalidAmount(String),
+    #[error("The amount `{0}` has more than {1} decimal places")]
+    AmountTooPrecise(String, u8),
+    #[error("Malformed payment URI parameter `{0}`")]
+    MalformedParameter(String),
+    #[error("Parameter `{0}` appears more than once in the payment URI")]
+    DuplicateParameter(String),
+    #[error("Unsupported required parameter `{0}` in the payment URI")]
+    UnsupportedRequiredParameter(String),
+}
+
+/// A request to pay to an address, optionally with a specific amount of coins
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentUri {
+    address: Address<Destination>,
+    amount: Option<Amount>,
+    label: Option<String>,
+    message: Option<String>,
+    coin_decimals: u8,
+}
+
+impl PaymentUri {
+    pub fn new(
+        chain_config: &ChainConfig,
+        address: Address<Destination>,
+        amount: Option<Amount>,
+        label: Option<String>,
+        message: Option<String>,
+    ) -> Self {
+        Self {
+            address,
+            amount,
+            label,
+            message,
+            coin_decimals: chain_config.coin_decimals(),
+        }
+    }
+
+    pub fn from_string(chain_config: &ChainConfig, uri: &str) -> Result<Self, PaymentUriError> {
+        let (scheme, rest) = uri.split_once(':').ok_or(PaymentUriError::InvalidScheme)?;
+        utils::ensure!(
+            scheme.eq_ignore_ascii_case(PAYMENT_URI_SCHEME),
+            PaymentUriError::InvalidScheme
+        );
+
+        let (address, params) = rest.split_once('?').unwrap_or((rest, ""));
+        utils::ensure!(!address.is_empty(), PaymentUriError::MissingAddress);
+        let address = Address::from_string(chain_config, address).map_err(|e| match e {
+            AddressError::InvalidPrefix(prefix) => PaymentUriError::WrongNetwork(prefix),
+            e => PaymentUriError::InvalidAddress(e),
+        })?;
+
+        let mut result = Self::new(chain_config, address, None, None, None);
+
+        for param in params.split('&').filter(|param| !param.is_empty()) {
+            let (name, value) = param
+                .split_once('=')
+                .ok_or_else(|| PaymentUriError::MalformedParameter(param.to_owned()))?;
+            let value = percent_decode_str(value)
+                .decode_utf8()
+                .map_err(|_| PaymentUriError::MalformedParameter(param.to_owned()))?
+                .into_owned();
+
+            let field = match name {
+                AMOUNT_PARAM => {
+                    utils::ensure!(
+                        result.amount.is_none(),
+                        PaymentUriError::DuplicateParameter(name.to_owned())
+                    );
+                    result.amount = Some(parse_amount(&value, result.coin_decimals)?);
+                    continue;
+                }
+                LABEL_PARAM => &mut result.label,
+                MESSAGE_PARAM => &mut result.message,
+                name if name.starts_with(REQUIRED_PARAM_PREFIX) => {
+                    return Err(PaymentUriError::UnsupportedRequiredParameter(
+                        name.to_owned(),
+                    ));
+                }
+                _ => continue,
+            };
+
+            utils::ensure!(
+                field.is_none(),
+                PaymentUriError::DuplicateParameter(name.to_owned())
+            );
+            *field = Some(value);
+        }
+
+        Ok(result)
+    }
+
+    pub fn address(&self) -> &Address<Destination> {
+        &self.address
+    }
+
+    pub fn amount(&self) -> Option<Amount> {
+        self.amount
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+}
+
+fn parse_amount(value: &str, coin_decimals: u8) -> Result<Amount, PaymentUriError> {
+    let decimal: DecimalAmount =
+        value.parse().map_err(|_| PaymentUriError::InvalidAmount(value.to_owned()))?;
+    decimal
+        .to_amount(coin_decimals)
+        .ok_or_else(|| PaymentUriError::AmountTooPrecise(value.to_owned(), coin_decimals))
+}
+
+impl Display for PaymentUri {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{PAYMENT_URI_SCHEME}:{}", self.address)?;
+
+        let amount = self
+            .amount
+            .map(|amount| DecimalAmount::from_amount_no_padding(amount, self.coin_decimals));
+        let params = [
+            (AMOUNT_PARAM, amount.map(|amount| amount.to_string())),
+            (LABEL_PARAM, self.label.clone()),
+            (MESSAGE_PARAM, self.message.clone()),
+        ];
+
+        let mut separator = '?';
+        for (name, value) in params {
+            if let Some(value) = value {
+                let value = utf8_percent_encode(&value, PARAM_VALUE_ENCODE_SET);
+                write!(f, "{separator}{name}={value}")?;
+                separator = '&';
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto::key::{KeyKind, PrivateKey};
+    use rstest::rstest;
+    use test_utils::random::{make_seedable_rng, CryptoRng, Rng, Seed};
+
+    use super::*;
+    use crate::{
+        address::pubkeyhash::PublicKeyHash,
+        chain::config::{create_mainnet, create_regtest},
+    };
+
+    fn random_address(
+        chain_config: &ChainConfig,
+        rng: &mut (impl Rng + CryptoRng),
+    ) -> Address<Destination> {
+        let (_private_key, public_key) = PrivateKey::new_from_rng(rng, KeyKind::Secp256k1Schnorr);
+        let destination = Destination::PublicKeyHash(PublicKeyHash::from(&public_key));
+        Address::new(chain_config, destination).unwrap()
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn round_trip(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let chain_config = create_regtest();
+
+        for _ in 0..10 {
+            let uri = PaymentUri::new(
+                &chain_config,
+                random_address(&chain_config, &mut rng),
+                rng.gen::<bool>()
+                    .then(|| Amount::from_atoms(rng.gen_range(0..u64::MAX as u128))),
+                rng.gen::<bool>().then(|| "Coffee & tea, 100% = ok?".to_owned()),
+                rng.gen::<bool>().then(|| "Paiement pour la commande n°42".to_owned()),
+            );
+
+            let uri_string = uri.to_string();
+            assert_eq!(PaymentUri::from_string(&chain_config, &uri_string), Ok(uri));
+        }
+    }
+
+    fn example_address(chain_config: &ChainConfig) -> Address<Destination> {
+        Address::new(
+            chain_config,
+            Destination::PublicKeyHash(PublicKeyHash::zero()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn format() {
+        let chain_config = create_regtest();
+        let address = example_address(&chain_config);
+
+        let uri = PaymentUri::new(&chain_config, address.clone(), None, None, None);
+        assert_eq!(uri.to_string(), format!("mintlayer:{address}"));
+
+        let uri = PaymentUri::new(
+            &chain_config,
+            address.clone(),
+            Some(Amount::from_atoms(1_050_000_000_000)),
+            Some("Coffee shop".to_owned()),
+            Some("Order #1".to_owned()),
+        );
+        assert_eq!(
+            uri.to_string(),
+            format!("mintlayer:{address}?amount=10.5&label=Coffee%20shop&message=Order%20%231")
+        );
+    }
+
+    #[test]
+    fn parse() {
+        let chain_config = create_regtest();
+        let address = example_address(&chain_config).into_string();
+
+        let uri = PaymentUri::from_string(
+            &chain_config,
+            &format!("MintLayer:{address}?label=Shop&unknown=1&amount=0.01"),
+        )
+        .unwrap();
+        assert_eq!(uri.address().as_str(), &address);
+        assert_eq!(uri.amount(), Some(Amount::from_atoms(1_000_000_000)));
+        assert_eq!(uri.label(), Some("Shop"));
+        assert_eq!(uri.message(), None);
+
+        let parse = |uri: &str| PaymentUri::from_string(&chain_config, uri);
+        assert_eq!(
+            parse(&format!("bitcoin:{address}")),
+            Err(PaymentUriError::InvalidScheme)
+        );
+        assert_eq!(parse(&address), Err(PaymentUriError::InvalidScheme));
+        assert_eq!(
+            parse("mintlayer:?amount=1"),
+            Err(PaymentUriError::MissingAddress)
+        );
+        assert!(matches!(
+            parse(&format!("mintlayer:{}x", address)),
+            Err(PaymentUriError::InvalidAddress(_))
+        ));
+        assert_eq!(
+            parse(&format!("mintlayer:{address}?amount=1,5")),
+            Err(PaymentUriError::InvalidAmount("1,5".to_owned()))
+        );
+        assert_eq!(
+            parse(&format!("mintlayer:{address}?amount=0.0000000000001")),
+            Err(PaymentUriError::AmountTooPrecise(
+                "0.0000000000001".to_owned(),
+                11
+            ))
+        );
+        assert_eq!(
+            parse(&format!("mintlayer:{address}?amount")),
+            Err(PaymentUriError::MalformedParameter("amount".to_owned()))
+        );
+        assert_eq!(
+            parse(&format!("mintlayer:{address}?label=a&label=b")),
+            Err(PaymentUriError::DuplicateParameter("label".to_owned()))
+        );
+        assert_eq!(
+            parse(&format!("mintlayer:{address}?req-expires=100")),
+            Err(PaymentUriError::UnsupportedRequiredParameter(
+                "req-expires".to_owned()
+            ))
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn wrong_network(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let mainnet = create_mainnet();
+        let regtest = create_regtest();
+
+        let uri = PaymentUri::new(
+            &mainnet,
+            random_address(&mainnet, &mut rng),
+            Some(Amount::from_atoms(rng.gen())),
+            None,
+            None,
+        );
+
+        assert_eq!(
+            PaymentUri::from_string(&regtest, &uri.to_string()),
+            Err(PaymentUriError::WrongNetwork("mtc".to_owned()))
+        );
+    }
+}
@@ -14,7 +14,11 @@
 // limitations under the License.
 
 use crypto::hash::StreamHasher;
-use merkletree_mintlayer::hasher::PairHasher;
+use merkletree_mintlayer::{
+    hasher::PairHasher,
+    proof::multi::{MultiProofHashes, MultiProofNodes},
+};
+use serialization::{Compact, Encode};
 
 use crate::primitives::{id::DefaultHashAlgoStream, H256};
 
@@ -38,10 +42,48 @@ impl PairHasher for MerkleHasher {
     }
 }
 
+/// Size of a multi-proof, to estimate the size of responses that contain proofs without
+/// serializing them first.
+pub trait MultiProofSizeEstimate {
+    fn proof_size_bytes(&self) -> usize;
+}
+
+/// Encoded size of a node of a multi-proof: its absolute index in the tree followed by its hash.
+const ENCODED_PROOF_NODE_SIZE: usize = std::mem::size_of::<u32>() + std::mem::size_of::<H256>();
+
+impl MultiProofSizeEstimate for MultiProofHashes<H256, MerkleHasher> {
+    /// The SCALE encoding of the proof consists of:
+    /// - the compact-encoded number of nodes,
+    /// - for each node, its index (u32) and its hash (H256),
+    /// - the number of leaves in the tree (u32).
+    ///
+    /// So the size is `compact_len(n) + n * (4 + 32) + 4` for a proof with `n` nodes.
+    fn proof_size_bytes(&self) -> usize {
+        let node_count = self.nodes().len();
+        Compact(node_count as u32).encoded_size()
+            + node_count * ENCODED_PROOF_NODE_SIZE
+            + std::mem::size_of::<u32>()
+    }
+}
+
+impl MultiProofSizeEstimate for MultiProofNodes<'_, H256, MerkleHasher> {
+    /// The size of the proof hashes, as above, plus the proven leaves, each of which is sent
+    /// the same way as a proof node, i.e. `(n + l) * (4 + 32)` plus the headers for a proof with
+    /// `n` nodes and `l` leaves.
+    fn proof_size_bytes(&self) -> usize {
+        let node_count = self.nodes().len();
+        Compact(node_count as u32).encoded_size()
+            + (node_count + self.leaves().len()) * ENCODED_PROOF_NODE_SIZE
+            + std::mem::size_of::<u32>()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use merkletree_mintlayer::tree::MerkleTree;
+    use randomness::{seq::IteratorRandom, Rng};
     use rstest::rstest;
+    use test_utils::random::{make_seedable_rng, Seed};
 
     use crate::primitives::id::default_hash;
 
@@ -129,4 +171,43 @@ mod tests {
     fn empty_tree_error() {
         MerkleTree::<H256, MerkleHasher>::from_leaves([]).unwrap_err();
     }
+
+    #[rstest]
+    #[trace]
+    #[case(2, Seed::from_entropy())]
+    #[trace]
+    #[case(4, Seed::from_entropy())]
+    #[trace]
+    #[case(8, Seed::from_entropy())]
+    #[trace]
+    #[case(16, Seed::from_entropy())]
+    #[trace]
+    #[case(64, Seed::from_entropy())]
+    fn multi_proof_size_matches_encoding(#[case] leaf_count: usize, #[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+
+        let leaves = (0..leaf_count).map(|v| default_hash(v.to_string())).collect::<Vec<_>>();
+        let tree = MerkleTree::<H256, MerkleHasher>::from_leaves(leaves.clone()).unwrap();
+
+        let proven_count = rng.gen_range(1..=leaf_count);
+        let mut proven_leaves = (0..leaf_count as u32).choose_multiple(&mut rng, proven_count);
+        proven_leaves.sort();
+
+        let proof_nodes = MultiProofNodes::from_tree_leaves(&tree, &proven_leaves).unwrap();
+        let nodes_size_estimate = proof_nodes.proof_size_bytes();
+
+        // Each proven leaf is sent as its index followed by its hash
+        let encoded_leaves_size = proven_leaves
+            .iter()
+            .map(|&index| (index, leaves[index as usize]).encode().len())
+            .sum::<usize>();
+
+        let proof_hashes = proof_nodes.into_values();
+        let encoded_hashes_size = proof_hashes.encode().len();
+        assert_eq!(proof_hashes.proof_size_bytes(), encoded_hashes_size);
+        assert_eq!(
+            nodes_size_estimate,
+            encoded_hashes_size + encoded_leaves_size
+        );
+    }
 }
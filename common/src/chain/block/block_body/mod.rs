@@ -25,6 +25,8 @@ use crate::chain::SignedTransaction;
 
 use self::merkle_proxy::BlockBodyMerkleProxy;
 
+pub use self::merkle_tools::MultiProofSizeEstimate;
+
 use super::BlockReward;
 
 #[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
@@ -32,7 +32,7 @@ pub mod timestamp;
 mod block_reward;
 mod block_v1;
 
-use serialization::{DirectDecode, DirectEncode};
+use serialization::{strict::DecodeStrict, DirectDecode, DirectEncode};
 use typename::TypeName;
 use utils::ensure;
 
@@ -66,6 +66,8 @@ pub enum Block {
     V1(BlockV1),
 }
 
+impl DecodeStrict for Block {}
+
 impl Block {
     pub fn new(
         transactions: Vec<SignedTransaction>,
@@ -443,4 +445,37 @@ mod tests {
 
         check_block_tag(&block);
     }
+
+    #[test]
+    fn strict_decoding() {
+        use serialization::hex_encoded::StrictHexEncoded;
+
+        let mut rng = make_pseudo_rng();
+
+        let header = BlockHeader {
+            version: Default::default(),
+            consensus_data: ConsensusData::None,
+            tx_merkle_root: H256::from_low_u64_be(rng.gen()),
+            witness_merkle_root: H256::from_low_u64_be(rng.gen()),
+            prev_block_id: Id::new(H256::from_low_u64_be(rng.gen())),
+            timestamp: BlockTimestamp::from_int_seconds(0),
+        }
+        .with_no_signature();
+
+        let encoded = header.encode();
+        assert_eq!(SignedBlockHeader::decode_strict(&encoded).unwrap(), header);
+        let hex = serde_json::json!(hex::encode(&encoded));
+        let decoded: StrictHexEncoded<SignedBlockHeader> = serde_json::from_value(hex).unwrap();
+        assert_eq!(decoded.take(), header);
+
+        // The zero timestamp that follows the version tag and the three hashes,
+        // with a non-minimal compact prefix
+        let timestamp_pos = 1 + 3 * 32;
+        assert_eq!(encoded[timestamp_pos], 0);
+        let mut non_canonical = encoded.clone();
+        non_canonical.splice(timestamp_pos..timestamp_pos + 1, [0b01, 0]);
+        assert!(SignedBlockHeader::decode_strict(&non_canonical).is_err());
+        let hex = serde_json::json!(hex::encode(&non_canonical));
+        assert!(serde_json::from_value::<StrictHexEncoded<SignedBlockHeader>>(hex).is_err());
+    }
 }
@@ -14,7 +14,7 @@
 // limitations under the License.
 
 use crypto::key::Signature;
-use serialization::{Decode, Encode};
+use serialization::{strict::DecodeStrict, Decode, Encode};
 use typename::TypeName;
 
 use crate::primitives::id::{Id, Idable};
@@ -57,6 +57,8 @@ pub struct SignedBlockHeader {
     signature_data: BlockHeaderSignature,
 }
 
+impl DecodeStrict for SignedBlockHeader {}
+
 impl SignedBlockHeader {
     pub fn new(signature: BlockHeaderSignature, block_header: BlockHeader) -> Self {
         Self {
@@ -28,6 +28,7 @@ use crate::{
         },
         pos_initial_difficulty,
         pow::PoWChainConfigBuilder,
+        transaction::fee::FeeRate,
         ChainstateUpgrade, ChangeTokenMetadataUriActivated, CoinUnit, ConsensusUpgrade,
         DataDepositFeeVersion, Destination, GenBlock, Genesis, HtlcActivated, NetUpgrades,
         OrdersActivated, PoSChainConfig, PoSConsensusVersion, PoWChainConfig,
@@ -160,6 +161,21 @@ impl ChainType {
         }
     }
 
+    /// The activation height of the latest upgrade in the default consensus and chainstate
+    /// upgrade schedules of this chain type
+    pub fn default_latest_upgrade_height(&self) -> BlockHeight {
+        let consensus_upgrades =
+            self.default_consensus_upgrades(super::DEFAULT_TARGET_BLOCK_SPACING);
+        let chainstate_upgrades = self.default_chainstate_upgrades();
+
+        consensus_upgrades
+            .iter()
+            .map(|(height, _)| height)
+            .chain(chainstate_upgrades.iter().map(|(height, _)| height))
+            .max()
+            .unwrap_or(BlockHeight::zero())
+    }
+
     fn default_chainstate_upgrades(&self) -> NetUpgrades<ChainstateUpgrade> {
         match self {
             ChainType::Mainnet => {
@@ -336,6 +352,10 @@ pub struct Builder {
     empty_consensus_reward_maturity_block_count: BlockCount,
     max_classic_multisig_public_keys_count: usize,
     min_stake_pool_pledge: Amount,
+    max_tx_inputs: usize,
+    max_tx_outputs: usize,
+    min_tx_relay_fee_rate: FeeRate,
+    dust_relay_fee_rate: Amount,
 }
 
 impl Builder {
@@ -383,6 +403,10 @@ impl Builder {
             empty_consensus_reward_maturity_block_count: BlockCount::new(0),
             max_classic_multisig_public_keys_count: super::MAX_CLASSIC_MULTISIG_PUBLIC_KEYS_COUNT,
             min_stake_pool_pledge: super::MIN_STAKE_POOL_PLEDGE,
+            max_tx_inputs: super::MAX_TX_INPUTS,
+            max_tx_outputs: super::MAX_TX_OUTPUTS,
+            min_tx_relay_fee_rate: super::MIN_TX_RELAY_FEE_RATE,
+            dust_relay_fee_rate: super::DUST_RELAY_FEE_RATE,
         }
     }
 
@@ -435,6 +459,10 @@ impl Builder {
             empty_consensus_reward_maturity_block_count,
             max_classic_multisig_public_keys_count,
             min_stake_pool_pledge,
+            max_tx_inputs,
+            max_tx_outputs,
+            min_tx_relay_fee_rate,
+            dust_relay_fee_rate,
         } = self;
 
         let emission_table = match emission_schedule {
@@ -527,6 +555,10 @@ impl Builder {
             token_max_hash_len,
             max_classic_multisig_public_keys_count,
             min_stake_pool_pledge,
+            max_tx_inputs,
+            max_tx_outputs,
+            min_tx_relay_fee_rate,
+            dust_relay_fee_rate,
         }
     }
 }
@@ -566,6 +598,10 @@ impl Builder {
     builder_method!(sealed_epoch_distance_from_tip: usize);
     builder_method!(data_deposit_max_size: Option<usize>);
     builder_method!(min_stake_pool_pledge: Amount);
+    builder_method!(max_tx_inputs: usize);
+    builder_method!(max_tx_outputs: usize);
+    builder_method!(min_tx_relay_fee_rate: FeeRate);
+    builder_method!(dust_relay_fee_rate: Amount);
 
     pub fn checkpoints(mut self, checkpoints: BTreeMap<BlockHeight, Id<GenBlock>>) -> Self {
         self.checkpoints = Some(checkpoints);
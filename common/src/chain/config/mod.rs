@@ -19,7 +19,9 @@ pub mod checkpoints_data;
 pub mod emission_schedule;
 pub mod regtest;
 pub mod regtest_options;
+mod tx_validation;
 pub use builder::Builder;
+pub use tx_validation::TransactionValidationError;
 
 use crypto::key::PublicKey;
 use crypto::vrf::VRFPublicKey;
@@ -35,7 +37,7 @@ use hex::FromHex;
 use serialization::{Decode, Encode};
 
 use crate::chain::block::timestamp::BlockTimestamp;
-use crate::chain::transaction::Destination;
+use crate::chain::transaction::{fee::FeeRate, Destination};
 use crate::chain::upgrades::NetUpgrades;
 use crate::chain::PoWChainConfig;
 use crate::chain::TxOutput;
@@ -293,6 +295,10 @@ pub struct ChainConfig {
     empty_consensus_reward_maturity_block_count: BlockCount,
     max_classic_multisig_public_keys_count: usize,
     min_stake_pool_pledge: Amount,
+    max_tx_inputs: usize,
+    max_tx_outputs: usize,
+    min_tx_relay_fee_rate: FeeRate,
+    dust_relay_fee_rate: Amount,
 }
 
 impl ChainConfig {
@@ -716,6 +722,30 @@ impl ChainConfig {
         self.min_stake_pool_pledge
     }
 
+    /// The maximum number of inputs a transaction can have to pass `is_valid_transaction`
+    #[must_use]
+    pub fn max_tx_inputs(&self) -> usize {
+        self.max_tx_inputs
+    }
+
+    /// The maximum number of outputs that the wallet puts into a single batch payment transaction
+    #[must_use]
+    pub fn max_tx_outputs(&self) -> usize {
+        self.max_tx_outputs
+    }
+
+    /// The minimum fee rate transactions have to pay to be relayed
+    #[must_use]
+    pub fn min_tx_relay_fee_rate(&self) -> FeeRate {
+        self.min_tx_relay_fee_rate
+    }
+
+    /// The amount per byte of an output that the output has to carry to not be considered dust
+    #[must_use]
+    pub fn dust_relay_fee_rate(&self) -> Amount {
+        self.dust_relay_fee_rate
+    }
+
     pub fn final_supply(&self) -> Option<CoinUnit> {
         self.final_supply
     }
@@ -762,6 +792,11 @@ const TOKEN_MAX_DESCRIPTION_LEN: usize = 100;
 const TOKEN_MAX_URI_LEN: usize = 1024;
 const MAX_CLASSIC_MULTISIG_PUBLIC_KEYS_COUNT: usize = 32;
 const MIN_STAKE_POOL_PLEDGE: Amount = Amount::from_atoms(40_000 * CoinUnit::ATOMS_PER_COIN);
+const MAX_TX_INPUTS: usize = 1000;
+const MAX_TX_OUTPUTS: usize = 1000;
+const MIN_TX_RELAY_FEE_RATE: FeeRate =
+    FeeRate::from_amount_per_kb(Amount::from_atoms(100_000_000_000));
+const DUST_RELAY_FEE_RATE: Amount = Amount::from_atoms(3);
 
 fn decode_hex<T: serialization::DecodeAll>(hex: &str) -> T {
     let bytes = Vec::from_hex(hex).expect("Hex decoding shouldn't fail");
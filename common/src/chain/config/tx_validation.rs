@@ -0,0 +1,198 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use thiserror::Error;
+
+use crate::{
+    chain::{output_value::OutputValue, timelock::OutputTimeLock, Transaction, TxOutput},
+    primitives::Amount,
+};
+
+use super::ChainConfig;
+
+/// A violation of the rules that can be checked on a transaction alone, without looking up
+/// the utxos it spends or verifying its signatures.
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum TransactionValidationError {
+    #[error("The transaction has no inputs")]
+    NoInputs,
+    #[error("The transaction has {0} inputs, the maximum is {1}")]
+    TooManyInputs(usize, usize),
+    #[error("The transaction has {0} token issuance outputs, at most one is allowed")]
+    MultipleIssuanceOutputs(usize),
+    #[error("Output {0} has a zero amount")]
+    ZeroOutputAmount(usize),
+    #[error("Output {0} has a timelock of {1} blocks, which is not a valid block distance")]
+    InvalidTimelockBlockCount(usize, u64),
+}
+
+impl ChainConfig {
+    /// Check the rules that don't depend on the utxo set or the signatures and return all the
+    /// violations found, so that they can be reported at once.
+    pub fn is_valid_transaction(&self, tx: &Transaction) -> Vec<TransactionValidationError> {
+        let mut errors = Vec::new();
+
+        if tx.inputs().is_empty() {
+            errors.push(TransactionValidationError::NoInputs);
+        }
+        if tx.inputs().len() > self.max_tx_inputs {
+            errors.push(TransactionValidationError::TooManyInputs(
+                tx.inputs().len(),
+                self.max_tx_inputs,
+            ));
+        }
+
+        let issuance_count = tx
+            .outputs()
+            .iter()
+            .filter(|output| match output {
+                TxOutput::IssueFungibleToken(_) | TxOutput::IssueNft(_, _, _) => true,
+                TxOutput::Transfer(_, _)
+                | TxOutput::LockThenTransfer(_, _, _)
+                | TxOutput::Burn(_)
+                | TxOutput::CreateStakePool(_, _)
+                | TxOutput::ProduceBlockFromStake(_, _)
+                | TxOutput::CreateDelegationId(_, _)
+                | TxOutput::DelegateStaking(_, _)
+                | TxOutput::DataDeposit(_)
+                | TxOutput::Htlc(_, _)
+                | TxOutput::AnyoneCanTake(_) => false,
+            })
+            .count();
+        if issuance_count > 1 {
+            errors.push(TransactionValidationError::MultipleIssuanceOutputs(
+                issuance_count,
+            ));
+        }
+
+        for (index, output) in tx.outputs().iter().enumerate() {
+            if output_amount(output).is_some_and(|amount| amount == Amount::ZERO) {
+                errors.push(TransactionValidationError::ZeroOutputAmount(index));
+            }
+
+            // The block count must be convertible to a block distance when the output is spent
+            if let TxOutput::LockThenTransfer(_, _, OutputTimeLock::ForBlockCount(count)) = output {
+                if i64::try_from(*count).is_err() {
+                    errors.push(TransactionValidationError::InvalidTimelockBlockCount(
+                        index, *count,
+                    ));
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// The amount transferred by the output, if it has a single one
+fn output_amount(output: &TxOutput) -> Option<Amount> {
+    match output {
+        TxOutput::Transfer(value, _)
+        | TxOutput::LockThenTransfer(value, _, _)
+        | TxOutput::Burn(value)
+        | TxOutput::Htlc(value, _) => match value {
+            OutputValue::Coin(amount) | OutputValue::TokenV1(_, amount) => Some(*amount),
+            OutputValue::TokenV0(_) => None,
+        },
+        TxOutput::DelegateStaking(amount, _) => Some(*amount),
+        TxOutput::CreateStakePool(_, _)
+        | TxOutput::ProduceBlockFromStake(_, _)
+        | TxOutput::CreateDelegationId(_, _)
+        | TxOutput::IssueFungibleToken(_)
+        | TxOutput::IssueNft(_, _, _)
+        | TxOutput::DataDeposit(_)
+        | TxOutput::AnyoneCanTake(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::{
+        chain::{
+            config::create_unit_test_config_builder,
+            tokens::{IsTokenFreezable, TokenIssuance, TokenIssuanceV1, TokenTotalSupply},
+            Destination, OutPointSourceId, TxInput,
+        },
+        primitives::{Id, H256},
+    };
+
+    use super::*;
+
+    use TransactionValidationError as E;
+
+    fn input() -> TxInput {
+        TxInput::from_utxo(OutPointSourceId::Transaction(Id::new(H256::zero())), 0)
+    }
+
+    fn transfer(atoms: u128) -> TxOutput {
+        TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(atoms)),
+            Destination::AnyoneCanSpend,
+        )
+    }
+
+    fn lock_for_blocks(count: u64) -> TxOutput {
+        TxOutput::LockThenTransfer(
+            OutputValue::Coin(Amount::from_atoms(1)),
+            Destination::AnyoneCanSpend,
+            OutputTimeLock::ForBlockCount(count),
+        )
+    }
+
+    fn issuance() -> TxOutput {
+        TxOutput::IssueFungibleToken(Box::new(TokenIssuance::V1(TokenIssuanceV1 {
+            token_ticker: b"TKN".to_vec(),
+            number_of_decimals: 2,
+            metadata_uri: Vec::new(),
+            total_supply: TokenTotalSupply::Unlimited,
+            authority: Destination::AnyoneCanSpend,
+            is_freezable: IsTokenFreezable::No,
+        })))
+    }
+
+    #[rstest]
+    #[case::valid(vec![input()], vec![transfer(1), issuance()], vec![])]
+    #[case::no_inputs(vec![], vec![transfer(1)], vec![E::NoInputs])]
+    #[case::too_many_inputs(vec![input(); 3], vec![transfer(1)], vec![E::TooManyInputs(3, 2)])]
+    #[case::multiple_issuances(
+        vec![input()],
+        vec![issuance(), transfer(1), issuance()],
+        vec![E::MultipleIssuanceOutputs(2)]
+    )]
+    #[case::zero_amount(vec![input()], vec![transfer(1), transfer(0)], vec![E::ZeroOutputAmount(1)])]
+    #[case::max_block_count(vec![input()], vec![lock_for_blocks(i64::MAX as u64)], vec![])]
+    #[case::invalid_block_count(
+        vec![input()],
+        vec![lock_for_blocks(i64::MAX as u64 + 1)],
+        vec![E::InvalidTimelockBlockCount(0, i64::MAX as u64 + 1)]
+    )]
+    #[case::all_violations(
+        vec![],
+        vec![issuance(), transfer(0), issuance()],
+        vec![E::NoInputs, E::MultipleIssuanceOutputs(2), E::ZeroOutputAmount(1)]
+    )]
+    fn is_valid_transaction(
+        #[case] inputs: Vec<TxInput>,
+        #[case] outputs: Vec<TxOutput>,
+        #[case] expected_errors: Vec<TransactionValidationError>,
+    ) {
+        let chain_config = create_unit_test_config_builder().max_tx_inputs(2).build();
+        let tx = Transaction::new(0, inputs, outputs).unwrap();
+
+        assert_eq!(chain_config.is_valid_transaction(&tx), expected_errors);
+    }
+}
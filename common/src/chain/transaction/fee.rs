@@ -0,0 +1,342 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Estimation of the fees a transaction has to pay before it is signed
+
+use serialization::Encode;
+
+use crate::{
+    chain::{ChainConfig, Transaction, TxOutput},
+    primitives::Amount,
+    size_estimation::{input_signature_size, DestinationInfoProvider, SizeEstimationError},
+};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum FeeEstimationError {
+    #[error("The number of UTXOs {0} does not match the number of inputs {1}")]
+    UtxoCountMismatch(usize, usize),
+    #[error("Signature size estimation error: {0}")]
+    SizeEstimation(#[from] SizeEstimationError),
+    #[error("Fee overflow")]
+    FeeOverflow,
+}
+
+/// Fee to be paid per 1000 bytes of a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FeeRate {
+    amount_per_kb: Amount,
+}
+
+impl FeeRate {
+    pub fn from_atoms_per_kb(atoms_per_kb: u128) -> Self {
+        Self::from_amount_per_kb(Amount::from_atoms(atoms_per_kb))
+    }
+
+    pub const fn from_amount_per_kb(amount_per_kb: Amount) -> Self {
+        Self { amount_per_kb }
+    }
+
+    pub const fn amount_per_kb(&self) -> Amount {
+        self.amount_per_kb
+    }
+
+    pub const fn atoms_per_kb(&self) -> u128 {
+        self.amount_per_kb.into_atoms()
+    }
+
+    /// The fee for a transaction of the given size, rounded up
+    pub fn compute_fee(&self, size: usize) -> Option<Amount> {
+        let size = u128::try_from(size).expect("compute_fee conversion");
+        let fee = (self.amount_per_kb * size)?;
+        // +999 for ceil operation
+        (fee + Amount::from_atoms(999))? / 1000
+    }
+}
+
+/// Estimate the size of the transaction once all of its inputs are signed.
+///
+/// The UTXOs are the outputs spent by the transaction inputs, in the same order. The size of each
+/// input signature is estimated from the destination of the spent output, see
+/// [input_signature_size] for the supported destinations.
+pub fn estimate_signed_tx_size(
+    tx: &Transaction,
+    utxos: &[TxOutput],
+    dest_info_provider: Option<&dyn DestinationInfoProvider>,
+) -> Result<usize, FeeEstimationError> {
+    utils::ensure!(
+        utxos.len() == tx.inputs().len(),
+        FeeEstimationError::UtxoCountMismatch(utxos.len(), tx.inputs().len())
+    );
+
+    let signatures_size = utxos.iter().try_fold(0, |total, utxo| {
+        input_signature_size(utxo, dest_info_provider).map(|size| total + size)
+    })?;
+    let signatures_count_size = serialization::Compact(utxos.len() as u32).encoded_size();
+
+    Ok(tx.encoded_size() + signatures_count_size + signatures_size)
+}
+
+/// The minimum fee rate, relative to the size of the unsigned transaction, that has to be paid
+/// for the transaction to be relayed once it is signed.
+///
+/// Paying this rate for the transaction body pays the chain's minimum relay fee rate for the
+/// estimated size of the signed transaction.
+pub fn estimate_fee_rate_needed(
+    tx: &Transaction,
+    utxos: &[TxOutput],
+    chain_config: &ChainConfig,
+    dest_info_provider: Option<&dyn DestinationInfoProvider>,
+) -> Result<FeeRate, FeeEstimationError> {
+    let signed_size = estimate_signed_tx_size(tx, utxos, dest_info_provider)?;
+    let min_fee = chain_config
+        .min_tx_relay_fee_rate()
+        .compute_fee(signed_size)
+        .ok_or(FeeEstimationError::FeeOverflow)?;
+
+    // The transaction encoding is never empty
+    let unsigned_size = u128::try_from(tx.encoded_size()).expect("size conversion");
+    let scaled_fee = (min_fee * 1000).ok_or(FeeEstimationError::FeeOverflow)?;
+    // + unsigned_size - 1 for ceil operation
+    let rounded_fee = (scaled_fee + Amount::from_atoms(unsigned_size - 1))
+        .ok_or(FeeEstimationError::FeeOverflow)?;
+    let amount_per_kb = (rounded_fee / unsigned_size).expect("unsigned size nonzero");
+
+    Ok(FeeRate::from_amount_per_kb(amount_per_kb))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU8;
+
+    use crypto::key::{KeyKind, PrivateKey, PublicKey};
+    use randomness::{CryptoRng, Rng};
+    use rstest::rstest;
+    use test_utils::random::{make_seedable_rng, Seed};
+
+    use super::*;
+    use crate::{
+        address::pubkeyhash::PublicKeyHash,
+        chain::{
+            classic_multisig::ClassicMultisigChallenge,
+            config::create_unit_test_config,
+            output_value::OutputValue,
+            signature::{
+                inputsig::{
+                    classical_multisig::authorize_classical_multisig::{
+                        sign_classical_multisig_spending, AuthorizedClassicalMultisigSpend,
+                        ClassicalMultisigCompletionStatus,
+                    },
+                    standard_signature::StandardInputSignature,
+                    InputWitness,
+                },
+                sighash::{sighashtype::SigHashType, signature_hash},
+            },
+            Destination, OutPointSourceId, SignedTransaction, TxInput,
+        },
+        primitives::{Id, H256},
+        size_estimation::MultisigInfo,
+    };
+
+    struct TestDestinationInfoProvider(Vec<ClassicMultisigChallenge>);
+
+    impl DestinationInfoProvider for TestDestinationInfoProvider {
+        fn get_multisig_info(&self, destination: &Destination) -> Option<MultisigInfo> {
+            self.0
+                .iter()
+                .find(|challenge| Destination::ClassicMultisig((*challenge).into()) == *destination)
+                .map(MultisigInfo::from_challenge)
+        }
+    }
+
+    enum Owner {
+        PublicKeyHash(PrivateKey),
+        PublicKey(PrivateKey),
+        Multisig(Vec<PrivateKey>, ClassicMultisigChallenge),
+    }
+
+    impl Owner {
+        fn new(rng: &mut (impl Rng + CryptoRng), chain_config: &ChainConfig, kind: &str) -> Self {
+            let mut new_key = || PrivateKey::new_from_rng(rng, KeyKind::Secp256k1Schnorr).0;
+            match kind {
+                "pkh" => Self::PublicKeyHash(new_key()),
+                "pk" => Self::PublicKey(new_key()),
+                "multisig" => {
+                    let keys: Vec<_> = (0..3).map(|_| new_key()).collect();
+                    let challenge = ClassicMultisigChallenge::new(
+                        chain_config,
+                        NonZeroU8::new(2).unwrap(),
+                        keys.iter().map(PublicKey::from_private_key).collect(),
+                    )
+                    .unwrap();
+                    Self::Multisig(keys, challenge)
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        fn destination(&self) -> Destination {
+            match self {
+                Self::PublicKeyHash(key) => Destination::PublicKeyHash(PublicKeyHash::from(
+                    &PublicKey::from_private_key(key),
+                )),
+                Self::PublicKey(key) => Destination::PublicKey(PublicKey::from_private_key(key)),
+                Self::Multisig(_, challenge) => Destination::ClassicMultisig(challenge.into()),
+            }
+        }
+
+        fn sign(
+            &self,
+            rng: &mut (impl Rng + CryptoRng),
+            chain_config: &ChainConfig,
+            tx: &Transaction,
+            utxos: &[Option<&TxOutput>],
+            input_num: usize,
+        ) -> InputWitness {
+            let sighash_type = SigHashType::try_from(SigHashType::ALL).unwrap();
+            let signature = match self {
+                Self::PublicKeyHash(key) | Self::PublicKey(key) => {
+                    StandardInputSignature::produce_uniparty_signature_for_input(
+                        key,
+                        sighash_type,
+                        self.destination(),
+                        tx,
+                        utxos,
+                        input_num,
+                        &mut *rng,
+                    )
+                    .unwrap()
+                }
+                Self::Multisig(keys, challenge) => {
+                    let sighash = signature_hash(sighash_type, tx, utxos, input_num).unwrap();
+                    let mut signatures =
+                        AuthorizedClassicalMultisigSpend::new_empty(challenge.clone());
+                    for (key_index, key) in keys.iter().enumerate() {
+                        match sign_classical_multisig_spending(
+                            chain_config,
+                            key_index as u8,
+                            key,
+                            challenge,
+                            &sighash,
+                            signatures,
+                            rng,
+                        )
+                        .unwrap()
+                        {
+                            ClassicalMultisigCompletionStatus::Complete(complete) => {
+                                signatures = complete;
+                                break;
+                            }
+                            ClassicalMultisigCompletionStatus::Incomplete(incomplete) => {
+                                signatures = incomplete;
+                            }
+                        }
+                    }
+                    StandardInputSignature::new(sighash_type, signatures.encode())
+                }
+            };
+            InputWitness::Standard(signature)
+        }
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy(), "pkh")]
+    #[trace]
+    #[case(Seed::from_entropy(), "pk")]
+    #[trace]
+    #[case(Seed::from_entropy(), "multisig")]
+    fn estimate_size_of_signed_tx(#[case] seed: Seed, #[case] kind: &str) {
+        let mut rng = make_seedable_rng(seed);
+        let chain_config = create_unit_test_config();
+
+        let owners: Vec<_> = (0..rng.gen_range(1..10))
+            .map(|_| Owner::new(&mut rng, &chain_config, kind))
+            .collect();
+        let utxos: Vec<_> = owners
+            .iter()
+            .map(|owner| {
+                let amount = Amount::from_atoms(rng.gen_range(1..1_000_000));
+                TxOutput::Transfer(OutputValue::Coin(amount), owner.destination())
+            })
+            .collect();
+        let inputs = (0..owners.len())
+            .map(|_| {
+                let source_id =
+                    OutPointSourceId::Transaction(Id::new(H256::random_using(&mut rng)));
+                TxInput::from_utxo(source_id, rng.gen_range(0..10))
+            })
+            .collect();
+        let outputs = (0..rng.gen_range(1..10))
+            .map(|_| {
+                let amount = Amount::from_atoms(rng.gen_range(1..1_000_000));
+                TxOutput::Transfer(OutputValue::Coin(amount), Destination::AnyoneCanSpend)
+            })
+            .collect();
+        let tx = Transaction::new(0, inputs, outputs).unwrap();
+
+        let challenges = owners
+            .iter()
+            .filter_map(|owner| match owner {
+                Owner::Multisig(_, challenge) => Some(challenge.clone()),
+                Owner::PublicKeyHash(_) | Owner::PublicKey(_) => None,
+            })
+            .collect();
+        let dest_info_provider = TestDestinationInfoProvider(challenges);
+        let estimated_size =
+            estimate_signed_tx_size(&tx, &utxos, Some(&dest_info_provider)).unwrap();
+
+        let utxo_refs: Vec<_> = utxos.iter().map(Some).collect();
+        let witnesses = owners
+            .iter()
+            .enumerate()
+            .map(|(input_num, owner)| {
+                owner.sign(&mut rng, &chain_config, &tx, &utxo_refs, input_num)
+            })
+            .collect();
+        let actual_size = SignedTransaction::new(tx.clone(), witnesses).unwrap().encoded_size();
+
+        assert!(estimated_size.abs_diff(actual_size) * 20 <= actual_size);
+
+        // Paying the estimated rate for the unsigned transaction covers the relay fee of the signed one
+        let fee_rate =
+            estimate_fee_rate_needed(&tx, &utxos, &chain_config, Some(&dest_info_provider))
+                .unwrap();
+        let fee = fee_rate.compute_fee(tx.encoded_size()).unwrap();
+        let min_fee = chain_config.min_tx_relay_fee_rate().compute_fee(estimated_size).unwrap();
+        assert!(fee >= min_fee);
+    }
+
+    #[test]
+    fn estimation_errors() {
+        let chain_config = create_unit_test_config();
+        let source_id = OutPointSourceId::Transaction(Id::new(H256::zero()));
+        let tx = Transaction::new(0, vec![TxInput::from_utxo(source_id, 0)], vec![]).unwrap();
+
+        assert_eq!(
+            estimate_fee_rate_needed(&tx, &[], &chain_config, None),
+            Err(FeeEstimationError::UtxoCountMismatch(0, 1))
+        );
+
+        // The multisig challenge can't be known without a destination info provider
+        let destination = Destination::ClassicMultisig(PublicKeyHash::zero());
+        let utxo = TxOutput::Transfer(OutputValue::Coin(Amount::ZERO), destination.clone());
+        assert_eq!(
+            estimate_fee_rate_needed(&tx, &[utxo], &chain_config, None),
+            Err(FeeEstimationError::SizeEstimation(
+                SizeEstimationError::UnsupportedInputDestination(destination)
+            ))
+        );
+    }
+}
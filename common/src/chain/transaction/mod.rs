@@ -34,6 +34,7 @@ pub use account_nonce::*;
 pub mod utxo_outpoint;
 pub use utxo_outpoint::*;
 
+pub mod fee;
 pub mod partially_signed_transaction;
 pub mod signed_transaction;
 
@@ -16,6 +16,8 @@
 const MESSAGE_MAGIC_PREFIX: &str = "===MINTLAYER MESSAGE BEGIN===\n";
 const MESSAGE_MAGIC_SUFFIX: &str = "\n===MINTLAYER MESSAGE END===";
 
+pub mod proof_of_reserves;
+
 use randomness::{CryptoRng, Rng};
 use thiserror::Error;
 
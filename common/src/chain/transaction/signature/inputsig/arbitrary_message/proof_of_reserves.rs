@@ -0,0 +1,197 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serialization::{Decode, Encode};
+
+use crate::{
+    chain::{signature::DestinationSigError, ChainConfig, Destination, GenBlock, UtxoOutPoint},
+    primitives::{BlockHeight, Id},
+};
+
+use super::{produce_message_challenge, ArbitraryMessageSignature};
+
+/// Produce the message that is signed for a utxo in a proof of reserves.
+///
+/// The message commits to the utxo, so that a signature can't be reused for another utxo
+/// with the same destination, and to the block, so that an old proof can't be passed off
+/// as a recent one.
+pub fn produce_reserve_proof_message(
+    outpoint: &UtxoOutPoint,
+    message: &[u8],
+    block_id: &Id<GenBlock>,
+) -> Vec<u8> {
+    outpoint
+        .encode()
+        .into_iter()
+        .chain(message.iter().copied())
+        .chain(block_id.encode())
+        .collect()
+}
+
+/// The signature of a single utxo in a proof of reserves.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct ReserveProofEntry {
+    outpoint: UtxoOutPoint,
+    /// The height of the block that contains the transaction or the block reward that created
+    /// the utxo; it allows the verifier to find the utxo after it has been spent.
+    source_block_height: BlockHeight,
+    /// An arbitrary message signature; for a multisig utxo it may have fewer signatures than
+    /// the multisig requires.
+    signature: Vec<u8>,
+}
+
+impl ReserveProofEntry {
+    pub fn new(
+        outpoint: UtxoOutPoint,
+        source_block_height: BlockHeight,
+        signature: ArbitraryMessageSignature,
+    ) -> Self {
+        Self {
+            outpoint,
+            source_block_height,
+            signature: signature.into_raw(),
+        }
+    }
+
+    pub fn outpoint(&self) -> &UtxoOutPoint {
+        &self.outpoint
+    }
+
+    pub fn source_block_height(&self) -> BlockHeight {
+        self.source_block_height
+    }
+
+    /// Verify the signature of the entry against the destination of the utxo.
+    ///
+    /// A valid but incomplete multisig signature results in
+    /// [DestinationSigError::IncompleteClassicalMultisigSignature].
+    pub fn verify_signature(
+        &self,
+        chain_config: &ChainConfig,
+        message: &[u8],
+        block_id: &Id<GenBlock>,
+        destination: &Destination,
+    ) -> Result<(), DestinationSigError> {
+        let challenge = produce_message_challenge(&produce_reserve_proof_message(
+            &self.outpoint,
+            message,
+            block_id,
+        ));
+        ArbitraryMessageSignature::from_data(self.signature.clone()).verify_signature(
+            chain_config,
+            destination,
+            &challenge,
+        )
+    }
+}
+
+/// A proof that the owner of a set of utxos controlled them at the time of a given block.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct ProofOfReserves {
+    message: Vec<u8>,
+    block_id: Id<GenBlock>,
+    entries: Vec<ReserveProofEntry>,
+}
+
+impl ProofOfReserves {
+    pub fn new(message: Vec<u8>, block_id: Id<GenBlock>, entries: Vec<ReserveProofEntry>) -> Self {
+        Self {
+            message,
+            block_id,
+            entries,
+        }
+    }
+
+    pub fn message(&self) -> &[u8] {
+        &self.message
+    }
+
+    pub fn block_id(&self) -> &Id<GenBlock> {
+        &self.block_id
+    }
+
+    pub fn entries(&self) -> &[ReserveProofEntry] {
+        &self.entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto::key::{KeyKind, PrivateKey};
+    use rstest::rstest;
+    use serialization::DecodeAll;
+    use test_utils::random::{make_seedable_rng, Seed};
+
+    use crate::{
+        address::pubkeyhash::PublicKeyHash,
+        chain::{config::create_regtest, OutPointSourceId},
+        primitives::H256,
+    };
+
+    use super::*;
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn sign_verify_entry(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let chain_config = create_regtest();
+
+        let (private_key, public_key) =
+            PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let destination = Destination::PublicKeyHash(PublicKeyHash::from(&public_key));
+        let outpoint = UtxoOutPoint::new(
+            OutPointSourceId::Transaction(Id::new(H256::random_using(&mut rng))),
+            1,
+        );
+        let block_id = Id::new(H256::random_using(&mut rng));
+        let message = b"reserves".to_vec();
+
+        let signature = ArbitraryMessageSignature::produce_uniparty_signature(
+            &private_key,
+            &destination,
+            &produce_reserve_proof_message(&outpoint, &message, &block_id),
+            &mut rng,
+        )
+        .unwrap();
+        let entry = ReserveProofEntry::new(outpoint.clone(), BlockHeight::new(5), signature);
+        let proof = ProofOfReserves::new(message.clone(), block_id, vec![entry.clone()]);
+        assert_eq!(
+            ProofOfReserves::decode_all(&mut proof.encode().as_slice()).unwrap(),
+            proof
+        );
+
+        assert_eq!(
+            entry.verify_signature(&chain_config, &message, &block_id, &destination),
+            Ok(())
+        );
+
+        // The signature is bound to the message, the block and the utxo
+        let other_block_id = Id::new(H256::random_using(&mut rng));
+        assert!(entry
+            .verify_signature(&chain_config, b"other", &block_id, &destination)
+            .is_err());
+        assert!(entry
+            .verify_signature(&chain_config, &message, &other_block_id, &destination)
+            .is_err());
+        let other_entry = ReserveProofEntry {
+            outpoint: UtxoOutPoint::new(outpoint.source_id(), 0),
+            ..entry
+        };
+        assert!(other_entry
+            .verify_signature(&chain_config, &message, &block_id, &destination)
+            .is_err());
+    }
+}
@@ -17,6 +17,7 @@ use std::io::BufWriter;
 
 use randomness::{CryptoRng, Rng};
 use serialization::{Decode, DecodeAll, Encode};
+use utils::ensure;
 
 use crate::{
     chain::{
@@ -143,6 +144,12 @@ impl StandardInputSignature {
         inputs_utxos: &[Option<&TxOutput>],
         input_num: usize,
     ) -> Result<Self, DestinationSigError> {
+        let tx_errors = chain_config.is_valid_transaction(tx);
+        ensure!(
+            tx_errors.is_empty(),
+            DestinationSigError::InvalidTransaction(tx_errors)
+        );
+
         let sighash = signature_hash(sighash_type, tx, inputs_utxos, input_num)?;
         let message = sighash.encode();
 
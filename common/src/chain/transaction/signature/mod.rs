@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::chain::{ChainConfig, TxInput};
+use crate::chain::{config::TransactionValidationError, ChainConfig, TxInput};
 
 use self::{
     inputsig::{
@@ -92,6 +92,8 @@ pub enum DestinationSigError {
     InvalidClassicalMultisigAuthorization,
     #[error("Standard signature creation failed. Incomplete classical multisig authorization")]
     IncompleteClassicalMultisigAuthorization,
+    #[error("Cannot sign an invalid transaction: {0:?}")]
+    InvalidTransaction(Vec<TransactionValidationError>),
     #[error("Unsupported yet!")]
     Unsupported,
 }
@@ -21,7 +21,7 @@ use crate::{
     chain::{TransactionCreationError, TxInput},
     primitives::id::{self, H256},
 };
-use serialization::{Decode, Encode};
+use serialization::{strict::DecodeStrict, Decode, Encode};
 use utils::ensure;
 
 #[derive(Debug, Clone, PartialEq, Eq, Encode)]
@@ -137,6 +137,8 @@ impl Decode for SignedTransaction {
     }
 }
 
+impl DecodeStrict for SignedTransaction {}
+
 #[cfg(test)]
 mod tests {
     use crate::primitives::Amount;
@@ -206,6 +208,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn strict_decoding() {
+        use serialization::hex_encoded::StrictHexEncoded;
+
+        let input = TxInput::from_utxo(Id::<Transaction>::new(H256([0x50; 32])).into(), 0);
+        let tx = Transaction::new(0x00, vec![input], vec![]).unwrap();
+        let tx = SignedTransaction::new(tx, vec![InputWitness::NoSignature(None)]).unwrap();
+
+        let encoded = tx.encode();
+        assert_eq!(SignedTransaction::decode_strict(&encoded).unwrap(), tx);
+        let hex = serde_json::json!(hex::encode(&encoded));
+        let decoded: StrictHexEncoded<SignedTransaction> = serde_json::from_value(hex).unwrap();
+        assert_eq!(decoded.take(), tx);
+
+        // The zero flags that follow the version tag, with a non-minimal compact prefix
+        assert_eq!(encoded[1], 0);
+        let mut non_canonical = encoded.clone();
+        non_canonical.splice(1..2, [0b01, 0]);
+        assert!(SignedTransaction::decode_strict(&non_canonical).is_err());
+        let hex = serde_json::json!(hex::encode(&non_canonical));
+        assert!(serde_json::from_value::<StrictHexEncoded<SignedTransaction>>(hex).is_err());
+    }
+
     #[rstest]
     #[trace]
     #[case(Seed::from_entropy())]
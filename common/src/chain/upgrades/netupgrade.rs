@@ -86,6 +86,11 @@ impl<T: Ord> NetUpgrades<T> {
     pub fn all_upgrades(&self) -> &[(BlockHeight, T)] {
         &self.0
     }
+
+    /// Iterate over the upgrades along with their activation heights, in the activation order
+    pub fn iter(&self) -> impl Iterator<Item = (BlockHeight, &T)> + '_ {
+        self.0.iter().map(|(height, upgrade)| (*height, upgrade))
+    }
 }
 
 #[cfg(test)]
@@ -160,6 +165,22 @@ mod tests {
         assert_eq!(5u8, MockVersion::Five as u8);
     }
 
+    #[test]
+    fn check_iter() {
+        let (upgrades, two_height, three_height) = mock_netupgrades();
+
+        let all: Vec<_> = upgrades.iter().collect();
+        assert_eq!(
+            all,
+            vec![
+                (BlockHeight::zero(), &MockVersion::Zero),
+                (BlockHeight::one(), &MockVersion::One),
+                (two_height, &MockVersion::Two),
+                (three_height, &MockVersion::Three),
+            ]
+        );
+    }
+
     #[test]
     fn check_upgrade_height_range() {
         let (upgrades, two_height, three_height) = mock_netupgrades();
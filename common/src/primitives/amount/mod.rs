@@ -96,6 +96,11 @@ impl Amount {
             (other - self).expect("cannot be negative")
         }
     }
+
+    /// Sum up the amounts, returning `None` on overflow
+    pub fn sum_checked(iter: impl IntoIterator<Item = Amount>) -> Option<Amount> {
+        iter.into_iter().sum()
+    }
 }
 
 impl std::ops::Add for Amount {
@@ -98,6 +98,30 @@ fn sum_empty() {
     )
 }
 
+#[test]
+fn sum_checked() {
+    assert_eq!(Amount::sum_checked([]), Some(Amount::ZERO));
+    assert_eq!(
+        Amount::sum_checked([Amount::from_atoms(1), Amount::from_atoms(2)]),
+        Some(Amount::from_atoms(3))
+    );
+    assert_eq!(
+        Amount::sum_checked([Amount::MAX, Amount::ZERO]),
+        Some(Amount::MAX)
+    );
+    assert_eq!(
+        Amount::sum_checked([
+            (Amount::MAX - Amount::from_atoms(1)).unwrap(),
+            Amount::from_atoms(1)
+        ]),
+        Some(Amount::MAX)
+    );
+    assert_eq!(
+        Amount::sum_checked([Amount::MAX, Amount::from_atoms(1)]),
+        None
+    );
+}
+
 #[test]
 fn sub_underflow() {
     assert_eq!(
@@ -152,6 +152,15 @@ impl BlockHeight {
         self.0.checked_add(rhs).map(Self::new)
     }
 
+    pub fn checked_sub(&self, rhs: HeightIntType) -> Option<Self> {
+        self.0.checked_sub(rhs).map(Self::new)
+    }
+
+    /// Subtract the given number of blocks, stopping at the genesis height instead of underflowing
+    pub fn saturating_sub(&self, rhs: HeightIntType) -> Self {
+        Self(self.0.saturating_sub(rhs))
+    }
+
     pub fn next_height(&self) -> BlockHeight {
         BlockHeight(self.0.checked_add(1).expect("Block height overflow"))
     }
@@ -340,6 +349,20 @@ mod tests {
         check(BlockHeight::new(u64::MAX));
     }
 
+    #[test]
+    fn saturating_sub() {
+        let h_5 = BlockHeight::new(5);
+        assert_eq!(h_5.saturating_sub(2), BlockHeight::new(3));
+        assert_eq!(h_5.saturating_sub(5), BlockHeight::zero());
+        assert_eq!(h_5.saturating_sub(6), BlockHeight::zero());
+        assert_eq!(h_5.saturating_sub(u64::MAX), BlockHeight::zero());
+        assert_eq!(BlockHeight::zero().saturating_sub(1), BlockHeight::zero());
+
+        assert_eq!(h_5.checked_sub(2), Some(BlockHeight::new(3)));
+        assert_eq!(h_5.checked_sub(5), Some(BlockHeight::zero()));
+        assert_eq!(h_5.checked_sub(6), None);
+    }
+
     #[test]
     fn iteration() {
         let heights = BlockHeight::new(1)
@@ -47,6 +47,7 @@ pub use crate::{
     error::ConsensusVerificationError,
     pos::{
         block_sig::BlockSignatureError,
+        block_time::expected_block_time,
         calc_pos_hash_from_prv_key, check_pos_hash, compact_target_to_target,
         error::ConsensusPoSError,
         find_timestamp_for_staking,
@@ -57,7 +58,10 @@ pub use crate::{
         },
         kernel::get_kernel_output,
         stake,
-        target::{calculate_target_required, calculate_target_required_from_block_index},
+        target::{
+            calculate_target_adjustment_from_block_index, calculate_target_required,
+            calculate_target_required_from_block_index, TargetAdjustment,
+        },
         EffectivePoolBalanceError, StakeResult,
     },
     pow::{
@@ -221,7 +225,7 @@ pub fn finalize_consensus_data(
     }
 }
 
-fn get_ancestor_from_block_index_handle(
+pub fn get_ancestor_from_block_index_handle(
     block_handle: &impl BlockIndexHandle,
     block_index: &BlockIndex,
     ancestor_height: BlockHeight,
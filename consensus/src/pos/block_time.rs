@@ -0,0 +1,110 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use common::{primitives::Amount, Uint256, Uint512};
+
+/// The expected time until the next block is found by any of the pools.
+///
+/// Each second every pool gets one attempt, which succeeds if the stake hash, which is uniformly
+/// distributed over the 256-bit range, doesn't exceed `target * effective_balance`
+/// (see `check_pos_hash`). So the pools together find a block with the probability of
+/// `target * total_effective_balance / 2^256` per second and the expected delay is the inverse
+/// of that.
+///
+/// Returns `None` if no block can be found, i.e. if there is no stake. The result is never less
+/// than a second, because the timestamps of consecutive PoS blocks must differ.
+pub fn expected_block_time(target: &Uint256, total_effective_balance: Amount) -> Option<Duration> {
+    let target: Uint512 = (*target).into();
+    let total_effective_balance: Uint512 = total_effective_balance.into();
+    let chance_numerator = (target * total_effective_balance)
+        .expect("Cannot fail because both were converted from smaller type");
+
+    let hash_space = Uint512::ONE << Uint256::BITS as usize;
+    let expected_secs = hash_space.checked_div(&chance_numerator)?;
+    let expected_secs = if expected_secs.bits() > u64::BITS as usize {
+        u64::MAX
+    } else {
+        expected_secs.low_u64()
+    };
+
+    Some(Duration::from_secs(std::cmp::max(expected_secs, 1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use randomness::Rng;
+    use rstest::rstest;
+    use test_utils::random::{make_seedable_rng, Seed};
+
+    use super::*;
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn proportional_to_stake_and_target(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+
+        // target * balance == 2^256 / expected_secs
+        let expected_secs = rng.gen_range(1000..10_000u64);
+        let target = Uint256::ONE << 200;
+        let balance = Amount::from_atoms((1u128 << 56) / expected_secs as u128);
+        let base = expected_block_time(&target, balance).unwrap().as_secs();
+        assert!(base >= expected_secs);
+
+        // More stake means faster blocks
+        let factor = rng.gen_range(2..10u128);
+        let balance_mul = (balance * factor).unwrap();
+        let time = expected_block_time(&target, balance_mul).unwrap().as_secs();
+        assert!(time * factor as u64 <= base && base < (time + 1) * factor as u64);
+
+        // Same for the target
+        let target_mul = target * Uint256::from_u64(factor as u64);
+        assert_eq!(
+            expected_block_time(&target_mul.unwrap(), balance).unwrap().as_secs(),
+            time
+        );
+    }
+
+    #[test]
+    fn edge_cases() {
+        let target = Uint256::ONE << 200;
+
+        assert_eq!(expected_block_time(&target, Amount::ZERO), None);
+        assert_eq!(
+            expected_block_time(&Uint256::ZERO, Amount::from_atoms(1)),
+            None
+        );
+
+        // The chance is 1/2 per second
+        assert_eq!(
+            expected_block_time(&target, Amount::from_atoms(1 << 55)),
+            Some(Duration::from_secs(2))
+        );
+
+        // Blocks can be found every second
+        assert_eq!(
+            expected_block_time(&Uint256::MAX, Amount::MAX),
+            Some(Duration::from_secs(1))
+        );
+
+        // A tiny chance
+        assert_eq!(
+            expected_block_time(&Uint256::ONE, Amount::from_atoms(1)),
+            Some(Duration::from_secs(u64::MAX))
+        );
+    }
+}
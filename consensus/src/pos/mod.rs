@@ -14,6 +14,7 @@
 // limitations under the License.
 
 pub mod block_sig;
+pub mod block_time;
 pub mod error;
 pub mod hash_check;
 pub mod input_data;
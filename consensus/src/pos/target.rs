@@ -21,7 +21,7 @@ use common::{
         block::ConsensusData, ChainConfig, GenBlock, GenBlockId, PoSChainConfig, PoSStatus,
         RequiredConsensus,
     },
-    primitives::{BlockDistance, BlockHeight, Compact, Id},
+    primitives::{BlockHeight, Compact, Id},
     Uint256, Uint512,
 };
 use utils::ensure;
@@ -39,10 +39,10 @@ where
     // Average is calculated based on 2 timestamps and then is divided by number of blocks in between.
     // Choose a block from the history that would be the start of a timespan.
     // It can cross net version but not genesis.
-    let block_distance_to_average =
-        BlockDistance::new(pos_config.block_count_to_average_for_blocktime() as i64 - 1);
-    let block_height_to_start_averaging =
-        (block_index.block_height() - block_distance_to_average).unwrap_or(BlockHeight::zero());
+    let block_count_to_average = pos_config.block_count_to_average_for_blocktime() as u64;
+    let block_height_to_start_averaging = block_index
+        .block_height()
+        .saturating_sub(block_count_to_average.saturating_sub(1));
 
     ensure!(
         block_index.block_height() > block_height_to_start_averaging,
@@ -107,12 +107,51 @@ fn calculate_new_target(
     Ok(Compact::from(new_target))
 }
 
+/// The values derived by the retarget algorithm when calculating the target for a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TargetAdjustment {
+    /// The average block time (in seconds) over the averaging window ending at the previous
+    /// block; `None` if the target doesn't depend on it (e.g. the initial difficulty is used).
+    pub average_block_time: Option<u64>,
+    /// The target required for the block.
+    pub target: Compact,
+}
+
+impl TargetAdjustment {
+    fn fixed(target: Compact) -> Self {
+        Self {
+            average_block_time: None,
+            target,
+        }
+    }
+}
+
 pub fn calculate_target_required_from_block_index<F>(
     chain_config: &ChainConfig,
     pos_status: &PoSStatus,
     prev_gen_block_index: &GenBlockIndex,
     get_ancestor: F,
 ) -> Result<Compact, ConsensusPoSError>
+where
+    F: Fn(&BlockIndex, BlockHeight) -> Result<GenBlockIndex, crate::ChainstateError>,
+{
+    calculate_target_adjustment_from_block_index(
+        chain_config,
+        pos_status,
+        prev_gen_block_index,
+        get_ancestor,
+    )
+    .map(|adjustment| adjustment.target)
+}
+
+/// Same as `calculate_target_required_from_block_index`, but also returns the intermediate
+/// values the target was derived from.
+pub fn calculate_target_adjustment_from_block_index<F>(
+    chain_config: &ChainConfig,
+    pos_status: &PoSStatus,
+    prev_gen_block_index: &GenBlockIndex,
+    get_ancestor: F,
+) -> Result<TargetAdjustment, ConsensusPoSError>
 where
     F: Fn(&BlockIndex, BlockHeight) -> Result<GenBlockIndex, crate::ChainstateError>,
 {
@@ -121,14 +160,16 @@ where
             initial_difficulty,
             config,
         } => match initial_difficulty {
-            Some(difficulty) => return Ok(*difficulty),
+            Some(difficulty) => return Ok(TargetAdjustment::fixed(*difficulty)),
             None => config,
         },
         PoSStatus::Ongoing(config) => config,
     };
 
     let prev_block_index = match prev_gen_block_index {
-        GenBlockIndex::Genesis(_) => return Ok(pos_config.target_limit().into()),
+        GenBlockIndex::Genesis(_) => {
+            return Ok(TargetAdjustment::fixed(pos_config.target_limit().into()))
+        }
         GenBlockIndex::Block(block_index) => block_index,
     };
 
@@ -166,6 +207,7 @@ pub fn calculate_target_required(
     };
 
     calculate_target_required_internal(chain_config, pos_config, &prev_block_index, get_ancestor)
+        .map(|adjustment| adjustment.target)
 }
 
 fn calculate_target_required_internal<F>(
@@ -173,7 +215,7 @@ fn calculate_target_required_internal<F>(
     pos_config: &PoSChainConfig,
     prev_block_index: &BlockIndex,
     get_ancestor: F,
-) -> Result<Compact, ConsensusPoSError>
+) -> Result<TargetAdjustment, ConsensusPoSError>
 where
     F: Fn(&BlockIndex, BlockHeight) -> Result<GenBlockIndex, crate::ChainstateError>,
 {
@@ -188,7 +230,7 @@ where
                 config: _,
             } => {
                 if let Some(difficulty) = initial_difficulty {
-                    return Ok(difficulty);
+                    return Ok(TargetAdjustment::fixed(difficulty));
                 }
             }
             PoSStatus::Ongoing(_) => { /*do nothing*/ }
@@ -218,12 +260,17 @@ where
     let target_block_time = NonZeroU64::new(chain_config.target_block_spacing().as_secs())
         .ok_or(ConsensusPoSError::InvalidTargetBlockTime)?;
 
-    calculate_new_target(
+    let target = calculate_new_target(
         pos_config,
         &prev_target,
         average_block_time,
         target_block_time,
-    )
+    )?;
+
+    Ok(TargetAdjustment {
+        average_block_time: Some(average_block_time),
+        target,
+    })
 }
 
 #[cfg(test)]
@@ -799,6 +846,138 @@ mod tests {
         }
     }
 
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn calculate_target_adjustment_test(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let target_limit_1 = Uint256::from_u64(rng.gen::<u64>());
+        let target_limit_2 = Uint256::from_u64(rng.gen::<u64>());
+        let pos_config_1 = PoSChainConfigBuilder::new_for_unit_test()
+            .targe_limit(target_limit_1)
+            .block_count_to_average_for_blocktime(2)
+            .build();
+        let pos_config_2 = PoSChainConfigBuilder::new_for_unit_test()
+            .targe_limit(target_limit_2)
+            .block_count_to_average_for_blocktime(5)
+            .build();
+        let upgrades = vec![
+            (
+                BlockHeight::new(0),
+                ConsensusUpgrade::PoS {
+                    initial_difficulty: Some(Compact::from(target_limit_1)),
+                    config: pos_config_1,
+                },
+            ),
+            (
+                BlockHeight::new(3),
+                ConsensusUpgrade::PoS {
+                    initial_difficulty: Some(Compact::from(target_limit_2)),
+                    config: pos_config_2,
+                },
+            ),
+        ];
+        let net_upgrades = NetUpgrades::initialize(upgrades).expect("valid net-upgrades");
+        let chain_config = ConfigBuilder::test_chain()
+            .consensus_upgrades(net_upgrades)
+            .genesis_custom(Genesis::new(
+                "msg".to_owned(),
+                BlockTimestamp::from_int_seconds(0),
+                vec![],
+            ))
+            .build();
+
+        let block_index_handle = TestBlockIndexHandle::new_with_blocks(
+            &mut rng,
+            &chain_config,
+            &[1, 2, 6, 8, 10, 12, 14],
+        );
+
+        let get_ancestor = |block_index: &BlockIndex, ancestor_height: BlockHeight| {
+            get_ancestor_from_block_index_handle(&block_index_handle, block_index, ancestor_height)
+        };
+        let prev_block_index = |height: u64| {
+            block_index_handle
+                .get_block_index_by_height(BlockHeight::new(height))
+                .unwrap()
+                .clone()
+                .into_gen_block_index()
+        };
+        let target_required = |height: u64| {
+            calculate_target_required(
+                &chain_config,
+                &get_pos_status(&chain_config, BlockHeight::new(height)),
+                prev_block_index(height - 1).block_id(),
+                &block_index_handle,
+            )
+            .unwrap()
+        };
+
+        // The initial difficulty doesn't depend on the block times
+        let adjustment = calculate_target_adjustment_from_block_index(
+            &chain_config,
+            &get_pos_status(&chain_config, BlockHeight::new(1)),
+            &GenBlockIndex::genesis(&chain_config),
+            get_ancestor,
+        )
+        .unwrap();
+        assert_eq!(
+            adjustment,
+            TargetAdjustment {
+                average_block_time: None,
+                target: Compact::from(target_limit_1),
+            }
+        );
+
+        // Average between genesis and block 1
+        let adjustment = calculate_target_adjustment_from_block_index(
+            &chain_config,
+            &get_pos_status(&chain_config, BlockHeight::new(2)),
+            &prev_block_index(1),
+            get_ancestor,
+        )
+        .unwrap();
+        assert_eq!(
+            adjustment,
+            TargetAdjustment {
+                average_block_time: Some(1),
+                target: target_required(2),
+            }
+        );
+
+        // Net version threshold
+        let adjustment = calculate_target_adjustment_from_block_index(
+            &chain_config,
+            &get_pos_status(&chain_config, BlockHeight::new(3)),
+            &prev_block_index(2),
+            get_ancestor,
+        )
+        .unwrap();
+        assert_eq!(
+            adjustment,
+            TargetAdjustment {
+                average_block_time: None,
+                target: Compact::from(target_limit_2),
+            }
+        );
+
+        // Average between blocks 2 and 6, crossing the net version
+        let adjustment = calculate_target_adjustment_from_block_index(
+            &chain_config,
+            &get_pos_status(&chain_config, BlockHeight::new(7)),
+            &prev_block_index(6),
+            get_ancestor,
+        )
+        .unwrap();
+        assert_eq!(
+            adjustment,
+            TargetAdjustment {
+                average_block_time: Some(2),
+                target: target_required(7),
+            }
+        );
+    }
+
     #[rstest]
     #[trace]
     #[case(Seed::from_entropy())]
@@ -20,7 +20,7 @@ use crate::key::hdkd::derivable::{Derivable, DerivationError};
 use crate::key::hdkd::derivation_path::DerivationPath;
 use crate::key::key_holder::{ExtendedPrivateKeyHolder, ExtendedPublicKeyHolder};
 use crate::key::secp256k1::extended_keys::{
-    Secp256k1ExtendedPrivateKey, Secp256k1ExtendedPublicKey,
+    Bip32DecodeError, Secp256k1ExtendedPrivateKey, Secp256k1ExtendedPublicKey,
 };
 use crate::key::{PrivateKey, PublicKey};
 use randomness::{make_true_rng, CryptoRng, Rng};
@@ -127,6 +127,27 @@ impl ExtendedPublicKey {
             ExtendedPublicKeyHolder::Secp256k1Schnorr(k) => k.into_public_key().into(),
         }
     }
+
+    /// Serialize the key according to BIP32 and encode it with base58check
+    pub fn to_bip32_string(&self, version: [u8; 4], parent_fingerprint: [u8; 4]) -> String {
+        match self.pub_key {
+            ExtendedPublicKeyHolder::Secp256k1Schnorr(ref k) => {
+                k.to_bip32_string(version, parent_fingerprint)
+            }
+        }
+    }
+
+    /// Decode a BIP32 serialized secp256k1 key derived with the given path
+    pub fn from_bip32_string(
+        s: &str,
+        version: [u8; 4],
+        derivation_path: DerivationPath,
+    ) -> Result<Self, Bip32DecodeError> {
+        let secp_key = Secp256k1ExtendedPublicKey::from_bip32_string(s, version, derivation_path)?;
+        Ok(ExtendedPublicKey {
+            pub_key: ExtendedPublicKeyHolder::Secp256k1Schnorr(secp_key),
+        })
+    }
 }
 
 impl Derivable for ExtendedPrivateKey {
@@ -20,7 +20,11 @@ use crate::key::hdkd::{
     derivable::{Derivable, DerivationError},
 };
 use crate::key::secp256k1::{Secp256k1PrivateKey, Secp256k1PublicKey};
-use crate::util::{self, new_hmac_sha_512};
+use crate::util::{
+    self,
+    base58::{self, Base58Error},
+    new_hmac_sha_512,
+};
 use hmac::{Hmac, Mac};
 use randomness::{CryptoRng, Rng};
 use secp256k1;
@@ -28,6 +32,23 @@ use serialization::{Decode, Encode};
 use sha2::Sha512;
 use std::cmp::Ordering;
 
+/// The length of an extended key serialized according to BIP32
+pub const BIP32_SERIALIZED_LENGTH: usize = 78;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq, Clone)]
+pub enum Bip32DecodeError {
+    #[error("Base58 decoding error: {0}")]
+    Base58(#[from] Base58Error),
+    #[error("Invalid serialized extended key length: {0}")]
+    InvalidLength(usize),
+    #[error("Unexpected extended key version: {0:?}")]
+    UnexpectedVersion([u8; 4]),
+    #[error("The extended key doesn't correspond to the derivation path {0}")]
+    DerivationPathMismatch(DerivationPath),
+    #[error("Invalid public key in the extended key")]
+    InvalidPublicKey,
+}
+
 /// Given a tree of keys that are derived from a master key using BIP32 rules, this struct represents
 /// the private key at one of the nodes of this tree.
 #[derive(Debug, PartialEq, Eq, Clone, Encode, Decode)]
@@ -200,6 +221,64 @@ impl Secp256k1ExtendedPublicKey {
                 .into(),
         }
     }
+
+    /// Serialize the key according to BIP32 and encode it with base58check.
+    ///
+    /// The version bytes select the network, e.g. "xpub" or "tpub"; the parent fingerprint
+    /// is only informational, since it can't be computed from the key itself.
+    pub fn to_bip32_string(&self, version: [u8; 4], parent_fingerprint: [u8; 4]) -> String {
+        let path = self.derivation_path.as_slice();
+        let child_number = path.last().map_or([0; 4], |num| num.into_encoded_be_bytes());
+
+        let mut data = Vec::with_capacity(BIP32_SERIALIZED_LENGTH);
+        data.extend_from_slice(&version);
+        // The path length is limited by MAX_PATH_SIZE, which fits into a byte
+        data.push(path.len() as u8);
+        data.extend_from_slice(&parent_fingerprint);
+        data.extend_from_slice(&child_number);
+        data.extend_from_slice(&self.chain_code.into_array());
+        data.extend_from_slice(&self.public_key.as_bytes());
+        debug_assert_eq!(data.len(), BIP32_SERIALIZED_LENGTH);
+
+        base58::encode_check(&data)
+    }
+
+    /// Decode a key produced by `to_bip32_string`.
+    ///
+    /// The serialized key only contains the depth and the last child number of its derivation
+    /// path, so the full path is provided by the caller and checked against them.
+    pub fn from_bip32_string(
+        s: &str,
+        version: [u8; 4],
+        derivation_path: DerivationPath,
+    ) -> Result<Self, Bip32DecodeError> {
+        let data = base58::decode_check(s)?;
+        if data.len() != BIP32_SERIALIZED_LENGTH {
+            return Err(Bip32DecodeError::InvalidLength(data.len()));
+        }
+
+        let data_version: [u8; 4] = data[0..4].try_into().expect("the length was checked");
+        if data_version != version {
+            return Err(Bip32DecodeError::UnexpectedVersion(data_version));
+        }
+
+        let path = derivation_path.as_slice();
+        let child_number = path.last().map_or([0; 4], |num| num.into_encoded_be_bytes());
+        if data[4] as usize != path.len() || data[9..13] != child_number {
+            return Err(Bip32DecodeError::DerivationPathMismatch(derivation_path));
+        }
+
+        let mut chain_code = [0; 32];
+        chain_code.copy_from_slice(&data[13..45]);
+        let public_key = Secp256k1PublicKey::from_bytes(&data[45..])
+            .map_err(|_| Bip32DecodeError::InvalidPublicKey)?;
+
+        Ok(Secp256k1ExtendedPublicKey {
+            derivation_path,
+            chain_code: chain_code.into(),
+            public_key,
+        })
+    }
 }
 
 impl Derivable for Secp256k1ExtendedPublicKey {
@@ -391,4 +470,51 @@ mod test {
         assert_encoded_eq(&path, path_encoded);
         assert_encoded_eq(&pk, format!("{path_encoded}{chaincode}{public}").as_str());
     }
+
+    #[test]
+    fn bip32_serialization() {
+        const XPUB_VERSION: [u8; 4] = [0x04, 0x88, 0xb2, 0x1e];
+
+        // Test vector 1 from BIP32
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master_key = Secp256k1ExtendedPrivateKey::new_master(&seed).unwrap();
+        let master_pub_key = Secp256k1ExtendedPublicKey::from_private_key(&master_key);
+        let encoded = master_pub_key.to_bip32_string(XPUB_VERSION, [0; 4]);
+        assert_eq!(
+            encoded,
+            "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8"
+        );
+        assert_eq!(
+            Secp256k1ExtendedPublicKey::from_bip32_string(
+                &encoded,
+                XPUB_VERSION,
+                DerivationPath::empty()
+            ),
+            Ok(master_pub_key)
+        );
+
+        let path = DerivationPath::from_str("m/44'/0'/1'").unwrap();
+        let pub_key = Secp256k1ExtendedPublicKey::from_private_key(
+            &master_key.derive_absolute_path(&path).unwrap(),
+        );
+        let encoded = pub_key.to_bip32_string(XPUB_VERSION, [1, 2, 3, 4]);
+        assert_eq!(
+            Secp256k1ExtendedPublicKey::from_bip32_string(&encoded, XPUB_VERSION, path.clone()),
+            Ok(pub_key)
+        );
+
+        let other_path = DerivationPath::from_str("m/44'/0'/2'").unwrap();
+        assert_eq!(
+            Secp256k1ExtendedPublicKey::from_bip32_string(
+                &encoded,
+                XPUB_VERSION,
+                other_path.clone()
+            ),
+            Err(Bip32DecodeError::DerivationPathMismatch(other_path))
+        );
+        assert_eq!(
+            Secp256k1ExtendedPublicKey::from_bip32_string(&encoded, [0x04, 0x35, 0x87, 0xcf], path),
+            Err(Bip32DecodeError::UnexpectedVersion(XPUB_VERSION))
+        );
+    }
 }
@@ -0,0 +1,152 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Base58check encoding, as used for BIP32 extended keys
+
+use sha2::{Digest, Sha256};
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+const CHECKSUM_LENGTH: usize = 4;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq, Clone)]
+pub enum Base58Error {
+    #[error("Invalid base58 character: {0}")]
+    InvalidCharacter(char),
+    #[error("The base58check data is too short")]
+    TooShort,
+    #[error("Invalid base58check checksum")]
+    InvalidChecksum,
+}
+
+fn checksum(data: &[u8]) -> [u8; CHECKSUM_LENGTH] {
+    let hash = Sha256::digest(Sha256::digest(data));
+    let mut checksum = [0; CHECKSUM_LENGTH];
+    checksum.copy_from_slice(&hash[..CHECKSUM_LENGTH]);
+    checksum
+}
+
+pub fn encode(data: &[u8]) -> String {
+    // The digits of the number in base 58, least significant first
+    let mut digits: Vec<u8> = Vec::with_capacity(data.len() * 138 / 100 + 1);
+    for byte in data {
+        let mut carry = *byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    // Each leading zero byte is encoded as the first character of the alphabet
+    let leading_zeros = data.iter().take_while(|byte| **byte == 0).count();
+
+    std::iter::repeat(ALPHABET[0] as char)
+        .take(leading_zeros)
+        .chain(digits.iter().rev().map(|digit| ALPHABET[*digit as usize] as char))
+        .collect()
+}
+
+pub fn decode(s: &str) -> Result<Vec<u8>, Base58Error> {
+    // The bytes of the number, least significant first
+    let mut bytes: Vec<u8> = Vec::with_capacity(s.len());
+    for c in s.chars() {
+        let mut carry = ALPHABET
+            .iter()
+            .position(|a| *a as char == c)
+            .ok_or(Base58Error::InvalidCharacter(c))? as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    let leading_zeros = s.chars().take_while(|c| *c == ALPHABET[0] as char).count();
+
+    Ok(std::iter::repeat(0)
+        .take(leading_zeros)
+        .chain(bytes.into_iter().rev())
+        .collect())
+}
+
+/// Encode the data followed by a 4-byte double SHA256 checksum
+pub fn encode_check(data: &[u8]) -> String {
+    let mut data_with_checksum = data.to_vec();
+    data_with_checksum.extend_from_slice(&checksum(data));
+    encode(&data_with_checksum)
+}
+
+/// Decode the data and verify its checksum
+pub fn decode_check(s: &str) -> Result<Vec<u8>, Base58Error> {
+    let mut data = decode(s)?;
+    let data_length = data.len().checked_sub(CHECKSUM_LENGTH).ok_or(Base58Error::TooShort)?;
+    let data_checksum = data.split_off(data_length);
+    if data_checksum != checksum(&data) {
+        return Err(Base58Error::InvalidChecksum);
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", "")]
+    #[case("00", "1")]
+    #[case("0000", "11")]
+    #[case("61", "2g")]
+    #[case("626262", "a3gV")]
+    #[case("636363", "aPEr")]
+    #[case("00000000000000000000", "1111111111")]
+    #[case("516b6fcd0f", "ABnLTmg")]
+    #[case("572e4794", "3EFU7m")]
+    #[case("ecac89cad93923c02321", "EJDM8drfXA6uyA")]
+    #[case("10c8511e", "Rt5zm")]
+    fn encode_decode(#[case] hex_data: &str, #[case] encoded: &str) {
+        let data = hex::decode(hex_data).unwrap();
+        assert_eq!(encode(&data), encoded);
+        assert_eq!(decode(encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn check() {
+        let data = b"some data";
+        let encoded = encode_check(data);
+        assert_eq!(decode_check(&encoded).unwrap(), data);
+
+        let mut corrupted = decode(&encoded).unwrap();
+        corrupted[0] ^= 1;
+        assert_eq!(
+            decode_check(&encode(&corrupted)),
+            Err(Base58Error::InvalidChecksum)
+        );
+        assert_eq!(decode_check("111"), Err(Base58Error::TooShort));
+        assert_eq!(
+            decode_check("0OIl"),
+            Err(Base58Error::InvalidCharacter('0'))
+        );
+    }
+}
@@ -23,6 +23,7 @@ use crate::key::hdkd::{
     derivable::DerivationError,
 };
 
+pub mod base58;
 pub mod eq;
 
 pub fn new_hmac_sha_512(key: &[u8]) -> Hmac<Sha512> {
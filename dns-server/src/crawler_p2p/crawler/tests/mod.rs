@@ -384,6 +384,7 @@ fn incompatible_node(#[case] seed: Seed) {
                 software_version: *chain_config.software_version(),
                 user_agent: mintlayer_core_user_agent(),
                 common_services: NodeType::DnsServer.into(),
+                peer_node_id: None,
             },
         },
         &mut rng,
@@ -895,5 +896,6 @@ fn make_peer_info(peer_id: PeerId, chain_config: &ChainConfig) -> PeerInfo {
         software_version: *chain_config.software_version(),
         user_agent: mintlayer_core_user_agent(),
         common_services: NodeType::DnsServer.into(),
+        peer_node_id: None,
     }
 }
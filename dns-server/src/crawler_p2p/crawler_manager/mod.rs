@@ -338,6 +338,12 @@ where
             ConnectivityEvent::Misbehaved { peer_id, error } => {
                 self.send_crawler_event(CrawlerEvent::Misbehaved { peer_id, error });
             }
+            ConnectivityEvent::PeerNodeIdReceived {
+                peer_id: _,
+                node_id: _,
+            } => {
+                unreachable!("unexpected inbound connection");
+            }
         }
     }
 
@@ -46,7 +46,7 @@ use p2p::{
         bannable_address::BannableAddress, peer_id::PeerId, services::Services,
         socket_address::SocketAddress,
     },
-    P2pEventHandler,
+    P2pEvent, P2pEventHandler,
 };
 use test_utils::BasicTestTimeGetter;
 use utils::atomics::SeqCstAtomicBool;
@@ -227,6 +227,7 @@ impl ConnectivityService<MockNetworkingService> for MockConnectivityHandle {
                     software_version: node.software_info.version,
                     user_agent: node.software_info.user_agent.clone(),
                     common_services: NodeType::DnsServer.into(),
+                    peer_node_id: None,
                 };
                 let old = self.state.connected.lock().unwrap().insert(address, peer_id);
                 assert!(old.is_none());
@@ -275,6 +276,10 @@ impl ConnectivityService<MockNetworkingService> for MockConnectivityHandle {
         Ok(())
     }
 
+    fn broadcast_event(&mut self, _event: P2pEvent) -> p2p::Result<()> {
+        Ok(())
+    }
+
     fn local_addresses(&self) -> &[SocketAddress] {
         &[]
     }
@@ -66,7 +66,11 @@ async fn run(options: DnsServerRunOptions) -> anyhow::Result<Never> {
     let p2p_config = Arc::new(P2pConfig {
         bind_addresses: Vec::new(),
         socks5_proxy: None,
+        socks5_proxy_auth: None,
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Vec::new(),
         reserved_nodes: Vec::new(),
         whitelisted_addresses: Default::default(),
@@ -74,6 +78,7 @@ async fn run(options: DnsServerRunOptions) -> anyhow::Result<Never> {
         // won't have any effect on the dns server.
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_check_period: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
@@ -82,6 +87,13 @@ async fn run(options: DnsServerRunOptions) -> anyhow::Result<Never> {
         allow_discover_private_ips: Default::default(),
         user_agent,
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
     });
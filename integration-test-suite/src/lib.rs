@@ -0,0 +1,315 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test harness running a regtest node, the wallet RPC service and the API server scanner
+//! against the same chain, so that the views of the three components can be compared.
+//!
+//! The node runs in-process with in-memory storage, the wallet service talks to it over RPC,
+//! just like the wallet RPC daemon does, and the scanner syncs into in-memory API server storage.
+
+use std::{sync::Arc, time::Duration};
+
+use api_blockchain_scanner_lib::blockchain_state::BlockchainState;
+use api_server_common::storage::{
+    impls::in_memory::transactional::TransactionalApiServerInMemoryStorage,
+    storage_api::{
+        ApiServerStorageRead, ApiServerStorageWrite, ApiServerTransactionRw, Transactional,
+    },
+};
+use common::{
+    chain::{config::regtest_options::ChainConfigOptions, ChainConfig, GenBlock},
+    primitives::{BlockHeight, Id},
+};
+use node_comm::{node_traits::NodeInterface, rpc_client::NodeRpcClient};
+use rpc::RpcAuthData;
+use test_utils::{test_dir::TestRoot, test_root};
+use wallet_rpc_lib::{
+    config::{WalletRpcConfig, WalletServiceConfig},
+    types::{AccountArg, BlockInfo},
+    WalletHandle, WalletService,
+};
+use wallet_test_node::{RPC_PASSWORD, RPC_USERNAME};
+use wallet_types::{seed_phrase::StoreSeedPhrase, wallet_type::WalletType};
+
+pub use randomness::Rng;
+pub use rpc::test_support::ClientT;
+pub use serde_json::Value as JsonValue;
+pub use test_utils::random::{make_seedable_rng, Seed};
+
+pub const ACCOUNT0_ARG: AccountArg = AccountArg(0);
+pub const ACCOUNT1_ARG: AccountArg = AccountArg(1);
+
+/// How long to wait for the whole stack to shut down
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub type ScannerStorage = TransactionalApiServerInMemoryStorage;
+
+/// Builder for [TestStack]
+pub struct TestStackBuilder {
+    chain_config_options: ChainConfigOptions,
+    mnemonic: &'static str,
+}
+
+impl TestStackBuilder {
+    pub fn new() -> Self {
+        Self {
+            chain_config_options: wallet_test_node::default_chain_config_options(),
+            mnemonic: wallet_test_node::MNEMONIC,
+        }
+    }
+
+    /// Customize the regtest chain the stack runs on
+    pub fn with_chain_config_options(mut self, options: ChainConfigOptions) -> Self {
+        self.chain_config_options = options;
+        self
+    }
+
+    /// Use a different mnemonic for the wallet.
+    ///
+    /// By default the wallet owns the genesis coins and the genesis staking pool.
+    pub fn with_mnemonic(mut self, mnemonic: &'static str) -> Self {
+        self.mnemonic = mnemonic;
+        self
+    }
+
+    /// Start the node, the wallet service and the scanner
+    pub async fn build(self, rng: &mut impl Rng) -> TestStack {
+        logging::init_logging();
+
+        let Self {
+            chain_config_options,
+            mnemonic,
+        } = self;
+
+        let chain_config = Arc::new(wallet_test_node::create_chain_config(
+            rng,
+            &chain_config_options,
+        ));
+        let chain_type = *chain_config.chain_type();
+
+        let test_root = test_root!("integration_test_suite").expect("test root creation");
+
+        // Create the wallet database
+        let wallet_path = {
+            let wallet_path = test_root.fresh_test_dir("wallet").as_ref().join("wallet.sqlite");
+            let db = wallet::wallet::open_or_create_wallet_file(&wallet_path).unwrap();
+
+            let _wallet = wallet::Wallet::create_new_wallet(
+                Arc::clone(&chain_config),
+                db,
+                mnemonic,
+                None,
+                StoreSeedPhrase::DoNotStore,
+                (BlockHeight::new(0), chain_config.genesis_block_id()),
+                WalletType::Hot,
+            )
+            .unwrap();
+
+            wallet_path
+        };
+
+        // Start the node
+        let (manager, node_rpc_addr) =
+            wallet_test_node::start_node(Arc::clone(&chain_config)).await;
+        let shutdown_trigger = manager.make_shutdown_trigger();
+        let node_manager_task = manager.main_in_task();
+
+        let make_node_rpc_client = || {
+            node_comm::make_rpc_client(
+                Arc::clone(&chain_config),
+                node_rpc_addr.to_string(),
+                RpcAuthData::Basic {
+                    username: RPC_USERNAME.to_string(),
+                    password: RPC_PASSWORD.to_string(),
+                },
+            )
+        };
+
+        // Start the wallet service
+        let (wallet_service, wallet_rpc_server) = {
+            let ws_config = WalletServiceConfig::new(chain_type, Some(wallet_path), false, vec![])
+                .with_regtest_options(chain_config_options)
+                .unwrap()
+                .with_custom_chain_config(Arc::clone(&chain_config));
+            let rpc_config = WalletRpcConfig {
+                bind_addr: "127.0.0.1:0".parse().unwrap(),
+                auth_credentials: None,
+                idempotency_key_retention: WalletRpcConfig::DEFAULT_IDEMPOTENCY_KEY_RETENTION,
+                admin_password: None,
+                audit_log: None,
+            };
+            let node_rpc = make_node_rpc_client().await.unwrap();
+
+            wallet_rpc_lib::start_services(ws_config, rpc_config, node_rpc, false)
+                .await
+                .unwrap()
+        };
+
+        // Initialize the scanner
+        let node_rpc = make_node_rpc_client().await.unwrap();
+        let scanner = {
+            let mut storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+
+            let mut db_tx = storage.transaction_rw().await.unwrap();
+            db_tx.reinitialize_storage(&chain_config).await.unwrap();
+            db_tx.commit().await.unwrap();
+
+            let mut scanner = BlockchainState::new(Arc::clone(&chain_config), storage);
+            scanner.scan_genesis(chain_config.genesis_block().as_ref()).await.unwrap();
+            scanner
+        };
+
+        TestStack {
+            chain_config,
+            node_rpc,
+            scanner,
+            wallet_service,
+            wallet_rpc_server,
+            shutdown_trigger,
+            node_manager_task,
+            test_root,
+        }
+    }
+}
+
+impl Default for TestStackBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A running node, wallet service and scanner, all following the same regtest chain
+pub struct TestStack {
+    chain_config: Arc<ChainConfig>,
+    node_rpc: NodeRpcClient,
+    scanner: BlockchainState<ScannerStorage>,
+    wallet_service: WalletService<NodeRpcClient>,
+    wallet_rpc_server: rpc::Rpc,
+    shutdown_trigger: subsystem::ShutdownTrigger,
+    node_manager_task: subsystem::ManagerJoinHandle,
+    test_root: TestRoot,
+}
+
+impl TestStack {
+    /// Start a stack with the default settings
+    pub async fn start(rng: &mut impl Rng) -> Self {
+        TestStackBuilder::new().build(rng).await
+    }
+
+    pub fn chain_config(&self) -> &Arc<ChainConfig> {
+        &self.chain_config
+    }
+
+    /// RPC client of the node, used by the scanner too
+    pub fn node(&self) -> &NodeRpcClient {
+        &self.node_rpc
+    }
+
+    pub fn wallet_handle(&self) -> WalletHandle<NodeRpcClient> {
+        self.wallet_service.handle()
+    }
+
+    /// HTTP client of the wallet RPC interface
+    pub fn wallet_rpc(&self) -> rpc::RpcHttpClient {
+        let rpc_addr = format!("http://{}", self.wallet_rpc_server.http_address());
+        rpc::new_http_client(rpc_addr, RpcAuthData::None).unwrap()
+    }
+
+    /// The API server storage filled in by the scanner
+    pub fn scanner_storage(&self) -> &ScannerStorage {
+        self.scanner.storage()
+    }
+
+    /// Generate blocks with the block rewards going to the given account of the wallet.
+    /// The transactions in the node's mempool are included in the first of them.
+    pub async fn generate_blocks(&self, account: AccountArg, block_count: u32) {
+        let _: () = self
+            .wallet_rpc()
+            .request("node_generate_blocks", (account, block_count))
+            .await
+            .unwrap();
+    }
+
+    /// Sync the wallet to the node's tip
+    pub async fn sync_wallet(&self) {
+        let _: () = self.wallet_rpc().request("wallet_sync", Vec::<u32>::new()).await.unwrap();
+    }
+
+    /// Sync the scanner to the node's tip
+    pub async fn sync_scanner(&mut self) {
+        api_blockchain_scanner_lib::sync::sync_once(
+            &self.chain_config,
+            &self.node_rpc,
+            &mut self.scanner,
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Sync both the wallet and the scanner, then check that they agree with the node on the tip
+    pub async fn sync_all(&mut self) -> (BlockHeight, Id<GenBlock>) {
+        self.sync_wallet().await;
+        self.sync_scanner().await;
+        self.assert_tips_agree().await
+    }
+
+    /// Check that the node, the wallet and the scanner all have the same best block
+    pub async fn assert_tips_agree(&self) -> (BlockHeight, Id<GenBlock>) {
+        let node_tip_id = self.node_rpc.get_best_block_id().await.unwrap();
+        let node_tip_height = self.node_rpc.get_best_block_height().await.unwrap();
+
+        let wallet_tip: BlockInfo =
+            self.wallet_rpc().request("wallet_best_block", Vec::<u32>::new()).await.unwrap();
+        assert_eq!(wallet_tip.id, node_tip_id);
+        assert_eq!(wallet_tip.height, node_tip_height);
+
+        let scanner_tip = {
+            let db_tx = self.scanner_storage().transaction_ro().await.unwrap();
+            db_tx.get_best_block().await.unwrap()
+        };
+        assert_eq!(scanner_tip.block_id(), node_tip_id);
+        assert_eq!(scanner_tip.block_height(), node_tip_height);
+
+        (node_tip_height, node_tip_id)
+    }
+
+    pub async fn stop(self) {
+        let TestStack {
+            chain_config: _,
+            node_rpc,
+            scanner: _,
+            wallet_service,
+            wallet_rpc_server,
+            shutdown_trigger,
+            node_manager_task,
+            test_root,
+        } = self;
+
+        let wallet_handle = wallet_service.handle();
+
+        let shutdown_sequence = async {
+            wallet_handle.stop().unwrap();
+            wallet_rpc_lib::wait_for_shutdown(wallet_service, wallet_rpc_server).await;
+
+            std::mem::drop(node_rpc);
+            shutdown_trigger.initiate();
+            node_manager_task.join().await;
+        };
+
+        tokio::time::timeout(SHUTDOWN_TIMEOUT, shutdown_sequence).await.unwrap();
+
+        test_root.delete();
+    }
+}
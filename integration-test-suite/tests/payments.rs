@@ -0,0 +1,271 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api_server_common::storage::storage_api::{ApiServerStorageRead, Transactional};
+use common::{
+    address::RpcAddress,
+    primitives::{Amount, CoinOrTokenId},
+};
+use integration_test_suite::{
+    make_seedable_rng, ClientT, Rng, Seed, TestStack, ACCOUNT0_ARG, ACCOUNT1_ARG,
+};
+use rstest::rstest;
+use wallet_rpc_lib::types::{
+    AddressInfo, Balances, DuplicateOutputs, NewAccountInfo, NewTransaction, RpcAmountIn,
+    RpcUtxoOutpoint, RpcUtxoState, SendToManyOutput, TransactionOptions,
+};
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[ignore = "Starts a full node, wallet service and scanner"]
+async fn send_coins_between_accounts(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let mut stack = TestStack::start(&mut rng).await;
+    stack.sync_all().await;
+
+    let wallet_rpc = stack.wallet_rpc();
+
+    let _: NewAccountInfo = wallet_rpc.request("account_create", Vec::<u32>::new()).await.unwrap();
+
+    let utxo_states = vec![RpcUtxoState::Confirmed];
+    let balances: Balances = wallet_rpc
+        .request("account_balance", (ACCOUNT0_ARG, &utxo_states))
+        .await
+        .unwrap();
+    let coins_before = balances.coins().amount();
+
+    // Make a few payments to fresh addresses of the second account
+    let payment_count = rng.gen_range(1..=3);
+    let mut payments = Vec::new();
+    for _ in 0..payment_count {
+        let address: AddressInfo = wallet_rpc.request("address_new", [ACCOUNT1_ARG]).await.unwrap();
+        let amount = Amount::from_atoms(rng.gen_range(1..=coins_before.into_atoms() / 100));
+
+        let tx: NewTransaction = wallet_rpc
+            .request(
+                "address_send",
+                (
+                    ACCOUNT0_ARG,
+                    &address.address,
+                    RpcAmountIn::from_atoms(amount),
+                    Vec::<RpcUtxoOutpoint>::new(),
+                    TransactionOptions {
+                        in_top_x_mb: None,
+                        idempotency_key: None,
+                        spend_pending_utxos: None,
+                    },
+                ),
+            )
+            .await
+            .unwrap();
+
+        // Confirm each payment in its own block
+        stack.generate_blocks(ACCOUNT0_ARG, 1).await;
+        payments.push((address.address, amount, tx.tx_id));
+    }
+
+    let (tip_height, _) = stack.sync_all().await;
+    assert_eq!(tip_height.into_int(), payment_count);
+
+    // The wallet sees all the payments
+    let total_paid = payments
+        .iter()
+        .try_fold(Amount::ZERO, |total, (_, amount, _)| total + *amount)
+        .unwrap();
+    let balances: Balances = wallet_rpc
+        .request("account_balance", (ACCOUNT1_ARG, &utxo_states))
+        .await
+        .unwrap();
+    assert_eq!(balances.coins().amount(), total_paid);
+
+    // The scanner sees the same balances and transactions per address
+    let db_tx = stack.scanner_storage().transaction_ro().await.unwrap();
+    for (address, amount, tx_id) in &payments {
+        let balance = db_tx.get_address_balance(address, CoinOrTokenId::Coin).await.unwrap();
+        assert_eq!(balance, Some(*amount));
+
+        let txs = db_tx.get_address_transactions(address).await.unwrap();
+        assert_eq!(txs, vec![*tx_id]);
+
+        let (block_id, _) = db_tx.get_transaction(*tx_id).await.unwrap().unwrap();
+        assert!(block_id.is_some());
+    }
+    std::mem::drop(db_tx);
+
+    std::mem::drop(wallet_rpc);
+    stack.stop().await;
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[ignore = "Starts a full node, wallet service and scanner"]
+async fn send_to_many_addresses(#[case] seed: Seed) {
+    const RECIPIENT_COUNT: usize = 50;
+
+    let mut rng = make_seedable_rng(seed);
+    let mut stack = TestStack::start(&mut rng).await;
+    stack.sync_all().await;
+
+    let wallet_rpc = stack.wallet_rpc();
+
+    let _: NewAccountInfo = wallet_rpc.request("account_create", Vec::<u32>::new()).await.unwrap();
+
+    let mut outputs = Vec::new();
+    for _ in 0..RECIPIENT_COUNT {
+        let address: AddressInfo = wallet_rpc.request("address_new", [ACCOUNT1_ARG]).await.unwrap();
+        let amount = Amount::from_atoms(rng.gen_range(1000..=1_000_000));
+        outputs.push((address.address, amount));
+    }
+    let rpc_outputs = |outputs: &[(String, Amount)]| {
+        outputs
+            .iter()
+            .map(|(address, amount)| SendToManyOutput {
+                address: RpcAddress::from_string(address.clone()),
+                amount: RpcAmountIn::from_atoms(*amount),
+                token_id: None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Duplicate addresses are refused when requested
+    let duplicate_address = outputs[rng.gen_range(0..RECIPIENT_COUNT)].0.clone();
+    let mut duplicate_outputs = rpc_outputs(&outputs);
+    duplicate_outputs.push(SendToManyOutput {
+        address: RpcAddress::from_string(duplicate_address.clone()),
+        amount: RpcAmountIn::from_atoms(Amount::ZERO),
+        token_id: None,
+    });
+    let err = wallet_rpc
+        .request::<NewTransaction, _>(
+            "address_send_to_many",
+            (
+                ACCOUNT0_ARG,
+                &duplicate_outputs,
+                Some(DuplicateOutputs::Reject),
+                TransactionOptions {
+                    in_top_x_mb: None,
+                    idempotency_key: None,
+                    spend_pending_utxos: None,
+                },
+            ),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains(&duplicate_address));
+
+    // The first invalid address is reported
+    let mut invalid_outputs = rpc_outputs(&outputs);
+    let invalid_idx = rng.gen_range(0..RECIPIENT_COUNT);
+    invalid_outputs[invalid_idx].address = RpcAddress::from_string("tmt1invalidaddress".to_owned());
+    let err = wallet_rpc
+        .request::<NewTransaction, _>(
+            "address_send_to_many",
+            (
+                ACCOUNT0_ARG,
+                &invalid_outputs,
+                Some(DuplicateOutputs::Reject),
+                TransactionOptions {
+                    in_top_x_mb: None,
+                    idempotency_key: None,
+                    spend_pending_utxos: None,
+                },
+            ),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("tmt1invalidaddress"));
+
+    // Pay all the recipients in one transaction
+    let batch_tx: NewTransaction = wallet_rpc
+        .request(
+            "address_send_to_many",
+            (
+                ACCOUNT0_ARG,
+                rpc_outputs(&outputs),
+                Some(DuplicateOutputs::Reject),
+                TransactionOptions {
+                    in_top_x_mb: None,
+                    idempotency_key: None,
+                    spend_pending_utxos: None,
+                },
+            ),
+        )
+        .await
+        .unwrap();
+    stack.generate_blocks(ACCOUNT0_ARG, 1).await;
+    stack.sync_all().await;
+
+    let batch_fee = {
+        let db_tx = stack.scanner_storage().transaction_ro().await.unwrap();
+        let (block_id, tx_info) = db_tx.get_transaction(batch_tx.tx_id).await.unwrap().unwrap();
+        assert!(block_id.is_some());
+
+        // All the payments are in the single transaction, plus the change
+        let tx_outputs = tx_info.tx.transaction().outputs();
+        assert!(tx_outputs.len() == RECIPIENT_COUNT || tx_outputs.len() == RECIPIENT_COUNT + 1);
+
+        for (address, amount) in &outputs {
+            let balance = db_tx.get_address_balance(address, CoinOrTokenId::Coin).await.unwrap();
+            assert_eq!(balance, Some(*amount));
+
+            let txs = db_tx.get_address_transactions(address).await.unwrap();
+            assert_eq!(txs, vec![batch_tx.tx_id]);
+        }
+
+        tx_info.additinal_info.fee
+    };
+
+    // Make the same payments one by one
+    let mut single_txs = Vec::new();
+    for (address, amount) in &outputs {
+        let tx: NewTransaction = wallet_rpc
+            .request(
+                "address_send",
+                (
+                    ACCOUNT0_ARG,
+                    address,
+                    RpcAmountIn::from_atoms(*amount),
+                    Vec::<RpcUtxoOutpoint>::new(),
+                    TransactionOptions {
+                        in_top_x_mb: None,
+                        idempotency_key: None,
+                        spend_pending_utxos: None,
+                    },
+                ),
+            )
+            .await
+            .unwrap();
+        stack.generate_blocks(ACCOUNT0_ARG, 1).await;
+        single_txs.push(tx.tx_id);
+    }
+    stack.sync_all().await;
+
+    let db_tx = stack.scanner_storage().transaction_ro().await.unwrap();
+    let mut single_fees = Amount::ZERO;
+    for tx_id in single_txs {
+        let (_, tx_info) = db_tx.get_transaction(tx_id).await.unwrap().unwrap();
+        single_fees = (single_fees + tx_info.additinal_info.fee).unwrap();
+    }
+    std::mem::drop(db_tx);
+
+    assert!(batch_fee < single_fees);
+
+    std::mem::drop(wallet_rpc);
+    stack.stop().await;
+}
@@ -0,0 +1,283 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api_server_common::storage::storage_api::{ApiServerStorageRead, Transactional};
+use common::{
+    address::RpcAddress,
+    chain::{tokens::TokenId, Transaction},
+    primitives::{Amount, CoinOrTokenId, Id},
+};
+use integration_test_suite::{
+    make_seedable_rng, ClientT, Rng, Seed, TestStack, ACCOUNT0_ARG, ACCOUNT1_ARG,
+};
+use rstest::rstest;
+use wallet_rpc_lib::types::{
+    AddressInfo, Balances, DuplicateOutputs, NewAccountInfo, NewTransaction, RpcAmountIn,
+    RpcString, RpcTokenId, RpcUtxoState, SendToManyOutput, SendToManyTransaction, TokenMetadata,
+    TokenTotalSupply, TransactionOptions,
+};
+
+/// Issue a new token and mint `amount` of it to the first account
+async fn issue_and_mint_token(stack: &mut TestStack, amount: Amount) -> TokenId {
+    let wallet_rpc = stack.wallet_rpc();
+    let address: AddressInfo = wallet_rpc.request("address_new", [ACCOUNT0_ARG]).await.unwrap();
+
+    let metadata = TokenMetadata {
+        token_ticker: RpcString::from_string("XXX".to_owned()),
+        number_of_decimals: 2,
+        metadata_uri: RpcString::from_string("http://uri".to_owned()),
+        token_supply: TokenTotalSupply::Unlimited,
+        is_freezable: false,
+    };
+    let token: RpcTokenId = wallet_rpc
+        .request(
+            "token_issue_new",
+            (
+                ACCOUNT0_ARG,
+                &address.address,
+                metadata,
+                TransactionOptions {
+                    in_top_x_mb: None,
+                    idempotency_key: None,
+                    spend_pending_utxos: None,
+                },
+            ),
+        )
+        .await
+        .unwrap();
+    stack.generate_blocks(ACCOUNT0_ARG, 1).await;
+    stack.sync_wallet().await;
+
+    let _: NewTransaction = wallet_rpc
+        .request(
+            "token_mint",
+            (
+                ACCOUNT0_ARG,
+                &token.token_id,
+                &address.address,
+                RpcAmountIn::from_atoms(amount),
+                TransactionOptions {
+                    in_top_x_mb: None,
+                    idempotency_key: None,
+                    spend_pending_utxos: None,
+                },
+            ),
+        )
+        .await
+        .unwrap();
+    stack.generate_blocks(ACCOUNT0_ARG, 1).await;
+    stack.sync_all().await;
+
+    token.token_id.decode_object(stack.chain_config()).unwrap()
+}
+
+/// Check that each of the addresses has received its amount in the given transaction only,
+/// according to the scanner
+async fn check_payments(
+    stack: &TestStack,
+    tx_id: Id<Transaction>,
+    payments: &[(String, Amount, CoinOrTokenId)],
+) {
+    let db_tx = stack.scanner_storage().transaction_ro().await.unwrap();
+
+    let (block_id, _) = db_tx.get_transaction(tx_id).await.unwrap().unwrap();
+    assert!(block_id.is_some());
+
+    for (address, amount, currency) in payments {
+        let balance = db_tx.get_address_balance(address, *currency).await.unwrap();
+        assert_eq!(balance, Some(*amount));
+
+        let txs = db_tx.get_address_transactions(address).await.unwrap();
+        assert_eq!(txs, vec![tx_id]);
+    }
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[ignore = "Starts a full node, wallet service and scanner"]
+async fn send_to_many_coins(#[case] seed: Seed) {
+    const RECIPIENT_COUNT: usize = 10;
+
+    let mut rng = make_seedable_rng(seed);
+    let mut stack = TestStack::start(&mut rng).await;
+    stack.sync_all().await;
+
+    let wallet_rpc = stack.wallet_rpc();
+    let _: NewAccountInfo = wallet_rpc.request("account_create", Vec::<u32>::new()).await.unwrap();
+
+    let mut payments = Vec::new();
+    for _ in 0..RECIPIENT_COUNT {
+        let address: AddressInfo = wallet_rpc.request("address_new", [ACCOUNT1_ARG]).await.unwrap();
+        let amount = Amount::from_atoms(rng.gen_range(1000..=1_000_000));
+        payments.push((address.address, amount, CoinOrTokenId::Coin));
+    }
+
+    let outputs: Vec<_> = payments
+        .iter()
+        .map(|(address, amount, _)| SendToManyOutput {
+            address: RpcAddress::from_string(address.clone()),
+            amount: RpcAmountIn::from_atoms(*amount),
+            token_id: None,
+        })
+        .collect();
+    let tx: SendToManyTransaction = wallet_rpc
+        .request(
+            "address_send_to_many",
+            (
+                ACCOUNT0_ARG,
+                outputs,
+                Some(DuplicateOutputs::Reject),
+                TransactionOptions {
+                    in_top_x_mb: None,
+                    idempotency_key: None,
+                    spend_pending_utxos: None,
+                },
+            ),
+        )
+        .await
+        .unwrap();
+    assert!(tx.merged_addresses.is_empty());
+    assert!(tx.fees.coins().amount() > Amount::ZERO);
+    assert_eq!(
+        Some(tx.fee_per_recipient.amount()),
+        tx.fees.coins().amount() / RECIPIENT_COUNT as u128
+    );
+
+    stack.generate_blocks(ACCOUNT0_ARG, 1).await;
+    stack.sync_all().await;
+
+    check_payments(&stack, tx.tx_id, &payments).await;
+
+    // The receiving account sees the total amount
+    let total_paid = payments
+        .iter()
+        .try_fold(Amount::ZERO, |total, (_, amount, _)| total + *amount)
+        .unwrap();
+    let balances: Balances = wallet_rpc
+        .request(
+            "account_balance",
+            (ACCOUNT1_ARG, vec![RpcUtxoState::Confirmed]),
+        )
+        .await
+        .unwrap();
+    assert_eq!(balances.coins().amount(), total_paid);
+
+    std::mem::drop(wallet_rpc);
+    stack.stop().await;
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[ignore = "Starts a full node, wallet service and scanner"]
+async fn send_to_many_coins_and_tokens(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let mut stack = TestStack::start(&mut rng).await;
+    stack.sync_all().await;
+
+    let token_id = issue_and_mint_token(&mut stack, Amount::from_atoms(1_000_000_000)).await;
+    let rpc_token_id = RpcAddress::new(stack.chain_config(), token_id).unwrap();
+
+    let wallet_rpc = stack.wallet_rpc();
+    let _: NewAccountInfo = wallet_rpc.request("account_create", Vec::<u32>::new()).await.unwrap();
+
+    let mut payments = Vec::new();
+    for i in 0..rng.gen_range(2..=10) {
+        let address: AddressInfo = wallet_rpc.request("address_new", [ACCOUNT1_ARG]).await.unwrap();
+        let amount = Amount::from_atoms(rng.gen_range(1000..=1_000_000));
+        // Make sure both the coins and the token are sent
+        let currency = if i % 2 == 0 {
+            CoinOrTokenId::Coin
+        } else {
+            CoinOrTokenId::TokenId(token_id)
+        };
+        payments.push((address.address, amount, currency));
+    }
+
+    let make_output =
+        |address: &String, amount: Amount, currency: &CoinOrTokenId| SendToManyOutput {
+            address: RpcAddress::from_string(address.clone()),
+            amount: RpcAmountIn::from_atoms(amount),
+            token_id: match currency {
+                CoinOrTokenId::Coin => None,
+                CoinOrTokenId::TokenId(_) => Some(rpc_token_id.clone()),
+            },
+        };
+    let mut outputs: Vec<_> = payments
+        .iter()
+        .map(|(address, amount, currency)| make_output(address, *amount, currency))
+        .collect();
+
+    // Pay one of the addresses twice, by default the payments are merged into a single output
+    let extra_amount = Amount::from_atoms(rng.gen_range(1000..=1_000_000));
+    let merged_idx = rng.gen_range(0..payments.len());
+    let (merged_address, merged_amount, merged_currency) = &mut payments[merged_idx];
+    outputs.push(make_output(merged_address, extra_amount, merged_currency));
+    *merged_amount = (*merged_amount + extra_amount).unwrap();
+    let merged_address = merged_address.clone();
+
+    let tx: SendToManyTransaction = wallet_rpc
+        .request(
+            "address_send_to_many",
+            (
+                ACCOUNT0_ARG,
+                outputs,
+                None::<DuplicateOutputs>,
+                TransactionOptions {
+                    in_top_x_mb: None,
+                    idempotency_key: None,
+                    spend_pending_utxos: None,
+                },
+            ),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        tx.merged_addresses,
+        vec![RpcAddress::from_string(merged_address)]
+    );
+
+    stack.generate_blocks(ACCOUNT0_ARG, 1).await;
+    stack.sync_all().await;
+
+    check_payments(&stack, tx.tx_id, &payments).await;
+
+    // The receiving account sees the total amounts of both currencies
+    let total_paid = |paid_currency: CoinOrTokenId| {
+        payments
+            .iter()
+            .filter(|(_, _, currency)| *currency == paid_currency)
+            .try_fold(Amount::ZERO, |total, (_, amount, _)| total + *amount)
+            .unwrap()
+    };
+    let balances: Balances = wallet_rpc
+        .request(
+            "account_balance",
+            (ACCOUNT1_ARG, vec![RpcUtxoState::Confirmed]),
+        )
+        .await
+        .unwrap();
+    assert_eq!(balances.coins().amount(), total_paid(CoinOrTokenId::Coin));
+    assert_eq!(
+        balances.tokens().get(&rpc_token_id).map(|amount| amount.amount()),
+        Some(total_paid(CoinOrTokenId::TokenId(token_id)))
+    );
+
+    std::mem::drop(wallet_rpc);
+    stack.stop().await;
+}
@@ -0,0 +1,145 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api_server_common::storage::storage_api::{ApiServerStorageRead, Transactional};
+use common::{
+    address::RpcAddress,
+    chain::PoolId,
+    primitives::{Amount, BlockHeight, H256},
+};
+use integration_test_suite::{make_seedable_rng, ClientT, Rng, Seed, TestStack, ACCOUNT0_ARG};
+use node_comm::node_traits::NodeInterface;
+use rstest::rstest;
+use wallet_controller::types::CreatedBlockInfo;
+use wallet_rpc_lib::types::{
+    AddressInfo, NewTransaction, PoolInfo, RpcAmountIn, TransactionOptions,
+};
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[ignore = "Starts a full node, wallet service and scanner"]
+async fn produce_blocks_with_genesis_pool(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let mut stack = TestStack::start(&mut rng).await;
+
+    let block_count = rng.gen_range(1..=10);
+    stack.generate_blocks(ACCOUNT0_ARG, block_count).await;
+    let (tip_height, _) = stack.sync_all().await;
+    assert_eq!(tip_height, BlockHeight::new(block_count.into()));
+
+    let genesis_pool_id: PoolId = H256::zero().into();
+    let genesis_pool_address =
+        RpcAddress::new(stack.chain_config(), genesis_pool_id).unwrap().into_string();
+
+    // The wallet has created all the blocks with the genesis pool
+    let created_blocks: Vec<CreatedBlockInfo> = stack
+        .wallet_rpc()
+        .request("staking_list_created_block_ids", [ACCOUNT0_ARG])
+        .await
+        .unwrap();
+    assert_eq!(created_blocks.len(), block_count as usize);
+    assert!(created_blocks.iter().all(|block| block.pool_id == genesis_pool_address));
+
+    // The scanner attributes the same blocks to the pool and agrees with the node on its balance
+    let db_tx = stack.scanner_storage().transaction_ro().await.unwrap();
+    let block_stats = db_tx
+        .get_pool_block_stats(
+            genesis_pool_id,
+            (BlockHeight::zero(), tip_height.next_height()),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(block_stats.block_count, block_count as u64);
+
+    for block in &created_blocks {
+        let block_id = block.id.classify(stack.chain_config()).chain_block_id().unwrap();
+        let aux_data = db_tx.get_block_aux_data(block_id).await.unwrap().unwrap();
+        assert_eq!(aux_data.block_height(), block.height);
+    }
+
+    let pool_data = db_tx.get_pool_data(genesis_pool_id).await.unwrap().unwrap();
+    let node_staker_balance =
+        stack.node().get_staker_balance(genesis_pool_id).await.unwrap().unwrap();
+    assert_eq!(pool_data.staker_balance().unwrap(), node_staker_balance);
+    std::mem::drop(db_tx);
+
+    stack.stop().await;
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[ignore = "Starts a full node, wallet service and scanner"]
+async fn create_stake_pool(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let mut stack = TestStack::start(&mut rng).await;
+    stack.sync_all().await;
+
+    let wallet_rpc = stack.wallet_rpc();
+
+    let min_pledge = stack.chain_config().min_stake_pool_pledge();
+    let pledge = (min_pledge * rng.gen_range(1..=3)).unwrap();
+    let decommission_address: AddressInfo =
+        wallet_rpc.request("address_new", [ACCOUNT0_ARG]).await.unwrap();
+
+    let tx: NewTransaction = wallet_rpc
+        .request(
+            "staking_create_pool",
+            (
+                ACCOUNT0_ARG,
+                RpcAmountIn::from_atoms(pledge),
+                RpcAmountIn::from_atoms(Amount::ZERO),
+                "0.1",
+                &decommission_address.address,
+                TransactionOptions {
+                    in_top_x_mb: None,
+                    idempotency_key: None,
+                    spend_pending_utxos: None,
+                },
+            ),
+        )
+        .await
+        .unwrap();
+
+    stack.generate_blocks(ACCOUNT0_ARG, 1).await;
+    let (tip_height, _) = stack.sync_all().await;
+
+    // The wallet knows the new pool
+    let pools: Vec<PoolInfo> =
+        wallet_rpc.request("staking_list_pools", [ACCOUNT0_ARG]).await.unwrap();
+    let pool = pools.iter().find(|pool| pool.height == tip_height).unwrap();
+    assert_eq!(pool.pledge.amount(), pledge);
+    let pool_id = pool.pool_id.decode_object(stack.chain_config()).unwrap();
+
+    // The node and the scanner agree with the wallet on the pool's balance
+    let node_balance = stack.node().get_stake_pool_balance(pool_id).await.unwrap();
+    assert_eq!(node_balance, Some(pool.balance.amount()));
+
+    let db_tx = stack.scanner_storage().transaction_ro().await.unwrap();
+    let pool_data = db_tx.get_pool_data(pool_id).await.unwrap().unwrap();
+    assert_eq!(pool_data.pledge_amount(), pledge);
+    assert_eq!(pool_data.staker_balance().unwrap(), pool.balance.amount());
+
+    let (block_id, _) = db_tx.get_transaction(tx.tx_id).await.unwrap().unwrap();
+    assert!(block_id.is_some());
+    std::mem::drop(db_tx);
+
+    std::mem::drop(wallet_rpc);
+    stack.stop().await;
+}
@@ -0,0 +1,186 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use chainstate_test_framework::{empty_witness, TestFramework, TransactionBuilder};
+use common::{
+    chain::{
+        block::timestamp::BlockTimestamp, output_value::OutputValue, Destination, GenBlock,
+        OutPointSourceId, SignedTransaction, TxInput, TxOutput,
+    },
+    primitives::{Amount, Id, Idable},
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+use mempool::{
+    tx_accumulator::{DefaultTxAccumulator, PackingStrategy},
+    tx_origin::LocalTxOrigin,
+    MempoolConfig, MempoolHandle, TxOptions,
+};
+use test_utils::random::make_seedable_rng;
+
+const NUM_TXS: usize = 1000;
+
+/// Set up a mempool on top of a chain where the tip block provides an output for each of the
+/// returned transactions to spend.
+fn setup(
+    rt: &tokio::runtime::Runtime,
+) -> (
+    subsystem::Manager,
+    MempoolHandle,
+    Id<GenBlock>,
+    Vec<SignedTransaction>,
+) {
+    let mut rng = make_seedable_rng(1111.into());
+    let mut tf = TestFramework::builder(&mut rng).build();
+    let chain_config = Arc::clone(tf.chain_config());
+
+    let output_amount = 40_000_000_000;
+    let fanout_tx = (0..NUM_TXS).fold(
+        TransactionBuilder::new().add_input(
+            TxInput::from_utxo(
+                OutPointSourceId::BlockReward(tf.genesis().get_id().into()),
+                0,
+            ),
+            empty_witness(&mut rng),
+        ),
+        |builder, _| {
+            builder.add_output(TxOutput::Transfer(
+                OutputValue::Coin(Amount::from_atoms(output_amount)),
+                Destination::AnyoneCanSpend,
+            ))
+        },
+    );
+    let fanout_tx = fanout_tx.build();
+    let fanout_tx_id = fanout_tx.transaction().get_id();
+    let tip = tf.make_block_builder().add_transaction(fanout_tx).build_and_process(&mut rng);
+    let tip_id = (*tip.unwrap().unwrap().block_id()).into();
+
+    let txs = (0..NUM_TXS as u32)
+        .map(|i| {
+            TransactionBuilder::new()
+                .add_input(
+                    TxInput::from_utxo(fanout_tx_id.into(), i),
+                    empty_witness(&mut rng),
+                )
+                .add_output(TxOutput::Transfer(
+                    OutputValue::Coin(Amount::from_atoms(output_amount - 20_000_000_000)),
+                    Destination::AnyoneCanSpend,
+                ))
+                .build()
+        })
+        .collect();
+
+    let _guard = rt.enter();
+    let mut manager = subsystem::Manager::new("mempool-bench");
+    let chainstate = manager.add_subsystem("chainstate", tf.chainstate());
+    let mempool = mempool::make_mempool(
+        chain_config,
+        MempoolConfig::new(),
+        chainstate,
+        Default::default(),
+    );
+    let mempool = manager.add_custom_subsystem("mempool", |hdl| mempool.init(hdl));
+
+    (manager, mempool, tip_id, txs)
+}
+
+async fn accept_all(mempool: &MempoolHandle, txs: Vec<SignedTransaction>) {
+    for tx in txs {
+        mempool
+            .call_mut(|m| {
+                m.add_transaction_local(
+                    tx,
+                    LocalTxOrigin::Mempool,
+                    TxOptions::default_for(LocalTxOrigin::Mempool.into()),
+                )
+            })
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
+
+/// Keep building block templates from mempool snapshots until told to stop
+async fn build_templates(mempool: MempoolHandle, tip_id: Id<GenBlock>, stop: Arc<AtomicBool>) {
+    while !stop.load(Ordering::Relaxed) {
+        let snapshot = mempool.call(|m| m.snapshot()).await.unwrap();
+        tokio::task::spawn_blocking(move || {
+            let accumulator = Box::new(DefaultTxAccumulator::new(
+                usize::MAX,
+                tip_id,
+                BlockTimestamp::from_int_seconds(0),
+            ));
+            snapshot.collect_txs(accumulator, vec![], PackingStrategy::FillSpaceFromMempool)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    }
+}
+
+/// Measure the time it takes the mempool to accept transactions, optionally while block templates
+/// are being continuously built at the same time.
+fn bench_acceptance(c: &mut Criterion, name: &str, with_templates: bool) {
+    let rt = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+
+    c.bench_function(name, |b| {
+        b.iter_custom(|iters| {
+            let mut total = Duration::ZERO;
+            for _ in 0..iters {
+                let (manager, mempool, tip_id, txs) = setup(&rt);
+                let shutdown = manager.make_shutdown_trigger();
+                let manager_task = rt.spawn(manager.main());
+
+                let stop = Arc::new(AtomicBool::new(false));
+                let templates_task = with_templates
+                    .then(|| rt.spawn(build_templates(mempool.clone(), tip_id, Arc::clone(&stop))));
+
+                let start = Instant::now();
+                rt.block_on(accept_all(&mempool, txs));
+                total += start.elapsed();
+
+                stop.store(true, Ordering::Relaxed);
+                if let Some(templates_task) = templates_task {
+                    rt.block_on(templates_task).unwrap();
+                }
+                shutdown.initiate();
+                rt.block_on(manager_task).unwrap();
+            }
+            total
+        })
+    });
+}
+
+pub fn acceptance(c: &mut Criterion) {
+    bench_acceptance(c, "Mempool acceptance", false);
+}
+
+pub fn acceptance_while_building_templates(c: &mut Criterion) {
+    bench_acceptance(c, "Mempool acceptance while building templates", true);
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = acceptance, acceptance_while_building_templates
+);
+criterion_main!(benches);
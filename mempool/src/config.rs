@@ -141,13 +141,43 @@ make_config_setting!(
     FeeRate::from_amount_per_kb(Amount::from_atoms(100_000_000_000))
 );
 
+make_config_setting!(MaxAncestorCount, usize, 25);
+make_config_setting!(MaxAncestorSize, usize, 101_000);
+make_config_setting!(MaxDescendantCount, usize, 25);
+make_config_setting!(MaxDescendantSize, usize, 101_000);
+
 #[derive(Debug, Clone, Default)]
 pub struct MempoolConfig {
     pub min_tx_relay_fee_rate: MinTxRelayFeeRate,
+    pub max_ancestor_count: MaxAncestorCount,
+    pub max_ancestor_size: MaxAncestorSize,
+    pub max_descendant_count: MaxDescendantCount,
+    pub max_descendant_size: MaxDescendantSize,
 }
 
 impl MempoolConfig {
     pub fn new() -> Self {
         Self::default()
     }
+
+    pub fn package_limits(&self) -> PackageLimits {
+        PackageLimits {
+            max_ancestor_count: *self.max_ancestor_count,
+            max_ancestor_size: *self.max_ancestor_size,
+            max_descendant_count: *self.max_descendant_count,
+            max_descendant_size: *self.max_descendant_size,
+        }
+    }
+}
+
+/// Limits on the chains of unconfirmed transactions in mempool.
+///
+/// Counts and sizes include the transaction itself, sizes are in bytes. Transactions that don't
+/// spend outputs of other mempool transactions are not subject to these limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct PackageLimits {
+    pub max_ancestor_count: usize,
+    pub max_ancestor_size: usize,
+    pub max_descendant_count: usize,
+    pub max_descendant_size: usize,
 }
@@ -42,6 +42,8 @@ impl MempoolBanScore for Error {
             Error::Orphan(_) => 0,
             // Tip moved during validation
             Error::TipMoved => 0,
+            // Transaction options are only specified locally
+            Error::TxOptions(_) => 0,
         }
     }
 }
@@ -69,6 +71,9 @@ impl MempoolBanScore for MempoolPolicyError {
             MempoolPolicyError::InsufficientFeesToRelay { .. } => 0,
             MempoolPolicyError::InsufficientFeesToRelayRBF => 0,
 
+            // The dust threshold is a relay policy, the transaction itself is valid
+            MempoolPolicyError::DustOutput(_) => 0,
+
             // Rolling fee may be out of sync
             MempoolPolicyError::RollingFeeThresholdNotMet { .. } => 0,
 
@@ -79,6 +84,7 @@ impl MempoolBanScore for MempoolPolicyError {
             MempoolPolicyError::FeeOverflow => 0,
             MempoolPolicyError::GetParentError => 0,
             MempoolPolicyError::DescendantOfExpiredTransaction => 0,
+            MempoolPolicyError::PackageLimits(_) => 0,
         }
     }
 }
@@ -109,6 +115,9 @@ impl MempoolBanScore for ChainstateError {
             ChainstateError::FailedToReadProperty(_) => 0,
             ChainstateError::BootstrapError(_) => 0,
             ChainstateError::BlockInvalidatorError(_) => 0,
+            ChainstateError::ReindexError(_) => 0,
+            ChainstateError::TransactionDecodingError(_) => 0,
+            ChainstateError::ProofOfReservesError(_) => 0,
         }
     }
 }
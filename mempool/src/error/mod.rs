@@ -21,11 +21,11 @@ use subsystem::error::CallError;
 use thiserror::Error;
 
 use common::{
-    chain::{Block, GenBlock, Transaction},
+    chain::{Block, GenBlock, OutPointSourceId, Transaction},
     primitives::{amount::DisplayAmount, Id, H256},
 };
 
-use crate::pool::fee::Fee;
+use crate::{pool::fee::Fee, tx_options::TxOptionsError};
 
 /// Error related to the construction of transaction sequence for inclusion in a block
 #[derive(Debug, Clone, Error, PartialEq, Eq)]
@@ -50,6 +50,53 @@ pub enum Error {
     Orphan(#[from] OrphanPoolError),
     #[error("Tip moved while trying to process transaction")]
     TipMoved,
+    #[error(transparent)]
+    TxOptions(#[from] TxOptionsError),
+}
+
+/// The kind of a transaction rejection, as far as the peer that relayed the transaction
+/// is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxRejectionClass {
+    /// The transaction spends an output that has already been spent in the chain
+    DoubleSpend,
+    /// The transaction conflicts with an irreplaceable transaction in the mempool
+    Conflict,
+    /// Any other reason
+    Other,
+}
+
+impl Error {
+    /// Double spends and conflicts are not punished via the ban score, because honest peers
+    /// may relay them occasionally, e.g. due to a race with a new block.
+    pub fn rejection_class(&self) -> TxRejectionClass {
+        use chainstate::tx_verifier::error::InputCheckErrorPayload as ICE;
+
+        match self {
+            Error::Validity(TxValidationError::TxValidation(err)) => {
+                let missing_outpoint = match err {
+                    ConnectTransactionError::MissingOutputOrSpent(outpoint) => Some(outpoint),
+                    ConnectTransactionError::InputCheck(e) => match e.error() {
+                        ICE::MissingUtxo(outpoint) => Some(outpoint),
+                        ICE::UtxoView(_) | ICE::Translation(_) | ICE::Verification(_) => None,
+                    },
+                    _ => None,
+                };
+                // A missing transaction output makes the transaction an orphan instead
+                match missing_outpoint.map(|outpoint| outpoint.source_id()) {
+                    Some(OutPointSourceId::BlockReward(_)) => TxRejectionClass::DoubleSpend,
+                    Some(OutPointSourceId::Transaction(_)) | None => TxRejectionClass::Other,
+                }
+            }
+            Error::Policy(MempoolPolicyError::Conflict(MempoolConflictError::Irreplacable))
+            | Error::Orphan(OrphanPoolError::MempoolConflict) => TxRejectionClass::Conflict,
+            Error::Validity(_)
+            | Error::Policy(_)
+            | Error::Orphan(_)
+            | Error::TipMoved
+            | Error::TxOptions(_) => TxRejectionClass::Other,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Error, PartialEq, Eq)]
@@ -64,6 +111,8 @@ pub enum MempoolPolicyError {
     NoOutputs,
     #[error("Transaction exceeds the maximum block size.")]
     ExceedsMaxBlockSize,
+    #[error("Transaction output {0} is dust.")]
+    DustOutput(usize),
     #[error("Replacement transaction has fee lower than the original. Replacement fee is {replacement_fee:?}, original fee {original_fee:?}")]
     ReplacementFeeLowerThanOriginal {
         replacement_tx: H256,
@@ -101,6 +150,31 @@ pub enum MempoolPolicyError {
     DescendantOfExpiredTransaction,
     #[error("Relay fee overflow error")]
     RelayFeeOverflow,
+    #[error(transparent)]
+    PackageLimits(#[from] PackageLimitError),
+}
+
+/// Transaction would make a chain of unconfirmed transactions in mempool too long or too big
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum PackageLimitError {
+    #[error(
+        "Transaction has too many unconfirmed ancestors ({count} including itself, limit {limit})"
+    )]
+    TooManyAncestors { count: usize, limit: usize },
+    #[error("Transaction and its unconfirmed ancestors are too big ({size} bytes, limit {limit})")]
+    AncestorSizeTooLarge { size: usize, limit: usize },
+    #[error("Unconfirmed ancestor {ancestor} would have too many descendants ({count} including itself, limit {limit})")]
+    TooManyDescendants {
+        ancestor: Id<Transaction>,
+        count: usize,
+        limit: usize,
+    },
+    #[error("Unconfirmed ancestor {ancestor} and its descendants would be too big ({size} bytes, limit {limit})")]
+    DescendantSizeTooLarge {
+        ancestor: Id<Transaction>,
+        size: usize,
+        limit: usize,
+    },
 }
 
 #[derive(Debug, Clone, Error, PartialEq, Eq)]
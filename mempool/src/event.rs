@@ -19,9 +19,10 @@ use common::{
 };
 
 use crate::{
-    error::{Error, MempoolBanScore},
+    error::{Error, MempoolBanScore, TxRejectionClass},
+    pool::FeeRate,
     tx_options::TxRelayPolicy,
-    tx_origin::TxOrigin,
+    tx_origin::{RemoteTxOrigin, TxOrigin},
 };
 
 /// Event triggered when a transaction has been fully validated
@@ -30,7 +31,7 @@ pub struct TransactionProcessed {
     tx_id: Id<Transaction>,
     origin: TxOrigin,
     relay_policy: TxRelayPolicy,
-    result: crate::Result<()>,
+    result: crate::Result<FeeRate>,
 }
 
 impl TransactionProcessed {
@@ -38,7 +39,7 @@ impl TransactionProcessed {
         tx_id: Id<Transaction>,
         origin: TxOrigin,
         relay_policy: TxRelayPolicy,
-        result: crate::Result<()>,
+        result: crate::Result<FeeRate>,
     ) -> Self {
         Self {
             tx_id,
@@ -48,15 +49,21 @@ impl TransactionProcessed {
         }
     }
 
-    pub fn accepted(tx_id: Id<Transaction>, relay_policy: TxRelayPolicy, origin: TxOrigin) -> Self {
-        Self::new(tx_id, origin, relay_policy, Ok(()))
+    pub fn accepted(
+        tx_id: Id<Transaction>,
+        fee_rate: FeeRate,
+        relay_policy: TxRelayPolicy,
+        origin: TxOrigin,
+    ) -> Self {
+        Self::new(tx_id, origin, relay_policy, Ok(fee_rate))
     }
 
     pub fn rejected(tx_id: Id<Transaction>, err: Error, origin: TxOrigin) -> Self {
         Self::new(tx_id, origin, TxRelayPolicy::DontRelay, Err(err))
     }
 
-    pub fn result(&self) -> &crate::Result<()> {
+    /// The fee rate of the transaction if it was accepted, or the rejection reason
+    pub fn result(&self) -> &crate::Result<FeeRate> {
         &self.result
     }
 
@@ -81,6 +88,41 @@ impl TransactionProcessed {
     }
 }
 
+/// Event triggered when a transaction relayed by a peer is rejected because it double spends
+/// a confirmed output or conflicts with an irreplaceable transaction in mempool
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TransactionConflict {
+    tx_id: Id<Transaction>,
+    origin: RemoteTxOrigin,
+    rejection_class: TxRejectionClass,
+}
+
+impl TransactionConflict {
+    pub fn new(
+        tx_id: Id<Transaction>,
+        origin: RemoteTxOrigin,
+        rejection_class: TxRejectionClass,
+    ) -> Self {
+        Self {
+            tx_id,
+            origin,
+            rejection_class,
+        }
+    }
+
+    pub fn tx_id(&self) -> &Id<Transaction> {
+        &self.tx_id
+    }
+
+    pub fn origin(&self) -> RemoteTxOrigin {
+        self.origin
+    }
+
+    pub fn rejection_class(&self) -> TxRejectionClass {
+        self.rejection_class
+    }
+}
+
 /// Event triggered when mempool has synced up to given tip
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct NewTip {
@@ -107,6 +149,7 @@ impl NewTip {
 pub enum MempoolEvent {
     NewTip(NewTip),
     TransactionProcessed(TransactionProcessed),
+    TransactionConflict(TransactionConflict),
 }
 
 impl From<TransactionProcessed> for MempoolEvent {
@@ -115,6 +158,12 @@ impl From<TransactionProcessed> for MempoolEvent {
     }
 }
 
+impl From<TransactionConflict> for MempoolEvent {
+    fn from(event: TransactionConflict) -> Self {
+        Self::TransactionConflict(event)
+    }
+}
+
 impl From<NewTip> for MempoolEvent {
     fn from(event: NewTip) -> Self {
         Self::NewTip(event)
@@ -14,11 +14,10 @@
 // limitations under the License.
 
 use crate::{
-    error::{BlockConstructionError, Error},
+    error::Error,
     event::MempoolEvent,
-    tx_accumulator::{PackingStrategy, TransactionAccumulator},
     tx_origin::{LocalTxOrigin, RemoteTxOrigin},
-    FeeRate, MempoolMaxSize, TxOptions, TxStatus,
+    FeeRate, MempoolMaxSize, MempoolSnapshot, PackageLimits, TxOptions, TxStatus,
 };
 use common::{
     chain::{GenBlock, SignedTransaction, Transaction},
@@ -46,6 +45,14 @@ pub trait MempoolInterface: Send + Sync {
     /// Get all transactions from mempool
     fn get_all(&self) -> Vec<SignedTransaction>;
 
+    /// Get the ids of at most `max_count` relayable transactions from the main mempool
+    /// (non-orphan), the ones with the highest descendant score first
+    fn best_transaction_ids(&self, max_count: usize) -> Vec<Id<Transaction>>;
+
+    /// Get the ids of at most `n` transactions from the main mempool (non-orphan),
+    /// the ones with the highest fee rate first
+    fn get_top_n_by_fee(&self, n: usize) -> Vec<Id<Transaction>>;
+
     /// Get a specific transaction from the main mempool (non-orphan)
     fn transaction(&self, id: &Id<Transaction>) -> Option<SignedTransaction>;
 
@@ -61,16 +68,11 @@ pub trait MempoolInterface: Send + Sync {
     /// Best block ID according to mempool. May be temporarily out of sync with chainstate.
     fn best_block_id(&self) -> Id<GenBlock>;
 
-    /// Collect transactions by putting them in given accumulator
-    /// Returns the accumulator with the collected transactions
-    /// Ok(None) is returned on recoverable errors, such as if
-    /// the tip changed before collecting transactions started.
-    fn collect_txs(
-        &self,
-        tx_accumulator: Box<dyn TransactionAccumulator + Send>,
-        transaction_ids: Vec<Id<Transaction>>,
-        packing_strategy: PackingStrategy,
-    ) -> Result<Option<Box<dyn TransactionAccumulator>>, BlockConstructionError>;
+    /// Get an immutable snapshot of the main mempool (non-orphan) contents.
+    /// Block templates can be built and the contents listed from the snapshot
+    /// (see [MempoolSnapshot::collect_txs]) without blocking the mempool from
+    /// accepting transactions in the meantime.
+    fn snapshot(&self) -> Arc<MempoolSnapshot>;
 
     /// Subscribe to events emitted by mempool
     fn subscribe_to_events(&mut self, handler: Arc<dyn Fn(MempoolEvent) + Send + Sync>);
@@ -84,6 +86,10 @@ pub trait MempoolInterface: Send + Sync {
     /// Set the allowed size limit for the total of all transactions in the mempool.
     fn set_size_limit(&mut self, max_size: MempoolMaxSize) -> Result<(), Error>;
 
+    /// Get the limits on the number and total size of unconfirmed ancestors and descendants
+    /// a transaction can have in the mempool.
+    fn get_package_limits(&self) -> PackageLimits;
+
     /// Get the fee rate such that it would put the new transaction in the top X MB of the mempool
     /// making it less likely to get rejected or trimmed in the case the mempool is full
     fn get_fee_rate(&self, in_top_x_mb: usize) -> FeeRate;
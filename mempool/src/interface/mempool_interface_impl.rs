@@ -15,12 +15,11 @@
 
 use crate::{
     config::MempoolConfig,
-    error::{BlockConstructionError, Error},
+    error::Error,
     event::MempoolEvent,
     pool::memory_usage_estimator::StoreMemoryUsageEstimator,
-    tx_accumulator::{PackingStrategy, TransactionAccumulator},
     tx_origin::{LocalTxOrigin, RemoteTxOrigin},
-    FeeRate, MempoolInterface, MempoolMaxSize, TxOptions, TxStatus,
+    FeeRate, MempoolInterface, MempoolMaxSize, MempoolSnapshot, PackageLimits, TxOptions, TxStatus,
 };
 use chainstate::ChainstateEventTracingWrapper;
 use common::{
@@ -97,7 +96,9 @@ impl MempoolInterface for Mempool {
         options: TxOptions,
     ) -> Result<(), Error> {
         let tx = self.make_entry(tx, origin.into(), options);
-        let status = self.add_transaction(tx)?;
+        let status = self.add_transaction(tx);
+        self.update_metrics();
+        let status = status?;
 
         // TODO The following assertion could be avoided by parametrizing the above
         // `add_transaction` by the origin type and have the return type depend on it.
@@ -113,13 +114,23 @@ impl MempoolInterface for Mempool {
         options: TxOptions,
     ) -> Result<TxStatus, Error> {
         let tx = self.make_entry(tx, origin.into(), options);
-        self.add_transaction(tx)
+        let status = self.add_transaction(tx);
+        self.update_metrics();
+        status
     }
 
     fn get_all(&self) -> Vec<SignedTransaction> {
         self.get_all()
     }
 
+    fn best_transaction_ids(&self, max_count: usize) -> Vec<Id<Transaction>> {
+        self.best_transaction_ids(max_count)
+    }
+
+    fn get_top_n_by_fee(&self, n: usize) -> Vec<Id<Transaction>> {
+        self.get_top_n_by_fee(n)
+    }
+
     fn contains_transaction(&self, tx_id: &Id<Transaction>) -> bool {
         self.contains_transaction(tx_id)
     }
@@ -140,14 +151,8 @@ impl MempoolInterface for Mempool {
         self.best_block_id()
     }
 
-    #[tracing::instrument(skip_all)]
-    fn collect_txs(
-        &self,
-        tx_accumulator: Box<dyn TransactionAccumulator + Send>,
-        transaction_ids: Vec<Id<Transaction>>,
-        packing_strategy: PackingStrategy,
-    ) -> Result<Option<Box<dyn TransactionAccumulator>>, BlockConstructionError> {
-        self.collect_txs(tx_accumulator, transaction_ids, packing_strategy)
+    fn snapshot(&self) -> Arc<MempoolSnapshot> {
+        self.snapshot()
     }
 
     fn subscribe_to_events(&mut self, handler: Arc<dyn Fn(MempoolEvent) + Send + Sync>) {
@@ -163,7 +168,13 @@ impl MempoolInterface for Mempool {
     }
 
     fn set_size_limit(&mut self, max_size: MempoolMaxSize) -> Result<(), Error> {
-        self.set_size_limit(max_size)
+        let result = self.set_size_limit(max_size);
+        self.update_metrics();
+        result
+    }
+
+    fn get_package_limits(&self) -> PackageLimits {
+        self.package_limits()
     }
 
     fn get_fee_rate(&self, in_top_x_mb: usize) -> FeeRate {
@@ -186,6 +197,7 @@ impl MempoolInterface for Mempool {
         if let Err(err) = self.process_chainstate_event(event) {
             log::error!("Error while handling a chainstate event: {err}");
         }
+        self.update_metrics();
     }
 }
 
@@ -201,7 +213,8 @@ impl subsystem::Subsystem for Mempool {
     }
 
     fn perform_background_work_unit(&mut self) {
-        self.perform_work_unit()
+        self.perform_work_unit();
+        self.update_metrics();
     }
 
     fn has_background_work(&self) -> bool {
@@ -15,7 +15,7 @@
 
 #![deny(clippy::clone_on_ref_ptr)]
 
-pub use config::MempoolMaxSize;
+pub use config::{MempoolMaxSize, PackageLimits};
 pub use interface::{make_mempool, MempoolInterface};
 pub use mempool_types::{tx_options, tx_origin, TxOptions, TxStatus};
 
@@ -23,11 +23,15 @@ mod config;
 pub mod error;
 pub mod event;
 mod interface;
+pub mod metrics;
 mod pool;
 pub mod rpc;
 pub mod tx_accumulator;
 
-pub use {config::MempoolConfig, pool::feerate_points::find_interpolated_value, pool::FeeRate};
+pub use {
+    config::MempoolConfig, pool::feerate_points::find_interpolated_value, pool::FeeRate,
+    pool::MempoolSnapshot,
+};
 
 pub type MempoolHandle = subsystem::Handle<dyn MempoolInterface>;
 
@@ -0,0 +1,44 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::{Arc, OnceLock};
+
+use utils::metrics::{registry, Gauge};
+
+pub struct MempoolMetrics {
+    pub memory_usage: Arc<Gauge>,
+    pub tx_count: Arc<Gauge>,
+}
+
+pub fn metrics() -> &'static MempoolMetrics {
+    static METRICS: OnceLock<MempoolMetrics> = OnceLock::new();
+    METRICS.get_or_init(|| MempoolMetrics {
+        memory_usage: registry().gauge(
+            "mempool_memory_usage_bytes",
+            "The estimated memory usage of the mempool transactions",
+            &[],
+        ),
+        tx_count: registry().gauge(
+            "mempool_transactions",
+            "The number of transactions in the mempool, not including the orphans",
+            &[],
+        ),
+    })
+}
+
+/// Register the metrics, so that they are exported even before they are first updated
+pub fn register() {
+    let _ = metrics();
+}
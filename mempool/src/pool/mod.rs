@@ -24,7 +24,10 @@ use common::{
 use logging::log;
 use utils::{const_value::ConstValue, ensure, eventhandler::EventsController};
 
-pub use self::{feerate::FeeRate, tx_pool::feerate_points};
+pub use self::{
+    feerate::FeeRate,
+    tx_pool::{feerate_points, MempoolSnapshot},
+};
 
 use self::{
     entry::{TxDependency, TxEntry},
@@ -36,14 +39,13 @@ use self::{
 use crate::{
     config,
     error::{
-        BlockConstructionError, ChainstateEventError, Error, MempoolPolicyError, OrphanPoolError,
-        ReorgError,
+        ChainstateEventError, Error, MempoolPolicyError, OrphanPoolError, ReorgError,
+        TxRejectionClass,
     },
     event::{self, MempoolEvent},
-    tx_accumulator::{PackingStrategy, TransactionAccumulator},
     tx_options::{TxOptions, TxTrustPolicy},
     tx_origin::{RemoteTxOrigin, TxOrigin},
-    MempoolMaxSize, TxStatus,
+    MempoolMaxSize, PackageLimits, TxStatus,
 };
 
 mod entry;
@@ -107,6 +109,18 @@ impl<M> Mempool<M> {
         self.tx_pool.get_all()
     }
 
+    pub fn snapshot(&self) -> Arc<MempoolSnapshot> {
+        self.tx_pool.snapshot()
+    }
+
+    pub fn best_transaction_ids(&self, max_count: usize) -> Vec<Id<Transaction>> {
+        self.tx_pool.best_transaction_ids(max_count)
+    }
+
+    pub fn get_top_n_by_fee(&self, n: usize) -> Vec<Id<Transaction>> {
+        self.tx_pool.get_top_n_by_fee(n)
+    }
+
     pub fn contains_transaction(&self, tx_id: &Id<Transaction>) -> bool {
         self.tx_pool.contains_transaction(tx_id)
     }
@@ -163,11 +177,27 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
             TxTrustPolicy::Untrusted => (),
         }
 
+        let tx_id = *transaction.tx_id();
+        let origin = transaction.origin();
         let (tx_pool, mut finalizer) = self.as_tx_pool_and_finalizer();
 
-        tx_pool.add_transaction(transaction, |outcome, tx_pool| {
-            finalizer.finalize_tx(tx_pool, outcome)
-        })?
+        let result = tx_pool
+            .add_transaction(transaction, |outcome, tx_pool| {
+                finalizer.finalize_tx(tx_pool, outcome)
+            })
+            .and_then(|result| result);
+
+        // Let p2p know about double spends and conflicts, so that it can decide whether
+        // the peer that relayed the transaction should be penalized.
+        if let (Err(err), TxOrigin::Remote(origin)) = (&result, origin) {
+            let rejection_class = err.rejection_class();
+            if rejection_class != TxRejectionClass::Other {
+                let evt = event::TransactionConflict::new(tx_id, origin, rejection_class);
+                self.events_controller.broadcast(evt.into());
+            }
+        }
+
+        result
     }
 
     /// Make transaction entry out of a signed transaction.
@@ -256,10 +286,20 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
         self.tx_pool.set_max_size(max_size)
     }
 
+    pub fn package_limits(&self) -> PackageLimits {
+        self.tx_pool.package_limits()
+    }
+
     pub fn memory_usage(&self) -> usize {
         self.tx_pool.memory_usage()
     }
 
+    pub fn update_metrics(&self) {
+        let metrics = crate::metrics::metrics();
+        metrics.memory_usage.set(self.memory_usage() as i64);
+        metrics.tx_count.set(self.tx_pool.tx_count() as i64);
+    }
+
     pub fn get_fee_rate(&self, in_top_x_mb: usize) -> FeeRate {
         self.tx_pool.get_fee_rate(in_top_x_mb)
     }
@@ -270,15 +310,6 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
     ) -> Result<Vec<(usize, FeeRate)>, MempoolPolicyError> {
         self.tx_pool.get_fee_rate_points(num_points)
     }
-
-    pub fn collect_txs(
-        &self,
-        tx_accumulator: Box<dyn TransactionAccumulator>,
-        transaction_ids: Vec<Id<Transaction>>,
-        packing_strategy: PackingStrategy,
-    ) -> Result<Option<Box<dyn TransactionAccumulator>>, BlockConstructionError> {
-        self.tx_pool.collect_txs(tx_accumulator, transaction_ids, packing_strategy)
-    }
 }
 
 /// [TxFinalizer] holds data needed to finalize the transaction processing after it's been processed
@@ -323,7 +354,9 @@ impl<'a> TxFinalizer<'a> {
                 log::trace!("Added transaction {tx_id}");
 
                 self.enqueue_children(transaction.tx_entry());
-                let evt = event::TransactionProcessed::accepted(tx_id, relay_policy, origin);
+                let fee_rate = transaction.fee_rate();
+                let evt =
+                    event::TransactionProcessed::accepted(tx_id, fee_rate, relay_policy, origin);
                 self.events_controller.broadcast(evt.into());
                 Ok(TxStatus::InMempool)
             }
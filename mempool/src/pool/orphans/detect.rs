@@ -14,6 +14,7 @@
 // limitations under the License.
 
 use chainstate::ConnectTransactionError;
+use common::chain::{OutPointSourceId, UtxoOutPoint};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
 pub enum OrphanType {
@@ -22,6 +23,15 @@ pub enum OrphanType {
 }
 
 impl OrphanType {
+    /// Block rewards never enter the mempool, so a transaction spending a missing block reward
+    /// output can't be an orphan; the output has most likely been spent already.
+    fn missing_utxo(outpoint: &UtxoOutPoint) -> Option<Self> {
+        match outpoint.source_id() {
+            OutPointSourceId::Transaction(_) => Some(Self::MissingUtxo),
+            OutPointSourceId::BlockReward(_) => None,
+        }
+    }
+
     /// Check an error signifies a potential orphan transaction
     pub fn from_error(err: ConnectTransactionError) -> Result<Self, ConnectTransactionError> {
         use chainstate::tx_verifier::error::InputCheckErrorPayload as ICE;
@@ -29,7 +39,7 @@ impl OrphanType {
 
         match &err {
             // Missing UTXO signifies a possible orphan
-            CTE::MissingOutputOrSpent(_) => Ok(Self::MissingUtxo),
+            CTE::MissingOutputOrSpent(outpoint) => Self::missing_utxo(outpoint).ok_or(err),
 
             // Nonce gap signifies a possible orphan
             CTE::NonceIsNotIncremental(_acct, expected, got) => {
@@ -38,7 +48,7 @@ impl OrphanType {
 
             CTE::InputCheck(e) => match e.error() {
                 // Missing UTXO signifies a possible orphan
-                ICE::MissingUtxo(_) => Ok(Self::MissingUtxo),
+                ICE::MissingUtxo(outpoint) => Self::missing_utxo(outpoint).ok_or(err),
                 ICE::UtxoView(_) | ICE::Translation(_) | ICE::Verification(_) => Err(err),
             },
 
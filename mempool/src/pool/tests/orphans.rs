@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::tx_origin::LocalTxOrigin;
+use crate::{error::TxRejectionClass, tx_origin::LocalTxOrigin, MempoolConfig};
 use common::primitives::id::hash_encoded;
 
 use super::*;
@@ -201,12 +201,41 @@ async fn orphan_conflicts_with_mempool_tx(#[case] seed: Seed) {
 
     // Check transaction that conflicts with one in mempool gets rejected instead of ending up in
     // the orphan pool.
+    let result = mempool.add_transaction_test(tx1b);
+    assert_eq!(result, Err(OrphanPoolError::MempoolConflict.into()));
     assert_eq!(
-        mempool.add_transaction_test(tx1b),
-        Err(OrphanPoolError::MempoolConflict.into()),
+        result.unwrap_err().rejection_class(),
+        TxRejectionClass::Conflict
     );
 }
 
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn spent_block_reward_output_not_orphan(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let mut tf = TestFramework::builder(&mut rng).build();
+    let genesis_outpt = OutPointSourceId::BlockReward(tf.genesis().get_id().into());
+
+    // Spend the genesis output in a block
+    let tx0 = make_tx(&mut rng, &[(genesis_outpt.clone(), 0)], &[100_000_000]);
+    tf.make_block_builder()
+        .add_transaction(tx0)
+        .build_and_process(&mut rng)
+        .unwrap();
+
+    // A transaction spending the same output is a double spend rather than an orphan, because
+    // block rewards never enter the mempool.
+    let tx1 = make_tx(&mut rng, &[(genesis_outpt, 0)], &[90_000_000]);
+    let tx1_id = tx1.transaction().get_id();
+    let mut mempool = setup_with_chainstate(tf.chainstate());
+    let err = mempool.add_transaction_test(tx1).unwrap_err();
+    assert_eq!(err.rejection_class(), TxRejectionClass::DoubleSpend);
+    assert!(!mempool.contains_orphan_transaction(&tx1_id));
+    assert!(!mempool.contains_transaction(&tx1_id));
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -243,7 +272,13 @@ async fn transaction_graph_subset_permutation(#[case] seed: Seed) {
     let mut results: Vec<Vec<Option<TxStatus>>> = Vec::new();
     for tx_subseq in [tx_subseq_0, tx_subseq_1] {
         let tf = TestFramework::builder(&mut rng).build();
-        let mut mempool = setup_with_chainstate(tf.chainstate());
+        // The generated graph may have longer chains than the default limits allow
+        let mempool_config = MempoolConfig {
+            max_ancestor_count: num_txs.into(),
+            max_descendant_count: num_txs.into(),
+            ..test_mempool_config()
+        };
+        let mut mempool = setup_with_chainstate_and_config(tf.chainstate(), mempool_config);
 
         // Now add each transaction in the subsequence
         tx_subseq.iter().for_each(|tx| {
@@ -24,17 +24,24 @@ pub use crate::pool::tx_pool::tests::utils::*;
 pub use rstest::rstest;
 
 use super::{Error, MemoryUsageEstimator, Mempool, TxEntry};
+use crate::MempoolConfig;
 
 pub fn setup_with_chainstate(
     chainstate: Box<dyn ChainstateInterface>,
+) -> Mempool<StoreMemoryUsageEstimator> {
+    setup_with_chainstate_and_config(chainstate, test_mempool_config())
+}
+
+pub fn setup_with_chainstate_and_config(
+    chainstate: Box<dyn ChainstateInterface>,
+    mempool_config: MempoolConfig,
 ) -> Mempool<StoreMemoryUsageEstimator> {
     logging::init_logging();
     let chain_config = std::sync::Arc::clone(chainstate.get_chain_config());
-    let mempool_config = create_mempool_config();
     let chainstate_handle = start_chainstate(chainstate);
     Mempool::new(
         chain_config,
-        mempool_config,
+        mempool_config.into(),
         chainstate_handle,
         Default::default(),
         StoreMemoryUsageEstimator,
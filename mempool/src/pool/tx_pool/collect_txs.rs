@@ -15,8 +15,9 @@
 
 use crate::{
     error::{BlockConstructionError, TxValidationError},
-    pool::tx_pool::{tx_verifier, TxMempoolEntry, TxPool},
+    pool::tx_pool::{snapshot::SnapshotEntry, tx_verifier, MempoolSnapshot},
     tx_accumulator::{PackingStrategy, TransactionAccumulator},
+    tx_options::TxBlockInclusionPolicy,
 };
 
 use std::{
@@ -36,7 +37,7 @@ use utils::{ensure, graph_traversals, shallow_clone::ShallowClone};
 /// Transaction entry together with priority
 #[derive(Clone, Debug, Eq, PartialEq)]
 struct EntryByScore<'a> {
-    entry: &'a TxMempoolEntry,
+    entry: &'a SnapshotEntry,
 }
 
 impl PartialOrd for EntryByScore<'_> {
@@ -46,7 +47,7 @@ impl PartialOrd for EntryByScore<'_> {
 }
 
 impl std::ops::Deref for EntryByScore<'_> {
-    type Target = TxMempoolEntry;
+    type Target = SnapshotEntry;
     fn deref(&self) -> &Self::Target {
         self.entry
     }
@@ -60,17 +61,17 @@ impl Ord for EntryByScore<'_> {
     }
 }
 
-impl<'a> From<&'a TxMempoolEntry> for EntryByScore<'a> {
-    fn from(entry: &'a TxMempoolEntry) -> Self {
+impl<'a> From<&'a SnapshotEntry> for EntryByScore<'a> {
+    fn from(entry: &'a SnapshotEntry) -> Self {
         Self { entry }
     }
 }
 
-/// Fill the TransactionAccumulator with transactions from the mempool
+/// Fill the TransactionAccumulator with transactions from a mempool snapshot
 /// Returns the updated TransactionAccumulator. Ok(None) means that a
 /// recoverable error happened (such as that the mempool tip moved).
-pub fn collect_txs<M>(
-    mempool: &TxPool<M>,
+pub fn collect_txs(
+    mempool: &MempoolSnapshot,
     mut tx_accumulator: Box<dyn TransactionAccumulator>,
     transaction_ids: Vec<Id<Transaction>>,
     packing_strategy: PackingStrategy,
@@ -82,21 +83,22 @@ pub fn collect_txs<M>(
         return Ok(None);
     }
 
-    let chainstate = tx_verifier::ChainstateHandle::new(mempool.chainstate_handle.shallow_clone());
-    let chain_config = mempool.chain_config.deref();
+    let chainstate =
+        tx_verifier::ChainstateHandle::new(mempool.chainstate_handle().shallow_clone());
+    let chain_config = mempool.chain_config().deref();
     let utxo_view = tx_verifier::MempoolUtxoView::new(mempool, chainstate.shallow_clone());
 
     // Transaction verifier to detect cases where mempool is not fully up-to-date with
     // transaction dependencies.
     let mut tx_verifier = tx_verifier::create(
-        mempool.chain_config.shallow_clone(),
-        mempool.chainstate_handle.shallow_clone(),
+        mempool.chain_config().shallow_clone(),
+        mempool.chainstate_handle().shallow_clone(),
     );
 
-    let best_index = mempool
-        .blocking_chainstate_handle()
-        .call(|c| c.get_best_block_index())?
-        .expect("best index to exist");
+    let best_index =
+        subsystem::blocking::BlockingHandle::new(mempool.chainstate_handle().shallow_clone())
+            .call(|c| c.get_best_block_index())?
+            .expect("best index to exist");
     let tx_source = TransactionSourceForConnect::for_mempool(&best_index);
 
     // Use transactions already in the Accumulator to check for uniqueness and to update the
@@ -120,20 +122,23 @@ pub fn collect_txs<M>(
     let given_txids = {
         for tx_id in &transaction_ids {
             ensure!(
-                mempool.store.get_entry(tx_id).is_some(),
+                mempool.get_entry(tx_id).is_some(),
                 BlockConstructionError::TxNotFound(*tx_id),
             );
         }
         // Pull in the parents before the user-specified transactions so we get a valid sequence
         graph_traversals::dag_depth_postorder_multiroot(&transaction_ids, |tx_id| {
-            mempool.store.get_entry(tx_id).expect("already checked").parents()
+            mempool.get_entry(tx_id).expect("already checked").parents()
         })
     };
 
     // Transaction IDs taken from mempool to fill in the rest of the block
     let mempool_txids = {
-        // Get transactions from mempool by score
-        let txids = mempool.store.txs_by_ancestor_score.iter().map(|x| &x.1).rev();
+        // Get transactions from mempool by score, skipping the ones that are only kept for relaying
+        let txids = mempool.txs_by_ancestor_score().rev().filter(|tx_id| {
+            let entry = mempool.get_entry(tx_id).expect("entry must exist");
+            entry.tx_entry().options().block_inclusion_policy() == TxBlockInclusionPolicy::Include
+        });
         // Take the appropriate amount of them as determined by the packing strategy
         txids.take(match packing_strategy {
             PackingStrategy::FillSpaceFromMempool => usize::MAX,
@@ -147,7 +152,7 @@ pub fn collect_txs<M>(
         .filter_map(|tx_id| {
             // If the transaction with this ID has already been processed, skip it
             ensure!(processed.insert(tx_id));
-            let tx = mempool.store.txs_by_id.get(tx_id).expect("already checked").deref();
+            let tx = mempool.get_entry(tx_id).expect("already checked");
 
             tx_verifier::input_check::verify_timelocks(
                 tx.transaction(),
@@ -225,7 +230,7 @@ pub fn collect_txs<M>(
                     0 => panic!("pending with 0 missing parents"),
                     1 => {
                         // This was the last missing parent, put the tx into the ready queue
-                        ready.push(mempool.store.txs_by_id[c.key()].deref().into());
+                        ready.push(mempool.get_entry(c.key()).expect("entry must exist").into());
                         c.remove();
                     }
                     n => *n -= 1,
@@ -18,10 +18,11 @@ pub mod feerate_points;
 pub mod memory_usage_estimator;
 mod reorg;
 mod rolling_fee_rate;
+mod snapshot;
 mod store;
 mod tx_verifier;
 
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::{
     collections::{BTreeMap, BTreeSet},
     num::NonZeroUsize,
@@ -41,7 +42,7 @@ use chainstate::{
 use common::{
     chain::{
         block::timestamp::BlockTimestamp, Block, ChainConfig, GenBlock, SignedTransaction,
-        Transaction, TxInput,
+        Transaction, TxInput, TxOutput,
     },
     primitives::{amount::DisplayAmount, time::Time, Amount, BlockHeight, Id},
     time_getter::TimeGetter,
@@ -49,15 +50,17 @@ use common::{
 use logging::log;
 use utils::{const_value::ConstValue, ensure, shallow_clone::ShallowClone};
 
+pub use self::snapshot::MempoolSnapshot;
+
 use self::{
     memory_usage_estimator::MemoryUsageEstimator,
     rolling_fee_rate::RollingFeeRate,
     store::{Conflicts, DescendantScore, MempoolRemovalReason, MempoolStore, TxMempoolEntry},
 };
 use crate::{
-    config::{self, MempoolConfig, MempoolMaxSize},
+    config::{self, MempoolConfig, MempoolMaxSize, PackageLimits},
     error::{
-        BlockConstructionError, Error, MempoolConflictError, MempoolPolicyError, OrphanPoolError,
+        Error, MempoolConflictError, MempoolPolicyError, OrphanPoolError, PackageLimitError,
         ReorgError, TxValidationError,
     },
     pool::{
@@ -65,7 +68,7 @@ use crate::{
         fee::Fee,
         feerate::FeeRate,
     },
-    tx_accumulator::{PackingStrategy, TransactionAccumulator},
+    tx_options::TxRelayPolicy,
     tx_origin::RemoteTxOrigin,
 };
 
@@ -80,6 +83,7 @@ pub struct TxPool<M> {
     clock: TimeGetter,
     memory_usage_estimator: M,
     tx_verifier: tx_verifier::TransactionVerifier,
+    snapshot: Mutex<Option<Arc<MempoolSnapshot>>>,
 }
 
 impl<M> std::fmt::Debug for TxPool<M> {
@@ -114,6 +118,7 @@ impl<M> TxPool<M> {
             clock,
             memory_usage_estimator,
             tx_verifier,
+            snapshot: Mutex::new(None),
         }
     }
 
@@ -136,6 +141,10 @@ impl<M> TxPool<M> {
         self.max_size
     }
 
+    pub fn package_limits(&self) -> PackageLimits {
+        self.mempool_config.package_limits()
+    }
+
     // Reset the mempool state, returning the list of transactions previously stored in mempool
     pub fn reset(&mut self) -> impl Iterator<Item = TxEntry> {
         // Discard the old tx verifier and replace it with a fresh one
@@ -144,7 +153,9 @@ impl<M> TxPool<M> {
             self.chainstate_handle.shallow_clone(),
         );
 
-        std::mem::replace(&mut self.store, MempoolStore::new()).into_transactions()
+        // Keep the generation growing so snapshots taken before the reset are seen as stale
+        let new_store = MempoolStore::with_generation(self.store.generation() + 1);
+        std::mem::replace(&mut self.store, new_store).into_transactions()
     }
 
     pub fn is_ibd(&self) -> bool {
@@ -160,6 +171,70 @@ impl<M> TxPool<M> {
             .map(|(_score, id)| self.store.get_entry(id).expect("entry").transaction().clone())
             .collect()
     }
+
+    /// Get an immutable snapshot of the mempool contents.
+    ///
+    /// The last snapshot is cached and handed out again until the mempool contents or the tip
+    /// change. Transaction data is shared between the store and the snapshots, so building a new
+    /// snapshot only copies the index data.
+    pub fn snapshot(&self) -> Arc<MempoolSnapshot> {
+        let best_block_id = self.best_block_id();
+        let mut cached = self.snapshot.lock();
+
+        if let Some(snapshot) = cached.as_ref().filter(|snapshot| {
+            snapshot.seq_no() == self.store.generation()
+                && snapshot.best_block_id() == best_block_id
+        }) {
+            return Arc::clone(snapshot);
+        }
+
+        let snapshot = Arc::new(MempoolSnapshot::new(
+            &self.store,
+            best_block_id,
+            self.chain_config.shallow_clone(),
+            self.chainstate_handle.shallow_clone(),
+        ));
+        *cached = Some(Arc::clone(&snapshot));
+        snapshot
+    }
+
+    pub fn best_transaction_ids(&self, max_count: usize) -> Vec<Id<Transaction>> {
+        self.store
+            .txs_by_descendant_score
+            .iter()
+            .rev()
+            .map(|(_score, id)| id)
+            .filter(|id| {
+                let entry = self.store.get_entry(id).expect("entry");
+                entry.tx_entry().options().relay_policy() == TxRelayPolicy::DoRelay
+            })
+            .take(max_count)
+            .copied()
+            .collect()
+    }
+
+    /// Iterate over the transactions in the order of descending fee rates
+    pub fn iter_by_fee_rate(&self) -> impl Iterator<Item = &SignedTransaction> {
+        self.store
+            .txs_by_fee_rate
+            .iter()
+            .rev()
+            .map(|(_fee_rate, id)| self.store.get_entry(id).expect("entry").transaction())
+    }
+
+    pub fn get_top_n_by_fee(&self, n: usize) -> Vec<Id<Transaction>> {
+        self.store
+            .txs_by_fee_rate
+            .iter()
+            .rev()
+            .take(n)
+            .map(|(_fee_rate, id)| *id)
+            .collect()
+    }
+
+    pub fn tx_count(&self) -> usize {
+        self.store.len()
+    }
 }
 
 // Rolling-fee-related methods
@@ -262,6 +337,44 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
         let max_size = self.chain_config.max_tx_size_for_mempool();
         ensure!(size <= max_size, MempoolPolicyError::ExceedsMaxBlockSize);
 
+        self.check_dust_outputs(tx)?;
+
+        Ok(())
+    }
+
+    // Outputs carrying fewer coins than their size times the dust relay fee rate are rejected,
+    // they would bloat the utxo set while costing more to spend than they are worth
+    fn check_dust_outputs(&self, tx: &SignedTransaction) -> Result<(), MempoolPolicyError> {
+        let dust_relay_fee_rate = self.chain_config.dust_relay_fee_rate();
+
+        for (index, output) in tx.transaction().outputs().iter().enumerate() {
+            let value = match output {
+                TxOutput::Transfer(value, _)
+                | TxOutput::LockThenTransfer(value, _, _)
+                | TxOutput::Htlc(value, _) => value,
+                // Burned coins don't end up in the utxo set and the staking outputs
+                // are subject to their own minimums
+                TxOutput::Burn(_)
+                | TxOutput::CreateStakePool(_, _)
+                | TxOutput::ProduceBlockFromStake(_, _)
+                | TxOutput::CreateDelegationId(_, _)
+                | TxOutput::DelegateStaking(_, _)
+                | TxOutput::IssueFungibleToken(_)
+                | TxOutput::IssueNft(_, _, _)
+                | TxOutput::DataDeposit(_)
+                | TxOutput::AnyoneCanTake(_) => continue,
+            };
+
+            // Token amounts are not comparable to the threshold in coins
+            let Some(amount) = value.coin_amount() else {
+                continue;
+            };
+
+            let output_size = serialization::Encode::encoded_size(output);
+            let threshold = (dust_relay_fee_rate * output_size as u128).unwrap_or(Amount::MAX);
+            ensure!(amount >= threshold, MempoolPolicyError::DustOutput(index));
+        }
+
         Ok(())
     }
 
@@ -272,6 +385,7 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
     ) -> Result<Conflicts, MempoolPolicyError> {
         self.pays_minimum_relay_fees(entry)?;
         self.pays_minimum_mempool_fee(entry)?;
+        self.within_package_limits(entry.tx_entry())?;
 
         if config::ENABLE_RBF {
             self.rbf_checks(entry)
@@ -327,6 +441,68 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
         Ok(())
     }
 
+    fn within_package_limits(&self, entry: &TxEntry) -> Result<(), MempoolPolicyError> {
+        let limits = self.package_limits();
+        let tx_size = entry.size().get();
+
+        let parents = self.store.unconfirmed_parents(entry.transaction().transaction());
+        let ancestors = TxMempoolEntry::unconfirmed_ancestors_from_parents(&parents, &self.store)?;
+
+        // A transaction that doesn't depend on other mempool transactions doesn't form a chain,
+        // its size is only subject to the maximum transaction size.
+        if ancestors.is_empty() {
+            return Ok(());
+        }
+
+        let ancestors = ancestors
+            .iter()
+            .map(|id| self.store.get_entry(id).ok_or(MempoolPolicyError::GetParentError))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let count = ancestors.len() + 1;
+        ensure!(
+            count <= limits.max_ancestor_count,
+            PackageLimitError::TooManyAncestors {
+                count,
+                limit: limits.max_ancestor_count,
+            }
+        );
+
+        let size = tx_size + ancestors.iter().map(|a| a.size().get()).sum::<usize>();
+        ensure!(
+            size <= limits.max_ancestor_size,
+            PackageLimitError::AncestorSizeTooLarge {
+                size,
+                limit: limits.max_ancestor_size,
+            }
+        );
+
+        // Every ancestor gets the new transaction as one more descendant
+        for ancestor in ancestors {
+            let count = ancestor.count_with_descendants() + 1;
+            ensure!(
+                count <= limits.max_descendant_count,
+                PackageLimitError::TooManyDescendants {
+                    ancestor: *ancestor.tx_id(),
+                    count,
+                    limit: limits.max_descendant_count,
+                }
+            );
+
+            let size = ancestor.size_with_descendants().get() + tx_size;
+            ensure!(
+                size <= limits.max_descendant_size,
+                PackageLimitError::DescendantSizeTooLarge {
+                    ancestor: *ancestor.tx_id(),
+                    size,
+                    limit: limits.max_descendant_size,
+                }
+            );
+        }
+
+        Ok(())
+    }
+
     fn conflicting_tx_ids<'a, O: crate::tx_origin::IsOrigin>(
         &'a self,
         entry: &'a TxEntry<O>,
@@ -853,15 +1029,6 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
         Ok(result)
     }
 
-    pub fn collect_txs(
-        &self,
-        tx_accumulator: Box<dyn TransactionAccumulator>,
-        transaction_ids: Vec<Id<Transaction>>,
-        packing_strategy: PackingStrategy,
-    ) -> Result<Option<Box<dyn TransactionAccumulator>>, BlockConstructionError> {
-        collect_txs::collect_txs(self, tx_accumulator, transaction_ids, packing_strategy)
-    }
-
     pub fn reorg(
         &mut self,
         block_id: Id<Block>,
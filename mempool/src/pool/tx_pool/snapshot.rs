@@ -0,0 +1,209 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Immutable snapshots of the mempool contents
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use common::{
+    chain::{ChainConfig, GenBlock, SignedTransaction, Transaction},
+    primitives::Id,
+};
+
+use super::{
+    collect_txs,
+    store::{AncestorScore, MempoolStore},
+    Fee, TxEntry,
+};
+use crate::{
+    error::BlockConstructionError,
+    tx_accumulator::{PackingStrategy, TransactionAccumulator},
+};
+
+/// Mempool entry as captured by a snapshot
+#[derive(Debug)]
+pub struct SnapshotEntry {
+    // The transaction data is shared with the mempool store, so it's not copied
+    entry: Arc<TxEntry>,
+    fee: Fee,
+    ancestor_score: AncestorScore,
+    parents: BTreeSet<Id<Transaction>>,
+    children: BTreeSet<Id<Transaction>>,
+}
+
+impl SnapshotEntry {
+    pub fn tx_id(&self) -> &Id<Transaction> {
+        self.entry.tx_id()
+    }
+
+    pub fn tx_entry(&self) -> &TxEntry {
+        &self.entry
+    }
+
+    pub fn transaction(&self) -> &SignedTransaction {
+        self.entry.transaction()
+    }
+
+    pub fn fee(&self) -> Fee {
+        self.fee
+    }
+
+    pub fn ancestor_score(&self) -> AncestorScore {
+        self.ancestor_score
+    }
+
+    pub fn parents(&self) -> impl Iterator<Item = &Id<Transaction>> {
+        self.parents.iter()
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = &Id<Transaction>> {
+        self.children.iter()
+    }
+}
+
+impl PartialEq for SnapshotEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.tx_id() == other.tx_id()
+    }
+}
+
+impl Eq for SnapshotEntry {}
+
+/// An immutable view of the mempool contents at some point in time.
+///
+/// Snapshots are shared behind an [Arc], so block templates can be built and the mempool contents
+/// listed from a snapshot without holding up the acceptance of new transactions in the meantime.
+/// The sequence number identifies the mempool state the snapshot has been taken from. It grows
+/// with every change to the mempool contents, so a snapshot with a higher sequence number is
+/// fresher.
+pub struct MempoolSnapshot {
+    seq_no: u64,
+    best_block_id: Id<GenBlock>,
+    chain_config: Arc<ChainConfig>,
+    chainstate_handle: chainstate::ChainstateHandle,
+    entries: BTreeMap<Id<Transaction>, SnapshotEntry>,
+    // Transaction IDs sorted by ancestor score, the lowest score first
+    txs_by_ancestor_score: Vec<Id<Transaction>>,
+    // Transaction IDs sorted by descendant score, the lowest score first
+    txs_by_descendant_score: Vec<Id<Transaction>>,
+}
+
+impl MempoolSnapshot {
+    pub(super) fn new(
+        store: &MempoolStore,
+        best_block_id: Id<GenBlock>,
+        chain_config: Arc<ChainConfig>,
+        chainstate_handle: chainstate::ChainstateHandle,
+    ) -> Self {
+        let mut entries = BTreeMap::new();
+        let txs_by_ancestor_score = store
+            .txs_by_ancestor_score
+            .iter()
+            .map(|(ancestor_score, tx_id)| {
+                let entry = store.get_entry(tx_id).expect("entry must exist");
+                let snapshot_entry = SnapshotEntry {
+                    entry: Arc::clone(entry.shared_tx_entry()),
+                    fee: entry.fee(),
+                    ancestor_score: *ancestor_score,
+                    parents: entry.parents().copied().collect(),
+                    children: entry.children().copied().collect(),
+                };
+                entries.insert(*tx_id, snapshot_entry);
+                *tx_id
+            })
+            .collect();
+        let txs_by_descendant_score =
+            store.txs_by_descendant_score.iter().map(|(_score, tx_id)| *tx_id).collect();
+
+        Self {
+            seq_no: store.generation(),
+            best_block_id,
+            chain_config,
+            chainstate_handle,
+            entries,
+            txs_by_ancestor_score,
+            txs_by_descendant_score,
+        }
+    }
+
+    /// Sequence number of the mempool state captured by this snapshot
+    pub fn seq_no(&self) -> u64 {
+        self.seq_no
+    }
+
+    /// Best block ID according to mempool at the time the snapshot was taken
+    pub fn best_block_id(&self) -> Id<GenBlock> {
+        self.best_block_id
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains_transaction(&self, tx_id: &Id<Transaction>) -> bool {
+        self.entries.contains_key(tx_id)
+    }
+
+    pub fn transaction(&self, tx_id: &Id<Transaction>) -> Option<&SignedTransaction> {
+        self.entries.get(tx_id).map(SnapshotEntry::transaction)
+    }
+
+    /// Get all transactions in the snapshot, in the order of increasing descendant score
+    pub fn get_all(&self) -> Vec<SignedTransaction> {
+        self.txs_by_descendant_score
+            .iter()
+            .map(|tx_id| self.entries[tx_id].transaction().clone())
+            .collect()
+    }
+
+    /// Fill the given accumulator with transactions from this snapshot.
+    ///
+    /// This may perform blocking calls to chainstate, so it should not be called from an async
+    /// context directly. Ok(None) means that the accumulator expects a different tip.
+    pub fn collect_txs(
+        &self,
+        tx_accumulator: Box<dyn TransactionAccumulator>,
+        transaction_ids: Vec<Id<Transaction>>,
+        packing_strategy: PackingStrategy,
+    ) -> Result<Option<Box<dyn TransactionAccumulator>>, BlockConstructionError> {
+        collect_txs::collect_txs(self, tx_accumulator, transaction_ids, packing_strategy)
+    }
+
+    pub(super) fn get_entry(&self, tx_id: &Id<Transaction>) -> Option<&SnapshotEntry> {
+        self.entries.get(tx_id)
+    }
+
+    /// Iterate over the entries in the order of increasing ancestor score
+    pub(super) fn txs_by_ancestor_score(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &Id<Transaction>> {
+        self.txs_by_ancestor_score.iter()
+    }
+
+    pub(super) fn chain_config(&self) -> &Arc<ChainConfig> {
+        &self.chain_config
+    }
+
+    pub(super) fn chainstate_handle(&self) -> &chainstate::ChainstateHandle {
+        &self.chainstate_handle
+    }
+}
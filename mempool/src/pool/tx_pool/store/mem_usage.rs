@@ -26,7 +26,7 @@ use common::chain::{
 };
 use logging::log;
 
-use super::{TxDependency, TxMempoolEntry};
+use super::{TxDependency, TxEntry, TxMempoolEntry};
 
 /// Structure that stores the current memory usage and keeps track of its changes
 #[derive(Debug)]
@@ -316,7 +316,9 @@ impl<T> MemoryUsage for common::primitives::Id<T> {
 
 impl MemoryUsage for TxMempoolEntry {
     fn indirect_memory_usage(&self) -> usize {
-        let transaction = self.transaction().indirect_memory_usage();
+        // The transaction entry is kept in an `Arc` allocation, together with the two ref counts
+        let shared = mem::size_of::<TxEntry>() + 2 * mem::size_of::<usize>();
+        let transaction = shared + self.transaction().indirect_memory_usage();
         let parents = self.parents.indirect_memory_usage();
         let children = self.children.indirect_memory_usage();
         transaction + parents + children
@@ -20,6 +20,7 @@ use std::{
     collections::{BTreeMap, BTreeSet},
     num::NonZeroUsize,
     ops::Deref,
+    sync::Arc,
 };
 
 use common::{
@@ -67,7 +68,7 @@ impl DescendantScore {
 }
 
 newtype! {
-    #[derive(Debug, PartialEq, Eq, Ord, PartialOrd)]
+    #[derive(Debug, PartialEq, Eq, Ord, PartialOrd, Clone, Copy)]
     pub struct AncestorScore(FeeRate);
 }
 
@@ -104,6 +105,11 @@ pub struct MempoolStore {
     //  min(score/size of entry's tx, score/size with all ancestors).
     pub txs_by_ancestor_score: TrackedTxIdMultiMap<AncestorScore>,
 
+    // Mempool entries sorted by the fee rate of the entry's tx alone.
+    // This allows traversing the mempool in the order of fee rates, e.g. when building a block
+    // template, without sorting the entries on every traversal.
+    pub txs_by_fee_rate: TrackedTxIdMultiMap<FeeRate>,
+
     // Entries that have remained in the mempool for a long time (see DEFAULT_MEMPOOL_EXPIRY) are
     // evicted. To efficiently know which entries to evict, we store the mempool entries sorted by
     // their creation time, from earliest to latest.
@@ -122,6 +128,10 @@ pub struct MempoolStore {
     seq_nos_by_tx: Tracked<BTreeMap<Id<Transaction>, usize>>,
     next_seq_no: usize,
 
+    // Incremented every time a transaction is added to or removed from the store, so that
+    // snapshots of the store contents can tell whether they are still up to date.
+    generation: u64,
+
     /// Memory usage accumulator
     mem_tracker: mem_usage::MemUsageTracker,
 }
@@ -143,15 +153,22 @@ pub enum MempoolRemovalReason {
 
 impl MempoolStore {
     pub fn new() -> Self {
+        Self::with_generation(0)
+    }
+
+    /// Create an empty store starting at the given generation
+    pub fn with_generation(generation: u64) -> Self {
         Self {
             txs_by_descendant_score: Tracked::default(),
             txs_by_ancestor_score: Tracked::default(),
+            txs_by_fee_rate: Tracked::default(),
             txs_by_id: Tracked::default(),
             txs_by_creation_time: Tracked::default(),
             spender_txs: Tracked::default(),
             txs_by_seq_no: Tracked::default(),
             seq_nos_by_tx: Tracked::default(),
             next_seq_no: 0,
+            generation,
             mem_tracker: mem_usage::MemUsageTracker::new(),
         }
     }
@@ -160,6 +177,10 @@ impl MempoolStore {
         self.txs_by_id.is_empty()
     }
 
+    pub fn len(&self) -> usize {
+        self.txs_by_id.len()
+    }
+
     pub fn get_entry(&self, id: &Id<Transaction>) -> Option<&TxMempoolEntry> {
         self.txs_by_id.get(id).map(|tx| tx.deref())
     }
@@ -172,6 +193,11 @@ impl MempoolStore {
         self.mem_tracker.get_usage()
     }
 
+    /// Number of modifications the store contents have undergone
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
     pub fn assert_valid(&self) {
         #[cfg(test)]
         self.assert_valid_inner()
@@ -188,6 +214,7 @@ impl MempoolStore {
         let expected_size = map_size_deep(&self.txs_by_id)
             + self.txs_by_descendant_score.indirect_memory_usage()
             + self.txs_by_ancestor_score.indirect_memory_usage()
+            + self.txs_by_fee_rate.indirect_memory_usage()
             + self.txs_by_creation_time.indirect_memory_usage()
             + self.spender_txs.indirect_memory_usage()
             + self.txs_by_seq_no.indirect_memory_usage()
@@ -207,6 +234,11 @@ impl MempoolStore {
             )
         }
 
+        assert_eq!(self.txs_by_fee_rate.len(), self.txs_by_id.len());
+        for (fee_rate, id) in self.txs_by_fee_rate.iter() {
+            assert_eq!(self.txs_by_id.get(id).expect("entry").fee_rate(), *fee_rate);
+        }
+
         for entry in self.txs_by_id.values() {
             for child in &entry.children {
                 assert!(self.txs_by_id.get(child).expect("child").parents.contains(entry.tx_id()))
@@ -258,11 +290,15 @@ impl MempoolStore {
                     |ancestor, _| -> Result<(), MempoolPolicyError> {
                         let total_fee = (ancestor.fees_with_descendants + entry.fee)
                             .ok_or(MempoolPolicyError::AncestorFeeUpdateOverflow)?;
-                        ancestor.fees_with_descendants = total_fee;
-                        ancestor.size_with_descendants = entry
+                        let total_size = entry
                             .size()
                             .checked_add(ancestor.size_with_descendants.get())
                             .expect("non-zero size");
+                        // Make sure the descendant score can still be computed
+                        FeeRate::from_total_tx_fee(total_fee, total_size)
+                            .map_err(|_| MempoolPolicyError::AncestorFeeUpdateOverflow)?;
+                        ancestor.fees_with_descendants = total_fee;
+                        ancestor.size_with_descendants = total_size;
                         ancestor.count_with_descendants += 1;
                         Ok(())
                     },
@@ -306,18 +342,21 @@ impl MempoolStore {
         })
     }
 
-    pub fn add_transaction(&mut self, entry: TxEntryWithFee) -> Result<(), MempoolPolicyError> {
+    /// Transactions in the store that the given transaction spends outputs of
+    pub fn unconfirmed_parents(&self, tx: &Transaction) -> BTreeSet<Id<Transaction>> {
         // Genesis transaction has no parent, hence the first filter_map
-        let parents = entry
-            .transaction()
-            .inputs()
+        tx.inputs()
             .iter()
             .filter_map(|input| match input {
                 TxInput::Utxo(outpoint) => outpoint.source_id().get_tx_id().cloned(),
                 TxInput::Account(..) | TxInput::AccountCommand(..) => None,
             })
             .filter(|id| self.txs_by_id.contains_key(id))
-            .collect::<BTreeSet<_>>();
+            .collect()
+    }
+
+    pub fn add_transaction(&mut self, entry: TxEntryWithFee) -> Result<(), MempoolPolicyError> {
+        let parents = self.unconfirmed_parents(entry.transaction().transaction());
         let ancestor_ids = TxMempoolEntry::unconfirmed_ancestors_from_parents(&parents, self)?;
         let ancestors = BTreeSet::from(ancestor_ids)
             .into_iter()
@@ -337,9 +376,13 @@ impl MempoolStore {
         let tx_id = *entry.tx_id();
         let seq_no = self.next_seq_no;
         self.next_seq_no += 1;
+        self.generation += 1;
 
         self.add_to_descendant_score_index(&entry);
         self.add_to_ancestor_score_index(&entry);
+        self.mem_tracker.modify(&mut self.txs_by_fee_rate, |by_fee_rate, _tracker| {
+            by_fee_rate.insert((entry.fee_rate(), tx_id));
+        });
         self.mem_tracker.modify(
             &mut self.txs_by_creation_time,
             |txs_by_creation_time, _tracker| {
@@ -433,6 +476,7 @@ impl MempoolStore {
         let entry = self.mem_tracker.modify(&mut self.txs_by_id, |by_id, _| by_id.remove(tx_id));
 
         if let Some(entry) = entry {
+            self.generation += 1;
             let entry = self.mem_tracker.release(entry);
             self.update_ancestor_state_for_drop(&entry);
             if reason == MempoolRemovalReason::Block {
@@ -456,6 +500,7 @@ impl MempoolStore {
         self.update_for_drop(entry);
         self.remove_from_descendant_score_index(entry);
         self.remove_from_ancestor_score_index(entry);
+        self.remove_from_fee_rate_index(entry);
         self.remove_from_creation_time_index(entry);
         self.remove_from_seq_no_index(entry);
         self.unspend_outpoints(entry);
@@ -475,6 +520,12 @@ impl MempoolStore {
         })
     }
 
+    fn remove_from_fee_rate_index(&mut self, entry: &TxMempoolEntry) {
+        self.mem_tracker.modify(&mut self.txs_by_fee_rate, |by_fr, _tracker| {
+            by_fr.remove(&(entry.fee_rate(), *entry.tx_id()));
+        })
+    }
+
     fn remove_from_creation_time_index(&mut self, entry: &TxMempoolEntry) {
         self.mem_tracker.modify(&mut self.txs_by_creation_time, |by_ct, _tracker| {
             by_ct.remove(&(entry.creation_time(), *entry.tx_id()));
@@ -521,7 +572,8 @@ impl MempoolStore {
         let txs_by_seq_no = MemUsageTracker::forget(std::mem::take(&mut self.txs_by_seq_no));
 
         txs_by_seq_no.into_values().map(move |id| {
-            MemUsageTracker::forget(txs_by_id.remove(&id).expect("entry must be present")).entry
+            let entry = txs_by_id.remove(&id).expect("entry must be present");
+            Arc::unwrap_or_clone(MemUsageTracker::forget(entry).entry)
         })
     }
 }
@@ -539,7 +591,8 @@ impl Drop for MempoolStore {
 
 #[derive(Debug, Eq, Clone)]
 pub struct TxMempoolEntry {
-    entry: TxEntry,
+    // The transaction data is shared with mempool snapshots
+    entry: Arc<TxEntry>,
     fee: Fee,
     parents: BTreeSet<Id<Transaction>>,
     children: BTreeSet<Id<Transaction>>,
@@ -570,11 +623,15 @@ impl TxMempoolEntry {
             .ok_or(MempoolPolicyError::AncestorFeeOverflow)?;
         let fees_with_ancestors =
             (fee + ancestor_fees).ok_or(MempoolPolicyError::AncestorFeeOverflow)?;
+        // Reject fees the ancestor and descendant scores could not be computed from
+        FeeRate::from_total_tx_fee(fee, size)?;
+        FeeRate::from_total_tx_fee(fees_with_ancestors, size_with_ancestors)
+            .map_err(|_| MempoolPolicyError::AncestorFeeOverflow)?;
         Ok(Self {
             size_with_ancestors,
             count_with_ancestors: 1 + ancestors.len(),
             size_with_descendants: size,
-            entry,
+            entry: Arc::new(entry),
             fee,
             parents,
             children: BTreeSet::default(),
@@ -611,6 +668,15 @@ impl TxMempoolEntry {
         self.count_with_descendants
     }
 
+    pub fn size_with_descendants(&self) -> NonZeroUsize {
+        self.size_with_descendants
+    }
+
+    #[cfg(test)]
+    pub fn count_with_ancestors(&self) -> usize {
+        self.count_with_ancestors
+    }
+
     #[cfg(test)]
     pub fn fees_with_descendants(&self) -> Fee {
         self.fees_with_descendants
@@ -621,11 +687,14 @@ impl TxMempoolEntry {
         self.fees_with_ancestors
     }
 
+    pub fn fee_rate(&self) -> FeeRate {
+        FeeRate::from_total_tx_fee(self.fee, self.size()).expect("checked on insertion")
+    }
+
     pub fn descendant_score(&self) -> DescendantScore {
         let a = FeeRate::from_total_tx_fee(self.fees_with_descendants, self.size_with_descendants)
-            .expect("cannot overflow due to max supply");
-        let b = FeeRate::from_total_tx_fee(self.fee, self.size())
-            .expect("cannot overflow due to max supply");
+            .expect("checked on insertion");
+        let b = FeeRate::from_total_tx_fee(self.fee, self.size()).expect("checked on insertion");
         std::cmp::max(a, b).into()
     }
 
@@ -639,9 +708,8 @@ impl TxMempoolEntry {
             self.size(),
         );
         let a = FeeRate::from_total_tx_fee(self.fees_with_ancestors, self.size_with_ancestors)
-            .expect("cannot overflow due to max supply");
-        let b = FeeRate::from_total_tx_fee(self.fee, self.size())
-            .expect("cannot overflow due to max supply");
+            .expect("checked on insertion");
+        let b = FeeRate::from_total_tx_fee(self.fee, self.size()).expect("checked on insertion");
         std::cmp::min(a, b).into()
     }
 
@@ -653,6 +721,10 @@ impl TxMempoolEntry {
         &self.entry
     }
 
+    pub fn shared_tx_entry(&self) -> &Arc<TxEntry> {
+        &self.entry
+    }
+
     pub fn size(&self) -> NonZeroUsize {
         // TODO(Roy) this should follow Bitcoin's GetTxSize, which weighs in sigops, etc.
         self.entry.size()
@@ -32,7 +32,9 @@ use crypto::key::{KeyKind, PrivateKey};
 use serialization::Compact;
 
 use super::*;
-use crate::tx_accumulator::DefaultTxAccumulator;
+use crate::{
+    tx_accumulator::DefaultTxAccumulator, tx_options::TxOptionsOverrides, tx_origin::LocalTxOrigin,
+};
 
 // Useful for testing cases where timestamp is irrelevant.
 const DUMMY_TIMESTAMP: BlockTimestamp = BlockTimestamp::from_int_seconds(0u64);
@@ -124,6 +126,7 @@ async fn transaction_order_respects_deps(#[case] seed: Seed) {
         DUMMY_TIMESTAMP,
     ));
     let accumulator = mempool
+        .snapshot()
         .collect_txs(accumulator, vec![], PackingStrategy::FillSpaceFromMempool)
         .unwrap();
     let tx_ids: Vec<_> = accumulator
@@ -136,6 +139,60 @@ async fn transaction_order_respects_deps(#[case] seed: Seed) {
     assert_eq!(tx_ids, vec![tx0_id, tx1_id, tx2_id]);
 }
 
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn relay_only_transactions_not_collected(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis_id = tf.genesis().get_id();
+
+    let tx0 = make_tx(&mut rng, &[(genesis_id.into(), 0)], &[900_000_000_000]);
+    let tx0_id = tx0.transaction().get_id();
+
+    let tx1 = make_tx(&mut rng, &[(tx0_id.into(), 0)], &[800_000_000_000]);
+    let tx1_id = tx1.transaction().get_id();
+
+    let tx2 = make_tx(&mut rng, &[(tx1_id.into(), 0)], &[500_000_000_000]);
+    let tx2_id = tx2.transaction().get_id();
+
+    let mut mempool = setup_with_chainstate(tf.chainstate());
+    assert_eq!(mempool.add_transaction_test(tx0), Ok(TxStatus::InMempool));
+
+    let origin = LocalTxOrigin::P2p.into();
+    let relay_only = TxOptions::default_for(origin)
+        .with_overrides(TxOptionsOverrides::default().with_relay_only(true))
+        .unwrap();
+    let tx1_entry = TxEntry::new(tx1, mempool.clock.get_time(), origin, relay_only);
+    assert_eq!(
+        mempool.add_transaction_bare(tx1_entry),
+        Ok(TxStatus::InMempool)
+    );
+    assert_eq!(mempool.add_transaction_test(tx2), Ok(TxStatus::InMempool));
+    assert!(mempool.contains_transaction(&tx1_id));
+    assert!(mempool.contains_transaction(&tx2_id));
+
+    let accumulator = Box::new(DefaultTxAccumulator::new(
+        1_000_000,
+        genesis_id.into(),
+        DUMMY_TIMESTAMP,
+    ));
+    let accumulator = mempool
+        .snapshot()
+        .collect_txs(accumulator, vec![], PackingStrategy::FillSpaceFromMempool)
+        .unwrap();
+    let tx_ids: Vec<_> = accumulator
+        .unwrap()
+        .transactions()
+        .iter()
+        .map(|tx| tx.transaction().get_id())
+        .collect();
+
+    // The relay-only transaction is skipped along with its descendant
+    assert_eq!(tx_ids, vec![tx0_id]);
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -166,6 +223,7 @@ async fn transaction_graph_respects_deps(#[case] seed: Seed) {
         DUMMY_TIMESTAMP,
     ));
     let accumulator = mempool
+        .snapshot()
         .collect_txs(
             accumulator,
             user_tx_ids,
@@ -203,6 +261,7 @@ async fn collect_transactions(#[case] seed: Seed) -> anyhow::Result<()> {
     let tx_accumulator =
         DefaultTxAccumulator::new(size_limit, mempool.best_block_id(), DUMMY_TIMESTAMP);
     let returned_accumulator = mempool
+        .snapshot()
         .collect_txs(
             Box::new(tx_accumulator),
             vec![],
@@ -222,7 +281,7 @@ async fn collect_transactions(#[case] seed: Seed) -> anyhow::Result<()> {
     );
     for i in 0..target_txs {
         tx_builder = tx_builder.add_output(TxOutput::Transfer(
-            OutputValue::Coin(Amount::from_atoms(1000 * (target_txs + 1 - i))),
+            OutputValue::Coin(Amount::from_atoms(1000 * (target_txs + 1 - i) + 100)),
             Destination::AnyoneCanSpend,
         ))
     }
@@ -236,7 +295,7 @@ async fn collect_transactions(#[case] seed: Seed) -> anyhow::Result<()> {
                 empty_witness(&mut rng),
             )
             .add_output(TxOutput::Transfer(
-                OutputValue::Coin(Amount::from_atoms(0)),
+                OutputValue::Coin(Amount::from_atoms(100)),
                 Destination::AnyoneCanSpend,
             ))
             .build();
@@ -247,6 +306,7 @@ async fn collect_transactions(#[case] seed: Seed) -> anyhow::Result<()> {
     let tx_accumulator =
         DefaultTxAccumulator::new(size_limit, mempool.best_block_id(), DUMMY_TIMESTAMP);
     let returned_accumulator = mempool
+        .snapshot()
         .collect_txs(
             Box::new(tx_accumulator),
             vec![],
@@ -263,6 +323,7 @@ async fn collect_transactions(#[case] seed: Seed) -> anyhow::Result<()> {
 
     let tx_accumulator = DefaultTxAccumulator::new(0, mempool.best_block_id(), DUMMY_TIMESTAMP);
     let returned_accumulator = mempool
+        .snapshot()
         .collect_txs(
             Box::new(tx_accumulator),
             vec![],
@@ -275,6 +336,7 @@ async fn collect_transactions(#[case] seed: Seed) -> anyhow::Result<()> {
 
     let tx_accumulator = DefaultTxAccumulator::new(1, mempool.best_block_id(), DUMMY_TIMESTAMP);
     let returned_accumulator = mempool
+        .snapshot()
         .collect_txs(
             Box::new(tx_accumulator),
             vec![],
@@ -384,6 +446,7 @@ async fn timelocked(#[case] seed: Seed, #[case] timelock: OutputTimeLock, #[case
         block1_time,
     ));
     let accumulator = mempool
+        .snapshot()
         .collect_txs(accumulator, vec![], PackingStrategy::FillSpaceFromMempool)
         .unwrap();
     let accumulated_ids: BTreeSet<_> = accumulator
@@ -421,6 +484,7 @@ async fn timelocked(#[case] seed: Seed, #[case] timelock: OutputTimeLock, #[case
         block2_time,
     ));
     let accumulator = mempool
+        .snapshot()
         .collect_txs(accumulator, vec![], PackingStrategy::FillSpaceFromMempool)
         .unwrap()
         .unwrap();
@@ -585,6 +649,7 @@ async fn timelocked_htlc_refund(
         block1_time,
     ));
     let accumulator = mempool
+        .snapshot()
         .collect_txs(accumulator, vec![], PackingStrategy::FillSpaceFromMempool)
         .unwrap();
     let accumulated_ids: BTreeSet<_> = accumulator
@@ -622,6 +687,7 @@ async fn timelocked_htlc_refund(
         block2_time,
     ));
     let accumulator = mempool
+        .snapshot()
         .collect_txs(accumulator, vec![], PackingStrategy::FillSpaceFromMempool)
         .unwrap()
         .unwrap();
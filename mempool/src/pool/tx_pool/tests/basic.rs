@@ -13,6 +13,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use randomness::SliceRandom;
+
 use super::*;
 
 #[test]
@@ -72,10 +74,13 @@ async fn add_single_tx() -> anyhow::Result<()> {
     assert!(mempool.contains_transaction(&tx_id));
     let all_txs = mempool.get_all();
     assert_eq!(all_txs, vec![tx_clone]);
+    assert_eq!(mempool.best_transaction_ids(10), vec![tx_id]);
+    assert_eq!(mempool.best_transaction_ids(0), vec![]);
     mempool.store.remove_tx(&tx_id, MempoolRemovalReason::Block);
     assert!(!mempool.contains_transaction(&tx_id));
     let all_txs = mempool.get_all();
     assert_eq!(all_txs, Vec::<SignedTransaction>::new());
+    assert_eq!(mempool.best_transaction_ids(10), vec![]);
     mempool.store.assert_valid();
     Ok(())
 }
@@ -143,7 +148,7 @@ async fn txs_sorted(#[case] seed: Seed) -> anyhow::Result<()> {
     );
     for i in 0..target_txs {
         tx_builder = tx_builder.add_output(TxOutput::Transfer(
-            OutputValue::Coin(Amount::from_atoms(1000 * (target_txs + 1 - i))),
+            OutputValue::Coin(Amount::from_atoms(1000 * (target_txs + 1 - i) + 100)),
             Destination::AnyoneCanSpend,
         ))
     }
@@ -157,7 +162,7 @@ async fn txs_sorted(#[case] seed: Seed) -> anyhow::Result<()> {
                 empty_witness(&mut rng),
             )
             .add_output(TxOutput::Transfer(
-                OutputValue::Coin(Amount::from_atoms(0)),
+                OutputValue::Coin(Amount::from_atoms(100)),
                 Destination::AnyoneCanSpend,
             ))
             .build();
@@ -359,6 +364,54 @@ async fn tx_too_big(#[case] seed: Seed) -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn tx_mempool_entry_fee_overflow() {
+    use common::primitives::time;
+    let txs = (1..=2)
+        .map(|i| {
+            SignedTransaction::new(
+                Transaction::new(i, vec![], vec![]).unwrap_or_else(|_| panic!("tx {i}")),
+                vec![],
+            )
+            .expect("invalid witness count")
+        })
+        .collect::<Vec<_>>();
+
+    // A fee whose fee rate cannot be represented is rejected instead of panicking later
+    assert_eq!(
+        TxMempoolEntry::new_from_data(
+            txs[0].clone(),
+            Amount::MAX.into(),
+            BTreeSet::default(),
+            BTreeSet::default(),
+            time::get_time(),
+        ),
+        Err(MempoolPolicyError::FeeOverflow)
+    );
+
+    let max_fee = (Amount::MAX / 1000).unwrap();
+    let parent = TxMempoolEntry::new_from_data(
+        txs[0].clone(),
+        max_fee.into(),
+        BTreeSet::default(),
+        BTreeSet::default(),
+        time::get_time(),
+    )
+    .unwrap();
+
+    // The sum with ancestors fits into an amount but not into a fee rate
+    assert_eq!(
+        TxMempoolEntry::new_from_data(
+            txs[1].clone(),
+            max_fee.into(),
+            BTreeSet::from([*parent.tx_id()]),
+            BTreeSet::from([parent]),
+            time::get_time(),
+        ),
+        Err(MempoolPolicyError::AncestorFeeOverflow)
+    );
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn tx_mempool_entry() -> anyhow::Result<()> {
     use common::primitives::time;
@@ -1358,7 +1411,11 @@ async fn no_empty_bags_in_indices(#[case] seed: Seed) -> anyhow::Result<()> {
         ));
     }
     let parent = tx_builder.build();
-    let mut mempool = setup_with_chainstate(tf.chainstate());
+    let config = MempoolConfig {
+        max_descendant_count: (num_outputs + 1).into(),
+        ..test_mempool_config()
+    };
+    let mut mempool = setup_with_chainstate_and_config(tf.chainstate(), config);
 
     let parent_id = parent.transaction().get_id();
 
@@ -1391,11 +1448,84 @@ async fn no_empty_bags_in_indices(#[case] seed: Seed) -> anyhow::Result<()> {
         mempool.store.remove_tx(&id, MempoolRemovalReason::Block);
     }
     assert!(mempool.store.txs_by_descendant_score.is_empty());
+    assert!(mempool.store.txs_by_fee_rate.is_empty());
     assert!(mempool.store.txs_by_creation_time.is_empty());
     mempool.store.assert_valid();
     Ok(())
 }
 
+fn check_txs_sorted_by_fee_rate<M>(tx_pool: &TxPool<M>) {
+    let fee_rates = tx_pool
+        .iter_by_fee_rate()
+        .map(|tx| tx_pool.store.get_entry(&tx.transaction().get_id()).expect("entry").fee_rate())
+        .collect::<Vec<_>>();
+    assert_eq!(fee_rates.len(), tx_pool.store.txs_by_id.len());
+    assert!(fee_rates.windows(2).all(|pair| pair[0] >= pair[1]));
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fee_rate_index(#[case] seed: Seed) -> anyhow::Result<()> {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis = tf.genesis();
+
+    let num_outputs: usize = rng.gen_range(10..50);
+    let mut tx_builder = TransactionBuilder::new().add_input(
+        TxInput::from_utxo(OutPointSourceId::BlockReward(genesis.get_id().into()), 0),
+        empty_witness(&mut rng),
+    );
+    for _ in 0..num_outputs {
+        tx_builder = tx_builder.add_output(TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(100_000)),
+            anyonecanspend_address(),
+        ));
+    }
+    let parent = tx_builder.build();
+    let config = MempoolConfig {
+        max_descendant_count: (num_outputs + 1).into(),
+        ..test_mempool_config()
+    };
+    let mut mempool = setup_with_chainstate_and_config(tf.chainstate(), config);
+
+    let outpoint_source_id = OutPointSourceId::Transaction(parent.transaction().get_id());
+    mempool.add_transaction_test(parent)?.assert_in_mempool();
+
+    let min_fee = get_relay_fee_from_tx_size(estimate_tx_size(1, 1));
+    let mut ids = Vec::new();
+    for i in 0..num_outputs {
+        let fee = (min_fee + Amount::from_atoms(rng.gen_range(0..10_000))).unwrap();
+        let tx = tx_spend_input(
+            &mempool,
+            TxInput::from_utxo(outpoint_source_id.clone(), u32::try_from(i).unwrap()),
+            empty_witness(&mut rng),
+            Fee::new(fee),
+            0,
+        )
+        .await?;
+        ids.push(tx.transaction().get_id());
+        mempool.add_transaction_test(tx)?.assert_in_mempool();
+        check_txs_sorted_by_fee_rate(&mempool);
+    }
+
+    let top_ids = mempool.get_top_n_by_fee(5);
+    assert_eq!(top_ids.len(), 5);
+    assert!(top_ids
+        .iter()
+        .zip(mempool.iter_by_fee_rate())
+        .all(|(id, tx)| *id == tx.transaction().get_id()));
+
+    ids.shuffle(&mut rng);
+    for id in ids.iter().take(rng.gen_range(0..num_outputs)) {
+        mempool.store.remove_tx(id, MempoolRemovalReason::Block);
+        check_txs_sorted_by_fee_rate(&mempool);
+    }
+    mempool.store.assert_valid();
+    Ok(())
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy(), 300, true)]
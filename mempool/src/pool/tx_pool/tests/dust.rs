@@ -0,0 +1,121 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    chain::{output_value::OutputValue, stakelock::StakePoolData, DelegationId, PoolId},
+    primitives::{per_thousand::PerThousand, H256},
+};
+use crypto::vrf::{VRFKeyKind, VRFPrivateKey};
+
+use super::*;
+
+fn transfer(amount: Amount) -> TxOutput {
+    TxOutput::Transfer(OutputValue::Coin(amount), Destination::AnyoneCanSpend)
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn dust_threshold(#[case] seed: Seed) -> anyhow::Result<()> {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis = tf.genesis();
+
+    let dust_relay_fee_rate = tf.chainstate.get_chain_config().dust_relay_fee_rate();
+    let output_size = transfer(Amount::ZERO).encoded_size();
+    let threshold = (dust_relay_fee_rate * output_size as u128).unwrap();
+    // The threshold doesn't change the size of the output it applies to
+    assert_eq!(transfer(threshold).encoded_size(), output_size);
+
+    let make_tx = |amount: Amount, rng: &mut _| {
+        TransactionBuilder::new()
+            .add_input(
+                TxInput::from_utxo(OutPointSourceId::BlockReward(genesis.get_id().into()), 0),
+                empty_witness(rng),
+            )
+            .add_output(transfer(Amount::from_atoms(1_000)))
+            .add_output(transfer(amount))
+            .build()
+    };
+
+    let mut mempool = setup_with_chainstate(tf.chainstate());
+
+    let below_threshold = (threshold - Amount::from_atoms(1)).unwrap();
+    assert_eq!(
+        mempool.add_transaction_test(make_tx(below_threshold, &mut rng)),
+        Err(MempoolPolicyError::DustOutput(1).into())
+    );
+
+    mempool.add_transaction_test(make_tx(threshold, &mut rng))?.assert_in_mempool();
+    mempool.store.assert_valid();
+    Ok(())
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn dust_exemptions(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let mempool = setup();
+
+    let (_, vrf_public_key) = VRFPrivateKey::new_from_rng(&mut rng, VRFKeyKind::Schnorrkel);
+    let pool_id = PoolId::new(H256::random_using(&mut rng));
+    let delegation_id = DelegationId::new(H256::random_using(&mut rng));
+    let tiny_amount = Amount::from_atoms(1);
+
+    let exempt_outputs = [
+        TxOutput::Burn(OutputValue::Coin(tiny_amount)),
+        TxOutput::CreateStakePool(
+            pool_id,
+            Box::new(StakePoolData::new(
+                tiny_amount,
+                Destination::AnyoneCanSpend,
+                vrf_public_key,
+                Destination::AnyoneCanSpend,
+                PerThousand::new(0).unwrap(),
+                Amount::ZERO,
+            )),
+        ),
+        TxOutput::ProduceBlockFromStake(Destination::AnyoneCanSpend, pool_id),
+        TxOutput::CreateDelegationId(Destination::AnyoneCanSpend, pool_id),
+        TxOutput::DelegateStaking(tiny_amount, delegation_id),
+    ];
+
+    for output in exempt_outputs {
+        let tx = TransactionBuilder::new()
+            .add_input(
+                TxInput::from_utxo(OutPointSourceId::Transaction(Id::new(H256::zero())), 0),
+                empty_witness(&mut rng),
+            )
+            .add_output(output)
+            .build();
+        assert_eq!(mempool.check_dust_outputs(&tx), Ok(()));
+    }
+
+    let tx = TransactionBuilder::new()
+        .add_input(
+            TxInput::from_utxo(OutPointSourceId::Transaction(Id::new(H256::zero())), 0),
+            empty_witness(&mut rng),
+        )
+        .add_output(TxOutput::DelegateStaking(tiny_amount, delegation_id))
+        .add_output(transfer(tiny_amount))
+        .build();
+    assert_eq!(
+        mempool.check_dust_outputs(&tx),
+        Err(MempoolPolicyError::DustOutput(1))
+    );
+}
@@ -19,6 +19,7 @@ use crate::{
         tx_pool::{memory_usage_estimator::StoreMemoryUsageEstimator, *},
         OrphanType, TxOptions, TxOrigin, TxStatus,
     },
+    tx_accumulator::PackingStrategy,
 };
 
 use ::utils::atomics::SeqCstAtomicU64;
@@ -43,9 +44,12 @@ use std::{collections::BTreeMap, ops::Deref, sync::Arc};
 
 mod accumulator;
 mod basic;
+mod dust;
 mod expiry;
+mod package_limits;
 mod reorg;
 mod replacement;
+mod snapshot;
 pub mod utils;
 
 use self::utils::*;
@@ -0,0 +1,268 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::PackageLimitError;
+
+use super::*;
+
+const INITIAL_AMOUNT: u128 = 1_000_000_000_000;
+const FEE: u128 = 100_000;
+
+/// A chain of transactions where each one spends the only output of the previous one
+fn make_chain(
+    rng: &mut (impl Rng + CryptoRng),
+    mut source: OutPointSourceId,
+    len: usize,
+) -> Vec<SignedTransaction> {
+    (1..=len as u128)
+        .map(|i| {
+            let tx = make_tx(rng, &[(source.clone(), 0)], &[INITIAL_AMOUNT - i * FEE]);
+            source = tx.transaction().get_id().into();
+            tx
+        })
+        .collect()
+}
+
+/// A transaction with `num_children` outputs and the children that spend them
+fn make_fan_out(
+    rng: &mut (impl Rng + CryptoRng),
+    source: OutPointSourceId,
+    num_children: usize,
+) -> (SignedTransaction, Vec<SignedTransaction>) {
+    let output_amount = INITIAL_AMOUNT / num_children as u128 - FEE;
+    let parent = make_tx(rng, &[(source, 0)], &vec![output_amount; num_children]);
+    let parent_id: OutPointSourceId = parent.transaction().get_id().into();
+    let children = (0..num_children as u32)
+        .map(|i| make_tx(rng, &[(parent_id.clone(), i)], &[output_amount - FEE]))
+        .collect();
+    (parent, children)
+}
+
+fn policy_error(err: PackageLimitError) -> Error {
+    MempoolPolicyError::from(err).into()
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn ancestor_count_limit(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis_id: OutPointSourceId = tf.genesis().get_id().into();
+    let limit = rng.gen_range(1..10);
+    let config = MempoolConfig {
+        max_ancestor_count: limit.into(),
+        ..test_mempool_config()
+    };
+    let mut mempool = setup_with_chainstate_and_config(tf.chainstate(), config);
+
+    let mut chain = make_chain(&mut rng, genesis_id.clone(), limit + 1);
+    let last_tx = chain.pop().unwrap();
+
+    for tx in chain {
+        mempool.add_transaction_test(tx).unwrap().assert_in_mempool();
+    }
+
+    assert_eq!(
+        mempool.add_transaction_test(last_tx.clone()),
+        Err(policy_error(PackageLimitError::TooManyAncestors {
+            count: limit + 1,
+            limit,
+        }))
+    );
+    assert!(!mempool.contains_transaction(&last_tx.transaction().get_id()));
+    mempool.store.assert_valid();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn ancestor_size_limit(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis_id: OutPointSourceId = tf.genesis().get_id().into();
+    let len = rng.gen_range(2..10);
+
+    let mut chain = make_chain(&mut rng, genesis_id.clone(), len);
+    let size: usize = chain.iter().map(|tx| tx.encoded_size()).sum();
+    let limit = size - 1;
+    let config = MempoolConfig {
+        max_ancestor_size: limit.into(),
+        max_descendant_size: size.into(),
+        ..test_mempool_config()
+    };
+    let mut mempool = setup_with_chainstate_and_config(tf.chainstate(), config);
+
+    let last_tx = chain.pop().unwrap();
+    for tx in chain {
+        mempool.add_transaction_test(tx).unwrap().assert_in_mempool();
+    }
+
+    assert_eq!(
+        mempool.add_transaction_test(last_tx),
+        Err(policy_error(PackageLimitError::AncestorSizeTooLarge {
+            size,
+            limit
+        }))
+    );
+    mempool.store.assert_valid();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn descendant_count_limit(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis_id: OutPointSourceId = tf.genesis().get_id().into();
+    let limit = rng.gen_range(1..10);
+    let config = MempoolConfig {
+        max_descendant_count: limit.into(),
+        ..test_mempool_config()
+    };
+    let mut mempool = setup_with_chainstate_and_config(tf.chainstate(), config);
+
+    let (parent, mut children) = make_fan_out(&mut rng, genesis_id, limit);
+    let parent_id = parent.transaction().get_id();
+    let last_child = children.pop().unwrap();
+
+    mempool.add_transaction_test(parent).unwrap().assert_in_mempool();
+    for child in children {
+        mempool.add_transaction_test(child).unwrap().assert_in_mempool();
+    }
+    assert_eq!(
+        mempool.store.get_entry(&parent_id).unwrap().count_with_descendants(),
+        limit
+    );
+
+    assert_eq!(
+        mempool.add_transaction_test(last_child),
+        Err(policy_error(PackageLimitError::TooManyDescendants {
+            ancestor: parent_id,
+            count: limit + 1,
+            limit,
+        }))
+    );
+    mempool.store.assert_valid();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn descendant_size_limit(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis_id: OutPointSourceId = tf.genesis().get_id().into();
+    let num_children = rng.gen_range(1..10);
+
+    let (parent, mut children) = make_fan_out(&mut rng, genesis_id, num_children);
+    let parent_id = parent.transaction().get_id();
+    let size = parent.encoded_size() + children.iter().map(|tx| tx.encoded_size()).sum::<usize>();
+    let limit = size - 1;
+    let config = MempoolConfig {
+        max_descendant_size: limit.into(),
+        ..test_mempool_config()
+    };
+    let mut mempool = setup_with_chainstate_and_config(tf.chainstate(), config);
+
+    let last_child = children.pop().unwrap();
+    mempool.add_transaction_test(parent).unwrap().assert_in_mempool();
+    for child in children {
+        mempool.add_transaction_test(child).unwrap().assert_in_mempool();
+    }
+
+    assert_eq!(
+        mempool.add_transaction_test(last_child),
+        Err(policy_error(PackageLimitError::DescendantSizeTooLarge {
+            ancestor: parent_id,
+            size,
+            limit,
+        }))
+    );
+    mempool.store.assert_valid();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn evicting_mid_chain_updates_descendant_counts(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis_id: OutPointSourceId = tf.genesis().get_id().into();
+    let limit = rng.gen_range(3..10);
+    let config = MempoolConfig {
+        max_descendant_count: limit.into(),
+        ..test_mempool_config()
+    };
+    let mut mempool = setup_with_chainstate_and_config(tf.chainstate(), config);
+
+    let chain = make_chain(&mut rng, genesis_id.clone(), limit);
+    let chain_ids: Vec<_> = chain.iter().map(|tx| tx.transaction().get_id()).collect();
+    for tx in chain {
+        mempool.add_transaction_test(tx).unwrap().assert_in_mempool();
+    }
+    for (i, tx_id) in chain_ids.iter().enumerate() {
+        let entry = mempool.store.get_entry(tx_id).unwrap();
+        assert_eq!(entry.count_with_descendants(), limit - i);
+        assert_eq!(entry.count_with_ancestors(), i + 1);
+    }
+
+    // The chain is full, so the root can't get another descendant
+    let extra_tx = make_tx(
+        &mut rng,
+        &[((*chain_ids.last().unwrap()).into(), 0)],
+        &[INITIAL_AMOUNT - (limit as u128 + 1) * FEE],
+    );
+    assert_eq!(
+        mempool.add_transaction_test(extra_tx),
+        Err(policy_error(PackageLimitError::TooManyDescendants {
+            ancestor: chain_ids[0],
+            count: limit + 1,
+            limit,
+        }))
+    );
+
+    // Evict a transaction in the middle of the chain together with its descendants
+    let evicted_idx = rng.gen_range(1..limit - 1);
+    mempool.remove_tx_and_descendants(&chain_ids[evicted_idx], MempoolRemovalReason::SizeLimit);
+    mempool.store.assert_valid();
+
+    for tx_id in &chain_ids[evicted_idx..] {
+        assert!(!mempool.contains_transaction(tx_id));
+    }
+    for (i, tx_id) in chain_ids[..evicted_idx].iter().enumerate() {
+        let entry = mempool.store.get_entry(tx_id).unwrap();
+        assert_eq!(entry.count_with_descendants(), evicted_idx - i);
+    }
+
+    // The freed up room can be used by a new branch
+    let mut source: OutPointSourceId = chain_ids[evicted_idx - 1].into();
+    for i in evicted_idx..limit {
+        let amount = INITIAL_AMOUNT - (i as u128 + 1) * FEE - 1;
+        let tx = make_tx(&mut rng, &[(source.clone(), 0)], &[amount]);
+        source = tx.transaction().get_id().into();
+        mempool.add_transaction_test(tx).unwrap().assert_in_mempool();
+    }
+    assert_eq!(
+        mempool.store.get_entry(&chain_ids[0]).unwrap().count_with_descendants(),
+        limit
+    );
+    mempool.store.assert_valid();
+}
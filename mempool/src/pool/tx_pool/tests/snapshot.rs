@@ -0,0 +1,153 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+
+use common::time_getter::TimeGetter;
+
+use super::*;
+use crate::tx_accumulator::DefaultTxAccumulator;
+
+// Useful for testing cases where timestamp is irrelevant.
+const DUMMY_TIMESTAMP: BlockTimestamp = BlockTimestamp::from_int_seconds(0u64);
+
+// Check that every transaction of the template is in the snapshot, that all the snapshot
+// transactions made it into the template and that parents always come before their children.
+fn assert_template_consistent(snapshot: &MempoolSnapshot, template: &[SignedTransaction]) {
+    let mut emitted = BTreeSet::new();
+
+    for tx in template {
+        let tx_id = tx.transaction().get_id();
+        assert!(snapshot.contains_transaction(&tx_id));
+
+        for input in tx.inputs() {
+            let parent_id = match input {
+                TxInput::Utxo(outpoint) => outpoint.source_id().get_tx_id().copied(),
+                TxInput::Account(_) | TxInput::AccountCommand(_, _) => None,
+            };
+            if let Some(parent_id) = parent_id.filter(|id| snapshot.contains_transaction(id)) {
+                assert!(emitted.contains(&parent_id), "Missing ancestor {parent_id}");
+            }
+        }
+
+        assert!(emitted.insert(tx_id), "Duplicate transaction {tx_id}");
+    }
+
+    assert_eq!(emitted.len(), snapshot.len());
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn snapshot_reused_until_contents_change(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let time = TimeGetter::default().get_time();
+    let txs: Vec<_> = generate_transaction_graph(&mut rng, time).take(2).collect();
+
+    let tf = TestFramework::builder(&mut rng).build();
+    let mut mempool = setup_with_chainstate(tf.chainstate());
+
+    let snapshot0 = mempool.snapshot();
+    assert!(snapshot0.is_empty());
+    assert_eq!(snapshot0.best_block_id(), mempool.best_block_id());
+    assert!(Arc::ptr_eq(&snapshot0, &mempool.snapshot()));
+
+    let tx0 = txs[0].transaction();
+    let tx0_id = tx0.transaction().get_id();
+    mempool
+        .add_transaction_bare(txs[0].tx_entry().clone())
+        .unwrap()
+        .assert_in_mempool();
+
+    let snapshot1 = mempool.snapshot();
+    assert!(!Arc::ptr_eq(&snapshot0, &snapshot1));
+    assert!(snapshot1.seq_no() > snapshot0.seq_no());
+    assert!(Arc::ptr_eq(&snapshot1, &mempool.snapshot()));
+    assert_eq!(snapshot1.get_all(), vec![tx0.clone()]);
+
+    // The snapshot shares the transaction data with the mempool
+    assert!(std::ptr::eq(
+        snapshot1.transaction(&tx0_id).unwrap(),
+        mempool.transaction(&tx0_id).unwrap(),
+    ));
+
+    let tx1_id = txs[1].transaction().transaction().get_id();
+    mempool
+        .add_transaction_bare(txs[1].tx_entry().clone())
+        .unwrap()
+        .assert_in_mempool();
+
+    // Older snapshots are not affected by subsequent changes
+    let snapshot2 = mempool.snapshot();
+    assert!(snapshot2.seq_no() > snapshot1.seq_no());
+    assert!(snapshot0.is_empty());
+    assert_eq!(snapshot1.len(), 1);
+    assert!(!snapshot1.contains_transaction(&tx1_id));
+    assert_eq!(snapshot2.len(), 2);
+    assert!(snapshot2.contains_transaction(&tx1_id));
+
+    // Resetting the mempool also makes the previous snapshot stale
+    let _ = mempool.reset();
+    let snapshot3 = mempool.snapshot();
+    assert!(snapshot3.seq_no() > snapshot2.seq_no());
+    assert!(snapshot3.is_empty());
+    assert_eq!(snapshot2.len(), 2);
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn template_from_snapshot_consistent_while_accepting(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let time = TimeGetter::default().get_time();
+    let num_txs = rng.gen_range(10..20);
+    let txs: Vec<_> = generate_transaction_graph(&mut rng, time).take(num_txs).collect();
+
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis_id = tf.genesis().get_id();
+    let mut mempool = setup_with_chainstate(tf.chainstate());
+
+    let num_initial = rng.gen_range(1..num_txs);
+    let (initial_txs, later_txs) = txs.split_at(num_initial);
+    for tx in initial_txs {
+        mempool.add_transaction_bare(tx.tx_entry().clone()).unwrap().assert_in_mempool();
+    }
+
+    // Build a template from a snapshot while the next transaction is being accepted
+    for tx in later_txs {
+        let snapshot = mempool.snapshot();
+        let template = tokio::task::spawn_blocking({
+            let snapshot = Arc::clone(&snapshot);
+            move || {
+                let accumulator = Box::new(DefaultTxAccumulator::new(
+                    1_000_000,
+                    genesis_id.into(),
+                    DUMMY_TIMESTAMP,
+                ));
+                snapshot.collect_txs(accumulator, vec![], PackingStrategy::FillSpaceFromMempool)
+            }
+        });
+
+        mempool.add_transaction_bare(tx.tx_entry().clone()).unwrap().assert_in_mempool();
+
+        let template = template.await.unwrap().unwrap().unwrap();
+        assert_template_consistent(&snapshot, template.transactions());
+        assert!(snapshot.seq_no() < mempool.snapshot().seq_no());
+    }
+
+    assert_eq!(mempool.snapshot().len(), num_txs);
+}
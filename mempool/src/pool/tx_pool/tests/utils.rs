@@ -41,10 +41,15 @@ pub const HUGE_MAX_TIP_AGE: MaxTipAge =
 pub const TEST_MIN_TX_RELAY_FEE_RATE: FeeRate =
     FeeRate::from_amount_per_kb(Amount::from_atoms(1000));
 
-pub fn create_mempool_config() -> ConstValue<MempoolConfig> {
-    ConstValue::new(MempoolConfig {
+pub fn test_mempool_config() -> MempoolConfig {
+    MempoolConfig {
         min_tx_relay_fee_rate: TEST_MIN_TX_RELAY_FEE_RATE.into(),
-    })
+        ..Default::default()
+    }
+}
+
+pub fn create_mempool_config() -> ConstValue<MempoolConfig> {
+    ConstValue::new(test_mempool_config())
 }
 
 pub fn get_relay_fee_from_tx_size(tx_size: usize) -> Amount {
@@ -342,6 +347,7 @@ pub fn setup_with_min_tx_relay_fee_rate(fee_rate: FeeRate) -> TxPool<StoreMemory
     let chain_config = Arc::new(common::chain::config::create_unit_test_config());
     let mempool_config = MempoolConfig {
         min_tx_relay_fee_rate: fee_rate.into(),
+        ..Default::default()
     };
     let chainstate_interface = start_chainstate_with_config(Arc::clone(&chain_config));
     TxPool::new(
@@ -355,13 +361,20 @@ pub fn setup_with_min_tx_relay_fee_rate(fee_rate: FeeRate) -> TxPool<StoreMemory
 
 pub fn setup_with_chainstate(
     chainstate: Box<dyn ChainstateInterface>,
+) -> TxPool<StoreMemoryUsageEstimator> {
+    setup_with_chainstate_and_config(chainstate, test_mempool_config())
+}
+
+pub fn setup_with_chainstate_and_config(
+    chainstate: Box<dyn ChainstateInterface>,
+    mempool_config: MempoolConfig,
 ) -> TxPool<StoreMemoryUsageEstimator> {
     logging::init_logging();
     let chain_config = Arc::clone(chainstate.get_chain_config());
     let chainstate_handle = start_chainstate(chainstate);
     TxPool::new(
         chain_config,
-        create_mempool_config(),
+        mempool_config.into(),
         chainstate_handle,
         Default::default(),
         StoreMemoryUsageEstimator,
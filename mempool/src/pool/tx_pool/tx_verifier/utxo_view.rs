@@ -16,7 +16,7 @@
 use common::chain::UtxoOutPoint;
 use utxo::{Utxo, UtxosView};
 
-use super::TxPool;
+use crate::pool::tx_pool::MempoolSnapshot;
 
 #[derive(Eq, PartialEq, Debug, thiserror::Error)]
 pub enum Error<P> {
@@ -26,7 +26,7 @@ pub enum Error<P> {
     ParentError(#[from] P),
 }
 
-/// Utxo view sourcing UTXOs from chainstate and mempool
+/// Utxo view sourcing UTXOs from chainstate and a mempool snapshot
 ///
 /// This sources the UTXOs from mempool and chainstate if not available in mempool. All mempool
 /// UTXOs are considered available, double spending is not checked by this view. This is useful for
@@ -34,18 +34,18 @@ pub enum Error<P> {
 /// be used to check RBF transactions without having to disconnect conflicting transactions or to
 /// check transactions to be included in the next block for time locks.
 /// However, it also means double spending has to be checked separately.
-pub struct MempoolUtxoView<'m, M, P> {
-    mempool: &'m TxPool<M>,
+pub struct MempoolUtxoView<'m, P> {
+    mempool: &'m MempoolSnapshot,
     parent: P,
 }
 
-impl<'m, M, P> MempoolUtxoView<'m, M, P> {
-    pub fn new(mempool: &'m TxPool<M>, parent: P) -> Self {
+impl<'m, P> MempoolUtxoView<'m, P> {
+    pub fn new(mempool: &'m MempoolSnapshot, parent: P) -> Self {
         Self { mempool, parent }
     }
 }
 
-impl<'m, M, P: UtxosView> UtxosView for MempoolUtxoView<'m, M, P> {
+impl<'m, P: UtxosView> UtxosView for MempoolUtxoView<'m, P> {
     type Error = Error<P::Error>;
 
     fn utxo(&self, outpoint: &UtxoOutPoint) -> Result<Option<Utxo>, Self::Error> {
@@ -54,10 +54,9 @@ impl<'m, M, P: UtxosView> UtxosView for MempoolUtxoView<'m, M, P> {
             None => return Ok(self.parent.utxo(outpoint)?),
         };
 
-        self.mempool.store.txs_by_id.get(&tx_id).map_or_else(
+        self.mempool.transaction(&tx_id).map_or_else(
             || Ok(self.parent.utxo(outpoint)?),
-            |tx_entry| {
-                let tx = tx_entry.transaction();
+            |tx| {
                 let output = tx
                     .outputs()
                     .get(outpoint.output_index() as usize)
@@ -19,13 +19,17 @@ use std::num::NonZeroUsize;
 
 use common::{
     chain::{GenBlock, SignedTransaction, Transaction},
-    primitives::Id,
+    primitives::{Id, Idable},
 };
-use mempool_types::{tx_options::TxOptionsOverrides, tx_origin::LocalTxOrigin, TxOptions};
-use serialization::hex_encoded::HexEncoded;
+use mempool_types::{
+    tx_options::{TxOptionsOverrides, TxRelayPolicy},
+    tx_origin::LocalTxOrigin,
+    TxOptions,
+};
+use serialization::hex_encoded::{HexEncoded, StrictHexEncoded};
 use utils::tap_log::TapLog;
 
-use crate::{FeeRate, MempoolMaxSize, TxStatus};
+use crate::{FeeRate, MempoolMaxSize, PackageLimits, TxStatus};
 
 use rpc::RpcResult;
 
@@ -70,10 +74,24 @@ trait MempoolRpc {
     #[method(name = "submit_transaction")]
     async fn submit_transaction(
         &self,
-        tx: HexEncoded<SignedTransaction>,
+        tx: StrictHexEncoded<SignedTransaction>,
         options: TxOptionsOverrides,
     ) -> RpcResult<()>;
 
+    /// Submit a raw signed transaction to the mempool and broadcast it to the network.
+    ///
+    /// Unlike `submit_transaction`, the transaction is relayed to peers by default.
+    /// With `relay_only`, the transaction is validated and relayed, but never included in
+    /// the blocks produced by this node. With `do_not_relay`, the transaction is added to
+    /// the local mempool only, without being announced to peers.
+    /// Returns the id of the transaction.
+    #[method(name = "submit_raw_transaction")]
+    async fn submit_raw_transaction(
+        &self,
+        tx: StrictHexEncoded<SignedTransaction>,
+        options: TxOptionsOverrides,
+    ) -> RpcResult<Id<Transaction>>;
+
     /// Return the id of the best block, as seen by the mempool.
     ///
     /// Typically this agrees with chainstate, but there could be some delay in responding to chainstate.
@@ -94,6 +112,11 @@ trait MempoolRpc {
     #[method(name = "set_size_limit")]
     async fn set_size_limit(&self, max_size: MempoolMaxSize) -> RpcResult<()>;
 
+    /// Get the limits on the number and total size (in bytes) of unconfirmed ancestors and
+    /// descendants a transaction can have in the mempool. Counts and sizes include the transaction itself.
+    #[method(name = "get_package_limits")]
+    async fn get_package_limits(&self) -> RpcResult<PackageLimits>;
+
     /// Get the current fee rate of the mempool, that puts the transaction in the top X MBs of the mempool.
     /// X, in this description, is provided as a parameter.
     #[method(name = "get_fee_rate")]
@@ -115,12 +138,9 @@ impl MempoolRpcServer for super::MempoolHandle {
     }
 
     async fn get_all_transactions(&self) -> rpc::RpcResult<Vec<HexEncoded<SignedTransaction>>> {
-        rpc::handle_result(
-            self.call(move |this| -> Vec<HexEncoded<SignedTransaction>> {
-                this.get_all().into_iter().map(HexEncoded::new).collect()
-            })
-            .await,
-        )
+        // Only take the snapshot in the mempool, the list is put together outside of it
+        let snapshot = rpc::handle_result(self.call(|this| this.snapshot()).await)?;
+        Ok(snapshot.get_all().into_iter().map(HexEncoded::new).collect())
     }
 
     async fn get_transaction(
@@ -145,11 +165,12 @@ impl MempoolRpcServer for super::MempoolHandle {
 
     async fn submit_transaction(
         &self,
-        tx: HexEncoded<SignedTransaction>,
+        tx: StrictHexEncoded<SignedTransaction>,
         options: TxOptionsOverrides,
     ) -> rpc::RpcResult<()> {
         let origin = LocalTxOrigin::Mempool;
-        let options = TxOptions::default_for(origin.into()).with_overrides(options);
+        let options: TxOptions =
+            rpc::handle_result(TxOptions::default_for(origin.into()).with_overrides(options))?;
         let res = self
             .call_mut(move |m| m.add_transaction_local(tx.take(), origin, options))
             .await
@@ -157,6 +178,27 @@ impl MempoolRpcServer for super::MempoolHandle {
         rpc::handle_result(res)
     }
 
+    async fn submit_raw_transaction(
+        &self,
+        tx: StrictHexEncoded<SignedTransaction>,
+        options: TxOptionsOverrides,
+    ) -> rpc::RpcResult<Id<Transaction>> {
+        let origin = LocalTxOrigin::Mempool;
+        let options: TxOptions = rpc::handle_result(
+            TxOptions::default_for(origin.into())
+                .with_relay_policy(TxRelayPolicy::DoRelay)
+                .with_overrides(options),
+        )?;
+        let tx = tx.take();
+        let tx_id = tx.transaction().get_id();
+        let res = self
+            .call_mut(move |m| m.add_transaction_local(tx, origin, options))
+            .await
+            .log_err();
+        rpc::handle_result(res)?;
+        Ok(tx_id)
+    }
+
     async fn local_best_block_id(&self) -> rpc::RpcResult<Id<GenBlock>> {
         rpc::handle_result(self.call(|this| this.best_block_id()).await)
     }
@@ -173,6 +215,10 @@ impl MempoolRpcServer for super::MempoolHandle {
         rpc::handle_result(self.call_mut(move |this| this.set_size_limit(max_size)).await)
     }
 
+    async fn get_package_limits(&self) -> rpc::RpcResult<PackageLimits> {
+        rpc::handle_result(self.call(|this| this.get_package_limits()).await)
+    }
+
     async fn get_fee_rate(&self, in_top_x_mb: usize) -> rpc::RpcResult<FeeRate> {
         rpc::handle_result(self.call(move |this| this.get_fee_rate(in_top_x_mb)).await)
     }
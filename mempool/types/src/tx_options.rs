@@ -35,6 +35,21 @@ pub enum TxRelayPolicy {
     DontRelay,
 }
 
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum TxBlockInclusionPolicy {
+    /// Transaction can be included in the blocks produced by this node
+    Include,
+
+    /// Transaction is only kept for relaying, it's never put into the blocks produced by this node
+    Exclude,
+}
+
+#[derive(thiserror::Error, Clone, Copy, Eq, PartialEq, Debug)]
+pub enum TxOptionsError {
+    #[error("The relay_only and do_not_relay options cannot be used together")]
+    RelayOnlyAndDoNotRelay,
+}
+
 /// Options specifying how should a transaction be handled by mempool and p2p.
 // Can be extended further with custom eviction policies, tx orphan pool policies, etc.
 #[derive(Clone, Eq, PartialEq, Debug)]
@@ -44,6 +59,9 @@ pub struct TxOptions {
 
     /// Whether the transaction should be relayed
     relay_policy: TxRelayPolicy,
+
+    /// Whether the transaction can be included in the blocks produced by this node
+    block_inclusion_policy: TxBlockInclusionPolicy,
 }
 
 impl TxOptions {
@@ -63,16 +81,39 @@ impl TxOptions {
         TxOptions {
             trust_policy,
             relay_policy,
+            block_inclusion_policy: TxBlockInclusionPolicy::Include,
         }
     }
 
+    /// Set the relay policy
+    pub const fn with_relay_policy(mut self, relay_policy: TxRelayPolicy) -> Self {
+        self.relay_policy = relay_policy;
+        self
+    }
+
     /// Apply given user-specified overrides to the options
-    pub const fn with_overrides(mut self, overrides: TxOptionsOverrides) -> Self {
+    pub const fn with_overrides(
+        mut self,
+        overrides: TxOptionsOverrides,
+    ) -> Result<Self, TxOptionsError> {
+        if overrides.relay_only && overrides.do_not_relay {
+            return Err(TxOptionsError::RelayOnlyAndDoNotRelay);
+        }
+
         if let Some(trust_policy) = overrides.trust_policy {
             self.trust_policy = trust_policy;
         }
 
-        self
+        if overrides.relay_only {
+            self.relay_policy = TxRelayPolicy::DoRelay;
+            self.block_inclusion_policy = TxBlockInclusionPolicy::Exclude;
+        }
+
+        if overrides.do_not_relay {
+            self.relay_policy = TxRelayPolicy::DontRelay;
+        }
+
+        Ok(self)
     }
 
     pub fn trust_policy(&self) -> TxTrustPolicy {
@@ -82,6 +123,10 @@ impl TxOptions {
     pub fn relay_policy(&self) -> TxRelayPolicy {
         self.relay_policy
     }
+
+    pub fn block_inclusion_policy(&self) -> TxBlockInclusionPolicy {
+        self.block_inclusion_policy
+    }
 }
 
 /// Mechanism to apply user-specified overrides to [TxOptions].
@@ -90,11 +135,34 @@ impl TxOptions {
 pub struct TxOptionsOverrides {
     /// Override transaction trust policy.
     trust_policy: Option<TxTrustPolicy>,
+
+    /// Validate the transaction and relay it to peers, but never include it in the blocks
+    /// produced by this node.
+    relay_only: bool,
+
+    /// Add the transaction to the local mempool, but never announce it to peers.
+    do_not_relay: bool,
+}
+
+impl TxOptionsOverrides {
+    pub const fn with_relay_only(mut self, relay_only: bool) -> Self {
+        self.relay_only = relay_only;
+        self
+    }
+
+    pub const fn with_do_not_relay(mut self, do_not_relay: bool) -> Self {
+        self.do_not_relay = do_not_relay;
+        self
+    }
 }
 
 impl rpc_description::HasValueHint for TxOptionsOverrides {
-    const HINT_SER: VH = VH::Object(&[(
-        "trust_policy",
-        &VH::Choice(&[&VH::StrLit("Trusted"), &VH::StrLit("Untrusted")]),
-    )]);
+    const HINT_SER: VH = VH::Object(&[
+        (
+            "trust_policy",
+            &VH::Choice(&[&VH::StrLit("Trusted"), &VH::StrLit("Untrusted")]),
+        ),
+        ("relay_only", &VH::BOOL),
+        ("do_not_relay", &VH::BOOL),
+    ]);
 }
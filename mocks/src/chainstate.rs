@@ -16,9 +16,11 @@
 use std::{collections::BTreeMap, num::NonZeroUsize, sync::Arc};
 
 use chainstate::{
-    BlockSource, ChainInfo, ChainstateConfig, ChainstateError, ChainstateEvent, Locator,
+    BlockHeaderCheckInfo, BlockSizeHistogram, BlockSource, ChainInfo, ChainstateConfig,
+    ChainstateError, ChainstateEvent, ConsensusInfo, DecodedTransaction, Locator,
+    NetworkUpgradeInfo, ProofOfReservesReport, StakePoolInfo,
 };
-use chainstate_types::{BlockIndex, EpochData, GenBlockIndex};
+use chainstate_types::{BlockFeesInfo, BlockIndex, EpochData, GenBlockIndex};
 use common::{
     chain::{
         block::{
@@ -44,6 +46,7 @@ mockall::mock! {
         fn subscribe_to_subsystem_events(&mut self, handler: Arc<dyn Fn(ChainstateEvent) + Send + Sync>);
         fn subscribe_to_rpc_events(&mut self) -> utils_networking::broadcaster::Receiver<ChainstateEvent>;
         fn process_block(&mut self, block: Block, source: BlockSource) -> Result<Option<BlockIndex>, ChainstateError>;
+        fn flush_storage(&mut self) -> Result<(), ChainstateError>;
         fn invalidate_block(&mut self, block_id: &Id<Block>) -> Result<(), ChainstateError>;
         fn reset_block_failure_flags(&mut self, block_id: &Id<Block>) -> Result<(), ChainstateError>;
         fn preliminary_block_check(&self, block: Block) -> Result<Block, ChainstateError>;
@@ -51,6 +54,10 @@ mockall::mock! {
             &self,
             headers: &[SignedBlockHeader],
         )-> Result<(), ChainstateError>;
+        fn check_block_header(
+            &self,
+            header: &SignedBlockHeader,
+        ) -> Result<BlockHeaderCheckInfo, ChainstateError>;
         fn get_best_block_id(&self) -> Result<Id<GenBlock>, ChainstateError>;
         fn get_best_block_height(&self) -> Result<BlockHeight, ChainstateError>;
         fn get_best_block_header(&self) -> Result<SignedBlockHeader, ChainstateError>;
@@ -71,6 +78,7 @@ mockall::mock! {
             max_count: usize,
         ) -> Result<Vec<Block>, ChainstateError>;
         fn get_block_header(&self, block_id: Id<Block>) -> Result<Option<SignedBlockHeader>, ChainstateError>;
+        fn get_ancestors(&self, block_id: Id<Block>, depth: u32) -> Result<Vec<Id<Block>>, ChainstateError>;
         fn get_locator(&self) -> Result<Locator, ChainstateError>;
         fn get_locator_from_height(&self, height: BlockHeight) -> Result<Locator, ChainstateError>;
         fn get_block_ids_as_checkpoints(
@@ -195,6 +203,28 @@ mockall::mock! {
             delegation_id: DelegationId,
         ) -> Result<Option<Amount>, ChainstateError>;
         fn info(&self) -> Result<ChainInfo, ChainstateError>;
+        fn get_network_upgrades(&self) -> Result<Vec<NetworkUpgradeInfo>, ChainstateError>;
+        fn get_block_size_histogram(
+            &self,
+            from_height: BlockHeight,
+            to_height: BlockHeight,
+        ) -> Result<BlockSizeHistogram, ChainstateError>;
+        fn estimate_next_block_timestamp(&self) -> Result<BlockTimestamp, ChainstateError>;
+        fn get_consensus_info(
+            &self,
+            from_height: BlockHeight,
+            to_height: BlockHeight,
+        ) -> Result<ConsensusInfo, ChainstateError>;
+        fn get_all_pools(&self) -> Result<Vec<StakePoolInfo>, ChainstateError>;
+        fn decode_transaction(&self, tx_hex: String) -> Result<DecodedTransaction, ChainstateError>;
+        fn verify_proof_of_reserves(
+            &self,
+            proof_hex: String,
+        ) -> Result<ProofOfReservesReport, ChainstateError>;
+        fn get_block_fees(
+            &self,
+            block_id: &Id<Block>,
+        ) -> Result<Option<BlockFeesInfo>, ChainstateError>;
         fn get_account_nonce_count(
             &self,
             account: AccountType,
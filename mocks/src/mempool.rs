@@ -22,11 +22,10 @@ use common::{
     primitives::Id,
 };
 use mempool::{
-    error::{BlockConstructionError, Error},
+    error::Error,
     event::MempoolEvent,
-    tx_accumulator::{PackingStrategy, TransactionAccumulator},
     tx_origin::{LocalTxOrigin, RemoteTxOrigin},
-    FeeRate, MempoolInterface, MempoolMaxSize, TxOptions, TxStatus,
+    FeeRate, MempoolInterface, MempoolMaxSize, MempoolSnapshot, PackageLimits, TxOptions, TxStatus,
 };
 
 mockall::mock! {
@@ -48,23 +47,20 @@ mockall::mock! {
         ) -> Result<TxStatus, Error>;
 
         fn get_all(&self) -> Vec<SignedTransaction>;
+        fn best_transaction_ids(&self, max_count: usize) -> Vec<Id<Transaction>>;
+        fn get_top_n_by_fee(&self, n: usize) -> Vec<Id<Transaction>>;
         fn transaction(&self, id: &Id<Transaction>) -> Option<SignedTransaction>;
         fn orphan_transaction(&self, id: &Id<Transaction>) -> Option<SignedTransaction>;
         fn contains_transaction(&self, tx: &Id<Transaction>) -> bool;
         fn contains_orphan_transaction(&self, tx: &Id<Transaction>) -> bool;
         fn best_block_id(&self) -> Id<GenBlock>;
-
-        fn collect_txs(
-            &self,
-            tx_accumulator: Box<dyn TransactionAccumulator + Send>,
-            transaction_ids: Vec<Id<Transaction>>,
-            packing_strategy: PackingStrategy,
-        ) -> Result<Option<Box<dyn TransactionAccumulator>>, BlockConstructionError>;
+        fn snapshot(&self) -> Arc<MempoolSnapshot>;
 
         fn subscribe_to_events(&mut self, handler: Arc<dyn Fn(MempoolEvent) + Send + Sync>);
         fn memory_usage(&self) -> usize;
         fn get_size_limit(&self) -> MempoolMaxSize;
         fn set_size_limit(&mut self, max_size: MempoolMaxSize) -> Result<(), Error>;
+        fn get_package_limits(&self) -> PackageLimits;
         fn get_fee_rate(&self, in_top_x_mb: usize) -> FeeRate;
         fn get_fee_rate_points(&self, num_points: NonZeroUsize) -> Result<Vec<(usize, FeeRate)>, Error>;
 
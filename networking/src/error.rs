@@ -24,6 +24,8 @@ pub enum NetworkingError {
     MessageCodecError(#[from] MessageCodecError),
     #[error("Noise protocol handshake error")]
     NoiseHandshakeError(String),
+    #[error("Invalid noise key: {0}")]
+    InvalidNoiseKey(String),
     #[error("Proxy error: {0}")]
     ProxyError(String),
 
@@ -35,6 +37,14 @@ pub enum NetworkingError {
 pub enum MessageCodecError {
     #[error("Message size {actual_size} exceeds the maximum size {max_size}")]
     MessageTooLarge { actual_size: usize, max_size: usize },
+    #[error(
+        "Size {actual_size} of the message with tag {tag} exceeds the maximum size {max_size}"
+    )]
+    MessageTypeTooLarge {
+        tag: u8,
+        actual_size: usize,
+        max_size: usize,
+    },
     #[error("Cannot decode data: {0}")]
     InvalidEncodedData(serialization::Error),
 }
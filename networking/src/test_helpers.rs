@@ -30,8 +30,8 @@ use std::{
 use randomness::Rng;
 
 use crate::transport::{
-    MpscChannelTransport, NoiseEncryptionAdapter, NoiseTcpTransport, TcpTransportSocket,
-    TransportListener, TransportSocket,
+    MpscChannelTransport, NoiseEncryptionAdapter, NoiseEncryptionAdapterMaker, NoiseTcpTransport,
+    TcpTransportSocket, TransportListener, TransportSocket,
 };
 
 /// An interface for creating transports and addresses used in tests.
@@ -86,7 +86,10 @@ impl TestTransportMaker for TestTransportNoise {
 
     fn make_transport() -> Self::Transport {
         let base_transport = TcpTransportSocket::new();
-        NoiseTcpTransport::new(NoiseEncryptionAdapter::gen_new, base_transport)
+        NoiseTcpTransport::new(
+            NoiseEncryptionAdapterMaker::new(NoiseEncryptionAdapter::gen_new()),
+            base_transport,
+        )
     }
 
     fn make_address() -> SocketAddr {
@@ -19,9 +19,12 @@ use bytes::BytesMut;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio_util::codec::{Decoder, Encoder};
 
-use serialization::{Decode, Encode};
+use serialization::{strict::DecodeStrict, Encode};
 
-use super::message_codec::MessageCodec;
+use super::{
+    message_codec::{MessageCodec, MessageTagSizeLimits},
+    metrics::metrics,
+};
 
 pub struct BufferedTranscoder<S, Msg> {
     stream: S,
@@ -39,6 +42,16 @@ impl<S, Msg> BufferedTranscoder<S, Msg> {
         }
     }
 
+    /// Set the size limits for specific types of the received messages.
+    pub fn set_recv_tag_size_limits(&mut self, limits: MessageTagSizeLimits) {
+        self.message_codec.set_recv_tag_size_limits(limits);
+    }
+
+    /// Set the size limits for specific types of the sent messages.
+    pub fn set_send_tag_size_limits(&mut self, limits: MessageTagSizeLimits) {
+        self.message_codec.set_send_tag_size_limits(limits);
+    }
+
     /// The inner stream. This is only accessible as an immutable reference, so it'll allow
     /// to read some additional info that the concrete stream might provide, but won't allow
     /// reading or writing the actual stream data.
@@ -49,7 +62,7 @@ impl<S, Msg> BufferedTranscoder<S, Msg> {
 
 impl<S, Msg> BufferedTranscoder<S, Msg>
 where
-    Msg: Encode + Decode,
+    Msg: Encode + DecodeStrict,
     S: AsyncWrite + AsyncRead + Unpin,
 {
     pub async fn send(&mut self, msg: Msg) -> crate::Result<()> {
@@ -57,6 +70,7 @@ where
         self.message_codec.encode(msg, &mut buf)?;
         self.stream.write_all(&buf).await?;
         self.stream.flush().await?;
+        metrics().bytes_sent.inc_by(buf.len() as u64);
         Ok(())
     }
 
@@ -70,9 +84,11 @@ where
         loop {
             match self.message_codec.decode(&mut self.buffer) {
                 Ok(None) => {
-                    if self.stream.read_buf(&mut self.buffer).await? == 0 {
+                    let read = self.stream.read_buf(&mut self.buffer).await?;
+                    if read == 0 {
                         return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
                     }
+                    metrics().bytes_received.inc_by(read as u64);
                     continue;
                 }
                 Ok(Some(msg)) => return Ok(msg),
@@ -0,0 +1,218 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    io,
+    net::SocketAddr,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::{
+    transport::{ConnectedSocketInfo, PeerStream, TransportListener, TransportSocket},
+    Result,
+};
+
+/// Transport that listens on some addresses using a secondary transport, in addition to
+/// the addresses of the primary one (e.g. an unencrypted listener on localhost alongside
+/// the encrypted public one).
+///
+/// Outbound connections always use the primary transport.
+#[derive(Debug)]
+pub struct DualTransportSocket<P, S> {
+    primary: P,
+    secondary: S,
+    secondary_bind_addresses: Vec<SocketAddr>,
+}
+
+impl<P, S> DualTransportSocket<P, S> {
+    pub fn new(primary: P, secondary: S, secondary_bind_addresses: Vec<SocketAddr>) -> Self {
+        Self {
+            primary,
+            secondary,
+            secondary_bind_addresses,
+        }
+    }
+}
+
+#[async_trait]
+impl<P: TransportSocket, S: TransportSocket> TransportSocket for DualTransportSocket<P, S> {
+    type Listener = DualTransportListener<P::Listener, S::Listener>;
+    type Stream = DualTransportStream<P::Stream, S::Stream>;
+
+    /// Bind the given addresses using the primary transport and the secondary bind addresses
+    /// using the secondary one.
+    async fn bind(&self, addresses: Vec<SocketAddr>) -> Result<Self::Listener> {
+        let primary = self.primary.bind(addresses).await?;
+        let secondary = self.secondary.bind(self.secondary_bind_addresses.clone()).await?;
+        Ok(DualTransportListener { primary, secondary })
+    }
+
+    fn connect(&self, address: SocketAddr) -> BoxFuture<'static, Result<Self::Stream>> {
+        let connect = self.primary.connect(address);
+        Box::pin(async move { Ok(DualTransportStream::Primary(connect.await?)) })
+    }
+
+    fn local_static_public_key(&self) -> Option<Vec<u8>> {
+        self.primary.local_static_public_key()
+    }
+}
+
+pub struct DualTransportListener<P, S> {
+    primary: P,
+    secondary: S,
+}
+
+#[async_trait]
+impl<P: TransportListener, S: TransportListener> TransportListener for DualTransportListener<P, S> {
+    type Stream = DualTransportStream<P::Stream, S::Stream>;
+
+    async fn accept(&mut self) -> Result<(Self::Stream, SocketAddr)> {
+        // Both accept calls are cancel safe
+        tokio::select! {
+            res = self.primary.accept() => {
+                res.map(|(stream, address)| (DualTransportStream::Primary(stream), address))
+            }
+            res = self.secondary.accept() => {
+                res.map(|(stream, address)| (DualTransportStream::Secondary(stream), address))
+            }
+        }
+    }
+
+    fn local_addresses(&self) -> Result<Vec<SocketAddr>> {
+        let mut addresses = self.primary.local_addresses()?;
+        addresses.extend(self.secondary.local_addresses()?);
+        Ok(addresses)
+    }
+}
+
+/// A stream of either the primary or the secondary transport
+pub enum DualTransportStream<P, S> {
+    Primary(P),
+    Secondary(S),
+}
+
+impl<P: PeerStream, S: PeerStream> AsyncRead for DualTransportStream<P, S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Primary(stream) => Pin::new(stream).poll_read(cx, buf),
+            Self::Secondary(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl<P: PeerStream, S: PeerStream> AsyncWrite for DualTransportStream<P, S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Self::Primary(stream) => Pin::new(stream).poll_write(cx, buf),
+            Self::Secondary(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Primary(stream) => Pin::new(stream).poll_flush(cx),
+            Self::Secondary(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Primary(stream) => Pin::new(stream).poll_shutdown(cx),
+            Self::Secondary(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
+
+impl<P: PeerStream, S: PeerStream> PeerStream for DualTransportStream<P, S> {}
+
+impl<P: ConnectedSocketInfo, S: ConnectedSocketInfo> ConnectedSocketInfo
+    for DualTransportStream<P, S>
+{
+    fn local_address(&self) -> Result<SocketAddr> {
+        match self {
+            Self::Primary(stream) => stream.local_address(),
+            Self::Secondary(stream) => stream.local_address(),
+        }
+    }
+
+    fn remote_address(&self) -> Result<SocketAddr> {
+        match self {
+            Self::Primary(stream) => stream.remote_address(),
+            Self::Secondary(stream) => stream.remote_address(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use crate::{
+        test_helpers::{TestTransportMaker, TestTransportNoise, TestTransportTcp},
+        transport::TcpTransportSocket,
+    };
+
+    use super::*;
+
+    #[tracing::instrument]
+    #[tokio::test]
+    async fn accept_on_both_transports() {
+        let transport = DualTransportSocket::new(
+            TestTransportNoise::make_transport(),
+            TcpTransportSocket::new(),
+            vec![TestTransportTcp::make_address()],
+        );
+        let mut listener = transport.bind(vec![TestTransportNoise::make_address()]).await.unwrap();
+        let addresses = listener.local_addresses().unwrap();
+        assert_eq!(addresses.len(), 2);
+
+        // An encrypted connection to the primary listener
+        let (accept_res, connect_res) =
+            tokio::join!(listener.accept(), transport.connect(addresses[0]));
+        let (mut server_stream, _) = accept_res.unwrap();
+        assert!(matches!(server_stream, DualTransportStream::Primary(_)));
+        let mut client_stream = connect_res.unwrap();
+        client_stream.write_all(b"noise").await.unwrap();
+        client_stream.flush().await.unwrap();
+        let mut buf = [0; 5];
+        server_stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"noise");
+
+        // A plaintext connection to the secondary listener
+        let plaintext_transport = TcpTransportSocket::new();
+        let (accept_res, connect_res) =
+            tokio::join!(listener.accept(), plaintext_transport.connect(addresses[1]));
+        let (mut server_stream, _) = accept_res.unwrap();
+        assert!(matches!(server_stream, DualTransportStream::Secondary(_)));
+        let mut client_stream = connect_res.unwrap();
+        client_stream.write_all(b"plain").await.unwrap();
+        client_stream.flush().await.unwrap();
+        server_stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"plain");
+    }
+}
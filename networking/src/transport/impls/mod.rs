@@ -14,6 +14,7 @@
 // limitations under the License.
 
 pub mod channel;
+pub mod dual;
 pub mod socks5;
 pub mod stream_adapter;
 pub mod tcp;
@@ -26,18 +26,26 @@ use crate::{
     Result,
 };
 
-// TODO: Add tests. A typical way to test this is to create a forwarding proxy with a socks interface
-// that can test that things sent through the proxy are delivered to the other end.
-
 #[derive(Debug)]
 pub struct Socks5TransportSocket {
     proxy: Arc<String>,
+    /// Username and password, if the proxy requires authentication
+    auth: Option<Arc<(String, String)>>,
 }
 
 impl Socks5TransportSocket {
     pub fn new(proxy: &str) -> Self {
         Self {
             proxy: Arc::new(proxy.to_owned()),
+            auth: None,
+        }
+    }
+
+    /// Connect through a proxy that requires the username/password authentication (RFC 1929)
+    pub fn with_auth(proxy: &str, username: String, password: String) -> Self {
+        Self {
+            proxy: Arc::new(proxy.to_owned()),
+            auth: Some(Arc::new((username, password))),
         }
     }
 }
@@ -53,14 +61,22 @@ impl TransportSocket for Socks5TransportSocket {
 
     fn connect(&self, address: SocketAddr) -> BoxFuture<'static, Result<Self::Stream>> {
         let proxy = Arc::clone(&self.proxy);
+        let auth = self.auth.clone();
         Box::pin(async move {
             let socket = TcpStream::connect(proxy.as_str()).await.map_err(|e| {
                 NetworkingError::ProxyError(format!("Connection to the SOCKS5 proxy failed: {e}"))
             })?;
 
-            let stream = Socks5Stream::connect_with_socket(socket, address).await.map_err(|e| {
-                NetworkingError::ProxyError(format!("Unexpected SOCKS5 error: {e}"))
-            })?;
+            let stream = match auth.as_deref() {
+                Some((username, password)) => {
+                    Socks5Stream::connect_with_password_and_socket(
+                        socket, address, username, password,
+                    )
+                    .await
+                }
+                None => Socks5Stream::connect_with_socket(socket, address).await,
+            }
+            .map_err(|e| NetworkingError::ProxyError(format!("Unexpected SOCKS5 error: {e}")))?;
 
             Ok(stream)
         })
@@ -105,3 +121,164 @@ impl ConnectedSocketInfo for Socks5TransportStream {
         Ok(TcpStream::peer_addr(self)?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    const SOCKS_VERSION: u8 = 0x05;
+    const AUTH_VERSION: u8 = 0x01;
+    const METHOD_PASSWORD: u8 = 0x02;
+    const METHOD_NOT_ACCEPTABLE: u8 = 0xff;
+
+    /// What the mock proxy has received from the client
+    #[derive(Debug, Default, PartialEq, Eq)]
+    struct ProxySession {
+        credentials: Option<(String, String)>,
+        target: Option<SocketAddr>,
+    }
+
+    async fn read_string(socket: &mut TcpStream) -> std::io::Result<String> {
+        let len = socket.read_u8().await?;
+        let mut buf = vec![0; len as usize];
+        socket.read_exact(&mut buf).await?;
+        Ok(String::from_utf8(buf).unwrap())
+    }
+
+    /// Accept a single connection, acting as a SOCKS5 proxy that requires the given credentials.
+    ///
+    /// Instead of connecting to the target, the proxy echoes back everything sent to it.
+    async fn run_mock_proxy(
+        listener: TcpListener,
+        username: &str,
+        password: &str,
+    ) -> std::io::Result<ProxySession> {
+        let (mut socket, _) = listener.accept().await?;
+        let mut session = ProxySession::default();
+
+        assert_eq!(socket.read_u8().await?, SOCKS_VERSION);
+        let methods_count = socket.read_u8().await?;
+        let mut methods = vec![0; methods_count as usize];
+        socket.read_exact(&mut methods).await?;
+        if !methods.contains(&METHOD_PASSWORD) {
+            socket.write_all(&[SOCKS_VERSION, METHOD_NOT_ACCEPTABLE]).await?;
+            return Ok(session);
+        }
+        socket.write_all(&[SOCKS_VERSION, METHOD_PASSWORD]).await?;
+
+        assert_eq!(socket.read_u8().await?, AUTH_VERSION);
+        let client_username = read_string(&mut socket).await?;
+        let client_password = read_string(&mut socket).await?;
+        let authenticated = client_username == username && client_password == password;
+        session.credentials = Some((client_username, client_password));
+        socket.write_all(&[AUTH_VERSION, if authenticated { 0 } else { 1 }]).await?;
+        if !authenticated {
+            return Ok(session);
+        }
+
+        // Only the CONNECT command to an IP address is expected
+        let mut request = [0; 4];
+        socket.read_exact(&mut request).await?;
+        assert_eq!(request[..3], [SOCKS_VERSION, 0x01, 0x00]);
+        let ip: std::net::IpAddr = match request[3] {
+            0x01 => {
+                let mut ip = [0; 4];
+                socket.read_exact(&mut ip).await?;
+                ip.into()
+            }
+            0x04 => {
+                let mut ip = [0; 16];
+                socket.read_exact(&mut ip).await?;
+                ip.into()
+            }
+            atyp => panic!("Unexpected address type {atyp}"),
+        };
+        let port = socket.read_u16().await?;
+        session.target = Some(SocketAddr::new(ip, port));
+        socket.write_all(&[SOCKS_VERSION, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0]).await?;
+
+        let (mut reader, mut writer) = socket.split();
+        tokio::io::copy(&mut reader, &mut writer).await?;
+
+        Ok(session)
+    }
+
+    async fn start_mock_proxy(
+        username: &'static str,
+        password: &'static str,
+    ) -> (
+        String,
+        tokio::task::JoinHandle<std::io::Result<ProxySession>>,
+    ) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy = listener.local_addr().unwrap().to_string();
+        let handle = tokio::spawn(run_mock_proxy(listener, username, password));
+        (proxy, handle)
+    }
+
+    #[tracing::instrument]
+    #[tokio::test]
+    async fn connect_with_auth() {
+        let (proxy, proxy_handle) = start_mock_proxy("user", "pass").await;
+        let target: SocketAddr = "10.1.2.3:3031".parse().unwrap();
+
+        let transport =
+            Socks5TransportSocket::with_auth(&proxy, "user".to_owned(), "pass".to_owned());
+        let mut stream = transport.connect(target).await.unwrap();
+
+        stream.write_all(b"hello").await.unwrap();
+        let mut buf = [0; 5];
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"hello");
+        drop(stream);
+
+        let session = proxy_handle.await.unwrap().unwrap();
+        assert_eq!(
+            session,
+            ProxySession {
+                credentials: Some(("user".to_owned(), "pass".to_owned())),
+                target: Some(target),
+            }
+        );
+    }
+
+    #[tracing::instrument]
+    #[tokio::test]
+    async fn wrong_credentials_rejected() {
+        let (proxy, proxy_handle) = start_mock_proxy("user", "pass").await;
+        let target: SocketAddr = "10.1.2.3:3031".parse().unwrap();
+
+        let transport =
+            Socks5TransportSocket::with_auth(&proxy, "user".to_owned(), "wrong".to_owned());
+        let err = transport.connect(target).await.unwrap_err();
+        assert!(matches!(err, NetworkingError::ProxyError(_)));
+
+        let session = proxy_handle.await.unwrap().unwrap();
+        assert_eq!(
+            session,
+            ProxySession {
+                credentials: Some(("user".to_owned(), "wrong".to_owned())),
+                target: None,
+            }
+        );
+    }
+
+    #[tracing::instrument]
+    #[tokio::test]
+    async fn unauthenticated_connection_rejected() {
+        let (proxy, proxy_handle) = start_mock_proxy("user", "pass").await;
+        let target: SocketAddr = "10.1.2.3:3031".parse().unwrap();
+
+        let transport = Socks5TransportSocket::new(&proxy);
+        let err = transport.connect(target).await.unwrap_err();
+        assert!(matches!(err, NetworkingError::ProxyError(_)));
+
+        let session = proxy_handle.await.unwrap().unwrap();
+        assert_eq!(session, ProxySession::default());
+    }
+}
@@ -16,16 +16,21 @@
 use std::{net::SocketAddr, sync::Arc, time::Duration};
 
 use futures::future::BoxFuture;
-use snowstorm::NoiseStream;
+use snowstorm::{
+    snow::resolvers::{CryptoResolver, DefaultResolver},
+    NoiseStream,
+};
 use tokio::time::timeout;
 
+use utils::ensure;
+
 use crate::{
     error::NetworkingError,
     transport::{ConnectedSocketInfo, PeerStream},
     types::ConnectionDirection,
 };
 
-use super::StreamAdapter;
+use super::traits::{StreamAdapter, StreamAdapterMaker};
 
 // How much time is allowed to spend setting up (optionally) encrypted stream.
 const DEFAULT_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
@@ -35,21 +40,68 @@ static NOISE_HANDSHAKE_PATTERN: &str = "Noise_XX_25519_ChaChaPoly_SHA256";
 static NOISE_HANDSHAKE_PARAMS: once_cell::sync::Lazy<snowstorm::NoiseParams> =
     once_cell::sync::Lazy::new(|| NOISE_HANDSHAKE_PATTERN.parse().expect("valid pattern"));
 
-pub type NoiseEncryptionAdapterMaker = fn() -> NoiseEncryptionAdapter;
+/// The static Noise keypair of the node.
+///
+/// The public key identifies the node to its peers, so it should be kept between restarts.
+#[derive(Clone)]
+pub struct NoiseStaticKeypair(Arc<snowstorm::Keypair>);
+
+impl NoiseStaticKeypair {
+    pub fn generate() -> Self {
+        let keypair = snowstorm::Builder::new(NOISE_HANDSHAKE_PARAMS.clone())
+            .generate_keypair()
+            .expect("key generation must succeed");
+        Self(Arc::new(keypair))
+    }
+
+    /// Restore the keypair from the private key, the public key is derived from it.
+    pub fn from_private_key(private_key: &[u8]) -> crate::Result<Self> {
+        let mut dh = DefaultResolver
+            .resolve_dh(&NOISE_HANDSHAKE_PARAMS.dh)
+            .expect("the handshake DH must be supported");
+        ensure!(
+            private_key.len() == dh.priv_len(),
+            NetworkingError::InvalidNoiseKey(format!(
+                "expected {} bytes, got {}",
+                dh.priv_len(),
+                private_key.len()
+            ))
+        );
+        dh.set(private_key);
+
+        Ok(Self(Arc::new(snowstorm::Keypair {
+            private: private_key.to_vec(),
+            public: dh.pubkey().to_vec(),
+        })))
+    }
+
+    pub fn private_key(&self) -> &[u8] {
+        &self.0.private
+    }
+
+    pub fn public_key(&self) -> &[u8] {
+        &self.0.public
+    }
+}
+
+impl std::fmt::Debug for NoiseStaticKeypair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NoiseStaticKeypair").finish()
+    }
+}
 
 #[derive(Clone)]
 pub struct NoiseEncryptionAdapter {
-    local_key: Arc<snowstorm::Keypair>,
+    local_key: NoiseStaticKeypair,
     handshake_timeout: Duration,
 }
 
 impl NoiseEncryptionAdapter {
     pub fn gen_new() -> Self {
-        let local_key = Arc::new(
-            snowstorm::Builder::new(NOISE_HANDSHAKE_PARAMS.clone())
-                .generate_keypair()
-                .expect("key generation must succeed"),
-        );
+        Self::from_keypair(NoiseStaticKeypair::generate())
+    }
+
+    pub fn from_keypair(local_key: NoiseStaticKeypair) -> Self {
         Self {
             local_key,
             handshake_timeout: DEFAULT_HANDSHAKE_TIMEOUT,
@@ -62,6 +114,11 @@ impl NoiseEncryptionAdapter {
             handshake_timeout,
         }
     }
+
+    /// The public part of the static keypair (32 bytes for X25519), which identifies the node
+    pub fn export_public_key(&self) -> Vec<u8> {
+        self.local_key.public_key().to_vec()
+    }
 }
 
 impl std::fmt::Debug for NoiseEncryptionAdapter {
@@ -70,6 +127,24 @@ impl std::fmt::Debug for NoiseEncryptionAdapter {
     }
 }
 
+/// Makes copies of the same adapter, so that all the connections use the same static keypair
+#[derive(Debug)]
+pub struct NoiseEncryptionAdapterMaker(NoiseEncryptionAdapter);
+
+impl NoiseEncryptionAdapterMaker {
+    pub fn new(adapter: NoiseEncryptionAdapter) -> Self {
+        Self(adapter)
+    }
+}
+
+impl StreamAdapterMaker for NoiseEncryptionAdapterMaker {
+    type Adapter = NoiseEncryptionAdapter;
+
+    fn make(&self) -> NoiseEncryptionAdapter {
+        self.0.clone()
+    }
+}
+
 /// StreamAdapter that encrypts the data going through it with noise protocol
 impl<T: PeerStream + ConnectedSocketInfo + 'static> StreamAdapter<T> for NoiseEncryptionAdapter {
     type Stream = NoiseStream<T>;
@@ -79,11 +154,11 @@ impl<T: PeerStream + ConnectedSocketInfo + 'static> StreamAdapter<T> for NoiseEn
         base: T,
         conn_dir: ConnectionDirection,
     ) -> BoxFuture<'static, crate::Result<Self::Stream>> {
-        let local_key = Arc::clone(&self.local_key);
+        let local_key = self.local_key.clone();
         let handshake_timeout = self.handshake_timeout;
         Box::pin(async move {
             let builder = snowstorm::Builder::new(NOISE_HANDSHAKE_PARAMS.clone())
-                .local_private_key(&local_key.private);
+                .local_private_key(local_key.private_key());
             let state = match conn_dir {
                 ConnectionDirection::Outbound => builder.build_initiator(),
                 ConnectionDirection::Inbound => builder.build_responder(),
@@ -103,6 +178,10 @@ impl<T: PeerStream + ConnectedSocketInfo + 'static> StreamAdapter<T> for NoiseEn
             Ok(stream)
         })
     }
+
+    fn local_static_public_key(&self) -> Option<Vec<u8>> {
+        Some(self.export_public_key())
+    }
 }
 
 impl<T: PeerStream> PeerStream for NoiseStream<T> {}
@@ -30,4 +30,24 @@ pub trait StreamAdapter<T>: Clone + Send + Sync + 'static {
         base: T,
         conn_dir: ConnectionDirection,
     ) -> BoxFuture<'static, crate::Result<Self::Stream>>;
+
+    /// The static public key used by the adapter during the handshake, if any.
+    fn local_static_public_key(&self) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+/// Makes a stream adapter for every new listener or outbound connection
+pub trait StreamAdapterMaker: Send + Sync + 'static {
+    type Adapter;
+
+    fn make(&self) -> Self::Adapter;
+}
+
+impl<S, F: Fn() -> S + Send + Sync + 'static> StreamAdapterMaker for F {
+    type Adapter = S;
+
+    fn make(&self) -> S {
+        self()
+    }
 }
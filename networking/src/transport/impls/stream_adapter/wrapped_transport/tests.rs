@@ -36,8 +36,8 @@ use crate::{
     transport::{
         impls::stream_adapter::wrapped_transport::wrapped_listener::MAX_CONCURRENT_HANDSHAKES,
         BufferedTranscoder, ChannelListener, IdentityStreamAdapter, MpscChannelTransport,
-        NoiseEncryptionAdapter, NoiseEncryptionAdapterMaker, PeerStream, TcpTransportSocket,
-        TransportListener, TransportSocket,
+        NoiseEncryptionAdapter, NoiseEncryptionAdapterMaker, NoiseStaticKeypair, PeerStream,
+        TcpTransportSocket, TransportListener, TransportSocket,
     },
 };
 
@@ -85,7 +85,7 @@ async fn test_send_recv() {
             TcpTransportSocket,
         >,
     >(WrappedTransportSocket::new(
-        NoiseEncryptionAdapter::gen_new,
+        NoiseEncryptionAdapterMaker::new(NoiseEncryptionAdapter::gen_new()),
         TcpTransportSocket::new(),
     ))
     .await;
@@ -98,7 +98,7 @@ async fn test_send_recv() {
             MpscChannelTransport,
         >,
     >(WrappedTransportSocket::new(
-        NoiseEncryptionAdapter::gen_new,
+        NoiseEncryptionAdapterMaker::new(NoiseEncryptionAdapter::gen_new()),
         MpscChannelTransport::new(),
     ))
     .await;
@@ -141,8 +141,11 @@ async fn test_send_recv() {
             >,
         >,
     >(WrappedTransportSocket::new(
-        NoiseEncryptionAdapter::gen_new,
-        WrappedTransportSocket::new(NoiseEncryptionAdapter::gen_new, TcpTransportSocket::new()),
+        NoiseEncryptionAdapterMaker::new(NoiseEncryptionAdapter::gen_new()),
+        WrappedTransportSocket::new(
+            NoiseEncryptionAdapterMaker::new(NoiseEncryptionAdapter::gen_new()),
+            TcpTransportSocket::new(),
+        ),
     ))
     .await;
 }
@@ -217,7 +220,10 @@ async fn test_bind_port_closed() {
         NoiseEncryptionAdapterMaker,
         NoiseEncryptionAdapter,
         TestTransport,
-    >::new(NoiseEncryptionAdapter::gen_new, TestTransport::new());
+    >::new(
+        NoiseEncryptionAdapterMaker::new(NoiseEncryptionAdapter::gen_new()),
+        TestTransport::new(),
+    );
     assert!(!*transport.base_transport.port_open.lock().unwrap());
 
     let address = SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0).into();
@@ -240,7 +246,10 @@ async fn send_2_reqs(#[case] seed: Seed) {
         NoiseEncryptionAdapterMaker,
         NoiseEncryptionAdapter,
         TcpTransportSocket,
-    >::new(NoiseEncryptionAdapter::gen_new, TcpTransportSocket::new());
+    >::new(
+        NoiseEncryptionAdapterMaker::new(NoiseEncryptionAdapter::gen_new()),
+        TcpTransportSocket::new(),
+    );
     let mut server = transport.bind(vec![TestTransportTcp::make_address()]).await.unwrap();
     let peer_fut = transport.connect(server.local_addresses().unwrap()[0]);
 
@@ -267,7 +276,10 @@ async fn pending_handshakes() {
         NoiseEncryptionAdapterMaker,
         NoiseEncryptionAdapter,
         TcpTransportSocket,
-    >::new(NoiseEncryptionAdapter::gen_new, TcpTransportSocket::new());
+    >::new(
+        NoiseEncryptionAdapterMaker::new(NoiseEncryptionAdapter::gen_new()),
+        TcpTransportSocket::new(),
+    );
     let mut server = transport.bind(vec![TestTransportTcp::make_address()]).await.unwrap();
     let local_addr = server.local_addresses().unwrap();
 
@@ -310,7 +322,9 @@ async fn handshake_timeout() {
         NoiseEncryptionAdapter,
         TcpTransportSocket,
     >::new(
-        || NoiseEncryptionAdapter::gen_new().with_handshake_timeout(Duration::from_millis(100)),
+        NoiseEncryptionAdapterMaker::new(
+            NoiseEncryptionAdapter::gen_new().with_handshake_timeout(Duration::from_millis(100)),
+        ),
         TcpTransportSocket::new(),
     );
     let mut server = transport.bind(vec![TestTransportTcp::make_address()]).await.unwrap();
@@ -332,3 +346,22 @@ async fn handshake_timeout() {
 
     join_handle.abort();
 }
+
+#[test]
+fn exported_public_key() {
+    let keypair = NoiseStaticKeypair::generate();
+    let adapter = NoiseEncryptionAdapter::from_keypair(keypair.clone());
+    let public_key = adapter.export_public_key();
+    assert_eq!(public_key.len(), 32);
+    assert_eq!(public_key, keypair.public_key());
+
+    let transport = WrappedTransportSocket::<
+        NoiseEncryptionAdapterMaker,
+        NoiseEncryptionAdapter,
+        TcpTransportSocket,
+    >::new(
+        NoiseEncryptionAdapterMaker::new(adapter),
+        TcpTransportSocket::new(),
+    );
+    assert_eq!(transport.local_static_public_key(), Some(public_key));
+}
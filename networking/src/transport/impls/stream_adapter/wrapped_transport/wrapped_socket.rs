@@ -21,7 +21,10 @@ use futures::future::BoxFuture;
 use super::wrapped_listener::AdaptedListener;
 
 use crate::{
-    transport::{impls::stream_adapter::traits::StreamAdapter, TransportSocket},
+    transport::{
+        impls::stream_adapter::traits::{StreamAdapter, StreamAdapterMaker},
+        TransportSocket,
+    },
     types::ConnectionDirection,
     Result,
 };
@@ -31,12 +34,12 @@ use crate::{
 /// be equivalent to the tcp transport layer with nothing done to it.
 /// More layers can be added on top of this, with this struct, where we add encryption on top.
 #[derive(Debug)]
-pub struct WrappedTransportSocket<M: Fn() -> S + Sync + Send + 'static, S, T> {
+pub struct WrappedTransportSocket<M: StreamAdapterMaker<Adapter = S>, S, T> {
     pub stream_adapter_maker: M,
     pub base_transport: T,
 }
 
-impl<M: Fn() -> S + Sync + Send + 'static, S, T> WrappedTransportSocket<M, S, T> {
+impl<M: StreamAdapterMaker<Adapter = S>, S, T> WrappedTransportSocket<M, S, T> {
     pub fn new(stream_adapter_maker: M, base_transport: T) -> Self {
         Self {
             stream_adapter_maker,
@@ -46,25 +49,29 @@ impl<M: Fn() -> S + Sync + Send + 'static, S, T> WrappedTransportSocket<M, S, T>
 }
 
 #[async_trait]
-impl<M: Fn() -> S + Sync + Send + 'static, S: StreamAdapter<T::Stream>, T: TransportSocket>
+impl<M: StreamAdapterMaker<Adapter = S>, S: StreamAdapter<T::Stream>, T: TransportSocket>
     TransportSocket for WrappedTransportSocket<M, S, T>
 {
     type Listener = AdaptedListener<S, T>;
     type Stream = S::Stream;
 
     async fn bind(&self, addresses: Vec<SocketAddr>) -> Result<Self::Listener> {
-        let stream_adapter = (self.stream_adapter_maker)();
+        let stream_adapter = self.stream_adapter_maker.make();
         let listener = self.base_transport.bind(addresses).await?;
         Ok(AdaptedListener::new(stream_adapter, listener))
     }
 
     fn connect(&self, address: SocketAddr) -> BoxFuture<'static, Result<Self::Stream>> {
         let base = self.base_transport.connect(address);
-        let stream_adapter = (self.stream_adapter_maker)();
+        let stream_adapter = self.stream_adapter_maker.make();
         Box::pin(async move {
             let base = base.await?;
             let stream = stream_adapter.handshake(base, ConnectionDirection::Outbound).await?;
             Ok(stream)
         })
     }
+
+    fn local_static_public_key(&self) -> Option<Vec<u8>> {
+        self.stream_adapter_maker.make().local_static_public_key()
+    }
 }
@@ -155,6 +155,7 @@ mod tests {
     use test_utils::random::{gen_random_bytes, Seed};
 
     use crate::{
+        error::NetworkingError,
         test_helpers::{TestTransportMaker, TestTransportTcp},
         transport::BufferedTranscoder,
     };
@@ -213,4 +214,102 @@ mod tests {
         assert_eq!(server_stream.recv().await.unwrap(), message_1);
         assert_eq!(server_stream.recv().await.unwrap(), message_2);
     }
+
+    #[tracing::instrument]
+    #[tokio::test]
+    async fn connect_refused() {
+        let transport = TcpTransportSocket::new();
+        let server = transport.bind(vec![TestTransportTcp::make_address()]).await.unwrap();
+        let address = server.local_addresses().unwrap()[0];
+        drop(server);
+
+        let err = transport.connect(address).await.unwrap_err();
+        assert_eq!(
+            err,
+            NetworkingError::IoError(std::io::ErrorKind::ConnectionRefused)
+        );
+    }
+
+    #[tracing::instrument]
+    #[tokio::test]
+    async fn bind_address_in_use() {
+        let transport = TcpTransportSocket::new();
+        let server = transport.bind(vec![TestTransportTcp::make_address()]).await.unwrap();
+        let address = server.local_addresses().unwrap()[0];
+
+        let err = transport.bind(vec![address]).await.err().unwrap();
+        assert_eq!(err, NetworkingError::IoError(std::io::ErrorKind::AddrInUse));
+    }
+
+    #[tracing::instrument]
+    #[tokio::test]
+    async fn bind_address_not_available() {
+        let transport = TcpTransportSocket::new();
+        // An address from TEST-NET-1, which can't be assigned to any local interface
+        let address = "192.0.2.1:0".parse().unwrap();
+
+        let err = transport.bind(vec![address]).await.err().unwrap();
+        assert_eq!(
+            err,
+            NetworkingError::IoError(std::io::ErrorKind::AddrNotAvailable)
+        );
+    }
+
+    #[tracing::instrument]
+    #[tokio::test]
+    async fn recv_connection_reset() {
+        let transport = TcpTransportSocket::new();
+        let mut server = transport.bind(vec![TestTransportTcp::make_address()]).await.unwrap();
+        let peer_fut = transport.connect(server.local_addresses().unwrap()[0]);
+
+        let (server_res, peer_res) = tokio::join!(server.accept(), peer_fut);
+        let server_stream = server_res.unwrap().0;
+        let peer_stream = peer_res.unwrap();
+
+        // Closing the socket with zero linger time resets the connection
+        server_stream.set_linger(Some(std::time::Duration::ZERO)).unwrap();
+        drop(server_stream);
+
+        let mut peer_stream = BufferedTranscoder::<_, Vec<u8>>::new(peer_stream, None);
+        assert_eq!(
+            peer_stream.recv().await.unwrap_err(),
+            NetworkingError::IoError(std::io::ErrorKind::ConnectionReset)
+        );
+    }
+
+    #[tracing::instrument]
+    #[tokio::test]
+    async fn recv_connection_closed() {
+        let transport = TcpTransportSocket::new();
+        let mut server = transport.bind(vec![TestTransportTcp::make_address()]).await.unwrap();
+        let peer_fut = transport.connect(server.local_addresses().unwrap()[0]);
+
+        let (server_res, peer_res) = tokio::join!(server.accept(), peer_fut);
+        drop(server_res.unwrap().0);
+        let peer_stream = peer_res.unwrap();
+
+        let mut peer_stream = BufferedTranscoder::<_, Vec<u8>>::new(peer_stream, None);
+        assert_eq!(
+            peer_stream.recv().await.unwrap_err(),
+            NetworkingError::IoError(std::io::ErrorKind::UnexpectedEof)
+        );
+    }
+
+    // The kinds of the transport errors that can't be reliably reproduced with real sockets
+    // are still passed through unchanged
+    #[rstest::rstest]
+    #[case(std::io::ErrorKind::ConnectionRefused)]
+    #[case(std::io::ErrorKind::ConnectionReset)]
+    #[case(std::io::ErrorKind::ConnectionAborted)]
+    #[case(std::io::ErrorKind::NotConnected)]
+    #[case(std::io::ErrorKind::AddrInUse)]
+    #[case(std::io::ErrorKind::AddrNotAvailable)]
+    #[case(std::io::ErrorKind::BrokenPipe)]
+    #[case(std::io::ErrorKind::TimedOut)]
+    #[case(std::io::ErrorKind::UnexpectedEof)]
+    #[case(std::io::ErrorKind::PermissionDenied)]
+    fn io_error_kind_preserved(#[case] kind: std::io::ErrorKind) {
+        let err: NetworkingError = std::io::Error::new(kind, "test error").into();
+        assert_eq!(err, NetworkingError::IoError(kind));
+    }
 }
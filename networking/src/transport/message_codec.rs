@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{marker::PhantomData, mem::size_of};
+use std::{collections::BTreeMap, marker::PhantomData, mem::size_of};
 
 use bytes::{Buf, BytesMut};
 use tokio_util::codec::{Decoder, Encoder};
@@ -22,14 +22,25 @@ use crate::{
     error::{MessageCodecError, NetworkingError},
     Result,
 };
-use serialization::{DecodeAll, Encode};
+use serialization::{strict::DecodeStrict, Encode};
 
 /// The header that precedes each message and specifies the size of the message, not including
 /// the header itself.
 type MsgLenHeader = u32;
 
+/// Maximum encoded sizes of specific message types.
+///
+/// The type of a message is identified by its tag, i.e. the first byte of the encoded message,
+/// which is the variant index if the message is an enum. Messages whose tags are not present
+/// are only subject to the general size limit.
+pub type MessageTagSizeLimits = BTreeMap<u8, usize>;
+
 pub struct MessageCodec<Msg> {
     max_encoded_message_size: Option<usize>,
+    /// The limits that are checked when decoding incoming messages.
+    recv_tag_size_limits: MessageTagSizeLimits,
+    /// The limits that are checked when encoding outgoing messages.
+    send_tag_size_limits: MessageTagSizeLimits,
     _phantom_msg: PhantomData<Msg>,
 }
 
@@ -37,10 +48,20 @@ impl<Msg> MessageCodec<Msg> {
     pub fn new(max_encoded_message_size: Option<usize>) -> Self {
         Self {
             max_encoded_message_size,
+            recv_tag_size_limits: MessageTagSizeLimits::new(),
+            send_tag_size_limits: MessageTagSizeLimits::new(),
             _phantom_msg: PhantomData::<Msg>,
         }
     }
 
+    pub fn set_recv_tag_size_limits(&mut self, limits: MessageTagSizeLimits) {
+        self.recv_tag_size_limits = limits;
+    }
+
+    pub fn set_send_tag_size_limits(&mut self, limits: MessageTagSizeLimits) {
+        self.send_tag_size_limits = limits;
+    }
+
     fn check_encoded_message_size(&self, size: usize) -> Result<()> {
         if let Some(max_message_size) = self.max_encoded_message_size {
             if size > max_message_size {
@@ -54,9 +75,28 @@ impl<Msg> MessageCodec<Msg> {
 
         Ok(())
     }
+
+    fn check_encoded_message_tag_size(
+        tag_size_limits: &MessageTagSizeLimits,
+        tag: u8,
+        size: usize,
+    ) -> Result<()> {
+        if let Some(max_size) = tag_size_limits.get(&tag) {
+            if size > *max_size {
+                return Err(MessageCodecError::MessageTypeTooLarge {
+                    tag,
+                    actual_size: size,
+                    max_size: *max_size,
+                }
+                .into());
+            }
+        }
+
+        Ok(())
+    }
 }
 
-impl<Msg: DecodeAll> Decoder for MessageCodec<Msg> {
+impl<Msg: DecodeStrict> Decoder for MessageCodec<Msg> {
     type Item = Msg;
     type Error = NetworkingError;
 
@@ -72,6 +112,12 @@ impl<Msg: DecodeAll> Decoder for MessageCodec<Msg> {
 
         self.check_encoded_message_size(length)?;
 
+        // The tag is checked as soon as it's available, so that an oversized message is rejected
+        // before its body has been received.
+        if let Some(tag) = remaining_bytes.first().filter(|_| length > 0) {
+            Self::check_encoded_message_tag_size(&self.recv_tag_size_limits, *tag, length)?;
+        }
+
         if remaining_bytes.len() < length {
             src.reserve(size_of::<MsgLenHeader>() + length - src.len());
             return Ok(None);
@@ -79,7 +125,9 @@ impl<Msg: DecodeAll> Decoder for MessageCodec<Msg> {
 
         let (body, _extra_bytes) = remaining_bytes.split_at_mut(length);
 
-        let decode_res = Msg::decode_all(&mut &body[..]);
+        // Only the canonical encodings are accepted, so that the ids of the received objects,
+        // which are the hashes of their encodings, are the same as the ones computed locally.
+        let decode_res = Msg::decode_strict(body);
 
         src.advance(size_of::<MsgLenHeader>() + length);
 
@@ -97,6 +145,9 @@ impl<Msg: Encode> Encoder<Msg> for MessageCodec<Msg> {
         let encoded = msg.encode();
 
         self.check_encoded_message_size(encoded.len())?;
+        if let Some(tag) = encoded.first() {
+            Self::check_encoded_message_tag_size(&self.send_tag_size_limits, *tag, encoded.len())?;
+        }
 
         let len_slice = u32::to_le_bytes(encoded.len() as u32);
 
@@ -123,6 +174,39 @@ mod tests {
         data: u64,
     }
 
+    impl DecodeStrict for TestMessage {}
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    enum TestEnumMessage {
+        #[codec(index = 0)]
+        Small(u64),
+        #[codec(index = 1)]
+        Large(Vec<u8>),
+    }
+
+    impl DecodeStrict for TestEnumMessage {}
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    struct TestMapMessage {
+        data: BTreeMap<u8, u8>,
+    }
+
+    impl DecodeStrict for TestMapMessage {}
+
+    const LARGE_MSG_TAG: u8 = 1;
+
+    fn make_large_message(rng: &mut impl Rng) -> (TestEnumMessage, usize) {
+        let message = TestEnumMessage::Large(test_utils::random::gen_random_bytes(rng, 10, 1000));
+        let encoded_size = message.encoded_size();
+        (message, encoded_size)
+    }
+
+    fn encode_unchecked(message: &TestEnumMessage) -> BytesMut {
+        let mut buf = BytesMut::new();
+        MessageCodec::new(None).encode(message.clone(), &mut buf).unwrap();
+        buf
+    }
+
     #[tracing::instrument(skip(seed))]
     #[rstest::rstest]
     #[trace]
@@ -198,4 +282,154 @@ mod tests {
         let decoded = encoder.decode(&mut buf).unwrap().unwrap();
         assert_eq!(message, decoded);
     }
+
+    // A message of a size-limited type can be sent and received if its size is exactly at the limit;
+    // messages of other types are not affected by the limit.
+    #[tracing::instrument(skip(seed))]
+    #[rstest::rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn tag_size_limit_roundtrip(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let (large_message, large_message_size) = make_large_message(&mut rng);
+        let small_message = TestEnumMessage::Small(rng.gen());
+
+        let mut codec = MessageCodec::new(Some(2048));
+        let limits = MessageTagSizeLimits::from([(LARGE_MSG_TAG, large_message_size)]);
+        codec.set_send_tag_size_limits(limits.clone());
+        codec.set_recv_tag_size_limits(limits);
+
+        let mut buf = BytesMut::new();
+        codec.encode(large_message.clone(), &mut buf).unwrap();
+        codec.encode(small_message.clone(), &mut buf).unwrap();
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(large_message));
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(small_message));
+        assert!(buf.is_empty());
+    }
+
+    #[tracing::instrument(skip(seed))]
+    #[rstest::rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn tag_size_limit_encode(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let (message, message_size) = make_large_message(&mut rng);
+
+        let mut encoder = MessageCodec::new(Some(2048));
+        encoder.set_send_tag_size_limits(MessageTagSizeLimits::from([(
+            LARGE_MSG_TAG,
+            message_size - 1,
+        )]));
+        // The receiving limits don't affect encoding.
+        encoder.set_recv_tag_size_limits(MessageTagSizeLimits::from([(LARGE_MSG_TAG, 0)]));
+
+        let mut buf = BytesMut::new();
+        let result = encoder.encode(message.clone(), &mut buf);
+        assert_eq!(
+            result,
+            Err(NetworkingError::MessageCodecError(
+                MessageCodecError::MessageTypeTooLarge {
+                    tag: LARGE_MSG_TAG,
+                    actual_size: message_size,
+                    max_size: message_size - 1,
+                }
+            ))
+        );
+        assert!(buf.is_empty());
+
+        encoder
+            .set_send_tag_size_limits(MessageTagSizeLimits::from([(LARGE_MSG_TAG, message_size)]));
+        encoder.encode(message, &mut buf).unwrap();
+    }
+
+    #[tracing::instrument(skip(seed))]
+    #[rstest::rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn tag_size_limit_decode(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let (message, message_size) = make_large_message(&mut rng);
+        let mut encoded = encode_unchecked(&message);
+
+        let mut decoder = MessageCodec::<TestEnumMessage>::new(Some(2048));
+        decoder.set_recv_tag_size_limits(MessageTagSizeLimits::from([(
+            LARGE_MSG_TAG,
+            message_size - 1,
+        )]));
+        let result = decoder.decode(&mut encoded);
+        assert_eq!(
+            result,
+            Err(NetworkingError::MessageCodecError(
+                MessageCodecError::MessageTypeTooLarge {
+                    tag: LARGE_MSG_TAG,
+                    actual_size: message_size,
+                    max_size: message_size - 1,
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn non_canonical_message() {
+        let message = TestMapMessage {
+            data: BTreeMap::from([(1, 10), (2, 20)]),
+        };
+        let mut codec = MessageCodec::new(None);
+        let mut buf = BytesMut::new();
+        codec.encode(message.clone(), &mut buf).unwrap();
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(message));
+
+        // The same map with the entries in a different order
+        let encoded = vec![(2u8, 20u8), (1, 10)].encode();
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(&u32::to_le_bytes(encoded.len() as u32));
+        buf.extend_from_slice(&encoded);
+        assert!(matches!(
+            codec.decode(&mut buf),
+            Err(NetworkingError::MessageCodecError(
+                MessageCodecError::InvalidEncodedData(_)
+            ))
+        ));
+        // The message is consumed
+        assert!(buf.is_empty());
+    }
+
+    // The size of a message is checked as soon as its tag has been received.
+    #[tracing::instrument(skip(seed))]
+    #[rstest::rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn tag_size_limit_decode_partial_message(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let (message, message_size) = make_large_message(&mut rng);
+        let encoded = encode_unchecked(&message);
+
+        let mut decoder = MessageCodec::<TestEnumMessage>::new(Some(2048));
+        decoder.set_recv_tag_size_limits(MessageTagSizeLimits::from([(
+            LARGE_MSG_TAG,
+            message_size - 1,
+        )]));
+
+        // Only the length header is available, so nothing can be checked yet.
+        let mut partial = BytesMut::from(&encoded[..size_of::<MsgLenHeader>()]);
+        assert_eq!(decoder.decode(&mut partial), Ok(None));
+
+        let received_len = rng.gen_range(size_of::<MsgLenHeader>() + 1..encoded.len());
+        let mut partial = BytesMut::from(&encoded[..received_len]);
+        let result = decoder.decode(&mut partial);
+        assert_eq!(
+            result,
+            Err(NetworkingError::MessageCodecError(
+                MessageCodecError::MessageTypeTooLarge {
+                    tag: LARGE_MSG_TAG,
+                    actual_size: message_size,
+                    max_size: message_size - 1,
+                }
+            ))
+        );
+    }
 }
@@ -0,0 +1,44 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::{Arc, OnceLock};
+
+use utils::metrics::{registry, Counter};
+
+pub struct TransportMetrics {
+    pub bytes_sent: Arc<Counter>,
+    pub bytes_received: Arc<Counter>,
+}
+
+pub fn metrics() -> &'static TransportMetrics {
+    static METRICS: OnceLock<TransportMetrics> = OnceLock::new();
+    METRICS.get_or_init(|| TransportMetrics {
+        bytes_sent: registry().counter(
+            "p2p_bytes_sent_total",
+            "The number of bytes sent to the peers",
+            &[],
+        ),
+        bytes_received: registry().counter(
+            "p2p_bytes_received_total",
+            "The number of bytes received from the peers",
+            &[],
+        ),
+    })
+}
+
+/// Register the metrics, so that they are exported even before they are first updated
+pub fn register() {
+    let _ = metrics();
+}
@@ -13,20 +13,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod metrics;
+
 mod buffered_transcoder;
 mod impls;
 mod message_codec;
 mod traits;
 
-use impls::{channel, socks5, stream_adapter, tcp};
+use impls::{channel, dual, socks5, stream_adapter, tcp};
 
 pub use self::{
     buffered_transcoder::BufferedTranscoder,
     channel::{ChannelListener, ChannelStream, MpscChannelTransport, MpscChannelTransportError},
+    dual::{DualTransportListener, DualTransportSocket, DualTransportStream},
+    message_codec::MessageTagSizeLimits,
     socks5::Socks5TransportSocket,
     stream_adapter::{
         identity::IdentityStreamAdapter,
-        noise::{NoiseEncryptionAdapter, NoiseEncryptionAdapterMaker},
+        noise::{NoiseEncryptionAdapter, NoiseEncryptionAdapterMaker, NoiseStaticKeypair},
         wrapped_transport::wrapped_socket::WrappedTransportSocket,
     },
     tcp::TcpTransportSocket,
@@ -35,6 +39,9 @@ pub use self::{
 
 pub type NoiseTcpTransport =
     WrappedTransportSocket<NoiseEncryptionAdapterMaker, NoiseEncryptionAdapter, TcpTransportSocket>;
+/// Noise-encrypted transport that also accepts unencrypted connections on some addresses
+pub type NoiseTcpWithPlaintextTransport =
+    DualTransportSocket<NoiseTcpTransport, TcpTransportSocket>;
 pub type NoiseSocks5Transport = WrappedTransportSocket<
     NoiseEncryptionAdapterMaker,
     NoiseEncryptionAdapter,
@@ -39,6 +39,11 @@ pub trait TransportSocket: Send + Sync + 'static {
 
     /// Returns a future that opens a connection to the given address.
     fn connect(&self, address: SocketAddr) -> BoxFuture<'static, Result<Self::Stream>>;
+
+    /// The static public key that identifies this node to its peers, if the transport has one.
+    fn local_static_public_key(&self) -> Option<Vec<u8>> {
+        None
+    }
 }
 
 /// Additional information for a connected socket.
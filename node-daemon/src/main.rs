@@ -27,6 +27,9 @@ pub async fn run() -> anyhow::Result<()> {
                 "Data directory is now clean. Please restart the node without `--clean-data` flag"
             );
         }
+        node_lib::NodeSetupResult::ConfigDumped(config) => {
+            print!("{config}");
+        }
     };
 
     Ok(())
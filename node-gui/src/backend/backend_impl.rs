@@ -33,11 +33,11 @@ use tokio::{
 use wallet::{account::transaction_list::TransactionList, wallet::Error, WalletError};
 use wallet_cli_commands::{
     get_repl_command, parse_input, CommandHandler, ConsoleCommand, ManageableWalletCommand,
-    WalletCommand,
+    ReplInput, WalletCommand,
 };
 use wallet_controller::{
-    make_cold_wallet_rpc_client, types::Balances, ControllerConfig, NodeInterface, UtxoState,
-    WalletHandlesClient,
+    make_cold_wallet_rpc_client, types::Balances, ControllerConfig, NodeInterface,
+    UtxoSelectionStrategy, UtxoState, WalletHandlesClient,
 };
 use wallet_rpc_client::handles_client::WalletRpcHandlesClient;
 use wallet_rpc_lib::{EventStream, WalletRpc, WalletService};
@@ -384,6 +384,9 @@ impl Backend {
             ControllerConfig {
                 in_top_x_mb: IN_TOP_X_MB,
                 broadcast_to_mempool: true,
+                utxo_selection_strategy: UtxoSelectionStrategy::default(),
+                spend_pending_utxos: false,
+                idempotent_request: None,
             },
             WalletRpcHandlesClient::new(wallet_rpc.clone(), None),
         )
@@ -542,6 +545,9 @@ impl Backend {
             ControllerConfig {
                 in_top_x_mb: IN_TOP_X_MB,
                 broadcast_to_mempool: true,
+                utxo_selection_strategy: UtxoSelectionStrategy::default(),
+                spend_pending_utxos: false,
+                idempotent_request: None,
             },
             WalletRpcHandlesClient::new(wallet_rpc.clone(), None),
         )
@@ -643,12 +649,12 @@ impl Backend {
 
         let (index, address) = match &mut wallet.controller {
             GuiHotColdController::Cold(w, _) => w
-                .issue_address(account_id.account_index())
+                .issue_address(account_id.account_index(), false)
                 .await
                 .map_err(|e| BackendError::WalletError(e.to_string()))
                 .map(|info| (info.index, info.address))?,
             GuiHotColdController::Hot(w, _) => w
-                .issue_address(account_id.account_index())
+                .issue_address(account_id.account_index(), false)
                 .await
                 .map_err(|e| BackendError::WalletError(e.to_string()))
                 .map(|info| (info.index, info.address))?,
@@ -716,10 +722,14 @@ impl Backend {
                     in_top_x_mb: IN_TOP_X_MB,
                     // don't broadcast_to_mempool before confirmation dialog
                     broadcast_to_mempool: false,
+                    utxo_selection_strategy: UtxoSelectionStrategy::default(),
+                    spend_pending_utxos: false,
+                    idempotent_request: None,
                 },
             )
             .await
-            .map_err(|e| BackendError::WalletError(e.to_string()))?;
+            .map_err(|e| BackendError::WalletError(e.to_string()))?
+            .tx;
 
         Ok(TransactionInfo { wallet_id, tx })
     }
@@ -764,6 +774,9 @@ impl Backend {
                     in_top_x_mb: IN_TOP_X_MB,
                     // don't broadcast_to_mempool before confirmation dialog
                     broadcast_to_mempool: false,
+                    utxo_selection_strategy: UtxoSelectionStrategy::default(),
+                    spend_pending_utxos: false,
+                    idempotent_request: None,
                 },
             )
             .await
@@ -793,6 +806,9 @@ impl Backend {
                     in_top_x_mb: IN_TOP_X_MB,
                     // don't broadcast_to_mempool before confirmation dialog
                     broadcast_to_mempool: false,
+                    utxo_selection_strategy: UtxoSelectionStrategy::default(),
+                    spend_pending_utxos: false,
+                    idempotent_request: None,
                 },
             )
             .await
@@ -822,6 +838,9 @@ impl Backend {
                     in_top_x_mb: IN_TOP_X_MB,
                     // don't broadcast_to_mempool before confirmation dialog
                     broadcast_to_mempool: false,
+                    utxo_selection_strategy: UtxoSelectionStrategy::default(),
+                    spend_pending_utxos: false,
+                    idempotent_request: None,
                 },
             )
             .await
@@ -858,6 +877,9 @@ impl Backend {
                     in_top_x_mb: IN_TOP_X_MB,
                     // don't broadcast_to_mempool before confirmation dialog
                     broadcast_to_mempool: false,
+                    utxo_selection_strategy: UtxoSelectionStrategy::default(),
+                    spend_pending_utxos: false,
+                    idempotent_request: None,
                 },
             )
             .await
@@ -898,6 +920,9 @@ impl Backend {
                     in_top_x_mb: IN_TOP_X_MB,
                     // don't broadcast_to_mempool before confirmation dialog
                     broadcast_to_mempool: false,
+                    utxo_selection_strategy: UtxoSelectionStrategy::default(),
+                    spend_pending_utxos: false,
+                    idempotent_request: None,
                 },
             )
             .await
@@ -1055,10 +1080,14 @@ impl Backend {
         account_id: AccountId,
         command: String,
     ) -> Result<ConsoleCommand, BackendError> {
-        let repl_command = get_repl_command(self.controller.is_cold(), false);
-        let command = parse_input::<ColdWalletClient>(&command, &repl_command)
+        let repl_command = get_repl_command(self.controller.is_cold(), false, &[]);
+        let command = match parse_input::<ColdWalletClient>(&command, &repl_command)
             .map_err(|e| BackendError::InvalidConsoleCommand(e.to_string()))?
-            .ok_or(BackendError::EmptyConsoleCommand)?;
+            .ok_or(BackendError::EmptyConsoleCommand)?
+        {
+            ReplInput::Command(command) => command,
+            ReplInput::Help(text) => return Ok(ConsoleCommand::Print(text)),
+        };
 
         let wallet = self
             .wallets
@@ -116,6 +116,12 @@ pub async fn node_initialize(
                 "Data directory is now clean. Please restart the node without `--clean-data` flag"
             );
                 }
+                node_lib::NodeSetupResult::ConfigDumped(config) => {
+                    print!("{config}");
+                    anyhow::bail!(
+                        "The config is printed. Please restart the node without `--dump-config` flag"
+                    );
+                }
             };
 
             let controller = node.controller().clone();
@@ -164,6 +170,7 @@ pub async fn node_initialize(
                 median_time: chain_config.genesis_block().timestamp(),
                 best_block_timestamp: chain_config.genesis_block().timestamp(),
                 is_initial_block_download: false,
+                update_recommended: false,
             };
 
             let manager_join_handle = tokio::spawn(async move {});
@@ -13,7 +13,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use common::chain::OutPointSourceId;
+use common::{
+    chain::{OutPointSourceId, Transaction},
+    primitives::Id,
+};
 use crypto::key::hdkd::u31::U31;
 use tokio::sync::mpsc::UnboundedSender;
 use wallet::wallet_events::WalletEvents;
@@ -51,4 +54,13 @@ impl WalletEvents for GuiWalletEvents {
     fn del_transaction(&self, _id: U31, _source: OutPointSourceId) {
         self.notify();
     }
+
+    fn tx_confirmation_threshold_reached(
+        &self,
+        _id: U31,
+        _tx_id: Id<Transaction>,
+        _confirmations: u64,
+    ) {
+        self.notify();
+    }
 }
@@ -379,6 +379,16 @@ impl MainWindow {
                     self.node_state.connected_peers.remove(&peer_id);
                     Command::none()
                 }
+                BackendEvent::P2p(P2pEvent::PossibleFork {
+                    local_tip,
+                    peer_tips,
+                }) => {
+                    let peer_count = peer_tips.values().sum::<u32>();
+                    self.show_error(format!(
+                        "Possible fork detected: {peer_count} peers report a tip different from {local_tip}"
+                    ));
+                    Command::none()
+                }
 
                 BackendEvent::OpenWallet(Ok(wallet_info))
                 | BackendEvent::ImportWallet(Ok(wallet_info)) => {
@@ -602,11 +612,16 @@ impl MainWindow {
                 }
                 BackendEvent::ConsoleResponse(wallet_id, _account_id, Ok(command)) => match command
                 {
+                    // The console of the GUI is not a terminal, so QR codes are not shown
                     ConsoleCommand::SetStatus {
                         status: _,
                         print_message: out,
                     }
-                    | ConsoleCommand::Print(out) => self
+                    | ConsoleCommand::Print(out)
+                    | ConsoleCommand::PrintWithQrCode {
+                        text: out,
+                        qr_code_data: _,
+                    } => self
                         .main_widget
                         .update(
                             MainWidgetMessage::TabsMessage(TabsMessage::WalletMessage(
@@ -619,6 +634,7 @@ impl MainWindow {
                     ConsoleCommand::ClearScreen
                     | ConsoleCommand::ClearHistory
                     | ConsoleCommand::PrintHistory
+                    | ConsoleCommand::Watch
                     | ConsoleCommand::Exit => self
                         .main_widget
                         .update(
@@ -17,7 +17,31 @@ use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
-use chainstate::ChainstateConfig;
+use chainstate::{ChainstateConfig, StorageSyncPolicy};
+
+/// When the changes committed to the chainstate storage are made durable.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum StorageSyncPolicyConfigFile {
+    /// Every commit is durable.
+    Always,
+    /// The storage is made durable every `interval_secs` seconds and on shutdown.
+    Periodic { interval_secs: u64 },
+    /// The storage is made durable on shutdown.
+    OnShutdown,
+}
+
+impl From<StorageSyncPolicyConfigFile> for StorageSyncPolicy {
+    fn from(policy: StorageSyncPolicyConfigFile) -> Self {
+        match policy {
+            StorageSyncPolicyConfigFile::Always => StorageSyncPolicy::Always,
+            StorageSyncPolicyConfigFile::Periodic { interval_secs } => {
+                StorageSyncPolicy::Periodic(Duration::from_secs(interval_secs))
+            }
+            StorageSyncPolicyConfigFile::OnShutdown => StorageSyncPolicy::OnShutdown,
+        }
+    }
+}
 
 /// The chainstate subsystem configuration.
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -36,6 +60,22 @@ pub struct ChainstateConfigFile {
     pub max_tip_age: Option<u64>,
     /// If true, additional computationally-expensive consistency checks will be performed by the chainstate.
     pub enable_heavy_checks: Option<bool>,
+    /// The maximum number of blocks that can be included in a single block size histogram query.
+    pub max_block_size_histogram_range: Option<usize>,
+    /// If true, blocks are written to the storage in batches during the initial block download.
+    pub ibd_write_batching: Option<bool>,
+    /// The maximum number of blocks in a write batch.
+    pub max_write_batch_blocks: Option<usize>,
+    /// The maximum total size of the blocks in a write batch, in bytes.
+    pub max_write_batch_size: Option<usize>,
+    /// When the changes committed to the storage are made durable.
+    ///
+    /// Relaxing the policy speeds up the initial block download, but the blocks processed since
+    /// the storage was last made durable may be lost on a crash.
+    pub storage_sync_policy: Option<StorageSyncPolicyConfigFile>,
+    /// The number of blocks before the activation of a network upgrade unknown to this node
+    /// at which the node starts warning that it should be updated.
+    pub upgrade_warning_window: Option<u64>,
 }
 
 impl From<ChainstateConfigFile> for ChainstateConfig {
@@ -46,6 +86,12 @@ impl From<ChainstateConfigFile> for ChainstateConfig {
             min_max_bootstrap_import_buffer_sizes,
             max_tip_age,
             enable_heavy_checks,
+            max_block_size_histogram_range,
+            ibd_write_batching,
+            max_write_batch_blocks,
+            max_write_batch_size,
+            storage_sync_policy,
+            upgrade_warning_window,
         } = config_file;
 
         ChainstateConfig {
@@ -54,6 +100,12 @@ impl From<ChainstateConfigFile> for ChainstateConfig {
             min_max_bootstrap_import_buffer_sizes: min_max_bootstrap_import_buffer_sizes.into(),
             max_tip_age: max_tip_age.map(Duration::from_secs).into(),
             enable_heavy_checks,
+            max_block_size_histogram_range: max_block_size_histogram_range.into(),
+            ibd_write_batching: ibd_write_batching.into(),
+            max_write_batch_blocks: max_write_batch_blocks.into(),
+            max_write_batch_size: max_write_batch_size.into(),
+            storage_sync_policy: storage_sync_policy.map(Into::into).unwrap_or_default(),
+            upgrade_warning_window: upgrade_warning_window.into(),
         }
     }
 }
@@ -15,8 +15,10 @@
 
 //! Chainstate launcher configuration
 
+use std::collections::BTreeMap;
+
 use chainstate_launcher::{ChainstateLauncherConfig, StorageBackendConfig};
-use serde::{Deserialize, Serialize};
+use serde::{de::IgnoredAny, ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
 
 use super::chainstate::ChainstateConfigFile;
 
@@ -51,12 +53,6 @@ impl std::str::FromStr for StorageBackendConfigFile {
 /// Storage configuration
 #[must_use]
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
-// TODO: without `#[serde(deny_unknown_fields)]` it's very easy to miss mistakes in the config file,
-// especially due to the hyphen/underscore discrepancy between command line options and config
-// file keys. E.g. for the config file key `max_orphan_blocks` the corresponding command line option
-// is `--max-orphan-blocks`.
-// But `serde(deny_unknown_fields)` and `serde(flatten)` don't work well together. Perhaps it's
-// better to get rid of `flatten` then?
 pub struct ChainstateLauncherConfigFile {
     /// Storage backend to use
     #[serde(default)]
@@ -65,6 +61,29 @@ pub struct ChainstateLauncherConfigFile {
     /// Chainstate configuration
     #[serde(flatten)]
     pub chainstate_config: ChainstateConfigFile,
+
+    // `serde(deny_unknown_fields)` doesn't work together with `serde(flatten)`, so the keys
+    // that are left over after deserializing the chainstate config are rejected here instead.
+    // Otherwise it's very easy to miss mistakes in the config file, e.g. due to the hyphen/underscore
+    // discrepancy between the command line options and the config file keys.
+    #[serde(
+        flatten,
+        deserialize_with = "deny_unknown_keys",
+        serialize_with = "serialize_nothing"
+    )]
+    pub(super) unknown_keys: (),
+}
+
+fn deny_unknown_keys<'de, D: Deserializer<'de>>(deserializer: D) -> Result<(), D::Error> {
+    let keys = BTreeMap::<String, IgnoredAny>::deserialize(deserializer)?;
+    match keys.into_keys().next() {
+        Some(key) => Err(serde::de::Error::custom(format!("unknown field `{key}`"))),
+        None => Ok(()),
+    }
+}
+
+fn serialize_nothing<S: Serializer>(_: &(), serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_map(Some(0))?.end()
 }
 
 impl ChainstateLauncherConfigFile {
@@ -78,6 +97,7 @@ impl From<ChainstateLauncherConfigFile> for ChainstateLauncherConfig {
         let ChainstateLauncherConfigFile {
             storage_backend,
             chainstate_config,
+            unknown_keys: (),
         } = config_file;
 
         ChainstateLauncherConfig {
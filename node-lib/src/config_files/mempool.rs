@@ -27,6 +27,14 @@ use crate::RunOptions;
 pub struct MempoolConfigFile {
     /// Minimum transaction relay fee rate (in atoms per 1000 bytes).
     pub min_tx_relay_fee_rate: Option<u64>,
+    /// Maximum number of unconfirmed ancestors of a transaction, including itself.
+    pub max_ancestor_count: Option<usize>,
+    /// Maximum total size (in bytes) of a transaction and its unconfirmed ancestors.
+    pub max_ancestor_size: Option<usize>,
+    /// Maximum number of unconfirmed descendants of a transaction, including itself.
+    pub max_descendant_count: Option<usize>,
+    /// Maximum total size (in bytes) of a transaction and its unconfirmed descendants.
+    pub max_descendant_size: Option<usize>,
 }
 
 impl MempoolConfigFile {
@@ -37,12 +45,24 @@ impl MempoolConfigFile {
     pub fn with_run_options(config: MempoolConfigFile, options: &RunOptions) -> MempoolConfigFile {
         let MempoolConfigFile {
             min_tx_relay_fee_rate,
+            max_ancestor_count,
+            max_ancestor_size,
+            max_descendant_count,
+            max_descendant_size,
         } = config;
 
-        let min_tx_relay_fee_rate = min_tx_relay_fee_rate.or(options.min_tx_relay_fee_rate);
+        let min_tx_relay_fee_rate = options.min_tx_relay_fee_rate.or(min_tx_relay_fee_rate);
+        let max_ancestor_count = options.mempool_max_ancestor_count.or(max_ancestor_count);
+        let max_ancestor_size = options.mempool_max_ancestor_size.or(max_ancestor_size);
+        let max_descendant_count = options.mempool_max_descendant_count.or(max_descendant_count);
+        let max_descendant_size = options.mempool_max_descendant_size.or(max_descendant_size);
 
         MempoolConfigFile {
             min_tx_relay_fee_rate,
+            max_ancestor_count,
+            max_ancestor_size,
+            max_descendant_count,
+            max_descendant_size,
         }
     }
 }
@@ -51,12 +71,20 @@ impl From<MempoolConfigFile> for MempoolConfig {
     fn from(config_file: MempoolConfigFile) -> Self {
         let MempoolConfigFile {
             min_tx_relay_fee_rate,
+            max_ancestor_count,
+            max_ancestor_size,
+            max_descendant_count,
+            max_descendant_size,
         } = config_file;
 
         Self {
             min_tx_relay_fee_rate: min_tx_relay_fee_rate
                 .map(|val| FeeRate::from_amount_per_kb(Amount::from_atoms(val.into())))
                 .into(),
+            max_ancestor_count: max_ancestor_count.into(),
+            max_ancestor_size: max_ancestor_size.into(),
+            max_descendant_count: max_descendant_count.into(),
+            max_descendant_size: max_descendant_size.into(),
         }
     }
 }
@@ -18,14 +18,19 @@
 pub const DEFAULT_RPC_ENABLED: bool = true;
 pub const DEFAULT_P2P_NETWORKING_ENABLED: bool = true;
 
+/// The value that the secrets are replaced with when the config is printed
+const REDACTED: &str = "<redacted>";
+
 pub use self::{
-    chainstate_launcher::StorageBackendConfigFile, p2p::NodeTypeConfigFile, rpc::RpcConfigFile,
+    chainstate_launcher::StorageBackendConfigFile, metrics::MetricsConfigFile,
+    p2p::NodeTypeConfigFile, rpc::RpcConfigFile,
 };
 
 mod blockprod;
 mod chainstate;
 mod chainstate_launcher;
 mod mempool;
+mod metrics;
 mod p2p;
 mod rpc;
 
@@ -54,6 +59,7 @@ pub struct NodeConfigFile {
     pub mempool: Option<MempoolConfigFile>,
     pub p2p: Option<P2pConfigFile>,
     pub rpc: Option<RpcConfigFile>,
+    pub metrics: Option<MetricsConfigFile>,
 }
 
 impl NodeConfigFile {
@@ -64,6 +70,7 @@ impl NodeConfigFile {
             mempool: None,
             p2p: None,
             rpc: None,
+            metrics: None,
         })
     }
 
@@ -80,6 +87,9 @@ impl NodeConfigFile {
     }
 
     /// Reads a configuration from the specified path and overrides the provided parameters.
+    ///
+    /// The values are taken from the command line options (or the corresponding environment
+    /// variables) first, then from the config file.
     pub fn read(
         chain_config: &ChainConfig,
         config_path: &Path,
@@ -93,13 +103,16 @@ impl NodeConfigFile {
             mempool,
             p2p,
             rpc,
-        } = toml::from_str(&config_as_str).context("Failed to parse config")?;
+            metrics,
+        } = toml::from_str(&config_as_str)
+            .with_context(|| format!("Failed to parse config file {}", config_path.display()))?;
 
         let blockprod = blockprod_config(blockprod.unwrap_or_default(), options);
         let chainstate = chainstate_config(chainstate.unwrap_or_default(), options);
         let mempool = MempoolConfigFile::with_run_options(mempool.unwrap_or_default(), options);
         let p2p = p2p_config(p2p.unwrap_or_default(), options);
         let rpc = RpcConfigFile::with_run_options(chain_config, rpc.unwrap_or_default(), options);
+        let metrics = MetricsConfigFile::with_run_options(metrics.unwrap_or_default(), options);
 
         Ok(Self {
             blockprod: Some(blockprod),
@@ -107,8 +120,29 @@ impl NodeConfigFile {
             mempool: Some(mempool),
             p2p: Some(p2p),
             rpc: Some(rpc),
+            metrics: Some(metrics),
         })
     }
+
+    /// Returns the config with the passwords replaced, so that it can be logged or shared.
+    pub fn redacted(mut self) -> Self {
+        if let Some(rpc) = &mut self.rpc {
+            if let Some(password) = &mut rpc.password {
+                *password = REDACTED.to_owned();
+            }
+        }
+        if let Some(p2p) = &mut self.p2p {
+            if let Some((_username, password)) = &mut p2p.socks5_proxy_auth {
+                *password = REDACTED.to_owned();
+            }
+        }
+        self
+    }
+
+    /// Serializes the config with the secrets redacted into the config file format.
+    pub fn to_redacted_toml(&self) -> Result<String> {
+        toml::to_string(&self.clone().redacted()).context("Failed to serialize config")
+    }
 }
 
 fn blockprod_config(config: BlockProdConfigFile, options: &RunOptions) -> BlockProdConfigFile {
@@ -144,6 +178,7 @@ fn chainstate_config(
     let ChainstateLauncherConfigFile {
         storage_backend,
         chainstate_config,
+        unknown_keys: (),
     } = config;
 
     let ChainstateConfigFile {
@@ -152,6 +187,12 @@ fn chainstate_config(
         min_max_bootstrap_import_buffer_sizes,
         max_tip_age,
         enable_heavy_checks,
+        max_block_size_histogram_range,
+        ibd_write_batching,
+        max_write_batch_blocks,
+        max_write_batch_size,
+        storage_sync_policy,
+        upgrade_warning_window,
     } = chainstate_config;
 
     let storage_backend = options.storage_backend.clone().unwrap_or(storage_backend);
@@ -159,6 +200,7 @@ fn chainstate_config(
     let max_orphan_blocks = options.max_orphan_blocks.or(max_orphan_blocks);
     let max_tip_age = options.max_tip_age.or(max_tip_age);
     let enable_heavy_checks = options.enable_chainstate_heavy_checks.or(enable_heavy_checks);
+    let ibd_write_batching = options.ibd_write_batching.or(ibd_write_batching);
 
     let chainstate_config = ChainstateConfigFile {
         max_db_commit_attempts,
@@ -166,10 +208,17 @@ fn chainstate_config(
         min_max_bootstrap_import_buffer_sizes,
         max_tip_age,
         enable_heavy_checks,
+        max_block_size_histogram_range,
+        ibd_write_batching,
+        max_write_batch_blocks,
+        max_write_batch_size,
+        storage_sync_policy,
+        upgrade_warning_window,
     };
     ChainstateLauncherConfigFile {
         storage_backend,
         chainstate_config,
+        unknown_keys: (),
     }
 }
 
@@ -178,28 +227,45 @@ fn p2p_config(config: P2pConfigFile, options: &RunOptions) -> P2pConfigFile {
         networking_enabled,
         bind_addresses,
         socks5_proxy,
+        socks5_proxy_auth,
         disable_noise,
+        noise_key_file,
         boot_nodes,
         reserved_nodes,
+        reserved_nodes_only,
+        enable_mempool_sync,
+        enable_port_mapping,
         whitelisted_addresses,
         max_inbound_connections,
         discouragement_threshold,
         discouragement_duration,
         max_clock_diff,
         outbound_connection_timeout,
+        inbound_connection_timeout,
         ping_check_period,
         ping_timeout,
         sync_stalling_timeout,
         node_type,
         force_dns_query_if_no_global_addresses_known,
+        min_peer_protocol_version,
+        allowed_user_agents,
+        denied_user_agents,
     } = config;
 
     let networking_enabled = options.p2p_networking_enabled.or(networking_enabled);
-    let bind_addresses = options.p2p_bind_addresses.clone().or(bind_addresses);
+    let bind_addresses = options
+        .p2p_bind_addresses
+        .as_ref()
+        .map(|addresses| addresses.iter().copied().map(Into::into).collect())
+        .or(bind_addresses);
     let socks5_proxy = options.p2p_socks5_proxy.clone().or(socks5_proxy);
     let disable_noise = options.p2p_disable_noise.or(disable_noise);
+    let noise_key_file = options.p2p_noise_key_file.clone().or(noise_key_file);
     let boot_nodes = options.p2p_boot_nodes.clone().or(boot_nodes);
     let reserved_nodes = options.p2p_reserved_nodes.clone().or(reserved_nodes);
+    let reserved_nodes_only = options.p2p_reserved_nodes_only.or(reserved_nodes_only);
+    let enable_mempool_sync = options.p2p_enable_mempool_sync.or(enable_mempool_sync);
+    let enable_port_mapping = options.p2p_enable_port_mapping.or(enable_port_mapping);
     let whitelisted_addresses = options.p2p_whitelist_addr.clone().or(whitelisted_addresses);
     let max_inbound_connections = options.p2p_max_inbound_connections.or(max_inbound_connections);
     let discouragement_threshold =
@@ -210,6 +276,8 @@ fn p2p_config(config: P2pConfigFile, options: &RunOptions) -> P2pConfigFile {
     let max_clock_diff = options.p2p_max_clock_diff.or(max_clock_diff);
     let outbound_connection_timeout =
         options.p2p_outbound_connection_timeout.or(outbound_connection_timeout);
+    let inbound_connection_timeout =
+        options.p2p_inbound_connection_timeout.or(inbound_connection_timeout);
     let sync_stalling_timeout = options.p2p_sync_stalling_timeout.or(sync_stalling_timeout);
     let node_type = options.node_type.or(node_type);
     let force_dns_query_if_no_global_addresses_known = options
@@ -220,20 +288,29 @@ fn p2p_config(config: P2pConfigFile, options: &RunOptions) -> P2pConfigFile {
         networking_enabled,
         bind_addresses,
         socks5_proxy,
+        socks5_proxy_auth,
         disable_noise,
+        noise_key_file,
         boot_nodes,
         reserved_nodes,
+        reserved_nodes_only,
+        enable_mempool_sync,
+        enable_port_mapping,
         whitelisted_addresses,
         max_inbound_connections,
         discouragement_threshold,
         discouragement_duration,
         max_clock_diff,
         outbound_connection_timeout,
+        inbound_connection_timeout,
         ping_check_period,
         ping_timeout,
         sync_stalling_timeout,
         node_type,
         force_dns_query_if_no_global_addresses_known,
+        min_peer_protocol_version,
+        allowed_user_agents,
+        denied_user_agents,
     }
 }
 
@@ -243,7 +320,10 @@ mod tests {
 
     use randomness::{distributions::Alphanumeric, make_pseudo_rng, Rng};
 
-    use super::*;
+    use super::{
+        p2p::{BindAddressConfigFile, TransportProfileConfigFile},
+        *,
+    };
 
     #[test]
     fn no_values_required_in_toml_files() {
@@ -253,6 +333,218 @@ mod tests {
         let _config: ChainstateConfigFile = toml::from_str("").unwrap();
         let _config: P2pConfigFile = toml::from_str("").unwrap();
         let _config: RpcConfigFile = toml::from_str("").unwrap();
+        let _config: MetricsConfigFile = toml::from_str("").unwrap();
+    }
+
+    #[test]
+    fn p2p_bind_addresses() {
+        let config: P2pConfigFile = toml::from_str(
+            r#"bind_addresses = [
+                "0.0.0.0:3031",
+                { address = "127.0.0.1:13031", transport = "plaintext" },
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.bind_addresses.unwrap(),
+            vec![
+                BindAddressConfigFile::Address("0.0.0.0:3031".parse().unwrap()),
+                BindAddressConfigFile::WithTransport {
+                    address: "127.0.0.1:13031".parse().unwrap(),
+                    transport: Some(TransportProfileConfigFile::Plaintext),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn p2p_admission_filters() {
+        let config: P2pConfigFile = toml::from_str(
+            r#"
+                min_peer_protocol_version = 5
+                allowed_user_agents = ["^MintlayerCore"]
+                denied_user_agents = ["Scanner", "^Bad"]
+            "#,
+        )
+        .unwrap();
+        let p2p_config: p2p::config::P2pConfig = config.into();
+        let admission_config = p2p_config.peer_admission_config;
+        assert_eq!(
+            admission_config.min_protocol_version,
+            Some(p2p::protocol::ProtocolVersion::new(5))
+        );
+        assert!(admission_config.is_user_agent_allowed("MintlayerCore"));
+        assert!(!admission_config.is_user_agent_allowed("MintlayerCoreScanner"));
+        assert!(!admission_config.is_user_agent_allowed("Other"));
+
+        // Invalid regular expressions are rejected when the config is read
+        toml::from_str::<P2pConfigFile>(r#"denied_user_agents = ["("]"#).unwrap_err();
+    }
+
+    const FULL_CONFIG: &str = r#"
+        [blockprod]
+        min_peers_to_produce_blocks = 1
+        skip_ibd_check = true
+        use_current_time_if_non_pos = false
+
+        [chainstate]
+        storage_backend = "inmemory"
+        max_db_commit_attempts = 5
+        max_orphan_blocks = 100
+        min_max_bootstrap_import_buffer_sizes = [10, 100]
+        max_tip_age = 3600
+        enable_heavy_checks = true
+        max_block_size_histogram_range = 1000
+        ibd_write_batching = true
+        max_write_batch_blocks = 50
+        max_write_batch_size = 1000000
+        storage_sync_policy = { mode = "periodic", interval_secs = 60 }
+        upgrade_warning_window = 100
+
+        [mempool]
+        min_tx_relay_fee_rate = 1000
+        max_ancestor_count = 10
+        max_ancestor_size = 100000
+        max_descendant_count = 20
+        max_descendant_size = 200000
+
+        [p2p]
+        networking_enabled = true
+        bind_addresses = ["0.0.0.0:3031", { address = "127.0.0.1:13031", transport = "plaintext" }]
+        socks5_proxy = "127.0.0.1:9050"
+        socks5_proxy_auth = ["proxy_user", "proxy_secret"]
+        boot_nodes = ["1.2.3.4:3031"]
+        reserved_nodes = ["5.6.7.8"]
+        reserved_nodes_only = false
+        enable_mempool_sync = true
+        enable_port_mapping = true
+        whitelisted_addresses = ["10.0.0.1"]
+        max_inbound_connections = 10
+        discouragement_threshold = 100
+        discouragement_duration = 3600
+        max_clock_diff = 10
+        outbound_connection_timeout = 10
+        inbound_connection_timeout = 5
+        ping_check_period = 60
+        ping_timeout = 150
+        sync_stalling_timeout = 25
+        node_type = "full-node"
+        min_peer_protocol_version = 5
+        allowed_user_agents = ["^MintlayerCore"]
+        denied_user_agents = ["Scanner"]
+
+        [rpc]
+        rpc_enabled = true
+        bind_address = "127.0.0.1:3030"
+        username = "rpc_user"
+        password = "rpc_secret"
+        cookie_file = "/tmp/cookie"
+
+        [metrics]
+        bind_address = "127.0.0.1:9100"
+    "#;
+
+    fn read_config(contents: &str, options: &RunOptions) -> Result<NodeConfigFile> {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config_path = dir.path().join("config.toml");
+        fs::write(&config_path, contents).unwrap();
+        let chain_config = common::chain::config::create_regtest();
+        NodeConfigFile::read(&chain_config, &config_path, options)
+    }
+
+    #[test]
+    fn dump_config_round_trip() {
+        let config = read_config(FULL_CONFIG, &RunOptions::default()).unwrap();
+        let dumped = config.to_redacted_toml().unwrap();
+
+        for section in ["blockprod", "chainstate", "mempool", "p2p", "rpc", "metrics"] {
+            assert!(dumped.contains(&format!("[{section}]")), "{dumped}");
+        }
+        assert!(!dumped.contains("secret"), "{dumped}");
+
+        // The dumped config can be used as a config file and yields the same config
+        let reread = read_config(&dumped, &RunOptions::default()).unwrap();
+        assert_eq!(toml::to_string(&reread).unwrap(), dumped);
+
+        let rpc = reread.rpc.unwrap();
+        assert_eq!(rpc.username.as_deref(), Some("rpc_user"));
+        assert_eq!(rpc.password.as_deref(), Some(REDACTED));
+        let p2p = reread.p2p.unwrap();
+        assert_eq!(
+            p2p.socks5_proxy_auth,
+            Some(("proxy_user".to_owned(), REDACTED.to_owned()))
+        );
+        assert_eq!(p2p.max_inbound_connections, Some(10));
+        let chainstate = reread.chainstate.unwrap();
+        assert_eq!(
+            chainstate.storage_backend,
+            StorageBackendConfigFile::InMemory
+        );
+        assert_eq!(chainstate.chainstate_config.max_orphan_blocks, Some(100));
+
+        // The defaults are filled in
+        let dumped = read_config("", &RunOptions::default()).unwrap().to_redacted_toml().unwrap();
+        let defaults: NodeConfigFile = toml::from_str(&dumped).unwrap();
+        let rpc = defaults.rpc.unwrap();
+        assert_eq!(rpc.rpc_enabled, Some(DEFAULT_RPC_ENABLED));
+        assert!(rpc.bind_address.is_some());
+        assert_eq!(defaults.blockprod.unwrap().skip_ibd_check, Some(false));
+    }
+
+    #[test]
+    fn unknown_keys_rejected() {
+        let err = read_config(
+            "[p2p]\nmax_inbound_connections = 10\nmax_inbound_connectionz = 10\n",
+            &RunOptions::default(),
+        )
+        .unwrap_err();
+        let message = format!("{err:#}");
+        assert!(message.contains("line 3"), "{message}");
+        assert!(
+            message.contains("unknown field `max_inbound_connectionz`"),
+            "{message}"
+        );
+
+        let err = read_config("[rpcc]\nusername = \"user\"\n", &RunOptions::default()).unwrap_err();
+        let message = format!("{err:#}");
+        assert!(message.contains("unknown field `rpcc`"), "{message}");
+
+        // The chainstate section is checked too, despite the flattened chainstate config
+        let err = read_config(
+            "[chainstate]\nmax_orphan_blocks = 1\nmax-orphan-blocks = 1\n",
+            &RunOptions::default(),
+        )
+        .unwrap_err();
+        let message = format!("{err:#}");
+        assert!(
+            message.contains("unknown field `max-orphan-blocks`"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn command_line_overrides_config_file() {
+        let options = RunOptions {
+            rpc_username: Some("cli_user".to_owned()),
+            p2p_max_inbound_connections: Some(20),
+            mempool_max_ancestor_count: Some(30),
+            max_orphan_blocks: Some(40),
+            ..Default::default()
+        };
+        let config = read_config(FULL_CONFIG, &options).unwrap();
+
+        let rpc = config.rpc.unwrap();
+        assert_eq!(rpc.username.as_deref(), Some("cli_user"));
+        assert_eq!(rpc.password.as_deref(), Some("rpc_secret"));
+        let p2p = config.p2p.unwrap();
+        assert_eq!(p2p.max_inbound_connections, Some(20));
+        assert_eq!(p2p.ping_check_period, Some(60));
+        let mempool = config.mempool.unwrap();
+        assert_eq!(mempool.max_ancestor_count, Some(30));
+        assert_eq!(mempool.max_descendant_count, Some(20));
+        let chainstate = config.chainstate.unwrap().chainstate_config;
+        assert_eq!(chainstate.max_orphan_blocks, Some(40));
+        assert_eq!(chainstate.max_db_commit_attempts, Some(5));
     }
 
     #[test]
@@ -24,9 +24,11 @@ use common::primitives::user_agent::mintlayer_core_user_agent;
 use serde::{Deserialize, Serialize};
 
 use p2p::{
+    admission_config::{PeerAdmissionConfig, UserAgentPattern},
     ban_config::BanConfig,
-    config::{NodeType, P2pConfig},
+    config::{BindAddress, NodeType, P2pConfig, TransportProfile},
     peer_manager::config::PeerManagerConfig,
+    protocol::ProtocolVersion,
 };
 use utils_networking::IpOrSocketAddress;
 
@@ -59,6 +61,55 @@ impl FromStr for NodeTypeConfigFile {
     }
 }
 
+/// The transport used by a listener.
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TransportProfileConfigFile {
+    /// Noise-encrypted TCP.
+    Noise,
+    /// Unencrypted TCP, only allowed on loopback addresses on regtest.
+    Plaintext,
+}
+
+impl From<TransportProfileConfigFile> for TransportProfile {
+    fn from(t: TransportProfileConfigFile) -> Self {
+        match t {
+            TransportProfileConfigFile::Noise => Self::Noise,
+            TransportProfileConfigFile::Plaintext => Self::Plaintext,
+        }
+    }
+}
+
+/// An address to bind P2P to, either a plain socket address or an address with a transport
+/// override, e.g. `{ address = "127.0.0.1:13031", transport = "plaintext" }`.
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum BindAddressConfigFile {
+    Address(SocketAddr),
+    WithTransport {
+        address: SocketAddr,
+        transport: Option<TransportProfileConfigFile>,
+    },
+}
+
+impl From<SocketAddr> for BindAddressConfigFile {
+    fn from(address: SocketAddr) -> Self {
+        Self::Address(address)
+    }
+}
+
+impl From<BindAddressConfigFile> for BindAddress {
+    fn from(bind_address: BindAddressConfigFile) -> Self {
+        match bind_address {
+            BindAddressConfigFile::Address(address) => address.into(),
+            BindAddressConfigFile::WithTransport { address, transport } => BindAddress {
+                address,
+                transport: transport.map(Into::into),
+            },
+        }
+    }
+}
+
 /// The p2p subsystem configuration.
 #[must_use]
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
@@ -66,16 +117,26 @@ impl FromStr for NodeTypeConfigFile {
 pub struct P2pConfigFile {
     /// Whether networking is enabled.
     pub networking_enabled: Option<bool>,
-    /// Address to bind P2P to.
-    pub bind_addresses: Option<Vec<SocketAddr>>,
+    /// Addresses to bind P2P to.
+    pub bind_addresses: Option<Vec<BindAddressConfigFile>>,
     /// SOCKS5 proxy.
     pub socks5_proxy: Option<String>,
+    /// SOCKS5 proxy username and password.
+    pub socks5_proxy_auth: Option<(String, String)>,
     /// Disable p2p encryption (for tests only).
     pub disable_noise: Option<bool>,
+    /// Custom file path for the Noise private key file.
+    pub noise_key_file: Option<String>,
     /// Optional list of boot node addresses to connect.
     pub boot_nodes: Option<Vec<IpOrSocketAddress>>,
     /// Optional list of reserved node addresses to connect.
     pub reserved_nodes: Option<Vec<IpOrSocketAddress>>,
+    /// Only communicate with the reserved nodes.
+    pub reserved_nodes_only: Option<bool>,
+    /// Request the mempool contents from the outbound peers after connecting to them.
+    pub enable_mempool_sync: Option<bool>,
+    /// Map the p2p port on the local gateway with UPnP or NAT-PMP.
+    pub enable_port_mapping: Option<bool>,
     /// Optional list of whitelisted addresses.
     pub whitelisted_addresses: Option<Vec<IpAddr>>,
     /// Maximum allowed number of inbound connections.
@@ -89,6 +150,8 @@ pub struct P2pConfigFile {
     pub max_clock_diff: Option<u64>,
     /// The outbound connection timeout value in seconds.
     pub outbound_connection_timeout: Option<NonZeroU64>,
+    /// The inbound connection handshake timeout value in seconds.
+    pub inbound_connection_timeout: Option<NonZeroU64>,
     /// How often send ping requests to peers.
     pub ping_check_period: Option<u64>,
     /// When a peer is detected as dead and disconnected.
@@ -100,6 +163,14 @@ pub struct P2pConfigFile {
     /// If true, the node will perform an early dns query if the peer db doesn't contain
     /// any global addresses at startup.
     pub force_dns_query_if_no_global_addresses_known: Option<bool>,
+    /// The minimum protocol version that the peers must support.
+    pub min_peer_protocol_version: Option<u32>,
+    /// If set, only the peers whose user agent matches one of these regular expressions
+    /// are accepted. Doesn't apply to the reserved nodes.
+    pub allowed_user_agents: Option<Vec<UserAgentPattern>>,
+    /// The peers whose user agent matches one of these regular expressions are refused.
+    /// Doesn't apply to the reserved nodes.
+    pub denied_user_agents: Option<Vec<UserAgentPattern>>,
 }
 
 impl From<P2pConfigFile> for P2pConfig {
@@ -108,28 +179,45 @@ impl From<P2pConfigFile> for P2pConfig {
             networking_enabled: _,
             bind_addresses,
             socks5_proxy,
+            socks5_proxy_auth,
             disable_noise,
+            noise_key_file: _,
             boot_nodes,
             reserved_nodes,
+            reserved_nodes_only,
+            enable_mempool_sync,
+            enable_port_mapping,
             whitelisted_addresses,
             max_inbound_connections,
             discouragement_threshold,
             discouragement_duration,
             max_clock_diff,
             outbound_connection_timeout,
+            inbound_connection_timeout,
             ping_check_period,
             ping_timeout,
             sync_stalling_timeout,
             node_type,
             force_dns_query_if_no_global_addresses_known,
+            min_peer_protocol_version,
+            allowed_user_agents,
+            denied_user_agents,
         } = config_file;
 
         P2pConfig {
-            bind_addresses: bind_addresses.unwrap_or_default(),
+            bind_addresses: bind_addresses
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
             socks5_proxy,
+            socks5_proxy_auth,
             disable_noise,
             boot_nodes: boot_nodes.unwrap_or_default(),
             reserved_nodes: reserved_nodes.unwrap_or_default(),
+            reserved_nodes_only,
+            enable_mempool_sync,
+            enable_port_mapping,
             whitelisted_addresses: whitelisted_addresses.unwrap_or_default(),
             ban_config: BanConfig {
                 discouragement_threshold: discouragement_threshold.into(),
@@ -139,6 +227,9 @@ impl From<P2pConfigFile> for P2pConfig {
             outbound_connection_timeout: outbound_connection_timeout
                 .map(|t| Duration::from_secs(t.into()))
                 .into(),
+            inbound_connection_timeout: inbound_connection_timeout
+                .map(|t| Duration::from_secs(t.into()))
+                .into(),
             ping_check_period: ping_check_period.map(Duration::from_secs).into(),
             ping_timeout: ping_timeout.map(|t| Duration::from_secs(t.into())).into(),
             node_type: node_type.map(Into::into).into(),
@@ -148,6 +239,17 @@ impl From<P2pConfigFile> for P2pConfig {
             sync_stalling_timeout: sync_stalling_timeout
                 .map(|t| Duration::from_secs(t.into()))
                 .into(),
+            fork_alert_threshold: Default::default(),
+            fork_alert_duration: Default::default(),
+            max_mempool_tx_broadcasts_per_peer: Default::default(),
+            max_buffered_block_response_bytes: Default::default(),
+            max_in_flight_block_responses_per_peer: Default::default(),
+            max_low_work_blocks_in_flight: Default::default(),
+            peer_admission_config: PeerAdmissionConfig {
+                min_protocol_version: min_peer_protocol_version.map(ProtocolVersion::new),
+                allowed_user_agents: allowed_user_agents.unwrap_or_default(),
+                denied_user_agents: denied_user_agents.unwrap_or_default(),
+            },
             peer_manager_config: PeerManagerConfig {
                 max_inbound_connections: max_inbound_connections.into(),
 
@@ -70,9 +70,9 @@ impl RpcConfigFile {
             .rpc_enabled
             .unwrap_or_else(|| rpc_enabled.unwrap_or(DEFAULT_RPC_ENABLED));
 
-        let username = username.or(options.rpc_username.clone());
-        let password = password.or(options.rpc_password.clone());
-        let cookie_file = cookie_file.or(options.rpc_cookie_file.clone());
+        let username = options.rpc_username.clone().or(username);
+        let password = options.rpc_password.clone().or(password);
+        let cookie_file = options.rpc_cookie_file.clone().or(cookie_file);
 
         RpcConfigFile {
             bind_address: Some(bind_address),
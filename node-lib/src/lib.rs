@@ -16,6 +16,7 @@
 //! Top-level node runner as a library
 
 mod config_files;
+pub mod metrics;
 mod mock_time;
 pub mod node_controller;
 mod options;
@@ -26,7 +27,7 @@ pub type Error = anyhow::Error;
 
 use chainstate_launcher::ChainConfig;
 pub use config_files::{
-    NodeConfigFile, NodeTypeConfigFile, RpcConfigFile, StorageBackendConfigFile,
+    MetricsConfigFile, NodeConfigFile, NodeTypeConfigFile, RpcConfigFile, StorageBackendConfigFile,
 };
 pub use options::{Command, Options, RunOptions};
 pub use runner::{setup, NodeSetupResult};
@@ -0,0 +1,146 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A minimal HTTP endpoint that serves the node metrics in the Prometheus text format.
+
+use std::{net::SocketAddr, time::Duration};
+
+use anyhow::Context;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+
+use logging::log;
+use utils::metrics::registry;
+
+pub const METRICS_PATH: &str = "/metrics";
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// The maximum size of the request head; the requests are not expected to have a body
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Register the metrics of all the subsystems, so that they are exported from the start
+pub fn register_metrics() {
+    chainstate::metrics::register();
+    mempool::metrics::register();
+    p2p::metrics::register();
+}
+
+/// The metrics subsystem
+pub struct MetricsServer {
+    local_addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl MetricsServer {
+    pub async fn new(bind_address: SocketAddr) -> anyhow::Result<Self> {
+        register_metrics();
+
+        let listener = TcpListener::bind(bind_address)
+            .await
+            .with_context(|| format!("Failed to bind the metrics endpoint to {bind_address}"))?;
+        let local_addr = listener.local_addr()?;
+        log::info!("Serving metrics on http://{local_addr}{METRICS_PATH}");
+
+        let task = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        tokio::spawn(async move {
+                            if let Err(err) = handle_connection(stream).await {
+                                log::debug!("Metrics request failed: {err}");
+                            }
+                        });
+                    }
+                    Err(err) => log::warn!("Accepting a metrics connection failed: {err}"),
+                }
+            }
+        });
+
+        Ok(Self { local_addr, task })
+    }
+
+    pub fn local_addr(&self) -> &SocketAddr {
+        &self.local_addr
+    }
+}
+
+async fn read_request_line(stream: &mut TcpStream) -> anyhow::Result<String> {
+    let mut buf = Vec::new();
+    loop {
+        let mut chunk = [0; 1024];
+        let read = stream.read(&mut chunk).await?;
+        anyhow::ensure!(
+            read != 0,
+            "Connection closed before the request was received"
+        );
+        buf.extend_from_slice(&chunk[..read]);
+
+        if buf.windows(4).any(|window| window == b"\r\n\r\n") {
+            break;
+        }
+        anyhow::ensure!(buf.len() <= MAX_REQUEST_SIZE, "Request is too large");
+    }
+
+    let request = String::from_utf8_lossy(&buf);
+    Ok(request.lines().next().unwrap_or_default().to_owned())
+}
+
+async fn handle_connection(mut stream: TcpStream) -> anyhow::Result<()> {
+    let request_line = tokio::time::timeout(REQUEST_TIMEOUT, read_request_line(&mut stream))
+        .await
+        .context("Request timed out")??;
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+    let path = path.split_once('?').map_or(path, |(path, _query)| path);
+
+    let (status, body) = match (method, path) {
+        ("GET", METRICS_PATH) => ("200 OK", registry().render()),
+        _ => ("404 Not Found", String::new()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {CONTENT_TYPE}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl subsystem::Subsystem for MetricsServer {
+    type Interface = Self;
+
+    fn interface_ref(&self) -> &Self {
+        self
+    }
+
+    fn interface_mut(&mut self) -> &mut Self {
+        self
+    }
+
+    async fn shutdown(self) {
+        self.task.abort();
+    }
+}
@@ -49,6 +49,11 @@ pub struct Options {
     #[clap(long = "create-datadir-if-missing", value_name = "VAL")]
     pub create_data_dir_if_missing: Option<bool>,
 
+    /// The path to the config file.
+    /// If not set, the config file in the data directory is used, if it exists.
+    #[clap(long = "config", value_name = "PATH")]
+    pub config_file: Option<PathBuf>,
+
     #[clap(subcommand)]
     pub command: Option<Command>,
 }
@@ -80,6 +85,19 @@ pub struct RunOptions {
     #[clap(long, short, action = clap::ArgAction::SetTrue)]
     pub clean_data: Option<bool>,
 
+    /// Print the effective configuration, i.e. the config file combined with the command line
+    /// options, and exit. The secrets are redacted.
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub dump_config: Option<bool>,
+
+    /// Rebuild the chainstate from the blocks stored in the data dir.
+    ///
+    /// Everything derived from the blocks (block indices, the utxo set, accounting data) is
+    /// discarded and the blocks are processed again, which may take a long time.
+    /// The rest of the node is started only after this is finished.
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub reindex: Option<bool>,
+
     /// Minimum number of connected peers to enable block production.
     #[clap(long, value_name = "COUNT")]
     pub blockprod_min_peers_to_produce_blocks: Option<usize>,
@@ -144,6 +162,11 @@ pub struct RunOptions {
     #[arg(hide = true)]
     pub p2p_disable_noise: Option<bool>,
 
+    /// Custom file path for the p2p Noise private key file.
+    /// If not set, the key file is created in the data dir.
+    #[clap(long, value_name = "PATH")]
+    pub p2p_noise_key_file: Option<String>,
+
     /// Optional list of boot node addresses to connect.
     /// Can be specified multiple times and/or be a comma-separated list.
     #[clap(long, value_name = "ADDR", value_delimiter(','))]
@@ -154,6 +177,21 @@ pub struct RunOptions {
     #[clap(long, value_name = "ADDR", value_delimiter(','))]
     pub p2p_reserved_nodes: Option<Vec<IpOrSocketAddress>>,
 
+    /// Only communicate with the reserved nodes.
+    /// Inbound connections from other addresses are refused, the DNS seeds are not queried
+    /// and the node's own address is not advertised.
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub p2p_reserved_nodes_only: Option<bool>,
+
+    /// Request the mempool contents from the outbound peers after connecting to them.
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub p2p_enable_mempool_sync: Option<bool>,
+
+    /// Map the p2p port on the local gateway with UPnP or NAT-PMP, so that the node can accept
+    /// inbound connections from behind a home router, and announce the external address to peers.
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub p2p_enable_port_mapping: Option<bool>,
+
     /// Optional list of whitelisted addresses.
     /// Can be specified multiple times and/or be a comma-separated list.
     #[clap(long, value_name = "ADDR", value_delimiter(','))]
@@ -175,6 +213,10 @@ pub struct RunOptions {
     #[clap(long, value_name = "TIMEOUT")]
     pub p2p_outbound_connection_timeout: Option<NonZeroU64>,
 
+    /// The inbound connection handshake timeout value in seconds.
+    #[clap(long, value_name = "TIMEOUT")]
+    pub p2p_inbound_connection_timeout: Option<NonZeroU64>,
+
     /// How often send ping requests to peers (in seconds).
     /// Set to 0 to disable sending ping requests.
     #[clap(long, value_name = "PERIOD")]
@@ -231,10 +273,31 @@ pub struct RunOptions {
     #[clap(long, value_name = "PATH")]
     pub rpc_cookie_file: Option<String>,
 
+    /// Address to serve the node metrics in the Prometheus text format on.
+    /// If not set, the metrics endpoint is disabled.
+    #[clap(long, value_name = "ADDR")]
+    pub metrics_bind_address: Option<SocketAddr>,
+
     /// Minimum transaction relay fee rate (in atoms per 1000 bytes).
     #[clap(long, value_name = "VAL")]
     pub min_tx_relay_fee_rate: Option<u64>,
 
+    /// Maximum number of unconfirmed ancestors of a mempool transaction, including itself.
+    #[clap(long, value_name = "COUNT")]
+    pub mempool_max_ancestor_count: Option<usize>,
+
+    /// Maximum total size (in bytes) of a mempool transaction and its unconfirmed ancestors.
+    #[clap(long, value_name = "SIZE")]
+    pub mempool_max_ancestor_size: Option<usize>,
+
+    /// Maximum number of unconfirmed descendants of a mempool transaction, including itself.
+    #[clap(long, value_name = "COUNT")]
+    pub mempool_max_descendant_count: Option<usize>,
+
+    /// Maximum total size (in bytes) of a mempool transaction and its unconfirmed descendants.
+    #[clap(long, value_name = "SIZE")]
+    pub mempool_max_descendant_size: Option<usize>,
+
     #[clap(flatten)]
     pub force_allow_run_as_root_outer: ForceRunAsRootOptions,
 
@@ -242,6 +305,11 @@ pub struct RunOptions {
     /// Defaults to true for regtest and false in other cases.
     #[clap(long, value_name = "VAL")]
     pub enable_chainstate_heavy_checks: Option<bool>,
+
+    /// If true, blocks are written to the chainstate storage in batches during the initial block
+    /// download, which makes it faster.
+    #[clap(long, value_name = "VAL")]
+    pub ibd_write_batching: Option<bool>,
 }
 
 impl Options {
@@ -260,10 +328,12 @@ impl Options {
 
     /// Returns a path to the config file
     pub fn config_path(&self, chain_type: ChainType) -> PathBuf {
-        self.data_dir
-            .clone()
-            .unwrap_or_else(|| default_data_dir(chain_type))
-            .join(CONFIG_NAME)
+        self.config_file.clone().unwrap_or_else(|| {
+            self.data_dir
+                .clone()
+                .unwrap_or_else(|| default_data_dir(chain_type))
+                .join(CONFIG_NAME)
+        })
     }
 }
 
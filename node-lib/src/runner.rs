@@ -34,7 +34,7 @@ use mempool::rpc::MempoolRpcServer;
 
 use test_rpc_functions::{empty::make_empty_rpc_test_functions, rpc::RpcTestFunctionsRpcServer};
 
-use p2p::{error::P2pError, rpc::P2pRpcServer};
+use p2p::{error::P2pError, rpc::P2pRpcServer, NoiseStaticKeypair};
 use rpc::rpc_creds::RpcCreds;
 use test_rpc_functions::make_rpc_test_functions;
 use utils::default_data_dir::prepare_data_dir;
@@ -48,10 +48,13 @@ use crate::{
 };
 
 const LOCK_FILE_NAME: &str = ".lock";
+const NOISE_KEY_FILE_NAME: &str = "noise_key";
 
 pub enum NodeSetupResult {
     Node(Node),
     DataDirCleanedUp,
+    /// The effective configuration in the TOML format, with the secrets redacted
+    ConfigDumped(String),
 }
 
 pub struct Node {
@@ -76,6 +79,7 @@ async fn initialize(
     chain_config: ChainConfig,
     data_dir: PathBuf,
     node_config: NodeConfigFile,
+    reindex: bool,
 ) -> Result<(subsystem::Manager, NodeController)> {
     let chain_config = Arc::new(chain_config);
 
@@ -91,6 +95,7 @@ async fn initialize(
         &data_dir,
         Arc::clone(&chain_config),
         node_config.chainstate.unwrap_or_default().into(),
+        reindex,
     )?;
     let chainstate = manager.add_subsystem("chainstate", chainstate);
 
@@ -153,6 +158,11 @@ async fn initialize(
         }
     }?;
     let p2p_config_file = node_config.p2p.unwrap_or_default();
+    let noise_key_file = match &p2p_config_file.noise_key_file {
+        Some(noise_key_file) => PathBuf::from(noise_key_file),
+        None => data_dir.join(NOISE_KEY_FILE_NAME),
+    };
+    let noise_keypair = load_or_create_noise_keypair(&noise_key_file)?;
     let p2p = p2p::make_p2p(
         p2p_config_file.networking_enabled.unwrap_or(DEFAULT_P2P_NETWORKING_ENABLED),
         Arc::clone(&chain_config),
@@ -162,6 +172,7 @@ async fn initialize(
         Default::default(),
         peerdb_storage,
     )?
+    .with_noise_keypair(noise_keypair)
     .add_to_manager("p2p", &mut manager);
 
     // Block production
@@ -221,6 +232,12 @@ async fn initialize(
         let _rpc = manager.add_subsystem("rpc", rpc);
     };
 
+    // Metrics endpoint
+    if let Some(bind_address) = node_config.metrics.and_then(|config| config.bind_address) {
+        let metrics_server = crate::metrics::MetricsServer::new(bind_address).await?;
+        let _metrics_server = manager.add_subsystem("metrics", metrics_server);
+    }
+
     let controller = NodeController {
         shutdown_trigger: manager.make_shutdown_trigger(),
         chainstate: chainstate.clone(),
@@ -235,6 +252,16 @@ async fn initialize(
 /// Processes options and potentially runs the node.
 pub async fn setup(options: Options, gui_mode: bool) -> Result<NodeSetupResult> {
     let command = options.command.clone().unwrap_or(Command::Mainnet(RunOptions::default()));
+
+    // Unlike the default config file, an explicitly specified one must exist
+    if let Some(config_file) = &options.config_file {
+        anyhow::ensure!(
+            config_file.is_file(),
+            "Config file {} doesn't exist",
+            config_file.display()
+        );
+    }
+
     match command {
         Command::Mainnet(run_options) => {
             let chain_config = common::chain::config::create_mainnet();
@@ -285,6 +312,39 @@ fn lock_data_dir(data_dir: &PathBuf) -> Result<std::fs::File> {
     Ok(lock)
 }
 
+/// Loads the node's static Noise keypair from the file, generating and saving a new one
+/// if the file doesn't exist yet.
+fn load_or_create_noise_keypair(path: &Path) -> Result<NoiseStaticKeypair> {
+    if path.exists() {
+        let private_key = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Cannot read noise key file {path:?}: {e}"))?;
+        let private_key = hex::decode(private_key.trim())
+            .map_err(|e| anyhow!("Invalid noise key file {path:?}: {e}"))?;
+        return NoiseStaticKeypair::from_private_key(&private_key)
+            .map_err(|e| anyhow!("Invalid noise key file {path:?}: {e}"));
+    }
+
+    let keypair = NoiseStaticKeypair::generate();
+
+    let mut options = std::fs::OpenOptions::new();
+    #[cfg(unix)]
+    {
+        // Prevent other users from reading the file
+        use std::os::unix::prelude::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .create_new(true)
+        .write(true)
+        .open(path)
+        .and_then(|mut file| {
+            std::io::Write::write_all(&mut file, hex::encode(keypair.private_key()).as_bytes())
+        })
+        .map_err(|e| anyhow!("Cannot create noise key file {path:?}: {e}"))?;
+
+    Ok(keypair)
+}
+
 fn clean_data_dir(data_dir: &Path, exclude: &[&Path]) -> Result<()> {
     for entry in std::fs::read_dir(data_dir)? {
         let entry_path = entry?.path();
@@ -333,6 +393,12 @@ async fn start(
     let node_config = NodeConfigFile::read(&chain_config, config_path, &run_options)
         .context("Failed to initialize config")?;
 
+    if run_options.dump_config.unwrap_or(false) {
+        return Ok(NodeSetupResult::ConfigDumped(
+            node_config.to_redacted_toml()?,
+        ));
+    }
+
     let data_dir = prepare_data_dir(
         || default_data_dir(*chain_config.chain_type()),
         datadir_path_opt,
@@ -354,11 +420,16 @@ async fn start(
         chain_config.software_version()
     );
 
-    log::info!("Starting with the following config:\n {node_config:#?}");
+    log::info!(
+        "Starting with the following config:\n {:#?}",
+        node_config.clone().redacted()
+    );
+    let reindex = run_options.reindex.unwrap_or(false);
     let (manager, controller) = match initialize(
         chain_config.clone(),
         data_dir.clone(),
         node_config.clone(),
+        reindex,
     )
     .await
     {
@@ -381,7 +452,7 @@ async fn start(
                     }
                 }
 
-                initialize(chain_config, data_dir, node_config).await?
+                initialize(chain_config, data_dir, node_config, reindex).await?
             }
             _ => return Err(error),
         },
@@ -398,6 +469,7 @@ async fn start(
 mod test {
     use std::io::{Read, Write};
 
+    use networking::transport::{TransportListener, TransportSocket};
     use randomness::{make_pseudo_rng, Rng};
     use tempfile::TempDir;
 
@@ -580,4 +652,52 @@ mod test {
 
         test_file_data(&file_path, &file_data);
     }
+
+    // Return the Noise public key that a client sees when connecting to a node that uses
+    // the given keypair.
+    async fn remote_noise_public_key(keypair: NoiseStaticKeypair) -> Vec<u8> {
+        let server_transport = p2p::make_p2p_transport_with_keypair(keypair);
+        let mut listener =
+            server_transport.bind(vec!["127.0.0.1:0".parse().unwrap()]).await.unwrap();
+        let address = listener.local_addresses().unwrap()[0];
+
+        let client_transport = p2p::make_p2p_transport();
+        let (server_res, client_res) =
+            tokio::join!(listener.accept(), client_transport.connect(address));
+        let _server_stream = server_res.unwrap();
+        let client_stream = client_res.unwrap();
+
+        client_stream.get_state().get_remote_static().unwrap().to_vec()
+    }
+
+    #[tokio::test]
+    async fn noise_keypair_persisted_between_restarts() {
+        let data_dir = TempDir::new().unwrap();
+        let noise_key_file = data_dir.path().join(NOISE_KEY_FILE_NAME);
+
+        // The key is generated on the first start
+        assert!(!noise_key_file.exists());
+        let keypair1 = load_or_create_noise_keypair(&noise_key_file).unwrap();
+        assert!(noise_key_file.is_file());
+        let public_key1 = remote_noise_public_key(keypair1.clone()).await;
+        assert_eq!(public_key1, keypair1.public_key());
+
+        // And reloaded after a restart
+        let keypair2 = load_or_create_noise_keypair(&noise_key_file).unwrap();
+        assert_eq!(keypair1.private_key(), keypair2.private_key());
+        let public_key2 = remote_noise_public_key(keypair2).await;
+        assert_eq!(public_key1, public_key2);
+    }
+
+    #[test]
+    fn invalid_noise_key_file() {
+        let data_dir = TempDir::new().unwrap();
+        let noise_key_file = data_dir.path().join(NOISE_KEY_FILE_NAME);
+
+        std::fs::write(&noise_key_file, "not a key").unwrap();
+        let _err = load_or_create_noise_keypair(&noise_key_file).unwrap_err();
+
+        std::fs::write(&noise_key_file, hex::encode([1u8; 16])).unwrap();
+        let _err = load_or_create_noise_keypair(&noise_key_file).unwrap_err();
+    }
 }
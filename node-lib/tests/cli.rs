@@ -75,6 +75,10 @@ fn create_default_config() {
         config.p2p.clone().unwrap_or_default().outbound_connection_timeout,
         None
     );
+    assert_eq!(
+        config.p2p.clone().unwrap_or_default().inbound_connection_timeout,
+        None
+    );
 
     assert_eq!(
         config.rpc.unwrap_or_default().bind_address,
@@ -103,12 +107,17 @@ fn read_config_override_values() {
     let p2p_bind_addr = "127.0.0.1:44444".parse::<SocketAddr>().unwrap();
     let p2p_socks5_proxy = "socks5_proxy";
     let p2p_disable_noise = false;
+    let p2p_noise_key_file = "noise_key_file";
     let p2p_boot_node: IpOrSocketAddress = "127.0.0.1".parse().unwrap();
     let p2p_reserved_node: IpOrSocketAddress = "127.0.0.1".parse().unwrap();
+    let p2p_reserved_nodes_only = true;
+    let p2p_enable_mempool_sync = true;
+    let p2p_enable_port_mapping = true;
     let p2p_max_inbound_connections = 123;
     let p2p_discouragement_threshold = 3;
     let p2p_discouragement_duration = 234;
     let p2p_timeout = NonZeroU64::new(10000).unwrap();
+    let p2p_inbound_timeout = NonZeroU64::new(3).unwrap();
     let p2p_ping_check_period = 30;
     let p2p_ping_timeout = NonZeroU64::new(60).unwrap();
     let p2p_sync_stalling_timeout = NonZeroU64::new(37).unwrap();
@@ -121,8 +130,14 @@ fn read_config_override_values() {
     let rpc_username = "username";
     let rpc_password = "password";
     let rpc_cookie_file = "cookie_file";
+    let metrics_bind_address = "127.0.0.1:9432".parse().unwrap();
     let min_tx_relay_fee_rate = 321;
+    let mempool_max_ancestor_count = 12;
+    let mempool_max_ancestor_size = 50_000;
+    let mempool_max_descendant_count = 13;
+    let mempool_max_descendant_size = 60_000;
     let enable_chainstate_heavy_checks = true;
+    let ibd_write_batching = true;
 
     let options = RunOptions {
         blockprod_min_peers_to_produce_blocks: Some(blockprod_min_peers_to_produce_blocks),
@@ -137,12 +152,17 @@ fn read_config_override_values() {
         p2p_bind_addresses: Some(vec![p2p_bind_addr]),
         p2p_socks5_proxy: Some(p2p_socks5_proxy.to_owned()),
         p2p_disable_noise: Some(p2p_disable_noise),
+        p2p_noise_key_file: Some(p2p_noise_key_file.to_owned()),
         p2p_boot_nodes: Some(vec![p2p_boot_node.clone()]),
         p2p_reserved_nodes: Some(vec![p2p_reserved_node.clone()]),
+        p2p_reserved_nodes_only: Some(p2p_reserved_nodes_only),
+        p2p_enable_mempool_sync: Some(p2p_enable_mempool_sync),
+        p2p_enable_port_mapping: Some(p2p_enable_port_mapping),
         p2p_max_inbound_connections: Some(p2p_max_inbound_connections),
         p2p_discouragement_threshold: Some(p2p_discouragement_threshold),
         p2p_discouragement_duration: Some(p2p_discouragement_duration),
         p2p_outbound_connection_timeout: Some(p2p_timeout),
+        p2p_inbound_connection_timeout: Some(p2p_inbound_timeout),
         p2p_ping_check_period: Some(p2p_ping_check_period),
         p2p_ping_timeout: Some(p2p_ping_timeout),
         p2p_sync_stalling_timeout: Some(p2p_sync_stalling_timeout),
@@ -157,10 +177,18 @@ fn read_config_override_values() {
         rpc_username: Some(rpc_username.to_owned()),
         rpc_password: Some(rpc_password.to_owned()),
         rpc_cookie_file: Some(rpc_cookie_file.to_owned()),
+        metrics_bind_address: Some(metrics_bind_address),
         clean_data: Some(false),
+        dump_config: Some(false),
+        reindex: Some(false),
         min_tx_relay_fee_rate: Some(min_tx_relay_fee_rate),
+        mempool_max_ancestor_count: Some(mempool_max_ancestor_count),
+        mempool_max_ancestor_size: Some(mempool_max_ancestor_size),
+        mempool_max_descendant_count: Some(mempool_max_descendant_count),
+        mempool_max_descendant_size: Some(mempool_max_descendant_size),
         force_allow_run_as_root_outer: Default::default(),
         enable_chainstate_heavy_checks: Some(enable_chainstate_heavy_checks),
+        ibd_write_batching: Some(ibd_write_batching),
     };
     let config = NodeConfigFile::read(&chain_config, &config_path, &options).unwrap();
 
@@ -193,14 +221,34 @@ fn read_config_override_values() {
     );
 
     assert_eq!(
-        config.mempool.unwrap().min_tx_relay_fee_rate,
+        config.mempool.clone().unwrap().min_tx_relay_fee_rate,
         Some(min_tx_relay_fee_rate)
     );
+    assert_eq!(
+        config.mempool.clone().unwrap().max_ancestor_count,
+        Some(mempool_max_ancestor_count)
+    );
+    assert_eq!(
+        config.mempool.clone().unwrap().max_ancestor_size,
+        Some(mempool_max_ancestor_size)
+    );
+    assert_eq!(
+        config.mempool.clone().unwrap().max_descendant_count,
+        Some(mempool_max_descendant_count)
+    );
+    assert_eq!(
+        config.mempool.unwrap().max_descendant_size,
+        Some(mempool_max_descendant_size)
+    );
 
     assert_eq!(
         config.chainstate.clone().unwrap().chainstate_config.enable_heavy_checks,
         Some(enable_chainstate_heavy_checks)
     );
+    assert_eq!(
+        config.chainstate.clone().unwrap().chainstate_config.ibd_write_batching,
+        Some(ibd_write_batching)
+    );
 
     assert_eq!(
         config.p2p.clone().unwrap().networking_enabled,
@@ -208,7 +256,7 @@ fn read_config_override_values() {
     );
     assert_eq!(
         config.p2p.clone().unwrap().bind_addresses,
-        Some(vec!(p2p_bind_addr))
+        Some(vec![p2p_bind_addr.into()])
     );
     assert_eq!(
         config.p2p.clone().unwrap().socks5_proxy,
@@ -218,6 +266,10 @@ fn read_config_override_values() {
         config.p2p.clone().unwrap().disable_noise,
         Some(p2p_disable_noise)
     );
+    assert_eq!(
+        config.p2p.clone().unwrap().noise_key_file,
+        Some(p2p_noise_key_file.to_owned())
+    );
     assert_eq!(
         config.p2p.clone().unwrap().boot_nodes,
         Some(vec!(p2p_boot_node))
@@ -226,6 +278,18 @@ fn read_config_override_values() {
         config.p2p.clone().unwrap().reserved_nodes,
         Some(vec!(p2p_reserved_node))
     );
+    assert_eq!(
+        config.p2p.clone().unwrap().reserved_nodes_only,
+        Some(p2p_reserved_nodes_only)
+    );
+    assert_eq!(
+        config.p2p.clone().unwrap().enable_mempool_sync,
+        Some(p2p_enable_mempool_sync)
+    );
+    assert_eq!(
+        config.p2p.clone().unwrap().enable_port_mapping,
+        Some(p2p_enable_port_mapping)
+    );
     assert_eq!(
         config.p2p.clone().unwrap().max_inbound_connections,
         Some(p2p_max_inbound_connections)
@@ -242,6 +306,10 @@ fn read_config_override_values() {
         config.p2p.clone().unwrap().outbound_connection_timeout,
         Some(p2p_timeout)
     );
+    assert_eq!(
+        config.p2p.clone().unwrap().inbound_connection_timeout,
+        Some(p2p_inbound_timeout)
+    );
     assert_eq!(
         config.p2p.clone().unwrap().ping_check_period,
         Some(p2p_ping_check_period)
@@ -283,5 +351,10 @@ fn read_config_override_values() {
         Some(rpc_cookie_file)
     );
 
+    assert_eq!(
+        config.metrics.as_ref().unwrap().bind_address,
+        Some(metrics_bind_address)
+    );
+
     assert_eq!(config.chainstate.unwrap().storage_backend, backend_type);
 }
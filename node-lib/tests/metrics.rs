@@ -0,0 +1,121 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::BTreeMap, net::SocketAddr};
+
+use rstest::rstest;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use chainstate_test_framework::TestFramework;
+use common::primitives::Idable;
+use networking::transport::BufferedTranscoder;
+use node_lib::metrics::{MetricsServer, METRICS_PATH};
+use test_utils::random::{make_seedable_rng, Seed};
+
+async fn http_get(addr: &SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let request = format!("GET {path} HTTP/1.1\r\nHost: {addr}\r\n\r\n");
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+/// Scrape the metrics and return the samples by their names including the labels
+async fn scrape(addr: &SocketAddr) -> BTreeMap<String, f64> {
+    let response = http_get(addr, METRICS_PATH).await;
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    assert!(head.starts_with("HTTP/1.1 200 OK"));
+    assert!(head.contains("Content-Type: text/plain; version=0.0.4"));
+
+    body.lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| {
+            let (name, value) = line.rsplit_once(' ').unwrap();
+            (name.to_owned(), value.parse().unwrap())
+        })
+        .collect()
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn scrape_metrics(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let server = MetricsServer::new("127.0.0.1:0".parse().unwrap()).await.unwrap();
+    let addr = *server.local_addr();
+
+    let response = http_get(&addr, "/other").await;
+    assert!(response.starts_with("HTTP/1.1 404 Not Found"));
+
+    let before = scrape(&addr).await;
+    for name in [
+        "p2p_connected_peers{direction=\"inbound\",role=\"inbound\"}",
+        "p2p_connected_peers{direction=\"outbound\",role=\"outbound_full_relay\"}",
+        "p2p_ban_events_total",
+        "p2p_bytes_sent_total",
+        "p2p_bytes_received_total",
+        "p2p_sync_blocks_in_flight",
+        "mempool_memory_usage_bytes",
+        "mempool_transactions",
+        "chainstate_best_block_height",
+        "chainstate_tip_age_seconds",
+        "chainstate_block_processing_seconds_bucket{le=\"+Inf\"}",
+        "chainstate_block_processing_seconds_sum",
+        "chainstate_block_processing_seconds_count",
+    ] {
+        assert!(before.contains_key(name), "{name} is missing");
+    }
+
+    // Send some messages through the transport
+    let (stream1, stream2) = tokio::io::duplex(1024);
+    let mut sender = BufferedTranscoder::new(stream1, None);
+    let mut receiver = BufferedTranscoder::<_, Vec<u8>>::new(stream2, None);
+    for _ in 0..3 {
+        sender.send(vec![1u8; 100]).await.unwrap();
+        receiver.recv().await.unwrap();
+    }
+
+    // Process some blocks
+    let blocks_count = 5;
+    let mut tf = TestFramework::builder(&mut rng).build();
+    tf.create_chain(&tf.genesis().get_id().into(), blocks_count, &mut rng).unwrap();
+
+    let after = scrape(&addr).await;
+
+    // The counters can't go down
+    for name in [
+        "p2p_ban_events_total",
+        "p2p_bytes_sent_total",
+        "p2p_bytes_received_total",
+        "chainstate_block_processing_seconds_bucket{le=\"+Inf\"}",
+        "chainstate_block_processing_seconds_count",
+    ] {
+        assert!(after[name] >= before[name], "{name} decreased");
+    }
+
+    assert!(after["p2p_bytes_sent_total"] >= before["p2p_bytes_sent_total"] + 300.0);
+    assert!(after["p2p_bytes_received_total"] >= before["p2p_bytes_received_total"] + 300.0);
+    assert!(
+        after["chainstate_block_processing_seconds_count"]
+            >= before["chainstate_block_processing_seconds_count"] + blocks_count as f64
+    );
+    assert_eq!(after["chainstate_best_block_height"], blocks_count as f64);
+}
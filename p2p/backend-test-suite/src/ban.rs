@@ -126,6 +126,7 @@ where
                 peer_id,
                 common_services: _,
                 protocol_version: _,
+                inbound: _,
                 block_sync_msg_receiver,
                 transaction_sync_msg_receiver: _,
             } => (peer_id, block_sync_msg_receiver),
@@ -150,7 +151,7 @@ where
     });
 
     match peer_mgr_event_receiver.recv().await {
-        Some(PeerManagerEvent::AdjustPeerScore(peer_id, score, _)) => {
+        Some(PeerManagerEvent::AdjustPeerScore(peer_id, score, _, _)) => {
             assert_eq!(peer_id, peer_info2.peer_id);
             assert_eq!(
                 score,
@@ -104,6 +104,7 @@ where
             peer_id: _,
             common_services: _,
             protocol_version: _,
+            inbound: _,
             block_sync_msg_receiver,
             transaction_sync_msg_receiver: _,
         } => block_sync_msg_receiver,
@@ -141,6 +142,7 @@ where
             peer_id: _,
             common_services: _,
             protocol_version: _,
+            inbound: _,
             block_sync_msg_receiver,
             transaction_sync_msg_receiver: _,
         } => block_sync_msg_receiver,
@@ -178,12 +180,17 @@ where
 
         bind_addresses: Vec::new(),
         socks5_proxy: None,
+        socks5_proxy_auth: None,
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Vec::new(),
         reserved_nodes: Vec::new(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_check_period: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
@@ -191,6 +198,13 @@ where
         allow_discover_private_ips: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
     });
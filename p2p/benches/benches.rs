@@ -15,16 +15,28 @@
 
 use std::{collections::BTreeSet, sync::Arc, time::Duration};
 
-use common::chain::config::create_unit_test_config;
+use common::{
+    chain::{
+        block::{timestamp::BlockTimestamp, BlockReward, ConsensusData},
+        config::create_unit_test_config,
+        output_value::OutputValue,
+        signature::inputsig::InputWitness,
+        Block, Destination, SignedTransaction, Transaction, TxInput, TxOutput,
+    },
+    primitives::{Amount, Id, H256},
+};
 use criterion::{criterion_group, criterion_main, Criterion};
 
 use networking::test_helpers::TestAddressMaker;
 use p2p::{
+    message::BlockResponse,
+    net::default_backend::types::Message,
     peer_manager::{address_groups::AddressGroup, peerdb::PeerDb},
     test_helpers::{peerdb_inmemory_store, test_p2p_config},
 };
 use p2p_types::socket_addr_ext::SocketAddrExt;
-use randomness::make_pseudo_rng;
+use randomness::{make_pseudo_rng, Rng};
+use serialization::{strict::DecodeStrict, DecodeAll, Encode};
 
 pub fn peer_db(c: &mut Criterion) {
     let mut rng = make_pseudo_rng();
@@ -59,5 +71,38 @@ pub fn peer_db(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, peer_db);
+/// The cost of checking that a received block is in its canonical encoding
+pub fn block_response_decoding(c: &mut Criterion) {
+    let mut rng = make_pseudo_rng();
+
+    let transactions = (0..1000)
+        .map(|_| {
+            let input = TxInput::from_utxo(Id::<Transaction>::new(H256(rng.gen())).into(), 0);
+            let output = TxOutput::Transfer(
+                OutputValue::Coin(Amount::from_atoms(rng.gen())),
+                Destination::AnyoneCanSpend,
+            );
+            let tx = Transaction::new(0, vec![input], vec![output]).unwrap();
+            SignedTransaction::new(tx, vec![InputWitness::NoSignature(Some(vec![0; 100]))]).unwrap()
+        })
+        .collect();
+    let block = Block::new(
+        transactions,
+        Id::new(H256(rng.gen())),
+        BlockTimestamp::from_int_seconds(rng.gen()),
+        ConsensusData::None,
+        BlockReward::new(vec![]),
+    )
+    .unwrap();
+    let encoded = Message::BlockResponse(BlockResponse::new(block)).encode();
+
+    c.bench_function("BlockResponse decode", |b| {
+        b.iter(|| Message::decode_all(&mut encoded.as_slice()).unwrap())
+    });
+    c.bench_function("BlockResponse strict decode", |b| {
+        b.iter(|| Message::decode_strict(&encoded).unwrap())
+    });
+}
+
+criterion_group!(benches, peer_db, block_response_decoding);
 criterion_main!(benches);
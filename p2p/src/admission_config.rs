@@ -0,0 +1,159 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::str::FromStr;
+
+use crate::protocol::ProtocolVersion;
+
+/// A regular expression matched against the user agents of the peers.
+///
+/// The pattern is not anchored, e.g. "MintlayerCore" matches any user agent containing it.
+#[derive(Debug, Clone)]
+pub struct UserAgentPattern(regex::Regex);
+
+impl UserAgentPattern {
+    pub fn is_match(&self, user_agent: &str) -> bool {
+        self.0.is_match(user_agent)
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl FromStr for UserAgentPattern {
+    type Err = regex::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        regex::Regex::new(s).map(Self)
+    }
+}
+
+impl PartialEq for UserAgentPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for UserAgentPattern {}
+
+impl serde::Serialize for UserAgentPattern {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for UserAgentPattern {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(d)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Filters applied to the peers right after the handshake, on top of the compatibility checks.
+///
+/// The peers rejected by the filters are discouraged, so that they are not dialed again
+/// for a while.
+#[derive(Default, Debug, Clone)]
+pub struct PeerAdmissionConfig {
+    /// The minimum protocol version that a peer must support. Only makes sense if it's above
+    /// the minimum version supported by this node. Applies to the reserved nodes as well.
+    pub min_protocol_version: Option<ProtocolVersion>,
+    /// If not empty, only the peers whose user agent matches one of these patterns are accepted.
+    /// Doesn't apply to the reserved nodes.
+    pub allowed_user_agents: Vec<UserAgentPattern>,
+    /// The peers whose user agent matches one of these patterns are rejected, even if it also
+    /// matches one of the allowed ones. Doesn't apply to the reserved nodes.
+    pub denied_user_agents: Vec<UserAgentPattern>,
+}
+
+impl PeerAdmissionConfig {
+    pub fn is_protocol_version_allowed(&self, protocol_version: ProtocolVersion) -> bool {
+        self.min_protocol_version
+            .map_or(true, |min_version| protocol_version >= min_version)
+    }
+
+    pub fn is_user_agent_allowed(&self, user_agent: &str) -> bool {
+        let allowed = self.allowed_user_agents.is_empty()
+            || self.allowed_user_agents.iter().any(|pattern| pattern.is_match(user_agent));
+        let denied = self.denied_user_agents.iter().any(|pattern| pattern.is_match(user_agent));
+        allowed && !denied
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(patterns: &[&str]) -> Vec<UserAgentPattern> {
+        patterns.iter().map(|pattern| pattern.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn user_agent_filters() {
+        let config = PeerAdmissionConfig::default();
+        assert!(config.is_user_agent_allowed("MintlayerCore"));
+        assert!(config.is_user_agent_allowed(""));
+
+        let config = PeerAdmissionConfig {
+            min_protocol_version: None,
+            allowed_user_agents: patterns(&["^MintlayerCore", "^OtherClient$"]),
+            denied_user_agents: patterns(&["Bad"]),
+        };
+        assert!(config.is_user_agent_allowed("MintlayerCore"));
+        assert!(config.is_user_agent_allowed("MintlayerCoreFork"));
+        assert!(config.is_user_agent_allowed("OtherClient"));
+        assert!(!config.is_user_agent_allowed("OtherClient2"));
+        assert!(!config.is_user_agent_allowed("Scanner"));
+        // The deny list takes precedence
+        assert!(!config.is_user_agent_allowed("MintlayerCoreBad"));
+
+        let config = PeerAdmissionConfig {
+            min_protocol_version: None,
+            allowed_user_agents: Vec::new(),
+            denied_user_agents: patterns(&["Bad"]),
+        };
+        assert!(config.is_user_agent_allowed("Scanner"));
+        assert!(!config.is_user_agent_allowed("BadScanner"));
+    }
+
+    #[test]
+    fn protocol_version_filter() {
+        let config = PeerAdmissionConfig::default();
+        assert!(config.is_protocol_version_allowed(ProtocolVersion::new(2)));
+
+        let config = PeerAdmissionConfig {
+            min_protocol_version: Some(ProtocolVersion::new(5)),
+            allowed_user_agents: Vec::new(),
+            denied_user_agents: Vec::new(),
+        };
+        assert!(!config.is_protocol_version_allowed(ProtocolVersion::new(4)));
+        assert!(config.is_protocol_version_allowed(ProtocolVersion::new(5)));
+        assert!(config.is_protocol_version_allowed(ProtocolVersion::new(6)));
+    }
+
+    #[test]
+    fn pattern_serde() {
+        assert!("(".parse::<UserAgentPattern>().is_err());
+
+        let pattern: UserAgentPattern = serde_json::from_str(r#""^Mintlayer.*""#).unwrap();
+        assert!(pattern.is_match("MintlayerCore"));
+        assert_eq!(
+            serde_json::to_string(&pattern).unwrap(),
+            r#""^Mintlayer.*""#
+        );
+        assert!(serde_json::from_str::<UserAgentPattern>(r#""(""#).is_err());
+    }
+}
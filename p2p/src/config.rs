@@ -13,14 +13,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::net::{IpAddr, SocketAddr};
+use std::net::{AddrParseError, IpAddr, SocketAddr};
+use std::num::NonZeroUsize;
+use std::str::FromStr;
 use std::time::Duration;
 
 use common::primitives::user_agent::UserAgent;
-use utils::make_config_setting;
+use utils::{const_nz_usize, make_config_setting};
 use utils_networking::IpOrSocketAddress;
 
 use crate::{
+    admission_config::PeerAdmissionConfig,
     ban_config::BanConfig,
     net::types::services::{Service, Services},
     peer_manager::config::PeerManagerConfig,
@@ -28,6 +31,7 @@ use crate::{
 };
 
 make_config_setting!(OutboundConnectionTimeout, Duration, Duration::from_secs(10));
+make_config_setting!(InboundConnectionTimeout, Duration, Duration::from_secs(5));
 make_config_setting!(NodeTypeSetting, NodeType, NodeType::Full);
 make_config_setting!(AllowDiscoverPrivateIps, bool, false);
 make_config_setting!(PingCheckPeriod, Duration, Duration::from_secs(60));
@@ -35,6 +39,20 @@ make_config_setting!(PingTimeout, Duration, Duration::from_secs(150));
 make_config_setting!(MaxClockDiff, Duration, Duration::from_secs(10));
 make_config_setting!(SyncStallingTimeout, Duration, Duration::from_secs(25));
 make_config_setting!(PeerHandshakeTimeout, Duration, Duration::from_secs(10));
+make_config_setting!(ForkAlertThreshold, usize, 3);
+make_config_setting!(ForkAlertDuration, Duration, Duration::from_secs(10 * 60));
+make_config_setting!(
+    MaxMempoolTxBroadcastsPerPeer,
+    NonZeroUsize,
+    const_nz_usize!(100)
+);
+make_config_setting!(MaxBufferedBlockResponseBytes, usize, 16 * 1024 * 1024);
+make_config_setting!(
+    MaxInFlightBlockResponsesPerPeer,
+    NonZeroUsize,
+    const_nz_usize!(2)
+);
+make_config_setting!(MaxLowWorkBlocksInFlight, usize, 50);
 
 /// A node type.
 #[derive(Debug, Copy, Clone)]
@@ -64,15 +82,60 @@ impl From<NodeType> for Services {
     }
 }
 
+/// The transport used by a listener.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TransportProfile {
+    /// Noise-encrypted TCP.
+    Noise,
+    /// Unencrypted TCP, only allowed on loopback addresses on regtest.
+    Plaintext,
+}
+
+/// An address to bind P2P to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BindAddress {
+    pub address: SocketAddr,
+    /// The transport to use instead of the node's default one.
+    pub transport: Option<TransportProfile>,
+}
+
+impl From<SocketAddr> for BindAddress {
+    fn from(address: SocketAddr) -> Self {
+        Self {
+            address,
+            transport: None,
+        }
+    }
+}
+
+impl FromStr for BindAddress {
+    type Err = AddrParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<SocketAddr>().map(Into::into)
+    }
+}
+
 /// The p2p subsystem configuration.
 #[derive(Debug)]
 pub struct P2pConfig {
-    /// Address to bind P2P to.
-    pub bind_addresses: Vec<SocketAddr>,
+    /// Addresses to bind P2P to.
+    pub bind_addresses: Vec<BindAddress>,
     /// SOCKS5 proxy.
     pub socks5_proxy: Option<String>,
+    /// SOCKS5 proxy username and password.
+    pub socks5_proxy_auth: Option<(String, String)>,
     /// Disable p2p encryption (for tests only).
     pub disable_noise: Option<bool>,
+    /// Only communicate with the reserved nodes: inbound connections from other addresses are
+    /// refused, the DNS seeds are not queried, discovered addresses are not stored in the peer db
+    /// and the node's own address is not advertised.
+    pub reserved_nodes_only: Option<bool>,
+    /// Request the mempool contents from the outbound peers after connecting to them.
+    pub enable_mempool_sync: Option<bool>,
+    /// Map the p2p port on the local gateway with UPnP or NAT-PMP and announce the external
+    /// address to peers.
+    pub enable_port_mapping: Option<bool>,
     /// Optional list of initial node addresses.
     /// Boot node addresses are added to PeerDb as regular discovered addresses.
     pub boot_nodes: Vec<IpOrSocketAddress>,
@@ -86,6 +149,9 @@ pub struct P2pConfig {
     pub ban_config: BanConfig,
     /// The outbound connection timeout value in seconds.
     pub outbound_connection_timeout: OutboundConnectionTimeout,
+    /// The time an inbound connection has to complete the handshake; slow peers are disconnected
+    /// without a ban. The handshake is also limited by `peer_handshake_timeout`.
+    pub inbound_connection_timeout: InboundConnectionTimeout,
     /// How often send ping requests to peers
     pub ping_check_period: PingCheckPeriod,
     /// When a peer is detected as dead and disconnected
@@ -103,6 +169,27 @@ pub struct P2pConfig {
     pub user_agent: UserAgent,
     /// A timeout after which a peer is disconnected.
     pub sync_stalling_timeout: SyncStallingTimeout,
+    /// The minimum number of peers reporting a best block different from the local tip
+    /// for the node to suspect that it's on a minority fork. Zero disables the detection.
+    pub fork_alert_threshold: ForkAlertThreshold,
+    /// How long the peers must keep reporting a different best block before a possible fork
+    /// is reported.
+    pub fork_alert_duration: ForkAlertDuration,
+    /// The maximum number of transaction announcements sent to a single peer per sync cycle;
+    /// the rest are sent in the following cycles, highest fee rate first.
+    pub max_mempool_tx_broadcasts_per_peer: MaxMempoolTxBroadcastsPerPeer,
+    /// The maximum total size of the blocks sent to all the peers that haven't been written
+    /// to the sockets yet.
+    pub max_buffered_block_response_bytes: MaxBufferedBlockResponseBytes,
+    /// The maximum number of block responses to a single peer that haven't been written
+    /// to the socket yet; the peers take turns sending their blocks.
+    pub max_in_flight_block_responses_per_peer: MaxInFlightBlockResponsesPerPeer,
+    /// The maximum number of blocks in flight from all the peers that aren't a part of
+    /// the header chain with the most work; such blocks are still downloaded to monitor
+    /// the forks, but the best chain takes priority.
+    pub max_low_work_blocks_in_flight: MaxLowWorkBlocksInFlight,
+    /// Filters on the protocol versions and user agents of the peers.
+    pub peer_admission_config: PeerAdmissionConfig,
     /// Various settings used internally by the peer manager.
     pub peer_manager_config: PeerManagerConfig,
     /// Various limits related to the protocol; these should only be overridden in tests.
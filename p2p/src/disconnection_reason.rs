@@ -65,6 +65,10 @@ pub enum DisconnectionReason {
     InsufficientServices { needed_services: Services },
     #[error("Networking disabled")]
     NetworkingDisabled,
+    #[error("Only reserved nodes are accepted")]
+    NotReservedNode,
+    #[error("Your software is incompatible")]
+    Incompatible,
 }
 
 impl DisconnectionReason {
@@ -128,6 +132,16 @@ impl DisconnectionReason {
                     needed_services: *needed_services,
                 }),
                 ConnectionValidationError::NetworkingDisabled => Some(Self::NetworkingDisabled),
+                ConnectionValidationError::NotReservedNode { address: _ } => {
+                    Some(Self::NotReservedNode)
+                }
+                ConnectionValidationError::ProtocolVersionBelowMinimum {
+                    peer_protocol_version: _,
+                    min_protocol_version: _,
+                }
+                | ConnectionValidationError::UserAgentNotAllowed { user_agent: _ } => {
+                    Some(Self::Incompatible)
+                }
             },
         }
     }
@@ -25,7 +25,11 @@ use networking::error::NetworkingError;
 use p2p_types::{services::Services, socket_address::SocketAddress, PeerId};
 use utils::try_as::TryAsRef;
 
-use crate::{net::types::PeerRole, peer_manager::peerdb_common, protocol::ProtocolVersion};
+use crate::{
+    net::types::PeerRole,
+    peer_manager::peerdb_common,
+    protocol::{ProtocolVersion, SizeLimitedMessageType},
+};
 
 /// Errors related to invalid data/peer information that results in connection getting closed
 /// and the peer getting banned.
@@ -47,6 +51,11 @@ pub enum ProtocolError {
     DisconnectedHeaders,
     #[error("Peer sent a message ({0}) that wasn't expected")]
     UnexpectedMessage(String),
+    #[error("Peer sent a message ({received_message}) that wasn't expected in the current state, expected state: {expected_state}")]
+    UnexpectedMessageForState {
+        expected_state: String,
+        received_message: String,
+    },
     #[error("Peer sent a block ({0}) that wasn't requested")]
     UnsolicitedBlockReceived(Id<Block>),
     #[error("Peer sent block {expected_block_id} while it was expected to send {actual_block_id}")]
@@ -64,6 +73,14 @@ pub enum ProtocolError {
     DuplicatedTransactionAnnouncement(Id<Transaction>),
     #[error("Announced too many transactions (limit is {0})")]
     TransactionAnnouncementLimitExceeded(usize),
+    #[error("Size {size} of a {message_type} message exceeds the limit {max_size}")]
+    MessageSizeLimitExceeded {
+        message_type: SizeLimitedMessageType,
+        size: usize,
+        max_size: usize,
+    },
+    #[error("Peer keeps requesting the headers that were already sent without requesting blocks")]
+    ExcessiveHeaderRequests,
 }
 
 /// Peer state errors (Errors either for an individual peer or for the [`PeerManager`](crate::peer_manager::PeerManager))
@@ -139,6 +156,35 @@ pub enum ConnectionValidationError {
     },
     #[error("Networking disabled")]
     NetworkingDisabled,
+    #[error("Address {address} is not a reserved node")]
+    NotReservedNode { address: String },
+    #[error("Peer protocol version {peer_protocol_version:?} is below the configured minimum {min_protocol_version:?}")]
+    ProtocolVersionBelowMinimum {
+        peer_protocol_version: ProtocolVersion,
+        min_protocol_version: ProtocolVersion,
+    },
+    #[error("Peer user agent '{user_agent}' is not allowed")]
+    UserAgentNotAllowed { user_agent: String },
+}
+
+impl ConnectionValidationError {
+    /// Whether the peer was rejected by the admission filters configured by the operator
+    pub fn is_rejected_by_admission_filter(&self) -> bool {
+        match self {
+            Self::ProtocolVersionBelowMinimum { .. } | Self::UserAgentNotAllowed { .. } => true,
+            Self::UnsupportedProtocol { .. }
+            | Self::TimeDiff { .. }
+            | Self::DifferentNetwork { .. }
+            | Self::TooManyInboundPeersAndThisOneIsDiscouraged
+            | Self::TooManyInboundPeersAndCannotEvictAnyone
+            | Self::AddressBanned { .. }
+            | Self::AddressDiscouraged { .. }
+            | Self::NoCommonServices
+            | Self::InsufficientServices { .. }
+            | Self::NetworkingDisabled
+            | Self::NotReservedNode { .. } => false,
+        }
+    }
 }
 
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
@@ -186,6 +232,87 @@ pub enum P2pError {
     SyncError(#[from] SyncError),
 }
 
+impl P2pError {
+    /// Whether the error is expected when the other tasks and the peer connections are torn down
+    /// during shutdown, in which case it shouldn't be reported as a failure.
+    pub fn is_expected_on_shutdown(&self) -> bool {
+        match self {
+            P2pError::ChannelClosed => true,
+            P2pError::NetworkingError(NetworkingError::IoError(kind)) => matches!(
+                kind,
+                std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+            ),
+            _ => false,
+        }
+    }
+
+    /// The name of the error variant, used as the reason of the peer score adjustments
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            P2pError::NetworkingError(_) => "NetworkingError",
+            P2pError::ProtocolError(_) => "ProtocolError",
+            P2pError::DialError(_) => "DialError",
+            P2pError::ChannelClosed => "ChannelClosed",
+            P2pError::PeerError(_) => "PeerError",
+            P2pError::SubsystemFailure => "SubsystemFailure",
+            P2pError::ChainstateError(_) => "ChainstateError",
+            P2pError::StorageFailure(_) => "StorageFailure",
+            P2pError::NoiseHandshakeError(_) => "NoiseHandshakeError",
+            P2pError::InvalidConfigurationValue(_) => "InvalidConfigurationValue",
+            P2pError::InvalidStorageState(_) => "InvalidStorageState",
+            P2pError::PeerDbStorageVersionMismatch {
+                expected_version: _,
+                actual_version: _,
+            } => "PeerDbStorageVersionMismatch",
+            P2pError::MempoolError(_) => "MempoolError",
+            P2pError::ConnectionValidationFailed(_) => "ConnectionValidationFailed",
+            P2pError::SyncError(_) => "SyncError",
+        }
+    }
+
+    /// The HTTP-style code of the error when it's returned by an RPC call
+    pub fn to_rpc_error_code(&self) -> i32 {
+        match self {
+            P2pError::ChannelClosed
+            | P2pError::SubsystemFailure
+            | P2pError::ConnectionValidationFailed(ConnectionValidationError::NetworkingDisabled) => {
+                rpc::SERVICE_UNAVAILABLE_CODE
+            }
+            P2pError::InvalidConfigurationValue(_) => rpc::INVALID_INPUT_CODE,
+            P2pError::PeerError(PeerError::PeerDoesntExist) => rpc::NOT_FOUND_CODE,
+            P2pError::DialError(DialError::AttemptToDialSelf)
+            | P2pError::PeerError(PeerError::AlreadyConnected { .. } | PeerError::Pending(_))
+            | P2pError::ConnectionValidationFailed(
+                ConnectionValidationError::AddressBanned { .. }
+                | ConnectionValidationError::AddressDiscouraged { .. },
+            ) => rpc::UNPROCESSABLE_CODE,
+            P2pError::ChainstateError(e) => e.to_rpc_error_code(),
+            P2pError::NetworkingError(_)
+            | P2pError::ProtocolError(_)
+            | P2pError::DialError(_)
+            | P2pError::PeerError(_)
+            | P2pError::StorageFailure(_)
+            | P2pError::NoiseHandshakeError(_)
+            | P2pError::InvalidStorageState(_)
+            | P2pError::PeerDbStorageVersionMismatch {
+                expected_version: _,
+                actual_version: _,
+            }
+            | P2pError::MempoolError(_)
+            | P2pError::ConnectionValidationFailed(_)
+            | P2pError::SyncError(_) => rpc::GENERIC_ERROR_CODE,
+        }
+    }
+}
+
+impl From<P2pError> for rpc::Error {
+    fn from(e: P2pError) -> Self {
+        rpc::categorized_error(e.to_rpc_error_code(), &e, e.variant_name())
+    }
+}
+
 impl From<DialError> for P2pError {
     fn from(e: DialError) -> P2pError {
         P2pError::DialError(e)
@@ -253,6 +380,10 @@ impl BanScore for ProtocolError {
             ProtocolError::DuplicatedBlockRequest(_) => 20,
             ProtocolError::DisconnectedHeaders => 20,
             ProtocolError::UnexpectedMessage(_) => 20,
+            ProtocolError::UnexpectedMessageForState {
+                expected_state: _,
+                received_message: _,
+            } => 20,
             ProtocolError::UnsolicitedBlockReceived(_) => 20,
             ProtocolError::BlocksReceivedInWrongOrder {
                 expected_block_id: _,
@@ -263,6 +394,12 @@ impl BanScore for ProtocolError {
             ProtocolError::AddressListLimitExceeded => 100,
             ProtocolError::DuplicatedTransactionAnnouncement(_) => 20,
             ProtocolError::TransactionAnnouncementLimitExceeded(_) => 20,
+            ProtocolError::MessageSizeLimitExceeded {
+                message_type: _,
+                size: _,
+                max_size: _,
+            } => 100,
+            ProtocolError::ExcessiveHeaderRequests => 20,
         }
     }
 }
@@ -300,3 +437,39 @@ impl TryAsRef<storage::Error> for P2pError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use super::*;
+
+    #[rstest::rstest]
+    #[case(ErrorKind::BrokenPipe, true)]
+    #[case(ErrorKind::ConnectionReset, true)]
+    #[case(ErrorKind::ConnectionAborted, true)]
+    #[case(ErrorKind::ConnectionRefused, false)]
+    #[case(ErrorKind::TimedOut, false)]
+    #[case(ErrorKind::AddrInUse, false)]
+    #[case(ErrorKind::AddrNotAvailable, false)]
+    #[case(ErrorKind::UnexpectedEof, false)]
+    fn io_errors(#[case] kind: ErrorKind, #[case] expected_on_shutdown: bool) {
+        let err = P2pError::NetworkingError(NetworkingError::IoError(kind));
+        assert_eq!(err.is_expected_on_shutdown(), expected_on_shutdown);
+        // Network failures are not the peer's fault
+        assert_eq!(err.ban_score(), 0);
+    }
+
+    #[test]
+    fn other_errors_on_shutdown() {
+        assert!(P2pError::ChannelClosed.is_expected_on_shutdown());
+        assert!(!P2pError::SubsystemFailure.is_expected_on_shutdown());
+        assert!(
+            !P2pError::DialError(DialError::ConnectionRefusedOrTimedOut).is_expected_on_shutdown()
+        );
+        assert!(
+            !P2pError::NetworkingError(NetworkingError::ProxyError("test".to_owned()))
+                .is_expected_on_shutdown()
+        );
+    }
+}
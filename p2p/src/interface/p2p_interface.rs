@@ -15,14 +15,19 @@
 
 use std::{sync::Arc, time::Duration};
 
+use chainstate::PossibleFork;
 use common::{chain::SignedTransaction, primitives::time::Time};
 use mempool::tx_options::TxOptionsOverrides;
 use p2p_types::{
-    bannable_address::BannableAddress, p2p_event::P2pEvent, socket_address::SocketAddress,
+    bannable_address::BannableAddress, p2p_event::P2pEvent, socket_address::SocketAddress, NodeId,
 };
 use utils_networking::IpOrSocketAddress;
 
-use crate::{interface::types::ConnectedPeer, types::peer_id::PeerId};
+use crate::{
+    interface::types::{BannedAddress, ConnectedPeer, MisbehaviorEvent},
+    sync::sync_status::SyncStateSnapshot,
+    types::peer_id::PeerId,
+};
 
 #[async_trait::async_trait]
 pub trait P2pInterface: Send + Sync {
@@ -31,15 +36,36 @@ pub trait P2pInterface: Send + Sync {
     async fn connect(&mut self, addr: IpOrSocketAddress) -> crate::Result<()>;
     async fn disconnect(&mut self, peer_id: PeerId) -> crate::Result<()>;
 
-    async fn list_banned(&self) -> crate::Result<Vec<(BannableAddress, Time)>>;
+    async fn list_banned(&self) -> crate::Result<Vec<BannedAddress>>;
     async fn ban(&mut self, addr: BannableAddress, duration: Duration) -> crate::Result<()>;
     async fn unban(&mut self, addr: BannableAddress) -> crate::Result<()>;
 
     async fn list_discouraged(&self) -> crate::Result<Vec<(BannableAddress, Time)>>;
 
+    /// Return up to `limit` most recent misbehavior events, oldest first, optionally only
+    /// the ones of the given peer.
+    async fn get_misbehavior_log(
+        &self,
+        limit: usize,
+        peer_filter: Option<PeerId>,
+    ) -> crate::Result<Vec<MisbehaviorEvent>>;
+
+    /// Return the connected peers whose ban score is at least `min_score`, together with
+    /// their addresses and scores.
+    async fn get_misbehaving_peers(
+        &self,
+        min_score: u32,
+    ) -> crate::Result<Vec<(PeerId, SocketAddress, u32)>>;
+
+    /// Return the ban score of a connected peer, `None` if the peer is not connected.
+    async fn get_peer_ban_score(&self, peer_id: PeerId) -> crate::Result<Option<u32>>;
+
     async fn get_peer_count(&self) -> crate::Result<usize>;
     async fn get_bind_addresses(&self) -> crate::Result<Vec<SocketAddress>>;
+    fn get_node_id(&self) -> NodeId;
     async fn get_connected_peers(&self) -> crate::Result<Vec<ConnectedPeer>>;
+    async fn get_sync_snapshot(&self) -> crate::Result<SyncStateSnapshot>;
+    async fn get_possible_fork(&self) -> crate::Result<Option<PossibleFork>>;
 
     async fn get_reserved_nodes(&self) -> crate::Result<Vec<SocketAddress>>;
     async fn add_reserved_node(&mut self, addr: IpOrSocketAddress) -> crate::Result<()>;
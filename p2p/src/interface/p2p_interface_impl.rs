@@ -15,20 +15,25 @@
 
 use std::{sync::Arc, time::Duration};
 
+use chainstate::PossibleFork;
 use common::{chain::SignedTransaction, primitives::time::Time};
 use mempool::{
     tx_options::{TxOptions, TxOptionsOverrides},
     tx_origin::LocalTxOrigin,
 };
-use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress};
+use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress, NodeId};
 use utils_networking::IpOrSocketAddress;
 
 use crate::{
     disconnection_reason::DisconnectionReason,
     error::P2pError,
-    interface::{p2p_interface::P2pInterface, types::ConnectedPeer},
+    interface::{
+        p2p_interface::P2pInterface,
+        types::{BannedAddress, ConnectedPeer, MisbehaviorEvent},
+    },
     net::NetworkingService,
     peer_manager_event::PeerDisconnectionDbAction,
+    sync::sync_status::SyncStateSnapshot,
     types::peer_id::PeerId,
     utils::oneshot_nofail,
     MessagingService, P2p, P2pEvent, PeerManagerEvent,
@@ -72,7 +77,7 @@ where
         response_receiver.await?
     }
 
-    async fn list_banned(&self) -> crate::Result<Vec<(BannableAddress, Time)>> {
+    async fn list_banned(&self) -> crate::Result<Vec<BannedAddress>> {
         let (response_sender, response_receiver) = oneshot_nofail::channel();
         self.peer_mgr_event_sender
             .send(PeerManagerEvent::ListBanned(response_sender))
@@ -106,6 +111,47 @@ where
         Ok(list)
     }
 
+    async fn get_misbehavior_log(
+        &self,
+        limit: usize,
+        peer_filter: Option<PeerId>,
+    ) -> crate::Result<Vec<MisbehaviorEvent>> {
+        let (response_sender, response_receiver) = oneshot_nofail::channel();
+        self.peer_mgr_event_sender
+            .send(PeerManagerEvent::GetMisbehaviorLog(
+                limit,
+                peer_filter,
+                response_sender,
+            ))
+            .map_err(|_| P2pError::ChannelClosed)?;
+        let events = response_receiver.await?;
+        Ok(events)
+    }
+
+    async fn get_misbehaving_peers(
+        &self,
+        min_score: u32,
+    ) -> crate::Result<Vec<(PeerId, SocketAddress, u32)>> {
+        let (response_sender, response_receiver) = oneshot_nofail::channel();
+        self.peer_mgr_event_sender
+            .send(PeerManagerEvent::GetMisbehavingPeers(
+                min_score,
+                response_sender,
+            ))
+            .map_err(|_| P2pError::ChannelClosed)?;
+        let peers = response_receiver.await?;
+        Ok(peers)
+    }
+
+    async fn get_peer_ban_score(&self, peer_id: PeerId) -> crate::Result<Option<u32>> {
+        let (response_sender, response_receiver) = oneshot_nofail::channel();
+        self.peer_mgr_event_sender
+            .send(PeerManagerEvent::GetPeerBanScore(peer_id, response_sender))
+            .map_err(|_| P2pError::ChannelClosed)?;
+        let score = response_receiver.await?;
+        Ok(score)
+    }
+
     async fn get_peer_count(&self) -> crate::Result<usize> {
         let (response_sender, response_receiver) = oneshot_nofail::channel();
         self.peer_mgr_event_sender
@@ -120,6 +166,10 @@ where
         Ok(response_receiver.await?)
     }
 
+    fn get_node_id(&self) -> NodeId {
+        self.node_id
+    }
+
     async fn get_connected_peers(&self) -> crate::Result<Vec<ConnectedPeer>> {
         let (response_sender, response_receiver) = oneshot_nofail::channel();
         self.peer_mgr_event_sender
@@ -127,6 +177,20 @@ where
         Ok(response_receiver.await?)
     }
 
+    async fn get_sync_snapshot(&self) -> crate::Result<SyncStateSnapshot> {
+        let (response_sender, response_receiver) = oneshot_nofail::channel();
+        self.peer_mgr_event_sender
+            .send(PeerManagerEvent::GetSyncSnapshot(response_sender))?;
+        Ok(response_receiver.await?)
+    }
+
+    async fn get_possible_fork(&self) -> crate::Result<Option<PossibleFork>> {
+        let (response_sender, response_receiver) = oneshot_nofail::channel();
+        self.peer_mgr_event_sender
+            .send(PeerManagerEvent::GetPossibleFork(response_sender))?;
+        Ok(response_receiver.await?)
+    }
+
     async fn get_reserved_nodes(&self) -> crate::Result<Vec<SocketAddress>> {
         let (response_sender, response_receiver) = oneshot_nofail::channel();
         self.peer_mgr_event_sender
@@ -158,7 +222,9 @@ where
         options: TxOptionsOverrides,
     ) -> crate::Result<()> {
         let origin = LocalTxOrigin::P2p;
-        let options = TxOptions::default_for(origin.into()).with_overrides(options);
+        let options = TxOptions::default_for(origin.into())
+            .with_overrides(options)
+            .map_err(mempool::error::Error::from)?;
         let res = self
             .mempool_handle
             .call_mut(move |mempool| mempool.add_transaction_local(tx, origin, options))
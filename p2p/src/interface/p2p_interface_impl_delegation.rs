@@ -19,14 +19,18 @@ use std::{
     time::Duration,
 };
 
+use chainstate::PossibleFork;
 use common::{chain::SignedTransaction, primitives::time::Time};
 use mempool::tx_options::TxOptionsOverrides;
-use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress};
+use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress, NodeId};
 use utils_networking::IpOrSocketAddress;
 
-use crate::{types::peer_id::PeerId, P2pEvent};
+use crate::{sync::sync_status::SyncStateSnapshot, types::peer_id::PeerId, P2pEvent};
 
-use super::{p2p_interface::P2pInterface, types::ConnectedPeer};
+use super::{
+    p2p_interface::P2pInterface,
+    types::{BannedAddress, ConnectedPeer, MisbehaviorEvent},
+};
 
 #[async_trait::async_trait]
 impl<T: Deref<Target = dyn P2pInterface> + DerefMut<Target = dyn P2pInterface> + Send + Sync>
@@ -44,7 +48,7 @@ impl<T: Deref<Target = dyn P2pInterface> + DerefMut<Target = dyn P2pInterface> +
         self.deref_mut().disconnect(peer_id).await
     }
 
-    async fn list_banned(&self) -> crate::Result<Vec<(BannableAddress, Time)>> {
+    async fn list_banned(&self) -> crate::Result<Vec<BannedAddress>> {
         self.deref().list_banned().await
     }
 
@@ -60,6 +64,25 @@ impl<T: Deref<Target = dyn P2pInterface> + DerefMut<Target = dyn P2pInterface> +
         self.deref().list_discouraged().await
     }
 
+    async fn get_misbehavior_log(
+        &self,
+        limit: usize,
+        peer_filter: Option<PeerId>,
+    ) -> crate::Result<Vec<MisbehaviorEvent>> {
+        self.deref().get_misbehavior_log(limit, peer_filter).await
+    }
+
+    async fn get_misbehaving_peers(
+        &self,
+        min_score: u32,
+    ) -> crate::Result<Vec<(PeerId, SocketAddress, u32)>> {
+        self.deref().get_misbehaving_peers(min_score).await
+    }
+
+    async fn get_peer_ban_score(&self, peer_id: PeerId) -> crate::Result<Option<u32>> {
+        self.deref().get_peer_ban_score(peer_id).await
+    }
+
     async fn get_peer_count(&self) -> crate::Result<usize> {
         self.deref().get_peer_count().await
     }
@@ -68,10 +91,22 @@ impl<T: Deref<Target = dyn P2pInterface> + DerefMut<Target = dyn P2pInterface> +
         self.deref().get_bind_addresses().await
     }
 
+    fn get_node_id(&self) -> NodeId {
+        self.deref().get_node_id()
+    }
+
     async fn get_connected_peers(&self) -> crate::Result<Vec<ConnectedPeer>> {
         self.deref().get_connected_peers().await
     }
 
+    async fn get_sync_snapshot(&self) -> crate::Result<SyncStateSnapshot> {
+        self.deref().get_sync_snapshot().await
+    }
+
+    async fn get_possible_fork(&self) -> crate::Result<Option<PossibleFork>> {
+        self.deref().get_possible_fork().await
+    }
+
     async fn get_reserved_nodes(&self) -> crate::Result<Vec<SocketAddress>> {
         self.deref().get_reserved_nodes().await
     }
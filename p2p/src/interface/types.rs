@@ -13,7 +13,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use p2p_types::socket_address::SocketAddress;
+use common::primitives::time::Time;
+use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress, NodeId};
 use serde::{Deserialize, Serialize};
 
 use crate::{net::types::PeerRole, types::peer_id::PeerId};
@@ -35,6 +36,9 @@ pub struct ConnectedPeer {
 
     pub software_version: String,
 
+    /// The node id advertised by the peer, if any
+    pub peer_node_id: Option<NodeId>,
+
     /// Time spent waiting for a current ping response, in milliseconds
     pub ping_wait: Option<u64>,
 
@@ -44,3 +48,35 @@ pub struct ConnectedPeer {
     /// Min time for a ping roundtrip, in milliseconds
     pub ping_min: Option<u64>,
 }
+
+/// A peer score adjustment recorded in the misbehavior log.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, rpc_description::HasValueHint)]
+pub struct MisbehaviorEvent {
+    pub time: Time,
+
+    /// The id of the connected peer, if the peer misbehaved before the handshake was completed
+    /// there is none.
+    pub peer_id: Option<PeerId>,
+
+    pub address: SocketAddress,
+
+    /// The name of the error variant the score was adjusted for
+    pub reason: String,
+
+    pub score_adjustment: u32,
+
+    /// The peer score after the adjustment
+    pub total_score: u32,
+}
+
+/// Helper type used to return information about a banned address from RPC.
+#[derive(Clone, Debug, Serialize, Deserialize, rpc_description::HasValueHint)]
+pub struct BannedAddress {
+    pub address: BannableAddress,
+
+    /// The time the ban expires
+    pub banned_until: Time,
+
+    /// The most recent misbehavior events for the address, oldest first
+    pub recent_misbehavior: Vec<MisbehaviorEvent>,
+}
@@ -13,14 +13,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod admission_config;
 pub mod ban_config;
 pub mod config;
 pub mod disconnection_reason;
 pub mod error;
 pub mod interface;
 pub mod message;
+pub mod metrics;
 pub mod net;
 pub mod peer_manager;
+pub mod port_mapping;
 pub mod protocol;
 pub mod rpc;
 pub mod sync;
@@ -54,14 +57,14 @@ use interface::p2p_interface::P2pInterface;
 use logging::log;
 use mempool::MempoolHandle;
 use networking::transport::{
-    NoiseEncryptionAdapter, NoiseSocks5Transport, NoiseTcpTransport, Socks5TransportSocket,
-    TcpTransportSocket,
+    DualTransportSocket, NoiseEncryptionAdapter, NoiseEncryptionAdapterMaker, NoiseSocks5Transport,
+    NoiseTcpTransport, NoiseTcpWithPlaintextTransport, Socks5TransportSocket, TcpTransportSocket,
 };
 use peer_manager::peerdb::storage::PeerDbStorage;
-use types::socket_address::SocketAddress;
+use types::{socket_address::SocketAddress, NodeId};
 
 use crate::{
-    config::P2pConfig,
+    config::{BindAddress, P2pConfig, TransportProfile},
     error::P2pError,
     net::{
         default_backend::DefaultNetworkingService, ConnectivityService, MessagingService,
@@ -69,6 +72,7 @@ use crate::{
     },
 };
 
+pub use networking::transport::NoiseStaticKeypair;
 pub use p2p_types as types;
 
 pub use crate::{
@@ -79,6 +83,10 @@ pub use crate::{
 /// Result type with P2P errors
 pub type Result<T> = core::result::Result<T, P2pError>;
 
+/// How long to wait for the p2p tasks to finish on shutdown. Should be shorter than the shutdown
+/// timeout of the subsystem, so that the stuck tasks are reported.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
 struct P2p<T: NetworkingService> {
     /// A sender for the peer manager events.
     peer_mgr_event_sender: mpsc::UnboundedSender<PeerManagerEvent>,
@@ -93,9 +101,13 @@ struct P2p<T: NetworkingService> {
     backend_task: JoinHandle<()>,
     peer_manager_task: JoinHandle<()>,
     sync_manager_task: JoinHandle<()>,
+    /// The shutdown sender and the task of the port mapping, if it's enabled.
+    port_mapping: Option<(oneshot::Sender<()>, JoinHandle<()>)>,
 
     subscribers_sender: mpsc::UnboundedSender<P2pEventHandler>,
 
+    node_id: NodeId,
+
     _phantom: PhantomData<T>,
 }
 
@@ -120,6 +132,7 @@ where
         mempool_handle: MempoolHandle,
         time_getter: TimeGetter,
         peerdb_storage: S,
+        node_id: NodeId,
     ) -> Result<Self> {
         let shutdown = Arc::new(SeqCstAtomicBool::new(false));
         let (backend_shutdown_sender, shutdown_receiver) = oneshot::channel();
@@ -148,6 +161,14 @@ where
         // a `oneshot::channel` object that must be used to send the response.
         let (peer_mgr_event_sender, peer_mgr_event_receiver) = mpsc::unbounded_channel();
 
+        let port_mapping = Self::start_port_mapping(
+            networking_enabled,
+            &p2p_config,
+            conn.local_addresses(),
+            time_getter.clone(),
+            peer_mgr_event_sender.clone(),
+        );
+
         let peer_manager = peer_manager::PeerManager::<T, _>::new(
             networking_enabled,
             Arc::clone(&chain_config),
@@ -161,8 +182,8 @@ where
         let peer_manager_task = logging::spawn_in_current_span(async move {
             match peer_manager.run().await {
                 Ok(never) => match never {},
-                // The channel can be closed during the shutdown process.
-                Err(P2pError::ChannelClosed) if shutdown_.load() => {
+                // The channels and the peer connections can be closed during the shutdown process.
+                Err(e) if shutdown_.load() && e.is_expected_on_shutdown() => {
                     log::info!("Peer manager is shut down");
                 }
                 Err(e) => {
@@ -186,8 +207,8 @@ where
         let sync_manager_task = logging::spawn_in_current_span(async move {
             match sync_manager.run().await {
                 Ok(never) => match never {},
-                // The channel can be closed during the shutdown process.
-                Err(P2pError::ChannelClosed) if shutdown_.load() => {
+                // The channels and the peer connections can be closed during the shutdown process.
+                Err(e) if shutdown_.load() && e.is_expected_on_shutdown() => {
                     log::info!("Sync manager is shut down");
                 }
                 Err(e) => {
@@ -205,50 +226,141 @@ where
             backend_task,
             peer_manager_task,
             sync_manager_task,
+            port_mapping,
             subscribers_sender,
+            node_id,
             _phantom: PhantomData,
         })
     }
 }
 
+impl<T: NetworkingService> P2p<T> {
+    /// Start the port mapping task if it's enabled and can be useful.
+    ///
+    /// The port of the first non-loopback IPv4 listening address is mapped, because the port
+    /// mapping protocols only support IPv4.
+    fn start_port_mapping(
+        networking_enabled: bool,
+        p2p_config: &P2pConfig,
+        local_addresses: &[SocketAddress],
+        time_getter: TimeGetter,
+        peer_mgr_event_sender: mpsc::UnboundedSender<PeerManagerEvent>,
+    ) -> Option<(oneshot::Sender<()>, JoinHandle<()>)> {
+        if p2p_config.enable_port_mapping != Some(true) || !networking_enabled {
+            return None;
+        }
+        if p2p_config.socks5_proxy.is_some() || p2p_config.reserved_nodes_only == Some(true) {
+            log::warn!("Port mapping is not used with a proxy or in the reserved-nodes-only mode");
+            return None;
+        }
+        let local_port = match local_addresses
+            .iter()
+            .map(SocketAddress::socket_addr)
+            .find(|address| address.is_ipv4() && !address.ip().is_loopback())
+        {
+            Some(address) => address.port(),
+            None => {
+                log::warn!("Port mapping is enabled, but there is no IPv4 listening address");
+                return None;
+            }
+        };
+
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel();
+        let task = logging::spawn_in_current_span(port_mapping::run_port_mapping(
+            port_mapping::DefaultPortMappingGateway::default(),
+            local_port,
+            port_mapping::PortMappingSettings::default(),
+            time_getter,
+            peer_mgr_event_sender,
+            shutdown_receiver,
+        ));
+        Some((shutdown_sender, task))
+    }
+}
+
 pub type P2pHandle = subsystem::Handle<dyn P2pInterface>;
 
 pub type P2pNetworkingService = DefaultNetworkingService<NoiseTcpTransport>;
 pub type P2pNetworkingServiceSocks5Proxy = DefaultNetworkingService<NoiseSocks5Transport>;
 pub type P2pNetworkingServiceUnencrypted = DefaultNetworkingService<TcpTransportSocket>;
+pub type P2pNetworkingServiceWithPlaintextListener =
+    DefaultNetworkingService<NoiseTcpWithPlaintextTransport>;
 
 pub fn make_p2p_transport() -> NoiseTcpTransport {
+    make_p2p_transport_with_keypair(NoiseStaticKeypair::generate())
+}
+
+pub fn make_p2p_transport_with_keypair(keypair: NoiseStaticKeypair) -> NoiseTcpTransport {
     let base_transport = TcpTransportSocket::new();
-    NoiseTcpTransport::new(NoiseEncryptionAdapter::gen_new, base_transport)
+    NoiseTcpTransport::new(
+        NoiseEncryptionAdapterMaker::new(NoiseEncryptionAdapter::from_keypair(keypair)),
+        base_transport,
+    )
 }
 
-pub fn make_p2p_transport_socks5_proxy(proxy: &str) -> NoiseSocks5Transport {
-    let base_transport = Socks5TransportSocket::new(proxy);
-    NoiseSocks5Transport::new(NoiseEncryptionAdapter::gen_new, base_transport)
+pub fn make_p2p_transport_socks5_proxy(
+    proxy: &str,
+    auth: Option<(String, String)>,
+    keypair: NoiseStaticKeypair,
+) -> NoiseSocks5Transport {
+    let base_transport = match auth {
+        Some((username, password)) => Socks5TransportSocket::with_auth(proxy, username, password),
+        None => Socks5TransportSocket::new(proxy),
+    };
+    NoiseSocks5Transport::new(
+        NoiseEncryptionAdapterMaker::new(NoiseEncryptionAdapter::from_keypair(keypair)),
+        base_transport,
+    )
 }
 
 pub fn make_p2p_transport_unencrypted() -> TcpTransportSocket {
     TcpTransportSocket::new()
 }
 
+/// Make a Noise-encrypted transport that also accepts unencrypted connections on the given
+/// addresses
+pub fn make_p2p_transport_with_plaintext_listener(
+    keypair: NoiseStaticKeypair,
+    plaintext_bind_addresses: Vec<SocketAddr>,
+) -> NoiseTcpWithPlaintextTransport {
+    DualTransportSocket::new(
+        make_p2p_transport_with_keypair(keypair),
+        make_p2p_transport_unencrypted(),
+        plaintext_bind_addresses,
+    )
+}
+
+/// Return the addresses to bind using the default transport and the addresses that override it
 fn get_p2p_bind_addresses(
-    bind_addresses: &[SocketAddr],
+    bind_addresses: &[BindAddress],
     p2p_port: u16,
     proxy_used: bool,
-) -> Vec<SocketAddress> {
+    default_transport: TransportProfile,
+) -> (Vec<SocketAddress>, Vec<SocketAddr>) {
     if !bind_addresses.is_empty() {
-        bind_addresses
-            .iter()
-            .map(|address| SocketAddress::new(*address))
-            .collect::<Vec<_>>()
+        let (default_addresses, overridden_addresses): (Vec<_>, Vec<_>) =
+            bind_addresses.iter().partition(|bind_address| {
+                bind_address.transport.unwrap_or(default_transport) == default_transport
+            });
+        (
+            default_addresses
+                .into_iter()
+                .map(|bind_address| SocketAddress::new(bind_address.address))
+                .collect(),
+            overridden_addresses
+                .into_iter()
+                .map(|bind_address| bind_address.address)
+                .collect(),
+        )
     } else if !proxy_used {
         // Bind to default addresses if none are specified by the user
-        vec![
+        let default_addresses = vec![
             SocketAddress::new(SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), p2p_port)),
             SocketAddress::new(SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), p2p_port)),
-        ]
+        ];
+        (default_addresses, Vec::new())
     } else {
-        Vec::new()
+        (Vec::new(), Vec::new())
     }
 }
 
@@ -263,10 +375,13 @@ pub struct P2pInit<S: PeerDbStorage + 'static> {
     time_getter: TimeGetter,
     peerdb_storage: S,
     bind_addresses: Vec<SocketAddress>,
+    /// Addresses where unencrypted connections are accepted alongside the encrypted ones
+    plaintext_bind_addresses: Vec<SocketAddr>,
+    noise_keypair: Option<NoiseStaticKeypair>,
 }
 
 impl<S: PeerDbStorage + 'static> P2pInit<S> {
-    async fn init<T>(self, transport: T::Transport) -> Result<P2p<T>>
+    async fn init<T>(self, transport: T::Transport, node_id: NodeId) -> Result<P2p<T>>
     where
         T: NetworkingService + Send + Sync + 'static,
         T::ConnectivityHandle: ConnectivityService<T>,
@@ -283,25 +398,54 @@ impl<S: PeerDbStorage + 'static> P2pInit<S> {
             self.mempool_handle,
             self.time_getter,
             self.peerdb_storage,
+            node_id,
         )
         .await
     }
 
-    pub fn add_to_manager(self, name: &'static str, manager: &mut subsystem::Manager) -> P2pHandle {
+    /// Use the given static Noise keypair instead of generating a new one.
+    pub fn with_noise_keypair(self, noise_keypair: NoiseStaticKeypair) -> Self {
+        Self {
+            noise_keypair: Some(noise_keypair),
+            ..self
+        }
+    }
+
+    pub fn add_to_manager(
+        mut self,
+        name: &'static str,
+        manager: &mut subsystem::Manager,
+    ) -> P2pHandle {
+        // Note: the keypair is generated even if encryption is disabled, so that the node
+        // always has an id; but in that case it's not advertised to peers.
+        let keypair = self.noise_keypair.take().unwrap_or_else(NoiseStaticKeypair::generate);
+        let node_id = NodeId::from_slice(keypair.public_key());
+
         if let Some(true) = self.p2p_config.disable_noise {
             type NetService = P2pNetworkingServiceUnencrypted;
             assert_eq!(*self.chain_config.chain_type(), ChainType::Regtest);
             assert!(self.p2p_config.socks5_proxy.is_none());
             let transport = make_p2p_transport_unencrypted();
-            manager.add_custom_subsystem(name, move |_| self.init::<NetService>(transport))
+            manager.add_custom_subsystem(name, move |_| self.init::<NetService>(transport, node_id))
         } else if let Some(socks5_proxy) = &self.p2p_config.socks5_proxy {
             type NetService = P2pNetworkingServiceSocks5Proxy;
-            let transport = make_p2p_transport_socks5_proxy(socks5_proxy);
-            manager.add_custom_subsystem(name, move |_| self.init::<NetService>(transport))
+            let transport = make_p2p_transport_socks5_proxy(
+                socks5_proxy,
+                self.p2p_config.socks5_proxy_auth.clone(),
+                keypair,
+            );
+            manager.add_custom_subsystem(name, move |_| self.init::<NetService>(transport, node_id))
+        } else if !self.plaintext_bind_addresses.is_empty() {
+            type NetService = P2pNetworkingServiceWithPlaintextListener;
+            let transport = make_p2p_transport_with_plaintext_listener(
+                keypair,
+                self.plaintext_bind_addresses.clone(),
+            );
+            manager.add_custom_subsystem(name, move |_| self.init::<NetService>(transport, node_id))
         } else {
             type NetService = P2pNetworkingService;
-            let transport = make_p2p_transport();
-            manager.add_custom_subsystem(name, move |_| self.init::<NetService>(transport))
+            let transport = make_p2p_transport_with_keypair(keypair);
+            manager.add_custom_subsystem(name, move |_| self.init::<NetService>(transport, node_id))
         }
     }
 }
@@ -360,12 +504,45 @@ pub fn make_p2p<S: PeerDbStorage + 'static>(
     );
 
     // Perform some early checks to prevent a failure in the run method.
-    let bind_addresses = get_p2p_bind_addresses(
+    let default_transport = if p2p_config.disable_noise == Some(true) {
+        TransportProfile::Plaintext
+    } else {
+        TransportProfile::Noise
+    };
+    let (bind_addresses, plaintext_bind_addresses) = get_p2p_bind_addresses(
         &p2p_config.bind_addresses,
         chain_config.p2p_port(),
         p2p_config.socks5_proxy.is_some(),
+        default_transport,
     );
 
+    if !plaintext_bind_addresses.is_empty() {
+        ensure!(
+            default_transport == TransportProfile::Noise,
+            P2pError::InvalidConfigurationValue(
+                "Encrypted listeners can't be used when P2P encryption is disabled".to_owned()
+            )
+        );
+        ensure!(
+            *chain_config.chain_type() == ChainType::Regtest,
+            P2pError::InvalidConfigurationValue(
+                "Unencrypted listeners are only allowed on the regtest network".to_owned()
+            )
+        );
+        ensure!(
+            plaintext_bind_addresses.iter().all(|address| address.ip().is_loopback()),
+            P2pError::InvalidConfigurationValue(
+                "Unencrypted listeners are only allowed on loopback addresses".to_owned()
+            )
+        );
+        ensure!(
+            p2p_config.socks5_proxy.is_none(),
+            P2pError::InvalidConfigurationValue(
+                "SOCKS5 proxy support is not implemented for unencrypted listeners".to_owned()
+            )
+        );
+    }
+
     if let Some(true) = p2p_config.disable_noise {
         ensure!(
             *chain_config.chain_type() == ChainType::Regtest,
@@ -390,6 +567,8 @@ pub fn make_p2p<S: PeerDbStorage + 'static>(
         time_getter,
         peerdb_storage,
         bind_addresses,
+        plaintext_bind_addresses,
+        noise_keypair: None,
     })
 }
 
@@ -415,12 +594,31 @@ where
         self.shutdown.store(true);
         let _ = self.backend_shutdown_sender.send(());
 
-        // Wait for the tasks to shut down.
-        futures::future::join_all([
-            self.backend_task,
-            self.peer_manager_task,
-            self.sync_manager_task,
-        ])
-        .await;
+        let mut tasks = vec![
+            ("backend", self.backend_task),
+            ("peer manager", self.peer_manager_task),
+            ("sync manager", self.sync_manager_task),
+        ];
+        if let Some((port_mapping_shutdown_sender, port_mapping_task)) = self.port_mapping {
+            // The task removes the mapping from the gateway before finishing
+            let _ = port_mapping_shutdown_sender.send(());
+            tasks.push(("port mapping", port_mapping_task));
+        }
+
+        // Wait for the tasks to shut down, but don't let a stuck task block the shutdown of
+        // the whole node.
+        tokio::select! {
+            _ = futures::future::join_all(tasks.iter_mut().map(|(_, task)| task)) => {}
+            () = tokio::time::sleep(SHUTDOWN_TIMEOUT) => {
+                for (name, task) in &tasks {
+                    if !task.is_finished() {
+                        log::error!(
+                            "P2p {name} task hasn't shut down in {SHUTDOWN_TIMEOUT:?}, aborting"
+                        );
+                        task.abort();
+                    }
+                }
+            }
+        }
     }
 }
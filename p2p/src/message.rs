@@ -31,6 +31,8 @@ pub enum BlockSyncMessage {
     BlockListRequest(BlockListRequest),
     HeaderList(HeaderList),
     BlockResponse(BlockResponse),
+    BlockHeaderRequest(BlockHeaderRequest),
+    BlockHeaderResponse(BlockHeaderResponse),
 
     // A "sentinel" message for testing purposes that allows to ensure that all block sync messages
     // that were sent into a channel have been processed by the receiver.
@@ -45,6 +47,8 @@ pub enum TransactionSyncMessage {
     NewTransaction(Id<Transaction>),
     TransactionRequest(Id<Transaction>),
     TransactionResponse(TransactionResponse),
+    GetMempool(MempoolRequest),
+    MempoolResponse(Vec<Id<Transaction>>),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -95,6 +99,29 @@ impl BlockListRequest {
     }
 }
 
+/// A request for the headers of the specified blocks.
+///
+/// Unlike `HeaderListRequest`, this allows fetching individual headers by their ids. The peer
+/// responds with a `BlockHeaderResponse` containing the headers in the requested order.
+#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct BlockHeaderRequest {
+    block_ids: Vec<Id<Block>>,
+}
+
+impl BlockHeaderRequest {
+    pub fn new(block_ids: Vec<Id<Block>>) -> Self {
+        Self { block_ids }
+    }
+
+    pub fn block_ids(&self) -> &[Id<Block>] {
+        &self.block_ids
+    }
+
+    pub fn into_block_ids(self) -> Vec<Id<Block>> {
+        self.block_ids
+    }
+}
+
 #[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
 pub struct AddrListRequest {}
 
@@ -131,6 +158,25 @@ impl HeaderList {
     }
 }
 
+#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct BlockHeaderResponse {
+    headers: Vec<SignedBlockHeader>,
+}
+
+impl BlockHeaderResponse {
+    pub fn new(headers: Vec<SignedBlockHeader>) -> Self {
+        Self { headers }
+    }
+
+    pub fn headers(&self) -> &[SignedBlockHeader] {
+        &self.headers
+    }
+
+    pub fn into_headers(self) -> Vec<SignedBlockHeader> {
+        self.headers
+    }
+}
+
 #[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
 pub struct BlockResponse {
     block: Box<Block>,
@@ -160,6 +206,15 @@ pub enum TransactionResponse {
     Found(SignedTransaction),
 }
 
+/// A request for the ids of the transactions in the peer's mempool.
+///
+/// It's sent once after connecting to an outbound peer; the peer responds with a
+/// `MempoolResponse` containing at most `max_txs` ids.
+#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct MempoolRequest {
+    pub max_txs: u32,
+}
+
 #[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
 pub struct AddrListResponse {
     pub addresses: Vec<PeerAddress>,
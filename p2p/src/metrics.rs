@@ -0,0 +1,84 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, OnceLock},
+};
+
+use utils::metrics::{registry, Counter, Gauge};
+
+use crate::net::types::PeerRole;
+
+pub struct P2pMetrics {
+    /// The number of the connected peers by their role
+    peers: BTreeMap<PeerRole, Arc<Gauge>>,
+    pub bans: Arc<Counter>,
+    pub blocks_in_flight: Arc<Gauge>,
+}
+
+impl P2pMetrics {
+    pub fn peers(&self, role: PeerRole) -> &Gauge {
+        self.peers.get(&role).expect("all roles must be present")
+    }
+}
+
+fn role_label(role: PeerRole) -> &'static str {
+    match role {
+        PeerRole::Inbound => "inbound",
+        PeerRole::OutboundFullRelay => "outbound_full_relay",
+        PeerRole::OutboundBlockRelay => "outbound_block_relay",
+        PeerRole::OutboundReserved => "outbound_reserved",
+        PeerRole::OutboundManual => "outbound_manual",
+        PeerRole::Feeler => "feeler",
+    }
+}
+
+pub fn metrics() -> &'static P2pMetrics {
+    static METRICS: OnceLock<P2pMetrics> = OnceLock::new();
+    METRICS.get_or_init(|| P2pMetrics {
+        peers: enum_iterator::all::<PeerRole>()
+            .map(|role| {
+                let direction = if role.is_outbound() {
+                    "outbound"
+                } else {
+                    "inbound"
+                };
+                let gauge = registry().gauge(
+                    "p2p_connected_peers",
+                    "The number of the connected peers",
+                    &[("direction", direction), ("role", role_label(role))],
+                );
+                (role, gauge)
+            })
+            .collect(),
+        bans: registry().counter(
+            "p2p_ban_events_total",
+            "The number of times a peer address was banned",
+            &[],
+        ),
+        blocks_in_flight: registry().gauge(
+            "p2p_sync_blocks_in_flight",
+            "The number of blocks requested from the peers and not yet received",
+            &[],
+        ),
+    })
+}
+
+/// Register the metrics, so that they are exported even before they are first updated
+pub fn register() {
+    let _ = metrics();
+    networking::transport::metrics::register();
+}
@@ -33,7 +33,7 @@ use common::{
 };
 use logging::log;
 use networking::transport::{ConnectedSocketInfo, TransportListener, TransportSocket};
-use p2p_types::socket_address::SocketAddress;
+use p2p_types::{socket_address::SocketAddress, NodeId};
 use randomness::{make_pseudo_rng, Rng};
 use utils::{
     atomics::SeqCstAtomicBool, eventhandler::EventsController, set_flag::SetFlag,
@@ -53,6 +53,7 @@ use crate::{
         types::{services::Services, ConnectivityEvent, PeerInfo, SyncingEvent},
     },
     protocol::{ProtocolVersion, SupportedProtocolVersion},
+    sync::block_serving::BufferedBlockResponse,
     types::{peer_address::PeerAddress, peer_id::PeerId},
     P2pEvent, P2pEventHandler,
 };
@@ -168,6 +169,9 @@ pub struct Backend<T: TransportSocket> {
     /// equal to default_networking_service::PREFERRED_PROTOCOL_VERSION, but it can be
     /// overridden for testing purposes.
     node_protocol_version: ProtocolVersion,
+
+    /// The node id advertised to peers; it's taken from the transport's static public key.
+    local_node_id: Option<NodeId>,
 }
 
 impl<T> Backend<T>
@@ -190,6 +194,8 @@ where
         subscribers_receiver: mpsc::UnboundedReceiver<P2pEventHandler>,
         node_protocol_version: ProtocolVersion,
     ) -> Self {
+        let local_node_id = transport.local_static_public_key().map(|key| NodeId::from_slice(&key));
+
         Self {
             networking_enabled,
             transport,
@@ -209,6 +215,7 @@ where
             events_controller: EventsController::new(),
             subscribers_receiver,
             node_protocol_version,
+            local_node_id,
         }
     }
 
@@ -240,7 +247,7 @@ where
                 Ok(
                     self.conn_event_sender.send(ConnectivityEvent::ConnectionError {
                         peer_address: address,
-                        error: P2pError::DialError(DialError::ConnectionRefusedOrTimedOut),
+                        error: err,
                     })?,
                 )
             }
@@ -272,6 +279,7 @@ where
                 peer_id,
                 common_services: peer.common_services,
                 protocol_version: peer.protocol_version,
+                inbound: peer.inbound,
                 block_sync_msg_receiver,
                 transaction_sync_msg_receiver,
             },
@@ -314,6 +322,23 @@ where
         Ok(peer.backend_event_sender.send(BackendEvent::SendMessage(Box::new(message)))?)
     }
 
+    /// Sends a block response to the remote peer; the guard is dropped when the response
+    /// has been written to the socket or if the peer is already disconnected.
+    fn send_block_response(
+        &mut self,
+        peer: PeerId,
+        message: Message,
+        guard: BufferedBlockResponse,
+    ) -> crate::Result<()> {
+        let peer = self
+            .peers
+            .get_mut(&peer)
+            .ok_or(P2pError::PeerError(PeerError::PeerDoesntExist))?;
+        Ok(peer
+            .backend_event_sender
+            .send(BackendEvent::SendBlockResponse(Box::new(message), guard))?)
+    }
+
     /// Runs the backend events loop.
     pub async fn run(mut self) -> crate::Result<Never> {
         loop {
@@ -401,13 +426,14 @@ where
             peer_event_sender,
             backend_event_receiver,
             self.node_protocol_version,
+            self.local_node_id,
             self.time_getter.shallow_clone(),
         );
         let shutdown = Arc::clone(&self.shutdown);
         let handle = logging::spawn_in_current_span(async move {
             match peer.run().await {
                 Ok(()) => {}
-                Err(P2pError::ChannelClosed) if shutdown.load() => {}
+                Err(e) if shutdown.load() && e.is_expected_on_shutdown() => {}
                 Err(e) => log::error!("Peer {peer_id} failed: {e}"),
             }
         });
@@ -589,6 +615,7 @@ where
                 user_agent,
                 software_version,
                 node_address_as_seen_by_peer,
+                peer_node_id,
                 handshake_nonce,
             }) => {
                 if self.networking_enabled {
@@ -602,6 +629,7 @@ where
                             software_version,
                             user_agent,
                             common_services,
+                            peer_node_id,
                         },
                         node_address_as_seen_by_peer,
                     )?;
@@ -610,6 +638,17 @@ where
                 Ok(())
             }
 
+            PeerEvent::NodeIdReceived { node_id } => {
+                // The peer may have already been disconnected (or not created at all, e.g.
+                // in the case of a self-connection).
+                if self.peers.contains_key(&peer_id) {
+                    self.conn_event_sender
+                        .send(ConnectivityEvent::PeerNodeIdReceived { peer_id, node_id })?;
+                }
+
+                Ok(())
+            }
+
             PeerEvent::MessageReceived { message } => {
                 if self.networking_enabled {
                     self.handle_message(peer_id, message)?;
@@ -757,6 +796,16 @@ where
                     log::debug!("Failed to send request to peer {peer_id}: {e}")
                 }
             }
+            Command::SendBlockResponse {
+                peer_id,
+                message,
+                guard,
+            } => {
+                let res = self.send_block_response(peer_id, message, guard);
+                if let Err(e) = res {
+                    log::debug!("Failed to send block response to peer {peer_id}: {e}")
+                }
+            }
             Command::EnableNetworking { enable } => {
                 if self.networking_enabled != enable {
                     self.networking_enabled = enable;
@@ -774,6 +823,9 @@ where
                     }
                 }
             }
+            Command::BroadcastEvent { event } => {
+                self.events_controller.broadcast(event);
+            }
         };
     }
 
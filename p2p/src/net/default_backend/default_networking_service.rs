@@ -39,7 +39,7 @@ use super::{backend::Backend, ConnectivityHandle, MessagingHandle, SyncingEventR
 // The preferred protocol version.
 // Note that we intentionally keep this constant private, because most of the code should
 // not depend on its value.
-const PREFERRED_PROTOCOL_VERSION: SupportedProtocolVersion = SupportedProtocolVersion::V3;
+const PREFERRED_PROTOCOL_VERSION: SupportedProtocolVersion = SupportedProtocolVersion::V7;
 
 // Some tests do need this value though in order to check the correct version selection.
 // So we make it available for them via a function with a test-specific name and under cfg(test).
@@ -100,7 +100,7 @@ impl<T: TransportSocket> DefaultNetworkingService<T> {
             async move {
                 match backend.run().await {
                     Ok(never) => match never {},
-                    Err(P2pError::ChannelClosed) if shutdown.load() => {
+                    Err(e) if shutdown.load() && e.is_expected_on_shutdown() => {
                         log::info!("Backend is shut down");
                     }
                     Err(e) => {
@@ -29,13 +29,15 @@ use p2p_types::{services::Services, socket_address::SocketAddress};
 use crate::{
     disconnection_reason::DisconnectionReason,
     error::P2pError,
-    message::{BlockSyncMessage, PeerManagerMessage, TransactionSyncMessage},
+    message::{BlockResponse, BlockSyncMessage, PeerManagerMessage, TransactionSyncMessage},
     net::{
         self,
         types::{ConnectivityEvent, SyncingEvent},
         ConnectivityService, MessagingService, NetworkingService,
     },
+    sync::block_serving::BufferedBlockResponse,
     types::peer_id::PeerId,
+    P2pEvent,
 };
 
 pub use default_networking_service::DefaultNetworkingService;
@@ -143,6 +145,10 @@ where
         })?)
     }
 
+    fn broadcast_event(&mut self, event: P2pEvent) -> crate::Result<()> {
+        Ok(self.cmd_sender.send(types::Command::BroadcastEvent { event })?)
+    }
+
     fn local_addresses(&self) -> &[SocketAddress] {
         &self.local_addresses
     }
@@ -164,6 +170,19 @@ impl MessagingService for MessagingHandle {
         })?)
     }
 
+    fn send_block_response(
+        &mut self,
+        peer_id: PeerId,
+        response: BlockResponse,
+        guard: BufferedBlockResponse,
+    ) -> crate::Result<()> {
+        Ok(self.command_sender.send(types::Command::SendBlockResponse {
+            peer_id,
+            message: BlockSyncMessage::BlockResponse(response).into(),
+            guard,
+        })?)
+    }
+
     fn send_transaction_sync_message(
         &mut self,
         peer_id: PeerId,
@@ -23,8 +23,11 @@ use tokio::{
 use chainstate::ban_score::BanScore;
 use common::{chain::ChainConfig, primitives::time::Time, time_getter::TimeGetter};
 use logging::log;
-use networking::transport::{BufferedTranscoder, ConnectedSocketInfo, TransportSocket};
-use p2p_types::{services::Services, socket_addr_ext::SocketAddrExt};
+use networking::{
+    error::{MessageCodecError, NetworkingError},
+    transport::{BufferedTranscoder, ConnectedSocketInfo, TransportSocket},
+};
+use p2p_types::{services::Services, socket_addr_ext::SocketAddrExt, NodeId};
 
 use crate::{
     config::P2pConfig,
@@ -32,13 +35,17 @@ use crate::{
     error::{ConnectionValidationError, P2pError, PeerError, ProtocolError},
     message::{BlockSyncMessage, TransactionSyncMessage, WillDisconnectMessage},
     net::default_backend::types::{BackendEvent, PeerEvent},
-    protocol::{choose_common_protocol_version, ProtocolVersion, SupportedProtocolVersion},
+    protocol::{
+        choose_common_protocol_version, MessageSizeLimits, ProtocolVersion,
+        SupportedProtocolVersion,
+    },
     types::peer_id::PeerId,
 };
 
 use super::types::{
-    can_send_will_disconnect, peer_event, CategorizedMessage, HandshakeMessage, HandshakeNonce,
-    Message, P2pTimestamp,
+    can_exchange_message_size_limits, can_exchange_node_ids, can_send_will_disconnect,
+    message_size_limit_error, message_tag_size_limits, peer_event, CategorizedMessage,
+    HandshakeMessage, HandshakeNonce, Message, P2pTimestamp,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -75,6 +82,9 @@ pub struct Peer<T: TransportSocket> {
     /// overridden for testing purposes.
     node_protocol_version: ProtocolVersion,
 
+    /// The node id that this node advertises to its peers, if any.
+    local_node_id: Option<NodeId>,
+
     /// The chosen common protocol version; available only after the handshake has completed.
     common_protocol_version: Option<SupportedProtocolVersion>,
 
@@ -96,10 +106,18 @@ where
         peer_event_sender: mpsc::Sender<PeerEvent>,
         backend_event_receiver: mpsc::UnboundedReceiver<BackendEvent>,
         node_protocol_version: ProtocolVersion,
+        local_node_id: Option<NodeId>,
         time_getter: TimeGetter,
     ) -> Self {
-        let socket =
+        let mut socket =
             BufferedTranscoder::new(socket, Some(*p2p_config.protocol_config.max_message_size));
+        // Until the peer has advertised its own limits, assume that it uses the default ones.
+        socket.set_recv_tag_size_limits(message_tag_size_limits(&MessageSizeLimits::from_config(
+            &p2p_config.protocol_config,
+        )));
+        socket.set_send_tag_size_limits(message_tag_size_limits(
+            &MessageSizeLimits::protocol_defaults(),
+        ));
 
         Self {
             peer_id,
@@ -110,6 +128,7 @@ where
             peer_event_sender,
             backend_event_receiver,
             node_protocol_version,
+            local_node_id,
             time_getter,
             common_protocol_version: None,
         }
@@ -219,6 +238,56 @@ where
         Ok(())
     }
 
+    /// Advertise our message size limits to the peer and apply the limits advertised by it
+    /// to the messages that we send.
+    async fn exchange_message_size_limits(
+        &mut self,
+        common_protocol_version: SupportedProtocolVersion,
+    ) -> crate::Result<()> {
+        if !can_exchange_message_size_limits(common_protocol_version) {
+            return Ok(());
+        }
+
+        self.socket
+            .send(Message::Handshake(HandshakeMessage::MessageSizeLimits(
+                MessageSizeLimits::from_config(&self.p2p_config.protocol_config),
+            )))
+            .await?;
+
+        let Message::Handshake(HandshakeMessage::MessageSizeLimits(peer_limits)) =
+            self.socket.recv().await?
+        else {
+            return Err(P2pError::ProtocolError(ProtocolError::HandshakeExpected));
+        };
+
+        self.socket.set_send_tag_size_limits(message_tag_size_limits(&peer_limits));
+
+        Ok(())
+    }
+
+    /// Advertise our node id to the peer and receive the one advertised by it.
+    async fn exchange_node_ids(
+        &mut self,
+        common_protocol_version: SupportedProtocolVersion,
+    ) -> crate::Result<Option<NodeId>> {
+        if !can_exchange_node_ids(common_protocol_version) {
+            return Ok(None);
+        }
+
+        self.socket
+            .send(Message::Handshake(HandshakeMessage::NodeId(
+                self.local_node_id,
+            )))
+            .await?;
+
+        let Message::Handshake(HandshakeMessage::NodeId(peer_node_id)) = self.socket.recv().await?
+        else {
+            return Err(P2pError::ProtocolError(ProtocolError::HandshakeExpected));
+        };
+
+        Ok(peer_node_id)
+    }
+
     async fn handshake(&mut self) -> crate::Result<()> {
         let init_time = self.time_getter.get_time();
         let peer_address = self.socket.inner_stream().remote_address()?;
@@ -272,6 +341,7 @@ where
                         user_agent,
                         software_version,
                         node_address_as_seen_by_peer,
+                        peer_node_id: None,
                         handshake_nonce,
                     }))
                     .await?;
@@ -298,6 +368,12 @@ where
                         current_time: P2pTimestamp::from_time(self.time_getter.get_time()),
                     }))
                     .await?;
+
+                self.exchange_message_size_limits(common_protocol_version).await?;
+
+                if let Some(node_id) = self.exchange_node_ids(common_protocol_version).await? {
+                    self.peer_event_sender.send(PeerEvent::NodeIdReceived { node_id }).await?;
+                }
             }
             ConnectionInfo::Outbound {
                 handshake_nonce,
@@ -350,6 +426,9 @@ where
 
                 let common_services = local_services & remote_services;
 
+                self.exchange_message_size_limits(common_protocol_version).await?;
+                let peer_node_id = self.exchange_node_ids(common_protocol_version).await?;
+
                 self.peer_event_sender
                     .send(PeerEvent::PeerInfoReceived(peer_event::PeerInfo {
                         protocol_version: common_protocol_version,
@@ -358,6 +437,7 @@ where
                         user_agent,
                         software_version,
                         node_address_as_seen_by_peer,
+                        peer_node_id,
                         handshake_nonce,
                     }))
                     .await?;
@@ -402,7 +482,13 @@ where
 
     async fn run_handshake(&mut self) -> crate::Result<()> {
         // handshake with remote peer and send peer's info to backend
-        let handshake_timeout = *self.p2p_config.peer_handshake_timeout;
+        let handshake_timeout = match self.connection_info {
+            ConnectionInfo::Inbound => std::cmp::min(
+                *self.p2p_config.inbound_connection_timeout,
+                *self.p2p_config.peer_handshake_timeout,
+            ),
+            ConnectionInfo::Outbound { .. } => *self.p2p_config.peer_handshake_timeout,
+        };
         let handshake_res = timeout(handshake_timeout, self.handshake()).await;
 
         match handshake_res {
@@ -440,6 +526,20 @@ where
         Ok(())
     }
 
+    async fn send_message(&mut self, message: Message) -> crate::Result<()> {
+        match self.socket.send(message).await {
+            Ok(()) => Ok(()),
+            // The message was not sent, because the peer wouldn't accept it anyway.
+            Err(NetworkingError::MessageCodecError(
+                err @ MessageCodecError::MessageTypeTooLarge { .. },
+            )) => {
+                log::warn!("Message to peer {} dropped: {err}", self.peer_id);
+                Ok(())
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
     async fn run_impl(&mut self) -> crate::Result<()> {
         // Run the handshake sequence first
         self.run_handshake().await?;
@@ -456,7 +556,12 @@ where
                     BackendEvent::Accepted{ block_sync_msg_sender, transaction_sync_msg_sender } => {
                         sync_msg_senders_opt = Some((block_sync_msg_sender, transaction_sync_msg_sender));
                     },
-                    BackendEvent::SendMessage(message) => self.socket.send(*message).await?,
+                    BackendEvent::SendMessage(message) => self.send_message(*message).await?,
+                    BackendEvent::SendBlockResponse(message, guard) => {
+                        self.send_message(*message).await?;
+                        // The response is no longer buffered
+                        drop(guard);
+                    },
                     BackendEvent::Disconnect {reason} => {
                         log::debug!("Disconnection requested for peer {}, the reason is {:?}", self.peer_id, reason);
                         if let Some(common_protocol_version) = self.common_protocol_version {
@@ -482,6 +587,15 @@ where
                         ).await?;
                     }
                     Err(err) => {
+                        if let Some(error) = message_size_limit_error(&err) {
+                            log::debug!("Peer {} sent an oversized message: {error}", self.peer_id);
+                            self.peer_event_sender
+                                .send(PeerEvent::Misbehaved {
+                                    error: P2pError::ProtocolError(error),
+                                })
+                                .await?;
+                        }
+
                         log::info!("Connection closed for peer {}, reason {err:?}", self.peer_id);
                         return Ok(());
                     }
@@ -515,22 +629,26 @@ mod tests {
     use futures::FutureExt;
 
     use chainstate::Locator;
+    use chainstate_test_framework::TestFramework;
     use common::chain::config::MagicBytes;
     use networking::test_helpers::{
         get_two_connected_sockets, TestTransportChannel, TestTransportMaker, TestTransportNoise,
         TestTransportTcp,
     };
     use networking::transport::{MpscChannelTransport, NoiseTcpTransport, TcpTransportSocket};
+    use randomness::Rng;
     use test_utils::{
         assert_matches,
         mock_time_getter::{mocked_time_getter_milliseconds, mocked_time_getter_seconds},
+        random::Seed,
     };
     use utils::atomics::SeqCstAtomicU64;
 
     use super::*;
     use crate::{
-        message::HeaderListRequest,
+        message::{BlockResponse, HeaderList, HeaderListRequest, PingRequest},
         net::types::services::Service,
+        protocol::{ProtocolConfig, SizeLimitedMessageType},
         test_helpers::{test_p2p_config, TEST_PROTOCOL_VERSION},
     };
 
@@ -596,6 +714,7 @@ mod tests {
             peer_event_sender,
             backend_event_receiver,
             TEST_PROTOCOL_VERSION.into(),
+            None,
             time_getter,
         );
 
@@ -630,6 +749,7 @@ mod tests {
                 user_agent: p2p_config.user_agent.clone(),
                 software_version: *chain_config.software_version(),
                 node_address_as_seen_by_peer: None,
+                peer_node_id: None,
                 handshake_nonce: 123,
             },
         )
@@ -682,6 +802,7 @@ mod tests {
             peer_event_sender,
             backend_event_receiver,
             TEST_PROTOCOL_VERSION.into(),
+            None,
             time_getter,
         );
 
@@ -715,6 +836,7 @@ mod tests {
                 user_agent: p2p_config.user_agent.clone(),
                 software_version: *chain_config.software_version(),
                 node_address_as_seen_by_peer: None,
+                peer_node_id: None,
                 handshake_nonce: 1,
             },
         )
@@ -763,6 +885,7 @@ mod tests {
             peer_event_sender,
             backend_event_receiver,
             TEST_PROTOCOL_VERSION.into(),
+            None,
             time_getter,
         );
 
@@ -831,6 +954,7 @@ mod tests {
             peer_event_sender,
             backend_event_receiver,
             TEST_PROTOCOL_VERSION.into(),
+            None,
             time_getter,
         );
 
@@ -870,6 +994,152 @@ mod tests {
         invalid_handshake_message::<TestTransportNoise, NoiseTcpTransport>().await;
     }
 
+    async fn message_size_limits<A, T>(seed: Seed)
+    where
+        A: TestTransportMaker<Transport = T>,
+        T: TransportSocket,
+    {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let (socket1, socket2) = get_two_connected_sockets::<A, T>().await;
+        let chain_config = Arc::new(common::chain::config::create_unit_test_config());
+        let p2p_config = Arc::new(P2pConfig {
+            protocol_config: ProtocolConfig {
+                max_block_response_message_size: 1.into(),
+                ..Default::default()
+            },
+            ..test_p2p_config()
+        });
+        let (peer_event_sender, mut peer_event_receiver) = mpsc::channel(TEST_CHAN_BUF_SIZE);
+        let (backend_event_sender, backend_event_receiver) = mpsc::unbounded_channel();
+        let cur_time = Arc::new(SeqCstAtomicU64::new(123456));
+        let time_getter = mocked_time_getter_seconds(cur_time);
+
+        let mut peer = Peer::<T>::new(
+            PeerId::new(),
+            ConnectionInfo::Inbound,
+            Arc::clone(&chain_config),
+            Arc::clone(&p2p_config),
+            socket1,
+            peer_event_sender,
+            backend_event_receiver,
+            SupportedProtocolVersion::V4.into(),
+            None,
+            time_getter,
+        );
+
+        let handle = logging::spawn_in_current_span(async move { peer.run_impl().await });
+
+        let mut socket2 =
+            BufferedTranscoder::new(socket2, Some(*p2p_config.protocol_config.max_message_size));
+        socket2
+            .send(Message::Handshake(HandshakeMessage::Hello {
+                protocol_version: SupportedProtocolVersion::V4.into(),
+                software_version: *chain_config.software_version(),
+                network: *chain_config.magic_bytes(),
+                user_agent: p2p_config.user_agent.clone(),
+                services: [Service::Blocks, Service::Transactions].as_slice().into(),
+                receiver_address: None,
+                current_time: P2pTimestamp::from_int_seconds(123456),
+                handshake_nonce: 123,
+            }))
+            .await
+            .unwrap();
+
+        expect_some_peer_info_received_event(&mut peer_event_receiver).await;
+        expect_sync_event(&mut peer_event_receiver).await;
+        assert_matches!(
+            socket2.recv().await.unwrap(),
+            Message::Handshake(HandshakeMessage::HelloAck { .. })
+        );
+
+        // The peer advertises its limits and applies ours to the messages it sends.
+        assert_eq!(
+            socket2.recv().await.unwrap(),
+            Message::Handshake(HandshakeMessage::MessageSizeLimits(
+                MessageSizeLimits::from_config(&p2p_config.protocol_config)
+            ))
+        );
+        let remote_limits = MessageSizeLimits {
+            header_list: 1,
+            ..MessageSizeLimits::protocol_defaults()
+        };
+        socket2
+            .send(Message::Handshake(HandshakeMessage::MessageSizeLimits(
+                remote_limits,
+            )))
+            .await
+            .unwrap();
+
+        let (block_sync_msg_sender, _block_sync_msg_receiver) = mpsc::channel(TEST_CHAN_BUF_SIZE);
+        let (transaction_sync_msg_sender, _transaction_sync_msg_receiver) =
+            mpsc::channel(TEST_CHAN_BUF_SIZE);
+        backend_event_sender
+            .send(BackendEvent::Accepted {
+                block_sync_msg_sender,
+                transaction_sync_msg_sender,
+            })
+            .unwrap();
+
+        // A message that exceeds the remote limit is not sent.
+        let ping = Message::PingRequest(PingRequest { nonce: rng.gen() });
+        backend_event_sender
+            .send(BackendEvent::SendMessage(Box::new(Message::HeaderList(
+                HeaderList::new(Vec::new()),
+            ))))
+            .unwrap();
+        backend_event_sender
+            .send(BackendEvent::SendMessage(Box::new(ping.clone())))
+            .unwrap();
+        assert_eq!(socket2.recv().await.unwrap(), ping);
+
+        // A message that exceeds our own limit is reported as misbehavior.
+        let mut tf = TestFramework::builder(&mut rng).build();
+        let block = tf
+            .make_block_builder()
+            .add_test_transaction_from_best_block(&mut rng)
+            .build(&mut rng);
+        socket2.send(Message::BlockResponse(BlockResponse::new(block))).await.unwrap();
+
+        assert_matches!(
+            peer_event_receiver.recv().await.unwrap(),
+            PeerEvent::Misbehaved {
+                error: P2pError::ProtocolError(ProtocolError::MessageSizeLimitExceeded {
+                    message_type: SizeLimitedMessageType::BlockResponse,
+                    size: _,
+                    max_size: 1,
+                })
+            }
+        );
+        handle.await.unwrap().unwrap();
+    }
+
+    #[tracing::instrument(skip(seed))]
+    #[rstest::rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    #[tokio::test]
+    async fn message_size_limits_tcp(#[case] seed: Seed) {
+        message_size_limits::<TestTransportTcp, TcpTransportSocket>(seed).await;
+    }
+
+    #[tracing::instrument(skip(seed))]
+    #[rstest::rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    #[tokio::test]
+    async fn message_size_limits_channels(#[case] seed: Seed) {
+        message_size_limits::<TestTransportChannel, MpscChannelTransport>(seed).await;
+    }
+
+    #[tracing::instrument(skip(seed))]
+    #[rstest::rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    #[tokio::test]
+    async fn message_size_limits_noise(#[case] seed: Seed) {
+        message_size_limits::<TestTransportNoise, NoiseTcpTransport>(seed).await;
+    }
+
     #[rstest::rstest]
     #[case::all_in_sync(
         123456,
@@ -952,6 +1222,7 @@ mod tests {
             tx1,
             rx2,
             TEST_PROTOCOL_VERSION.into(),
+            None,
             peer_time_getter,
         );
 
@@ -985,4 +1256,65 @@ mod tests {
         let result = handle.await.unwrap();
         result_check(result);
     }
+
+    // An inbound peer that doesn't send anything is disconnected after `inbound_connection_timeout`,
+    // while an outbound one is only disconnected after `peer_handshake_timeout`.
+    #[rstest::rstest]
+    #[case::inbound(ConnectionInfo::Inbound, Duration::from_secs(5))]
+    #[case::outbound(
+        ConnectionInfo::Outbound {
+            handshake_nonce: 1,
+            local_services_override: None,
+        },
+        Duration::from_secs(10),
+    )]
+    #[tokio::test]
+    async fn slow_handshake_timeout(
+        #[case] connection_info: ConnectionInfo,
+        #[case] expected_timeout: Duration,
+    ) {
+        tokio::time::pause();
+        let time_getter = mocked_time_getter_seconds(Arc::new(SeqCstAtomicU64::new(123456)));
+
+        let (socket1, _socket2) =
+            get_two_connected_sockets::<TestTransportChannel, MpscChannelTransport>().await;
+        let chain_config = Arc::new(common::chain::config::create_unit_test_config());
+        let p2p_config = Arc::new(P2pConfig {
+            inbound_connection_timeout: Duration::from_secs(5).into(),
+            peer_handshake_timeout: Duration::from_secs(10).into(),
+            ..test_p2p_config()
+        });
+        let (tx1, _rx1) = mpsc::channel(TEST_CHAN_BUF_SIZE);
+        let (_tx2, rx2) = mpsc::unbounded_channel();
+
+        let mut peer = Peer::<MpscChannelTransport>::new(
+            PeerId::new(),
+            connection_info,
+            chain_config,
+            p2p_config,
+            socket1,
+            tx1,
+            rx2,
+            TEST_PROTOCOL_VERSION.into(),
+            None,
+            time_getter,
+        );
+
+        let handle = logging::spawn_in_current_span(async move { peer.run_handshake().await });
+
+        // Advance tokio time in 200ms increments up to just before the expected timeout.
+        let increment = 200;
+        for _ in 0..(expected_timeout.as_millis() as u64 / increment - 1) {
+            tokio::time::advance(Duration::from_millis(increment)).await;
+        }
+        tokio::task::yield_now().await;
+        assert!(!handle.is_finished());
+
+        tokio::time::advance(Duration::from_millis(increment)).await;
+        let result = handle.await.unwrap();
+        assert_eq!(
+            result,
+            Err(P2pError::ProtocolError(ProtocolError::Unresponsive))
+        );
+    }
 }
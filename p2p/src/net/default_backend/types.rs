@@ -21,20 +21,29 @@ use common::{
     chain::{config::MagicBytes, Transaction},
     primitives::{semver::SemVer, time::Time, user_agent::UserAgent, Id},
 };
-use p2p_types::socket_address::SocketAddress;
-use serialization::{Decode, Encode};
+use networking::{
+    error::{MessageCodecError, NetworkingError},
+    transport::MessageTagSizeLimits,
+};
+use p2p_types::{socket_address::SocketAddress, NodeId};
+use serialization::{strict::DecodeStrict, Decode, Encode};
 
 use crate::{
     disconnection_reason::DisconnectionReason,
-    error::P2pError,
+    error::{P2pError, ProtocolError},
     message::{
-        AddrListRequest, AddrListResponse, AnnounceAddrRequest, BlockListRequest, BlockResponse,
-        BlockSyncMessage, HeaderList, HeaderListRequest, PeerManagerMessage, PingRequest,
-        PingResponse, TransactionResponse, TransactionSyncMessage, WillDisconnectMessage,
+        AddrListRequest, AddrListResponse, AnnounceAddrRequest, BlockHeaderRequest,
+        BlockHeaderResponse, BlockListRequest, BlockResponse, BlockSyncMessage, HeaderList,
+        HeaderListRequest, MempoolRequest, PeerManagerMessage, PingRequest, PingResponse,
+        TransactionResponse, TransactionSyncMessage, WillDisconnectMessage,
     },
     net::types::services::Services,
-    protocol::{ProtocolVersion, SupportedProtocolVersion},
+    protocol::{
+        MessageSizeLimits, ProtocolVersion, SizeLimitedMessageType, SupportedProtocolVersion,
+    },
+    sync::block_serving::BufferedBlockResponse,
     types::{peer_address::PeerAddress, peer_id::PeerId},
+    P2pEvent,
 };
 
 #[derive(Debug, Eq, PartialEq)]
@@ -54,9 +63,18 @@ pub enum Command {
         peer_id: PeerId,
         message: Message,
     },
+    /// Same as `SendMessage`, but the guard is kept until the message is written to the socket.
+    SendBlockResponse {
+        peer_id: PeerId,
+        message: Message,
+        guard: BufferedBlockResponse,
+    },
     EnableNetworking {
         enable: bool,
     },
+    BroadcastEvent {
+        event: P2pEvent,
+    },
 }
 
 /// Random nonce sent in outbound handshake.
@@ -103,6 +121,10 @@ pub mod peer_event {
         pub software_version: SemVer,
         pub node_address_as_seen_by_peer: Option<PeerAddress>,
 
+        /// The node id advertised by the peer; only available here for outbound connections,
+        /// because for inbound ones it's received after `PeerInfoReceived` has been sent.
+        pub peer_node_id: Option<NodeId>,
+
         /// For outbound connections that is what we sent.
         /// For inbound connections that is what was received from remote peer.
         pub handshake_nonce: HandshakeNonce,
@@ -115,6 +137,9 @@ pub enum PeerEvent {
     /// Peer information received from remote
     PeerInfoReceived(peer_event::PeerInfo),
 
+    /// The node id advertised by an inbound peer at the end of the handshake
+    NodeIdReceived { node_id: NodeId },
+
     /// Connection closed to remote
     ConnectionClosed,
 
@@ -143,6 +168,7 @@ pub enum BackendEvent {
         transaction_sync_msg_sender: Sender<TransactionSyncMessage>,
     },
     SendMessage(Box<Message>),
+    SendBlockResponse(Box<Message>, BufferedBlockResponse),
     Disconnect {
         reason: Option<DisconnectionReason>,
     },
@@ -179,6 +205,15 @@ pub enum HandshakeMessage {
 
         current_time: P2pTimestamp,
     },
+    /// Sent by both sides after Hello/HelloAck, if the chosen protocol version
+    /// is V4 or later.
+    #[codec(index = 2)]
+    MessageSizeLimits(MessageSizeLimits),
+    /// Sent by both sides after MessageSizeLimits, if the chosen protocol version
+    /// is V5 or later. `None` means that the node has no stable identity (i.e. it doesn't
+    /// use an encrypted transport).
+    #[codec(index = 3)]
+    NodeId(Option<NodeId>),
 }
 
 #[derive(Debug, Encode, Decode, PartialEq, Eq, Clone)]
@@ -218,12 +253,53 @@ pub enum Message {
     #[codec(index = 13)]
     WillDisconnect(WillDisconnectMessage),
 
+    /// Initial mempool sync messages. Available since protocol V6.
+    #[codec(index = 14)]
+    GetMempool(MempoolRequest),
+    #[codec(index = 15)]
+    MempoolResponse(Vec<Id<Transaction>>),
+
+    /// Requesting block headers by their ids. Available since protocol V7.
+    #[codec(index = 16)]
+    BlockHeaderRequest(BlockHeaderRequest),
+    #[codec(index = 17)]
+    BlockHeaderResponse(BlockHeaderResponse),
+
     // A message that corresponds to BlockSyncMessage::TestSentinel.
     #[cfg(test)]
     #[codec(index = 255)]
     TestBlockSyncMsgSentinel(Id<()>),
 }
 
+impl DecodeStrict for Message {
+    /// Only the messages carrying blocks, headers and transactions are checked, because the other
+    /// messages don't contain anything that is identified by the hash of its encoding.
+    fn needs_canonical_check(&self) -> bool {
+        match self {
+            Message::HeaderList(_)
+            | Message::BlockResponse(_)
+            | Message::BlockHeaderResponse(_)
+            | Message::TransactionResponse(_) => true,
+            Message::Handshake(_)
+            | Message::PingRequest(_)
+            | Message::PingResponse(_)
+            | Message::NewTransaction(_)
+            | Message::HeaderListRequest(_)
+            | Message::BlockListRequest(_)
+            | Message::BlockHeaderRequest(_)
+            | Message::TransactionRequest(_)
+            | Message::AnnounceAddrRequest(_)
+            | Message::AddrListRequest(_)
+            | Message::AddrListResponse(_)
+            | Message::WillDisconnect(_)
+            | Message::GetMempool(_)
+            | Message::MempoolResponse(_) => false,
+            #[cfg(test)]
+            Message::TestBlockSyncMsgSentinel(_) => false,
+        }
+    }
+}
+
 impl From<PeerManagerMessage> for Message {
     fn from(message: PeerManagerMessage) -> Self {
         match message {
@@ -244,6 +320,8 @@ impl From<BlockSyncMessage> for Message {
             BlockSyncMessage::BlockListRequest(r) => Message::BlockListRequest(r),
             BlockSyncMessage::HeaderList(r) => Message::HeaderList(r),
             BlockSyncMessage::BlockResponse(r) => Message::BlockResponse(r),
+            BlockSyncMessage::BlockHeaderRequest(r) => Message::BlockHeaderRequest(r),
+            BlockSyncMessage::BlockHeaderResponse(r) => Message::BlockHeaderResponse(r),
             #[cfg(test)]
             BlockSyncMessage::TestSentinel(id) => Message::TestBlockSyncMsgSentinel(id),
         }
@@ -256,6 +334,8 @@ impl From<TransactionSyncMessage> for Message {
             TransactionSyncMessage::NewTransaction(id) => Message::NewTransaction(id),
             TransactionSyncMessage::TransactionRequest(id) => Message::TransactionRequest(id),
             TransactionSyncMessage::TransactionResponse(tx) => Message::TransactionResponse(tx),
+            TransactionSyncMessage::GetMempool(req) => Message::GetMempool(req),
+            TransactionSyncMessage::MempoolResponse(ids) => Message::MempoolResponse(ids),
         }
     }
 }
@@ -306,6 +386,12 @@ impl Message {
             Message::BlockResponse(msg) => {
                 CategorizedMessage::BlockSyncMessage(BlockSyncMessage::BlockResponse(msg))
             }
+            Message::BlockHeaderRequest(msg) => {
+                CategorizedMessage::BlockSyncMessage(BlockSyncMessage::BlockHeaderRequest(msg))
+            }
+            Message::BlockHeaderResponse(msg) => {
+                CategorizedMessage::BlockSyncMessage(BlockSyncMessage::BlockHeaderResponse(msg))
+            }
             #[cfg(test)]
             Message::TestBlockSyncMsgSentinel(id) => {
                 CategorizedMessage::BlockSyncMessage(BlockSyncMessage::TestSentinel(id))
@@ -320,6 +406,12 @@ impl Message {
             Message::TransactionResponse(msg) => CategorizedMessage::TransactionSyncMessage(
                 TransactionSyncMessage::TransactionResponse(msg),
             ),
+            Message::GetMempool(msg) => {
+                CategorizedMessage::TransactionSyncMessage(TransactionSyncMessage::GetMempool(msg))
+            }
+            Message::MempoolResponse(msg) => CategorizedMessage::TransactionSyncMessage(
+                TransactionSyncMessage::MempoolResponse(msg),
+            ),
         }
     }
 }
@@ -330,6 +422,60 @@ pub fn can_send_will_disconnect(peer_protocol_version: ProtocolVersion) -> bool
     peer_protocol_version >= SupportedProtocolVersion::V3.into()
 }
 
+/// Return true if the message size limits are exchanged during the handshake with the specified
+/// protocol version.
+pub fn can_exchange_message_size_limits(protocol_version: SupportedProtocolVersion) -> bool {
+    protocol_version >= SupportedProtocolVersion::V4
+}
+
+/// Return true if the node ids are exchanged during the handshake with the specified
+/// protocol version.
+pub fn can_exchange_node_ids(protocol_version: SupportedProtocolVersion) -> bool {
+    protocol_version >= SupportedProtocolVersion::V5
+}
+
+/// The tag (i.e. the first byte of the encoded `Message`) of the messages of the specified type.
+pub fn size_limited_message_tag(message_type: SizeLimitedMessageType) -> u8 {
+    // Note: these must match the codec indices of the corresponding `Message` variants.
+    match message_type {
+        SizeLimitedMessageType::HeaderList => 5,
+        SizeLimitedMessageType::BlockResponse => 7,
+        SizeLimitedMessageType::AddrListResponse => 10,
+        SizeLimitedMessageType::TransactionResponse => 12,
+    }
+}
+
+/// Convert the limits to the form used by the message codec.
+pub fn message_tag_size_limits(limits: &MessageSizeLimits) -> MessageTagSizeLimits {
+    enum_iterator::all::<SizeLimitedMessageType>()
+        .map(|message_type| {
+            (
+                size_limited_message_tag(message_type),
+                limits.get(message_type),
+            )
+        })
+        .collect()
+}
+
+/// If the error means that the peer has sent a message exceeding the limit for its type,
+/// return the corresponding protocol error.
+pub fn message_size_limit_error(error: &NetworkingError) -> Option<ProtocolError> {
+    match error {
+        NetworkingError::MessageCodecError(MessageCodecError::MessageTypeTooLarge {
+            tag,
+            actual_size,
+            max_size,
+        }) => enum_iterator::all::<SizeLimitedMessageType>()
+            .find(|message_type| size_limited_message_tag(*message_type) == *tag)
+            .map(|message_type| ProtocolError::MessageSizeLimitExceeded {
+                message_type,
+                size: *actual_size,
+                max_size: *max_size,
+            }),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
@@ -337,14 +483,20 @@ mod tests {
     use chainstate::Locator;
     use chainstate_test_framework::TestFramework;
     use common::{
-        chain::config::MagicBytes,
-        primitives::{semver::SemVer, Id},
+        chain::{
+            block::{timestamp::BlockTimestamp, BlockHeader, ConsensusData},
+            config::MagicBytes,
+            signature::inputsig::InputWitness,
+            SignedTransaction, TxInput,
+        },
+        primitives::{semver::SemVer, Id, H256},
     };
     use networking::test_helpers::{get_two_connected_sockets, TestTransportChannel};
     use networking::transport::{BufferedTranscoder, MpscChannelTransport};
     use p2p_types::services::Service;
-    use randomness::Rng;
+    use randomness::{CryptoRng, Rng};
     use test_utils::random::Seed;
+    use tokio::io::AsyncWriteExt;
 
     use crate::{
         message::{
@@ -415,6 +567,16 @@ mod tests {
                 ),
                 current_time: P2pTimestamp::from_int_seconds(rng.gen()),
             }),
+            Message::Handshake(HandshakeMessage::MessageSizeLimits(MessageSizeLimits {
+                block_response: rng.gen(),
+                header_list: rng.gen(),
+                addr_list_response: rng.gen(),
+                transaction_response: rng.gen(),
+            })),
+            Message::Handshake(HandshakeMessage::NodeId(Some(NodeId::from(
+                rng.gen::<[u8; 32]>(),
+            )))),
+            Message::Handshake(HandshakeMessage::NodeId(None)),
             Message::PingRequest(PingRequest { nonce: rng.gen() }),
             Message::PingResponse(PingResponse { nonce: rng.gen() }),
             Message::NewTransaction(Id::new(rng.gen())),
@@ -448,6 +610,13 @@ mod tests {
                 )
                 .into()],
             }),
+            Message::GetMempool(MempoolRequest { max_txs: rng.gen() }),
+            Message::MempoolResponse(vec![Id::new(rng.gen()), Id::new(rng.gen())]),
+            Message::BlockHeaderRequest(BlockHeaderRequest::new(vec![
+                Id::new(rng.gen()),
+                Id::new(rng.gen()),
+            ])),
+            Message::BlockHeaderResponse(BlockHeaderResponse::new(vec![block.header().clone()])),
         ];
 
         let (socket1, socket2) =
@@ -468,4 +637,268 @@ mod tests {
         assert!(sender.is_empty());
         assert!(receiver.is_empty());
     }
+
+    /// Frame the encoded message the same way the codec does, send it over the socket
+    /// and return the result of receiving it.
+    async fn send_raw_message(encoded: &[u8]) -> networking::Result<Message> {
+        let (mut socket1, socket2) =
+            get_two_connected_sockets::<TestTransportChannel, MpscChannelTransport>().await;
+        let mut receiver = BufferedTranscoder::<_, Message>::new(socket2, None);
+
+        socket1.write_all(&u32::to_le_bytes(encoded.len() as u32)).await.unwrap();
+        socket1.write_all(encoded).await.unwrap();
+        receiver.recv().await
+    }
+
+    /// Replace the single byte zero compact integer at the position with its non-minimal
+    /// two byte encoding
+    fn make_non_canonical(encoded: &[u8], pos: usize) -> Vec<u8> {
+        assert_eq!(encoded[pos], 0);
+        let mut non_canonical = encoded.to_vec();
+        non_canonical.splice(pos..pos + 1, [0b01, 0]);
+        non_canonical
+    }
+
+    // Transactions and headers are only accepted in their canonical encoding.
+    #[tracing::instrument(skip(seed))]
+    #[rstest::rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    #[tokio::test]
+    async fn non_canonical_consensus_objects(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let input = TxInput::from_utxo(
+            Id::<Transaction>::new(H256::random_using(&mut rng)).into(),
+            0,
+        );
+        let tx = Transaction::new(0, vec![input], vec![]).unwrap();
+        let tx = SignedTransaction::new(tx, vec![InputWitness::NoSignature(None)]).unwrap();
+        let header = BlockHeader::new(
+            Id::new(H256::random_using(&mut rng)),
+            H256::random_using(&mut rng),
+            H256::random_using(&mut rng),
+            BlockTimestamp::from_int_seconds(0),
+            ConsensusData::None,
+        )
+        .with_no_signature();
+
+        let message = Message::TransactionResponse(TransactionResponse::Found(tx));
+        let encoded = message.encode();
+        assert_eq!(send_raw_message(&encoded).await.unwrap(), message);
+        // The zero flags of the transaction follow the message tag, the variant index
+        // and the version tag of the transaction
+        let error = send_raw_message(&make_non_canonical(&encoded, 3)).await.unwrap_err();
+        assert!(matches!(
+            error,
+            NetworkingError::MessageCodecError(MessageCodecError::InvalidEncodedData(_))
+        ));
+
+        let message = Message::HeaderList(HeaderList::new(vec![header]));
+        let encoded = message.encode();
+        assert_eq!(send_raw_message(&encoded).await.unwrap(), message);
+        // The zero timestamp of the header follows the message tag, the length of the list,
+        // the version tag of the header and three hashes
+        let error = send_raw_message(&make_non_canonical(&encoded, 3 + 3 * 32)).await.unwrap_err();
+        assert!(matches!(
+            error,
+            NetworkingError::MessageCodecError(MessageCodecError::InvalidEncodedData(_))
+        ));
+    }
+
+    fn make_size_limited_message(
+        message_type: SizeLimitedMessageType,
+        tf: &mut TestFramework,
+        rng: &mut (impl Rng + CryptoRng),
+    ) -> Message {
+        let block = tf.make_block_builder().add_test_transaction_from_best_block(rng).build(rng);
+
+        match message_type {
+            SizeLimitedMessageType::BlockResponse => {
+                Message::BlockResponse(BlockResponse::new(block))
+            }
+            SizeLimitedMessageType::HeaderList => Message::HeaderList(HeaderList::new(
+                (0..rng.gen_range(1..100)).map(|_| block.header().clone()).collect(),
+            )),
+            SizeLimitedMessageType::AddrListResponse => {
+                Message::AddrListResponse(AddrListResponse {
+                    addresses: (0..rng.gen_range(1..100))
+                        .map(|_| {
+                            SocketAddr::new(
+                                IpAddr::V4(Ipv4Addr::new(
+                                    rng.gen(),
+                                    rng.gen(),
+                                    rng.gen(),
+                                    rng.gen(),
+                                )),
+                                rng.gen(),
+                            )
+                            .into()
+                        })
+                        .collect(),
+                })
+            }
+            SizeLimitedMessageType::TransactionResponse => Message::TransactionResponse(
+                TransactionResponse::Found(block.transactions()[0].clone()),
+            ),
+        }
+    }
+
+    fn limits_with(message_type: SizeLimitedMessageType, limit: usize) -> MessageSizeLimits {
+        let mut limits = MessageSizeLimits::protocol_defaults();
+        let limit = limit as u64;
+        match message_type {
+            SizeLimitedMessageType::BlockResponse => limits.block_response = limit,
+            SizeLimitedMessageType::HeaderList => limits.header_list = limit,
+            SizeLimitedMessageType::AddrListResponse => limits.addr_list_response = limit,
+            SizeLimitedMessageType::TransactionResponse => limits.transaction_response = limit,
+        }
+        limits
+    }
+
+    /// Send the message to a receiver that applies the specified limits and return the result
+    /// of receiving it.
+    async fn send_to_limited_receiver(
+        message: Message,
+        limits: &MessageSizeLimits,
+    ) -> networking::Result<Message> {
+        let (socket1, socket2) =
+            get_two_connected_sockets::<TestTransportChannel, MpscChannelTransport>().await;
+        let mut sender = BufferedTranscoder::new(socket1, None);
+        let mut receiver = BufferedTranscoder::<_, Message>::new(socket2, None);
+        receiver.set_recv_tag_size_limits(message_tag_size_limits(limits));
+
+        sender.send(message).await.unwrap();
+        receiver.recv().await
+    }
+
+    // The tags used for the size limits must match the actual encoding of the messages.
+    #[tracing::instrument(skip(seed))]
+    #[rstest::rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn size_limited_message_tags(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        for message_type in enum_iterator::all::<SizeLimitedMessageType>() {
+            let message = make_size_limited_message(message_type, &mut tf, &mut rng);
+            assert_eq!(
+                message.encode()[0],
+                size_limited_message_tag(message_type),
+                "{message_type}"
+            );
+        }
+    }
+
+    // For each size-limited message type, a message exactly at the limit is accepted and
+    // a message one byte over the limit is rejected, both by the sender and by the receiver.
+    #[tracing::instrument(skip(seed))]
+    #[rstest::rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    #[tokio::test]
+    async fn message_size_limit_boundaries(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        for message_type in enum_iterator::all::<SizeLimitedMessageType>() {
+            let message = make_size_limited_message(message_type, &mut tf, &mut rng);
+            let size = message.encoded_size();
+
+            let received =
+                send_to_limited_receiver(message.clone(), &limits_with(message_type, size))
+                    .await
+                    .unwrap();
+            assert_eq!(received, message);
+
+            let error =
+                send_to_limited_receiver(message.clone(), &limits_with(message_type, size - 1))
+                    .await
+                    .unwrap_err();
+            assert_eq!(
+                message_size_limit_error(&error),
+                Some(ProtocolError::MessageSizeLimitExceeded {
+                    message_type,
+                    size,
+                    max_size: size - 1,
+                })
+            );
+
+            // Other limits don't affect the message.
+            for other_type in enum_iterator::all::<SizeLimitedMessageType>() {
+                if other_type != message_type {
+                    let received =
+                        send_to_limited_receiver(message.clone(), &limits_with(other_type, 0))
+                            .await
+                            .unwrap();
+                    assert_eq!(received, message);
+                }
+            }
+
+            let (socket1, _socket2) =
+                get_two_connected_sockets::<TestTransportChannel, MpscChannelTransport>().await;
+            let mut sender = BufferedTranscoder::<_, Message>::new(socket1, None);
+            sender.set_send_tag_size_limits(message_tag_size_limits(&limits_with(
+                message_type,
+                size - 1,
+            )));
+            assert_eq!(
+                sender.send(message.clone()).await,
+                Err(NetworkingError::MessageCodecError(
+                    MessageCodecError::MessageTypeTooLarge {
+                        tag: size_limited_message_tag(message_type),
+                        actual_size: size,
+                        max_size: size - 1,
+                    }
+                ))
+            );
+            sender.set_send_tag_size_limits(message_tag_size_limits(&limits_with(
+                message_type,
+                size,
+            )));
+            sender.send(message).await.unwrap();
+        }
+    }
+
+    // A header list with the maximum number of headers fits into the default limit; it's accepted
+    // when its size is exactly at the limit and rejected when it's one byte over.
+    #[tracing::instrument(skip(seed))]
+    #[rstest::rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    #[tokio::test]
+    async fn header_list_size_limit(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let p2p_config = test_p2p_config();
+        let mut tf = TestFramework::builder(&mut rng).build();
+        let block = tf
+            .make_block_builder()
+            .add_test_transaction_from_best_block(&mut rng)
+            .build(&mut rng);
+
+        let message = Message::HeaderList(HeaderList::new(vec![
+            block.header().clone();
+            *p2p_config
+                .protocol_config
+                .msg_header_count_limit
+        ]));
+        let size = message.encoded_size();
+        assert!(size <= *p2p_config.protocol_config.max_header_list_message_size);
+
+        let limits = limits_with(SizeLimitedMessageType::HeaderList, size);
+        let received = send_to_limited_receiver(message.clone(), &limits).await.unwrap();
+        assert_eq!(received, message);
+
+        let limits = limits_with(SizeLimitedMessageType::HeaderList, size - 1);
+        let error = send_to_limited_receiver(message, &limits).await.unwrap_err();
+        assert_eq!(
+            message_size_limit_error(&error),
+            Some(ProtocolError::MessageSizeLimitExceeded {
+                message_type: SizeLimitedMessageType::HeaderList,
+                size,
+                max_size: size - 1,
+            })
+        );
+    }
 }
@@ -34,9 +34,10 @@ use utils::atomics::SeqCstAtomicBool;
 use crate::{
     config,
     disconnection_reason::DisconnectionReason,
-    message::{BlockSyncMessage, PeerManagerMessage, TransactionSyncMessage},
+    message::{BlockResponse, BlockSyncMessage, PeerManagerMessage, TransactionSyncMessage},
+    sync::block_serving::BufferedBlockResponse,
     types::peer_id::PeerId,
-    P2pEventHandler,
+    P2pEvent, P2pEventHandler,
 };
 
 /// [NetworkingService] provides the low-level network interface
@@ -118,6 +119,9 @@ where
     /// Sends a message to the given peer.
     fn send_message(&mut self, peer: PeerId, message: PeerManagerMessage) -> crate::Result<()>;
 
+    /// Send the event to the p2p event subscribers
+    fn broadcast_event(&mut self, event: P2pEvent) -> crate::Result<()>;
+
     /// Return the socket addresses of the network service provider
     fn local_addresses(&self) -> &[SocketAddress];
 
@@ -139,6 +143,15 @@ pub trait MessagingService: Clone {
         message: BlockSyncMessage,
     ) -> crate::Result<()>;
 
+    /// Sends a block response to the peer; the guard is dropped once the response has been
+    /// written to the socket or discarded.
+    fn send_block_response(
+        &mut self,
+        peer: PeerId,
+        response: BlockResponse,
+        guard: BufferedBlockResponse,
+    ) -> crate::Result<()>;
+
     /// Sends a transaction sync message to the peer.
     fn send_transaction_sync_message(
         &mut self,
@@ -21,7 +21,7 @@ use common::{
     chain::{config::MagicBytes, ChainConfig},
     primitives::{semver::SemVer, user_agent::UserAgent},
 };
-use p2p_types::socket_address::SocketAddress;
+use p2p_types::{socket_address::SocketAddress, NodeId};
 use tokio::sync::mpsc::Receiver;
 
 use crate::{
@@ -108,6 +108,9 @@ pub struct PeerInfo {
     /// All services that will be enabled for this peer if it's accepted.
     /// The Peer Manager can disconnect the peer if some required services are missing.
     pub common_services: Services,
+
+    /// The node id advertised by the peer, if any.
+    pub peer_node_id: Option<NodeId>,
 }
 
 impl PeerInfo {
@@ -137,6 +140,9 @@ impl Display for PeerInfo {
         writeln!(f, "--> Network: {:x?}", self.network)?;
         writeln!(f, "--> Software version: {}", self.software_version)?;
         writeln!(f, "--> User agent: {}", self.user_agent)?;
+        if let Some(peer_node_id) = &self.peer_node_id {
+            writeln!(f, "--> Node ID: {peer_node_id:x}")?;
+        }
 
         Ok(())
     }
@@ -222,6 +228,15 @@ pub enum ConnectivityEvent {
         /// Error that occurred
         error: P2pError,
     },
+
+    /// An inbound peer has advertised its node id after the connection has been accepted
+    PeerNodeIdReceived {
+        /// Unique ID of the peer
+        peer_id: PeerId,
+
+        /// The advertised node id
+        node_id: NodeId,
+    },
 }
 
 /// Syncing-related events (sent from the backend)
@@ -232,6 +247,7 @@ pub enum SyncingEvent {
         peer_id: PeerId,
         common_services: Services,
         protocol_version: SupportedProtocolVersion,
+        inbound: bool,
         block_sync_msg_receiver: Receiver<BlockSyncMessage>,
         transaction_sync_msg_receiver: Receiver<TransactionSyncMessage>,
     },
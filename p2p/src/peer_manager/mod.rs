@@ -34,28 +34,39 @@ use std::{
 use futures::never::Never;
 use tokio::sync::mpsc;
 
-use chainstate::ban_score::BanScore;
+use chainstate::{ban_score::BanScore, ForkDetector, PossibleFork};
 use common::{
-    chain::ChainConfig,
-    primitives::time::{duration_to_int, Time},
+    chain::{ChainConfig, GenBlock},
+    primitives::{
+        time::{duration_to_int, Time},
+        BlockHeight, Id,
+    },
     time_getter::TimeGetter,
 };
 use logging::log;
 use networking::types::ConnectionDirection;
-use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress, IsGlobalIp};
-use randomness::{make_pseudo_rng, seq::IteratorRandom, Rng};
-use utils::{bloom_filters::rolling_bloom_filter::RollingBloomFilter, ensure, set_flag::SetFlag};
+use p2p_types::{
+    bannable_address::BannableAddress, p2p_event::P2pEvent, socket_address::SocketAddress,
+    IsGlobalIp,
+};
+use randomness::{
+    make_pseudo_rng,
+    seq::{IteratorRandom, SliceRandom},
+    Rng,
+};
+use utils::{bloom_filters::rolling_bloom_filter::RollingBloomFilter, ensure};
 use utils_networking::IpOrSocketAddress;
 
 use crate::{
     config::P2pConfig,
     disconnection_reason::DisconnectionReason,
     error::{ConnectionValidationError, P2pError, PeerError, ProtocolError},
-    interface::types::ConnectedPeer,
+    interface::types::{BannedAddress, ConnectedPeer, MisbehaviorEvent},
     message::{
         AddrListRequest, AddrListResponse, AnnounceAddrRequest, PeerManagerMessage, PingRequest,
         PingResponse, WillDisconnectMessage,
     },
+    metrics::metrics,
     net::{
         types::{
             services::{Service, Services},
@@ -64,7 +75,7 @@ use crate::{
         ConnectivityService, NetworkingService,
     },
     peer_manager_event::PeerDisconnectionDbAction,
-    sync::sync_status::PeerBlockSyncStatus,
+    sync::sync_status::{PeerBlockSyncStatus, SyncStateSnapshot},
     types::{
         peer_address::{PeerAddress, PeerAddressIp4, PeerAddressIp6},
         peer_id::PeerId,
@@ -103,10 +114,25 @@ pub const ADDR_RATE_BUCKET_SIZE: u32 = 10;
 /// To how many peers resend received address
 const PEER_ADDRESS_RESEND_COUNT: usize = 2;
 
+/// How many outbound full relay peers are remembered on shutdown and dialed first after restart
+const MAX_ANCHOR_PEERS: usize = 2;
+
 // Use the same parameters as Bitcoin Core (last 5000 addresses)
 const PEER_ADDRESSES_ROLLING_BLOOM_FILTER_SIZE: usize = 5000;
 const PEER_ADDRESSES_ROLLING_BLOOM_FPP: f64 = 0.001;
 
+/// How often the connected peers may be asked for more addresses when there are not enough
+/// addresses to establish outbound connections to.
+/// This is not shorter than the lifetime of a cached address list response, so that the peer
+/// is likely to respond with new addresses.
+pub const ADDR_LIST_REQUEST_INTERVAL: Duration = addr_list_response_cache::EXPIRATION_INTERVAL_MAX;
+
+/// Repeated address list requests from a peer are ignored if they come more often than this.
+const ADDR_LIST_REQUEST_MIN_INTERVAL: Duration = addr_list_response_cache::EXPIRATION_INTERVAL_MIN;
+
+/// How many of the most recent misbehavior events are reported for each banned address
+const BANNED_ADDRESS_MISBEHAVIOR_EVENTS: usize = 5;
+
 enum OutboundConnectType {
     Automatic {
         block_relay_only: bool,
@@ -207,6 +233,12 @@ where
     init_time: Time,
     /// Last time when a new tip was added to the chainstate.
     last_chainstate_tip_block_time: Option<Time>,
+    /// The current chainstate tip, if known.
+    chainstate_tip: Option<Id<GenBlock>>,
+    /// Tracks the best blocks reported by peers to detect a possible fork.
+    fork_detector: ForkDetector<PeerId>,
+    /// The height of the block that was received last, from any peer.
+    last_received_block_height: BlockHeight,
     /// Last heartbeat time.
     last_heartbeat_time: Option<Time>,
     /// Last time dns seed was queried.
@@ -215,6 +247,9 @@ where
     last_ping_check_time: Option<Time>,
     /// The time after which a new feeler connection can be established.
     next_feeler_connection_time: Time,
+    /// The external address of this node on the local gateway, obtained with port mapping.
+    /// If set, it's announced to peers instead of the addresses they report.
+    port_mapping_address: Option<SocketAddress>,
 }
 
 /// Takes IP or socket address and converts it to socket address (adding the default peer port if IP address is used)
@@ -297,10 +332,14 @@ where
             dns_seed,
             init_time: now,
             last_chainstate_tip_block_time: None,
+            chainstate_tip: None,
+            fork_detector: ForkDetector::new(),
+            last_received_block_height: BlockHeight::zero(),
             last_heartbeat_time: None,
             last_dns_query_time: None,
             last_ping_check_time: None,
             next_feeler_connection_time,
+            port_mapping_address: None,
         })
     }
 
@@ -331,16 +370,16 @@ where
         common_services: Services,
         node_address_as_seen_by_peer: Option<PeerAddress>,
     ) -> Option<SocketAddress> {
-        let discover = match peer_role {
-            PeerRole::Inbound | PeerRole::OutboundBlockRelay | PeerRole::Feeler => false,
-            PeerRole::OutboundFullRelay | PeerRole::OutboundReserved | PeerRole::OutboundManual => {
-                common_services.has_service(Service::PeerAddresses)
-            }
-        };
-        if !discover {
+        if !self.should_discover_own_address(peer_role, common_services) {
             return None;
         }
 
+        // The mapped address is known to be reachable, unlike the one reported by the peer,
+        // whose port may have been chosen by the NAT.
+        if let Some(port_mapping_address) = &self.port_mapping_address {
+            return Some(*port_mapping_address);
+        }
+
         let node_address_as_seen_by_peer = node_address_as_seen_by_peer?;
 
         // Take IP and use port numbers from all listening sockets (with same IP version)
@@ -385,6 +424,62 @@ where
         chosen_discovered_address
     }
 
+    fn should_discover_own_address(&self, peer_role: PeerRole, common_services: Services) -> bool {
+        // The own address is not advertised in the reserved-nodes-only mode,
+        // so there is no need to discover it.
+        if self.reserved_nodes_only() {
+            return false;
+        }
+
+        match peer_role {
+            PeerRole::Inbound | PeerRole::OutboundBlockRelay | PeerRole::Feeler => false,
+            PeerRole::OutboundFullRelay | PeerRole::OutboundReserved | PeerRole::OutboundManual => {
+                common_services.has_service(Service::PeerAddresses)
+            }
+        }
+    }
+
+    /// Update the external address obtained with port mapping (`None` if the mapping is lost)
+    /// and announce the new own address to the connected peers.
+    fn set_port_mapping_address(&mut self, address: Option<SocketAddress>) {
+        let address = address.and_then(|address| {
+            address
+                .as_peer_address()
+                .as_discoverable_socket_address(*self.p2p_config.allow_discover_private_ips)
+        });
+        if address == self.port_mapping_address {
+            return;
+        }
+        log::info!("External address from port mapping changed: {address:?}");
+        self.port_mapping_address = address;
+
+        let peers = self
+            .peers
+            .values()
+            .map(|peer| {
+                (
+                    peer.info.peer_id,
+                    peer.peer_role,
+                    peer.info.common_services,
+                    peer.node_address_as_seen_by_peer.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        for (peer_id, peer_role, common_services, node_address_as_seen_by_peer) in peers {
+            let discovered_own_address = self.discover_own_address(
+                peer_id,
+                peer_role,
+                common_services,
+                node_address_as_seen_by_peer,
+            );
+            let peer = self.peers.get_mut(&peer_id).expect("peer must be known");
+            if peer.discovered_own_address != discovered_own_address {
+                peer.discovered_own_address = discovered_own_address;
+                Self::send_own_address_to_peer(&mut self.peer_connectivity_handle, peer);
+            }
+        }
+    }
+
     /// Send address announcement to the selected peer (if the address is new)
     /// `peer_id` must be from the connected peer.
     fn announce_address(&mut self, peer_id: PeerId, address: SocketAddress) {
@@ -405,6 +500,7 @@ where
         peer_connectivity_handle: &mut T::ConnectivityHandle,
         peer: &PeerContext,
     ) {
+        // Note: `discovered_own_address` is never set in the reserved-nodes-only mode.
         if let Some(discovered_addr) = peer.discovered_own_address.as_ref() {
             Self::send_peer_message(
                 peer_connectivity_handle,
@@ -427,6 +523,19 @@ where
         }
     }
 
+    fn reserved_nodes_only(&self) -> bool {
+        self.p2p_config.reserved_nodes_only.unwrap_or(false)
+    }
+
+    /// Check whether the address belongs to a reserved node.
+    ///
+    /// Only the ip address is compared, because inbound connections come from arbitrary ports.
+    fn is_reserved_node_ip(&self, address: &SocketAddress) -> bool {
+        self.peerdb
+            .get_reserved_nodes()
+            .any(|reserved_address| reserved_address.ip_addr() == address.ip_addr())
+    }
+
     fn is_whitelisted_node(&self, peer_role: PeerRole, address: &SocketAddress) -> bool {
         match peer_role {
             PeerRole::Inbound
@@ -441,8 +550,9 @@ where
 
     /// Adjust peer score
     ///
+    /// The adjustment is recorded in the misbehavior log along with the reason.
     /// Discourage the peer if the score reaches the corresponding threshold.
-    fn adjust_peer_score(&mut self, peer_id: PeerId, score: u32) {
+    fn adjust_peer_score(&mut self, peer_id: PeerId, score: u32, reason: &str) {
         let peer = match self.peers.get(&peer_id) {
             Some(peer) => peer,
             None => return,
@@ -467,6 +577,17 @@ where
             peer.score
         );
 
+        if score > 0 {
+            self.peerdb.record_misbehavior(MisbehaviorEvent {
+                time: self.time_getter.get_time(),
+                peer_id: Some(peer_id),
+                address: peer.peer_address,
+                reason: reason.to_owned(),
+                score_adjustment: score,
+                total_score: peer.score,
+            });
+        }
+
         if let Some(o) = self.observer.as_mut() {
             o.on_peer_ban_score_adjustment(peer.peer_address, peer.score)
         }
@@ -481,7 +602,12 @@ where
     ///
     /// Note that currently intermediate scores are not stored in the peer db, so this call will
     /// only make any effect if the passed score is bigger than the threshold.
-    fn adjust_peer_score_on_failed_handshake(&mut self, peer_address: SocketAddress, score: u32) {
+    fn adjust_peer_score_on_failed_handshake(
+        &mut self,
+        peer_address: SocketAddress,
+        score: u32,
+        reason: &str,
+    ) {
         let whitelisted_node =
             self.pending_outbound_connects
                 .get(&peer_address)
@@ -498,6 +624,17 @@ where
             return;
         }
 
+        if score > 0 {
+            self.peerdb.record_misbehavior(MisbehaviorEvent {
+                time: self.time_getter.get_time(),
+                peer_id: None,
+                address: peer_address,
+                reason: reason.to_owned(),
+                score_adjustment: score,
+                total_score: score,
+            });
+        }
+
         if let Some(o) = self.observer.as_mut() {
             o.on_peer_ban_score_adjustment(peer_address, score);
         }
@@ -508,6 +645,52 @@ where
         }
     }
 
+    fn list_banned(&self) -> Vec<BannedAddress> {
+        self.peerdb
+            .list_banned()
+            .map(|(address, banned_until)| {
+                let mut recent_misbehavior = self
+                    .peerdb
+                    .misbehavior_events()
+                    .rev()
+                    .filter(|event| event.address.as_bannable() == address)
+                    .take(BANNED_ADDRESS_MISBEHAVIOR_EVENTS)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                recent_misbehavior.reverse();
+
+                BannedAddress {
+                    address,
+                    banned_until,
+                    recent_misbehavior,
+                }
+            })
+            .collect()
+    }
+
+    /// Return up to `limit` most recent misbehavior events, oldest first
+    fn misbehavior_log(&self, limit: usize, peer_filter: Option<PeerId>) -> Vec<MisbehaviorEvent> {
+        let mut events = self
+            .peerdb
+            .misbehavior_events()
+            .rev()
+            .filter(|event| peer_filter.is_none() || event.peer_id == peer_filter)
+            .take(limit)
+            .cloned()
+            .collect::<Vec<_>>();
+        events.reverse();
+        events
+    }
+
+    /// Return the connected peers whose ban score is at least `min_score`, ordered by peer id
+    fn misbehaving_peers(&self, min_score: u32) -> Vec<(PeerId, SocketAddress, u32)> {
+        self.peers
+            .values()
+            .filter(|peer| peer.score >= min_score)
+            .map(|peer| (peer.info.peer_id, peer.peer_address, peer.score))
+            .collect()
+    }
+
     fn bannable_peers_for_addr(&self, address: BannableAddress) -> Vec<PeerId> {
         self.peers
             .values()
@@ -531,6 +714,7 @@ where
         );
 
         self.peerdb.ban(address, duration);
+        metrics().bans.inc();
 
         if let Some(o) = self.observer.as_mut() {
             o.on_peer_ban(address);
@@ -643,6 +827,7 @@ where
             }
             Err(e) => {
                 log::debug!("outbound connection to {address:?} failed: {e}");
+                self.remove_anchor(&address);
                 match outbound_connect_type {
                     OutboundConnectType::Automatic {
                         block_relay_only: _,
@@ -730,6 +915,7 @@ where
         );
 
         info.check_compatibility(&self.chain_config)?;
+        self.check_admission_filters(address, peer_role, info)?;
 
         let is_peer_connected = self.is_peer_connected(info.peer_id);
         // This is a rather strange situation that should never happen.
@@ -762,6 +948,15 @@ where
 
         match peer_role {
             PeerRole::Inbound => {
+                if self.reserved_nodes_only() && !self.is_reserved_node_ip(address) {
+                    log::debug!("Rejecting inbound connection from non-reserved address {address}");
+                    return Err(P2pError::ConnectionValidationFailed(
+                        ConnectionValidationError::NotReservedNode {
+                            address: address.to_string(),
+                        },
+                    ));
+                }
+
                 // If the maximum number of inbound connections is reached,
                 // the new inbound connection cannot be accepted even if it's valid.
                 // Outbound peer count is not checked because the node initiates new connections
@@ -818,6 +1013,43 @@ where
         Ok(())
     }
 
+    /// Check the peer against the admission filters configured by the operator.
+    ///
+    /// The reserved nodes bypass the user agent filters, but not the protocol version floor.
+    fn check_admission_filters(
+        &self,
+        address: &SocketAddress,
+        peer_role: PeerRole,
+        info: &PeerInfo,
+    ) -> crate::Result<()> {
+        let admission_config = &self.p2p_config.peer_admission_config;
+
+        let peer_protocol_version = info.protocol_version.into_raw_version();
+        if let Some(min_protocol_version) = admission_config.min_protocol_version {
+            ensure!(
+                admission_config.is_protocol_version_allowed(peer_protocol_version),
+                P2pError::ConnectionValidationFailed(
+                    ConnectionValidationError::ProtocolVersionBelowMinimum {
+                        peer_protocol_version,
+                        min_protocol_version,
+                    }
+                ),
+            );
+        }
+
+        let is_reserved =
+            peer_role == PeerRole::OutboundReserved || self.is_reserved_node_ip(address);
+        let user_agent = info.user_agent.to_string();
+        ensure!(
+            is_reserved || admission_config.is_user_agent_allowed(&user_agent),
+            P2pError::ConnectionValidationFailed(ConnectionValidationError::UserAgentNotAllowed {
+                user_agent
+            }),
+        );
+
+        Ok(())
+    }
+
     fn eviction_candidates(&self, peer_role: PeerRole) -> Vec<peers_eviction::EvictionCandidate> {
         let now = self.time_getter.get_time();
         self.peers
@@ -948,7 +1180,12 @@ where
             self.subscribed_to_peer_addresses.insert(info.peer_id);
         }
 
-        if Self::should_load_addresses_from(peer_role) {
+        // Outbound peers are asked for addresses right after the handshake, so that a new node
+        // fills its address book quickly. Inbound peers are never asked, which limits the ability
+        // of the connecting nodes to fingerprint the node by the addresses it knows.
+        let now = self.time_getter.get_time();
+        let load_addresses = Self::should_load_addresses_from(peer_role);
+        if load_addresses {
             log::debug!("Asking peer {peer_id} for addresses");
             Self::send_peer_message(
                 &mut self.peer_connectivity_handle,
@@ -974,11 +1211,11 @@ where
             peer_id,
             peer_role,
             info.common_services,
-            node_address_as_seen_by_peer,
+            node_address_as_seen_by_peer.clone(),
         );
 
         let peer = PeerContext {
-            created_at: self.time_getter.get_time(),
+            created_at: now,
             info,
             peer_address,
             bind_address,
@@ -987,11 +1224,13 @@ where
             sent_ping: None,
             ping_last: None,
             ping_min: None,
-            addr_list_req_received: SetFlag::new(),
-            addr_list_resp_received: SetFlag::new(),
+            last_addr_list_req_received_time: None,
+            last_addr_list_req_sent_time: load_addresses.then_some(now),
+            addr_list_resp_expected: load_addresses,
             announced_addresses,
             address_rate_limiter,
             discovered_own_address,
+            node_address_as_seen_by_peer,
             last_tip_block_time: None,
             last_tx_time: None,
             block_sync_status: PeerBlockSyncStatus::new(),
@@ -1001,31 +1240,12 @@ where
 
         let old_value = self.peers.insert(peer_id, peer);
         assert!(old_value.is_none());
+        metrics().peers(peer_role).inc();
 
         if peer_role.is_outbound() {
             self.peerdb.outbound_peer_connected(peer_address);
         }
 
-        if peer_role == PeerRole::OutboundBlockRelay {
-            let anchor_addresses = self
-                .peers
-                .values()
-                .filter_map(|peer| match peer.peer_role {
-                    PeerRole::Inbound
-                    | PeerRole::OutboundFullRelay
-                    | PeerRole::OutboundReserved
-                    | PeerRole::OutboundManual
-                    | PeerRole::Feeler => None,
-                    PeerRole::OutboundBlockRelay => Some(peer.peer_address),
-                })
-                // Note: there may be more than outbound_block_relay_count block relay connections
-                // at a given moment, but the extra ones will soon be evicted. Since connections
-                // with smaller peer ids are less likely to be evicted, we choose them here.
-                .take(*self.p2p_config.peer_manager_config.outbound_block_relay_count)
-                .collect();
-            self.peerdb.set_anchors(anchor_addresses);
-        }
-
         if let Some(o) = self.observer.as_mut() {
             o.on_connection_accepted(peer_address, peer_role)
         }
@@ -1042,6 +1262,7 @@ where
         node_address_as_seen_by_peer: Option<PeerAddress>,
     ) {
         let peer_id = info.peer_id;
+        let peer_node_id = info.peer_node_id;
 
         let (peer_role, response_sender) = match conn_dir {
             ConnectionDirection::Inbound => (PeerRole::Inbound, None),
@@ -1095,13 +1316,33 @@ where
             if peer_role.is_outbound() {
                 self.peerdb.report_outbound_failure(peer_address);
             }
-        } else if peer_role == PeerRole::Feeler {
-            self.disconnect(
-                peer_id,
-                PeerDisconnectionDbAction::Keep,
-                Some(DisconnectionReason::FeelerConnection),
-                None,
-            );
+
+            // Stop dialing the peers rejected by the admission filters for a while
+            if let P2pError::ConnectionValidationFailed(validation_err) = accept_err {
+                if validation_err.is_rejected_by_admission_filter()
+                    && !self.is_whitelisted_node(peer_role, &peer_address)
+                {
+                    log::info!(
+                        "Discouraging address {peer_address} rejected by the admission filters"
+                    );
+                    self.peerdb.discourage(peer_address.as_bannable());
+                }
+            }
+        } else {
+            // Remember the address of the node, so that it can be found by its id
+            // if the address changes later
+            if let (true, Some(node_id)) = (peer_role.is_outbound(), peer_node_id) {
+                self.peerdb.set_node_address(node_id, peer_address);
+            }
+
+            if peer_role == PeerRole::Feeler {
+                self.disconnect(
+                    peer_id,
+                    PeerDisconnectionDbAction::Keep,
+                    Some(DisconnectionReason::FeelerConnection),
+                    None,
+                );
+            }
         }
 
         if let Some(response_sender) = response_sender {
@@ -1119,6 +1360,7 @@ where
     /// update its own records.
     fn handle_outbound_error(&mut self, address: SocketAddress, error: P2pError) {
         self.peerdb.report_outbound_failure(address);
+        self.remove_anchor(&address);
 
         let PendingConnect {
             outbound_connect_type,
@@ -1137,6 +1379,42 @@ where
         }
     }
 
+    /// Select the anchor peers, i.e. the longest-lived well-behaved outbound full relay peers,
+    /// and store them in the db, so that they are dialed first after restart.
+    ///
+    /// Block relay and feeler connections never become anchors.
+    fn store_anchors(&mut self) {
+        let mut candidates = self
+            .peers
+            .values()
+            .filter(|peer| match peer.peer_role {
+                PeerRole::OutboundFullRelay => peer.score == 0,
+                PeerRole::Inbound
+                | PeerRole::OutboundBlockRelay
+                | PeerRole::OutboundReserved
+                | PeerRole::OutboundManual
+                | PeerRole::Feeler => false,
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|peer| (peer.created_at, peer.info.peer_id));
+
+        let anchor_addresses = candidates
+            .into_iter()
+            .take(MAX_ANCHOR_PEERS)
+            .map(|peer| peer.peer_address)
+            .collect();
+        self.peerdb.set_anchors(anchor_addresses);
+    }
+
+    /// Forget the anchor peer if the connection to it has failed.
+    fn remove_anchor(&mut self, address: &SocketAddress) {
+        if self.peerdb.anchors().contains(address) {
+            let mut anchor_addresses = self.peerdb.anchors().clone();
+            anchor_addresses.remove(address);
+            self.peerdb.set_anchors(anchor_addresses);
+        }
+    }
+
     /// The connection to a remote peer is reported as closed.
     ///
     /// This can happen when the remote peer has dropped its connection
@@ -1149,6 +1427,7 @@ where
                 peer.info.peer_id,
                 peer.peer_address
             );
+            metrics().peers(peer.peer_role).dec();
 
             if peer.peer_role.is_outbound() {
                 self.peerdb.outbound_peer_disconnected(peer.peer_address);
@@ -1174,6 +1453,7 @@ where
             }
 
             self.subscribed_to_peer_addresses.remove(&peer_id);
+            self.fork_detector.remove_peer(&peer_id);
         }
     }
 
@@ -1239,6 +1519,8 @@ where
             self.evict_full_relay_peer();
         }
 
+        self.check_possible_fork();
+
         self.last_heartbeat_time = Some(self.time_getter.get_time());
 
         if let Some(o) = self.observer.as_mut() {
@@ -1246,6 +1528,39 @@ where
         }
     }
 
+    /// Notify the event subscribers if too many peers have been reporting best blocks
+    /// different from the local tip for too long.
+    fn check_possible_fork(&mut self) {
+        let Some(local_tip) = self.chainstate_tip else {
+            return;
+        };
+
+        let possible_fork = self.fork_detector.check(
+            local_tip,
+            *self.p2p_config.fork_alert_threshold,
+            *self.p2p_config.fork_alert_duration,
+            self.time_getter.get_time(),
+        );
+
+        if let Some(PossibleFork {
+            local_tip,
+            peer_tips,
+        }) = possible_fork
+        {
+            log::warn!(
+                "Possible fork detected, local tip: {local_tip}, tips reported by peers: {peer_tips:?}"
+            );
+
+            let res = self.peer_connectivity_handle.broadcast_event(P2pEvent::PossibleFork {
+                local_tip,
+                peer_tips,
+            });
+            if let Err(err) = res {
+                log::error!("broadcast_event failed unexpectedly: {err:?}");
+            }
+        }
+    }
+
     fn establish_new_connections(&mut self) {
         let mut cur_outbound_full_relay_conn_count = 0;
         let mut cur_outbound_block_relay_conn_count = 0;
@@ -1312,6 +1627,10 @@ where
         // manual connections (see CConnman::MaybePickPreferredNetwork for reference).
         // See https://github.com/mintlayer/mintlayer-core/issues/1433
 
+        if new_full_relay_conn_addresses.len() < needed_outbound_full_relay_conn_count {
+            self.request_more_addresses();
+        }
+
         for address in &new_full_relay_conn_addresses {
             let addr_group = AddressGroup::from_peer_address(&address.as_peer_address());
             cur_outbound_conn_addr_groups.insert(addr_group);
@@ -1392,6 +1711,32 @@ where
         }
     }
 
+    /// Ask the connected peers for more addresses, unless they have been asked recently.
+    fn request_more_addresses(&mut self) {
+        if self.reserved_nodes_only() {
+            return;
+        }
+
+        let now = self.time_getter.get_time();
+        for (peer_id, peer) in self.peers.iter_mut() {
+            let request_needed = Self::should_load_addresses_from(peer.peer_role)
+                && peer.last_addr_list_req_sent_time.map_or(true, |sent_time| {
+                    (sent_time + ADDR_LIST_REQUEST_INTERVAL)
+                        .is_some_and(|next_time| now >= next_time)
+                });
+            if request_needed {
+                log::debug!("Asking peer {peer_id} for more addresses");
+                peer.last_addr_list_req_sent_time = Some(now);
+                peer.addr_list_resp_expected = true;
+                Self::send_peer_message(
+                    &mut self.peer_connectivity_handle,
+                    *peer_id,
+                    PeerManagerMessage::AddrListRequest(AddrListRequest {}),
+                );
+            }
+        }
+    }
+
     fn handle_incoming_message(&mut self, peer: PeerId, message: PeerManagerMessage) {
         match message {
             PeerManagerMessage::AddrListRequest(_) => self.handle_addr_list_request(peer),
@@ -1424,7 +1769,18 @@ where
 
             peer.announced_addresses.insert(&address, &mut make_pseudo_rng());
 
-            self.peerdb.peer_discovered(address);
+            // The address is self-reported if the peer announces its own ip, otherwise it's
+            // being relayed on behalf of some other node.
+            let self_reported = address.ip_addr() == peer.peer_address.ip_addr();
+            let source_peer = peer.peer_address.as_bannable();
+
+            if !self.reserved_nodes_only() {
+                if self_reported {
+                    self.peerdb.peer_discovered(address);
+                } else {
+                    self.peerdb.address_discovered_from_peer(address, source_peer);
+                }
+            }
 
             if !self.peerdb.is_address_banned_or_discouraged(&address.as_bannable()) {
                 let peer_ids = self
@@ -1440,22 +1796,26 @@ where
     }
 
     fn handle_addr_list_request(&mut self, peer_id: PeerId) {
+        let now = self.time_getter.get_time();
         let peer = self.peers.get_mut(&peer_id).expect("peer must be known");
-        // Only one request allowed to reduce load in case of DoS attacks
-        if !Self::should_send_addresses_to(peer.peer_role)
-            || peer.addr_list_req_received.test_and_set()
-        {
+        // Repeated requests are only allowed after some time to reduce load in case of DoS attacks
+        let request_allowed = peer.last_addr_list_req_received_time.map_or(true, |received_time| {
+            (received_time + ADDR_LIST_REQUEST_MIN_INTERVAL)
+                .is_some_and(|next_time| now >= next_time)
+        });
+        if !Self::should_send_addresses_to(peer.peer_role) || !request_allowed {
             log::warn!("Ignore unexpected address list request from peer {peer_id}");
             return;
         }
+        peer.last_addr_list_req_received_time = Some(now);
 
         let max_addr_count = *self.p2p_config.protocol_config.max_addr_list_response_address_count;
 
-        let now = self.time_getter.get_time();
         let addresses = self
             .addr_list_response_cache
             .get_or_create(peer, now, || {
-                self.peerdb
+                let mut addresses = self
+                    .peerdb
                     .known_addresses()
                     .filter_map(|address| {
                         let peer_addr = address.as_peer_address();
@@ -1468,7 +1828,11 @@ where
                             None
                         }
                     })
-                    .choose_multiple(&mut make_pseudo_rng(), max_addr_count)
+                    .choose_multiple(&mut make_pseudo_rng(), max_addr_count);
+                // The order of the chosen addresses is not random, so shuffle them to not reveal
+                // anything about the structure of the peer db.
+                addresses.shuffle(&mut make_pseudo_rng());
+                addresses
             })
             // Note: some of the addresses may have become banned or discouraged after they've been
             // cached. It's not clear whether it's better to filter them out here, which will
@@ -1506,20 +1870,27 @@ where
         );
         ensure!(
             Self::should_load_addresses_from(peer.peer_role)
-                && !peer.addr_list_resp_received.test_and_set(),
+                && std::mem::take(&mut peer.addr_list_resp_expected),
             P2pError::ProtocolError(ProtocolError::UnexpectedMessage(
                 "AddrListResponse".to_owned()
             ))
         );
 
-        for address in &addresses {
-            if let Some(address) =
-                address.as_discoverable_socket_address(*self.p2p_config.allow_discover_private_ips)
-            {
-                self.peerdb.peer_discovered(address);
-            }
+        let source_peer = peer.peer_address.as_bannable();
+
+        if self.reserved_nodes_only() {
+            return Ok(());
         }
 
+        let allow_private_ips = *self.p2p_config.allow_discover_private_ips;
+        let added = self.peerdb.addresses_received_from_peer(
+            source_peer,
+            addresses
+                .iter()
+                .filter_map(|address| address.as_discoverable_socket_address(allow_private_ips)),
+        );
+        log::debug!("[peer id = {peer_id}] {added} new addresses added to the peer db");
+
         Ok(())
     }
 
@@ -1527,7 +1898,7 @@ where
         let res = self.try_handle_addr_list_response(peer_id, addresses);
         if let Err(err) = res {
             log::debug!("try_handle_addr_list_response failed: {err}");
-            self.adjust_peer_score(peer_id, err.ban_score());
+            self.adjust_peer_score(peer_id, err.ban_score(), err.variant_name());
         }
     }
 
@@ -1592,9 +1963,9 @@ where
             PeerManagerEvent::Disconnect(peer_id, peerdb_action, reason, response_sender) => {
                 self.disconnect(peer_id, peerdb_action, reason, Some(response_sender));
             }
-            PeerManagerEvent::AdjustPeerScore(peer_id, score, response_sender) => {
-                log::debug!("adjust peer {peer_id} score: {score}");
-                self.adjust_peer_score(peer_id, score);
+            PeerManagerEvent::AdjustPeerScore(peer_id, score, reason, response_sender) => {
+                log::debug!("adjust peer {peer_id} score: {score}, reason: {reason}");
+                self.adjust_peer_score(peer_id, score, reason);
                 response_sender.send(Ok(()));
             }
             PeerManagerEvent::NewTipReceived { peer_id, block_id } => {
@@ -1606,6 +1977,8 @@ where
             PeerManagerEvent::NewChainstateTip(block_id) => {
                 log::debug!("new tip {block_id} added to chainstate");
                 self.last_chainstate_tip_block_time = Some(self.time_getter.get_time());
+                self.chainstate_tip = Some(block_id.into());
+                self.check_possible_fork();
             }
             PeerManagerEvent::NewValidTransactionReceived { peer_id, txid } => {
                 if let Some(peer) = self.peers.get_mut(&peer_id) {
@@ -1619,6 +1992,11 @@ where
             } => {
                 if let Some(peer) = self.peers.get_mut(&peer_id) {
                     log::debug!("Block sync status update received from peer {peer_id}, new status is {status:?}");
+                    if let Some(height) = status.last_received_block_height {
+                        if peer.block_sync_status.last_received_block_height != Some(height) {
+                            self.last_received_block_height = height;
+                        }
+                    }
                     peer.block_sync_status = status;
                 }
             }
@@ -1633,6 +2011,30 @@ where
                 let peers = self.get_connected_peers();
                 response_sender.send(peers);
             }
+            PeerManagerEvent::ReportedBestBlock {
+                peer_id,
+                block_id,
+                local_tip,
+            } => {
+                if self.peers.contains_key(&peer_id) {
+                    log::debug!("best block {block_id} reported by peer {peer_id}");
+                    // Note: the chainstate tip is normally updated via NewChainstateTip; the tip
+                    // from the report is only used until the first such event is received.
+                    self.chainstate_tip.get_or_insert(local_tip);
+                    self.fork_detector.report_best_block(
+                        peer_id,
+                        block_id,
+                        self.time_getter.get_time(),
+                    );
+                    self.check_possible_fork();
+                }
+            }
+            PeerManagerEvent::GetPossibleFork(response_sender) => {
+                response_sender.send(self.fork_detector.possible_fork().cloned());
+            }
+            PeerManagerEvent::GetSyncSnapshot(response_sender) => {
+                response_sender.send(self.get_sync_state_snapshot());
+            }
             PeerManagerEvent::GetReserved(response_sender) => {
                 response_sender.send(self.peerdb.get_reserved_nodes().collect())
             }
@@ -1651,7 +2053,7 @@ where
                 response_sender.send(Ok(()));
             }
             PeerManagerEvent::ListBanned(response_sender) => {
-                response_sender.send(self.peerdb.list_banned().collect())
+                response_sender.send(self.list_banned())
             }
             PeerManagerEvent::Ban(address, duration, response_sender) => {
                 self.ban(address, duration);
@@ -1664,12 +2066,24 @@ where
             PeerManagerEvent::ListDiscouraged(response_sender) => {
                 response_sender.send(self.peerdb.list_discouraged().collect())
             }
+            PeerManagerEvent::GetMisbehaviorLog(limit, peer_filter, response_sender) => {
+                response_sender.send(self.misbehavior_log(limit, peer_filter))
+            }
+            PeerManagerEvent::GetMisbehavingPeers(min_score, response_sender) => {
+                response_sender.send(self.misbehaving_peers(min_score))
+            }
+            PeerManagerEvent::GetPeerBanScore(peer_id, response_sender) => {
+                response_sender.send(self.peers.get(&peer_id).map(|peer| peer.score))
+            }
             PeerManagerEvent::EnableNetworking {
                 enable,
                 response_sender,
             } => {
                 response_sender.send(self.enable_networking(enable));
             }
+            PeerManagerEvent::PortMappingAddressChanged(address) => {
+                self.set_port_mapping_address(address);
+            }
             PeerManagerEvent::GenericQuery(query_func) => {
                 query_func(self);
             }
@@ -1725,13 +2139,22 @@ where
                 self.handle_outbound_error(peer_address, error);
             }
             ConnectivityEvent::Misbehaved { peer_id, error } => {
-                self.adjust_peer_score(peer_id, error.ban_score());
+                self.adjust_peer_score(peer_id, error.ban_score(), error.variant_name());
             }
             ConnectivityEvent::MisbehavedOnHandshake {
                 peer_address,
                 error,
             } => {
-                self.adjust_peer_score_on_failed_handshake(peer_address, error.ban_score());
+                self.adjust_peer_score_on_failed_handshake(
+                    peer_address,
+                    error.ban_score(),
+                    error.variant_name(),
+                );
+            }
+            ConnectivityEvent::PeerNodeIdReceived { peer_id, node_id } => {
+                if let Some(peer) = self.peers.get_mut(&peer_id) {
+                    peer.info.peer_node_id = Some(node_id);
+                }
             }
         }
     }
@@ -1753,6 +2176,7 @@ where
                 ban_score: context.score,
                 user_agent: context.info.user_agent.to_string(),
                 software_version: context.info.software_version.to_string(),
+                peer_node_id: context.info.peer_node_id,
                 ping_wait: context.sent_ping.as_ref().map(|sent_ping| {
                     duration_to_int(&(now - sent_ping.timestamp).unwrap_or_default())
                         .expect("valid timestamp expected (ping_wait)")
@@ -1767,6 +2191,23 @@ where
             .collect()
     }
 
+    fn get_sync_state_snapshot(&self) -> SyncStateSnapshot {
+        let statuses = self.peers.iter().map(|(peer_id, peer)| (*peer_id, &peer.block_sync_status));
+
+        SyncStateSnapshot {
+            pending_header_count_per_peer: statuses
+                .clone()
+                .map(|(peer_id, status)| (peer_id, status.pending_header_count))
+                .collect(),
+            in_flight_block_ids: statuses
+                .clone()
+                .flat_map(|(_, status)| status.requested_blocks.iter().copied())
+                .collect(),
+            last_received_block_height: self.last_received_block_height,
+            stalling_since: statuses.filter_map(|(_, status)| status.expecting_blocks_since).min(),
+        }
+    }
+
     /// Checks if the peer is in active state
     fn is_peer_connected(&self, peer_id: PeerId) -> bool {
         self.peers.contains_key(&peer_id)
@@ -1968,6 +2409,10 @@ where
     /// Note that we avoid querying dns seeds unless really necessary in order to reduce their
     /// influence on the network topology (which can be bad if one of the seeds is compromised).
     fn dns_seed_query_needed(&self) -> bool {
+        if self.reserved_nodes_only() {
+            return false;
+        }
+
         if self.last_dns_query_time.is_none() {
             // If the peer db is empty, it makes sense to perform the first query immediately,
             // instead of waiting for DNS_SEED_QUERY_INTERVAL to pass.
@@ -2015,7 +2460,7 @@ where
 
     /// Return true if we need to load predefined addresses into peerdb.
     fn need_load_predefined_addresses(&self) -> bool {
-        if self.chain_config.predefined_peer_addresses().is_empty() {
+        if self.reserved_nodes_only() || self.chain_config.predefined_peer_addresses().is_empty() {
             return false;
         }
 
@@ -2090,22 +2535,37 @@ where
     async fn run_internal(
         &mut self,
         loop_started_sender: Option<oneshot_nofail::Sender<()>>,
+    ) -> crate::Result<Never> {
+        let result = self.run_main_loop(loop_started_sender).await;
+
+        // The main loop only exits when p2p is being shut down (or on a fatal error),
+        // so this is the time to remember the current outbound peers.
+        if self.networking_enabled {
+            self.store_anchors();
+        }
+
+        result
+    }
+
+    async fn run_main_loop(
+        &mut self,
+        loop_started_sender: Option<oneshot_nofail::Sender<()>>,
     ) -> crate::Result<Never> {
         if self.networking_enabled {
             let anchor_peers = self.peerdb.anchors().clone();
-            if anchor_peers.is_empty() {
+            if anchor_peers.is_empty() || self.reserved_nodes_only() {
                 // Run heartbeat immediately to start outbound connections, but only if there are no stored anchor peers.
                 self.heartbeat();
             } else {
                 // Skip heartbeat to give the stored anchor peers more time to connect to prevent churn!
-                // The stored anchor peers should be the first connected block relay peers.
+                // The anchor peers take the outbound full relay slots before the address pool
+                // is consulted.
                 for anchor_address in anchor_peers {
                     log::debug!("try to connect to anchor peer {anchor_address}");
-                    // The first peers should become anchor peers
                     self.connect(
                         anchor_address,
                         OutboundConnectType::Automatic {
-                            block_relay_only: true,
+                            block_relay_only: false,
                         },
                     );
                 }
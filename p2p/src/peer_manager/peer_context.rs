@@ -16,8 +16,8 @@
 use std::time::Duration;
 
 use common::primitives::time::Time;
-use p2p_types::socket_address::SocketAddress;
-use utils::{bloom_filters::rolling_bloom_filter::RollingBloomFilter, set_flag::SetFlag};
+use p2p_types::{peer_address::PeerAddress, socket_address::SocketAddress};
+use utils::bloom_filters::rolling_bloom_filter::RollingBloomFilter;
 
 use crate::{
     net::types::{PeerInfo, PeerRole},
@@ -57,11 +57,14 @@ pub struct PeerContext {
     /// Min ping time
     pub ping_min: Option<Duration>,
 
-    /// Set if address list request was already received from this peer
-    pub addr_list_req_received: SetFlag,
+    /// The last time an address list request was received from this peer
+    pub last_addr_list_req_received_time: Option<Time>,
 
-    /// Set if address list response was already received from this peer
-    pub addr_list_resp_received: SetFlag,
+    /// The last time an address list request was sent to this peer
+    pub last_addr_list_req_sent_time: Option<Time>,
+
+    /// Set if an address list response is expected from this peer
+    pub addr_list_resp_expected: bool,
 
     /// All addresses that were announced to or from this peer.
     /// Used to prevent infinity loops while broadcasting addresses.
@@ -73,6 +76,9 @@ pub struct PeerContext {
     /// Can be set for outbound connections only.
     pub discovered_own_address: Option<SocketAddress>,
 
+    /// The address of this node as reported by the peer during the handshake.
+    pub node_address_as_seen_by_peer: Option<PeerAddress>,
+
     pub last_tip_block_time: Option<Time>,
 
     pub last_tx_time: Option<Time>,
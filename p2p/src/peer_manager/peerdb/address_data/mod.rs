@@ -16,6 +16,7 @@
 use std::time::Duration;
 
 use common::primitives::time::Time;
+use p2p_types::bannable_address::BannableAddress;
 use randomness::Rng;
 
 /// Maximum delay between reconnection attempts to reserved nodes
@@ -90,6 +91,10 @@ pub struct AddressData {
     state: AddressState,
 
     reserved: bool,
+
+    /// The peer the address was heard of from (e.g. in an address list response), if it wasn't
+    /// reported by the node itself; such addresses are less trustworthy.
+    source_peer: Option<BannableAddress>,
 }
 
 impl AddressData {
@@ -101,6 +106,7 @@ impl AddressData {
                 next_connect_after: now,
             },
             reserved,
+            source_peer: None,
         }
     }
 
@@ -108,6 +114,18 @@ impl AddressData {
         self.reserved
     }
 
+    pub fn discovered_from_peer(&self) -> bool {
+        self.source_peer.is_some()
+    }
+
+    pub fn source_peer(&self) -> Option<BannableAddress> {
+        self.source_peer
+    }
+
+    pub fn set_source_peer(&mut self, source_peer: Option<BannableAddress>) {
+        self.source_peer = source_peer;
+    }
+
     /// Returns true when it is time to attempt a new outbound connection
     pub fn connect_now(&self, now: Time) -> bool {
         match self.state {
@@ -21,6 +21,9 @@ use super::salt::Salt;
 make_config_setting!(NewAddrTableBucketCount, usize, 1024);
 make_config_setting!(TriedAddrTableBucketCount, usize, 256);
 make_config_setting!(AddrTablesBucketSize, usize, 64);
+make_config_setting!(MisbehaviorLogSize, usize, 1000);
+make_config_setting!(MaxAddressesPerSourcePerDay, usize, 1000);
+make_config_setting!(MaxAddressesPerSourcePercent, usize, 10);
 
 #[derive(Default, Debug, Clone)]
 pub struct PeerDbConfig {
@@ -32,4 +35,12 @@ pub struct PeerDbConfig {
     pub addr_tables_bucket_size: AddrTablesBucketSize,
     /// The initial value for the peer db's salt.
     pub salt: Option<Salt>,
+    /// The maximum number of events in the misbehavior log.
+    pub misbehavior_log_size: MisbehaviorLogSize,
+    /// The maximum number of addresses accepted from a single peer's address list responses
+    /// within a day.
+    pub max_addresses_per_source_per_day: MaxAddressesPerSourcePerDay,
+    /// The maximum share (in percent) of the "new" address table that the addresses received
+    /// from a single peer may occupy.
+    pub max_addresses_per_source_percent: MaxAddressesPerSourcePercent,
 }
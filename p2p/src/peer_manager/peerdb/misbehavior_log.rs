@@ -0,0 +1,107 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+
+use crate::interface::types::MisbehaviorEvent;
+
+/// The most recent peer misbehavior events, the oldest events are evicted once the log is full.
+///
+/// Each event has a sequence number, which is used as its key in the peer db storage.
+pub struct MisbehaviorLog {
+    events: VecDeque<(u64, MisbehaviorEvent)>,
+    max_size: usize,
+    next_seq_no: u64,
+}
+
+impl MisbehaviorLog {
+    /// Create the log from the stored events.
+    ///
+    /// Returns the log and the sequence numbers of the events that didn't fit into it.
+    pub fn new(mut events: Vec<(u64, MisbehaviorEvent)>, max_size: usize) -> (Self, Vec<u64>) {
+        events.sort_by_key(|(seq_no, _)| *seq_no);
+        let next_seq_no = events.last().map_or(0, |(seq_no, _)| seq_no + 1);
+
+        let mut log = Self {
+            events: events.into(),
+            max_size,
+            next_seq_no,
+        };
+        let evicted = log.evict_excess();
+
+        (log, evicted)
+    }
+
+    /// Add a new event.
+    ///
+    /// Returns the sequence number of the event and the sequence numbers of the evicted events.
+    pub fn push(&mut self, event: MisbehaviorEvent) -> (u64, Vec<u64>) {
+        let seq_no = self.next_seq_no;
+        self.next_seq_no += 1;
+        self.events.push_back((seq_no, event));
+
+        let evicted = self.evict_excess();
+
+        (seq_no, evicted)
+    }
+
+    /// The events, oldest first
+    pub fn events(&self) -> impl DoubleEndedIterator<Item = &MisbehaviorEvent> {
+        self.events.iter().map(|(_, event)| event)
+    }
+
+    fn evict_excess(&mut self) -> Vec<u64> {
+        let excess = self.events.len().saturating_sub(self.max_size);
+        self.events.drain(..excess).map(|(seq_no, _)| seq_no).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::primitives::time::Time;
+    use p2p_types::socket_address::SocketAddress;
+
+    use super::*;
+
+    fn make_event(score_adjustment: u32) -> MisbehaviorEvent {
+        MisbehaviorEvent {
+            time: Time::from_secs_since_epoch(score_adjustment.into()),
+            peer_id: None,
+            address: "127.0.0.1:3031".parse::<SocketAddress>().unwrap(),
+            reason: "ProtocolError".to_owned(),
+            score_adjustment,
+            total_score: score_adjustment,
+        }
+    }
+
+    #[test]
+    fn eviction() {
+        let (mut log, evicted) = MisbehaviorLog::new(
+            vec![(5, make_event(5)), (3, make_event(3)), (4, make_event(4))],
+            2,
+        );
+        assert_eq!(evicted, vec![3]);
+        assert_eq!(
+            log.events().cloned().collect::<Vec<_>>(),
+            vec![make_event(4), make_event(5)]
+        );
+
+        assert_eq!(log.push(make_event(6)), (6, vec![4]));
+        assert_eq!(
+            log.events().cloned().collect::<Vec<_>>(),
+            vec![make_event(5), make_event(6)]
+        );
+    }
+}
@@ -18,6 +18,8 @@
 //! The peer database stores:
 //! - all outbound peer addresses
 //! - banned addresses
+//! - the most recent peer misbehavior events
+//! - the latest addresses of the outbound peers that advertised their node ids
 //!
 //! Connected peers are those peers that the [`crate::peer_manager::PeerManager`] has an active
 //! connection with. Available addresses are discovered through various peer discovery mechanisms and they are
@@ -27,6 +29,7 @@
 pub mod address_data;
 pub mod address_tables;
 pub mod config;
+pub mod misbehavior_log;
 pub mod salt;
 pub mod storage;
 pub mod storage_impl;
@@ -40,14 +43,15 @@ use std::{
 
 use common::{chain::ChainConfig, primitives::time::Time, time_getter::TimeGetter};
 use logging::log;
-use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress};
+use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress, NodeId};
 use randomness::{make_pseudo_rng, seq::IteratorRandom, Rng, SliceRandom};
 
-use crate::config::P2pConfig;
+use crate::{config::P2pConfig, interface::types::MisbehaviorEvent};
 
 use self::{
     address_data::{AddressData, AddressStateTransitionTo},
     address_tables::AddressTables,
+    misbehavior_log::MisbehaviorLog,
     salt::Salt,
     storage::{KnownAddressState, PeerDbStorage, PeerDbStorageWrite},
     storage_load::LoadedStorage,
@@ -60,6 +64,23 @@ use super::{
 
 pub use storage_load::{open_storage, CURRENT_STORAGE_VERSION};
 
+/// The period that `max_addresses_per_source_per_day` applies to
+pub const ADDRESS_SOURCE_QUOTA_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The number of addresses accepted from a peer since the start of the current quota period
+struct AddressSourceQuota {
+    period_start: Time,
+    accepted: usize,
+}
+
+/// The order in which the addresses from the "new" table are selected for outbound connections
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum NewAddressPriority {
+    SelfReported,
+    DiscoveredFromPeer,
+    DiscoveredFromBannedPeer,
+}
+
 pub struct PeerDb<S> {
     /// P2P configuration
     p2p_config: Arc<P2pConfig>,
@@ -88,6 +109,17 @@ pub struct PeerDb<S> {
     /// Anchor addresses
     anchor_addresses: BTreeSet<SocketAddress>,
 
+    /// The most recent peer score adjustments
+    misbehavior_log: MisbehaviorLog,
+
+    /// The latest addresses of the outbound peers by their node ids, so that a node can be found
+    /// again after its address changes
+    node_addresses: BTreeMap<NodeId, SocketAddress>,
+
+    /// The number of addresses recently accepted from each peer's address list responses.
+    /// Like the source peers of the addresses, this is not persisted.
+    address_source_quotas: BTreeMap<BannableAddress, AddressSourceQuota>,
+
     time_getter: TimeGetter,
 
     storage: S,
@@ -108,9 +140,24 @@ impl<S: PeerDbStorage> PeerDb<S> {
             banned_addresses,
             discouraged_addresses,
             anchor_addresses,
+            misbehavior_events,
+            node_addresses,
             salt,
         } = LoadedStorage::load_storage(&storage, &p2p_config.peer_manager_config.peerdb_config)?;
 
+        let (misbehavior_log, evicted_events) = MisbehaviorLog::new(
+            misbehavior_events,
+            *p2p_config.peer_manager_config.peerdb_config.misbehavior_log_size,
+        );
+        if !evicted_events.is_empty() {
+            update_db(&storage, |tx| {
+                for seq_no in &evicted_events {
+                    tx.del_misbehavior_event(*seq_no)?;
+                }
+                crate::Result::Ok(())
+            })?;
+        }
+
         let reserved_nodes = p2p_config
             .reserved_nodes
             .iter()
@@ -183,6 +230,9 @@ impl<S: PeerDbStorage> PeerDb<S> {
             banned_addresses,
             discouraged_addresses,
             anchor_addresses,
+            misbehavior_log,
+            node_addresses,
+            address_source_quotas: BTreeMap::new(),
             p2p_config,
             time_getter,
             storage,
@@ -256,9 +306,28 @@ impl<S: PeerDbStorage> PeerDb<S> {
         // Note: here we want to select "new" and "tried" addresses with roughly equal probability.
         // To do so, we first select "count" addresses of each kind, shuffle the results and then
         // iteratively choose addresses from one of the vectors based on a randomly generated value.
-        let mut selected_new =
-            self.address_tables.new_addresses().filter(filter).choose_multiple(rng, count);
-        selected_new.shuffle(rng);
+        // Among the "new" addresses, the ones that were discovered from other peers are only
+        // selected if there are not enough self-reported ones, and the ones discovered from
+        // peers that have since been banned or discouraged are selected last.
+        let mut selected_new = Vec::with_capacity(count);
+        for priority in [
+            NewAddressPriority::SelfReported,
+            NewAddressPriority::DiscoveredFromPeer,
+            NewAddressPriority::DiscoveredFromBannedPeer,
+        ] {
+            let mut selected = self
+                .address_tables
+                .new_addresses()
+                .filter(filter)
+                .filter(|addr| {
+                    self.addresses
+                        .get(addr)
+                        .is_some_and(|addr_data| self.new_address_priority(addr_data) == priority)
+                })
+                .choose_multiple(rng, count - selected_new.len());
+            selected.shuffle(rng);
+            selected_new.extend(selected);
+        }
         let mut selected_tried =
             self.address_tables.tried_addresses().filter(filter).choose_multiple(rng, count);
         selected_tried.shuffle(rng);
@@ -295,6 +364,16 @@ impl<S: PeerDbStorage> PeerDb<S> {
         addr_group_to_addr_map.values().copied().collect()
     }
 
+    fn new_address_priority(&self, addr_data: &AddressData) -> NewAddressPriority {
+        match addr_data.source_peer() {
+            None => NewAddressPriority::SelfReported,
+            Some(source) if self.is_address_banned_or_discouraged(&source) => {
+                NewAddressPriority::DiscoveredFromBannedPeer
+            }
+            Some(_) => NewAddressPriority::DiscoveredFromPeer,
+        }
+    }
+
     pub fn select_non_reserved_outbound_address_from_new_addr_table(
         &self,
     ) -> Option<SocketAddress> {
@@ -372,26 +451,119 @@ impl<S: PeerDbStorage> PeerDb<S> {
 
             discouraged
         });
+
+        let quota_period_start = now.saturating_duration_sub(ADDRESS_SOURCE_QUOTA_PERIOD);
+        self.address_source_quotas
+            .retain(|_, quota| quota.period_start > quota_period_start);
     }
 
-    /// Add a new peer address
+    /// Add a new peer address reported by the node itself (or by a trusted source, e.g. a dns seed)
     pub fn peer_discovered(&mut self, address: SocketAddress) {
-        #[allow(clippy::map_entry)]
-        if !self.addresses.contains_key(&address) {
-            log::debug!("New address discovered: {}", address.to_string());
+        if let Some(address_data) = self.addresses.get_mut(&address) {
+            address_data.set_source_peer(None);
+        } else {
+            self.add_discovered_address(address, None);
+        }
+    }
 
-            debug_assert!(
-                !self.address_tables.have_addr(&address),
-                "Address {address} is in 'address_tables' but not in 'addresses'"
-            );
+    /// Whether the known address was heard of from another peer rather than reported by the node
+    /// itself
+    pub fn is_address_discovered_from_peer(&self, address: &SocketAddress) -> Option<bool> {
+        self.addresses.get(address).map(AddressData::discovered_from_peer)
+    }
 
-            if self.add_addr_to_new(&address) {
-                self.addresses.insert(
-                    address,
-                    AddressData::new(false, false, self.time_getter.get_time()),
-                );
+    /// The peer that the known address was heard of from, if any
+    pub fn address_source_peer(&self, address: &SocketAddress) -> Option<BannableAddress> {
+        self.addresses.get(address).and_then(AddressData::source_peer)
+    }
+
+    /// Add a new peer address that was heard of from another peer;
+    /// return true if the address was added
+    pub fn address_discovered_from_peer(
+        &mut self,
+        address: SocketAddress,
+        source_peer: BannableAddress,
+    ) -> bool {
+        !self.addresses.contains_key(&address)
+            && self.add_discovered_address(address, Some(source_peer))
+    }
+
+    /// Add the addresses received in an address list response from the peer.
+    ///
+    /// A single peer may only contribute a limited number of addresses per day and may only
+    /// occupy a limited share of the "new" table, so that it can't flood the db with addresses
+    /// under its control. Return the number of the added addresses.
+    pub fn addresses_received_from_peer(
+        &mut self,
+        source_peer: BannableAddress,
+        addresses: impl IntoIterator<Item = SocketAddress>,
+    ) -> usize {
+        let now = self.time_getter.get_time();
+        let config = &self.p2p_config.peer_manager_config.peerdb_config;
+        let max_per_day = *config.max_addresses_per_source_per_day;
+        let max_contributed = *config.new_addr_table_bucket_count
+            * *config.addr_tables_bucket_size
+            * *config.max_addresses_per_source_percent
+            / 100;
+
+        let quota_period_start = now.saturating_duration_sub(ADDRESS_SOURCE_QUOTA_PERIOD);
+        let current_quota = self
+            .address_source_quotas
+            .get(&source_peer)
+            .filter(|quota| quota.period_start > quota_period_start);
+        let period_start = current_quota.map_or(now, |quota| quota.period_start);
+        let mut accepted = current_quota.map_or(0, |quota| quota.accepted);
+        let mut contributed = self
+            .addresses
+            .values()
+            .filter(|addr_data| addr_data.source_peer() == Some(source_peer))
+            .count();
+
+        let mut added = 0;
+        for address in addresses {
+            if accepted >= max_per_day || contributed >= max_contributed {
+                log::debug!("Address limit reached for source peer {source_peer}");
+                break;
+            }
+
+            if self.address_discovered_from_peer(address, source_peer) {
+                accepted += 1;
+                contributed += 1;
+                added += 1;
             }
         }
+
+        self.address_source_quotas.insert(
+            source_peer,
+            AddressSourceQuota {
+                period_start,
+                accepted,
+            },
+        );
+
+        added
+    }
+
+    fn add_discovered_address(
+        &mut self,
+        address: SocketAddress,
+        source_peer: Option<BannableAddress>,
+    ) -> bool {
+        log::debug!("New address discovered: {}", address.to_string());
+
+        debug_assert!(
+            !self.address_tables.have_addr(&address),
+            "Address {address} is in 'address_tables' but not in 'addresses'"
+        );
+
+        if self.add_addr_to_new(&address) {
+            let mut address_data = AddressData::new(false, false, self.time_getter.get_time());
+            address_data.set_source_peer(source_peer);
+            self.addresses.insert(address, address_data);
+            true
+        } else {
+            false
+        }
     }
 
     /// Report outbound connection failure
@@ -580,6 +752,25 @@ impl<S: PeerDbStorage> PeerDb<S> {
         self.banned_addresses.remove(address);
     }
 
+    /// Record a peer score adjustment in the misbehavior log
+    pub fn record_misbehavior(&mut self, event: MisbehaviorEvent) {
+        let (seq_no, evicted_events) = self.misbehavior_log.push(event.clone());
+
+        update_db(&self.storage, |tx| {
+            tx.add_misbehavior_event(seq_no, &event)?;
+            for seq_no in &evicted_events {
+                tx.del_misbehavior_event(*seq_no)?;
+            }
+            crate::Result::Ok(())
+        })
+        .expect("adding misbehavior event is expected to succeed");
+    }
+
+    /// The most recent misbehavior events, oldest first
+    pub fn misbehavior_events(&self) -> impl DoubleEndedIterator<Item = &MisbehaviorEvent> {
+        self.misbehavior_log.events()
+    }
+
     /// Checks if the given address is discouraged
     pub fn is_address_discouraged(&self, address: &BannableAddress) -> bool {
         self.discouraged_addresses.contains_key(address)
@@ -631,6 +822,44 @@ impl<S: PeerDbStorage> PeerDb<S> {
         self.anchor_addresses = anchor_addresses;
     }
 
+    /// The latest known address of the node with the given id
+    pub fn node_address(&self, node_id: &NodeId) -> Option<SocketAddress> {
+        self.node_addresses.get(node_id).copied()
+    }
+
+    /// Remember the address at which the node with the given id was reached.
+    ///
+    /// The nodes previously reached at the same address are forgotten, since the address now
+    /// belongs to another node.
+    pub fn set_node_address(&mut self, node_id: NodeId, address: SocketAddress) {
+        if self.node_addresses.get(&node_id) == Some(&address) {
+            return;
+        }
+        let stale_node_ids = self
+            .node_addresses
+            .iter()
+            .filter_map(|(other_node_id, other_address)| {
+                (*other_address == address).then_some(*other_node_id)
+            })
+            .collect::<Vec<_>>();
+        update_db(&self.storage, |tx| {
+            for stale_node_id in &stale_node_ids {
+                tx.del_node_address(stale_node_id)?;
+            }
+            tx.add_node_address(&node_id, &address)
+        })
+        .expect("node address update is expected to succeed");
+        for stale_node_id in &stale_node_ids {
+            self.node_addresses.remove(stale_node_id);
+        }
+        self.node_addresses.insert(node_id, address);
+    }
+
+    #[cfg(test)]
+    pub fn into_storage(self) -> S {
+        self.storage
+    }
+
     pub fn known_addresses_count(&self) -> usize {
         self.addresses.len()
     }
@@ -14,11 +14,12 @@
 // limitations under the License.
 
 use common::primitives::time::Time;
-use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress};
+use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress, NodeId};
 use serialization::{Decode, Encode};
 
-use crate::peer_manager::peerdb_common::{
-    StorageVersion, TransactionRo, TransactionRw, Transactional,
+use crate::{
+    interface::types::MisbehaviorEvent,
+    peer_manager::peerdb_common::{StorageVersion, TransactionRo, TransactionRw, Transactional},
 };
 
 use super::salt::Salt;
@@ -43,6 +44,10 @@ pub trait PeerDbStorageRead {
     fn get_discouraged_addresses(&self) -> crate::Result<Vec<(BannableAddress, Time)>>;
 
     fn get_anchor_addresses(&self) -> crate::Result<Vec<SocketAddress>>;
+
+    fn get_misbehavior_events(&self) -> crate::Result<Vec<(u64, MisbehaviorEvent)>>;
+
+    fn get_node_addresses(&self) -> crate::Result<Vec<(NodeId, SocketAddress)>>;
 }
 
 pub trait PeerDbStorageWrite {
@@ -71,6 +76,13 @@ pub trait PeerDbStorageWrite {
 
     fn add_anchor_address(&mut self, address: &SocketAddress) -> crate::Result<()>;
     fn del_anchor_address(&mut self, address: &SocketAddress) -> crate::Result<()>;
+
+    fn add_misbehavior_event(&mut self, seq_no: u64, event: &MisbehaviorEvent)
+        -> crate::Result<()>;
+    fn del_misbehavior_event(&mut self, seq_no: u64) -> crate::Result<()>;
+
+    fn add_node_address(&mut self, node_id: &NodeId, address: &SocketAddress) -> crate::Result<()>;
+    fn del_node_address(&mut self, node_id: &NodeId) -> crate::Result<()>;
 }
 
 // Note: here we want to say something like:
@@ -16,16 +16,18 @@
 use std::time::Duration;
 
 use common::primitives::time::Time;
-use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress};
-use serialization::{encoded::Encoded, DecodeAll, Encode};
+use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress, NodeId};
+use serialization::{encoded::Encoded, Decode, DecodeAll, Encode};
 use storage::MakeMapRef;
 
 use crate::{
     error::P2pError,
+    interface::types::MisbehaviorEvent,
     peer_manager::peerdb_common::{
         storage_impl::{StorageImpl, StorageTxRo, StorageTxRw},
         StorageVersion,
     },
+    types::peer_id::PeerId,
 };
 
 use super::{
@@ -35,6 +37,18 @@ use super::{
 
 type ValueId = u32;
 
+/// The stored form of [MisbehaviorEvent]
+#[derive(Encode, Decode)]
+pub struct MisbehaviorEventData {
+    /// Duration is a timestamp since UNIX Epoch
+    time: Duration,
+    peer_id: Option<PeerId>,
+    address: String,
+    reason: String,
+    score_adjustment: u32,
+    total_score: u32,
+}
+
 storage::decl_schema! {
     /// Database schema for peer db storage
     pub Schema {
@@ -54,6 +68,12 @@ storage::decl_schema! {
 
         /// Table for anchor peers addresses
         pub DBAnchorAddresses: Map<String, ()>,
+
+        /// Table for the misbehavior log events vs their sequence numbers
+        pub DBMisbehaviorEvents: Map<u64, MisbehaviorEventData>,
+
+        /// Table for the node ids of the outbound peers vs their latest addresses
+        pub DBNodeAddresses: Map<NodeId, String>,
     }
 }
 
@@ -124,6 +144,37 @@ impl<'st, B: storage::Backend> PeerDbStorageWrite for PeerDbStoreTxRw<'st, B> {
     fn del_anchor_address(&mut self, address: &SocketAddress) -> crate::Result<()> {
         Ok(self.storage().get_mut::<DBAnchorAddresses, _>().del(address.to_string())?)
     }
+
+    fn add_misbehavior_event(
+        &mut self,
+        seq_no: u64,
+        event: &MisbehaviorEvent,
+    ) -> crate::Result<()> {
+        let data = MisbehaviorEventData {
+            time: event.time.as_duration_since_epoch(),
+            peer_id: event.peer_id,
+            address: event.address.to_string(),
+            reason: event.reason.clone(),
+            score_adjustment: event.score_adjustment,
+            total_score: event.total_score,
+        };
+        Ok(self.storage().get_mut::<DBMisbehaviorEvents, _>().put(seq_no, data)?)
+    }
+
+    fn del_misbehavior_event(&mut self, seq_no: u64) -> crate::Result<()> {
+        Ok(self.storage().get_mut::<DBMisbehaviorEvents, _>().del(seq_no)?)
+    }
+
+    fn add_node_address(&mut self, node_id: &NodeId, address: &SocketAddress) -> crate::Result<()> {
+        Ok(self
+            .storage()
+            .get_mut::<DBNodeAddresses, _>()
+            .put(node_id, address.to_string())?)
+    }
+
+    fn del_node_address(&mut self, node_id: &NodeId) -> crate::Result<()> {
+        Ok(self.storage().get_mut::<DBNodeAddresses, _>().del(node_id)?)
+    }
 }
 
 impl<'st, B: storage::Backend> PeerDbStorageRead for PeerDbStoreTxRo<'st, B> {
@@ -205,4 +256,39 @@ impl<'st, B: storage::Backend> PeerDbStorageRead for PeerDbStoreTxRo<'st, B> {
         });
         itertools::process_results(iter, |iter| iter.collect::<Vec<_>>())
     }
+
+    fn get_misbehavior_events(&self) -> crate::Result<Vec<(u64, MisbehaviorEvent)>> {
+        let map = self.storage().get::<DBMisbehaviorEvents, _>();
+        let iter = map.prefix_iter_decoded(&())?.map(|(seq_no, data)| {
+            let address = data.address.parse::<SocketAddress>().map_err(|err| {
+                P2pError::InvalidStorageState(format!(
+                    "Error parsing address from {:?}: {err}",
+                    data.address
+                ))
+            })?;
+            let event = MisbehaviorEvent {
+                time: Time::from_duration_since_epoch(data.time),
+                peer_id: data.peer_id,
+                address,
+                reason: data.reason,
+                score_adjustment: data.score_adjustment,
+                total_score: data.total_score,
+            };
+            crate::Result::Ok((seq_no, event))
+        });
+        itertools::process_results(iter, |iter| iter.collect::<Vec<_>>())
+    }
+
+    fn get_node_addresses(&self) -> crate::Result<Vec<(NodeId, SocketAddress)>> {
+        let map = self.storage().get::<DBNodeAddresses, _>();
+        let iter = map.prefix_iter_decoded(&())?.map(|(node_id, addr_str)| {
+            let addr = addr_str.parse::<SocketAddress>().map_err(|err| {
+                P2pError::InvalidStorageState(format!(
+                    "Error parsing address from {addr_str:?}: {err}"
+                ))
+            })?;
+            crate::Result::Ok((node_id, addr))
+        });
+        itertools::process_results(iter, |iter| iter.collect::<Vec<_>>())
+    }
 }
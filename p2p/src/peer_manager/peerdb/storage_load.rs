@@ -16,10 +16,11 @@
 use std::collections::{BTreeMap, BTreeSet};
 
 use common::primitives::time::Time;
-use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress};
+use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress, NodeId};
 
 use crate::{
     error::P2pError,
+    interface::types::MisbehaviorEvent,
     peer_manager::peerdb_common::{StorageVersion, TransactionRo, TransactionRw, Transactional},
 };
 
@@ -37,6 +38,8 @@ pub struct LoadedStorage {
     pub banned_addresses: BTreeMap<BannableAddress, Time>,
     pub discouraged_addresses: BTreeMap<BannableAddress, Time>,
     pub anchor_addresses: BTreeSet<SocketAddress>,
+    pub misbehavior_events: Vec<(u64, MisbehaviorEvent)>,
+    pub node_addresses: BTreeMap<NodeId, SocketAddress>,
     pub salt: Salt,
 }
 
@@ -75,6 +78,8 @@ impl LoadedStorage {
             banned_addresses: BTreeMap::new(),
             discouraged_addresses: BTreeMap::new(),
             anchor_addresses: BTreeSet::new(),
+            misbehavior_events: Vec::new(),
+            node_addresses: BTreeMap::new(),
             salt,
         })
     }
@@ -91,6 +96,10 @@ impl LoadedStorage {
 
         let anchor_addresses = tx.get_anchor_addresses()?.into_iter().collect::<BTreeSet<_>>();
 
+        let misbehavior_events = tx.get_misbehavior_events()?;
+
+        let node_addresses = tx.get_node_addresses()?.into_iter().collect::<BTreeMap<_, _>>();
+
         let salt = tx
             .get_salt()?
             .ok_or_else(|| P2pError::InvalidStorageState("Missing salt".to_owned()))?;
@@ -100,6 +109,8 @@ impl LoadedStorage {
             banned_addresses,
             discouraged_addresses,
             anchor_addresses,
+            misbehavior_events,
+            node_addresses,
             salt,
         })
     }
@@ -16,6 +16,7 @@
 use std::{
     collections::{BTreeMap, BTreeSet},
     fmt::Debug,
+    net::SocketAddr,
     sync::Arc,
     time::Duration,
 };
@@ -29,11 +30,12 @@ use ::test_utils::{
 };
 use common::{chain::config::create_unit_test_config, primitives::time::Time};
 use networking::test_helpers::TestAddressMaker;
-use p2p_types::socket_addr_ext::SocketAddrExt;
+use p2p_types::{socket_addr_ext::SocketAddrExt, NodeId};
 use randomness::Rng;
 
 use crate::{
     ban_config::BanConfig,
+    interface::types::MisbehaviorEvent,
     peer_manager::{
         peerdb::{
             address_data::{self, PURGE_REACHABLE_FAIL_COUNT, PURGE_UNREACHABLE_TIME},
@@ -46,6 +48,7 @@ use crate::{
         peerdb_inmemory_store, test_p2p_config, test_p2p_config_with_ban_config,
         test_p2p_config_with_peer_db_config,
     },
+    types::peer_id::PeerId,
 };
 
 use super::{
@@ -500,6 +503,153 @@ fn anchor_peers(#[case] seed: Seed) {
     assert_addr_consistency(&peerdb);
 }
 
+// Record more misbehavior events than the log can hold, check that only the most recent ones
+// are kept, both in memory and in the db, and that they survive reopening the db.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn misbehavior_log(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let db_store = peerdb_inmemory_store();
+    let time_getter = BasicTestTimeGetter::new();
+    let chain_config = create_unit_test_config();
+    let log_size = 5;
+    let p2p_config = Arc::new(test_p2p_config_with_peer_db_config(PeerDbConfig {
+        misbehavior_log_size: log_size.into(),
+        ..Default::default()
+    }));
+
+    let mut peerdb = PeerDb::new(
+        &chain_config,
+        Arc::clone(&p2p_config),
+        time_getter.get_time_getter(),
+        db_store,
+    )
+    .unwrap();
+
+    let make_event = |address: SocketAddr| MisbehaviorEvent {
+        time: time_getter.get_time_getter().get_time(),
+        peer_id: Some(PeerId::new()),
+        address: address.into(),
+        reason: "ProtocolError".to_owned(),
+        score_adjustment: 10,
+        total_score: 10,
+    };
+
+    let events = (0..log_size + 3)
+        .map(|_| make_event(TestAddressMaker::new_random_address(&mut rng)))
+        .collect::<Vec<_>>();
+    for event in &events {
+        peerdb.record_misbehavior(event.clone());
+        time_getter.advance_time(Duration::from_secs(1));
+    }
+
+    let expected_events = &events[events.len() - log_size..];
+    assert_eq!(
+        peerdb.misbehavior_events().cloned().collect::<Vec<_>>(),
+        expected_events
+    );
+    let stored_events = peerdb.storage.transaction_ro().unwrap().get_misbehavior_events().unwrap();
+    assert_eq!(
+        stored_events.into_iter().map(|(_, event)| event).collect::<Vec<_>>(),
+        expected_events
+    );
+
+    let mut peerdb = PeerDb::new(
+        &chain_config,
+        Arc::clone(&p2p_config),
+        time_getter.get_time_getter(),
+        peerdb.storage,
+    )
+    .unwrap();
+    assert_eq!(
+        peerdb.misbehavior_events().cloned().collect::<Vec<_>>(),
+        expected_events
+    );
+
+    // New events are added after the loaded ones
+    let event = make_event(TestAddressMaker::new_random_address(&mut rng));
+    peerdb.record_misbehavior(event.clone());
+    let expected_events = expected_events[1..].iter().cloned().chain([event]).collect::<Vec<_>>();
+    assert_eq!(
+        peerdb.misbehavior_events().cloned().collect::<Vec<_>>(),
+        expected_events
+    );
+
+    // Reopening with a smaller log size drops the oldest events
+    let p2p_config = Arc::new(test_p2p_config_with_peer_db_config(PeerDbConfig {
+        misbehavior_log_size: 2.into(),
+        ..Default::default()
+    }));
+    let peerdb = PeerDb::new(
+        &chain_config,
+        p2p_config,
+        time_getter.get_time_getter(),
+        peerdb.storage,
+    )
+    .unwrap();
+    assert_eq!(
+        peerdb.misbehavior_events().cloned().collect::<Vec<_>>(),
+        expected_events[log_size - 2..]
+    );
+    let stored_events = peerdb.storage.transaction_ro().unwrap().get_misbehavior_events().unwrap();
+    assert_eq!(stored_events.len(), 2);
+}
+
+// The node addresses are stored in the db and follow the nodes when their addresses change.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn node_addresses(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let db_store = peerdb_inmemory_store();
+    let time_getter = BasicTestTimeGetter::new();
+    let chain_config = create_unit_test_config();
+    let p2p_config = Arc::new(test_p2p_config());
+
+    let mut peerdb = PeerDb::new(
+        &chain_config,
+        Arc::clone(&p2p_config),
+        time_getter.get_time_getter(),
+        db_store,
+    )
+    .unwrap();
+
+    let node_id1 = NodeId::from(rng.gen::<[u8; 32]>());
+    let node_id2 = NodeId::from(rng.gen::<[u8; 32]>());
+    let address1 = TestAddressMaker::new_random_address(&mut rng).into();
+    let address2 = TestAddressMaker::new_random_address(&mut rng).into();
+
+    assert_eq!(peerdb.node_address(&node_id1), None);
+    peerdb.set_node_address(node_id1, address1);
+    assert_eq!(peerdb.node_address(&node_id1), Some(address1));
+
+    // The node has moved to another address
+    peerdb.set_node_address(node_id1, address2);
+    assert_eq!(peerdb.node_address(&node_id1), Some(address2));
+
+    let mut peerdb = PeerDb::new(
+        &chain_config,
+        Arc::clone(&p2p_config),
+        time_getter.get_time_getter(),
+        peerdb.storage,
+    )
+    .unwrap();
+    assert_eq!(peerdb.node_address(&node_id1), Some(address2));
+
+    // Another node has taken over the address
+    peerdb.set_node_address(node_id2, address2);
+    assert_eq!(peerdb.node_address(&node_id1), None);
+    assert_eq!(peerdb.node_address(&node_id2), Some(address2));
+
+    let stored = peerdb.storage.transaction_ro().unwrap().get_node_addresses().unwrap();
+    assert_eq!(stored, vec![(node_id2, address2)]);
+}
+
 // Call 'remove_address' on new and tried addresses, check that the db is
 // in consistent state.
 #[tracing::instrument(skip(seed))]
@@ -517,6 +667,9 @@ fn remove_addr(#[case] seed: Seed) {
         new_addr_table_bucket_count: 10.into(),
         tried_addr_table_bucket_count: 10.into(),
         salt: Some(Salt::new_random_with_rng(&mut rng)),
+        misbehavior_log_size: Default::default(),
+        max_addresses_per_source_per_day: Default::default(),
+        max_addresses_per_source_percent: Default::default(),
     }));
 
     let mut peerdb = PeerDb::new(
@@ -578,6 +731,9 @@ fn remove_unreachable(#[case] seed: Seed) {
         new_addr_table_bucket_count: 10.into(),
         tried_addr_table_bucket_count: 10.into(),
         salt: Some(Salt::new_random_with_rng(&mut rng)),
+        misbehavior_log_size: Default::default(),
+        max_addresses_per_source_per_day: Default::default(),
+        max_addresses_per_source_percent: Default::default(),
     }));
 
     let mut peerdb = PeerDb::new(
@@ -674,6 +830,9 @@ fn new_addr_count_limit(#[case] seed: Seed, #[values(true, false)] use_reserved_
         new_addr_table_bucket_count: bucket_count.into(),
         tried_addr_table_bucket_count: bucket_count.into(),
         salt: Some(Salt::new_random_with_rng(&mut rng)),
+        misbehavior_log_size: Default::default(),
+        max_addresses_per_source_per_day: Default::default(),
+        max_addresses_per_source_percent: Default::default(),
     }));
 
     let mut peerdb = PeerDb::new(
@@ -733,6 +892,9 @@ fn tried_addr_count_limit(#[case] seed: Seed, #[values(true, false)] use_reserve
         new_addr_table_bucket_count: bucket_count.into(),
         tried_addr_table_bucket_count: bucket_count.into(),
         salt: Some(Salt::new_random_with_rng(&mut rng)),
+        misbehavior_log_size: Default::default(),
+        max_addresses_per_source_per_day: Default::default(),
+        max_addresses_per_source_percent: Default::default(),
     }));
 
     let mut peerdb = PeerDb::new(
@@ -797,6 +959,9 @@ fn new_tried_addr_selection_frequency() {
                 new_addr_table_bucket_count: bucket_count.into(),
                 tried_addr_table_bucket_count: bucket_count.into(),
                 salt: Some(Salt::new_random_with_rng(&mut rng)),
+                misbehavior_log_size: Default::default(),
+                max_addresses_per_source_per_day: Default::default(),
+                max_addresses_per_source_percent: Default::default(),
             }));
 
             let mut peerdb = PeerDb::new(
@@ -873,6 +1038,191 @@ fn new_tried_addr_selection_frequency() {
     }
 }
 
+// Add some self-reported addresses and some addresses discovered from other peers to the "new"
+// table. Check that the latter are only selected if there are not enough of the former.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn addresses_discovered_from_peer_selected_last(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let db_store = peerdb_inmemory_store();
+    let time_getter = BasicTestTimeGetter::new();
+    let chain_config = create_unit_test_config();
+    let mut peerdb = PeerDb::new(
+        &chain_config,
+        Arc::new(test_p2p_config()),
+        time_getter.get_time_getter(),
+        db_store,
+    )
+    .unwrap();
+
+    let addrs = super::test_utils::make_non_colliding_addresses_for_peer_db_in_distinct_addr_groups(
+        &peerdb, 20, &mut rng,
+    );
+    let source_peer = make_random_address(&mut rng).as_bannable();
+    let (self_reported_addrs, discovered_from_peer_addrs) = addrs.split_at(10);
+    for addr in self_reported_addrs {
+        peerdb.peer_discovered(*addr);
+    }
+    for addr in discovered_from_peer_addrs {
+        assert!(peerdb.address_discovered_from_peer(*addr, source_peer));
+    }
+    // Already known addresses are not tagged
+    assert!(!peerdb.address_discovered_from_peer(self_reported_addrs[0], source_peer));
+    assert_eq!(
+        peerdb.is_address_discovered_from_peer(&self_reported_addrs[0]),
+        Some(false)
+    );
+    assert_addr_consistency(&peerdb);
+
+    let count = rng.gen_range(1..=self_reported_addrs.len());
+    let selected =
+        peerdb.select_non_reserved_outbound_addresses(&BTreeSet::new(), &|_| true, count);
+    assert_eq!(selected.len(), count);
+    assert!(selected.iter().all(|addr| self_reported_addrs.contains(addr)));
+
+    let count = rng.gen_range(self_reported_addrs.len() + 1..=addrs.len());
+    let selected =
+        peerdb.select_non_reserved_outbound_addresses(&BTreeSet::new(), &|_| true, count);
+    assert_eq!(selected.len(), count);
+    assert!(self_reported_addrs.iter().all(|addr| selected.contains(addr)));
+
+    // The tag is cleared once the node reports the address itself
+    let addr = discovered_from_peer_addrs[0];
+    assert_eq!(peerdb.is_address_discovered_from_peer(&addr), Some(true));
+    peerdb.peer_discovered(addr);
+    assert_eq!(peerdb.is_address_discovered_from_peer(&addr), Some(false));
+}
+
+// Add addresses discovered from two peers to the "new" table and ban one of the peers.
+// Check that the addresses of the banned peer are selected only after all the others.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn addresses_from_banned_source_selected_last(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let db_store = peerdb_inmemory_store();
+    let time_getter = BasicTestTimeGetter::new();
+    let chain_config = create_unit_test_config();
+    let mut peerdb = PeerDb::new(
+        &chain_config,
+        Arc::new(test_p2p_config()),
+        time_getter.get_time_getter(),
+        db_store,
+    )
+    .unwrap();
+
+    let addrs = super::test_utils::make_non_colliding_addresses_for_peer_db_in_distinct_addr_groups(
+        &peerdb, 20, &mut rng,
+    );
+    let good_source = make_random_address(&mut rng).as_bannable();
+    let banned_source = make_random_address(&mut rng).as_bannable();
+    let (good_source_addrs, banned_source_addrs) = addrs.split_at(10);
+    assert_eq!(
+        peerdb.addresses_received_from_peer(good_source, good_source_addrs.iter().copied()),
+        good_source_addrs.len()
+    );
+    assert_eq!(
+        peerdb.addresses_received_from_peer(banned_source, banned_source_addrs.iter().copied()),
+        banned_source_addrs.len()
+    );
+    assert_eq!(
+        peerdb.address_source_peer(&banned_source_addrs[0]),
+        Some(banned_source)
+    );
+    assert_addr_consistency(&peerdb);
+
+    peerdb.ban(banned_source, Duration::from_secs(60));
+
+    let count = rng.gen_range(1..=good_source_addrs.len());
+    let selected =
+        peerdb.select_non_reserved_outbound_addresses(&BTreeSet::new(), &|_| true, count);
+    assert_eq!(selected.len(), count);
+    assert!(selected.iter().all(|addr| good_source_addrs.contains(addr)));
+
+    // The addresses of the banned peer are still used if there are no others
+    let count = rng.gen_range(good_source_addrs.len() + 1..=addrs.len());
+    let selected =
+        peerdb.select_non_reserved_outbound_addresses(&BTreeSet::new(), &|_| true, count);
+    assert_eq!(selected.len(), count);
+    assert!(good_source_addrs.iter().all(|addr| selected.contains(addr)));
+}
+
+// Check that a single peer can only contribute a limited number of addresses per day
+// and can only occupy a limited share of the "new" table.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn per_source_address_limits(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let db_store = peerdb_inmemory_store();
+    let time_getter = BasicTestTimeGetter::new();
+    let chain_config = create_unit_test_config();
+    let max_per_day = 5;
+    // The "new" table has 80 slots, a single peer may occupy 10% of them
+    let max_contributed = 8;
+    let p2p_config = Arc::new(test_p2p_config_with_peer_db_config(PeerDbConfig {
+        new_addr_table_bucket_count: 10.into(),
+        addr_tables_bucket_size: 8.into(),
+        max_addresses_per_source_per_day: max_per_day.into(),
+        max_addresses_per_source_percent: 10.into(),
+        ..Default::default()
+    }));
+    let mut peerdb = PeerDb::new(
+        &chain_config,
+        p2p_config,
+        time_getter.get_time_getter(),
+        db_store,
+    )
+    .unwrap();
+
+    let addrs = super::test_utils::make_non_colliding_addresses_for_peer_db_in_distinct_addr_groups(
+        &peerdb, 30, &mut rng,
+    );
+    let (source1_addrs, source2_addrs) = addrs.split_at(20);
+    let source1 = make_random_address(&mut rng).as_bannable();
+    let source2 = make_random_address(&mut rng).as_bannable();
+    let source1_addr_count = |peerdb: &PeerDb<_>| {
+        source1_addrs
+            .iter()
+            .filter(|addr| peerdb.address_source_peer(addr) == Some(source1))
+            .count()
+    };
+
+    // Only the daily limit is accepted
+    assert_eq!(
+        peerdb.addresses_received_from_peer(source1, source1_addrs.iter().copied()),
+        max_per_day
+    );
+    assert_eq!(
+        peerdb.addresses_received_from_peer(source1, source1_addrs.iter().copied()),
+        0
+    );
+    assert_eq!(source1_addr_count(&peerdb), max_per_day);
+
+    // Other peers have their own limits
+    assert_eq!(
+        peerdb.addresses_received_from_peer(source2, source2_addrs.iter().copied()),
+        max_per_day
+    );
+
+    // On the next day, the peer can only add addresses up to its share of the table
+    time_getter.advance_time(super::ADDRESS_SOURCE_QUOTA_PERIOD);
+    peerdb.heartbeat();
+    assert_eq!(
+        peerdb.addresses_received_from_peer(source1, source1_addrs.iter().copied()),
+        max_contributed - max_per_day
+    );
+    assert_eq!(source1_addr_count(&peerdb), max_contributed);
+    assert_addr_consistency(&peerdb);
+}
+
 fn assert_eq_sets<T, I1, I2>(iter1: I1, iter2: I2)
 where
     I1: Iterator<Item = T>,
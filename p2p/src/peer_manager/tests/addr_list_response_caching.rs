@@ -243,17 +243,30 @@ fn make_p2p_config() -> P2pConfig {
             max_request_blocks_count: Default::default(),
             msg_max_locator_count: Default::default(),
             max_message_size: Default::default(),
+            max_mempool_sync_txs: Default::default(),
             max_peer_tx_announcements: Default::default(),
+            max_block_response_message_size: Default::default(),
+            max_header_list_message_size: Default::default(),
+            max_addr_list_response_message_size: Default::default(),
+            max_transaction_response_message_size: Default::default(),
+            max_repeated_headers_served: Default::default(),
+            repeated_headers_window: Default::default(),
+            max_throttled_header_responses: Default::default(),
         },
 
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
+        socks5_proxy_auth: Default::default(),
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_check_period: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
@@ -262,6 +275,13 @@ fn make_p2p_config() -> P2pConfig {
         allow_discover_private_ips: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         peer_manager_config: Default::default(),
     }
 }
@@ -307,6 +327,7 @@ fn make_new_peer(chain_config: &ChainConfig) -> (PeerId, PeerInfo) {
         software_version: *chain_config.software_version(),
         user_agent: mintlayer_core_user_agent(),
         common_services: NodeType::Full.into(),
+        peer_node_id: None,
     };
     (id, info)
 }
@@ -14,7 +14,7 @@
 // limitations under the License.
 
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     sync::{Arc, Mutex},
     time::Duration,
 };
@@ -59,12 +59,14 @@ use crate::{
     },
     peer_manager::{
         self,
+        address_groups::AddressGroup,
         tests::{
             make_peer_manager_custom,
             utils::{cmd_to_peer_man_msg, expect_cmd_connect_to, make_full_relay_peer_info},
         },
         OutboundConnectType, PeerManager, DNS_SEED_QUERY_INTERVAL,
     },
+    protocol::ProtocolConfig,
     test_helpers::{peerdb_inmemory_store, test_p2p_config, TEST_PROTOCOL_VERSION},
     tests::helpers::TestDnsSeed,
     types::peer_id::PeerId,
@@ -112,6 +114,7 @@ where
         software_version: *config.software_version(),
         user_agent: mintlayer_core_user_agent(),
         common_services: NodeType::Full.into(),
+        peer_node_id: None,
     };
     pm.accept_connection(
         address.into(),
@@ -198,6 +201,7 @@ fn test_addr_list_handling_inbound(#[case] seed: Seed) {
         software_version: *chain_config.software_version(),
         user_agent: mintlayer_core_user_agent(),
         common_services: NodeType::Full.into(),
+        peer_node_id: None,
     };
     pm.accept_connection(
         TestAddressMaker::new_random_address(&mut rng).into(),
@@ -242,6 +246,14 @@ fn test_addr_list_handling_inbound(#[case] seed: Seed) {
     }
     assert_eq!(pm.peers.get(&peer_id_1).unwrap().score, 0);
 
+    // After some time, the peer may request addresses again
+    time_getter.advance_time(peer_manager::ADDR_LIST_REQUEST_MIN_INTERVAL);
+    pm.handle_addr_list_request(peer_id_1);
+    let cmd = cmd_receiver.try_recv().unwrap();
+    let (peer_id, peer_msg) = cmd_to_peer_man_msg(cmd);
+    assert_eq!(peer_id, peer_id_1);
+    assert_matches!(peer_msg, PeerManagerMessage::AddrListResponse(_));
+
     // Check that the peer is scored if it tries to send an unexpected address list response
     pm.handle_addr_list_response(
         peer_id_1,
@@ -290,6 +302,7 @@ fn test_addr_list_handling_outbound(#[case] seed: Seed) {
         software_version: *chain_config.software_version(),
         user_agent: mintlayer_core_user_agent(),
         common_services: NodeType::Full.into(),
+        peer_node_id: None,
     };
     pm.connect(
         peer_address,
@@ -355,6 +368,222 @@ fn test_addr_list_handling_outbound(#[case] seed: Seed) {
     assert_ne!(pm.peers.get(&peer_id_1).unwrap().score, 0);
 }
 
+// Verify that the node asks its outbound peers for more addresses when there are not enough
+// addresses to connect to, and that the addresses in the response are tagged as discovered from
+// a peer.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn addr_list_rerequested_when_not_enough_addresses(#[case] seed: Seed) {
+    type TestNetworkingService = DefaultNetworkingService<TcpTransportSocket>;
+
+    let mut rng = make_seedable_rng(seed);
+
+    let bind_address = TestTransportTcp::make_address().into();
+    let chain_config = Arc::new(config::create_unit_test_config());
+    let p2p_config = Arc::new(test_p2p_config());
+    let (cmd_sender, mut cmd_receiver) = tokio::sync::mpsc::unbounded_channel();
+    let (_conn_event_sender, conn_event_receiver) = tokio::sync::mpsc::unbounded_channel();
+    let (_peer_mgr_event_sender, peer_mgr_event_receiver) =
+        tokio::sync::mpsc::unbounded_channel::<PeerManagerEvent>();
+    let time_getter = BasicTestTimeGetter::new();
+    let connectivity_handle =
+        ConnectivityHandle::<TestNetworkingService>::new(vec![], cmd_sender, conn_event_receiver);
+
+    let mut pm = PeerManager::<TestNetworkingService, _>::new(
+        true,
+        Arc::clone(&chain_config),
+        Arc::clone(&p2p_config),
+        connectivity_handle,
+        peer_mgr_event_receiver,
+        time_getter.get_time_getter(),
+        peerdb_inmemory_store(),
+    )
+    .unwrap();
+
+    let peer_id_1 = PeerId::new();
+    let peer_address = TestAddressMaker::new_random_address(&mut rng).into();
+    pm.connect(
+        peer_address,
+        OutboundConnectType::Automatic {
+            block_relay_only: false,
+        },
+    );
+    expect_cmd_connect_to(&cmd_receiver.try_recv().unwrap(), &peer_address);
+
+    pm.accept_connection(
+        peer_address,
+        bind_address,
+        ConnectionDirection::Outbound,
+        make_full_relay_peer_info(peer_id_1, &chain_config),
+        None,
+    );
+    match cmd_receiver.try_recv() {
+        Ok(Command::Accept { peer_id }) if peer_id == peer_id_1 => {}
+        v => panic!("unexpected result: {v:?}"),
+    }
+    let (peer_id, peer_msg) = cmd_to_peer_man_msg(cmd_receiver.try_recv().unwrap());
+    assert_eq!(peer_id, peer_id_1);
+    assert_matches!(peer_msg, PeerManagerMessage::AddrListRequest(_));
+
+    // The peer has no addresses to share
+    pm.handle_addr_list_response(peer_id_1, Vec::new());
+    assert_eq!(pm.peers.get(&peer_id_1).unwrap().score, 0);
+
+    // There are no addresses to connect to, but the peer has just been asked
+    pm.heartbeat();
+    assert_matches!(cmd_receiver.try_recv(), Err(TryRecvError::Empty));
+
+    // The peer is asked again once the request interval has passed
+    time_getter.advance_time(peer_manager::ADDR_LIST_REQUEST_INTERVAL);
+    pm.heartbeat();
+    let (peer_id, peer_msg) = cmd_to_peer_man_msg(cmd_receiver.try_recv().unwrap());
+    assert_eq!(peer_id, peer_id_1);
+    assert_matches!(peer_msg, PeerManagerMessage::AddrListRequest(_));
+
+    // And this time it responds with some addresses
+    let addresses = (0..rng.gen_range(1..10))
+        .map(|_| get_new_discoverable_address(&mut rng))
+        .collect::<Vec<_>>();
+    pm.handle_addr_list_response(peer_id_1, addresses.clone());
+    assert_eq!(pm.peers.get(&peer_id_1).unwrap().score, 0);
+
+    let addresses = addresses
+        .iter()
+        .map(|address| address.as_discoverable_socket_address(false).unwrap())
+        .collect::<BTreeSet<_>>();
+    let known_addresses = pm
+        .peerdb
+        .known_addresses()
+        .filter(|address| addresses.contains(address))
+        .copied()
+        .collect::<Vec<_>>();
+    assert!(!known_addresses.is_empty());
+    for address in known_addresses {
+        assert_eq!(
+            pm.peerdb.is_address_discovered_from_peer(&address),
+            Some(true)
+        );
+    }
+
+    // Another response is unexpected
+    pm.handle_addr_list_response(peer_id_1, vec![get_new_discoverable_address(&mut rng)]);
+    assert_ne!(pm.peers.get(&peer_id_1).unwrap().score, 0);
+}
+
+// Verify that only a limited number of addresses is accepted from a peer that responds with
+// a huge address list, and that the addresses are selected last once the peer is banned.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn addr_list_response_limited_per_source(#[case] seed: Seed) {
+    type TestNetworkingService = DefaultNetworkingService<TcpTransportSocket>;
+
+    let mut rng = make_seedable_rng(seed);
+
+    let response_size = 5000;
+    let bind_address = TestTransportTcp::make_address().into();
+    let chain_config = Arc::new(config::create_unit_test_config());
+    let p2p_config = Arc::new(P2pConfig {
+        protocol_config: ProtocolConfig {
+            max_addr_list_response_address_count: response_size.into(),
+            ..Default::default()
+        },
+        ..test_p2p_config()
+    });
+    let max_per_source =
+        *p2p_config.peer_manager_config.peerdb_config.max_addresses_per_source_per_day;
+    let (cmd_sender, mut cmd_receiver) = tokio::sync::mpsc::unbounded_channel();
+    let (_conn_event_sender, conn_event_receiver) = tokio::sync::mpsc::unbounded_channel();
+    let (_peer_mgr_event_sender, peer_mgr_event_receiver) =
+        tokio::sync::mpsc::unbounded_channel::<PeerManagerEvent>();
+    let time_getter = BasicTestTimeGetter::new();
+    let connectivity_handle =
+        ConnectivityHandle::<TestNetworkingService>::new(vec![], cmd_sender, conn_event_receiver);
+
+    let mut pm = PeerManager::<TestNetworkingService, _>::new(
+        true,
+        Arc::clone(&chain_config),
+        Arc::clone(&p2p_config),
+        connectivity_handle,
+        peer_mgr_event_receiver,
+        time_getter.get_time_getter(),
+        peerdb_inmemory_store(),
+    )
+    .unwrap();
+
+    let peer_id_1 = PeerId::new();
+    let peer_address: SocketAddress = TestAddressMaker::new_random_address(&mut rng).into();
+    pm.connect(
+        peer_address,
+        OutboundConnectType::Automatic {
+            block_relay_only: false,
+        },
+    );
+    expect_cmd_connect_to(&cmd_receiver.try_recv().unwrap(), &peer_address);
+
+    pm.accept_connection(
+        peer_address,
+        bind_address,
+        ConnectionDirection::Outbound,
+        make_full_relay_peer_info(peer_id_1, &chain_config),
+        None,
+    );
+    match cmd_receiver.try_recv() {
+        Ok(Command::Accept { peer_id }) if peer_id == peer_id_1 => {}
+        v => panic!("unexpected result: {v:?}"),
+    }
+
+    // The outbound peer is asked for addresses right after the handshake
+    let (peer_id, peer_msg) = cmd_to_peer_man_msg(cmd_receiver.try_recv().unwrap());
+    assert_eq!(peer_id, peer_id_1);
+    assert_matches!(peer_msg, PeerManagerMessage::AddrListRequest(_));
+
+    let addresses = (0..response_size)
+        .map(|_| get_new_discoverable_address(&mut rng))
+        .collect::<Vec<_>>();
+    pm.handle_addr_list_response(peer_id_1, addresses);
+    assert_eq!(pm.peers.get(&peer_id_1).unwrap().score, 0);
+
+    let source = peer_address.as_bannable();
+    let source_addresses = pm
+        .peerdb
+        .known_addresses()
+        .filter(|address| pm.peerdb.address_source_peer(address) == Some(source))
+        .copied()
+        .collect::<Vec<_>>();
+    assert_eq!(source_addresses.len(), max_per_source);
+
+    // Some addresses heard of from another peer, in distinct address groups
+    let other_source = TestAddressMaker::new_random_address(&mut rng).as_bannable();
+    let mut other_addresses = BTreeMap::new();
+    while other_addresses.len() < 3 {
+        let address = get_new_discoverable_address(&mut rng);
+        let group = AddressGroup::from_peer_address(&address);
+        let address = address.as_discoverable_socket_address(false).unwrap();
+        if !other_addresses.contains_key(&group)
+            && pm.peerdb.address_discovered_from_peer(address, other_source)
+        {
+            other_addresses.insert(group, address);
+        }
+    }
+    let other_addresses = other_addresses.into_values().collect::<BTreeSet<_>>();
+
+    // Once the source is banned, its addresses are only selected after the others
+    pm.ban(source, Duration::from_secs(60));
+    let selected = pm.peerdb.select_non_reserved_outbound_addresses(
+        &BTreeSet::new(),
+        &|_| true,
+        other_addresses.len(),
+    );
+    assert_eq!(
+        selected.into_iter().collect::<BTreeSet<_>>(),
+        other_addresses
+    );
+}
+
 // Verify that the node periodically resends its own address
 #[tracing::instrument(skip(seed))]
 #[rstest]
@@ -409,6 +638,7 @@ async fn resend_own_addresses(#[case] seed: Seed) {
             software_version: *chain_config.software_version(),
             user_agent: mintlayer_core_user_agent(),
             common_services: NodeType::Full.into(),
+            peer_node_id: None,
         };
         pm.connect(peer_address, OutboundConnectType::Reserved);
 
@@ -467,6 +697,108 @@ async fn resend_own_addresses(#[case] seed: Seed) {
     }
 }
 
+// Verify that the external address obtained by port mapping replaces the discovered own address
+// and is announced to the connected peers.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn announce_port_mapping_address(#[case] seed: Seed) {
+    type TestNetworkingService = DefaultNetworkingService<TcpTransportSocket>;
+
+    let mut rng = make_seedable_rng(seed);
+
+    let listening_address: SocketAddress = "1.2.3.4:3031".parse().unwrap();
+    let outbound_address: SocketAddress = "1.2.3.4:12345".parse().unwrap();
+    let port_mapping_address: SocketAddress = "5.6.7.8:4000".parse().unwrap();
+
+    let chain_config = Arc::new(config::create_unit_test_config());
+    let p2p_config = Arc::new(test_p2p_config());
+    let (cmd_sender, mut cmd_receiver) = tokio::sync::mpsc::unbounded_channel();
+    let (_conn_event_sender, conn_event_receiver) = tokio::sync::mpsc::unbounded_channel();
+    let (_peer_mgr_event_sender, peer_mgr_event_receiver) =
+        tokio::sync::mpsc::unbounded_channel::<PeerManagerEvent>();
+    let time_getter = BasicTestTimeGetter::new();
+    let connectivity_handle = ConnectivityHandle::<TestNetworkingService>::new(
+        vec![listening_address],
+        cmd_sender,
+        conn_event_receiver,
+    );
+
+    let mut pm = PeerManager::<TestNetworkingService, _>::new(
+        true,
+        Arc::clone(&chain_config),
+        Arc::clone(&p2p_config),
+        connectivity_handle,
+        peer_mgr_event_receiver,
+        time_getter.get_time_getter(),
+        peerdb_inmemory_store(),
+    )
+    .unwrap();
+
+    let mut connect_peer = |pm: &mut PeerManager<TestNetworkingService, _>| {
+        let peer_id = PeerId::new();
+        let peer_address = TestAddressMaker::new_random_address(&mut rng).into();
+        let peer_info = make_full_relay_peer_info(peer_id, &chain_config);
+        pm.connect(peer_address, OutboundConnectType::Reserved);
+        pm.accept_connection(
+            peer_address,
+            listening_address,
+            ConnectionDirection::Outbound,
+            peer_info,
+            Some(outbound_address.as_peer_address()),
+        );
+        peer_id
+    };
+
+    let expect_announcement = |cmd_receiver: &mut UnboundedReceiver<Command>,
+                               expected_peer_id: PeerId,
+                               expected_address: SocketAddress| {
+        loop {
+            if let Command::SendMessage { peer_id, message } = cmd_receiver.try_recv().unwrap() {
+                if let CategorizedMessage::PeerManagerMessage(
+                    PeerManagerMessage::AnnounceAddrRequest(AnnounceAddrRequest { address }),
+                ) = message.categorize()
+                {
+                    assert_eq!(peer_id, expected_peer_id);
+                    assert_eq!(address, expected_address.as_peer_address());
+                    break;
+                }
+            }
+        }
+    };
+
+    // Without port mapping, the address seen by the peer is announced
+    let peer1 = connect_peer(&mut pm);
+    expect_announcement(&mut cmd_receiver, peer1, listening_address);
+
+    // The mapped address replaces it and is announced immediately
+    pm.set_port_mapping_address(Some(port_mapping_address));
+    expect_announcement(&mut cmd_receiver, peer1, port_mapping_address);
+    assert_eq!(
+        pm.peers.get(&peer1).unwrap().discovered_own_address,
+        Some(port_mapping_address)
+    );
+
+    // Reporting the same address again doesn't cause a new announcement
+    pm.set_port_mapping_address(Some(port_mapping_address));
+    assert_eq!(cmd_receiver.try_recv(), Err(TryRecvError::Empty));
+
+    // New peers get the mapped address
+    let peer2 = connect_peer(&mut pm);
+    expect_announcement(&mut cmd_receiver, peer2, port_mapping_address);
+
+    // Once the mapping is gone, the address seen by the peers is used again
+    pm.set_port_mapping_address(None);
+    expect_announcement(&mut cmd_receiver, peer1, listening_address);
+    expect_announcement(&mut cmd_receiver, peer2, listening_address);
+    assert_eq!(
+        pm.peers.get(&peer2).unwrap().discovered_own_address,
+        Some(listening_address)
+    );
+}
+
 // Configure the peer manager with an empty dns seed and a predefined peer address.
 // Check that it attempts to connect to the predefined address.
 #[tracing::instrument(skip(seed))]
@@ -706,12 +1038,17 @@ async fn dont_use_dns_seed_if_connections_exist(#[case] seed: Seed) {
 
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
+        socks5_proxy_auth: Default::default(),
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
         max_clock_diff: Default::default(),
@@ -719,6 +1056,13 @@ async fn dont_use_dns_seed_if_connections_exist(#[case] seed: Seed) {
         allow_discover_private_ips: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
     });
@@ -0,0 +1,337 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{net::SocketAddr, sync::Arc};
+
+use rstest::rstest;
+
+use common::{
+    chain::{config, ChainConfig},
+    primitives::user_agent::{mintlayer_core_user_agent, UserAgent},
+};
+use networking::test_helpers::{TestAddressMaker, TestTransportMaker, TestTransportTcp};
+use p2p_test_utils::expect_recv;
+use p2p_types::socket_address::SocketAddress;
+use test_utils::{
+    random::{make_seedable_rng, Rng, Seed},
+    BasicTestTimeGetter,
+};
+use tokio::sync::mpsc;
+use utils_networking::IpOrSocketAddress;
+
+use crate::{
+    admission_config::PeerAdmissionConfig,
+    config::P2pConfig,
+    disconnection_reason::DisconnectionReason,
+    net::{
+        default_backend::types::Command,
+        types::{ConnectivityEvent, PeerInfo},
+    },
+    peer_manager::tests::{
+        make_standalone_peer_manager,
+        utils::{expect_cmd_connect_to, make_full_relay_peer_info, start_manually_connecting},
+    },
+    protocol::{ProtocolVersion, SupportedProtocolVersion},
+    test_helpers::test_peer_mgr_config_with_no_auto_outbound_connections,
+    types::peer_id::PeerId,
+};
+
+fn make_p2p_config(
+    reserved_nodes: Vec<IpOrSocketAddress>,
+    peer_admission_config: PeerAdmissionConfig,
+) -> P2pConfig {
+    P2pConfig {
+        reserved_nodes,
+        peer_admission_config,
+        allow_discover_private_ips: true.into(),
+        peer_manager_config: test_peer_mgr_config_with_no_auto_outbound_connections(),
+
+        bind_addresses: Default::default(),
+        socks5_proxy: Default::default(),
+        socks5_proxy_auth: Default::default(),
+        disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
+        boot_nodes: Default::default(),
+        whitelisted_addresses: Default::default(),
+        ban_config: Default::default(),
+        outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
+        ping_check_period: Default::default(),
+        ping_timeout: Default::default(),
+        peer_handshake_timeout: Default::default(),
+        max_clock_diff: Default::default(),
+        node_type: Default::default(),
+        user_agent: mintlayer_core_user_agent(),
+        sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        protocol_config: Default::default(),
+    }
+}
+
+fn make_peer_info(
+    chain_config: &ChainConfig,
+    user_agent: &str,
+    protocol_version: SupportedProtocolVersion,
+) -> PeerInfo {
+    PeerInfo {
+        user_agent: UserAgent::try_from(user_agent).unwrap(),
+        protocol_version,
+        ..make_full_relay_peer_info(PeerId::new(), chain_config)
+    }
+}
+
+fn inbound_peer_accepted_by_backend(
+    conn_event_sender: &mpsc::UnboundedSender<ConnectivityEvent>,
+    peer_address: SocketAddress,
+    bind_address: SocketAddress,
+    peer_info: PeerInfo,
+) -> PeerId {
+    let peer_id = peer_info.peer_id;
+    conn_event_sender
+        .send(ConnectivityEvent::InboundAccepted {
+            peer_address,
+            bind_address,
+            peer_info,
+            node_address_as_seen_by_peer: None,
+        })
+        .unwrap();
+
+    peer_id
+}
+
+fn admission_config() -> PeerAdmissionConfig {
+    PeerAdmissionConfig {
+        min_protocol_version: Some(ProtocolVersion::new(5)),
+        allowed_user_agents: vec!["^MintlayerCore".parse().unwrap()],
+        denied_user_agents: vec!["Scanner".parse().unwrap()],
+    }
+}
+
+// Inbound peers are checked against the user agent filters and the minimum protocol version;
+// the rejected ones are disconnected and their addresses are discouraged.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn inbound_filters(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let chain_config = Arc::new(config::create_unit_test_config());
+    let p2p_config = Arc::new(make_p2p_config(Vec::new(), admission_config()));
+
+    let time_getter = BasicTestTimeGetter::new();
+    let bind_addr = TestTransportTcp::make_address().into();
+
+    let (peer_mgr, conn_event_sender, peer_mgr_event_sender, mut cmd_receiver, _) =
+        make_standalone_peer_manager(
+            Arc::clone(&chain_config),
+            Arc::clone(&p2p_config),
+            vec![bind_addr],
+            time_getter.get_time_getter(),
+        );
+
+    let peer_mgr_join_handle = logging::spawn_in_current_span(async move {
+        let mut peer_mgr = peer_mgr;
+        let _ = peer_mgr.run_internal(None).await;
+        peer_mgr
+    });
+
+    let test_cases = [
+        ("MintlayerCore", SupportedProtocolVersion::V5, true),
+        ("MintlayerCoreFork", SupportedProtocolVersion::V6, true),
+        ("OtherClient", SupportedProtocolVersion::V6, false),
+        ("MintlayerCoreScanner", SupportedProtocolVersion::V6, false),
+        ("MintlayerCore", SupportedProtocolVersion::V4, false),
+    ];
+
+    let mut accepted_addrs = Vec::new();
+    let mut rejected_addrs = Vec::new();
+
+    for (user_agent, protocol_version, expect_accepted) in test_cases {
+        let peer_addr: SocketAddress = TestAddressMaker::new_random_address(&mut rng).into();
+        let peer_id = inbound_peer_accepted_by_backend(
+            &conn_event_sender,
+            peer_addr,
+            bind_addr,
+            make_peer_info(&chain_config, user_agent, protocol_version),
+        );
+
+        let cmd = expect_recv!(cmd_receiver);
+        if expect_accepted {
+            assert_eq!(cmd, Command::Accept { peer_id });
+            accepted_addrs.push(peer_addr);
+        } else {
+            assert_eq!(
+                cmd,
+                Command::Disconnect {
+                    peer_id,
+                    reason: Some(DisconnectionReason::Incompatible)
+                }
+            );
+            rejected_addrs.push(peer_addr);
+        }
+    }
+
+    drop(conn_event_sender);
+    drop(peer_mgr_event_sender);
+    let peer_mgr = peer_mgr_join_handle.await.unwrap();
+
+    for addr in accepted_addrs {
+        assert!(!peer_mgr.peerdb.is_address_banned_or_discouraged(&addr.as_bannable()));
+    }
+    for addr in rejected_addrs {
+        assert!(peer_mgr.peerdb.is_address_banned_or_discouraged(&addr.as_bannable()));
+    }
+}
+
+// The user agent filters don't apply to the reserved nodes, but the minimum protocol version does.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn reserved_nodes(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let reserved_addr: SocketAddress = TestAddressMaker::new_random_address(&mut rng).into();
+
+    let chain_config = Arc::new(config::create_unit_test_config());
+    let p2p_config = Arc::new(make_p2p_config(
+        vec![IpOrSocketAddress::new_socket_address(reserved_addr.socket_addr())],
+        admission_config(),
+    ));
+
+    let time_getter = BasicTestTimeGetter::new();
+    let bind_addr = TestTransportTcp::make_address().into();
+
+    let (peer_mgr, conn_event_sender, peer_mgr_event_sender, mut cmd_receiver, _) =
+        make_standalone_peer_manager(
+            Arc::clone(&chain_config),
+            Arc::clone(&p2p_config),
+            vec![bind_addr],
+            time_getter.get_time_getter(),
+        );
+
+    let peer_mgr_join_handle = logging::spawn_in_current_span(async move {
+        let mut peer_mgr = peer_mgr;
+        let _ = peer_mgr.run_internal(None).await;
+        peer_mgr
+    });
+
+    let cmd = expect_recv!(cmd_receiver);
+    expect_cmd_connect_to(&cmd, &reserved_addr);
+
+    // Inbound connections from the reserved node's ip come from different ports.
+    let peer_addr = SocketAddress::new(SocketAddr::new(reserved_addr.ip_addr(), rng.gen()));
+    let peer_id = inbound_peer_accepted_by_backend(
+        &conn_event_sender,
+        peer_addr,
+        bind_addr,
+        make_peer_info(&chain_config, "Scanner", SupportedProtocolVersion::V5),
+    );
+    let cmd = expect_recv!(cmd_receiver);
+    assert_eq!(cmd, Command::Accept { peer_id });
+
+    let peer_addr = SocketAddress::new(SocketAddr::new(reserved_addr.ip_addr(), rng.gen()));
+    let peer_id = inbound_peer_accepted_by_backend(
+        &conn_event_sender,
+        peer_addr,
+        bind_addr,
+        make_peer_info(&chain_config, "MintlayerCore", SupportedProtocolVersion::V4),
+    );
+    let cmd = expect_recv!(cmd_receiver);
+    assert_eq!(
+        cmd,
+        Command::Disconnect {
+            peer_id,
+            reason: Some(DisconnectionReason::Incompatible)
+        }
+    );
+
+    drop(conn_event_sender);
+    drop(peer_mgr_event_sender);
+    let _peer_mgr = peer_mgr_join_handle.await.unwrap();
+}
+
+// The filters are applied to the outbound connections as well.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn outbound_filters(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let chain_config = Arc::new(config::create_unit_test_config());
+    let p2p_config = Arc::new(make_p2p_config(Vec::new(), admission_config()));
+
+    let time_getter = BasicTestTimeGetter::new();
+    let bind_addr = TestTransportTcp::make_address().into();
+
+    let (peer_mgr, conn_event_sender, peer_mgr_event_sender, mut cmd_receiver, _) =
+        make_standalone_peer_manager(
+            Arc::clone(&chain_config),
+            Arc::clone(&p2p_config),
+            vec![bind_addr],
+            time_getter.get_time_getter(),
+        );
+
+    let peer_mgr_join_handle = logging::spawn_in_current_span(async move {
+        let mut peer_mgr = peer_mgr;
+        let _ = peer_mgr.run_internal(None).await;
+        peer_mgr
+    });
+
+    let peer_addr: SocketAddress = TestAddressMaker::new_random_address(&mut rng).into();
+    let connect_result_receiver = start_manually_connecting(&peer_mgr_event_sender, peer_addr);
+
+    let cmd = expect_recv!(cmd_receiver);
+    expect_cmd_connect_to(&cmd, &peer_addr);
+
+    let peer_info = make_peer_info(&chain_config, "OtherClient", SupportedProtocolVersion::V6);
+    let peer_id = peer_info.peer_id;
+    conn_event_sender
+        .send(ConnectivityEvent::OutboundAccepted {
+            peer_address: peer_addr,
+            bind_address: bind_addr,
+            peer_info,
+            node_address_as_seen_by_peer: None,
+        })
+        .unwrap();
+
+    let cmd = expect_recv!(cmd_receiver);
+    assert_eq!(
+        cmd,
+        Command::Disconnect {
+            peer_id,
+            reason: Some(DisconnectionReason::Incompatible)
+        }
+    );
+    assert!(connect_result_receiver.await.unwrap().is_err());
+
+    drop(conn_event_sender);
+    drop(peer_mgr_event_sender);
+    let peer_mgr = peer_mgr_join_handle.await.unwrap();
+
+    assert!(peer_mgr.peerdb.is_address_banned_or_discouraged(&peer_addr.as_bannable()));
+}
@@ -0,0 +1,274 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::BTreeSet, sync::Arc, time::Duration};
+
+use rstest::rstest;
+
+use common::{chain::config, primitives::user_agent::mintlayer_core_user_agent};
+use networking::test_helpers::{TestTransportMaker, TestTransportTcp};
+use p2p_test_utils::expect_recv;
+use p2p_types::socket_address::SocketAddress;
+use test_utils::{
+    random::{make_seedable_rng, Seed},
+    BasicTestTimeGetter,
+};
+
+use crate::{
+    config::P2pConfig,
+    error::{DialError, P2pError},
+    net::{default_backend::types::Command, types::ConnectivityEvent},
+    peer_manager::{
+        self,
+        config::PeerManagerConfig,
+        peerdb::test_utils::make_non_colliding_addresses_for_peer_db_in_distinct_addr_groups,
+        tests::{
+            make_standalone_peer_manager_with_storage,
+            utils::{
+                expect_cmd_connect_to_one_of, mutate_peer_manager,
+                outbound_block_relay_peer_accepted_by_backend,
+                outbound_full_relay_peer_accepted_by_backend, recv_command_advance_time,
+            },
+        },
+    },
+    test_helpers::peerdb_inmemory_store,
+};
+
+fn make_p2p_config() -> P2pConfig {
+    P2pConfig {
+        peer_manager_config: PeerManagerConfig {
+            outbound_full_relay_count: 3.into(),
+            outbound_full_relay_extra_count: 0.into(),
+            outbound_block_relay_count: 1.into(),
+            outbound_block_relay_extra_count: 0.into(),
+
+            // Feeler connections must never become anchors, but they'd mess up the test.
+            enable_feeler_connections: false.into(),
+
+            max_inbound_connections: Default::default(),
+            preserved_inbound_count_address_group: Default::default(),
+            preserved_inbound_count_ping: Default::default(),
+            preserved_inbound_count_new_blocks: Default::default(),
+            preserved_inbound_count_new_transactions: Default::default(),
+            outbound_block_relay_connection_min_age: Default::default(),
+            outbound_full_relay_connection_min_age: Default::default(),
+            stale_tip_time_diff: Default::default(),
+            main_loop_tick_interval: Default::default(),
+            feeler_connections_interval: Default::default(),
+            force_dns_query_if_no_global_addresses_known: Default::default(),
+            allow_same_ip_connections: Default::default(),
+            peerdb_config: Default::default(),
+        },
+
+        // Disable pings so that they don't interfere with the testing logic.
+        ping_check_period: Duration::ZERO.into(),
+
+        bind_addresses: Default::default(),
+        socks5_proxy: Default::default(),
+        socks5_proxy_auth: Default::default(),
+        disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
+        boot_nodes: Default::default(),
+        reserved_nodes: Default::default(),
+        whitelisted_addresses: Default::default(),
+        ban_config: Default::default(),
+        outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
+        ping_timeout: Default::default(),
+        peer_handshake_timeout: Default::default(),
+        max_clock_diff: Default::default(),
+        node_type: Default::default(),
+        allow_discover_private_ips: Default::default(),
+        user_agent: mintlayer_core_user_agent(),
+        sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
+        protocol_config: Default::default(),
+    }
+}
+
+// Test scenario:
+// 1) Establish 3 outbound full relay connections and 1 block relay connection and shut down
+// the peer manager; the 2 oldest full relay peers must be stored as anchors.
+// 2) Restart the peer manager with the same storage; the first dial attempts must target
+// the anchors. One of them fails and the other one is connected.
+// 3) Shut down again; the failed anchor must be forgotten.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn anchors_dialed_first_after_restart(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let chain_config = Arc::new(config::create_unit_test_config());
+    let p2p_config = Arc::new(make_p2p_config());
+    let time_getter = BasicTestTimeGetter::new();
+    let bind_addr: SocketAddress = TestTransportTcp::make_address().into();
+
+    let (peer_mgr, conn_event_sender, peer_mgr_event_sender, mut cmd_receiver, _) =
+        make_standalone_peer_manager_with_storage(
+            Arc::clone(&chain_config),
+            Arc::clone(&p2p_config),
+            vec![bind_addr],
+            time_getter.get_time_getter(),
+            peerdb_inmemory_store(),
+        );
+
+    let peer_addrs = make_non_colliding_addresses_for_peer_db_in_distinct_addr_groups(
+        &peer_mgr.peerdb,
+        4,
+        &mut rng,
+    );
+
+    let peer_mgr_join_handle = logging::spawn_in_current_span(async move {
+        let mut peer_mgr = peer_mgr;
+        let _ = peer_mgr.run_internal(None).await;
+        peer_mgr
+    });
+
+    for addr in peer_addrs.clone() {
+        mutate_peer_manager(&peer_mgr_event_sender, move |peer_mgr| {
+            peer_mgr.peer_db_mut().peer_discovered(addr)
+        })
+        .await;
+    }
+
+    // Full relay peers in the order of their connection
+    let mut full_relay_addrs = Vec::new();
+    let mut block_relay_addrs = Vec::new();
+    let mut accepted_count = 0;
+    while full_relay_addrs.len() + block_relay_addrs.len() < peer_addrs.len() {
+        let cmd = recv_command_advance_time(
+            &mut cmd_receiver,
+            &time_getter,
+            peer_manager::HEARTBEAT_INTERVAL_MAX,
+        )
+        .await
+        .unwrap();
+
+        match cmd {
+            Command::Connect {
+                address,
+                local_services_override,
+            } => {
+                if local_services_override.is_some() {
+                    outbound_block_relay_peer_accepted_by_backend(
+                        &conn_event_sender,
+                        address,
+                        bind_addr,
+                        &chain_config,
+                    );
+                    block_relay_addrs.push(address);
+                } else {
+                    outbound_full_relay_peer_accepted_by_backend(
+                        &conn_event_sender,
+                        address,
+                        bind_addr,
+                        &chain_config,
+                    );
+                    full_relay_addrs.push(address);
+                }
+            }
+            Command::Accept { peer_id: _ } => accepted_count += 1,
+            _ => {}
+        }
+    }
+    assert_eq!(full_relay_addrs.len(), 3);
+    assert_eq!(block_relay_addrs.len(), 1);
+
+    // Wait until all the connections are accepted.
+    while accepted_count < peer_addrs.len() {
+        if let Command::Accept { peer_id: _ } = expect_recv!(cmd_receiver) {
+            accepted_count += 1;
+        }
+    }
+
+    drop(conn_event_sender);
+    drop(peer_mgr_event_sender);
+
+    let peer_mgr = peer_mgr_join_handle.await.unwrap();
+
+    let expected_anchors = full_relay_addrs[..2].iter().copied().collect::<BTreeSet<_>>();
+    assert_eq!(*peer_mgr.peerdb.anchors(), expected_anchors);
+
+    // Restart with the same storage
+    let (peer_mgr, conn_event_sender, peer_mgr_event_sender, mut cmd_receiver, _) =
+        make_standalone_peer_manager_with_storage(
+            Arc::clone(&chain_config),
+            Arc::clone(&p2p_config),
+            vec![bind_addr],
+            time_getter.get_time_getter(),
+            peer_mgr.peerdb.into_storage(),
+        );
+    assert_eq!(*peer_mgr.peerdb.anchors(), expected_anchors);
+
+    let peer_mgr_join_handle = logging::spawn_in_current_span(async move {
+        let mut peer_mgr = peer_mgr;
+        let _ = peer_mgr.run_internal(None).await;
+        peer_mgr
+    });
+
+    let mut anchors_to_dial = expected_anchors.clone();
+    let mut dialed_anchors = Vec::new();
+    for _ in 0..expected_anchors.len() {
+        let cmd = expect_recv!(cmd_receiver);
+        let address = expect_cmd_connect_to_one_of(&cmd, &mut anchors_to_dial);
+        // Anchors are dialed as full relay peers
+        assert_eq!(
+            cmd,
+            Command::Connect {
+                address,
+                local_services_override: None
+            }
+        );
+        dialed_anchors.push(address);
+    }
+
+    let [failed_anchor, connected_anchor]: [_; 2] = dialed_anchors.try_into().unwrap();
+    conn_event_sender
+        .send(ConnectivityEvent::ConnectionError {
+            peer_address: failed_anchor,
+            error: P2pError::DialError(DialError::ConnectionRefusedOrTimedOut),
+        })
+        .unwrap();
+    let peer_id = outbound_full_relay_peer_accepted_by_backend(
+        &conn_event_sender,
+        connected_anchor,
+        bind_addr,
+        &chain_config,
+    );
+    loop {
+        if expect_recv!(cmd_receiver) == (Command::Accept { peer_id }) {
+            break;
+        }
+    }
+
+    drop(conn_event_sender);
+    drop(peer_mgr_event_sender);
+
+    let peer_mgr = peer_mgr_join_handle.await.unwrap();
+    assert_eq!(
+        *peer_mgr.peerdb.anchors(),
+        BTreeSet::from([connected_anchor])
+    );
+}
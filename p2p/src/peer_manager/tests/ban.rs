@@ -53,6 +53,9 @@ use crate::{
         test_p2p_config, test_p2p_config_with_ban_config, test_p2p_config_with_peer_mgr_config,
         test_peer_mgr_config_with_no_auto_outbound_connections,
     },
+    types::peer_id::PeerId,
+    utils::oneshot_nofail,
+    PeerManagerEvent,
 };
 
 // Check that a peer is not banned automatically.
@@ -218,6 +221,236 @@ async fn disconnect_manually_banned_peer(#[case] seed: Seed) {
     let _peer_mgr = peer_mgr_join_handle.await.unwrap();
 }
 
+// Check that peer score adjustments are recorded in the misbehavior log and reported
+// for banned addresses.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn misbehavior_log(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let chain_config = Arc::new(config::create_unit_test_config());
+    let p2p_config = Arc::new(test_p2p_config());
+
+    let time_getter = BasicTestTimeGetter::new();
+    let bind_addr = TestTransportTcp::make_address().into();
+
+    let (
+        peer_mgr,
+        conn_event_sender,
+        peer_mgr_event_sender,
+        mut cmd_receiver,
+        _peer_mgr_notification_receiver,
+    ) = make_standalone_peer_manager(
+        Arc::clone(&chain_config),
+        Arc::clone(&p2p_config),
+        vec![bind_addr],
+        time_getter.get_time_getter(),
+    );
+
+    let peer_mgr_join_handle = logging::spawn_in_current_span(async move {
+        let mut peer_mgr = peer_mgr;
+        let _ = peer_mgr.run_internal(None).await;
+        peer_mgr
+    });
+
+    let peer_addr1 = TestAddressMaker::new_random_address(&mut rng).into();
+    let peer_id1 = inbound_block_relay_peer_accepted_by_backend(
+        &conn_event_sender,
+        peer_addr1,
+        bind_addr,
+        &chain_config,
+    );
+    let cmd = expect_recv!(cmd_receiver);
+    assert_eq!(cmd, Command::Accept { peer_id: peer_id1 });
+
+    let peer_addr2 = TestAddressMaker::new_random_address(&mut rng).into();
+    let peer_id2 = inbound_block_relay_peer_accepted_by_backend(
+        &conn_event_sender,
+        peer_addr2,
+        bind_addr,
+        &chain_config,
+    );
+    let cmd = expect_recv!(cmd_receiver);
+    assert_eq!(cmd, Command::Accept { peer_id: peer_id2 });
+
+    adjust_peer_score(&peer_mgr_event_sender, peer_id1, 10).await;
+    adjust_peer_score(&peer_mgr_event_sender, peer_id2, 20).await;
+    adjust_peer_score(&peer_mgr_event_sender, peer_id1, 30).await;
+
+    let get_misbehavior_log = |limit, peer_filter| {
+        let (response_sender, response_receiver) = oneshot_nofail::channel();
+        peer_mgr_event_sender
+            .send(PeerManagerEvent::GetMisbehaviorLog(
+                limit,
+                peer_filter,
+                response_sender,
+            ))
+            .unwrap();
+        response_receiver
+    };
+
+    let events = get_misbehavior_log(10, None).await.unwrap();
+    assert_eq!(
+        events
+            .iter()
+            .map(|event| (
+                event.peer_id,
+                event.address,
+                event.score_adjustment,
+                event.total_score
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            (Some(peer_id1), peer_addr1, 10, 10),
+            (Some(peer_id2), peer_addr2, 20, 20),
+            (Some(peer_id1), peer_addr1, 30, 40),
+        ]
+    );
+    assert!(events.iter().all(|event| event.reason == "ProtocolError"));
+
+    // The most recent events are returned
+    let limited_events = get_misbehavior_log(2, None).await.unwrap();
+    assert_eq!(limited_events, events[1..]);
+
+    let peer1_events = get_misbehavior_log(10, Some(peer_id1)).await.unwrap();
+    assert_eq!(peer1_events, vec![events[0].clone(), events[2].clone()]);
+
+    ban_peer_manually(
+        &peer_mgr_event_sender,
+        peer_addr1.as_bannable(),
+        Duration::from_secs(60 * 60),
+    )
+    .await;
+
+    let (response_sender, response_receiver) = oneshot_nofail::channel();
+    peer_mgr_event_sender
+        .send(PeerManagerEvent::ListBanned(response_sender))
+        .unwrap();
+    let banned = response_receiver.await.unwrap();
+    assert_eq!(banned.len(), 1);
+    assert_eq!(banned[0].address, peer_addr1.as_bannable());
+    assert_eq!(banned[0].recent_misbehavior, peer1_events);
+
+    drop(conn_event_sender);
+    drop(peer_mgr_event_sender);
+
+    let _peer_mgr = peer_mgr_join_handle.await.unwrap();
+}
+
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn misbehaving_peers(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let chain_config = Arc::new(config::create_unit_test_config());
+    let p2p_config = Arc::new(test_p2p_config());
+
+    let time_getter = BasicTestTimeGetter::new();
+    let bind_addr = TestTransportTcp::make_address().into();
+
+    let (
+        peer_mgr,
+        conn_event_sender,
+        peer_mgr_event_sender,
+        mut cmd_receiver,
+        _peer_mgr_notification_receiver,
+    ) = make_standalone_peer_manager(
+        Arc::clone(&chain_config),
+        Arc::clone(&p2p_config),
+        vec![bind_addr],
+        time_getter.get_time_getter(),
+    );
+
+    let peer_mgr_join_handle = logging::spawn_in_current_span(async move {
+        let mut peer_mgr = peer_mgr;
+        let _ = peer_mgr.run_internal(None).await;
+        peer_mgr
+    });
+
+    let mut peers = Vec::new();
+    for _ in 0..3 {
+        let peer_addr = TestAddressMaker::new_random_address(&mut rng).into();
+        let peer_id = inbound_block_relay_peer_accepted_by_backend(
+            &conn_event_sender,
+            peer_addr,
+            bind_addr,
+            &chain_config,
+        );
+        let cmd = expect_recv!(cmd_receiver);
+        assert_eq!(cmd, Command::Accept { peer_id });
+        peers.push((peer_id, peer_addr));
+    }
+    let (peer_id1, peer_addr1) = peers[0];
+    let (peer_id2, peer_addr2) = peers[1];
+    let (peer_id3, peer_addr3) = peers[2];
+
+    // The scores stay below the ban threshold, so that all the peers remain connected
+    adjust_peer_score(&peer_mgr_event_sender, peer_id1, 10).await;
+    adjust_peer_score(&peer_mgr_event_sender, peer_id2, 20).await;
+    adjust_peer_score(&peer_mgr_event_sender, peer_id1, 30).await;
+
+    let get_misbehaving_peers = |min_score| {
+        let (response_sender, response_receiver) = oneshot_nofail::channel();
+        peer_mgr_event_sender
+            .send(PeerManagerEvent::GetMisbehavingPeers(
+                min_score,
+                response_sender,
+            ))
+            .unwrap();
+        response_receiver
+    };
+    let get_peer_ban_score = |peer_id| {
+        let (response_sender, response_receiver) = oneshot_nofail::channel();
+        peer_mgr_event_sender
+            .send(PeerManagerEvent::GetPeerBanScore(peer_id, response_sender))
+            .unwrap();
+        response_receiver
+    };
+
+    assert_eq!(get_peer_ban_score(peer_id1).await.unwrap(), Some(40));
+    assert_eq!(get_peer_ban_score(peer_id2).await.unwrap(), Some(20));
+    assert_eq!(get_peer_ban_score(peer_id3).await.unwrap(), Some(0));
+    assert_eq!(get_peer_ban_score(PeerId::new()).await.unwrap(), None);
+
+    // Zero matches all the connected peers, the peers are ordered by their ids
+    assert_eq!(
+        get_misbehaving_peers(0).await.unwrap(),
+        vec![
+            (peer_id1, peer_addr1, 40),
+            (peer_id2, peer_addr2, 20),
+            (peer_id3, peer_addr3, 0),
+        ]
+    );
+    // The threshold is inclusive
+    assert_eq!(
+        get_misbehaving_peers(20).await.unwrap(),
+        vec![(peer_id1, peer_addr1, 40), (peer_id2, peer_addr2, 20)]
+    );
+    assert_eq!(
+        get_misbehaving_peers(21).await.unwrap(),
+        vec![(peer_id1, peer_addr1, 40)]
+    );
+    assert_eq!(get_misbehaving_peers(41).await.unwrap(), vec![]);
+
+    // Further misbehavior moves a peer over the threshold
+    adjust_peer_score(&peer_mgr_event_sender, peer_id3, 25).await;
+    assert_eq!(
+        get_misbehaving_peers(21).await.unwrap(),
+        vec![(peer_id1, peer_addr1, 40), (peer_id3, peer_addr3, 25)]
+    );
+
+    drop(conn_event_sender);
+    drop(peer_mgr_event_sender);
+
+    let _peer_mgr = peer_mgr_join_handle.await.unwrap();
+}
+
 // Check that an incoming connection from a banned peer is rejected.
 #[tracing::instrument(skip(seed))]
 #[rstest]
@@ -387,12 +620,17 @@ async fn banned_address_is_not_announced(#[case] seed: Seed) {
 
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
+        socks5_proxy_auth: Default::default(),
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_check_period: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
@@ -400,6 +638,13 @@ async fn banned_address_is_not_announced(#[case] seed: Seed) {
         node_type: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
     });
@@ -508,12 +753,17 @@ async fn banned_address_not_in_addr_response(#[case] seed: Seed) {
 
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
+        socks5_proxy_auth: Default::default(),
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_check_period: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
@@ -521,6 +771,13 @@ async fn banned_address_not_in_addr_response(#[case] seed: Seed) {
         node_type: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         protocol_config: Default::default(),
     });
 
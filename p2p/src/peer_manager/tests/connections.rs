@@ -79,7 +79,7 @@ use crate::{
         PeerManager,
     },
     test_helpers::{
-        connect_and_accept_services, connect_services, get_connectivity_event,
+        connect_and_accept_services, connect_services, filter_connectivity_event,
         make_transport_with_local_addr_in_group, peerdb_inmemory_store, test_p2p_config,
         test_p2p_config_with_peer_mgr_config, TEST_PROTOCOL_VERSION,
     },
@@ -121,6 +121,7 @@ where
                 common_services: [Service::Blocks, Service::Transactions, Service::PeerAddresses]
                     .as_slice()
                     .into(),
+                peer_node_id: None,
             },
             None,
         );
@@ -198,7 +199,11 @@ where
     // that tries to connect to the first manager
     logging::spawn_in_current_span(async move { pm1.run().await });
 
-    let event = get_connectivity_event::<T>(&mut pm2.peer_connectivity_handle).await;
+    // Note: with an encrypted transport, pm2 also receives the node id of pm1.
+    let event = filter_connectivity_event::<T, _>(&mut pm2.peer_connectivity_handle, |event| {
+        !matches!(event, Ok(ConnectivityEvent::PeerNodeIdReceived { .. }))
+    })
+    .await;
     match event {
         Ok(net::types::ConnectivityEvent::ConnectionClosed { peer_id })
             if peer_id == peer_info.peer_id => {}
@@ -255,7 +260,7 @@ async fn test_peer_manager_connect<T>(
         peer_manager.peer_connectivity_handle.poll_next().await,
         Ok(net::types::ConnectivityEvent::ConnectionError {
             peer_address: _,
-            error: P2pError::DialError(DialError::ConnectionRefusedOrTimedOut)
+            error: P2pError::NetworkingError(_)
         })
     ));
 }
@@ -664,7 +669,11 @@ where
     // that tries to connect to the first manager
     logging::spawn_in_current_span(async move { pm1.run().await });
 
-    let event = get_connectivity_event::<T>(&mut pm2.peer_connectivity_handle).await;
+    // Note: with an encrypted transport, pm2 also receives the node id of pm1.
+    let event = filter_connectivity_event::<T, _>(&mut pm2.peer_connectivity_handle, |event| {
+        !matches!(event, Ok(ConnectivityEvent::PeerNodeIdReceived { .. }))
+    })
+    .await;
     if let Ok(net::types::ConnectivityEvent::ConnectionClosed { peer_id }) = event {
         assert_eq!(peer_id, peer_info.peer_id);
     } else {
@@ -687,6 +696,7 @@ async fn inbound_connection_too_many_peers_tcp() {
                     software_version: *config.software_version(),
                     user_agent: mintlayer_core_user_agent(),
                     common_services: [Service::Blocks, Service::Transactions].as_slice().into(),
+                    peer_node_id: None,
                 },
             )
         })
@@ -714,6 +724,7 @@ async fn inbound_connection_too_many_peers_channels() {
                     software_version: *config.software_version(),
                     user_agent: mintlayer_core_user_agent(),
                     common_services: [Service::Blocks, Service::Transactions].as_slice().into(),
+                    peer_node_id: None,
                 },
             )
         })
@@ -741,6 +752,7 @@ async fn inbound_connection_too_many_peers_noise() {
                     software_version: *config.software_version(),
                     user_agent: mintlayer_core_user_agent(),
                     common_services: [Service::Blocks, Service::Transactions].as_slice().into(),
+                    peer_node_id: None,
                 },
             )
         })
@@ -838,10 +850,15 @@ async fn connection_timeout_rpc_notified<T>(
     let config = Arc::new(config::create_unit_test_config());
     let p2p_config = Arc::new(P2pConfig {
         outbound_connection_timeout: Duration::from_secs(1).into(),
+        inbound_connection_timeout: Default::default(),
 
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
+        socks5_proxy_auth: Default::default(),
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
@@ -854,6 +871,13 @@ async fn connection_timeout_rpc_notified<T>(
         allow_discover_private_ips: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
     });
@@ -955,12 +979,17 @@ where
     let p2p_config_1 = Arc::new(P2pConfig {
         bind_addresses: Default::default(),
         socks5_proxy: None,
+        socks5_proxy_auth: None,
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_check_period: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
@@ -969,6 +998,13 @@ where
         allow_discover_private_ips: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
     });
@@ -1000,11 +1036,16 @@ where
 
         bind_addresses: Default::default(),
         socks5_proxy: None,
+        socks5_proxy_auth: None,
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_check_period: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
@@ -1013,6 +1054,13 @@ where
         allow_discover_private_ips: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
     });
@@ -1110,12 +1158,17 @@ where
 
         bind_addresses: Default::default(),
         socks5_proxy: None,
+        socks5_proxy_auth: None,
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_check_period: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
@@ -1123,6 +1176,13 @@ where
         node_type: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         protocol_config: Default::default(),
     });
     let (peer_mgr_event_sender1, _shutdown_sender, _subscribers_sender) = run_peer_manager::<T>(
@@ -1155,11 +1215,16 @@ where
 
         bind_addresses: Default::default(),
         socks5_proxy: None,
+        socks5_proxy_auth: None,
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_check_period: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
@@ -1167,6 +1232,13 @@ where
         node_type: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         protocol_config: Default::default(),
     });
     let (peer_mgr_event_sender2, _shutdown_sender, _subscribers_sender) = run_peer_manager::<T>(
@@ -1186,11 +1258,16 @@ where
 
         bind_addresses: Default::default(),
         socks5_proxy: None,
+        socks5_proxy_auth: None,
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_check_period: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
@@ -1198,6 +1275,13 @@ where
         node_type: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         protocol_config: Default::default(),
     });
     let (peer_mgr_event_sender3, _shutdown_sender, _subscribers_sender) = run_peer_manager::<T>(
@@ -1314,12 +1398,17 @@ async fn discovered_node_2_groups() {
 
         bind_addresses: Default::default(),
         socks5_proxy: None,
+        socks5_proxy_auth: None,
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_check_period: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
@@ -1327,6 +1416,13 @@ async fn discovered_node_2_groups() {
         node_type: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         protocol_config: Default::default(),
     });
     let (peer_mgr_event_sender1, _shutdown_sender, _subscribers_sender) =
@@ -1360,11 +1456,16 @@ async fn discovered_node_2_groups() {
 
         bind_addresses: Default::default(),
         socks5_proxy: None,
+        socks5_proxy_auth: None,
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_check_period: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
@@ -1372,6 +1473,13 @@ async fn discovered_node_2_groups() {
         node_type: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         protocol_config: Default::default(),
     });
     let (peer_mgr_event_sender2, _shutdown_sender, _subscribers_sender) =
@@ -1392,11 +1500,16 @@ async fn discovered_node_2_groups() {
 
         bind_addresses: Default::default(),
         socks5_proxy: None,
+        socks5_proxy_auth: None,
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_check_period: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
@@ -1404,6 +1517,13 @@ async fn discovered_node_2_groups() {
         node_type: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         protocol_config: Default::default(),
     });
     let (peer_mgr_event_sender3, _shutdown_sender, _subscribers_sender) =
@@ -1481,12 +1601,17 @@ async fn discovered_node_separate_groups() {
 
         bind_addresses: Default::default(),
         socks5_proxy: None,
+        socks5_proxy_auth: None,
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_check_period: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
@@ -1494,6 +1619,13 @@ async fn discovered_node_separate_groups() {
         node_type: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         protocol_config: Default::default(),
     });
     let (peer_mgr_event_sender1, _shutdown_sender, _subscribers_sender) =
@@ -1527,11 +1659,16 @@ async fn discovered_node_separate_groups() {
 
         bind_addresses: Default::default(),
         socks5_proxy: None,
+        socks5_proxy_auth: None,
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_check_period: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
@@ -1539,6 +1676,13 @@ async fn discovered_node_separate_groups() {
         node_type: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         protocol_config: Default::default(),
     });
     let (peer_mgr_event_sender2, _shutdown_sender, _subscribers_sender) =
@@ -1559,11 +1703,16 @@ async fn discovered_node_separate_groups() {
 
         bind_addresses: Default::default(),
         socks5_proxy: None,
+        socks5_proxy_auth: None,
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_check_period: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
@@ -1571,6 +1720,13 @@ async fn discovered_node_separate_groups() {
         node_type: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         protocol_config: Default::default(),
     });
     let (peer_mgr_event_sender3, _shutdown_sender, _subscribers_sender) =
@@ -1851,6 +2007,9 @@ mod feeler_connections_test_utils {
                     new_addr_table_bucket_count: Default::default(),
                     tried_addr_table_bucket_count: Default::default(),
                     addr_tables_bucket_size: Default::default(),
+                    misbehavior_log_size: Default::default(),
+                    max_addresses_per_source_per_day: Default::default(),
+                    max_addresses_per_source_percent: Default::default(),
                 },
 
                 preserved_inbound_count_address_group: Default::default(),
@@ -1872,12 +2031,17 @@ mod feeler_connections_test_utils {
 
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
+            socks5_proxy_auth: Default::default(),
             disable_noise: Default::default(),
+            reserved_nodes_only: Default::default(),
+            enable_mempool_sync: Default::default(),
+            enable_port_mapping: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
             ban_config: Default::default(),
             outbound_connection_timeout: Default::default(),
+            inbound_connection_timeout: Default::default(),
             ping_timeout: Default::default(),
             peer_handshake_timeout: Default::default(),
             max_clock_diff: Default::default(),
@@ -1885,6 +2049,13 @@ mod feeler_connections_test_utils {
             allow_discover_private_ips: Default::default(),
             user_agent: mintlayer_core_user_agent(),
             sync_stalling_timeout: Default::default(),
+            fork_alert_threshold: Default::default(),
+            fork_alert_duration: Default::default(),
+            max_mempool_tx_broadcasts_per_peer: Default::default(),
+            max_buffered_block_response_bytes: Default::default(),
+            max_in_flight_block_responses_per_peer: Default::default(),
+            max_low_work_blocks_in_flight: Default::default(),
+            peer_admission_config: Default::default(),
             protocol_config: Default::default(),
         }
     }
@@ -1958,12 +2129,17 @@ async fn reject_connection_to_existing_ip(#[case] seed: Seed) {
 
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
+        socks5_proxy_auth: Default::default(),
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
         max_clock_diff: Default::default(),
@@ -1971,6 +2147,13 @@ async fn reject_connection_to_existing_ip(#[case] seed: Seed) {
         allow_discover_private_ips: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         protocol_config: Default::default(),
     });
 
@@ -105,6 +105,9 @@ mod dont_evict_if_blocks_in_flight {
                     new_addr_table_bucket_count: Default::default(),
                     tried_addr_table_bucket_count: Default::default(),
                     addr_tables_bucket_size: Default::default(),
+                    misbehavior_log_size: Default::default(),
+                    max_addresses_per_source_per_day: Default::default(),
+                    max_addresses_per_source_percent: Default::default(),
                 },
 
                 preserved_inbound_count_address_group: Default::default(),
@@ -126,12 +129,17 @@ mod dont_evict_if_blocks_in_flight {
 
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
+            socks5_proxy_auth: Default::default(),
             disable_noise: Default::default(),
+            reserved_nodes_only: Default::default(),
+            enable_mempool_sync: Default::default(),
+            enable_port_mapping: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
             ban_config: Default::default(),
             outbound_connection_timeout: Default::default(),
+            inbound_connection_timeout: Default::default(),
             ping_timeout: Default::default(),
             peer_handshake_timeout: Default::default(),
             max_clock_diff: Default::default(),
@@ -139,6 +147,13 @@ mod dont_evict_if_blocks_in_flight {
             allow_discover_private_ips: Default::default(),
             user_agent: mintlayer_core_user_agent(),
             sync_stalling_timeout: Default::default(),
+            fork_alert_threshold: Default::default(),
+            fork_alert_duration: Default::default(),
+            max_mempool_tx_broadcasts_per_peer: Default::default(),
+            max_buffered_block_response_bytes: Default::default(),
+            max_in_flight_block_responses_per_peer: Default::default(),
+            max_low_work_blocks_in_flight: Default::default(),
+            peer_admission_config: Default::default(),
             protocol_config: Default::default(),
         });
 
@@ -270,6 +285,7 @@ mod dont_evict_if_blocks_in_flight {
                     peer_id: peer_ids[0],
                     new_status: PeerBlockSyncStatus {
                         expecting_blocks_since: Some(expect_blocks_since),
+                        ..PeerBlockSyncStatus::new()
                     },
                 })
                 .unwrap();
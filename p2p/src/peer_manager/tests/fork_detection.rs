@@ -0,0 +1,256 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use tokio::sync::mpsc;
+
+use chainstate::PossibleFork;
+use common::{
+    chain::{config, Block},
+    primitives::{Id, H256},
+};
+use networking::{
+    test_helpers::{TestTransportMaker, TestTransportTcp},
+    transport::TcpTransportSocket,
+};
+use p2p_test_utils::expect_recv;
+use test_utils::BasicTestTimeGetter;
+
+use crate::{
+    net::{
+        default_backend::{types::Command, ConnectivityHandle, DefaultNetworkingService},
+        types::ConnectivityEvent,
+    },
+    peer_manager::{
+        tests::{get_connected_peers, utils::make_full_relay_peer_info},
+        PeerManager,
+    },
+    test_helpers::{peerdb_inmemory_store, test_p2p_config},
+    types::peer_id::PeerId,
+    utils::oneshot_nofail,
+    P2pEvent, PeerManagerEvent,
+};
+
+fn block_id(n: u64) -> Id<Block> {
+    Id::new(H256::from_low_u64_be(n))
+}
+
+async fn get_possible_fork(
+    peer_mgr_event_sender: &mpsc::UnboundedSender<PeerManagerEvent>,
+) -> Option<PossibleFork> {
+    let (response_sender, response_receiver) = oneshot_nofail::channel();
+    peer_mgr_event_sender
+        .send(PeerManagerEvent::GetPossibleFork(response_sender))
+        .unwrap();
+    response_receiver.await.unwrap()
+}
+
+/// Expect the PossibleFork event to be broadcast, ignoring other commands.
+async fn expect_possible_fork_event(
+    cmd_receiver: &mut mpsc::UnboundedReceiver<Command>,
+) -> P2pEvent {
+    loop {
+        match expect_recv!(cmd_receiver) {
+            Command::BroadcastEvent { event } => break event,
+            _ => {}
+        }
+    }
+}
+
+// 3 of 4 peers report a different tip for longer than fork_alert_duration; the node should
+// notify the subscribers about a possible fork and return it via GetPossibleFork.
+#[tracing::instrument]
+#[tokio::test]
+async fn possible_fork_detected() {
+    type TestNetworkingService = DefaultNetworkingService<TcpTransportSocket>;
+
+    let chain_config = Arc::new(config::create_unit_test_config());
+    let p2p_config = Arc::new(test_p2p_config());
+    let fork_alert_duration = *p2p_config.fork_alert_duration;
+    assert_eq!(*p2p_config.fork_alert_threshold, 3);
+
+    let (cmd_sender, mut cmd_receiver) = mpsc::unbounded_channel();
+    let (conn_event_sender, conn_event_receiver) = mpsc::unbounded_channel();
+    let (peer_mgr_event_sender, peer_mgr_event_receiver) =
+        mpsc::unbounded_channel::<PeerManagerEvent>();
+    let time_getter = BasicTestTimeGetter::new();
+    let bind_address = TestTransportTcp::make_address().into();
+    let connectivity_handle = ConnectivityHandle::<TestNetworkingService>::new(
+        vec![bind_address],
+        cmd_sender,
+        conn_event_receiver,
+    );
+
+    let peer_manager = PeerManager::<TestNetworkingService, _>::new(
+        true,
+        Arc::clone(&chain_config),
+        Arc::clone(&p2p_config),
+        connectivity_handle,
+        peer_mgr_event_receiver,
+        time_getter.get_time_getter(),
+        peerdb_inmemory_store(),
+    )
+    .unwrap();
+
+    logging::spawn_in_current_span(async move {
+        let _ = peer_manager.run().await;
+    });
+
+    let mut peer_ids = Vec::new();
+    for i in 0..4 {
+        let peer_id = PeerId::new();
+        conn_event_sender
+            .send(ConnectivityEvent::InboundAccepted {
+                peer_address: format!("123.123.{i}.123:12345").parse().unwrap(),
+                bind_address,
+                peer_info: make_full_relay_peer_info(peer_id, &chain_config),
+                node_address_as_seen_by_peer: None,
+            })
+            .unwrap();
+
+        let cmd = expect_recv!(cmd_receiver);
+        assert_eq!(cmd, Command::Accept { peer_id });
+        peer_ids.push(peer_id);
+    }
+
+    let local_tip = block_id(1);
+    let fork_tip = block_id(2);
+    let tips = [local_tip, fork_tip, fork_tip, fork_tip];
+    for (peer_id, tip) in peer_ids.iter().zip(tips) {
+        peer_mgr_event_sender
+            .send(PeerManagerEvent::ReportedBestBlock {
+                peer_id: *peer_id,
+                block_id: tip,
+                local_tip: local_tip.into(),
+            })
+            .unwrap();
+    }
+
+    // The peers haven't been disagreeing long enough.
+    assert_eq!(get_possible_fork(&peer_mgr_event_sender).await, None);
+
+    // Advance the time and trigger the check.
+    time_getter.advance_time(fork_alert_duration);
+    peer_mgr_event_sender
+        .send(PeerManagerEvent::NewChainstateTip(local_tip))
+        .unwrap();
+
+    let expected_peer_tips = BTreeMap::from([(fork_tip, 3)]);
+    let event = expect_possible_fork_event(&mut cmd_receiver).await;
+    assert_eq!(
+        event,
+        P2pEvent::PossibleFork {
+            local_tip: local_tip.into(),
+            peer_tips: expected_peer_tips.clone(),
+        }
+    );
+    assert_eq!(
+        get_possible_fork(&peer_mgr_event_sender).await,
+        Some(PossibleFork {
+            local_tip: local_tip.into(),
+            peer_tips: expected_peer_tips,
+        })
+    );
+
+    // The node has switched to the peers' chain, the possible fork is gone.
+    peer_mgr_event_sender
+        .send(PeerManagerEvent::NewChainstateTip(fork_tip))
+        .unwrap();
+    assert_eq!(get_possible_fork(&peer_mgr_event_sender).await, None);
+}
+
+// Disconnected peers are not taken into account.
+#[tracing::instrument]
+#[tokio::test]
+async fn disconnected_peers_ignored() {
+    type TestNetworkingService = DefaultNetworkingService<TcpTransportSocket>;
+
+    let chain_config = Arc::new(config::create_unit_test_config());
+    let p2p_config = Arc::new(test_p2p_config());
+    let fork_alert_duration = *p2p_config.fork_alert_duration;
+
+    let (cmd_sender, mut cmd_receiver) = mpsc::unbounded_channel();
+    let (conn_event_sender, conn_event_receiver) = mpsc::unbounded_channel();
+    let (peer_mgr_event_sender, peer_mgr_event_receiver) =
+        mpsc::unbounded_channel::<PeerManagerEvent>();
+    let time_getter = BasicTestTimeGetter::new();
+    let bind_address = TestTransportTcp::make_address().into();
+    let connectivity_handle = ConnectivityHandle::<TestNetworkingService>::new(
+        vec![bind_address],
+        cmd_sender,
+        conn_event_receiver,
+    );
+
+    let peer_manager = PeerManager::<TestNetworkingService, _>::new(
+        true,
+        Arc::clone(&chain_config),
+        Arc::clone(&p2p_config),
+        connectivity_handle,
+        peer_mgr_event_receiver,
+        time_getter.get_time_getter(),
+        peerdb_inmemory_store(),
+    )
+    .unwrap();
+
+    logging::spawn_in_current_span(async move {
+        let _ = peer_manager.run().await;
+    });
+
+    let local_tip = block_id(1);
+    let fork_tip = block_id(2);
+
+    let mut peer_ids = Vec::new();
+    for i in 0..3 {
+        let peer_id = PeerId::new();
+        conn_event_sender
+            .send(ConnectivityEvent::InboundAccepted {
+                peer_address: format!("123.123.{i}.123:12345").parse().unwrap(),
+                bind_address,
+                peer_info: make_full_relay_peer_info(peer_id, &chain_config),
+                node_address_as_seen_by_peer: None,
+            })
+            .unwrap();
+
+        let cmd = expect_recv!(cmd_receiver);
+        assert_eq!(cmd, Command::Accept { peer_id });
+
+        peer_mgr_event_sender
+            .send(PeerManagerEvent::ReportedBestBlock {
+                peer_id,
+                block_id: fork_tip,
+                local_tip: local_tip.into(),
+            })
+            .unwrap();
+        peer_ids.push(peer_id);
+    }
+
+    conn_event_sender
+        .send(ConnectivityEvent::ConnectionClosed {
+            peer_id: peer_ids[0],
+        })
+        .unwrap();
+    while get_connected_peers(&peer_mgr_event_sender).await.len() != 2 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    time_getter.advance_time(fork_alert_duration + Duration::from_secs(1));
+    peer_mgr_event_sender
+        .send(PeerManagerEvent::NewChainstateTip(local_tip))
+        .unwrap();
+
+    // Only 2 peers report a different tip.
+    assert_eq!(get_possible_fork(&peer_mgr_event_sender).await, None);
+}
@@ -15,12 +15,17 @@
 
 mod addr_list_response_caching;
 mod addresses;
+mod admission_filters;
+mod anchors;
 mod ban;
 mod connections;
 mod discouragement;
 mod eviction;
+mod fork_detection;
+mod node_addresses;
 mod peer_types;
 mod ping;
+mod reserved_nodes_only;
 pub mod utils;
 mod whitelist;
 
@@ -156,6 +161,29 @@ pub fn make_standalone_peer_manager(
     mpsc::UnboundedSender<PeerManagerEvent>,
     mpsc::UnboundedReceiver<Command>,
     mpsc::UnboundedReceiver<PeerManagerNotification>,
+) {
+    make_standalone_peer_manager_with_storage(
+        chain_config,
+        p2p_config,
+        bind_addresses,
+        time_getter,
+        peerdb_inmemory_store(),
+    )
+}
+
+#[allow(clippy::type_complexity)]
+pub fn make_standalone_peer_manager_with_storage<S: PeerDbStorage>(
+    chain_config: Arc<ChainConfig>,
+    p2p_config: Arc<P2pConfig>,
+    bind_addresses: Vec<SocketAddress>,
+    time_getter: TimeGetter,
+    peerdb_storage: S,
+) -> (
+    PeerManager<TcpNetworkingService, S>,
+    mpsc::UnboundedSender<ConnectivityEvent>,
+    mpsc::UnboundedSender<PeerManagerEvent>,
+    mpsc::UnboundedReceiver<Command>,
+    mpsc::UnboundedReceiver<PeerManagerNotification>,
 ) {
     let (cmd_sender, cmd_receiver) = mpsc::unbounded_channel();
     let (conn_event_sender, conn_event_receiver) = mpsc::unbounded_channel();
@@ -177,7 +205,7 @@ pub fn make_standalone_peer_manager(
         connectivity_handle,
         peer_mgr_event_receiver,
         time_getter,
-        peerdb_inmemory_store(),
+        peerdb_storage,
         Some(peer_mgr_observer),
         Box::new(dns_seed),
     )
@@ -0,0 +1,133 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use rstest::rstest;
+
+use common::chain::config;
+use networking::test_helpers::{TestAddressMaker, TestTransportMaker, TestTransportTcp};
+use p2p_test_utils::expect_recv;
+use p2p_types::{socket_address::SocketAddress, NodeId};
+use test_utils::{
+    random::{make_seedable_rng, Rng, Seed},
+    BasicTestTimeGetter,
+};
+
+use crate::{
+    net::{
+        default_backend::types::Command,
+        types::{ConnectivityEvent, PeerInfo},
+    },
+    peer_manager::tests::{
+        make_standalone_peer_manager,
+        utils::{expect_cmd_connect_to, make_full_relay_peer_info, start_manually_connecting},
+    },
+    test_helpers::{
+        test_p2p_config_with_peer_mgr_config,
+        test_peer_mgr_config_with_no_auto_outbound_connections,
+    },
+    types::peer_id::PeerId,
+};
+
+// The node ids advertised by the outbound peers are stored in the peer db along with
+// the addresses the peers were reached at; the node ids of the inbound peers are not,
+// because their addresses can't be dialed.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn outbound_node_addresses_stored(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let chain_config = Arc::new(config::create_unit_test_config());
+    let p2p_config = Arc::new(test_p2p_config_with_peer_mgr_config(
+        test_peer_mgr_config_with_no_auto_outbound_connections(),
+    ));
+
+    let time_getter = BasicTestTimeGetter::new();
+    let bind_addr = TestTransportTcp::make_address().into();
+
+    let (peer_mgr, conn_event_sender, peer_mgr_event_sender, mut cmd_receiver, _) =
+        make_standalone_peer_manager(
+            Arc::clone(&chain_config),
+            Arc::clone(&p2p_config),
+            vec![bind_addr],
+            time_getter.get_time_getter(),
+        );
+
+    let peer_mgr_join_handle = logging::spawn_in_current_span(async move {
+        let mut peer_mgr = peer_mgr;
+        let _ = peer_mgr.run_internal(None).await;
+        peer_mgr
+    });
+
+    let outbound_node_id = NodeId::from(rng.gen::<[u8; 32]>());
+    let outbound_addr: SocketAddress = TestAddressMaker::new_random_address(&mut rng).into();
+    let connect_result_receiver = start_manually_connecting(&peer_mgr_event_sender, outbound_addr);
+
+    let cmd = expect_recv!(cmd_receiver);
+    expect_cmd_connect_to(&cmd, &outbound_addr);
+
+    let peer_info = PeerInfo {
+        peer_node_id: Some(outbound_node_id),
+        ..make_full_relay_peer_info(PeerId::new(), &chain_config)
+    };
+    let peer_id = peer_info.peer_id;
+    conn_event_sender
+        .send(ConnectivityEvent::OutboundAccepted {
+            peer_address: outbound_addr,
+            bind_address: bind_addr,
+            peer_info,
+            node_address_as_seen_by_peer: None,
+        })
+        .unwrap();
+
+    let cmd = expect_recv!(cmd_receiver);
+    assert_eq!(cmd, Command::Accept { peer_id });
+    connect_result_receiver.await.unwrap().unwrap();
+
+    let inbound_node_id = NodeId::from(rng.gen::<[u8; 32]>());
+    let inbound_addr: SocketAddress = TestAddressMaker::new_random_address(&mut rng).into();
+    let peer_info = make_full_relay_peer_info(PeerId::new(), &chain_config);
+    let peer_id = peer_info.peer_id;
+    conn_event_sender
+        .send(ConnectivityEvent::InboundAccepted {
+            peer_address: inbound_addr,
+            bind_address: bind_addr,
+            peer_info,
+            node_address_as_seen_by_peer: None,
+        })
+        .unwrap();
+    let cmd = expect_recv!(cmd_receiver);
+    assert_eq!(cmd, Command::Accept { peer_id });
+    conn_event_sender
+        .send(ConnectivityEvent::PeerNodeIdReceived {
+            peer_id,
+            node_id: inbound_node_id,
+        })
+        .unwrap();
+
+    drop(conn_event_sender);
+    drop(peer_mgr_event_sender);
+    let peer_mgr = peer_mgr_join_handle.await.unwrap();
+
+    assert_eq!(
+        peer_mgr.peerdb.node_address(&outbound_node_id),
+        Some(outbound_addr)
+    );
+    assert_eq!(peer_mgr.peerdb.node_address(&inbound_node_id), None);
+}
@@ -47,12 +47,17 @@ fn validate_services() {
 
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
+            socks5_proxy_auth: Default::default(),
             disable_noise: Default::default(),
+            reserved_nodes_only: Default::default(),
+            enable_mempool_sync: Default::default(),
+            enable_port_mapping: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
             ban_config: Default::default(),
             outbound_connection_timeout: Default::default(),
+            inbound_connection_timeout: Default::default(),
             ping_check_period: Default::default(),
             ping_timeout: Default::default(),
             peer_handshake_timeout: Default::default(),
@@ -60,6 +65,13 @@ fn validate_services() {
             allow_discover_private_ips: Default::default(),
             user_agent: mintlayer_core_user_agent(),
             sync_stalling_timeout: Default::default(),
+            fork_alert_threshold: Default::default(),
+            fork_alert_duration: Default::default(),
+            max_mempool_tx_broadcasts_per_peer: Default::default(),
+            max_buffered_block_response_bytes: Default::default(),
+            max_in_flight_block_responses_per_peer: Default::default(),
+            max_low_work_blocks_in_flight: Default::default(),
+            peer_admission_config: Default::default(),
             peer_manager_config: Default::default(),
             protocol_config: Default::default(),
         });
@@ -105,6 +117,7 @@ fn validate_services() {
                     software_version: *chain_config.software_version(),
                     user_agent: mintlayer_core_user_agent(),
                     common_services: services,
+                    peer_node_id: None,
                 };
 
                 let res = pm.validate_connection(
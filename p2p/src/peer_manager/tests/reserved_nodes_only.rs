@@ -0,0 +1,170 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{net::SocketAddr, sync::Arc};
+
+use rstest::rstest;
+
+use common::{chain::config, primitives::user_agent::mintlayer_core_user_agent};
+use networking::test_helpers::{TestAddressMaker, TestTransportMaker, TestTransportTcp};
+use p2p_test_utils::{expect_no_recv, expect_recv};
+use p2p_types::socket_address::SocketAddress;
+use test_utils::{
+    random::{make_seedable_rng, Rng, Seed},
+    BasicTestTimeGetter,
+};
+use utils_networking::IpOrSocketAddress;
+
+use crate::{
+    config::P2pConfig,
+    disconnection_reason::DisconnectionReason,
+    message::{AnnounceAddrRequest, PeerManagerMessage},
+    net::{default_backend::types::Command, types::ConnectivityEvent},
+    peer_manager::tests::{
+        make_standalone_peer_manager,
+        utils::{expect_cmd_connect_to, inbound_full_relay_peer_accepted_by_backend},
+    },
+    test_helpers::test_peer_mgr_config_with_no_auto_outbound_connections,
+};
+
+// In the reserved-nodes-only mode, an inbound connection from a non-reserved address is closed
+// immediately, without banning or storing the address; a reserved node can connect from any port,
+// but the addresses it announces are not stored either.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn inbound_connection_from_non_reserved_address(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let reserved_addr: SocketAddress = TestAddressMaker::new_random_address(&mut rng).into();
+
+    let chain_config = Arc::new(config::create_unit_test_config());
+    let p2p_config = Arc::new(P2pConfig {
+        reserved_nodes_only: Some(true),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
+        reserved_nodes: vec![IpOrSocketAddress::new_socket_address(reserved_addr.socket_addr())],
+        allow_discover_private_ips: true.into(),
+        peer_manager_config: test_peer_mgr_config_with_no_auto_outbound_connections(),
+
+        bind_addresses: Default::default(),
+        socks5_proxy: Default::default(),
+        socks5_proxy_auth: Default::default(),
+        disable_noise: Default::default(),
+        boot_nodes: Default::default(),
+        whitelisted_addresses: Default::default(),
+        ban_config: Default::default(),
+        outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
+        ping_check_period: Default::default(),
+        ping_timeout: Default::default(),
+        peer_handshake_timeout: Default::default(),
+        max_clock_diff: Default::default(),
+        node_type: Default::default(),
+        user_agent: mintlayer_core_user_agent(),
+        sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
+        protocol_config: Default::default(),
+    });
+
+    let time_getter = BasicTestTimeGetter::new();
+    let bind_addr = TestTransportTcp::make_address().into();
+
+    let (peer_mgr, conn_event_sender, peer_mgr_event_sender, mut cmd_receiver, _) =
+        make_standalone_peer_manager(
+            Arc::clone(&chain_config),
+            Arc::clone(&p2p_config),
+            vec![bind_addr],
+            time_getter.get_time_getter(),
+        );
+
+    let peer_mgr_join_handle = logging::spawn_in_current_span(async move {
+        let mut peer_mgr = peer_mgr;
+        let _ = peer_mgr.run_internal(None).await;
+        peer_mgr
+    });
+
+    // The connection to the reserved node is still attempted.
+    let cmd = expect_recv!(cmd_receiver);
+    expect_cmd_connect_to(&cmd, &reserved_addr);
+
+    let non_reserved_addr: SocketAddress = TestAddressMaker::new_random_address(&mut rng).into();
+    let peer_id = inbound_full_relay_peer_accepted_by_backend(
+        &conn_event_sender,
+        non_reserved_addr,
+        bind_addr,
+        &chain_config,
+    );
+
+    let cmd = expect_recv!(cmd_receiver);
+    assert_eq!(
+        cmd,
+        Command::Disconnect {
+            peer_id,
+            reason: Some(DisconnectionReason::NotReservedNode)
+        }
+    );
+
+    // An inbound connection from the reserved node's ip comes from a different port.
+    let reserved_peer_addr =
+        SocketAddress::new(SocketAddr::new(reserved_addr.ip_addr(), rng.gen()));
+    let reserved_peer_id = inbound_full_relay_peer_accepted_by_backend(
+        &conn_event_sender,
+        reserved_peer_addr,
+        bind_addr,
+        &chain_config,
+    );
+
+    let cmd = expect_recv!(cmd_receiver);
+    assert_eq!(
+        cmd,
+        Command::Accept {
+            peer_id: reserved_peer_id
+        }
+    );
+
+    let announced_addr: SocketAddress = TestAddressMaker::new_random_address(&mut rng).into();
+    conn_event_sender
+        .send(ConnectivityEvent::Message {
+            peer_id: reserved_peer_id,
+            message: PeerManagerMessage::AnnounceAddrRequest(AnnounceAddrRequest {
+                address: announced_addr.as_peer_address(),
+            }),
+        })
+        .unwrap();
+
+    expect_no_recv!(cmd_receiver);
+
+    drop(conn_event_sender);
+    drop(peer_mgr_event_sender);
+
+    let peer_mgr = peer_mgr_join_handle.await.unwrap();
+
+    assert_eq!(
+        peer_mgr.peerdb.known_addresses().collect::<Vec<_>>(),
+        vec![&reserved_addr]
+    );
+    assert!(!peer_mgr
+        .peerdb
+        .is_address_banned_or_discouraged(&non_reserved_addr.as_bannable()));
+}
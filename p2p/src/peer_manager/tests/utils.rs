@@ -78,6 +78,7 @@ pub fn make_full_relay_peer_info(peer_id: PeerId, chain_config: &ChainConfig) ->
         software_version: *chain_config.software_version(),
         user_agent: mintlayer_core_user_agent(),
         common_services: NodeType::Full.into(),
+        peer_node_id: None,
     }
 }
 
@@ -89,6 +90,7 @@ pub fn make_block_relay_peer_info(peer_id: PeerId, chain_config: &ChainConfig) -
         software_version: *chain_config.software_version(),
         user_agent: mintlayer_core_user_agent(),
         common_services: [Service::Blocks].as_slice().into(),
+        peer_node_id: None,
     }
 }
 
@@ -285,6 +287,7 @@ pub async fn adjust_peer_score(
         .send(PeerManagerEvent::AdjustPeerScore(
             peer_id,
             score_adjustment,
+            "ProtocolError",
             result_sender,
         ))
         .unwrap();
@@ -15,17 +15,21 @@
 
 use std::time::Duration;
 
+use chainstate::PossibleFork;
 use common::{
-    chain::{Block, Transaction},
+    chain::{Block, GenBlock, Transaction},
     primitives::{time::Time, Id},
 };
 use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress};
 use utils_networking::IpOrSocketAddress;
 
 use crate::{
-    disconnection_reason::DisconnectionReason, interface::types::ConnectedPeer,
-    peer_manager::PeerManagerInterface, sync::sync_status::PeerBlockSyncStatus,
-    types::peer_id::PeerId, utils::oneshot_nofail,
+    disconnection_reason::DisconnectionReason,
+    interface::types::{BannedAddress, ConnectedPeer, MisbehaviorEvent},
+    peer_manager::PeerManagerInterface,
+    sync::sync_status::{PeerBlockSyncStatus, SyncStateSnapshot},
+    types::peer_id::PeerId,
+    utils::oneshot_nofail,
 };
 
 #[derive(Debug)]
@@ -60,10 +64,18 @@ pub enum PeerManagerEvent {
     /// Get peer IDs and addresses of connected peers
     GetConnectedPeers(oneshot_nofail::Sender<Vec<ConnectedPeer>>),
 
-    /// Increases the ban score of a peer by the given amount.
+    /// Get a point-in-time snapshot of the block sync state
+    GetSyncSnapshot(oneshot_nofail::Sender<SyncStateSnapshot>),
+
+    /// Increases the ban score of a peer by the given amount, for the given reason.
     ///
     /// The peer is discouraged if the new score exceeds the corresponding threshold.
-    AdjustPeerScore(PeerId, u32, oneshot_nofail::Sender<crate::Result<()>>),
+    AdjustPeerScore(
+        PeerId,
+        u32,
+        &'static str,
+        oneshot_nofail::Sender<crate::Result<()>>,
+    ),
 
     /// New tip block received.
     ///
@@ -96,11 +108,24 @@ pub enum PeerManagerEvent {
         new_status: PeerBlockSyncStatus,
     },
 
+    /// The peer has reported its best block.
+    ///
+    /// It is used to detect the situation when the node is on a minority fork.
+    /// `local_tip` is the node's own tip at the moment when the report was handled.
+    ReportedBestBlock {
+        peer_id: PeerId,
+        block_id: Id<Block>,
+        local_tip: Id<GenBlock>,
+    },
+
+    /// Get the currently detected possible fork, if any.
+    GetPossibleFork(oneshot_nofail::Sender<Option<PossibleFork>>),
+
     GetReserved(oneshot_nofail::Sender<Vec<SocketAddress>>),
     AddReserved(IpOrSocketAddress, oneshot_nofail::Sender<crate::Result<()>>),
     RemoveReserved(IpOrSocketAddress, oneshot_nofail::Sender<crate::Result<()>>),
 
-    ListBanned(oneshot_nofail::Sender<Vec<(BannableAddress, Time)>>),
+    ListBanned(oneshot_nofail::Sender<Vec<BannedAddress>>),
     Ban(
         BannableAddress,
         Duration,
@@ -110,11 +135,32 @@ pub enum PeerManagerEvent {
 
     ListDiscouraged(oneshot_nofail::Sender<Vec<(BannableAddress, Time)>>),
 
+    /// Get up to the given number of the most recent misbehavior events, optionally only
+    /// the ones of the given peer.
+    GetMisbehaviorLog(
+        usize,
+        Option<PeerId>,
+        oneshot_nofail::Sender<Vec<MisbehaviorEvent>>,
+    ),
+
+    /// Get the connected peers whose ban score is at least the given one.
+    GetMisbehavingPeers(
+        u32,
+        oneshot_nofail::Sender<Vec<(PeerId, SocketAddress, u32)>>,
+    ),
+
+    /// Get the ban score of the given peer, `None` if the peer is not connected.
+    GetPeerBanScore(PeerId, oneshot_nofail::Sender<Option<u32>>),
+
     EnableNetworking {
         enable: bool,
         response_sender: oneshot_nofail::Sender<crate::Result<()>>,
     },
 
+    /// The external address of this node on the local gateway has been obtained with port
+    /// mapping, or the mapping has been lost (`None`).
+    PortMappingAddressChanged(Option<SocketAddress>),
+
     GenericQuery(Box<dyn PeerManagerQueryFunc>),
     #[cfg(test)]
     GenericMut(Box<dyn PeerManagerMutFunc>),
@@ -0,0 +1,254 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Mapping of the p2p port on the local gateway (usually a home router) with NAT-PMP or UPnP,
+//! so that the node can accept inbound connections from behind a NAT.
+
+pub mod nat_pmp;
+pub mod upnp;
+
+#[cfg(test)]
+mod tests;
+
+use std::{net::SocketAddr, time::Duration};
+
+use async_trait::async_trait;
+use tokio::sync::{mpsc, oneshot};
+
+use common::{primitives::time::Time, time_getter::TimeGetter};
+use logging::log;
+use p2p_types::socket_address::SocketAddress;
+
+use crate::PeerManagerEvent;
+
+/// The lease duration requested from the gateway.
+const DEFAULT_LEASE_DURATION: Duration = Duration::from_secs(60 * 60);
+
+/// How long to wait before trying again after the gateway has failed to map the port.
+const DEFAULT_RETRY_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// How often to check whether the mapping should be renewed.
+const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The timeout of a single request to the gateway.
+const GATEWAY_REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum PortMappingError {
+    #[error("I/O error: {0}")]
+    Io(String),
+    #[error("No gateway found")]
+    GatewayNotFound,
+    #[error("The gateway did not respond in time")]
+    Timeout,
+    #[error("Invalid response from the gateway: {0}")]
+    InvalidResponse(String),
+    #[error("The gateway refused the request: {0}")]
+    Refused(String),
+    #[error("No port mapping protocol is supported by the gateway")]
+    NotSupported,
+}
+
+impl From<std::io::Error> for PortMappingError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e.to_string())
+    }
+}
+
+/// A port mapping created on the gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortMapping {
+    /// The address under which the mapped port is reachable from the outside.
+    pub external_address: SocketAddr,
+    /// The lease duration granted by the gateway, zero if the mapping doesn't expire.
+    pub lease_duration: Duration,
+}
+
+/// A gateway that can forward an external TCP port to a port of this host.
+#[async_trait]
+pub trait PortMappingGateway: Send {
+    /// Map the local TCP port on the gateway, or renew the existing mapping.
+    async fn map_port(
+        &mut self,
+        local_port: u16,
+        lease_duration: Duration,
+    ) -> Result<PortMapping, PortMappingError>;
+
+    /// Remove the mapping of the local TCP port.
+    async fn unmap_port(&mut self, local_port: u16) -> Result<(), PortMappingError>;
+}
+
+/// The gateway that tries NAT-PMP first and UPnP if that fails, remembering the protocol
+/// that has worked.
+#[derive(Default)]
+pub struct DefaultPortMappingGateway {
+    nat_pmp: nat_pmp::NatPmpGateway,
+    upnp: upnp::UpnpGateway,
+    use_upnp: Option<bool>,
+}
+
+#[async_trait]
+impl PortMappingGateway for DefaultPortMappingGateway {
+    async fn map_port(
+        &mut self,
+        local_port: u16,
+        lease_duration: Duration,
+    ) -> Result<PortMapping, PortMappingError> {
+        match self.use_upnp {
+            Some(false) => return self.nat_pmp.map_port(local_port, lease_duration).await,
+            Some(true) => return self.upnp.map_port(local_port, lease_duration).await,
+            None => {}
+        }
+
+        match self.nat_pmp.map_port(local_port, lease_duration).await {
+            Ok(mapping) => {
+                self.use_upnp = Some(false);
+                return Ok(mapping);
+            }
+            Err(e) => log::debug!("NAT-PMP port mapping failed: {e}"),
+        }
+        match self.upnp.map_port(local_port, lease_duration).await {
+            Ok(mapping) => {
+                self.use_upnp = Some(true);
+                Ok(mapping)
+            }
+            Err(e) => {
+                log::debug!("UPnP port mapping failed: {e}");
+                Err(PortMappingError::NotSupported)
+            }
+        }
+    }
+
+    async fn unmap_port(&mut self, local_port: u16) -> Result<(), PortMappingError> {
+        match self.use_upnp {
+            Some(false) => self.nat_pmp.unmap_port(local_port).await,
+            Some(true) => self.upnp.unmap_port(local_port).await,
+            None => Ok(()),
+        }
+    }
+}
+
+/// Timing settings of the port mapping task.
+#[derive(Debug, Clone, Copy)]
+pub struct PortMappingSettings {
+    /// The lease duration requested from the gateway.
+    pub lease_duration: Duration,
+    /// How long to wait before trying again after the gateway has failed to map the port.
+    pub retry_interval: Duration,
+    /// How often to check whether the mapping should be renewed.
+    pub check_interval: Duration,
+}
+
+impl Default for PortMappingSettings {
+    fn default() -> Self {
+        Self {
+            lease_duration: DEFAULT_LEASE_DURATION,
+            retry_interval: DEFAULT_RETRY_INTERVAL,
+            check_interval: DEFAULT_CHECK_INTERVAL,
+        }
+    }
+}
+
+/// Keep the local port mapped on the gateway until `shutdown_receiver` fires, then remove
+/// the mapping.
+///
+/// The mapping is renewed when half of its lease has passed. The external address is reported
+/// to the peer manager whenever it changes, and `None` is reported once the mapping expires
+/// without being renewed. Failures are only logged.
+pub async fn run_port_mapping<G: PortMappingGateway>(
+    mut gateway: G,
+    local_port: u16,
+    settings: PortMappingSettings,
+    time_getter: TimeGetter,
+    peer_mgr_event_sender: mpsc::UnboundedSender<PeerManagerEvent>,
+    mut shutdown_receiver: oneshot::Receiver<()>,
+) {
+    let report_address = |address: Option<SocketAddr>| {
+        // The peer manager may already be shut down, which is fine.
+        let _ = peer_mgr_event_sender.send(PeerManagerEvent::PortMappingAddressChanged(
+            address.map(SocketAddress::new),
+        ));
+    };
+
+    // The current mapping and the time when it expires (`None` if it doesn't)
+    let mut current_mapping: Option<(PortMapping, Option<Time>)> = None;
+    let mut next_attempt_time = time_getter.get_time();
+    let mut check_interval = tokio::time::interval(settings.check_interval);
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown_receiver => break,
+            _ = check_interval.tick() => {}
+        }
+
+        let now = time_getter.get_time();
+        if now < next_attempt_time {
+            continue;
+        }
+
+        // A request can involve several round trips to the gateway, so it shouldn't
+        // delay the shutdown.
+        let result = tokio::select! {
+            _ = &mut shutdown_receiver => break,
+            result = gateway.map_port(local_port, settings.lease_duration) => result,
+        };
+
+        match result {
+            Ok(mapping) => {
+                let previous_address = current_mapping.map(|(mapping, _)| mapping.external_address);
+                if previous_address != Some(mapping.external_address) {
+                    log::info!(
+                        "Port {local_port} is mapped to the external address {}",
+                        mapping.external_address
+                    );
+                    report_address(Some(mapping.external_address));
+                }
+
+                let renew_after = if mapping.lease_duration.is_zero() {
+                    settings.lease_duration / 2
+                } else {
+                    mapping.lease_duration / 2
+                };
+                let expiration_time = (!mapping.lease_duration.is_zero()).then(|| {
+                    (now + mapping.lease_duration)
+                        .expect("Time derived from local clock; cannot fail")
+                });
+                current_mapping = Some((mapping, expiration_time));
+                next_attempt_time =
+                    (now + renew_after).expect("Time derived from local clock; cannot fail");
+            }
+            Err(e) => {
+                log::warn!("Failed to map port {local_port} on the gateway: {e}");
+
+                let expired = current_mapping
+                    .is_some_and(|(_, expiration_time)| expiration_time.is_some_and(|t| t <= now));
+                if expired {
+                    log::info!("Port mapping has expired");
+                    current_mapping = None;
+                    report_address(None);
+                }
+                next_attempt_time = (now + settings.retry_interval)
+                    .expect("Time derived from local clock; cannot fail");
+            }
+        }
+    }
+
+    if current_mapping.is_some() {
+        match gateway.unmap_port(local_port).await {
+            Ok(()) => log::info!("Port mapping of port {local_port} removed"),
+            Err(e) => log::warn!("Failed to remove the mapping of port {local_port}: {e}"),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A minimal NAT-PMP client (RFC 6886).
+
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use tokio::net::UdpSocket;
+
+use super::{PortMapping, PortMappingError, PortMappingGateway, GATEWAY_REQUEST_TIMEOUT};
+
+const NAT_PMP_PORT: u16 = 5351;
+const NAT_PMP_VERSION: u8 = 0;
+
+const OPCODE_EXTERNAL_ADDRESS: u8 = 0;
+const OPCODE_MAP_TCP: u8 = 2;
+const RESPONSE_OPCODE_FLAG: u8 = 128;
+
+/// The initial retransmission interval, doubled after every attempt.
+const INITIAL_RETRANSMISSION_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Default)]
+pub struct NatPmpGateway {
+    /// The gateway address, detected on the first request.
+    gateway: Option<Ipv4Addr>,
+}
+
+impl NatPmpGateway {
+    pub fn new(gateway: Ipv4Addr) -> Self {
+        Self {
+            gateway: Some(gateway),
+        }
+    }
+
+    fn gateway(&mut self) -> Result<Ipv4Addr, PortMappingError> {
+        if self.gateway.is_none() {
+            self.gateway = default_gateway();
+        }
+        self.gateway.ok_or(PortMappingError::GatewayNotFound)
+    }
+
+    async fn request(
+        &mut self,
+        request: &[u8],
+        response_len: usize,
+    ) -> Result<Vec<u8>, PortMappingError> {
+        let gateway = SocketAddr::new(self.gateway()?.into(), NAT_PMP_PORT);
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+        socket.connect(gateway).await?;
+
+        let mut buf = [0; 16];
+        let mut retransmission_interval = INITIAL_RETRANSMISSION_INTERVAL;
+        let deadline = tokio::time::Instant::now() + GATEWAY_REQUEST_TIMEOUT;
+        while tokio::time::Instant::now() < deadline {
+            socket.send(request).await?;
+            match tokio::time::timeout(retransmission_interval, socket.recv(&mut buf)).await {
+                Ok(received) => {
+                    let received = received?;
+                    return parse_response(&buf[..received], request[1], response_len);
+                }
+                Err(_) => retransmission_interval *= 2,
+            }
+        }
+
+        Err(PortMappingError::Timeout)
+    }
+}
+
+fn parse_response(
+    response: &[u8],
+    request_opcode: u8,
+    response_len: usize,
+) -> Result<Vec<u8>, PortMappingError> {
+    if response.len() < response_len {
+        return Err(PortMappingError::InvalidResponse(format!(
+            "unexpected length {}",
+            response.len()
+        )));
+    }
+    if response[0] != NAT_PMP_VERSION || response[1] != request_opcode | RESPONSE_OPCODE_FLAG {
+        return Err(PortMappingError::InvalidResponse(format!(
+            "unexpected header {:?}",
+            &response[..2]
+        )));
+    }
+    let result_code = u16::from_be_bytes([response[2], response[3]]);
+    if result_code != 0 {
+        return Err(PortMappingError::Refused(format!(
+            "result code {result_code}"
+        )));
+    }
+    Ok(response[..response_len].to_vec())
+}
+
+fn map_request(local_port: u16, external_port: u16, lifetime: Duration) -> [u8; 12] {
+    let mut request = [0; 12];
+    request[0] = NAT_PMP_VERSION;
+    request[1] = OPCODE_MAP_TCP;
+    request[4..6].copy_from_slice(&local_port.to_be_bytes());
+    request[6..8].copy_from_slice(&external_port.to_be_bytes());
+    let lifetime = u32::try_from(lifetime.as_secs()).unwrap_or(u32::MAX);
+    request[8..12].copy_from_slice(&lifetime.to_be_bytes());
+    request
+}
+
+#[async_trait]
+impl PortMappingGateway for NatPmpGateway {
+    async fn map_port(
+        &mut self,
+        local_port: u16,
+        lease_duration: Duration,
+    ) -> Result<PortMapping, PortMappingError> {
+        let response =
+            self.request(&map_request(local_port, local_port, lease_duration), 16).await?;
+        let external_port = u16::from_be_bytes([response[10], response[11]]);
+        let lifetime = u32::from_be_bytes([response[12], response[13], response[14], response[15]]);
+
+        let response = self.request(&[NAT_PMP_VERSION, OPCODE_EXTERNAL_ADDRESS], 12).await?;
+        let external_ip = Ipv4Addr::new(response[8], response[9], response[10], response[11]);
+
+        Ok(PortMapping {
+            external_address: SocketAddr::new(external_ip.into(), external_port),
+            lease_duration: Duration::from_secs(lifetime.into()),
+        })
+    }
+
+    async fn unmap_port(&mut self, local_port: u16) -> Result<(), PortMappingError> {
+        // A mapping is deleted by requesting a zero lifetime and external port
+        self.request(&map_request(local_port, 0, Duration::ZERO), 16).await?;
+        Ok(())
+    }
+}
+
+/// The default IPv4 gateway of this host, if it can be determined.
+#[cfg(target_os = "linux")]
+fn default_gateway() -> Option<Ipv4Addr> {
+    // The routes are listed with the addresses in hex, in the host byte order
+    let routes = std::fs::read_to_string("/proc/net/route").ok()?;
+    routes.lines().skip(1).find_map(|line| {
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+        let (destination, gateway) = (fields.get(1)?, fields.get(2)?);
+        if *destination != "00000000" {
+            return None;
+        }
+        let gateway = u32::from_str_radix(gateway, 16).ok()?;
+        Some(Ipv4Addr::from(gateway.to_ne_bytes()))
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn default_gateway() -> Option<Ipv4Addr> {
+    None
+}
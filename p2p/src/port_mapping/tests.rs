@@ -0,0 +1,281 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
+
+use p2p_test_utils::{expect_future_val, expect_no_recv, expect_recv};
+use test_utils::{assert_matches, BasicTestTimeGetter};
+
+use super::*;
+
+const LOCAL_PORT: u16 = 3031;
+
+#[derive(Debug, PartialEq, Eq)]
+enum GatewayCall {
+    MapPort(u16, Duration),
+    UnmapPort(u16),
+}
+
+/// A gateway that records the calls and returns the configured result from `map_port`.
+struct MockGateway {
+    calls_sender: mpsc::UnboundedSender<GatewayCall>,
+    map_port_result: Arc<Mutex<Result<PortMapping, PortMappingError>>>,
+}
+
+#[async_trait]
+impl PortMappingGateway for MockGateway {
+    async fn map_port(
+        &mut self,
+        local_port: u16,
+        lease_duration: Duration,
+    ) -> Result<PortMapping, PortMappingError> {
+        self.calls_sender
+            .send(GatewayCall::MapPort(local_port, lease_duration))
+            .unwrap();
+        self.map_port_result.lock().unwrap().clone()
+    }
+
+    async fn unmap_port(&mut self, local_port: u16) -> Result<(), PortMappingError> {
+        self.calls_sender.send(GatewayCall::UnmapPort(local_port)).unwrap();
+        Ok(())
+    }
+}
+
+struct TestPortMapping {
+    time_getter: BasicTestTimeGetter,
+    settings: PortMappingSettings,
+    map_port_result: Arc<Mutex<Result<PortMapping, PortMappingError>>>,
+    calls_receiver: mpsc::UnboundedReceiver<GatewayCall>,
+    peer_mgr_event_receiver: mpsc::UnboundedReceiver<PeerManagerEvent>,
+    shutdown_sender: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+impl TestPortMapping {
+    fn start(map_port_result: Result<PortMapping, PortMappingError>) -> Self {
+        let time_getter = BasicTestTimeGetter::new();
+        let settings = PortMappingSettings {
+            lease_duration: Duration::from_secs(60 * 60),
+            retry_interval: Duration::from_secs(5 * 60),
+            check_interval: Duration::from_millis(10),
+        };
+        let map_port_result = Arc::new(Mutex::new(map_port_result));
+        let (calls_sender, calls_receiver) = mpsc::unbounded_channel();
+        let (peer_mgr_event_sender, peer_mgr_event_receiver) = mpsc::unbounded_channel();
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel();
+
+        let gateway = MockGateway {
+            calls_sender,
+            map_port_result: Arc::clone(&map_port_result),
+        };
+        let task = logging::spawn_in_current_span(run_port_mapping(
+            gateway,
+            LOCAL_PORT,
+            settings,
+            time_getter.get_time_getter(),
+            peer_mgr_event_sender,
+            shutdown_receiver,
+        ));
+
+        Self {
+            time_getter,
+            settings,
+            map_port_result,
+            calls_receiver,
+            peer_mgr_event_receiver,
+            shutdown_sender,
+            task,
+        }
+    }
+
+    fn set_map_port_result(&self, result: Result<PortMapping, PortMappingError>) {
+        *self.map_port_result.lock().unwrap() = result;
+    }
+
+    async fn expect_map_port(&mut self) {
+        assert_eq!(
+            expect_recv!(self.calls_receiver),
+            GatewayCall::MapPort(LOCAL_PORT, self.settings.lease_duration)
+        );
+    }
+
+    async fn expect_address(&mut self, expected: Option<SocketAddr>) {
+        let event = expect_recv!(self.peer_mgr_event_receiver);
+        assert_matches!(
+            &event,
+            PeerManagerEvent::PortMappingAddressChanged(address)
+                if *address == expected.map(SocketAddress::new)
+        );
+    }
+
+    async fn shutdown(self) -> mpsc::UnboundedReceiver<GatewayCall> {
+        self.shutdown_sender.send(()).unwrap();
+        expect_future_val!(self.task).unwrap();
+        self.calls_receiver
+    }
+}
+
+fn make_mapping(external_address: &str, lease_duration: Duration) -> PortMapping {
+    PortMapping {
+        external_address: external_address.parse().unwrap(),
+        lease_duration,
+    }
+}
+
+// The port is mapped on start, the mapping is renewed after half of the lease and removed on
+// shutdown.
+#[tracing::instrument]
+#[tokio::test]
+async fn mapping_lifecycle() {
+    let lease_duration = Duration::from_secs(20 * 60);
+    let mapping = make_mapping("5.6.7.8:4000", lease_duration);
+    let mut test = TestPortMapping::start(Ok(mapping));
+
+    test.expect_map_port().await;
+    test.expect_address(Some(mapping.external_address)).await;
+
+    // The lease granted by the gateway is used, not the requested one
+    test.time_getter.advance_time(lease_duration / 2 - Duration::from_secs(1));
+    expect_no_recv!(test.calls_receiver);
+
+    test.time_getter.advance_time(Duration::from_secs(1));
+    test.expect_map_port().await;
+    // The address hasn't changed, so it isn't reported again
+    expect_no_recv!(test.peer_mgr_event_receiver);
+
+    // A changed address is reported after the renewal
+    let new_mapping = make_mapping("5.6.7.9:4001", lease_duration);
+    test.set_map_port_result(Ok(new_mapping));
+    test.time_getter.advance_time(lease_duration / 2);
+    test.expect_map_port().await;
+    test.expect_address(Some(new_mapping.external_address)).await;
+
+    let mut calls_receiver = test.shutdown().await;
+    assert_eq!(
+        calls_receiver.try_recv().unwrap(),
+        GatewayCall::UnmapPort(LOCAL_PORT)
+    );
+}
+
+// A mapping without a lease is still renewed periodically, in case the gateway has restarted.
+#[tracing::instrument]
+#[tokio::test]
+async fn mapping_without_lease() {
+    let mapping = make_mapping("5.6.7.8:4000", Duration::ZERO);
+    let mut test = TestPortMapping::start(Ok(mapping));
+
+    test.expect_map_port().await;
+    test.expect_address(Some(mapping.external_address)).await;
+
+    test.time_getter.advance_time(test.settings.lease_duration / 2);
+    test.expect_map_port().await;
+    expect_no_recv!(test.peer_mgr_event_receiver);
+
+    // Failures never expire the mapping
+    test.set_map_port_result(Err(PortMappingError::Timeout));
+    test.time_getter.advance_time(test.settings.lease_duration * 10);
+    test.expect_map_port().await;
+    expect_no_recv!(test.peer_mgr_event_receiver);
+
+    test.shutdown().await;
+}
+
+// Failures are retried after the retry interval, and the address is withdrawn once the lease
+// has expired.
+#[tracing::instrument]
+#[tokio::test]
+async fn mapping_failures() {
+    let lease_duration = Duration::from_secs(20 * 60);
+    let mut test = TestPortMapping::start(Err(PortMappingError::GatewayNotFound));
+
+    test.expect_map_port().await;
+    expect_no_recv!(test.peer_mgr_event_receiver);
+
+    test.time_getter
+        .advance_time(test.settings.retry_interval - Duration::from_secs(1));
+    expect_no_recv!(test.calls_receiver);
+
+    let mapping = make_mapping("5.6.7.8:4000", lease_duration);
+    test.set_map_port_result(Ok(mapping));
+    test.time_getter.advance_time(Duration::from_secs(1));
+    test.expect_map_port().await;
+    test.expect_address(Some(mapping.external_address)).await;
+
+    // The renewal fails, but the mapping is still valid
+    test.set_map_port_result(Err(PortMappingError::Timeout));
+    test.time_getter.advance_time(lease_duration / 2);
+    test.expect_map_port().await;
+    expect_no_recv!(test.peer_mgr_event_receiver);
+
+    // The next attempt also fails, after the mapping has expired
+    test.time_getter.advance_time(lease_duration / 2);
+    test.expect_map_port().await;
+    test.expect_address(None).await;
+
+    // The expired mapping isn't removed on shutdown
+    let mut calls_receiver = test.shutdown().await;
+    assert_eq!(
+        calls_receiver.try_recv(),
+        Err(mpsc::error::TryRecvError::Disconnected)
+    );
+}
+
+// The task stops promptly even if the gateway doesn't respond.
+#[tracing::instrument]
+#[tokio::test]
+async fn shutdown_during_request() {
+    struct UnresponsiveGateway;
+
+    #[async_trait]
+    impl PortMappingGateway for UnresponsiveGateway {
+        async fn map_port(
+            &mut self,
+            _local_port: u16,
+            _lease_duration: Duration,
+        ) -> Result<PortMapping, PortMappingError> {
+            std::future::pending().await
+        }
+
+        async fn unmap_port(&mut self, _local_port: u16) -> Result<(), PortMappingError> {
+            unreachable!("nothing was mapped")
+        }
+    }
+
+    let time_getter = BasicTestTimeGetter::new();
+    let (peer_mgr_event_sender, mut peer_mgr_event_receiver) = mpsc::unbounded_channel();
+    let (shutdown_sender, shutdown_receiver) = oneshot::channel();
+    let task = logging::spawn_in_current_span(run_port_mapping(
+        UnresponsiveGateway,
+        LOCAL_PORT,
+        PortMappingSettings::default(),
+        time_getter.get_time_getter(),
+        peer_mgr_event_sender,
+        shutdown_receiver,
+    ));
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    shutdown_sender.send(()).unwrap();
+    expect_future_val!(task).unwrap();
+    assert_matches!(peer_mgr_event_receiver.try_recv(), Err(_));
+}
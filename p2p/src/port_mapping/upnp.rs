@@ -0,0 +1,371 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A minimal UPnP Internet Gateway Device client: SSDP discovery and the WANIPConnection
+//! port mapping actions over plain HTTP.
+
+use std::{
+    future::Future,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpStream, UdpSocket},
+};
+
+use super::{PortMapping, PortMappingError, PortMappingGateway, GATEWAY_REQUEST_TIMEOUT};
+
+const SSDP_ADDRESS: SocketAddr =
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(239, 255, 255, 250)), 1900);
+const SEARCH_TARGET: &str = "urn:schemas-upnp-org:device:InternetGatewayDevice:1";
+const PORT_MAPPING_SERVICES: [&str; 2] = ["WANIPConnection", "WANPPPConnection"];
+const PORT_MAPPING_DESCRIPTION: &str = "Mintlayer node";
+
+/// The error code returned by the gateways that don't support leases other than zero.
+const ONLY_PERMANENT_LEASES_SUPPORTED: &str = "725";
+
+/// The maximum size of a response accepted from the gateway.
+const MAX_RESPONSE_SIZE: u64 = 1024 * 1024;
+
+/// The port mapping service of a discovered gateway.
+#[derive(Debug, Clone)]
+struct ControlPoint {
+    address: SocketAddr,
+    path: String,
+    service_type: String,
+}
+
+#[derive(Default)]
+pub struct UpnpGateway {
+    control_point: Option<ControlPoint>,
+}
+
+async fn with_timeout<T>(
+    future: impl Future<Output = Result<T, PortMappingError>>,
+) -> Result<T, PortMappingError> {
+    tokio::time::timeout(GATEWAY_REQUEST_TIMEOUT, future)
+        .await
+        .map_err(|_| PortMappingError::Timeout)?
+}
+
+/// Find the gateway with SSDP and return the location of its device description.
+async fn discover_gateway() -> Result<String, PortMappingError> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    let request = format!(
+        "M-SEARCH * HTTP/1.1\r\n\
+        HOST: {SSDP_ADDRESS}\r\n\
+        ST: {SEARCH_TARGET}\r\n\
+        MAN: \"ssdp:discover\"\r\n\
+        MX: 2\r\n\r\n"
+    );
+    socket.send_to(request.as_bytes(), SSDP_ADDRESS).await?;
+
+    let mut buf = [0; 2048];
+    loop {
+        let (received, _) = socket.recv_from(&mut buf).await?;
+        let response = String::from_utf8_lossy(&buf[..received]);
+        if let Some(location) = header_value(&response, "location") {
+            return Ok(location.to_owned());
+        }
+    }
+}
+
+fn header_value<'a>(response: &'a str, name: &str) -> Option<&'a str> {
+    response.lines().find_map(|line| {
+        let (header, value) = line.split_once(':')?;
+        header.trim().eq_ignore_ascii_case(name).then_some(value.trim())
+    })
+}
+
+/// Split an `http://<ip>:<port>/<path>` URL into the address and the path.
+fn parse_url(url: &str) -> Result<(SocketAddr, String), PortMappingError> {
+    let invalid_url = || PortMappingError::InvalidResponse(format!("unsupported URL {url}"));
+    let rest = url.strip_prefix("http://").ok_or_else(invalid_url)?;
+    let (host, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let address = match host.parse::<SocketAddr>() {
+        Ok(address) => address,
+        Err(_) => SocketAddr::new(host.parse::<IpAddr>().map_err(|_| invalid_url())?, 80),
+    };
+    let path = if path.is_empty() { "/" } else { path };
+    Ok((address, path.to_owned()))
+}
+
+/// The text of the first element with the given name.
+fn xml_element<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{name}>"))? + name.len() + 2;
+    let end = start + xml[start..].find(&format!("</{name}>"))?;
+    Some(xml[start..end].trim())
+}
+
+/// Find the port mapping service in the device description.
+fn find_control_point(
+    description: &str,
+    description_address: SocketAddr,
+) -> Result<ControlPoint, PortMappingError> {
+    description
+        .split("<service>")
+        .skip(1)
+        .find_map(|service| {
+            let service_type = xml_element(service, "serviceType")?;
+            if !PORT_MAPPING_SERVICES.iter().any(|name| service_type.contains(name)) {
+                return None;
+            }
+            let control_url = xml_element(service, "controlURL")?;
+            let (address, path) = if control_url.starts_with("http://") {
+                parse_url(control_url).ok()?
+            } else if control_url.starts_with('/') {
+                (description_address, control_url.to_owned())
+            } else {
+                (description_address, format!("/{control_url}"))
+            };
+            Some(ControlPoint {
+                address,
+                path,
+                service_type: service_type.to_owned(),
+            })
+        })
+        .ok_or(PortMappingError::NotSupported)
+}
+
+/// Make an HTTP/1.0 request and return the status code and the body of the response.
+async fn http_request(
+    address: SocketAddr,
+    head: String,
+    body: &str,
+) -> Result<(u16, String), PortMappingError> {
+    let mut stream = TcpStream::connect(address).await?;
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+
+    let mut response = Vec::new();
+    (&mut stream).take(MAX_RESPONSE_SIZE).read_to_end(&mut response).await?;
+    let response = String::from_utf8_lossy(&response);
+
+    let (headers, body) = response.split_once("\r\n\r\n").unwrap_or((&response, ""));
+    let status = headers
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(|| PortMappingError::InvalidResponse("missing HTTP status".to_owned()))?;
+    Ok((status, body.to_owned()))
+}
+
+async fn soap_request(
+    control_point: &ControlPoint,
+    action: &str,
+    arguments: &[(&str, String)],
+) -> Result<String, PortMappingError> {
+    let ControlPoint {
+        address,
+        path,
+        service_type,
+    } = control_point;
+    let arguments = arguments
+        .iter()
+        .map(|(name, value)| format!("<{name}>{value}</{name}>"))
+        .collect::<String>();
+    let body = format!(
+        "<?xml version=\"1.0\"?>\
+        <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+        s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+        <s:Body><u:{action} xmlns:u=\"{service_type}\">{arguments}</u:{action}></s:Body>\
+        </s:Envelope>"
+    );
+    let head = format!(
+        "POST {path} HTTP/1.0\r\n\
+        Host: {address}\r\n\
+        Content-Type: text/xml; charset=\"utf-8\"\r\n\
+        SOAPAction: \"{service_type}#{action}\"\r\n\
+        Content-Length: {}\r\n\r\n",
+        body.len()
+    );
+
+    let (status, response) = http_request(*address, head, &body).await?;
+    if status != 200 {
+        let error_code = xml_element(&response, "errorCode").unwrap_or("unknown");
+        return Err(PortMappingError::Refused(error_code.to_owned()));
+    }
+    Ok(response)
+}
+
+impl UpnpGateway {
+    async fn control_point(&mut self) -> Result<ControlPoint, PortMappingError> {
+        if let Some(control_point) = &self.control_point {
+            return Ok(control_point.clone());
+        }
+
+        let location = with_timeout(discover_gateway()).await?;
+        let (address, path) = parse_url(&location)?;
+        let head = format!("GET {path} HTTP/1.0\r\nHost: {address}\r\n\r\n");
+        let (_, description) = with_timeout(http_request(address, head, "")).await?;
+        let control_point = find_control_point(&description, address)?;
+
+        self.control_point = Some(control_point.clone());
+        Ok(control_point)
+    }
+
+    async fn add_port_mapping(
+        control_point: &ControlPoint,
+        local_port: u16,
+        lease_duration: Duration,
+    ) -> Result<Duration, PortMappingError> {
+        // The internal client is the address this host uses to reach the gateway
+        let local_ip = TcpStream::connect(control_point.address).await?.local_addr()?.ip();
+        let arguments = |lease_duration: Duration| {
+            [
+                ("NewRemoteHost", String::new()),
+                ("NewExternalPort", local_port.to_string()),
+                ("NewProtocol", "TCP".to_owned()),
+                ("NewInternalPort", local_port.to_string()),
+                ("NewInternalClient", local_ip.to_string()),
+                ("NewEnabled", "1".to_owned()),
+                (
+                    "NewPortMappingDescription",
+                    PORT_MAPPING_DESCRIPTION.to_owned(),
+                ),
+                ("NewLeaseDuration", lease_duration.as_secs().to_string()),
+            ]
+        };
+
+        match soap_request(control_point, "AddPortMapping", &arguments(lease_duration)).await {
+            Ok(_) => Ok(lease_duration),
+            Err(PortMappingError::Refused(code)) if code == ONLY_PERMANENT_LEASES_SUPPORTED => {
+                soap_request(control_point, "AddPortMapping", &arguments(Duration::ZERO)).await?;
+                Ok(Duration::ZERO)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn try_map_port(
+        &mut self,
+        local_port: u16,
+        lease_duration: Duration,
+    ) -> Result<PortMapping, PortMappingError> {
+        let control_point = self.control_point().await?;
+        let lease_duration = with_timeout(Self::add_port_mapping(
+            &control_point,
+            local_port,
+            lease_duration,
+        ))
+        .await?;
+
+        let response =
+            with_timeout(soap_request(&control_point, "GetExternalIPAddress", &[])).await?;
+        let external_ip = xml_element(&response, "NewExternalIPAddress")
+            .and_then(|ip| ip.parse::<IpAddr>().ok())
+            .ok_or_else(|| {
+                PortMappingError::InvalidResponse("missing external IP address".to_owned())
+            })?;
+
+        Ok(PortMapping {
+            external_address: SocketAddr::new(external_ip, local_port),
+            lease_duration,
+        })
+    }
+}
+
+#[async_trait]
+impl PortMappingGateway for UpnpGateway {
+    async fn map_port(
+        &mut self,
+        local_port: u16,
+        lease_duration: Duration,
+    ) -> Result<PortMapping, PortMappingError> {
+        let result = self.try_map_port(local_port, lease_duration).await;
+        if result.is_err() {
+            // The gateway may have changed, discover it again on the next attempt
+            self.control_point = None;
+        }
+        result
+    }
+
+    async fn unmap_port(&mut self, local_port: u16) -> Result<(), PortMappingError> {
+        let control_point = self.control_point().await?;
+        let arguments = [
+            ("NewRemoteHost", String::new()),
+            ("NewExternalPort", local_port.to_string()),
+            ("NewProtocol", "TCP".to_owned()),
+        ];
+        with_timeout(soap_request(
+            &control_point,
+            "DeletePortMapping",
+            &arguments,
+        ))
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_gateway_description() {
+        let description = r#"<?xml version="1.0"?>
+            <root xmlns="urn:schemas-upnp-org:device-1-0">
+            <device><serviceList>
+            <service>
+                <serviceType>urn:schemas-upnp-org:service:Layer3Forwarding:1</serviceType>
+                <controlURL>/ctl/L3F</controlURL>
+            </service>
+            <service>
+                <serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>
+                <controlURL>/ctl/IPConn</controlURL>
+            </service>
+            </serviceList></device></root>"#;
+        let address: SocketAddr = "192.168.1.1:5000".parse().unwrap();
+
+        let control_point = find_control_point(description, address).unwrap();
+        assert_eq!(control_point.address, address);
+        assert_eq!(control_point.path, "/ctl/IPConn");
+        assert_eq!(
+            control_point.service_type,
+            "urn:schemas-upnp-org:service:WANIPConnection:1"
+        );
+
+        assert_eq!(
+            find_control_point("<root></root>", address).unwrap_err(),
+            PortMappingError::NotSupported
+        );
+    }
+
+    #[test]
+    fn parse_location() {
+        assert_eq!(
+            parse_url("http://192.168.1.1:5000/rootDesc.xml").unwrap(),
+            (
+                "192.168.1.1:5000".parse().unwrap(),
+                "/rootDesc.xml".to_owned()
+            )
+        );
+        assert_eq!(
+            parse_url("http://10.0.0.1").unwrap(),
+            ("10.0.0.1:80".parse().unwrap(), "/".to_owned())
+        );
+        assert!(parse_url("https://10.0.0.1/desc.xml").is_err());
+
+        let response = "HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=120\r\n\
+            Location: http://192.168.1.1:5000/rootDesc.xml\r\n\r\n";
+        assert_eq!(
+            header_value(response, "location"),
+            Some("http://192.168.1.1:5000/rootDesc.xml")
+        );
+    }
+}
@@ -13,6 +13,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Duration;
+
 use enum_iterator::Sequence;
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
@@ -43,6 +45,10 @@ impl ProtocolVersion {
 pub enum SupportedProtocolVersion {
     V2 = 2,
     V3 = 3,
+    V4 = 4,
+    V5 = 5,
+    V6 = 6,
+    V7 = 7,
 }
 
 lazy_static::lazy_static! {
@@ -81,6 +87,18 @@ pub fn choose_common_protocol_version(
     min_version.into()
 }
 
+/// Return true if the initial mempool sync messages (`GetMempool` and `MempoolResponse`)
+/// can be exchanged with a peer with the specified protocol version.
+pub fn can_sync_mempool(protocol_version: SupportedProtocolVersion) -> bool {
+    protocol_version >= SupportedProtocolVersion::V6
+}
+
+/// Return true if individual block headers can be requested by their ids (via the
+/// `BlockHeaderRequest` message) from a peer with the specified protocol version.
+pub fn can_request_block_headers(protocol_version: SupportedProtocolVersion) -> bool {
+    protocol_version >= SupportedProtocolVersion::V7
+}
+
 make_config_setting!(HeaderLimit, usize, 2000);
 make_config_setting!(MaxLocatorSize, usize, 101);
 make_config_setting!(RequestedBlocksLimit, usize, 500);
@@ -88,6 +106,18 @@ make_config_setting!(MaxMessageSize, usize, 10 * 1024 * 1024);
 make_config_setting!(MaxPeerTxAnnouncements, usize, 5000);
 make_config_setting!(MaxUnconnectedHeaders, usize, 10);
 make_config_setting!(MaxAddrListResponseAddressCount, usize, 1000);
+make_config_setting!(MaxBlockResponseMessageSize, usize, 4 * 1024 * 1024);
+make_config_setting!(MaxHeaderListMessageSize, usize, 3 * 1024 * 1024);
+make_config_setting!(MaxAddrListResponseMessageSize, usize, 64 * 1024);
+make_config_setting!(MaxTransactionResponseMessageSize, usize, 2 * 1024 * 1024);
+make_config_setting!(MaxMempoolSyncTxs, u32, 1000);
+make_config_setting!(MaxRepeatedHeadersServed, usize, 20000);
+make_config_setting!(
+    RepeatedHeadersWindow,
+    Duration,
+    Duration::from_secs(10 * 60)
+);
+make_config_setting!(MaxThrottledHeaderResponses, usize, 5);
 
 /// Protocol configuration. These values are supposed to be modified in tests only.
 ///
@@ -115,4 +145,88 @@ pub struct ProtocolConfig {
     pub max_message_size: MaxMessageSize,
     /// The maximum number of announcements (hashes) for which we haven't receive transactions.
     pub max_peer_tx_announcements: MaxPeerTxAnnouncements,
+    /// The maximum size of a block response message in bytes.
+    pub max_block_response_message_size: MaxBlockResponseMessageSize,
+    /// The maximum size of a header list message in bytes.
+    pub max_header_list_message_size: MaxHeaderListMessageSize,
+    /// The maximum size of an address list response message in bytes.
+    pub max_addr_list_response_message_size: MaxAddrListResponseMessageSize,
+    /// The maximum size of a transaction response message in bytes.
+    pub max_transaction_response_message_size: MaxTransactionResponseMessageSize,
+    /// The maximum number of transaction ids to request from or send to a peer during
+    /// the initial mempool sync.
+    pub max_mempool_sync_txs: MaxMempoolSyncTxs,
+    /// The maximum number of headers that can be sent to a peer again, after they have already
+    /// been sent to it, within `repeated_headers_window`. Requesting blocks resets the count.
+    /// Once the limit is exceeded, the header responses to the peer are throttled.
+    pub max_repeated_headers_served: MaxRepeatedHeadersServed,
+    /// The time window in which the repeatedly sent headers are counted.
+    pub repeated_headers_window: RepeatedHeadersWindow,
+    /// The maximum number of throttled header responses; further header requests without
+    /// requesting any blocks are treated as misbehavior.
+    pub max_throttled_header_responses: MaxThrottledHeaderResponses,
+}
+
+/// The types of messages that have their own size limits, which are stricter than `max_message_size`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Sequence)]
+pub enum SizeLimitedMessageType {
+    BlockResponse,
+    HeaderList,
+    AddrListResponse,
+    TransactionResponse,
+}
+
+impl std::fmt::Display for SizeLimitedMessageType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SizeLimitedMessageType::BlockResponse => "BlockResponse",
+            SizeLimitedMessageType::HeaderList => "HeaderList",
+            SizeLimitedMessageType::AddrListResponse => "AddrListResponse",
+            SizeLimitedMessageType::TransactionResponse => "TransactionResponse",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The maximum sizes of the messages of specific types that a node accepts.
+///
+/// Since protocol V4 the nodes advertise their limits during the handshake, so that
+/// a well-behaved peer never sends a message that will be rejected; with older peers
+/// the default limits are assumed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct MessageSizeLimits {
+    #[codec(compact)]
+    pub block_response: u64,
+    #[codec(compact)]
+    pub header_list: u64,
+    #[codec(compact)]
+    pub addr_list_response: u64,
+    #[codec(compact)]
+    pub transaction_response: u64,
+}
+
+impl MessageSizeLimits {
+    pub fn from_config(protocol_config: &ProtocolConfig) -> Self {
+        Self {
+            block_response: *protocol_config.max_block_response_message_size as u64,
+            header_list: *protocol_config.max_header_list_message_size as u64,
+            addr_list_response: *protocol_config.max_addr_list_response_message_size as u64,
+            transaction_response: *protocol_config.max_transaction_response_message_size as u64,
+        }
+    }
+
+    /// The limits that are assumed for peers that don't advertise their own ones.
+    pub fn protocol_defaults() -> Self {
+        Self::from_config(&ProtocolConfig::default())
+    }
+
+    pub fn get(&self, message_type: SizeLimitedMessageType) -> usize {
+        let limit = match message_type {
+            SizeLimitedMessageType::BlockResponse => self.block_response,
+            SizeLimitedMessageType::HeaderList => self.header_list,
+            SizeLimitedMessageType::AddrListResponse => self.addr_list_response,
+            SizeLimitedMessageType::TransactionResponse => self.transaction_response,
+        };
+        usize::try_from(limit).unwrap_or(usize::MAX)
+    }
 }
@@ -15,13 +15,18 @@
 
 use std::time::Duration;
 
+use chainstate::PossibleFork;
 use common::{chain::SignedTransaction, primitives::time::Time};
 use mempool::tx_options::TxOptionsOverrides;
-use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress};
-use serialization::hex_encoded::HexEncoded;
+use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress, NodeId};
+use serialization::hex_encoded::StrictHexEncoded;
 use utils_networking::IpOrSocketAddress;
 
-use crate::{interface::types::ConnectedPeer, types::peer_id::PeerId};
+use crate::{
+    interface::types::{BannedAddress, ConnectedPeer, MisbehaviorEvent},
+    sync::sync_status::SyncStateSnapshot,
+    types::peer_id::PeerId,
+};
 use rpc::RpcResult;
 
 #[rpc::describe]
@@ -42,9 +47,9 @@ trait P2pRpc {
     #[method(name = "disconnect")]
     async fn disconnect(&self, peer_id: PeerId) -> RpcResult<()>;
 
-    /// List banned peers and their ban expiry time.
+    /// List banned peers, their ban expiry time and their most recent misbehavior.
     #[method(name = "list_banned")]
-    async fn list_banned(&self) -> RpcResult<Vec<(BannableAddress, Time)>>;
+    async fn list_banned(&self) -> RpcResult<Vec<BannedAddress>>;
 
     /// Ban a peer by their address for a given amount of time.
     #[method(name = "ban")]
@@ -60,6 +65,17 @@ trait P2pRpc {
     #[method(name = "list_discouraged")]
     async fn list_discouraged(&self) -> RpcResult<Vec<(BannableAddress, Time)>>;
 
+    /// Get the most recent peer misbehavior events, oldest first.
+    ///
+    /// At most `limit` events are returned. If `peer_filter` is set, only the events of the
+    /// given peer are returned.
+    #[method(name = "get_misbehavior_log")]
+    async fn get_misbehavior_log(
+        &self,
+        limit: usize,
+        peer_filter: Option<PeerId>,
+    ) -> RpcResult<Vec<MisbehaviorEvent>>;
+
     /// Get the number of peers connected to this node.
     #[method(name = "get_peer_count")]
     async fn get_peer_count(&self) -> RpcResult<usize>;
@@ -68,10 +84,29 @@ trait P2pRpc {
     #[method(name = "get_bind_addresses")]
     async fn get_bind_addresses(&self) -> RpcResult<Vec<SocketAddress>>;
 
+    /// Get the node id of this node, i.e. the public part of its static Noise key.
+    ///
+    /// The node id is advertised to the connected peers and is stable across restarts.
+    #[method(name = "get_node_id")]
+    async fn get_node_id(&self) -> RpcResult<NodeId>;
+
     /// Get details of connected peers.
     #[method(name = "get_connected_peers")]
     async fn get_connected_peers(&self) -> RpcResult<Vec<ConnectedPeer>>;
 
+    /// Get a snapshot of the block sync state, for debugging.
+    ///
+    /// This is a point-in-time read, syncing is not paused while it's taken.
+    #[method(name = "get_sync_snapshot")]
+    async fn get_sync_snapshot(&self) -> RpcResult<SyncStateSnapshot>;
+
+    /// Get the possible fork detected from the best blocks reported by peers, if any.
+    ///
+    /// A possible fork is reported when at least `fork_alert_threshold` peers have been reporting
+    /// tips different from the local one for at least `fork_alert_duration`.
+    #[method(name = "get_possible_fork")]
+    async fn get_possible_fork(&self) -> RpcResult<Option<PossibleFork>>;
+
     /// Get addresses of reserved nodes.
     #[method(name = "get_reserved_nodes")]
     async fn get_reserved_nodes(&self) -> RpcResult<Vec<SocketAddress>>;
@@ -93,7 +128,7 @@ trait P2pRpc {
     #[method(name = "submit_transaction")]
     async fn submit_transaction(
         &self,
-        tx: HexEncoded<SignedTransaction>,
+        tx: StrictHexEncoded<SignedTransaction>,
         options: TxOptionsOverrides,
     ) -> RpcResult<()>;
 }
@@ -102,77 +137,111 @@ trait P2pRpc {
 impl P2pRpcServer for super::P2pHandle {
     async fn enable_networking(&self, enable: bool) -> RpcResult<()> {
         let res = self.call_async_mut(move |this| this.enable_networking(enable)).await;
-        rpc::handle_result(res)
+        handle_p2p_result(res)
     }
 
     async fn connect(&self, addr: IpOrSocketAddress) -> RpcResult<()> {
         let res = self.call_async_mut(|this| this.connect(addr)).await;
-        rpc::handle_result(res)
+        handle_p2p_result(res)
     }
 
     async fn disconnect(&self, peer_id: PeerId) -> RpcResult<()> {
         let res = self.call_async_mut(move |this| this.disconnect(peer_id)).await;
-        rpc::handle_result(res)
+        handle_p2p_result(res)
     }
 
-    async fn list_banned(&self) -> RpcResult<Vec<(BannableAddress, Time)>> {
+    async fn list_banned(&self) -> RpcResult<Vec<BannedAddress>> {
         let res = self.call_async(|this| this.list_banned()).await;
-        rpc::handle_result(res)
+        handle_p2p_result(res)
     }
 
     async fn ban(&self, address: BannableAddress, duration: Duration) -> RpcResult<()> {
         let res = self.call_async_mut(move |this| this.ban(address, duration)).await;
-        rpc::handle_result(res)
+        handle_p2p_result(res)
     }
 
     async fn unban(&self, address: BannableAddress) -> RpcResult<()> {
         let res = self.call_async_mut(move |this| this.unban(address)).await;
-        rpc::handle_result(res)
+        handle_p2p_result(res)
     }
 
     async fn list_discouraged(&self) -> RpcResult<Vec<(BannableAddress, Time)>> {
         let res = self.call_async(|this| this.list_discouraged()).await;
-        rpc::handle_result(res)
+        handle_p2p_result(res)
+    }
+
+    async fn get_misbehavior_log(
+        &self,
+        limit: usize,
+        peer_filter: Option<PeerId>,
+    ) -> RpcResult<Vec<MisbehaviorEvent>> {
+        let res = self.call_async(move |this| this.get_misbehavior_log(limit, peer_filter)).await;
+        handle_p2p_result(res)
     }
 
     async fn get_peer_count(&self) -> RpcResult<usize> {
         let res = self.call_async(|this| this.get_peer_count()).await;
-        rpc::handle_result(res)
+        handle_p2p_result(res)
     }
 
     async fn get_bind_addresses(&self) -> RpcResult<Vec<SocketAddress>> {
         let res = self.call_async(|this| this.get_bind_addresses()).await;
+        handle_p2p_result(res)
+    }
+
+    async fn get_node_id(&self) -> RpcResult<NodeId> {
+        let res = self.call(|this| this.get_node_id()).await;
         rpc::handle_result(res)
     }
 
     async fn get_connected_peers(&self) -> RpcResult<Vec<ConnectedPeer>> {
         let res = self.call_async(|this| this.get_connected_peers()).await;
-        rpc::handle_result(res)
+        handle_p2p_result(res)
+    }
+
+    async fn get_sync_snapshot(&self) -> RpcResult<SyncStateSnapshot> {
+        let res = self.call_async(|this| this.get_sync_snapshot()).await;
+        handle_p2p_result(res)
+    }
+
+    async fn get_possible_fork(&self) -> RpcResult<Option<PossibleFork>> {
+        let res = self.call_async(|this| this.get_possible_fork()).await;
+        handle_p2p_result(res)
     }
 
     async fn get_reserved_nodes(&self) -> RpcResult<Vec<SocketAddress>> {
         let res = self.call_async(|this| this.get_reserved_nodes()).await;
-        rpc::handle_result(res)
+        handle_p2p_result(res)
     }
 
     async fn add_reserved_node(&self, addr: IpOrSocketAddress) -> RpcResult<()> {
         let res = self.call_async_mut(|this| this.add_reserved_node(addr)).await;
-        rpc::handle_result(res)
+        handle_p2p_result(res)
     }
 
     async fn remove_reserved_node(&self, addr: IpOrSocketAddress) -> RpcResult<()> {
         let res = self.call_async_mut(move |this| this.remove_reserved_node(addr)).await;
-        rpc::handle_result(res)
+        handle_p2p_result(res)
     }
 
     async fn submit_transaction(
         &self,
-        tx: HexEncoded<SignedTransaction>,
+        tx: StrictHexEncoded<SignedTransaction>,
         options: TxOptionsOverrides,
     ) -> RpcResult<()> {
         let res = self
             .call_async_mut(move |this| this.submit_transaction(tx.take(), options))
             .await;
-        rpc::handle_result(res)
+        handle_p2p_result(res)
+    }
+}
+
+/// Convert the result of a p2p call, keeping the code and the type of the p2p error
+fn handle_p2p_result<T>(
+    res: Result<crate::Result<T>, subsystem::error::CallError>,
+) -> RpcResult<T> {
+    match res {
+        Ok(res) => res.map_err(Into::into),
+        Err(e) => Err(rpc::service_unavailable_error(e)),
     }
 }
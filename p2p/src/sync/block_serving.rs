@@ -0,0 +1,244 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use tokio::sync::Notify;
+use utils::sync::{Arc, Mutex};
+
+use crate::types::peer_id::PeerId;
+
+/// Schedules the block responses sent to all the peers, so that a peer requesting many blocks
+/// can't starve the others and the blocks waiting to be written to the sockets don't take
+/// too much memory.
+///
+/// The peers that have a block to send are served in the round-robin order; each peer may have
+/// a limited number of responses in flight and the total size of the blocks in flight is capped.
+#[derive(Clone)]
+pub struct BlockServingScheduler {
+    state: Arc<Mutex<SchedulerState>>,
+    notify: Arc<Notify>,
+}
+
+struct SchedulerState {
+    max_buffered_bytes: usize,
+    max_in_flight_per_peer: usize,
+    /// The peers waiting to send a block, in the order they will be served
+    waiting: VecDeque<PeerId>,
+    /// The number of block responses in flight for each peer
+    in_flight: BTreeMap<PeerId, usize>,
+    /// The total size of the blocks in flight
+    buffered_bytes: usize,
+}
+
+impl SchedulerState {
+    fn can_send(&self, peer_id: PeerId, size: usize) -> bool {
+        // A block that is bigger than the limit is still sent once nothing else is buffered
+        let fits = self.buffered_bytes == 0
+            || self.buffered_bytes.saturating_add(size) <= self.max_buffered_bytes;
+
+        fits && self.waiting.iter().find(|id| self.can_peer_proceed(**id)) == Some(&peer_id)
+    }
+
+    /// Whether the peer isn't blocked by its own in-flight limit, so that a peer whose responses
+    /// aren't written yet doesn't hold up the ones behind it.
+    fn can_peer_proceed(&self, peer_id: PeerId) -> bool {
+        self.in_flight.get(&peer_id).copied().unwrap_or(0) < self.max_in_flight_per_peer
+    }
+}
+
+impl BlockServingScheduler {
+    pub fn new(max_buffered_bytes: usize, max_in_flight_per_peer: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SchedulerState {
+                max_buffered_bytes,
+                max_in_flight_per_peer,
+                waiting: VecDeque::new(),
+                in_flight: BTreeMap::new(),
+                buffered_bytes: 0,
+            })),
+            notify: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Wait until the peer's turn to send a block of the given size comes.
+    ///
+    /// The returned guard must be kept until the block is written to the socket or discarded.
+    /// If the future is dropped before completing, the peer keeps its place in the queue
+    /// until it calls `acquire` again or is removed.
+    pub async fn acquire(&self, peer_id: PeerId, size: usize) -> BufferedBlockResponse {
+        loop {
+            // Subscribe before checking the state, so that no notification is missed
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            {
+                let mut state = self.state.lock().expect("poisoned mutex");
+                if !state.waiting.contains(&peer_id) {
+                    state.waiting.push_back(peer_id);
+                }
+
+                if state.can_send(peer_id, size) {
+                    state.waiting.retain(|id| *id != peer_id);
+                    *state.in_flight.entry(peer_id).or_default() += 1;
+                    state.buffered_bytes += size;
+                    drop(state);
+
+                    // The next waiting peer may be able to send as well
+                    self.notify.notify_waiters();
+
+                    return BufferedBlockResponse {
+                        scheduler: self.clone(),
+                        peer_id,
+                        size,
+                    };
+                }
+            }
+
+            notified.await;
+        }
+    }
+
+    /// Forget the peer; called when the peer is disconnected.
+    ///
+    /// The peer's responses that are still in flight release their bytes when dropped.
+    pub fn remove_peer(&self, peer_id: &PeerId) {
+        let mut state = self.state.lock().expect("poisoned mutex");
+        state.waiting.retain(|id| id != peer_id);
+        state.in_flight.remove(peer_id);
+        drop(state);
+
+        self.notify.notify_waiters();
+    }
+
+    fn release(&self, peer_id: PeerId, size: usize) {
+        let mut state = self.state.lock().expect("poisoned mutex");
+        state.buffered_bytes = state.buffered_bytes.saturating_sub(size);
+        if let Some(in_flight) = state.in_flight.get_mut(&peer_id) {
+            *in_flight = in_flight.saturating_sub(1);
+            if *in_flight == 0 {
+                state.in_flight.remove(&peer_id);
+            }
+        }
+        drop(state);
+
+        self.notify.notify_waiters();
+    }
+}
+
+/// A block response that has been scheduled for sending; its bytes are released when dropped.
+pub struct BufferedBlockResponse {
+    scheduler: BlockServingScheduler,
+    peer_id: PeerId,
+    size: usize,
+}
+
+impl Drop for BufferedBlockResponse {
+    fn drop(&mut self) {
+        self.scheduler.release(self.peer_id, self.size);
+    }
+}
+
+impl std::fmt::Debug for BufferedBlockResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BufferedBlockResponse")
+            .field("peer_id", &self.peer_id)
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+impl PartialEq for BufferedBlockResponse {
+    fn eq(&self, other: &Self) -> bool {
+        self.peer_id == other.peer_id && self.size == other.size
+    }
+}
+
+impl Eq for BufferedBlockResponse {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    async fn is_blocked(scheduler: &BlockServingScheduler, peer_id: PeerId, size: usize) -> bool {
+        tokio::time::timeout(Duration::from_millis(50), scheduler.acquire(peer_id, size))
+            .await
+            .is_err()
+    }
+
+    #[tokio::test]
+    async fn limits() {
+        let scheduler = BlockServingScheduler::new(100, 2);
+        let peer1 = PeerId::new();
+        let peer2 = PeerId::new();
+
+        // The in-flight limit per peer
+        let guard1 = scheduler.acquire(peer1, 10).await;
+        let guard2 = scheduler.acquire(peer1, 10).await;
+        assert!(is_blocked(&scheduler, peer1, 10).await);
+        // The blocked peer doesn't hold up the others
+        let guard3 = scheduler.acquire(peer2, 70).await;
+        assert_eq!(scheduler.state.lock().unwrap().buffered_bytes, 90);
+
+        // The buffered bytes limit
+        drop(guard1);
+        assert!(is_blocked(&scheduler, peer1, 30).await);
+        drop(guard2);
+        let guard4 = scheduler.acquire(peer1, 30).await;
+
+        // A block bigger than the limit is sent once nothing else is buffered
+        drop(guard3);
+        assert!(is_blocked(&scheduler, peer2, 200).await);
+        drop(guard4);
+        let guard5 = scheduler.acquire(peer2, 200).await;
+
+        scheduler.remove_peer(&peer1);
+        drop(guard5);
+        let state = scheduler.state.lock().unwrap();
+        assert_eq!(state.buffered_bytes, 0);
+        assert!(state.waiting.is_empty());
+        assert!(state.in_flight.is_empty());
+    }
+
+    #[tokio::test]
+    async fn round_robin() {
+        let scheduler = BlockServingScheduler::new(10, 1);
+        let peer1 = PeerId::new();
+        let peer2 = PeerId::new();
+
+        let guard = scheduler.acquire(peer1, 10).await;
+
+        // Both peers wait for the buffer to be freed; peer2 has registered first
+        let task2 = tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move { scheduler.acquire(peer2, 10).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let task1 = tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move { scheduler.acquire(peer1, 10).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        drop(guard);
+        let guard = task2.await.unwrap();
+        assert!(!task1.is_finished());
+        drop(guard);
+        drop(task1.await.unwrap());
+    }
+}
@@ -0,0 +1,261 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
+
+use common::{
+    chain::Block,
+    primitives::{time::Time, Id},
+    time_getter::TimeGetter,
+    Uint256,
+};
+
+use crate::{metrics::metrics, types::peer_id::PeerId};
+
+struct BlockInFlight {
+    peer_id: PeerId,
+    requested_at: Time,
+    /// Whether the block isn't a part of the branch with the most work
+    low_work: bool,
+}
+
+/// The header chain whose blocks are being downloaded from a peer.
+struct Branch {
+    /// The chain work at the last header
+    work: Uint256,
+    block_ids: BTreeSet<Id<Block>>,
+}
+
+/// Keeps track of the blocks requested from all the peers, so that the same block isn't
+/// downloaded from multiple peers at once.
+///
+/// A block that is in flight from one peer can be requested from another one only after
+/// the first request times out.
+///
+/// The blocks of the branch with the most chain work are downloaded first; only a limited
+/// number of blocks of the other branches can be in flight at once.
+pub struct BlocksInFlight {
+    time_getter: TimeGetter,
+    request_timeout: Duration,
+    max_low_work_blocks: usize,
+    blocks: BTreeMap<Id<Block>, BlockInFlight>,
+    branches: BTreeMap<PeerId, Branch>,
+}
+
+impl BlocksInFlight {
+    pub fn new(
+        time_getter: TimeGetter,
+        request_timeout: Duration,
+        max_low_work_blocks: usize,
+    ) -> Self {
+        Self {
+            time_getter,
+            request_timeout,
+            max_low_work_blocks,
+            blocks: BTreeMap::new(),
+            branches: BTreeMap::new(),
+        }
+    }
+
+    /// Set the branch that is being downloaded from the peer.
+    pub fn set_branch(
+        &mut self,
+        peer_id: PeerId,
+        work: Uint256,
+        block_ids: impl IntoIterator<Item = Id<Block>>,
+    ) {
+        let branch = Branch {
+            work,
+            block_ids: block_ids.into_iter().collect(),
+        };
+        self.branches.insert(peer_id, branch);
+    }
+
+    /// Called when there is nothing more to download from the peer.
+    pub fn clear_branch(&mut self, peer_id: &PeerId) {
+        self.branches.remove(peer_id);
+    }
+
+    /// Register the block as requested from the peer, unless it's already in flight from
+    /// another peer or it's not a part of the branch with the most work and too many such
+    /// blocks are already in flight; return whether the block may be requested.
+    pub fn try_request(&mut self, peer_id: PeerId, block_id: Id<Block>) -> bool {
+        let now = self.time_getter.get_time();
+
+        if let Some(block) = self.blocks.get(&block_id) {
+            if block.peer_id != peer_id && !self.is_expired(block, now) {
+                return false;
+            }
+        }
+
+        let low_work = self.is_low_work(peer_id, &block_id);
+        if low_work {
+            let low_work_block_count = self
+                .blocks
+                .iter()
+                .filter(|(id, block)| {
+                    **id != block_id && block.low_work && !self.is_expired(block, now)
+                })
+                .count();
+            if low_work_block_count >= self.max_low_work_blocks {
+                return false;
+            }
+        }
+
+        let block = BlockInFlight {
+            peer_id,
+            requested_at: now,
+            low_work,
+        };
+        self.blocks.insert(block_id, block);
+        self.update_metrics();
+        true
+    }
+
+    /// Called when the block has been received from the peer; the entry is only removed
+    /// if the block is still in flight from that peer.
+    pub fn remove(&mut self, peer_id: PeerId, block_id: &Id<Block>) {
+        if self.blocks.get(block_id).is_some_and(|block| block.peer_id == peer_id) {
+            self.blocks.remove(block_id);
+            self.update_metrics();
+        }
+    }
+
+    pub fn remove_peer(&mut self, peer_id: &PeerId) {
+        self.blocks.retain(|_, block| block.peer_id != *peer_id);
+        self.branches.remove(peer_id);
+        self.update_metrics();
+    }
+
+    fn is_expired(&self, block: &BlockInFlight, now: Time) -> bool {
+        let expires_at = (block.requested_at + self.request_timeout)
+            .expect("All from local clock. Cannot fail.");
+        now >= expires_at
+    }
+
+    /// Whether the block belongs to a branch with less work than the best branch being
+    /// downloaded, and not to the best branch itself.
+    ///
+    /// Note that peers may be at different points of the same chain, in which case their
+    /// blocks are a part of the best branch.
+    fn is_low_work(&self, peer_id: PeerId, block_id: &Id<Block>) -> bool {
+        let work = match self.branches.get(&peer_id) {
+            Some(branch) => branch.work,
+            None => return false,
+        };
+        let best_work = match self.branches.values().map(|branch| branch.work).max() {
+            Some(best_work) => best_work,
+            None => return false,
+        };
+
+        work < best_work
+            && !self
+                .branches
+                .values()
+                .any(|branch| branch.work == best_work && branch.block_ids.contains(block_id))
+    }
+
+    fn update_metrics(&self) {
+        metrics().blocks_in_flight.set(self.blocks.len() as i64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::primitives::H256;
+    use test_utils::BasicTestTimeGetter;
+
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    const MAX_LOW_WORK_BLOCKS: usize = 2;
+
+    #[test]
+    fn request_from_one_peer_at_a_time() {
+        let time_getter = BasicTestTimeGetter::new();
+        let mut blocks_in_flight =
+            BlocksInFlight::new(time_getter.get_time_getter(), TIMEOUT, MAX_LOW_WORK_BLOCKS);
+        let peer1 = PeerId::new();
+        let peer2 = PeerId::new();
+        let block1 = Id::new(H256::from_low_u64_be(1));
+        let block2 = Id::new(H256::from_low_u64_be(2));
+
+        assert!(blocks_in_flight.try_request(peer1, block1));
+        assert!(blocks_in_flight.try_request(peer1, block2));
+        assert!(!blocks_in_flight.try_request(peer2, block1));
+
+        // Only the peer the block was requested from can release it
+        blocks_in_flight.remove(peer2, &block1);
+        assert!(!blocks_in_flight.try_request(peer2, block1));
+        blocks_in_flight.remove(peer1, &block1);
+        assert!(blocks_in_flight.try_request(peer2, block1));
+
+        // The request is taken over by another peer after the timeout
+        time_getter.advance_time(TIMEOUT);
+        assert!(blocks_in_flight.try_request(peer2, block2));
+        assert!(!blocks_in_flight.try_request(peer1, block2));
+
+        blocks_in_flight.remove_peer(&peer2);
+        assert!(blocks_in_flight.blocks.is_empty());
+    }
+
+    #[test]
+    fn low_work_blocks_limit() {
+        let time_getter = BasicTestTimeGetter::new();
+        let mut blocks_in_flight =
+            BlocksInFlight::new(time_getter.get_time_getter(), TIMEOUT, MAX_LOW_WORK_BLOCKS);
+        let peer1 = PeerId::new();
+        let peer2 = PeerId::new();
+        let peer3 = PeerId::new();
+        let block = |n: u64| Id::new(H256::from_low_u64_be(n));
+        // Both branches start with the common block 1
+        let low_work_branch = [1, 2, 3, 4].map(block);
+        let high_work_branch = [1, 5, 6].map(block);
+
+        blocks_in_flight.set_branch(peer1, Uint256::from_u64(4), low_work_branch);
+        blocks_in_flight.set_branch(peer2, Uint256::from_u64(5), high_work_branch);
+        // The third peer has only sent a part of the best branch so far
+        blocks_in_flight.set_branch(peer3, Uint256::from_u64(3), [1, 5].map(block));
+
+        // The common block and the blocks of the best branch aren't limited
+        assert!(blocks_in_flight.try_request(peer1, block(1)));
+        assert!(blocks_in_flight.try_request(peer3, block(5)));
+        assert!(blocks_in_flight.try_request(peer2, block(6)));
+
+        assert!(blocks_in_flight.try_request(peer1, block(2)));
+        assert!(blocks_in_flight.try_request(peer1, block(3)));
+        assert!(!blocks_in_flight.try_request(peer1, block(4)));
+
+        // The limit applies to the blocks in flight
+        blocks_in_flight.remove(peer1, &block(2));
+        assert!(blocks_in_flight.try_request(peer1, block(4)));
+        assert!(!blocks_in_flight.try_request(peer1, block(2)));
+
+        // The expired requests don't count
+        time_getter.advance_time(TIMEOUT);
+        assert!(blocks_in_flight.try_request(peer1, block(2)));
+
+        // Once the best branch is downloaded, the other branch is no longer limited
+        blocks_in_flight.clear_branch(&peer2);
+        blocks_in_flight.remove_peer(&peer3);
+        blocks_in_flight.remove(peer1, &block(2));
+        assert!(blocks_in_flight.try_request(peer1, block(2)));
+        assert!(blocks_in_flight.try_request(peer1, block(3)));
+        assert!(blocks_in_flight.try_request(peer1, block(4)));
+    }
+}
@@ -16,11 +16,14 @@
 //! This module is responsible for both initial syncing and further blocks processing (the reaction
 //! to block announcement from peers and the announcement of blocks produced by this node).
 
+pub mod block_serving;
+mod blocks_in_flight;
 mod chainstate_handle;
 mod peer;
 mod peer_activity;
 mod peer_common;
 pub mod sync_status;
+mod tx_conflicts;
 
 use std::collections::HashMap;
 
@@ -37,9 +40,16 @@ use common::{
     time_getter::TimeGetter,
 };
 use logging::log;
-use mempool::{event::TransactionProcessed, tx_origin::TxOrigin, MempoolHandle};
+use mempool::{
+    event::{TransactionConflict, TransactionProcessed},
+    tx_origin::TxOrigin,
+    FeeRate, MempoolHandle,
+};
 use tracing::Instrument;
-use utils::{sync::Arc, tap_log::TapLog};
+use utils::{
+    sync::{Arc, Mutex},
+    tap_log::TapLog,
+};
 
 use crate::{
     config::P2pConfig,
@@ -54,12 +64,15 @@ use crate::{
     PeerManagerEvent, Result,
 };
 
-use self::chainstate_handle::ChainstateHandle;
+use self::{
+    blocks_in_flight::BlocksInFlight, chainstate_handle::ChainstateHandle,
+    tx_conflicts::TxConflictsTracker,
+};
 
 #[derive(Debug, Clone)]
 pub enum LocalEvent {
     ChainstateNewTip(Id<Block>),
-    MempoolNewTx(Id<Transaction>),
+    MempoolNewTx(Id<Transaction>, FeeRate),
 }
 
 pub struct PeerContext {
@@ -88,6 +101,15 @@ pub struct SyncManager<T: NetworkingService> {
     /// The list of connected peers
     peers: HashMap<PeerId, PeerContext>,
 
+    /// Double spending and conflicting transactions relayed by the peers.
+    tx_conflicts: TxConflictsTracker,
+
+    /// The blocks requested from all the peers, shared with the peer tasks.
+    blocks_in_flight: Arc<Mutex<BlocksInFlight>>,
+
+    /// Schedules the blocks sent to all the peers, shared with the peer tasks.
+    block_serving_scheduler: BlockServingScheduler,
+
     time_getter: TimeGetter,
 
     /// SyncManager's observer for use by tests.
@@ -138,6 +160,18 @@ where
         time_getter: TimeGetter,
         observer: Option<BoxedObserver>,
     ) -> Self {
+        // A block is requested from another peer before the first one is disconnected
+        // for stalling, so that syncing is not delayed by the whole stalling timeout.
+        let blocks_in_flight = BlocksInFlight::new(
+            time_getter.clone(),
+            *p2p_config.sync_stalling_timeout / 2,
+            *p2p_config.max_low_work_blocks_in_flight,
+        );
+        let block_serving_scheduler = BlockServingScheduler::new(
+            *p2p_config.max_buffered_block_response_bytes,
+            p2p_config.max_in_flight_block_responses_per_peer.get(),
+        );
+
         Self {
             chain_config,
             p2p_config,
@@ -147,6 +181,9 @@ where
             chainstate_handle: ChainstateHandle::new(chainstate_handle),
             mempool_handle,
             peers: Default::default(),
+            tx_conflicts: TxConflictsTracker::new(time_getter.clone()),
+            blocks_in_flight: Arc::new(Mutex::new(blocks_in_flight)),
+            block_serving_scheduler,
             time_getter,
             observer,
         }
@@ -158,6 +195,7 @@ where
 
         let mut new_tip_receiver = subscribe_to_new_tip(&self.chainstate_handle).await?;
         let mut tx_processed_receiver = subscribe_to_tx_processed(&self.mempool_handle).await?;
+        let mut tx_conflict_receiver = subscribe_to_tx_conflicts(&self.mempool_handle).await?;
 
         loop {
             tokio::select! {
@@ -172,6 +210,11 @@ where
                     self.handle_transaction_processed(&tx_proc)?;
                 },
 
+                tx_conflict = tx_conflict_receiver.recv() => {
+                    let tx_conflict = tx_conflict.expect("Transaction conflict sender closed");
+                    self.handle_transaction_conflict(&tx_conflict)?;
+                },
+
                 event = self.syncing_event_receiver.poll_next() => {
                     self.handle_peer_event(event?).await;
                 },
@@ -184,7 +227,8 @@ where
         &mut self,
         peer_id: PeerId,
         common_services: Services,
-        _protocol_version: SupportedProtocolVersion,
+        protocol_version: SupportedProtocolVersion,
+        inbound: bool,
         block_sync_msg_receiver: Receiver<BlockSyncMessage>,
         transaction_sync_msg_receiver: Receiver<TransactionSyncMessage>,
     ) {
@@ -197,6 +241,7 @@ where
         let mut mgr = peer::block_manager::PeerBlockSyncManager::<T>::new(
             peer_id,
             common_services,
+            protocol_version,
             Arc::clone(&self.chain_config),
             Arc::clone(&self.p2p_config),
             self.chainstate_handle.clone(),
@@ -204,6 +249,8 @@ where
             block_sync_msg_receiver,
             self.messaging_handle.clone(),
             local_event_receiver,
+            Arc::clone(&self.blocks_in_flight),
+            self.block_serving_scheduler.clone(),
             self.time_getter.clone(),
         );
 
@@ -220,6 +267,8 @@ where
         let mut mgr = peer::transaction_manager::PeerTransactionSyncManager::<T>::new(
             peer_id,
             common_services,
+            protocol_version,
+            inbound,
             Arc::clone(&self.p2p_config),
             self.chainstate_handle.clone(),
             self.mempool_handle.clone(),
@@ -258,6 +307,9 @@ where
             .unwrap_or_else(|| panic!("Unregistering unknown peer: {peer_id}"));
         // Call `abort` because the peer tasks may be sleeping for a long time in the `sync_clock` function
         peer.tasks.abort_all();
+        self.tx_conflicts.remove_peer(&peer_id);
+        self.blocks_in_flight.lock().expect("poisoned mutex").remove_peer(&peer_id);
+        self.block_serving_scheduler.remove_peer(&peer_id);
     }
 
     fn send_local_event(&mut self, event: &LocalEvent) {
@@ -289,12 +341,12 @@ where
         let origin = tx_proc_event.origin();
 
         match tx_proc_event.result() {
-            Ok(()) => {
+            Ok(fee_rate) => {
                 use mempool::tx_options::TxRelayPolicy;
                 match tx_proc_event.relay_policy() {
                     TxRelayPolicy::DoRelay => {
                         log::info!("Broadcasting transaction {tx_id} originating in {origin}");
-                        self.send_local_event(&LocalEvent::MempoolNewTx(tx_id));
+                        self.send_local_event(&LocalEvent::MempoolNewTx(tx_id, *fee_rate));
                     }
                     TxRelayPolicy::DontRelay => {
                         log::trace!("Not propagating transaction {tx_id} originating in {origin}");
@@ -310,8 +362,12 @@ where
                         let (response_sender, _response_receiver) =
                             crate::utils::oneshot_nofail::channel();
                         let peer_id = remote_origin.peer_id();
-                        let event =
-                            PeerManagerEvent::AdjustPeerScore(peer_id, ban_score, response_sender);
+                        let event = PeerManagerEvent::AdjustPeerScore(
+                            peer_id,
+                            ban_score,
+                            "MempoolError",
+                            response_sender,
+                        );
                         self.peer_mgr_event_sender
                             .send(event)
                             .map_err(|_| P2pError::ChannelClosed)?;
@@ -323,6 +379,34 @@ where
         Ok(())
     }
 
+    /// Penalize the peers that keep relaying double spending or conflicting transactions.
+    fn handle_transaction_conflict(&mut self, tx_conflict: &TransactionConflict) -> Result<()> {
+        let peer_id = tx_conflict.origin().peer_id();
+        if !self.peers.contains_key(&peer_id) {
+            return Ok(());
+        }
+
+        let ban_score = self.tx_conflicts.record_offense(peer_id);
+        log::debug!(
+            "Transaction {} relayed by peer {peer_id} was rejected: {:?}, ban score increment: {ban_score}",
+            tx_conflict.tx_id(),
+            tx_conflict.rejection_class(),
+        );
+
+        if ban_score > 0 {
+            let (response_sender, _response_receiver) = crate::utils::oneshot_nofail::channel();
+            let event = PeerManagerEvent::AdjustPeerScore(
+                peer_id,
+                ban_score,
+                "MempoolTxConflict",
+                response_sender,
+            );
+            self.peer_mgr_event_sender.send(event).map_err(|_| P2pError::ChannelClosed)?;
+        }
+
+        Ok(())
+    }
+
     /// Sends an event to the corresponding peer.
     async fn handle_peer_event(&mut self, event: SyncingEvent) {
         match event {
@@ -330,12 +414,14 @@ where
                 peer_id,
                 common_services,
                 protocol_version,
+                inbound,
                 block_sync_msg_receiver,
                 transaction_sync_msg_receiver,
             } => self.register_peer(
                 peer_id,
                 common_services,
                 protocol_version,
+                inbound,
                 block_sync_msg_receiver,
                 transaction_sync_msg_receiver,
             ),
@@ -395,7 +481,31 @@ pub async fn subscribe_to_tx_processed(
         mempool::event::MempoolEvent::TransactionProcessed(tpe) => {
             let _ = sender.send(tpe).log_err_pfx("The tx processed receiver closed");
         }
-        mempool::event::MempoolEvent::NewTip(_) => (),
+        mempool::event::MempoolEvent::TransactionConflict(_)
+        | mempool::event::MempoolEvent::NewTip(_) => (),
+    };
+    let subscribe_func = Arc::new(subscribe_func);
+
+    mempool_handle
+        .call_mut(|this| this.subscribe_to_events(subscribe_func))
+        .await
+        .map_err(|_| P2pError::SubsystemFailure)?;
+
+    Ok(receiver)
+}
+
+/// Returns a receiver for the mempool `TransactionConflict` events.
+pub async fn subscribe_to_tx_conflicts(
+    mempool_handle: &MempoolHandle,
+) -> Result<UnboundedReceiver<TransactionConflict>> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+
+    let subscribe_func = move |event: mempool::event::MempoolEvent| match event {
+        mempool::event::MempoolEvent::TransactionConflict(tce) => {
+            let _ = sender.send(tce).log_err_pfx("The tx conflict receiver closed");
+        }
+        mempool::event::MempoolEvent::TransactionProcessed(_)
+        | mempool::event::MempoolEvent::NewTip(_) => (),
     };
     let subscribe_func = Arc::new(subscribe_func);
 
@@ -0,0 +1,111 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::BinaryHeap, num::NonZeroUsize};
+
+use common::{chain::Transaction, primitives::Id};
+use mempool::FeeRate;
+
+/// Limits the number of transaction announcements sent to a peer per sync cycle, so that
+/// a large batch of new mempool transactions doesn't cause latency spikes.
+///
+/// The announcements that don't fit into the current cycle are sent in the following ones,
+/// highest fee rate first.
+pub struct AnnouncementQueue {
+    max_per_cycle: NonZeroUsize,
+    sent_in_cycle: usize,
+    txs: BinaryHeap<(FeeRate, Id<Transaction>)>,
+}
+
+impl AnnouncementQueue {
+    pub fn new(max_per_cycle: NonZeroUsize) -> Self {
+        Self {
+            max_per_cycle,
+            sent_in_cycle: 0,
+            txs: BinaryHeap::new(),
+        }
+    }
+
+    pub fn push(&mut self, tx: Id<Transaction>, fee_rate: FeeRate) {
+        self.txs.push((fee_rate, tx));
+    }
+
+    /// Take the announcements that can still be sent in the current cycle
+    pub fn take_sendable(&mut self) -> Vec<Id<Transaction>> {
+        let count = std::cmp::min(
+            self.max_per_cycle.get().saturating_sub(self.sent_in_cycle),
+            self.txs.len(),
+        );
+        self.sent_in_cycle += count;
+        (0..count).filter_map(|_| self.txs.pop().map(|(_, tx)| tx)).collect()
+    }
+
+    pub fn start_new_cycle(&mut self) {
+        self.sent_in_cycle = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::primitives::H256;
+    use rstest::rstest;
+    use test_utils::random::{make_seedable_rng, Rng, Seed};
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn limit_per_cycle(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+
+        let max_per_cycle = rng.gen_range(1..100);
+        let remainder = rng.gen_range(1..max_per_cycle + 1);
+        let mut queue = AnnouncementQueue::new(NonZeroUsize::new(max_per_cycle).unwrap());
+
+        let mut txs = (0..max_per_cycle + remainder)
+            .map(|_| {
+                let tx = Id::<Transaction>::new(H256::random_using(&mut rng));
+                let fee_rate = FeeRate::from_atoms_per_kb(rng.gen_range(0..1_000_000));
+                (fee_rate, tx)
+            })
+            .collect::<Vec<_>>();
+        for (fee_rate, tx) in &txs {
+            queue.push(*tx, *fee_rate);
+        }
+        txs.sort_by(|a, b| b.cmp(a));
+        let txs = txs.into_iter().map(|(_, tx)| tx).collect::<Vec<_>>();
+
+        // Only the limit is sent in the first cycle, highest fee rate first
+        assert_eq!(queue.take_sendable(), txs[..max_per_cycle]);
+        assert_eq!(queue.take_sendable(), []);
+        assert_eq!(queue.txs.len(), remainder);
+
+        // The remainder is sent in the next cycle
+        queue.start_new_cycle();
+        assert_eq!(queue.take_sendable(), txs[max_per_cycle..]);
+        assert!(queue.txs.is_empty());
+
+        // The announcements sent in a cycle count towards its limit
+        let tx = Id::<Transaction>::new(H256::random_using(&mut rng));
+        queue.push(tx, FeeRate::from_atoms_per_kb(0));
+        let expected: &[Id<Transaction>] = if remainder < max_per_cycle {
+            &[tx]
+        } else {
+            &[]
+        };
+        assert_eq!(queue.take_sendable(), expected);
+    }
+}
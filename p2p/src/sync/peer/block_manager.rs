@@ -16,12 +16,15 @@
 use std::{
     collections::{BTreeSet, VecDeque},
     mem,
+    time::Duration,
 };
 
 use itertools::Itertools;
 use tokio::sync::mpsc::{Receiver, UnboundedReceiver, UnboundedSender};
 
-use chainstate::{chainstate_interface::ChainstateInterface, BlockIndex, BlockSource, Locator};
+use chainstate::{
+    chainstate_interface::ChainstateInterface, BlockIndex, BlockSource, GenBlockIndex, Locator,
+};
 use common::{
     chain::{
         block::{signed_block_header::SignedBlockHeader, timestamp::BlockTimestamp},
@@ -29,23 +32,32 @@ use common::{
     },
     primitives::{time::Time, BlockHeight, Id, Idable},
     time_getter::TimeGetter,
+    Uint256,
 };
 use logging::log;
+use serialization::Encode;
 use utils::const_value::ConstValue;
-use utils::sync::Arc;
+use utils::sync::{Arc, Mutex};
 
 use crate::{
     config::P2pConfig,
     disconnection_reason::DisconnectionReason,
     error::{P2pError, PeerError, ProtocolError, SyncError},
-    message::{BlockListRequest, BlockResponse, BlockSyncMessage, HeaderList, HeaderListRequest},
+    message::{
+        BlockHeaderResponse, BlockListRequest, BlockResponse, BlockSyncMessage, HeaderList,
+        HeaderListRequest,
+    },
     net::{
         types::services::{Service, Services},
         NetworkingService,
     },
     peer_manager_event::PeerDisconnectionDbAction,
+    protocol::{can_request_block_headers, SupportedProtocolVersion},
     sync::{
+        block_serving::{BlockServingScheduler, BufferedBlockResponse},
+        blocks_in_flight::BlocksInFlight,
         chainstate_handle::ChainstateHandle,
+        peer::served_headers::ServedHeaders,
         peer_activity::PeerActivity,
         peer_common::{choose_peers_best_block, handle_message_processing_result},
         sync_status::PeerBlockSyncStatus,
@@ -56,7 +68,10 @@ use crate::{
     MessagingService, PeerManagerEvent, Result,
 };
 
-// TODO: Take into account the chain work when syncing.
+/// How often to check whether the blocks that are in flight from other peers have been received
+/// or their requests have timed out, when all the blocks that we need from this peer are such.
+const BLOCKS_IN_FLIGHT_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Block syncing manager.
 ///
 /// Syncing logic runs in a separate task for each peer.
@@ -65,11 +80,16 @@ pub struct PeerBlockSyncManager<T: NetworkingService> {
     chain_config: Arc<ChainConfig>,
     p2p_config: Arc<P2pConfig>,
     common_services: Services,
+    protocol_version: SupportedProtocolVersion,
     chainstate_handle: ChainstateHandle,
     peer_mgr_event_sender: UnboundedSender<PeerManagerEvent>,
     messaging_handle: T::MessagingHandle,
     sync_msg_receiver: Receiver<BlockSyncMessage>,
     local_event_receiver: UnboundedReceiver<LocalEvent>,
+    /// The blocks requested from all the peers.
+    blocks_in_flight: Arc<Mutex<BlocksInFlight>>,
+    /// Schedules the blocks sent to all the peers.
+    block_serving_scheduler: BlockServingScheduler,
     time_getter: TimeGetter,
     /// Incoming data state.
     incoming: IncomingDataState,
@@ -81,11 +101,26 @@ pub struct PeerBlockSyncManager<T: NetworkingService> {
     /// of headers less than the maximum. This is the signal to the peer that we have no more
     /// headers, so it may not ask us for more of them in the future.
     have_sent_all_headers: bool,
+    /// The headers that were sent in response to the peer's header requests.
+    served_headers: ServedHeaders,
+    /// The last response to the peer's header request.
+    last_header_response: Option<HeaderResponse>,
+}
+
+/// A response to a header request, which is kept so that a repeated identical request
+/// can be answered without querying the chainstate again.
+struct HeaderResponse {
+    locator: Locator,
+    /// Our best block at the moment the response was made.
+    best_block_id: Id<GenBlock>,
+    headers: Vec<SignedBlockHeader>,
+    first_header_height: BlockHeight,
 }
 
 struct IncomingDataState {
     /// A list of headers received via the `HeaderListResponse` message that we haven't yet
-    /// requested the blocks for.
+    /// requested the blocks for. If `requested_blocks` is empty, the leading blocks are
+    /// in flight from other peers.
     pending_headers: Vec<SignedBlockHeader>,
     /// A list of blocks that we requested from this peer.
     requested_blocks: VecDeque<Id<Block>>,
@@ -93,11 +128,16 @@ struct IncomingDataState {
     /// This includes headers received by any means, e.g. via HeaderList messages, as part
     /// of a locator during peer's header requests, via block responses.
     peers_best_block_that_we_have: Option<Id<GenBlock>>,
+    /// The height of the last block received from the peer.
+    last_received_block_height: Option<BlockHeight>,
 }
 
 struct OutgoingDataState {
     /// A queue of the blocks requested by this peer.
     blocks_queue: VecDeque<Id<Block>>,
+    /// The block at the front of `blocks_queue` and its index, read from the chainstate
+    /// when it's this block's turn to be sent, so that only one block is kept in memory.
+    next_block: Option<(Block, BlockIndex)>,
     /// The index of the best block that we've sent to the peer.
     best_sent_block: Option<BlockIndex>,
     /// The id of the best block header that we've sent to the peer.
@@ -114,6 +154,7 @@ where
     pub fn new(
         id: PeerId,
         common_services: Services,
+        protocol_version: SupportedProtocolVersion,
         chain_config: Arc<ChainConfig>,
         p2p_config: Arc<P2pConfig>,
         chainstate_handle: ChainstateHandle,
@@ -121,6 +162,8 @@ where
         sync_msg_receiver: Receiver<BlockSyncMessage>,
         messaging_handle: T::MessagingHandle,
         local_event_receiver: UnboundedReceiver<LocalEvent>,
+        blocks_in_flight: Arc<Mutex<BlocksInFlight>>,
+        block_serving_scheduler: BlockServingScheduler,
         time_getter: TimeGetter,
     ) -> Self {
         Self {
@@ -128,24 +171,31 @@ where
             chain_config,
             p2p_config,
             common_services,
+            protocol_version,
             chainstate_handle,
             peer_mgr_event_sender,
             messaging_handle,
             sync_msg_receiver,
             local_event_receiver,
+            blocks_in_flight,
+            block_serving_scheduler,
             time_getter,
             incoming: IncomingDataState {
                 pending_headers: Vec::new(),
                 requested_blocks: VecDeque::new(),
                 peers_best_block_that_we_have: None,
+                last_received_block_height: None,
             },
             outgoing: OutgoingDataState {
                 blocks_queue: VecDeque::new(),
+                next_block: None,
                 best_sent_block: None,
                 best_sent_block_header: None,
             },
             peer_activity: PeerActivity::new(),
             have_sent_all_headers: false,
+            served_headers: ServedHeaders::new(),
+            last_header_response: None,
         }
     }
 
@@ -176,16 +226,25 @@ where
         loop {
             let last_sync_status = self.get_sync_status();
 
+            if self.outgoing.next_block.is_none() {
+                if let Some(id) = self.outgoing.blocks_queue.front() {
+                    self.outgoing.next_block = Some(self.read_block(*id).await?);
+                }
+            }
+            let peer_id = self.id();
+            let next_block_size =
+                self.outgoing.next_block.as_ref().map(|(block, _)| block.encoded_size());
+
             tokio::select! {
                 message = self.sync_msg_receiver.recv() => {
                     let message = message.ok_or(P2pError::ChannelClosed)?;
                     self.handle_message(message).await?;
                 }
 
-                block_to_send_to_peer = async {
-                    self.outgoing.blocks_queue.pop_front().expect("The block queue is empty")
-                }, if !self.outgoing.blocks_queue.is_empty() => {
-                    self.send_block(block_to_send_to_peer).await?;
+                // The peers take turns sending their blocks
+                guard = self.block_serving_scheduler.acquire(peer_id, next_block_size.unwrap_or(0)),
+                    if next_block_size.is_some() => {
+                    self.send_block(guard).await?;
                 }
 
                 event = self.local_event_receiver.recv() => {
@@ -195,6 +254,13 @@ where
 
                 _ = tokio::time::sleep(stalling_timeout),
                     if self.peer_activity.earliest_expected_activity_time().is_some() => {}
+
+                _ = tokio::time::sleep(BLOCKS_IN_FLIGHT_CHECK_INTERVAL),
+                    if self.is_waiting_for_blocks_in_flight() => {}
+            }
+
+            if self.is_waiting_for_blocks_in_flight() {
+                self.request_pending_blocks().await?;
             }
 
             self.handle_sync_status_change(&last_sync_status)?;
@@ -207,6 +273,9 @@ where
     fn get_sync_status(&self) -> PeerBlockSyncStatus {
         PeerBlockSyncStatus {
             expecting_blocks_since: self.peer_activity.expecting_blocks_since(),
+            pending_header_count: self.incoming.pending_headers.len(),
+            requested_blocks: self.incoming.requested_blocks.iter().copied().collect(),
+            last_received_block_height: self.incoming.last_received_block_height,
         }
     }
 
@@ -333,7 +402,7 @@ where
 
         match event {
             LocalEvent::ChainstateNewTip(new_tip_id) => self.handle_new_tip(&new_tip_id).await,
-            LocalEvent::MempoolNewTx(_) => Ok(()),
+            LocalEvent::MempoolNewTx(..) => Ok(()),
         }
     }
 
@@ -374,6 +443,17 @@ where
             }
             BlockSyncMessage::HeaderList(l) => self.handle_header_list(l.into_headers()).await,
             BlockSyncMessage::BlockResponse(r) => self.handle_block_response(r.into_block()).await,
+            BlockSyncMessage::BlockHeaderRequest(r) => {
+                self.handle_block_header_request(r.into_block_ids()).await
+            }
+            // Note: this node never requests individual headers, so a response is always
+            // unsolicited.
+            BlockSyncMessage::BlockHeaderResponse(_) => Err(P2pError::ProtocolError(
+                ProtocolError::UnexpectedMessageForState {
+                    expected_state: "a block header request sent to the peer".to_owned(),
+                    received_message: "BlockHeaderResponse".to_owned(),
+                },
+            )),
 
             #[cfg(test)]
             BlockSyncMessage::TestSentinel(id) => {
@@ -406,33 +486,96 @@ where
             return Ok(());
         }
 
-        // Obtain headers and also determine the new value for peers_best_block_that_we_have.
+        let cur_time = self.time_getter.get_time();
+        let allowed_header_count = self
+            .served_headers
+            .allowed_header_count(cur_time, &self.p2p_config.protocol_config)
+            .ok_or(P2pError::ProtocolError(
+                ProtocolError::ExcessiveHeaderRequests,
+            ))?;
+
         let header_count_limit = *self.p2p_config.protocol_config.msg_header_count_limit;
-        let old_peers_best_block_that_we_have = self.incoming.peers_best_block_that_we_have;
-        let (headers, peers_best_block_that_we_have) = self
-            .chainstate_handle
-            .call(move |c| {
-                let headers = c.get_mainchain_headers_by_locator(&locator, header_count_limit)?;
-                let peers_best_block_that_we_have = if let Some(header) = headers.first() {
-                    // If headers obtained from the locator are non-empty, the parent of
-                    // the first one represents the locator's latest block that is present in
-                    // this node's main chain (or the genesis).
-                    let last_common_block_id = *header.prev_block_id();
-                    choose_peers_best_block(
-                        c,
-                        old_peers_best_block_that_we_have,
-                        Some(last_common_block_id),
-                    )?
-                } else {
-                    // If headers are empty, the peer already has our best block.
-                    Some(c.get_best_block_id()?)
-                };
 
-                Ok((headers, peers_best_block_that_we_have))
-            })
-            .await?;
-        debug_assert!(headers.len() <= header_count_limit);
-        self.incoming.peers_best_block_that_we_have = peers_best_block_that_we_have;
+        let cached_response = match self.last_header_response.take() {
+            Some(response) if response.locator == locator => {
+                let best_block_id =
+                    self.chainstate_handle.call(|c| Ok(c.get_best_block_id()?)).await?;
+                (response.best_block_id == best_block_id).then_some(response)
+            }
+            _ => None,
+        };
+
+        let response = if let Some(response) = cached_response {
+            // Note: peers_best_block_that_we_have was updated when the response was made
+            // and it can only have improved since then.
+            log::debug!(
+                "[peer id = {}] Repeating the previous header response",
+                self.id()
+            );
+            response
+        } else {
+            // Obtain headers and also determine the new value for peers_best_block_that_we_have.
+            let old_peers_best_block_that_we_have = self.incoming.peers_best_block_that_we_have;
+            let (response, peers_best_block_that_we_have) = self
+                .chainstate_handle
+                .call(move |c| {
+                    let best_block_id = c.get_best_block_id()?;
+                    let headers =
+                        c.get_mainchain_headers_by_locator(&locator, header_count_limit)?;
+                    let (first_header_height, peers_best_block_that_we_have) = if let Some(header) =
+                        headers.first()
+                    {
+                        // If headers obtained from the locator are non-empty, the parent of
+                        // the first one represents the locator's latest block that is present
+                        // in this node's main chain (or the genesis).
+                        let last_common_block_id = *header.prev_block_id();
+                        let last_common_block_height = c
+                            .get_block_height_in_main_chain(&last_common_block_id)?
+                            .expect("The parent of a mainchain header must be in the mainchain");
+                        let peers_best_block_that_we_have = choose_peers_best_block(
+                            c,
+                            old_peers_best_block_that_we_have,
+                            Some(last_common_block_id),
+                        )?;
+                        (
+                            last_common_block_height.next_height(),
+                            peers_best_block_that_we_have,
+                        )
+                    } else {
+                        // If headers are empty, the peer already has our best block.
+                        (
+                            c.get_best_block_height()?.next_height(),
+                            Some(best_block_id),
+                        )
+                    };
+
+                    let response = HeaderResponse {
+                        locator,
+                        best_block_id,
+                        headers,
+                        first_header_height,
+                    };
+                    Ok((response, peers_best_block_that_we_have))
+                })
+                .await?;
+            debug_assert!(response.headers.len() <= header_count_limit);
+            self.incoming.peers_best_block_that_we_have = peers_best_block_that_we_have;
+
+            response
+        };
+
+        let headers: Vec<_> = response.headers.iter().take(allowed_header_count).cloned().collect();
+        if headers.len() < response.headers.len() {
+            log::debug!(
+                "[peer id = {}] Throttling header response to {} headers instead of {}",
+                self.id(),
+                headers.len(),
+                response.headers.len()
+            );
+        }
+        self.served_headers
+            .on_headers_sent(cur_time, response.first_header_height, headers.len());
+        self.last_header_response = Some(response);
 
         // Sending a below-the-max amount of headers is a signal to the peer that we've sent
         // all headers that were available at the moment.
@@ -502,7 +645,7 @@ where
                     // following check will fail without peer's fault. (But this situation should be rare, so we
                     // probably won't care about it anyway, because its impact - erroneously discourage/or be discouraged
                     // by a peer - is low.)
-                    // Also see a similar note in send_block.
+                    // Also see a similar note in read_block.
                     let index = c.get_block_index_for_persisted_block(&id)?.ok_or(
                         P2pError::ProtocolError(ProtocolError::UnknownBlockRequested(id)),
                     )?;
@@ -539,10 +682,68 @@ where
         // another one for the previous request(s), so that it can distinguish previously
         // requested blocks that were "cancelled" in-flight from unsolicited ones.
         self.outgoing.blocks_queue.extend(block_ids.into_iter());
+        self.served_headers.on_blocks_requested();
 
         Ok(())
     }
 
+    /// Processes a request for individual block headers by sending them to the peer.
+    async fn handle_block_header_request(&mut self, block_ids: Vec<Id<Block>>) -> Result<()> {
+        utils::ensure!(
+            can_request_block_headers(self.protocol_version),
+            P2pError::ProtocolError(ProtocolError::UnexpectedMessageForState {
+                expected_state: format!(
+                    "protocol version supporting block header requests, but it is {:?}",
+                    self.protocol_version
+                ),
+                received_message: "BlockHeaderRequest".to_owned(),
+            })
+        );
+
+        utils::ensure!(
+            !block_ids.is_empty(),
+            P2pError::ProtocolError(ProtocolError::ZeroBlocksInRequest)
+        );
+
+        log::debug!(
+            "[peer id = {}] Handling block header request: {}-{} ({})",
+            self.id(),
+            block_ids.first().expect("block_ids is not empty"),
+            block_ids.last().expect("block_ids is not empty"),
+            block_ids.len(),
+        );
+
+        let max_request_blocks_count = *self.p2p_config.protocol_config.max_request_blocks_count;
+        utils::ensure!(
+            block_ids.len() <= max_request_blocks_count,
+            P2pError::ProtocolError(ProtocolError::BlocksRequestLimitExceeded(
+                block_ids.len(),
+                max_request_blocks_count,
+            ))
+        );
+
+        let headers = self
+            .chainstate_handle
+            .call(move |c| {
+                block_ids
+                    .into_iter()
+                    .map(|id| {
+                        // Note: same as in handle_block_request, only persisted blocks are
+                        // considered known here.
+                        let index = c.get_block_index_for_persisted_block(&id)?.ok_or(
+                            P2pError::ProtocolError(ProtocolError::UnknownBlockRequested(id)),
+                        )?;
+                        Ok(index.block_header().clone())
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .await?;
+
+        self.send_message(BlockSyncMessage::BlockHeaderResponse(
+            BlockHeaderResponse::new(headers),
+        ))
+    }
+
     /// Delays the processing of a new block until it can be accepted by the chainstate (but not more than `max_clock_diff`).
     /// This is needed to allow the local or remote node to have slightly inaccurate clocks.
     /// Without it, even a 1 second difference can break block synchronization
@@ -632,15 +833,16 @@ where
         // header updates when we're downloading blocks from them, as mentioned above) that
         // would only complicate the logic.
 
-        let first_header_prev_block_height = self
+        let first_header_prev_block_index = self
             .chainstate_handle
             // Use get_gen_block_index_for_any_block instead of get_gen_block_index_for_persisted_block
             // to avoid bailing out with the DisconnectedHeaders error early (the appropriate error will
             // be generated when checking the header later and its ban score will be bigger).
             .call(move |c| Ok(c.get_gen_block_index_for_any_block(&first_header_prev_id)?))
             .await?
-            .ok_or(P2pError::ProtocolError(ProtocolError::DisconnectedHeaders))?
-            .block_height();
+            .ok_or(P2pError::ProtocolError(ProtocolError::DisconnectedHeaders))?;
+        let first_header_prev_block_height = first_header_prev_block_index.block_height();
+        let headers_chain_work = chain_work(&first_header_prev_block_index, &headers);
 
         let last_header = headers.last().expect("Headers shouldn't be empty");
         let last_header_height = first_header_prev_block_height
@@ -650,11 +852,12 @@ where
 
         let peer_may_have_more_headers =
             headers.len() == *self.p2p_config.protocol_config.msg_header_count_limit;
+        let last_header_id = last_header.get_id();
 
         // Filter out any existing headers from "headers" and determine the new value for
         // peers_best_block_that_we_have.
         let old_peers_best_block_that_we_have = self.incoming.peers_best_block_that_we_have;
-        let (new_block_headers, peers_best_block_that_we_have) = self
+        let (new_block_headers, peers_best_block_that_we_have, local_tip) = self
             .chainstate_handle
             .call(move |c| {
                 let (existing_block_headers, new_block_headers) =
@@ -664,23 +867,40 @@ where
                     old_peers_best_block_that_we_have,
                     existing_block_headers.last().map(|header| header.get_id().into()),
                 )?;
+                let local_tip = c.get_best_block_id()?;
 
-                Ok((new_block_headers, peers_best_block_that_we_have))
+                Ok((new_block_headers, peers_best_block_that_we_have, local_tip))
             })
             .await?;
 
         self.incoming.peers_best_block_that_we_have = peers_best_block_that_we_have;
 
+        if !peer_may_have_more_headers {
+            // The last header is the peer's tip; let the peer manager know about it, so that
+            // it can detect the situation when the node is on a different chain than its peers.
+            self.peer_mgr_event_sender.send(PeerManagerEvent::ReportedBestBlock {
+                peer_id: self.id(),
+                block_id: last_header_id,
+                local_tip,
+            })?;
+        }
+
         if !self.incoming.requested_blocks.is_empty() {
             // We are already downloading blocks, so bail out.
             // Note that we unconditionally replace pending_headers with new_block_headers
             // even if the latter is empty (because this will just mean that the peer has reorged
             // to something similar to our mainchain, so the old pending_headers are stale now).
+            self.set_pending_branch(headers_chain_work, &new_block_headers);
             self.incoming.pending_headers = new_block_headers;
             return Ok(());
         }
 
+        // If we've been waiting for the blocks in flight from other peers, the old
+        // pending_headers are stale now for the same reason.
+        self.incoming.pending_headers.clear();
+
         if new_block_headers.is_empty() {
+            self.set_pending_branch(headers_chain_work, &new_block_headers);
             if peer_may_have_more_headers {
                 self.request_headers().await?;
             }
@@ -696,6 +916,7 @@ where
                 .await?;
         }
 
+        self.set_pending_branch(headers_chain_work, &new_block_headers);
         self.request_blocks(new_block_headers)
     }
 
@@ -730,6 +951,10 @@ where
         }
 
         self.incoming.requested_blocks.pop_front();
+        self.blocks_in_flight
+            .lock()
+            .expect("poisoned mutex")
+            .remove(self.id(), &block_id);
 
         if self.incoming.requested_blocks.is_empty() {
             self.peer_activity.set_expecting_blocks_since(None);
@@ -742,7 +967,7 @@ where
         // Process the block and also determine the new value for peers_best_block_that_we_have.
         let peer_id = self.id();
         let old_peers_best_block_that_we_have = self.incoming.peers_best_block_that_we_have;
-        let (best_block, new_tip_received) = self
+        let (best_block, new_tip_received, block_height) = self
             .chainstate_handle
             .call_mut(move |c| {
                 // If the block already exists in the block tree, skip it.
@@ -759,16 +984,23 @@ where
                         block_index.is_some()
                     };
 
+                let block_height = c
+                    .get_block_index_for_persisted_block(&block_id)?
+                    .map(|block_index| block_index.block_height());
+
                 let best_block = choose_peers_best_block(
                     c,
                     old_peers_best_block_that_we_have,
                     Some(block_id.into()),
                 )?;
 
-                Ok((best_block, new_tip_received))
+                Ok((best_block, new_tip_received, block_height))
             })
             .await?;
         self.incoming.peers_best_block_that_we_have = best_block;
+        if block_height.is_some() {
+            self.incoming.last_received_block_height = block_height;
+        }
 
         if new_tip_received {
             self.peer_mgr_event_sender.send(PeerManagerEvent::NewTipReceived {
@@ -778,44 +1010,88 @@ where
         }
 
         if self.incoming.requested_blocks.is_empty() {
-            let headers = mem::take(&mut self.incoming.pending_headers);
-            // Note: we could have received some of these blocks from another peer in the meantime,
-            // so filter out any existing blocks from 'headers' first.
-            // TODO: we can still request the same block from multiple peers, potentially from all
-            // of them, which is sub-optimal. See https://github.com/mintlayer/mintlayer-core/issues/1323
-            let headers = if headers.is_empty() {
-                headers
-            } else {
-                self.chainstate_handle
-                    .call(|c| Ok(c.split_off_leading_known_headers(headers)?))
-                    .await?
-                    .1
-            };
-
-            if headers.is_empty() {
-                // Request more headers.
-                self.request_headers().await?;
-            } else {
-                // Download remaining blocks.
-                self.request_blocks(headers)?;
-            }
+            self.request_pending_blocks().await?;
         }
 
         Ok(())
     }
 
+    /// Register the header chain that is downloaded from the peer, so that the blocks of the chain
+    /// with the most work are requested first from all the peers.
+    fn set_pending_branch(&self, chain_work: Uint256, headers: &[SignedBlockHeader]) {
+        let mut blocks_in_flight = self.blocks_in_flight.lock().expect("poisoned mutex");
+        if headers.is_empty() {
+            blocks_in_flight.clear_branch(&self.id());
+        } else {
+            blocks_in_flight.set_branch(
+                self.id(),
+                chain_work,
+                headers.iter().map(|header| header.get_id()),
+            );
+        }
+    }
+
+    /// Whether all the blocks that we need from the peer are in flight from other peers.
+    fn is_waiting_for_blocks_in_flight(&self) -> bool {
+        self.incoming.requested_blocks.is_empty() && !self.incoming.pending_headers.is_empty()
+    }
+
+    /// Requests the blocks for the pending headers, or more headers if there are none.
+    async fn request_pending_blocks(&mut self) -> Result<()> {
+        debug_assert!(self.incoming.requested_blocks.is_empty());
+
+        let headers = mem::take(&mut self.incoming.pending_headers);
+        // Note: we could have received some of these blocks from another peer in the meantime,
+        // so filter out any existing blocks from 'headers' first.
+        let headers = if headers.is_empty() {
+            headers
+        } else {
+            self.chainstate_handle
+                .call(|c| Ok(c.split_off_leading_known_headers(headers)?))
+                .await?
+                .1
+        };
+
+        if headers.is_empty() {
+            self.blocks_in_flight.lock().expect("poisoned mutex").clear_branch(&self.id());
+            // Request more headers.
+            self.request_headers().await
+        } else {
+            // Download remaining blocks.
+            self.request_blocks(headers)
+        }
+    }
+
     /// Sends a block list request.
     ///
-    /// The number of blocks requested equals `ProtocolConfig::max_request_blocks_count`,
+    /// The number of blocks requested is at most `ProtocolConfig::max_request_blocks_count`,
     /// the remaining headers are stored in the peer context.
+    /// Only the leading blocks that are not in flight from other peers are requested; if there
+    /// are none, nothing is requested and the blocks will be requested later, once the other
+    /// peers send them or their requests time out. The same applies if the blocks aren't a part
+    /// of the header chain with the most work and too many such blocks are already in flight.
     fn request_blocks(&mut self, mut headers: Vec<SignedBlockHeader>) -> Result<()> {
         debug_assert!(self.incoming.pending_headers.is_empty());
         debug_assert!(self.incoming.requested_blocks.is_empty());
         debug_assert!(!headers.is_empty());
 
-        if headers.len() > *self.p2p_config.protocol_config.max_request_blocks_count {
-            self.incoming.pending_headers =
-                headers.split_off(*self.p2p_config.protocol_config.max_request_blocks_count);
+        let block_count = {
+            let mut blocks_in_flight = self.blocks_in_flight.lock().expect("poisoned mutex");
+            headers
+                .iter()
+                .take(*self.p2p_config.protocol_config.max_request_blocks_count)
+                .take_while(|header| blocks_in_flight.try_request(self.id(), header.get_id()))
+                .count()
+        };
+        self.incoming.pending_headers = headers.split_off(block_count);
+
+        if headers.is_empty() {
+            log::debug!(
+                "[peer id = {}] Not requesting block {} from the peer, because it's in flight from another peer or a chain with more work is being downloaded",
+                self.id(),
+                self.incoming.pending_headers.first().expect("pending_headers is not empty").get_id(),
+            );
+            return Ok(());
         }
 
         let block_ids: Vec<_> = headers.into_iter().map(|h| h.get_id()).collect();
@@ -839,7 +1115,7 @@ where
         Ok(())
     }
 
-    async fn send_block(&mut self, id: Id<Block>) -> Result<()> {
+    async fn read_block(&self, id: Id<Block>) -> Result<(Block, BlockIndex)> {
         let (block, block_index) = self
             .chainstate_handle
             .call(move |c| {
@@ -856,6 +1132,15 @@ where
         let block = block?.ok_or(SyncError::BlockDataMissingInSendBlock(id))?;
         let block_index = block_index?.ok_or(SyncError::BlockIndexMissingInSendBlock(id))?;
 
+        Ok((block, block_index))
+    }
+
+    async fn send_block(&mut self, guard: BufferedBlockResponse) -> Result<()> {
+        let (block, block_index) =
+            self.outgoing.next_block.take().expect("The next block must be present");
+        let id = self.outgoing.blocks_queue.pop_front().expect("The block queue is empty");
+        debug_assert_eq!(id, block.get_id());
+
         let old_best_sent_block_id = self.outgoing.best_sent_block.as_ref().map(|idx| {
             let id: Id<GenBlock> = (*idx.block_id()).into();
             id
@@ -874,7 +1159,8 @@ where
             self.id(),
             block.get_id()
         );
-        self.send_message(BlockSyncMessage::BlockResponse(BlockResponse::new(block)))
+        self.messaging_handle
+            .send_block_response(self.id(), BlockResponse::new(block), guard)
     }
 
     async fn disconnect_if_stalling(&mut self) -> Result<()> {
@@ -919,3 +1205,20 @@ where
         }
     }
 }
+
+/// The chain work at the end of the headers, which follow the given block.
+///
+/// The proofs that can't be calculated are ignored, such headers will be rejected later.
+fn chain_work(prev_block_index: &GenBlockIndex, headers: &[SignedBlockHeader]) -> Uint256 {
+    let mut work = prev_block_index.chain_trust();
+    let mut prev_timestamp = prev_block_index.block_timestamp();
+    for header in headers {
+        let proof = header
+            .consensus_data()
+            .get_block_proof(prev_timestamp, header.timestamp())
+            .unwrap_or(Uint256::ZERO);
+        work = (work + proof).unwrap_or(Uint256::MAX);
+        prev_timestamp = header.timestamp();
+    }
+    work
+}
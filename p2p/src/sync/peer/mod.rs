@@ -17,4 +17,6 @@ pub mod block_manager;
 pub mod requested_transactions;
 pub mod transaction_manager;
 
+mod announcement_queue;
 mod pending_transactions;
+mod served_headers;
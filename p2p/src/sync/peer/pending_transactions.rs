@@ -17,9 +17,10 @@ use std::{cmp::Reverse, collections::BinaryHeap};
 use tokio::time::Instant;
 
 use common::{chain::Transaction, primitives::Id};
+use mempool::FeeRate;
 
 pub struct PendingTransactions {
-    txs: BinaryHeap<Reverse<(Instant, Id<Transaction>)>>,
+    txs: BinaryHeap<Reverse<(Instant, Id<Transaction>, FeeRate)>>,
 }
 
 impl PendingTransactions {
@@ -29,21 +30,21 @@ impl PendingTransactions {
         }
     }
 
-    pub fn push(&mut self, tx: Id<Transaction>, due_time: Instant) {
-        self.txs.push(Reverse((due_time, tx)));
+    pub fn push(&mut self, tx: Id<Transaction>, fee_rate: FeeRate, due_time: Instant) {
+        self.txs.push(Reverse((due_time, tx, fee_rate)));
     }
 
-    pub fn pop(&mut self) -> Option<Id<Transaction>> {
+    pub fn pop(&mut self) -> Option<(Id<Transaction>, FeeRate)> {
         self.txs.pop().map(|item| {
-            let (_, tx) = item.0;
-            tx
+            let (_, tx, fee_rate) = item.0;
+            (tx, fee_rate)
         })
     }
 
     pub async fn due(&self) {
         match self.txs.peek() {
             Some(item) => {
-                let (due, _) = item.0;
+                let (due, _, _) = item.0;
                 tokio::time::sleep_until(due).await;
             }
             None => std::future::pending().await,
@@ -78,13 +79,14 @@ mod tests {
         let mut txs = PendingTransactions::new();
         assert_eq!(None, txs.pop());
 
-        txs.push(tx3, instant3);
-        txs.push(tx1, instant1);
-        txs.push(tx2, instant2);
+        let fee_rate = FeeRate::from_atoms_per_kb(rng.gen());
+        txs.push(tx3, fee_rate, instant3);
+        txs.push(tx1, fee_rate, instant1);
+        txs.push(tx2, fee_rate, instant2);
 
-        assert_eq!(Some(tx1), txs.pop());
-        assert_eq!(Some(tx2), txs.pop());
-        assert_eq!(Some(tx3), txs.pop());
+        assert_eq!(Some((tx1, fee_rate)), txs.pop());
+        assert_eq!(Some((tx2, fee_rate)), txs.pop());
+        assert_eq!(Some((tx3, fee_rate)), txs.pop());
         assert_eq!(None, txs.pop());
     }
 
@@ -96,7 +98,7 @@ mod tests {
         let due_instant = Instant::now() + Duration::from_secs(1);
 
         let mut txs = PendingTransactions::new();
-        txs.push(tx, due_instant);
+        txs.push(tx, FeeRate::from_atoms_per_kb(0), due_instant);
 
         tokio::time::pause();
         tokio::spawn(async {
@@ -116,7 +118,7 @@ mod tests {
         let due_instant = Instant::now();
 
         let mut txs = PendingTransactions::new();
-        txs.push(tx, due_instant);
+        txs.push(tx, FeeRate::from_atoms_per_kb(0), due_instant);
 
         tokio::time::pause();
         tokio::time::advance(Duration::from_secs(1)).await;
@@ -0,0 +1,191 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+
+use common::primitives::{time::Time, BlockHeight};
+
+use crate::protocol::ProtocolConfig;
+
+/// This struct tracks the headers that were sent to a peer more than once.
+///
+/// A small header request can make us send a lot of headers, so a peer that keeps asking for
+/// the same headers without ever requesting the corresponding blocks can use us to amplify
+/// its traffic. Once the number of the repeatedly sent headers exceeds the budget, the header
+/// responses are made progressively smaller and eventually the peer is penalized.
+pub struct ServedHeaders {
+    /// The numbers of headers that were sent again, along with the time when they were sent.
+    repeated_headers: VecDeque<(Time, usize)>,
+    /// The height of the best header that was sent to the peer.
+    best_sent_header_height: Option<BlockHeight>,
+    /// The number of header responses that were throttled since the peer last requested blocks.
+    throttled_response_count: usize,
+}
+
+impl ServedHeaders {
+    pub fn new() -> Self {
+        Self {
+            repeated_headers: VecDeque::new(),
+            best_sent_header_height: None,
+            throttled_response_count: 0,
+        }
+    }
+
+    /// The peer has requested blocks, so it makes use of the headers it gets from us.
+    pub fn on_blocks_requested(&mut self) {
+        self.repeated_headers.clear();
+        self.throttled_response_count = 0;
+    }
+
+    /// Return the maximum number of headers that may be sent in response to a header request,
+    /// or None if the peer should be penalized instead.
+    pub fn allowed_header_count(
+        &mut self,
+        cur_time: Time,
+        protocol_config: &ProtocolConfig,
+    ) -> Option<usize> {
+        let header_count_limit = *protocol_config.msg_header_count_limit;
+
+        if let Some(window_start) = cur_time - *protocol_config.repeated_headers_window {
+            while self.repeated_headers.front().is_some_and(|(time, _)| *time < window_start) {
+                self.repeated_headers.pop_front();
+            }
+        }
+
+        let repeated_count: usize = self.repeated_headers.iter().map(|(_, count)| count).sum();
+        if repeated_count < *protocol_config.max_repeated_headers_served {
+            self.throttled_response_count = 0;
+            return Some(header_count_limit);
+        }
+
+        self.throttled_response_count += 1;
+        if self.throttled_response_count > *protocol_config.max_throttled_header_responses {
+            return None;
+        }
+
+        // Halve the batch size on each throttled response
+        let allowed_count = header_count_limit
+            .checked_shr(self.throttled_response_count as u32)
+            .unwrap_or(0)
+            .max(1);
+        Some(allowed_count)
+    }
+
+    /// Record that `count` consecutive headers starting at `first_header_height` were sent.
+    pub fn on_headers_sent(
+        &mut self,
+        cur_time: Time,
+        first_header_height: BlockHeight,
+        count: usize,
+    ) {
+        if count == 0 {
+            return;
+        }
+
+        let first_height = first_header_height.into_int();
+        let last_height = first_height + count as u64 - 1;
+
+        if let Some(best_height) = self.best_sent_header_height {
+            let best_height = best_height.into_int();
+            if best_height >= first_height {
+                let repeated_count = std::cmp::min(best_height - first_height + 1, count as u64);
+                self.repeated_headers.push_back((cur_time, repeated_count as usize));
+            }
+        }
+
+        self.best_sent_header_height = Some(BlockHeight::new(std::cmp::max(
+            last_height,
+            self.best_sent_header_height.map_or(0, |height| height.into_int()),
+        )));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn make_config() -> ProtocolConfig {
+        ProtocolConfig {
+            msg_header_count_limit: 100.into(),
+            max_repeated_headers_served: 250.into(),
+            repeated_headers_window: Duration::from_secs(60).into(),
+            max_throttled_header_responses: 3.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn advancing_requests_not_throttled() {
+        let config = make_config();
+        let mut served = ServedHeaders::new();
+        let time = Time::from_secs_since_epoch(1000);
+
+        for i in 0..100 {
+            assert_eq!(served.allowed_header_count(time, &config), Some(100));
+            served.on_headers_sent(time, BlockHeight::new(1 + i * 100), 100);
+        }
+        assert!(served.repeated_headers.is_empty());
+    }
+
+    #[test]
+    fn repeated_requests_throttled() {
+        let config = make_config();
+        let mut served = ServedHeaders::new();
+        let time = Time::from_secs_since_epoch(1000);
+
+        // The budget allows the same headers to be sent 3 more times
+        for _ in 0..4 {
+            assert_eq!(served.allowed_header_count(time, &config), Some(100));
+            served.on_headers_sent(time, BlockHeight::new(1), 100);
+        }
+
+        assert_eq!(served.allowed_header_count(time, &config), Some(50));
+        assert_eq!(served.allowed_header_count(time, &config), Some(25));
+        assert_eq!(served.allowed_header_count(time, &config), Some(12));
+        assert_eq!(served.allowed_header_count(time, &config), None);
+
+        // Requesting blocks resets the accounting
+        served.on_blocks_requested();
+        assert_eq!(served.allowed_header_count(time, &config), Some(100));
+    }
+
+    #[test]
+    fn old_repeats_expire() {
+        let config = make_config();
+        let mut served = ServedHeaders::new();
+        let time = Time::from_secs_since_epoch(1000);
+
+        for _ in 0..4 {
+            served.on_headers_sent(time, BlockHeight::new(1), 100);
+        }
+        assert_eq!(served.allowed_header_count(time, &config), Some(50));
+
+        let later_time = (time + Duration::from_secs(61)).unwrap();
+        assert_eq!(served.allowed_header_count(later_time, &config), Some(100));
+    }
+
+    #[test]
+    fn partially_repeated_headers() {
+        let mut served = ServedHeaders::new();
+        let time = Time::from_secs_since_epoch(1000);
+
+        served.on_headers_sent(time, BlockHeight::new(1), 100);
+        served.on_headers_sent(time, BlockHeight::new(51), 100);
+        assert_eq!(served.repeated_headers, [(time, 50)]);
+        assert_eq!(served.best_sent_header_height, Some(BlockHeight::new(150)));
+    }
+}
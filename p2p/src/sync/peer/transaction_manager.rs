@@ -27,18 +27,19 @@ use common::{
     time_getter::TimeGetter,
 };
 use logging::log;
-use mempool::{MempoolHandle, TxOptions};
+use mempool::{error::TxRejectionClass, MempoolHandle, TxOptions};
 use utils::const_value::ConstValue;
 use utils::sync::Arc;
 
 use crate::{
     config::P2pConfig,
     error::{P2pError, ProtocolError},
-    message::{TransactionResponse, TransactionSyncMessage},
+    message::{MempoolRequest, TransactionResponse, TransactionSyncMessage},
     net::{
         types::services::{Service, Services},
         NetworkingService,
     },
+    protocol::{can_sync_mempool, SupportedProtocolVersion},
     sync::{
         chainstate_handle::ChainstateHandle,
         peer_common::{handle_message_processing_result, KnownTransactions},
@@ -49,12 +50,50 @@ use crate::{
 };
 
 use super::{
-    pending_transactions::PendingTransactions, requested_transactions::RequestedTransactions,
+    announcement_queue::AnnouncementQueue, pending_transactions::PendingTransactions,
+    requested_transactions::RequestedTransactions,
 };
 
 // TODO: add smaller interval for outbound connections
 pub const TX_RELAY_DELAY_INTERVAL: Duration = Duration::from_secs(5);
 
+/// The state of the initial mempool sync with the peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MempoolSyncState {
+    /// The mempool hasn't been requested yet.
+    NotRequested,
+    /// `GetMempool` has been sent, waiting for the response.
+    Requested { max_txs: u32 },
+    /// The response has been received, or the mempool sync is not applicable to this peer.
+    Done,
+}
+
+impl MempoolSyncState {
+    fn name(&self) -> &'static str {
+        match self {
+            MempoolSyncState::NotRequested => "NotRequested",
+            MempoolSyncState::Requested { max_txs: _ } => "Requested",
+            MempoolSyncState::Done => "Done",
+        }
+    }
+
+    /// Returns the number of the requested transactions if a mempool response is expected.
+    fn expect_mempool_response(&self) -> Result<u32> {
+        match self {
+            MempoolSyncState::Requested { max_txs } => Ok(*max_txs),
+            MempoolSyncState::NotRequested | MempoolSyncState::Done => Err(
+                P2pError::ProtocolError(ProtocolError::UnexpectedMessageForState {
+                    expected_state: format!(
+                        "mempool sync state Requested, but it is {}",
+                        self.name()
+                    ),
+                    received_message: "MempoolResponse".to_owned(),
+                }),
+            ),
+        }
+    }
+}
+
 // TODO: Take into account the chain work when syncing.
 /// Transaction sync manager.
 ///
@@ -63,6 +102,8 @@ pub struct PeerTransactionSyncManager<T: NetworkingService> {
     id: ConstValue<PeerId>,
     p2p_config: Arc<P2pConfig>,
     common_services: Services,
+    protocol_version: SupportedProtocolVersion,
+    inbound: bool,
     chainstate_handle: ChainstateHandle,
     mempool_handle: MempoolHandle,
     peer_mgr_event_sender: UnboundedSender<PeerManagerEvent>,
@@ -77,6 +118,12 @@ pub struct PeerTransactionSyncManager<T: NetworkingService> {
     /// Txs aren't relayed immediately but rather put into a collection to be propagated later
     /// with random delay to make tracing transactions' origin harder
     pending_transactions: PendingTransactions,
+    /// The announcements of the txs that are due, but exceed the per-cycle limit
+    announcement_queue: AnnouncementQueue,
+    /// The state of the mempool sync initiated by us.
+    mempool_sync_state: MempoolSyncState,
+    /// Whether the peer has already requested our mempool.
+    mempool_request_received: bool,
     /// SyncManager's observer for use by tests.
     observer: Option<BoxedObserver>,
 }
@@ -90,6 +137,8 @@ where
     pub fn new(
         id: PeerId,
         common_services: Services,
+        protocol_version: SupportedProtocolVersion,
+        inbound: bool,
         p2p_config: Arc<P2pConfig>,
         chainstate_handle: ChainstateHandle,
         mempool_handle: MempoolHandle,
@@ -101,11 +150,15 @@ where
         observer: Option<BoxedObserver>,
     ) -> Self {
         let known_transactions = KnownTransactions::new();
+        let announcement_queue =
+            AnnouncementQueue::new(*p2p_config.max_mempool_tx_broadcasts_per_peer);
 
         Self {
             id: id.into(),
             p2p_config,
             common_services,
+            protocol_version,
+            inbound,
             chainstate_handle,
             mempool_handle,
             peer_mgr_event_sender,
@@ -115,6 +168,9 @@ where
             known_transactions,
             requested_transactions: RequestedTransactions::new(time_getter),
             pending_transactions: PendingTransactions::new(),
+            announcement_queue,
+            mempool_sync_state: MempoolSyncState::NotRequested,
+            mempool_request_received: false,
             observer,
         }
     }
@@ -141,6 +197,8 @@ where
         );
         maintenance_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
+        self.request_mempool_if_needed().await?;
+
         loop {
             if let Some(o) = self.observer.as_mut() {
                 o.on_new_transaction_sync_mgr_main_loop_iteration(peer_id);
@@ -154,16 +212,21 @@ where
 
                 event = self.local_event_receiver.recv() => {
                     let event = event.ok_or(P2pError::ChannelClosed)?;
-                    self.handle_local_event(event)?;
+                    self.handle_local_event(event).await?;
                 }
 
                 _ = self.pending_transactions.due() => {
-                    if let Some(new_tx) = self.pending_transactions.pop(){
-                        self.send_message(TransactionSyncMessage::NewTransaction(new_tx))?;
+                    if let Some((new_tx, fee_rate)) = self.pending_transactions.pop() {
+                        self.announcement_queue.push(new_tx, fee_rate);
+                        self.send_queued_announcements()?;
                     }
                 }
 
-                _ = maintenance_interval.tick() => {}
+                // The maintenance interval also serves as the tx announcement cycle
+                _ = maintenance_interval.tick() => {
+                    self.announcement_queue.start_new_cycle();
+                    self.send_queued_announcements()?;
+                }
             }
 
             self.requested_transactions.purge_if_needed();
@@ -174,15 +237,25 @@ where
         self.messaging_handle.send_transaction_sync_message(self.id(), message)
     }
 
-    fn handle_local_event(&mut self, event: LocalEvent) -> Result<()> {
+    fn send_queued_announcements(&mut self) -> Result<()> {
+        for tx in self.announcement_queue.take_sendable() {
+            self.send_message(TransactionSyncMessage::NewTransaction(tx))?;
+        }
+        Ok(())
+    }
+
+    async fn handle_local_event(&mut self, event: LocalEvent) -> Result<()> {
         log::debug!(
             "[peer id = {}] Handling local peer mgr event: {event:?}",
             self.id()
         );
 
         match event {
-            LocalEvent::ChainstateNewTip(_) => Ok(()),
-            LocalEvent::MempoolNewTx(txid) => {
+            // Note: the sync manager only sends this event once the initial block download
+            // is over, so this is the point where the mempool can be requested if the node
+            // was still syncing when the peer connected.
+            LocalEvent::ChainstateNewTip(_) => self.request_mempool_if_needed().await,
+            LocalEvent::MempoolNewTx(txid, fee_rate) => {
                 if !self.known_transactions.contains(&txid)
                     && self.common_services.has_service(Service::Transactions)
                 {
@@ -192,7 +265,7 @@ where
                     let now = Instant::now();
                     let delay = TX_RELAY_DELAY_INTERVAL
                         .mul_f64(utils::exp_rand::exponential_rand(&mut make_pseudo_rng()));
-                    self.pending_transactions.push(txid, now + delay);
+                    self.pending_transactions.push(txid, fee_rate, now + delay);
                 }
                 Ok(())
             }
@@ -215,10 +288,111 @@ where
             TransactionSyncMessage::TransactionResponse(tx) => {
                 self.handle_transaction_response(tx).await
             }
+            TransactionSyncMessage::GetMempool(req) => self.handle_mempool_request(req).await,
+            TransactionSyncMessage::MempoolResponse(ids) => self.handle_mempool_response(ids).await,
         };
         handle_message_processing_result(&self.peer_mgr_event_sender, self.id(), res).await
     }
 
+    /// Send `GetMempool` to the peer if the initial mempool sync is enabled and applicable
+    /// to the peer and the node is not in the initial block download.
+    async fn request_mempool_if_needed(&mut self) -> Result<()> {
+        if self.mempool_sync_state != MempoolSyncState::NotRequested {
+            return Ok(());
+        }
+
+        if !self.p2p_config.enable_mempool_sync.unwrap_or(false)
+            || self.inbound
+            || !can_sync_mempool(self.protocol_version)
+            || !self.common_services.has_service(Service::Transactions)
+        {
+            self.mempool_sync_state = MempoolSyncState::Done;
+            return Ok(());
+        }
+
+        if self.chainstate_handle.is_initial_block_download().await? {
+            return Ok(());
+        }
+
+        let max_txs = *self.p2p_config.protocol_config.max_mempool_sync_txs;
+        log::debug!(
+            "[peer id = {}] Requesting mempool, max_txs = {max_txs}",
+            self.id()
+        );
+        self.send_message(TransactionSyncMessage::GetMempool(MempoolRequest {
+            max_txs,
+        }))?;
+        self.mempool_sync_state = MempoolSyncState::Requested { max_txs };
+
+        Ok(())
+    }
+
+    /// Transaction messages are only expected if both nodes have the corresponding service.
+    fn ensure_transactions_service(&self, received_message: &str) -> Result<()> {
+        if !self.common_services.has_service(Service::Transactions) {
+            return Err(P2pError::ProtocolError(
+                ProtocolError::UnexpectedMessageForState {
+                    expected_state: "the Transactions service is enabled on both nodes".to_owned(),
+                    received_message: received_message.to_owned(),
+                },
+            ));
+        }
+        Ok(())
+    }
+
+    async fn handle_mempool_request(&mut self, req: MempoolRequest) -> Result<()> {
+        self.ensure_transactions_service("GetMempool")?;
+
+        if self.mempool_request_received {
+            return Err(P2pError::ProtocolError(
+                ProtocolError::UnexpectedMessageForState {
+                    expected_state: "no mempool request received from the peer yet".to_owned(),
+                    received_message: "repeated GetMempool".to_owned(),
+                },
+            ));
+        }
+        self.mempool_request_received = true;
+
+        let max_txs = std::cmp::min(
+            req.max_txs,
+            *self.p2p_config.protocol_config.max_mempool_sync_txs,
+        ) as usize;
+        let ids = self.mempool_handle.call(move |m| m.best_transaction_ids(max_txs)).await?;
+
+        // The peer will request the transactions it doesn't have, there is no need to announce
+        // them again.
+        for id in &ids {
+            self.add_known_transaction(*id);
+        }
+
+        self.send_message(TransactionSyncMessage::MempoolResponse(ids))
+    }
+
+    async fn handle_mempool_response(&mut self, ids: Vec<Id<Transaction>>) -> Result<()> {
+        let max_txs = self.mempool_sync_state.expect_mempool_response()?;
+        self.mempool_sync_state = MempoolSyncState::Done;
+
+        if ids.len() > max_txs as usize {
+            return Err(P2pError::ProtocolError(
+                ProtocolError::TransactionAnnouncementLimitExceeded(max_txs as usize),
+            ));
+        }
+
+        log::debug!(
+            "[peer id = {}] Received {} transaction ids in mempool response",
+            self.id(),
+            ids.len()
+        );
+
+        // The ids are handled as regular announcements, i.e. the transactions that are missing
+        // in our mempool will be requested via `TransactionRequest`.
+        for id in ids {
+            self.handle_transaction_announcement(id).await?;
+        }
+
+        Ok(())
+    }
+
     async fn handle_transaction_request(&mut self, id: Id<Transaction>) -> Result<()> {
         // TODO: should we handle a request if we haven't actually announced the tx?
         // Currently we do.
@@ -227,11 +401,7 @@ where
         // (see PeerManagerImpl::FindTxForGetData), but they don't punish peers for such
         // requests either.
 
-        if !self.common_services.has_service(Service::Transactions) {
-            return Err(P2pError::ProtocolError(ProtocolError::UnexpectedMessage(
-                "A transaction request is received, but this node doesn't have the corresponding service".to_owned(),
-            )));
-        }
+        self.ensure_transactions_service("TransactionRequest")?;
 
         let tx = self.mempool_handle.call(move |m| m.transaction(&id)).await?;
         let res = match tx {
@@ -280,10 +450,25 @@ where
             let origin = mempool::tx_origin::RemoteTxOrigin::new(self.id());
             let options = TxOptions::default_for(origin.into());
             let txid = transaction.transaction().get_id();
-            let tx_status = self
+            let tx_status = match self
                 .mempool_handle
                 .call_mut(move |m| m.add_transaction_remote(transaction, origin, options))
-                .await??;
+                .await?
+            {
+                Ok(tx_status) => tx_status,
+                Err(err) => match err.rejection_class() {
+                    // The sync manager decides whether the peer should be penalized for these,
+                    // see `TransactionConflict`.
+                    TxRejectionClass::DoubleSpend | TxRejectionClass::Conflict => {
+                        log::debug!(
+                            "[peer id = {}] Transaction {txid} rejected: {err}",
+                            self.id()
+                        );
+                        return Ok(());
+                    }
+                    TxRejectionClass::Other => return Err(err.into()),
+                },
+            };
             match tx_status {
                 mempool::TxStatus::InMempool => {
                     self.peer_mgr_event_sender.send(
@@ -321,11 +506,7 @@ where
             return Ok(());
         }
 
-        if !self.common_services.has_service(Service::Transactions) {
-            return Err(P2pError::ProtocolError(ProtocolError::UnexpectedMessage(
-                "A transaction announcement is received, but this node doesn't have the corresponding service".to_owned(),
-            )));
-        }
+        self.ensure_transactions_service("NewTransaction")?;
 
         if self.requested_transactions.contains(&tx) {
             // Ignore duplicate announcements.
@@ -379,3 +560,35 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chainstate::ban_score::BanScore;
+
+    use super::*;
+
+    #[test]
+    fn unexpected_mempool_response() {
+        assert_eq!(
+            MempoolSyncState::Requested { max_txs: 10 }.expect_mempool_response().unwrap(),
+            10
+        );
+
+        for state in [MempoolSyncState::NotRequested, MempoolSyncState::Done] {
+            let err = state.expect_mempool_response().unwrap_err();
+            assert!(matches!(
+                err,
+                P2pError::ProtocolError(ProtocolError::UnexpectedMessageForState { .. })
+            ));
+            assert_eq!(
+                err.ban_score(),
+                P2pError::ProtocolError(ProtocolError::UnexpectedMessage("".to_owned()))
+                    .ban_score()
+            );
+
+            let msg = err.to_string();
+            assert!(msg.contains(state.name()), "{msg}");
+            assert!(msg.contains("MempoolResponse"), "{msg}");
+        }
+    }
+}
@@ -72,7 +72,10 @@ pub async fn handle_message_processing_result(
 
                 let (sender, receiver) = oneshot_nofail::channel();
                 peer_mgr_event_sender.send(PeerManagerEvent::AdjustPeerScore(
-                    peer_id, ban_score, sender,
+                    peer_id,
+                    ban_score,
+                    e.variant_name(),
+                    sender,
                 ))?;
                 receiver.await?.or_else(|e| match e {
                     P2pError::PeerError(PeerError::PeerDoesntExist) => Ok(()),
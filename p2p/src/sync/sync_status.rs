@@ -13,19 +13,50 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use common::primitives::time::Time;
+use std::collections::BTreeMap;
+
+use common::{
+    chain::Block,
+    primitives::{time::Time, BlockHeight, Id},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::types::peer_id::PeerId;
 
 /// Certain information about the current state of block syncing that other parts of p2p
 /// (namely, the peer manager) may be interested in.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PeerBlockSyncStatus {
     pub expecting_blocks_since: Option<Time>,
+    /// The number of received headers for which the blocks haven't been requested yet.
+    pub pending_header_count: usize,
+    /// Blocks that have been requested from the peer but not received yet.
+    pub requested_blocks: Vec<Id<Block>>,
+    /// The height of the last block received from the peer.
+    pub last_received_block_height: Option<BlockHeight>,
 }
 
 impl PeerBlockSyncStatus {
     pub fn new() -> Self {
         Self {
             expecting_blocks_since: None,
+            pending_header_count: 0,
+            requested_blocks: Vec::new(),
+            last_received_block_height: None,
         }
     }
 }
+
+/// A point-in-time view of the block sync state across all connected peers, for debugging.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, rpc_description::HasValueHint)]
+pub struct SyncStateSnapshot {
+    /// The number of received headers for which the blocks haven't been requested yet,
+    /// per peer.
+    pub pending_header_count_per_peer: BTreeMap<PeerId, usize>,
+    /// Blocks that have been requested from peers but not received yet.
+    pub in_flight_block_ids: Vec<Id<Block>>,
+    /// The height of the block that was received last, from any peer.
+    pub last_received_block_height: BlockHeight,
+    /// The earliest time since which we've been waiting for blocks from some peer.
+    pub stalling_since: Option<Time>,
+}
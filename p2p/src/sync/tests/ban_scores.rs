@@ -61,7 +61,7 @@ async fn peer_handle_result() {
     logging::spawn_in_current_span(async move {
         while let Some(event) = peer_mgr_event_receiver.recv().await {
             match event {
-                crate::PeerManagerEvent::AdjustPeerScore(_, _, score) => {
+                crate::PeerManagerEvent::AdjustPeerScore(_, _, _, score) => {
                     score.send(Ok(()));
                 }
                 e => unreachable!("Unexpected event: {e:?}"),
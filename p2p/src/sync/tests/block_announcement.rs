@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use chainstate::{ban_score::BanScore, BlockError, ChainstateError, CheckBlockError};
 use chainstate_test_framework::TestFramework;
@@ -522,17 +522,30 @@ async fn send_headers_connected_to_previously_sent_headers(#[case] seed: Seed) {
                 max_addr_list_response_address_count: Default::default(),
                 msg_max_locator_count: Default::default(),
                 max_message_size: Default::default(),
+                max_mempool_sync_txs: Default::default(),
                 max_peer_tx_announcements: Default::default(),
+                max_block_response_message_size: Default::default(),
+                max_header_list_message_size: Default::default(),
+                max_addr_list_response_message_size: Default::default(),
+                max_transaction_response_message_size: Default::default(),
+                max_repeated_headers_served: Default::default(),
+                repeated_headers_window: Default::default(),
+                max_throttled_header_responses: Default::default(),
             },
 
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
+            socks5_proxy_auth: Default::default(),
             disable_noise: Default::default(),
+            reserved_nodes_only: Default::default(),
+            enable_mempool_sync: Default::default(),
+            enable_port_mapping: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
             ban_config: Default::default(),
             outbound_connection_timeout: Default::default(),
+            inbound_connection_timeout: Default::default(),
             ping_check_period: Default::default(),
             ping_timeout: Default::default(),
             peer_handshake_timeout: Default::default(),
@@ -541,6 +554,13 @@ async fn send_headers_connected_to_previously_sent_headers(#[case] seed: Seed) {
             allow_discover_private_ips: Default::default(),
             user_agent: mintlayer_core_user_agent(),
             sync_stalling_timeout: Default::default(),
+            fork_alert_threshold: Default::default(),
+            fork_alert_duration: Default::default(),
+            max_mempool_tx_broadcasts_per_peer: Default::default(),
+            max_buffered_block_response_bytes: Default::default(),
+            max_in_flight_block_responses_per_peer: Default::default(),
+            max_low_work_blocks_in_flight: Default::default(),
+            peer_admission_config: Default::default(),
             peer_manager_config: Default::default(),
         });
 
@@ -625,17 +645,30 @@ async fn send_headers_connected_to_block_which_is_being_downloaded(#[case] seed:
                 max_addr_list_response_address_count: Default::default(),
                 msg_max_locator_count: Default::default(),
                 max_message_size: Default::default(),
+                max_mempool_sync_txs: Default::default(),
                 max_peer_tx_announcements: Default::default(),
+                max_block_response_message_size: Default::default(),
+                max_header_list_message_size: Default::default(),
+                max_addr_list_response_message_size: Default::default(),
+                max_transaction_response_message_size: Default::default(),
+                max_repeated_headers_served: Default::default(),
+                repeated_headers_window: Default::default(),
+                max_throttled_header_responses: Default::default(),
             },
 
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
+            socks5_proxy_auth: Default::default(),
             disable_noise: Default::default(),
+            reserved_nodes_only: Default::default(),
+            enable_mempool_sync: Default::default(),
+            enable_port_mapping: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
             ban_config: Default::default(),
             outbound_connection_timeout: Default::default(),
+            inbound_connection_timeout: Default::default(),
             ping_check_period: Default::default(),
             ping_timeout: Default::default(),
             peer_handshake_timeout: Default::default(),
@@ -644,6 +677,13 @@ async fn send_headers_connected_to_block_which_is_being_downloaded(#[case] seed:
             allow_discover_private_ips: Default::default(),
             user_agent: mintlayer_core_user_agent(),
             sync_stalling_timeout: Default::default(),
+            fork_alert_threshold: Default::default(),
+            fork_alert_duration: Default::default(),
+            max_mempool_tx_broadcasts_per_peer: Default::default(),
+            max_buffered_block_response_bytes: Default::default(),
+            max_in_flight_block_responses_per_peer: Default::default(),
+            max_low_work_blocks_in_flight: Default::default(),
+            peer_admission_config: Default::default(),
             peer_manager_config: Default::default(),
         });
 
@@ -725,17 +765,30 @@ async fn correct_pending_headers_update(#[case] seed: Seed) {
                 max_addr_list_response_address_count: Default::default(),
                 msg_max_locator_count: Default::default(),
                 max_message_size: Default::default(),
+                max_mempool_sync_txs: Default::default(),
                 max_peer_tx_announcements: Default::default(),
+                max_block_response_message_size: Default::default(),
+                max_header_list_message_size: Default::default(),
+                max_addr_list_response_message_size: Default::default(),
+                max_transaction_response_message_size: Default::default(),
+                max_repeated_headers_served: Default::default(),
+                repeated_headers_window: Default::default(),
+                max_throttled_header_responses: Default::default(),
             },
 
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
+            socks5_proxy_auth: Default::default(),
             disable_noise: Default::default(),
+            reserved_nodes_only: Default::default(),
+            enable_mempool_sync: Default::default(),
+            enable_port_mapping: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
             ban_config: Default::default(),
             outbound_connection_timeout: Default::default(),
+            inbound_connection_timeout: Default::default(),
             ping_check_period: Default::default(),
             ping_timeout: Default::default(),
             peer_handshake_timeout: Default::default(),
@@ -744,6 +797,13 @@ async fn correct_pending_headers_update(#[case] seed: Seed) {
             allow_discover_private_ips: Default::default(),
             user_agent: mintlayer_core_user_agent(),
             sync_stalling_timeout: Default::default(),
+            fork_alert_threshold: Default::default(),
+            fork_alert_duration: Default::default(),
+            max_mempool_tx_broadcasts_per_peer: Default::default(),
+            max_buffered_block_response_bytes: Default::default(),
+            max_in_flight_block_responses_per_peer: Default::default(),
+            max_low_work_blocks_in_flight: Default::default(),
+            peer_admission_config: Default::default(),
             peer_manager_config: Default::default(),
         });
 
@@ -921,3 +981,141 @@ async fn correct_best_sent_block_update(#[case] seed: Seed) {
     })
     .await;
 }
+
+// Two peers announce competing branches, the second one has more work. The blocks of the branch
+// with more work should be requested first, the other branch should only be requested once
+// the first one has been downloaded.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn higher_work_branch_requested_first(#[case] seed: Seed) {
+    for_each_protocol_version(|protocol_version| async move {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let chain_config = Arc::new(create_unit_test_config());
+        let time_getter = BasicTestTimeGetter::new();
+        let p2p_config = Arc::new(P2pConfig {
+            max_low_work_blocks_in_flight: 0.into(),
+            ..test_p2p_config()
+        });
+
+        let common_block = make_new_blocks(
+            &chain_config,
+            None,
+            &time_getter.get_time_getter(),
+            1,
+            &mut rng,
+        )
+        .pop()
+        .unwrap();
+        let low_work_branch = make_new_blocks(
+            &chain_config,
+            Some(&common_block),
+            &time_getter.get_time_getter(),
+            1,
+            &mut rng,
+        );
+        let high_work_branch = make_new_blocks(
+            &chain_config,
+            Some(&common_block),
+            &time_getter.get_time_getter(),
+            2,
+            &mut rng,
+        );
+        let branch_headers = |branch: &[Block]| {
+            std::iter::once(&common_block)
+                .chain(branch)
+                .map(|block| block.header().clone())
+                .collect::<Vec<_>>()
+        };
+        let block_ids = |branch: &[Block]| branch.iter().map(|block| block.get_id()).collect();
+
+        let mut node = TestNode::builder(protocol_version)
+            .with_chain_config(Arc::clone(&chain_config))
+            .with_p2p_config(p2p_config)
+            .with_time_getter(time_getter.get_time_getter())
+            .build()
+            .await;
+
+        let common_block_peer = node.connect_peer(PeerId::new(), protocol_version).await;
+        let low_work_peer = node.connect_peer(PeerId::new(), protocol_version).await;
+        let high_work_peer = node.connect_peer(PeerId::new(), protocol_version).await;
+
+        // The common block is in flight from the first peer while the branches are announced
+        common_block_peer.send_headers(vec![common_block.header().clone()]).await;
+        let (sent_to, message) = node.get_sent_block_sync_message().await;
+        assert_eq!(sent_to, common_block_peer.get_id());
+        assert_eq!(
+            message,
+            BlockSyncMessage::BlockListRequest(BlockListRequest::new(vec![common_block.get_id()]))
+        );
+
+        low_work_peer.send_headers(branch_headers(&low_work_branch)).await;
+        high_work_peer.send_headers(branch_headers(&high_work_branch)).await;
+        node.assert_no_sync_message().await;
+
+        common_block_peer
+            .send_block_sync_message(BlockSyncMessage::BlockResponse(BlockResponse::new(
+                common_block.clone(),
+            )))
+            .await;
+
+        // Only the branch with more work is requested; the peers send their messages
+        // independently, so the order isn't checked.
+        let messages = get_sent_messages_by_peer(&mut node, 2).await;
+        assert!(matches!(
+            messages[&common_block_peer.get_id()],
+            BlockSyncMessage::HeaderListRequest(_)
+        ));
+        assert_eq!(
+            messages[&high_work_peer.get_id()],
+            BlockSyncMessage::BlockListRequest(BlockListRequest::new(block_ids(&high_work_branch)))
+        );
+        node.assert_no_sync_message().await;
+
+        for block in &high_work_branch {
+            high_work_peer
+                .send_block_sync_message(BlockSyncMessage::BlockResponse(BlockResponse::new(
+                    block.clone(),
+                )))
+                .await;
+        }
+
+        // The other branch is requested once the branch with more work has been downloaded
+        let messages = get_sent_messages_by_peer(&mut node, 2).await;
+        assert!(matches!(
+            messages[&high_work_peer.get_id()],
+            BlockSyncMessage::HeaderListRequest(_)
+        ));
+        assert_eq!(
+            messages[&low_work_peer.get_id()],
+            BlockSyncMessage::BlockListRequest(BlockListRequest::new(block_ids(&low_work_branch)))
+        );
+
+        let best_block_id =
+            node.chainstate().call(|cs| cs.get_best_block_id().unwrap()).await.unwrap();
+        assert_eq!(
+            best_block_id,
+            high_work_branch.last().unwrap().get_id().into()
+        );
+
+        node.assert_no_error().await;
+
+        node.join_subsystem_manager().await;
+    })
+    .await;
+}
+
+async fn get_sent_messages_by_peer(
+    node: &mut TestNode,
+    count: usize,
+) -> BTreeMap<PeerId, BlockSyncMessage> {
+    let mut messages = BTreeMap::new();
+    for _ in 0..count {
+        let (sent_to, message) = node.get_sent_block_sync_message().await;
+        assert!(messages.insert(sent_to, message).is_none());
+    }
+    messages
+}
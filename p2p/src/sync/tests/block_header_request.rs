@@ -0,0 +1,286 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{iter, sync::Arc};
+
+use chainstate::ban_score::BanScore;
+use chainstate_test_framework::TestFramework;
+use common::{chain::config::create_unit_test_config, primitives::Idable};
+use test_utils::random::Seed;
+
+use crate::{
+    error::ProtocolError,
+    message::{BlockHeaderRequest, BlockHeaderResponse, BlockSyncMessage},
+    protocol::{can_request_block_headers, ProtocolVersion, SupportedProtocolVersion},
+    sync::tests::helpers::TestNode,
+    test_helpers::{for_each_protocol_version, test_p2p_config},
+    types::peer_id::PeerId,
+    P2pError,
+};
+
+const PROTOCOL_VERSION: SupportedProtocolVersion = SupportedProtocolVersion::V7;
+
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn valid_request(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+    let protocol_version: ProtocolVersion = PROTOCOL_VERSION.into();
+
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut tf = TestFramework::builder(&mut rng)
+        .with_chain_config(chain_config.as_ref().clone())
+        .build();
+    let blocks: Vec<_> = (0..3)
+        .map(|_| tf.make_block_builder().build_and_process(&mut rng).unwrap().unwrap())
+        .collect();
+
+    let mut node = TestNode::builder(protocol_version)
+        .with_chain_config(chain_config)
+        .with_chainstate(tf.into_chainstate())
+        .build()
+        .await;
+
+    let peer = node.connect_peer(PeerId::new(), protocol_version).await;
+
+    // Request a single header from the middle of the chain.
+    let block = &blocks[1];
+    peer.send_block_sync_message(BlockSyncMessage::BlockHeaderRequest(
+        BlockHeaderRequest::new(vec![*block.block_id()]),
+    ))
+    .await;
+
+    let (sent_to, message) = node.get_sent_block_sync_message().await;
+    assert_eq!(peer.get_id(), sent_to);
+    assert_eq!(
+        message,
+        BlockSyncMessage::BlockHeaderResponse(BlockHeaderResponse::new(vec![block
+            .block_header()
+            .clone()]))
+    );
+
+    // The headers are returned in the requested order.
+    let ids = vec![*blocks[2].block_id(), *blocks[0].block_id()];
+    peer.send_block_sync_message(BlockSyncMessage::BlockHeaderRequest(
+        BlockHeaderRequest::new(ids),
+    ))
+    .await;
+
+    let (sent_to, message) = node.get_sent_block_sync_message().await;
+    assert_eq!(peer.get_id(), sent_to);
+    assert_eq!(
+        message,
+        BlockSyncMessage::BlockHeaderResponse(BlockHeaderResponse::new(vec![
+            blocks[2].block_header().clone(),
+            blocks[0].block_header().clone(),
+        ]))
+    );
+
+    node.assert_no_error().await;
+    node.assert_no_peer_manager_event().await;
+
+    node.join_subsystem_manager().await;
+}
+
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn unknown_header(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+    let protocol_version: ProtocolVersion = PROTOCOL_VERSION.into();
+
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut tf = TestFramework::builder(&mut rng)
+        .with_chain_config(chain_config.as_ref().clone())
+        .build();
+    let known_block = tf.make_block_builder().build_and_process(&mut rng).unwrap().unwrap();
+    // A block that is never processed by the node.
+    let unknown_block = tf.make_block_builder().build(&mut rng);
+
+    let mut node = TestNode::builder(protocol_version)
+        .with_chain_config(chain_config)
+        .with_chainstate(tf.into_chainstate())
+        .build()
+        .await;
+
+    let peer = node.connect_peer(PeerId::new(), protocol_version).await;
+
+    let expected_score =
+        P2pError::ProtocolError(ProtocolError::UnknownBlockRequested(unknown_block.get_id()))
+            .ban_score();
+    assert!(expected_score > 0);
+    peer.send_block_sync_message(BlockSyncMessage::BlockHeaderRequest(
+        BlockHeaderRequest::new(vec![*known_block.block_id(), unknown_block.get_id()]),
+    ))
+    .await;
+
+    let (adjusted_peer, score) = node.receive_adjust_peer_score_event().await;
+    assert_eq!(peer.get_id(), adjusted_peer);
+    assert_eq!(score, expected_score);
+    node.assert_no_sync_message().await;
+
+    node.join_subsystem_manager().await;
+}
+
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn max_header_count_in_request_exceeded(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+    let protocol_version: ProtocolVersion = PROTOCOL_VERSION.into();
+
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut tf = TestFramework::builder(&mut rng)
+        .with_chain_config(chain_config.as_ref().clone())
+        .build();
+    let block_index = tf.make_block_builder().build_and_process(&mut rng).unwrap().unwrap();
+
+    let p2p_config = Arc::new(test_p2p_config());
+    let mut node = TestNode::builder(protocol_version)
+        .with_chain_config(chain_config)
+        .with_p2p_config(Arc::clone(&p2p_config))
+        .with_chainstate(tf.into_chainstate())
+        .build()
+        .await;
+
+    let peer = node.connect_peer(PeerId::new(), protocol_version).await;
+
+    let ids = iter::repeat(*block_index.block_id())
+        .take(*p2p_config.protocol_config.max_request_blocks_count + 1)
+        .collect();
+    peer.send_block_sync_message(BlockSyncMessage::BlockHeaderRequest(
+        BlockHeaderRequest::new(ids),
+    ))
+    .await;
+
+    let (adjusted_peer, score) = node.receive_adjust_peer_score_event().await;
+    assert_eq!(peer.get_id(), adjusted_peer);
+    assert_eq!(
+        score,
+        P2pError::ProtocolError(ProtocolError::BlocksRequestLimitExceeded(0, 0)).ban_score()
+    );
+    node.assert_no_sync_message().await;
+
+    node.join_subsystem_manager().await;
+}
+
+// This node never requests individual headers, so a response is always unsolicited.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn unsolicited_response(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+    let protocol_version: ProtocolVersion = PROTOCOL_VERSION.into();
+
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut tf = TestFramework::builder(&mut rng)
+        .with_chain_config(chain_config.as_ref().clone())
+        .build();
+    let block_index = tf.make_block_builder().build_and_process(&mut rng).unwrap().unwrap();
+
+    let mut node = TestNode::builder(protocol_version)
+        .with_chain_config(chain_config)
+        .with_chainstate(tf.into_chainstate())
+        .build()
+        .await;
+
+    let peer = node.connect_peer(PeerId::new(), protocol_version).await;
+
+    peer.send_block_sync_message(BlockSyncMessage::BlockHeaderResponse(
+        BlockHeaderResponse::new(vec![block_index.block_header().clone()]),
+    ))
+    .await;
+
+    let (adjusted_peer, score) = node.receive_adjust_peer_score_event().await;
+    assert_eq!(peer.get_id(), adjusted_peer);
+    assert_eq!(
+        score,
+        P2pError::ProtocolError(ProtocolError::UnexpectedMessageForState {
+            expected_state: String::new(),
+            received_message: String::new(),
+        })
+        .ban_score()
+    );
+    node.assert_no_sync_message().await;
+
+    node.join_subsystem_manager().await;
+}
+
+// Headers can only be requested by id from peers with a protocol version that supports it.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn request_depends_on_protocol_version(#[case] seed: Seed) {
+    for_each_protocol_version(|protocol_version| async move {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let chain_config = Arc::new(create_unit_test_config());
+        let mut tf = TestFramework::builder(&mut rng)
+            .with_chain_config(chain_config.as_ref().clone())
+            .build();
+        let block_index = tf.make_block_builder().build_and_process(&mut rng).unwrap().unwrap();
+
+        let mut node = TestNode::builder(protocol_version)
+            .with_chain_config(chain_config)
+            .with_chainstate(tf.into_chainstate())
+            .build()
+            .await;
+
+        let peer = node.connect_peer(PeerId::new(), protocol_version).await;
+
+        peer.send_block_sync_message(BlockSyncMessage::BlockHeaderRequest(
+            BlockHeaderRequest::new(vec![*block_index.block_id()]),
+        ))
+        .await;
+
+        let supported_version: Option<SupportedProtocolVersion> = protocol_version.into();
+        if can_request_block_headers(supported_version.unwrap()) {
+            let (sent_to, message) = node.get_sent_block_sync_message().await;
+            assert_eq!(peer.get_id(), sent_to);
+            assert_eq!(
+                message,
+                BlockSyncMessage::BlockHeaderResponse(BlockHeaderResponse::new(vec![block_index
+                    .block_header()
+                    .clone()]))
+            );
+            node.assert_no_error().await;
+        } else {
+            let (adjusted_peer, score) = node.receive_adjust_peer_score_event().await;
+            assert_eq!(peer.get_id(), adjusted_peer);
+            assert_eq!(
+                score,
+                P2pError::ProtocolError(ProtocolError::UnexpectedMessageForState {
+                    expected_state: String::new(),
+                    received_message: String::new(),
+                })
+                .ban_score()
+            );
+            node.assert_no_sync_message().await;
+        }
+
+        node.join_subsystem_manager().await;
+    })
+    .await;
+}
@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{iter, sync::Arc};
+use std::{collections::BTreeMap, iter, sync::Arc};
 
 use chainstate::{ban_score::BanScore, BlockSource};
 use chainstate_test_framework::TestFramework;
@@ -24,6 +24,7 @@ use common::{
 use logging::log;
 use p2p_test_utils::create_n_blocks;
 use randomness::Rng;
+use serialization::Encode;
 use test_utils::{random::Seed, BasicTestTimeGetter};
 
 use crate::{
@@ -32,7 +33,7 @@ use crate::{
     sync::tests::helpers::{make_new_blocks, TestNode},
     test_helpers::{for_each_protocol_version, test_p2p_config},
     types::peer_id::PeerId,
-    P2pError,
+    P2pConfig, P2pError,
 };
 
 #[tracing::instrument(skip(seed))]
@@ -307,3 +308,78 @@ async fn request_same_block_while_downloading(#[case] seed: Seed) {
     })
     .await;
 }
+
+// Two peers request many blocks at once; the node sends the blocks to them in turns and
+// the total size of the buffered block responses doesn't exceed the limit.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fair_block_serving(#[case] seed: Seed) {
+    for_each_protocol_version(|protocol_version| async move {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let chain_config = Arc::new(create_unit_test_config());
+        let mut tf = TestFramework::builder(&mut rng)
+            .with_chain_config(chain_config.as_ref().clone())
+            .build();
+        let num_blocks = 100;
+        let blocks = create_n_blocks(&mut rng, &mut tf, num_blocks);
+        for block in blocks.clone() {
+            tf.process_block(block, BlockSource::Local).unwrap().unwrap();
+        }
+
+        let max_block_size = blocks.iter().map(|b| b.encoded_size()).max().unwrap();
+        let max_buffered_bytes = 3 * max_block_size;
+        let p2p_config = Arc::new(P2pConfig {
+            max_buffered_block_response_bytes: max_buffered_bytes.into(),
+            ..test_p2p_config()
+        });
+        let mut node = TestNode::builder(protocol_version)
+            .with_chain_config(chain_config)
+            .with_p2p_config(p2p_config)
+            .with_chainstate(tf.into_chainstate())
+            .build()
+            .await;
+
+        let peer1 = node.connect_peer(PeerId::new(), protocol_version).await;
+        let peer2 = node.connect_peer(PeerId::new(), protocol_version).await;
+
+        let ids: Vec<_> = blocks.iter().map(|b| b.get_id()).collect();
+        for peer in [&peer1, &peer2] {
+            peer.send_block_sync_message(BlockSyncMessage::BlockListRequest(
+                BlockListRequest::new(ids.clone()),
+            ))
+            .await;
+        }
+
+        let mut received_by = Vec::new();
+        let mut next_block_idx = BTreeMap::from([(peer1.get_id(), 0), (peer2.get_id(), 0)]);
+        for _ in 0..2 * num_blocks {
+            let (sent_to, message) = node.get_sent_block_sync_message().await;
+            let idx = next_block_idx.get_mut(&sent_to).unwrap();
+            assert_eq!(
+                message,
+                BlockSyncMessage::BlockResponse(BlockResponse::new(blocks[*idx].clone()))
+            );
+            *idx += 1;
+            received_by.push(sent_to);
+        }
+
+        // Neither peer has received all its blocks before the other one has started receiving
+        for (peer, other_peer) in [(&peer1, &peer2), (&peer2, &peer1)] {
+            let last_idx = received_by.iter().rposition(|id| *id == peer.get_id()).unwrap();
+            let first_other_idx =
+                received_by.iter().position(|id| *id == other_peer.get_id()).unwrap();
+            assert!(first_other_idx < last_idx);
+        }
+        assert!(node.max_buffered_block_response_bytes() <= max_buffered_bytes);
+
+        node.assert_no_error().await;
+        node.assert_no_peer_manager_event().await;
+
+        node.join_subsystem_manager().await;
+    })
+    .await;
+}
@@ -37,6 +37,7 @@ use crate::{
     ban_config::BanConfig,
     error::ProtocolError,
     message::{BlockListRequest, BlockResponse, BlockSyncMessage, HeaderList, HeaderListRequest},
+    protocol::ProtocolConfig,
     sync::tests::helpers::{
         make_new_blocks, make_new_top_blocks_return_headers, PeerManagerEventDesc, TestNode,
     },
@@ -141,6 +142,130 @@ async fn valid_response(#[case] seed: Seed) {
     .await;
 }
 
+// Send more headers than can be requested at once, then send the requested blocks one by one;
+// the sync status reported to the peer manager must reflect the blocks in flight and
+// the headers that are still pending.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn sync_status_reflects_in_flight_blocks(#[case] seed: Seed) {
+    for_each_protocol_version(|protocol_version| async move {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let chain_config = Arc::new(create_unit_test_config());
+        let mut tf = TestFramework::builder(&mut rng)
+            .with_chain_config(chain_config.as_ref().clone())
+            .build();
+        let blocks = create_n_blocks(&mut rng, &mut tf, 5);
+        let ids: Vec<_> = blocks.iter().map(|b| b.get_id()).collect();
+
+        let p2p_config = Arc::new(P2pConfig {
+            protocol_config: ProtocolConfig {
+                max_request_blocks_count: 2.into(),
+
+                msg_header_count_limit: Default::default(),
+                max_addr_list_response_address_count: Default::default(),
+                msg_max_locator_count: Default::default(),
+                max_message_size: Default::default(),
+                max_mempool_sync_txs: Default::default(),
+                max_peer_tx_announcements: Default::default(),
+                max_block_response_message_size: Default::default(),
+                max_header_list_message_size: Default::default(),
+                max_addr_list_response_message_size: Default::default(),
+                max_transaction_response_message_size: Default::default(),
+                max_repeated_headers_served: Default::default(),
+                repeated_headers_window: Default::default(),
+                max_throttled_header_responses: Default::default(),
+            },
+
+            bind_addresses: Default::default(),
+            socks5_proxy: Default::default(),
+            socks5_proxy_auth: Default::default(),
+            disable_noise: Default::default(),
+            reserved_nodes_only: Default::default(),
+            enable_mempool_sync: Default::default(),
+            enable_port_mapping: Default::default(),
+            boot_nodes: Default::default(),
+            reserved_nodes: Default::default(),
+            whitelisted_addresses: Default::default(),
+            ban_config: Default::default(),
+            outbound_connection_timeout: Default::default(),
+            inbound_connection_timeout: Default::default(),
+            ping_check_period: Default::default(),
+            ping_timeout: Default::default(),
+            peer_handshake_timeout: Default::default(),
+            max_clock_diff: Default::default(),
+            node_type: Default::default(),
+            allow_discover_private_ips: Default::default(),
+            user_agent: mintlayer_core_user_agent(),
+            sync_stalling_timeout: Default::default(),
+            fork_alert_threshold: Default::default(),
+            fork_alert_duration: Default::default(),
+            max_mempool_tx_broadcasts_per_peer: Default::default(),
+            max_buffered_block_response_bytes: Default::default(),
+            max_in_flight_block_responses_per_peer: Default::default(),
+            max_low_work_blocks_in_flight: Default::default(),
+            peer_admission_config: Default::default(),
+            peer_manager_config: Default::default(),
+        });
+        let mut node = TestNode::builder(protocol_version)
+            .with_chain_config(chain_config)
+            .with_p2p_config(p2p_config)
+            .with_chainstate(tf.into_chainstate())
+            .build()
+            .await;
+
+        let peer = node.connect_peer(PeerId::new(), protocol_version).await;
+
+        let headers = blocks.iter().map(|b| b.header().clone()).collect();
+        peer.send_block_sync_message(BlockSyncMessage::HeaderList(HeaderList::new(headers)))
+            .await;
+
+        let (sent_to, message) = node.get_sent_block_sync_message().await;
+        assert_eq!(peer.get_id(), sent_to);
+        assert_eq!(
+            message,
+            BlockSyncMessage::BlockListRequest(BlockListRequest::new(ids[0..2].to_vec()))
+        );
+
+        let (peer_id, status) = node.receive_block_sync_status_update().await;
+        assert_eq!(peer_id, peer.get_id());
+        assert_eq!(status.pending_header_count, 3);
+        assert_eq!(status.requested_blocks, ids[0..2]);
+        assert_eq!(status.last_received_block_height, None);
+        assert!(status.expecting_blocks_since.is_some());
+
+        peer.send_block_sync_message(BlockSyncMessage::BlockResponse(BlockResponse::new(
+            blocks[0].clone(),
+        )))
+        .await;
+
+        let (_, status) = node.receive_block_sync_status_update().await;
+        assert_eq!(status.pending_header_count, 3);
+        assert_eq!(status.requested_blocks, ids[1..2]);
+        assert_eq!(status.last_received_block_height, Some(1.into()));
+
+        // Once all requested blocks are received, the next portion of pending headers
+        // is requested.
+        peer.send_block_sync_message(BlockSyncMessage::BlockResponse(BlockResponse::new(
+            blocks[1].clone(),
+        )))
+        .await;
+
+        let (_, status) = node.receive_block_sync_status_update().await;
+        assert_eq!(status.pending_header_count, 1);
+        assert_eq!(status.requested_blocks, ids[2..4]);
+        assert_eq!(status.last_received_block_height, Some(2.into()));
+
+        node.assert_no_error().await;
+
+        node.join_subsystem_manager().await;
+    })
+    .await;
+}
+
 #[tracing::instrument(skip(seed))]
 #[rstest::rstest]
 #[trace]
@@ -161,11 +286,16 @@ async fn block_responses_in_wrong_order(#[case] seed: Seed) {
 
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
+            socks5_proxy_auth: Default::default(),
             disable_noise: Default::default(),
+            reserved_nodes_only: Default::default(),
+            enable_mempool_sync: Default::default(),
+            enable_port_mapping: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
             outbound_connection_timeout: Default::default(),
+            inbound_connection_timeout: Default::default(),
             ping_check_period: Default::default(),
             ping_timeout: Default::default(),
             peer_handshake_timeout: Default::default(),
@@ -174,6 +304,13 @@ async fn block_responses_in_wrong_order(#[case] seed: Seed) {
             allow_discover_private_ips: Default::default(),
             user_agent: "test".try_into().unwrap(),
             sync_stalling_timeout: Default::default(),
+            fork_alert_threshold: Default::default(),
+            fork_alert_duration: Default::default(),
+            max_mempool_tx_broadcasts_per_peer: Default::default(),
+            max_buffered_block_response_bytes: Default::default(),
+            max_in_flight_block_responses_per_peer: Default::default(),
+            max_low_work_blocks_in_flight: Default::default(),
+            peer_admission_config: Default::default(),
             peer_manager_config: Default::default(),
             protocol_config: Default::default(),
         });
@@ -242,7 +379,13 @@ async fn block_responses_in_wrong_order(#[case] seed: Seed) {
                             ]
                             .into_iter(),
                         ),
-                        |event| matches!(event, PeerManagerEvent::PeerBlockSyncStatusUpdate { .. }),
+                        |event| {
+                            matches!(
+                                event,
+                                PeerManagerEvent::PeerBlockSyncStatusUpdate { .. }
+                                    | PeerManagerEvent::ReportedBestBlock { .. }
+                            )
+                        },
                     )
                     .await;
                 } else {
@@ -286,15 +429,27 @@ async fn disconnect(#[case] seed: Seed) {
 
         let p2p_config = Arc::new(P2pConfig {
             sync_stalling_timeout: Duration::from_millis(100).into(),
+            fork_alert_threshold: Default::default(),
+            fork_alert_duration: Default::default(),
+            max_mempool_tx_broadcasts_per_peer: Default::default(),
+            max_buffered_block_response_bytes: Default::default(),
+            max_in_flight_block_responses_per_peer: Default::default(),
+            max_low_work_blocks_in_flight: Default::default(),
+            peer_admission_config: Default::default(),
 
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
+            socks5_proxy_auth: Default::default(),
             disable_noise: Default::default(),
+            reserved_nodes_only: Default::default(),
+            enable_mempool_sync: Default::default(),
+            enable_port_mapping: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
             ban_config: Default::default(),
             outbound_connection_timeout: Default::default(),
+            inbound_connection_timeout: Default::default(),
             ping_check_period: Default::default(),
             ping_timeout: Default::default(),
             peer_handshake_timeout: Default::default(),
@@ -353,15 +508,27 @@ async fn slow_response(#[case] seed: Seed) {
         let chain_config = Arc::new(create_unit_test_config());
         let p2p_config = Arc::new(P2pConfig {
             sync_stalling_timeout: STALLING_TIMEOUT.into(),
+            fork_alert_threshold: Default::default(),
+            fork_alert_duration: Default::default(),
+            max_mempool_tx_broadcasts_per_peer: Default::default(),
+            max_buffered_block_response_bytes: Default::default(),
+            max_in_flight_block_responses_per_peer: Default::default(),
+            max_low_work_blocks_in_flight: Default::default(),
+            peer_admission_config: Default::default(),
 
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
+            socks5_proxy_auth: Default::default(),
             disable_noise: Default::default(),
+            reserved_nodes_only: Default::default(),
+            enable_mempool_sync: Default::default(),
+            enable_port_mapping: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
             ban_config: Default::default(),
             outbound_connection_timeout: Default::default(),
+            inbound_connection_timeout: Default::default(),
             ping_check_period: Default::default(),
             ping_timeout: Default::default(),
             peer_handshake_timeout: Default::default(),
@@ -429,6 +596,102 @@ async fn slow_response(#[case] seed: Seed) {
     .await;
 }
 
+// Two peers announce the same block; it should only be requested from the first one.
+// Once the first request times out, the block is requested from the second peer; the late
+// response from the first peer should be accepted without a penalty.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn block_in_flight_from_another_peer(#[case] seed: Seed) {
+    for_each_protocol_version(|protocol_version| async move {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let time_getter = BasicTestTimeGetter::new();
+
+        const STALLING_TIMEOUT: Duration = Duration::from_secs(10);
+
+        let chain_config = Arc::new(create_unit_test_config());
+        let p2p_config = Arc::new(P2pConfig {
+            sync_stalling_timeout: STALLING_TIMEOUT.into(),
+            ..test_p2p_config()
+        });
+
+        let mut tf = TestFramework::builder(&mut rng)
+            .with_chain_config(chain_config.as_ref().clone())
+            .build();
+        let block = tf
+            .make_block_builder()
+            .with_parent(chain_config.genesis_block_id())
+            .build(&mut rng);
+
+        let mut node = TestNode::builder(protocol_version)
+            .with_chain_config(chain_config)
+            .with_p2p_config(Arc::clone(&p2p_config))
+            .with_time_getter(time_getter.get_time_getter())
+            .with_chainstate(tf.into_chainstate())
+            .build()
+            .await;
+
+        let slow_peer = node.connect_peer(PeerId::new(), protocol_version).await;
+        let fast_peer = node.connect_peer(PeerId::new(), protocol_version).await;
+
+        slow_peer.send_headers(vec![block.header().clone()]).await;
+
+        let (sent_to, message) = node.get_sent_block_sync_message().await;
+        assert_eq!(sent_to, slow_peer.get_id());
+        assert_eq!(
+            message,
+            BlockSyncMessage::BlockListRequest(BlockListRequest::new(vec![block.get_id()]))
+        );
+
+        // The block is already in flight, so it's not requested from the second peer.
+        fast_peer.send_headers(vec![block.header().clone()]).await;
+        node.assert_no_sync_message().await;
+
+        // The block is requested from the second peer once the first request times out,
+        // but before the first peer is considered stalling.
+        time_getter.advance_time(STALLING_TIMEOUT / 2);
+
+        let (sent_to, message) = node.get_sent_block_sync_message().await;
+        assert_eq!(sent_to, fast_peer.get_id());
+        assert_eq!(
+            message,
+            BlockSyncMessage::BlockListRequest(BlockListRequest::new(vec![block.get_id()]))
+        );
+
+        fast_peer
+            .send_block_sync_message(BlockSyncMessage::BlockResponse(BlockResponse::new(
+                block.clone(),
+            )))
+            .await;
+
+        let (sent_to, message) = node.get_sent_block_sync_message().await;
+        assert_eq!(sent_to, fast_peer.get_id());
+        assert!(matches!(message, BlockSyncMessage::HeaderListRequest(_)));
+        let best_block_id =
+            node.chainstate().call(|cs| cs.get_best_block_id().unwrap()).await.unwrap();
+        assert_eq!(best_block_id, block.get_id().into());
+
+        // The late response from the first peer is not penalized.
+        slow_peer
+            .send_block_sync_message(BlockSyncMessage::BlockResponse(BlockResponse::new(
+                block.clone(),
+            )))
+            .await;
+
+        let (sent_to, message) = node.get_sent_block_sync_message().await;
+        assert_eq!(sent_to, slow_peer.get_id());
+        assert!(matches!(message, BlockSyncMessage::HeaderListRequest(_)));
+
+        node.assert_no_error().await;
+        node.assert_no_peer_manager_event().await;
+
+        node.join_subsystem_manager().await;
+    })
+    .await;
+}
+
 // Check that requesting a previously invalidated block is handled correctly.
 // The test scenario:
 // 1) Send some blocks to the peer.
@@ -21,14 +21,16 @@ use common::{
     chain::config::create_unit_test_config,
     primitives::{user_agent::mintlayer_core_user_agent, Idable},
 };
+use randomness::Rng;
 use test_utils::{random::Seed, BasicTestTimeGetter};
 
 use crate::{
     config::P2pConfig,
     error::ProtocolError,
     message::{BlockSyncMessage, HeaderList, HeaderListRequest},
+    protocol::ProtocolConfig,
     sync::tests::helpers::TestNode,
-    test_helpers::for_each_protocol_version,
+    test_helpers::{for_each_protocol_version, test_p2p_config},
     types::peer_id::PeerId,
     P2pError,
 };
@@ -134,15 +136,27 @@ async fn respond_with_empty_header_list_when_in_ibd() {
         let chain_config = Arc::new(create_unit_test_config());
         let p2p_config = Arc::new(P2pConfig {
             sync_stalling_timeout: STALLING_TIMEOUT.into(),
+            fork_alert_threshold: Default::default(),
+            fork_alert_duration: Default::default(),
+            max_mempool_tx_broadcasts_per_peer: Default::default(),
+            max_buffered_block_response_bytes: Default::default(),
+            max_in_flight_block_responses_per_peer: Default::default(),
+            max_low_work_blocks_in_flight: Default::default(),
+            peer_admission_config: Default::default(),
 
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
+            socks5_proxy_auth: Default::default(),
             disable_noise: Default::default(),
+            reserved_nodes_only: Default::default(),
+            enable_mempool_sync: Default::default(),
+            enable_port_mapping: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
             ban_config: Default::default(),
             outbound_connection_timeout: Default::default(),
+            inbound_connection_timeout: Default::default(),
             ping_check_period: Default::default(),
             ping_timeout: Default::default(),
             peer_handshake_timeout: Default::default(),
@@ -189,3 +203,142 @@ async fn respond_with_empty_header_list_when_in_ibd() {
     })
     .await;
 }
+
+fn header_throttling_p2p_config() -> P2pConfig {
+    P2pConfig {
+        protocol_config: ProtocolConfig {
+            msg_header_count_limit: 8.into(),
+            max_repeated_headers_served: 16.into(),
+            max_throttled_header_responses: 2.into(),
+            ..Default::default()
+        },
+        ..test_p2p_config()
+    }
+}
+
+// A peer that keeps requesting the same headers without ever requesting blocks gets
+// progressively smaller responses and is eventually penalized.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn repeated_requests_throttled(#[case] seed: Seed) {
+    for_each_protocol_version(|protocol_version| async move {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let chain_config = Arc::new(create_unit_test_config());
+        let mut tf = TestFramework::builder(&mut rng)
+            .with_chain_config(chain_config.as_ref().clone())
+            .build();
+        for _ in 0..20 {
+            tf.make_block_builder().build_and_process(&mut rng).unwrap().unwrap();
+        }
+
+        let mut node = TestNode::builder(protocol_version)
+            .with_chain_config(chain_config)
+            .with_p2p_config(Arc::new(header_throttling_p2p_config()))
+            .with_chainstate(tf.into_chainstate())
+            .build()
+            .await;
+
+        let peer = node.connect_peer(PeerId::new(), protocol_version).await;
+        let locator = node.get_locator_from_height(0.into()).await;
+
+        // The first response plus the budget of 16 repeated headers, then the halved responses.
+        let mut first_response = None;
+        for expected_header_count in [8, 8, 8, 4, 2] {
+            peer.send_block_sync_message(BlockSyncMessage::HeaderListRequest(
+                HeaderListRequest::new(locator.clone()),
+            ))
+            .await;
+
+            let (sent_to, message) = node.get_sent_block_sync_message().await;
+            assert_eq!(peer.get_id(), sent_to);
+            let headers = match message {
+                BlockSyncMessage::HeaderList(l) => l.into_headers(),
+                m => panic!("Unexpected message: {m:?}"),
+            };
+            assert_eq!(headers.len(), expected_header_count);
+
+            // The same headers are sent each time, the throttled responses are truncated.
+            let first_response = first_response.get_or_insert_with(|| headers.clone());
+            assert_eq!(&first_response[..headers.len()], headers.as_slice());
+        }
+        node.assert_no_error().await;
+
+        peer.send_block_sync_message(BlockSyncMessage::HeaderListRequest(HeaderListRequest::new(
+            locator,
+        )))
+        .await;
+
+        let (adjusted_peer, score) = node.receive_adjust_peer_score_event().await;
+        assert_eq!(peer.get_id(), adjusted_peer);
+        assert_eq!(
+            score,
+            P2pError::ProtocolError(ProtocolError::ExcessiveHeaderRequests).ban_score()
+        );
+        node.assert_no_sync_message().await;
+
+        node.join_subsystem_manager().await;
+    })
+    .await;
+}
+
+// A peer whose header requests advance never hits the budget, no matter how many
+// headers it gets.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn advancing_requests_not_throttled(#[case] seed: Seed) {
+    for_each_protocol_version(|protocol_version| async move {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let chain_config = Arc::new(create_unit_test_config());
+        let mut tf = TestFramework::builder(&mut rng)
+            .with_chain_config(chain_config.as_ref().clone())
+            .build();
+        let block_count: u64 = rng.gen_range(50..100);
+        for _ in 0..block_count {
+            tf.make_block_builder().build_and_process(&mut rng).unwrap().unwrap();
+        }
+
+        let mut node = TestNode::builder(protocol_version)
+            .with_chain_config(chain_config)
+            .with_p2p_config(Arc::new(header_throttling_p2p_config()))
+            .with_chainstate(tf.into_chainstate())
+            .build()
+            .await;
+
+        let peer = node.connect_peer(PeerId::new(), protocol_version).await;
+
+        let mut height = 0;
+        loop {
+            let locator = node.get_locator_from_height(height.into()).await;
+            peer.send_block_sync_message(BlockSyncMessage::HeaderListRequest(
+                HeaderListRequest::new(locator),
+            ))
+            .await;
+
+            let (sent_to, message) = node.get_sent_block_sync_message().await;
+            assert_eq!(peer.get_id(), sent_to);
+            let headers = match message {
+                BlockSyncMessage::HeaderList(l) => l.into_headers(),
+                m => panic!("Unexpected message: {m:?}"),
+            };
+            assert_eq!(headers.len() as u64, std::cmp::min(8, block_count - height));
+
+            if headers.is_empty() {
+                break;
+            }
+            height += headers.len() as u64;
+        }
+        node.assert_no_error().await;
+        node.assert_no_peer_manager_event().await;
+
+        node.join_subsystem_manager().await;
+    })
+    .await;
+}
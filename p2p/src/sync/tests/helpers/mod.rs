@@ -13,7 +13,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{collections::BTreeSet, panic, sync::Arc, time::Duration};
+use std::{
+    collections::BTreeSet,
+    panic,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use async_trait::async_trait;
 use tokio::{
@@ -50,16 +58,20 @@ use networking::transport::TcpTransportSocket;
 use p2p_test_utils::{expect_future_val, expect_no_recv, expect_recv, SHORT_TIMEOUT};
 use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress};
 use randomness::Rng;
+use serialization::Encode;
 use subsystem::{ManagerJoinHandle, ShutdownTrigger};
 use test_utils::random::Seed;
 use utils::atomics::SeqCstAtomicBool;
 use utils_networking::IpOrSocketAddress;
 
 use crate::{
-    message::{BlockSyncMessage, HeaderList, TransactionSyncMessage},
+    message::{BlockResponse, BlockSyncMessage, HeaderList, TransactionSyncMessage},
     net::types::SyncingEvent,
     protocol::{choose_common_protocol_version, ProtocolVersion},
-    sync::{subscribe_to_new_tip, Observer, SyncManager},
+    sync::{
+        block_serving::BufferedBlockResponse, subscribe_to_new_tip,
+        sync_status::PeerBlockSyncStatus, Observer, SyncManager,
+    },
     test_helpers::test_p2p_config,
     types::peer_id::PeerId,
     MessagingService, NetworkingService, P2pConfig, P2pError, P2pEventHandler, PeerManagerEvent,
@@ -68,6 +80,10 @@ use crate::{
 
 pub mod test_node_group;
 
+/// A message sent by the sync manager; block responses are accompanied by the guard that keeps
+/// them buffered until the message is received by the test.
+type SentBlockSyncMessage = (PeerId, BlockSyncMessage, Option<TrackedBlockResponse>);
+
 /// A wrapper over other ends of the sync manager channels that simulates a test node.
 ///
 /// Provides methods for manipulating and observing the sync manager state.
@@ -77,8 +93,9 @@ pub struct TestNode {
     p2p_config: Arc<P2pConfig>,
     peer_manager_event_receiver: UnboundedReceiver<PeerManagerEvent>,
     syncing_event_sender: UnboundedSender<SyncingEvent>,
-    block_sync_msg_receiver: UnboundedReceiver<(PeerId, BlockSyncMessage)>,
+    block_sync_msg_receiver: UnboundedReceiver<SentBlockSyncMessage>,
     transaction_sync_msg_receiver: UnboundedReceiver<(PeerId, TransactionSyncMessage)>,
+    buffered_block_responses: Arc<BufferedBlockResponseStats>,
     error_receiver: UnboundedReceiver<P2pError>,
     sync_manager_handle: JoinHandle<()>,
     shutdown_trigger: ShutdownTrigger,
@@ -118,9 +135,11 @@ impl TestNode {
         let (transaction_sync_msg_sender, transaction_sync_msg_receiver) =
             mpsc::unbounded_channel();
         let (syncing_event_sender, syncing_event_receiver) = mpsc::unbounded_channel();
+        let buffered_block_responses = Arc::new(BufferedBlockResponseStats::default());
         let messaging_handle = MessagingHandleMock {
             block_sync_msg_sender,
             transaction_sync_msg_sender,
+            buffered_block_responses: Arc::clone(&buffered_block_responses),
         };
         let syncing_event_receiver_mock = SyncingEventReceiverMock {
             events_receiver: syncing_event_receiver,
@@ -158,6 +177,7 @@ impl TestNode {
             syncing_event_sender,
             block_sync_msg_receiver,
             transaction_sync_msg_receiver,
+            buffered_block_responses,
             error_receiver,
             sync_manager_handle,
             shutdown_trigger,
@@ -192,6 +212,25 @@ impl TestNode {
         &mut self,
         peer_id: PeerId,
         protocol_version: ProtocolVersion,
+    ) -> TestPeer {
+        self.try_connect_peer_impl(peer_id, protocol_version, true)
+    }
+
+    /// Same as `try_connect_peer`, but the connection is reported as outbound.
+    #[must_use]
+    pub fn try_connect_outbound_peer(
+        &mut self,
+        peer_id: PeerId,
+        protocol_version: ProtocolVersion,
+    ) -> TestPeer {
+        self.try_connect_peer_impl(peer_id, protocol_version, false)
+    }
+
+    fn try_connect_peer_impl(
+        &mut self,
+        peer_id: PeerId,
+        protocol_version: ProtocolVersion,
+        inbound: bool,
     ) -> TestPeer {
         let (block_sync_msg_sender, block_sync_msg_receiver) = mpsc::channel(20);
         let (transaction_sync_msg_sender, transaction_sync_msg_receiver) = mpsc::channel(20);
@@ -202,6 +241,7 @@ impl TestNode {
                 peer_id,
                 common_services: (*self.p2p_config.node_type).into(),
                 protocol_version: common_protocol_version,
+                inbound,
                 block_sync_msg_receiver,
                 transaction_sync_msg_receiver,
             })
@@ -232,17 +272,24 @@ impl TestNode {
     /// Get a message that was sent from the node's sync manager by reading it from
     /// the channel
     pub async fn get_sent_block_sync_message(&mut self) -> (PeerId, BlockSyncMessage) {
-        expect_recv!(self.block_sync_msg_receiver)
+        let (peer_id, message, _) = expect_recv!(self.block_sync_msg_receiver);
+        (peer_id, message)
     }
 
     pub fn try_get_sent_block_sync_message(&mut self) -> Option<(PeerId, BlockSyncMessage)> {
         match self.block_sync_msg_receiver.try_recv() {
-            Ok(message) => Some(message),
+            Ok((peer_id, message, _)) => Some((peer_id, message)),
             Err(mpsc::error::TryRecvError::Empty) => None,
             Err(mpsc::error::TryRecvError::Disconnected) => panic!("Failed to receive event"),
         }
     }
 
+    /// The maximum total size of the block responses that were sent by the sync manager,
+    /// but not yet received by the test.
+    pub fn max_buffered_block_response_bytes(&self) -> usize {
+        self.buffered_block_responses.max.load(Ordering::SeqCst)
+    }
+
     pub async fn get_sent_transaction_sync_message(&mut self) -> (PeerId, TransactionSyncMessage) {
         expect_recv!(self.transaction_sync_msg_receiver)
     }
@@ -253,16 +300,17 @@ impl TestNode {
     }
 
     /// Expect an `AdjustPeerScore` event from the peer manager.
-    /// PeerBlockSyncStatusUpdate events are ignored.
+    /// PeerBlockSyncStatusUpdate and ReportedBestBlock events are ignored.
     pub async fn receive_adjust_peer_score_event(&mut self) -> (PeerId, u32) {
         let future = async {
             loop {
                 match self.peer_manager_event_receiver.recv().await.unwrap() {
-                    PeerManagerEvent::AdjustPeerScore(peer, score, sender) => {
+                    PeerManagerEvent::AdjustPeerScore(peer, score, _, sender) => {
                         sender.send(Ok(()));
                         break (peer, score);
                     }
-                    PeerManagerEvent::PeerBlockSyncStatusUpdate { .. } => {}
+                    PeerManagerEvent::PeerBlockSyncStatusUpdate { .. }
+                    | PeerManagerEvent::ReportedBestBlock { .. } => {}
                     e => panic!("Expected peer score adjustment, received: {e:?}"),
                 }
             }
@@ -272,7 +320,7 @@ impl TestNode {
     }
 
     /// Expect a `Disconnect` event from the peer manager.
-    /// PeerBlockSyncStatusUpdate events are ignored.
+    /// PeerBlockSyncStatusUpdate and ReportedBestBlock events are ignored.
     pub async fn receive_disconnect_peer_event(&mut self, id: PeerId) {
         let future = async {
             loop {
@@ -282,7 +330,8 @@ impl TestNode {
                         sender.send(Ok(()));
                         break;
                     }
-                    PeerManagerEvent::PeerBlockSyncStatusUpdate { .. } => {}
+                    PeerManagerEvent::PeerBlockSyncStatusUpdate { .. }
+                    | PeerManagerEvent::ReportedBestBlock { .. } => {}
                     e => panic!("Expected PeerManagerEvent::Disconnect, received: {e:?}"),
                 }
             }
@@ -291,14 +340,47 @@ impl TestNode {
         expect_future_val!(future);
     }
 
+    /// Expect a `PeerBlockSyncStatusUpdate` event from the peer manager.
+    /// NewTipReceived, NewChainstateTip and ReportedBestBlock events are ignored.
+    pub async fn receive_block_sync_status_update(&mut self) -> (PeerId, PeerBlockSyncStatus) {
+        let future = async {
+            loop {
+                match self.peer_manager_event_receiver.recv().await.unwrap() {
+                    PeerManagerEvent::PeerBlockSyncStatusUpdate {
+                        peer_id,
+                        new_status,
+                    } => {
+                        break (peer_id, new_status);
+                    }
+                    PeerManagerEvent::NewTipReceived { .. }
+                    | PeerManagerEvent::NewChainstateTip(_)
+                    | PeerManagerEvent::ReportedBestBlock { .. } => {}
+                    e => panic!(
+                        "Expected PeerManagerEvent::PeerBlockSyncStatusUpdate, received: {e:?}"
+                    ),
+                }
+            }
+        };
+
+        expect_future_val!(future)
+    }
+
+    /// Expect the specified events from the peer manager.
+    /// ReportedBestBlock events are ignored unless they are explicitly expected.
     pub async fn receive_peer_manager_events(
         &mut self,
         mut events: BTreeSet<PeerManagerEventDesc>,
     ) {
         while !events.is_empty() {
             let event = expect_recv!(self.peer_manager_event_receiver);
+            let event_desc: PeerManagerEventDesc = (&event).into();
+            if matches!(event_desc, PeerManagerEventDesc::ReportedBestBlock { .. })
+                && !events.contains(&event_desc)
+            {
+                continue;
+            }
             assert!(
-                events.remove(&(&event).into()),
+                events.remove(&event_desc),
                 "Unexpected peer manager event: {event:?}"
             );
         }
@@ -350,7 +432,9 @@ impl TestNode {
                     | PeerManagerEvent::GetPeerCount(_)
                     | PeerManagerEvent::GetBindAddresses(_)
                     | PeerManagerEvent::GetConnectedPeers(_)
-                    | PeerManagerEvent::AdjustPeerScore(_, _, _)
+                    | PeerManagerEvent::GetSyncSnapshot(_)
+                    | PeerManagerEvent::GetPossibleFork(_)
+                    | PeerManagerEvent::AdjustPeerScore(_, _, _, _)
                     | PeerManagerEvent::GetReserved(_)
                     | PeerManagerEvent::AddReserved(_, _)
                     | PeerManagerEvent::RemoveReserved(_, _)
@@ -358,7 +442,11 @@ impl TestNode {
                     | PeerManagerEvent::Ban(_, _, _)
                     | PeerManagerEvent::Unban(_, _)
                     | PeerManagerEvent::ListDiscouraged(_)
+                    | PeerManagerEvent::GetMisbehaviorLog(_, _, _)
+                    | PeerManagerEvent::GetMisbehavingPeers(_, _)
+                    | PeerManagerEvent::GetPeerBanScore(_, _)
                     | PeerManagerEvent::EnableNetworking { .. }
+                    | PeerManagerEvent::PortMappingAddressChanged(_)
                     | PeerManagerEvent::GenericQuery(_)
                     | PeerManagerEvent::GenericMut(_) => {
                         panic!("Unexpected peer manager event: {peer_event:?}");
@@ -366,7 +454,8 @@ impl TestNode {
                     PeerManagerEvent::NewTipReceived { .. }
                     | PeerManagerEvent::NewChainstateTip(_)
                     | PeerManagerEvent::NewValidTransactionReceived { .. }
-                    | PeerManagerEvent::PeerBlockSyncStatusUpdate { .. } => {
+                    | PeerManagerEvent::PeerBlockSyncStatusUpdate { .. }
+                    | PeerManagerEvent::ReportedBestBlock { .. } => {
                         // Ignored
                     }
                 }
@@ -615,6 +704,8 @@ pub enum PeerManagerEventDesc {
     GetPeerCount,
     GetBindAddresses,
     GetConnectedPeers,
+    GetSyncSnapshot,
+    GetPossibleFork,
     AdjustPeerScore {
         peer_id: PeerId,
         score: u32,
@@ -635,6 +726,10 @@ pub enum PeerManagerEventDesc {
         // values must be easy to predict. Currently, PeerBlockSyncStatus only contains a Time
         // value, which may be hard to predict, depending on the test.
     },
+    ReportedBestBlock {
+        peer_id: PeerId,
+        block_id: Id<Block>,
+    },
     GetReserved,
     AddReserved(IpOrSocketAddress),
     RemoveReserved(IpOrSocketAddress),
@@ -642,9 +737,18 @@ pub enum PeerManagerEventDesc {
     Ban(BannableAddress, Duration),
     Unban(BannableAddress),
     ListDiscouraged,
+    GetMisbehaviorLog {
+        limit: usize,
+        peer_filter: Option<PeerId>,
+    },
+    GetMisbehavingPeers {
+        min_score: u32,
+    },
+    GetPeerBanScore(PeerId),
     EnableNetworking {
         enable: bool,
     },
+    PortMappingAddressChanged(Option<SocketAddress>),
     GenericQuery,
     GenericMut,
 }
@@ -659,7 +763,9 @@ impl From<&PeerManagerEvent> for PeerManagerEventDesc {
             PeerManagerEvent::GetPeerCount(_) => PeerManagerEventDesc::GetPeerCount,
             PeerManagerEvent::GetBindAddresses(_) => PeerManagerEventDesc::GetBindAddresses,
             PeerManagerEvent::GetConnectedPeers(_) => PeerManagerEventDesc::GetConnectedPeers,
-            PeerManagerEvent::AdjustPeerScore(peer_id, score, _) => {
+            PeerManagerEvent::GetSyncSnapshot(_) => PeerManagerEventDesc::GetSyncSnapshot,
+            PeerManagerEvent::GetPossibleFork(_) => PeerManagerEventDesc::GetPossibleFork,
+            PeerManagerEvent::AdjustPeerScore(peer_id, score, _, _) => {
                 PeerManagerEventDesc::AdjustPeerScore {
                     peer_id: *peer_id,
                     score: *score,
@@ -684,6 +790,14 @@ impl From<&PeerManagerEvent> for PeerManagerEventDesc {
                 peer_id,
                 new_status: _,
             } => PeerManagerEventDesc::PeerBlockSyncStatusUpdate { peer_id: *peer_id },
+            PeerManagerEvent::ReportedBestBlock {
+                peer_id,
+                block_id,
+                local_tip: _,
+            } => PeerManagerEventDesc::ReportedBestBlock {
+                peer_id: *peer_id,
+                block_id: *block_id,
+            },
             PeerManagerEvent::GetReserved(_) => PeerManagerEventDesc::GetReserved,
             PeerManagerEvent::AddReserved(addr, _) => {
                 PeerManagerEventDesc::AddReserved(addr.clone())
@@ -695,10 +809,27 @@ impl From<&PeerManagerEvent> for PeerManagerEventDesc {
             PeerManagerEvent::Ban(addr, duration, _) => PeerManagerEventDesc::Ban(*addr, *duration),
             PeerManagerEvent::Unban(addr, _) => PeerManagerEventDesc::Unban(*addr),
             PeerManagerEvent::ListDiscouraged(_) => PeerManagerEventDesc::ListDiscouraged,
+            PeerManagerEvent::GetMisbehaviorLog(limit, peer_filter, _) => {
+                PeerManagerEventDesc::GetMisbehaviorLog {
+                    limit: *limit,
+                    peer_filter: *peer_filter,
+                }
+            }
+            PeerManagerEvent::GetMisbehavingPeers(min_score, _) => {
+                PeerManagerEventDesc::GetMisbehavingPeers {
+                    min_score: *min_score,
+                }
+            }
+            PeerManagerEvent::GetPeerBanScore(peer_id, _) => {
+                PeerManagerEventDesc::GetPeerBanScore(*peer_id)
+            }
             PeerManagerEvent::EnableNetworking {
                 enable,
                 response_sender: _,
             } => PeerManagerEventDesc::EnableNetworking { enable: *enable },
+            PeerManagerEvent::PortMappingAddressChanged(address) => {
+                PeerManagerEventDesc::PortMappingAddressChanged(*address)
+            }
             PeerManagerEvent::GenericQuery(_) => PeerManagerEventDesc::GenericQuery,
             PeerManagerEvent::GenericMut(_) => PeerManagerEventDesc::GenericMut,
         }
@@ -739,15 +870,56 @@ impl NetworkingService for NetworkingServiceStub {
     }
 }
 
+#[derive(Default)]
+struct BufferedBlockResponseStats {
+    current: AtomicUsize,
+    max: AtomicUsize,
+}
+
+/// A block response that is counted as buffered until dropped.
+struct TrackedBlockResponse {
+    _guard: BufferedBlockResponse,
+    size: usize,
+    stats: Arc<BufferedBlockResponseStats>,
+}
+
+impl Drop for TrackedBlockResponse {
+    fn drop(&mut self) {
+        self.stats.current.fetch_sub(self.size, Ordering::SeqCst);
+    }
+}
+
 #[derive(Clone)]
 struct MessagingHandleMock {
-    block_sync_msg_sender: UnboundedSender<(PeerId, BlockSyncMessage)>,
+    block_sync_msg_sender: UnboundedSender<SentBlockSyncMessage>,
     transaction_sync_msg_sender: UnboundedSender<(PeerId, TransactionSyncMessage)>,
+    buffered_block_responses: Arc<BufferedBlockResponseStats>,
 }
 
 impl MessagingService for MessagingHandleMock {
     fn send_block_sync_message(&mut self, peer: PeerId, message: BlockSyncMessage) -> Result<()> {
-        self.block_sync_msg_sender.send((peer, message)).unwrap();
+        self.block_sync_msg_sender.send((peer, message, None)).unwrap();
+        Ok(())
+    }
+
+    fn send_block_response(
+        &mut self,
+        peer: PeerId,
+        response: BlockResponse,
+        guard: BufferedBlockResponse,
+    ) -> Result<()> {
+        let size = response.block().encoded_size();
+        let stats = &self.buffered_block_responses;
+        let current = stats.current.fetch_add(size, Ordering::SeqCst) + size;
+        stats.max.fetch_max(current, Ordering::SeqCst);
+
+        let tracked = TrackedBlockResponse {
+            _guard: guard,
+            size,
+            stats: Arc::clone(stats),
+        };
+        let message = BlockSyncMessage::BlockResponse(response);
+        self.block_sync_msg_sender.send((peer, message, Some(tracked))).unwrap();
         Ok(())
     }
 
@@ -114,7 +114,7 @@ impl TestNodeGroup {
         let (receiver_peer_id_msg, future_idx, _) =
             time::timeout(LONG_TIMEOUT, combined_future).await.unwrap();
         let sender_node_idx = sync_msg_receivers[future_idx].0;
-        let (receiver_peer_id, msg) = receiver_peer_id_msg.unwrap();
+        let (receiver_peer_id, msg, _) = receiver_peer_id_msg.unwrap();
         let receiver_node_idx = self.node_idx_by_peer_id(receiver_peer_id);
 
         BlockSyncMessageWithNodeIdx {
@@ -229,7 +229,7 @@ impl TestNodeGroup {
             loop {
                 self.assert_no_peer_manager_events_if_needed();
 
-                let (dest_peer_id, sync_msg) =
+                let (dest_peer_id, sync_msg, _) =
                     self.data[i].node.block_sync_msg_receiver.recv().await.unwrap();
 
                 // Send sync messages between peers
@@ -316,7 +316,9 @@ impl TestNodeGroup {
                         | PeerManagerEvent::GetPeerCount(_)
                         | PeerManagerEvent::GetBindAddresses(_)
                         | PeerManagerEvent::GetConnectedPeers(_)
-                        | PeerManagerEvent::AdjustPeerScore(_, _, _)
+                        | PeerManagerEvent::GetSyncSnapshot(_)
+                        | PeerManagerEvent::GetPossibleFork(_)
+                        | PeerManagerEvent::AdjustPeerScore(_, _, _, _)
                         | PeerManagerEvent::GetReserved(_)
                         | PeerManagerEvent::AddReserved(_, _)
                         | PeerManagerEvent::RemoveReserved(_, _)
@@ -324,7 +326,11 @@ impl TestNodeGroup {
                         | PeerManagerEvent::Ban(_, _, _)
                         | PeerManagerEvent::Unban(_, _)
                         | PeerManagerEvent::ListDiscouraged(_)
+                        | PeerManagerEvent::GetMisbehaviorLog(_, _, _)
+                        | PeerManagerEvent::GetMisbehavingPeers(_, _)
+                        | PeerManagerEvent::GetPeerBanScore(_, _)
                         | PeerManagerEvent::EnableNetworking { .. }
+                        | PeerManagerEvent::PortMappingAddressChanged(_)
                         | PeerManagerEvent::GenericQuery(_)
                         | PeerManagerEvent::GenericMut(_) => {
                             panic!("Unexpected peer manager event: {peer_event:?}");
@@ -332,7 +338,8 @@ impl TestNodeGroup {
                         PeerManagerEvent::NewTipReceived { .. }
                         | PeerManagerEvent::NewChainstateTip(_)
                         | PeerManagerEvent::NewValidTransactionReceived { .. }
-                        | PeerManagerEvent::PeerBlockSyncStatusUpdate { .. } => {
+                        | PeerManagerEvent::PeerBlockSyncStatusUpdate { .. }
+                        | PeerManagerEvent::ReportedBestBlock { .. } => {
                             // Ignored
                         }
                     }
@@ -0,0 +1,379 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use chainstate::ban_score::BanScore;
+use chainstate_test_framework::TestFramework;
+use common::{
+    chain::{
+        config::create_unit_test_config, output_value::OutputValue,
+        signature::inputsig::InputWitness, ChainConfig, GenBlock, OutPointSourceId,
+        SignedTransaction, Transaction, TxInput, TxOutput,
+    },
+    primitives::{Amount, Id, Idable, H256},
+};
+use mempool::tx_origin::RemoteTxOrigin;
+use test_utils::random::Seed;
+
+use crate::{
+    error::ProtocolError,
+    message::{BlockSyncMessage, MempoolRequest, TransactionSyncMessage},
+    protocol::{can_sync_mempool, ProtocolConfig, ProtocolVersion, SupportedProtocolVersion},
+    sync::tests::helpers::TestNode,
+    test_helpers::{for_each_protocol_version, test_p2p_config},
+    types::peer_id::PeerId,
+    P2pConfig, P2pError,
+};
+
+fn mempool_sync_p2p_config(max_mempool_sync_txs: u32) -> P2pConfig {
+    P2pConfig {
+        enable_mempool_sync: Some(true),
+        enable_port_mapping: Default::default(),
+        protocol_config: ProtocolConfig {
+            max_mempool_sync_txs: max_mempool_sync_txs.into(),
+            ..Default::default()
+        },
+        ..test_p2p_config()
+    }
+}
+
+// Create a node that is not in the initial block download.
+async fn make_node(
+    rng: &mut (impl randomness::Rng + randomness::CryptoRng),
+    chain_config: Arc<ChainConfig>,
+    p2p_config: P2pConfig,
+    protocol_version: ProtocolVersion,
+) -> TestNode {
+    let mut tf = TestFramework::builder(rng)
+        .with_chain_config(chain_config.as_ref().clone())
+        .build();
+    // Process a block to finish the initial block download.
+    tf.make_block_builder().build_and_process(rng).unwrap().unwrap();
+
+    TestNode::builder(protocol_version)
+        .with_chain_config(chain_config)
+        .with_p2p_config(Arc::new(p2p_config))
+        .with_chainstate(tf.into_chainstate())
+        .build()
+        .await
+}
+
+// The mempool is requested from outbound peers only, and only if they support it.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn mempool_requested_from_outbound_peer(#[case] seed: Seed) {
+    for_each_protocol_version(|protocol_version| async move {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let chain_config = Arc::new(create_unit_test_config());
+        let mut node = make_node(
+            &mut rng,
+            chain_config,
+            mempool_sync_p2p_config(123),
+            protocol_version,
+        )
+        .await;
+
+        let _inbound_peer = node.connect_peer(PeerId::new(), protocol_version).await;
+        node.assert_no_sync_message().await;
+
+        let outbound_peer = node.try_connect_outbound_peer(PeerId::new(), protocol_version);
+        let (sent_to, message) = node.get_sent_block_sync_message().await;
+        assert_eq!(outbound_peer.get_id(), sent_to);
+        assert!(matches!(message, BlockSyncMessage::HeaderListRequest(_)));
+
+        let protocol_version: Option<SupportedProtocolVersion> = protocol_version.into();
+        if can_sync_mempool(protocol_version.unwrap()) {
+            let (sent_to, message) = node.get_sent_transaction_sync_message().await;
+            assert_eq!(outbound_peer.get_id(), sent_to);
+            assert_eq!(
+                message,
+                TransactionSyncMessage::GetMempool(MempoolRequest { max_txs: 123 })
+            );
+        }
+        node.assert_no_sync_message().await;
+        node.assert_no_error().await;
+
+        node.join_subsystem_manager().await;
+    })
+    .await;
+}
+
+// The mempool is not requested if the corresponding option is not enabled.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn mempool_sync_disabled(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+    let protocol_version = SupportedProtocolVersion::V6.into();
+
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut node = make_node(&mut rng, chain_config, test_p2p_config(), protocol_version).await;
+
+    let outbound_peer = node.try_connect_outbound_peer(PeerId::new(), protocol_version);
+    let (sent_to, message) = node.get_sent_block_sync_message().await;
+    assert_eq!(outbound_peer.get_id(), sent_to);
+    assert!(matches!(message, BlockSyncMessage::HeaderListRequest(_)));
+
+    node.assert_no_sync_message().await;
+    node.assert_no_error().await;
+
+    node.join_subsystem_manager().await;
+}
+
+// The node responds to a mempool request with the ids of its best transactions.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn mempool_request(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+    let protocol_version = SupportedProtocolVersion::V6.into();
+
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut node = make_node(
+        &mut rng,
+        Arc::clone(&chain_config),
+        test_p2p_config(),
+        protocol_version,
+    )
+    .await;
+
+    let tx = transaction(chain_config.genesis_block_id());
+    let tx_id = tx.transaction().get_id();
+    let origin = RemoteTxOrigin::new(PeerId::new());
+    let options = mempool::TxOptions::default_for(origin.into());
+    let res = node
+        .mempool()
+        .call_mut(move |m| m.add_transaction_remote(tx, origin, options))
+        .await
+        .unwrap();
+    assert_eq!(res, Ok(mempool::TxStatus::InMempool));
+
+    let peer = node.connect_peer(PeerId::new(), protocol_version).await;
+
+    peer.send_transaction_sync_message(TransactionSyncMessage::GetMempool(MempoolRequest {
+        max_txs: 10,
+    }))
+    .await;
+    let (sent_to, message) = node.get_sent_transaction_sync_message().await;
+    assert_eq!(peer.get_id(), sent_to);
+    assert_eq!(
+        message,
+        TransactionSyncMessage::MempoolResponse(vec![tx_id])
+    );
+
+    // A repeated request is a protocol violation.
+    peer.send_transaction_sync_message(TransactionSyncMessage::GetMempool(MempoolRequest {
+        max_txs: 10,
+    }))
+    .await;
+    let (adjusted_peer, score) = node.receive_adjust_peer_score_event().await;
+    assert_eq!(peer.get_id(), adjusted_peer);
+    assert_eq!(
+        score,
+        P2pError::ProtocolError(ProtocolError::UnexpectedMessageForState {
+            expected_state: String::new(),
+            received_message: String::new(),
+        })
+        .ban_score()
+    );
+    node.assert_no_sync_message().await;
+
+    node.join_subsystem_manager().await;
+}
+
+// The transactions from a mempool response are requested if they are missing.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn mempool_response(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+    let protocol_version = SupportedProtocolVersion::V6.into();
+
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut node = make_node(
+        &mut rng,
+        Arc::clone(&chain_config),
+        mempool_sync_p2p_config(10),
+        protocol_version,
+    )
+    .await;
+
+    let peer = node.try_connect_outbound_peer(PeerId::new(), protocol_version);
+    let (_, message) = node.get_sent_block_sync_message().await;
+    assert!(matches!(message, BlockSyncMessage::HeaderListRequest(_)));
+    let (_, message) = node.get_sent_transaction_sync_message().await;
+    assert_eq!(
+        message,
+        TransactionSyncMessage::GetMempool(MempoolRequest { max_txs: 10 })
+    );
+
+    let tx_id = transaction(chain_config.genesis_block_id()).transaction().get_id();
+    peer.send_transaction_sync_message(TransactionSyncMessage::MempoolResponse(vec![tx_id]))
+        .await;
+
+    let (sent_to, message) = node.get_sent_transaction_sync_message().await;
+    assert_eq!(peer.get_id(), sent_to);
+    assert_eq!(message, TransactionSyncMessage::TransactionRequest(tx_id));
+    node.assert_no_error().await;
+
+    node.join_subsystem_manager().await;
+}
+
+// A mempool response after the mempool sync is done is a protocol violation.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn repeated_mempool_response(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+    let protocol_version = SupportedProtocolVersion::V6.into();
+
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut node = make_node(
+        &mut rng,
+        chain_config,
+        mempool_sync_p2p_config(10),
+        protocol_version,
+    )
+    .await;
+
+    let peer = node.try_connect_outbound_peer(PeerId::new(), protocol_version);
+    let (_, message) = node.get_sent_block_sync_message().await;
+    assert!(matches!(message, BlockSyncMessage::HeaderListRequest(_)));
+    let (_, message) = node.get_sent_transaction_sync_message().await;
+    assert_eq!(
+        message,
+        TransactionSyncMessage::GetMempool(MempoolRequest { max_txs: 10 })
+    );
+
+    peer.send_transaction_sync_message(TransactionSyncMessage::MempoolResponse(Vec::new()))
+        .await;
+    node.assert_no_error().await;
+
+    peer.send_transaction_sync_message(TransactionSyncMessage::MempoolResponse(Vec::new()))
+        .await;
+    let (adjusted_peer, score) = node.receive_adjust_peer_score_event().await;
+    assert_eq!(peer.get_id(), adjusted_peer);
+    assert_eq!(
+        score,
+        P2pError::ProtocolError(ProtocolError::UnexpectedMessageForState {
+            expected_state: String::new(),
+            received_message: String::new(),
+        })
+        .ban_score()
+    );
+    node.assert_no_sync_message().await;
+
+    node.join_subsystem_manager().await;
+}
+
+// A mempool response with more ids than requested is a protocol violation.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn mempool_response_too_many_ids(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+    let protocol_version = SupportedProtocolVersion::V6.into();
+
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut node = make_node(
+        &mut rng,
+        chain_config,
+        mempool_sync_p2p_config(1),
+        protocol_version,
+    )
+    .await;
+
+    let peer = node.try_connect_outbound_peer(PeerId::new(), protocol_version);
+    let (_, message) = node.get_sent_block_sync_message().await;
+    assert!(matches!(message, BlockSyncMessage::HeaderListRequest(_)));
+    let (_, message) = node.get_sent_transaction_sync_message().await;
+    assert_eq!(
+        message,
+        TransactionSyncMessage::GetMempool(MempoolRequest { max_txs: 1 })
+    );
+
+    let ids = vec![
+        Id::<Transaction>::new(H256::random_using(&mut rng)),
+        Id::<Transaction>::new(H256::random_using(&mut rng)),
+    ];
+    peer.send_transaction_sync_message(TransactionSyncMessage::MempoolResponse(ids))
+        .await;
+
+    let (adjusted_peer, score) = node.receive_adjust_peer_score_event().await;
+    assert_eq!(peer.get_id(), adjusted_peer);
+    assert_eq!(
+        score,
+        P2pError::ProtocolError(ProtocolError::TransactionAnnouncementLimitExceeded(0)).ban_score()
+    );
+    node.assert_no_sync_message().await;
+
+    node.join_subsystem_manager().await;
+}
+
+// An unsolicited mempool response is a protocol violation.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn unsolicited_mempool_response(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+    let protocol_version = SupportedProtocolVersion::V6.into();
+    let mut node = TestNode::builder(protocol_version).build().await;
+
+    let peer = node.connect_peer(PeerId::new(), protocol_version).await;
+    let tx_id = Id::<Transaction>::new(H256::random_using(&mut rng));
+    peer.send_transaction_sync_message(TransactionSyncMessage::MempoolResponse(vec![tx_id]))
+        .await;
+
+    let (adjusted_peer, score) = node.receive_adjust_peer_score_event().await;
+    assert_eq!(peer.get_id(), adjusted_peer);
+    assert_eq!(
+        score,
+        P2pError::ProtocolError(ProtocolError::UnexpectedMessageForState {
+            expected_state: String::new(),
+            received_message: String::new(),
+        })
+        .ban_score()
+    );
+    node.assert_no_sync_message().await;
+
+    node.join_subsystem_manager().await;
+}
+
+fn transaction(out_point: Id<GenBlock>) -> SignedTransaction {
+    let tx = Transaction::new(
+        0x00,
+        vec![TxInput::from_utxo(OutPointSourceId::from(out_point), 0)],
+        vec![TxOutput::Burn(OutputValue::Coin(Amount::from_atoms(1)))],
+    )
+    .unwrap();
+    SignedTransaction::new(tx, vec![InputWitness::NoSignature(None)]).unwrap()
+}
@@ -15,11 +15,13 @@
 
 mod ban_scores;
 mod block_announcement;
+mod block_header_request;
 mod block_list_request;
 mod block_response;
 mod header_list_request;
 mod header_list_response;
 pub mod helpers;
+mod mempool_sync;
 mod network_sync;
 mod peer_events;
 mod tx_announcement;
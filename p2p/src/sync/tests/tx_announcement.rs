@@ -27,7 +27,8 @@ use common::{
 };
 use mempool::{
     error::{Error as MempoolError, MempoolPolicyError},
-    tx_origin::RemoteTxOrigin,
+    tx_options::{TxOptionsError, TxOptionsOverrides},
+    tx_origin::{LocalTxOrigin, RemoteTxOrigin},
     FeeRate, MempoolConfig,
 };
 use serialization::Encode;
@@ -36,11 +37,12 @@ use test_utils::{random::Seed, BasicTestTimeGetter};
 use crate::{
     config::NodeType,
     error::ProtocolError,
-    message::{TransactionResponse, TransactionSyncMessage},
-    protocol::ProtocolConfig,
+    message::{MempoolRequest, TransactionResponse, TransactionSyncMessage},
+    protocol::{ProtocolConfig, SupportedProtocolVersion},
     sync::{
         peer::requested_transactions::REQUESTED_TX_EXPIRY_PERIOD,
         tests::helpers::{PeerManagerEventDesc, SyncManagerNotification, TestNode},
+        tx_conflicts::{TX_CONFLICTS_ALLOWANCE, TX_CONFLICT_BAN_SCORE},
     },
     test_helpers::{for_each_protocol_version, test_p2p_config},
     types::peer_id::PeerId,
@@ -155,12 +157,17 @@ async fn no_transaction_service(#[case] seed: Seed) {
 
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
+            socks5_proxy_auth: Default::default(),
             disable_noise: Default::default(),
+            reserved_nodes_only: Default::default(),
+            enable_mempool_sync: Default::default(),
+            enable_port_mapping: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
             ban_config: Default::default(),
             outbound_connection_timeout: Default::default(),
+            inbound_connection_timeout: Default::default(),
             ping_check_period: Default::default(),
             ping_timeout: Default::default(),
             peer_handshake_timeout: Default::default(),
@@ -168,6 +175,13 @@ async fn no_transaction_service(#[case] seed: Seed) {
             allow_discover_private_ips: Default::default(),
             user_agent: "test".try_into().unwrap(),
             sync_stalling_timeout: Default::default(),
+            fork_alert_threshold: Default::default(),
+            fork_alert_duration: Default::default(),
+            max_mempool_tx_broadcasts_per_peer: Default::default(),
+            max_buffered_block_response_bytes: Default::default(),
+            max_in_flight_block_responses_per_peer: Default::default(),
+            max_low_work_blocks_in_flight: Default::default(),
+            peer_admission_config: Default::default(),
             peer_manager_config: Default::default(),
             protocol_config: Default::default(),
         });
@@ -190,7 +204,11 @@ async fn no_transaction_service(#[case] seed: Seed) {
         assert_eq!(peer.get_id(), adjusted_peer);
         assert_eq!(
             score,
-            P2pError::ProtocolError(ProtocolError::UnexpectedMessage("".to_owned())).ban_score()
+            P2pError::ProtocolError(ProtocolError::UnexpectedMessageForState {
+                expected_state: String::new(),
+                received_message: String::new(),
+            })
+            .ban_score()
         );
         node.assert_no_sync_message().await;
 
@@ -220,22 +238,35 @@ async fn too_many_announcements(#[case] seed: Seed) {
         let p2p_config = Arc::new(P2pConfig {
             protocol_config: ProtocolConfig {
                 max_peer_tx_announcements: 1.into(),
+                max_block_response_message_size: Default::default(),
+                max_header_list_message_size: Default::default(),
+                max_addr_list_response_message_size: Default::default(),
+                max_transaction_response_message_size: Default::default(),
+                max_repeated_headers_served: Default::default(),
+                repeated_headers_window: Default::default(),
+                max_throttled_header_responses: Default::default(),
 
                 msg_header_count_limit: Default::default(),
                 max_request_blocks_count: Default::default(),
                 max_addr_list_response_address_count: Default::default(),
                 msg_max_locator_count: Default::default(),
                 max_message_size: Default::default(),
+                max_mempool_sync_txs: Default::default(),
             },
 
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
+            socks5_proxy_auth: Default::default(),
             disable_noise: Default::default(),
+            reserved_nodes_only: Default::default(),
+            enable_mempool_sync: Default::default(),
+            enable_port_mapping: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
             ban_config: Default::default(),
             outbound_connection_timeout: Default::default(),
+            inbound_connection_timeout: Default::default(),
             ping_check_period: Default::default(),
             ping_timeout: Default::default(),
             peer_handshake_timeout: Default::default(),
@@ -244,6 +275,13 @@ async fn too_many_announcements(#[case] seed: Seed) {
             allow_discover_private_ips: Default::default(),
             user_agent: "test".try_into().unwrap(),
             sync_stalling_timeout: Default::default(),
+            fork_alert_threshold: Default::default(),
+            fork_alert_duration: Default::default(),
+            max_mempool_tx_broadcasts_per_peer: Default::default(),
+            max_buffered_block_response_bytes: Default::default(),
+            max_in_flight_block_responses_per_peer: Default::default(),
+            max_low_work_blocks_in_flight: Default::default(),
+            peer_admission_config: Default::default(),
             peer_manager_config: Default::default(),
         });
         let mut node = TestNode::builder(protocol_version)
@@ -444,6 +482,7 @@ async fn valid_transaction_with_fee_below_minimum(#[case] seed: Seed) {
         let p2p_config = Arc::new(test_p2p_config());
         let mempool_config = MempoolConfig {
             min_tx_relay_fee_rate: min_fee_rate.into(),
+            ..Default::default()
         };
         let mut node = TestNode::builder(protocol_version)
             .with_p2p_config(Arc::clone(&p2p_config))
@@ -520,6 +559,73 @@ async fn valid_transaction_with_fee_below_minimum(#[case] seed: Seed) {
     .await;
 }
 
+// A peer relaying transactions that spend an already spent output is not punished for
+// an occasional one, but its score is increased once it does it repeatedly.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn repeated_double_spends(#[case] seed: Seed) {
+    for_each_protocol_version(|protocol_version| async move {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let chain_config = Arc::new(create_unit_test_config());
+        let mut tf = TestFramework::builder(&mut rng)
+            .with_chain_config(chain_config.as_ref().clone())
+            .build();
+        // Spend the genesis output in a block, which also finishes the initial block download.
+        tf.make_block_builder()
+            .add_transaction(transaction(chain_config.genesis_block_id()))
+            .build_and_process(&mut rng)
+            .unwrap()
+            .unwrap();
+
+        let p2p_config = Arc::new(test_p2p_config());
+        let mut node = TestNode::builder(protocol_version)
+            .with_chain_config(Arc::clone(&chain_config))
+            .with_p2p_config(Arc::clone(&p2p_config))
+            .with_chainstate(tf.into_chainstate())
+            .build()
+            .await;
+
+        let peer = node.connect_peer(PeerId::new(), protocol_version).await;
+
+        for offense in 0..=TX_CONFLICTS_ALLOWANCE {
+            // Every transaction spends the genesis output again.
+            let tx = transaction_with_amount(chain_config.genesis_block_id(), 2 + offense as u128);
+            peer.send_transaction_sync_message(TransactionSyncMessage::NewTransaction(
+                tx.transaction().get_id(),
+            ))
+            .await;
+
+            let (sent_to, message) = node.get_sent_transaction_sync_message().await;
+            assert_eq!(peer.get_id(), sent_to);
+            assert_eq!(
+                message,
+                TransactionSyncMessage::TransactionRequest(tx.transaction().get_id())
+            );
+
+            peer.send_transaction_sync_message(TransactionSyncMessage::TransactionResponse(
+                TransactionResponse::Found(tx),
+            ))
+            .await;
+
+            if offense < TX_CONFLICTS_ALLOWANCE {
+                node.assert_no_peer_manager_event().await;
+            } else {
+                node.assert_peer_score_adjustment(peer.get_id(), TX_CONFLICT_BAN_SCORE).await;
+            }
+        }
+
+        node.assert_no_sync_message().await;
+        node.assert_no_error().await;
+
+        node.join_subsystem_manager().await;
+    })
+    .await;
+}
+
 #[tracing::instrument(skip(seed))]
 #[rstest::rstest]
 #[trace]
@@ -542,6 +648,7 @@ async fn transaction_sequence_via_orphan_pool(#[case] seed: Seed) {
             .with_mempool_config(MempoolConfig {
                 min_tx_relay_fee_rate: FeeRate::from_amount_per_kb(Amount::from_atoms(100_000_000))
                     .into(),
+                ..Default::default()
             })
             .with_p2p_config(Arc::clone(&p2p_config))
             .with_chainstate(tf.into_chainstate())
@@ -622,6 +729,91 @@ async fn transaction_sequence_via_orphan_pool(#[case] seed: Seed) {
     .await;
 }
 
+// Check how the relay-related submission options affect the announcement of a local transaction.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy(), false, false)]
+#[case(Seed::from_entropy(), true, false)]
+#[case(Seed::from_entropy(), false, true)]
+#[case(Seed::from_entropy(), true, true)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn local_transaction_relay_options(
+    #[case] seed: Seed,
+    #[case] relay_only: bool,
+    #[case] do_not_relay: bool,
+) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+    let protocol_version = SupportedProtocolVersion::V6.into();
+
+    let chain_config = Arc::new(create_unit_test_config());
+    let mut tf = TestFramework::builder(&mut rng)
+        .with_chain_config(chain_config.as_ref().clone())
+        .build();
+    // Process a block to finish the initial block download.
+    tf.make_block_builder().build_and_process(&mut rng).unwrap().unwrap();
+
+    let mut node = TestNode::builder(protocol_version)
+        .with_chain_config(Arc::clone(&chain_config))
+        .with_chainstate(tf.into_chainstate())
+        .build()
+        .await;
+
+    let peer = node.connect_peer(PeerId::new(), protocol_version).await;
+
+    let tx = transaction(chain_config.genesis_block_id());
+    let tx_id = tx.transaction().get_id();
+    let origin = LocalTxOrigin::P2p;
+    let overrides = TxOptionsOverrides::default()
+        .with_relay_only(relay_only)
+        .with_do_not_relay(do_not_relay);
+    let options = match mempool::TxOptions::default_for(origin.into()).with_overrides(overrides) {
+        Ok(options) => options,
+        Err(err) => {
+            // The options are mutually exclusive.
+            assert!(relay_only && do_not_relay);
+            assert_eq!(err, TxOptionsError::RelayOnlyAndDoNotRelay);
+
+            node.assert_no_sync_message().await;
+            node.join_subsystem_manager().await;
+            return;
+        }
+    };
+    assert!(!(relay_only && do_not_relay));
+
+    node.mempool()
+        .call_mut(move |m| m.add_transaction_local(tx, origin, options))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(node.mempool().call(move |m| m.contains_transaction(&tx_id)).await.unwrap());
+
+    let expect_relay = !do_not_relay;
+    if expect_relay {
+        let (sent_to, message) = node.get_sent_transaction_sync_message().await;
+        assert_eq!(sent_to, peer.get_id());
+        assert_eq!(message, TransactionSyncMessage::NewTransaction(tx_id));
+    }
+
+    // Transactions that shouldn't be relayed are not reported in a mempool response either.
+    peer.send_transaction_sync_message(TransactionSyncMessage::GetMempool(MempoolRequest {
+        max_txs: 10,
+    }))
+    .await;
+    let (sent_to, message) = node.get_sent_transaction_sync_message().await;
+    assert_eq!(sent_to, peer.get_id());
+    let expected_ids = if expect_relay { vec![tx_id] } else { vec![] };
+    assert_eq!(
+        message,
+        TransactionSyncMessage::MempoolResponse(expected_ids)
+    );
+
+    node.assert_no_sync_message().await;
+    node.assert_no_error().await;
+
+    node.join_subsystem_manager().await;
+}
+
 /// Creates a simple transaction.
 fn transaction_with_amount(out_point: Id<GenBlock>, amount_atoms: u128) -> SignedTransaction {
     let tx = Transaction::new(
@@ -0,0 +1,104 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    time::Duration,
+};
+
+use common::{primitives::time::Time, time_getter::TimeGetter};
+
+use crate::types::peer_id::PeerId;
+
+/// The number of double spending or conflicting transactions that a peer may relay
+/// within `TX_CONFLICTS_PERIOD` without being penalized.
+pub const TX_CONFLICTS_ALLOWANCE: usize = 3;
+
+pub const TX_CONFLICTS_PERIOD: Duration = Duration::from_secs(10 * 60);
+
+/// The ban score increment for each conflicting transaction above the allowance.
+pub const TX_CONFLICT_BAN_SCORE: u32 = 5;
+
+/// Keeps track of the double spending and conflicting transactions relayed by the peers.
+///
+/// Honest peers may relay such transactions occasionally, e.g. if a new block arrives while
+/// the transaction is in flight, so only the peers doing it repeatedly are penalized.
+pub struct TxConflictsTracker {
+    time_getter: TimeGetter,
+    /// The times of the recent offenses of each peer
+    offenses: BTreeMap<PeerId, VecDeque<Time>>,
+}
+
+impl TxConflictsTracker {
+    pub fn new(time_getter: TimeGetter) -> Self {
+        Self {
+            time_getter,
+            offenses: BTreeMap::new(),
+        }
+    }
+
+    /// Record an offense by the peer and return the ban score increment for it.
+    pub fn record_offense(&mut self, peer_id: PeerId) -> u32 {
+        let now = self.time_getter.get_time();
+        let period_start = now.saturating_duration_sub(TX_CONFLICTS_PERIOD);
+
+        let offenses = self.offenses.entry(peer_id).or_default();
+        while offenses.front().is_some_and(|time| *time <= period_start) {
+            offenses.pop_front();
+        }
+        offenses.push_back(now);
+
+        if offenses.len() > TX_CONFLICTS_ALLOWANCE {
+            TX_CONFLICT_BAN_SCORE
+        } else {
+            0
+        }
+    }
+
+    pub fn remove_peer(&mut self, peer_id: &PeerId) {
+        self.offenses.remove(peer_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::BasicTestTimeGetter;
+
+    use super::*;
+
+    #[test]
+    fn allowance_per_period() {
+        let time_getter = BasicTestTimeGetter::new();
+        let mut tracker = TxConflictsTracker::new(time_getter.get_time_getter());
+        let peer1 = PeerId::new();
+        let peer2 = PeerId::new();
+
+        for _ in 0..TX_CONFLICTS_ALLOWANCE {
+            assert_eq!(tracker.record_offense(peer1), 0);
+        }
+        assert_eq!(tracker.record_offense(peer1), TX_CONFLICT_BAN_SCORE);
+        assert_eq!(tracker.record_offense(peer1), TX_CONFLICT_BAN_SCORE);
+
+        // The offenses are counted separately for each peer
+        assert_eq!(tracker.record_offense(peer2), 0);
+
+        // The old offenses are forgotten
+        time_getter.advance_time(TX_CONFLICTS_PERIOD);
+        assert_eq!(tracker.record_offense(peer1), 0);
+
+        tracker.remove_peer(&peer2);
+        assert!(!tracker.offenses.contains_key(&peer2));
+    }
+}
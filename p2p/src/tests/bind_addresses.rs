@@ -0,0 +1,77 @@
+// Copyright (c) 2021-2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+
+use crate::{
+    config::{BindAddress, TransportProfile},
+    get_p2p_bind_addresses,
+    types::socket_address::SocketAddress,
+};
+
+fn addr(s: &str) -> SocketAddr {
+    s.parse().unwrap()
+}
+
+#[test]
+fn transport_overrides() {
+    let bind_addresses = vec![
+        BindAddress::from(addr("0.0.0.0:3031")),
+        BindAddress {
+            address: addr("127.0.0.1:3032"),
+            transport: Some(TransportProfile::Plaintext),
+        },
+        BindAddress {
+            address: addr("127.0.0.1:3033"),
+            transport: Some(TransportProfile::Noise),
+        },
+    ];
+
+    let (default_addresses, overridden_addresses) =
+        get_p2p_bind_addresses(&bind_addresses, 3031, false, TransportProfile::Noise);
+    assert_eq!(
+        default_addresses,
+        vec![
+            SocketAddress::new(addr("0.0.0.0:3031")),
+            SocketAddress::new(addr("127.0.0.1:3033"))
+        ]
+    );
+    assert_eq!(overridden_addresses, vec![addr("127.0.0.1:3032")]);
+
+    // With encryption disabled, the Noise entry is the one overriding the default
+    let (default_addresses, overridden_addresses) =
+        get_p2p_bind_addresses(&bind_addresses, 3031, false, TransportProfile::Plaintext);
+    assert_eq!(
+        default_addresses,
+        vec![
+            SocketAddress::new(addr("0.0.0.0:3031")),
+            SocketAddress::new(addr("127.0.0.1:3032"))
+        ]
+    );
+    assert_eq!(overridden_addresses, vec![addr("127.0.0.1:3033")]);
+}
+
+#[test]
+fn default_bind_addresses() {
+    let (default_addresses, overridden_addresses) =
+        get_p2p_bind_addresses(&[], 3031, false, TransportProfile::Noise);
+    assert_eq!(default_addresses.len(), 2);
+    assert!(overridden_addresses.is_empty());
+
+    let (default_addresses, overridden_addresses) =
+        get_p2p_bind_addresses(&[], 3031, true, TransportProfile::Noise);
+    assert!(default_addresses.is_empty());
+    assert!(overridden_addresses.is_empty());
+}
@@ -17,6 +17,7 @@
 //! via methods under #[cfg(test)],
 
 mod bad_time_diff;
+mod bind_addresses;
 mod correct_handshake;
 mod disable_networking;
 mod disconnect_on_will_disconnect_msg;
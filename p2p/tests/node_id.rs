@@ -0,0 +1,149 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{sync::Arc, time::Duration};
+
+use chainstate::{make_chainstate, ChainstateConfig, DefaultTransactionVerificationStrategy};
+use common::chain::{config::create_unit_test_config, ChainConfig};
+use mempool::MempoolConfig;
+use storage_inmemory::InMemory;
+use utils_networking::IpOrSocketAddress;
+
+use p2p::{
+    config::P2pConfig,
+    interface::types::ConnectedPeer,
+    make_p2p,
+    peer_manager::peerdb::storage_impl::PeerDbStorageImpl,
+    test_helpers::test_p2p_config,
+    types::{socket_address::SocketAddress, NodeId},
+    NoiseStaticKeypair, P2pHandle,
+};
+
+#[ctor::ctor]
+fn init() {
+    logging::init_logging();
+}
+
+fn start_node(
+    name: &'static str,
+    chain_config: &Arc<ChainConfig>,
+    p2p_config: &Arc<P2pConfig>,
+    noise_keypair: NoiseStaticKeypair,
+) -> (
+    subsystem::ManagerJoinHandle,
+    subsystem::ShutdownTrigger,
+    P2pHandle,
+) {
+    let mut manager = subsystem::Manager::new(name);
+    let shutdown_trigger = manager.make_shutdown_trigger();
+
+    let chainstate = make_chainstate(
+        Arc::clone(chain_config),
+        ChainstateConfig::new(),
+        chainstate_storage::inmemory::Store::new_empty().unwrap(),
+        DefaultTransactionVerificationStrategy::new(),
+        None,
+        Default::default(),
+    )
+    .unwrap();
+    let chainstate = manager.add_subsystem("chainstate", chainstate);
+
+    let mempool = mempool::make_mempool(
+        Arc::clone(chain_config),
+        MempoolConfig::new(),
+        chainstate.clone(),
+        Default::default(),
+    );
+    let mempool = manager.add_custom_subsystem("mempool", |hdl| mempool.init(hdl));
+
+    let p2p = make_p2p(
+        true,
+        Arc::clone(chain_config),
+        Arc::clone(p2p_config),
+        chainstate,
+        mempool,
+        Default::default(),
+        PeerDbStorageImpl::new(InMemory::new()).unwrap(),
+    )
+    .unwrap()
+    .with_noise_keypair(noise_keypair)
+    .add_to_manager("p2p", &mut manager);
+
+    (manager.main_in_task(), shutdown_trigger, p2p)
+}
+
+async fn wait_for_peer_with_node_id(p2p: &P2pHandle) -> ConnectedPeer {
+    loop {
+        let peers = p2p.call_async(|this| this.get_connected_peers()).await.unwrap().unwrap();
+        if let [peer] = peers.as_slice() {
+            if peer.peer_node_id.is_some() {
+                return peer.clone();
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+// Start two nodes with fixed keypairs, connect them and check that each node sees
+// the other one's node id.
+#[tracing::instrument]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn node_ids_are_exchanged() {
+    let chain_config = Arc::new(create_unit_test_config());
+    let p2p_config = Arc::new(P2pConfig {
+        bind_addresses: vec!["127.0.0.1:0".parse().unwrap()],
+        ..test_p2p_config()
+    });
+
+    let keypair1 = NoiseStaticKeypair::from_private_key(&[1; 32]).unwrap();
+    let keypair2 = NoiseStaticKeypair::from_private_key(&[2; 32]).unwrap();
+    let node_id1 = NodeId::from_slice(keypair1.public_key());
+    let node_id2 = NodeId::from_slice(keypair2.public_key());
+    assert_ne!(node_id1, node_id2);
+
+    let (task1, shutdown1, p2p1) = start_node("node1", &chain_config, &p2p_config, keypair1);
+    let (task2, shutdown2, p2p2) = start_node("node2", &chain_config, &p2p_config, keypair2);
+
+    assert_eq!(
+        p2p1.call(|this| this.get_node_id()).await.unwrap(),
+        node_id1
+    );
+    assert_eq!(
+        p2p2.call(|this| this.get_node_id()).await.unwrap(),
+        node_id2
+    );
+
+    let node1_addresses: Vec<SocketAddress> =
+        p2p1.call_async(|this| this.get_bind_addresses()).await.unwrap().unwrap();
+    let node1_address = IpOrSocketAddress::new_socket_address(node1_addresses[0].socket_addr());
+    p2p2.call_async_mut(move |this| this.connect(node1_address))
+        .await
+        .unwrap()
+        .unwrap();
+
+    let timeout = Duration::from_secs(30);
+    let peer_of_node1 =
+        tokio::time::timeout(timeout, wait_for_peer_with_node_id(&p2p1)).await.unwrap();
+    let peer_of_node2 =
+        tokio::time::timeout(timeout, wait_for_peer_with_node_id(&p2p2)).await.unwrap();
+
+    assert_eq!(peer_of_node1.peer_node_id, Some(node_id2));
+    assert_eq!(peer_of_node2.peer_node_id, Some(node_id1));
+
+    shutdown1.initiate();
+    shutdown2.initiate();
+    task1.join().await;
+    task2.join().await;
+}
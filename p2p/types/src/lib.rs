@@ -16,6 +16,7 @@
 pub mod bannable_address;
 pub mod global_ip;
 pub mod ip_address;
+pub mod node_id;
 pub mod p2p_event;
 pub mod peer_address;
 pub mod peer_id;
@@ -24,4 +25,5 @@ pub mod socket_addr_ext;
 pub mod socket_address;
 
 pub use global_ip::IsGlobalIp;
+pub use node_id::NodeId;
 pub use peer_id::PeerId;
@@ -13,9 +13,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
-use common::primitives::{semver::SemVer, user_agent::UserAgent};
+use common::{
+    chain::{Block, GenBlock},
+    primitives::{semver::SemVer, user_agent::UserAgent, Id},
+};
 
 use crate::{peer_id::PeerId, services::Services};
 
@@ -32,4 +35,10 @@ pub enum P2pEvent {
         software_version: SemVer,
     },
     PeerDisconnected(PeerId),
+    /// A number of peers have been reporting best blocks different from the local tip
+    /// for a while, so the node may be on a minority fork.
+    PossibleFork {
+        local_tip: Id<GenBlock>,
+        peer_tips: BTreeMap<Id<Block>, u32>,
+    },
 }
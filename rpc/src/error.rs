@@ -17,6 +17,8 @@
 
 use std::fmt::Display;
 
+use serde::{de::DeserializeOwned, Serialize};
+
 /// RPC error
 use jsonrpsee::types::{error::CALL_EXECUTION_FAILED_CODE, ErrorObject, ErrorObjectOwned};
 
@@ -27,6 +29,63 @@ pub type RpcClientResult<T> = std::result::Result<T, jsonrpsee::core::ClientErro
 pub type Error = ErrorObjectOwned;
 pub type ClientError = jsonrpsee::core::ClientError;
 
+/// Create an error that carries structured data in addition to the message,
+/// so that the clients don't have to parse the message to handle the error.
+pub fn error_with_data(err: impl Display, data: impl Serialize) -> Error {
+    ErrorObject::owned(CALL_EXECUTION_FAILED_CODE, err.to_string(), Some(data))
+}
+
+/// The error code of a call that can't be served in the current state of the service,
+/// e.g. when the required resource isn't loaded
+pub const SERVICE_UNAVAILABLE_CODE: i32 = 503;
+
+/// Create an error with the [SERVICE_UNAVAILABLE_CODE] code.
+pub fn service_unavailable_error(err: impl Display) -> Error {
+    ErrorObject::owned(SERVICE_UNAVAILABLE_CODE, err.to_string(), None::<()>)
+}
+
+/// The error code of a call with an invalid argument
+pub const INVALID_INPUT_CODE: i32 = 400;
+
+/// The error code of a call referring to something that doesn't exist, e.g. an unknown block
+pub const NOT_FOUND_CODE: i32 = 404;
+
+/// The error code of a call with valid arguments that can't be acted upon, e.g. a banned address
+pub const UNPROCESSABLE_CODE: i32 = 422;
+
+/// The error code used when no more specific code applies
+pub const GENERIC_ERROR_CODE: i32 = CALL_EXECUTION_FAILED_CODE;
+
+/// The data of the errors created by [categorized_error]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ErrorCategory {
+    /// Machine-readable category of the error, e.g. the name of the error variant
+    #[serde(rename = "type")]
+    pub error_type: String,
+}
+
+/// Create an error with the given code, carrying the category of the error in its data,
+/// so that the clients can tell the errors apart without parsing the message.
+pub fn categorized_error(code: i32, err: impl Display, error_type: &str) -> Error {
+    ErrorObject::owned(
+        code,
+        err.to_string(),
+        Some(ErrorCategory {
+            error_type: error_type.to_owned(),
+        }),
+    )
+}
+
+/// Extract the data created by [error_with_data] from an error returned to a client.
+///
+/// Returns `None` if the error is not a call error or if it doesn't have the expected data.
+pub fn error_data<D: DeserializeOwned>(err: &ClientError) -> Option<D> {
+    match err {
+        ClientError::Call(err) => err.data().and_then(|data| serde_json::from_str(data.get()).ok()),
+        _ => None,
+    }
+}
+
 /// Handle RPC result
 ///
 /// This is a generic way of converting the likes of:
@@ -37,7 +37,12 @@ use jsonrpsee::{
 
 use logging::log;
 
-pub use error::{handle_result, ClientError, Error, RpcCallResult, RpcClientResult, RpcResult};
+pub use error::{
+    categorized_error, error_data, error_with_data, handle_result, service_unavailable_error,
+    ClientError, Error, ErrorCategory, RpcCallResult, RpcClientResult, RpcResult,
+    GENERIC_ERROR_CODE, INVALID_INPUT_CODE, NOT_FOUND_CODE, SERVICE_UNAVAILABLE_CODE,
+    UNPROCESSABLE_CODE,
+};
 
 pub use jsonrpsee::{core::server::Methods, proc_macros::rpc};
 use rpc_auth::RpcAuth;
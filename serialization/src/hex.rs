@@ -15,6 +15,8 @@
 
 use serialization_core::{Decode, DecodeAll, Encode};
 
+use crate::strict::DecodeStrict;
+
 #[derive(thiserror::Error, Debug, Clone, PartialEq)]
 pub enum HexError {
     #[error("Scale codec decode error: {0}")]
@@ -38,5 +40,14 @@ pub trait HexDecode: Decode + Sized {
     }
 }
 
+pub trait HexDecodeStrict: DecodeStrict + Sized {
+    fn hex_decode_strict<T: AsRef<str>>(data: T) -> Result<Self, HexError> {
+        let unhexed = hex::decode(data.as_ref())?;
+        let decoded = Self::decode_strict(&unhexed)?;
+        Ok(decoded)
+    }
+}
+
 impl<T: Encode + Sized> HexEncode for T {}
 impl<T: Decode + Sized> HexDecode for T {}
+impl<T: DecodeStrict + Sized> HexDecodeStrict for T {}
@@ -15,7 +15,10 @@
 
 use std::{fmt::Display, str::FromStr};
 
-use crate::hex::{HexDecode, HexEncode, HexError};
+use crate::{
+    hex::{HexDecode, HexDecodeStrict, HexEncode, HexError},
+    strict::DecodeStrict,
+};
 
 /// Wrapper that serializes objects as hex encoded string for `serde`
 #[derive(Debug, Clone)]
@@ -75,3 +78,62 @@ impl<T: serialization_core::Encode> Display for HexEncoded<T> {
 impl<T> rpc_description::HasValueHint for HexEncoded<T> {
     const HINT_SER: rpc_description::ValueHint = rpc_description::ValueHint::HEX_STRING;
 }
+
+/// Same as [HexEncoded], but only the canonical encoding is accepted when deserializing
+#[derive(Debug, Clone)]
+pub struct StrictHexEncoded<T>(T);
+
+impl<T> StrictHexEncoded<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn take(self) -> T {
+        self.0
+    }
+}
+
+impl<T> AsRef<T> for StrictHexEncoded<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> From<T> for StrictHexEncoded<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: serialization_core::Encode> serde::Serialize for StrictHexEncoded<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let hex = self.0.hex_encode();
+        serializer.serialize_str(&hex)
+    }
+}
+
+impl<'de, T: DecodeStrict> serde::Deserialize<'de> for StrictHexEncoded<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        let value = T::hex_decode_strict(hex).map_err(serde::de::Error::custom)?;
+        Ok(StrictHexEncoded(value))
+    }
+}
+
+impl<T: DecodeStrict> FromStr for StrictHexEncoded<T> {
+    type Err = HexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        <T as HexDecodeStrict>::hex_decode_strict(s).map(Self)
+    }
+}
+
+impl<T: serialization_core::Encode> Display for StrictHexEncoded<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0.hex_encode())
+    }
+}
+
+impl<T> rpc_description::HasValueHint for StrictHexEncoded<T> {
+    const HINT_SER: rpc_description::ValueHint = rpc_description::ValueHint::HEX_STRING;
+}
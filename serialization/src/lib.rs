@@ -20,6 +20,7 @@ pub mod extras;
 pub mod hex;
 pub mod hex_encoded;
 pub mod json_encoded;
+pub mod strict;
 
 // Re-export all the constituent parts
 pub use serialization_core::*;
@@ -0,0 +1,95 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoding that only accepts the canonical encoding of a value
+
+use serialization_core::{DecodeAll, Encode, Error};
+
+/// Decoding of the data received from untrusted sources.
+///
+/// SCALE decoding may accept several encodings of the same value (e.g. a map with unsorted
+/// entries), while the ids of the consensus objects are the hashes of their encodings. The strict
+/// decoding re-encodes the decoded value and rejects the input if it's not the same.
+pub trait DecodeStrict: DecodeAll + Encode {
+    /// Whether the value has to be re-encoded to make sure that it was decoded from its canonical
+    /// encoding. This can be overridden for the values that have a single valid encoding.
+    fn needs_canonical_check(&self) -> bool {
+        true
+    }
+
+    fn decode_strict(bytes: &[u8]) -> Result<Self, Error> {
+        let value = Self::decode_all(&mut &bytes[..])?;
+        if value.needs_canonical_check() && !value.using_encoded(|encoded| encoded == bytes) {
+            return Err(Error::from("Non-canonical encoding"));
+        }
+        Ok(value)
+    }
+}
+
+/// Any byte string is decoded from its only encoding
+impl DecodeStrict for Vec<u8> {
+    fn needs_canonical_check(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    impl DecodeStrict for BTreeMap<u8, u8> {}
+
+    #[test]
+    fn canonical_map() {
+        let map = BTreeMap::from([(1, 10), (2, 20)]);
+        let encoded = map.encode();
+        assert_eq!(BTreeMap::decode_strict(&encoded).unwrap(), map);
+
+        // Same entries in a different order
+        let unsorted = vec![(2u8, 20u8), (1, 10)].encode();
+        assert_eq!(
+            BTreeMap::<u8, u8>::decode_all(&mut unsorted.as_slice()).unwrap(),
+            map
+        );
+        assert!(BTreeMap::<u8, u8>::decode_strict(&unsorted).is_err());
+
+        // Duplicate entries
+        let duplicate = vec![(1u8, 10u8), (1, 10), (2, 20)].encode();
+        assert_eq!(
+            BTreeMap::<u8, u8>::decode_all(&mut duplicate.as_slice()).unwrap(),
+            map
+        );
+        assert!(BTreeMap::<u8, u8>::decode_strict(&duplicate).is_err());
+    }
+
+    #[test]
+    fn invalid_encoding() {
+        let bytes = vec![1u8, 2, 3];
+        let encoded = bytes.encode();
+        assert_eq!(Vec::<u8>::decode_strict(&encoded).unwrap(), bytes);
+
+        // Trailing bytes
+        let mut trailing = encoded.clone();
+        trailing.push(0);
+        assert!(Vec::<u8>::decode_strict(&trailing).is_err());
+
+        // Length 3 with a non-minimal two byte compact prefix
+        let mut non_minimal = vec![0b0000_1101, 0];
+        non_minimal.extend_from_slice(&bytes);
+        assert!(Vec::<u8>::decode_strict(&non_minimal).is_err());
+    }
+}
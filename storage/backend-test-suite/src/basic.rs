@@ -292,7 +292,51 @@ fn put_and_iterate_delete_some<B: Backend, F: BackendFn<B>>(backend_fn: Arc<F>)
     drop(dbtx);
 }
 
+fn prefix_iter_boundaries<B: Backend, F: BackendFn<B>>(backend_fn: Arc<F>) {
+    let store = backend_fn().open(desc(1)).expect("db open to succeed");
+
+    let mut dbtx = store.transaction_rw(None).unwrap();
+    for key in [
+        vec![0x01],
+        vec![0x01, 0x00],
+        vec![0x01, 0xff],
+        vec![0x01, 0xff, 0xff],
+        vec![0x02],
+        vec![0x02, 0x00],
+        vec![0xff],
+        vec![0xff, 0xff],
+    ] {
+        dbtx.put(MAPID.0, key.clone(), key).unwrap();
+    }
+    dbtx.commit().expect("commit to succeed");
+
+    let check = |prefix: &[u8], expected: &[&[u8]]| {
+        let dbtx = store.transaction_ro().unwrap();
+        let keys: Vec<_> =
+            dbtx.prefix_iter(MAPID.0, prefix.to_vec()).unwrap().map(|x| x.0).collect();
+        assert_eq!(keys, expected, "prefix={prefix:?}");
+    };
+
+    // The prefix is itself a key
+    check(
+        &[0x01],
+        &[&[0x01], &[0x01, 0x00], &[0x01, 0xff], &[0x01, 0xff, 0xff]],
+    );
+    check(&[0x01, 0xff], &[&[0x01, 0xff], &[0x01, 0xff, 0xff]]);
+    check(&[0x01, 0xff, 0xff], &[&[0x01, 0xff, 0xff]]);
+    // Adjacent prefixes don't leak into each other
+    check(&[0x02], &[&[0x02], &[0x02, 0x00]]);
+    check(&[0x01, 0x00], &[&[0x01, 0x00]]);
+    check(&[0x01, 0x01], &[]);
+    check(&[0x00], &[]);
+    // Prefixes consisting of 0xff bytes only have no successor
+    check(&[0xff], &[&[0xff], &[0xff, 0xff]]);
+    check(&[0xff, 0xff], &[&[0xff, 0xff]]);
+    check(&[0xff, 0xff, 0xff], &[]);
+}
+
 tests![
+    prefix_iter_boundaries,
     put_and_abort,
     put_and_commit,
     put_and_iterate_delete_some,
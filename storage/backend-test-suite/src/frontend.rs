@@ -164,4 +164,187 @@ mod iter_sort_preserving_numbers {
     }
 }
 
-tests![iter_sort_preserving_numbers::test1, iter_sort_preserving_numbers::test2];
+mod ordered_iteration {
+    use std::{collections::BTreeMap, ops::Bound};
+
+    use storage::{decl_schema, MakeMapRef, OrderPreservingValue, Storage};
+
+    use crate::with_rng_seed;
+
+    use super::*;
+
+    type Key = (OrderPreservingValue<u32>, OrderPreservingValue<u32>);
+    type ModelKey = (u32, u32);
+
+    mod test_schema {
+        use super::*;
+
+        decl_schema! {
+            pub Schema {
+                pub TestMap: Map<Key, u64>,
+            }
+        }
+    }
+
+    use test_schema::{Schema, TestMap};
+
+    fn make_key((major, minor): ModelKey) -> Key {
+        (
+            OrderPreservingValue::new(major),
+            OrderPreservingValue::new(minor),
+        )
+    }
+
+    fn to_model_entries(entries: impl Iterator<Item = (Key, u64)>) -> Vec<(ModelKey, u64)> {
+        entries
+            .map(|((major, minor), val)| ((major.inner(), minor.inner()), val))
+            .collect()
+    }
+
+    fn gen_model_key(rng: &mut impl Rng) -> ModelKey {
+        // Make the boundary values of the second part of the key more likely.
+        let minor = match rng.gen_range(0..10) {
+            0 => 0,
+            1 => u32::MAX,
+            _ => rng.gen_range(0..100),
+        };
+        (rng.gen_range(0..8), minor)
+    }
+
+    fn gen_bound(rng: &mut impl Rng) -> Bound<ModelKey> {
+        let key = gen_model_key(rng);
+        match rng.gen_range(0..3) {
+            0 => Bound::Included(key),
+            1 => Bound::Excluded(key),
+            _ => Bound::Unbounded,
+        }
+    }
+
+    fn gen_range(rng: &mut impl Rng) -> (Bound<ModelKey>, Bound<ModelKey>) {
+        loop {
+            let start = gen_bound(rng);
+            let end = gen_bound(rng);
+            // BTreeMap::range panics if the start is greater than the end, or if they are equal
+            // and both excluded.
+            let is_valid = match (start, end) {
+                (Bound::Excluded(s), Bound::Excluded(e)) => s < e,
+                (
+                    Bound::Included(s) | Bound::Excluded(s),
+                    Bound::Included(e) | Bound::Excluded(e),
+                ) => s <= e,
+                _ => true,
+            };
+            if is_valid {
+                return (start, end);
+            }
+        }
+    }
+
+    // Iterating over the whole map, over a prefix or over a range must produce the same entries
+    // in the same order as the model does.
+    pub fn matches_model<B: Backend, F: BackendFn<B>>(backend_fn: Arc<F>) {
+        let storage = Storage::<_, Schema>::new(backend_fn()).unwrap();
+
+        with_rng_seed(move |seed| {
+            let mut rng = make_seedable_rng(seed);
+
+            let model = (0..rng.gen_range(0..200))
+                .map(|_| (gen_model_key(&mut rng), rng.gen::<u64>()))
+                .collect::<BTreeMap<_, _>>();
+
+            let mut dbtx = storage.transaction_rw(None).unwrap();
+            let mut map = dbtx.get_mut::<TestMap, _>();
+            for (key, val) in &model {
+                map.put(&make_key(*key), val).unwrap();
+            }
+            dbtx.commit().unwrap();
+
+            let dbtx = storage.transaction_ro().unwrap();
+            let map = dbtx.get::<TestMap, _>();
+
+            let expected = model.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>();
+            assert_eq!(to_model_entries(map.iter_decoded().unwrap()), expected);
+            assert_eq!(
+                map.iter_keys().unwrap().collect::<Vec<_>>(),
+                model.keys().copied().map(make_key).collect::<Vec<_>>()
+            );
+
+            // The prefixes are adjacent, and the keys with the smallest and the largest second
+            // part are on the boundaries.
+            for major in 0..10 {
+                let prefix = (OrderPreservingValue::new(major),);
+                let expected = model
+                    .range((major, 0)..=(major, u32::MAX))
+                    .map(|(k, v)| (*k, *v))
+                    .collect::<Vec<_>>();
+                let entries = map.prefix_iter_decoded(&prefix).unwrap();
+                assert_eq!(to_model_entries(entries), expected, "prefix: {major}");
+            }
+
+            for _ in 0..50 {
+                let (start, end) = gen_range(&mut rng);
+                let expected = model.range((start, end)).map(|(k, v)| (*k, *v)).collect::<Vec<_>>();
+                let range = (start.map(make_key), end.map(make_key));
+
+                assert_eq!(
+                    to_model_entries(map.range_decoded(range).unwrap()),
+                    expected,
+                    "range: {start:?}..{end:?}"
+                );
+                assert_eq!(
+                    map.range_keys(range).unwrap().collect::<Vec<_>>(),
+                    expected.iter().map(|(k, _)| make_key(*k)).collect::<Vec<_>>(),
+                    "range: {start:?}..{end:?}"
+                );
+            }
+        });
+    }
+
+    // The iterators created by a write transaction see its uncommitted changes; once the
+    // transaction is committed or aborted, only the committed changes are visible.
+    pub fn write_tx_iteration<B: Backend, F: BackendFn<B>>(backend_fn: Arc<F>) {
+        let storage = Storage::<_, Schema>::new(backend_fn()).unwrap();
+
+        let iter_all_ro = || {
+            let dbtx = storage.transaction_ro().unwrap();
+            let entries = to_model_entries(dbtx.get::<TestMap, _>().iter_decoded().unwrap());
+            entries
+        };
+
+        let mut dbtx = storage.transaction_rw(None).unwrap();
+        let mut map = dbtx.get_mut::<TestMap, _>();
+        map.put(&make_key((1, 1)), 11).unwrap();
+        map.put(&make_key((1, 2)), 12).unwrap();
+        map.put(&make_key((2, 1)), 21).unwrap();
+        assert_eq!(
+            to_model_entries(dbtx.get::<TestMap, _>().iter_decoded().unwrap()),
+            [((1, 1), 11), ((1, 2), 12), ((2, 1), 21)]
+        );
+        dbtx.commit().unwrap();
+
+        assert_eq!(iter_all_ro(), [((1, 1), 11), ((1, 2), 12), ((2, 1), 21)]);
+
+        let mut dbtx = storage.transaction_rw(None).unwrap();
+        let mut map = dbtx.get_mut::<TestMap, _>();
+        map.del(&make_key((1, 2))).unwrap();
+        map.put(&make_key((1, 3)), 13).unwrap();
+        assert_eq!(
+            to_model_entries(
+                map.range(make_key((1, 0))..make_key((2, 0)))
+                    .unwrap()
+                    .map(|(k, v)| (k, v.decode()))
+            ),
+            [((1, 1), 11), ((1, 3), 13)]
+        );
+        dbtx.abort();
+
+        assert_eq!(iter_all_ro(), [((1, 1), 11), ((1, 2), 12), ((2, 1), 21)]);
+    }
+}
+
+tests![
+    iter_sort_preserving_numbers::test1,
+    iter_sort_preserving_numbers::test2,
+    ordered_iteration::matches_model,
+    ordered_iteration::write_tx_iteration,
+];
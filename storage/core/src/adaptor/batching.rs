@@ -0,0 +1,327 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An adaptor that can group multiple committed transactions into a single write to the
+//! underlying backend.
+//!
+//! While batching is enabled, committed changes are kept in memory and are only written out
+//! to the underlying backend on [backend::BackendImpl::flush], when batching is disabled or
+//! when the last handle to the backend is dropped. Changes that haven't been written out are
+//! lost if the process crashes.
+
+use std::{borrow::Cow, collections::BTreeMap};
+
+use utils::{shallow_clone::ShallowClone, sync};
+
+use super::locking::{merge_iterators, DeltaMap};
+use crate::{
+    backend::{self, BackendImpl, ReadOps, WriteOps},
+    util::MapPrefixIter,
+    Data, DbDesc, DbMapCount, DbMapId, DbMapsData,
+};
+
+struct BatchState {
+    /// Whether committed transactions are kept in memory
+    enabled: bool,
+
+    /// Committed changes that have not been written to the underlying backend yet
+    pending: DbMapsData<DeltaMap>,
+}
+
+impl BatchState {
+    fn new(num_maps: DbMapCount) -> Self {
+        Self {
+            enabled: false,
+            pending: DbMapsData::new(num_maps, |_| BTreeMap::new()),
+        }
+    }
+
+    fn has_pending(&self) -> bool {
+        let num_maps = self.pending.db_map_count();
+        num_maps.indices().any(|map_id| !self.pending[map_id].is_empty())
+    }
+
+    /// Write the pending changes to the underlying backend in one transaction
+    fn write_out<T: BackendImpl>(&mut self, backend: &T) -> crate::Result<()> {
+        if !self.has_pending() {
+            return Ok(());
+        }
+
+        write_deltas(backend, &self.pending)?;
+
+        let num_maps = self.pending.db_map_count();
+        num_maps.indices().for_each(|map_id| self.pending[map_id].clear());
+        Ok(())
+    }
+}
+
+fn write_deltas<T: BackendImpl>(backend: &T, deltas: &DbMapsData<DeltaMap>) -> crate::Result<()> {
+    let mut dbtx = backend.transaction_rw(None)?;
+    for map_id in deltas.db_map_count().indices() {
+        for (key, val) in &deltas[map_id] {
+            match val {
+                None => dbtx.del(map_id, key)?,
+                Some(val) => dbtx.put(map_id, key.clone(), val.clone())?,
+            }
+        }
+    }
+    backend::TxRw::commit(dbtx)
+}
+
+struct Shared<T: BackendImpl> {
+    inner: T,
+    state: sync::RwLock<BatchState>,
+}
+
+impl<T: BackendImpl> Drop for Shared<T> {
+    fn drop(&mut self) {
+        let mut state = self.state.write().expect("lock to be alive");
+        if let Err(err) = state.write_out(&self.inner) {
+            logging::log::error!("Failed to write out the batched storage changes: {err}");
+        }
+    }
+}
+
+/// Read-only transaction sees the pending changes on top of the underlying backend
+pub struct TxRo<'tx, Tx> {
+    state: sync::RwLockReadGuard<'tx, BatchState>,
+    inner: Tx,
+}
+
+impl<'tx, Tx: ReadOps> ReadOps for TxRo<'tx, Tx> {
+    fn get(&self, map_id: DbMapId, key: &[u8]) -> crate::Result<Option<Cow<[u8]>>> {
+        self.state.pending[map_id].get(key).map_or_else(
+            || self.inner.get(map_id, key),
+            |x| Ok(x.as_deref().map(|p| p.into())),
+        )
+    }
+
+    fn prefix_iter(
+        &self,
+        map_id: DbMapId,
+        prefix: Data,
+    ) -> crate::Result<impl Iterator<Item = (Data, Data)> + '_> {
+        let db_iter = self.inner.prefix_iter(map_id, prefix.clone())?;
+        let pending_iter = MapPrefixIter::new(&self.state.pending[map_id], prefix);
+
+        Ok(merge_iterators(db_iter, pending_iter))
+    }
+
+    fn greater_equal_iter(
+        &self,
+        map_id: DbMapId,
+        key: Data,
+    ) -> crate::Result<impl Iterator<Item = (Data, Data)> + '_> {
+        let db_iter = self.inner.greater_equal_iter(map_id, key.clone())?;
+        let pending_iter = self.state.pending[map_id].range(key..);
+
+        Ok(merge_iterators(db_iter, pending_iter))
+    }
+}
+
+impl<'tx, Tx: ReadOps> backend::TxRo for TxRo<'tx, Tx> {}
+
+/// Read-write transaction tracks its own changes on top of the pending ones.
+///
+/// It holds the write lock to the batch state, so read-write transactions are serialized.
+pub struct TxRw<'tx, T: BackendImpl> {
+    state: sync::RwLockWriteGuard<'tx, BatchState>,
+    inner: T::TxRo<'tx>,
+    backend: &'tx T,
+    deltas: DbMapsData<DeltaMap>,
+}
+
+impl<'tx, T: BackendImpl> ReadOps for TxRw<'tx, T> {
+    fn get(&self, map_id: DbMapId, key: &[u8]) -> crate::Result<Option<Cow<[u8]>>> {
+        let delta = self.deltas[map_id].get(key).or_else(|| self.state.pending[map_id].get(key));
+        delta.map_or_else(
+            || self.inner.get(map_id, key),
+            |x| Ok(x.as_deref().map(|p| p.into())),
+        )
+    }
+
+    fn prefix_iter(
+        &self,
+        map_id: DbMapId,
+        prefix: Data,
+    ) -> crate::Result<impl Iterator<Item = (Data, Data)> + '_> {
+        let db_iter = self.inner.prefix_iter(map_id, prefix.clone())?;
+        let pending_iter = MapPrefixIter::new(&self.state.pending[map_id], prefix.clone());
+        let delta_iter = MapPrefixIter::new(&self.deltas[map_id], prefix);
+
+        Ok(merge_iterators(
+            merge_iterators(db_iter, pending_iter),
+            delta_iter,
+        ))
+    }
+
+    fn greater_equal_iter(
+        &self,
+        map_id: DbMapId,
+        key: Data,
+    ) -> crate::Result<impl Iterator<Item = (Data, Data)> + '_> {
+        let db_iter = self.inner.greater_equal_iter(map_id, key.clone())?;
+        let pending_iter = self.state.pending[map_id].range(key.clone()..);
+        let delta_iter = self.deltas[map_id].range(key..);
+
+        Ok(merge_iterators(
+            merge_iterators(db_iter, pending_iter),
+            delta_iter,
+        ))
+    }
+}
+
+impl<'tx, T: BackendImpl> WriteOps for TxRw<'tx, T> {
+    fn put(&mut self, map_id: DbMapId, key: Data, val: Data) -> crate::Result<()> {
+        self.deltas[map_id].insert(key, Some(val));
+        Ok(())
+    }
+
+    fn del(&mut self, map_id: DbMapId, key: &[u8]) -> crate::Result<()> {
+        self.deltas[map_id].insert(key.to_vec(), None);
+        Ok(())
+    }
+}
+
+impl<'tx, T: BackendImpl> backend::TxRw for TxRw<'tx, T> {
+    fn commit(self) -> crate::Result<()> {
+        let Self {
+            mut state,
+            inner,
+            backend,
+            deltas,
+        } = self;
+
+        // The underlying read-only transaction has to be closed before writing
+        drop(inner);
+
+        if state.enabled {
+            for (map_id, delta) in deltas.into_iter_with_id() {
+                state.pending[map_id].extend(delta);
+            }
+            Ok(())
+        } else {
+            // Without batching, there is nothing pending and the changes go straight through
+            debug_assert!(!state.has_pending());
+            write_deltas(backend, &deltas)
+        }
+    }
+}
+
+/// Backend implementation of [WriteBatching]
+pub struct WriteBatchingImpl<T: BackendImpl> {
+    shared: sync::Arc<Shared<T>>,
+    num_maps: DbMapCount,
+}
+
+impl<T: BackendImpl> Clone for WriteBatchingImpl<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: sync::Arc::clone(&self.shared),
+            num_maps: self.num_maps,
+        }
+    }
+}
+
+impl<T: BackendImpl> ShallowClone for WriteBatchingImpl<T> {
+    fn shallow_clone(&self) -> Self {
+        self.clone()
+    }
+}
+
+impl<T: BackendImpl> BackendImpl for WriteBatchingImpl<T> {
+    type TxRo<'a> = TxRo<'a, T::TxRo<'a>>;
+
+    type TxRw<'a> = TxRw<'a, T>;
+
+    fn transaction_ro(&self) -> crate::Result<Self::TxRo<'_>> {
+        let state = self.shared.state.read().expect("lock to be alive");
+        let inner = self.shared.inner.transaction_ro()?;
+        Ok(TxRo { state, inner })
+    }
+
+    fn transaction_rw(&self, _size: Option<usize>) -> crate::Result<Self::TxRw<'_>> {
+        let state = self.shared.state.write().expect("lock to be alive");
+        let inner = self.shared.inner.transaction_ro()?;
+        Ok(TxRw {
+            state,
+            inner,
+            backend: &self.shared.inner,
+            deltas: DbMapsData::new(self.num_maps, |_| BTreeMap::new()),
+        })
+    }
+
+    fn flush(&self) -> crate::Result<()> {
+        self.shared
+            .state
+            .write()
+            .expect("lock to be alive")
+            .write_out(&self.shared.inner)?;
+        self.shared.inner.flush()
+    }
+
+    fn set_write_batching(&self, enabled: bool) -> crate::Result<()> {
+        let mut state = self.shared.state.write().expect("lock to be alive");
+        if !enabled {
+            state.write_out(&self.shared.inner)?;
+        }
+        state.enabled = enabled;
+        Ok(())
+    }
+}
+
+/// Add write batching to the given backend.
+///
+/// Batching is disabled initially unless requested by [Self::with_batching], it can be toggled
+/// later using [backend::BackendImpl::set_write_batching].
+#[derive(Clone)]
+pub struct WriteBatching<B> {
+    inner: B,
+    enabled: bool,
+}
+
+impl<B> WriteBatching<B> {
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            enabled: false,
+        }
+    }
+
+    /// Set whether batching is enabled once the backend is open
+    pub fn with_batching(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+
+impl<B: backend::Backend> backend::Backend for WriteBatching<B> {
+    type Impl = WriteBatchingImpl<B::Impl>;
+
+    fn open(self, desc: DbDesc) -> crate::Result<Self::Impl> {
+        let num_maps = desc.db_map_count();
+        let inner = self.inner.open(desc)?;
+        let mut state = BatchState::new(num_maps);
+        state.enabled = self.enabled;
+        let shared = Shared {
+            inner,
+            state: sync::RwLock::new(state),
+        };
+        Ok(WriteBatchingImpl {
+            shared: sync::Arc::new(shared),
+            num_maps,
+        })
+    }
+}
@@ -55,7 +55,7 @@ impl<'tx, T: ReadOps> ReadOps for TxRo<'tx, T> {
 impl<'tx, T: ReadOps> backend::TxRo for TxRo<'tx, T> {}
 
 // Tracker for database changes
-type DeltaMap = BTreeMap<Data, Option<Data>>;
+pub(super) type DeltaMap = BTreeMap<Data, Option<Data>>;
 
 // RW transaction holds a write lock to the database and a list of changes performed
 pub struct TxRw<'tx, T> {
@@ -101,7 +101,7 @@ impl<'tx, T: ReadOps> ReadOps for TxRw<'tx, T> {
     }
 }
 
-fn merge_iterators<'a>(
+pub(super) fn merge_iterators<'a>(
     db_iter: impl Iterator<Item = (Data, Data)> + 'a,
     delta_iter: impl Iterator<Item = (&'a Data, &'a Option<Data>)> + 'a,
 ) -> impl Iterator<Item = (Data, Data)> + 'a {
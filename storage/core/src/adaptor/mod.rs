@@ -15,8 +15,10 @@
 
 //! A number of tools to compose backends from smaller components
 
+mod batching;
 mod locking;
 
+pub use batching::{WriteBatching, WriteBatchingImpl};
 pub use locking::Locking;
 
 use crate::backend;
@@ -77,6 +77,21 @@ pub trait BackendImpl: Send + Sync + ShallowClone + 'static {
 
     /// Start a read-write transaction
     fn transaction_rw(&self, size: Option<usize>) -> crate::Result<Self::TxRw<'_>>;
+
+    /// Make sure all the committed changes have been written out and are durable.
+    ///
+    /// Only needed for backends that may delay writing out committed transactions.
+    fn flush(&self) -> crate::Result<()> {
+        Ok(())
+    }
+
+    /// Allow or disallow grouping multiple committed transactions into a single write.
+    ///
+    /// Disabling batching writes out the pending changes. Ignored by backends that always
+    /// write out transactions as they are committed.
+    fn set_write_batching(&self, _enabled: bool) -> crate::Result<()> {
+        Ok(())
+    }
 }
 
 /// Storage backend type. Used to set up storage.
@@ -129,6 +129,14 @@ impl<T: backend::BackendImpl> backend::BackendImpl for FailingImpl<T> {
         let inner = self.inner.transaction_rw(size)?;
         Ok(TxRw { inner, state })
     }
+
+    fn flush(&self) -> storage_core::Result<()> {
+        self.inner.flush()
+    }
+
+    fn set_write_batching(&self, enabled: bool) -> storage_core::Result<()> {
+        self.inner.set_write_batching(enabled)
+    }
 }
 
 struct RwTxState<'a> {
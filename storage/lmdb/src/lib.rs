@@ -233,6 +233,10 @@ impl backend::BackendImpl for LmdbImpl {
         self.resize_if_resize_scheduled();
         self.start_transaction(|env| lmdb::Environment::begin_rw_txn(env, size))
     }
+
+    fn flush(&self) -> storage_core::Result<()> {
+        self.env.sync(true).or_else(error::process_with_unit)
+    }
 }
 
 pub struct Lmdb {
@@ -269,6 +273,20 @@ impl Lmdb {
         self
     }
 
+    /// Don't flush the data to disk on every commit.
+    ///
+    /// The most recently committed transactions may be lost on a system crash unless
+    /// [backend::BackendImpl::flush] has been called since. LMDB keeps the database consistent
+    /// in this mode only if the file system preserves the order of writes; otherwise, a system
+    /// crash may leave the database corrupted. Combined with [Self::with_write_map], a system
+    /// crash may corrupt the database regardless of the file system, so the two should not be
+    /// used together. An application crash (as opposed to an OS crash or a power loss) doesn't
+    /// affect the database. See [lmdb::EnvironmentFlags::NO_SYNC] for more detail.
+    pub fn with_no_sync(mut self) -> Self {
+        self.flags |= lmdb::EnvironmentFlags::NO_SYNC;
+        self
+    }
+
     fn open_db(env: &lmdb::Environment, desc: &DbMapDesc) -> storage_core::Result<lmdb::Database> {
         let name = Some(desc.name());
         let flags = lmdb::DatabaseFlags::default();
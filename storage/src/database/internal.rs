@@ -15,13 +15,13 @@
 
 //! Internal database implementation utils
 
-use std::borrow::Cow;
+use std::{borrow::Cow, ops::Bound};
 
 use crate::schema;
 use serialization::{encoded::Encoded, EncodeLike};
 use storage_core::{
     backend::{self, ReadOps},
-    Backend, DbMapId,
+    Backend, Data, DbMapId,
 };
 
 /// Map high-level transaction type to the backend-specific implementation type
@@ -106,3 +106,55 @@ pub fn greater_equal_iter_keys<DbMap: schema::DbMap, Tx: ReadOps>(
     dbtx.greater_equal_iter(map_id, key)
         .map(|iter| iter.map(|(k, _v)| Encoded::from_bytes_unchecked(k).decode()))
 }
+
+/// Iterate over raw entries with encoded keys within the given bounds
+fn raw_range_iter<Tx: ReadOps>(
+    dbtx: &Tx,
+    map_id: DbMapId,
+    start: Bound<Data>,
+    end: Bound<Data>,
+) -> crate::Result<impl Iterator<Item = (Data, Data)> + '_> {
+    let (start_key, excluded_start_key) = match start {
+        Bound::Included(key) => (key, None),
+        Bound::Excluded(key) => (key.clone(), Some(key)),
+        // The empty key is less than or equal to any other key
+        Bound::Unbounded => (Data::new(), None),
+    };
+
+    let iter = dbtx
+        .greater_equal_iter(map_id, start_key)?
+        .skip_while(move |(key, _)| excluded_start_key.as_ref() == Some(key))
+        .take_while(move |(key, _)| match &end {
+            Bound::Included(end_key) => key <= end_key,
+            Bound::Excluded(end_key) => key < end_key,
+            Bound::Unbounded => true,
+        });
+
+    Ok(iter)
+}
+
+pub fn range_iter<DbMap: schema::DbMap, Tx: ReadOps>(
+    dbtx: &Tx,
+    map_id: DbMapId,
+    start: Bound<Data>,
+    end: Bound<Data>,
+) -> crate::Result<impl EntryIterator<DbMap> + '_> {
+    raw_range_iter(dbtx, map_id, start, end).map(|iter| {
+        iter.map(|(k, v)| {
+            (
+                Encoded::from_bytes_unchecked(k).decode(),
+                Encoded::from_bytes_unchecked(v),
+            )
+        })
+    })
+}
+
+pub fn range_iter_keys<DbMap: schema::DbMap, Tx: ReadOps>(
+    dbtx: &Tx,
+    map_id: DbMapId,
+    start: Bound<Data>,
+    end: Bound<Data>,
+) -> crate::Result<impl Iterator<Item = DbMap::Key> + '_> {
+    raw_range_iter(dbtx, map_id, start, end)
+        .map(|iter| iter.map(|(k, _v)| Encoded::from_bytes_unchecked(k).decode()))
+}
@@ -18,7 +18,7 @@
 mod internal;
 pub mod raw;
 
-use std::borrow::Cow;
+use std::{borrow::Cow, ops::RangeBounds};
 
 use internal::{EntryIterator, TxImpl};
 use utils::shallow_clone::ShallowClone;
@@ -97,6 +97,16 @@ impl<B: Backend, Sch: Schema> Storage<B, Sch> {
         let _schema = std::marker::PhantomData;
         Ok(TransactionRw { dbtx, _schema })
     }
+
+    /// Make sure all the committed transactions are durable
+    pub fn flush(&self) -> crate::Result<()> {
+        backend::BackendImpl::flush(&self.backend)
+    }
+
+    /// Allow the backend to write out multiple committed transactions at once, if supported
+    pub fn set_write_batching(&self, enabled: bool) -> crate::Result<()> {
+        backend::BackendImpl::set_write_batching(&self.backend, enabled)
+    }
 }
 
 pub trait MakeMapRef<'tx, B: Backend, Sch: Schema>: TxImpl + Sized {
@@ -199,6 +209,23 @@ where
         internal::get::<DbMap, _, _>(self.dbtx, self.map_id, key)
     }
 
+    /// Iterator over all entries of the map, ordered by the encoded key
+    pub fn iter(&self) -> crate::Result<impl EntryIterator<DbMap> + 'tx> {
+        internal::prefix_iter(self.dbtx, self.map_id, Vec::new())
+    }
+
+    /// Iterator over all keys of the map, ordered by the encoded key
+    pub fn iter_keys(&self) -> crate::Result<impl Iterator<Item = DbMap::Key> + 'tx> {
+        internal::prefix_iter_keys::<DbMap, _>(self.dbtx, self.map_id, Vec::new())
+    }
+
+    /// Iterator over all decoded entries of the map, ordered by the encoded key
+    pub fn iter_decoded(
+        &self,
+    ) -> crate::Result<impl Iterator<Item = (DbMap::Key, DbMap::Value)> + 'tx> {
+        self.iter().map(|item| item.map(|(k, v)| (k, v.decode())))
+    }
+
     /// Iterator over entries with key starting with given prefix
     pub fn prefix_iter<Pfx>(&self, prefix: &Pfx) -> crate::Result<impl EntryIterator<DbMap> + 'tx>
     where
@@ -263,6 +290,38 @@ where
     ) -> crate::Result<impl Iterator<Item = (DbMap::Key, DbMap::Value)> + 'tx> {
         self.greater_equal_iter(key).map(|item| item.map(|(k, v)| (k, v.decode())))
     }
+
+    /// Iterator over entries with keys within the specified range.
+    ///
+    /// Same as with `greater_equal_iter`, only the `Encode`d representations of keys are
+    /// compared, so the range bounds are only meaningful if the relevant parts of `Key` are
+    /// wrapped in `OrderPreservingValue`.
+    pub fn range(
+        &self,
+        range: impl RangeBounds<DbMap::Key>,
+    ) -> crate::Result<impl EntryIterator<DbMap> + 'tx> {
+        let start = range.start_bound().map(Encode::encode);
+        let end = range.end_bound().map(Encode::encode);
+        internal::range_iter(self.dbtx, self.map_id, start, end)
+    }
+
+    /// Same as `range`, but only the keys are returned.
+    pub fn range_keys(
+        &self,
+        range: impl RangeBounds<DbMap::Key>,
+    ) -> crate::Result<impl Iterator<Item = DbMap::Key> + 'tx> {
+        let start = range.start_bound().map(Encode::encode);
+        let end = range.end_bound().map(Encode::encode);
+        internal::range_iter_keys::<DbMap, _>(self.dbtx, self.map_id, start, end)
+    }
+
+    /// Same as `range`, but already decoded values are returned.
+    pub fn range_decoded(
+        &self,
+        range: impl RangeBounds<DbMap::Key>,
+    ) -> crate::Result<impl Iterator<Item = (DbMap::Key, DbMap::Value)> + 'tx> {
+        self.range(range).map(|item| item.map(|(k, v)| (k, v.decode())))
+    }
 }
 
 /// Represents a mutable view of a key-value map
@@ -296,6 +355,11 @@ where
         internal::get::<DbMap, _, _>(self.dbtx, self.map_id, key)
     }
 
+    /// Iterator over all entries of the map, ordered by the encoded key
+    pub fn iter(&self) -> crate::Result<impl EntryIterator<DbMap> + '_> {
+        internal::prefix_iter(self.dbtx, self.map_id, Vec::new())
+    }
+
     /// Iterator over entries with key starting with given prefix
     pub fn prefix_iter<Pfx>(&self, prefix: &Pfx) -> crate::Result<impl EntryIterator<DbMap> + '_>
     where
@@ -304,6 +368,16 @@ where
     {
         internal::prefix_iter(self.dbtx, self.map_id, prefix.encode())
     }
+
+    /// Iterator over entries with keys within the specified range, see [MapRef::range].
+    pub fn range(
+        &self,
+        range: impl RangeBounds<DbMap::Key>,
+    ) -> crate::Result<impl EntryIterator<DbMap> + '_> {
+        let start = range.start_bound().map(Encode::encode);
+        let end = range.end_bound().map(Encode::encode);
+        internal::range_iter(self.dbtx, self.map_id, start, end)
+    }
 }
 
 impl<Tx: TxImpl, DbMap: schema::DbMap> MapMut<'_, Tx, DbMap>
@@ -38,6 +38,31 @@
 //! type `H256` representing the transaction ID. The result is an iterator over all
 //! `(Outpoint, Utxo)` pairs that belong to given transaction.
 //!
+//! # Ordered iteration
+//!
+//! Apart from prefix iteration, a map can be iterated over as a whole (`map.iter()`) or over
+//! a range of keys (`map.range(start..end)`). In all cases, the entries are ordered
+//! lexicographically by the encoded key, which is the same for all backends. For the ordering to
+//! match the ordering of the keys themselves, the relevant parts of the key have to be wrapped in
+//! [OrderPreservingValue].
+//!
+//! The iterators borrow the transaction they have been created from, so the transaction cannot
+//! be committed or aborted while the iterator is still alive:
+//!
+//! ```compile_fail
+//! # use storage::{MakeMapRef, Storage};
+//! # storage::decl_schema! {
+//! #     Schema {
+//! #         MyMap: Map<String, u64>,
+//! #     }
+//! # }
+//! let store = Storage::<_, Schema>::new(storage_inmemory::InMemory::new()).unwrap();
+//! let tx = store.transaction_rw(None).unwrap();
+//! let mut iter = tx.get::<MyMap, _>().iter().unwrap();
+//! tx.commit().unwrap();
+//! iter.next();
+//! ```
+//!
 //! # Example
 //!
 //! ```
@@ -99,7 +124,7 @@ pub mod schema;
 
 // Re-export user-facing items from core
 pub use order_preserving_value::OrderPreservingValue;
-pub use storage_core::{error, Backend, Error, Result};
+pub use storage_core::{adaptor::WriteBatching, error, Backend, Error, Result};
 
 // Re-export the interface types
 pub use database::*;
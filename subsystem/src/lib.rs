@@ -31,7 +31,9 @@
 //! 1. As soon as any subsystem terminates, the main task is notified.
 //! 2. The main task broadcasts the shutdown request to all subsystems. The subsystems react to the
 //!    request by shutting themselves down.
-//! 3. The main task waits for all subsystems to terminate.
+//! 3. The main task waits for all subsystems to terminate, one by one in the reverse order of
+//!    creation. A subsystem that does not terminate within its shutdown timeout is aborted and
+//!    the shutdown proceeds with the next one.
 
 mod calls;
 mod manager;
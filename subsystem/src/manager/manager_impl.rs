@@ -88,6 +88,7 @@ impl Manager {
         E: std::error::Error + 'static,
     {
         let full_name = self.config.full_name_of(subsys_name);
+        let shutdown_timeout = self.config.shutdown_timeout_of(subsys_name);
 
         // Shutdown-related channels
         let (shutdown_tx, shutdown_rx) = oneshot::channel();
@@ -109,6 +110,7 @@ impl Manager {
 
         self.subsystems.push(SubsystemData {
             full_name,
+            shutdown_timeout,
             task,
             shutdown_tx,
         });
@@ -162,9 +164,11 @@ impl Manager {
             Err(err) => log::error!("Manager {manager_name} shutting down: {err}"),
         }
 
-        // Shut down the subsystems in the reverse order of creation.
+        // Shut down the subsystems in the reverse order of creation. A subsystem that fails to
+        // shut down in time is aborted, so the subsystems created before it (typically the ones
+        // owning the storage) still get the chance to shut down cleanly.
         for subsys in subsystems.into_iter().rev() {
-            subsys.shutdown(self.config.shutdown_timeout_per_subsystem).await;
+            subsys.shutdown().await;
         }
 
         log::info!("Manager {manager_name} terminated");
@@ -198,6 +202,7 @@ impl Manager {
 /// Information about each subsystem stored by the manager
 struct SubsystemData<T> {
     full_name: String,
+    shutdown_timeout: Option<Duration>,
     shutdown_tx: oneshot::Sender<()>,
     task: T,
 }
@@ -206,11 +211,13 @@ impl<T> SubsystemData<T> {
     fn map_task<U>(self, f: impl FnOnce(T) -> U) -> SubsystemData<U> {
         let Self {
             full_name,
+            shutdown_timeout,
             shutdown_tx,
             task,
         } = self;
         SubsystemData {
             full_name,
+            shutdown_timeout,
             shutdown_tx,
             task: f(task),
         }
@@ -218,22 +225,32 @@ impl<T> SubsystemData<T> {
 }
 
 impl SubsystemData<JoinHandle<()>> {
-    async fn shutdown(self, timeout: Option<Duration>) {
-        let full_name = self.full_name;
+    async fn shutdown(self) {
+        let Self {
+            full_name,
+            shutdown_timeout,
+            shutdown_tx,
+            mut task,
+        } = self;
 
-        if let Err(()) = self.shutdown_tx.send(()) {
+        if let Err(()) = shutdown_tx.send(()) {
             log::warn!("Subsystem {full_name} is already down");
         }
 
-        let shutdown_future =
-            async { task::handle_result(&full_name, "top-level", self.task.await) };
-
-        if let Some(timeout) = timeout {
+        if let Some(timeout) = shutdown_timeout {
             cfg_if::cfg_if! {
                 if #[cfg(all(feature = "time", not(loom)))] {
                     // Wait for shutdown under a timeout.
-                    if tokio::time::timeout(timeout, shutdown_future).await.is_err() {
-                        log::error!("Subsystem {full_name} shutdown timed out");
+                    match tokio::time::timeout(timeout, &mut task).await {
+                        Ok(res) => task::handle_result(&full_name, "top-level", res),
+                        Err(_) => {
+                            // The task is not awaited after the abort since it may be stuck
+                            // without ever reaching a point where it could be cancelled.
+                            log::error!(
+                                "Subsystem {full_name} shutdown timed out after {timeout:?}, aborting"
+                            );
+                            task.abort();
+                        }
                     }
                 } else {
                     // Timeout was requested but is not supported
@@ -242,12 +259,12 @@ impl SubsystemData<JoinHandle<()>> {
                     } else if cfg!(loom) {
                         log::warn!("Shutdown timeout disabled under loom");
                     }
-                    shutdown_future.await
+                    task::handle_result(&full_name, "top-level", task.await)
                 }
             }
         } else {
             // No timeout requested, just wait for shutdown
-            shutdown_future.await
+            task::handle_result(&full_name, "top-level", task.await)
         };
     }
 }
@@ -20,7 +20,7 @@ mod shutdown_signal;
 
 pub use manager_impl::{Manager, ManagerJoinHandle, ShutdownTrigger};
 
-use std::time::Duration;
+use std::{collections::BTreeMap, time::Duration};
 
 /// Subsystem manager configuration options
 pub struct ManagerConfig {
@@ -30,6 +30,9 @@ pub struct ManagerConfig {
     /// Shutdown timeout. Set to `None` for no (i.e. unlimited) timeout.
     pub shutdown_timeout_per_subsystem: Option<Duration>,
 
+    /// Shutdown timeouts of individual subsystems, overriding the one above.
+    pub subsystem_shutdown_timeouts: BTreeMap<&'static str, Option<Duration>>,
+
     /// Whether to enable signal handlers
     pub enable_signal_handlers: bool,
 }
@@ -47,6 +50,7 @@ impl ManagerConfig {
         Self {
             name,
             shutdown_timeout_per_subsystem: Self::DEFAULT_SHUTDOWN_TIMEOUT,
+            subsystem_shutdown_timeouts: BTreeMap::new(),
             enable_signal_handlers: false,
         }
     }
@@ -63,9 +67,30 @@ impl ManagerConfig {
         self
     }
 
+    /// How long to wait for the given subsystem before force-termination. Takes precedence over
+    /// the timeout set for all subsystems.
+    #[cfg(all(feature = "time", not(loom)))]
+    pub fn with_subsystem_shutdown_timeout(
+        mut self,
+        subsys_name: &'static str,
+        timeout: Duration,
+    ) -> Self {
+        self.subsystem_shutdown_timeouts.insert(subsys_name, Some(timeout));
+        self
+    }
+
+    /// Shutdown timeout of given subsystem.
+    pub fn shutdown_timeout_of(&self, subsys_name: &str) -> Option<Duration> {
+        self.subsystem_shutdown_timeouts
+            .get(subsys_name)
+            .copied()
+            .unwrap_or(self.shutdown_timeout_per_subsystem)
+    }
+
     /// Disable the timeout for subsystem shutdown.
     pub fn disable_shutdown_timeout(mut self) -> Self {
         self.shutdown_timeout_per_subsystem = None;
+        self.subsystem_shutdown_timeouts.clear();
         self
     }
 
@@ -15,6 +15,14 @@
 
 #![cfg(all(feature = "time", not(loom)))]
 
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
 // A subsystem that blocks the shutdown process.
 struct NoExit;
 
@@ -53,3 +61,55 @@ async fn shutdown_timeout() {
         assert!(logs.iter().any(|entry| entry.body.contains("shutdown timed out")));
     });
 }
+
+// A subsystem that records it has been shut down.
+struct Flushing(Arc<AtomicBool>);
+
+#[async_trait::async_trait]
+impl subsystem::Subsystem for Flushing {
+    type Interface = Self;
+
+    fn interface_ref(&self) -> &Self {
+        self
+    }
+
+    fn interface_mut(&mut self) -> &mut Self {
+        self
+    }
+
+    async fn shutdown(self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+#[tokio::test]
+async fn stuck_subsystem_aborted() {
+    testing_logger::setup();
+
+    // The stuck subsystem has a much shorter grace period than the others.
+    let config = subsystem::ManagerConfig::new("stuck_test")
+        .with_shutdown_timeout_per_subsystem(Duration::from_secs(600))
+        .with_subsystem_shutdown_timeout("stuck", Duration::from_millis(100));
+    let mut man = subsystem::Manager::new_with_config(config);
+
+    // Subsystems are shut down in the reverse order, the stuck one is in the middle.
+    let flushed = [(); 2].map(|()| Arc::new(AtomicBool::new(false)));
+    man.add_subsystem("storage", Flushing(Arc::clone(&flushed[0])));
+    man.add_subsystem("stuck", NoExit);
+    man.add_subsystem("network", Flushing(Arc::clone(&flushed[1])));
+
+    man.make_shutdown_trigger().initiate();
+    tokio::time::timeout(Duration::from_secs(60), man.main())
+        .await
+        .expect("the stuck subsystem to be aborted");
+
+    assert!(flushed.iter().all(|flushed| flushed.load(Ordering::SeqCst)));
+
+    testing_logger::validate(|logs| {
+        let timeouts: Vec<_> =
+            logs.iter().filter(|entry| entry.body.contains("shutdown timed out")).collect();
+        assert_eq!(timeouts.len(), 1);
+        assert!(timeouts[0].body.contains("stuck_test/stuck"));
+        assert_eq!(timeouts[0].level, logging::log::Level::Error);
+    });
+}
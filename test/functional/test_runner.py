@@ -133,6 +133,7 @@ BASE_SCRIPTS = [
     'wallet_account_info_rpc.py',
     'wallet_conflict.py',
     'wallet_list_txs.py',
+    'wallet_reorg_simulation.py',
     'wallet_sign_message.py',
     'wallet_sign_message_rpc.py',
     'wallet_cold_wallet_send.py',
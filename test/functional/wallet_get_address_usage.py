@@ -114,23 +114,23 @@ class WalletGetAddressUsage(BitcoinTestFramework):
             output = await wallet.sync()
             assert_in("Success", output)
 
-            expected_output = """+-------+----------------------------------------------+--------------------------------+
-| Index | Address                                      | Is used in transaction history |
-+=======+==============================================+================================+
-| 0     | rmt1qx5p4r2en7c99mpmg2tz9hucxfarf4k6dypq388a | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 1     | rmt1q9jvqp9p8rzp2prmpa8y9vde7yrvlxgz3s54n787 | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 2     | rmt1qx7dwah3rtkh2mv7lyd4qserqx59mqjknc6qdn77 | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 3     | rmt1qxrkx54pykusw7am7zr282t6tzsl3wzkysrh0k2a | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 4     | rmt1qyyra5j3qduhyd43wa50lpn2ddpg9ql0u50ceu68 | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 5     | rmt1q8upmt2mjxel84msaqjj2rkquguvswwzquy6w8sn | No                             |
-+-------+----------------------------------------------+--------------------------------+
-| 6     | rmt1q8lrw5tzgmwjnsc26v8qfu8k2jmddpmhwqz6kwt7 | No                             |
-+-------+----------------------------------------------+--------------------------------+"""
+            expected_output = """+-------+----------------------------------------------+--------------------------------+-------+
+| Index | Address                                      | Is used in transaction history | Label |
++=======+==============================================+================================+=======+
+| 0     | rmt1qx5p4r2en7c99mpmg2tz9hucxfarf4k6dypq388a | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 1     | rmt1q9jvqp9p8rzp2prmpa8y9vde7yrvlxgz3s54n787 | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 2     | rmt1qx7dwah3rtkh2mv7lyd4qserqx59mqjknc6qdn77 | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 3     | rmt1qxrkx54pykusw7am7zr282t6tzsl3wzkysrh0k2a | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 4     | rmt1qyyra5j3qduhyd43wa50lpn2ddpg9ql0u50ceu68 | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 5     | rmt1q8upmt2mjxel84msaqjj2rkquguvswwzquy6w8sn | No                             |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 6     | rmt1q8lrw5tzgmwjnsc26v8qfu8k2jmddpmhwqz6kwt7 | No                             |       |
++-------+----------------------------------------------+--------------------------------+-------+"""
             output = await wallet.get_addresses_usage()
             for (line, expected_line) in zip(output.split(), expected_output.split()):
                 assert_equal(line, expected_line)
@@ -155,25 +155,25 @@ class WalletGetAddressUsage(BitcoinTestFramework):
             assert_in("The transaction was submitted successfully", await wallet.create_stake_pool(stake_pool_amount, 0, 0.5, decommission_address))
             self.generate_block()
 
-            expected_output = """+-------+----------------------------------------------+--------------------------------+
-| Index | Address                                      | Is used in transaction history |
-+=======+==============================================+================================+
-| 0     | rmt1qx5p4r2en7c99mpmg2tz9hucxfarf4k6dypq388a | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 1     | rmt1q9jvqp9p8rzp2prmpa8y9vde7yrvlxgz3s54n787 | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 2     | rmt1qx7dwah3rtkh2mv7lyd4qserqx59mqjknc6qdn77 | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 3     | rmt1qxrkx54pykusw7am7zr282t6tzsl3wzkysrh0k2a | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 4     | rmt1qyyra5j3qduhyd43wa50lpn2ddpg9ql0u50ceu68 | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 5     | rmt1q8upmt2mjxel84msaqjj2rkquguvswwzquy6w8sn | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 6     | rmt1q8lrw5tzgmwjnsc26v8qfu8k2jmddpmhwqz6kwt7 | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 7     | rmt1q824xhhlcdazxj38yuqr6llqz3wm7whhgvmyvyjz | Yes                            |
-+-------+----------------------------------------------+--------------------------------+"""
+            expected_output = """+-------+----------------------------------------------+--------------------------------+-------+
+| Index | Address                                      | Is used in transaction history | Label |
++=======+==============================================+================================+=======+
+| 0     | rmt1qx5p4r2en7c99mpmg2tz9hucxfarf4k6dypq388a | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 1     | rmt1q9jvqp9p8rzp2prmpa8y9vde7yrvlxgz3s54n787 | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 2     | rmt1qx7dwah3rtkh2mv7lyd4qserqx59mqjknc6qdn77 | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 3     | rmt1qxrkx54pykusw7am7zr282t6tzsl3wzkysrh0k2a | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 4     | rmt1qyyra5j3qduhyd43wa50lpn2ddpg9ql0u50ceu68 | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 5     | rmt1q8upmt2mjxel84msaqjj2rkquguvswwzquy6w8sn | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 6     | rmt1q8lrw5tzgmwjnsc26v8qfu8k2jmddpmhwqz6kwt7 | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 7     | rmt1q824xhhlcdazxj38yuqr6llqz3wm7whhgvmyvyjz | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+"""
             output = await wallet.get_addresses_usage()
             for (line, expected_line) in zip(output.split(), expected_output.split()):
                 assert_equal(line, expected_line)
@@ -0,0 +1,148 @@
+#!/usr/bin/env python3
+#  Copyright (c) 2023 RBB S.r.l
+#  Copyright (c) 2017-2021 The Bitcoin Core developers
+#  opensource@mintlayer.org
+#  SPDX-License-Identifier: MIT
+#  Licensed under the MIT License;
+#  you may not use this file except in compliance with the License.
+#  You may obtain a copy of the License at
+#
+#  https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+#
+#  Unless required by applicable law or agreed to in writing, software
+#  distributed under the License is distributed on an "AS IS" BASIS,
+#  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+#  See the License for the specific language governing permissions and
+#  limitations under the License.
+"""Wallet reorg simulation test
+
+Check that:
+* We can create a new wallet and fund it,
+* send a transaction from the wallet and get it confirmed,
+* replace the block with the transaction using blockprod_invalidate_tip_and_remine,
+* the transaction is back in the mempool and the wallet doesn't see it as confirmed anymore,
+* the transaction is confirmed again in the new chain after a new block is generated,
+* a shorter side chain generated with blockprod_fork_at doesn't become the best chain.
+"""
+
+from test_framework.test_framework import BitcoinTestFramework
+from test_framework.mintlayer import (make_tx, reward_input, ATOMS_PER_COIN)
+from test_framework.util import assert_in, assert_equal
+from test_framework.mintlayer import block_input_data_obj
+from test_framework.wallet_cli_controller import WalletCliController
+
+import asyncio
+import sys
+import random
+
+
+class WalletReorgSimulation(BitcoinTestFramework):
+
+    def set_test_params(self):
+        self.setup_clean_chain = True
+        self.num_nodes = 1
+        self.extra_args = [[
+            "--blockprod-min-peers-to-produce-blocks=0",
+        ]]
+
+    def setup_network(self):
+        self.setup_nodes()
+        self.sync_all(self.nodes[0:1])
+
+    def block_input_data(self):
+        block_input_data = { "PoW": { "reward_destination": "AnyoneCanSpend" } }
+        return block_input_data_obj.encode(block_input_data).to_hex()[2:]
+
+    def generate_block(self):
+        node = self.nodes[0]
+
+        # create a new block, taking transactions from mempool
+        block = node.blockprod_generate_block(self.block_input_data(), [], [], "FillSpaceFromMempool")
+        node.chainstate_submit_block(block)
+        block_id = node.chainstate_best_block_id()
+
+        # Wait for mempool to sync
+        self.wait_until(lambda: node.mempool_local_best_block_id() == block_id, timeout = 5)
+
+        return block_id
+
+    def run_test(self):
+        if 'win32' in sys.platform:
+            asyncio.set_event_loop_policy(asyncio.WindowsProactorEventLoopPolicy())
+        asyncio.run(self.async_test())
+
+    async def async_test(self):
+        node = self.nodes[0]
+        async with WalletCliController(node, self.config, self.log) as wallet:
+            await wallet.create_wallet()
+
+            pub_key_bytes = await wallet.new_public_key()
+            assert_equal(len(pub_key_bytes), 33)
+
+            tip_id = node.chainstate_best_block_id()
+
+            # Fund the wallet
+            coins_to_send = random.randint(200, 300)
+            output = {
+                    'Transfer': [ { 'Coin': coins_to_send * ATOMS_PER_COIN }, { 'PublicKey': {'key': {'Secp256k1Schnorr' : {'pubkey_data': pub_key_bytes}}} } ],
+            }
+            encoded_tx, _ = make_tx([reward_input(tip_id)], [output], 0)
+            node.mempool_submit_transaction(encoded_tx, {})
+
+            self.generate_block() # Block 1
+            self.generate_block() # Block 2
+            assert_in("Success", await wallet.sync())
+            assert_in(f"Coins amount: {coins_to_send}", await wallet.get_balance())
+
+            # Send a transaction from the wallet and get it confirmed in block 3
+            address = await wallet.new_address()
+            output = await wallet.send_to_address(address, 1)
+            assert_in("The transaction was submitted successfully", output)
+            tx_id = (await wallet.list_pending_transactions())[0]
+            assert node.mempool_contains_tx(tx_id)
+
+            self.generate_block() # Block 3
+            self.generate_block() # Block 4
+            self.generate_block() # Block 5
+            assert not node.mempool_contains_tx(tx_id)
+            assert_in("Success", await wallet.sync())
+            assert_equal(await wallet.get_best_block_height(), '5')
+            assert_equal((await wallet.get_transaction(tx_id))[1]['Confirmed'][0], 3)
+
+            # Replace blocks 3-5 with 4 empty blocks
+            info = node.blockprod_invalidate_tip_and_remine(self.block_input_data(), 3, 4)
+            assert info['is_new_chain_best']
+            assert_equal(len(info['new_block_ids']), 4)
+            assert_equal(info['best_block_height'], 6)
+            assert_equal(info['best_block_id'], node.chainstate_best_block_id())
+            assert_equal(node.mempool_local_best_block_id(), info['best_block_id'])
+            assert_equal(node.chainstate_block_id_at_height(3), info['new_block_ids'][0])
+
+            # The disconnected transaction is back in the mempool
+            assert node.mempool_contains_tx(tx_id)
+
+            # The wallet follows the reorg and the transaction is no longer confirmed
+            assert_in("Success", await wallet.sync())
+            assert_equal(await wallet.get_best_block(), info['best_block_id'])
+            assert_equal(await wallet.get_best_block_height(), '6')
+            output = await wallet.get_transaction(tx_id)
+            assert not isinstance(output, list) or 'Confirmed' not in output[1]
+
+            # The transaction gets confirmed again in the new chain
+            self.generate_block() # Block 7
+            assert not node.mempool_contains_tx(tx_id)
+            assert_in("Success", await wallet.sync())
+            assert_equal((await wallet.get_transaction(tx_id))[1]['Confirmed'][0], 7)
+
+            # A shorter side chain doesn't replace the main chain
+            best_block_id = node.chainstate_best_block_id()
+            info = node.blockprod_fork_at(self.block_input_data(), 4, 1)
+            assert not info['is_new_chain_best']
+            assert_equal(info['best_block_id'], best_block_id)
+            assert_equal(info['best_block_height'], 7)
+            assert_in("Success", await wallet.sync())
+            assert_equal((await wallet.get_transaction(tx_id))[1]['Confirmed'][0], 7)
+
+
+if __name__ == '__main__':
+    WalletReorgSimulation().main()
@@ -27,6 +27,10 @@ async fn main() -> Result<(), node_lib::Error> {
                 "Data directory is now clean. Please restart the node without `--clean-data` flag"
             );
         }
+        node_lib::NodeSetupResult::ConfigDumped(config) => {
+            print!("{config}");
+            return Ok(());
+        }
     };
     node.main().await;
     Ok(())
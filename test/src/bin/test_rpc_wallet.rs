@@ -17,10 +17,9 @@ use logging::log;
 use wallet_rpc_lib::cmdline;
 
 async fn run() -> Result<(), Box<dyn std::error::Error>> {
-    let (ws_config, rpc_config) =
-        <cmdline::WalletRpcDaemonArgs as clap::Parser>::parse().into_config()?;
+    let action = <cmdline::WalletRpcDaemonArgs as clap::Parser>::parse().into_action()?;
 
-    wallet_rpc_lib::run(ws_config, rpc_config).await?;
+    wallet_rpc_lib::run_action(action).await?;
 
     Ok(())
 }
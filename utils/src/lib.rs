@@ -32,6 +32,7 @@ pub mod exp_rand;
 pub mod graph_traversals;
 pub mod log_utils;
 pub mod maybe_encrypted;
+pub mod metrics;
 pub mod newtype;
 pub mod once_destructor;
 pub mod qrcode;
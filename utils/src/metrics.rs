@@ -0,0 +1,371 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A small registry of process-wide metrics that can be exported in the Prometheus text format.
+//!
+//! The metrics are plain atomics, so updating them is cheap; the registry lock is only taken
+//! when a metric is registered or when the metrics are rendered.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicI64, AtomicU64, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+};
+
+/// A value that can only go up
+#[derive(Debug, Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    pub fn inc(&self) {
+        self.inc_by(1);
+    }
+
+    pub fn inc_by(&self, value: u64) {
+        self.0.fetch_add(value, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A value that can go up and down
+#[derive(Debug, Default)]
+pub struct Gauge(AtomicI64);
+
+impl Gauge {
+    pub fn set(&self, value: i64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn dec(&self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Counts the observed values in buckets with the given upper bounds
+#[derive(Debug)]
+pub struct Histogram {
+    bounds: Vec<f64>,
+    /// The counts of the values falling into each bucket, not cumulative
+    buckets: Vec<AtomicU64>,
+    /// The sum of the observed values, as the bits of `f64`
+    sum: AtomicU64,
+    count: AtomicU64,
+}
+
+impl Histogram {
+    fn new(bounds: &[f64]) -> Self {
+        Self {
+            bounds: bounds.to_vec(),
+            buckets: bounds.iter().map(|_| AtomicU64::new(0)).collect(),
+            sum: AtomicU64::new(0f64.to_bits()),
+            count: AtomicU64::new(0),
+        }
+    }
+
+    pub fn observe(&self, value: f64) {
+        if let Some(idx) = self.bounds.iter().position(|bound| value <= *bound) {
+            self.buckets[idx].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+
+        let _ = self.sum.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |sum| {
+            Some((f64::from_bits(sum) + value).to_bits())
+        });
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    pub fn sum(&self) -> f64 {
+        f64::from_bits(self.sum.load(Ordering::Relaxed))
+    }
+}
+
+/// The default histogram buckets for durations in seconds
+pub const DEFAULT_DURATION_BUCKETS: [f64; 11] =
+    [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+pub type GaugeFn = Box<dyn Fn() -> i64 + Send + Sync>;
+
+enum Metric {
+    Counter(Arc<Counter>),
+    Gauge(Arc<Gauge>),
+    GaugeFn(GaugeFn),
+    Histogram(Arc<Histogram>),
+}
+
+impl Metric {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Metric::Counter(_) => "counter",
+            Metric::Gauge(_) | Metric::GaugeFn(_) => "gauge",
+            Metric::Histogram(_) => "histogram",
+        }
+    }
+}
+
+struct Family {
+    help: String,
+    /// The metrics of the family by their labels
+    metrics: BTreeMap<Vec<(String, String)>, Metric>,
+}
+
+#[derive(Default)]
+pub struct Registry {
+    families: Mutex<BTreeMap<String, Family>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the metric with the given name and labels, registering it first if needed.
+    ///
+    /// Panics if a metric of another type is registered with the same name.
+    fn get_or_register<T>(
+        &self,
+        name: &str,
+        help: &str,
+        labels: &[(&str, &str)],
+        make: impl FnOnce() -> Metric,
+        get: impl FnOnce(&Metric) -> Option<T>,
+    ) -> T {
+        let mut families = self.families.lock().expect("poisoned mutex");
+        let family = families.entry(name.to_owned()).or_insert_with(|| Family {
+            help: help.to_owned(),
+            metrics: BTreeMap::new(),
+        });
+        let labels = labels.iter().map(|(k, v)| ((*k).to_owned(), (*v).to_owned())).collect();
+        let metric = family.metrics.entry(labels).or_insert_with(make);
+        let type_name = metric.type_name();
+        let result = get(metric);
+
+        // All the metrics of a family must be of the same type
+        match result {
+            Some(result) if family.metrics.values().all(|m| m.type_name() == type_name) => result,
+            _ => panic!("Metric {name} is registered with another type"),
+        }
+    }
+
+    pub fn counter(&self, name: &str, help: &str, labels: &[(&str, &str)]) -> Arc<Counter> {
+        self.get_or_register(
+            name,
+            help,
+            labels,
+            || Metric::Counter(Arc::default()),
+            |metric| match metric {
+                Metric::Counter(counter) => Some(Arc::clone(counter)),
+                Metric::Gauge(_) | Metric::GaugeFn(_) | Metric::Histogram(_) => None,
+            },
+        )
+    }
+
+    pub fn gauge(&self, name: &str, help: &str, labels: &[(&str, &str)]) -> Arc<Gauge> {
+        self.get_or_register(
+            name,
+            help,
+            labels,
+            || Metric::Gauge(Arc::default()),
+            |metric| match metric {
+                Metric::Gauge(gauge) => Some(Arc::clone(gauge)),
+                Metric::Counter(_) | Metric::GaugeFn(_) | Metric::Histogram(_) => None,
+            },
+        )
+    }
+
+    /// Register a gauge whose value is computed when the metrics are rendered;
+    /// does nothing if the gauge is already registered.
+    pub fn gauge_fn(&self, name: &str, help: &str, labels: &[(&str, &str)], func: GaugeFn) {
+        self.get_or_register(
+            name,
+            help,
+            labels,
+            || Metric::GaugeFn(func),
+            |metric| match metric {
+                Metric::GaugeFn(_) => Some(()),
+                Metric::Counter(_) | Metric::Gauge(_) | Metric::Histogram(_) => None,
+            },
+        )
+    }
+
+    pub fn histogram(
+        &self,
+        name: &str,
+        help: &str,
+        labels: &[(&str, &str)],
+        bounds: &[f64],
+    ) -> Arc<Histogram> {
+        self.get_or_register(
+            name,
+            help,
+            labels,
+            || Metric::Histogram(Arc::new(Histogram::new(bounds))),
+            |metric| match metric {
+                Metric::Histogram(histogram) => Some(Arc::clone(histogram)),
+                Metric::Counter(_) | Metric::Gauge(_) | Metric::GaugeFn(_) => None,
+            },
+        )
+    }
+
+    /// Render all the registered metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let families = self.families.lock().expect("poisoned mutex");
+        let mut out = String::new();
+
+        for (name, family) in families.iter() {
+            let type_name = match family.metrics.values().next() {
+                Some(metric) => metric.type_name(),
+                None => continue,
+            };
+            let _ = writeln!(out, "# HELP {name} {}", family.help);
+            let _ = writeln!(out, "# TYPE {name} {type_name}");
+
+            for (labels, metric) in family.metrics.iter() {
+                match metric {
+                    Metric::Counter(counter) => {
+                        write_sample(&mut out, name, labels, None, counter.get());
+                    }
+                    Metric::Gauge(gauge) => {
+                        write_sample(&mut out, name, labels, None, gauge.get());
+                    }
+                    Metric::GaugeFn(func) => {
+                        write_sample(&mut out, name, labels, None, func());
+                    }
+                    Metric::Histogram(histogram) => {
+                        let bucket_name = format!("{name}_bucket");
+                        let mut cumulative = 0;
+                        for (bound, bucket) in histogram.bounds.iter().zip(&histogram.buckets) {
+                            cumulative += bucket.load(Ordering::Relaxed);
+                            let le = bound.to_string();
+                            write_sample(&mut out, &bucket_name, labels, Some(&le), cumulative);
+                        }
+                        let count = histogram.count();
+                        write_sample(&mut out, &bucket_name, labels, Some("+Inf"), count);
+                        write_sample(
+                            &mut out,
+                            &format!("{name}_sum"),
+                            labels,
+                            None,
+                            histogram.sum(),
+                        );
+                        write_sample(&mut out, &format!("{name}_count"), labels, None, count);
+                    }
+                }
+            }
+        }
+
+        out
+    }
+}
+
+fn write_sample(
+    out: &mut String,
+    name: &str,
+    labels: &[(String, String)],
+    le: Option<&str>,
+    value: impl std::fmt::Display,
+) {
+    let labels = labels
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .chain(le.map(|le| ("le", le)))
+        .map(|(k, v)| format!("{k}=\"{}\"", escape_label_value(v)))
+        .collect::<Vec<_>>();
+
+    if labels.is_empty() {
+        let _ = writeln!(out, "{name} {value}");
+    } else {
+        let _ = writeln!(out, "{name}{{{}}} {value}", labels.join(","));
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// The process-wide registry that the node subsystems report their metrics to
+pub fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(Registry::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render() {
+        let registry = Registry::new();
+
+        let counter = registry.counter("test_events_total", "Events", &[("kind", "a\"b")]);
+        counter.inc_by(3);
+        let gauge = registry.gauge("test_level", "Level", &[]);
+        gauge.set(5);
+        gauge.dec();
+        registry.gauge_fn("test_computed", "Computed", &[], Box::new(|| 42));
+        let histogram = registry.histogram("test_duration_seconds", "Duration", &[], &[0.1, 1.0]);
+        histogram.observe(0.0625);
+        histogram.observe(0.5);
+        histogram.observe(4.0);
+
+        // The same metric is returned for the same name and labels
+        registry.counter("test_events_total", "Events", &[("kind", "a\"b")]).inc();
+        assert_eq!(counter.get(), 4);
+
+        let expected = "\
+# HELP test_computed Computed
+# TYPE test_computed gauge
+test_computed 42
+# HELP test_duration_seconds Duration
+# TYPE test_duration_seconds histogram
+test_duration_seconds_bucket{le=\"0.1\"} 1
+test_duration_seconds_bucket{le=\"1\"} 2
+test_duration_seconds_bucket{le=\"+Inf\"} 3
+test_duration_seconds_sum 4.5625
+test_duration_seconds_count 3
+# HELP test_events_total Events
+# TYPE test_events_total counter
+test_events_total{kind=\"a\\\"b\"} 4
+# HELP test_level Level
+# TYPE test_level gauge
+test_level 4
+";
+        assert_eq!(registry.render(), expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn type_mismatch() {
+        let registry = Registry::new();
+        registry.counter("test_metric", "Metric", &[]);
+        registry.gauge("test_metric", "Metric", &[]);
+    }
+}
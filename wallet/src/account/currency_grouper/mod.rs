@@ -18,17 +18,13 @@ use crate::{WalletError, WalletResult};
 use std::collections::BTreeMap;
 
 use common::{
-    chain::{output_value::OutputValue, tokens::TokenId, ChainConfig, TxOutput},
+    chain::{output_value::OutputValue, ChainConfig, TxOutput},
     primitives::{Amount, BlockHeight},
 };
 
 use super::UtxoSelectorError;
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, serde::Serialize, serde::Deserialize)]
-pub enum Currency {
-    Coin,
-    Token(TokenId),
-}
+pub use wallet_types::currency::Currency;
 
 pub(crate) fn group_outputs<T, Grouped: Clone>(
     outputs: impl Iterator<Item = T>,
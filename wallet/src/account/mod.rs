@@ -32,12 +32,16 @@ use common::size_estimation::{
 };
 use common::Uint256;
 use crypto::key::hdkd::child_number::ChildNumber;
-use mempool::FeeRate;
+use mempool::error::PackageLimitError;
+use mempool::{FeeRate, PackageLimits};
 use serialization::hex_encoded::HexEncoded;
 use utils::ensure;
 pub use utxo_selector::UtxoSelectorError;
 use wallet_types::account_id::AccountPrefixedId;
 use wallet_types::account_info::{StandaloneAddressDetails, StandaloneAddresses};
+use wallet_types::confirmations::{BalancesByConfirmations, DEFAULT_CONFIRMATION_THRESHOLD};
+use wallet_types::currency::CurrencyAmountMap;
+use wallet_types::spending_policy::TxOutflow;
 use wallet_types::with_locked::WithLocked;
 
 use crate::account::utxo_selector::{select_coins, OutputGroup};
@@ -45,8 +49,9 @@ use crate::destination_getters::{get_tx_output_destination, HtlcSpendingConditio
 use crate::key_chain::{AccountKeyChainImpl, KeyChainError};
 use crate::send_request::{
     make_address_output, make_address_output_from_delegation, make_address_output_token,
-    make_decommission_stake_pool_output, make_mint_token_outputs, make_stake_output,
-    make_unmint_token_outputs, IssueNftArguments, SelectedInputs, StakePoolDataArguments,
+    make_create_delegation_output, make_decommission_stake_pool_output, make_mint_token_outputs,
+    make_stake_output, make_unmint_token_outputs, IssueNftArguments, SelectedInputs,
+    StakePoolDataArguments,
 };
 use crate::wallet::WalletPoolsFilter;
 use crate::wallet_events::{WalletEvents, WalletEventsNoOp};
@@ -75,6 +80,7 @@ use wallet_storage::{
     StoreTxRw, WalletStorageReadLocked, WalletStorageReadUnlocked, WalletStorageWriteLocked,
     WalletStorageWriteUnlocked,
 };
+use wallet_types::address_set::{AddressSet, ImportedAddressSet};
 use wallet_types::utxo_types::{get_utxo_type, UtxoState, UtxoStates, UtxoType, UtxoTypes};
 use wallet_types::wallet_tx::{BlockData, TxData, TxState};
 use wallet_types::{
@@ -87,7 +93,9 @@ pub use self::output_cache::{
     DelegationData, FungibleTokenInfo, PoolData, TxInfo, UnconfirmedTokenInfo, UtxoWithTxOutput,
 };
 use self::output_cache::{OutputCache, TokenIssuanceData};
-use self::transaction_list::{get_transaction_list, TransactionList};
+use self::transaction_list::{
+    get_transaction_info, get_transaction_list, TransactionInfo, TransactionList,
+};
 use self::utxo_selector::PayFee;
 
 pub use self::utxo_selector::CoinSelectionAlgo;
@@ -238,13 +246,28 @@ impl Account {
         )?;
 
         let (utxos, selection_algo) = if input_utxos.is_empty() {
+            let mut utxos = self.get_utxos(
+                UtxoType::Transfer | UtxoType::LockThenTransfer | UtxoType::IssueNft,
+                median_time,
+                UtxoState::Confirmed | UtxoState::InMempool | UtxoState::Inactive,
+                WithLocked::Unlocked,
+            );
+            // If the wallet has a confirmation threshold configured, only the outputs that
+            // reached it are spent unless they are selected explicitly
+            let threshold = match input_utxos {
+                SelectedInputs::IncludingPending => None,
+                SelectedInputs::Utxos(_) | SelectedInputs::Inputs(_) => {
+                    db_tx.get_confirmation_threshold()?
+                }
+            };
+            if let Some(threshold) = threshold {
+                let best_block_height = self.account_info.best_block_height();
+                utxos.retain(|(outpoint, _)| {
+                    self.output_cache.utxo_confirmations(outpoint, best_block_height) >= threshold
+                });
+            }
             (
-                self.get_utxos(
-                    UtxoType::Transfer | UtxoType::LockThenTransfer | UtxoType::IssueNft,
-                    median_time,
-                    UtxoState::Confirmed | UtxoState::InMempool | UtxoState::Inactive,
-                    WithLocked::Unlocked,
-                ),
+                utxos,
                 selection_algo.unwrap_or(CoinSelectionAlgo::Randomize),
             )
         } else {
@@ -267,6 +290,7 @@ impl Account {
                         .collect(),
                     selection_algo,
                 ),
+                SelectedInputs::IncludingPending => unreachable!("nothing is preselected"),
             }
         };
 
@@ -501,10 +525,7 @@ impl Account {
             Some(self),
         )?;
 
-        let input_fees = grouped_inputs
-            .values()
-            .map(|(_, fee)| *fee)
-            .sum::<Option<Amount>>()
+        let input_fees = Amount::sum_checked(grouped_inputs.values().map(|(_, fee)| *fee))
             .ok_or(WalletError::OutputAmountOverflow)?;
 
         let coin_input = grouped_inputs.remove(&Currency::Coin).ok_or(WalletError::NoUtxos)?;
@@ -619,7 +640,7 @@ impl Account {
         change_addresses: BTreeMap<Currency, Address<Destination>>,
         median_time: BlockTimestamp,
         fee_rate: CurrentFeeRate,
-    ) -> WalletResult<(PartiallySignedTransaction, BTreeMap<Currency, Amount>)> {
+    ) -> WalletResult<(PartiallySignedTransaction, CurrencyAmountMap)> {
         let mut request = self.select_inputs_for_send_request(
             request,
             inputs,
@@ -636,11 +657,13 @@ impl Account {
         Ok((ptx, fees))
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn process_send_request_and_sign(
         &mut self,
         db_tx: &mut impl WalletStorageWriteUnlocked,
         request: SendRequest,
         inputs: SelectedInputs,
+        selection_algo: Option<CoinSelectionAlgo>,
         change_addresses: BTreeMap<Currency, Address<Destination>>,
         median_time: BlockTimestamp,
         fee_rate: CurrentFeeRate,
@@ -648,7 +671,7 @@ impl Account {
         self.select_inputs_for_send_request(
             request,
             inputs,
-            None,
+            selection_algo,
             change_addresses,
             db_tx,
             median_time,
@@ -857,6 +880,22 @@ impl Account {
             .collect()
     }
 
+    /// The destination that can decommission the pool, if its key is owned by this account
+    pub fn get_pool_decommission_destination(
+        &self,
+        pool_id: PoolId,
+        db_tx: &impl WalletStorageReadUnlocked,
+    ) -> WalletResult<Destination> {
+        let pool_data = self.output_cache.pool_data(pool_id)?;
+        ensure!(
+            self.key_chain
+                .get_private_key_for_destination(&pool_data.decommission_key, db_tx)?
+                .is_some(),
+            WalletError::PoolDecommissionKeyNotOwned(pool_id)
+        );
+        Ok(pool_data.decommission_key.clone())
+    }
+
     pub fn get_delegations(&self) -> impl Iterator<Item = (&DelegationId, &DelegationData)> {
         self.output_cache
             .delegation_ids()
@@ -951,6 +990,76 @@ impl Account {
         Ok(request)
     }
 
+    /// Create a request for a transaction that creates a new delegation to the given pool,
+    /// owned by a new address of this account, and delegates the given amount to it
+    pub fn create_delegation_and_stake_tx(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteUnlocked,
+        pool_id: PoolId,
+        amount: Amount,
+        median_time: BlockTimestamp,
+        fee_rate: CurrentFeeRate,
+    ) -> WalletResult<SendRequest> {
+        let (_, owner) = self.key_chain.issue_address(db_tx, KeyPurpose::ReceiveFunds)?;
+
+        // same as for the pool_id in create_stake_pool_tx, the delegation_id depends on the
+        // first UTXO, so use a dummy one until the inputs are selected
+        let dummy_delegation_id = DelegationId::new(Uint256::from_u64(0).into());
+        let request = SendRequest::new().with_outputs([
+            make_create_delegation_output(owner, pool_id),
+            TxOutput::DelegateStaking(amount, dummy_delegation_id),
+        ]);
+        let mut request = self.select_inputs_for_send_request(
+            request,
+            SelectedInputs::Utxos(vec![]),
+            None,
+            BTreeMap::new(),
+            db_tx,
+            median_time,
+            fee_rate,
+        )?;
+
+        let new_delegation_id = match request
+            .inputs()
+            .first()
+            .expect("selector must have selected something or returned an error")
+        {
+            TxInput::Utxo(input0_outpoint) => {
+                Some(pos_accounting::make_delegation_id(input0_outpoint))
+            }
+            TxInput::Account(..) | TxInput::AccountCommand(..) => None,
+        }
+        .ok_or(WalletError::NoUtxos)?;
+
+        // update the dummy_delegation_id with the new delegation_id
+        let old_delegation_id = request
+            .get_outputs_mut()
+            .iter_mut()
+            .find_map(|out| match out {
+                TxOutput::DelegateStaking(_, delegation_id)
+                    if *delegation_id == dummy_delegation_id =>
+                {
+                    Some(delegation_id)
+                }
+                TxOutput::DelegateStaking(_, _)
+                | TxOutput::CreateStakePool(_, _)
+                | TxOutput::Burn(_)
+                | TxOutput::Transfer(_, _)
+                | TxOutput::LockThenTransfer(_, _, _)
+                | TxOutput::CreateDelegationId(_, _)
+                | TxOutput::ProduceBlockFromStake(_, _)
+                | TxOutput::IssueFungibleToken(_)
+                | TxOutput::IssueNft(_, _, _)
+                | TxOutput::DataDeposit(_)
+                | TxOutput::Htlc(_, _)
+                | TxOutput::AnyoneCanTake(_) => None,
+            })
+            .expect("find output with dummy_delegation_id");
+        *old_delegation_id = new_delegation_id;
+
+        Ok(request)
+    }
+
     pub fn create_htlc_tx(
         &mut self,
         db_tx: &mut impl WalletStorageWriteUnlocked,
@@ -1282,6 +1391,25 @@ impl Account {
         &self,
         tx: Transaction,
         median_time: BlockTimestamp,
+    ) -> WalletResult<PartiallySignedTransaction> {
+        self.tx_to_partially_signed_tx_impl(tx, median_time, false)
+    }
+
+    /// Same as tx_to_partially_signed_tx, but the utxos that don't belong to this account are left
+    /// without a destination instead of failing, so they are left for their owners to sign
+    pub fn tx_to_partially_signed_tx_skipping_foreign_utxos(
+        &self,
+        tx: Transaction,
+        median_time: BlockTimestamp,
+    ) -> WalletResult<PartiallySignedTransaction> {
+        self.tx_to_partially_signed_tx_impl(tx, median_time, true)
+    }
+
+    fn tx_to_partially_signed_tx_impl(
+        &self,
+        tx: Transaction,
+        median_time: BlockTimestamp,
+        skip_foreign_utxos: bool,
     ) -> WalletResult<PartiallySignedTransaction> {
         let current_block_info = BlockInfo {
             height: self.account_info.best_block_height(),
@@ -1294,8 +1422,13 @@ impl Account {
             .map(|tx_inp| match tx_inp {
                 TxInput::Utxo(outpoint) => {
                     // find utxo from cache
-                    self.find_unspent_utxo_with_destination(outpoint, current_block_info)
-                        .map(|(out, dest)| (Some(out), Some(dest)))
+                    match self.find_unspent_utxo_with_destination(outpoint, current_block_info) {
+                        Ok((out, dest)) => Ok((Some(out), Some(dest))),
+                        Err(WalletError::CannotFindUtxo(_)) if skip_foreign_utxos => {
+                            Ok((None, None))
+                        }
+                        Err(err) => Err(err),
+                    }
                 }
                 TxInput::Account(acc_outpoint) => {
                     // find delegation destination
@@ -1342,6 +1475,19 @@ impl Account {
         Ok(ptx)
     }
 
+    /// Return true if any of the inputs of the transaction can be signed by this account
+    pub fn has_own_inputs(&self, ptx: &PartiallySignedTransaction) -> bool {
+        ptx.destinations().iter().flatten().any(|destination| {
+            self.is_destination_mine(destination)
+                || self.key_chain.get_multisig_challenge(destination).is_some()
+        })
+    }
+
+    /// Find an output of a transaction or block of this account, whether it's spent or not
+    pub fn find_txo(&self, outpoint: &UtxoOutPoint) -> Option<&TxOutput> {
+        self.output_cache.get_txo(outpoint)
+    }
+
     pub fn find_unspent_utxo_with_destination(
         &self,
         outpoint: &UtxoOutPoint,
@@ -1407,6 +1553,46 @@ impl Account {
         Ok(self.key_chain.add_standalone_private_key(db_tx, private_key, label)?)
     }
 
+    /// Watch the addresses from the address set that aren't watched by this account yet
+    pub fn import_address_set(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        address_set: &AddressSet,
+    ) -> WalletResult<ImportedAddressSet> {
+        let mut added_addresses = 0;
+        for public_key in address_set.receive_keys.iter().chain(&address_set.change_keys) {
+            let public_key_hash = PublicKeyHash::from(public_key);
+            if !self.key_chain.is_public_key_hash_mine_or_watched(public_key_hash) {
+                self.key_chain.add_standalone_watch_only_address(db_tx, public_key_hash, None)?;
+                added_addresses += 1;
+            }
+        }
+
+        let unused_receive_addresses = address_set
+            .receive_keys
+            .iter()
+            .filter(|public_key| {
+                !self.is_standalone_destination_used(&Destination::PublicKeyHash(
+                    PublicKeyHash::from(*public_key),
+                ))
+            })
+            .count();
+
+        Ok(ImportedAddressSet {
+            account_fingerprint: address_set.account_fingerprint(),
+            added_addresses,
+            unused_receive_addresses,
+        })
+    }
+
+    /// Return true if any of the known transactions pays to the destination
+    fn is_standalone_destination_used(&self, destination: &Destination) -> bool {
+        self.output_cache
+            .txs_with_unconfirmed()
+            .values()
+            .any(|tx| tx.outputs().iter().any(|txo| self.is_watched_by(txo, destination)))
+    }
+
     /// Add a standalone multisig address to be watched
     pub fn add_standalone_multisig(
         &mut self,
@@ -1426,6 +1612,27 @@ impl Account {
         Ok(self.key_chain.issue_address(db_tx, purpose)?)
     }
 
+    /// Get an address that has never received funds, skipping over the issued but used ones
+    pub fn get_unused_address(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        purpose: KeyPurpose,
+    ) -> WalletResult<(ChildNumber, Address<Destination>)> {
+        Ok(self.key_chain.issue_unused_address(db_tx, purpose)?)
+    }
+
+    /// Get the last issued receive address even if it has already received funds,
+    /// a new address is issued only if none has been issued yet
+    pub fn get_reusable_address(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+    ) -> WalletResult<(ChildNumber, Address<Destination>)> {
+        match self.key_chain.last_issued_address(KeyPurpose::ReceiveFunds) {
+            Some(issued) => Ok(issued),
+            None => self.get_unused_address(db_tx, KeyPurpose::ReceiveFunds),
+        }
+    }
+
     /// Get a new vrf key that hasn't been used before
     pub fn get_new_vrf_key(
         &mut self,
@@ -1456,6 +1663,18 @@ impl Account {
         self.key_chain.get_all_issued_addresses()
     }
 
+    pub fn get_all_issued_addresses_with_usage(
+        &self,
+        purpose: KeyPurpose,
+    ) -> BTreeMap<ChildNumber, (Address<Destination>, bool)> {
+        self.key_chain.get_all_issued_addresses_with_usage(purpose)
+    }
+
+    /// Return true if the destination belongs to this account and has already received funds
+    pub fn is_destination_used(&self, destination: &Destination) -> bool {
+        self.key_chain.is_destination_used(destination)
+    }
+
     pub fn get_all_standalone_addresses(&self) -> StandaloneAddresses {
         self.key_chain.get_all_standalone_addresses()
     }
@@ -1464,11 +1683,7 @@ impl Account {
         &self,
         address: Destination,
         median_time: BlockTimestamp,
-    ) -> WalletResult<(
-        Destination,
-        BTreeMap<Currency, Amount>,
-        StandaloneAddressDetails,
-    )> {
+    ) -> WalletResult<(Destination, CurrencyAmountMap, StandaloneAddressDetails)> {
         let (address, standalone_key) = self
             .key_chain
             .get_all_standalone_address_details(address.clone())
@@ -1498,7 +1713,7 @@ impl Account {
             Amount::ZERO,
         )?;
 
-        Ok((address, amounts_by_currency, standalone_key))
+        Ok((address, amounts_by_currency.into(), standalone_key))
     }
 
     pub fn get_all_issued_vrf_public_keys(
@@ -1511,8 +1726,8 @@ impl Account {
         self.key_chain.get_legacy_vrf_public_key()
     }
 
-    pub fn get_addresses_usage(&self) -> &KeychainUsageState {
-        self.key_chain.get_addresses_usage_state()
+    pub fn get_addresses_usage(&self, purpose: KeyPurpose) -> &KeychainUsageState {
+        self.key_chain.get_addresses_usage_state(purpose)
     }
 
     fn collect_output_destinations(&self, txo: &TxOutput) -> Vec<Destination> {
@@ -1540,6 +1755,43 @@ impl Account {
         }
     }
 
+    /// The funds that the transaction sends out of this account
+    pub fn tx_outflow(&self, tx: &Transaction) -> WalletResult<TxOutflow> {
+        let mut outflow = TxOutflow::default();
+        for output in tx.outputs() {
+            let (coins, destination) = match output {
+                TxOutput::Transfer(value, destination)
+                | TxOutput::LockThenTransfer(value, destination, _) => {
+                    if self.is_destination_mine(destination) {
+                        continue;
+                    }
+                    (value.coin_amount(), Some(destination))
+                }
+                TxOutput::Htlc(value, htlc) => (value.coin_amount(), Some(&htlc.spend_key)),
+                TxOutput::Burn(value) => (value.coin_amount(), None),
+                TxOutput::AnyoneCanTake(order) => (order.give().coin_amount(), None),
+                TxOutput::CreateStakePool(_, data) => (Some(data.pledge()), None),
+                TxOutput::DelegateStaking(amount, _) => (Some(*amount), None),
+                TxOutput::ProduceBlockFromStake(_, _)
+                | TxOutput::CreateDelegationId(_, _)
+                | TxOutput::IssueFungibleToken(_)
+                | TxOutput::IssueNft(_, _, _)
+                | TxOutput::DataDeposit(_) => continue,
+            };
+
+            match destination {
+                Some(destination) => {
+                    outflow.destinations.insert(destination.clone());
+                }
+                None => outflow.has_other_recipients = true,
+            }
+            if let Some(coins) = coins {
+                outflow.coins = (outflow.coins + coins).ok_or(WalletError::OutputAmountOverflow)?;
+            }
+        }
+        Ok(outflow)
+    }
+
     /// Return true if this transaction output can be spent by this account
     fn is_mine(&self, txo: &TxOutput) -> bool {
         self.collect_output_destinations(txo)
@@ -1676,15 +1928,50 @@ impl Account {
         utxo_states: UtxoStates,
         median_time: BlockTimestamp,
         with_locked: WithLocked,
-    ) -> WalletResult<BTreeMap<currency_grouper::Currency, Amount>> {
+    ) -> WalletResult<CurrencyAmountMap> {
+        Self::sum_balance(self.get_utxos(
+            UtxoType::Transfer | UtxoType::LockThenTransfer | UtxoType::IssueNft,
+            median_time,
+            utxo_states,
+            with_locked,
+        ))
+    }
+
+    /// The balance split into the confirmed, pending and unconfirmed parts, the outputs
+    /// spent by the transactions in the mempool are not included
+    pub fn get_balance_by_confirmations(
+        &self,
+        confirmation_threshold: u64,
+        median_time: BlockTimestamp,
+        with_locked: WithLocked,
+    ) -> WalletResult<BalancesByConfirmations> {
+        let best_block_height = self.account_info.best_block_height();
+        let (mut confirmed, mut pending, mut unconfirmed) = (vec![], vec![], vec![]);
+        for utxo in self.get_utxos(
+            UtxoType::Transfer | UtxoType::LockThenTransfer | UtxoType::IssueNft,
+            median_time,
+            UtxoState::Confirmed | UtxoState::InMempool,
+            with_locked,
+        ) {
+            match self.output_cache.utxo_confirmations(&utxo.0, best_block_height) {
+                0 => unconfirmed.push(utxo),
+                confirmations if confirmations < confirmation_threshold => pending.push(utxo),
+                _ => confirmed.push(utxo),
+            }
+        }
+
+        Ok(BalancesByConfirmations {
+            confirmed: Self::sum_balance(confirmed)?,
+            pending: Self::sum_balance(pending)?,
+            unconfirmed: Self::sum_balance(unconfirmed)?,
+        })
+    }
+
+    fn sum_balance(
+        utxos: Vec<(UtxoOutPoint, (&TxOutput, Option<TokenId>))>,
+    ) -> WalletResult<CurrencyAmountMap> {
         let amounts_by_currency = currency_grouper::group_utxos_for_input(
-            self.get_utxos(
-                UtxoType::Transfer | UtxoType::LockThenTransfer | UtxoType::IssueNft,
-                median_time,
-                utxo_states,
-                with_locked,
-            )
-            .into_iter(),
+            utxos.into_iter(),
             |(_, (tx_output, _))| tx_output,
             |total: &mut Amount, _, amount| -> WalletResult<()> {
                 *total = (*total + amount).ok_or(WalletError::OutputAmountOverflow)?;
@@ -1692,7 +1979,30 @@ impl Account {
             },
             Amount::ZERO,
         )?;
-        Ok(amounts_by_currency)
+        Ok(amounts_by_currency.into())
+    }
+
+    /// The confirmed transfer utxos of the account, including the watched multisig ones,
+    /// along with their destinations and the heights of the blocks that confirmed them
+    pub fn get_reserve_utxos(
+        &self,
+        median_time: BlockTimestamp,
+    ) -> Vec<(UtxoOutPoint, Destination, BlockHeight)> {
+        let utxo_types = UtxoType::Transfer | UtxoType::LockThenTransfer;
+        let utxo_states = UtxoState::Confirmed.into();
+        self.get_utxos(utxo_types, median_time, utxo_states, WithLocked::Any)
+            .into_iter()
+            .chain(self.get_multisig_utxos(utxo_types, median_time, utxo_states, WithLocked::Any))
+            .filter_map(|(outpoint, (output, _))| {
+                let destination = match output {
+                    TxOutput::Transfer(_, destination)
+                    | TxOutput::LockThenTransfer(_, destination, _) => destination.clone(),
+                    _ => return None,
+                };
+                let height = self.output_cache.utxo_confirmation_height(&outpoint)?;
+                Some((outpoint, destination, height))
+            })
+            .collect()
     }
 
     pub fn get_multisig_utxos(
@@ -1744,6 +2054,14 @@ impl Account {
         self.output_cache.get_transaction(transaction_id)
     }
 
+    /// Get the type (e.g. sent or received), the amount and the state of the transaction
+    pub fn get_transaction_info(
+        &self,
+        transaction_id: Id<Transaction>,
+    ) -> WalletResult<TransactionInfo> {
+        get_transaction_info(&self.key_chain, &self.output_cache, transaction_id)
+    }
+
     pub fn reset_to_height<B: storage::Backend>(
         &mut self,
         db_tx: &mut StoreTxRw<B>,
@@ -1905,9 +2223,45 @@ impl Account {
         self.account_info.update_best_block(best_block_height, best_block_id);
         db_tx.set_account(&self.key_chain.get_account_id(), &self.account_info)?;
 
+        let confirmation_threshold =
+            db_tx.get_confirmation_threshold()?.unwrap_or(DEFAULT_CONFIRMATION_THRESHOLD);
+        self.notify_confirmation_threshold_reached(
+            wallet_events,
+            common_block_height,
+            best_block_height,
+            confirmation_threshold,
+        );
+
         Ok(new_tx_was_added)
     }
 
+    /// Notify about the transactions that have reached the confirmation threshold with the blocks
+    /// above `common_block_height` up to `best_block_height`
+    fn notify_confirmation_threshold_reached(
+        &self,
+        wallet_events: &impl WalletEvents,
+        common_block_height: BlockHeight,
+        best_block_height: BlockHeight,
+        confirmation_threshold: u64,
+    ) {
+        for height in (common_block_height.into_int() + 1)..=best_block_height.into_int() {
+            // The transactions in the block at this height have exactly `confirmation_threshold`
+            // confirmations when this block is the best one
+            let Some(confirmed_height) = (height + 1).checked_sub(confirmation_threshold) else {
+                continue;
+            };
+            for tx in self.output_cache.txs_confirmed_at(BlockHeight::new(confirmed_height)) {
+                if let WalletTx::Tx(tx_data) = tx {
+                    wallet_events.tx_confirmation_threshold_reached(
+                        self.account_index(),
+                        tx_data.get_transaction().get_id(),
+                        confirmation_threshold,
+                    );
+                }
+            }
+        }
+    }
+
     /// Check for any conflicting txs and update the new state in the DB
     fn update_conflicting_txs<B: storage::Backend>(
         &mut self,
@@ -2063,6 +2417,14 @@ impl Account {
         self.output_cache.pending_transactions()
     }
 
+    pub fn check_package_limits(
+        &self,
+        tx: &SignedTransaction,
+        limits: &PackageLimits,
+    ) -> Result<(), PackageLimitError> {
+        self.output_cache.check_package_limits(tx, limits)
+    }
+
     pub fn mainchain_transactions(
         &self,
         destination: Option<Destination>,
@@ -30,14 +30,16 @@ use common::{
             TokenTotalSupply,
         },
         AccountCommand, AccountNonce, AccountSpending, DelegationId, Destination, GenBlock,
-        OutPointSourceId, PoolId, Transaction, TxInput, TxOutput, UtxoOutPoint,
+        OutPointSourceId, PoolId, SignedTransaction, Transaction, TxInput, TxOutput, UtxoOutPoint,
     },
     primitives::{id::WithId, per_thousand::PerThousand, Amount, BlockHeight, Id, Idable},
 };
 use crypto::vrf::VRFPublicKey;
 use itertools::Itertools;
+use mempool::{error::PackageLimitError, PackageLimits};
 use pos_accounting::make_delegation_id;
 use rpc_description::HasValueHint;
+use serialization::Encode;
 use tx_verifier::transaction_verifier::calculate_tokens_burned_in_outputs;
 use utils::ensure;
 use wallet_types::{
@@ -56,6 +58,8 @@ pub struct TxInfo {
     pub id: Id<Transaction>,
     pub height: BlockHeight,
     pub timestamp: BlockTimestamp,
+    /// The label set by the user for this transaction
+    pub label: Option<String>,
 }
 
 impl TxInfo {
@@ -64,6 +68,7 @@ impl TxInfo {
             id,
             height,
             timestamp,
+            label: None,
         }
     }
 }
@@ -486,6 +491,9 @@ impl TokenIssuanceData {
 /// A similar approach is used by the Bitcoin Core wallet.
 pub struct OutputCache {
     txs: BTreeMap<OutPointSourceId, WalletTx>,
+    /// The confirmed transactions by the height of their block, so that the transactions
+    /// reaching some number of confirmations can be found without scanning all of them
+    confirmed_at_height: BTreeMap<BlockHeight, BTreeSet<OutPointSourceId>>,
     consumed: BTreeMap<UtxoOutPoint, TxState>,
     unconfirmed_descendants: BTreeMap<OutPointSourceId, BTreeSet<OutPointSourceId>>,
     pools: BTreeMap<PoolId, PoolData>,
@@ -497,6 +505,7 @@ impl OutputCache {
     pub fn empty() -> Self {
         Self {
             txs: BTreeMap::new(),
+            confirmed_at_height: BTreeMap::new(),
             consumed: BTreeMap::new(),
             unconfirmed_descendants: BTreeMap::new(),
             pools: BTreeMap::new(),
@@ -519,6 +528,36 @@ impl OutputCache {
         &self.txs
     }
 
+    /// The transactions confirmed in the block at the given height
+    pub fn txs_confirmed_at(&self, height: BlockHeight) -> impl Iterator<Item = &WalletTx> {
+        self.confirmed_at_height
+            .get(&height)
+            .into_iter()
+            .flatten()
+            .filter_map(|tx_id| self.txs.get(tx_id))
+    }
+
+    /// The number of confirmations of the transaction that created the output,
+    /// zero if it isn't confirmed or isn't known
+    pub fn utxo_confirmations(
+        &self,
+        outpoint: &UtxoOutPoint,
+        best_block_height: BlockHeight,
+    ) -> u64 {
+        self.txs
+            .get(&outpoint.source_id())
+            .map_or(0, |tx| tx.state().confirmations(best_block_height))
+    }
+
+    /// The height of the block that confirmed the transaction or the block reward
+    /// that created the output, if it's confirmed
+    pub fn utxo_confirmation_height(&self, outpoint: &UtxoOutPoint) -> Option<BlockHeight> {
+        self.txs
+            .get(&outpoint.source_id())
+            .and_then(get_block_info)
+            .map(|block_info| block_info.height)
+    }
+
     pub fn has_confirmed_transactions(&self) -> bool {
         self.txs.values().any(|tx| match tx.state() {
             TxState::Inactive(_)
@@ -759,10 +798,28 @@ impl OutputCache {
 
         self.update_outputs(&tx, get_block_info(&tx), already_present)?;
 
-        self.txs.insert(tx_id, tx);
+        if let Some(height) = tx.state().block_height() {
+            self.confirmed_at_height.entry(height).or_default().insert(tx_id.clone());
+        }
+        if let Some(old_tx) = self.txs.insert(tx_id.clone(), tx) {
+            self.remove_from_confirmed_at_height(&tx_id, &old_tx);
+        }
         Ok(())
     }
 
+    fn remove_from_confirmed_at_height(&mut self, tx_id: &OutPointSourceId, tx: &WalletTx) {
+        let old_height = tx.state().block_height();
+        let new_height = self.txs.get(tx_id).and_then(|tx| tx.state().block_height());
+        if let Some(height) = old_height.filter(|height| Some(*height) != new_height) {
+            if let Entry::Occupied(mut entry) = self.confirmed_at_height.entry(height) {
+                entry.get_mut().remove(tx_id);
+                if entry.get().is_empty() {
+                    entry.remove();
+                }
+            }
+        }
+    }
+
     /// Update the pool states for a newly confirmed transaction
     fn update_outputs(
         &mut self,
@@ -1004,6 +1061,7 @@ impl OutputCache {
     pub fn remove_tx(&mut self, tx_id: &OutPointSourceId) -> WalletResult<()> {
         let tx_opt = self.txs.remove(tx_id);
         if let Some(tx) = tx_opt {
+            self.remove_from_confirmed_at_height(tx_id, &tx);
             for input in tx.inputs() {
                 match input {
                     TxInput::Utxo(outpoint) => {
@@ -1214,6 +1272,118 @@ impl OutputCache {
             .collect()
     }
 
+    /// Check that the transaction would not exceed the mempool limits on chains of unconfirmed
+    /// transactions, as far as the unconfirmed transactions of this account are concerned
+    pub fn check_package_limits(
+        &self,
+        tx: &SignedTransaction,
+        limits: &PackageLimits,
+    ) -> Result<(), PackageLimitError> {
+        let tx_size = tx.encoded_size();
+        let ancestors = self.unconfirmed_ancestors(tx.transaction());
+        if ancestors.is_empty() {
+            return Ok(());
+        }
+
+        let count = ancestors.len() + 1;
+        ensure!(
+            count <= limits.max_ancestor_count,
+            PackageLimitError::TooManyAncestors {
+                count,
+                limit: limits.max_ancestor_count,
+            }
+        );
+
+        let size = tx_size
+            + ancestors
+                .values()
+                .map(|tx| tx.get_signed_transaction().encoded_size())
+                .sum::<usize>();
+        ensure!(
+            size <= limits.max_ancestor_size,
+            PackageLimitError::AncestorSizeTooLarge {
+                size,
+                limit: limits.max_ancestor_size,
+            }
+        );
+
+        let unconfirmed_txs_with_ancestors = self
+            .txs
+            .keys()
+            .filter_map(|id| self.unconfirmed_tx(id))
+            .map(|tx| (tx, self.unconfirmed_ancestors(tx.get_transaction())))
+            .collect::<Vec<_>>();
+
+        for (ancestor_source_id, ancestor) in ancestors {
+            let descendants = unconfirmed_txs_with_ancestors
+                .iter()
+                .filter(|(_, tx_ancestors)| tx_ancestors.contains_key(&ancestor_source_id))
+                .map(|(tx, _)| tx.get_signed_transaction().encoded_size())
+                .collect::<Vec<_>>();
+            let ancestor_id = ancestor.get_transaction().get_id();
+
+            // The ancestor itself, its current descendants and the new transaction
+            let count = descendants.len() + 2;
+            ensure!(
+                count <= limits.max_descendant_count,
+                PackageLimitError::TooManyDescendants {
+                    ancestor: ancestor_id,
+                    count,
+                    limit: limits.max_descendant_count,
+                }
+            );
+
+            let size = ancestor.get_signed_transaction().encoded_size()
+                + descendants.iter().sum::<usize>()
+                + tx_size;
+            ensure!(
+                size <= limits.max_descendant_size,
+                PackageLimitError::DescendantSizeTooLarge {
+                    ancestor: ancestor_id,
+                    size,
+                    limit: limits.max_descendant_size,
+                }
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Get the transaction if it is known to this account and is waiting to be included in a block
+    fn unconfirmed_tx(&self, tx_id: &OutPointSourceId) -> Option<&TxData> {
+        match self.txs.get(tx_id)? {
+            WalletTx::Block(_) => None,
+            WalletTx::Tx(tx) => match tx.state() {
+                TxState::InMempool(_) | TxState::Inactive(_) => Some(tx),
+                TxState::Confirmed(_, _, _) | TxState::Conflicted(_) | TxState::Abandoned => None,
+            },
+        }
+    }
+
+    /// Unconfirmed transactions the given transaction spends outputs of, directly or indirectly
+    fn unconfirmed_ancestors(&self, tx: &Transaction) -> BTreeMap<OutPointSourceId, &TxData> {
+        let mut ancestors = BTreeMap::new();
+        let mut to_visit = vec![tx];
+
+        while let Some(tx) = to_visit.pop() {
+            for input in tx.inputs() {
+                let source_id = match input {
+                    TxInput::Utxo(outpoint) => outpoint.source_id(),
+                    TxInput::Account(_) | TxInput::AccountCommand(_, _) => continue,
+                };
+                if ancestors.contains_key(&source_id) {
+                    continue;
+                }
+                if let Some(parent) = self.unconfirmed_tx(&source_id) {
+                    to_visit.push(parent.get_transaction());
+                    ancestors.insert(source_id, parent);
+                }
+            }
+        }
+
+        ancestors
+    }
+
     pub fn mainchain_transactions(
         &self,
         destination: Option<Destination>,
@@ -31,7 +31,7 @@ use super::{currency_grouper::group_outputs, output_cache::OutputCache};
 // TODO: Show send/recv addresses and amounts
 // TODO: Show token amounts
 // TODO: Show network fee for Sent and Redeposit
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TxType {
     /// All inputs are own, all outputs are own
     Redeposit {},
@@ -70,7 +70,7 @@ impl TxType {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TransactionInfo {
     pub txid: Id<Transaction>,
     pub tx_type: TxType,
@@ -214,6 +214,15 @@ fn get_transaction(
     })
 }
 
+pub fn get_transaction_info(
+    key_chain: &AccountKeyChainImpl,
+    output_cache: &OutputCache,
+    transaction_id: Id<Transaction>,
+) -> WalletResult<TransactionInfo> {
+    let tx_data = output_cache.get_transaction(transaction_id)?;
+    get_transaction(key_chain, output_cache, tx_data)
+}
+
 pub fn get_transaction_list(
     key_chain: &AccountKeyChainImpl,
     output_cache: &OutputCache,
@@ -481,11 +481,9 @@ fn select_coins_bnb(
     let mut curr_selection_weight = 0; // sum of selected utxo weight
 
     // Calculate curr_available_value
-    let mut curr_available_value = utxo_pool
-        .iter()
-        .map(|utxo| utxo.get_effective_value(pay_fees))
-        .sum::<Option<Amount>>()
-        .ok_or(UtxoSelectorError::AmountArithmeticError)?;
+    let mut curr_available_value =
+        Amount::sum_checked(utxo_pool.iter().map(|utxo| utxo.get_effective_value(pay_fees)))
+            .ok_or(UtxoSelectorError::AmountArithmeticError)?;
 
     if curr_available_value < selection_target {
         return Err(UtxoSelectorError::NotEnoughFunds(
@@ -652,12 +650,50 @@ fn select_all_coins(
     Ok(result)
 }
 
-#[derive(Clone, Copy)]
+/// Select the largest OutputGroups first until the target is satisfied, so the fewest inputs
+/// are spent and the lowest fee is paid
+fn select_coins_largest_first(
+    mut utxo_pool: Vec<OutputGroup>,
+    target_value: Amount,
+    max_weight: u32,
+    pay_fees: PayFee,
+) -> Result<SelectionResult, UtxoSelectorError> {
+    let mut result = SelectionResult::new(target_value);
+
+    utxo_pool.sort_by_key(|g| std::cmp::Reverse(g.get_effective_value(pay_fees)));
+
+    for group in &utxo_pool {
+        if result.effective_value >= target_value {
+            break;
+        }
+        result.add_input(group, pay_fees)?;
+    }
+
+    ensure!(
+        result.weight <= max_weight,
+        UtxoSelectorError::MaxWeightExceeded
+    );
+    ensure!(
+        result.effective_value >= target_value,
+        UtxoSelectorError::NoSolutionFound
+    );
+
+    Ok(result)
+}
+
+#[derive(Debug, Clone, Copy)]
 pub enum CoinSelectionAlgo {
     /// Use all specified inputs.
     UsePreselected,
     /// Choose inputs randomly until the target is satisfied.
     Randomize,
+    /// Choose the largest inputs first, spending the fewest inputs.
+    LargestFirst,
+    /// Choose a single random subset of the inputs, without preferring any of the inputs.
+    SingleRandomDraw,
+    /// Look for the inputs matching the target exactly so no change is needed,
+    /// else choose the inputs exceeding the target the least.
+    LeastChange,
 }
 
 pub fn select_coins(
@@ -672,11 +708,9 @@ pub fn select_coins(
     }
     ensure!(!utxo_pool.is_empty(), UtxoSelectorError::NoUtxos);
 
-    let total_available_value = utxo_pool
-        .iter()
-        .map(|utxo| utxo.get_effective_value(pay_fees))
-        .sum::<Option<Amount>>()
-        .ok_or(UtxoSelectorError::AmountArithmeticError)?;
+    let total_available_value =
+        Amount::sum_checked(utxo_pool.iter().map(|utxo| utxo.get_effective_value(pay_fees)))
+            .ok_or(UtxoSelectorError::AmountArithmeticError)?;
 
     ensure!(
         total_available_value >= selection_target,
@@ -690,6 +724,105 @@ pub fn select_coins(
         CoinSelectionAlgo::Randomize => {
             select_random_coins(utxo_pool, selection_target, cost_of_change, pay_fees)
         }
+        CoinSelectionAlgo::LargestFirst => {
+            select_largest_first_coins(utxo_pool, selection_target, cost_of_change, pay_fees)
+        }
+        CoinSelectionAlgo::SingleRandomDraw => {
+            select_single_random_draw_coins(utxo_pool, selection_target, cost_of_change, pay_fees)
+        }
+        CoinSelectionAlgo::LeastChange => {
+            select_least_change_coins(utxo_pool, selection_target, cost_of_change, pay_fees)
+        }
+    }
+}
+
+fn select_largest_first_coins(
+    utxo_pool: Vec<OutputGroup>,
+    selection_target: Amount,
+    cost_of_change: Amount,
+    pay_fees: PayFee,
+) -> Result<SelectionResult, UtxoSelectorError> {
+    // TODO: set some max weight
+    let max_weight = 999;
+
+    let mut result = select_coins_largest_first(utxo_pool, selection_target, max_weight, pay_fees)?;
+    result.compute_and_set_waste(cost_of_change, cost_of_change, cost_of_change, pay_fees)?;
+    Ok(result)
+}
+
+fn select_single_random_draw_coins(
+    utxo_pool: Vec<OutputGroup>,
+    selection_target: Amount,
+    cost_of_change: Amount,
+    pay_fees: PayFee,
+) -> Result<SelectionResult, UtxoSelectorError> {
+    // TODO: set some max weight
+    let max_weight = 999;
+    let mut rng = make_pseudo_rng();
+
+    let mut result = select_coins_srd(
+        &utxo_pool,
+        selection_target,
+        &mut rng,
+        cost_of_change,
+        max_weight,
+        pay_fees,
+    )
+    .or_else(|error| match error {
+        // The available value can cover the target but not the change as well
+        UtxoSelectorError::NoSolutionFound => select_coins_srd(
+            &utxo_pool,
+            selection_target,
+            &mut rng,
+            Amount::ZERO,
+            max_weight,
+            pay_fees,
+        ),
+        error => Err(error),
+    })?;
+    result.compute_and_set_waste(cost_of_change, cost_of_change, cost_of_change, pay_fees)?;
+    Ok(result)
+}
+
+fn select_least_change_coins(
+    mut utxo_pool: Vec<OutputGroup>,
+    selection_target: Amount,
+    cost_of_change: Amount,
+    pay_fees: PayFee,
+) -> Result<SelectionResult, UtxoSelectorError> {
+    // TODO: set some max weight
+    let max_weight = 999;
+
+    match select_coins_bnb(
+        utxo_pool.clone(),
+        selection_target,
+        cost_of_change,
+        max_weight,
+        pay_fees,
+    ) {
+        Ok(result) => Ok(result),
+        Err(UtxoSelectorError::AmountArithmeticError) => {
+            Err(UtxoSelectorError::AmountArithmeticError)
+        }
+        // There is no exact match, fall back to the subset exceeding the target the least
+        Err(_) => {
+            let mut rng = make_pseudo_rng();
+            let mut result = knapsack_solver(
+                &mut utxo_pool,
+                selection_target,
+                cost_of_change,
+                &mut rng,
+                max_weight,
+                pay_fees,
+            )?;
+            result.compute_and_set_waste(
+                cost_of_change,
+                cost_of_change,
+                cost_of_change,
+                pay_fees,
+            )?;
+            Ok(result)
+        }
     }
 }
 
@@ -324,6 +324,44 @@ fn test_bnb_solver_not_exact_solution_fail() {
     assert_eq!(result.outputs.len(), 3);
 }
 
+#[test]
+fn test_select_coins_total_overflow() {
+    let mut groups = vec![];
+    add_output((Amount::MAX - Amount::from_atoms(1)).unwrap(), &mut groups);
+    add_output(Amount::from_atoms(1), &mut groups);
+
+    let result = select_coins(
+        groups.clone(),
+        Amount::MAX,
+        PayFee::PayFeeWithThisCurrency,
+        Amount::ZERO,
+        CoinSelectionAlgo::UsePreselected,
+    )
+    .unwrap();
+    assert_eq!(result.effective_value, Amount::MAX);
+
+    add_output(Amount::from_atoms(1), &mut groups);
+    let error = select_coins(
+        groups.clone(),
+        Amount::MAX,
+        PayFee::PayFeeWithThisCurrency,
+        Amount::ZERO,
+        CoinSelectionAlgo::Randomize,
+    )
+    .unwrap_err();
+    assert_eq!(error, UtxoSelectorError::AmountArithmeticError);
+
+    let error = select_coins_bnb(
+        groups,
+        Amount::MAX,
+        Amount::ZERO,
+        100,
+        PayFee::PayFeeWithThisCurrency,
+    )
+    .unwrap_err();
+    assert_eq!(error, UtxoSelectorError::AmountArithmeticError);
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -358,3 +396,175 @@ fn test_srd_solver_find_solution(#[case] seed: Seed) {
 
     assert!(result.effective_value >= target_value);
 }
+
+fn add_output_with_fee(value: Amount, fee: Amount, groups: &mut Vec<OutputGroup>) {
+    add_output(value, groups);
+    let group = groups.last_mut().expect("just added");
+    group.fee = fee;
+    group.long_term_fee = fee;
+    group.weight = 1;
+}
+
+fn selected_value(result: &SelectionResult) -> Amount {
+    Amount::sum_checked(result.outputs.iter().map(|(_, output)| match output {
+        TxOutput::Transfer(OutputValue::Coin(value), _) => *value,
+        _ => panic!("unexpected output"),
+    }))
+    .unwrap()
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy(), CoinSelectionAlgo::LargestFirst)]
+#[case(Seed::from_entropy(), CoinSelectionAlgo::SingleRandomDraw)]
+#[case(Seed::from_entropy(), CoinSelectionAlgo::LeastChange)]
+fn test_select_coins_satisfies_target_and_fee(
+    #[case] seed: Seed,
+    #[case] coin_selection_algo: CoinSelectionAlgo,
+) {
+    let mut rng = make_seedable_rng(seed);
+
+    let mut groups = vec![];
+    let mut total_effective_value = Amount::ZERO;
+    for _ in 0..rng.gen_range(1..100) {
+        let value = Amount::from_atoms(rng.gen_range(100..10000));
+        let fee = Amount::from_atoms(rng.gen_range(0..100));
+        add_output_with_fee(value, fee, &mut groups);
+        total_effective_value = (total_effective_value + (value - fee).unwrap()).unwrap();
+    }
+
+    let target_value = Amount::from_atoms(rng.gen_range(1..=total_effective_value.into_atoms()));
+    let cost_of_change = Amount::from_atoms(rng.gen_range(0..100));
+    let result = select_coins(
+        groups,
+        target_value,
+        PayFee::PayFeeWithThisCurrency,
+        cost_of_change,
+        coin_selection_algo,
+    )
+    .unwrap();
+
+    // the selected UTXOs pay for both the target and the fee of spending them
+    assert!(result.effective_value >= target_value);
+    assert_eq!(
+        (selected_value(&result) - result.get_total_fees()).unwrap(),
+        result.effective_value
+    );
+}
+
+#[rstest]
+#[trace]
+#[case(CoinSelectionAlgo::LargestFirst)]
+#[case(CoinSelectionAlgo::SingleRandomDraw)]
+#[case(CoinSelectionAlgo::LeastChange)]
+#[case(CoinSelectionAlgo::UsePreselected)]
+fn test_select_coins_not_enough_funds(#[case] coin_selection_algo: CoinSelectionAlgo) {
+    let mut groups = vec![];
+    for value in [1, 2, 5, 10, 20] {
+        add_output_with_fee(
+            Amount::from_atoms(value),
+            Amount::from_atoms(1),
+            &mut groups,
+        );
+    }
+
+    // the effective value of the UTXOs is 33
+    let target_value = Amount::from_atoms(34);
+    let error = select_coins(
+        groups,
+        target_value,
+        PayFee::PayFeeWithThisCurrency,
+        Amount::ZERO,
+        coin_selection_algo,
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        error,
+        UtxoSelectorError::NotEnoughFunds(Amount::from_atoms(33), target_value)
+    );
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn test_select_coins_largest_first(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let mut values = [1, 2, 5, 10, 20];
+    values.shuffle(&mut rng);
+    let mut groups = vec![];
+    for value in values {
+        add_output(Amount::from_atoms(value), &mut groups);
+    }
+
+    let target_value = Amount::from_atoms(rng.gen_range(21..=30));
+    let result = select_coins(
+        groups,
+        target_value,
+        PayFee::PayFeeWithThisCurrency,
+        Amount::ZERO,
+        CoinSelectionAlgo::LargestFirst,
+    )
+    .unwrap();
+
+    // 20 and 10 are enough, no smaller UTXOs are spent
+    assert_eq!(result.outputs.len(), 2);
+    assert_eq!(result.effective_value, Amount::from_atoms(30));
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn test_select_coins_least_change_exact_match(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let mut groups = vec![];
+    let mut target_value = Amount::ZERO;
+    for _ in 0..rng.gen_range(1..12) {
+        let value = Amount::from_atoms(rng.gen_range(1..1000));
+        add_output(value, &mut groups);
+        // make the target from a random subset of the UTXOs
+        if rng.gen::<bool>() {
+            target_value = (target_value + value).unwrap();
+        }
+    }
+    if target_value == Amount::ZERO {
+        target_value = groups[0].value;
+    }
+    groups.shuffle(&mut rng);
+
+    let result = select_coins(
+        groups,
+        target_value,
+        PayFee::PayFeeWithThisCurrency,
+        Amount::ZERO,
+        CoinSelectionAlgo::LeastChange,
+    )
+    .unwrap();
+
+    assert_eq!(result.effective_value, target_value);
+    assert_eq!(result.get_change(), Amount::ZERO);
+}
+
+#[test]
+fn test_select_coins_least_change_no_exact_match() {
+    let mut groups = vec![];
+    for value in [10, 20, 40] {
+        add_output(Amount::from_atoms(value), &mut groups);
+    }
+
+    // 35 cannot be made exactly, 40 exceeds it the least
+    let target_value = Amount::from_atoms(35);
+    let result = select_coins(
+        groups,
+        target_value,
+        PayFee::PayFeeWithThisCurrency,
+        Amount::ZERO,
+        CoinSelectionAlgo::LeastChange,
+    )
+    .unwrap();
+
+    assert_eq!(result.effective_value, Amount::from_atoms(40));
+    assert_eq!(result.outputs.len(), 1);
+}
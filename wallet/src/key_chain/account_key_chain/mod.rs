@@ -260,6 +260,37 @@ impl AccountKeyChainImpl {
         self.account_vrf_public_key.as_ref()
     }
 
+    /// Derive the first `count` public keys for the purpose, without issuing them
+    pub fn derive_public_keys(
+        &self,
+        purpose: KeyPurpose,
+        count: U31,
+    ) -> KeyChainResult<Vec<PublicKey>> {
+        let purpose_key = self
+            .account_public_key
+            .as_ref()
+            .clone()
+            .derive_child(purpose.get_deterministic_index())?;
+        (0..count.into_u32())
+            .map(|index| {
+                let index = U31::from_u32(index).expect("below count");
+                Ok(purpose_key
+                    .clone()
+                    .derive_child(ChildNumber::from_normal(index))?
+                    .into_public_key())
+            })
+            .collect()
+    }
+
+    /// Get the account private key, which can be used to prove that the data was produced
+    /// by the owner of the account
+    pub fn account_private_key(
+        &self,
+        db_tx: &impl WalletStorageReadUnlocked,
+    ) -> KeyChainResult<PrivateKey> {
+        Ok(self.derive_account_private_key(db_tx)?.private_key())
+    }
+
     /// Return the next unused address and don't mark it as issued
     pub fn next_unused_address(
         &mut self,
@@ -282,6 +313,27 @@ impl AccountKeyChainImpl {
         Ok((index, address))
     }
 
+    /// Issue an address that hasn't been used yet, skipping over the already used ones. If the
+    /// lookahead doesn't allow issuing a new address, an earlier issued but unused one is returned
+    pub fn issue_unused_address(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        purpose: KeyPurpose,
+    ) -> KeyChainResult<(ChildNumber, Address<Destination>)> {
+        let lookahead_size = self.lookahead_size();
+        let (index, _key, address) =
+            self.get_leaf_key_chain_mut(purpose).issue_unused(db_tx, lookahead_size)?;
+        Ok((index, address))
+    }
+
+    /// Get the last issued address, regardless of whether it has been used or not
+    pub fn last_issued_address(
+        &self,
+        purpose: KeyPurpose,
+    ) -> Option<(ChildNumber, Address<Destination>)> {
+        self.get_leaf_key_chain(purpose).last_issued_address()
+    }
+
     /// Issue a new derived key that hasn't been used before
     pub fn issue_key(
         &mut self,
@@ -449,6 +501,13 @@ impl AccountKeyChainImpl {
             .any(|p| self.get_leaf_key_chain(*p).is_destination_mine(destination))
     }
 
+    // Return true if the provided destination belongs to this key chain and has been used
+    pub fn is_destination_used(&self, destination: &Destination) -> bool {
+        KeyPurpose::ALL
+            .iter()
+            .any(|p| self.get_leaf_key_chain(*p).is_destination_used(destination))
+    }
+
     // Return true if the provided public key belongs to this key chain
     pub fn is_public_key_mine(&self, public_key: &PublicKey) -> bool {
         KeyPurpose::ALL
@@ -538,6 +597,10 @@ impl AccountKeyChainImpl {
             let addr = RpcAddress::new(&self.chain_config, destination)?;
             return Err(KeyChainError::StandaloneAddressAlreadyExists(addr));
         }
+        if self.is_public_key_mine(&public_key) {
+            let addr = RpcAddress::new(&self.chain_config, destination)?;
+            return Err(KeyChainError::StandaloneKeyAlreadyDerived(addr));
+        }
 
         let public_key_hash = PublicKeyHash::from(&public_key);
         let id = AccountPrefixedId::new(self.get_account_id(), public_key);
@@ -645,6 +708,19 @@ impl AccountKeyChainImpl {
         self.get_leaf_key_chain(KeyPurpose::ReceiveFunds).get_all_issued_addresses()
     }
 
+    /// Get all the issued addresses of the given purpose along with whether they have been used
+    pub fn get_all_issued_addresses_with_usage(
+        &self,
+        purpose: KeyPurpose,
+    ) -> BTreeMap<ChildNumber, (Address<Destination>, bool)> {
+        let leaf_keys = self.get_leaf_key_chain(purpose);
+        leaf_keys
+            .get_all_issued_addresses()
+            .into_iter()
+            .map(|(index, address)| (index, (address, leaf_keys.is_used(index))))
+            .collect()
+    }
+
     pub fn get_all_standalone_addresses(&self) -> StandaloneAddresses {
         StandaloneAddresses {
             watch_only_addresses: self.standalone_watch_only_keys.clone().into_iter().collect(),
@@ -689,8 +765,8 @@ impl AccountKeyChainImpl {
         self.vrf_chain.get_legacy_vrf_public_key()
     }
 
-    pub fn get_addresses_usage_state(&self) -> &KeychainUsageState {
-        self.get_leaf_key_chain(KeyPurpose::ReceiveFunds).usage_state()
+    pub fn get_addresses_usage_state(&self, purpose: KeyPurpose) -> &KeychainUsageState {
+        self.get_leaf_key_chain(purpose).usage_state()
     }
 }
 
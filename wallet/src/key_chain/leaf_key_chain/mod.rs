@@ -23,7 +23,7 @@ use crypto::key::hdkd::child_number::ChildNumber;
 use crypto::key::hdkd::derivable::Derivable;
 use crypto::key::hdkd::u31::U31;
 use crypto::key::PublicKey;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
 use utils::const_value::ConstValue;
 use utils::ensure;
@@ -65,6 +65,9 @@ pub struct LeafKeySoftChain {
 
     /// The usage state of this key chain
     usage_state: KeychainUsageState,
+
+    /// The indexes of the keys that have been seen on the blockchain
+    used_indexes: BTreeSet<U31>,
 }
 
 impl LeafKeySoftChain {
@@ -84,6 +87,7 @@ impl LeafKeySoftChain {
             public_key_to_index: BTreeMap::new(),
             public_key_hash_to_index: BTreeMap::new(),
             usage_state: KeychainUsageState::default(),
+            used_indexes: BTreeSet::new(),
         }
     }
 
@@ -97,6 +101,7 @@ impl LeafKeySoftChain {
         addresses: BTreeMap<ChildNumber, Address<Destination>>,
         derived_public_keys: BTreeMap<ChildNumber, ExtendedPublicKey>,
         usage_state: KeychainUsageState,
+        used_indexes: BTreeSet<U31>,
     ) -> KeyChainResult<Self> {
         // TODO optimize for database structure
         let public_keys_to_index: BTreeMap<PublicKey, ChildNumber> = derived_public_keys
@@ -118,6 +123,7 @@ impl LeafKeySoftChain {
             public_key_to_index: public_keys_to_index,
             public_key_hash_to_index: public_key_hashes_to_index,
             usage_state,
+            used_indexes,
         })
     }
 
@@ -154,6 +160,16 @@ impl LeafKeySoftChain {
             .map(|(k, v)| (k.into_item_id(), v))
             .collect();
 
+        let receive_used_indexes = db_tx
+            .get_keychain_used_indexes(&AccountKeyPurposeId::new(
+                id.clone(),
+                KeyPurpose::ReceiveFunds,
+            ))?
+            .unwrap_or_default();
+        let change_used_indexes = db_tx
+            .get_keychain_used_indexes(&AccountKeyPurposeId::new(id.clone(), KeyPurpose::Change))?
+            .unwrap_or_default();
+
         Ok(WithPurpose::new(
             LeafKeySoftChain::new_from_parts(
                 chain_config.clone(),
@@ -167,6 +183,7 @@ impl LeafKeySoftChain {
                 usage_states.remove(&KeyPurpose::ReceiveFunds).ok_or(
                     KeyChainError::MissingDatabaseProperty("ReceiveFunds usage state"),
                 )?,
+                receive_used_indexes,
             )?,
             LeafKeySoftChain::new_from_parts(
                 chain_config,
@@ -180,6 +197,7 @@ impl LeafKeySoftChain {
                 usage_states
                     .remove(&KeyPurpose::Change)
                     .ok_or(KeyChainError::MissingDatabaseProperty("Change usage state"))?,
+                change_used_indexes,
             )?,
         ))
     }
@@ -245,6 +263,42 @@ impl LeafKeySoftChain {
         Ok((index, key, address))
     }
 
+    /// Issue a key that has never been used. A new key is issued if the lookahead allows it,
+    /// otherwise the first already issued key that hasn't been used yet is returned
+    pub fn issue_unused(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        lookahead_size: u32,
+    ) -> KeyChainResult<(ChildNumber, ExtendedPublicKey, Address<Destination>)> {
+        match self.issue_new(db_tx, lookahead_size) {
+            Err(KeyChainError::LookAheadExceeded) => {}
+            result => return result,
+        }
+
+        let (index, address) = self
+            .get_all_issued_addresses()
+            .into_iter()
+            .find(|(index, _)| !self.is_used(*index))
+            .ok_or(KeyChainError::LookAheadExceeded)?;
+        let key = self
+            .derived_public_keys
+            .get(&index)
+            .expect("The key of an issued address should be derived")
+            .clone();
+
+        Ok((index, key, address))
+    }
+
+    /// Persist the used key indexes to the database
+    fn save_used_indexes(&self, db_tx: &mut impl WalletStorageWriteLocked) -> KeyChainResult<()> {
+        db_tx
+            .set_keychain_used_indexes(
+                &AccountKeyPurposeId::new(self.account_id.clone(), self.purpose),
+                &self.used_indexes,
+            )
+            .map_err(KeyChainError::DatabaseError)
+    }
+
     /// Persist the usage state to the database
     pub fn save_usage_state(
         &self,
@@ -441,6 +495,9 @@ impl LeafKeySoftChain {
         child_num: ChildNumber,
         lookahead_size: u32,
     ) -> KeyChainResult<()> {
+        if self.used_indexes.insert(child_num.get_index()) {
+            self.save_used_indexes(db_tx)?;
+        }
         self.usage_state.increment_up_to_last_used(child_num.get_index());
         self.save_usage_state(db_tx)?;
         self.top_up(db_tx, lookahead_size)
@@ -484,6 +541,23 @@ impl LeafKeySoftChain {
         self.usage_state.last_issued()
     }
 
+    /// Check if the key with the specified index has been seen on the blockchain
+    pub fn is_used(&self, child_num: ChildNumber) -> bool {
+        self.used_indexes.contains(&child_num.get_index())
+    }
+
+    /// Check if the destination belongs to this key chain and has been seen on the blockchain
+    pub fn is_destination_used(&self, dest: &Destination) -> bool {
+        self.get_child_num_from_destination(dest)
+            .is_some_and(|child_num| self.is_used(child_num))
+    }
+
+    /// Get the last issued address or None if no key is issued
+    pub fn last_issued_address(&self) -> Option<(ChildNumber, Address<Destination>)> {
+        let last_issued = ChildNumber::from_normal(self.last_issued()?);
+        self.addresses.get(&last_issued).map(|address| (last_issued, address.clone()))
+    }
+
     pub fn get_all_issued_addresses(&self) -> BTreeMap<ChildNumber, Address<Destination>> {
         let last_issued = match self.usage_state.last_issued() {
             Some(index) => index,
@@ -23,9 +23,12 @@ use crypto::vrf::ExtendedVRFPrivateKey;
 use std::sync::Arc;
 use wallet_storage::{
     StoreTxRwUnlocked, WalletStorageReadLocked, WalletStorageReadUnlocked,
-    WalletStorageWriteUnlocked,
+    WalletStorageWriteLocked, WalletStorageWriteUnlocked,
+};
+use wallet_types::{
+    keys::{RootKeysInfo, WalletFingerprint},
+    seed_phrase::{SerializableSeedPhrase, StoreSeedPhrase},
 };
-use wallet_types::seed_phrase::{SerializableSeedPhrase, StoreSeedPhrase};
 
 use super::DEFAULT_VRF_KEY_KIND;
 
@@ -71,6 +74,14 @@ impl MasterKeyChain {
 
         let (root_key, root_vrf_key, seed_phrase) =
             Self::mnemonic_to_root_key(mnemonic_str, passphrase)?;
+
+        // Never store the passphrase itself, only whether it was used
+        let root_keys_info = RootKeysInfo {
+            fingerprint: WalletFingerprint::from_root_key(&root_key),
+            passphrase_used: passphrase.is_some_and(|p| !p.is_empty()),
+        };
+        db_tx.set_root_keys_info(&root_keys_info)?;
+
         Self::new_from_root_key(
             chain_config,
             db_tx,
@@ -40,7 +40,7 @@ pub use master_key_chain::MasterKeyChain;
 
 use common::address::pubkeyhash::PublicKeyHashError;
 use common::address::{AddressError, RpcAddress};
-use common::chain::config::BIP44_PATH;
+use common::chain::config::{ChainType, BIP44_PATH};
 use common::chain::{ChainConfig, Destination};
 use crypto::key::extended::{ExtendedKeyKind, ExtendedPublicKey};
 use crypto::key::hdkd::child_number::ChildNumber;
@@ -105,6 +105,8 @@ pub enum KeyChainError {
     NoStandaloneAddressFound(RpcAddress<Destination>),
     #[error("Standalone address already exists: {0}")]
     StandaloneAddressAlreadyExists(RpcAddress<Destination>),
+    #[error("The key is already derived from this account: {0}")]
+    StandaloneKeyAlreadyDerived(RpcAddress<Destination>),
 }
 
 pub enum FoundPubKey {
@@ -136,6 +138,19 @@ pub fn make_account_path(chain_config: &ChainConfig, account_index: U31) -> Deri
     path.try_into().expect("Path creation should not fail")
 }
 
+/// The BIP32 version bytes of the extended public keys exported on mainnet ("xpub")
+const MAINNET_XPUB_VERSION: [u8; 4] = [0x04, 0x88, 0xb2, 0x1e];
+/// The BIP32 version bytes of the extended public keys exported on the test networks ("tpub")
+const TESTNET_XPUB_VERSION: [u8; 4] = [0x04, 0x35, 0x87, 0xcf];
+
+/// The BIP32 version bytes of the extended public keys exported for the chain
+pub fn xpub_version(chain_config: &ChainConfig) -> [u8; 4] {
+    match chain_config.chain_type() {
+        ChainType::Mainnet => MAINNET_XPUB_VERSION,
+        ChainType::Testnet | ChainType::Regtest | ChainType::Signet => TESTNET_XPUB_VERSION,
+    }
+}
+
 pub const VRF_INDEX: ChildNumber = ChildNumber::from_hardened(U31::TWO);
 
 /// Create a deterministic path for the default VRF key for the account
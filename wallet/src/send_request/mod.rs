@@ -13,7 +13,6 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::BTreeMap;
 use std::mem::take;
 
 use common::address::Address;
@@ -34,6 +33,7 @@ use crate::account::currency_grouper::Currency;
 use crate::account::PoolData;
 use crate::destination_getters::{get_tx_output_destination, HtlcSpendingCondition};
 use crate::{WalletError, WalletResult};
+use wallet_types::currency::CurrencyAmountMap;
 
 /// The `SendRequest` struct provides the necessary information to the wallet
 /// on the precise method of sending funds to a designated destination.
@@ -51,7 +51,7 @@ pub struct SendRequest {
 
     outputs: Vec<TxOutput>,
 
-    fees: BTreeMap<Currency, Amount>,
+    fees: CurrencyAmountMap,
 }
 
 pub fn make_address_output(address: Address<Destination>, amount: Amount) -> TxOutput {
@@ -186,14 +186,12 @@ impl SendRequest {
             destinations: Vec::new(),
             inputs: Vec::new(),
             outputs: Vec::new(),
-            fees: BTreeMap::new(),
+            fees: CurrencyAmountMap::new(),
         }
     }
 
     pub fn add_fee(&mut self, currency: Currency, fee: Amount) -> WalletResult<()> {
-        let prev_fee = self.fees.entry(currency).or_insert(Amount::ZERO);
-        *prev_fee = (*prev_fee + fee).ok_or(WalletError::FeeAmountOverflow)?;
-        Ok(())
+        self.fees.checked_add(currency, fee).map_err(|_| WalletError::FeeAmountOverflow)
     }
 
     pub fn from_transaction<'a, PoolDataGetter>(
@@ -220,7 +218,7 @@ impl SendRequest {
             destinations,
             inputs: transaction.inputs().to_vec(),
             outputs: transaction.outputs().to_vec(),
-            fees: BTreeMap::new(),
+            fees: CurrencyAmountMap::new(),
         })
     }
 
@@ -284,7 +282,7 @@ impl SendRequest {
         &mut self.outputs
     }
 
-    pub fn get_fees(&mut self) -> BTreeMap<Currency, Amount> {
+    pub fn get_fees(&mut self) -> CurrencyAmountMap {
         take(&mut self.fees)
     }
 
@@ -307,6 +305,9 @@ impl SendRequest {
 pub enum SelectedInputs {
     Utxos(Vec<UtxoOutPoint>),
     Inputs(Vec<(UtxoOutPoint, TxOutput)>),
+    /// Nothing is preselected and the inputs are selected from all the spendable UTXOs,
+    /// including the ones that haven't reached the wallet's confirmation threshold yet
+    IncludingPending,
 }
 
 impl SelectedInputs {
@@ -314,6 +315,7 @@ impl SelectedInputs {
         match self {
             Self::Utxos(utxos) => utxos.is_empty(),
             Self::Inputs(inputs) => inputs.is_empty(),
+            Self::IncludingPending => true,
         }
     }
 }
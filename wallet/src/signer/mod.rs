@@ -63,7 +63,8 @@ pub trait Signer {
         Vec<SignatureStatus>,
     )>;
 
-    /// sign an arbitrary message for a destination known to this key chain
+    /// sign an arbitrary message for a destination known to this key chain;
+    /// for a multisig destination, the signature may be partial
     fn sign_challenge(
         &self,
         message: Vec<u8>,
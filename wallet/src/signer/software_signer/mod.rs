@@ -16,11 +16,12 @@
 use std::sync::Arc;
 
 use common::chain::{
+    classic_multisig::ClassicMultisigChallenge,
     htlc::HtlcSecret,
     partially_signed_transaction::PartiallySignedTransaction,
     signature::{
         inputsig::{
-            arbitrary_message::ArbitraryMessageSignature,
+            arbitrary_message::{produce_message_challenge, ArbitraryMessageSignature},
             classical_multisig::{
                 authorize_classical_multisig::{
                     sign_classical_multisig_spending, AuthorizedClassicalMultisigSpend,
@@ -44,6 +45,8 @@ use crypto::key::{
 };
 use itertools::Itertools;
 use randomness::make_true_rng;
+use serialization::Encode;
+use utils::ensure;
 use wallet_storage::WalletStorageReadUnlocked;
 use wallet_types::signature_status::SignatureStatus;
 
@@ -235,6 +238,54 @@ impl<'a, T: WalletStorageReadUnlocked> SoftwareSigner<'a, T> {
 
         Ok((current_signatures, previous_status, final_status))
     }
+
+    /// Sign an arbitrary message with the keys of the multisig that belong to this key chain;
+    /// the signature is partial if the key chain doesn't have enough of the keys
+    fn sign_multisig_challenge(
+        &self,
+        message: &[u8],
+        challenge: ClassicMultisigChallenge,
+        key_chain: &impl AccountKeyChains,
+    ) -> SignerResult<ArbitraryMessageSignature> {
+        let message_challenge = produce_message_challenge(message);
+        let mut current_signatures = AuthorizedClassicalMultisigSpend::new_empty(challenge.clone());
+
+        for (key_index, public_key) in challenge.public_keys().iter().enumerate() {
+            if let Some(private_key) = self.get_private_key_for_destination(
+                &Destination::PublicKey(public_key.clone()),
+                key_chain,
+            )? {
+                let res = sign_classical_multisig_spending(
+                    &self.chain_config,
+                    key_index as u8,
+                    &private_key,
+                    &challenge,
+                    &message_challenge,
+                    current_signatures,
+                    &mut make_true_rng(),
+                )
+                .map_err(DestinationSigError::ClassicalMultisigSigningFailed)?;
+
+                match res {
+                    ClassicalMultisigCompletionStatus::Complete(signatures) => {
+                        current_signatures = signatures;
+                        break;
+                    }
+                    ClassicalMultisigCompletionStatus::Incomplete(signatures) => {
+                        current_signatures = signatures;
+                    }
+                }
+            }
+        }
+
+        ensure!(
+            !current_signatures.is_empty(),
+            SignerError::DestinationNotFromThisWallet
+        );
+        Ok(ArbitraryMessageSignature::from_data(
+            current_signatures.encode(),
+        ))
+    }
 }
 
 impl<'a, T: WalletStorageReadUnlocked> Signer for SoftwareSigner<'a, T> {
@@ -345,6 +396,13 @@ impl<'a, T: WalletStorageReadUnlocked> Signer for SoftwareSigner<'a, T> {
         destination: Destination,
         key_chain: &impl AccountKeyChains,
     ) -> SignerResult<ArbitraryMessageSignature> {
+        if let Destination::ClassicMultisig(_) = destination {
+            let challenge = key_chain
+                .find_multisig_challenge(&destination)
+                .ok_or(SignerError::DestinationNotFromThisWallet)?;
+            return self.sign_multisig_challenge(&message, challenge.clone(), key_chain);
+        }
+
         let private_key = self
             .get_private_key_for_destination(&destination, key_chain)?
             .ok_or(SignerError::DestinationNotFromThisWallet)?;
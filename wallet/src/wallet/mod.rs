@@ -17,15 +17,15 @@ use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use crate::account::transaction_list::TransactionList;
+use crate::account::transaction_list::{TransactionInfo, TransactionList};
 use crate::account::{
     currency_grouper::Currency, CurrentFeeRate, DelegationData, PoolData, TransactionToSign,
     UnconfirmedTokenInfo, UtxoSelectorError,
 };
 use crate::account::{CoinSelectionAlgo, TxInfo};
 use crate::key_chain::{
-    make_account_path, make_path_to_vrf_key, KeyChainError, MasterKeyChain, LOOKAHEAD_SIZE,
-    VRF_INDEX,
+    make_account_path, make_path_to_vrf_key, xpub_version, KeyChainError, MasterKeyChain,
+    LOOKAHEAD_SIZE, VRF_INDEX,
 };
 use crate::send_request::{
     make_issue_token_outputs, IssueNftArguments, SelectedInputs, StakePoolDataArguments,
@@ -43,6 +43,7 @@ use common::chain::htlc::HashedTimelockContract;
 use common::chain::output_value::OutputValue;
 use common::chain::partially_signed_transaction::PartiallySignedTransaction;
 use common::chain::signature::inputsig::arbitrary_message::{
+    proof_of_reserves::{produce_reserve_proof_message, ProofOfReserves, ReserveProofEntry},
     ArbitraryMessageSignature, SignArbitraryMessageError,
 };
 use common::chain::signature::DestinationSigError;
@@ -59,11 +60,13 @@ use common::size_estimation::SizeEstimationError;
 use consensus::PoSGenerateBlockInputData;
 use crypto::key::hdkd::child_number::ChildNumber;
 use crypto::key::hdkd::derivable::Derivable;
+use crypto::key::hdkd::derivation_path::DerivationPath;
 use crypto::key::hdkd::u31::U31;
 use crypto::key::{PrivateKey, PublicKey};
 use crypto::vrf::VRFPublicKey;
-use mempool::FeeRate;
+use mempool::{error::PackageLimitError, FeeRate, PackageLimits};
 use pos_accounting::make_delegation_id;
+use randomness::make_true_rng;
 use tx_verifier::error::TokenIssuanceError;
 use tx_verifier::{check_transaction, CheckTransactionError};
 use utils::ensure;
@@ -73,10 +76,22 @@ use wallet_storage::{
     TransactionRwUnlocked, Transactional, WalletStorageReadLocked, WalletStorageReadUnlocked,
     WalletStorageWriteLocked, WalletStorageWriteUnlocked,
 };
+use wallet_types::account_id::AccountOutflowId;
 use wallet_types::account_info::{StandaloneAddressDetails, StandaloneAddresses};
+use wallet_types::address_set::{
+    AddressSet, AddressSetError, ImportedAddressSet, SignedAddressSet,
+};
 use wallet_types::chain_info::ChainInfo;
+use wallet_types::confirmations::{BalancesByConfirmations, DEFAULT_CONFIRMATION_THRESHOLD};
+use wallet_types::currency::CurrencyAmountMap;
+use wallet_types::idempotency::{IdempotencyKey, IdempotencyRecord};
+use wallet_types::keys::{DescriptorType, WalletFingerprint};
+use wallet_types::label::{matches_search_text, Label, SearchMatch};
 use wallet_types::seed_phrase::{SerializableSeedPhrase, StoreSeedPhrase};
 use wallet_types::signature_status::SignatureStatus;
+use wallet_types::spending_policy::{
+    SpendingPolicy, SpendingPolicyViolation, DAILY_CAP_PERIOD_SECS,
+};
 use wallet_types::utxo_types::{UtxoStates, UtxoTypes};
 use wallet_types::wallet_tx::{TxData, TxState};
 use wallet_types::wallet_type::WalletType;
@@ -91,7 +106,14 @@ pub const WALLET_VERSION_V4: u32 = 4;
 pub const WALLET_VERSION_V5: u32 = 5;
 pub const WALLET_VERSION_V6: u32 = 6;
 pub const WALLET_VERSION_V7: u32 = 7;
-pub const CURRENT_WALLET_VERSION: u32 = WALLET_VERSION_V7;
+pub const WALLET_VERSION_V8: u32 = 8;
+pub const WALLET_VERSION_V9: u32 = 9;
+pub const CURRENT_WALLET_VERSION: u32 = WALLET_VERSION_V9;
+/// The oldest wallet version that can still be migrated to the current one
+pub const MIN_SUPPORTED_WALLET_VERSION: u32 = WALLET_VERSION_V1;
+
+/// The version of this wallet software, recorded in the wallet DB when it's created or upgraded
+pub const WALLET_SOFTWARE_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Wallet errors
 #[derive(thiserror::Error, Debug, Eq, PartialEq)]
@@ -104,6 +126,8 @@ pub enum WalletError {
     DifferentChainType,
     #[error("Unsupported wallet version: {0}, max supported version of this software is {CURRENT_WALLET_VERSION}")]
     UnsupportedWalletVersion(u32),
+    #[error("The wallet file was created by wallet v{1}, please upgrade (wallet version: {0}, max supported version of this software is {CURRENT_WALLET_VERSION})")]
+    WalletCreatedByNewerSoftware(u32, String),
     #[error("Wallet database error: {0}")]
     DatabaseError(#[from] wallet_storage::Error),
     #[error("Transaction already present: {0}")]
@@ -220,6 +244,8 @@ pub enum WalletError {
     PartiallySignedTransactionInDecommissionCommand,
     #[error("Failed to create decommission request as all the signatures are present. Use staking-decommission-pool command.")]
     FullySignedTransactionInDecommissionReq,
+    #[error("The decommission key of pool {0} is not owned by the selected account")]
+    PoolDecommissionKeyNotOwned(PoolId),
     #[error("Destination does not belong to this wallet")]
     DestinationNotFromThisWallet,
     #[error("Sign message error: {0}")]
@@ -234,6 +260,20 @@ pub enum WalletError {
     StandaloneAddressNotFound(RpcAddress<Destination>),
     #[error("Signer error: {0}")]
     SignerError(#[from] SignerError),
+    #[error("None of the transaction inputs belong to this account")]
+    NoOwnedInputs,
+    #[error("Idempotency key {0} was already used for a different request")]
+    IdempotencyKeyReused(String),
+    #[error("Spending policy violation: {0}")]
+    SpendingPolicyViolation(#[from] SpendingPolicyViolation),
+    #[error("Signing arbitrary transactions is not allowed for an account with a spending policy")]
+    RawSigningForbiddenBySpendingPolicy,
+    #[error("Address set error: {0}")]
+    AddressSetError(#[from] AddressSetError),
+    #[error("The address set was exported for a different chain")]
+    AddressSetChainMismatch,
+    #[error("The confirmation threshold must be at least 1")]
+    ZeroConfirmationThreshold,
 }
 
 /// Result type used for the wallet
@@ -270,6 +310,50 @@ pub fn create_wallet_in_memory() -> WalletResult<Store<DefaultBackend>> {
     Ok(Store::new(DefaultBackend::new_in_memory())?)
 }
 
+pub fn create_wallet_file_from_backup<P: AsRef<Path>>(
+    path: P,
+    backup: &[u8],
+    password: Option<&String>,
+) -> WalletResult<Store<DefaultBackend>> {
+    Ok(Store::new_from_backup(
+        DefaultBackend::new(path),
+        backup,
+        password,
+    )?)
+}
+
+/// Copy the wallet file before migrating it from `version`, so it can be restored if anything
+/// goes wrong. The copy is placed next to the file, with a `_backup_v{version}` suffix.
+pub fn make_wallet_file_backup<P: AsRef<Path>>(
+    file_path: P,
+    version: u32,
+) -> WalletResult<PathBuf> {
+    let backup_name = file_path
+        .as_ref()
+        .file_name()
+        .map(|file_name| {
+            let mut file_name = file_name.to_os_string();
+            file_name.push(format!("_backup_v{version}"));
+            file_name
+        })
+        .ok_or(WalletError::WalletFileError(
+            file_path.as_ref().to_owned(),
+            "File path is not a file".to_owned(),
+        ))?;
+    let backup_file_path = file_path.as_ref().with_file_name(backup_name);
+    logging::log::info!(
+        "The wallet DB requires a migration, creating a backup file: {}",
+        backup_file_path.to_string_lossy()
+    );
+    std::fs::copy(&file_path, &backup_file_path).map_err(|_| {
+        WalletError::WalletFileError(
+            file_path.as_ref().to_owned(),
+            "Could not make a backup of the file before migrating it".to_owned(),
+        )
+    })?;
+    Ok(backup_file_path)
+}
+
 impl<B: storage::Backend> Wallet<B> {
     pub fn create_new_wallet(
         chain_config: Arc<ChainConfig>,
@@ -331,6 +415,7 @@ impl<B: storage::Backend> Wallet<B> {
         )?;
 
         db_tx.set_storage_version(CURRENT_WALLET_VERSION)?;
+        db_tx.set_software_version(WALLET_SOFTWARE_VERSION)?;
         db_tx.set_chain_info(&ChainInfo::new(chain_config.as_ref()))?;
         db_tx.set_lookahead_size(LOOKAHEAD_SIZE)?;
         db_tx.set_wallet_type(wallet_type)?;
@@ -369,69 +454,52 @@ impl<B: storage::Backend> Wallet<B> {
     /// Migrate the wallet DB from version 1 to version 2
     /// * save the chain info in the DB based on the chain type specified by the user
     /// * reset transactions
-    fn migration_v2(db: &Store<B>, chain_config: Arc<ChainConfig>) -> WalletResult<()> {
-        let mut db_tx = db.transaction_rw_unlocked(None)?;
+    fn migration_v2(
+        db_tx: &mut impl WalletStorageWriteUnlocked,
+        chain_config: Arc<ChainConfig>,
+    ) -> WalletResult<()> {
         // set new chain info to the one provided by the user assuming it is the correct one
         db_tx.set_chain_info(&ChainInfo::new(chain_config.as_ref()))?;
 
         // reset wallet transaction as now we will need to rescan the blockchain to store the
         // correct order of the transactions to avoid bugs in loading them in the wrong order
-        Self::reset_wallet_transactions(chain_config.clone(), &mut db_tx)?;
+        Self::reset_wallet_transactions(chain_config.clone(), db_tx)?;
 
         // Create the next unused account
-        Self::migrate_next_unused_account(chain_config, &mut db_tx)?;
-
-        db_tx.set_storage_version(WALLET_VERSION_V2)?;
-        db_tx.commit()?;
-        logging::log::info!(
-            "Successfully migrated wallet database to latest version {}",
-            WALLET_VERSION_V2
-        );
+        Self::migrate_next_unused_account(chain_config, db_tx)?;
 
         Ok(())
     }
 
     /// Migrate the wallet DB from version 2 to version 3
     /// * reset transactions as now we store SignedTransaction instead of Transaction in WalletTx
-    fn migration_v3(db: &Store<B>, chain_config: Arc<ChainConfig>) -> WalletResult<()> {
-        let mut db_tx = db.transaction_rw_unlocked(None)?;
+    fn migration_v3(
+        db_tx: &mut impl WalletStorageWriteLocked,
+        chain_config: Arc<ChainConfig>,
+    ) -> WalletResult<()> {
         // reset wallet transaction as now we will need to rescan the blockchain to store the
         // correct order of the transactions to avoid bugs in loading them in the wrong order
-        Self::reset_wallet_transactions(chain_config.clone(), &mut db_tx)?;
-
-        db_tx.set_storage_version(WALLET_VERSION_V3)?;
-        db_tx.commit()?;
-        logging::log::info!(
-            "Successfully migrated wallet database to latest version {}",
-            WALLET_VERSION_V3
-        );
+        Self::reset_wallet_transactions(chain_config, db_tx)?;
 
         Ok(())
     }
 
     /// Migrate the wallet DB from version 3 to version 4
     /// * set lookahead_size in the DB
-    fn migration_v4(db: &Store<B>) -> WalletResult<()> {
-        let mut db_tx = db.transaction_rw_unlocked(None)?;
-
+    fn migration_v4(db_tx: &mut impl WalletStorageWriteLocked) -> WalletResult<()> {
         db_tx.set_lookahead_size(LOOKAHEAD_SIZE)?;
-        db_tx.set_storage_version(WALLET_VERSION_V4)?;
-        db_tx.commit()?;
-        logging::log::info!(
-            "Successfully migrated wallet database to latest version {}",
-            WALLET_VERSION_V4
-        );
 
         Ok(())
     }
 
     /// Migrate the wallet DB from version 4 to version 5
     /// * set vrf key_chain usage
-    fn migration_v5(db: &Store<B>, chain_config: Arc<ChainConfig>) -> WalletResult<()> {
-        let mut db_tx = db.transaction_rw_unlocked(None)?;
-
+    fn migration_v5(
+        db_tx: &mut impl WalletStorageWriteUnlocked,
+        chain_config: Arc<ChainConfig>,
+    ) -> WalletResult<()> {
         for (id, info) in db_tx.get_accounts_info()? {
-            let root_vrf_key = MasterKeyChain::load_root_vrf_key(&db_tx)?;
+            let root_vrf_key = MasterKeyChain::load_root_vrf_key(&*db_tx)?;
             let account_path = make_account_path(&chain_config, info.account_index());
             let legacy_key_path = make_path_to_vrf_key(&chain_config, info.account_index());
             let legacy_vrf_key = root_vrf_key
@@ -456,37 +524,24 @@ impl<B: storage::Backend> Wallet<B> {
             )?;
         }
 
-        Self::reset_wallet_transactions_and_load(chain_config.clone(), &mut db_tx)?;
-
-        db_tx.set_storage_version(WALLET_VERSION_V5)?;
-        db_tx.commit()?;
-        logging::log::info!(
-            "Successfully migrated wallet database to latest version {}",
-            WALLET_VERSION_V5
-        );
+        Self::reset_wallet_transactions_and_load(chain_config.clone(), db_tx)?;
 
         Ok(())
     }
 
-    fn migration_v6(db: &Store<B>, _chain_config: Arc<ChainConfig>) -> WalletResult<()> {
-        let mut db_tx = db.transaction_rw(None)?;
-        // nothing to do the seed phrase na passphrase are backwards compatible
-        db_tx.set_storage_version(WALLET_VERSION_V6)?;
-        db_tx.commit()?;
-
-        logging::log::info!(
-            "Successfully migrated wallet database to latest version {}",
-            WALLET_VERSION_V6
-        );
+    /// Migrate the wallet DB from version 5 to version 6
+    /// * nothing to do, the seed phrase and passphrase are backwards compatible
+    fn migration_v6() -> WalletResult<()> {
         Ok(())
     }
 
+    /// Migrate the wallet DB from version 6 to version 7
+    /// * set the wallet type, only wallets that are still on genesis can be cold wallets
     fn migration_v7(
-        db: &Store<B>,
+        db_tx: &mut impl WalletStorageWriteLocked,
         chain_config: Arc<ChainConfig>,
         wallet_type: WalletType,
     ) -> WalletResult<()> {
-        let mut db_tx = db.transaction_rw(None)?;
         let accs = db_tx.get_accounts_info()?;
         // if all accounts are still on genesis this is a cold wallet
         let cold_wallet =
@@ -499,58 +554,143 @@ impl<B: storage::Backend> Wallet<B> {
 
         db_tx.set_wallet_type(wallet_type)?;
 
-        db_tx.set_storage_version(WALLET_VERSION_V7)?;
-        db_tx.commit()?;
+        Ok(())
+    }
+
+    /// Migrate the wallet DB from version 7 to version 8
+    /// * rescan the blockchain to record which addresses have been used
+    fn migration_v8(
+        db_tx: &mut impl WalletStorageWriteLocked,
+        chain_config: Arc<ChainConfig>,
+    ) -> WalletResult<()> {
+        Self::reset_wallet_transactions_and_load(chain_config, db_tx)?;
 
-        logging::log::info!(
-            "Successfully migrated wallet database to latest version {}",
-            WALLET_VERSION_V7
-        );
         Ok(())
     }
 
-    /// Check the wallet DB version and perform any migrations needed
+    /// Migrate the wallet DB from version 8 to version 9
+    /// * record the version of the wallet software that has upgraded the DB, the wallets created
+    ///   from now on record it on creation
+    fn migration_v9(db_tx: &mut impl WalletStorageWriteLocked) -> WalletResult<()> {
+        db_tx.set_software_version(WALLET_SOFTWARE_VERSION)?;
+
+        Ok(())
+    }
+
+    /// Perform the migrations up to version 5, which need access to the private keys
+    fn migrate_to_v5(
+        db_tx: &mut impl WalletStorageWriteUnlocked,
+        mut version: u32,
+        chain_config: Arc<ChainConfig>,
+    ) -> WalletResult<u32> {
+        while version < WALLET_VERSION_V5 {
+            match version {
+                WALLET_VERSION_V1 => Self::migration_v2(db_tx, chain_config.clone())?,
+                WALLET_VERSION_V2 => Self::migration_v3(db_tx, chain_config.clone())?,
+                WALLET_VERSION_V3 => Self::migration_v4(db_tx)?,
+                WALLET_VERSION_V4 => Self::migration_v5(db_tx, chain_config.clone())?,
+                _ => return Err(WalletError::UnsupportedWalletVersion(version)),
+            }
+            version += 1;
+            Self::set_migrated_version(db_tx, version)?;
+        }
+
+        Ok(version)
+    }
+
+    /// Perform the migrations from version 5 up to the current version
+    fn migrate_to_current(
+        db_tx: &mut impl WalletStorageWriteLocked,
+        mut version: u32,
+        chain_config: Arc<ChainConfig>,
+        wallet_type: WalletType,
+    ) -> WalletResult<()> {
+        while version < CURRENT_WALLET_VERSION {
+            match version {
+                WALLET_VERSION_V5 => Self::migration_v6()?,
+                WALLET_VERSION_V6 => Self::migration_v7(db_tx, chain_config.clone(), wallet_type)?,
+                WALLET_VERSION_V7 => Self::migration_v8(db_tx, chain_config.clone())?,
+                WALLET_VERSION_V8 => Self::migration_v9(db_tx)?,
+                _ => return Err(WalletError::UnsupportedWalletVersion(version)),
+            }
+            version += 1;
+            Self::set_migrated_version(db_tx, version)?;
+        }
+
+        Ok(())
+    }
+
+    fn set_migrated_version(
+        db_tx: &mut impl WalletStorageWriteLocked,
+        version: u32,
+    ) -> WalletResult<()> {
+        db_tx.set_storage_version(version)?;
+        logging::log::info!("Migrated wallet database to version {version}");
+        Ok(())
+    }
+
+    /// Check that the wallet DB is initialized and not newer than this software supports,
+    /// and return its version
+    fn check_db_version(db: &Store<B>) -> WalletResult<u32> {
+        let db_tx = db.transaction_ro()?;
+        let version = db_tx.get_storage_version()?;
+
+        ensure!(
+            version != WALLET_VERSION_UNINITIALIZED,
+            WalletError::WalletNotInitialized
+        );
+
+        if version > CURRENT_WALLET_VERSION {
+            // Files created before the software version was recorded can't be newer than this
+            // software, but the record may still be missing if the file is corrupted
+            return Err(match db_tx.get_software_version()? {
+                Some(software_version) => {
+                    WalletError::WalletCreatedByNewerSoftware(version, software_version)
+                }
+                None => WalletError::UnsupportedWalletVersion(version),
+            });
+        }
+
+        Ok(version)
+    }
+
+    /// Perform any migrations needed to bring the wallet DB from `version` to the current one.
+    ///
+    /// `pre_migration` is called once before the first migration, e.g. to make a backup of the
+    /// wallet file. All the migrations are performed in a single DB transaction, so a failed
+    /// migration leaves the DB unchanged.
     fn check_and_migrate_db<F: Fn(u32) -> Result<(), WalletError>>(
         db: &Store<B>,
+        version: u32,
         chain_config: Arc<ChainConfig>,
         pre_migration: F,
         wallet_type: WalletType,
     ) -> WalletResult<()> {
-        let version = db.transaction_ro()?.get_storage_version()?;
+        if version == CURRENT_WALLET_VERSION {
+            return Ok(());
+        }
 
-        match version {
-            WALLET_VERSION_UNINITIALIZED => return Err(WalletError::WalletNotInitialized),
-            WALLET_VERSION_V1 => {
-                pre_migration(WALLET_VERSION_V1)?;
-                Self::migration_v2(db, chain_config.clone())?;
-            }
-            WALLET_VERSION_V2 => {
-                pre_migration(WALLET_VERSION_V2)?;
-                Self::migration_v3(db, chain_config.clone())?;
-            }
-            WALLET_VERSION_V3 => {
-                pre_migration(WALLET_VERSION_V3)?;
-                Self::migration_v4(db)?;
-            }
-            WALLET_VERSION_V4 => {
-                pre_migration(WALLET_VERSION_V4)?;
-                Self::migration_v5(db, chain_config.clone())?;
-            }
-            WALLET_VERSION_V5 => {
-                pre_migration(WALLET_VERSION_V5)?;
-                Self::migration_v6(db, chain_config.clone())?;
-            }
-            WALLET_VERSION_V6 => {
-                pre_migration(WALLET_VERSION_V6)?;
-                Self::migration_v7(db, chain_config.clone(), wallet_type)?;
-            }
-            CURRENT_WALLET_VERSION => return Ok(()),
-            unsupported_version => {
-                return Err(WalletError::UnsupportedWalletVersion(unsupported_version))
-            }
+        pre_migration(version)?;
+
+        // The private keys are only needed by the older migrations, so a locked wallet can still
+        // be migrated from the newer versions
+        if version < WALLET_VERSION_V5 {
+            let mut db_tx = db.transaction_rw_unlocked(None)?;
+            let version = Self::migrate_to_v5(&mut db_tx, version, chain_config.clone())?;
+            Self::migrate_to_current(&mut db_tx, version, chain_config, wallet_type)?;
+            db_tx.commit()?;
+        } else {
+            let mut db_tx = db.transaction_rw(None)?;
+            Self::migrate_to_current(&mut db_tx, version, chain_config, wallet_type)?;
+            db_tx.commit()?;
         }
 
-        Self::check_and_migrate_db(db, chain_config, pre_migration, wallet_type)
+        logging::log::info!(
+            "Successfully migrated wallet database to latest version {}",
+            CURRENT_WALLET_VERSION
+        );
+
+        Ok(())
     }
 
     fn validate_chain_info(
@@ -647,6 +787,9 @@ impl<B: storage::Backend> Wallet<B> {
                 &AccountKeyPurposeId::new(id.clone(), KeyPurpose::ReceiveFunds),
                 &KeychainUsageState::new(None, None),
             )?;
+            for purpose in KeyPurpose::ALL {
+                db_tx.del_keychain_used_indexes(&AccountKeyPurposeId::new(id.clone(), purpose))?;
+            }
             db_tx
                 .set_vrf_keychain_usage_state(&id.clone(), &KeychainUsageState::new(None, None))?;
         }
@@ -710,10 +853,19 @@ impl<B: storage::Backend> Wallet<B> {
         wallet_type: WalletType,
         force_change_wallet_type: bool,
     ) -> WalletResult<Self> {
+        // Refuse newer wallets before touching anything else in the DB
+        let version = Self::check_db_version(&db)?;
+
         if let Some(password) = password {
             db.unlock_private_keys(&password)?;
         }
-        Self::check_and_migrate_db(&db, chain_config.clone(), pre_migration, wallet_type)?;
+        Self::check_and_migrate_db(
+            &db,
+            version,
+            chain_config.clone(),
+            pre_migration,
+            wallet_type,
+        )?;
         if force_change_wallet_type {
             Self::force_migrate_wallet_type(wallet_type, &db, chain_config.clone())?;
         }
@@ -755,6 +907,29 @@ impl<B: storage::Backend> Wallet<B> {
         })
     }
 
+    /// Load a wallet from a DB restored from a backup made with `make_backup`.
+    /// The synced transactions are not part of the backup, so the wallet is reset to genesis
+    /// to rescan the blockchain.
+    pub fn load_wallet_from_backup(
+        chain_config: Arc<ChainConfig>,
+        db: Store<B>,
+        wallet_type: WalletType,
+    ) -> WalletResult<Self> {
+        let mut db_tx = db.transaction_rw(None)?;
+        Self::reset_wallet_transactions(chain_config.clone(), &mut db_tx)?;
+        db_tx.commit()?;
+
+        let mut wallet = Self::load_wallet(chain_config, db, None, |_| Ok(()), wallet_type, false)?;
+        wallet.reset_wallet_to_genesis()?;
+
+        Ok(wallet)
+    }
+
+    /// Make an encrypted backup of the wallet data that can't be recovered from the blockchain
+    pub fn make_backup(&self, password: Option<&String>) -> WalletResult<Vec<u8>> {
+        self.db.make_backup(password).map_err(WalletError::from)
+    }
+
     pub fn seed_phrase(&self) -> WalletResult<Option<SerializableSeedPhrase>> {
         self.db.transaction_ro_unlocked()?.get_seed_phrase().map_err(WalletError::from)
     }
@@ -767,10 +942,28 @@ impl<B: storage::Backend> Wallet<B> {
         Ok(seed_phrase)
     }
 
+    /// Returns the fingerprint of the wallet and whether a passphrase was used to create it.
+    ///
+    /// The passphrase usage is unknown for wallets created before it was recorded. The fingerprint
+    /// of such wallets is computed from the root key, which requires the wallet to be unlocked.
+    pub fn fingerprint(&self) -> WalletResult<(WalletFingerprint, Option<bool>)> {
+        if let Some(info) = self.db.transaction_ro()?.get_root_keys_info()? {
+            return Ok((info.fingerprint, Some(info.passphrase_used)));
+        }
+
+        let root_key = MasterKeyChain::load_root_key(&self.db.transaction_ro_unlocked()?)?;
+        Ok((WalletFingerprint::from_root_key(&root_key), None))
+    }
+
     pub fn is_encrypted(&self) -> bool {
         self.db.is_encrypted()
     }
 
+    /// The version of the wallet DB schema
+    pub fn storage_version(&self) -> WalletResult<u32> {
+        Ok(self.db.transaction_ro()?.get_storage_version()?)
+    }
+
     pub fn is_locked(&self) -> bool {
         self.db.is_locked()
     }
@@ -792,10 +985,17 @@ impl<B: storage::Backend> Wallet<B> {
         lookahead_size: u32,
         force_reduce: bool,
     ) -> WalletResult<()> {
-        let last_used = self.accounts.values().fold(None, |last, acc| {
-            let usage = acc.get_addresses_usage();
-            std::cmp::max(last, usage.last_used().map(U31::into_u32))
-        });
+        // The change addresses are found by the same lookahead as the receive ones
+        let last_used = self
+            .accounts
+            .values()
+            .flat_map(|acc| {
+                KeyPurpose::ALL
+                    .iter()
+                    .filter_map(|purpose| acc.get_addresses_usage(*purpose).last_used())
+            })
+            .max()
+            .map(U31::into_u32);
 
         if let Some(last_used) = last_used {
             ensure!(
@@ -1034,7 +1234,7 @@ impl<B: storage::Backend> Wallet<B> {
         account_index: U31,
         utxo_states: UtxoStates,
         with_locked: WithLocked,
-    ) -> WalletResult<BTreeMap<Currency, Amount>> {
+    ) -> WalletResult<CurrencyAmountMap> {
         self.get_account(account_index)?.get_balance(
             utxo_states,
             self.latest_median_time,
@@ -1042,6 +1242,28 @@ impl<B: storage::Backend> Wallet<B> {
         )
     }
 
+    /// The balance split by the number of confirmations; the wallet's confirmation threshold
+    /// is used if none is given
+    pub fn get_balance_by_confirmations(
+        &self,
+        account_index: U31,
+        confirmation_threshold: Option<u64>,
+        with_locked: WithLocked,
+    ) -> WalletResult<BalancesByConfirmations> {
+        let confirmation_threshold = match confirmation_threshold {
+            Some(threshold) => {
+                ensure!(threshold > 0, WalletError::ZeroConfirmationThreshold);
+                threshold
+            }
+            None => self.get_confirmation_threshold()?,
+        };
+        self.get_account(account_index)?.get_balance_by_confirmations(
+            confirmation_threshold,
+            self.latest_median_time,
+            with_locked,
+        )
+    }
+
     pub fn get_multisig_utxos(
         &self,
         account_index: U31,
@@ -1084,6 +1306,15 @@ impl<B: storage::Backend> Wallet<B> {
         Ok(utxos)
     }
 
+    /// Find an output of a transaction or block of the account, whether it's spent or not
+    pub fn find_account_txo(
+        &self,
+        account_index: U31,
+        outpoint: &UtxoOutPoint,
+    ) -> WalletResult<Option<TxOutput>> {
+        Ok(self.get_account(account_index)?.find_txo(outpoint).cloned())
+    }
+
     pub fn find_unspent_utxo_with_destination(
         &self,
         outpoint: &UtxoOutPoint,
@@ -1106,6 +1337,20 @@ impl<B: storage::Backend> Wallet<B> {
         Ok(transactions)
     }
 
+    /// Check that the transaction would not exceed the mempool limits on chains of unconfirmed
+    /// transactions, taking into account the unconfirmed transactions of the account.
+    ///
+    /// Returns the limit that would be exceeded, if any.
+    pub fn check_package_limits(
+        &self,
+        account_index: U31,
+        tx: &SignedTransaction,
+        limits: &PackageLimits,
+    ) -> WalletResult<Option<PackageLimitError>> {
+        let account = self.get_account(account_index)?;
+        Ok(account.check_package_limits(tx, limits).err())
+    }
+
     pub fn mainchain_transactions(
         &self,
         account_index: U31,
@@ -1113,7 +1358,11 @@ impl<B: storage::Backend> Wallet<B> {
         limit: usize,
     ) -> WalletResult<Vec<TxInfo>> {
         let account = self.get_account(account_index)?;
-        let transactions = account.mainchain_transactions(destination, limit);
+        let mut transactions = account.mainchain_transactions(destination, limit);
+        let labels = self.db.transaction_ro()?.get_transaction_labels()?;
+        for tx in transactions.iter_mut() {
+            tx.label = labels.get(&tx.id).map(|label| label.as_str().to_owned());
+        }
         Ok(transactions)
     }
 
@@ -1205,15 +1454,49 @@ impl<B: storage::Backend> Wallet<B> {
         })
     }
 
+    /// Get a receive address that has never received any funds
     pub fn get_new_address(
         &mut self,
         account_index: U31,
     ) -> WalletResult<(ChildNumber, Address<Destination>)> {
         self.for_account_rw(account_index, |account, db_tx| {
-            account.get_new_address(db_tx, KeyPurpose::ReceiveFunds)
+            account.get_unused_address(db_tx, KeyPurpose::ReceiveFunds)
         })
     }
 
+    /// Get the last issued receive address, even if it has already received funds
+    pub fn get_reusable_address(
+        &mut self,
+        account_index: U31,
+    ) -> WalletResult<(ChildNumber, Address<Destination>)> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.get_reusable_address(db_tx)
+        })
+    }
+
+    /// Return true if the destination belongs to any of the wallet's accounts and has already
+    /// received funds
+    pub fn is_used_own_destination(&self, destination: &Destination) -> bool {
+        self.accounts.values().any(|account| account.is_destination_used(destination))
+    }
+
+    /// Return the addresses, out of the given ones, that belong to the wallet and have already
+    /// received funds, i.e. paying them again would reuse an address
+    pub fn find_used_own_addresses<'a>(
+        &self,
+        addresses: impl IntoIterator<Item = &'a Address<Destination>>,
+    ) -> Vec<Address<Destination>> {
+        let mut used_addresses: Vec<Address<Destination>> = Vec::new();
+        for address in addresses {
+            if self.is_used_own_destination(address.as_object())
+                && !used_addresses.contains(address)
+            {
+                used_addresses.push(address.clone());
+            }
+        }
+        used_addresses
+    }
+
     pub fn get_vrf_key(
         &mut self,
         account_index: U31,
@@ -1257,6 +1540,15 @@ impl<B: storage::Backend> Wallet<B> {
         account.get_transaction(transaction_id)
     }
 
+    pub fn get_transaction_info(
+        &self,
+        account_index: U31,
+        transaction_id: Id<Transaction>,
+    ) -> WalletResult<TransactionInfo> {
+        let account = self.get_account(account_index)?;
+        account.get_transaction_info(transaction_id)
+    }
+
     pub fn get_transactions_to_be_broadcast(&self) -> WalletResult<Vec<SignedTransaction>> {
         self.db
             .transaction_ro()?
@@ -1272,6 +1564,24 @@ impl<B: storage::Backend> Wallet<B> {
         Ok(account.get_all_issued_addresses())
     }
 
+    pub fn get_all_issued_addresses_with_usage(
+        &self,
+        account_index: U31,
+    ) -> WalletResult<BTreeMap<ChildNumber, (Address<Destination>, bool)>> {
+        let account = self.get_account(account_index)?;
+        Ok(account.get_all_issued_addresses_with_usage(KeyPurpose::ReceiveFunds))
+    }
+
+    /// Get the issued change addresses along with whether they have been used.
+    /// Change addresses are issued by the wallet itself when a transaction sends change back.
+    pub fn get_all_issued_change_addresses_with_usage(
+        &self,
+        account_index: U31,
+    ) -> WalletResult<BTreeMap<ChildNumber, (Address<Destination>, bool)>> {
+        let account = self.get_account(account_index)?;
+        Ok(account.get_all_issued_addresses_with_usage(KeyPurpose::Change))
+    }
+
     pub fn get_all_standalone_addresses(
         &self,
         account_index: U31,
@@ -1284,11 +1594,7 @@ impl<B: storage::Backend> Wallet<B> {
         &self,
         account_index: U31,
         address: Destination,
-    ) -> WalletResult<(
-        Destination,
-        BTreeMap<Currency, Amount>,
-        StandaloneAddressDetails,
-    )> {
+    ) -> WalletResult<(Destination, CurrencyAmountMap, StandaloneAddressDetails)> {
         let account = self.get_account(account_index)?;
         account.get_all_standalone_address_details(address, self.latest_median_time)
     }
@@ -1309,9 +1615,104 @@ impl<B: storage::Backend> Wallet<B> {
         Ok(account.get_legacy_vrf_public_key())
     }
 
+    /// Returns the account's extended public key serialized according to BIP32 and encoded with
+    /// base58check, along with its derivation path.
+    ///
+    /// The parent fingerprint is left zero, because the account key is derived from its parent
+    /// with hardened derivation, so the parent public key isn't available without the root key.
+    pub fn export_account_xpub(
+        &self,
+        account_index: U31,
+    ) -> WalletResult<(String, DerivationPath)> {
+        let account = self.get_account(account_index)?;
+        let xpub = account.key_chain().account_public_key();
+        Ok((
+            xpub.to_bip32_string(xpub_version(&self.chain_config), [0; 4]),
+            xpub.get_derivation_path().clone(),
+        ))
+    }
+
+    /// Returns the output descriptor of the account's receiving addresses,
+    /// e.g. `pkh(xpub.../0/*)`
+    pub fn export_account_descriptor(
+        &self,
+        account_index: U31,
+        descriptor_type: DescriptorType,
+    ) -> WalletResult<String> {
+        let (xpub, _) = self.export_account_xpub(account_index)?;
+        let receive_index = KeyPurpose::ReceiveFunds.get_deterministic_index();
+        Ok(format!("{descriptor_type}({xpub}/{receive_index}/*)"))
+    }
+
+    /// Returns the first `count` receive and change public keys of the account, signed with
+    /// the account's private key, so that a hot wallet can watch them without having the keys
+    /// to derive them.
+    pub fn export_address_set(
+        &self,
+        account_index: U31,
+        count: U31,
+    ) -> WalletResult<SignedAddressSet> {
+        let account = self.get_account(account_index)?;
+        let key_chain = account.key_chain();
+        let address_set = AddressSet {
+            genesis_block_id: self.chain_config.genesis_block_id(),
+            account_public_key: key_chain.account_public_key().clone().into_public_key(),
+            receive_keys: key_chain.derive_public_keys(KeyPurpose::ReceiveFunds, count)?,
+            change_keys: key_chain.derive_public_keys(KeyPurpose::Change, count)?,
+        };
+
+        let db_tx = self.db.transaction_ro_unlocked()?;
+        let account_private_key = key_chain.account_private_key(&db_tx)?;
+        Ok(SignedAddressSet::new(
+            address_set,
+            &account_private_key,
+            make_true_rng(),
+        )?)
+    }
+
+    /// Watches the addresses from an address set exported by a cold wallet with the account.
+    ///
+    /// The account can't derive any addresses beyond the imported ones, so more addresses should
+    /// be exported when the imported ones are running out.
+    pub fn import_address_set(
+        &mut self,
+        account_index: U31,
+        signed_address_set: SignedAddressSet,
+    ) -> WalletResult<ImportedAddressSet> {
+        let address_set = signed_address_set.verify()?;
+        ensure!(
+            address_set.genesis_block_id == self.chain_config.genesis_block_id(),
+            WalletError::AddressSetChainMismatch
+        );
+
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.import_address_set(db_tx, &address_set)
+        })
+    }
+
     pub fn get_addresses_usage(&self, account_index: U31) -> WalletResult<&KeychainUsageState> {
         let account = self.get_account(account_index)?;
-        Ok(account.get_addresses_usage())
+        Ok(account.get_addresses_usage(KeyPurpose::ReceiveFunds))
+    }
+
+    pub fn get_change_addresses_usage(
+        &self,
+        account_index: U31,
+    ) -> WalletResult<&KeychainUsageState> {
+        let account = self.get_account(account_index)?;
+        Ok(account.get_addresses_usage(KeyPurpose::Change))
+    }
+
+    /// The highest index of a used change address over all the accounts
+    pub fn last_used_change_index(&self) -> Option<U31> {
+        self.accounts
+            .values()
+            .filter_map(|acc| acc.get_addresses_usage(KeyPurpose::Change).last_used())
+            .max()
+    }
+
+    pub fn lookahead_size(&self) -> WalletResult<u32> {
+        Ok(self.db.transaction_ro()?.get_lookahead_size()?)
     }
 
     /// Creates a transaction to send funds to specified addresses.
@@ -1334,11 +1735,13 @@ impl<B: storage::Backend> Wallet<B> {
     /// # Returns
     ///
     /// A `WalletResult` containing the signed transaction if successful, or an error indicating the reason for failure.
+    #[allow(clippy::too_many_arguments)]
     pub fn create_transaction_to_addresses(
         &mut self,
         account_index: U31,
         outputs: impl IntoIterator<Item = TxOutput>,
         inputs: SelectedInputs,
+        selection_algo: Option<CoinSelectionAlgo>,
         change_addresses: BTreeMap<Currency, Address<Destination>>,
         current_fee_rate: FeeRate,
         consolidate_fee_rate: FeeRate,
@@ -1350,6 +1753,7 @@ impl<B: storage::Backend> Wallet<B> {
                 db_tx,
                 request,
                 inputs,
+                selection_algo,
                 change_addresses,
                 latest_median_time,
                 CurrentFeeRate {
@@ -1370,7 +1774,7 @@ impl<B: storage::Backend> Wallet<B> {
         change_addresses: BTreeMap<Currency, Address<Destination>>,
         current_fee_rate: FeeRate,
         consolidate_fee_rate: FeeRate,
-    ) -> WalletResult<(PartiallySignedTransaction, BTreeMap<Currency, Amount>)> {
+    ) -> WalletResult<(PartiallySignedTransaction, CurrencyAmountMap)> {
         let request = SendRequest::new().with_outputs(outputs);
         let latest_median_time = self.latest_median_time;
         self.for_account_rw(account_index, |account, db_tx| {
@@ -1628,6 +2032,7 @@ impl<B: storage::Backend> Wallet<B> {
             account_index,
             outputs,
             SelectedInputs::Utxos(vec![]),
+            None,
             BTreeMap::new(),
             current_fee_rate,
             consolidate_fee_rate,
@@ -1643,6 +2048,40 @@ impl<B: storage::Backend> Wallet<B> {
         Ok((delegation_id, tx))
     }
 
+    /// Create a transaction that creates a new delegation to the given pool and delegates the
+    /// given amount to it, returning the id of the new delegation
+    pub fn create_delegation_and_stake(
+        &mut self,
+        account_index: U31,
+        pool_id: PoolId,
+        amount: Amount,
+        current_fee_rate: FeeRate,
+        consolidate_fee_rate: FeeRate,
+    ) -> WalletResult<(DelegationId, SignedTransaction)> {
+        let latest_median_time = self.latest_median_time;
+        let tx = self.for_account_rw_unlocked_and_check_tx(account_index, |account, db_tx| {
+            account.create_delegation_and_stake_tx(
+                db_tx,
+                pool_id,
+                amount,
+                latest_median_time,
+                CurrentFeeRate {
+                    current_fee_rate,
+                    consolidate_fee_rate,
+                },
+            )
+        })?;
+        let input0_outpoint = tx
+            .transaction()
+            .inputs()
+            .first()
+            .ok_or(WalletError::NoUtxos)?
+            .utxo_outpoint()
+            .ok_or(WalletError::NoUtxos)?;
+        let delegation_id = make_delegation_id(input0_outpoint);
+        Ok((delegation_id, tx))
+    }
+
     pub fn issue_new_token(
         &mut self,
         account_index: U31,
@@ -1656,6 +2095,7 @@ impl<B: storage::Backend> Wallet<B> {
             account_index,
             outputs,
             SelectedInputs::Utxos(vec![]),
+            None,
             BTreeMap::new(),
             current_fee_rate,
             consolidate_fee_rate,
@@ -1741,6 +2181,32 @@ impl<B: storage::Backend> Wallet<B> {
         )
     }
 
+    /// Create a transaction that decommissions the pool and returns the pledge together with the
+    /// staking proceeds to the pool's decommission destination, which must be owned by the account
+    pub fn create_decommission_pool_transaction(
+        &mut self,
+        account_index: U31,
+        pool_id: PoolId,
+        pool_balance: Amount,
+        current_fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        self.for_account_rw_unlocked_and_check_tx_custom_error(
+            account_index,
+            |account, db_tx| {
+                let decommission_destination =
+                    account.get_pool_decommission_destination(pool_id, db_tx)?;
+                account.decommission_stake_pool(
+                    db_tx,
+                    pool_id,
+                    pool_balance,
+                    Some(decommission_destination),
+                    current_fee_rate,
+                )
+            },
+            |_err| WalletError::PartiallySignedTransactionInDecommissionCommand,
+        )
+    }
+
     pub fn decommission_stake_pool_request(
         &mut self,
         account_index: U31,
@@ -1810,6 +2276,41 @@ impl<B: storage::Backend> Wallet<B> {
                     account.tx_to_partially_signed_tx(tx, latest_median_time)?
                 }
             };
+            // The signed transaction could be broadcast bypassing the spending limits
+            ensure!(
+                db_tx.get_spending_policy(&account.get_account_id())?.is_none(),
+                WalletError::RawSigningForbiddenBySpendingPolicy
+            );
+
+            let signer = SoftwareSigner::new(db_tx, Arc::new(chain_config.clone()), account_index);
+
+            let res = signer.sign_tx(ptx, account.key_chain())?;
+            Ok(res)
+        })
+    }
+
+    /// Sign the inputs of a raw transaction that belong to the account, the rest of the inputs are
+    /// left for their owners to sign. Fails if none of the inputs belong to the account.
+    pub fn sign_raw_transaction_own_inputs(
+        &mut self,
+        account_index: U31,
+        tx: Transaction,
+    ) -> WalletResult<(
+        PartiallySignedTransaction,
+        Vec<SignatureStatus>,
+        Vec<SignatureStatus>,
+    )> {
+        let latest_median_time = self.latest_median_time;
+        self.for_account_rw_unlocked(account_index, |account, db_tx, chain_config| {
+            let ptx =
+                account.tx_to_partially_signed_tx_skipping_foreign_utxos(tx, latest_median_time)?;
+            ensure!(account.has_own_inputs(&ptx), WalletError::NoOwnedInputs);
+            // The signed transaction could be broadcast bypassing the spending limits
+            ensure!(
+                db_tx.get_spending_policy(&account.get_account_id())?.is_none(),
+                WalletError::RawSigningForbiddenBySpendingPolicy
+            );
+
             let signer = SoftwareSigner::new(db_tx, Arc::new(chain_config.clone()), account_index);
 
             let res = signer.sign_tx(ptx, account.key_chain())?;
@@ -1830,6 +2331,42 @@ impl<B: storage::Backend> Wallet<B> {
         })
     }
 
+    /// Returns a proof of reserves for the confirmed transfer utxos of the account; every utxo
+    /// is signed over its outpoint, the message and the id of the account's best block.
+    ///
+    /// Multisig utxos are signed with the keys of the account only, so their signatures
+    /// may be partial, and the utxos without any keys in the wallet are skipped.
+    pub fn generate_proof_of_reserves(
+        &self,
+        account_index: U31,
+        message: Vec<u8>,
+    ) -> WalletResult<ProofOfReserves> {
+        let account = self.get_account(account_index)?;
+        let (block_id, _) = account.best_block();
+
+        let db_tx = self.db.transaction_ro_unlocked()?;
+        let signer = SoftwareSigner::new(&db_tx, self.chain_config.clone(), account_index);
+
+        let mut entries = Vec::new();
+        for (outpoint, destination, source_block_height) in
+            account.get_reserve_utxos(self.latest_median_time)
+        {
+            let proof_message = produce_reserve_proof_message(&outpoint, &message, &block_id);
+            match signer.sign_challenge(proof_message, destination, account.key_chain()) {
+                Ok(signature) => entries.push(ReserveProofEntry::new(
+                    outpoint,
+                    source_block_height,
+                    signature,
+                )),
+                Err(SignerError::DestinationNotFromThisWallet) => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        ensure!(!entries.is_empty(), WalletError::NoUtxos);
+
+        Ok(ProofOfReserves::new(message, block_id, entries))
+    }
+
     pub fn get_pos_gen_block_data(
         &self,
         account_index: U31,
@@ -2012,6 +2549,248 @@ impl<B: storage::Backend> Wallet<B> {
         })
     }
 
+    /// Save an unconfirmed transaction created by a request with an idempotency key.
+    ///
+    /// The key is recorded in the same DB transaction as the wallet transaction, so a retried
+    /// request either finds both or neither of them.
+    pub fn add_account_unconfirmed_tx_with_idempotency_key(
+        &mut self,
+        account_index: U31,
+        transaction: SignedTransaction,
+        idempotency_key: &IdempotencyKey,
+        request_hash: H256,
+        now: BlockTimestamp,
+        wallet_events: &impl WalletEvents,
+    ) -> WalletResult<()> {
+        let record = IdempotencyRecord::new(request_hash, transaction.transaction().get_id(), now);
+        self.for_account_rw(account_index, |acc, db_tx| {
+            db_tx.set_idempotency_record(idempotency_key, &record)?;
+            acc.scan_new_inactive_transactions(&[transaction], db_tx, wallet_events)
+        })
+    }
+
+    /// Find the transaction created by an earlier request with the same idempotency key.
+    ///
+    /// Fails if the key was used for a request with different parameters.
+    pub fn find_idempotent_transaction(
+        &self,
+        idempotency_key: &IdempotencyKey,
+        request_hash: &H256,
+    ) -> WalletResult<Option<Id<Transaction>>> {
+        let record = self.db.transaction_ro()?.get_idempotency_record(idempotency_key)?;
+        record
+            .map(|record| {
+                ensure!(
+                    record.request_hash() == request_hash,
+                    WalletError::IdempotencyKeyReused(idempotency_key.as_str().to_owned())
+                );
+                Ok(record.tx_id())
+            })
+            .transpose()
+    }
+
+    /// Delete the idempotency keys recorded before the given time, so that they can be reused
+    pub fn purge_idempotency_keys(&mut self, created_before: BlockTimestamp) -> WalletResult<()> {
+        let mut db_tx = self.db.transaction_rw(None)?;
+        for (key, record) in db_tx.get_idempotency_records()? {
+            if record.created_at() < created_before {
+                db_tx.del_idempotency_record(&key)?;
+            }
+        }
+        db_tx.commit()?;
+        Ok(())
+    }
+
+    /// Set or remove (if `label` is None) the label of a transaction of the account.
+    ///
+    /// Labels are kept when the wallet is rescanned.
+    pub fn set_transaction_label(
+        &mut self,
+        account_index: U31,
+        tx_id: Id<Transaction>,
+        label: Option<Label>,
+    ) -> WalletResult<()> {
+        self.get_transaction(account_index, tx_id)?;
+        let mut db_tx = self.db.transaction_rw(None)?;
+        match label {
+            Some(label) => db_tx.set_transaction_label(&tx_id, &label)?,
+            None => db_tx.del_transaction_label(&tx_id)?,
+        }
+        db_tx.commit()?;
+        Ok(())
+    }
+
+    /// Set or remove (if `label` is None) the label of an address.
+    ///
+    /// The address doesn't have to belong to the wallet, so that the recipients can be labeled too.
+    pub fn set_address_label(
+        &mut self,
+        address: &Destination,
+        label: Option<Label>,
+    ) -> WalletResult<()> {
+        let mut db_tx = self.db.transaction_rw(None)?;
+        match label {
+            Some(label) => db_tx.set_address_label(address, &label)?,
+            None => db_tx.del_address_label(address)?,
+        }
+        db_tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_address_labels(&self) -> WalletResult<BTreeMap<Destination, Label>> {
+        Ok(self.db.transaction_ro()?.get_address_labels()?)
+    }
+
+    /// Find the transactions of the account and the addresses whose label, id or address
+    /// contains the given text
+    pub fn search(&self, account_index: U31, text: &str) -> WalletResult<Vec<SearchMatch>> {
+        let account = self.get_account(account_index)?;
+        let db_tx = self.db.transaction_ro()?;
+        let tx_labels = db_tx.get_transaction_labels()?;
+        let address_labels = db_tx.get_address_labels()?;
+
+        let tx_ids = account
+            .mainchain_transactions(None, usize::MAX)
+            .into_iter()
+            .map(|tx| tx.id)
+            .chain(account.pending_transactions().iter().map(WithId::id))
+            .collect::<BTreeSet<_>>();
+        let transactions = tx_ids.into_iter().filter_map(|id| {
+            let label = tx_labels.get(&id);
+            let found = matches_search_text(&format!("{id:x}"), text)
+                || label.is_some_and(|label| matches_search_text(label.as_str(), text));
+            found.then(|| SearchMatch::Transaction {
+                id,
+                label: label.cloned(),
+            })
+        });
+
+        let addresses = account
+            .get_all_issued_addresses()
+            .into_values()
+            .map(Address::into_object)
+            .chain(address_labels.keys().cloned())
+            .collect::<BTreeSet<_>>();
+        let mut result = transactions.collect::<Vec<_>>();
+        for address in addresses {
+            let label = address_labels.get(&address);
+            let address_str = Address::new(&self.chain_config, address.clone())?;
+            if matches_search_text(address_str.as_str(), text)
+                || label.is_some_and(|label| matches_search_text(label.as_str(), text))
+            {
+                result.push(SearchMatch::Address {
+                    address,
+                    label: label.cloned(),
+                });
+            }
+        }
+
+        Ok(result)
+    }
+
+    pub fn get_spending_policy(&self, account_index: U31) -> WalletResult<SpendingPolicy> {
+        let account_id = self.get_account(account_index)?.get_account_id();
+        let policy = self.db.transaction_ro()?.get_spending_policy(&account_id)?;
+        Ok(policy.unwrap_or_default())
+    }
+
+    /// Set the spending policy of the account, an empty policy removes all the limits
+    pub fn set_spending_policy(
+        &mut self,
+        account_index: U31,
+        policy: SpendingPolicy,
+    ) -> WalletResult<()> {
+        let account_id = self.get_account(account_index)?.get_account_id();
+        let mut db_tx = self.db.transaction_rw(None)?;
+        if policy.is_empty() {
+            db_tx.del_spending_policy(&account_id)?;
+        } else {
+            db_tx.set_spending_policy(&account_id, &policy)?;
+        }
+        db_tx.commit()?;
+        Ok(())
+    }
+
+    /// The number of confirmations after which the received funds are considered final
+    pub fn get_confirmation_threshold(&self) -> WalletResult<u64> {
+        let threshold = self.db.transaction_ro()?.get_confirmation_threshold()?;
+        Ok(threshold.unwrap_or(DEFAULT_CONFIRMATION_THRESHOLD))
+    }
+
+    /// Set the number of confirmations after which the received funds are considered final.
+    ///
+    /// Once set, only the outputs with at least this number of confirmations are spent,
+    /// unless selected explicitly.
+    pub fn set_confirmation_threshold(&mut self, threshold: u64) -> WalletResult<()> {
+        ensure!(threshold > 0, WalletError::ZeroConfirmationThreshold);
+        let mut db_tx = self.db.transaction_rw(None)?;
+        db_tx.set_confirmation_threshold(threshold)?;
+        db_tx.commit()?;
+        Ok(())
+    }
+
+    /// The coins sent out of the account within the daily cap period before `now`.
+    ///
+    /// The outflows recorded before the period are not needed anymore and are deleted.
+    fn recent_outflow(
+        db_tx: &mut impl WalletStorageWriteLocked,
+        account_id: &AccountId,
+        now: BlockTimestamp,
+    ) -> WalletResult<Amount> {
+        let period_start = now.as_int_seconds().saturating_sub(DAILY_CAP_PERIOD_SECS);
+        let mut total = Amount::ZERO;
+        for (id, amount) in db_tx.get_account_outflows(account_id)? {
+            let (time, _tx_id) = id.item_id();
+            if time.as_int_seconds() <= period_start {
+                db_tx.del_account_outflow(&id)?;
+            } else {
+                total = (total + amount).ok_or(WalletError::OutputAmountOverflow)?;
+            }
+        }
+        Ok(total)
+    }
+
+    /// The coins sent out of the account within the daily cap period before `now`
+    pub fn get_recent_outflow(
+        &mut self,
+        account_index: U31,
+        now: BlockTimestamp,
+    ) -> WalletResult<Amount> {
+        let account_id = self.get_account(account_index)?.get_account_id();
+        let mut db_tx = self.db.transaction_rw(None)?;
+        let total = Self::recent_outflow(&mut db_tx, &account_id, now)?;
+        db_tx.commit()?;
+        Ok(total)
+    }
+
+    /// Check a transaction about to be broadcast against the spending policy of the account
+    /// and record the coins it sends, so that they count towards the daily cap
+    pub fn check_and_record_outflow(
+        &mut self,
+        account_index: U31,
+        tx: &SignedTransaction,
+        now: BlockTimestamp,
+    ) -> WalletResult<()> {
+        let account = self.get_account(account_index)?;
+        let account_id = account.get_account_id();
+        let mut db_tx = self.db.transaction_rw(None)?;
+        let policy = match db_tx.get_spending_policy(&account_id)? {
+            Some(policy) => policy,
+            None => return Ok(()),
+        };
+
+        let outflow = account.tx_outflow(tx.transaction())?;
+        let sent_in_period = Self::recent_outflow(&mut db_tx, &account_id, now)?;
+        policy.check(&outflow, sent_in_period)?;
+
+        if outflow.coins != Amount::ZERO {
+            let id = AccountOutflowId::new(account_id, (now, tx.transaction().get_id()));
+            db_tx.set_account_outflow(&id, outflow.coins)?;
+        }
+        db_tx.commit()?;
+        Ok(())
+    }
+
     pub fn set_median_time(&mut self, median_time: BlockTimestamp) -> WalletResult<()> {
         self.latest_median_time = median_time;
         let mut db_tx = self.db.transaction_rw(None)?;
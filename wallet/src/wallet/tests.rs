@@ -15,13 +15,13 @@
 
 use crate::{
     account::currency_grouper::Currency,
-    key_chain::{make_account_path, LOOKAHEAD_SIZE},
+    key_chain::{make_account_path, xpub_version, LOOKAHEAD_SIZE},
     send_request::{make_address_output, make_create_delegation_output},
     wallet_events::WalletEventsNoOp,
     DefaultWallet,
 };
 use serialization::hex::HexEncode;
-use serialization::Encode;
+use serialization::{DecodeAll, Encode};
 use std::{
     collections::BTreeSet,
     num::{NonZeroU8, NonZeroUsize},
@@ -41,8 +41,9 @@ use common::{
     },
     primitives::{per_thousand::PerThousand, Idable, H256},
 };
-use crypto::key::hdkd::{
-    child_number::ChildNumber, derivable::Derivable, derivation_path::DerivationPath,
+use crypto::key::{
+    extended::ExtendedPublicKey,
+    hdkd::{child_number::ChildNumber, derivable::Derivable, derivation_path::DerivationPath},
 };
 use itertools::Itertools;
 use randomness::{CryptoRng, Rng, SliceRandom};
@@ -176,9 +177,7 @@ fn get_coin_balance_for_acc(wallet: &DefaultWallet, account: U31) -> Amount {
     let coin_balance = wallet
         .get_balance(account, UtxoState::Confirmed.into(), WithLocked::Unlocked)
         .unwrap()
-        .get(&Currency::Coin)
-        .copied()
-        .unwrap_or(Amount::ZERO);
+        .coins();
     coin_balance
 }
 
@@ -190,9 +189,7 @@ fn get_coin_balance_with_inactive(wallet: &DefaultWallet) -> Amount {
             WithLocked::Unlocked,
         )
         .unwrap()
-        .get(&Currency::Coin)
-        .copied()
-        .unwrap_or(Amount::ZERO);
+        .coins();
     coin_balance
 }
 
@@ -201,24 +198,15 @@ fn get_coin_balance(wallet: &DefaultWallet) -> Amount {
 }
 
 fn get_currency_balances(wallet: &DefaultWallet) -> (Amount, Vec<(TokenId, Amount)>) {
-    let mut currency_balances = wallet
+    let (coins, token_balances) = wallet
         .get_balance(
             DEFAULT_ACCOUNT_INDEX,
             UtxoState::Confirmed.into(),
             WithLocked::Unlocked,
         )
-        .unwrap();
-
-    let coins = currency_balances.remove(&Currency::Coin).unwrap_or(Amount::ZERO);
-
-    let token_balances = currency_balances
-        .into_iter()
-        .filter_map(|(c, amount)| match c {
-            Currency::Coin => None,
-            Currency::Token(token_id) => Some((token_id, amount)),
-        })
-        .collect_vec();
-    (coins, token_balances)
+        .unwrap()
+        .into_coins_and_tokens();
+    (coins, token_balances.into_iter().collect_vec())
 }
 
 #[track_caller]
@@ -403,6 +391,8 @@ fn wallet_migration_to_v2(#[case] seed: Seed) {
     // remove config
     let values = raw_db.get_mut(&DbMapId::new::<schema::DBValue, _>()).unwrap();
     values.remove(stringify!(StoreChainInfo).as_bytes());
+    // the software version is only recorded since v9
+    assert!(values.remove(stringify!(SoftwareVersion).as_bytes()).is_some());
     // insert some old txs that can't be deserialized to the new tx structure
     let txs = raw_db.get_mut(&DbMapId::new::<schema::DBTxs, _>()).unwrap();
     for idx in 0..10 {
@@ -429,10 +419,13 @@ fn wallet_migration_to_v2(#[case] seed: Seed) {
     .unwrap();
 
     // Migration has been done and new version is v2
+    let db_tx = wallet.db.transaction_ro().unwrap();
+    assert_eq!(db_tx.get_storage_version().unwrap(), CURRENT_WALLET_VERSION);
     assert_eq!(
-        wallet.db.transaction_ro().unwrap().get_storage_version().unwrap(),
-        CURRENT_WALLET_VERSION
+        db_tx.get_software_version().unwrap().as_deref(),
+        Some(WALLET_SOFTWARE_VERSION)
     );
+    db_tx.close();
 
     // accounts have been reset back to genesis to rescan the blockchain
     assert_eq!(
@@ -442,6 +435,105 @@ fn wallet_migration_to_v2(#[case] seed: Seed) {
     verify_wallet_balance(&chain_config, &wallet, genesis_amount);
 }
 
+#[test]
+fn wallet_migration_to_v9_with_backup() {
+    let chain_config = Arc::new(create_regtest());
+    let wallet = create_wallet(chain_config.clone());
+
+    // Make a v8 wallet file, which doesn't have the software version
+    let mut raw_db = wallet.db.dump_raw().unwrap();
+    let values = raw_db.get_mut(&DbMapId::new::<schema::DBValue, _>()).unwrap();
+    values.insert(
+        stringify!(StoreVersion).as_bytes().to_vec(),
+        WALLET_VERSION_V8.encode(),
+    );
+    assert!(values.remove(stringify!(SoftwareVersion).as_bytes()).is_some());
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let file_path = dir.path().join("wallet.sqlite");
+    drop(Store::new_from_dump(DefaultBackend::new(&file_path), raw_db).unwrap());
+    let original_file = std::fs::read(&file_path).unwrap();
+
+    let backup_file_path = std::sync::Mutex::new(None);
+    let wallet = Wallet::load_wallet(
+        Arc::clone(&chain_config),
+        open_or_create_wallet_file(&file_path).unwrap(),
+        None,
+        |version| {
+            assert_eq!(version, WALLET_VERSION_V8);
+            let path = make_wallet_file_backup(&file_path, version)?;
+            assert!(backup_file_path.lock().unwrap().replace(path).is_none());
+            Ok(())
+        },
+        WalletType::Hot,
+        false,
+    )
+    .unwrap();
+
+    let db_tx = wallet.db.transaction_ro().unwrap();
+    assert_eq!(db_tx.get_storage_version().unwrap(), CURRENT_WALLET_VERSION);
+    assert_eq!(
+        db_tx.get_software_version().unwrap().as_deref(),
+        Some(WALLET_SOFTWARE_VERSION)
+    );
+    db_tx.close();
+
+    // The backup is an exact copy of the file before the migration
+    let backup_file_path = backup_file_path.into_inner().unwrap().unwrap();
+    assert_eq!(backup_file_path, dir.path().join("wallet.sqlite_backup_v8"));
+    assert_eq!(std::fs::read(backup_file_path).unwrap(), original_file);
+}
+
+#[test]
+fn wallet_from_newer_version_is_refused() {
+    let chain_config = Arc::new(create_regtest());
+    let newer_version = CURRENT_WALLET_VERSION + 1;
+
+    let wallet = create_wallet(chain_config.clone());
+    let mut db_tx = wallet.db.transaction_rw(None).unwrap();
+    db_tx.set_storage_version(newer_version).unwrap();
+    db_tx.set_software_version("99.1.0").unwrap();
+    db_tx.commit().unwrap();
+
+    let err = Wallet::load_wallet(
+        Arc::clone(&chain_config),
+        wallet.db.clone(),
+        None,
+        |_| panic!("a newer wallet must not be migrated"),
+        WalletType::Hot,
+        false,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        WalletError::WalletCreatedByNewerSoftware(newer_version, "99.1.0".to_owned())
+    );
+    assert!(err.to_string().contains("created by wallet v99.1.0, please upgrade"));
+
+    // The DB is left untouched
+    assert_eq!(
+        wallet.db.transaction_ro().unwrap().get_storage_version().unwrap(),
+        newer_version
+    );
+
+    // Without the software version, the wallet is still refused
+    let mut raw_db = wallet.db.dump_raw().unwrap();
+    let values = raw_db.get_mut(&DbMapId::new::<schema::DBValue, _>()).unwrap();
+    values.remove(stringify!(SoftwareVersion).as_bytes());
+    let db = Store::new_from_dump(DefaultBackend::new_in_memory(), raw_db).unwrap();
+
+    let err = Wallet::load_wallet(
+        chain_config,
+        db,
+        None,
+        |_| panic!("a newer wallet must not be migrated"),
+        WalletType::Hot,
+        false,
+    )
+    .unwrap_err();
+    assert_eq!(err, WalletError::UnsupportedWalletVersion(newer_version));
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -601,6 +693,212 @@ fn wallet_seed_phrase_check_address() {
     assert_eq!(expected_pk2, pk.hex_encode().strip_prefix("00").unwrap());
 }
 
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn wallet_fingerprint(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+    let genesis_block_id = chain_config.genesis_block_id();
+
+    let create_wallet = |passphrase: Option<&str>, store_seed_phrase| {
+        let db = create_wallet_in_memory().unwrap();
+        Wallet::create_new_wallet(
+            Arc::clone(&chain_config),
+            db,
+            MNEMONIC,
+            passphrase,
+            store_seed_phrase,
+            (BlockHeight::new(0), genesis_block_id),
+            WalletType::Hot,
+        )
+        .unwrap()
+    };
+
+    let mut wallet = create_wallet(None, StoreSeedPhrase::Store);
+    let (fingerprint, passphrase_used) = wallet.fingerprint().unwrap();
+    assert_eq!(passphrase_used, Some(false));
+    let address = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    // An empty passphrase is the same as no passphrase
+    let mut empty_passphrase_wallet = create_wallet(Some(""), StoreSeedPhrase::Store);
+    assert_eq!(
+        empty_passphrase_wallet.fingerprint().unwrap(),
+        (fingerprint, Some(false))
+    );
+    assert_eq!(
+        empty_passphrase_wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap(),
+        address
+    );
+
+    let passphrase = gen_random_password(&mut rng);
+    let mut passphrase_wallet = create_wallet(Some(&passphrase), StoreSeedPhrase::Store);
+    let (passphrase_fingerprint, passphrase_used) = passphrase_wallet.fingerprint().unwrap();
+    assert_eq!(passphrase_used, Some(true));
+    assert_ne!(passphrase_fingerprint, fingerprint);
+    let passphrase_address = passphrase_wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_ne!(passphrase_address, address);
+
+    // Restoring with the same passphrase reproduces the fingerprint and the addresses
+    let mut restored_wallet = create_wallet(Some(&passphrase), StoreSeedPhrase::DoNotStore);
+    assert_eq!(
+        restored_wallet.fingerprint().unwrap(),
+        (passphrase_fingerprint, Some(true))
+    );
+    assert_eq!(
+        restored_wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap(),
+        passphrase_address
+    );
+
+    // The fingerprint doesn't need the private keys
+    let password = gen_random_password(&mut rng);
+    restored_wallet.encrypt_wallet(&Some(password)).unwrap();
+    restored_wallet.lock_wallet().unwrap();
+    assert_eq!(
+        restored_wallet.fingerprint().unwrap(),
+        (passphrase_fingerprint, Some(true))
+    );
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn export_import_address_set(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+    let mut cold_wallet = create_wallet(chain_config.clone());
+    let mut hot_wallet = create_wallet_with_mnemonic(chain_config.clone(), MNEMONIC2);
+    let count = U31::from_u32(6).unwrap();
+
+    // Signing the address set requires the private keys
+    let password = gen_random_password(&mut rng);
+    cold_wallet.encrypt_wallet(&Some(password.clone())).unwrap();
+    cold_wallet.lock_wallet().unwrap();
+    assert!(cold_wallet.export_address_set(DEFAULT_ACCOUNT_INDEX, count).is_err());
+    cold_wallet.unlock_wallet(&password).unwrap();
+
+    let address_set = cold_wallet.export_address_set(DEFAULT_ACCOUNT_INDEX, count).unwrap();
+    let file_contents = address_set.encode();
+
+    // A tampered file is rejected
+    let mut tampered_contents = file_contents.clone();
+    *tampered_contents.last_mut().unwrap() ^= 1;
+    let tampered_address_set = SignedAddressSet::decode_all(&mut &tampered_contents[..]).unwrap();
+    assert_eq!(
+        hot_wallet.import_address_set(DEFAULT_ACCOUNT_INDEX, tampered_address_set),
+        Err(WalletError::AddressSetError(
+            AddressSetError::InvalidSignature
+        ))
+    );
+
+    // So is one from another chain
+    let mainnet_wallet = create_wallet(Arc::new(create_mainnet()));
+    let mainnet_address_set =
+        mainnet_wallet.export_address_set(DEFAULT_ACCOUNT_INDEX, count).unwrap();
+    assert_eq!(
+        hot_wallet.import_address_set(DEFAULT_ACCOUNT_INDEX, mainnet_address_set),
+        Err(WalletError::AddressSetChainMismatch)
+    );
+
+    let address_set = SignedAddressSet::decode_all(&mut &file_contents[..]).unwrap();
+    let imported = hot_wallet.import_address_set(DEFAULT_ACCOUNT_INDEX, address_set).unwrap();
+    assert_eq!(
+        imported.account_fingerprint,
+        WalletFingerprint::from_public_key(
+            &cold_wallet
+                .get_account(DEFAULT_ACCOUNT_INDEX)
+                .unwrap()
+                .key_chain()
+                .account_public_key()
+                .clone()
+                .into_public_key()
+        )
+    );
+    assert_eq!(imported.added_addresses, 12);
+    assert_eq!(imported.unused_receive_addresses, 6);
+    assert!(!imported.is_low_on_unused_addresses());
+
+    // The funds sent to the first receive address of the cold wallet are detected
+    let reward = Amount::from_atoms(rng.gen_range(1..10000));
+    let (address, block) = create_block(&chain_config, &mut cold_wallet, vec![], reward, 0);
+    scan_wallet(&mut hot_wallet, BlockHeight::new(0), vec![block]);
+    let (_, balances, _) = hot_wallet
+        .get_all_standalone_address_details(DEFAULT_ACCOUNT_INDEX, address.into_object())
+        .unwrap();
+    assert_eq!(balances.coins(), reward);
+
+    // Importing again only reports the unused addresses
+    let address_set = SignedAddressSet::decode_all(&mut &file_contents[..]).unwrap();
+    let imported = hot_wallet.import_address_set(DEFAULT_ACCOUNT_INDEX, address_set).unwrap();
+    assert_eq!(imported.added_addresses, 0);
+    assert_eq!(imported.unused_receive_addresses, 5);
+
+    let address_set = cold_wallet
+        .export_address_set(DEFAULT_ACCOUNT_INDEX, U31::from_u32(4).unwrap())
+        .unwrap();
+    let imported = hot_wallet.import_address_set(DEFAULT_ACCOUNT_INDEX, address_set).unwrap();
+    assert_eq!(imported.added_addresses, 0);
+    assert_eq!(imported.unused_receive_addresses, 3);
+    assert!(imported.is_low_on_unused_addresses());
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn export_xpub(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+    let mut wallet = create_wallet(chain_config.clone());
+
+    // Only the public keys are needed
+    let password = gen_random_password(&mut rng);
+    wallet.encrypt_wallet(&Some(password)).unwrap();
+    wallet.lock_wallet().unwrap();
+
+    let (xpub, derivation_path) = wallet.export_account_xpub(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert!(xpub.starts_with("xpub"));
+    assert_eq!(
+        derivation_path,
+        make_account_path(&chain_config, DEFAULT_ACCOUNT_INDEX)
+    );
+    assert_eq!(
+        wallet
+            .export_account_descriptor(DEFAULT_ACCOUNT_INDEX, DescriptorType::PublicKeyHash)
+            .unwrap(),
+        format!("pkh({xpub}/0/*)")
+    );
+
+    // A watch-only key chain restored from the exported key produces the same addresses
+    let imported_xpub =
+        ExtendedPublicKey::from_bip32_string(&xpub, xpub_version(&chain_config), derivation_path)
+            .unwrap();
+    let receiving_key = imported_xpub
+        .derive_child(KeyPurpose::ReceiveFunds.get_deterministic_index())
+        .unwrap();
+    for index in 0..LOOKAHEAD_SIZE {
+        let (child_number, address) = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+        assert_eq!(
+            child_number,
+            ChildNumber::from_normal(U31::from_u32(index).unwrap())
+        );
+
+        let public_key =
+            receiving_key.clone().derive_child(child_number).unwrap().into_public_key();
+        let expected_address = Address::new(
+            &chain_config,
+            Destination::PublicKeyHash(PublicKeyHash::from(&public_key)),
+        )
+        .unwrap();
+        assert_eq!(address, expected_address);
+    }
+
+    // The other networks use the testnet version bytes
+    let chain_config = Arc::new(create_regtest());
+    let wallet = create_wallet(chain_config.clone());
+    let (xpub, _) = wallet.export_account_xpub(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert!(xpub.starts_with("tpub"));
+}
+
 #[test]
 fn wallet_balance_genesis() {
     let chain_type = ChainType::Mainnet;
@@ -920,6 +1218,7 @@ fn wallet_accounts_creation() {
                 acc1_pk.into_object(),
             )],
             SelectedInputs::Utxos(vec![]),
+            None,
             BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -1079,6 +1378,7 @@ fn locked_wallet_cant_sign_transaction(#[case] seed: Seed) {
             DEFAULT_ACCOUNT_INDEX,
             [new_output.clone()],
             SelectedInputs::Utxos(vec![]),
+            None,
             BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -1096,6 +1396,7 @@ fn locked_wallet_cant_sign_transaction(#[case] seed: Seed) {
                 DEFAULT_ACCOUNT_INDEX,
                 [new_output],
                 SelectedInputs::Utxos(vec![]),
+                None,
                 BTreeMap::new(),
                 FeeRate::from_amount_per_kb(Amount::ZERO),
                 FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -1125,6 +1426,7 @@ fn locked_wallet_cant_sign_transaction(#[case] seed: Seed) {
                 DEFAULT_ACCOUNT_INDEX,
                 [new_output],
                 SelectedInputs::Utxos(vec![]),
+                None,
                 BTreeMap::new(),
                 FeeRate::from_amount_per_kb(Amount::ZERO),
                 FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -1152,6 +1454,7 @@ fn wallet_get_transaction(#[case] seed: Seed) {
             DEFAULT_ACCOUNT_INDEX,
             [gen_random_transfer(&mut rng, Amount::from_atoms(1))],
             SelectedInputs::Utxos(vec![]),
+            None,
             BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -1172,21 +1475,280 @@ fn wallet_get_transaction(#[case] seed: Seed) {
     assert_eq!(*found_tx.state(), TxState::Inactive(1));
     assert_eq!(found_tx.get_transaction(), tx.transaction());
 
-    // put the tx in a block and scan it as confirmed
-    let (_, block) = create_block(
-        &chain_config,
-        &mut wallet,
-        vec![tx.clone()],
-        Amount::ZERO,
-        1,
+    // put the tx in a block and scan it as confirmed
+    let (_, block) = create_block(
+        &chain_config,
+        &mut wallet,
+        vec![tx.clone()],
+        Amount::ZERO,
+        1,
+    );
+    let found_tx = wallet.get_transaction(DEFAULT_ACCOUNT_INDEX, tx_id).unwrap();
+
+    assert_eq!(
+        *found_tx.state(),
+        TxState::Confirmed(BlockHeight::new(2), block.timestamp(), 0)
+    );
+    assert_eq!(found_tx.get_transaction(), tx.transaction());
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn wallet_idempotency_keys(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let mut wallet = create_wallet(chain_config.clone());
+    let block1_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    let tx = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            [gen_random_transfer(&mut rng, Amount::from_atoms(1))],
+            SelectedInputs::Utxos(vec![]),
+            None,
+            BTreeMap::new(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+    let tx_id = tx.transaction().get_id();
+
+    let key = IdempotencyKey::new("key".to_owned()).unwrap();
+    let request_hash = H256::random_using(&mut rng);
+    assert_eq!(
+        wallet.find_idempotent_transaction(&key, &request_hash),
+        Ok(None)
+    );
+
+    let created_at = BlockTimestamp::from_int_seconds(1000);
+    wallet
+        .add_account_unconfirmed_tx_with_idempotency_key(
+            DEFAULT_ACCOUNT_INDEX,
+            tx,
+            &key,
+            request_hash,
+            created_at,
+            &WalletEventsNoOp,
+        )
+        .unwrap();
+    // The key is recorded together with the transaction
+    assert_eq!(
+        *wallet.get_transaction(DEFAULT_ACCOUNT_INDEX, tx_id).unwrap().state(),
+        TxState::Inactive(1)
+    );
+    assert_eq!(
+        wallet.find_idempotent_transaction(&key, &request_hash),
+        Ok(Some(tx_id))
+    );
+
+    // The key can't be used for another request
+    let other_request_hash = H256::random_using(&mut rng);
+    assert_eq!(
+        wallet.find_idempotent_transaction(&key, &other_request_hash),
+        Err(WalletError::IdempotencyKeyReused("key".to_owned()))
+    );
+
+    // Purging the older keys keeps this one
+    wallet.purge_idempotency_keys(created_at).unwrap();
+    assert_eq!(
+        wallet.find_idempotent_transaction(&key, &request_hash),
+        Ok(Some(tx_id))
+    );
+
+    wallet.purge_idempotency_keys(created_at.add_int_seconds(1).unwrap()).unwrap();
+    assert_eq!(
+        wallet.find_idempotent_transaction(&key, &other_request_hash),
+        Ok(None)
+    );
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn wallet_spending_policy_daily_cap(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let mut wallet = create_wallet(chain_config.clone());
+    let block1_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    let mut make_tx = |wallet: &mut DefaultWallet, amount: u128| {
+        wallet
+            .create_transaction_to_addresses(
+                DEFAULT_ACCOUNT_INDEX,
+                [gen_random_transfer(&mut rng, Amount::from_atoms(amount))],
+                SelectedInputs::Utxos(vec![]),
+                None,
+                BTreeMap::new(),
+                FeeRate::from_amount_per_kb(Amount::ZERO),
+                FeeRate::from_amount_per_kb(Amount::ZERO),
+            )
+            .unwrap()
+    };
+
+    let policy = SpendingPolicy {
+        max_per_tx: None,
+        daily_cap: Some(Amount::from_atoms(100)),
+        allowed_destinations: None,
+    };
+    wallet.set_spending_policy(DEFAULT_ACCOUNT_INDEX, policy.clone()).unwrap();
+    assert_eq!(
+        wallet.get_spending_policy(DEFAULT_ACCOUNT_INDEX),
+        Ok(policy)
+    );
+
+    // Spend the whole cap
+    let now = BlockTimestamp::from_int_seconds(1000);
+    for amount in [60, 40] {
+        let tx = make_tx(&mut wallet, amount);
+        wallet.check_and_record_outflow(DEFAULT_ACCOUNT_INDEX, &tx, now).unwrap();
+    }
+    assert_eq!(
+        wallet.get_recent_outflow(DEFAULT_ACCOUNT_INDEX, now),
+        Ok(Amount::from_atoms(100))
+    );
+
+    let tx = make_tx(&mut wallet, 1);
+    assert_eq!(
+        wallet.check_and_record_outflow(DEFAULT_ACCOUNT_INDEX, &tx, now),
+        Err(WalletError::SpendingPolicyViolation(
+            SpendingPolicyViolation::DailyCapExceeded {
+                amount: Amount::from_atoms(1),
+                remaining: Amount::ZERO,
+            }
+        ))
+    );
+
+    // The cap is available again once the period has passed
+    let later = now.add_int_seconds(DAILY_CAP_PERIOD_SECS).unwrap();
+    wallet.check_and_record_outflow(DEFAULT_ACCOUNT_INDEX, &tx, later).unwrap();
+    assert_eq!(
+        wallet.get_recent_outflow(DEFAULT_ACCOUNT_INDEX, later),
+        Ok(Amount::from_atoms(1))
+    );
+
+    // Without a policy nothing is checked or recorded
+    wallet
+        .set_spending_policy(DEFAULT_ACCOUNT_INDEX, SpendingPolicy::default())
+        .unwrap();
+    let tx = make_tx(&mut wallet, 1000);
+    wallet.check_and_record_outflow(DEFAULT_ACCOUNT_INDEX, &tx, later).unwrap();
+    assert_eq!(
+        wallet.get_spending_policy(DEFAULT_ACCOUNT_INDEX),
+        Ok(SpendingPolicy::default())
+    );
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn wallet_balance_by_confirmations(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let mut wallet = create_wallet(chain_config.clone());
+    assert_eq!(
+        wallet.get_confirmation_threshold(),
+        Ok(DEFAULT_CONFIRMATION_THRESHOLD)
+    );
+    assert_eq!(
+        wallet.set_confirmation_threshold(0),
+        Err(WalletError::ZeroConfirmationThreshold)
+    );
+    wallet.set_confirmation_threshold(3).unwrap();
+    assert_eq!(wallet.get_confirmation_threshold(), Ok(3));
+
+    let get_balances = |wallet: &DefaultWallet, threshold: Option<u64>| {
+        let balances = wallet
+            .get_balance_by_confirmations(DEFAULT_ACCOUNT_INDEX, threshold, WithLocked::Unlocked)
+            .unwrap();
+        (
+            balances.confirmed.coins(),
+            balances.pending.coins(),
+            balances.unconfirmed.coins(),
+        )
+    };
+
+    let block1_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+    assert_eq!(
+        get_balances(&wallet, None),
+        (Amount::ZERO, block1_amount, Amount::ZERO)
+    );
+    assert_eq!(
+        get_balances(&wallet, Some(1)),
+        (block1_amount, Amount::ZERO, Amount::ZERO)
+    );
+    assert_eq!(
+        wallet.get_balance_by_confirmations(DEFAULT_ACCOUNT_INDEX, Some(0), WithLocked::Unlocked),
+        Err(WalletError::ZeroConfirmationThreshold)
+    );
+
+    // The pending funds are not selected automatically
+    let make_tx = |wallet: &mut DefaultWallet, inputs: SelectedInputs| {
+        wallet.create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            [make_address_output(
+                get_address(
+                    &chain_config,
+                    MNEMONIC2,
+                    DEFAULT_ACCOUNT_INDEX,
+                    KeyPurpose::ReceiveFunds,
+                    0.try_into().unwrap(),
+                ),
+                Amount::from_atoms(1),
+            )],
+            inputs,
+            None,
+            BTreeMap::new(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+    };
+    assert!(matches!(
+        make_tx(&mut wallet, SelectedInputs::Utxos(vec![])),
+        Err(WalletError::CoinSelectionError(
+            UtxoSelectorError::NotEnoughFunds(_, _)
+        ))
+    ));
+
+    let block2_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block2_amount, 1);
+    let block3_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block3_amount, 2);
+    assert_eq!(
+        get_balances(&wallet, None),
+        (
+            block1_amount,
+            (block2_amount + block3_amount).unwrap(),
+            Amount::ZERO
+        )
     );
-    let found_tx = wallet.get_transaction(DEFAULT_ACCOUNT_INDEX, tx_id).unwrap();
 
+    // A reorg removing the last two blocks makes the first reward pending again
+    let block4_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block4_amount, 1);
     assert_eq!(
-        *found_tx.state(),
-        TxState::Confirmed(BlockHeight::new(2), block.timestamp(), 0)
+        get_balances(&wallet, None),
+        (
+            Amount::ZERO,
+            (block1_amount + block4_amount).unwrap(),
+            Amount::ZERO
+        )
     );
-    assert_eq!(found_tx.get_transaction(), tx.transaction());
+
+    // Unless the pending funds are explicitly allowed
+    assert!(matches!(
+        make_tx(&mut wallet, SelectedInputs::Utxos(vec![])),
+        Err(WalletError::CoinSelectionError(
+            UtxoSelectorError::NotEnoughFunds(_, _)
+        ))
+    ));
+    make_tx(&mut wallet, SelectedInputs::IncludingPending).unwrap();
 }
 
 #[rstest]
@@ -1211,6 +1773,7 @@ fn wallet_list_mainchain_transactions(#[case] seed: Seed) {
             DEFAULT_ACCOUNT_INDEX,
             [TxOutput::Transfer(OutputValue::Coin(block1_amount), dest.clone())],
             SelectedInputs::Utxos(vec![]),
+            None,
             BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -1233,6 +1796,7 @@ fn wallet_list_mainchain_transactions(#[case] seed: Seed) {
             DEFAULT_ACCOUNT_INDEX,
             [gen_random_transfer(&mut rng, block1_amount)],
             SelectedInputs::Utxos(vec![]),
+            None,
             BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -1255,6 +1819,99 @@ fn wallet_list_mainchain_transactions(#[case] seed: Seed) {
     assert!(txs.iter().any(|info| info.id == spend_from_tx_id));
 }
 
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn wallet_labels_survive_rescan(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+
+    let mut wallet = create_wallet(chain_config.clone());
+    let block1_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
+    let (addr, block1) = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+    let dest = addr.into_object();
+
+    let tx = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            [TxOutput::Transfer(OutputValue::Coin(block1_amount), dest.clone())],
+            SelectedInputs::Utxos(vec![]),
+            None,
+            BTreeMap::new(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+    let tx_id = tx.transaction().get_id();
+    let (_, block2) = create_block(&chain_config, &mut wallet, vec![tx], Amount::ZERO, 1);
+
+    // Only the transactions of the wallet can be labeled
+    let unknown_tx_id = Id::<Transaction>::new(H256::random_using(&mut rng));
+    assert_eq!(
+        wallet.set_transaction_label(
+            DEFAULT_ACCOUNT_INDEX,
+            unknown_tx_id,
+            Some(Label::new("unknown".to_owned()).unwrap())
+        ),
+        Err(WalletError::NoTransactionFound(unknown_tx_id))
+    );
+
+    let tx_label = Label::new("Rent March".to_owned()).unwrap();
+    wallet
+        .set_transaction_label(DEFAULT_ACCOUNT_INDEX, tx_id, Some(tx_label.clone()))
+        .unwrap();
+    let address_label = Label::new("Savings".to_owned()).unwrap();
+    wallet.set_address_label(&dest, Some(address_label.clone())).unwrap();
+
+    let check_labels = |wallet: &DefaultWallet| {
+        let txs = wallet.mainchain_transactions(DEFAULT_ACCOUNT_INDEX, None, 100).unwrap();
+        let tx_info = txs.iter().find(|info| info.id == tx_id).unwrap();
+        assert_eq!(tx_info.label.as_deref(), Some(tx_label.as_str()));
+        assert_eq!(
+            wallet.get_address_labels().unwrap(),
+            BTreeMap::from([(dest.clone(), address_label.clone())])
+        );
+
+        assert_eq!(
+            wallet.search(DEFAULT_ACCOUNT_INDEX, "rent").unwrap(),
+            vec![SearchMatch::Transaction {
+                id: tx_id,
+                label: Some(tx_label.clone()),
+            }]
+        );
+        assert_eq!(
+            wallet.search(DEFAULT_ACCOUNT_INDEX, &format!("{tx_id:x}")[..10]).unwrap(),
+            vec![SearchMatch::Transaction {
+                id: tx_id,
+                label: Some(tx_label.clone()),
+            }]
+        );
+        assert_eq!(
+            wallet.search(DEFAULT_ACCOUNT_INDEX, "SAVINGS").unwrap(),
+            vec![SearchMatch::Address {
+                address: dest.clone(),
+                label: Some(address_label.clone()),
+            }]
+        );
+    };
+    check_labels(&wallet);
+
+    // Rescan the blockchain from scratch
+    wallet.reset_wallet_to_genesis().unwrap();
+    assert!(wallet
+        .mainchain_transactions(DEFAULT_ACCOUNT_INDEX, None, 100)
+        .unwrap()
+        .is_empty());
+    scan_wallet(&mut wallet, BlockHeight::new(0), vec![block1, block2]);
+    check_labels(&wallet);
+
+    // Removing the labels
+    wallet.set_transaction_label(DEFAULT_ACCOUNT_INDEX, tx_id, None).unwrap();
+    wallet.set_address_label(&dest, None).unwrap();
+    assert!(wallet.search(DEFAULT_ACCOUNT_INDEX, "rent").unwrap().is_empty());
+    assert!(wallet.get_address_labels().unwrap().is_empty());
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -1287,6 +1944,7 @@ fn wallet_transaction_with_fees(#[case] seed: Seed) {
                 DEFAULT_ACCOUNT_INDEX,
                 [gen_random_transfer(&mut rng, Amount::from_atoms(1))],
                 SelectedInputs::Utxos(vec![]),
+                None,
                 BTreeMap::new(),
                 very_big_feerate,
                 very_big_feerate,
@@ -1314,6 +1972,7 @@ fn wallet_transaction_with_fees(#[case] seed: Seed) {
             DEFAULT_ACCOUNT_INDEX,
             outputs,
             SelectedInputs::Utxos(vec![]),
+            None,
             BTreeMap::new(),
             feerate,
             feerate,
@@ -1400,6 +2059,7 @@ fn spend_from_user_specified_utxos(#[case] seed: Seed) {
                 DEFAULT_ACCOUNT_INDEX,
                 [TxOutput::Burn(OutputValue::Coin(burn_amount))],
                 SelectedInputs::Utxos(vec![missing_utxo.clone()]),
+                None,
                 BTreeMap::new(),
                 FeeRate::from_amount_per_kb(Amount::ZERO),
                 FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -1426,6 +2086,7 @@ fn spend_from_user_specified_utxos(#[case] seed: Seed) {
             DEFAULT_ACCOUNT_INDEX,
             [TxOutput::Burn(OutputValue::Coin(burn_amount))],
             SelectedInputs::Utxos(selected_utxos.clone()),
+            None,
             BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -1463,6 +2124,7 @@ fn spend_from_user_specified_utxos(#[case] seed: Seed) {
                 DEFAULT_ACCOUNT_INDEX,
                 [TxOutput::Burn(OutputValue::Coin(burn_amount))],
                 SelectedInputs::Utxos(selected_utxos.clone()),
+                None,
                 BTreeMap::new(),
                 FeeRate::from_amount_per_kb(Amount::ZERO),
                 FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -1693,6 +2355,7 @@ fn send_to_unknown_delegation(#[case] seed: Seed) {
             DEFAULT_ACCOUNT_INDEX,
             [TxOutput::DelegateStaking(delegation_amount, unknown_delegation_id)],
             SelectedInputs::Utxos(vec![]),
+            None,
             BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -1742,6 +2405,57 @@ fn send_to_unknown_delegation(#[case] seed: Seed) {
     assert_eq!(*delegations.first().unwrap().0, delegation_id);
 }
 
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn create_delegation_and_stake_in_one_tx(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let mut wallet = create_wallet(chain_config.clone());
+
+    let delegation_amount = Amount::from_atoms(rng.gen_range(2..100));
+    let block1_amount = (delegation_amount + Amount::from_atoms(rng.gen_range(0..100))).unwrap();
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    let pool_id = PoolId::new(H256::random_using(&mut rng));
+    let (delegation_id, tx) = wallet
+        .create_delegation_and_stake(
+            DEFAULT_ACCOUNT_INDEX,
+            pool_id,
+            delegation_amount,
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+
+    // The delegation is created before the coins are delegated to it
+    let outputs = tx.transaction().outputs();
+    let create_pos = outputs
+        .iter()
+        .position(|out| match out {
+            TxOutput::CreateDelegationId(_, out_pool_id) => *out_pool_id == pool_id,
+            _ => false,
+        })
+        .unwrap();
+    let stake_pos = outputs
+        .iter()
+        .position(|out| *out == TxOutput::DelegateStaking(delegation_amount, delegation_id))
+        .unwrap();
+    assert!(create_pos < stake_pos);
+
+    let _ = create_block(&chain_config, &mut wallet, vec![tx], Amount::ZERO, 1);
+
+    let mut delegations = wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap().collect_vec();
+    assert_eq!(delegations.len(), 1);
+    let (deleg_id, deleg_data) = delegations.pop().unwrap();
+    assert_eq!(*deleg_id, delegation_id);
+    assert_eq!(deleg_data.pool_id, pool_id);
+
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, (block1_amount - delegation_amount).unwrap());
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -1827,6 +2541,7 @@ fn create_spend_from_delegations(#[case] seed: Seed) {
             DEFAULT_ACCOUNT_INDEX,
             [TxOutput::DelegateStaking(delegation_amount, delegation_id)],
             SelectedInputs::Utxos(vec![]),
+            None,
             BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -1918,9 +2633,7 @@ fn create_spend_from_delegations(#[case] seed: Seed) {
     let coin_balance = wallet
         .get_balance(other_acc_idx, UtxoState::Confirmed.into(), WithLocked::Any)
         .unwrap()
-        .get(&Currency::Coin)
-        .copied()
-        .unwrap_or(Amount::ZERO);
+        .coins();
     assert_eq!(coin_balance, Amount::from_atoms(1));
 
     let delegations = wallet.get_delegations(other_acc_idx).unwrap().collect_vec();
@@ -1932,9 +2645,7 @@ fn create_spend_from_delegations(#[case] seed: Seed) {
     let coin_balance = wallet
         .get_balance(other_acc_idx, UtxoState::Confirmed.into(), WithLocked::Any)
         .unwrap()
-        .get(&Currency::Coin)
-        .copied()
-        .unwrap_or(Amount::ZERO);
+        .coins();
     assert_eq!(coin_balance, Amount::ZERO);
 
     wallet
@@ -2133,6 +2844,7 @@ fn issue_and_transfer_tokens(#[case] seed: Seed) {
             DEFAULT_ACCOUNT_INDEX,
             [new_output],
             SelectedInputs::Utxos(vec![]),
+            None,
             BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -2186,6 +2898,7 @@ fn issue_and_transfer_tokens(#[case] seed: Seed) {
             DEFAULT_ACCOUNT_INDEX,
             [new_output],
             SelectedInputs::Utxos(vec![]),
+            None,
             BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -2247,6 +2960,7 @@ fn check_tokens_v0_are_ignored(#[case] seed: Seed) {
             address2.into_object(),
         )],
         SelectedInputs::Utxos(vec![]),
+        None,
         BTreeMap::new(),
         FeeRate::from_amount_per_kb(Amount::ZERO),
         FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -2466,6 +3180,7 @@ fn freeze_and_unfreeze_tokens(#[case] seed: Seed) {
             DEFAULT_ACCOUNT_INDEX,
             [new_output],
             SelectedInputs::Utxos(vec![]),
+            None,
             BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -2541,8 +3256,7 @@ fn freeze_and_unfreeze_tokens(#[case] seed: Seed) {
             WithLocked::Unlocked,
         )
         .unwrap()
-        .remove(&Currency::Token(issued_token_id))
-        .unwrap_or(Amount::ZERO);
+        .get(&Currency::Token(issued_token_id));
     assert_eq!(
         conflicted_token_balance,
         (amount_to_mint - tokens_to_transfer).unwrap()
@@ -3295,6 +4009,7 @@ fn lock_then_transfer(#[case] seed: Seed) {
             DEFAULT_ACCOUNT_INDEX,
             [new_output],
             SelectedInputs::Utxos(vec![]),
+            None,
             BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -3339,10 +4054,7 @@ fn lock_then_transfer(#[case] seed: Seed) {
             )
             .unwrap();
         // check that the amount is still locked
-        assert_eq!(
-            currency_balances.get(&Currency::Coin).copied().unwrap_or(Amount::ZERO),
-            amount_to_lock_then_transfer
-        );
+        assert_eq!(currency_balances.coins(), amount_to_lock_then_transfer);
 
         let new_block = Block::new(
             vec![],
@@ -3416,6 +4128,7 @@ fn wallet_multiple_transactions_in_single_block(#[case] seed: Seed) {
                 DEFAULT_ACCOUNT_INDEX,
                 [new_output],
                 SelectedInputs::Utxos(vec![]),
+                None,
                 BTreeMap::new(),
                 FeeRate::from_amount_per_kb(Amount::ZERO),
                 FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -3507,6 +4220,7 @@ fn wallet_scan_multiple_transactions_from_mempool(#[case] seed: Seed) {
                 DEFAULT_ACCOUNT_INDEX,
                 [new_output, change_output],
                 SelectedInputs::Utxos(vec![]),
+                None,
                 BTreeMap::new(),
                 FeeRate::from_amount_per_kb(Amount::ZERO),
                 FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -3542,6 +4256,7 @@ fn wallet_scan_multiple_transactions_from_mempool(#[case] seed: Seed) {
             DEFAULT_ACCOUNT_INDEX,
             [new_output],
             SelectedInputs::Utxos(vec![]),
+            None,
             BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -3581,6 +4296,7 @@ fn wallet_scan_multiple_transactions_from_mempool(#[case] seed: Seed) {
             DEFAULT_ACCOUNT_INDEX,
             [new_output],
             SelectedInputs::Utxos(vec![]),
+            None,
             BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -3607,6 +4323,7 @@ fn wallet_scan_multiple_transactions_from_mempool(#[case] seed: Seed) {
             DEFAULT_ACCOUNT_INDEX,
             [new_output],
             SelectedInputs::Utxos(vec![]),
+            None,
             BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -3691,6 +4408,7 @@ fn wallet_abandone_transactions(#[case] seed: Seed) {
                 DEFAULT_ACCOUNT_INDEX,
                 [new_output, change_output],
                 SelectedInputs::Utxos(vec![]),
+                None,
                 BTreeMap::new(),
                 FeeRate::from_amount_per_kb(Amount::ZERO),
                 FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -3739,6 +4457,114 @@ fn wallet_abandone_transactions(#[case] seed: Seed) {
     assert_eq!(coin_balance, coins_after_abandon);
 }
 
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn wallet_package_limits(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let mut wallet = create_wallet(chain_config.clone());
+
+    let block1_amount = Amount::from_atoms(rng.gen_range(10000..20000));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    let make_transaction = |wallet: &mut DefaultWallet| {
+        let new_output = TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(1)),
+            Destination::AnyoneCanSpend,
+        );
+        wallet
+            .create_transaction_to_addresses(
+                DEFAULT_ACCOUNT_INDEX,
+                [new_output],
+                SelectedInputs::Utxos(vec![]),
+                None,
+                BTreeMap::new(),
+                FeeRate::from_amount_per_kb(Amount::ZERO),
+                FeeRate::from_amount_per_kb(Amount::ZERO),
+            )
+            .unwrap()
+    };
+
+    // A chain of unconfirmed transactions, each one spending the change of the previous one
+    let num_transactions = rng.gen_range(1..10);
+    let mut transactions = vec![];
+    for _ in 0..num_transactions {
+        let transaction = make_transaction(&mut wallet);
+        wallet
+            .add_account_unconfirmed_tx(
+                DEFAULT_ACCOUNT_INDEX,
+                transaction.clone(),
+                &WalletEventsNoOp,
+            )
+            .unwrap();
+        transactions.push(transaction);
+    }
+
+    let new_transaction = make_transaction(&mut wallet);
+    let size = new_transaction.encoded_size()
+        + transactions.iter().map(|tx| tx.encoded_size()).sum::<usize>();
+    let limits = PackageLimits {
+        max_ancestor_count: num_transactions + 1,
+        max_ancestor_size: size,
+        max_descendant_count: num_transactions + 1,
+        max_descendant_size: size,
+    };
+    let check = |limits| {
+        wallet
+            .check_package_limits(DEFAULT_ACCOUNT_INDEX, &new_transaction, &limits)
+            .unwrap()
+    };
+
+    assert_eq!(check(limits), None);
+
+    assert_eq!(
+        check(PackageLimits {
+            max_ancestor_count: num_transactions,
+            ..limits
+        }),
+        Some(PackageLimitError::TooManyAncestors {
+            count: num_transactions + 1,
+            limit: num_transactions,
+        })
+    );
+    assert_eq!(
+        check(PackageLimits {
+            max_ancestor_size: size - 1,
+            ..limits
+        }),
+        Some(PackageLimitError::AncestorSizeTooLarge {
+            size,
+            limit: size - 1,
+        })
+    );
+
+    // Only the first transaction of the chain would get too many descendants
+    assert_eq!(
+        check(PackageLimits {
+            max_descendant_count: num_transactions,
+            ..limits
+        }),
+        Some(PackageLimitError::TooManyDescendants {
+            ancestor: transactions[0].transaction().get_id(),
+            count: num_transactions + 1,
+            limit: num_transactions,
+        })
+    );
+    assert_eq!(
+        check(PackageLimits {
+            max_descendant_size: size - 1,
+            ..limits
+        }),
+        Some(PackageLimitError::DescendantSizeTooLarge {
+            ancestor: transactions[0].transaction().get_id(),
+            size,
+            limit: size - 1,
+        })
+    );
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -3773,6 +4599,75 @@ fn wallet_address_usage(#[case] seed: Seed) {
     assert_eq!(usage.last_issued(), Some(last_used.try_into().unwrap()));
 }
 
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn wallet_new_address_skips_used(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+    let mut wallet = create_wallet(chain_config.clone());
+
+    // issue all the addresses allowed by the lookahead
+    let addresses = (0..LOOKAHEAD_SIZE)
+        .map(|_| wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1)
+        .collect_vec();
+    assert!(addresses.iter().all_unique());
+
+    // receive to a few of the first addresses and to the last one
+    let used_count = rng.gen_range(1..LOOKAHEAD_SIZE as usize - 1);
+    let used_addresses =
+        addresses[..used_count].iter().chain(addresses.last()).cloned().collect_vec();
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(
+            used_addresses
+                .iter()
+                .map(|address| make_address_output(address.clone(), Amount::from_atoms(10000)))
+                .collect(),
+        ),
+    )
+    .unwrap();
+    scan_wallet(&mut wallet, BlockHeight::new(0), vec![block1]);
+
+    for address in &addresses {
+        assert_eq!(
+            wallet.is_used_own_destination(address.as_object()),
+            used_addresses.contains(address)
+        );
+    }
+    let usage = wallet.get_all_issued_addresses_with_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(usage.len(), addresses.len());
+    for (address, used) in usage.values() {
+        assert_eq!(*used, used_addresses.contains(address));
+    }
+
+    // reusing an address returns the last issued one, even though it was already used
+    let (_, reused_address) = wallet.get_reusable_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(&reused_address, addresses.last().unwrap());
+
+    // paying the used addresses is reported as a reuse, the unused ones are not
+    let fresh_address = addresses[used_count].clone();
+    assert_eq!(
+        wallet.find_used_own_addresses([&addresses[0], &fresh_address, &addresses[0]]),
+        vec![addresses[0].clone()]
+    );
+
+    // new addresses are issued until the lookahead is exhausted again
+    for _ in 0..LOOKAHEAD_SIZE {
+        let (_, new_address) = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+        assert!(!addresses.contains(&new_address));
+        assert!(!wallet.is_used_own_destination(new_address.as_object()));
+    }
+
+    // then the first issued address that has never received anything is returned
+    let (_, new_address) = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(new_address, fresh_address);
+    assert!(!used_addresses.contains(&new_address));
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -3809,31 +4704,121 @@ fn wallet_set_lookahead_size(#[case] seed: Seed) {
     let coins = get_coin_balance_for_acc(&wallet, DEFAULT_ACCOUNT_INDEX);
     assert_eq!(coins, block1_amount);
 
-    let less_than_last_used = rng.gen_range(1..=last_used);
-    let err = wallet.set_lookahead_size(less_than_last_used, false).unwrap_err();
+    let less_than_last_used = rng.gen_range(1..=last_used);
+    let err = wallet.set_lookahead_size(less_than_last_used, false).unwrap_err();
+    assert_eq!(
+        err,
+        WalletError::ReducedLookaheadSize(less_than_last_used, last_used)
+    );
+
+    wallet.set_lookahead_size(less_than_last_used, true).unwrap();
+
+    scan_wallet(&mut wallet, BlockHeight::new(0), vec![block1.clone()]);
+    let coins = get_coin_balance_for_acc(&wallet, DEFAULT_ACCOUNT_INDEX);
+    assert_eq!(coins, Amount::ZERO);
+    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(usage.last_used(), None);
+    assert_eq!(usage.last_issued(), None);
+
+    let more_than_last_used = rng.gen_range(last_used + 1..100);
+    wallet.set_lookahead_size(more_than_last_used, false).unwrap();
+
+    scan_wallet(&mut wallet, BlockHeight::new(0), vec![block1.clone()]);
+    let coins = get_coin_balance_for_acc(&wallet, DEFAULT_ACCOUNT_INDEX);
+    assert_eq!(coins, block1_amount);
+    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(usage.last_used(), Some(last_used.try_into().unwrap()));
+    assert_eq!(usage.last_issued(), Some(last_used.try_into().unwrap()));
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn change_addresses_lookahead(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+
+    // The change addresses are used with gaps that the default lookahead covers
+    let small_lookahead_size = 5;
+    let change_indexes = [0, 3, 10, 17];
+    let mut total_amount = Amount::ZERO;
+    let mut prev_block_id = chain_config.genesis_block_id();
+    let blocks = change_indexes
+        .iter()
+        .map(|idx| {
+            let address = get_address(
+                &chain_config,
+                MNEMONIC,
+                DEFAULT_ACCOUNT_INDEX,
+                KeyPurpose::Change,
+                U31::from_u32(*idx).unwrap(),
+            );
+            let amount = Amount::from_atoms(rng.gen_range(1..10000));
+            total_amount = (total_amount + amount).unwrap();
+            let block = Block::new(
+                vec![],
+                prev_block_id,
+                chain_config.genesis_block().timestamp(),
+                ConsensusData::None,
+                BlockReward::new(vec![make_address_output(address, amount)]),
+            )
+            .unwrap();
+            prev_block_id = block.get_id().into();
+            block
+        })
+        .collect::<Vec<_>>();
+
+    let mut wallet = create_wallet(chain_config.clone());
+    scan_wallet(&mut wallet, BlockHeight::new(0), blocks.clone());
     assert_eq!(
-        err,
-        WalletError::ReducedLookaheadSize(less_than_last_used, last_used)
+        get_coin_balance_for_acc(&wallet, DEFAULT_ACCOUNT_INDEX),
+        total_amount
     );
+    let last_used_change_index = wallet.last_used_change_index().unwrap().into_u32();
+    assert_eq!(last_used_change_index, 17);
 
-    wallet.set_lookahead_size(less_than_last_used, true).unwrap();
+    // Restore the wallet with a lookahead that doesn't cover the gap between 3 and 10
+    let mut restored = create_wallet(chain_config.clone());
+    restored.set_lookahead_size(small_lookahead_size, false).unwrap();
+    scan_wallet(&mut restored, BlockHeight::new(0), blocks.clone());
 
-    scan_wallet(&mut wallet, BlockHeight::new(0), vec![block1.clone()]);
-    let coins = get_coin_balance_for_acc(&wallet, DEFAULT_ACCOUNT_INDEX);
-    assert_eq!(coins, Amount::ZERO);
-    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
-    assert_eq!(usage.last_used(), None);
-    assert_eq!(usage.last_issued(), None);
+    // The restored wallet can detect that the lookahead is insufficient
+    assert!(last_used_change_index >= restored.lookahead_size().unwrap());
+    assert_eq!(
+        restored.last_used_change_index(),
+        Some(U31::from_u32(3).unwrap())
+    );
+    assert!(get_coin_balance_for_acc(&restored, DEFAULT_ACCOUNT_INDEX) < total_amount);
+    let used_change_addresses = restored
+        .get_all_issued_change_addresses_with_usage(DEFAULT_ACCOUNT_INDEX)
+        .unwrap()
+        .into_iter()
+        .filter_map(|(idx, (_, used))| used.then_some(idx.get_index().into_u32()))
+        .collect::<Vec<_>>();
+    assert_eq!(used_change_addresses, [0, 3]);
 
-    let more_than_last_used = rng.gen_range(last_used + 1..100);
-    wallet.set_lookahead_size(more_than_last_used, false).unwrap();
+    // Reducing the lookahead below the last used change address is not allowed
+    let err = restored.set_lookahead_size(3, false).unwrap_err();
+    assert_eq!(err, WalletError::ReducedLookaheadSize(3, 3));
 
-    scan_wallet(&mut wallet, BlockHeight::new(0), vec![block1.clone()]);
-    let coins = get_coin_balance_for_acc(&wallet, DEFAULT_ACCOUNT_INDEX);
-    assert_eq!(coins, block1_amount);
-    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
-    assert_eq!(usage.last_used(), Some(last_used.try_into().unwrap()));
-    assert_eq!(usage.last_issued(), Some(last_used.try_into().unwrap()));
+    // The funds are recovered once the lookahead is raised
+    restored.set_lookahead_size(LOOKAHEAD_SIZE, false).unwrap();
+    scan_wallet(&mut restored, BlockHeight::new(0), blocks);
+    assert_eq!(
+        get_coin_balance_for_acc(&restored, DEFAULT_ACCOUNT_INDEX),
+        total_amount
+    );
+    assert_eq!(
+        restored.last_used_change_index(),
+        wallet.last_used_change_index()
+    );
+    let used_change_addresses = restored
+        .get_all_issued_change_addresses_with_usage(DEFAULT_ACCOUNT_INDEX)
+        .unwrap()
+        .into_iter()
+        .filter_map(|(idx, (_, used))| used.then_some(idx.get_index().into_u32()))
+        .collect::<Vec<_>>();
+    assert_eq!(used_change_addresses, change_indexes);
 }
 
 #[rstest]
@@ -3929,6 +4914,133 @@ fn decommission_pool_wrong_account(#[case] seed: Seed) {
     assert_eq!(coin_balance, pool_amount);
 }
 
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn create_decommission_pool_transaction(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+
+    let acc_0_index = DEFAULT_ACCOUNT_INDEX;
+    let acc_1_index = U31::ONE;
+
+    let mut wallet = create_wallet(chain_config.clone());
+
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    let pool_amount = Amount::from_atoms(rng.gen_range(1..block1_amount.into_atoms()));
+    let delegation_amount = (block1_amount - pool_amount).unwrap();
+
+    let res = wallet.create_next_account(Some("name".into())).unwrap();
+    assert_eq!(res, (U31::from_u32(1).unwrap(), Some("name".into())));
+
+    let decommission_key = wallet.get_new_address(acc_1_index).unwrap().1.into_object();
+
+    let stake_pool_transaction = wallet
+        .create_stake_pool_tx(
+            acc_0_index,
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            StakePoolDataArguments {
+                amount: pool_amount,
+                margin_ratio_per_thousand: PerThousand::new_from_rng(&mut rng),
+                cost_per_block: Amount::ZERO,
+                decommission_key: decommission_key.clone(),
+            },
+        )
+        .unwrap();
+    let (address, _) = create_block(
+        &chain_config,
+        &mut wallet,
+        vec![stake_pool_transaction],
+        Amount::ZERO,
+        1,
+    );
+
+    let pool_id = wallet.get_pool_ids(acc_0_index, WalletPoolsFilter::All).unwrap()[0].0;
+
+    // Delegate the rest of the coins to the pool
+    let (delegation_id, delegation_tx) = wallet
+        .create_delegation(
+            acc_0_index,
+            vec![make_create_delegation_output(address, pool_id)],
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+    let _ = create_block(
+        &chain_config,
+        &mut wallet,
+        vec![delegation_tx],
+        Amount::ZERO,
+        2,
+    );
+    let delegation_stake_tx = wallet
+        .create_transaction_to_addresses(
+            acc_0_index,
+            [TxOutput::DelegateStaking(delegation_amount, delegation_id)],
+            SelectedInputs::Utxos(vec![]),
+            None,
+            BTreeMap::new(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+    let _ = create_block(
+        &chain_config,
+        &mut wallet,
+        vec![delegation_stake_tx],
+        Amount::ZERO,
+        3,
+    );
+    assert_eq!(get_coin_balance_for_acc(&wallet, acc_0_index), Amount::ZERO);
+
+    // The account that created the pool doesn't own the decommission key
+    let res = wallet.create_decommission_pool_transaction(
+        acc_0_index,
+        pool_id,
+        pool_amount,
+        FeeRate::from_amount_per_kb(Amount::ZERO),
+    );
+    assert_eq!(
+        res.unwrap_err(),
+        WalletError::PoolDecommissionKeyNotOwned(pool_id)
+    );
+
+    let decommission_tx = wallet
+        .create_decommission_pool_transaction(
+            acc_1_index,
+            pool_id,
+            pool_amount,
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+
+    // The whole pledge goes to the decommission key
+    assert_eq!(decommission_tx.transaction().outputs().len(), 1);
+    match &decommission_tx.transaction().outputs()[0] {
+        TxOutput::LockThenTransfer(value, destination, _) => {
+            assert_eq!(value, &OutputValue::Coin(pool_amount));
+            assert_eq!(destination, &decommission_key);
+        }
+        output => panic!("unexpected output {output:?}"),
+    }
+
+    let _ = create_block(
+        &chain_config,
+        &mut wallet,
+        vec![decommission_tx],
+        Amount::ZERO,
+        4,
+    );
+
+    assert!(wallet.get_pool_ids(acc_1_index, WalletPoolsFilter::All).unwrap().is_empty());
+    assert_eq!(get_coin_balance_for_acc(&wallet, acc_1_index), pool_amount);
+    // The delegation is still there
+    assert_eq!(wallet.get_delegations(acc_0_index).unwrap().count(), 1);
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -4015,6 +5127,64 @@ fn decommission_pool_request_wrong_account(#[case] seed: Seed) {
     );
 }
 
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn sign_raw_transaction_own_inputs(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+
+    let mut wallet = create_wallet(chain_config.clone());
+
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
+    let (_, block1) = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+    let own_input = TxInput::from_utxo(OutPointSourceId::BlockReward(block1.get_id().into()), 0);
+    // an input the wallet knows nothing about
+    let other_input = TxInput::from_utxo(
+        OutPointSourceId::Transaction(Id::new(H256::random_using(&mut rng))),
+        rng.gen_range(0..10),
+    );
+    let output = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(1)),
+        Destination::AnyoneCanSpend,
+    );
+
+    // a single own input is fully signed
+    let tx = Transaction::new(0, vec![own_input.clone()], vec![output.clone()]).unwrap();
+    let (ptx, prev_statuses, new_statuses) =
+        wallet.sign_raw_transaction_own_inputs(DEFAULT_ACCOUNT_INDEX, tx).unwrap();
+    assert_eq!(prev_statuses, vec![SignatureStatus::NotSigned]);
+    assert_eq!(new_statuses, vec![SignatureStatus::FullySigned]);
+    assert!(ptx.into_signed_tx().is_ok());
+
+    // only the own input is signed, the other one is left for its owner
+    let tx = Transaction::new(
+        0,
+        vec![own_input, other_input.clone()],
+        vec![output.clone()],
+    )
+    .unwrap();
+    let (ptx, _, new_statuses) = wallet
+        .sign_raw_transaction_own_inputs(DEFAULT_ACCOUNT_INDEX, tx.clone())
+        .unwrap();
+    assert_eq!(
+        new_statuses,
+        vec![SignatureStatus::FullySigned, SignatureStatus::NotSigned]
+    );
+    assert!(!ptx.all_signatures_available());
+
+    // signing all the inputs still requires knowing all the utxos
+    let err = wallet
+        .sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, TransactionToSign::Tx(tx))
+        .unwrap_err();
+    assert!(matches!(err, WalletError::CannotFindUtxo(_)));
+
+    // none of the inputs can be signed
+    let tx = Transaction::new(0, vec![other_input], vec![output]).unwrap();
+    let err = wallet.sign_raw_transaction_own_inputs(DEFAULT_ACCOUNT_INDEX, tx).unwrap_err();
+    assert_eq!(err, WalletError::NoOwnedInputs);
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -4463,6 +5633,7 @@ fn test_not_exhaustion_of_keys(#[case] seed: Seed) {
                     address.as_object().clone(),
                 )],
                 SelectedInputs::Inputs(vec![]),
+                None,
                 [].into(),
                 FeeRate::from_amount_per_kb(Amount::ZERO),
                 FeeRate::from_amount_per_kb(Amount::ZERO),
@@ -4524,6 +5695,70 @@ fn test_add_standalone_private_key(#[case] seed: Seed) {
     assert_eq!(tx_data.get_transaction(), tx.transaction());
 }
 
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn test_add_standalone_private_key_duplicates(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+
+    let mut wallet = create_wallet(chain_config.clone());
+
+    let (private_key, pub_key) =
+        crypto::key::PrivateKey::new_from_rng(&mut rng, crypto::key::KeyKind::Secp256k1Schnorr);
+    let label = Some("imported".to_owned());
+
+    wallet
+        .add_standalone_private_key(DEFAULT_ACCOUNT_INDEX, private_key.clone(), label.clone())
+        .unwrap();
+
+    // the same private key cannot be added twice
+    let err = wallet
+        .add_standalone_private_key(DEFAULT_ACCOUNT_INDEX, private_key, None)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        WalletError::KeyChainError(KeyChainError::StandaloneAddressAlreadyExists(
+            RpcAddress::new(&chain_config, Destination::PublicKey(pub_key.clone())).unwrap()
+        ))
+    );
+
+    // a private key already derived from the account cannot be added as a standalone one
+    let (root_key, _root_vrf_key, _) =
+        MasterKeyChain::mnemonic_to_root_key(MNEMONIC, None).unwrap();
+    let mut key_path = make_account_path(&chain_config, DEFAULT_ACCOUNT_INDEX).into_vec();
+    key_path.push(KeyPurpose::ReceiveFunds.get_deterministic_index());
+    key_path.push(ChildNumber::from_normal(U31::ZERO));
+    let derived_private_key = root_key
+        .derive_absolute_path(&DerivationPath::try_from(key_path).unwrap())
+        .unwrap()
+        .private_key();
+    let derived_public_key = crypto::key::PublicKey::from_private_key(&derived_private_key);
+
+    let err = wallet
+        .add_standalone_private_key(DEFAULT_ACCOUNT_INDEX, derived_private_key, None)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        WalletError::KeyChainError(KeyChainError::StandaloneKeyAlreadyDerived(
+            RpcAddress::new(&chain_config, Destination::PublicKey(derived_public_key)).unwrap()
+        ))
+    );
+
+    // the added key persists after reloading the wallet
+    let wallet = Wallet::load_wallet(
+        Arc::clone(&chain_config),
+        wallet.db,
+        None,
+        |_| Ok(()),
+        WalletType::Hot,
+        false,
+    )
+    .unwrap();
+    let standalone_addresses = wallet.get_all_standalone_addresses(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(standalone_addresses.private_keys, vec![(pub_key, label)]);
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -4938,3 +6173,119 @@ fn create_htlc_and_refund(#[case] seed: Seed) {
     assert_eq!(get_coin_balance(&wallet1), coin_balance);
     assert_eq!(get_coin_balance(&wallet2), Amount::ZERO);
 }
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn wallet_backup_and_restore(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+
+    let mut wallet = create_wallet(chain_config.clone());
+    wallet.set_account_name(DEFAULT_ACCOUNT_INDEX, Some("main".to_owned())).unwrap();
+    let (_, pub_key) =
+        crypto::key::PrivateKey::new_from_rng(&mut rng, crypto::key::KeyKind::Secp256k1Schnorr);
+    wallet
+        .add_standalone_address(
+            DEFAULT_ACCOUNT_INDEX,
+            PublicKeyHash::from(&pub_key),
+            Some("watched".to_owned()),
+        )
+        .unwrap();
+    let labeled_address = Destination::PublicKeyHash(PublicKeyHash::from(&pub_key));
+    wallet
+        .set_address_label(
+            &labeled_address,
+            Some(Label::new("savings".to_owned()).unwrap()),
+        )
+        .unwrap();
+
+    let wallet_password = rng.gen_bool(0.5).then(|| gen_random_password(&mut rng));
+    if wallet_password.is_some() {
+        wallet.encrypt_wallet(&wallet_password).unwrap();
+    }
+
+    let mut blocks = vec![];
+    let mut balance = Amount::ZERO;
+    for block_height in 0..rng.gen_range(1..10) {
+        let reward = Amount::from_atoms(rng.gen_range(1..10000));
+        let (_, block) = create_block(&chain_config, &mut wallet, vec![], reward, block_height);
+        blocks.push(block);
+        balance = (balance + reward).unwrap();
+    }
+    assert_eq!(get_coin_balance(&wallet), balance);
+
+    let backup_password = rng.gen_bool(0.5).then(|| gen_random_password(&mut rng));
+    let backup = wallet.make_backup(backup_password.as_ref()).unwrap();
+    let (_, account_names) = wallet.wallet_info();
+    let watch_only_labels = wallet
+        .get_all_standalone_addresses(DEFAULT_ACCOUNT_INDEX)
+        .unwrap()
+        .watch_only_addresses
+        .into_iter()
+        .map(|(dest, key)| (dest, key.label))
+        .collect_vec();
+    assert_eq!(watch_only_labels.len(), 1);
+    let address_labels = wallet.get_address_labels().unwrap();
+    assert_eq!(address_labels.len(), 1);
+    drop(wallet);
+
+    let db = Store::new_from_backup(
+        DefaultBackend::new_in_memory(),
+        &backup,
+        backup_password.as_ref(),
+    )
+    .unwrap();
+    let mut wallet =
+        Wallet::load_wallet_from_backup(chain_config.clone(), db, WalletType::Hot).unwrap();
+
+    // The restored wallet starts from genesis and needs to rescan the blockchain
+    assert_eq!(
+        get_best_block(&wallet),
+        (chain_config.genesis_block_id(), BlockHeight::new(0))
+    );
+    assert_eq!(get_coin_balance(&wallet), Amount::ZERO);
+    assert_eq!(wallet.is_encrypted(), wallet_password.is_some());
+    if let Some(wallet_password) = &wallet_password {
+        wallet.unlock_wallet(wallet_password).unwrap();
+    }
+
+    scan_wallet(&mut wallet, BlockHeight::new(0), blocks);
+    assert_eq!(get_coin_balance(&wallet), balance);
+
+    assert_eq!(wallet.wallet_info().1, account_names);
+    let restored_watch_only_labels = wallet
+        .get_all_standalone_addresses(DEFAULT_ACCOUNT_INDEX)
+        .unwrap()
+        .watch_only_addresses
+        .into_iter()
+        .map(|(dest, key)| (dest, key.label))
+        .collect_vec();
+    assert_eq!(restored_watch_only_labels, watch_only_labels);
+    assert_eq!(wallet.get_address_labels().unwrap(), address_labels);
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn wallet_restore_tampered_backup(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+
+    let wallet = create_wallet(chain_config);
+    let backup_password = rng.gen_bool(0.5).then(|| gen_random_password(&mut rng));
+    let mut backup = wallet.make_backup(backup_password.as_ref()).unwrap();
+
+    // Tamper with the end of the backup, which is always part of the encrypted contents
+    let idx = rng.gen_range(backup.len() - 100..backup.len());
+    backup[idx] ^= 1 << rng.gen_range(0..8);
+
+    let err = Store::new_from_backup(
+        DefaultBackend::new_in_memory(),
+        &backup,
+        backup_password.as_ref(),
+    )
+    .err()
+    .unwrap();
+    assert_eq!(err, wallet_storage::Error::BackupAuthenticationFailed);
+}
@@ -13,7 +13,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use common::chain::OutPointSourceId;
+use common::{
+    chain::{OutPointSourceId, Transaction},
+    primitives::Id,
+};
 use crypto::key::hdkd::u31::U31;
 use wallet_types::WalletTx;
 
@@ -29,6 +32,14 @@ pub trait WalletEvents {
 
     /// The transaction is removed from the DB
     fn del_transaction(&self, id: U31, source_id: OutPointSourceId);
+
+    /// The transaction has got the number of confirmations the wallet requires to consider it final
+    fn tx_confirmation_threshold_reached(
+        &self,
+        id: U31,
+        tx_id: Id<Transaction>,
+        confirmations: u64,
+    );
 }
 
 pub struct WalletEventsNoOp;
@@ -37,4 +48,11 @@ impl WalletEvents for WalletEventsNoOp {
     fn new_block(&self) {}
     fn set_transaction(&self, _id: U31, _tx: &WalletTx) {}
     fn del_transaction(&self, _id: U31, _source: OutPointSourceId) {}
+    fn tx_confirmation_threshold_reached(
+        &self,
+        _id: U31,
+        _tx_id: Id<Transaction>,
+        _confirmations: u64,
+    ) {
+    }
 }
@@ -0,0 +1,316 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compact encrypted wallet backups
+//!
+//! A backup consists of a header followed by the encrypted contents of the wallet DB.
+//! The header is authenticated together with the contents, so any modification of the backup
+//! is detected before any of its data is used.
+//!
+//! Only the data that can't be recovered from the blockchain is included. The synced
+//! transactions and the derived public keys and addresses are left out and are rebuilt
+//! by rescanning the blockchain after the backup is restored.
+
+use crypto::{
+    kdf::KdfChallenge,
+    symkey::{SymmetricKey, SymmetricKeyKind},
+};
+use randomness::make_true_rng;
+use serialization::{Decode, DecodeAll, Encode};
+use storage::raw::{self, DbMapId};
+
+use crate::schema::{self, Schema};
+
+use super::{challenge_to_sym_key, password_to_sym_key, Store};
+
+/// Magic bytes identifying a wallet backup
+const BACKUP_MAGIC: [u8; 8] = *b"MLWALBAK";
+
+/// The current version of the backup format.
+/// Backups with a newer version are refused, as they may contain data this version doesn't know.
+const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// The DB maps that are not included in a backup because they are rebuilt by a rescan
+///
+/// The idempotency records are excluded too, as they refer to the transactions that are rebuilt.
+fn excluded_maps() -> [DbMapId<Schema>; 5] {
+    [
+        DbMapId::new::<schema::DBTxs, _>(),
+        DbMapId::new::<schema::DBPubKeys, _>(),
+        DbMapId::new::<schema::DBAddresses, _>(),
+        DbMapId::new::<schema::DBUnconfirmedTxCounters, _>(),
+        DbMapId::new::<schema::DBIdempotencyRecords, _>(),
+    ]
+}
+
+/// How the contents of a backup are encrypted
+#[derive(Encode, Decode)]
+enum BackupEncryption {
+    /// Encrypted with a random key stored in the backup itself, which only protects the backup
+    /// against accidental corruption. The private keys are still protected by the wallet's own
+    /// encryption, if the wallet is encrypted.
+    #[codec(index = 0)]
+    NoPassword(SymmetricKey),
+    /// Encrypted with a key derived from a password
+    #[codec(index = 1)]
+    Password(KdfChallenge),
+}
+
+/// The contents of the wallet DB, with the maps identified by their names so that
+/// the backup doesn't depend on the order of the maps in the schema
+#[derive(Encode, Decode)]
+struct BackupContents {
+    maps: Vec<(String, Vec<(Vec<u8>, Vec<u8>)>)>,
+}
+
+impl<B: storage::Backend> Store<B> {
+    /// Create an encrypted backup of the wallet data.
+    ///
+    /// If no `password` is specified, the backup is only protected against accidental corruption
+    /// and the private keys in it are only as safe as they are in the wallet file.
+    pub fn make_backup(&self, password: Option<&String>) -> crate::Result<Vec<u8>> {
+        let mut rng = make_true_rng();
+
+        let (sym_key, encryption) = match password {
+            Some(password) => {
+                let (sym_key, challenge) = password_to_sym_key(password)?;
+                (sym_key, BackupEncryption::Password(challenge))
+            }
+            None => {
+                let sym_key = SymmetricKey::new(SymmetricKeyKind::XChacha20Poly1305, &mut rng);
+                (sym_key.clone(), BackupEncryption::NoPassword(sym_key))
+            }
+        };
+
+        let excluded_maps = excluded_maps();
+        let maps = self
+            .dump_raw()?
+            .into_iter()
+            .filter(|(map_id, _)| !excluded_maps.contains(map_id))
+            .map(|(map_id, contents)| (map_id.name(), contents.into_iter().collect()))
+            .collect();
+        let contents = BackupContents { maps }.encode();
+
+        let mut backup = (BACKUP_MAGIC, BACKUP_FORMAT_VERSION, encryption).encode();
+        let cipher_text =
+            sym_key.encrypt(&contents, &mut rng, Some(&backup)).expect("should not fail");
+        backup.extend(cipher_text);
+
+        Ok(backup)
+    }
+
+    /// Create a new wallet storage from a backup made with [Store::make_backup].
+    ///
+    /// The backup is authenticated before anything is written to the `backend`.
+    pub fn new_from_backup(
+        backend: B,
+        backup: &[u8],
+        password: Option<&String>,
+    ) -> crate::Result<Self> {
+        let mut input = backup;
+        let magic = <[u8; 8]>::decode(&mut input).map_err(|_| crate::Error::BackupInvalidFormat)?;
+        utils::ensure!(magic == BACKUP_MAGIC, crate::Error::BackupInvalidFormat);
+        let version = u32::decode(&mut input).map_err(|_| crate::Error::BackupInvalidFormat)?;
+        utils::ensure!(
+            version <= BACKUP_FORMAT_VERSION,
+            crate::Error::BackupUnsupportedVersion(version)
+        );
+        let encryption =
+            BackupEncryption::decode(&mut input).map_err(|_| crate::Error::BackupInvalidFormat)?;
+        let (header, cipher_text) = backup.split_at(backup.len() - input.len());
+
+        let sym_key = match encryption {
+            BackupEncryption::NoPassword(sym_key) => sym_key,
+            BackupEncryption::Password(challenge) => {
+                let password = password.ok_or(crate::Error::BackupPasswordRequired)?;
+                challenge_to_sym_key(password, challenge)?
+            }
+        };
+        let contents = sym_key
+            .decrypt(cipher_text, Some(header))
+            .map_err(|_| crate::Error::BackupAuthenticationFailed)?;
+        let contents = BackupContents::decode_all(&mut contents.as_slice())
+            .map_err(|_| crate::Error::BackupInvalidFormat)?;
+
+        let dump = contents
+            .maps
+            .into_iter()
+            .map(|(name, contents)| {
+                let map_id = DbMapId::<Schema>::from_name(&name)
+                    .ok_or(crate::Error::BackupUnknownMap(name))?;
+                Ok((map_id, contents.into_iter().collect::<raw::MapContents>()))
+            })
+            .collect::<crate::Result<raw::StorageContents<Schema>>>()?;
+
+        Self::new_from_dump(backend, dump)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto::key::extended::{ExtendedKeyKind, ExtendedPrivateKey};
+    use randomness::{CryptoRng, Rng};
+    use rstest::rstest;
+    use test_utils::random::{make_seedable_rng, Seed};
+    use wallet_types::AccountId;
+
+    use super::*;
+    use crate::{
+        DefaultBackend, TransactionRwLocked, Transactional, WalletStorageReadLocked,
+        WalletStorageWriteLocked,
+    };
+
+    fn gen_random_password(rng: &mut (impl Rng + CryptoRng)) -> String {
+        (0..rng.gen_range(1..100)).map(|_| rng.gen::<char>()).collect()
+    }
+
+    fn make_store() -> Store<DefaultBackend> {
+        let store = Store::new(DefaultBackend::new_in_memory()).unwrap();
+        let mut db_tx = store.transaction_rw(None).unwrap();
+        db_tx.set_storage_version(5).unwrap();
+        db_tx.set_lookahead_size(42).unwrap();
+        db_tx.commit().unwrap();
+        store
+    }
+
+    fn header_len(backup: &[u8]) -> usize {
+        let mut input = backup;
+        <[u8; 8]>::decode(&mut input).unwrap();
+        u32::decode(&mut input).unwrap();
+        BackupEncryption::decode(&mut input).unwrap();
+        backup.len() - input.len()
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn backup_round_trip(#[case] seed: Seed, #[values(false, true)] with_password: bool) {
+        let mut rng = make_seedable_rng(seed);
+        let password = with_password.then(|| gen_random_password(&mut rng));
+
+        let store = make_store();
+        let raw_db = store.dump_raw().unwrap();
+
+        let backup = store.make_backup(password.as_ref()).unwrap();
+        let restored =
+            Store::new_from_backup(DefaultBackend::new_in_memory(), &backup, password.as_ref())
+                .unwrap();
+
+        {
+            let db_tx = restored.transaction_ro().unwrap();
+            assert_eq!(db_tx.get_storage_version().unwrap(), 5);
+            assert_eq!(db_tx.get_lookahead_size().unwrap(), 42);
+        }
+        assert_eq!(restored.dump_raw().unwrap(), raw_db);
+
+        if with_password {
+            let wrong_password = gen_random_password(&mut rng);
+            assert!(Store::new_from_backup(
+                DefaultBackend::new_in_memory(),
+                &backup,
+                Some(&wrong_password)
+            )
+            .is_err());
+            assert_eq!(
+                Store::new_from_backup(DefaultBackend::new_in_memory(), &backup, None).err(),
+                Some(crate::Error::BackupPasswordRequired)
+            );
+        }
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn synced_data_excluded(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let store = make_store();
+        let raw_db = store.dump_raw().unwrap();
+
+        let (_, xpub) =
+            ExtendedPrivateKey::new_from_rng(&mut rng, ExtendedKeyKind::Secp256k1Schnorr);
+        let account_id = AccountId::new_from_xpub(&xpub);
+        let mut db_tx = store.transaction_rw(None).unwrap();
+        db_tx.set_account_unconfirmed_tx_counter(&account_id, rng.gen()).unwrap();
+        db_tx.commit().unwrap();
+        assert_ne!(store.dump_raw().unwrap(), raw_db);
+
+        let backup = store.make_backup(None).unwrap();
+        let restored =
+            Store::new_from_backup(DefaultBackend::new_in_memory(), &backup, None).unwrap();
+        assert_eq!(restored.dump_raw().unwrap(), raw_db);
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn tampered_backup_refused(#[case] seed: Seed, #[values(false, true)] with_password: bool) {
+        let mut rng = make_seedable_rng(seed);
+        let password = with_password.then(|| gen_random_password(&mut rng));
+
+        let store = make_store();
+        let backup = store.make_backup(password.as_ref()).unwrap();
+
+        // Tamper with the encrypted contents
+        let mut tampered = backup.clone();
+        let idx = rng.gen_range(header_len(&backup)..backup.len());
+        tampered[idx] ^= 1 << rng.gen_range(0..8);
+        assert_eq!(
+            Store::new_from_backup(
+                DefaultBackend::new_in_memory(),
+                &tampered,
+                password.as_ref()
+            )
+            .err(),
+            Some(crate::Error::BackupAuthenticationFailed)
+        );
+
+        // Tamper with the magic bytes
+        let mut tampered = backup.clone();
+        tampered[rng.gen_range(0..BACKUP_MAGIC.len())] ^= 1 << rng.gen_range(0..8);
+        assert_eq!(
+            Store::new_from_backup(
+                DefaultBackend::new_in_memory(),
+                &tampered,
+                password.as_ref()
+            )
+            .err(),
+            Some(crate::Error::BackupInvalidFormat)
+        );
+
+        // Truncate the backup
+        let truncated = &backup[..rng.gen_range(0..backup.len())];
+        assert!(Store::new_from_backup(
+            DefaultBackend::new_in_memory(),
+            truncated,
+            password.as_ref()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn newer_version_refused() {
+        let store = make_store();
+        let mut backup = store.make_backup(None).unwrap();
+        backup[BACKUP_MAGIC.len()..BACKUP_MAGIC.len() + 4]
+            .copy_from_slice(&(BACKUP_FORMAT_VERSION + 1).to_le_bytes());
+
+        assert_eq!(
+            Store::new_from_backup(DefaultBackend::new_in_memory(), &backup, None).err(),
+            Some(crate::Error::BackupUnsupportedVersion(
+                BACKUP_FORMAT_VERSION + 1
+            ))
+        );
+    }
+}
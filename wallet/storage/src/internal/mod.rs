@@ -20,6 +20,8 @@ use crate::{
     WalletStorageEncryptionWrite,
 };
 
+mod backup;
+
 mod password;
 use password::{challenge_to_sym_key, password_to_sym_key};
 
@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::{
     schema::{self as db, Schema},
@@ -22,11 +22,12 @@ use crate::{
 };
 use common::{
     address::Address,
-    chain::{block::timestamp::BlockTimestamp, Destination, SignedTransaction},
+    chain::{block::timestamp::BlockTimestamp, Destination, SignedTransaction, Transaction},
+    primitives::{Amount, Id},
 };
 use crypto::{
     kdf::KdfChallenge,
-    key::{extended::ExtendedPublicKey, PrivateKey},
+    key::{extended::ExtendedPublicKey, hdkd::u31::U31, PrivateKey},
     symkey::SymmetricKey,
 };
 use serialization::{Codec, DecodeAll, Encode, EncodeLike};
@@ -36,13 +37,16 @@ use utils::{
     maybe_encrypted::{MaybeEncrypted, MaybeEncryptedError},
 };
 use wallet_types::{
-    account_id::{AccountAddress, AccountPublicKey},
+    account_id::{AccountAddress, AccountOutflowId, AccountPublicKey},
     account_info::{
         AccountVrfKeys, StandaloneMultisig, StandalonePrivateKey, StandaloneWatchOnlyKey,
     },
     chain_info::ChainInfo,
-    keys::{RootKeyConstant, RootKeys},
+    idempotency::{IdempotencyKey, IdempotencyRecord},
+    keys::{RootKeyConstant, RootKeys, RootKeysInfo},
+    label::Label,
     seed_phrase::{SeedPhraseConstant, SerializableSeedPhrase},
+    spending_policy::SpendingPolicy,
     wallet_type::WalletType,
     AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId, AccountWalletCreatedTxId,
     AccountWalletTxId, KeychainUsageState, WalletTx,
@@ -50,7 +54,9 @@ use wallet_types::{
 mod well_known {
     use common::chain::block::timestamp::BlockTimestamp;
     use crypto::kdf::KdfChallenge;
-    use wallet_types::{account_info::AccountVrfKeys, chain_info::ChainInfo, wallet_type};
+    use wallet_types::{
+        account_info::AccountVrfKeys, chain_info::ChainInfo, keys::RootKeysInfo, wallet_type,
+    };
 
     use super::Codec;
 
@@ -73,12 +79,15 @@ mod well_known {
     }
 
     declare_entry!(StoreVersion: u32);
+    declare_entry!(SoftwareVersion: String);
     declare_entry!(EncryptionKeyKdfChallenge: KdfChallenge);
     declare_entry!(MedianTime: BlockTimestamp);
     declare_entry!(StoreChainInfo: ChainInfo);
     declare_entry!(LookaheadSize: u32);
+    declare_entry!(StoreRootKeysInfo: RootKeysInfo);
     declare_entry!(LegacyVfrPubKey: AccountVrfKeys);
     declare_entry!(WalletType: wallet_type::WalletType);
+    declare_entry!(ConfirmationThreshold: u64);
 }
 
 #[derive(PartialEq, Clone)]
@@ -161,6 +170,10 @@ macro_rules! impl_read_ops {
                 self.read_value::<well_known::StoreVersion>().map(|v| v.unwrap_or_default())
             }
 
+            fn get_software_version(&self) -> crate::Result<Option<String>> {
+                self.read_value::<well_known::SoftwareVersion>()
+            }
+
             fn get_wallet_type(&self) -> crate::Result<WalletType> {
                 self.read_value::<well_known::WalletType>()
                     .and_then(|v| v.ok_or(crate::Error::WalletDbInconsistentState))
@@ -311,6 +324,13 @@ macro_rules! impl_read_ops {
                     .map(Iterator::collect)
             }
 
+            fn get_keychain_used_indexes(
+                &self,
+                id: &AccountKeyPurposeId,
+            ) -> crate::Result<Option<BTreeSet<U31>>> {
+                self.read::<db::DBKeychainUsedIndexes, _, _>(id)
+            }
+
             fn get_public_key(
                 &self,
                 id: &AccountDerivationPathId,
@@ -337,6 +357,65 @@ macro_rules! impl_read_ops {
                 let lookahead = self.read_value::<well_known::LookaheadSize>()?;
                 lookahead.ok_or(crate::Error::WalletDbInconsistentState)
             }
+
+            fn get_confirmation_threshold(&self) -> crate::Result<Option<u64>> {
+                self.read_value::<well_known::ConfirmationThreshold>()
+            }
+
+            fn get_root_keys_info(&self) -> crate::Result<Option<RootKeysInfo>> {
+                self.read_value::<well_known::StoreRootKeysInfo>()
+            }
+
+            fn get_idempotency_record(
+                &self,
+                key: &IdempotencyKey,
+            ) -> crate::Result<Option<IdempotencyRecord>> {
+                self.read::<db::DBIdempotencyRecords, _, _>(key)
+            }
+
+            fn get_idempotency_records(
+                &self,
+            ) -> crate::Result<BTreeMap<IdempotencyKey, IdempotencyRecord>> {
+                self.storage
+                    .get::<db::DBIdempotencyRecords, _>()
+                    .prefix_iter_decoded(&())
+                    .map_err(crate::Error::from)
+                    .map(Iterator::collect)
+            }
+
+            fn get_transaction_labels(&self) -> crate::Result<BTreeMap<Id<Transaction>, Label>> {
+                self.storage
+                    .get::<db::DBTransactionLabels, _>()
+                    .prefix_iter_decoded(&())
+                    .map_err(crate::Error::from)
+                    .map(Iterator::collect)
+            }
+
+            fn get_address_labels(&self) -> crate::Result<BTreeMap<Destination, Label>> {
+                self.storage
+                    .get::<db::DBAddressLabels, _>()
+                    .prefix_iter_decoded(&())
+                    .map_err(crate::Error::from)
+                    .map(Iterator::collect)
+            }
+
+            fn get_spending_policy(
+                &self,
+                account_id: &AccountId,
+            ) -> crate::Result<Option<SpendingPolicy>> {
+                self.read::<db::DBSpendingPolicies, _, _>(account_id)
+            }
+
+            fn get_account_outflows(
+                &self,
+                account_id: &AccountId,
+            ) -> crate::Result<BTreeMap<AccountOutflowId, Amount>> {
+                self.storage
+                    .get::<db::DBAccountOutflows, _>()
+                    .prefix_iter_decoded(account_id)
+                    .map_err(crate::Error::from)
+                    .map(Iterator::collect)
+            }
         }
 
         impl<'st, B: storage::Backend> $TxType<'st, B> {
@@ -443,6 +522,10 @@ macro_rules! impl_write_ops {
                 self.write_value::<well_known::StoreVersion>(&version)
             }
 
+            fn set_software_version(&mut self, version: &str) -> crate::Result<()> {
+                self.write_value::<well_known::SoftwareVersion>(&version.to_owned())
+            }
+
             fn set_wallet_type(&mut self, wallet_type: WalletType) -> crate::Result<()> {
                 self.write_value::<well_known::WalletType>(&wallet_type)
             }
@@ -488,6 +571,78 @@ macro_rules! impl_write_ops {
                 })
             }
 
+            fn set_idempotency_record(
+                &mut self,
+                key: &IdempotencyKey,
+                record: &IdempotencyRecord,
+            ) -> crate::Result<()> {
+                self.write::<db::DBIdempotencyRecords, _, _, _>(key, record)
+            }
+
+            fn del_idempotency_record(&mut self, key: &IdempotencyKey) -> crate::Result<()> {
+                self.storage
+                    .get_mut::<db::DBIdempotencyRecords, _>()
+                    .del(key)
+                    .map_err(Into::into)
+            }
+
+            fn set_transaction_label(
+                &mut self,
+                tx_id: &Id<Transaction>,
+                label: &Label,
+            ) -> crate::Result<()> {
+                self.write::<db::DBTransactionLabels, _, _, _>(tx_id, label)
+            }
+
+            fn del_transaction_label(&mut self, tx_id: &Id<Transaction>) -> crate::Result<()> {
+                self.storage
+                    .get_mut::<db::DBTransactionLabels, _>()
+                    .del(tx_id)
+                    .map_err(Into::into)
+            }
+
+            fn set_address_label(
+                &mut self,
+                address: &Destination,
+                label: &Label,
+            ) -> crate::Result<()> {
+                self.write::<db::DBAddressLabels, _, _, _>(address, label)
+            }
+
+            fn del_address_label(&mut self, address: &Destination) -> crate::Result<()> {
+                self.storage
+                    .get_mut::<db::DBAddressLabels, _>()
+                    .del(address)
+                    .map_err(Into::into)
+            }
+
+            fn set_spending_policy(
+                &mut self,
+                account_id: &AccountId,
+                policy: &SpendingPolicy,
+            ) -> crate::Result<()> {
+                self.write::<db::DBSpendingPolicies, _, _, _>(account_id, policy)
+            }
+
+            fn del_spending_policy(&mut self, account_id: &AccountId) -> crate::Result<()> {
+                self.storage
+                    .get_mut::<db::DBSpendingPolicies, _>()
+                    .del(account_id)
+                    .map_err(Into::into)
+            }
+
+            fn set_account_outflow(
+                &mut self,
+                id: &AccountOutflowId,
+                amount: Amount,
+            ) -> crate::Result<()> {
+                self.write::<db::DBAccountOutflows, _, _, _>(id, amount)
+            }
+
+            fn del_account_outflow(&mut self, id: &AccountOutflowId) -> crate::Result<()> {
+                self.storage.get_mut::<db::DBAccountOutflows, _>().del(id).map_err(Into::into)
+            }
+
             fn set_account_unconfirmed_tx_counter(
                 &mut self,
                 id: &AccountId,
@@ -574,6 +729,21 @@ macro_rules! impl_write_ops {
                     .map_err(Into::into)
             }
 
+            fn set_keychain_used_indexes(
+                &mut self,
+                id: &AccountKeyPurposeId,
+                used_indexes: &BTreeSet<U31>,
+            ) -> crate::Result<()> {
+                self.write::<db::DBKeychainUsedIndexes, _, _, _>(id, used_indexes)
+            }
+
+            fn del_keychain_used_indexes(&mut self, id: &AccountKeyPurposeId) -> crate::Result<()> {
+                self.storage
+                    .get_mut::<db::DBKeychainUsedIndexes, _>()
+                    .del(id)
+                    .map_err(Into::into)
+            }
+
             fn del_vrf_keychain_usage_state(&mut self, id: &AccountId) -> crate::Result<()> {
                 self.storage
                     .get_mut::<db::DBVrfKeychainUsageStates, _>()
@@ -600,6 +770,14 @@ macro_rules! impl_write_ops {
             fn set_lookahead_size(&mut self, lookahead_size: u32) -> crate::Result<()> {
                 self.write_value::<well_known::LookaheadSize>(&lookahead_size)
             }
+
+            fn set_confirmation_threshold(&mut self, threshold: u64) -> crate::Result<()> {
+                self.write_value::<well_known::ConfirmationThreshold>(&threshold)
+            }
+
+            fn set_root_keys_info(&mut self, info: &RootKeysInfo) -> crate::Result<()> {
+                self.write_value::<well_known::StoreRootKeysInfo>(info)
+            }
         }
 
         impl<'st, B: storage::Backend> $TxType<'st, B> {
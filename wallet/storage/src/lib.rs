@@ -21,22 +21,26 @@ pub mod schema;
 
 use common::{
     address::{Address, AddressError},
-    chain::{block::timestamp::BlockTimestamp, Destination, SignedTransaction},
+    chain::{block::timestamp::BlockTimestamp, Destination, SignedTransaction, Transaction},
+    primitives::{Amount, Id},
 };
 use crypto::{
     kdf::KdfChallenge,
-    key::{extended::ExtendedPublicKey, PrivateKey},
+    key::{extended::ExtendedPublicKey, hdkd::u31::U31, PrivateKey},
     symkey::SymmetricKey,
 };
 pub use internal::{Store, StoreTxRo, StoreTxRoUnlocked, StoreTxRw, StoreTxRwUnlocked};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use wallet_types::{
-    account_id::{AccountAddress, AccountPublicKey},
+    account_id::{AccountAddress, AccountOutflowId, AccountPublicKey},
     account_info::{AccountVrfKeys, StandaloneMultisig, StandaloneWatchOnlyKey},
     chain_info::ChainInfo,
-    keys::RootKeys,
+    idempotency::{IdempotencyKey, IdempotencyRecord},
+    keys::{RootKeys, RootKeysInfo},
+    label::Label,
     seed_phrase::SerializableSeedPhrase,
+    spending_policy::SpendingPolicy,
     wallet_type::WalletType,
     AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId, AccountWalletCreatedTxId,
     AccountWalletTxId, KeychainUsageState, WalletTx,
@@ -63,6 +67,16 @@ pub enum Error {
     CannotDecodeAddress(#[from] AddressError),
     #[error("Wallet DB is not in a consistent state")]
     WalletDbInconsistentState,
+    #[error("Invalid wallet backup format")]
+    BackupInvalidFormat,
+    #[error("Unsupported wallet backup version {0}")]
+    BackupUnsupportedVersion(u32),
+    #[error("The wallet backup is encrypted with a password")]
+    BackupPasswordRequired,
+    #[error("The wallet backup is corrupted or the password is invalid")]
+    BackupAuthenticationFailed,
+    #[error("Unknown DB map in the wallet backup: {0}")]
+    BackupUnknownMap(String),
 }
 
 /// Possibly failing result of wallet storage query
@@ -72,6 +86,8 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub trait WalletStorageReadLocked {
     /// Get storage version
     fn get_storage_version(&self) -> Result<u32>;
+    /// Get the version of the wallet software that has created or last upgraded the storage
+    fn get_software_version(&self) -> Result<Option<String>>;
     fn get_wallet_type(&self) -> Result<WalletType>;
     fn get_chain_info(&self) -> Result<ChainInfo>;
     fn get_transaction(&self, id: &AccountWalletTxId) -> Result<Option<WalletTx>>;
@@ -111,6 +127,7 @@ pub trait WalletStorageReadLocked {
         &self,
         account_id: &AccountId,
     ) -> Result<BTreeMap<AccountKeyPurposeId, KeychainUsageState>>;
+    fn get_keychain_used_indexes(&self, id: &AccountKeyPurposeId) -> Result<Option<BTreeSet<U31>>>;
     fn get_public_key(&self, id: &AccountDerivationPathId) -> Result<Option<ExtendedPublicKey>>;
     fn get_public_keys(
         &self,
@@ -118,6 +135,17 @@ pub trait WalletStorageReadLocked {
     ) -> Result<BTreeMap<AccountDerivationPathId, ExtendedPublicKey>>;
     fn get_median_time(&self) -> Result<Option<BlockTimestamp>>;
     fn get_lookahead_size(&self) -> Result<u32>;
+    fn get_confirmation_threshold(&self) -> Result<Option<u64>>;
+    fn get_root_keys_info(&self) -> Result<Option<RootKeysInfo>>;
+    fn get_idempotency_record(&self, key: &IdempotencyKey) -> Result<Option<IdempotencyRecord>>;
+    fn get_idempotency_records(&self) -> Result<BTreeMap<IdempotencyKey, IdempotencyRecord>>;
+    fn get_transaction_labels(&self) -> Result<BTreeMap<Id<Transaction>, Label>>;
+    fn get_address_labels(&self) -> Result<BTreeMap<Destination, Label>>;
+    fn get_spending_policy(&self, account_id: &AccountId) -> Result<Option<SpendingPolicy>>;
+    fn get_account_outflows(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<BTreeMap<AccountOutflowId, Amount>>;
 }
 
 /// Queries on persistent wallet data with access to encrypted data
@@ -140,6 +168,8 @@ pub trait WalletStorageEncryptionRead {
 pub trait WalletStorageWriteLocked: WalletStorageReadLocked {
     /// Set storage version
     fn set_storage_version(&mut self, version: u32) -> Result<()>;
+    /// Set the version of the wallet software that has created or upgraded the storage
+    fn set_software_version(&mut self, version: &str) -> Result<()>;
     fn set_wallet_type(&mut self, wallet_type: WalletType) -> Result<()>;
     fn set_chain_info(&mut self, chain_info: &ChainInfo) -> Result<()>;
     fn set_transaction(&mut self, id: &AccountWalletTxId, tx: &WalletTx) -> Result<()>;
@@ -186,6 +216,12 @@ pub trait WalletStorageWriteLocked: WalletStorageReadLocked {
         usage_state: &KeychainUsageState,
     ) -> Result<()>;
     fn del_keychain_usage_state(&mut self, id: &AccountKeyPurposeId) -> Result<()>;
+    fn set_keychain_used_indexes(
+        &mut self,
+        id: &AccountKeyPurposeId,
+        used_indexes: &BTreeSet<U31>,
+    ) -> Result<()>;
+    fn del_keychain_used_indexes(&mut self, id: &AccountKeyPurposeId) -> Result<()>;
     fn del_vrf_keychain_usage_state(&mut self, id: &AccountId) -> Result<()>;
     fn set_public_key(
         &mut self,
@@ -195,8 +231,28 @@ pub trait WalletStorageWriteLocked: WalletStorageReadLocked {
     fn det_public_key(&mut self, id: &AccountDerivationPathId) -> Result<()>;
     fn set_median_time(&mut self, median_time: BlockTimestamp) -> Result<()>;
     fn set_lookahead_size(&mut self, lookahead_size: u32) -> Result<()>;
+    fn set_confirmation_threshold(&mut self, threshold: u64) -> Result<()>;
+    fn set_root_keys_info(&mut self, info: &RootKeysInfo) -> Result<()>;
     fn clear_public_keys(&mut self) -> Result<()>;
     fn clear_addresses(&mut self) -> Result<()>;
+    fn set_idempotency_record(
+        &mut self,
+        key: &IdempotencyKey,
+        record: &IdempotencyRecord,
+    ) -> Result<()>;
+    fn del_idempotency_record(&mut self, key: &IdempotencyKey) -> Result<()>;
+    fn set_transaction_label(&mut self, tx_id: &Id<Transaction>, label: &Label) -> Result<()>;
+    fn del_transaction_label(&mut self, tx_id: &Id<Transaction>) -> Result<()>;
+    fn set_address_label(&mut self, address: &Destination, label: &Label) -> Result<()>;
+    fn del_address_label(&mut self, address: &Destination) -> Result<()>;
+    fn set_spending_policy(
+        &mut self,
+        account_id: &AccountId,
+        policy: &SpendingPolicy,
+    ) -> Result<()>;
+    fn del_spending_policy(&mut self, account_id: &AccountId) -> Result<()>;
+    fn set_account_outflow(&mut self, id: &AccountOutflowId, amount: Amount) -> Result<()>;
+    fn del_account_outflow(&mut self, id: &AccountOutflowId) -> Result<()>;
 }
 
 /// Modifying operations on persistent wallet data with access to encrypted data
@@ -15,16 +15,24 @@
 
 //! Wallet database schema
 
-use common::chain::SignedTransaction;
-use crypto::key::extended::ExtendedPublicKey;
+use std::collections::BTreeSet;
+
+use common::{
+    chain::{Destination, SignedTransaction, Transaction},
+    primitives::{Amount, Id},
+};
+use crypto::key::{extended::ExtendedPublicKey, hdkd::u31::U31};
 use utils::maybe_encrypted::MaybeEncrypted;
 use wallet_types::{
-    account_id::{AccountAddress, AccountPublicKey},
+    account_id::{AccountAddress, AccountOutflowId, AccountPublicKey},
     account_info::{
         AccountVrfKeys, StandaloneMultisig, StandalonePrivateKey, StandaloneWatchOnlyKey,
     },
+    idempotency::{IdempotencyKey, IdempotencyRecord},
     keys::{RootKeyConstant, RootKeys},
+    label::Label,
     seed_phrase::{SeedPhraseConstant, SerializableSeedPhrase},
+    spending_policy::SpendingPolicy,
     AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId, AccountWalletCreatedTxId,
     AccountWalletTxId, KeychainUsageState, WalletTx,
 };
@@ -38,6 +46,8 @@ storage::decl_schema! {
         pub DBAccounts: Map<AccountId, AccountInfo>,
         /// Store keychain usage states
         pub DBKeychainUsageStates: Map<AccountKeyPurposeId, KeychainUsageState>,
+        /// Store the indexes of the keychain addresses that have received funds
+        pub DBKeychainUsedIndexes: Map<AccountKeyPurposeId, BTreeSet<U31>>,
         /// Store keychain usage states
         pub DBVrfKeychainUsageStates: Map<AccountId, KeychainUsageState>,
         /// Store for all the private keys in this wallet
@@ -62,5 +72,15 @@ storage::decl_schema! {
         pub DBStandaloneMultisigKeys: Map<AccountAddress, StandaloneMultisig>,
         /// Store for standalone private keys added to accounts
         pub DBStandalonePrivateKeys: Map<AccountPublicKey, StandalonePrivateKey>,
+        /// Store for the transactions created by the requests with an idempotency key
+        pub DBIdempotencyRecords: Map<IdempotencyKey, IdempotencyRecord>,
+        /// Store for the user labels of transactions
+        pub DBTransactionLabels: Map<Id<Transaction>, Label>,
+        /// Store for the user labels of addresses
+        pub DBAddressLabels: Map<Destination, Label>,
+        /// Store for each account's spending policy
+        pub DBSpendingPolicies: Map<AccountId, SpendingPolicy>,
+        /// Store for the coins sent out of the accounts recently, used for the daily caps
+        pub DBAccountOutflows: Map<AccountOutflowId, Amount>,
     }
 }
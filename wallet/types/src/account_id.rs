@@ -16,7 +16,7 @@
 use crate::keys::KeyPurpose;
 use common::{
     address::pubkeyhash::PublicKeyHash,
-    chain::{Destination, OutPointSourceId, Transaction},
+    chain::{block::timestamp::BlockTimestamp, Destination, OutPointSourceId, Transaction},
     primitives::Id,
 };
 use crypto::key::hdkd::derivation_path::DerivationPath;
@@ -83,3 +83,5 @@ pub type AccountDerivationPathId = AccountPrefixedId<DerivationPath>;
 pub type AccountKeyPurposeId = AccountPrefixedId<KeyPurpose>;
 pub type AccountAddress = AccountPrefixedId<Destination>;
 pub type AccountPublicKey = AccountPrefixedId<PublicKey>;
+/// The coins sent out of an account by a transaction at the given time
+pub type AccountOutflowId = AccountPrefixedId<(BlockTimestamp, Id<Transaction>)>;
@@ -0,0 +1,154 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A set of an account's public keys, exported from a cold wallet to be watched by a hot one
+
+use common::{chain::GenBlock, primitives::Id};
+use crypto::key::{PrivateKey, PublicKey, Signature, SignatureError};
+use randomness::{CryptoRng, Rng};
+use serialization::{Decode, Encode};
+
+use crate::keys::WalletFingerprint;
+
+/// The hot wallet can't derive the addresses beyond the imported ones, so the user is warned
+/// to export more when fewer unused receive addresses than this remain.
+pub const LOW_UNUSED_ADDRESSES_THRESHOLD: usize = 5;
+
+/// AddressSet errors
+#[derive(thiserror::Error, Debug, Eq, PartialEq)]
+pub enum AddressSetError {
+    #[error("Failed to sign the address set: {0}")]
+    Signing(#[from] SignatureError),
+    #[error("The signature of the address set is invalid")]
+    InvalidSignature,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct AddressSet {
+    /// The genesis block id of the chain the addresses are used on
+    pub genesis_block_id: Id<GenBlock>,
+    /// The public key of the account the addresses are derived from
+    pub account_public_key: PublicKey,
+    /// The receive public keys, starting from the index 0
+    pub receive_keys: Vec<PublicKey>,
+    /// The change public keys, starting from the index 0
+    pub change_keys: Vec<PublicKey>,
+}
+
+impl AddressSet {
+    /// The fingerprint of the account's public key, which can be compared with the one shown
+    /// by the cold wallet
+    pub fn account_fingerprint(&self) -> WalletFingerprint {
+        WalletFingerprint::from_public_key(&self.account_public_key)
+    }
+}
+
+/// The result of importing an address set into a watch-only account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportedAddressSet {
+    pub account_fingerprint: WalletFingerprint,
+    /// The number of the addresses that weren't watched before
+    pub added_addresses: usize,
+    /// The number of the imported receive addresses that haven't received funds yet
+    /// according to the transactions known to the wallet
+    pub unused_receive_addresses: usize,
+}
+
+impl ImportedAddressSet {
+    pub fn is_low_on_unused_addresses(&self) -> bool {
+        self.unused_receive_addresses < LOW_UNUSED_ADDRESSES_THRESHOLD
+    }
+}
+
+/// An address set signed with the account's private key, so that the hot wallet can check
+/// that it hasn't been tampered with
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct SignedAddressSet {
+    address_set: AddressSet,
+    signature: Signature,
+}
+
+impl SignedAddressSet {
+    pub fn new(
+        address_set: AddressSet,
+        account_private_key: &PrivateKey,
+        rng: impl Rng + CryptoRng,
+    ) -> Result<Self, AddressSetError> {
+        let signature = account_private_key.sign_message(&address_set.encode(), rng)?;
+        Ok(Self {
+            address_set,
+            signature,
+        })
+    }
+
+    /// The address set, whose signature hasn't been checked
+    pub fn address_set(&self) -> &AddressSet {
+        &self.address_set
+    }
+
+    /// Check the signature and return the address set
+    pub fn verify(self) -> Result<AddressSet, AddressSetError> {
+        utils::ensure!(
+            self.address_set
+                .account_public_key
+                .verify_message(&self.signature, &self.address_set.encode()),
+            AddressSetError::InvalidSignature
+        );
+        Ok(self.address_set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::primitives::H256;
+    use crypto::key::KeyKind;
+    use rstest::rstest;
+    use test_utils::random::{make_seedable_rng, Seed};
+
+    use super::*;
+
+    fn new_public_key(rng: &mut (impl Rng + CryptoRng)) -> PublicKey {
+        PrivateKey::new_from_rng(rng, KeyKind::Secp256k1Schnorr).1
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn sign_and_verify(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let (account_private_key, account_public_key) =
+            PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let address_set = AddressSet {
+            genesis_block_id: Id::new(H256::zero()),
+            account_public_key,
+            receive_keys: vec![new_public_key(&mut rng), new_public_key(&mut rng)],
+            change_keys: vec![new_public_key(&mut rng)],
+        };
+
+        let signed =
+            SignedAddressSet::new(address_set.clone(), &account_private_key, &mut rng).unwrap();
+        assert_eq!(signed.clone().verify(), Ok(address_set.clone()));
+
+        // A tampered address set
+        let mut tampered = signed.clone();
+        tampered.address_set.receive_keys[1] = new_public_key(&mut rng);
+        assert_eq!(tampered.verify(), Err(AddressSetError::InvalidSignature));
+
+        // Signed with another key
+        let (other_private_key, _) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let forged = SignedAddressSet::new(address_set, &other_private_key, &mut rng).unwrap();
+        assert_eq!(forged.verify(), Err(AddressSetError::InvalidSignature));
+    }
+}
@@ -0,0 +1,32 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::currency::CurrencyAmountMap;
+
+/// The number of confirmations after which a transaction is considered final,
+/// if the wallet has no threshold configured
+pub const DEFAULT_CONFIRMATION_THRESHOLD: u64 = 1;
+
+/// The balance of an account split by the number of confirmations of the transactions
+/// that created the outputs
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct BalancesByConfirmations {
+    /// The outputs with at least the threshold number of confirmations
+    pub confirmed: CurrencyAmountMap,
+    /// The outputs that are in the chain but don't have enough confirmations yet
+    pub pending: CurrencyAmountMap,
+    /// The outputs of the transactions in the mempool
+    pub unconfirmed: CurrencyAmountMap,
+}
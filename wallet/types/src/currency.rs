@@ -0,0 +1,243 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use common::{
+    chain::tokens::TokenId,
+    primitives::{Amount, Id, H256},
+};
+
+/// The string form of [Currency::Coin]
+const COIN_STR: &str = "coin";
+
+/// Either the coin of the chain or a token
+///
+/// The coin is ordered before all the tokens, the tokens are ordered by their ids.
+/// In serialized form the coin is the string "coin" and a token is its id in hex.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Hash)]
+pub enum Currency {
+    Coin,
+    Token(TokenId),
+}
+
+impl Currency {
+    pub fn token_id(&self) -> Option<&TokenId> {
+        match self {
+            Self::Coin => None,
+            Self::Token(token_id) => Some(token_id),
+        }
+    }
+}
+
+impl From<Option<TokenId>> for Currency {
+    fn from(token_id: Option<TokenId>) -> Self {
+        token_id.map_or(Self::Coin, Self::Token)
+    }
+}
+
+impl std::fmt::Display for Currency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Coin => f.write_str(COIN_STR),
+            Self::Token(token_id) => write!(f, "{token_id:x}"),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid currency: {0}")]
+pub struct CurrencyParseError(String);
+
+impl FromStr for Currency {
+    type Err = CurrencyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == COIN_STR {
+            return Ok(Self::Coin);
+        }
+        let hash = H256::from_str(s).map_err(|_| CurrencyParseError(s.to_owned()))?;
+        Ok(Self::Token(Id::new(hash)))
+    }
+}
+
+impl serde::Serialize for Currency {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Currency {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(d)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum CurrencyAmountError {
+    #[error("Amount overflow for currency {0}")]
+    Overflow(Currency),
+    #[error("Amount underflow for currency {0}")]
+    Underflow(Currency),
+}
+
+/// Amounts of coins and tokens, keyed by currency in a deterministic order.
+///
+/// A currency that was never added has a zero amount.
+#[derive(PartialEq, Eq, Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct CurrencyAmountMap(BTreeMap<Currency, Amount>);
+
+impl CurrencyAmountMap {
+    pub fn new() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    pub fn get(&self, currency: &Currency) -> Amount {
+        self.0.get(currency).copied().unwrap_or(Amount::ZERO)
+    }
+
+    pub fn coins(&self) -> Amount {
+        self.get(&Currency::Coin)
+    }
+
+    pub fn checked_add(
+        &mut self,
+        currency: Currency,
+        amount: Amount,
+    ) -> Result<(), CurrencyAmountError> {
+        let total = self.get(&currency);
+        let total =
+            (total + amount).ok_or_else(|| CurrencyAmountError::Overflow(currency.clone()))?;
+        self.0.insert(currency, total);
+        Ok(())
+    }
+
+    pub fn checked_sub(
+        &mut self,
+        currency: Currency,
+        amount: Amount,
+    ) -> Result<(), CurrencyAmountError> {
+        let total = self.get(&currency);
+        let total =
+            (total - amount).ok_or_else(|| CurrencyAmountError::Underflow(currency.clone()))?;
+        self.0.insert(currency, total);
+        Ok(())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Currency, &Amount)> {
+        self.0.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Split into the amount of coins and the amounts of the individual tokens
+    pub fn into_coins_and_tokens(mut self) -> (Amount, BTreeMap<TokenId, Amount>) {
+        let coins = self.0.remove(&Currency::Coin).unwrap_or(Amount::ZERO);
+        let tokens = self
+            .0
+            .into_iter()
+            .filter_map(|(currency, amount)| {
+                currency.token_id().map(|token_id| (*token_id, amount))
+            })
+            .collect();
+        (coins, tokens)
+    }
+}
+
+impl From<BTreeMap<Currency, Amount>> for CurrencyAmountMap {
+    fn from(amounts: BTreeMap<Currency, Amount>) -> Self {
+        Self(amounts)
+    }
+}
+
+impl IntoIterator for CurrencyAmountMap {
+    type Item = (Currency, Amount);
+    type IntoIter = std::collections::btree_map::IntoIter<Currency, Amount>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn currency_serde() {
+        let token_id = TokenId::new(H256([0x50; 32]));
+        let token_json = r#""5050505050505050505050505050505050505050505050505050505050505050""#;
+        for (currency, json) in
+            [(Currency::Coin, r#""coin""#), (Currency::Token(token_id), token_json)]
+        {
+            assert_eq!(serde_json::to_string(&currency).unwrap(), json);
+            assert_eq!(serde_json::from_str::<Currency>(json).unwrap(), currency);
+        }
+        assert!(serde_json::from_str::<Currency>("\"tokens\"").is_err());
+
+        // The coin is always the first key
+        let mut amounts = CurrencyAmountMap::new();
+        amounts.checked_add(Currency::Token(token_id), Amount::from_atoms(2)).unwrap();
+        amounts.checked_add(Currency::Coin, Amount::from_atoms(1)).unwrap();
+        let json = serde_json::to_string(&amounts).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"coin":{"atoms":"1"},"#,
+                r#""5050505050505050505050505050505050505050505050505050505050505050":{"atoms":"2"}}"#,
+            )
+        );
+        assert_eq!(
+            serde_json::from_str::<CurrencyAmountMap>(&json).unwrap(),
+            amounts
+        );
+    }
+
+    #[test]
+    fn checked_arithmetic() {
+        let token = Currency::Token(TokenId::new(H256::from_low_u64_be(1)));
+        let mut amounts = CurrencyAmountMap::new();
+        assert_eq!(amounts.get(&token), Amount::ZERO);
+
+        amounts.checked_add(Currency::Coin, Amount::MAX).unwrap();
+        assert_eq!(
+            amounts.checked_add(Currency::Coin, Amount::from_atoms(1)),
+            Err(CurrencyAmountError::Overflow(Currency::Coin))
+        );
+        // A failed operation leaves the amount unchanged
+        assert_eq!(amounts.coins(), Amount::MAX);
+
+        // Overflow in one currency doesn't affect the others
+        amounts.checked_add(token.clone(), Amount::from_atoms(5)).unwrap();
+        amounts.checked_sub(token.clone(), Amount::from_atoms(3)).unwrap();
+        assert_eq!(amounts.get(&token), Amount::from_atoms(2));
+        assert_eq!(
+            amounts.checked_sub(token.clone(), Amount::from_atoms(3)),
+            Err(CurrencyAmountError::Underflow(token.clone()))
+        );
+        assert_eq!(amounts.get(&token), Amount::from_atoms(2));
+
+        let (coins, tokens) = amounts.into_coins_and_tokens();
+        assert_eq!(coins, Amount::MAX);
+        assert_eq!(
+            tokens,
+            BTreeMap::from([(*token.token_id().unwrap(), Amount::from_atoms(2))])
+        );
+    }
+}
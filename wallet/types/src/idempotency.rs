@@ -0,0 +1,102 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    chain::{block::timestamp::BlockTimestamp, Transaction},
+    primitives::{Id, H256},
+};
+use serialization::{Decode, Encode};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum IdempotencyKeyError {
+    #[error("Idempotency key is empty")]
+    Empty,
+    #[error("Idempotency key length {0} exceeds the maximum length {max}", max = IdempotencyKey::MAX_LEN)]
+    TooLong(usize),
+}
+
+/// A client-supplied key that identifies a request creating a transaction, so that retrying the
+/// request doesn't create another transaction
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
+pub struct IdempotencyKey(String);
+
+impl IdempotencyKey {
+    pub const MAX_LEN: usize = 128;
+
+    pub fn new(key: String) -> Result<Self, IdempotencyKeyError> {
+        match key.len() {
+            0 => Err(IdempotencyKeyError::Empty),
+            len if len > Self::MAX_LEN => Err(IdempotencyKeyError::TooLong(len)),
+            _ => Ok(Self(key)),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// The transaction created by a request with an idempotency key
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct IdempotencyRecord {
+    /// The hash of the request parameters, a key can only be reused with the same parameters
+    request_hash: H256,
+    tx_id: Id<Transaction>,
+    created_at: BlockTimestamp,
+}
+
+impl IdempotencyRecord {
+    pub fn new(request_hash: H256, tx_id: Id<Transaction>, created_at: BlockTimestamp) -> Self {
+        Self {
+            request_hash,
+            tx_id,
+            created_at,
+        }
+    }
+
+    pub fn request_hash(&self) -> &H256 {
+        &self.request_hash
+    }
+
+    pub fn tx_id(&self) -> Id<Transaction> {
+        self.tx_id
+    }
+
+    pub fn created_at(&self) -> BlockTimestamp {
+        self.created_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_length() {
+        assert_eq!(
+            IdempotencyKey::new(String::new()),
+            Err(IdempotencyKeyError::Empty)
+        );
+
+        let key = "k".repeat(IdempotencyKey::MAX_LEN);
+        assert_eq!(IdempotencyKey::new(key.clone()).unwrap().as_str(), key);
+
+        let key = "k".repeat(IdempotencyKey::MAX_LEN + 1);
+        assert_eq!(
+            IdempotencyKey::new(key),
+            Err(IdempotencyKeyError::TooLong(IdempotencyKey::MAX_LEN + 1))
+        );
+    }
+}
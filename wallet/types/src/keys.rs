@@ -13,10 +13,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::{fmt::Display, str::FromStr};
+
 use crate::keys::KeyPurpose::{Change, ReceiveFunds};
-use crypto::key::extended::ExtendedPrivateKey;
+use common::address::pubkeyhash::PublicKeyHash;
+use crypto::key::extended::{ExtendedPrivateKey, ExtendedPublicKey};
 use crypto::key::hdkd::child_number::ChildNumber;
 use crypto::key::hdkd::u31::U31;
+use crypto::key::PublicKey;
 use crypto::vrf::ExtendedVRFPrivateKey;
 use serialization::{Decode, Encode};
 
@@ -130,6 +134,97 @@ pub struct RootKeys {
     pub root_vrf_key: ExtendedVRFPrivateKey,
 }
 
+/// WalletFingerprint errors
+#[derive(thiserror::Error, Debug, Eq, PartialEq)]
+pub enum WalletFingerprintError {
+    #[error("Invalid wallet fingerprint: {0}")]
+    InvalidFingerprint(String),
+}
+
+/// A short hash of the master public key of the wallet.
+///
+/// Wallets created from the same mnemonic and passphrase have the same fingerprint, so it can be
+/// used to check that a restored wallet matches the original one without exposing any secrets.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct WalletFingerprint([u8; 4]);
+
+impl WalletFingerprint {
+    pub fn from_root_key(root_key: &ExtendedPrivateKey) -> Self {
+        Self::from_public_key(&ExtendedPublicKey::from_private_key(root_key).into_public_key())
+    }
+
+    pub fn from_public_key(public_key: &PublicKey) -> Self {
+        let hash = PublicKeyHash::from(public_key);
+        let mut fingerprint = [0; 4];
+        fingerprint.copy_from_slice(&hash.as_bytes()[..4]);
+        Self(fingerprint)
+    }
+}
+
+impl Display for WalletFingerprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", hex::encode(self.0))
+    }
+}
+
+impl FromStr for WalletFingerprint {
+    type Err = WalletFingerprintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes =
+            hex::decode(s).map_err(|_| WalletFingerprintError::InvalidFingerprint(s.to_owned()))?;
+        let fingerprint = bytes
+            .try_into()
+            .map_err(|_| WalletFingerprintError::InvalidFingerprint(s.to_owned()))?;
+        Ok(Self(fingerprint))
+    }
+}
+
+/// DescriptorType errors
+#[derive(thiserror::Error, Debug, Eq, PartialEq)]
+pub enum DescriptorTypeError {
+    #[error("Unknown descriptor type: {0}, expected one of: pkh, pk")]
+    UnknownDescriptorType(String),
+}
+
+/// The type of the output descriptor describing the addresses of an account
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DescriptorType {
+    /// Public key hash destinations, which are used for the receiving addresses
+    PublicKeyHash,
+    /// Public key destinations
+    PublicKey,
+}
+
+impl Display for DescriptorType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PublicKeyHash => write!(f, "pkh"),
+            Self::PublicKey => write!(f, "pk"),
+        }
+    }
+}
+
+impl FromStr for DescriptorType {
+    type Err = DescriptorTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pkh" => Ok(Self::PublicKeyHash),
+            "pk" => Ok(Self::PublicKey),
+            _ => Err(DescriptorTypeError::UnknownDescriptorType(s.to_owned())),
+        }
+    }
+}
+
+/// Information about how the root keys were derived, it doesn't contain any secrets
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct RootKeysInfo {
+    pub fingerprint: WalletFingerprint,
+    /// Whether a non-empty BIP39 passphrase was used along the mnemonic
+    pub passphrase_used: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -157,4 +252,34 @@ mod tests {
         assert_eq!(state.last_issued(), Some(index_1));
         assert_eq!(state.last_used(), Some(index_1));
     }
+
+    #[test]
+    fn descriptor_type_from_str() {
+        for descriptor_type in [DescriptorType::PublicKeyHash, DescriptorType::PublicKey] {
+            assert_eq!(descriptor_type.to_string().parse(), Ok(descriptor_type));
+        }
+        assert_eq!(
+            "wpkh".parse::<DescriptorType>(),
+            Err(DescriptorTypeError::UnknownDescriptorType(
+                "wpkh".to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn wallet_fingerprint_from_str() {
+        let fingerprint = WalletFingerprint([0x01, 0xab, 0xcd, 0xef]);
+        assert_eq!(fingerprint.to_string(), "01abcdef");
+        assert_eq!("01abcdef".parse(), Ok(fingerprint));
+        assert_eq!("01ABCDEF".parse(), Ok(fingerprint));
+
+        for invalid in ["", "01abcd", "01abcdef00", "01abcdeg"] {
+            assert_eq!(
+                invalid.parse::<WalletFingerprint>(),
+                Err(WalletFingerprintError::InvalidFingerprint(
+                    invalid.to_owned()
+                ))
+            );
+        }
+    }
 }
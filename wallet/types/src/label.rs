@@ -0,0 +1,96 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    chain::{Destination, Transaction},
+    primitives::Id,
+};
+use serialization::{Decode, Encode};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum LabelError {
+    #[error("Label is empty")]
+    Empty,
+    #[error("Label length {0} exceeds the maximum length {max}", max = Label::MAX_LEN)]
+    TooLong(usize),
+}
+
+/// A private note attached to a transaction or an address, it's only stored in the wallet
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
+pub struct Label(String);
+
+impl Label {
+    pub const MAX_LEN: usize = 256;
+
+    pub fn new(label: String) -> Result<Self, LabelError> {
+        match label.len() {
+            0 => Err(LabelError::Empty),
+            len if len > Self::MAX_LEN => Err(LabelError::TooLong(len)),
+            _ => Ok(Self(label)),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+/// Case-insensitive substring search used by the wallet search
+pub fn matches_search_text(value: &str, text: &str) -> bool {
+    value.to_lowercase().contains(&text.to_lowercase())
+}
+
+/// A transaction or an address found by the wallet search, along with its label
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchMatch {
+    Transaction {
+        id: Id<Transaction>,
+        label: Option<Label>,
+    },
+    Address {
+        address: Destination,
+        label: Option<Label>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label_length() {
+        assert_eq!(Label::new(String::new()), Err(LabelError::Empty));
+
+        let label = "l".repeat(Label::MAX_LEN);
+        assert_eq!(Label::new(label.clone()).unwrap().as_str(), label);
+
+        let label = "l".repeat(Label::MAX_LEN + 1);
+        assert_eq!(
+            Label::new(label),
+            Err(LabelError::TooLong(Label::MAX_LEN + 1))
+        );
+    }
+
+    #[test]
+    fn search_is_case_insensitive() {
+        assert!(matches_search_text("Paid Alice back", "alice"));
+        assert!(matches_search_text("rent march", "RENT"));
+        assert!(!matches_search_text("rent march", "april"));
+    }
+}
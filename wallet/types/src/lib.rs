@@ -15,10 +15,16 @@
 
 pub mod account_id;
 pub mod account_info;
+pub mod address_set;
 pub mod chain_info;
+pub mod confirmations;
+pub mod currency;
+pub mod idempotency;
 pub mod keys;
+pub mod label;
 pub mod seed_phrase;
 pub mod signature_status;
+pub mod spending_policy;
 pub mod utxo_types;
 pub mod wallet_tx;
 pub mod wallet_type;
@@ -0,0 +1,208 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+
+use common::{chain::Destination, primitives::Amount};
+use rpc_description::HasValueHint;
+use serialization::{Decode, Encode};
+use utils::ensure;
+
+/// The length of the rolling window the daily cap applies to
+pub const DAILY_CAP_PERIOD_SECS: u64 = 24 * 60 * 60;
+
+/// Limits on the coins that an account can send out, so that a compromised wallet
+/// interface can't drain it at once
+#[derive(Debug, Clone, Default, PartialEq, Eq, Encode, Decode)]
+pub struct SpendingPolicy {
+    /// The maximum amount of coins that a single transaction can send
+    pub max_per_tx: Option<Amount>,
+    /// The maximum amount of coins that can be sent within the last 24 hours
+    pub daily_cap: Option<Amount>,
+    /// If set, the account can only send coins and tokens to these destinations
+    pub allowed_destinations: Option<BTreeSet<Destination>>,
+}
+
+/// The funds that a transaction sends out of an account, the change and the fee are not included
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TxOutflow {
+    /// The amount of the sent coins
+    pub coins: Amount,
+    /// The destinations that coins or tokens are sent to
+    pub destinations: BTreeSet<Destination>,
+    /// Whether the funds are also sent elsewhere, e.g. staked, delegated or burned
+    pub has_other_recipients: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, HasValueHint)]
+#[serde(rename_all = "snake_case")]
+pub enum SpendingRule {
+    MaxPerTx,
+    DailyCap,
+    AllowedDestinations,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum SpendingPolicyViolation {
+    #[error(
+        "The transaction sends {} atoms, the maximum per transaction is {} atoms",
+        .amount.into_atoms(),
+        .max_per_tx.into_atoms()
+    )]
+    MaxPerTxExceeded { amount: Amount, max_per_tx: Amount },
+    #[error(
+        "The transaction sends {} atoms, only {} atoms remain of the daily cap",
+        .amount.into_atoms(),
+        .remaining.into_atoms()
+    )]
+    DailyCapExceeded { amount: Amount, remaining: Amount },
+    #[error("The transaction sends funds to a destination that is not allowed")]
+    DestinationNotAllowed,
+}
+
+impl SpendingPolicyViolation {
+    pub fn rule(&self) -> SpendingRule {
+        match self {
+            Self::MaxPerTxExceeded { .. } => SpendingRule::MaxPerTx,
+            Self::DailyCapExceeded { .. } => SpendingRule::DailyCap,
+            Self::DestinationNotAllowed => SpendingRule::AllowedDestinations,
+        }
+    }
+
+    /// The amount that can still be sent under the violated rule, if it limits the amount
+    pub fn remaining_allowance(&self) -> Option<Amount> {
+        match self {
+            Self::MaxPerTxExceeded { max_per_tx, .. } => Some(*max_per_tx),
+            Self::DailyCapExceeded { remaining, .. } => Some(*remaining),
+            Self::DestinationNotAllowed => None,
+        }
+    }
+}
+
+impl SpendingPolicy {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Check a transaction against the policy, given the amount of coins already sent within
+    /// the daily cap period
+    pub fn check(
+        &self,
+        outflow: &TxOutflow,
+        sent_in_period: Amount,
+    ) -> Result<(), SpendingPolicyViolation> {
+        if let Some(max_per_tx) = self.max_per_tx {
+            ensure!(
+                outflow.coins <= max_per_tx,
+                SpendingPolicyViolation::MaxPerTxExceeded {
+                    amount: outflow.coins,
+                    max_per_tx
+                }
+            );
+        }
+
+        if let Some(daily_cap) = self.daily_cap {
+            let remaining = (daily_cap - sent_in_period).unwrap_or(Amount::ZERO);
+            ensure!(
+                outflow.coins <= remaining,
+                SpendingPolicyViolation::DailyCapExceeded {
+                    amount: outflow.coins,
+                    remaining
+                }
+            );
+        }
+
+        if let Some(allowed_destinations) = &self.allowed_destinations {
+            ensure!(
+                !outflow.has_other_recipients
+                    && outflow.destinations.is_subset(allowed_destinations),
+                SpendingPolicyViolation::DestinationNotAllowed
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::primitives::{Id, H256};
+
+    use super::*;
+
+    fn outflow(coins: u128, destinations: &[Destination]) -> TxOutflow {
+        TxOutflow {
+            coins: Amount::from_atoms(coins),
+            destinations: destinations.iter().cloned().collect(),
+            has_other_recipients: false,
+        }
+    }
+
+    #[test]
+    fn check_rules() {
+        let allowed = Destination::AnyoneCanSpend;
+        let other = Destination::ScriptHash(Id::new(H256::zero()));
+
+        let policy = SpendingPolicy::default();
+        assert!(policy.is_empty());
+        assert_eq!(
+            policy.check(&outflow(u128::MAX, &[other.clone()]), Amount::MAX),
+            Ok(())
+        );
+
+        let policy = SpendingPolicy {
+            max_per_tx: Some(Amount::from_atoms(10)),
+            daily_cap: Some(Amount::from_atoms(25)),
+            allowed_destinations: Some([allowed.clone()].into()),
+        };
+        assert_eq!(
+            policy.check(&outflow(10, &[allowed.clone()]), Amount::ZERO),
+            Ok(())
+        );
+
+        let err = policy.check(&outflow(11, &[allowed.clone()]), Amount::ZERO).unwrap_err();
+        assert_eq!(err.rule(), SpendingRule::MaxPerTx);
+        assert_eq!(err.remaining_allowance(), Some(Amount::from_atoms(10)));
+
+        assert_eq!(
+            policy.check(&outflow(5, &[allowed.clone()]), Amount::from_atoms(20)),
+            Ok(())
+        );
+        let err = policy
+            .check(&outflow(6, &[allowed.clone()]), Amount::from_atoms(20))
+            .unwrap_err();
+        assert_eq!(err.rule(), SpendingRule::DailyCap);
+        assert_eq!(err.remaining_allowance(), Some(Amount::from_atoms(5)));
+
+        // Nothing remains if more than the cap was sent before it was set
+        let err = policy
+            .check(&outflow(1, &[allowed.clone()]), Amount::from_atoms(30))
+            .unwrap_err();
+        assert_eq!(err.remaining_allowance(), Some(Amount::ZERO));
+
+        let err = policy.check(&outflow(1, &[allowed.clone(), other]), Amount::ZERO).unwrap_err();
+        assert_eq!(err, SpendingPolicyViolation::DestinationNotAllowed);
+        assert_eq!(err.remaining_allowance(), None);
+
+        let staking = TxOutflow {
+            has_other_recipients: true,
+            ..outflow(1, &[])
+        };
+        assert_eq!(
+            policy.check(&staking, Amount::ZERO),
+            Err(SpendingPolicyViolation::DestinationNotAllowed)
+        );
+    }
+}
@@ -85,6 +85,17 @@ impl TxState {
         }
     }
 
+    /// The number of blocks confirming the transaction, including its own block, when the best
+    /// block is at `best_block_height`; zero if the transaction is not confirmed
+    pub fn confirmations(&self, best_block_height: BlockHeight) -> u64 {
+        self.block_height().map_or(0, |block_height| {
+            best_block_height
+                .into_int()
+                .checked_sub(block_height.into_int())
+                .map_or(0, |depth| depth + 1)
+        })
+    }
+
     pub fn is_abandoned(&self) -> bool {
         match self {
             TxState::Abandoned => true,
@@ -87,6 +87,11 @@ where
         Ok((&self.wallet, state.selected_account))
     }
 
+    /// Access the wallet without syncing the local state, for requests that don't depend on it
+    pub fn get_wallet_without_state_update(&self) -> &W {
+        &self.wallet
+    }
+
     pub async fn get_wallet<N: NodeInterface>(&mut self) -> Result<&W, WalletCliCommandError<N>> {
         Self::update_state(&mut self.state, &self.wallet).await?;
         Ok(&self.wallet)
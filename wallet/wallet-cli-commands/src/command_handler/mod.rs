@@ -15,35 +15,39 @@
 
 mod local_state;
 
-use std::{fmt::Write, str::FromStr};
+use std::{collections::BTreeMap, fmt::Write, marker::PhantomData, str::FromStr, sync::Arc};
 
 use common::{
-    address::Address,
+    address::{payment_uri::PAYMENT_URI_SCHEME, Address, RpcAddress},
     chain::{
         config::checkpoints_data::print_block_heights_ids_as_checkpoints_data,
         partially_signed_transaction::PartiallySignedTransaction, ChainConfig, Destination,
         SignedTransaction, TxOutput, UtxoOutPoint,
     },
-    primitives::H256,
+    primitives::{Amount, H256},
     text_summary::TextSummary,
 };
-use crypto::key::hdkd::u31::U31;
+use crypto::key::{hdkd::u31::U31, PublicKey};
 use itertools::Itertools;
 use mempool::tx_options::TxOptionsOverrides;
 use node_comm::node_traits::NodeInterface;
 use serialization::{hex::HexEncode, hex_encoded::HexEncoded};
+use tokio::sync::mpsc;
 use utils::qrcode::{QrCode, QrCodeError};
 use wallet::version::get_version;
-use wallet_controller::types::GenericTokenTransfer;
+use wallet_controller::types::{GenericTokenTransfer, WalletFingerprintInfo};
 use wallet_rpc_client::wallet_rpc_traits::{PartialOrSignedTx, WalletInterface};
 use wallet_rpc_lib::types::{
     Balances, ComposedTransaction, ControllerConfig, MnemonicInfo, NewTransaction, NftMetadata,
-    RpcInspectTransaction, RpcSignatureStats, RpcSignatureStatus, RpcStandaloneAddressDetails,
-    RpcValidatedSignatures, TokenMetadata,
+    RpcInspectTransaction, RpcSearchMatch, RpcSignatureStats, RpcSignatureStatus,
+    RpcStandaloneAddressDetails, RpcValidatedSignatures, SendTransaction, TokenMetadata,
 };
+use wallet_types::{keys::WalletFingerprint, utxo_types::UtxoState, with_locked::WithLocked};
 
 use crate::{
-    errors::WalletCliCommandError, helper_types::parse_generic_token_transfer,
+    errors::WalletCliCommandError,
+    helper_types::parse_generic_token_transfer,
+    plugin::{PluginError, WalletCommandRunner, WalletPlugin},
     ManageableWalletCommand, WalletManagementCommand,
 };
 
@@ -51,8 +55,12 @@ use self::local_state::WalletWithState;
 
 use super::{
     helper_types::{
-        format_delegation_info, format_pool_info, parse_coin_output, parse_token_supply,
-        parse_utxo_outpoint, CliForceReduce, CliUtxoState,
+        create_multisig_address, filter_and_sort_stake_pools, format_decoded_transaction,
+        format_delegation_info, format_node_status, format_pool_info, format_stake_pools_table,
+        format_staking_pool_status, format_watch_event, parse_coin_output,
+        parse_own_utxo_destinations, parse_send_destination, parse_send_to_many_output,
+        parse_token_supply, parse_utxo_outpoint, read_send_to_many_outputs, CliForceReduce,
+        CliUtxoState, CliUtxoTypes,
     },
     ColdWalletCommand, ConsoleCommand, WalletCommand,
 };
@@ -61,6 +69,8 @@ pub struct CommandHandler<W> {
     config: ControllerConfig,
 
     wallet: WalletWithState<W>,
+
+    plugins: Vec<Arc<dyn WalletPlugin>>,
 }
 
 impl<W, E> CommandHandler<W>
@@ -71,9 +81,16 @@ where
         CommandHandler {
             config,
             wallet: WalletWithState::new(wallet).await,
+            plugins: Vec::new(),
         }
     }
 
+    /// Handle the commands of the plugins
+    pub fn with_plugins(mut self, plugins: Vec<Arc<dyn WalletPlugin>>) -> Self {
+        self.plugins = plugins;
+        self
+    }
+
     pub async fn rpc_completed(&self) {
         self.wallet.rpc_completed().await
     }
@@ -116,6 +133,39 @@ where
         Ok(status)
     }
 
+    /// The peer count and the sync state of the node, shown in the interactive prompt
+    pub async fn node_status<N: NodeInterface>(&self) -> Result<String, WalletCliCommandError<N>>
+    where
+        WalletCliCommandError<N>: From<E>,
+    {
+        // Don't update the local wallet state here, so that a wallet opened or closed by someone
+        // else is still reported on the next command
+        let status = self.wallet.get_wallet_without_state_update().node_connection_status().await?;
+        Ok(format_node_status(status))
+    }
+
+    /// The wallet events formatted as lines, produced until the receiver is dropped
+    pub async fn watch_events<N: NodeInterface>(
+        &mut self,
+        chain_config: Arc<ChainConfig>,
+    ) -> Result<mpsc::UnboundedReceiver<String>, WalletCliCommandError<N>>
+    where
+        WalletCliCommandError<N>: From<E>,
+    {
+        let mut events = self.non_empty_wallet().await?.watch_events().await?;
+        let (line_tx, line_rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                if line_tx.send(format_watch_event(&chain_config, &event)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(line_rx)
+    }
+
     pub fn new_tx_submitted_command(new_tx: NewTransaction) -> ConsoleCommand {
         let status_text = format!(
             "The transaction was submitted successfully with ID:\n{}",
@@ -124,6 +174,15 @@ where
         ConsoleCommand::Print(status_text)
     }
 
+    fn send_tx_submitted_command(new_tx: SendTransaction) -> ConsoleCommand {
+        let mut status_text = format!(
+            "The transaction was submitted successfully with ID:\n{}",
+            id_to_hex_string(*new_tx.tx_id.as_hash())
+        );
+        format_reused_addresses(&mut status_text, &new_tx.reused_addresses);
+        ConsoleCommand::Print(status_text)
+    }
+
     async fn non_empty_wallet<N: NodeInterface>(&mut self) -> Result<&W, WalletCliCommandError<N>> {
         self.wallet.get_wallet_with_acc().await.map(|(w, _)| w)
     }
@@ -145,7 +204,16 @@ where
                 mnemonic,
                 whether_to_store_seed_phrase,
                 passphrase,
+                ask_passphrase,
+                expected_fingerprint,
+                expected_last_used_change_index,
             } => {
+                // The interactive console replaces the flag with the entered passphrase
+                utils::ensure!(
+                    !ask_passphrase,
+                    WalletCliCommandError::<N>::PassphrasePromptNotSupported
+                );
+
                 let newly_generated_mnemonic = self
                     .wallet()
                     .await?
@@ -159,6 +227,21 @@ where
 
                 self.wallet.update_wallet::<N>().await;
 
+                let fingerprint_msg = if expected_fingerprint.is_some()
+                    || expected_last_used_change_index.is_some()
+                {
+                    let info = self.wallet().await?.wallet_fingerprint().await?;
+                    let fingerprint_msg = expected_fingerprint
+                        .map(|expected| fingerprint_check_message(&info, expected))
+                        .unwrap_or_default();
+                    let lookahead_msg = expected_last_used_change_index
+                        .and_then(|expected| change_lookahead_warning(&info, expected))
+                        .unwrap_or_default();
+                    fingerprint_msg + &lookahead_msg
+                } else {
+                    String::new()
+                };
+
                 let msg = match newly_generated_mnemonic.mnemonic {
                     MnemonicInfo::NewlyGenerated {
                         mnemonic,
@@ -181,6 +264,7 @@ where
                     }
                     MnemonicInfo::UserProvided => "New wallet created successfully".to_owned(),
                 };
+                let msg = msg + &fingerprint_msg;
 
                 Ok(ConsoleCommand::SetStatus {
                     status: self.repl_status().await?,
@@ -209,6 +293,24 @@ where
                 })
             }
 
+            WalletManagementCommand::RestoreWalletFromBackup {
+                backup_path,
+                wallet_path,
+                backup_password,
+            } => {
+                self.wallet()
+                    .await?
+                    .restore_wallet_from_backup(backup_path, wallet_path, backup_password)
+                    .await?;
+                self.wallet.update_wallet::<N>().await;
+
+                Ok(ConsoleCommand::SetStatus {
+                    status: self.repl_status().await?,
+                    print_message: "Wallet restored successfully, rescanning the blockchain"
+                        .to_owned(),
+                })
+            }
+
             WalletManagementCommand::CloseWallet => {
                 self.wallet().await?.close_wallet().await?;
                 self.wallet.update_wallet::<N>().await;
@@ -325,6 +427,36 @@ where
                 Ok(ConsoleCommand::Print(msg))
             }
 
+            ColdWalletCommand::BackupWallet {
+                backup_path,
+                password,
+            } => {
+                self.non_empty_wallet().await?.backup_wallet(backup_path, password).await?;
+
+                Ok(ConsoleCommand::Print(
+                    "Wallet backup created successfully".to_owned(),
+                ))
+            }
+
+            ColdWalletCommand::WalletFingerprint => {
+                let info = self.non_empty_wallet().await?.wallet_fingerprint().await?;
+                let passphrase_used = match info.passphrase_used {
+                    Some(true) => "yes",
+                    Some(false) => "no",
+                    None => "unknown",
+                };
+
+                let last_used_change_index = info
+                    .last_used_change_index
+                    .map_or_else(|| "none".to_owned(), |idx| idx.to_string());
+
+                Ok(ConsoleCommand::Print(format!(
+                    "Wallet fingerprint: {}\nPassphrase used: {passphrase_used}\n\
+                    Lookahead size: {}\nLast used change address index: {last_used_change_index}",
+                    info.fingerprint, info.lookahead_size
+                )))
+            }
+
             ColdWalletCommand::SetLookaheadSize {
                 lookahead_size,
                 i_know_what_i_am_doing,
@@ -355,10 +487,51 @@ where
                 Ok(ConsoleCommand::Print(qr_code_string))
             }
 
-            ColdWalletCommand::NewAddress => {
+            ColdWalletCommand::NewAddress { qr, allow_reuse } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let address = wallet.issue_address(selected_account, allow_reuse).await?;
+                if qr {
+                    Ok(ConsoleCommand::PrintWithQrCode {
+                        qr_code_data: format!("{PAYMENT_URI_SCHEME}:{}", address.address),
+                        text: address.address,
+                    })
+                } else {
+                    Ok(ConsoleCommand::Print(address.address))
+                }
+            }
+
+            ColdWalletCommand::NewPaymentUri { amount, label, qr } => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
-                let address = wallet.issue_address(selected_account).await?;
-                Ok(ConsoleCommand::Print(address.address))
+                let payment_uri = wallet.new_payment_uri(selected_account, amount, label).await?;
+                if qr {
+                    Ok(ConsoleCommand::PrintWithQrCode {
+                        qr_code_data: payment_uri.uri.clone(),
+                        text: payment_uri.uri,
+                    })
+                } else {
+                    Ok(ConsoleCommand::Print(payment_uri.uri))
+                }
+            }
+
+            ColdWalletCommand::DecodePaymentUri { uri } => {
+                let payment_uri = self.wallet().await?.decode_payment_uri(uri).await?;
+
+                let mut output = format!("Address: {}\n", payment_uri.address);
+                if let Some(amount) = payment_uri.amount {
+                    writeln!(&mut output, "Amount: {}", amount.decimal())
+                        .expect("Writing to a memory buffer should not fail");
+                }
+                if let Some(label) = payment_uri.label {
+                    writeln!(&mut output, "Label: {label}")
+                        .expect("Writing to a memory buffer should not fail");
+                }
+                if let Some(message) = payment_uri.message {
+                    writeln!(&mut output, "Message: {message}")
+                        .expect("Writing to a memory buffer should not fail");
+                }
+                output.pop();
+
+                Ok(ConsoleCommand::Print(output))
             }
 
             ColdWalletCommand::RevealPublicKey { public_key_hash } => {
@@ -379,9 +552,50 @@ where
                 ))
             }
 
-            ColdWalletCommand::ShowReceiveAddresses => {
+            ColdWalletCommand::CreateMultisigAddress { n, m, pubkeys } => {
+                let address = create_multisig_address(n, m, &pubkeys, chain_config)?;
+                Ok(ConsoleCommand::Print(address.into_string()))
+            }
+
+            ColdWalletCommand::ShowReceiveAddresses { only_unused } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let addresses_with_usage = wallet
+                    .get_issued_addresses(selected_account)
+                    .await?
+                    .into_iter()
+                    .filter(|info| !only_unused || !info.used);
+
+                let addresses_table = {
+                    let mut addresses_table = prettytable::Table::new();
+                    addresses_table.set_titles(prettytable::row![
+                        "Index",
+                        "Address",
+                        "Is used in transaction history",
+                        "Label",
+                    ]);
+
+                    addresses_table.extend(addresses_with_usage.into_iter().map(|info| {
+                        let is_used = if info.used { "Yes" } else { "No" };
+                        let label = info.label.unwrap_or_default();
+                        prettytable::row![info.index, info.address, is_used, label]
+                    }));
+
+                    addresses_table
+                };
+
+                Ok(ConsoleCommand::Print(addresses_table.to_string()))
+            }
+
+            ColdWalletCommand::ShowChangeAddresses {
+                only_unused,
+                only_used,
+            } => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
-                let addresses_with_usage = wallet.get_issued_addresses(selected_account).await?;
+                let addresses_with_usage = wallet
+                    .get_issued_change_addresses(selected_account)
+                    .await?
+                    .into_iter()
+                    .filter(|info| !(only_unused && info.used) && !(only_used && !info.used));
 
                 let addresses_table = {
                     let mut addresses_table = prettytable::Table::new();
@@ -389,11 +603,13 @@ where
                         "Index",
                         "Address",
                         "Is used in transaction history",
+                        "Label",
                     ]);
 
                     addresses_table.extend(addresses_with_usage.into_iter().map(|info| {
                         let is_used = if info.used { "Yes" } else { "No" };
-                        prettytable::row![info.index, info.address, is_used]
+                        let label = info.label.unwrap_or_default();
+                        prettytable::row![info.index, info.address, is_used, label]
                     }));
 
                     addresses_table
@@ -402,6 +618,70 @@ where
                 Ok(ConsoleCommand::Print(addresses_table.to_string()))
             }
 
+            ColdWalletCommand::SetAddressLabel { address, label } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                wallet.set_address_label(selected_account, address, label).await?;
+
+                Ok(ConsoleCommand::Print(
+                    "Success, the label has been changed.".to_owned(),
+                ))
+            }
+
+            ColdWalletCommand::ExportXpub { account } => {
+                let info = self.non_empty_wallet().await?.export_xpub(account).await?;
+                Ok(ConsoleCommand::Print(format!(
+                    "Extended public key: {}\nDerivation path: {}",
+                    info.xpub, info.derivation_path
+                )))
+            }
+
+            ColdWalletCommand::ExportDescriptor {
+                account,
+                descriptor_type,
+            } => {
+                let descriptor = self
+                    .non_empty_wallet()
+                    .await?
+                    .export_descriptor(account, descriptor_type)
+                    .await?;
+                Ok(ConsoleCommand::Print(descriptor))
+            }
+
+            ColdWalletCommand::ExportAddresses { path, count } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let info = wallet.export_address_set(selected_account, path, count).await?;
+                Ok(ConsoleCommand::Print(format!(
+                    "Exported {} receive and {} change addresses.\n\
+                    Account fingerprint: {}",
+                    info.count, info.count, info.account_fingerprint
+                )))
+            }
+
+            ColdWalletCommand::Search { text } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let matches = wallet.search(selected_account, text).await?;
+
+                let table = {
+                    let mut table = prettytable::Table::new();
+                    table.set_titles(prettytable::row!["Type", "Id or address", "Label"]);
+
+                    table.extend(matches.into_iter().map(|search_match| match search_match {
+                        RpcSearchMatch::Transaction { tx_id, label } => prettytable::row![
+                            "Transaction",
+                            id_to_hex_string(*tx_id.as_hash()),
+                            label.unwrap_or_default()
+                        ],
+                        RpcSearchMatch::Address { address, label } => {
+                            prettytable::row!["Address", address, label.unwrap_or_default()]
+                        }
+                    }));
+
+                    table
+                };
+
+                Ok(ConsoleCommand::Print(table.to_string()))
+            }
+
             ColdWalletCommand::ShowStandaloneAddresses => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
                 let addresses = wallet.get_standalone_addresses(selected_account).await?;
@@ -527,10 +807,50 @@ where
                 Ok(ConsoleCommand::Print(legacy_pubkey.vrf_public_key))
             }
 
+            ColdWalletCommand::DecodeTransaction { tx_hex } => {
+                let tx = tx_hex.take();
+
+                // The transaction can be decoded without a wallet, but if one is open,
+                // the inputs that spend its outputs are annotated
+                let selected_account = self
+                    .wallet
+                    .get_opt_state()
+                    .await?
+                    .as_ref()
+                    .map(|state| state.selected_account());
+                let own_utxos = match selected_account {
+                    Some(selected_account) => {
+                        let utxos = self
+                            .wallet()
+                            .await?
+                            .get_utxos(
+                                selected_account,
+                                CliUtxoTypes::All.to_wallet_types(),
+                                vec![
+                                    UtxoState::Confirmed,
+                                    UtxoState::InMempool,
+                                    UtxoState::Inactive,
+                                ],
+                                WithLocked::Any,
+                            )
+                            .await?;
+                        parse_own_utxo_destinations(utxos)?
+                    }
+                    None => BTreeMap::new(),
+                };
+
+                Ok(ConsoleCommand::Print(format_decoded_transaction(
+                    &tx,
+                    &own_utxos,
+                    chain_config,
+                )))
+            }
+
             ColdWalletCommand::SignRawTransaction { transaction } => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
-                let result =
-                    wallet.sign_raw_transaction(selected_account, transaction, self.config).await?;
+                let result = wallet
+                    .sign_raw_transaction(selected_account, transaction, self.config.clone())
+                    .await?;
 
                 let output_str = match result.transaction {
                     PartialOrSignedTx::Signed(signed_tx) => {
@@ -660,6 +980,27 @@ where
                 self.handle_cold_wallet_command(command, chain_config).await
             }
 
+            WalletCommand::PluginCommand { name, args } => {
+                let plugin = self
+                    .plugins
+                    .iter()
+                    .find(|plugin| {
+                        plugin.commands().iter().any(|command| command.get_name() == name)
+                    })
+                    .cloned()
+                    .ok_or_else(|| WalletCliCommandError::UnknownCommand {
+                        command: name,
+                        suggestion: None,
+                    })?;
+
+                let mut runner = PluginCommandRunner {
+                    command_handler: self,
+                    chain_config,
+                    _node: PhantomData::<fn() -> N>,
+                };
+                Ok(plugin.handle(&args, &mut runner).await?)
+            }
+
             WalletCommand::ChainstateInfo => {
                 let info = self.wallet().await?.chainstate_info().await?;
                 Ok(ConsoleCommand::Print(format!("{info:#?}")))
@@ -692,6 +1033,13 @@ where
                 }
             }
 
+            WalletCommand::VerifyProofOfReserves { path } => {
+                let report = self.wallet().await?.node_verify_proof_of_reserves(path).await?;
+                Ok(ConsoleCommand::Print(
+                    serde_json::to_string(&report).expect("ok"),
+                ))
+            }
+
             WalletCommand::GetBlock { hash } => {
                 let hash = self.wallet().await?.node_block(hash).await?;
                 match hash {
@@ -771,6 +1119,72 @@ where
                 })
             }
 
+            WalletCommand::SetSpendingPolicy {
+                admin_password,
+                max_per_tx,
+                daily_cap,
+                allowed_addresses,
+            } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let allowed_addresses =
+                    (!allowed_addresses.is_empty()).then_some(allowed_addresses);
+                wallet
+                    .set_spending_policy(
+                        admin_password,
+                        selected_account,
+                        max_per_tx,
+                        daily_cap,
+                        allowed_addresses,
+                    )
+                    .await?;
+
+                Ok(ConsoleCommand::Print(
+                    "Success, the spending policy has been updated.".into(),
+                ))
+            }
+
+            WalletCommand::ShowSpendingPolicy => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let policy = wallet.spending_policy(selected_account).await?;
+
+                let no_limit = || "no limit".to_owned();
+                let allowed_addresses = policy.allowed_addresses.map_or_else(
+                    || "any".to_owned(),
+                    |addresses| addresses.iter().map(|a| a.as_str()).collect::<Vec<_>>().join(", "),
+                );
+                let output = format!(
+                    "Max per transaction: {}\nDaily cap: {}\nAllowed addresses: {}\nSent in the last 24 hours: {}\nRemaining today: {}",
+                    policy.max_per_tx.map_or_else(no_limit, |a| a.decimal().to_string()),
+                    policy.daily_cap.map_or_else(no_limit, |a| a.decimal().to_string()),
+                    allowed_addresses,
+                    policy.sent_in_last_24h.decimal(),
+                    policy.daily_remaining.map_or_else(no_limit, |a| a.decimal().to_string()),
+                );
+
+                Ok(ConsoleCommand::Print(output))
+            }
+
+            WalletCommand::SetConfirmationThreshold {
+                confirmation_threshold,
+            } => {
+                self.non_empty_wallet()
+                    .await?
+                    .set_confirmation_threshold(confirmation_threshold)
+                    .await?;
+
+                Ok(ConsoleCommand::Print(
+                    "Success. The confirmation threshold has been updated.".to_owned(),
+                ))
+            }
+
+            WalletCommand::ShowConfirmationThreshold => {
+                let threshold = self.non_empty_wallet().await?.confirmation_threshold().await?;
+
+                Ok(ConsoleCommand::Print(format!(
+                    "Confirmation threshold: {threshold}"
+                )))
+            }
+
             WalletCommand::StandaloneAddressLabelRename { address, label } => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
                 wallet.standalone_address_label_rename(selected_account, address, label).await?;
@@ -781,6 +1195,41 @@ where
                 })
             }
 
+            WalletCommand::ImportAddressFile { path } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let info = wallet.import_address_set(selected_account, path).await?;
+
+                let mut output = format!(
+                    "Imported the addresses of the account with the fingerprint {}.\n\
+                    New addresses: {}, unused receive addresses: {}",
+                    info.account_fingerprint, info.added_addresses, info.unused_receive_addresses
+                );
+                if info.added_addresses > 0 {
+                    output +=
+                        "\nRescanning the blockchain to detect balance in added new addresses";
+                }
+                if info.low_on_unused_addresses {
+                    output += "\nWarning: few unused addresses remain, \
+                        export more addresses from the cold wallet";
+                }
+
+                Ok(ConsoleCommand::SetStatus {
+                    status: self.repl_status().await?,
+                    print_message: output,
+                })
+            }
+
+            WalletCommand::GenerateProofOfReserves { message, path } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let info =
+                    wallet.generate_proof_of_reserves(selected_account, message, path).await?;
+                Ok(ConsoleCommand::Print(format!(
+                    "Generated a proof of reserves for {} utxos at the block {}",
+                    info.utxo_count,
+                    id_to_hex_string(*info.block_id.as_hash())
+                )))
+            }
+
             WalletCommand::AddStandaloneKey {
                 address,
                 label,
@@ -810,20 +1259,27 @@ where
                 no_rescan,
             } => {
                 let no_rescan = no_rescan.unwrap_or(false);
+                let public_key = PublicKey::from_private_key(hex_private_key.as_ref());
+                let address = Address::new(
+                    chain_config,
+                    Destination::PublicKeyHash((&public_key).into()),
+                )
+                .map_err(|err| WalletCliCommandError::InvalidInput(err.to_string()))?;
+
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
                 wallet
                     .add_standalone_private_key(selected_account, hex_private_key, label, no_rescan)
                     .await?;
 
                 let output = if no_rescan {
-                    "Success, the new private key has been added to the account."
+                    format!("Success, the new private key for address {address} has been added to the account.")
                 } else {
-                    "Success, the new private key has been added to the account.\nRescanning the blockchain to detect balance in added new addresses"
+                    format!("Success, the new private key for address {address} has been added to the account.\nRescanning the blockchain to detect balance in added new addresses")
                 };
 
                 Ok(ConsoleCommand::SetStatus {
                     status: self.repl_status().await?,
-                    print_message: output.into(),
+                    print_message: output,
                 })
             }
 
@@ -910,6 +1366,18 @@ where
                 Ok(ConsoleCommand::Print(status.to_string()))
             }
 
+            WalletCommand::GetStakingStatus => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let pools: Vec<_> = wallet
+                    .staking_pools_status(selected_account)
+                    .await?
+                    .pools
+                    .into_iter()
+                    .map(format_staking_pool_status)
+                    .collect();
+                Ok(ConsoleCommand::Print(format!("{}\n", pools.join("\n"))))
+            }
+
             WalletCommand::StakePoolBalance { pool_id } => {
                 let balance_opt =
                     self.non_empty_wallet().await?.stake_pool_balance(pool_id).await?;
@@ -919,8 +1387,32 @@ where
                 }
             }
 
+            WalletCommand::ListStakingPools {
+                min_pledge,
+                max_margin_ratio,
+                sort_by,
+            } => {
+                let min_pledge = min_pledge
+                    .map(|amount| {
+                        amount.to_amount(chain_config.coin_decimals()).ok_or_else(|| {
+                            WalletCliCommandError::InvalidInput(format!(
+                                "Invalid pledge amount: {amount}"
+                            ))
+                        })
+                    })
+                    .transpose()?;
+                let pools = self.non_empty_wallet().await?.list_all_staking_pools().await?;
+                let pools =
+                    filter_and_sort_stake_pools(pools, min_pledge, max_margin_ratio, sort_by);
+                Ok(ConsoleCommand::Print(format_stake_pools_table(&pools)))
+            }
+
             WalletCommand::SubmitBlock { block } => {
-                self.wallet().await?.submit_block(block).await?;
+                self.wallet()
+                    .await?
+                    .submit_block(block)
+                    .await?
+                    .map_err(WalletCliCommandError::BlockRejected)?;
                 Ok(ConsoleCommand::Print(
                     "The block was submitted successfully".to_owned(),
                 ))
@@ -929,15 +1421,16 @@ where
             WalletCommand::SubmitTransaction {
                 transaction,
                 do_not_store,
+                relay_only,
+                do_not_relay,
             } => {
+                let options = TxOptionsOverrides::default()
+                    .with_relay_only(relay_only)
+                    .with_do_not_relay(do_not_relay);
                 let new_tx = self
                     .non_empty_wallet()
                     .await?
-                    .submit_raw_transaction(
-                        transaction,
-                        do_not_store,
-                        TxOptionsOverrides::default(),
-                    )
+                    .submit_raw_transaction(transaction, do_not_store, options)
                     .await?;
                 Ok(Self::new_tx_submitted_command(new_tx))
             }
@@ -958,7 +1451,11 @@ where
                     .collect::<Result<Vec<_>, WalletCliCommandError<N>>>(
                 )?;
 
-                let ComposedTransaction { hex, fees } = self
+                let ComposedTransaction {
+                    hex,
+                    fees,
+                    reused_addresses,
+                } = self
                     .non_empty_wallet()
                     .await?
                     .compose_transaction(input_utxos, outputs, None, only_transaction)
@@ -966,6 +1463,7 @@ where
                 let mut output = format!("The hex encoded transaction is:\n{hex}\n");
 
                 format_fees(&mut output, &fees);
+                format_reused_addresses(&mut output, &reused_addresses);
 
                 Ok(ConsoleCommand::Print(output))
             }
@@ -978,6 +1476,19 @@ where
                 ))
             }
 
+            WalletCommand::SetTransactionLabel {
+                transaction_id,
+                label,
+            } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                wallet
+                    .set_transaction_label(selected_account, transaction_id.take(), label)
+                    .await?;
+                Ok(ConsoleCommand::Print(
+                    "Success, the label has been changed.".to_owned(),
+                ))
+            }
+
             WalletCommand::IssueNewToken {
                 token_ticker,
                 number_of_decimals,
@@ -1000,7 +1511,7 @@ where
                             token_supply,
                             is_freezable: is_freezable.to_bool(),
                         },
-                        self.config,
+                        self.config.clone(),
                     )
                     .await?;
 
@@ -1035,7 +1546,12 @@ where
 
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
                 let new_token = wallet
-                    .issue_new_nft(selected_account, destination_address, metadata, self.config)
+                    .issue_new_nft(
+                        selected_account,
+                        destination_address,
+                        metadata,
+                        self.config.clone(),
+                    )
                     .await?;
 
                 Ok(ConsoleCommand::Print(format!(
@@ -1052,7 +1568,13 @@ where
             } => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
                 let new_tx = wallet
-                    .mint_tokens(selected_account, token_id, address, amount, self.config)
+                    .mint_tokens(
+                        selected_account,
+                        token_id,
+                        address,
+                        amount,
+                        self.config.clone(),
+                    )
                     .await?;
 
                 Ok(Self::new_tx_submitted_command(new_tx))
@@ -1060,16 +1582,18 @@ where
 
             WalletCommand::UnmintTokens { token_id, amount } => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
-                let new_tx =
-                    wallet.unmint_tokens(selected_account, token_id, amount, self.config).await?;
+                let new_tx = wallet
+                    .unmint_tokens(selected_account, token_id, amount, self.config.clone())
+                    .await?;
 
                 Ok(Self::new_tx_submitted_command(new_tx))
             }
 
             WalletCommand::LockTokenSupply { token_id } => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
-                let new_tx =
-                    wallet.lock_token_supply(selected_account, token_id, self.config).await?;
+                let new_tx = wallet
+                    .lock_token_supply(selected_account, token_id, self.config.clone())
+                    .await?;
 
                 Ok(Self::new_tx_submitted_command(new_tx))
             }
@@ -1084,7 +1608,7 @@ where
                         selected_account,
                         token_id,
                         is_unfreezable.to_bool(),
-                        self.config,
+                        self.config.clone(),
                     )
                     .await?;
 
@@ -1093,7 +1617,8 @@ where
 
             WalletCommand::UnfreezeToken { token_id } => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
-                let new_tx = wallet.unfreeze_token(selected_account, token_id, self.config).await?;
+                let new_tx =
+                    wallet.unfreeze_token(selected_account, token_id, self.config.clone()).await?;
 
                 Ok(Self::new_tx_submitted_command(new_tx))
             }
@@ -1101,7 +1626,12 @@ where
             WalletCommand::ChangeTokenAuthority { token_id, address } => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
                 let new_tx = wallet
-                    .change_token_authority(selected_account, token_id, address, self.config)
+                    .change_token_authority(
+                        selected_account,
+                        token_id,
+                        address,
+                        self.config.clone(),
+                    )
                     .await?;
 
                 Ok(Self::new_tx_submitted_command(new_tx))
@@ -1117,7 +1647,7 @@ where
                         selected_account,
                         token_id,
                         metadata_uri,
-                        self.config,
+                        self.config.clone(),
                     )
                     .await?;
 
@@ -1136,6 +1666,11 @@ where
                 Ok(ConsoleCommand::Print("Success".to_owned()))
             }
 
+            WalletCommand::Watch => {
+                self.non_empty_wallet().await?;
+                Ok(ConsoleCommand::Watch)
+            }
+
             WalletCommand::GetBalance {
                 utxo_states,
                 with_locked,
@@ -1163,6 +1698,42 @@ where
                 Ok(ConsoleCommand::Print(output))
             }
 
+            WalletCommand::GetBalanceByConfirmations {
+                with_locked,
+                confirmation_threshold,
+            } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let balances = wallet
+                    .get_balance_by_confirmations(
+                        selected_account,
+                        confirmation_threshold,
+                        with_locked.to_wallet_type(),
+                    )
+                    .await?;
+
+                let mut output = format!(
+                    "Confirmation threshold: {}\n",
+                    balances.confirmation_threshold
+                );
+                for (name, balance) in [
+                    ("Confirmed", &balances.confirmed),
+                    ("Pending", &balances.pending),
+                    ("Unconfirmed", &balances.unconfirmed),
+                ] {
+                    let coins = balance.coins().decimal();
+                    writeln!(&mut output, "{name}:\nCoins amount: {coins}")
+                        .expect("Writing to a memory buffer should not fail");
+                    for (token_id, amount) in balance.tokens() {
+                        let amount = amount.decimal();
+                        writeln!(&mut output, "Token: {token_id} amount: {amount}")
+                            .expect("Writing to a memory buffer should not fail");
+                    }
+                }
+                output.pop();
+
+                Ok(ConsoleCommand::Print(output))
+            }
+
             WalletCommand::ListUtxo {
                 utxo_type,
                 utxo_states,
@@ -1196,13 +1767,19 @@ where
 
                 let table = {
                     let mut table = prettytable::Table::new();
-                    table.set_titles(prettytable::row!["Id", "BlockHeight", "BlockTimestamp",]);
+                    table.set_titles(prettytable::row![
+                        "Id",
+                        "BlockHeight",
+                        "BlockTimestamp",
+                        "Label",
+                    ]);
 
                     table.extend(txs.into_iter().map(|info| {
                         prettytable::row![
                             id_to_hex_string(*info.id.as_hash()),
                             info.height,
-                            info.timestamp
+                            info.timestamp,
+                            info.label.unwrap_or_default()
                         ]
                     }));
 
@@ -1249,11 +1826,63 @@ where
                     .map(|s| parse_utxo_outpoint(s))
                     .collect::<Result<Vec<_>, WalletCliCommandError<N>>>(
                 )?;
+                let (address, amount) = parse_send_destination(address, amount, chain_config)?;
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
                 let new_tx = wallet
-                    .send_coins(selected_account, address, amount, input_utxos, self.config)
+                    .send_coins(
+                        selected_account,
+                        address,
+                        amount,
+                        input_utxos,
+                        self.config.clone(),
+                    )
                     .await?;
-                Ok(Self::new_tx_submitted_command(new_tx))
+                Ok(Self::send_tx_submitted_command(new_tx))
+            }
+
+            WalletCommand::SendToMany {
+                outputs,
+                file,
+                duplicate_outputs,
+            } => {
+                let outputs = match file {
+                    Some(file) => read_send_to_many_outputs(&file)?,
+                    None => outputs
+                        .iter()
+                        .map(|output| parse_send_to_many_output(output))
+                        .collect::<Result<Vec<_>, WalletCliCommandError<N>>>()?,
+                };
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let new_tx = wallet
+                    .send_to_many_addresses(
+                        selected_account,
+                        outputs,
+                        duplicate_outputs.to_wallet_type(),
+                        self.config.clone(),
+                    )
+                    .await?;
+
+                let mut output = format!(
+                    "The transaction was submitted successfully with ID:\n{}",
+                    id_to_hex_string(*new_tx.tx_id.as_hash())
+                );
+                write!(
+                    output,
+                    "\n\nTotal fee: {}\nFee per recipient: {}",
+                    new_tx.fees.coins().decimal(),
+                    new_tx.fee_per_recipient.decimal()
+                )
+                .expect("Writing to a memory buffer should not fail");
+                for address in new_tx.merged_addresses {
+                    write!(
+                        output,
+                        "\nWarning: the payments to {address} were merged into a single output"
+                    )
+                    .expect("Writing to a memory buffer should not fail");
+                }
+                format_reused_addresses(&mut output, &new_tx.reused_addresses);
+
+                Ok(ConsoleCommand::Print(output))
             }
 
             WalletCommand::SweepFromAddress {
@@ -1267,7 +1896,7 @@ where
                         selected_account,
                         destination_address,
                         addresses,
-                        self.config,
+                        self.config.clone(),
                     )
                     .await?;
 
@@ -1285,7 +1914,7 @@ where
                         selected_account,
                         destination_address,
                         delegation_id,
-                        self.config,
+                        self.config.clone(),
                     )
                     .await?;
 
@@ -1300,14 +1929,18 @@ where
             } => {
                 let selected_input = parse_utxo_outpoint(&utxo)?;
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
-                let ComposedTransaction { hex, fees } = wallet
+                let ComposedTransaction {
+                    hex,
+                    fees,
+                    reused_addresses,
+                } = wallet
                     .transaction_from_cold_input(
                         selected_account,
                         address,
                         amount,
                         selected_input,
                         change_address,
-                        self.config,
+                        self.config.clone(),
                     )
                     .await?;
 
@@ -1324,6 +1957,7 @@ where
                     Or scan the Qr code with it:\n\n{qr_code_string}\n\n{summary}\n"
                 );
                 format_fees(&mut output_str, &fees);
+                format_reused_addresses(&mut output_str, &reused_addresses);
 
                 Ok(ConsoleCommand::Print(output_str))
             }
@@ -1394,7 +2028,13 @@ where
             } => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
                 let new_tx = wallet
-                    .send_tokens(selected_account, token_id, address, amount, self.config)
+                    .send_tokens(
+                        selected_account,
+                        token_id,
+                        address,
+                        amount,
+                        self.config.clone(),
+                    )
                     .await?;
 
                 Ok(Self::new_tx_submitted_command(new_tx))
@@ -1417,7 +2057,7 @@ where
                         from_address,
                         fee_change_address,
                         outputs,
-                        self.config,
+                        self.config.clone(),
                     )
                     .await?;
 
@@ -1468,7 +2108,7 @@ where
             WalletCommand::CreateDelegation { owner, pool_id } => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
                 let delegation_id = wallet
-                    .create_delegation(selected_account, owner, pool_id, self.config)
+                    .create_delegation(selected_account, owner, pool_id, self.config.clone())
                     .await?
                     .delegation_id;
 
@@ -1484,7 +2124,7 @@ where
             } => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
                 wallet
-                    .delegate_staking(selected_account, amount, delegation_id, self.config)
+                    .delegate_staking(selected_account, amount, delegation_id, self.config.clone())
                     .await?;
 
                 Ok(ConsoleCommand::Print(
@@ -1505,7 +2145,7 @@ where
                         address,
                         amount,
                         delegation_id,
-                        self.config,
+                        self.config.clone(),
                     )
                     .await?;
                 Ok(ConsoleCommand::Print(
@@ -1513,6 +2153,45 @@ where
                 ))
             }
 
+            WalletCommand::DelegateStake {
+                pool_id,
+                amount,
+                account,
+            } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let new_delegation = wallet
+                    .create_delegation_and_stake(
+                        account.unwrap_or(selected_account),
+                        pool_id,
+                        amount,
+                        self.config.clone(),
+                    )
+                    .await?;
+
+                Ok(ConsoleCommand::Print(format!(
+                    "Success, the delegation transaction was broadcast to the network. Delegation id: {}",
+                    new_delegation.delegation_id
+                )))
+            }
+
+            WalletCommand::WithdrawDelegation {
+                delegation_id,
+                amount,
+            } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let address = wallet.issue_address(selected_account, false).await?;
+                let new_tx = wallet
+                    .withdraw_from_delegation(
+                        selected_account,
+                        address.address,
+                        amount,
+                        delegation_id,
+                        self.config.clone(),
+                    )
+                    .await?;
+                Ok(Self::new_tx_submitted_command(new_tx))
+            }
+
             WalletCommand::CreateStakePool {
                 amount,
                 cost_per_block,
@@ -1527,7 +2206,7 @@ where
                         cost_per_block,
                         margin_ratio_per_thousand,
                         decommission_address,
-                        self.config,
+                        self.config.clone(),
                     )
                     .await?;
 
@@ -1544,12 +2223,40 @@ where
                         selected_account,
                         pool_id,
                         Some(output_address),
-                        self.config,
+                        self.config.clone(),
                     )
                     .await?;
                 Ok(Self::new_tx_submitted_command(new_tx))
             }
 
+            WalletCommand::CreateDecommissionPoolTransaction { pool_id } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let result = wallet
+                    .create_decommission_pool_transaction(
+                        selected_account,
+                        pool_id,
+                        self.config.clone(),
+                    )
+                    .await?;
+
+                let mut output_str = format!(
+                    "Decommission transaction created. The pledge and the proceeds from staking \
+                    will be returned to {}.\n\
+                    Submit the following transaction with node-submit-transaction:\n\n{}",
+                    result.decommission_key, result.transaction
+                );
+                if result.delegated_balance.amount() != Amount::ZERO {
+                    output_str.push_str(&format!(
+                        "\n\nWarning: the pool has {} coins delegated to it. Once the pool is \
+                        decommissioned the delegations stop earning rewards and the delegators \
+                        can only withdraw their coins subject to the maturity timelock.",
+                        result.delegated_balance.decimal()
+                    ));
+                }
+
+                Ok(ConsoleCommand::Print(output_str))
+            }
+
             WalletCommand::DecommissionStakePoolRequest {
                 pool_id,
                 output_address,
@@ -1560,7 +2267,7 @@ where
                         selected_account,
                         pool_id,
                         Some(output_address),
-                        self.config,
+                        self.config.clone(),
                     )
                     .await?;
 
@@ -1576,7 +2283,8 @@ where
 
             WalletCommand::DepositData { hex_data } => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
-                let new_tx = wallet.deposit_data(selected_account, hex_data, self.config).await?;
+                let new_tx =
+                    wallet.deposit_data(selected_account, hex_data, self.config.clone()).await?;
                 Ok(Self::new_tx_submitted_command(new_tx))
             }
 
@@ -1737,6 +2445,31 @@ where
     }
 }
 
+/// Runs the wallet commands on behalf of a plugin
+struct PluginCommandRunner<'a, W, N> {
+    command_handler: &'a mut CommandHandler<W>,
+    chain_config: &'a ChainConfig,
+    _node: PhantomData<fn() -> N>,
+}
+
+#[async_trait::async_trait]
+impl<W, E, N> WalletCommandRunner for PluginCommandRunner<'_, W, N>
+where
+    W: WalletInterface<Error = E> + Send + Sync + 'static,
+    N: NodeInterface,
+    WalletCliCommandError<N>: From<E>,
+{
+    async fn run_wallet_command(
+        &mut self,
+        command: WalletCommand,
+    ) -> Result<ConsoleCommand, PluginError> {
+        self.command_handler
+            .handle_wallet_command::<N>(self.chain_config, command)
+            .await
+            .map_err(|err| PluginError::CommandFailed(err.to_string()))
+    }
+}
+
 fn format_signature_status((idx, status): (usize, &RpcSignatureStatus)) -> String {
     let status = match status {
         RpcSignatureStatus::FullySigned => "FullySigned".to_owned(),
@@ -1766,11 +2499,65 @@ fn format_fees(output: &mut String, fees: &Balances) {
     output.pop();
 }
 
+/// Warn about paying the wallet's own addresses that have already received funds
+fn format_reused_addresses(output: &mut String, reused_addresses: &[RpcAddress<Destination>]) {
+    for address in reused_addresses {
+        write!(
+            output,
+            "\nWarning: the address {address} of this wallet has already received funds, \
+            paying it again reuses the address and hurts privacy"
+        )
+        .expect("Writing to a memory buffer should not fail");
+    }
+}
+
 fn id_to_hex_string(id: H256) -> String {
     let hex_string = format!("{:?}", id);
     hex_string.strip_prefix("0x").unwrap_or(&hex_string).to_string()
 }
 
+/// Compare the fingerprint of a newly created wallet with the one the user expects,
+/// hinting at the most likely cause in case of a mismatch
+fn fingerprint_check_message(
+    info: &WalletFingerprintInfo,
+    expected_fingerprint: WalletFingerprint,
+) -> String {
+    if info.fingerprint == expected_fingerprint.to_string() {
+        return format!(
+            "\nThe wallet fingerprint {} matches the expected one",
+            info.fingerprint
+        );
+    }
+
+    let hint = if info.passphrase_used == Some(true) {
+        "Please check that both the seed phrase and the passphrase are correct."
+    } else {
+        "If the original wallet was created with a passphrase, please recreate this wallet with the same passphrase."
+    };
+    format!(
+        "\nWarning: the wallet fingerprint {} doesn't match the expected {expected_fingerprint}. {hint}",
+        info.fingerprint
+    )
+}
+
+/// Warn if the lookahead size of a newly created wallet may be too small to find the change
+/// outputs of the original wallet, whose last used change address has the expected index
+fn change_lookahead_warning(
+    info: &WalletFingerprintInfo,
+    expected_last_used_change_index: u32,
+) -> Option<String> {
+    (expected_last_used_change_index >= info.lookahead_size).then(|| {
+        format!(
+            "\nWarning: the original wallet used the change address {expected_last_used_change_index}, \
+            which is beyond the lookahead size {}. If some change addresses were skipped, \
+            the funds sent to the later ones won't be found. \
+            Please compare the last used change index shown by `wallet-fingerprint` after the sync \
+            and increase the lookahead size with `wallet-set-lookahead-size` if it's lower.",
+            info.lookahead_size
+        )
+    })
+}
+
 /// This is a helper function used to ensure that failing to output a QR code will only display an error message instead of completely failing the command
 fn qrcode_or_error_string(str_data: &str) -> String {
     let make_error_str = |e: QrCodeError| format!("<<Failed to generate QR Code: {e}>>");
@@ -13,6 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use chainstate::rpc::BlockSubmissionError;
 use crypto::key::hdkd::u31::U31;
 use node_comm::node_traits::NodeInterface;
 use utils::qrcode::QrCodeError;
@@ -20,12 +21,19 @@ use wallet_controller::types::GenericCurrencyTransferToTxOutputConversionError;
 use wallet_rpc_client::{handles_client::WalletRpcHandlesClientError, rpc_client::WalletRpcError};
 use wallet_rpc_lib::RpcError;
 
+use crate::plugin::PluginError;
+
 #[derive(thiserror::Error, Debug)]
 pub enum WalletCliCommandError<N: NodeInterface> {
     #[error("Invalid quoting")]
     InvalidQuoting,
     #[error("{0}")]
     InvalidCommandInput(clap::Error),
+    #[error("Unknown command '{command}'.{} Use 'help' to see all available commands.", format_suggestion(.suggestion))]
+    UnknownCommand {
+        command: String,
+        suggestion: Option<String>,
+    },
     #[error("Invalid input: {0}")]
     InvalidInput(String),
     #[error("Please open or create a wallet file first")]
@@ -50,4 +58,26 @@ pub enum WalletCliCommandError<N: NodeInterface> {
     ExistingWalletWasClosed,
     #[error("Invalid tx output: {0}")]
     InvalidTxOutput(GenericCurrencyTransferToTxOutputConversionError),
+    #[error("Asking for the passphrase is only supported in the interactive console, please use --passphrase instead")]
+    PassphrasePromptNotSupported,
+    #[error("{}", format_block_rejection(.0))]
+    BlockRejected(BlockSubmissionError),
+    #[error("{0}")]
+    PluginError(#[from] PluginError),
+}
+
+fn format_block_rejection(err: &BlockSubmissionError) -> String {
+    let tx_id = err.tx_id.map_or_else(String::new, |tx_id| {
+        format!("\nOffending transaction: {tx_id:x}")
+    });
+    format!(
+        "The block was rejected ({}): {}{tx_id}\nHint: {}",
+        err.category, err.message, err.hint
+    )
+}
+
+fn format_suggestion(suggestion: &Option<String>) -> String {
+    suggestion
+        .as_ref()
+        .map_or_else(String::new, |command| format!(" Did you mean '{command}'?"))
 }
@@ -0,0 +1,312 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Write;
+
+use clap::{Command, Subcommand};
+
+use crate::ManageableWalletCommand;
+
+/// Build the `after_help` section of a command from its example invocations.
+///
+/// The examples are rendered as part of the command's help; the tests parse them back to make sure
+/// every command has examples and that they are valid invocations of the command.
+macro_rules! examples {
+    ($($example:literal),+ $(,)?) => {
+        concat!("Examples:", $("\n  ", $example),+)
+    };
+}
+
+pub(crate) use examples;
+
+/// The maximum edit distance for a command name to be suggested instead of an unknown one
+const MAX_SUGGESTION_DISTANCE: usize = 3;
+
+/// Groups of related commands, in the order they are listed by the bare `help` command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CommandCategory {
+    WalletManagement,
+    Transactions,
+    Staking,
+    Node,
+    Plugins,
+}
+
+impl CommandCategory {
+    pub const ALL: [CommandCategory; 5] = [
+        CommandCategory::WalletManagement,
+        CommandCategory::Transactions,
+        CommandCategory::Staking,
+        CommandCategory::Node,
+        CommandCategory::Plugins,
+    ];
+
+    pub fn of_command(name: &str) -> Self {
+        // Commands that create transactions, but whose names are not prefixed with "transaction-"
+        const TRANSACTION_COMMANDS: [&str; 3] =
+            ["address-send", "address-sweep-spendable", "address-deposit-data"];
+
+        if !ManageableWalletCommand::has_subcommand(name) {
+            CommandCategory::Plugins
+        } else if name.starts_with("node-") {
+            CommandCategory::Node
+        } else if name.starts_with("staking-") || name.starts_with("delegation-") {
+            CommandCategory::Staking
+        } else if name.starts_with("transaction-")
+            || name.starts_with("token-")
+            || TRANSACTION_COMMANDS.contains(&name)
+        {
+            CommandCategory::Transactions
+        } else {
+            CommandCategory::WalletManagement
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            CommandCategory::WalletManagement => "Wallet management",
+            CommandCategory::Transactions => "Transactions",
+            CommandCategory::Staking => "Staking",
+            CommandCategory::Node => "Node",
+            CommandCategory::Plugins => "Plugins",
+        }
+    }
+}
+
+/// Render the list of all the visible commands, grouped by category
+pub fn render_commands_list(repl_command: &Command) -> String {
+    let commands = repl_command
+        .get_subcommands()
+        .filter(|command| !command.is_hide_set())
+        .collect::<Vec<_>>();
+    let name_width = commands.iter().map(|command| command.get_name().len()).max().unwrap_or(0);
+
+    let mut output = String::new();
+    for category in CommandCategory::ALL {
+        let mut category_commands = commands
+            .iter()
+            .filter(|command| CommandCategory::of_command(command.get_name()) == category)
+            .peekable();
+        if category_commands.peek().is_none() {
+            continue;
+        }
+
+        writeln!(&mut output, "{}:", category.title())
+            .expect("Writing to a memory buffer should not fail");
+        for command in category_commands {
+            let about = command
+                .get_about()
+                .map(|about| about.to_string())
+                .and_then(|about| about.lines().next().map(ToOwned::to_owned))
+                .unwrap_or_default();
+            writeln!(&mut output, "  {:name_width$}  {about}", command.get_name())
+                .expect("Writing to a memory buffer should not fail");
+        }
+        output.push('\n');
+    }
+    output.push_str("Use 'help <command>' to learn more about the parameters of the command.");
+
+    output
+}
+
+/// Render the help of a single command, including its arguments and examples.
+/// Returns `None` if there is no such command.
+pub fn render_command_help(repl_command: &Command, name: &str) -> Option<String> {
+    let mut repl_command = repl_command.clone();
+    repl_command.build();
+    let command = repl_command.find_subcommand_mut(name)?;
+
+    let category = CommandCategory::of_command(command.get_name());
+    let help = command.render_long_help().to_string();
+
+    Some(format!(
+        "Category: {}\n\n{}",
+        category.title(),
+        help.trim_end()
+    ))
+}
+
+/// Find the visible command with the name closest to the given one
+pub fn suggest_command(repl_command: &Command, name: &str) -> Option<String> {
+    repl_command
+        .get_subcommands()
+        .filter(|command| !command.is_hide_set())
+        .map(|command| (edit_distance(command.get_name(), name), command.get_name()))
+        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, command_name)| command_name.to_owned())
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev_row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut row = Vec::with_capacity(b.len() + 1);
+        row.push(i + 1);
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = prev_row[j] + usize::from(a_char != *b_char);
+            let deletion = prev_row[j + 1] + 1;
+            let insertion = row[j] + 1;
+            row.push(substitution.min(deletion).min(insertion));
+        }
+        prev_row = row;
+    }
+
+    prev_row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::FromArgMatches;
+
+    use crate::get_repl_command;
+
+    use super::*;
+
+    const EXAMPLES_HEADING: &str = "Examples:";
+
+    /// Return the example invocations registered for the command
+    fn command_examples(command: &Command) -> Vec<String> {
+        command
+            .get_after_help()
+            .map(|after_help| after_help.to_string())
+            .and_then(|after_help| {
+                after_help.strip_prefix(EXAMPLES_HEADING).map(|examples| {
+                    examples
+                        .lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty())
+                        .map(ToOwned::to_owned)
+                        .collect()
+                })
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn every_command_has_a_parsable_example() {
+        for (cold_wallet, mutable_wallet) in [(false, true), (true, true)] {
+            let repl_command = get_repl_command(cold_wallet, mutable_wallet, &[]);
+
+            for command in repl_command.get_subcommands() {
+                let examples = command_examples(command);
+                assert!(
+                    !examples.is_empty(),
+                    "Command {} has no examples",
+                    command.get_name()
+                );
+
+                for example in examples {
+                    assert!(
+                        example.starts_with(command.get_name()),
+                        "Example '{example}' does not invoke {}",
+                        command.get_name()
+                    );
+
+                    let args = shlex::split(&example).unwrap();
+                    let mut matches = repl_command
+                        .clone()
+                        .try_get_matches_from(args)
+                        .unwrap_or_else(|e| panic!("Failed to parse example '{example}': {e}"));
+                    ManageableWalletCommand::from_arg_matches_mut(&mut matches)
+                        .unwrap_or_else(|e| panic!("Failed to parse example '{example}': {e}"));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn command_categories() {
+        assert_eq!(
+            CommandCategory::of_command("wallet-create"),
+            CommandCategory::WalletManagement
+        );
+        assert_eq!(
+            CommandCategory::of_command("address-new"),
+            CommandCategory::WalletManagement
+        );
+        assert_eq!(
+            CommandCategory::of_command("address-send"),
+            CommandCategory::Transactions
+        );
+        assert_eq!(
+            CommandCategory::of_command("token-mint"),
+            CommandCategory::Transactions
+        );
+        assert_eq!(
+            CommandCategory::of_command("delegation-stake"),
+            CommandCategory::Staking
+        );
+        assert_eq!(
+            CommandCategory::of_command("node-version"),
+            CommandCategory::Node
+        );
+        assert_eq!(
+            CommandCategory::of_command("ping"),
+            CommandCategory::Plugins
+        );
+    }
+
+    #[test]
+    fn help_output() {
+        let plugin_command = Command::new("ping").about("Reply with pong");
+        let repl_command = get_repl_command(false, true, &[plugin_command]);
+
+        let list = render_commands_list(&repl_command);
+        for category in CommandCategory::ALL {
+            assert!(list.contains(&format!("{}:", category.title())));
+        }
+        assert!(list.contains("address-send"));
+        assert!(list.contains("ping"));
+        // hidden commands are not listed
+        assert!(!list.contains("node-generate-blocks "));
+
+        let help = render_command_help(&repl_command, "address-send").unwrap();
+        assert!(help.starts_with("Category: Transactions"));
+        assert!(help.contains("<ADDRESS>"));
+        assert!(help.contains("Examples:"));
+
+        let help = render_command_help(&repl_command, "ping").unwrap();
+        assert!(help.starts_with("Category: Plugins"));
+
+        assert_eq!(render_command_help(&repl_command, "no-such-command"), None);
+    }
+
+    #[test]
+    fn command_suggestions() {
+        let repl_command = get_repl_command(false, true, &[]);
+
+        assert_eq!(
+            suggest_command(&repl_command, "adress-send"),
+            Some("address-send".to_owned())
+        );
+        assert_eq!(
+            suggest_command(&repl_command, "account-balanse"),
+            Some("account-balance".to_owned())
+        );
+        assert_eq!(suggest_command(&repl_command, "completely-unrelated"), None);
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("address-new", "address-new"), 0);
+    }
+}
@@ -13,17 +13,41 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{fmt::Display, str::FromStr};
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    fmt::{Display, Write},
+    num::NonZeroU8,
+    path::Path,
+    str::FromStr,
+};
 
 use clap::ValueEnum;
 
+use chainstate::{
+    rpc::{RpcStakePoolInfo, RpcTxOutput},
+    STAKE_POOL_BLOCKS_WINDOW,
+};
 use common::{
-    address::Address,
-    chain::{ChainConfig, OutPointSourceId, TxOutput, UtxoOutPoint},
-    primitives::{DecimalAmount, Id, H256},
+    address::{payment_uri::PaymentUri, pubkeyhash::PublicKeyHash, Address, RpcAddress},
+    chain::{
+        classic_multisig::ClassicMultisigChallenge,
+        signature::{inputsig::InputWitness, sighash::sighashtype::SigHashType},
+        ChainConfig, Destination, OutPointSourceId, SignedTransaction, TxInput, TxOutput,
+        UtxoOutPoint,
+    },
+    primitives::{Amount, DecimalAmount, Id, Idable, H256},
+    text_summary::TextSummary,
+};
+use crypto::key::PublicKey;
+use serialization::hex::HexDecode;
+use utils::ensure;
+use wallet_controller::types::{
+    DuplicateOutputs, GenericCurrencyTransfer, GenericTokenTransfer, WatchEvent,
+};
+use wallet_rpc_lib::types::{
+    NodeConnectionStatus, NodeInterface, PoolInfo, StakingPoolStatus, TokenTotalSupply, UtxoInfo,
 };
-use wallet_controller::types::{GenericCurrencyTransfer, GenericTokenTransfer};
-use wallet_rpc_lib::types::{NodeInterface, PoolInfo, TokenTotalSupply};
 use wallet_types::{
     utxo_types::{UtxoState, UtxoType},
     with_locked::WithLocked,
@@ -106,10 +130,258 @@ pub fn format_pool_info(pool_info: PoolInfo) -> String {
     )
 }
 
+pub fn format_staking_pool_status(status: StakingPoolStatus) -> String {
+    format!(
+        "Pool Id: {}, Balance: {}, Blocks produced in the last 24h: {}, Expected blocks: {}, Staking: {}",
+        status.pool_id,
+        status.balance.decimal(),
+        status.blocks_produced_last_24h,
+        status.expected_blocks_last_24h,
+        status.is_staking,
+    )
+}
+
+/// The order in which the staking pools known to the node are listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CliStakePoolsSortOrder {
+    #[value(name = "pledge_desc")]
+    PledgeDesc,
+    #[value(name = "total_stake_desc")]
+    TotalStakeDesc,
+    #[value(name = "blocks_produced_desc")]
+    BlocksProducedDesc,
+}
+
+/// Keep the pools with a pledge of at least `min_pledge` and a margin ratio of at most
+/// `max_margin_ratio`, sorted in the given order. The pools that compare equal, or all of them
+/// if no order is given, are kept in the order returned by the node.
+pub fn filter_and_sort_stake_pools(
+    mut pools: Vec<RpcStakePoolInfo>,
+    min_pledge: Option<Amount>,
+    max_margin_ratio: Option<f64>,
+    sort_by: Option<CliStakePoolsSortOrder>,
+) -> Vec<RpcStakePoolInfo> {
+    pools.retain(|pool| {
+        min_pledge.map_or(true, |min_pledge| pool.pledge.amount() >= min_pledge)
+            && max_margin_ratio.map_or(true, |max_margin_ratio| {
+                pool.margin_ratio_per_thousand.as_f64() <= max_margin_ratio
+            })
+    });
+
+    match sort_by {
+        Some(CliStakePoolsSortOrder::PledgeDesc) => {
+            pools.sort_by_key(|pool| Reverse(pool.pledge.amount()))
+        }
+        Some(CliStakePoolsSortOrder::TotalStakeDesc) => {
+            pools.sort_by_key(|pool| Reverse(pool.total_stake.amount()))
+        }
+        Some(CliStakePoolsSortOrder::BlocksProducedDesc) => {
+            pools.sort_by_key(|pool| Reverse(pool.blocks_produced))
+        }
+        None => {}
+    }
+
+    pools
+}
+
+pub fn format_stake_pools_table(pools: &[RpcStakePoolInfo]) -> String {
+    let mut table = prettytable::Table::new();
+    table.set_titles(prettytable::row![
+        "Pool Id",
+        "Pledge",
+        "Total stake",
+        "Cost per block",
+        "Margin ratio",
+        format!("Blocks produced (last {STAKE_POOL_BLOCKS_WINDOW})"),
+    ]);
+    table.extend(pools.iter().map(|pool| {
+        prettytable::row![
+            pool.pool_id,
+            pool.pledge.decimal(),
+            pool.total_stake.decimal(),
+            pool.cost_per_block.decimal(),
+            pool.margin_ratio_per_thousand.to_percentage_str(),
+            pool.blocks_produced,
+        ]
+    }));
+    table.to_string()
+}
+
 pub fn format_delegation_info(delegation_id: String, balance: String) -> String {
     format!("Delegation Id: {}, Balance: {}", delegation_id, balance,)
 }
 
+/// The node status shown in the interactive prompt, e.g. " [peers: 8]" or " [peers: 2, syncing]"
+pub fn format_node_status(status: NodeConnectionStatus) -> String {
+    if status.is_synced {
+        format!(" [peers: {}]", status.peer_count)
+    } else {
+        format!(" [peers: {}, syncing]", status.peer_count)
+    }
+}
+
+/// A single line describing a wallet event in the watch mode
+pub fn format_watch_event(chain_config: &ChainConfig, event: &WatchEvent) -> String {
+    match event {
+        WatchEvent::NewTip {
+            block_height,
+            block_id,
+            confirmed_txs,
+        } => format!(
+            "New tip: height {block_height}, id {block_id:x}, confirmed transactions: {confirmed_txs}"
+        ),
+        WatchEvent::Transaction {
+            account_index,
+            info,
+        } => {
+            let amount = info.tx_type.amount().map_or(String::new(), |amount| {
+                let amount =
+                    DecimalAmount::from_amount_no_padding(amount, chain_config.coin_decimals());
+                format!(" {amount}")
+            });
+            format!(
+                "Transaction {:x} (account {account_index}): {}{amount}, {}",
+                info.txid,
+                info.tx_type.type_name(),
+                info.state
+            )
+        }
+        WatchEvent::BlockStaked {
+            account_index,
+            block_height,
+            block_id,
+        } => format!(
+            "Block staked (account {account_index}): height {block_height}, id {block_id:x}"
+        ),
+    }
+}
+
+/// Collect the destinations of the wallet's utxos, as returned by `get_utxos`, by their outpoints.
+/// The destination is `None` for the outputs that don't have one (e.g. stake pool creation).
+pub fn parse_own_utxo_destinations(
+    utxos: Vec<serde_json::Value>,
+) -> Result<BTreeMap<UtxoOutPoint, Option<String>>, serde_json::Error> {
+    utxos
+        .into_iter()
+        .map(|utxo| {
+            let utxo: UtxoInfo = serde_json::from_value(utxo)?;
+            let destination = rpc_output_destination(&utxo.output).map(|d| d.as_str().to_owned());
+            Ok((utxo.outpoint.into_outpoint(), destination))
+        })
+        .collect()
+}
+
+fn rpc_output_destination(output: &RpcTxOutput) -> Option<&RpcAddress<Destination>> {
+    match output {
+        RpcTxOutput::Transfer {
+            value: _,
+            destination,
+        }
+        | RpcTxOutput::LockThenTransfer {
+            value: _,
+            destination,
+            timelock: _,
+        }
+        | RpcTxOutput::ProduceBlockFromStake {
+            destination,
+            pool_id: _,
+        }
+        | RpcTxOutput::CreateDelegationId {
+            destination,
+            pool_id: _,
+        }
+        | RpcTxOutput::IssueNft {
+            token_id: _,
+            data: _,
+            destination,
+        } => Some(destination),
+        RpcTxOutput::Burn { .. }
+        | RpcTxOutput::CreateStakePool { .. }
+        | RpcTxOutput::DelegateStaking { .. }
+        | RpcTxOutput::IssueFungibleToken { .. }
+        | RpcTxOutput::DataDeposit { .. }
+        | RpcTxOutput::Htlc { .. }
+        | RpcTxOutput::AnyoneCanTake { .. } => None,
+    }
+}
+
+fn format_input_witness(witness: &InputWitness) -> String {
+    match witness {
+        InputWitness::NoSignature(None) => "None".to_owned(),
+        InputWitness::NoSignature(Some(data)) => {
+            format!("None (with data: {})", hex::encode(data))
+        }
+        InputWitness::Standard(sig) => {
+            let sighash_type = sig.sighash_type().get();
+            let outputs_mode = match sighash_type & !SigHashType::ANYONECANPAY {
+                SigHashType::NONE => "NONE",
+                SigHashType::SINGLE => "SINGLE",
+                _ => "ALL",
+            };
+            if sighash_type & SigHashType::ANYONECANPAY != 0 {
+                format!("Standard (sighash: {outputs_mode}|ANYONECANPAY)")
+            } else {
+                format!("Standard (sighash: {outputs_mode})")
+            }
+        }
+    }
+}
+
+/// Describe the inputs, outputs and witnesses of a signed transaction.
+/// The inputs spending the outpoints from `own_utxos` are marked as owned by the wallet.
+pub fn format_decoded_transaction(
+    tx: &SignedTransaction,
+    own_utxos: &BTreeMap<UtxoOutPoint, Option<String>>,
+    chain_config: &ChainConfig,
+) -> String {
+    let mut output = String::new();
+    let transaction = tx.transaction();
+
+    writeln!(
+        output,
+        "Transaction id: {:x}",
+        transaction.get_id().to_hash()
+    )
+    .expect("Writing to a memory buffer should not fail");
+    writeln!(output, "Version: {}", transaction.version_byte())
+        .expect("Writing to a memory buffer should not fail");
+    writeln!(output, "Flags: {}", transaction.flags())
+        .expect("Writing to a memory buffer should not fail");
+
+    writeln!(output, "Inputs ({}):", transaction.inputs().len())
+        .expect("Writing to a memory buffer should not fail");
+    for (index, (input, witness)) in transaction.inputs().iter().zip(tx.signatures()).enumerate() {
+        let ownership = match input {
+            TxInput::Utxo(outpoint) => match own_utxos.get(outpoint) {
+                Some(Some(destination)) => format!(", own key: {destination}"),
+                Some(None) => ", own key".to_owned(),
+                None => String::new(),
+            },
+            TxInput::Account(_) | TxInput::AccountCommand(_, _) => String::new(),
+        };
+        writeln!(
+            output,
+            "  #{index}: {}, witness: {}{ownership}",
+            input.text_summary(chain_config),
+            format_input_witness(witness),
+        )
+        .expect("Writing to a memory buffer should not fail");
+    }
+
+    writeln!(output, "Outputs ({}):", transaction.outputs().len())
+        .expect("Writing to a memory buffer should not fail");
+    for (index, tx_output) in transaction.outputs().iter().enumerate() {
+        writeln!(
+            output,
+            "  #{index}: {}",
+            tx_output.text_summary(chain_config)
+        )
+        .expect("Writing to a memory buffer should not fail");
+    }
+
+    output.trim_end().to_owned()
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum CliWithLocked {
     Any,
@@ -127,6 +399,23 @@ impl CliWithLocked {
     }
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CliDuplicateOutputs {
+    Merge,
+    Allow,
+    Reject,
+}
+
+impl CliDuplicateOutputs {
+    pub fn to_wallet_type(self) -> DuplicateOutputs {
+        match self {
+            CliDuplicateOutputs::Merge => DuplicateOutputs::Merge,
+            CliDuplicateOutputs::Allow => DuplicateOutputs::Allow,
+            CliDuplicateOutputs::Reject => DuplicateOutputs::Reject,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum CliStoreSeedPhrase {
     StoreSeedPhrase,
@@ -241,6 +530,56 @@ pub fn parse_generic_currency_transfer<N: NodeInterface>(
     Ok(output)
 }
 
+/// An output of the `address-send-to-many` command: the address, the amount and the optional
+/// token id
+pub type SendToManyOutput = (String, DecimalAmount, Option<String>);
+
+/// Parses an output of the `address-send-to-many` command.
+/// The string format is expected to be `address:amount` or `transfer(address,amount)` for coins,
+/// and `address:amount:token_id` or `transfer(address,amount,token_id)` for tokens,
+/// e.g `tmt1qy7y8ra99sgmt97lu2kn249yds23pnp7xsv62p77:10.1`.
+/// Unlike `parse_generic_currency_transfer`, the address and the token id are not decoded,
+/// so that the wallet can report which of them is invalid.
+pub fn parse_send_to_many_output<N: NodeInterface>(
+    input: &str,
+) -> Result<SendToManyOutput, WalletCliCommandError<N>> {
+    let invalid_format = || {
+        WalletCliCommandError::<N>::InvalidInput(format!(
+            "Invalid output {input}, expecting address:amount[:token_id] or transfer(address,amount[,token_id])"
+        ))
+    };
+
+    let (dest_str, amount_str, token_id) = match parse_funclike_expr(input) {
+        Some((name, mut args)) => {
+            match (name, args.next(), args.next(), args.next(), args.next()) {
+                ("transfer", Some(dest_str), Some(amount_str), token_id, None)
+                    if token_id.map_or(true, |id| !id.is_empty()) =>
+                {
+                    (dest_str, amount_str, token_id)
+                }
+                (_, _, _, _, _) => return Err(invalid_format()),
+            }
+        }
+        None => {
+            let mut parts = input.trim().split(':').map(|s| s.trim());
+            match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some(dest_str), Some(amount_str), token_id, None)
+                    if !dest_str.is_empty() && token_id.map_or(true, |id| !id.is_empty()) =>
+                {
+                    (dest_str, amount_str, token_id)
+                }
+                (_, _, _, _) => return Err(invalid_format()),
+            }
+        }
+    };
+
+    let amount = DecimalAmount::from_str(amount_str).map_err(|err| {
+        WalletCliCommandError::<N>::InvalidInput(format!("Invalid amount {amount_str} {err}"))
+    })?;
+
+    Ok((dest_str.to_owned(), amount, token_id.map(str::to_owned)))
+}
+
 /// Parses a string into `GenericTokenTransfer`.
 /// The string format is expected to be `transfer(token_id,address,amount)`
 pub fn parse_generic_token_transfer<N: NodeInterface>(
@@ -298,6 +637,51 @@ pub fn parse_generic_token_transfer<N: NodeInterface>(
     Ok(output)
 }
 
+/// An output in the JSON file of the `address-send-to-many` command
+#[derive(serde::Deserialize)]
+struct SendToManyFileOutput {
+    address: String,
+    amount: String,
+    #[serde(default)]
+    token_id: Option<String>,
+}
+
+/// Reads the outputs of the `address-send-to-many` command from a JSON file,
+/// which is expected to contain an array of objects with the `address`, `amount` and optional
+/// `token_id` fields, e.g. `[{"address": "tmt1qy7y8ra99sgmt97lu2kn249yds23pnp7xsv62p77", "amount": "10.1"}]`
+pub fn read_send_to_many_outputs<N: NodeInterface>(
+    path: &Path,
+) -> Result<Vec<SendToManyOutput>, WalletCliCommandError<N>> {
+    let contents = std::fs::read_to_string(path).map_err(|err| {
+        WalletCliCommandError::<N>::InvalidInput(format!(
+            "Cannot read file {}: {err}",
+            path.display()
+        ))
+    })?;
+    parse_send_to_many_outputs_json(&contents)
+}
+
+fn parse_send_to_many_outputs_json<N: NodeInterface>(
+    json: &str,
+) -> Result<Vec<SendToManyOutput>, WalletCliCommandError<N>> {
+    let outputs: Vec<SendToManyFileOutput> = serde_json::from_str(json).map_err(|err| {
+        WalletCliCommandError::<N>::InvalidInput(format!("Invalid outputs file: {err}"))
+    })?;
+
+    outputs
+        .into_iter()
+        .map(|output| {
+            let amount = DecimalAmount::from_str(&output.amount).map_err(|err| {
+                WalletCliCommandError::<N>::InvalidInput(format!(
+                    "Invalid amount {} {err}",
+                    output.amount
+                ))
+            })?;
+            Ok((output.address, amount, output.token_id))
+        })
+        .collect()
+}
+
 /// Parse simple strings of the form "foo(x,y,z)".
 fn parse_funclike_expr(input: &str) -> Option<(&str, impl Iterator<Item = &'_ str>)> {
     let input = input.trim();
@@ -345,6 +729,109 @@ pub fn parse_coin_output<N: NodeInterface>(
         .map_err(WalletCliCommandError::<N>::InvalidTxOutput)
 }
 
+/// Take the receiving address and the amount of the send command, where the address can also be
+/// a payment URI. The amount requested by the URI is used if no amount is given explicitly, and
+/// a different explicit amount is refused.
+pub fn parse_send_destination<N: NodeInterface>(
+    address: String,
+    amount: Option<DecimalAmount>,
+    chain_config: &ChainConfig,
+) -> Result<(String, DecimalAmount), WalletCliCommandError<N>> {
+    // Addresses can't contain a colon, so this must be a URI
+    if !address.contains(':') {
+        let amount = amount.ok_or(WalletCliCommandError::<N>::InvalidInput(
+            "The amount to send is missing".into(),
+        ))?;
+        return Ok((address, amount));
+    }
+
+    let uri = PaymentUri::from_string(chain_config, &address)
+        .map_err(|err| WalletCliCommandError::<N>::InvalidInput(err.to_string()))?;
+    let decimals = chain_config.coin_decimals();
+
+    let amount = match (amount, uri.amount()) {
+        (Some(amount), Some(uri_amount)) => {
+            let uri_amount_decimal = DecimalAmount::from_amount_no_padding(uri_amount, decimals);
+            ensure!(
+                amount.to_amount(decimals) == Some(uri_amount),
+                WalletCliCommandError::<N>::InvalidInput(format!(
+                    "The amount {amount} conflicts with the amount {uri_amount_decimal} \
+                    requested by the payment URI"
+                ))
+            );
+            amount
+        }
+        (Some(amount), None) => amount,
+        (None, Some(uri_amount)) => DecimalAmount::from_amount_no_padding(uri_amount, decimals),
+        (None, None) => {
+            return Err(WalletCliCommandError::<N>::InvalidInput(
+                "The payment URI doesn't request an amount, it must be specified explicitly".into(),
+            ));
+        }
+    };
+
+    Ok((uri.address().to_string(), amount))
+}
+
+/// The maximum number of public keys of a multisig address created by `address-create-multisig`
+pub const MAX_MULTISIG_PUBLIC_KEYS: u32 = 15;
+
+/// Create a classic multisig address that requires `min_required_signatures` signatures out of
+/// `public_keys_count` hex encoded public keys.
+pub fn create_multisig_address<N: NodeInterface>(
+    min_required_signatures: u32,
+    public_keys_count: u32,
+    public_keys: &[String],
+    chain_config: &ChainConfig,
+) -> Result<Address<Destination>, WalletCliCommandError<N>> {
+    let invalid_input = |msg: String| WalletCliCommandError::<N>::InvalidInput(msg);
+
+    ensure!(
+        public_keys_count <= MAX_MULTISIG_PUBLIC_KEYS,
+        invalid_input(format!(
+            "The number of public keys {public_keys_count} exceeds \
+            the maximum of {MAX_MULTISIG_PUBLIC_KEYS}"
+        ))
+    );
+    ensure!(
+        public_keys.len() == public_keys_count as usize,
+        invalid_input(format!(
+            "Expected {public_keys_count} public keys, but {} were provided",
+            public_keys.len()
+        ))
+    );
+    ensure!(
+        min_required_signatures <= public_keys_count,
+        invalid_input(format!(
+            "The number of required signatures {min_required_signatures} exceeds \
+            the number of public keys {public_keys_count}"
+        ))
+    );
+    let min_required_signatures = u8::try_from(min_required_signatures)
+        .ok()
+        .and_then(NonZeroU8::new)
+        .ok_or_else(|| {
+            invalid_input("The number of required signatures must be at least 1".into())
+        })?;
+
+    let public_keys = public_keys
+        .iter()
+        .enumerate()
+        .map(|(idx, public_key)| {
+            PublicKey::hex_decode_all(public_key).map_err(|err| {
+                invalid_input(format!("Invalid public key #{idx} '{public_key}': {err}"))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let challenge =
+        ClassicMultisigChallenge::new(chain_config, min_required_signatures, public_keys)
+            .map_err(|err| invalid_input(err.to_string()))?;
+    let destination = Destination::ClassicMultisig(PublicKeyHash::from(&challenge));
+
+    Address::new(chain_config, destination).map_err(|err| invalid_input(err.to_string()))
+}
+
 /// Try to parse a total token supply from a string
 /// Valid values are "unlimited", "lockable" and "fixed(Amount)"
 pub fn parse_token_supply<N: NodeInterface>(
@@ -438,10 +925,13 @@ impl YesNo {
 mod tests {
     use rstest::rstest;
 
+    use chainstate::{chainstate_interface::ChainstateInterface, StakePoolInfo};
     use common::{
         address::pubkeyhash::PublicKeyHash,
-        chain::{self, Destination},
+        chain::{self, Destination, PoolId},
+        primitives::per_thousand::PerThousand,
     };
+    use mocks::MockChainstateInterface;
     use node_comm::rpc_client::ColdWalletClient;
     use randomness::Rng;
     use test_utils::{
@@ -643,4 +1133,562 @@ mod tests {
             parse_assert_error(&format!("transfer {token_id_as_addr},{addr},{amount}"));
         }
     }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn test_decode_transaction(#[case] seed: Seed) {
+        use common::{
+            chain::{
+                block::timestamp::BlockTimestamp,
+                signature::inputsig::standard_signature::StandardInputSignature,
+                timelock::OutputTimeLock, Block, Transaction,
+            },
+            primitives::Amount,
+        };
+        use serialization::hex_encoded::HexEncoded;
+
+        let mut rng = make_seedable_rng(seed);
+        let chain_config = chain::config::create_unit_test_config();
+
+        let own_dest = Destination::PublicKeyHash(PublicKeyHash::random_using(&mut rng));
+        let own_addr = Address::new(&chain_config, own_dest.clone()).unwrap();
+        let foreign_dest = Destination::PublicKeyHash(PublicKeyHash::random_using(&mut rng));
+        let foreign_addr = Address::new(&chain_config, foreign_dest.clone()).unwrap();
+
+        let own_outpoint = UtxoOutPoint::new(
+            OutPointSourceId::Transaction(Id::<Transaction>::new(H256::random_using(&mut rng))),
+            rng.gen_range(0..10),
+        );
+        let foreign_outpoint = UtxoOutPoint::new(
+            OutPointSourceId::BlockReward(Id::<Block>::new(H256::random_using(&mut rng)).into()),
+            rng.gen_range(0..10),
+        );
+        let flags = rng.gen::<u128>();
+
+        let tx = Transaction::new(
+            flags,
+            vec![TxInput::Utxo(own_outpoint.clone()), TxInput::Utxo(foreign_outpoint.clone())],
+            vec![
+                TxOutput::Transfer(
+                    common::chain::output_value::OutputValue::Coin(Amount::from_atoms(
+                        12_345_000_000_000,
+                    )),
+                    foreign_dest.clone(),
+                ),
+                TxOutput::LockThenTransfer(
+                    common::chain::output_value::OutputValue::Coin(Amount::from_atoms(
+                        100_000_000_000,
+                    )),
+                    own_dest.clone(),
+                    OutputTimeLock::UntilTime(BlockTimestamp::from_int_seconds(1_700_000_000)),
+                ),
+            ],
+        )
+        .unwrap();
+        let tx_id = tx.get_id();
+        let signed_tx = SignedTransaction::new(
+            tx,
+            vec![
+                InputWitness::Standard(StandardInputSignature::new(
+                    SigHashType::try_from(SigHashType::SINGLE | SigHashType::ANYONECANPAY).unwrap(),
+                    vec![1, 2, 3],
+                )),
+                InputWitness::NoSignature(Some(vec![0xab, 0xcd])),
+            ],
+        )
+        .unwrap();
+
+        // Decode the transaction the same way the command does
+        let hex = HexEncoded::new(signed_tx).to_string();
+        let decoded_tx = HexEncoded::<SignedTransaction>::from_str(&hex).unwrap().take();
+
+        // The wallet's utxos as returned by `get_utxos`
+        let own_utxos = parse_own_utxo_destinations(vec![serde_json::to_value(
+            UtxoInfo::new(
+                own_outpoint,
+                TxOutput::Transfer(
+                    common::chain::output_value::OutputValue::Coin(Amount::from_atoms(1)),
+                    own_dest,
+                ),
+                &chain_config,
+            )
+            .unwrap(),
+        )
+        .unwrap()])
+        .unwrap();
+
+        let own_hash = match own_utxos.keys().next().unwrap().source_id() {
+            OutPointSourceId::Transaction(id) => id.to_hash(),
+            OutPointSourceId::BlockReward(_) => panic!("unexpected source"),
+        };
+        let foreign_hash = match foreign_outpoint.source_id() {
+            OutPointSourceId::BlockReward(id) => id.to_hash(),
+            OutPointSourceId::Transaction(_) => panic!("unexpected source"),
+        };
+        let own_index = own_utxos.keys().next().unwrap().output_index();
+        let foreign_index = foreign_outpoint.output_index();
+
+        let expected = format!(
+            "Transaction id: {:x}\n\
+             Version: 1\n\
+             Flags: {flags}\n\
+             Inputs (2):\n  \
+             #0: Transaction({own_hash:?}, {own_index}), witness: Standard (sighash: SINGLE|ANYONECANPAY), own key: {own_addr}\n  \
+             #1: BlockReward({foreign_hash:?}, {foreign_index}), witness: None (with data: abcd)\n\
+             Outputs (2):\n  \
+             #0: Transfer({foreign_addr}, 123.45)\n  \
+             #1: LockThenTransfer({own_addr}, 1, OutputTimeLock::UntilTime(2023-11-14 22:13:20 UTC))",
+            tx_id.to_hash(),
+        );
+        assert_eq!(
+            format_decoded_transaction(&decoded_tx, &own_utxos, &chain_config),
+            expected
+        );
+
+        // Without the wallet's utxos, no input is annotated
+        let expected_without_wallet = expected.replace(&format!(", own key: {own_addr}"), "");
+        assert_eq!(
+            format_decoded_transaction(&decoded_tx, &BTreeMap::new(), &chain_config),
+            expected_without_wallet
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn test_parse_send_destination(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let chain_config = chain::config::create_regtest();
+        let address = Address::new(
+            &chain_config,
+            Destination::PublicKeyHash(PublicKeyHash::random_using(&mut rng)),
+        )
+        .unwrap();
+        let parse = |address: &str, amount: Option<&str>| {
+            parse_send_destination::<ColdWalletClient>(
+                address.to_owned(),
+                amount.map(|amount| amount.parse().unwrap()),
+                &chain_config,
+            )
+            .map(|(address, amount)| (address, amount.to_string()))
+        };
+        let expected = |amount: &str| (address.to_string(), amount.to_owned());
+
+        assert_eq!(
+            parse(address.as_str(), Some("1.5")).unwrap(),
+            expected("1.5")
+        );
+        assert_matches!(
+            parse(address.as_str(), None),
+            Err(WalletCliCommandError::InvalidInput(_))
+        );
+
+        let uri = format!("mintlayer:{address}?amount=1.5");
+        assert_eq!(parse(&uri, None).unwrap(), expected("1.5"));
+        assert_eq!(parse(&uri, Some("1.50")).unwrap(), expected("1.50"));
+        assert_matches!(
+            parse(&uri, Some("2")),
+            Err(WalletCliCommandError::InvalidInput(_))
+        );
+
+        let uri = format!("mintlayer:{address}?label=Shop");
+        assert_eq!(parse(&uri, Some("2")).unwrap(), expected("2"));
+        assert_matches!(
+            parse(&uri, None),
+            Err(WalletCliCommandError::InvalidInput(_))
+        );
+
+        let mainnet_address = Address::new(
+            &chain::config::create_mainnet(),
+            Destination::PublicKeyHash(PublicKeyHash::random_using(&mut rng)),
+        )
+        .unwrap();
+        assert_matches!(
+            parse(&format!("mintlayer:{mainnet_address}?amount=1"), None),
+            Err(WalletCliCommandError::InvalidInput(_))
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy(), 1, 2)]
+    #[case(Seed::from_entropy(), 2, 3)]
+    #[case(Seed::from_entropy(), 15, 15)]
+    fn test_create_multisig_address(
+        #[case] seed: Seed,
+        #[case] min_required_signatures: u32,
+        #[case] public_keys_count: u32,
+    ) {
+        use crypto::key::{KeyKind, PrivateKey};
+        use serialization::hex::HexEncode;
+
+        let mut rng = make_seedable_rng(seed);
+        let chain_config = chain::config::create_regtest();
+
+        let public_keys = (0..public_keys_count)
+            .map(|_| PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr).1)
+            .collect::<Vec<_>>();
+        let public_keys_hex = public_keys.iter().map(HexEncode::hex_encode).collect::<Vec<_>>();
+
+        let address = create_multisig_address::<ColdWalletClient>(
+            min_required_signatures,
+            public_keys_count,
+            &public_keys_hex,
+            &chain_config,
+        )
+        .unwrap();
+
+        let challenge = ClassicMultisigChallenge::new(
+            &chain_config,
+            NonZeroU8::new(min_required_signatures as u8).unwrap(),
+            public_keys,
+        )
+        .unwrap();
+        let expected_destination = Destination::ClassicMultisig(PublicKeyHash::from(&challenge));
+        assert_eq!(address.as_object(), &expected_destination);
+
+        let decoded = Address::<Destination>::from_string(&chain_config, address.as_str()).unwrap();
+        assert_eq!(decoded.into_object(), expected_destination);
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn test_create_multisig_address_invalid(#[case] seed: Seed) {
+        use crypto::key::{KeyKind, PrivateKey};
+        use serialization::hex::HexEncode;
+
+        let mut rng = make_seedable_rng(seed);
+        let chain_config = chain::config::create_regtest();
+
+        let public_keys_hex = (0..MAX_MULTISIG_PUBLIC_KEYS + 1)
+            .map(|_| PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr).1.hex_encode())
+            .collect::<Vec<_>>();
+        let create = |n: u32, m: u32, public_keys: &[String]| {
+            create_multisig_address::<ColdWalletClient>(n, m, public_keys, &chain_config)
+        };
+
+        // Too many public keys
+        assert_matches!(
+            create(1, 16, &public_keys_hex),
+            Err(WalletCliCommandError::InvalidInput(_))
+        );
+        // The number of public keys doesn't match
+        assert_matches!(
+            create(1, 3, &public_keys_hex[..2]),
+            Err(WalletCliCommandError::InvalidInput(_))
+        );
+        // More required signatures than public keys
+        assert_matches!(
+            create(3, 2, &public_keys_hex[..2]),
+            Err(WalletCliCommandError::InvalidInput(_))
+        );
+        // No required signatures
+        assert_matches!(
+            create(0, 2, &public_keys_hex[..2]),
+            Err(WalletCliCommandError::InvalidInput(_))
+        );
+        // No public keys
+        assert_matches!(
+            create(0, 0, &[]),
+            Err(WalletCliCommandError::InvalidInput(_))
+        );
+        // Invalid public key
+        let invalid_keys = [public_keys_hex[0].clone(), "00abcd".to_owned()];
+        assert_matches!(
+            create(1, 2, &invalid_keys),
+            Err(WalletCliCommandError::InvalidInput(msg)) if msg.starts_with("Invalid public key #1")
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn test_parse_send_to_many_output(#[case] seed: Seed) {
+        use common::chain::tokens::TokenId;
+
+        let mut rng = make_seedable_rng(seed);
+        let chain_config = chain::config::create_unit_test_config();
+
+        let parse_assert_error = |str_to_parse: &str| {
+            let err = parse_send_to_many_output::<ColdWalletClient>(str_to_parse).unwrap_err();
+            assert_matches!(
+                err,
+                WalletCliCommandError::<ColdWalletClient>::InvalidInput(_)
+            );
+        };
+
+        for _ in 0..10 {
+            let token_id = TokenId::new(H256::random_using(&mut rng));
+            let token_id = Address::new(&chain_config, token_id).unwrap().into_string();
+            let pkh = PublicKeyHash::random_using(&mut rng);
+            let addr = Address::new(&chain_config, Destination::PublicKeyHash(pkh))
+                .unwrap()
+                .into_string();
+            let amount = DecimalAmount::from_uint_decimal(
+                rng.gen_range(0..=u128::MAX),
+                rng.gen_range(0..=u8::MAX),
+            );
+
+            let (parsed_addr, parsed_amount, parsed_token_id) =
+                parse_send_to_many_output::<ColdWalletClient>(&format!(
+                    "transfer({addr},{amount})"
+                ))
+                .unwrap();
+            assert_eq!(parsed_addr, addr);
+            assert_eq!(parsed_amount.mantissa(), amount.mantissa());
+            assert_eq!(parsed_amount.decimals(), amount.decimals());
+            assert_eq!(parsed_token_id, None);
+
+            let (parsed_addr, parsed_amount, parsed_token_id) =
+                parse_send_to_many_output::<ColdWalletClient>(&format!(
+                    "transfer({addr},{amount},{token_id})"
+                ))
+                .unwrap();
+            assert_eq!(parsed_addr, addr);
+            assert_eq!(parsed_amount.mantissa(), amount.mantissa());
+            assert_eq!(parsed_token_id, Some(token_id.clone()));
+
+            let (parsed_addr, parsed_amount, parsed_token_id) =
+                parse_send_to_many_output::<ColdWalletClient>(&format!("{addr}:{amount}")).unwrap();
+            assert_eq!(parsed_addr, addr);
+            assert_eq!(parsed_amount.mantissa(), amount.mantissa());
+            assert_eq!(parsed_amount.decimals(), amount.decimals());
+            assert_eq!(parsed_token_id, None);
+
+            let (parsed_addr, parsed_amount, parsed_token_id) =
+                parse_send_to_many_output::<ColdWalletClient>(&format!(
+                    "{addr}:{amount}:{token_id}"
+                ))
+                .unwrap();
+            assert_eq!(parsed_addr, addr);
+            assert_eq!(parsed_amount.mantissa(), amount.mantissa());
+            assert_eq!(parsed_token_id, Some(token_id.clone()));
+
+            let json = format!(
+                r#"[{{"address": "{addr}", "amount": "{amount}"}},
+                    {{"address": "{addr}", "amount": "{amount}", "token_id": "{token_id}"}}]"#
+            );
+            let parsed = parse_send_to_many_outputs_json::<ColdWalletClient>(&json).unwrap();
+            assert_eq!(parsed.len(), 2);
+            assert_eq!(parsed[0].0, addr);
+            assert_eq!(parsed[0].2, None);
+            assert_eq!(parsed[1].1.mantissa(), amount.mantissa());
+            assert_eq!(parsed[1].2, Some(token_id.clone()));
+
+            parse_assert_error(&format!("transfer({addr})"));
+            parse_assert_error(&format!("transfer({addr},foo)"));
+            parse_assert_error(&format!("send({addr},{amount})"));
+            parse_assert_error(&format!("transfer({addr},{amount},)"));
+            parse_assert_error(&format!("transfer({addr},{amount},{token_id},{amount})"));
+            parse_assert_error(&addr);
+            parse_assert_error(&format!("{addr}:foo"));
+            parse_assert_error(&format!(":{amount}"));
+            parse_assert_error(&format!("{addr}:{amount}:"));
+            parse_assert_error(&format!("{addr}:{amount}:{token_id}:{amount}"));
+            assert!(
+                parse_send_to_many_outputs_json::<ColdWalletClient>(&format!(
+                    r#"[{{"address": "{addr}", "amount": "foo"}}]"#
+                ))
+                .is_err()
+            );
+            assert!(
+                parse_send_to_many_outputs_json::<ColdWalletClient>(&format!(
+                    r#"{{"address": "{addr}", "amount": "{amount}"}}"#
+                ))
+                .is_err()
+            );
+        }
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    #[tokio::test]
+    async fn test_format_node_status(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+
+        let peer_count = rng.gen::<u32>();
+        let synced = NodeConnectionStatus {
+            peer_count,
+            is_synced: true,
+        };
+        assert_eq!(
+            format_node_status(synced),
+            format!(" [peers: {peer_count}]")
+        );
+        let syncing = NodeConnectionStatus {
+            peer_count,
+            is_synced: false,
+        };
+        assert_eq!(
+            format_node_status(syncing),
+            format!(" [peers: {peer_count}, syncing]")
+        );
+
+        // The cold wallet has no node, so it has no peers and is never synced
+        let cold_node = node_comm::make_cold_wallet_rpc_client(std::sync::Arc::new(
+            chain::config::create_unit_test_config(),
+        ));
+        let cold_status = NodeConnectionStatus {
+            peer_count: cold_node.get_peer_count().await.unwrap(),
+            is_synced: cold_node.is_node_synced().await.unwrap(),
+        };
+        assert_eq!(format_node_status(cold_status), " [peers: 0, syncing]");
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn test_filter_and_sort_stake_pools(#[case] seed: Seed) {
+        use CliStakePoolsSortOrder::{BlocksProducedDesc, PledgeDesc, TotalStakeDesc};
+
+        let mut rng = make_seedable_rng(seed);
+        let chain_config = chain::config::create_regtest();
+
+        // Pledge, total stake, margin ratio per thousand and blocks produced of each pool;
+        // the node returns the pools ordered by id
+        let pools_params = [
+            (100, 500, 50, 3),
+            (400, 400, 200, 10),
+            (200, 900, 100, 0),
+            (300, 600, 0, 7),
+            (50, 50, 1000, 1),
+        ];
+        let mut pool_ids = (0..pools_params.len())
+            .map(|_| PoolId::new(H256::random_using(&mut rng)))
+            .collect::<Vec<_>>();
+        pool_ids.sort();
+        let pools = pool_ids
+            .iter()
+            .zip(pools_params)
+            .map(
+                |(pool_id, (pledge, total_stake, margin_ratio, blocks_produced))| StakePoolInfo {
+                    pool_id: *pool_id,
+                    pledge: Amount::from_atoms(pledge),
+                    total_stake: Amount::from_atoms(total_stake),
+                    cost_per_block: Amount::from_atoms(rng.gen_range(0..100)),
+                    margin_ratio_per_thousand: PerThousand::new(margin_ratio).unwrap(),
+                    blocks_produced,
+                },
+            )
+            .collect::<Vec<_>>();
+
+        let mut chainstate = MockChainstateInterface::new();
+        chainstate.expect_get_all_pools().returning(move || Ok(pools.clone()));
+
+        let test_cases: [(
+            Option<u128>,
+            Option<f64>,
+            Option<CliStakePoolsSortOrder>,
+            &[usize],
+        ); 17] = [
+            (None, None, None, &[0, 1, 2, 3, 4]),
+            (None, None, Some(PledgeDesc), &[1, 3, 2, 0, 4]),
+            (None, None, Some(TotalStakeDesc), &[2, 3, 0, 1, 4]),
+            (None, None, Some(BlocksProducedDesc), &[1, 3, 0, 4, 2]),
+            // The bounds are inclusive
+            (Some(200), None, None, &[1, 2, 3]),
+            (Some(200), None, Some(PledgeDesc), &[1, 3, 2]),
+            (Some(200), None, Some(TotalStakeDesc), &[2, 3, 1]),
+            (Some(200), None, Some(BlocksProducedDesc), &[1, 3, 2]),
+            (None, Some(0.1), None, &[0, 2, 3]),
+            (None, Some(0.1), Some(PledgeDesc), &[3, 2, 0]),
+            (None, Some(0.1), Some(TotalStakeDesc), &[2, 3, 0]),
+            (None, Some(0.1), Some(BlocksProducedDesc), &[3, 0, 2]),
+            (Some(200), Some(0.1), None, &[2, 3]),
+            (Some(200), Some(0.1), Some(PledgeDesc), &[3, 2]),
+            (Some(200), Some(0.1), Some(TotalStakeDesc), &[2, 3]),
+            (Some(200), Some(0.1), Some(BlocksProducedDesc), &[3, 2]),
+            (Some(1000), None, Some(PledgeDesc), &[]),
+        ];
+
+        for (min_pledge, max_margin_ratio, sort_by, expected) in test_cases {
+            let pools = chainstate
+                .get_all_pools()
+                .unwrap()
+                .iter()
+                .map(|info| RpcStakePoolInfo::new(&chain_config, info).unwrap())
+                .collect();
+            let pools = filter_and_sort_stake_pools(
+                pools,
+                min_pledge.map(Amount::from_atoms),
+                max_margin_ratio,
+                sort_by,
+            );
+
+            let listed_ids = pools
+                .iter()
+                .map(|pool| pool.pool_id.decode_object(&chain_config).unwrap())
+                .collect::<Vec<_>>();
+            let expected_ids = expected.iter().map(|idx| pool_ids[*idx]).collect::<Vec<_>>();
+            assert_eq!(
+                listed_ids, expected_ids,
+                "min_pledge: {min_pledge:?}, max_margin_ratio: {max_margin_ratio:?}, sort_by: {sort_by:?}"
+            );
+
+            let table = format_stake_pools_table(&pools);
+            for pool in &pools {
+                assert!(table.contains(pool.pool_id.as_str()));
+            }
+        }
+    }
+
+    #[test]
+    fn watch_event_lines() {
+        use common::primitives::BlockHeight;
+        use crypto::key::hdkd::u31::U31;
+        use wallet::account::transaction_list::{TransactionInfo, TxType};
+        use wallet_types::wallet_tx::TxState;
+
+        let chain_config = chain::config::create_unit_test_config();
+        let block_id = Id::new(H256::from_low_u64_be(1));
+        let txid = Id::new(H256::from_low_u64_be(2));
+
+        let line = format_watch_event(
+            &chain_config,
+            &WatchEvent::NewTip {
+                block_height: BlockHeight::new(5),
+                block_id,
+                confirmed_txs: 2,
+            },
+        );
+        assert_eq!(
+            line,
+            format!("New tip: height 5, id {block_id:x}, confirmed transactions: 2")
+        );
+
+        let line = format_watch_event(
+            &chain_config,
+            &WatchEvent::Transaction {
+                account_index: U31::ZERO,
+                info: TransactionInfo {
+                    txid,
+                    tx_type: TxType::Received {
+                        amount: Amount::from_atoms(150_000_000_000),
+                    },
+                    timestamp: None,
+                    state: TxState::InMempool(0),
+                },
+            },
+        );
+        assert_eq!(
+            line,
+            format!("Transaction {txid:x} (account 0): Received 1.5, InMempool")
+        );
+
+        let line = format_watch_event(
+            &chain_config,
+            &WatchEvent::BlockStaked {
+                account_index: U31::ONE,
+                block_height: BlockHeight::new(6),
+                block_id,
+            },
+        );
+        assert_eq!(
+            line,
+            format!("Block staked (account 1): height 6, id {block_id:x}")
+        );
+    }
 }
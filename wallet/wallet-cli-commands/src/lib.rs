@@ -15,17 +15,21 @@
 
 mod command_handler;
 mod errors;
+mod help;
 mod helper_types;
+pub mod plugin;
 
 pub use command_handler::CommandHandler;
 pub use errors::WalletCliCommandError;
+use help::examples;
+pub use help::CommandCategory;
 use helper_types::YesNo;
 use rpc::description::{Described, Module};
 use wallet_rpc_lib::{types::NodeInterface, ColdWalletRpcDescription, WalletRpcDescription};
 
 use std::{fmt::Debug, num::NonZeroUsize, path::PathBuf, time::Duration};
 
-use clap::{Command, FromArgMatches, Parser, Subcommand};
+use clap::{ArgMatches, Command, FromArgMatches, Parser, Subcommand};
 
 use common::{
     chain::{Block, SignedTransaction, Transaction},
@@ -35,16 +39,22 @@ use crypto::key::{hdkd::u31::U31, PrivateKey, PublicKey};
 use p2p_types::{bannable_address::BannableAddress, PeerId};
 use serialization::hex_encoded::HexEncoded;
 use utils_networking::IpOrSocketAddress;
+use wallet_types::keys::WalletFingerprint;
 
 use self::helper_types::{
-    CliForceReduce, CliIsFreezable, CliIsUnfreezable, CliStoreSeedPhrase, CliUtxoState,
-    CliUtxoTypes, CliWithLocked, EnableOrDisable,
+    CliDuplicateOutputs, CliForceReduce, CliIsFreezable, CliIsUnfreezable, CliStakePoolsSortOrder,
+    CliStoreSeedPhrase, CliUtxoState, CliUtxoTypes, CliWithLocked, EnableOrDisable,
 };
 
 #[derive(Debug, Parser)]
 #[clap(rename_all = "kebab-case")]
 pub enum WalletManagementCommand {
     #[clap(name = "wallet-create")]
+    #[clap(after_help = examples!(
+        "wallet-create \"my wallet\" store-seed-phrase",
+        "wallet-create wallet.dat do-not-store-seed-phrase \"<12, 15 or 24 words>\" --passphrase secret",
+        "wallet-create wallet.dat do-not-store-seed-phrase \"<12, 15 or 24 words>\" --ask-passphrase --expected-fingerprint 1a2b3c4d",
+    ))]
     CreateWallet {
         /// File path of the wallet file
         wallet_path: PathBuf,
@@ -62,9 +72,29 @@ pub enum WalletManagementCommand {
         /// Passphrase along the mnemonic
         #[arg(long = "passphrase")]
         passphrase: Option<String>,
+
+        /// Ask for the passphrase without echoing it, instead of passing it as an argument.
+        /// Only available in interactive mode.
+        #[arg(long, conflicts_with = "passphrase")]
+        ask_passphrase: bool,
+
+        /// The fingerprint the wallet is expected to have, as shown by `wallet-fingerprint`
+        /// for the original wallet. A warning is printed if the restored wallet doesn't match it.
+        #[arg(long)]
+        expected_fingerprint: Option<WalletFingerprint>,
+
+        /// The last used change address index of the original wallet, as shown by
+        /// `wallet-fingerprint`. A warning is printed if the wallet's lookahead size may be
+        /// too small to find all the change outputs.
+        #[arg(long)]
+        expected_last_used_change_index: Option<u32>,
     },
 
     #[clap(name = "wallet-open")]
+    #[clap(after_help = examples!(
+        "wallet-open \"my wallet\"",
+        "wallet-open wallet.dat \"my password\"",
+    ))]
     OpenWallet {
         /// File path of the wallet file
         wallet_path: PathBuf,
@@ -75,14 +105,33 @@ pub enum WalletManagementCommand {
         force_change_wallet_type: bool,
     },
 
+    /// Create a new wallet file from a backup made with `wallet-backup` and open it.
+    /// The wallet rescans the blockchain to rebuild its transaction history and balances.
+    #[clap(name = "wallet-restore-from-backup")]
+    #[clap(after_help = examples!(
+        "wallet-restore-from-backup wallet.backup wallet.dat",
+        "wallet-restore-from-backup wallet.backup wallet.dat \"backup password\"",
+    ))]
+    RestoreWalletFromBackup {
+        /// File path of the backup file
+        backup_path: PathBuf,
+        /// File path of the new wallet file
+        wallet_path: PathBuf,
+        /// The password the backup was made with, if any
+        backup_password: Option<String>,
+    },
+
     #[clap(name = "wallet-close")]
+    #[clap(after_help = examples!("wallet-close"))]
     CloseWallet,
 
     /// Shutdown the RPC interface or the remote wallet it is connected to
     /// and exit the wallet
+    #[clap(after_help = examples!("rpc-shutdown-and-exit"))]
     RpcShutdownAndExit,
 
     /// Exit the wallet
+    #[clap(after_help = examples!("exit"))]
     Exit,
 }
 
@@ -90,33 +139,66 @@ pub enum WalletManagementCommand {
 #[clap(rename_all = "kebab-case")]
 pub enum ColdWalletCommand {
     #[clap(name = "wallet-info")]
+    #[clap(after_help = examples!("wallet-info"))]
     WalletInfo,
 
     #[clap(name = "wallet-encrypt-private-keys")]
+    #[clap(after_help = examples!("wallet-encrypt-private-keys \"my password\""))]
     EncryptPrivateKeys {
         /// The new encryption password
         password: String,
     },
 
     #[clap(name = "wallet-disable-private-keys-encryption")]
+    #[clap(after_help = examples!("wallet-disable-private-keys-encryption"))]
     RemovePrivateKeysEncryption,
 
     #[clap(name = "wallet-unlock-private-keys")]
+    #[clap(after_help = examples!("wallet-unlock-private-keys \"my password\""))]
     UnlockPrivateKeys {
         /// The current encryption password.
         password: String,
     },
 
     #[clap(name = "wallet-lock-private-keys")]
+    #[clap(after_help = examples!("wallet-lock-private-keys"))]
     LockPrivateKeys,
 
     #[clap(name = "wallet-show-seed-phrase")]
+    #[clap(after_help = examples!("wallet-show-seed-phrase"))]
     ShowSeedPhrase,
 
     #[clap(name = "wallet-purge-seed-phrase")]
+    #[clap(after_help = examples!("wallet-purge-seed-phrase"))]
     PurgeSeedPhrase,
 
+    /// Show the fingerprint of the wallet's root key, which can be compared between wallets
+    /// to check that they were restored from the same seed phrase and passphrase
+    #[clap(name = "wallet-fingerprint")]
+    #[clap(after_help = examples!("wallet-fingerprint"))]
+    WalletFingerprint,
+
+    /// Write an encrypted backup of the wallet to a new file.
+    /// The backup contains the keys, accounts, labels, wallet created transactions and settings,
+    /// but not the transactions that are recovered by rescanning the blockchain.
+    /// Without a password, the private keys are only protected by the wallet's own encryption.
+    #[clap(name = "wallet-backup")]
+    #[clap(after_help = examples!(
+        "wallet-backup wallet.backup",
+        "wallet-backup wallet.backup \"backup password\"",
+    ))]
+    BackupWallet {
+        /// File path of the backup file
+        backup_path: PathBuf,
+        /// The password to encrypt the backup with
+        password: Option<String>,
+    },
+
     #[clap(name = "wallet-set-lookahead-size")]
+    #[clap(after_help = examples!(
+        "wallet-set-lookahead-size 100",
+        "wallet-set-lookahead-size 10 i-know-what-i-am-doing",
+    ))]
     SetLookaheadSize {
         /// The new lookahead size
         lookahead_size: u32,
@@ -128,55 +210,205 @@ pub enum ColdWalletCommand {
 
     /// Creates a QR code of the provided address
     #[clap(name = "address-qrcode")]
+    #[clap(after_help = examples!("address-qrcode tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh"))]
     AddressQRCode {
         /// A Destination address
         address: String,
     },
 
+    /// Generate a new address that has never received any funds
     #[clap(name = "address-new")]
-    NewAddress,
+    #[clap(after_help = examples!("address-new", "address-new --qr", "address-new --allow-reuse"))]
+    NewAddress {
+        /// Also show a QR code of the address, to be scanned by a mobile device
+        #[arg(long)]
+        qr: bool,
+        /// Return the last issued address even if it has already received funds.
+        /// Reusing addresses hurts the privacy of both the sender and the receiver
+        #[arg(long)]
+        allow_reuse: bool,
+    },
+
+    #[clap(name = "payment-uri-new")]
+    #[clap(after_help = examples!(
+        "payment-uri-new 10.5",
+        "payment-uri-new 10.5 \"Coffee shop\" --qr",
+    ))]
+    NewPaymentUri {
+        /// The requested amount of coins, in decimal format
+        amount: DecimalAmount,
+        /// A label describing the recipient of the payment
+        label: Option<String>,
+        /// Also show a QR code of the payment URI, to be scanned by a mobile device
+        #[arg(long)]
+        qr: bool,
+    },
+
+    #[clap(name = "payment-uri-decode")]
+    #[clap(after_help = examples!(
+        "payment-uri-decode mintlayer:tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh?amount=10.5",
+    ))]
+    DecodePaymentUri {
+        /// The payment URI
+        uri: String,
+    },
 
     /// Reveal the public key behind this address in hex encoding
     #[clap(name = "address-reveal-public-key-as-hex")]
-    RevealPublicKeyHex {
-        public_key_hash: String,
-    },
+    #[clap(after_help = examples!(
+        "address-reveal-public-key-as-hex tmt1qx3aa7w2gak6xxcfs2eq47qgg09c56de0slcg8p3",
+    ))]
+    RevealPublicKeyHex { public_key_hash: String },
 
     /// Reveal the public key behind this address in address encoding.
     /// Note that this isn't a normal address to be used in transactions.
     /// It's preferred to take the address from address-show command
     #[clap(name = "address-reveal-public-key-as-address")]
-    RevealPublicKey {
-        public_key_hash: String,
+    #[clap(after_help = examples!(
+        "address-reveal-public-key-as-address tmt1qx3aa7w2gak6xxcfs2eq47qgg09c56de0slcg8p3",
+    ))]
+    RevealPublicKey { public_key_hash: String },
+
+    /// Create a classic multisig address, requiring the given number of signatures out of
+    /// the specified public keys. The address isn't added to the wallet,
+    /// use `standalone-add-multisig` to track its balance.
+    #[clap(name = "address-create-multisig")]
+    #[clap(after_help = examples!(
+        "address-create-multisig 1 2 000279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798 0002c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+    ))]
+    CreateMultisigAddress {
+        /// The number of signatures required to spend from the address
+        n: u32,
+        /// The total number of public keys
+        m: u32,
+        /// The hex encoded public keys, as shown by `address-reveal-public-key-as-hex`
+        pubkeys: Vec<String>,
     },
 
     #[clap(name = "address-show")]
-    ShowReceiveAddresses,
+    #[clap(after_help = examples!("address-show", "address-show --only-unused"))]
+    ShowReceiveAddresses {
+        /// Only show the addresses that have never received any funds
+        #[arg(long)]
+        only_unused: bool,
+    },
+
+    /// Show the change addresses issued by the wallet with their usage state
+    #[clap(name = "change-address-show")]
+    #[clap(after_help = examples!("change-address-show", "change-address-show --only-used"))]
+    ShowChangeAddresses {
+        /// Only show the addresses that have never received any funds
+        #[arg(long, conflicts_with = "only_used")]
+        only_unused: bool,
+        /// Only show the addresses that have received funds
+        #[arg(long)]
+        only_used: bool,
+    },
+
+    /// Export the account's extended public key, serialized according to BIP32.
+    /// It can be imported by other software to watch the account's addresses;
+    /// the private keys never leave the wallet.
+    #[clap(name = "account-export-xpub")]
+    #[clap(after_help = examples!("account-export-xpub 0"))]
+    ExportXpub {
+        /// The index of the account to export
+        account: U31,
+    },
+
+    /// Export the output descriptor of the account's receiving addresses, e.g. `pkh(xpub.../0/*)`
+    #[clap(name = "account-export-descriptor")]
+    #[clap(after_help = examples!("account-export-descriptor 0 pkh"))]
+    ExportDescriptor {
+        /// The index of the account to export
+        account: U31,
+        /// The descriptor type, one of: pkh, pk
+        descriptor_type: String,
+    },
+
+    /// Write the first receive and change addresses of the selected account to a new file,
+    /// signed with the account's key, to be imported by a hot wallet with `importaddressfile`.
+    /// The private keys never leave the wallet, but the wallet must be unlocked to sign the file.
+    #[clap(name = "account-export-addresses", visible_alias = "exportaddresses")]
+    #[clap(after_help = examples!(
+        "account-export-addresses addresses.bin",
+        "exportaddresses addresses.bin --count 100",
+    ))]
+    ExportAddresses {
+        /// File path of the new address file
+        path: PathBuf,
+        /// The number of the receive addresses to export, the same number of change addresses
+        /// is exported too. Export more than before to extend the set on the hot wallet.
+        #[arg(long, default_value_t = 20)]
+        count: u32,
+    },
+
+    /// Add, replace or delete the label of an address.
+    /// The address doesn't have to belong to the wallet, so recipients can be labeled too.
+    #[clap(name = "address-set-label", visible_alias = "setaddresslabel")]
+    #[clap(after_help = examples!(
+        "address-set-label tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh \"savings\"",
+        "setaddresslabel tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh",
+    ))]
+    SetAddressLabel {
+        /// The address to label
+        address: String,
+        /// The new label, not specifying a label will remove the existing one
+        label: Option<String>,
+    },
+
+    /// Search the transactions and the addresses of the selected account
+    /// by their label, transaction id or address. The search is case-insensitive.
+    #[clap(name = "wallet-search", visible_alias = "search")]
+    #[clap(after_help = examples!("wallet-search rent", "search tmt1q864lut"))]
+    Search {
+        /// The text to look for
+        text: String,
+    },
 
     #[clap(name = "standalone-address-show")]
+    #[clap(after_help = examples!("standalone-address-show"))]
     ShowStandaloneAddresses,
 
     #[clap(name = "standalone-address-details")]
-    ShowStandaloneAddressDetails {
-        address: String,
-    },
+    #[clap(after_help = examples!(
+        "standalone-address-details tmt1qx3aa7w2gak6xxcfs2eq47qgg09c56de0slcg8p3",
+    ))]
+    ShowStandaloneAddressDetails { address: String },
 
     #[clap(name = "staking-new-vrf-public-key")]
+    #[clap(after_help = examples!("staking-new-vrf-public-key"))]
     NewVrfPublicKey,
 
     #[clap(name = "staking-show-vrf-public-keys")]
+    #[clap(after_help = examples!("staking-show-vrf-public-keys"))]
     GetVrfPublicKey,
 
     #[clap(name = "staking-show-legacy-vrf-key")]
+    #[clap(after_help = examples!("staking-show-legacy-vrf-key"))]
     GetLegacyVrfPublicKey,
 
     #[clap(name = "account-sign-raw-transaction")]
+    #[clap(after_help = examples!("account-sign-raw-transaction 01000400008e1b1ec2b5b4ba6b8a0dd0a4a4c9a1ff3a1f5c5e8d1f2c9b7d2a6b0ab5c4e3d2010000000400000b00a0724e180901a3def9ca476da31b0982b20af80843cb8a69b97c"))]
     SignRawTransaction {
         /// Hex encoded transaction or PartiallySignedTransaction.
         transaction: String,
     },
 
+    /// Decode a hex encoded signed transaction and show its inputs, outputs and witnesses.
+    /// The inputs spending the outputs owned by the selected account are marked as such.
+    /// Unlike `transaction-inspect`, this works offline and without an open wallet: the witnesses
+    /// are shown as they are, without checking the signatures or computing the fees
+    #[clap(name = "transaction-decode")]
+    #[clap(after_help = examples!("transaction-decode 01000400008e1b1ec2b5b4ba6b8a0dd0a4a4c9a1ff3a1f5c5e8d1f2c9b7d2a6b0ab5c4e3d2010000000400000b00a0724e180901a3def9ca476da31b0982b20af80843cb8a69b97c040000"))]
+    DecodeTransaction {
+        /// Hex encoded signed transaction
+        tx_hex: HexEncoded<SignedTransaction>,
+    },
+
     #[clap(name = "challenge-sign-hex")]
+    #[clap(after_help = examples!(
+        "challenge-sign-hex 48656c6c6f tmt1qx3aa7w2gak6xxcfs2eq47qgg09c56de0slcg8p3",
+    ))]
     #[clap(hide = true)]
     SignChallegeHex {
         /// Hex encoded message to be signed
@@ -186,6 +418,9 @@ pub enum ColdWalletCommand {
     },
 
     #[clap(name = "challenge-sign-plain")]
+    #[clap(after_help = examples!(
+        "challenge-sign-plain \"Hello, world\" tmt1qx3aa7w2gak6xxcfs2eq47qgg09c56de0slcg8p3",
+    ))]
     SignChallege {
         /// The message to be signed
         message: String,
@@ -194,6 +429,9 @@ pub enum ColdWalletCommand {
     },
 
     #[clap(name = "challenge-verify-hex")]
+    #[clap(after_help = examples!(
+        "challenge-verify-hex 48656c6c6f 00032197c70763930862f5ef24b0bf486645242a1b5853d2c80ee97e532b4c4d374c005ca75534f2df83b5c5735955d82259f9ed9615c8b13682aac78bc863f2afb277e5d41def0e74d9a5face9205f729e045f0692f50c4510ac306ac2b7f8049c2c3 tmt1qx3aa7w2gak6xxcfs2eq47qgg09c56de0slcg8p3",
+    ))]
     #[clap(hide = true)]
     VerifyChallengeHex {
         /// The hex encoded message that was signed
@@ -205,6 +443,9 @@ pub enum ColdWalletCommand {
     },
 
     #[clap(name = "challenge-verify-plain")]
+    #[clap(after_help = examples!(
+        "challenge-verify-plain \"Hello, world\" 00032197c70763930862f5ef24b0bf486645242a1b5853d2c80ee97e532b4c4d374c00e9a5cb6804a6db9ff1397174d8235238acf75bc0b393e5d428b05e737d0b87293ae97c88cb31bab0bd84fbb4c41e5ce3bafff8980558f2fba5658f6e4ad42f6c tmt1qx3aa7w2gak6xxcfs2eq47qgg09c56de0slcg8p3",
+    ))]
     VerifyChallenge {
         /// The message that was signed
         message: String,
@@ -216,16 +457,20 @@ pub enum ColdWalletCommand {
 
     /// Print command history in the wallet for this execution
     #[clap(name = "history-print")]
+    #[clap(after_help = examples!("history-print"))]
     PrintHistory,
 
     /// Clear command history for this execution
     #[clap(name = "history-clear")]
+    #[clap(after_help = examples!("history-clear"))]
     ClearHistory,
 
     /// Clear screen
     #[clap(name = "screen-clear")]
+    #[clap(after_help = examples!("screen-clear"))]
     ClearScreen,
 
+    #[clap(after_help = examples!("version"))]
     Version,
 }
 
@@ -236,16 +481,66 @@ pub enum WalletCommand {
     ColdCommands(ColdWalletCommand),
 
     #[clap(name = "account-create")]
+    #[clap(after_help = examples!(
+        "account-create",
+        "account-create Savings",
+    ))]
     CreateNewAccount { name: Option<String> },
 
     #[clap(name = "account-rename")]
+    #[clap(after_help = examples!("account-rename Savings"))]
     RenameAccount { name: Option<String> },
 
+    /// Set the spending policy of the selected account, replacing the existing one.
+    /// Not specifying any of the limits removes the policy.
+    /// Requires the admin password the wallet RPC server was started with.
+    #[clap(name = "account-set-spending-policy")]
+    #[clap(after_help = examples!(
+        "account-set-spending-policy my-admin-password --max-per-tx 100 --daily-cap 500",
+        "account-set-spending-policy my-admin-password",
+    ))]
+    SetSpendingPolicy {
+        /// The admin password of the wallet RPC server
+        admin_password: String,
+        /// The maximum amount of coins that a single transaction can send
+        #[arg(long)]
+        max_per_tx: Option<DecimalAmount>,
+        /// The maximum amount of coins that can be sent within 24 hours
+        #[arg(long)]
+        daily_cap: Option<DecimalAmount>,
+        /// An address that coins and tokens can be sent to; if none are specified,
+        /// any address is allowed
+        #[arg(long = "allowed-address")]
+        allowed_addresses: Vec<String>,
+    },
+
+    /// Show the spending policy of the selected account and the coins sent within the last 24 hours.
+    #[clap(name = "account-show-spending-policy")]
+    ShowSpendingPolicy,
+
+    /// Set the number of confirmations a transaction needs before its outputs are
+    /// considered confirmed and selected automatically when sending.
+    #[clap(name = "wallet-set-confirmation-threshold")]
+    #[clap(after_help = examples!("wallet-set-confirmation-threshold 6"))]
+    SetConfirmationThreshold {
+        /// The new confirmation threshold, must be at least 1
+        confirmation_threshold: u64,
+    },
+
+    /// Show the number of confirmations a transaction needs before its outputs are considered confirmed.
+    #[clap(name = "wallet-show-confirmation-threshold")]
+    ShowConfirmationThreshold,
+
     /// Switch to a given wallet account.
     #[clap(name = "account-select")]
+    #[clap(after_help = examples!("account-select 1"))]
     SelectAccount { account_index: U31 },
 
     #[clap(name = "account-utxos")]
+    #[clap(after_help = examples!(
+        "account-utxos",
+        "account-utxos transfer locked confirmed in-mempool",
+    ))]
     ListUtxo {
         /// The type of utxo to be listed. Default is "all".
         #[arg(value_enum, default_value_t = CliUtxoTypes::All)]
@@ -259,6 +554,10 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "account-balance")]
+    #[clap(after_help = examples!(
+        "account-balance",
+        "account-balance any confirmed in-mempool",
+    ))]
     GetBalance {
         /// Whether to include locked outputs (outputs that cannot be spend and need time to mature)
         #[arg(value_enum, default_value_t = CliWithLocked::Unlocked)]
@@ -268,7 +567,26 @@ pub enum WalletCommand {
         utxo_states: Vec<CliUtxoState>,
     },
 
+    /// Show the balance of the selected account split into confirmed, pending
+    /// (below the confirmation threshold) and unconfirmed (in mempool) amounts.
+    #[clap(name = "account-balance-by-confirmations")]
+    #[clap(after_help = examples!(
+        "account-balance-by-confirmations",
+        "account-balance-by-confirmations --confirmation-threshold 6 any",
+    ))]
+    GetBalanceByConfirmations {
+        /// Whether to include locked outputs (outputs that cannot be spend and need time to mature)
+        #[arg(value_enum, default_value_t = CliWithLocked::Unlocked)]
+        with_locked: CliWithLocked,
+        /// The number of confirmations to use instead of the wallet's confirmation threshold
+        #[arg(long)]
+        confirmation_threshold: Option<u64>,
+    },
+
     #[clap(name = "standalone-address-label-rename")]
+    #[clap(after_help = examples!(
+        "standalone-address-label-rename tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh --label \"cold storage\"",
+    ))]
     StandaloneAddressLabelRename {
         /// The existing standalone address
         address: String,
@@ -279,6 +597,9 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "standalone-add-watch-only-address")]
+    #[clap(after_help = examples!(
+        "standalone-add-watch-only-address tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh --label watched --no-rescan true",
+    ))]
     AddStandaloneKey {
         /// The new standalone watch only address to be added to the selected account
         address: String,
@@ -292,7 +613,43 @@ pub enum WalletCommand {
         no_rescan: Option<bool>,
     },
 
+    /// Watch the addresses from a file written by `exportaddresses` of a cold wallet with
+    /// the selected account, after checking that the file is signed by the exporting account.
+    /// The wallet can't derive any addresses beyond the imported ones, so a warning is printed
+    /// when few unused ones remain. The blockchain is rescanned if any new addresses are added.
+    #[clap(
+        name = "account-import-address-file",
+        visible_alias = "importaddressfile"
+    )]
+    #[clap(after_help = examples!("account-import-address-file addresses.bin"))]
+    ImportAddressFile {
+        /// File path of the address file
+        path: PathBuf,
+    },
+
+    /// Write a proof that the selected account controls its confirmed utxos at the current best
+    /// block to a new file, signing the message with each of them. The proof can be checked by
+    /// anyone with a node using `node-verify-proof-of-reserves`.
+    #[clap(
+        name = "account-generate-proof-of-reserves",
+        visible_alias = "generateproofofreserves"
+    )]
+    #[clap(after_help = examples!(
+        "account-generate-proof-of-reserves \"Reserves of Example Ltd on 2024-06-30\" reserves.bin",
+    ))]
+    GenerateProofOfReserves {
+        /// The message to sign, e.g. the name of the owner and the date
+        message: String,
+        /// File path of the new proof file
+        path: PathBuf,
+    },
+
+    /// Add a private key that is not derived from the wallet's seed phrase to the selected account,
+    /// e.g. one recovered from another wallet. Adding a key that is already known to the account fails
     #[clap(name = "standalone-add-private-key-from-hex")]
+    #[clap(after_help = examples!(
+        "standalone-add-private-key-from-hex 00f571aa6a2e69072f2de07b0723172eebc5f8fdee1f3a0ecc63229830773b58f0 --label recovered",
+    ))]
     AddStandalonePrivateKey {
         /// The new hex encoded standalone private key to be added to the selected account
         hex_private_key: HexEncoded<PrivateKey>,
@@ -307,6 +664,9 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "standalone-add-multisig")]
+    #[clap(after_help = examples!(
+        "standalone-add-multisig 2 tpmt1qqqqymnnhlcajjc2w5qpgklgyu3ww32ahgt986d4dm42qs79nq9w6dm0ys30aa tpmt1qqqqxy0gtdmhpmzraqprkacmu2enfv892s7d97xe3thrercfxe9uzraj3qusp8 --label shared",
+    ))]
     AddStandaloneMultisig {
         /// The minimum required signatures out of the specified public keys
         min_required_signatures: u8,
@@ -324,6 +684,10 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "standalone-multisig-utxos")]
+    #[clap(after_help = examples!(
+        "standalone-multisig-utxos",
+        "standalone-multisig-utxos transfer any confirmed",
+    ))]
     ListMultisigUtxo {
         /// The type of utxo to be listed. Default is "all".
         #[arg(value_enum, default_value_t = CliUtxoTypes::All)]
@@ -337,6 +701,9 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "token-nft-issue-new")]
+    #[clap(after_help = examples!(
+        "token-nft-issue-new tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh 2cf24dba5fb0a30e26e83b2ac5b9e29e \"My NFT\" \"A unique item\" MNFT",
+    ))]
     IssueNewNft {
         /// The receiver of the token
         destination_address: String,
@@ -358,6 +725,10 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "token-issue-new")]
+    #[clap(after_help = examples!(
+        "token-issue-new XYZ 2 https://example.com tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh unlimited freezable",
+        "token-issue-new XYZ 2 https://example.com tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh \"fixed(1000000)\" not-freezable",
+    ))]
     IssueNewToken {
         /// The ticker/symbol of the token created
         token_ticker: String,
@@ -374,15 +745,24 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "token-change-authority")]
+    #[clap(after_help = examples!(
+        "token-change-authority tmltk183rfa8tvtp6ax7jr7dfaf7ywv870sykxdmhrg46xtfqtpkjp20sqc2u4cy tmt1qx3aa7w2gak6xxcfs2eq47qgg09c56de0slcg8p3",
+    ))]
     ChangeTokenAuthority { token_id: String, address: String },
 
     #[clap(name = "token-change-metadata-uri")]
+    #[clap(after_help = examples!(
+        "token-change-metadata-uri tmltk183rfa8tvtp6ax7jr7dfaf7ywv870sykxdmhrg46xtfqtpkjp20sqc2u4cy https://example.com/metadata",
+    ))]
     ChangeTokenMetadataUri {
         token_id: String,
         metadata_uri: String,
     },
 
     #[clap(name = "token-mint")]
+    #[clap(after_help = examples!(
+        "token-mint tmltk183rfa8tvtp6ax7jr7dfaf7ywv870sykxdmhrg46xtfqtpkjp20sqc2u4cy tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh 1000",
+    ))]
     MintTokens {
         /// The token id of the tokens to be minted
         token_id: String,
@@ -393,6 +773,9 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "token-unmint")]
+    #[clap(after_help = examples!(
+        "token-unmint tmltk183rfa8tvtp6ax7jr7dfaf7ywv870sykxdmhrg46xtfqtpkjp20sqc2u4cy 500",
+    ))]
     UnmintTokens {
         /// The token id of the tokens to be unminted
         token_id: String,
@@ -401,12 +784,18 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "token-lock-supply")]
+    #[clap(after_help = examples!(
+        "token-lock-supply tmltk183rfa8tvtp6ax7jr7dfaf7ywv870sykxdmhrg46xtfqtpkjp20sqc2u4cy",
+    ))]
     LockTokenSupply {
         /// The token id of the token, whose supply will be locked
         token_id: String,
     },
 
     #[clap(name = "token-freeze")]
+    #[clap(after_help = examples!(
+        "token-freeze tmltk183rfa8tvtp6ax7jr7dfaf7ywv870sykxdmhrg46xtfqtpkjp20sqc2u4cy unfreezable",
+    ))]
     FreezeToken {
         /// The token id of the token to be frozen.
         token_id: String,
@@ -415,12 +804,18 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "token-unfreeze")]
+    #[clap(after_help = examples!(
+        "token-unfreeze tmltk183rfa8tvtp6ax7jr7dfaf7ywv870sykxdmhrg46xtfqtpkjp20sqc2u4cy",
+    ))]
     UnfreezeToken {
         /// The token id of the token to be unfrozen.
         token_id: String,
     },
 
     #[clap(name = "token-send")]
+    #[clap(after_help = examples!(
+        "token-send tmltk183rfa8tvtp6ax7jr7dfaf7ywv870sykxdmhrg46xtfqtpkjp20sqc2u4cy tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh 10.5",
+    ))]
     SendTokensToAddress {
         /// The token id of the tokens to be sent
         token_id: String,
@@ -437,6 +832,9 @@ pub enum WalletCommand {
     /// The optional `fee_change_address` specifies the destination for the change for the fee payment;
     /// If it's unset, the destination will be taken from one of existing single-sig utxos.
     #[clap(name = "token-make-tx-to-send-from-multisig-address")]
+    #[clap(after_help = examples!(
+        "token-make-tx-to-send-from-multisig-address tmt1qnmcts7wr4vqerezc8dc59x0zf5wgsneludqykps \"transfer(tmltk183rfa8tvtp6ax7jr7dfaf7ywv870sykxdmhrg46xtfqtpkjp20sqc2u4cy,tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh,0.9)\"",
+    ))]
     #[clap(hide = true)]
     MakeTxToSendTokensFromMultisigAddress {
         /// The source multisig address; the change will be sent to it as well.
@@ -452,11 +850,17 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "address-send")]
+    #[clap(after_help = examples!(
+        "address-send tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh 10.5",
+        "address-send tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh 10.5 \"tx(1b5b9ccb3e8d006a5230de9bda23ff91edc794d4f56410560830b418528e446c,1)\"",
+        "address-send mintlayer:tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh?amount=10.5",
+    ))]
     SendToAddress {
-        /// The receiving address of the coins
+        /// The receiving address of the coins, or a payment URI
         address: String,
-        /// The amount to be sent, in decimal format
-        amount: DecimalAmount,
+        /// The amount to be sent, in decimal format.
+        /// Can be omitted if the payment URI specifies the amount, otherwise it must match it.
+        amount: Option<DecimalAmount>,
         /// You can choose what utxos to spend (space separated as additional arguments). A utxo can be from a transaction output or a block reward output:
         /// e.g tx(000000000000000000059fa50103b9683e51e5aba83b8a34c9b98ce67d66136c,1) or
         /// block(000000000000000000059fa50103b9683e51e5aba83b8a34c9b98ce67d66136c,2)
@@ -464,7 +868,36 @@ pub enum WalletCommand {
         utxos: Vec<String>,
     },
 
+    /// Send coins or tokens to many addresses in a single transaction, which is cheaper than
+    /// sending them one by one.
+    #[clap(name = "address-send-to-many")]
+    #[clap(after_help = examples!(
+        "address-send-to-many tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh:10.5 tmt1qykr5sjf6ac8qpvxf8dasgku4au4wkr0ec9hp5d8:2",
+        "address-send-to-many \"transfer(tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh,10.5)\" \"transfer(tmt1qykr5sjf6ac8qpvxf8dasgku4au4wkr0ec9hp5d8,2,tmltk1e7egscactagl7e3met67658hpl4vf9ux0ralaculjvnzhtc4qmsqv9y857)\"",
+        "address-send-to-many --file payments.json --duplicate-outputs reject",
+    ))]
+    SendToMany {
+        /// The outputs, in the format `address:amount` or `transfer(address,amount)` for coins,
+        /// and `address:amount:token_id` or `transfer(address,amount,token_id)` for tokens,
+        /// e.g. tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh:10.5
+        #[arg(required_unless_present = "file")]
+        outputs: Vec<String>,
+        /// A JSON file with the outputs instead, containing an array of objects with the
+        /// `address`, `amount` and optional `token_id` fields,
+        /// e.g. [{"address": "tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh", "amount": "10.5"}]
+        #[arg(long = "file", conflicts_with = "outputs")]
+        file: Option<PathBuf>,
+        /// How to handle the payments to the same address in the same currency:
+        /// merge them into a single output with a warning, make a separate output for each
+        /// of them, or refuse to send
+        #[arg(long = "duplicate-outputs", value_enum, default_value_t = CliDuplicateOutputs::Merge)]
+        duplicate_outputs: CliDuplicateOutputs,
+    },
+
     #[clap(name = "address-sweep-spendable")]
+    #[clap(after_help = examples!(
+        "address-sweep-spendable tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh tmt1qykr5sjf6ac8qpvxf8dasgku4au4wkr0ec9hp5d8",
+    ))]
     SweepFromAddress {
         /// The receiving address of the coins or tokens
         destination_address: String,
@@ -473,6 +906,9 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "staking-sweep-delegation")]
+    #[clap(after_help = examples!(
+        "staking-sweep-delegation tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh tdelg1f5qpq3tg8vc69y6t4d9ld8m29xzwuguprn3trsp3vs9yr287caxs4zk0ec",
+    ))]
     SweepFromDelegation {
         /// The receiving address of the coins
         destination_address: String,
@@ -481,6 +917,9 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "transaction-create-from-cold-input")]
+    #[clap(after_help = examples!(
+        "transaction-create-from-cold-input tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh 10 \"tx(1b5b9ccb3e8d006a5230de9bda23ff91edc794d4f56410560830b418528e446c,0)\" --change tmt1qykr5sjf6ac8qpvxf8dasgku4au4wkr0ec9hp5d8",
+    ))]
     CreateTxFromColdInput {
         /// The receiving address of the coins
         address: String,
@@ -495,19 +934,27 @@ pub enum WalletCommand {
         change_address: Option<String>,
     },
 
+    /// Check the signatures and compute the fees of a transaction or a partially signed
+    /// transaction, using the utxos known to the node. To just see the contents of a signed
+    /// transaction, use `transaction-decode`
     #[clap(name = "transaction-inspect")]
+    #[clap(after_help = examples!("transaction-inspect 01000400008e1b1ec2b5b4ba6b8a0dd0a4a4c9a1ff3a1f5c5e8d1f2c9b7d2a6b0ab5c4e3d2010000000400000b00a0724e180901a3def9ca476da31b0982b20af80843cb8a69b97c"))]
     InspectTransaction {
         /// Hex encoded transaction or PartiallySignedTransaction.
         transaction: String,
     },
 
     #[clap(name = "address-deposit-data")]
+    #[clap(after_help = examples!("address-deposit-data 48656c6c6f"))]
     DepositData {
         /// The data to be deposited on the blockchain as hex. DO NOT start the data with 0x.
         hex_data: String,
     },
 
     #[clap(name = "delegation-create")]
+    #[clap(after_help = examples!(
+        "delegation-create tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh tpool1yl9v25pcxem9e5g828f84d9xu97h4qx5e9yyxzj6s9gnjulek50qcwxkqe",
+    ))]
     CreateDelegation {
         /// The address, that will have the authority to sign withdrawals from a pool.
         owner: String,
@@ -516,9 +963,13 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "delegation-list-ids")]
+    #[clap(after_help = examples!("delegation-list-ids"))]
     ListDelegationIds,
 
     #[clap(name = "delegation-stake")]
+    #[clap(after_help = examples!(
+        "delegation-stake 1000 tdelg1f5qpq3tg8vc69y6t4d9ld8m29xzwuguprn3trsp3vs9yr287caxs4zk0ec",
+    ))]
     DelegateStaking {
         /// The amount to be delegated for staking
         amount: DecimalAmount,
@@ -526,7 +977,27 @@ pub enum WalletCommand {
         delegation_id: String,
     },
 
+    /// Create a new delegation to the given pool, owned by a new address of the account,
+    /// and delegate the given amount to it in a single transaction
+    #[clap(name = "delegation-create-and-stake")]
+    #[clap(after_help = examples!(
+        "delegation-create-and-stake tpool1yl9v25pcxem9e5g828f84d9xu97h4qx5e9yyxzj6s9gnjulek50qcwxkqe 1000",
+        "delegation-create-and-stake tpool1yl9v25pcxem9e5g828f84d9xu97h4qx5e9yyxzj6s9gnjulek50qcwxkqe 1000 --account 1",
+    ))]
+    DelegateStake {
+        /// The pool id of the pool that will get the delegation and stake the coins.
+        pool_id: String,
+        /// The amount to be delegated for staking
+        amount: DecimalAmount,
+        /// The account paying for the delegation, the selected account if not specified
+        #[arg(long = "account")]
+        account: Option<U31>,
+    },
+
     #[clap(name = "delegation-withdraw")]
+    #[clap(after_help = examples!(
+        "delegation-withdraw tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh 500 tdelg1f5qpq3tg8vc69y6t4d9ld8m29xzwuguprn3trsp3vs9yr287caxs4zk0ec",
+    ))]
     WithdrawFromDelegation {
         /// The address that will be receiving the coins
         address: String,
@@ -536,28 +1007,80 @@ pub enum WalletCommand {
         delegation_id: String,
     },
 
+    /// Withdraw coins from a delegation to a new address of the selected account.
+    /// Note that the withdrawn coins are locked for a period before they can be spent.
+    #[clap(name = "delegation-withdraw-to-wallet")]
+    #[clap(after_help = examples!(
+        "delegation-withdraw-to-wallet tdelg1f5qpq3tg8vc69y6t4d9ld8m29xzwuguprn3trsp3vs9yr287caxs4zk0ec 500",
+    ))]
+    WithdrawDelegation {
+        /// The delegation id, from which the delegated coins will be taken
+        delegation_id: String,
+        /// The amount that will be taken away from the delegation
+        amount: DecimalAmount,
+    },
+
     #[clap(name = "staking-list-pools")]
+    #[clap(after_help = examples!("staking-list-pools"))]
     ListPools,
 
     #[clap(name = "staking-list-owned-pools-for-decommission")]
+    #[clap(after_help = examples!("staking-list-owned-pools-for-decommission"))]
     ListOwnedPoolsForDecommission,
 
     #[clap(name = "staking-start")]
+    #[clap(after_help = examples!("staking-start"))]
     StartStaking,
 
     #[clap(name = "staking-stop")]
+    #[clap(after_help = examples!("staking-stop"))]
     StopStaking,
 
     #[clap(name = "staking-status")]
+    #[clap(after_help = examples!("staking-status"))]
     StakingStatus,
 
+    /// Show the number of blocks produced by each pool owned by the selected account over
+    /// the last 24 hours, compared to the number expected from the pool's share of the stake
+    #[clap(name = "staking-pools-status")]
+    #[clap(after_help = examples!("staking-pools-status"))]
+    GetStakingStatus,
+
     #[clap(name = "staking-pool-balance")]
+    #[clap(after_help = examples!(
+        "staking-pool-balance tpool1yl9v25pcxem9e5g828f84d9xu97h4qx5e9yyxzj6s9gnjulek50qcwxkqe",
+    ))]
     StakePoolBalance { pool_id: String },
 
+    /// List all the staking pools known to the node, e.g. to choose one to delegate to.
+    ///
+    /// For each pool the pledge, the total stake, the cost per block, the margin ratio and
+    /// the number of blocks produced among the latest 1000 blocks are shown.
+    #[clap(name = "staking-list-all-pools")]
+    #[clap(after_help = examples!(
+        "staking-list-all-pools",
+        "staking-list-all-pools --min-pledge 40000 --max-margin-ratio 0.1 --sort-by total_stake_desc",
+    ))]
+    ListStakingPools {
+        /// Only list the pools with at least this pledge
+        #[arg(long)]
+        min_pledge: Option<DecimalAmount>,
+        /// Only list the pools with at most this margin ratio, e.g. 0.1 for 10%
+        #[arg(long)]
+        max_margin_ratio: Option<f64>,
+        /// The order of the listed pools; by default they are ordered by pool id
+        #[arg(long, value_enum)]
+        sort_by: Option<CliStakePoolsSortOrder>,
+    },
+
     #[clap(name = "staking-list-created-block-ids")]
+    #[clap(after_help = examples!("staking-list-created-block-ids"))]
     ListCreatedBlocksIds,
 
     #[clap(name = "staking-create-pool")]
+    #[clap(after_help = examples!(
+        "staking-create-pool 40000 10 0.05 tmt1qx3aa7w2gak6xxcfs2eq47qgg09c56de0slcg8p3",
+    ))]
     CreateStakePool {
         /// The amount to be pledged to the pool. There is a minimum to be accepted.
         /// This amount, and the rewards gained by the pool, CANNOT be taken out without decommissioning the pool.
@@ -582,6 +1105,9 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "staking-decommission-pool")]
+    #[clap(after_help = examples!(
+        "staking-decommission-pool tpool1yl9v25pcxem9e5g828f84d9xu97h4qx5e9yyxzj6s9gnjulek50qcwxkqe tmt1qx3aa7w2gak6xxcfs2eq47qgg09c56de0slcg8p3",
+    ))]
     DecommissionStakePool {
         /// The pool id of the pool to be decommissioned.
         /// Notice that this only works if the selected account in this wallet owns the decommission key.
@@ -590,7 +1116,23 @@ pub enum WalletCommand {
         output_address: String,
     },
 
+    /// Create a transaction that decommissions a pool, returning the staker's balance (both pledge
+    /// and proceeds from staking) to the pool's decommission key. The transaction is not broadcast;
+    /// it can be submitted with node-submit-transaction.
+    #[clap(name = "staking-create-decommission-pool-tx")]
+    #[clap(after_help = examples!(
+        "staking-create-decommission-pool-tx tpool1yl9v25pcxem9e5g828f84d9xu97h4qx5e9yyxzj6s9gnjulek50qcwxkqe",
+    ))]
+    CreateDecommissionPoolTransaction {
+        /// The pool id of the pool to be decommissioned.
+        /// Notice that this only works if the selected account in this wallet owns the decommission key.
+        pool_id: String,
+    },
+
     #[clap(name = "staking-decommission-pool-request")]
+    #[clap(after_help = examples!(
+        "staking-decommission-pool-request tpool1yl9v25pcxem9e5g828f84d9xu97h4qx5e9yyxzj6s9gnjulek50qcwxkqe tmt1qx3aa7w2gak6xxcfs2eq47qgg09c56de0slcg8p3",
+    ))]
     DecommissionStakePoolRequest {
         /// The pool id of the pool to be decommissioned.
         pool_id: String,
@@ -599,31 +1141,52 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "wallet-rescan")]
+    #[clap(after_help = examples!("wallet-rescan"))]
     Rescan,
 
     #[clap(name = "wallet-sync")]
+    #[clap(after_help = examples!("wallet-sync"))]
     SyncWallet,
 
+    /// Print the new blocks, the wallet transactions and the staked blocks as they happen,
+    /// until a key is pressed. Only available in the interactive mode
+    #[clap(name = "wallet-watch", visible_alias = "watch")]
+    #[clap(after_help = examples!("wallet-watch"))]
+    Watch,
+
     #[clap(name = "node-version")]
+    #[clap(after_help = examples!("node-version"))]
     NodeVersion,
 
     #[clap(name = "node-shutdown")]
+    #[clap(after_help = examples!("node-shutdown"))]
     NodeShutdown,
 
     /// Enable or disable p2p networking in the node
     #[clap(name = "node-enable-p2p-networking")]
+    #[clap(after_help = examples!(
+        "node-enable-p2p-networking enable",
+        "node-enable-p2p-networking disable",
+    ))]
     NodeEnableNetworking { enable: EnableOrDisable },
 
     #[clap(name = "node-connect-to-peer")]
+    #[clap(after_help = examples!("node-connect-to-peer 127.0.0.1:13031"))]
     Connect { address: IpOrSocketAddress },
 
     #[clap(name = "node-disconnect-peer")]
+    #[clap(after_help = examples!("node-disconnect-peer 1"))]
     Disconnect { peer_id: PeerId },
 
     #[clap(name = "node-list-banned-peers")]
+    #[clap(after_help = examples!("node-list-banned-peers"))]
     ListBanned,
 
     #[clap(name = "node-ban-peer-address")]
+    #[clap(after_help = examples!(
+        "node-ban-peer-address 1.2.3.4 1d",
+        "node-ban-peer-address 1.2.3.4 \"1M 10d 6h\"",
+    ))]
     Ban {
         /// IP address to ban.
         address: BannableAddress,
@@ -634,77 +1197,127 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "node-unban-peer-address")]
+    #[clap(after_help = examples!("node-unban-peer-address 1.2.3.4"))]
     Unban { address: BannableAddress },
 
     #[clap(name = "node-list-discouraged-peers")]
+    #[clap(after_help = examples!("node-list-discouraged-peers"))]
     ListDiscouraged,
 
     #[clap(name = "node-peer-count")]
+    #[clap(after_help = examples!("node-peer-count"))]
     PeerCount,
 
     #[clap(name = "node-list-connected-peers")]
+    #[clap(after_help = examples!("node-list-connected-peers"))]
     ConnectedPeers,
 
     /// Get connected peers in JSON format
     #[clap(name = "node-list-connected-peers-json")]
+    #[clap(after_help = examples!("node-list-connected-peers-json"))]
     #[clap(hide = true)]
     ConnectedPeersJson,
 
     #[clap(name = "node-list-reserved-peers")]
+    #[clap(after_help = examples!("node-list-reserved-peers"))]
     ReservedPeers,
 
     #[clap(name = "node-add-reserved-peer")]
+    #[clap(after_help = examples!("node-add-reserved-peer 1.2.3.4:3031"))]
     AddReservedPeer { address: IpOrSocketAddress },
 
     #[clap(name = "node-remove-reserved-peer")]
+    #[clap(after_help = examples!("node-remove-reserved-peer 1.2.3.4:3031"))]
     RemoveReservedPeer { address: IpOrSocketAddress },
 
     #[clap(name = "node-submit-block")]
+    #[clap(after_help = examples!("node-submit-block 01000000000000000000059fa50103b9683e51e5aba83b8a34c9b98ce67d66136c2fa3f686df876995167e7c2e5d74c4c7b6e48f8068fe0e44208344d480f7904c2fa3f686df876995167e7c2e5d74c4c7b6e48f8068fe0e44208344d480f7904c0300f1536500000000"))]
     SubmitBlock {
         /// Hex encoded block
         block: HexEncoded<Block>,
     },
 
-    #[clap(name = "node-submit-transaction")]
+    #[clap(name = "node-submit-transaction", visible_alias = "submittx")]
+    #[clap(after_help = examples!(
+        "node-submit-transaction 01000400008e1b1ec2b5b4ba6b8a0dd0a4a4c9a1ff3a1f5c5e8d1f2c9b7d2a6b0ab5c4e3d2010000000400000b00a0724e180901a3def9ca476da31b0982b20af80843cb8a69b97c040000",
+        "node-submit-transaction 01000400008e1b1ec2b5b4ba6b8a0dd0a4a4c9a1ff3a1f5c5e8d1f2c9b7d2a6b0ab5c4e3d2010000000400000b00a0724e180901a3def9ca476da31b0982b20af80843cb8a69b97c040000 --do-not-store",
+        "submittx 01000400008e1b1ec2b5b4ba6b8a0dd0a4a4c9a1ff3a1f5c5e8d1f2c9b7d2a6b0ab5c4e3d2010000000400000b00a0724e180901a3def9ca476da31b0982b20af80843cb8a69b97c040000 --relay-only",
+    ))]
     SubmitTransaction {
         /// Hex encoded transaction.
         transaction: HexEncoded<SignedTransaction>,
         /// Do not store the transaction in the wallet
         #[arg(long = "do-not-store", default_value_t = false)]
         do_not_store: bool,
+        /// Validate and relay the transaction to peers, but never include it in the blocks
+        /// produced by the node
+        #[arg(
+            long = "relay-only",
+            default_value_t = false,
+            conflicts_with = "do_not_relay"
+        )]
+        relay_only: bool,
+        /// Add the transaction to the node's mempool, but never announce it to peers
+        #[arg(long = "do-not-relay", default_value_t = false)]
+        do_not_relay: bool,
     },
 
     #[clap(name = "node-chainstate-info")]
+    #[clap(after_help = examples!("node-chainstate-info"))]
     ChainstateInfo,
 
     #[clap(name = "node-best-block-id")]
+    #[clap(after_help = examples!("node-best-block-id"))]
     BestBlock,
 
     #[clap(name = "node-best-block-height")]
+    #[clap(after_help = examples!("node-best-block-height"))]
     BestBlockHeight,
 
     /// Returns the current best block timestamp
     #[clap(name = "node-best-block-timestamp")]
+    #[clap(after_help = examples!("node-best-block-timestamp"))]
     BestBlockTimestamp,
 
     #[clap(name = "node-block-id")]
+    #[clap(after_help = examples!("node-block-id 1000"))]
     BlockId {
         /// Block height
         height: BlockHeight,
     },
 
+    /// Check a proof of reserves file against the mainchain, printing the status of each utxo
+    /// and the total provable amounts as JSON
+    #[clap(
+        name = "node-verify-proof-of-reserves",
+        visible_alias = "verifyproofofreserves"
+    )]
+    #[clap(after_help = examples!("node-verify-proof-of-reserves reserves.bin"))]
+    VerifyProofOfReserves {
+        /// File path of the proof file
+        path: PathBuf,
+    },
+
     #[clap(name = "node-get-block")]
+    #[clap(after_help = examples!(
+        "node-get-block 5af2392a948f950e3f93e9f171da623fe6629440bc8d7e27fa7b39573e40ee73",
+    ))]
     GetBlock {
         /// Block hash
         hash: String,
     },
 
     #[clap(name = "node-generate-block")]
+    #[clap(after_help = examples!(
+        "node-generate-block",
+        "node-generate-block 01000400008e1b1ec2b5b4ba6b8a0dd0a4a4c9a1ff3a1f5c5e8d1f2c9b7d2a6b0ab5c4e3d2010000000400000b00a0724e180901a3def9ca476da31b0982b20af80843cb8a69b97c040000",
+    ))]
     GenerateBlock {
         transactions: Vec<HexEncoded<SignedTransaction>>,
     },
 
     #[clap(name = "node-generate-blocks")]
+    #[clap(after_help = examples!("node-generate-blocks 10"))]
     #[clap(hide = true)]
     GenerateBlocks { block_count: u32 },
 
@@ -719,6 +1332,9 @@ pub enum WalletCommand {
     /// last height in the range; for all other heights the maximum timestamp is the timestamp
     /// of the next block.
     #[clap(name = "node-find-timestamps-for-staking")]
+    #[clap(after_help = examples!(
+        "node-find-timestamps-for-staking tpool1yl9v25pcxem9e5g828f84d9xu97h4qx5e9yyxzj6s9gnjulek50qcwxkqe 1 100 60 no",
+    ))]
     #[clap(hide = true)]
     FindTimestampsForStaking {
         pool_id: String,
@@ -729,6 +1345,7 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "node-get-block-ids-as-checkpoints")]
+    #[clap(after_help = examples!("node-get-block-ids-as-checkpoints 0 10000 1000"))]
     #[clap(hide = true)]
     GetBlockIdsAsCheckpoints {
         start_height: BlockHeight,
@@ -737,6 +1354,10 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "transaction-compose")]
+    #[clap(after_help = examples!(
+        "transaction-compose \"transfer(tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh,0.9)\"",
+        "transaction-compose \"transfer(tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh,0.9)\" --utxos \"tx(1b5b9ccb3e8d006a5230de9bda23ff91edc794d4f56410560830b418528e446c,1)\" --only-transaction",
+    ))]
     TransactionCompose {
         /// The transaction outputs, in the format `transfer(address,amount)`
         /// e.g. transfer(tmt1q8lhgxhycm8e6yk9zpnetdwtn03h73z70c3ha4l7,0.9)
@@ -752,15 +1373,37 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "transaction-abandon")]
+    #[clap(after_help = examples!(
+        "transaction-abandon 1b5b9ccb3e8d006a5230de9bda23ff91edc794d4f56410560830b418528e446c",
+    ))]
     AbandonTransaction {
         /// The id of the transaction that will be abandoned, in hex.
         transaction_id: HexEncoded<Id<Transaction>>,
     },
 
+    /// Add, replace or delete the label of a transaction.
+    /// Labels are only stored in the wallet and are kept when the wallet is rescanned.
+    #[clap(name = "transaction-set-label", visible_alias = "settxlabel")]
+    #[clap(after_help = examples!(
+        "transaction-set-label 1b5b9ccb3e8d006a5230de9bda23ff91edc794d4f56410560830b418528e446c \"rent march\"",
+        "settxlabel 1b5b9ccb3e8d006a5230de9bda23ff91edc794d4f56410560830b418528e446c",
+    ))]
+    SetTransactionLabel {
+        /// The id of the transaction, in hex.
+        transaction_id: HexEncoded<Id<Transaction>>,
+        /// The new label, not specifying a label will remove the existing one
+        label: Option<String>,
+    },
+
     #[clap(name = "transaction-list-pending")]
+    #[clap(after_help = examples!("transaction-list-pending"))]
     ListPendingTransactions,
 
     #[clap(name = "transaction-list-by-address")]
+    #[clap(after_help = examples!(
+        "transaction-list-by-address",
+        "transaction-list-by-address tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh --limit 10",
+    ))]
     ListMainchainTransactions {
         /// Address to filter by
         address: Option<String>,
@@ -770,22 +1413,35 @@ pub enum WalletCommand {
     },
 
     #[clap(name = "transaction-get")]
+    #[clap(after_help = examples!(
+        "transaction-get 1b5b9ccb3e8d006a5230de9bda23ff91edc794d4f56410560830b418528e446c",
+    ))]
     GetTransaction {
         /// Transaction id, encoded in hex
         transaction_id: HexEncoded<Id<Transaction>>,
     },
 
     #[clap(name = "transaction-get-raw")]
+    #[clap(after_help = examples!(
+        "transaction-get-raw 1b5b9ccb3e8d006a5230de9bda23ff91edc794d4f56410560830b418528e446c",
+    ))]
     GetRawTransaction {
         /// Transaction id, encoded in hex
         transaction_id: HexEncoded<Id<Transaction>>,
     },
 
     #[clap(name = "transaction-get-signed-raw")]
+    #[clap(after_help = examples!(
+        "transaction-get-signed-raw 1b5b9ccb3e8d006a5230de9bda23ff91edc794d4f56410560830b418528e446c",
+    ))]
     GetRawSignedTransaction {
         /// Transaction id, encoded in hex
         transaction_id: HexEncoded<Id<Transaction>>,
     },
+
+    /// A command added by a plugin, `args` contains the invoked command as a subcommand
+    #[clap(skip)]
+    PluginCommand { name: String, args: ArgMatches },
 }
 
 #[derive(Debug, Parser)]
@@ -801,6 +1457,11 @@ pub enum ManageableWalletCommand {
 #[derive(Debug, Clone)]
 pub enum ConsoleCommand {
     Print(String),
+    /// Print the text followed by a QR code of the data, sized to fit the console
+    PrintWithQrCode {
+        text: String,
+        qr_code_data: String,
+    },
     ClearScreen,
     PrintHistory,
     ClearHistory,
@@ -808,6 +1469,8 @@ pub enum ConsoleCommand {
         status: String,
         print_message: String,
     },
+    /// Print the wallet events until a key is pressed
+    Watch,
     Exit,
 }
 
@@ -824,7 +1487,11 @@ const COMMAND_HELP_TEMPLATE: &str = "\
     {all-args}{after-help}\
 ";
 
-pub fn get_repl_command(cold_wallet: bool, mutable_wallet: bool) -> Command {
+pub fn get_repl_command(
+    cold_wallet: bool,
+    mutable_wallet: bool,
+    plugin_commands: &[Command],
+) -> Command {
     const COLD_WALLET_DESC: &Module = &ColdWalletRpcDescription::DESCRIPTION;
     const WALLET_DESC: &Module = &WalletRpcDescription::DESCRIPTION;
 
@@ -834,6 +1501,7 @@ pub fn get_repl_command(cold_wallet: bool, mutable_wallet: bool) -> Command {
         .subcommand_required(true)
         .subcommand_value_name("Command")
         .subcommand_help_heading("Commands")
+        .disable_help_subcommand(true)
         .help_template(MAIN_HELP_TEMPLATE);
 
     // Add commands from generated by clap-derive
@@ -843,12 +1511,14 @@ pub fn get_repl_command(cold_wallet: bool, mutable_wallet: bool) -> Command {
         WalletCommand::augment_subcommands(repl_command)
     };
 
-    let mut repl_command = if mutable_wallet {
+    let repl_command = if mutable_wallet {
         WalletManagementCommand::augment_subcommands(repl_command)
     } else {
         repl_command
     };
 
+    let mut repl_command = repl_command.subcommands(plugin_commands.iter().cloned());
+
     // Customize the help template for all commands to make it more REPL friendly
     for subcommand in repl_command.get_subcommands_mut() {
         if let Some(desc) =
@@ -870,22 +1540,71 @@ pub fn get_repl_command(cold_wallet: bool, mutable_wallet: bool) -> Command {
     repl_command
 }
 
-/// Try to parse REPL input string as a [WalletCommands]
+/// A parsed REPL input line
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+pub enum ReplInput {
+    /// A command to be executed
+    Command(ManageableWalletCommand),
+    /// The output of the `help` command, that is handled by the REPL itself
+    Help(String),
+}
+
+/// Try to parse REPL input string as a [WalletCommands] or a `help` request
 pub fn parse_input<N: NodeInterface>(
     line: &str,
     repl_command: &Command,
-) -> Result<Option<ManageableWalletCommand>, WalletCliCommandError<N>> {
+) -> Result<Option<ReplInput>, WalletCliCommandError<N>> {
     let line = line.trim();
     if line.is_empty() || line.starts_with('#') {
         return Ok(None);
     }
     // Split arguments as a normal shell would do
     let args = shlex::split(line).ok_or(WalletCliCommandError::InvalidQuoting)?;
-    let mut matches = repl_command
-        .clone()
-        .try_get_matches_from(args)
-        .map_err(WalletCliCommandError::InvalidCommandInput)?;
+
+    match args.as_slice() {
+        [help] if help == "help" => {
+            return Ok(Some(ReplInput::Help(help::render_commands_list(
+                repl_command,
+            ))));
+        }
+        [help, command_name] if help == "help" => {
+            let help = help::render_command_help(repl_command, command_name).ok_or_else(|| {
+                WalletCliCommandError::UnknownCommand {
+                    command: command_name.clone(),
+                    suggestion: help::suggest_command(repl_command, command_name),
+                }
+            })?;
+            return Ok(Some(ReplInput::Help(help)));
+        }
+        _ => {}
+    }
+
+    let mut matches = repl_command.clone().try_get_matches_from(&args).map_err(|err| {
+        match (err.kind(), args.first()) {
+            (clap::error::ErrorKind::InvalidSubcommand, Some(command_name)) => {
+                WalletCliCommandError::UnknownCommand {
+                    command: command_name.clone(),
+                    suggestion: help::suggest_command(repl_command, command_name),
+                }
+            }
+            _ => WalletCliCommandError::InvalidCommandInput(err),
+        }
+    })?;
+
+    if let Some(name) = matches
+        .subcommand_name()
+        .filter(|name| !ManageableWalletCommand::has_subcommand(name))
+    {
+        return Ok(Some(ReplInput::Command(
+            ManageableWalletCommand::WalletCommands(WalletCommand::PluginCommand {
+                name: name.to_owned(),
+                args: matches,
+            }),
+        )));
+    }
+
     let command = ManageableWalletCommand::from_arg_matches_mut(&mut matches)
         .map_err(WalletCliCommandError::InvalidCommandInput)?;
-    Ok(Some(command))
+    Ok(Some(ReplInput::Command(command)))
 }
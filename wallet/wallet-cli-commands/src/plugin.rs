@@ -0,0 +1,83 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Third-party commands that are added to the wallet console without modifying it.
+//!
+//! A plugin is a shared library that exports its [WalletPlugin] implementation using the
+//! [declare_wallet_plugin] macro. Trait objects are passed across the library boundary,
+//! so the plugin must be built with the same compiler and versions of the wallet crates.
+
+use clap::{ArgMatches, Command};
+
+use crate::{ConsoleCommand, WalletCommand};
+
+/// Incremented on every incompatible change of the plugin interface
+pub const WALLET_PLUGIN_API_VERSION: u32 = 1;
+
+/// The name of the symbol holding the API version the plugin was built with
+pub const PLUGIN_API_VERSION_SYMBOL: &str = "_wallet_plugin_api_version";
+
+/// The name of the function that creates the plugin
+pub const CREATE_PLUGIN_SYMBOL: &str = "_wallet_plugin_create";
+
+#[derive(thiserror::Error, Debug)]
+pub enum PluginError {
+    #[error("Failed to load the plugin library: {0}")]
+    LibraryLoadingFailed(String),
+    #[error("The plugin was built for API version {actual}, but version {expected} is required")]
+    ApiVersionMismatch { expected: u32, actual: u32 },
+    #[error("Command '{0}' is already defined")]
+    DuplicateCommand(String),
+    #[error("{0}")]
+    CommandFailed(String),
+}
+
+/// Gives the plugins access to the wallet by running the regular wallet commands
+#[async_trait::async_trait]
+pub trait WalletCommandRunner: Send {
+    async fn run_wallet_command(
+        &mut self,
+        command: WalletCommand,
+    ) -> Result<ConsoleCommand, PluginError>;
+}
+
+#[async_trait::async_trait]
+pub trait WalletPlugin: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// The commands that are added to the console
+    fn commands(&self) -> Vec<Command>;
+
+    /// Handle one of the plugin's commands, `args` contains the invoked command as a subcommand
+    async fn handle(
+        &self,
+        args: &ArgMatches,
+        wallet: &mut dyn WalletCommandRunner,
+    ) -> Result<ConsoleCommand, PluginError>;
+}
+
+/// Export the plugin created by the given constructor from a shared library
+#[macro_export]
+macro_rules! declare_wallet_plugin {
+    ($constructor:path) => {
+        #[no_mangle]
+        pub static _wallet_plugin_api_version: u32 = $crate::plugin::WALLET_PLUGIN_API_VERSION;
+
+        #[no_mangle]
+        pub fn _wallet_plugin_create() -> Box<dyn $crate::plugin::WalletPlugin> {
+            Box::new($constructor())
+        }
+    };
+}
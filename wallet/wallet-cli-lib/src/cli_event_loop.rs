@@ -17,9 +17,11 @@ use std::{fmt::Debug, sync::Arc};
 
 use common::chain::ChainConfig;
 use tokio::sync::{mpsc, oneshot};
-use wallet_cli_commands::{CommandHandler, ConsoleCommand, ManageableWalletCommand};
+use wallet_cli_commands::{
+    plugin::WalletPlugin, CommandHandler, ConsoleCommand, ManageableWalletCommand,
+};
 use wallet_rpc_client::{handles_client::WalletRpcHandlesClient, rpc_client::ClientWalletRpc};
-use wallet_rpc_lib::types::{ControllerConfig, NodeInterface};
+use wallet_rpc_lib::types::{ControllerConfig, NodeInterface, UtxoSelectionStrategy};
 use wallet_rpc_lib::{
     config::WalletRpcConfig, ColdWalletRpcServer, WalletEventsRpcServer, WalletRpc,
     WalletRpcServer, WalletService,
@@ -33,6 +35,13 @@ pub enum Event<N: NodeInterface> {
         command: ManageableWalletCommand,
         res_tx: oneshot::Sender<Result<ConsoleCommand, WalletCliError<N>>>,
     },
+    GetNodeStatus {
+        res_tx: oneshot::Sender<Result<String, WalletCliError<N>>>,
+    },
+    /// Start receiving the wallet events, formatted as lines
+    WatchWalletEvents {
+        res_tx: oneshot::Sender<Result<mpsc::UnboundedReceiver<String>, WalletCliError<N>>>,
+    },
 }
 
 pub enum WalletType<N> {
@@ -52,6 +61,7 @@ pub async fn run<N: NodeInterface + Clone + Send + Sync + Debug + 'static>(
     in_top_x_mb: usize,
     wallet_type: WalletType<N>,
     cold_wallet: bool,
+    plugins: Vec<Arc<dyn WalletPlugin>>,
 ) -> Result<(), WalletCliError<N>> {
     match wallet_type {
         WalletType::Local {
@@ -93,19 +103,32 @@ pub async fn run<N: NodeInterface + Clone + Send + Sync + Debug + 'static>(
                 ControllerConfig {
                     in_top_x_mb,
                     broadcast_to_mempool: true,
+                    utxo_selection_strategy: UtxoSelectionStrategy::default(),
+                    spend_pending_utxos: false,
+                    idempotent_request: None,
                 },
                 wallet,
             )
-            .await;
+            .await
+            .with_plugins(plugins);
 
             loop {
                 tokio::select! {
                     cmd = event_rx.recv() => {
-                        if let Some(Event::HandleCommand { command, res_tx }) = cmd {
-                            let res = command_handler.handle_manageable_wallet_command(&chain_config, command).await;
-                            let _ = res_tx.send(res.map_err(WalletCliError::WalletCommandError));
-                        } else {
-                            return Ok(());
+                        match cmd {
+                            Some(Event::HandleCommand { command, res_tx }) => {
+                                let res = command_handler.handle_manageable_wallet_command(&chain_config, command).await;
+                                let _ = res_tx.send(res.map_err(WalletCliError::WalletCommandError));
+                            }
+                            Some(Event::GetNodeStatus { res_tx }) => {
+                                let res = command_handler.node_status().await;
+                                let _ = res_tx.send(res.map_err(WalletCliError::WalletCommandError));
+                            }
+                            Some(Event::WatchWalletEvents { res_tx }) => {
+                                let res = command_handler.watch_events(chain_config.clone()).await;
+                                let _ = res_tx.send(res.map_err(WalletCliError::WalletCommandError));
+                            }
+                            None => return Ok(()),
                         }
                     }
                     _ = command_handler.rpc_completed() => {
@@ -124,19 +147,32 @@ pub async fn run<N: NodeInterface + Clone + Send + Sync + Debug + 'static>(
                 ControllerConfig {
                     in_top_x_mb,
                     broadcast_to_mempool: true,
+                    utxo_selection_strategy: UtxoSelectionStrategy::default(),
+                    spend_pending_utxos: false,
+                    idempotent_request: None,
                 },
                 wallet,
             )
-            .await;
+            .await
+            .with_plugins(plugins);
 
             loop {
                 tokio::select! {
                     cmd = event_rx.recv() => {
-                        if let Some(Event::HandleCommand { command, res_tx }) = cmd {
-                            let res = command_handler.handle_manageable_wallet_command(chain_config, command).await;
-                            let _ = res_tx.send(res.map_err(WalletCliError::WalletCommandError));
-                        } else {
-                            return Ok(());
+                        match cmd {
+                            Some(Event::HandleCommand { command, res_tx }) => {
+                                let res = command_handler.handle_manageable_wallet_command(chain_config, command).await;
+                                let _ = res_tx.send(res.map_err(WalletCliError::WalletCommandError));
+                            }
+                            Some(Event::GetNodeStatus { res_tx }) => {
+                                let res = command_handler.node_status().await;
+                                let _ = res_tx.send(res.map_err(WalletCliError::WalletCommandError));
+                            }
+                            Some(Event::WatchWalletEvents { res_tx }) => {
+                                let res = command_handler.watch_events(chain_config.clone()).await;
+                                let _ = res_tx.send(res.map_err(WalletCliError::WalletCommandError));
+                            }
+                            None => return Ok(()),
                         }
                     }
                     _ = command_handler.rpc_completed() => {
@@ -182,6 +182,11 @@ pub struct CliArgs {
     /// For a remote RPC wallet, this will not use any authentication
     #[arg(long, conflicts_with_all(["remote_rpc_wallet_password", "remote_rpc_wallet_username", "remote_rpc_wallet_cookie_file"]))]
     pub remote_rpc_wallet_no_authentication: bool,
+
+    /// Paths to the plugin libraries that add new commands to the wallet.
+    /// Only load the plugins from trusted sources, they have full access to the wallet!
+    #[clap(long, value_delimiter(','))]
+    pub plugin_paths: Vec<PathBuf>,
 }
 
 impl From<&Network> for ChainType {
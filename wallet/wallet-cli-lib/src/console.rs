@@ -31,6 +31,9 @@ pub trait ConsoleOutput: Send + 'static {
     fn print_line(&mut self, line: &str);
 
     fn print_error<N: NodeInterface>(&mut self, error: WalletCliError<N>);
+
+    /// The number of columns of the terminal, if known
+    fn terminal_width(&self) -> Option<u16>;
 }
 
 pub struct StdioInputConsole;
@@ -67,6 +70,10 @@ impl ConsoleOutput for StdioOutputConsole {
             println!("{error}");
         }
     }
+
+    fn terminal_width(&self) -> Option<u16> {
+        crossterm::terminal::size().ok().map(|(columns, _rows)| columns)
+    }
 }
 
 pub struct FileInput {
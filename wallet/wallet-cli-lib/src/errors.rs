@@ -32,6 +32,8 @@ pub enum WalletCliError<N: NodeInterface> {
     InvalidConfig(String),
     #[error("Invalid input: {0}")]
     InvalidInput(String),
+    #[error("Console I/O error: {0}")]
+    ConsoleIoError(#[from] std::io::Error),
     #[error("Error converting to json: {0}")]
     SerdeJsonFormatError(#[from] serde_json::Error),
     #[error("{0}")]
@@ -17,6 +17,7 @@ mod cli_event_loop;
 pub mod config;
 pub mod console;
 pub mod errors;
+pub mod plugins;
 mod repl;
 
 use std::{
@@ -36,7 +37,9 @@ use node_comm::{make_cold_wallet_rpc_client, make_rpc_client, rpc_client::ColdWa
 use rpc::RpcAuthData;
 use tokio::sync::mpsc;
 use utils::{cookie::COOKIE_FILENAME, default_data_dir::default_data_dir_for_chain, ensure};
-use wallet_cli_commands::{ManageableWalletCommand, WalletCommand, WalletManagementCommand};
+use wallet_cli_commands::{
+    plugin::WalletPlugin, ManageableWalletCommand, WalletCommand, WalletManagementCommand,
+};
 use wallet_rpc_lib::types::NodeInterface;
 use wallet_rpc_lib::{cmdline::make_wallet_config, config::WalletRpcConfig};
 
@@ -55,6 +58,17 @@ pub async fn run(
     output: impl ConsoleOutput,
     args: config::WalletCliArgs,
     chain_config: Option<Arc<ChainConfig>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    run_with_plugins(input, output, args, chain_config, Vec::new()).await
+}
+
+/// Same as `run`, but with the given plugins in addition to the ones loaded from `plugin_paths`
+pub async fn run_with_plugins(
+    input: impl ConsoleInput,
+    output: impl ConsoleOutput,
+    args: config::WalletCliArgs,
+    chain_config: Option<Arc<ChainConfig>>,
+    mut plugins: Vec<Box<dyn WalletPlugin>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let chain_type = args.network.as_ref().map_or(ChainType::Mainnet, |network| network.into());
     let chain_config = match chain_config {
@@ -71,6 +85,12 @@ pub async fn run(
 
     let cli_args = args.cli_args();
 
+    for plugin_path in &cli_args.plugin_paths {
+        plugins.push(plugins::load_plugin(plugin_path)?);
+    }
+    let plugins: Vec<Arc<dyn WalletPlugin>> = plugins.into_iter().map(Arc::from).collect();
+    plugins::check_plugin_commands(&plugins)?;
+
     let mode = if let Some(file_path) = &cli_args.commands_file {
         repl::non_interactive::log::init();
         let file_input = console::FileInput::new::<ColdWalletClient>(file_path.clone())?;
@@ -93,10 +113,20 @@ pub async fn run(
             input,
             chain_config,
             in_top_x_mb,
+            plugins,
         )
         .await
     } else if cli_args.cold_wallet {
-        start_cold_wallet(cli_args, mode, output, input, chain_config, in_top_x_mb).await
+        start_cold_wallet(
+            cli_args,
+            mode,
+            output,
+            input,
+            chain_config,
+            in_top_x_mb,
+            plugins,
+        )
+        .await
     } else {
         start_hot_wallet(
             cli_args,
@@ -106,11 +136,13 @@ pub async fn run(
             input,
             chain_config,
             in_top_x_mb,
+            plugins,
         )
         .await
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn start_hot_wallet(
     cli_args: CliArgs,
     chain_type: ChainType,
@@ -119,6 +151,7 @@ async fn start_hot_wallet(
     input: impl ConsoleInput,
     chain_config: Arc<ChainConfig>,
     in_top_x_mb: usize,
+    plugins: Vec<Arc<dyn WalletPlugin>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let (event_tx, event_rx) = mpsc::unbounded_channel();
 
@@ -155,8 +188,9 @@ async fn start_hot_wallet(
         cli_args.node_rpc_address.clone().unwrap_or(default_addr)
     };
 
-    let (repl_handle, wallet_rpc_config) =
-        setup_events_and_repl(cli_args, mode, output, input, event_tx, chain_type)?;
+    let (repl_handle, wallet_rpc_config) = setup_events_and_repl(
+        cli_args, mode, output, input, event_tx, chain_type, &plugins,
+    )?;
 
     let node_rpc = make_rpc_client(chain_config.clone(), rpc_address.to_string(), rpc_auth).await?;
     cli_event_loop::run(
@@ -168,6 +202,7 @@ async fn start_hot_wallet(
             wallet_rpc_config,
         },
         false,
+        plugins,
     )
     .await?;
     Ok(repl_handle.join().expect("Should not panic")?)
@@ -180,6 +215,7 @@ async fn start_cold_wallet(
     input: impl ConsoleInput,
     chain_config: Arc<ChainConfig>,
     in_top_x_mb: usize,
+    plugins: Vec<Arc<dyn WalletPlugin>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let (event_tx, event_rx) = mpsc::unbounded_channel();
 
@@ -190,6 +226,7 @@ async fn start_cold_wallet(
         input,
         event_tx,
         *chain_config.chain_type(),
+        &plugins,
     )?;
 
     cli_event_loop::run(
@@ -201,11 +238,13 @@ async fn start_cold_wallet(
             wallet_rpc_config,
         },
         true,
+        plugins,
     )
     .await?;
     Ok(repl_handle.join().expect("Should not panic")?)
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn connect_to_rpc_wallet(
     cli_args: CliArgs,
     chain_type: ChainType,
@@ -214,6 +253,7 @@ async fn connect_to_rpc_wallet(
     input: impl ConsoleInput,
     chain_config: Arc<ChainConfig>,
     in_top_x_mb: usize,
+    plugins: Vec<Arc<dyn WalletPlugin>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let (event_tx, event_rx) = mpsc::unbounded_channel();
 
@@ -242,8 +282,9 @@ async fn connect_to_rpc_wallet(
     };
 
     let remote_socket_address = cli_args.remote_rpc_wallet_address.clone().expect("checked");
-    let (repl_handle, _wallet_rpc_config) =
-        setup_events_and_repl(cli_args, mode, output, input, event_tx, chain_type)?;
+    let (repl_handle, _wallet_rpc_config) = setup_events_and_repl(
+        cli_args, mode, output, input, event_tx, chain_type, &plugins,
+    )?;
 
     cli_event_loop::run(
         &chain_config.clone(),
@@ -254,6 +295,7 @@ async fn connect_to_rpc_wallet(
             rpc_auth,
         },
         false,
+        plugins,
     )
     .await?;
     Ok(repl_handle.join().expect("Should not panic")?)
@@ -267,6 +309,7 @@ fn setup_events_and_repl<N: NodeInterface + Send + Sync + 'static>(
     input: impl ConsoleInput,
     event_tx: mpsc::UnboundedSender<Event<N>>,
     chain_type: ChainType,
+    plugins: &[Arc<dyn WalletPlugin>],
 ) -> Result<
     (
         std::thread::JoinHandle<Result<(), WalletCliError<N>>>,
@@ -337,6 +380,8 @@ fn setup_events_and_repl<N: NodeInterface + Send + Sync + 'static>(
         None
     };
 
+    let plugin_commands = plugins.iter().flat_map(|plugin| plugin.commands()).collect();
+
     // Run a blocking loop in a separate thread
     let repl = std::thread::spawn(move || match mode {
         Mode::Interactive { logger } => repl::interactive::run(
@@ -348,6 +393,7 @@ fn setup_events_and_repl<N: NodeInterface + Send + Sync + 'static>(
             args.vi_mode,
             startup_command_futures,
             args.cold_wallet,
+            plugin_commands,
         ),
         Mode::NonInteractive => repl::non_interactive::run(
             input,
@@ -356,6 +402,7 @@ fn setup_events_and_repl<N: NodeInterface + Send + Sync + 'static>(
             args.exit_on_error.unwrap_or(false),
             args.cold_wallet,
             startup_command_futures,
+            plugin_commands,
         ),
         Mode::CommandsList { file_input } => repl::non_interactive::run(
             file_input,
@@ -364,6 +411,7 @@ fn setup_events_and_repl<N: NodeInterface + Send + Sync + 'static>(
             args.exit_on_error.unwrap_or(true),
             args.cold_wallet,
             startup_command_futures,
+            plugin_commands,
         ),
     });
 
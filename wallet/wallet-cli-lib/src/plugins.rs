@@ -0,0 +1,113 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::BTreeSet, path::Path, sync::Arc};
+
+use clap::{ArgMatches, Command, Subcommand};
+use dlopen2::raw::Library;
+use logging::log;
+use utils::ensure;
+use wallet_cli_commands::{
+    plugin::{
+        PluginError, WalletCommandRunner, WalletPlugin, CREATE_PLUGIN_SYMBOL,
+        PLUGIN_API_VERSION_SYMBOL, WALLET_PLUGIN_API_VERSION,
+    },
+    ConsoleCommand, ManageableWalletCommand,
+};
+
+/// A plugin loaded from a shared library, which must outlive the plugin
+struct LibraryPlugin {
+    // Declared first to be dropped before the library
+    plugin: Box<dyn WalletPlugin>,
+    _library: Library,
+}
+
+#[async_trait::async_trait]
+impl WalletPlugin for LibraryPlugin {
+    fn name(&self) -> &str {
+        self.plugin.name()
+    }
+
+    fn commands(&self) -> Vec<Command> {
+        self.plugin.commands()
+    }
+
+    async fn handle(
+        &self,
+        args: &ArgMatches,
+        wallet: &mut dyn WalletCommandRunner,
+    ) -> Result<ConsoleCommand, PluginError> {
+        self.plugin.handle(args, wallet).await
+    }
+}
+
+/// Load a plugin from a shared library that exports it with the `declare_wallet_plugin` macro
+pub fn load_plugin(path: &Path) -> Result<Box<dyn WalletPlugin>, PluginError> {
+    let to_loading_error = |err: dlopen2::Error| {
+        PluginError::LibraryLoadingFailed(format!("{}: {err}", path.display()))
+    };
+
+    let library = Library::open(path).map_err(to_loading_error)?;
+
+    // SAFETY: the symbols are defined by the `declare_wallet_plugin` macro with these types
+    let api_version = unsafe {
+        *library
+            .symbol::<*const u32>(PLUGIN_API_VERSION_SYMBOL)
+            .map_err(to_loading_error)?
+    };
+    ensure!(
+        api_version == WALLET_PLUGIN_API_VERSION,
+        PluginError::ApiVersionMismatch {
+            expected: WALLET_PLUGIN_API_VERSION,
+            actual: api_version,
+        }
+    );
+
+    // SAFETY: same as above
+    let create_plugin = unsafe {
+        library
+            .symbol::<fn() -> Box<dyn WalletPlugin>>(CREATE_PLUGIN_SYMBOL)
+            .map_err(to_loading_error)?
+    };
+    let plugin = create_plugin();
+
+    log::info!(
+        "Loaded wallet plugin {} from {}",
+        plugin.name(),
+        path.display()
+    );
+
+    Ok(Box::new(LibraryPlugin {
+        plugin,
+        _library: library,
+    }))
+}
+
+/// Check that the plugins don't redefine the existing commands or each other's commands
+pub fn check_plugin_commands(plugins: &[Arc<dyn WalletPlugin>]) -> Result<(), PluginError> {
+    let mut names = BTreeSet::new();
+    for command in plugins.iter().flat_map(|plugin| plugin.commands()) {
+        let name = command.get_name().to_owned();
+        ensure!(
+            name != "help" && !ManageableWalletCommand::has_subcommand(&name),
+            PluginError::DuplicateCommand(name)
+        );
+        ensure!(
+            names.insert(name.clone()),
+            PluginError::DuplicateCommand(name)
+        );
+    }
+    Ok(())
+}
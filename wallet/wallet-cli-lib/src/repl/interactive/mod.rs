@@ -15,8 +15,10 @@
 
 mod key_bindings;
 pub mod log;
+mod passphrase_prompt;
 mod wallet_completions;
 mod wallet_prompt;
+mod watch;
 
 use std::path::PathBuf;
 
@@ -27,7 +29,10 @@ use reedline::{
     Reedline, ReedlineMenu, Signal, Vi,
 };
 use tokio::sync::{mpsc, oneshot};
-use wallet_cli_commands::{get_repl_command, parse_input, ConsoleCommand};
+use wallet_cli_commands::{
+    get_repl_command, parse_input, ConsoleCommand, ManageableWalletCommand, ReplInput,
+    WalletManagementCommand,
+};
 use wallet_rpc_lib::types::NodeInterface;
 
 use crate::{
@@ -118,11 +123,31 @@ fn process_line<N: NodeInterface>(
 
     let command_opt = parse_input(&line, repl_command)?;
 
-    let command = match command_opt {
-        Some(command) => command,
+    let mut command = match command_opt {
+        Some(ReplInput::Command(command)) => command,
+        Some(ReplInput::Help(text)) => return Ok(Some(ConsoleCommand::Print(text))),
         None => return Ok(None),
     };
 
+    // Ask for the passphrase here so that it is not echoed and doesn't end up in the history
+    if let ManageableWalletCommand::ManagementCommands(WalletManagementCommand::CreateWallet {
+        mnemonic,
+        passphrase,
+        ask_passphrase,
+        ..
+    }) = &mut command
+    {
+        if *ask_passphrase {
+            // A typo in the passphrase of a new wallet can't be detected later
+            let confirm = mnemonic.is_none();
+            match passphrase_prompt::ask_passphrase(confirm)? {
+                Some(entered) => *passphrase = Some(entered),
+                None => return Ok(None),
+            }
+            *ask_passphrase = false;
+        }
+    }
+
     super::run_command_blocking(event_tx, command).map(Option::Some)
 }
 
@@ -136,8 +161,9 @@ pub fn run<N: NodeInterface>(
     vi_mode: bool,
     startup_command_futures: Vec<oneshot::Receiver<Result<ConsoleCommand, WalletCliError<N>>>>,
     cold_wallet: bool,
+    plugin_commands: Vec<Command>,
 ) -> Result<(), WalletCliError<N>> {
-    let repl_command = get_repl_command(cold_wallet, true);
+    let repl_command = get_repl_command(cold_wallet, true, &plugin_commands);
 
     let mut line_editor = create_line_editor(
         logger.printer().clone(),
@@ -154,6 +180,7 @@ pub fn run<N: NodeInterface>(
         if let Some(value) = handle_response(
             res.map(Some),
             &mut console,
+            &event_tx,
             &mut prompt,
             &mut line_editor,
             true,
@@ -168,6 +195,11 @@ pub fn run<N: NodeInterface>(
     console.print_line("Use 'exit' or Ctrl-D to quit.");
 
     loop {
+        // The node status can change at any time, so refresh it before showing the prompt
+        if !cold_wallet {
+            prompt.set_node_status(super::node_status_blocking(&event_tx).unwrap_or_default());
+        }
+
         logger.set_print_directly(false);
         let sig = line_editor.read_line(&prompt).expect("Should not fail normally");
         logger.set_print_directly(true);
@@ -177,6 +209,7 @@ pub fn run<N: NodeInterface>(
         if let Some(value) = handle_response(
             res,
             &mut console,
+            &event_tx,
             &mut prompt,
             &mut line_editor,
             exit_on_error,
@@ -189,6 +222,7 @@ pub fn run<N: NodeInterface>(
 fn handle_response<N: NodeInterface>(
     res: Result<Option<ConsoleCommand>, WalletCliError<N>>,
     console: &mut impl ConsoleOutput,
+    event_tx: &mpsc::UnboundedSender<Event<N>>,
     prompt: &mut wallet_prompt::WalletPrompt,
     line_editor: &mut Reedline,
     exit_on_error: bool,
@@ -197,6 +231,14 @@ fn handle_response<N: NodeInterface>(
         Ok(Some(ConsoleCommand::Print(text))) => {
             console.print_line(&text);
         }
+        Ok(Some(ConsoleCommand::PrintWithQrCode { text, qr_code_data })) => {
+            console.print_line(&super::qr_code::text_with_qr_code(
+                &text,
+                &qr_code_data,
+                console.terminal_width(),
+                super::qr_code::QrCodeStyle::UnicodeBlocks,
+            ));
+        }
         Ok(Some(ConsoleCommand::SetStatus {
             status,
             print_message,
@@ -213,6 +255,14 @@ fn handle_response<N: NodeInterface>(
         Ok(Some(ConsoleCommand::PrintHistory)) => {
             line_editor.print_history().expect("Should not fail normally");
         }
+        Ok(Some(ConsoleCommand::Watch)) => {
+            if let Err(err) = watch::run_watch_mode(console, event_tx) {
+                if exit_on_error {
+                    return Some(Err(err));
+                }
+                console.print_error(err);
+            }
+        }
         Ok(Some(ConsoleCommand::Exit)) => return Some(Ok(())),
 
         Ok(None) => {}
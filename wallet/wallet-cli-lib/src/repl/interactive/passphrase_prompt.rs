@@ -0,0 +1,89 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write;
+
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    terminal,
+};
+use wallet_rpc_lib::types::NodeInterface;
+
+use crate::errors::WalletCliError;
+
+/// Read a line from the terminal without echoing it.
+///
+/// Returns `None` if the user cancels the input with Ctrl-C or Esc.
+fn read_hidden_line(prompt: &str) -> std::io::Result<Option<String>> {
+    print!("{prompt}");
+    std::io::stdout().flush()?;
+
+    terminal::enable_raw_mode()?;
+    let line = read_keys();
+    terminal::disable_raw_mode()?;
+    println!();
+
+    line
+}
+
+fn read_keys() -> std::io::Result<Option<String>> {
+    let mut line = String::new();
+    loop {
+        if let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            state: _,
+        }) = event::read()?
+        {
+            match code {
+                KeyCode::Enter => return Ok(Some(line)),
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return Ok(None),
+                KeyCode::Char(c) => line.push(c),
+                KeyCode::Backspace => {
+                    line.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Ask for the BIP39 passphrase; if `confirm` is set, it has to be entered twice.
+///
+/// Returns `None` if the user cancels the input.
+pub fn ask_passphrase<N: NodeInterface>(
+    confirm: bool,
+) -> Result<Option<String>, WalletCliError<N>> {
+    let passphrase = match read_hidden_line("Passphrase: ")? {
+        Some(passphrase) => passphrase,
+        None => return Ok(None),
+    };
+
+    if confirm {
+        match read_hidden_line("Repeat passphrase: ")? {
+            Some(repeated) if repeated == passphrase => {}
+            Some(_) => {
+                return Err(WalletCliError::InvalidInput(
+                    "The passphrases don't match".to_owned(),
+                ))
+            }
+            None => return Ok(None),
+        }
+    }
+
+    Ok(Some(passphrase))
+}
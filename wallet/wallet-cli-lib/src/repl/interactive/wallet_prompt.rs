@@ -22,16 +22,28 @@ use reedline::{Prompt, PromptEditMode, PromptHistorySearch, PromptHistorySearchS
 #[derive(Clone)]
 pub struct WalletPrompt {
     prompt_left: String,
+    status: String,
+    node_status: String,
 }
 
 impl WalletPrompt {
     pub fn new() -> Self {
         WalletPrompt {
             prompt_left: "Wallet".into(),
+            status: String::new(),
+            node_status: String::new(),
         }
     }
     pub fn set_status(&mut self, status: String) {
-        self.prompt_left = format!("Wallet{}", status);
+        self.status = status;
+        self.update_prompt_left();
+    }
+    pub fn set_node_status(&mut self, node_status: String) {
+        self.node_status = node_status;
+        self.update_prompt_left();
+    }
+    fn update_prompt_left(&mut self) {
+        self.prompt_left = format!("Wallet{}{}", self.status, self.node_status);
     }
 }
 
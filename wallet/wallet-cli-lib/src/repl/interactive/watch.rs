@@ -0,0 +1,85 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use crossterm::{
+    event::{self, Event as TerminalEvent, KeyEvent, KeyEventKind},
+    terminal,
+};
+use tokio::sync::mpsc::{self, error::TryRecvError};
+use wallet_rpc_lib::types::NodeInterface;
+
+use crate::{cli_event_loop::Event, console::ConsoleOutput, errors::WalletCliError};
+
+/// How long to wait for a key press before printing the newly received events
+const KEY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Wait up to `timeout` for a key to be pressed, returns true if one was
+fn poll_key_press(timeout: Duration) -> std::io::Result<bool> {
+    terminal::enable_raw_mode()?;
+    let pressed = poll_key_press_raw(timeout);
+    terminal::disable_raw_mode()?;
+
+    pressed
+}
+
+fn poll_key_press_raw(timeout: Duration) -> std::io::Result<bool> {
+    if !event::poll(timeout)? {
+        return Ok(false);
+    }
+
+    let pressed = matches!(
+        event::read()?,
+        TerminalEvent::Key(KeyEvent {
+            kind: KeyEventKind::Press,
+            ..
+        })
+    );
+    Ok(pressed)
+}
+
+/// Print the wallet events as they arrive, until a key is pressed or the wallet is closed
+pub fn run_watch_mode<N: NodeInterface>(
+    console: &mut impl ConsoleOutput,
+    event_tx: &mpsc::UnboundedSender<Event<N>>,
+) -> Result<(), WalletCliError<N>> {
+    let (res_tx, res_rx) = tokio::sync::oneshot::channel();
+    // channel is closed, there is nothing to watch
+    if event_tx.send(Event::WatchWalletEvents { res_tx }).is_err() {
+        return Ok(());
+    }
+    let mut lines = res_rx.blocking_recv().expect("Channel must be open")?;
+
+    console.print_line("Watching the wallet events, press any key to stop...");
+
+    loop {
+        loop {
+            match lines.try_recv() {
+                Ok(line) => console.print_line(&line),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    console.print_line("The wallet events are no longer available");
+                    return Ok(());
+                }
+            }
+        }
+
+        if poll_key_press(KEY_POLL_INTERVAL)? {
+            console.print_line("Stopped watching the wallet events");
+            return Ok(());
+        }
+    }
+}
@@ -15,6 +15,7 @@
 
 pub mod interactive;
 pub mod non_interactive;
+mod qr_code;
 
 use clap::Command;
 use tokio::sync::mpsc;
@@ -35,3 +36,15 @@ fn run_command_blocking<N: NodeInterface>(
 
     res_rx.blocking_recv().expect("Channel must be open")
 }
+
+fn node_status_blocking<N: NodeInterface>(
+    event_tx: &mpsc::UnboundedSender<Event<N>>,
+) -> Result<String, WalletCliError<N>> {
+    let (res_tx, res_rx) = tokio::sync::oneshot::channel();
+    // channel is closed, there is nothing to show
+    if event_tx.send(Event::GetNodeStatus { res_tx }).is_err() {
+        return Ok(String::new());
+    }
+
+    res_rx.blocking_recv().expect("Channel must be open")
+}
@@ -17,7 +17,7 @@ pub mod log;
 
 use clap::Command;
 use tokio::sync::{mpsc, oneshot};
-use wallet_cli_commands::{get_repl_command, parse_input, ConsoleCommand};
+use wallet_cli_commands::{get_repl_command, parse_input, ConsoleCommand, ReplInput};
 use wallet_rpc_lib::types::NodeInterface;
 
 use crate::{cli_event_loop::Event, console::ConsoleOutput, errors::WalletCliError, ConsoleInput};
@@ -33,32 +33,44 @@ fn process_line<N: NodeInterface>(
     repl_command: &Command,
     event_tx: &mpsc::UnboundedSender<Event<N>>,
     line: &str,
+    terminal_width: Option<u16>,
 ) -> Result<LineOutput, WalletCliError<N>> {
     let command_opt = parse_input(line, repl_command)?;
 
     let command = match command_opt {
-        Some(command) => command,
+        Some(ReplInput::Command(command)) => command,
+        Some(ReplInput::Help(text)) => return Ok(LineOutput::Print(text)),
         None => return Ok(LineOutput::None),
     };
 
     let command_output = super::run_command_blocking(event_tx, command)?;
 
-    to_line_output(command_output, line)
+    to_line_output(command_output, line, terminal_width)
 }
 
 fn to_line_output<N: NodeInterface>(
     command_output: ConsoleCommand,
     line: &str,
+    terminal_width: Option<u16>,
 ) -> Result<LineOutput, WalletCliError<N>> {
     match command_output {
         ConsoleCommand::Print(text) => Ok(LineOutput::Print(text)),
+        ConsoleCommand::PrintWithQrCode { text, qr_code_data } => {
+            Ok(LineOutput::Print(super::qr_code::text_with_qr_code(
+                &text,
+                &qr_code_data,
+                terminal_width,
+                super::qr_code::QrCodeStyle::Ascii,
+            )))
+        }
         ConsoleCommand::SetStatus {
             status: _,
             print_message,
         } => Ok(LineOutput::Print(print_message)),
         ConsoleCommand::ClearScreen
         | ConsoleCommand::PrintHistory
-        | ConsoleCommand::ClearHistory => Err(WalletCliError::InvalidInput(format!(
+        | ConsoleCommand::ClearHistory
+        | ConsoleCommand::Watch => Err(WalletCliError::InvalidInput(format!(
             "Unsupported command in non-interactive mode: {}",
             line,
         ))),
@@ -73,19 +85,20 @@ pub fn run<N: NodeInterface>(
     exit_on_error: bool,
     cold_wallet: bool,
     startup_command_futures: Vec<oneshot::Receiver<Result<ConsoleCommand, WalletCliError<N>>>>,
+    plugin_commands: Vec<Command>,
 ) -> Result<(), WalletCliError<N>> {
     for res_rx in startup_command_futures {
         let res = res_rx.blocking_recv().expect("Channel must be open")?;
-        let line_out = to_line_output(res, "startup command");
+        let line_out = to_line_output(res, "startup command", output.terminal_width());
         if let Some(value) = handle_response(line_out, &mut output, true) {
             return value;
         }
     }
 
-    let repl_command = get_repl_command(cold_wallet, true);
+    let repl_command = get_repl_command(cold_wallet, true, &plugin_commands);
 
     while let Some(line) = input.read_line() {
-        let res = process_line(&repl_command, &event_tx, &line);
+        let res = process_line(&repl_command, &event_tx, &line, output.terminal_width());
 
         if let Some(value) = handle_response(res, &mut output, exit_on_error) {
             return value;
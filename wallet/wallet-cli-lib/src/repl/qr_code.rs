@@ -0,0 +1,220 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use utils::qrcode::{qrcode_from_str, QrCode};
+
+/// QR codes aren't shown on terminals narrower than this
+const MIN_QR_CODE_TERMINAL_WIDTH: usize = 40;
+
+/// Upper limit for the number of rows a single QR code module takes on wide terminals
+const MAX_MODULE_SIZE: usize = 3;
+
+const BORDER_SIZE: usize = 1;
+
+const FILLED_CHAR: char = '#';
+const EMPTY_CHAR: char = ' ';
+
+const FULL_BLOCK: char = '\u{2588}';
+const UPPER_HALF_BLOCK: char = '\u{2580}';
+const LOWER_HALF_BLOCK: char = '\u{2584}';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QrCodeStyle {
+    /// Each module takes two characters of a line, works on any terminal
+    Ascii,
+    /// Unicode half blocks are used to show two modules in a single character,
+    /// which makes the code twice as small
+    UnicodeBlocks,
+}
+
+impl QrCodeStyle {
+    /// The number of characters a module of the smallest size takes in a line
+    fn module_width(self) -> usize {
+        match self {
+            QrCodeStyle::Ascii => 2,
+            QrCodeStyle::UnicodeBlocks => 1,
+        }
+    }
+}
+
+/// Append a QR code of `data` to `text`, using the biggest module size that fits the terminal.
+///
+/// If the terminal width is unknown, the smallest module size is used.
+pub fn text_with_qr_code(
+    text: &str,
+    data: &str,
+    terminal_width: Option<u16>,
+    style: QrCodeStyle,
+) -> String {
+    let qr = match qrcode_from_str(data) {
+        Ok(qr) => qr,
+        Err(e) => return format!("{text}\n<<Failed to generate QR Code: {e}>>"),
+    };
+
+    // Terminal characters are about twice as high as they are wide, the module width accounts
+    // for that to make the modules appear square
+    let min_width = (qr.side_length() + 2 * BORDER_SIZE) * style.module_width();
+
+    let module_size = match terminal_width {
+        Some(width) => {
+            let width = width as usize;
+            let required_width = std::cmp::max(min_width, MIN_QR_CODE_TERMINAL_WIDTH);
+            if width < required_width {
+                return format!(
+                    "{text}\nWarning: the terminal is too narrow to show the QR code \
+                    ({width} columns, at least {required_width} are needed)"
+                );
+            }
+            std::cmp::min(width / min_width, MAX_MODULE_SIZE)
+        }
+        None => 1,
+    };
+
+    let qr_code_string = match style {
+        QrCodeStyle::Ascii => encode_to_console_string(&qr, module_size),
+        QrCodeStyle::UnicodeBlocks => encode_to_unicode_blocks(&qr, module_size),
+    };
+    format!("{text}\n{qr_code_string}")
+}
+
+fn encode_to_console_string(qr: &impl QrCode, module_size: usize) -> String {
+    let mut result = String::new();
+    for row in module_rows(qr, module_size) {
+        let line: String = row
+            .iter()
+            .flat_map(|filled| {
+                let c = if *filled { FILLED_CHAR } else { EMPTY_CHAR };
+                std::iter::repeat(c).take(2)
+            })
+            .collect();
+        result.push_str(&line);
+        result.push('\n');
+    }
+    result
+}
+
+fn encode_to_unicode_blocks(qr: &impl QrCode, module_size: usize) -> String {
+    let rows = module_rows(qr, module_size);
+
+    let mut result = String::new();
+    for pair in rows.chunks(2) {
+        let upper = &pair[0];
+        let lower = pair.get(1);
+        let line: String = upper
+            .iter()
+            .enumerate()
+            .map(|(x, upper_filled)| {
+                let lower_filled = lower.is_some_and(|lower| lower[x]);
+                match (*upper_filled, lower_filled) {
+                    (true, true) => FULL_BLOCK,
+                    (true, false) => UPPER_HALF_BLOCK,
+                    (false, true) => LOWER_HALF_BLOCK,
+                    (false, false) => EMPTY_CHAR,
+                }
+            })
+            .collect();
+        result.push_str(&line);
+        result.push('\n');
+    }
+    result
+}
+
+/// The modules of the QR code with the border, each one repeated `module_size` times
+/// in both directions
+fn module_rows(qr: &impl QrCode, module_size: usize) -> Vec<Vec<bool>> {
+    let border = BORDER_SIZE as i64;
+    let side_length = qr.side_length() as i64;
+
+    (-border..side_length + border)
+        .flat_map(|y| {
+            let row: Vec<bool> = (-border..side_length + border)
+                .flat_map(|x| {
+                    let filled = qr.pixel_or_false(x as usize, y as usize);
+                    std::iter::repeat(filled).take(module_size)
+                })
+                .collect();
+            std::iter::repeat(row).take(module_size)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &str = "mintlayer:tmt1q864lut0vm6rxcpxdw2akmu0asqawcp3q5h75cgh";
+
+    fn qr_lines(output: &str) -> Vec<&str> {
+        output.lines().skip(1).collect()
+    }
+
+    #[test]
+    fn module_size_depends_on_width() {
+        let side_length = qrcode_from_str(DATA).unwrap().side_length() + 2 * BORDER_SIZE;
+
+        let output = text_with_qr_code("text", DATA, None, QrCodeStyle::Ascii);
+        assert_eq!(output.lines().next(), Some("text"));
+        let lines = qr_lines(&output);
+        assert_eq!(lines.len(), side_length);
+        assert!(lines.iter().all(|line| line.chars().count() == side_length * 2));
+
+        let output = text_with_qr_code(
+            "text",
+            DATA,
+            Some((side_length * 4) as u16),
+            QrCodeStyle::Ascii,
+        );
+        let lines = qr_lines(&output);
+        assert_eq!(lines.len(), side_length * 2);
+        assert!(lines.iter().all(|line| line.chars().count() == side_length * 4));
+
+        let output = text_with_qr_code("text", DATA, Some(u16::MAX), QrCodeStyle::Ascii);
+        let lines = qr_lines(&output);
+        assert_eq!(lines.len(), side_length * MAX_MODULE_SIZE);
+    }
+
+    #[test]
+    fn unicode_blocks() {
+        let side_length = qrcode_from_str(DATA).unwrap().side_length() + 2 * BORDER_SIZE;
+
+        let output = text_with_qr_code("text", DATA, None, QrCodeStyle::UnicodeBlocks);
+        let lines = qr_lines(&output);
+        assert_eq!(lines.len(), side_length.div_ceil(2));
+        assert!(lines.iter().all(|line| line.chars().count() == side_length));
+        assert!(lines.iter().all(|line| line.chars().all(|c| {
+            [FULL_BLOCK, UPPER_HALF_BLOCK, LOWER_HALF_BLOCK, EMPTY_CHAR].contains(&c)
+        })));
+
+        // The ascii code fits only with the smallest module size, the unicode one with twice that
+        let width = (side_length * 2) as u16;
+        let output = text_with_qr_code("text", DATA, Some(width), QrCodeStyle::UnicodeBlocks);
+        let lines = qr_lines(&output);
+        assert_eq!(lines.len(), side_length);
+        assert!(lines.iter().all(|line| line.chars().count() == side_length * 2));
+    }
+
+    #[test]
+    fn narrow_terminal() {
+        let side_length = qrcode_from_str(DATA).unwrap().side_length() + 2 * BORDER_SIZE;
+
+        for width in [0, MIN_QR_CODE_TERMINAL_WIDTH - 1, side_length * 2 - 1] {
+            let output = text_with_qr_code("text", DATA, Some(width as u16), QrCodeStyle::Ascii);
+            let mut lines = output.lines();
+            assert_eq!(lines.next(), Some("text"));
+            assert!(lines.next().unwrap().starts_with("Warning: the terminal is too narrow"));
+            assert_eq!(lines.next(), None);
+        }
+    }
+}
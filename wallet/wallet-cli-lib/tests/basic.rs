@@ -17,11 +17,19 @@ mod cli_test_framework;
 
 use randomness::Rng;
 
-use common::{address::Address, chain::PoolId, primitives::H256};
+use chainstate::{DecodedOutputValue, ProofOfReservesReport, ReserveProofEntryStatus};
+use common::{
+    address::Address,
+    chain::{Destination, OutPointSourceId, PoolId},
+    primitives::H256,
+};
+use crypto::key::{KeyKind, PrivateKey};
 use rstest::rstest;
+use serialization::hex_encoded::HexEncoded;
 use test_utils::random::{make_seedable_rng, Seed};
+use wallet_rpc_lib::types::UtxoInfo;
 
-use crate::cli_test_framework::CliTestFramework;
+use crate::cli_test_framework::{CliTestFramework, MNEMONIC};
 
 #[rstest]
 #[case(test_utils::random::Seed::from_entropy())]
@@ -70,6 +78,293 @@ async fn wallet_cli_file(#[case] seed: Seed) {
     test.shutdown().await;
 }
 
+#[rstest]
+#[case(test_utils::random::Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn wallet_fingerprint_check(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let test = CliTestFramework::setup(&mut rng).await;
+
+    let wallet_dir = test.test_root.fresh_test_dir("wallet dir");
+    let file_name = |name: &str| wallet_dir.as_ref().join(name).to_str().unwrap().to_owned();
+
+    assert_eq!(
+        test.exec(&format!(
+            "wallet-create \"{}\" do-not-store-seed-phrase \"{MNEMONIC}\" --passphrase secret",
+            file_name("original")
+        )),
+        "New wallet created successfully"
+    );
+    let output = test.exec("wallet-fingerprint");
+    let fingerprint = output
+        .lines()
+        .next()
+        .unwrap()
+        .strip_prefix("Wallet fingerprint: ")
+        .unwrap()
+        .to_owned();
+    assert_eq!(output.lines().nth(1), Some("Passphrase used: yes"));
+    assert_eq!(test.exec("wallet-close"), "Successfully closed the wallet.");
+
+    // Restoring without the passphrase gives a different wallet
+    let output = test.exec(&format!(
+        "wallet-create \"{}\" do-not-store-seed-phrase \"{MNEMONIC}\" --expected-fingerprint {fingerprint}",
+        file_name("no_passphrase")
+    ));
+    assert!(output.contains(&format!("doesn't match the expected {fingerprint}")));
+    assert!(output.contains("created with a passphrase"));
+    assert_eq!(test.exec("wallet-close"), "Successfully closed the wallet.");
+
+    // The wrong passphrase also gives a different wallet
+    let output = test.exec(&format!(
+        "wallet-create \"{}\" do-not-store-seed-phrase \"{MNEMONIC}\" --passphrase wrong --expected-fingerprint {fingerprint}",
+        file_name("wrong_passphrase")
+    ));
+    assert!(output.contains(&format!("doesn't match the expected {fingerprint}")));
+    assert!(output.contains("check that both the seed phrase and the passphrase are correct"));
+    assert_eq!(test.exec("wallet-close"), "Successfully closed the wallet.");
+
+    assert_eq!(
+        test.exec(&format!(
+            "wallet-create \"{}\" do-not-store-seed-phrase \"{MNEMONIC}\" --passphrase secret --expected-fingerprint {fingerprint}",
+            file_name("restored")
+        )),
+        format!(
+            "New wallet created successfully\nThe wallet fingerprint {fingerprint} matches the expected one"
+        )
+    );
+    assert_eq!(test.exec("wallet-close"), "Successfully closed the wallet.");
+
+    // The passphrase can't be asked for outside of the interactive console
+    assert_eq!(
+        test.exec(&format!(
+            "wallet-create \"{}\" do-not-store-seed-phrase \"{MNEMONIC}\" --ask-passphrase",
+            file_name("ask_passphrase")
+        )),
+        "Asking for the passphrase is only supported in the interactive console, please use --passphrase instead"
+    );
+
+    test.shutdown().await;
+}
+
+#[rstest]
+#[case(test_utils::random::Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn new_address_qr_code(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let test = CliTestFramework::setup(&mut rng).await;
+
+    test.create_genesis_wallet();
+
+    let output = test.exec("address-new --qr");
+    let mut lines = output.lines();
+    let address = lines.next().unwrap();
+    assert!(Address::<Destination>::from_string(&test.chain_config, address).is_ok());
+    assert!(lines.any(|line| line.contains('#') && line.chars().all(|c| c == '#' || c == ' ')));
+
+    // Without the flag only the address is printed
+    let output = test.exec("address-new");
+    assert_eq!(output.lines().count(), 1);
+    assert_ne!(output, address);
+
+    test.shutdown().await;
+}
+
+#[rstest]
+#[case(test_utils::random::Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn send_with_payment_uri(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let test = CliTestFramework::setup(&mut rng).await;
+
+    test.create_genesis_wallet();
+
+    assert_eq!(
+        test.exec("account-create"),
+        "Success, the new account index is: 1"
+    );
+    assert_eq!(test.exec("account-select 1"), "Success");
+    let uri = test.exec("payment-uri-new 123.5 \"Coffee shop\"");
+    assert!(uri.starts_with("mintlayer:"));
+    assert!(uri.ends_with("?amount=123.5&label=Coffee%20shop"));
+
+    let output = test.exec(&format!("payment-uri-decode {uri}"));
+    let mut lines = output.lines();
+    let address = lines.next().unwrap().strip_prefix("Address: ").unwrap();
+    assert!(Address::<Destination>::from_string(&test.chain_config, address).is_ok());
+    assert_eq!(lines.next(), Some("Amount: 123.5"));
+    assert_eq!(lines.next(), Some("Label: Coffee shop"));
+    assert_eq!(lines.next(), None);
+
+    assert_eq!(test.exec("account-select 0"), "Success");
+    assert!(test
+        .exec(&format!("address-send {uri} 100"))
+        .contains("conflicts with the amount"));
+    assert!(test
+        .exec(&format!("address-send {uri}"))
+        .starts_with("The transaction was submitted successfully with ID"));
+    assert_eq!(test.exec("node-generate-blocks 1"), "Success");
+
+    assert_eq!(test.exec("account-select 1"), "Success");
+    assert_eq!(test.exec("account-balance"), "Coins amount: 123.5");
+
+    test.shutdown().await;
+}
+
+#[rstest]
+#[case(test_utils::random::Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn decode_transaction(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let test = CliTestFramework::setup(&mut rng).await;
+
+    test.create_genesis_wallet();
+
+    let acc0_address = test.exec("address-new");
+    assert_eq!(
+        test.exec("account-create"),
+        "Success, the new account index is: 1"
+    );
+    assert_eq!(test.exec("account-select 1"), "Success");
+    let acc1_address = test.exec("address-new");
+
+    assert_eq!(test.exec("account-select 0"), "Success");
+    assert!(test
+        .exec(&format!("address-send {acc1_address} 100"))
+        .starts_with("The transaction was submitted successfully with ID"));
+    assert_eq!(test.exec("node-generate-blocks 1"), "Success");
+
+    // Spend the only utxo of the second account without submitting the transaction
+    assert_eq!(test.exec("account-select 1"), "Success");
+    let utxos: Vec<UtxoInfo> = serde_json::from_str(&test.exec("account-utxos")).unwrap();
+    assert_eq!(utxos.len(), 1);
+    let outpoint = utxos[0].outpoint.clone().into_outpoint();
+    let tx_id = match outpoint.source_id() {
+        OutPointSourceId::Transaction(tx_id) => tx_id,
+        OutPointSourceId::BlockReward(_) => panic!("unexpected source"),
+    };
+    let output = test.exec(&format!(
+        "transaction-compose \"transfer({acc0_address},50)\" --utxos \"tx({:x},{})\"",
+        tx_id.to_hash(),
+        outpoint.output_index(),
+    ));
+    let tx = output.lines().nth(1).unwrap();
+    let output = test.exec(&format!("account-sign-raw-transaction {tx}"));
+    let signed_tx = output.lines().nth(2).unwrap();
+
+    let output = test.exec(&format!("transaction-decode {signed_tx}"));
+    let mut lines = output.lines().skip(3);
+    assert_eq!(lines.next(), Some("Inputs (1):"));
+    let input = lines.next().unwrap();
+    assert!(input.ends_with(&format!(", own key: {acc1_address}")));
+    assert_eq!(lines.next(), Some("Outputs (1):"));
+    assert!(lines.next().unwrap().contains(&acc0_address));
+    assert_eq!(lines.next(), None);
+
+    // The input is not marked as owned by the other account
+    assert_eq!(test.exec("account-select 0"), "Success");
+    let output = test.exec(&format!("transaction-decode {signed_tx}"));
+    assert!(!output.contains("own key"));
+
+    test.shutdown().await;
+}
+
+#[rstest]
+#[case(test_utils::random::Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn proof_of_reserves(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let test = CliTestFramework::setup(&mut rng).await;
+
+    let proof_dir = test.test_root.fresh_test_dir("proof dir");
+    let file_name = |name: &str| proof_dir.as_ref().join(name).to_str().unwrap().to_owned();
+
+    test.create_genesis_wallet();
+
+    let acc0_address = test.exec("address-new");
+    assert_eq!(
+        test.exec("account-create"),
+        "Success, the new account index is: 1"
+    );
+    assert_eq!(test.exec("account-select 1"), "Success");
+    let acc1_address = test.exec("address-new");
+
+    assert_eq!(test.exec("account-select 0"), "Success");
+    assert!(test
+        .exec(&format!("address-send {acc1_address} 100"))
+        .starts_with("The transaction was submitted successfully with ID"));
+    assert_eq!(test.exec("node-generate-blocks 1"), "Success");
+
+    assert_eq!(test.exec("account-select 1"), "Success");
+    assert!(test
+        .exec(&format!(
+            "account-generate-proof-of-reserves \"reserves\" \"{}\"",
+            file_name("proof")
+        ))
+        .starts_with("Generated a proof of reserves for 1 utxos at the block"));
+
+    let verify = || -> ProofOfReservesReport {
+        serde_json::from_str(&test.exec(&format!(
+            "node-verify-proof-of-reserves \"{}\"",
+            file_name("proof")
+        )))
+        .unwrap()
+    };
+
+    let report = verify();
+    assert_eq!(report.entries.len(), 1);
+    let entry = &report.entries[0];
+    assert_eq!(entry.status, ReserveProofEntryStatus::Valid);
+    assert!(!entry.spent);
+    assert_eq!(entry.destination.as_ref(), Some(&acc1_address));
+    match entry.value {
+        Some(DecodedOutputValue::Coin { amount }) => assert_eq!(report.provable_coins, amount),
+        _ => panic!("unexpected value"),
+    }
+
+    // The proof stays valid after the utxo is spent, but the utxo is reported as spent
+    assert!(test
+        .exec(&format!("address-send {acc0_address} 50"))
+        .starts_with("The transaction was submitted successfully with ID"));
+    assert_eq!(test.exec("node-generate-blocks 1"), "Success");
+
+    let spent_report = verify();
+    assert_eq!(spent_report.entries.len(), 1);
+    assert_eq!(
+        spent_report.entries[0].status,
+        ReserveProofEntryStatus::Valid
+    );
+    assert!(spent_report.entries[0].spent);
+    assert_eq!(spent_report.provable_coins, report.provable_coins);
+
+    // An existing file is not overwritten
+    assert!(test
+        .exec(&format!(
+            "account-generate-proof-of-reserves \"reserves\" \"{}\"",
+            file_name("proof")
+        ))
+        .contains("File already exists"));
+
+    test.shutdown().await;
+}
+
+// The watch mode waits for a key press, so it's only available in the interactive mode
+#[rstest]
+#[case(test_utils::random::Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn watch_non_interactive(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let test = CliTestFramework::setup(&mut rng).await;
+
+    test.create_genesis_wallet();
+    assert_eq!(
+        test.exec("wallet-watch"),
+        "Invalid input: Unsupported command in non-interactive mode: wallet-watch"
+    );
+
+    test.shutdown().await;
+}
+
 #[rstest]
 #[case(test_utils::random::Seed::from_entropy())]
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
@@ -179,3 +474,72 @@ async fn produce_blocks_decommission_genesis_pool(#[case] seed: Seed) {
 
     test.shutdown().await;
 }
+
+#[rstest]
+#[case(test_utils::random::Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn import_private_key(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let test = CliTestFramework::setup(&mut rng).await;
+
+    let file_name = test
+        .test_root
+        .fresh_test_dir("wallet dir")
+        .as_ref()
+        .join("import_wallet")
+        .to_str()
+        .unwrap()
+        .to_owned();
+    assert_eq!(
+        test.exec(&format!(
+            "wallet-create \"{file_name}\" store-seed-phrase \"{MNEMONIC}\""
+        )),
+        "New wallet created successfully"
+    );
+
+    let (private_key, public_key) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+    let private_key_hex = HexEncoded::new(private_key).to_string();
+    let address = Address::new(
+        &test.chain_config,
+        Destination::PublicKeyHash((&public_key).into()),
+    )
+    .unwrap();
+
+    assert_eq!(
+        test.exec(&format!(
+            "standalone-add-private-key-from-hex {private_key_hex} --label imported"
+        )),
+        format!("Success, the new private key for address {address} has been added to the account.\nRescanning the blockchain to detect balance in added new addresses")
+    );
+
+    // adding the same key twice is prohibited
+    assert!(test
+        .exec(&format!(
+            "standalone-add-private-key-from-hex {private_key_hex}"
+        ))
+        .contains("Standalone address already exists"));
+
+    let output = test.exec("standalone-address-show");
+    assert!(output.contains(&address.to_string()));
+    assert!(output.contains("imported"));
+
+    // UTXOs sent to the imported key are detected as owned
+    assert!(test
+        .exec(&format!("address-send {address} 1000"))
+        .starts_with("The transaction was submitted successfully with ID"));
+    assert_eq!(test.exec("node-generate-blocks 1"), "Success");
+    assert!(test
+        .exec(&format!("standalone-address-details {address}"))
+        .contains("Coins amount: 1000"));
+
+    // the imported key persists across wallet close/open
+    assert_eq!(test.exec("wallet-close"), "Successfully closed the wallet.");
+    assert_eq!(
+        test.exec(&format!("wallet-open \"{file_name}\"")),
+        "Wallet loaded successfully"
+    );
+    assert!(test.exec("standalone-address-show").contains(&address.to_string()));
+
+    test.shutdown().await;
+}
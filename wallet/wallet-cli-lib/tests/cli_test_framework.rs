@@ -25,6 +25,7 @@ use std::{
 
 use subsystem::{ManagerJoinHandle, ShutdownTrigger};
 use test_utils::test_dir::TestRoot;
+use wallet_cli_commands::plugin::WalletPlugin;
 use wallet_cli_lib::{
     config::{Network, RegtestOptions, WalletCliArgs},
     console::{ConsoleInput, ConsoleOutput},
@@ -45,6 +46,9 @@ struct MockConsoleOutput {
     output_tx: mpsc::Sender<String>,
 }
 
+/// Wide enough for QR codes to be shown
+const MOCK_TERMINAL_WIDTH: u16 = 200;
+
 impl ConsoleInput for MockConsoleInput {
     fn is_tty(&self) -> bool {
         false
@@ -63,6 +67,10 @@ impl ConsoleOutput for MockConsoleOutput {
     fn print_error<N: NodeInterface>(&mut self, error: WalletCliError<N>) {
         self.output_tx.send(error.to_string()).unwrap();
     }
+
+    fn terminal_width(&self) -> Option<u16> {
+        Some(MOCK_TERMINAL_WIDTH)
+    }
 }
 
 pub struct CliTestFramework {
@@ -78,6 +86,13 @@ pub struct CliTestFramework {
 
 impl CliTestFramework {
     pub async fn setup(rng: &mut impl Rng) -> Self {
+        Self::setup_with_plugins(rng, Vec::new()).await
+    }
+
+    pub async fn setup_with_plugins(
+        rng: &mut impl Rng,
+        plugins: Vec<Box<dyn WalletPlugin>>,
+    ) -> Self {
         logging::init_logging();
 
         let test_root = test_utils::test_root!("wallet-cli-tests").unwrap();
@@ -121,6 +136,7 @@ impl CliTestFramework {
                     remote_rpc_wallet_password: None,
                     remote_rpc_wallet_cookie_file: None,
                     remote_rpc_wallet_no_authentication: true,
+                    plugin_paths: vec![],
                 },
             }))),
             run_options: wallet_cli_lib::config::CliArgs {
@@ -150,6 +166,7 @@ impl CliTestFramework {
                 remote_rpc_wallet_password: None,
                 remote_rpc_wallet_cookie_file: None,
                 remote_rpc_wallet_no_authentication: true,
+                plugin_paths: vec![],
             },
         };
 
@@ -164,7 +181,13 @@ impl CliTestFramework {
         let wallet_task = tokio::spawn(async move {
             tokio::time::timeout(
                 Duration::from_secs(120),
-                wallet_cli_lib::run(input, output, wallet_options, Some(wallet_chain_config)),
+                wallet_cli_lib::run_with_plugins(
+                    input,
+                    output,
+                    wallet_options,
+                    Some(wallet_chain_config),
+                    plugins,
+                ),
             )
             .await
             .unwrap()
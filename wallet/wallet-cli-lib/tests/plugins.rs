@@ -0,0 +1,64 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod cli_test_framework;
+
+use clap::{ArgMatches, Command};
+use rstest::rstest;
+use test_utils::random::{make_seedable_rng, Seed};
+use wallet_cli_commands::{
+    plugin::{PluginError, WalletCommandRunner, WalletPlugin},
+    ConsoleCommand,
+};
+
+use crate::cli_test_framework::CliTestFramework;
+
+struct PingPlugin;
+
+#[async_trait::async_trait]
+impl WalletPlugin for PingPlugin {
+    fn name(&self) -> &str {
+        "ping"
+    }
+
+    fn commands(&self) -> Vec<Command> {
+        vec![Command::new("ping").about("Reply with pong")]
+    }
+
+    async fn handle(
+        &self,
+        args: &ArgMatches,
+        _wallet: &mut dyn WalletCommandRunner,
+    ) -> Result<ConsoleCommand, PluginError> {
+        assert_eq!(args.subcommand_name(), Some("ping"));
+        Ok(ConsoleCommand::Print("pong".to_owned()))
+    }
+}
+
+#[rstest]
+#[case(test_utils::random::Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn plugin_command(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let test = CliTestFramework::setup_with_plugins(&mut rng, vec![Box::new(PingPlugin)]).await;
+
+    let output = test.exec("help");
+    assert!(output.contains("Plugins:"));
+    assert!(output.contains("Reply with pong"));
+
+    assert_eq!(test.exec("ping"), "pong");
+
+    test.shutdown().await;
+}
@@ -15,6 +15,7 @@
 
 mod cli_test_framework;
 
+use randomness::Rng;
 use rstest::rstest;
 use test_utils::random::{make_seedable_rng, Seed};
 
@@ -63,3 +64,94 @@ async fn staking_locked_wallet(#[case] seed: Seed) {
 
     test.shutdown().await;
 }
+
+#[rstest]
+#[case(test_utils::random::Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn delegate_stake_and_withdraw(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let test = CliTestFramework::setup(&mut rng).await;
+
+    test.create_genesis_wallet();
+
+    // Create a pool in another account, so that only the genesis pool gets the block rewards
+    // and the balance of the new pool only changes with the delegation
+    assert_eq!(
+        test.exec("account-create"),
+        "Success, the new account index is: 1"
+    );
+    assert_eq!(test.exec("account-select 1"), "Success");
+    let acc1_address = test.exec("address-new");
+    assert_eq!(test.exec("account-select 0"), "Success");
+    assert!(test
+        .exec(&format!("address-send {acc1_address} 50000"))
+        .starts_with("The transaction was submitted successfully with ID"));
+    assert_eq!(test.exec("node-generate-blocks 1"), "Success");
+
+    assert_eq!(test.exec("account-select 1"), "Success");
+    assert!(test
+        .exec(&format!(
+            "staking-create-pool 40000 0 0.{} {acc1_address}",
+            rng.gen_range(1..100),
+        ))
+        .starts_with("The transaction was submitted successfully with ID"));
+    assert_eq!(test.exec("account-select 0"), "Success");
+    assert_eq!(test.exec("node-generate-blocks 1"), "Success");
+
+    assert_eq!(test.exec("account-select 1"), "Success");
+    let pools = test.exec("staking-list-pools");
+    let pool_id = pools
+        .strip_prefix("Pool Id: ")
+        .and_then(|pools| pools.split(',').next())
+        .unwrap()
+        .to_owned();
+    assert_eq!(
+        test.exec(&format!("staking-pool-balance {pool_id}")),
+        "40000"
+    );
+
+    // An invalid pool address is rejected
+    assert_eq!(test.exec("account-select 0"), "Success");
+    assert!(test
+        .exec(&format!("delegation-create-and-stake {acc1_address} 1000"))
+        .ends_with("Invalid pool ID"));
+
+    let output = test.exec(&format!("delegation-create-and-stake {pool_id} 1000"));
+    let delegation_id = output
+        .strip_prefix(
+            "Success, the delegation transaction was broadcast to the network. Delegation id: ",
+        )
+        .unwrap()
+        .to_owned();
+    assert_eq!(test.exec("node-generate-blocks 1"), "Success");
+
+    // The pool received the delegation
+    assert_eq!(
+        test.exec(&format!("staking-pool-balance {pool_id}")),
+        "41000"
+    );
+    assert_eq!(
+        test.exec("delegation-list-ids"),
+        format!("Delegation Id: {delegation_id}, Balance: 1000")
+    );
+
+    assert!(test
+        .exec(&format!(
+            "delegation-withdraw-to-wallet {delegation_id} 400"
+        ))
+        .starts_with("The transaction was submitted successfully with ID"));
+    assert_eq!(test.exec("node-generate-blocks 1"), "Success");
+
+    // The withdrawal reduced the delegated amount
+    assert_eq!(
+        test.exec(&format!("staking-pool-balance {pool_id}")),
+        "40600"
+    );
+    assert_eq!(
+        test.exec("delegation-list-ids"),
+        format!("Delegation Id: {delegation_id}, Balance: 600")
+    );
+
+    test.shutdown().await;
+}
@@ -25,8 +25,11 @@ const NORMAL_DELAY: Duration = Duration::from_secs(1);
 const ERROR_DELAY: Duration = Duration::from_secs(10);
 
 use blockprod::BlockProductionError;
-use chainstate::tx_verifier::{
-    self, error::ScriptError, input_check::signature_only_check::SignatureOnlyVerifiable,
+use chainstate::{
+    rpc::BlockSubmissionError,
+    tx_verifier::{
+        self, error::ScriptError, input_check::signature_only_check::SignatureOnlyVerifiable,
+    },
 };
 use futures::{
     never::Never,
@@ -44,7 +47,8 @@ use std::{
 };
 use types::{
     Balances, GenericCurrencyTransferToTxOutputConversionError, InspectTransaction,
-    SeedWithPassPhrase, SignatureStats, TransactionToInspect, ValidatedSignatures, WalletInfo,
+    SeedWithPassPhrase, SignatureStats, TransactionToInspect, ValidatedSignatures,
+    WalletFingerprintInfo, WalletInfo, WalletVersionInfo,
 };
 
 use read::ReadOnlyController;
@@ -52,9 +56,9 @@ use sync::InSync;
 use synced_controller::SyncedController;
 
 use common::{
-    address::{AddressError, RpcAddress},
+    address::{Address, AddressError, RpcAddress},
     chain::{
-        block::timestamp::BlockTimestamp,
+        block::{timestamp::BlockTimestamp, BlockMerkleTreeError},
         htlc::HtlcSecret,
         partially_signed_transaction::PartiallySignedTransaction,
         signature::{inputsig::InputWitness, DestinationSigError, Transactable},
@@ -65,7 +69,7 @@ use common::{
     primitives::{
         amount::RpcAmountOut,
         time::{get_time, Time},
-        Amount, BlockHeight, Id, Idable,
+        Amount, BlockHeight, Id, Idable, H256,
     },
 };
 use consensus::{GenerateBlockInputData, PoSTimestampSearchInputData};
@@ -79,21 +83,23 @@ pub use node_comm::{
 };
 use randomness::{make_pseudo_rng, make_true_rng, Rng};
 use wallet::{
-    account::{
-        currency_grouper::{self, Currency},
-        TransactionToSign,
-    },
+    account::{currency_grouper, TransactionToSign},
     destination_getters::{get_tx_output_destination, HtlcSpendingCondition},
     wallet::WalletPoolsFilter,
     wallet_events::WalletEvents,
-    DefaultWallet, WalletError, WalletResult,
+    DefaultWallet, WalletError,
 };
 pub use wallet_types::{
     account_info::DEFAULT_ACCOUNT_INDEX,
     utxo_types::{UtxoState, UtxoStates, UtxoType, UtxoTypes},
 };
 use wallet_types::{
-    seed_phrase::StoreSeedPhrase, signature_status::SignatureStatus, wallet_type::WalletType,
+    currency::{Currency, CurrencyAmountMap},
+    idempotency::IdempotencyKey,
+    seed_phrase::StoreSeedPhrase,
+    signature_status::SignatureStatus,
+    spending_policy::SpendingPolicy,
+    wallet_type::WalletType,
     with_locked::WithLocked,
 };
 
@@ -131,6 +137,8 @@ pub enum ControllerError<T: NodeInterface> {
     NoWallet,
     #[error("Search for timestamps failed: {0}")]
     SearchForTimestampsFailed(BlockProductionError),
+    #[error("{0}")]
+    BlockRejected(#[from] BlockSubmissionError),
     #[error("Expecting non-empty inputs")]
     ExpectingNonEmptyInputs,
     #[error("Expecting non-empty outputs")]
@@ -141,9 +149,15 @@ pub enum ControllerError<T: NodeInterface> {
     InvalidTxOutput(GenericCurrencyTransferToTxOutputConversionError),
     #[error("The specified token {0} is not a fungible token")]
     NotFungibleToken(TokenId),
+    #[error("Too many outputs: {0}, the maximum is {1}")]
+    TooManyOutputs(usize, usize),
+    #[error("Duplicate output address: {0}")]
+    DuplicateOutputAddress(String),
+    #[error("Block merkle tree error: {0}")]
+    BlockMerkleTreeError(#[from] BlockMerkleTreeError),
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct ControllerConfig {
     /// In which top N MB should we aim for our transactions to be in the mempool
     /// e.g. for 5, we aim to be in the top 5 MB of transactions based on paid fees
@@ -153,6 +167,44 @@ pub struct ControllerConfig {
     /// Should the controller broadcast the created transactions to the mempool
     /// Set to False by the GUI wallet to allow for a confirmation dialog before broadcasting
     pub broadcast_to_mempool: bool,
+
+    /// How the UTXOs paying for the sent coins and tokens are selected
+    pub utxo_selection_strategy: UtxoSelectionStrategy,
+
+    /// Whether the UTXOs that haven't reached the wallet's confirmation threshold yet
+    /// can be spent; the explicitly selected UTXOs are always spent
+    pub spend_pending_utxos: bool,
+
+    /// The idempotency key of the request the transaction is created for, it is recorded
+    /// together with the transaction when it is broadcast to the mempool
+    pub idempotent_request: Option<IdempotentRequest>,
+}
+
+/// A request identified by a client-supplied idempotency key, so that retrying it returns
+/// the transaction created the first time instead of creating another one
+#[derive(Debug, Clone)]
+pub struct IdempotentRequest {
+    pub key: IdempotencyKey,
+    /// The hash of the request parameters, the key cannot be reused for a different request
+    pub request_hash: H256,
+}
+
+/// How the UTXOs spent by a transaction are selected from the wallet.
+///
+/// If the available UTXOs cannot cover the amount to be paid, the selection fails with
+/// the not enough funds error regardless of the strategy.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum UtxoSelectionStrategy {
+    /// Spend the fewest UTXOs, taking the largest ones first, to pay the lowest fee
+    MinimizeFee,
+    /// Spend a random subset of the UTXOs, to avoid fingerprinting the wallet by its selection
+    #[default]
+    MaximizePrivacy,
+    /// Look for the UTXOs matching the amount exactly so no change output is created,
+    /// else spend the UTXOs exceeding the amount the least
+    MinimizeChange,
+    /// Spend only the specified UTXOs
+    Manual(Vec<UtxoOutPoint>),
 }
 
 pub struct Controller<T, W> {
@@ -277,31 +329,38 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         Ok(wallet)
     }
 
-    fn make_backup_wallet_file(file_path: impl AsRef<Path>, version: u32) -> WalletResult<()> {
-        let backup_name = file_path
-            .as_ref()
-            .file_name()
-            .map(|file_name| {
-                let mut file_name = file_name.to_os_string();
-                file_name.push(format!("_backup_v{version}"));
-                file_name
-            })
-            .ok_or(WalletError::WalletFileError(
-                file_path.as_ref().to_owned(),
-                "File path is not a file".to_owned(),
-            ))?;
-        let backup_file_path = file_path.as_ref().with_file_name(backup_name);
-        logging::log::info!(
-            "The wallet DB requires a migration, creating a backup file: {}",
-            backup_file_path.to_string_lossy()
-        );
-        fs::copy(&file_path, backup_file_path).map_err(|_| {
-            WalletError::WalletFileError(
+    /// Create a new wallet file from a backup made with `backup_wallet`.
+    /// The wallet is reset to genesis, so it will rescan the blockchain once it's synced.
+    pub fn restore_wallet_from_backup(
+        chain_config: Arc<ChainConfig>,
+        backup_path: impl AsRef<Path>,
+        file_path: impl AsRef<Path>,
+        backup_password: Option<String>,
+        wallet_type: WalletType,
+    ) -> Result<DefaultWallet, ControllerError<T>> {
+        utils::ensure!(
+            !file_path.as_ref().exists(),
+            ControllerError::WalletFileError(
                 file_path.as_ref().to_owned(),
-                "Could not make a backup of the file before migrating it".to_owned(),
+                "File already exists".to_owned()
             )
+        );
+
+        let backup = fs::read(backup_path.as_ref()).map_err(|e| {
+            ControllerError::WalletFileError(backup_path.as_ref().to_owned(), e.to_string())
         })?;
-        Ok(())
+
+        let db = wallet::wallet::create_wallet_file_from_backup(
+            file_path,
+            &backup,
+            backup_password.as_ref(),
+        )
+        .map_err(ControllerError::WalletError)?;
+        let wallet =
+            wallet::Wallet::load_wallet_from_backup(Arc::clone(&chain_config), db, wallet_type)
+                .map_err(ControllerError::WalletError)?;
+
+        Ok(wallet)
     }
 
     pub fn open_wallet(
@@ -326,7 +385,9 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
             Arc::clone(&chain_config),
             db,
             password,
-            |version| Self::make_backup_wallet_file(file_path.as_ref(), version),
+            |version| {
+                wallet::wallet::make_wallet_file_backup(file_path.as_ref(), version).map(|_| ())
+            },
             wallet_type,
             force_change_wallet_type,
         )
@@ -342,6 +403,36 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
             .map_err(ControllerError::WalletError)
     }
 
+    /// Returns the fingerprint of the wallet's root key and whether a BIP39 passphrase was used,
+    /// if known (wallets created by older versions don't store this information)
+    pub fn fingerprint(&self) -> Result<WalletFingerprintInfo, ControllerError<T>> {
+        let (fingerprint, passphrase_used) =
+            self.wallet.fingerprint().map_err(ControllerError::WalletError)?;
+        let lookahead_size = self.wallet.lookahead_size().map_err(ControllerError::WalletError)?;
+        Ok(WalletFingerprintInfo {
+            fingerprint: fingerprint.to_string(),
+            passphrase_used,
+            lookahead_size,
+            last_used_change_index: self.wallet.last_used_change_index().map(U31::into_u32),
+        })
+    }
+
+    /// Returns the version of the wallet software and of the wallet DB schema
+    pub fn version_info(
+        &self,
+        open_wallet_path: Option<&Path>,
+    ) -> Result<WalletVersionInfo, ControllerError<T>> {
+        let wallet_schema_version =
+            self.wallet.storage_version().map_err(ControllerError::WalletError)?;
+        Ok(WalletVersionInfo {
+            software_version: wallet::wallet::WALLET_SOFTWARE_VERSION.to_owned(),
+            wallet_schema_version,
+            min_compatible_schema_version: wallet::wallet::MIN_SUPPORTED_WALLET_VERSION,
+            chain_type: self.chain_config.chain_type().name().to_owned(),
+            open_wallet_path: open_wallet_path.map(|path| path.display().to_string()),
+        })
+    }
+
     /// Delete the seed phrase if stored in the database
     pub fn delete_seed_phrase(&self) -> Result<Option<SeedWithPassPhrase>, ControllerError<T>> {
         self.wallet
@@ -350,6 +441,30 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
             .map_err(ControllerError::WalletError)
     }
 
+    /// Write an encrypted backup of the wallet to `file_path`.
+    /// The backup only contains the data that can't be recovered by rescanning the blockchain.
+    pub fn backup_wallet(
+        &self,
+        file_path: impl AsRef<Path>,
+        password: Option<String>,
+    ) -> Result<(), ControllerError<T>> {
+        utils::ensure!(
+            !file_path.as_ref().exists(),
+            ControllerError::WalletFileError(
+                file_path.as_ref().to_owned(),
+                "File already exists".to_owned()
+            )
+        );
+
+        let backup = self
+            .wallet
+            .make_backup(password.as_ref())
+            .map_err(ControllerError::WalletError)?;
+        fs::write(file_path.as_ref(), backup).map_err(|e| {
+            ControllerError::WalletFileError(file_path.as_ref().to_owned(), e.to_string())
+        })
+    }
+
     /// Rescan the blockchain
     /// Resets the wallet to the genesis block
     pub fn reset_wallet_to_genesis(&mut self) -> Result<(), ControllerError<T>> {
@@ -527,7 +642,7 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
             self.rpc_client
                 .submit_block(block)
                 .await
-                .map_err(ControllerError::NodeCallError)?;
+                .map_err(ControllerError::NodeCallError)??;
         }
 
         self.sync_once().await
@@ -612,6 +727,15 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
             .expect("there must be at least one account")
     }
 
+    /// Return the addresses, out of the given ones, that belong to this wallet and have already
+    /// received funds, so paying them again would reuse an address
+    pub fn find_reused_addresses<'a>(
+        &self,
+        addresses: impl IntoIterator<Item = &'a Address<Destination>>,
+    ) -> Vec<Address<Destination>> {
+        self.wallet.find_used_own_addresses(addresses)
+    }
+
     pub async fn get_stake_pool_balances(
         &self,
         account_index: U31,
@@ -670,6 +794,14 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         }
     }
 
+    /// Whether the error is caused by the node being unreachable, e.g. while it's restarting
+    pub fn is_node_unavailable(&self, error: &ControllerError<T>) -> bool {
+        match error {
+            ControllerError::NodeCallError(e) => self.rpc_client.is_node_unavailable(e),
+            _ => false,
+        }
+    }
+
     pub async fn try_sync_once(&mut self) -> Result<(), ControllerError<T>> {
         sync::sync_once(
             &self.chain_config,
@@ -699,6 +831,76 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         ))
     }
 
+    /// Find the transaction created by an earlier request with the same idempotency key.
+    ///
+    /// The keys recorded longer than `retention` ago are forgotten first, so they can be reused.
+    pub fn find_idempotent_transaction(
+        &mut self,
+        account_index: U31,
+        request: Option<&IdempotentRequest>,
+        retention: Duration,
+    ) -> Result<Option<SignedTransaction>, ControllerError<T>> {
+        let request = match request {
+            Some(request) => request,
+            None => return Ok(None),
+        };
+
+        let created_before = (get_time() - retention).unwrap_or(Time::from_secs_since_epoch(0));
+        self.wallet
+            .purge_idempotency_keys(BlockTimestamp::from_time(created_before))
+            .map_err(ControllerError::WalletError)?;
+
+        let tx_id = match self
+            .wallet
+            .find_idempotent_transaction(&request.key, &request.request_hash)
+            .map_err(ControllerError::WalletError)?
+        {
+            Some(tx_id) => tx_id,
+            None => return Ok(None),
+        };
+        let tx = self
+            .wallet
+            .get_transaction(account_index, tx_id)
+            .map_err(ControllerError::WalletError)?;
+        Ok(Some(tx.get_signed_transaction().clone()))
+    }
+
+    /// The spending policy of the account and the coins it sent within the daily cap period
+    pub fn get_spending_policy(
+        &mut self,
+        account_index: U31,
+    ) -> Result<(SpendingPolicy, Amount), ControllerError<T>> {
+        let policy = self
+            .wallet
+            .get_spending_policy(account_index)
+            .map_err(ControllerError::WalletError)?;
+        let sent_in_period = self
+            .wallet
+            .get_recent_outflow(account_index, BlockTimestamp::from_time(get_time()))
+            .map_err(ControllerError::WalletError)?;
+        Ok((policy, sent_in_period))
+    }
+
+    pub fn set_spending_policy(
+        &mut self,
+        account_index: U31,
+        policy: SpendingPolicy,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .set_spending_policy(account_index, policy)
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub fn get_confirmation_threshold(&self) -> Result<u64, ControllerError<T>> {
+        self.wallet.get_confirmation_threshold().map_err(ControllerError::WalletError)
+    }
+
+    pub fn set_confirmation_threshold(&mut self, threshold: u64) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .set_confirmation_threshold(threshold)
+            .map_err(ControllerError::WalletError)
+    }
+
     pub fn readonly_controller(&self, account_index: U31) -> ReadOnlyController<T> {
         ReadOnlyController::new(
             &self.wallet,
@@ -965,60 +1167,9 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         inputs: &[TxOutput],
         outputs: &[TxOutput],
     ) -> Result<Balances, ControllerError<T>> {
-        let mut inputs = self.group_inputs(inputs)?;
-        let outputs = self.group_outputs(outputs)?;
-
-        let mut fees = BTreeMap::new();
-
-        for (currency, output) in outputs {
-            let input_amount = inputs.remove(&currency).ok_or(
-                ControllerError::<T>::WalletError(WalletError::NotEnoughUtxo(Amount::ZERO, output)),
-            )?;
-
-            let fee = (input_amount - output).ok_or(ControllerError::<T>::WalletError(
-                WalletError::NotEnoughUtxo(input_amount, output),
-            ))?;
-            fees.insert(currency, fee);
-        }
-        // add any leftover inputs
-        fees.extend(inputs);
-
-        into_balances(&self.rpc_client, &self.chain_config, fees).await
-    }
+        let fees = calculate_fees(&self.chain_config, self.best_block().1, inputs, outputs)?;
 
-    fn group_outputs(
-        &self,
-        outputs: &[TxOutput],
-    ) -> Result<BTreeMap<Currency, Amount>, ControllerError<T>> {
-        let best_block_height = self.best_block().1;
-        currency_grouper::group_outputs_with_issuance_fee(
-            outputs.iter(),
-            |&output| output,
-            |grouped: &mut Amount, _, new_amount| -> Result<(), WalletError> {
-                *grouped = grouped.add(new_amount).ok_or(WalletError::OutputAmountOverflow)?;
-                Ok(())
-            },
-            Amount::ZERO,
-            &self.chain_config,
-            best_block_height,
-        )
-        .map_err(|err| ControllerError::WalletError(err))
-    }
-
-    fn group_inputs(
-        &self,
-        input_utxos: &[TxOutput],
-    ) -> Result<BTreeMap<Currency, Amount>, ControllerError<T>> {
-        currency_grouper::group_utxos_for_input(
-            input_utxos.iter(),
-            |tx_output| tx_output,
-            |total: &mut Amount, _, amount| -> Result<(), WalletError> {
-                *total = (*total + amount).ok_or(WalletError::OutputAmountOverflow)?;
-                Ok(())
-            },
-            Amount::ZERO,
-        )
-        .map_err(|err| ControllerError::WalletError(err))
+        into_balances(&self.rpc_client, &self.chain_config, fees.into()).await
     }
 
     async fn fetch_utxos(
@@ -1062,14 +1213,31 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
     pub async fn run(&mut self) -> Result<Never, ControllerError<T>> {
         let mut rebroadcast_txs_timer = get_time();
         let staking_started = self.staking_started.clone();
+        let mut node_unavailable = false;
 
         'outer: loop {
             let sync_res = self.sync_once().await;
 
-            if let Err(e) = sync_res {
-                log::error!("Wallet sync error: {e}");
-                tokio::time::sleep(ERROR_DELAY).await;
-                continue;
+            match sync_res {
+                Ok(()) => {
+                    if node_unavailable {
+                        log::info!("Node is available again, resuming wallet sync");
+                        node_unavailable = false;
+                    }
+                }
+                Err(e) => {
+                    // Report an unreachable node once and keep waiting for it quietly
+                    if self.is_node_unavailable(&e) {
+                        if !node_unavailable {
+                            log::warn!("Waiting for the node to become available: {e}");
+                            node_unavailable = true;
+                        }
+                    } else {
+                        log::error!("Wallet sync error: {e}");
+                    }
+                    tokio::time::sleep(ERROR_DELAY).await;
+                    continue;
+                }
             }
 
             for account_index in staking_started.iter() {
@@ -1089,9 +1257,16 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
                     );
 
                     let submit_res = self.rpc_client.submit_block(block).await;
-                    if let Err(e) = submit_res {
-                        log::error!("Block submit failed: {e}");
-                        tokio::time::sleep(ERROR_DELAY).await;
+                    match submit_res {
+                        Ok(Ok(())) => {}
+                        Ok(Err(e)) => {
+                            log::error!("Block submit failed: {e}; hint: {}", e.hint);
+                            tokio::time::sleep(ERROR_DELAY).await;
+                        }
+                        Err(e) => {
+                            log::error!("Block submit failed: {e}");
+                            tokio::time::sleep(ERROR_DELAY).await;
+                        }
                     }
 
                     continue 'outer;
@@ -1144,22 +1319,65 @@ pub async fn fetch_token_info<T: NodeInterface>(
         )))
 }
 
+/// Calculate the fees paid by a transaction, per currency, given the utxos spent by its inputs
+pub(crate) fn calculate_fees<T: NodeInterface>(
+    chain_config: &ChainConfig,
+    best_block_height: BlockHeight,
+    inputs: &[TxOutput],
+    outputs: &[TxOutput],
+) -> Result<BTreeMap<Currency, Amount>, ControllerError<T>> {
+    let mut inputs = currency_grouper::group_utxos_for_input(
+        inputs.iter(),
+        |tx_output| tx_output,
+        |total: &mut Amount, _, amount| -> Result<(), WalletError> {
+            *total = (*total + amount).ok_or(WalletError::OutputAmountOverflow)?;
+            Ok(())
+        },
+        Amount::ZERO,
+    )
+    .map_err(ControllerError::WalletError)?;
+    let outputs = currency_grouper::group_outputs_with_issuance_fee(
+        outputs.iter(),
+        |&output| output,
+        |grouped: &mut Amount, _, new_amount| -> Result<(), WalletError> {
+            *grouped = grouped.add(new_amount).ok_or(WalletError::OutputAmountOverflow)?;
+            Ok(())
+        },
+        Amount::ZERO,
+        chain_config,
+        best_block_height,
+    )
+    .map_err(ControllerError::WalletError)?;
+
+    let mut fees = BTreeMap::new();
+
+    for (currency, output) in outputs {
+        let input_amount = inputs.remove(&currency).ok_or(ControllerError::<T>::WalletError(
+            WalletError::NotEnoughUtxo(Amount::ZERO, output),
+        ))?;
+
+        let fee = (input_amount - output).ok_or(ControllerError::<T>::WalletError(
+            WalletError::NotEnoughUtxo(input_amount, output),
+        ))?;
+        fees.insert(currency, fee);
+    }
+    // add any leftover inputs
+    fees.extend(inputs);
+
+    Ok(fees)
+}
+
 pub async fn into_balances<T: NodeInterface>(
     rpc_client: &T,
     chain_config: &ChainConfig,
-    mut balances: BTreeMap<Currency, Amount>,
+    balances: CurrencyAmountMap,
 ) -> Result<Balances, ControllerError<T>> {
-    let coins = balances.remove(&Currency::Coin).unwrap_or(Amount::ZERO);
+    let (coins, tokens) = balances.into_coins_and_tokens();
     let coins = RpcAmountOut::from_amount_no_padding(coins, chain_config.coin_decimals());
 
-    let tasks: FuturesUnordered<_> = balances
+    let tasks: FuturesUnordered<_> = tokens
         .into_iter()
-        .map(|(currency, amount)| async move {
-            let token_id = match currency {
-                Currency::Coin => panic!("Removed just above"),
-                Currency::Token(token_id) => token_id,
-            };
-
+        .map(|(token_id, amount)| async move {
             fetch_token_info(rpc_client, token_id).await.map(|info| {
                 let decimals = info.token_number_of_decimals();
                 let amount = RpcAmountOut::from_amount_no_padding(amount, decimals);
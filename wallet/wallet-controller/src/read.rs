@@ -15,30 +15,42 @@
 
 //! Read operations for the wallet
 
-use std::collections::BTreeMap;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
 
 use common::{
     address::Address,
-    chain::{ChainConfig, DelegationId, Destination, PoolId, Transaction, TxOutput, UtxoOutPoint},
-    primitives::{id::WithId, Amount, Id},
+    chain::{
+        block::ConsensusData,
+        signature::inputsig::arbitrary_message::proof_of_reserves::ProofOfReserves, ChainConfig,
+        DelegationId, Destination, PoolId, Transaction, TxOutput, UtxoOutPoint,
+    },
+    primitives::{id::WithId, Amount, BlockHeight, Id},
 };
 use crypto::{
-    key::hdkd::{child_number::ChildNumber, u31::U31},
+    key::hdkd::{child_number::ChildNumber, derivation_path::DerivationPath, u31::U31},
     vrf::VRFPublicKey,
 };
 use futures::{stream::FuturesUnordered, FutureExt, TryStreamExt};
 use node_comm::node_traits::NodeInterface;
+use serialization::Encode;
 use utils::tap_log::TapLog;
 use wallet::{
     account::{
-        currency_grouper::Currency, transaction_list::TransactionList, DelegationData, PoolData,
-        TxInfo,
+        transaction_list::{TransactionInfo, TransactionList},
+        DelegationData, PoolData, TxInfo,
     },
     wallet::WalletPoolsFilter,
-    DefaultWallet,
+    DefaultWallet, WalletError,
 };
 use wallet_types::{
     account_info::StandaloneAddresses,
+    currency::CurrencyAmountMap,
+    keys::{DescriptorType, WalletFingerprint},
+    label::{Label, SearchMatch},
     utxo_types::{UtxoStates, UtxoTypes},
     wallet_tx::TxData,
     with_locked::WithLocked,
@@ -46,7 +58,11 @@ use wallet_types::{
 };
 
 use crate::{
-    types::{AccountStandaloneKeyDetails, Balances, CreatedBlockInfo},
+    types::{
+        calculate_pools_staking_status, AccountStandaloneKeyDetails, Balances,
+        BalancesByConfirmations, CreatedBlockInfo, InclusionStatus, PoolStakingStatus,
+        STAKING_STATUS_PERIOD,
+    },
     ControllerError,
 };
 
@@ -83,7 +99,7 @@ impl<'a, T: NodeInterface> ReadOnlyController<'a, T> {
         &self,
         utxo_states: UtxoStates,
         with_locked: WithLocked,
-    ) -> Result<BTreeMap<Currency, Amount>, ControllerError<T>> {
+    ) -> Result<CurrencyAmountMap, ControllerError<T>> {
         self.wallet
             .get_balance(self.account_index, utxo_states, with_locked)
             .map_err(ControllerError::WalletError)
@@ -98,6 +114,47 @@ impl<'a, T: NodeInterface> ReadOnlyController<'a, T> {
         super::into_balances(&self.rpc_client, self.chain_config, balances).await
     }
 
+    /// The balance split by the number of confirmations; the wallet's confirmation threshold
+    /// is used if none is given
+    pub async fn get_decimal_balance_by_confirmations(
+        &self,
+        confirmation_threshold: Option<u64>,
+        with_locked: WithLocked,
+    ) -> Result<BalancesByConfirmations, ControllerError<T>> {
+        let confirmation_threshold = match confirmation_threshold {
+            Some(threshold) => threshold,
+            None => {
+                self.wallet.get_confirmation_threshold().map_err(ControllerError::WalletError)?
+            }
+        };
+        let balances = self
+            .wallet
+            .get_balance_by_confirmations(
+                self.account_index,
+                Some(confirmation_threshold),
+                with_locked,
+            )
+            .map_err(ControllerError::WalletError)?;
+
+        Ok(BalancesByConfirmations {
+            confirmation_threshold,
+            confirmed: super::into_balances(
+                &self.rpc_client,
+                self.chain_config,
+                balances.confirmed,
+            )
+            .await?,
+            pending: super::into_balances(&self.rpc_client, self.chain_config, balances.pending)
+                .await?,
+            unconfirmed: super::into_balances(
+                &self.rpc_client,
+                self.chain_config,
+                balances.unconfirmed,
+            )
+            .await?,
+        })
+    }
+
     pub fn get_multisig_utxos(
         &self,
         utxo_types: UtxoTypes,
@@ -142,6 +199,16 @@ impl<'a, T: NodeInterface> ReadOnlyController<'a, T> {
             .map_err(ControllerError::WalletError)
     }
 
+    pub fn get_address_labels(&self) -> Result<BTreeMap<Destination, Label>, ControllerError<T>> {
+        self.wallet.get_address_labels().map_err(ControllerError::WalletError)
+    }
+
+    pub fn search(&self, text: &str) -> Result<Vec<SearchMatch>, ControllerError<T>> {
+        self.wallet
+            .search(self.account_index, text)
+            .map_err(ControllerError::WalletError)
+    }
+
     pub fn get_transaction_list(
         &self,
         skip: usize,
@@ -161,6 +228,85 @@ impl<'a, T: NodeInterface> ReadOnlyController<'a, T> {
             .map_err(ControllerError::WalletError)
     }
 
+    pub fn get_transaction_info(
+        &self,
+        transaction_id: Id<Transaction>,
+    ) -> Result<TransactionInfo, ControllerError<T>> {
+        self.wallet
+            .get_transaction_info(self.account_index, transaction_id)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Check whether the transaction has been included in the mainchain or is still waiting
+    /// in the mempool. If it's confirmed, the proof of its inclusion in the block is provided.
+    pub async fn verify_transaction_inclusion(
+        &self,
+        transaction_id: Id<Transaction>,
+    ) -> Result<InclusionStatus, ControllerError<T>> {
+        if self
+            .rpc_client
+            .mempool_contains_tx(transaction_id)
+            .await
+            .map_err(ControllerError::NodeCallError)?
+        {
+            return Ok(InclusionStatus::InMempool);
+        }
+        if self
+            .rpc_client
+            .mempool_contains_orphan_tx(transaction_id)
+            .await
+            .map_err(ControllerError::NodeCallError)?
+        {
+            return Ok(InclusionStatus::InOrphanPool);
+        }
+
+        // The node doesn't index the transactions, so the block is looked up by the height
+        // known to the wallet
+        let block_height = match self.wallet.get_transaction(self.account_index, transaction_id) {
+            Ok(tx) => tx.state().block_height(),
+            Err(WalletError::NoTransactionFound(_)) => None,
+            Err(err) => return Err(ControllerError::WalletError(err)),
+        };
+        let block_height = match block_height {
+            Some(block_height) => block_height,
+            None => return Ok(InclusionStatus::NotFound),
+        };
+
+        let block_id = self
+            .rpc_client
+            .get_block_id_at_height(block_height)
+            .await
+            .map_err(ControllerError::NodeCallError)?
+            .and_then(|block_id| block_id.classify(self.chain_config).chain_block_id());
+        let block = match block_id {
+            Some(block_id) => self
+                .rpc_client
+                .get_block(block_id)
+                .await
+                .map_err(ControllerError::NodeCallError)?,
+            None => None,
+        };
+        let best_block_height = self
+            .rpc_client
+            .get_best_block_height()
+            .await
+            .map_err(ControllerError::NodeCallError)?;
+
+        // The wallet may not have caught up with a reorg yet, in which case the block at
+        // the height doesn't contain the transaction anymore
+        let status = match block {
+            Some(block) => InclusionStatus::from_mainchain_block(
+                &block,
+                block_height,
+                best_block_height,
+                &transaction_id,
+            )?,
+            None => None,
+        };
+
+        Ok(status.unwrap_or(InclusionStatus::NotFound))
+    }
+
     pub fn get_all_issued_addresses(
         &self,
     ) -> Result<BTreeMap<ChildNumber, Address<Destination>>, ControllerError<T>> {
@@ -183,6 +329,75 @@ impl<'a, T: NodeInterface> ReadOnlyController<'a, T> {
             .map_err(ControllerError::WalletError)
     }
 
+    /// Get the account's BIP32 extended public key and its derivation path
+    pub fn export_xpub(&self) -> Result<(String, DerivationPath), ControllerError<T>> {
+        self.wallet
+            .export_account_xpub(self.account_index)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Write the account's first `count` receive and change public keys, signed with the
+    /// account's private key, to a new file, so that they can be watched by a hot wallet.
+    pub fn export_address_set(
+        &self,
+        file_path: impl AsRef<Path>,
+        count: U31,
+    ) -> Result<WalletFingerprint, ControllerError<T>> {
+        utils::ensure!(
+            !file_path.as_ref().exists(),
+            ControllerError::WalletFileError(
+                file_path.as_ref().to_owned(),
+                "File already exists".to_owned()
+            )
+        );
+
+        let signed_address_set = self
+            .wallet
+            .export_address_set(self.account_index, count)
+            .map_err(ControllerError::WalletError)?;
+        fs::write(file_path.as_ref(), signed_address_set.encode()).map_err(|e| {
+            ControllerError::WalletFileError(file_path.as_ref().to_owned(), e.to_string())
+        })?;
+
+        Ok(signed_address_set.address_set().account_fingerprint())
+    }
+
+    /// Write a proof of reserves for the account's confirmed utxos to a new file,
+    /// to be checked by a node with `verify_proof_of_reserves`.
+    pub fn generate_proof_of_reserves(
+        &self,
+        file_path: impl AsRef<Path>,
+        message: Vec<u8>,
+    ) -> Result<ProofOfReserves, ControllerError<T>> {
+        utils::ensure!(
+            !file_path.as_ref().exists(),
+            ControllerError::WalletFileError(
+                file_path.as_ref().to_owned(),
+                "File already exists".to_owned()
+            )
+        );
+
+        let proof = self
+            .wallet
+            .generate_proof_of_reserves(self.account_index, message)
+            .map_err(ControllerError::WalletError)?;
+        fs::write(file_path.as_ref(), proof.encode()).map_err(|e| {
+            ControllerError::WalletFileError(file_path.as_ref().to_owned(), e.to_string())
+        })?;
+
+        Ok(proof)
+    }
+
+    /// Get the output descriptor of the account's receiving addresses
+    pub fn export_descriptor(
+        &self,
+        descriptor_type: DescriptorType,
+    ) -> Result<String, ControllerError<T>> {
+        self.wallet
+            .export_account_descriptor(self.account_index, descriptor_type)
+            .map_err(ControllerError::WalletError)
+    }
+
     pub fn get_addresses_usage(&self) -> Result<&'a KeychainUsageState, ControllerError<T>> {
         self.wallet
             .get_addresses_usage(self.account_index)
@@ -190,23 +405,23 @@ impl<'a, T: NodeInterface> ReadOnlyController<'a, T> {
     }
 
     /// Get all addresses with usage information
-    /// The boolean in the BTreeMap's value is true if the address is used, false is otherwise
-    /// Note that the usage statistics follow strictly the rules of the wallet. For example,
-    /// the initial wallet only stored information about the last used address, so the usage
-    /// of all addresses after the first unused address will have the result `false`.
+    /// The boolean in the BTreeMap's value is true if the address has received funds,
+    /// false is otherwise
     pub fn get_addresses_with_usage(
         &self,
     ) -> Result<MapAddressWithUsage<Destination>, ControllerError<T>> {
-        let addresses = self.get_all_issued_addresses()?;
-        let usage = self.get_addresses_usage()?;
+        self.wallet
+            .get_all_issued_addresses_with_usage(self.account_index)
+            .map_err(ControllerError::WalletError)
+    }
 
-        Ok(addresses
-            .into_iter()
-            .map(|(child_number, address)| {
-                let used = usage.last_used().is_some_and(|used| used >= child_number.get_index());
-                (child_number, (address, used))
-            })
-            .collect())
+    /// Get all the issued change addresses with usage information
+    pub fn get_change_addresses_with_usage(
+        &self,
+    ) -> Result<MapAddressWithUsage<Destination>, ControllerError<T>> {
+        self.wallet
+            .get_all_issued_change_addresses_with_usage(self.account_index)
+            .map_err(ControllerError::WalletError)
     }
 
     /// Get all standalone addresses with their labels
@@ -253,6 +468,61 @@ impl<'a, T: NodeInterface> ReadOnlyController<'a, T> {
         self.get_pools(WalletPoolsFilter::Stake).await
     }
 
+    /// Get the block production statistics of the pools owned by this account over
+    /// the last `STAKING_STATUS_PERIOD`
+    pub async fn get_staking_status(&self) -> Result<Vec<PoolStakingStatus>, ControllerError<T>> {
+        let pools = self.get_staking_pools().await?;
+
+        let block_spacing = std::cmp::max(self.chain_config.target_block_spacing().as_secs(), 1);
+        let block_count = std::cmp::max(STAKING_STATUS_PERIOD.as_secs() / block_spacing, 1);
+        let best_block_height = self
+            .rpc_client
+            .get_best_block_height()
+            .await
+            .map_err(ControllerError::NodeCallError)?;
+        let from_height = std::cmp::max(
+            best_block_height.saturating_sub(block_count - 1),
+            BlockHeight::one(),
+        );
+        let blocks = self
+            .rpc_client
+            .get_mainchain_blocks(from_height, block_count as usize)
+            .await
+            .map_err(ControllerError::NodeCallError)?;
+
+        // The balances of the other pools that produced the blocks are needed to calculate
+        // the stake share of our pools
+        let mut pool_balances = pools
+            .iter()
+            .map(|(pool_id, _, balance, _)| (*pool_id, *balance))
+            .collect::<BTreeMap<_, _>>();
+        let other_pool_ids = blocks
+            .iter()
+            .filter_map(|block| match block.consensus_data() {
+                ConsensusData::PoS(pos_data) => Some(*pos_data.stake_pool_id()),
+                ConsensusData::None | ConsensusData::PoW(_) => None,
+            })
+            .filter(|pool_id| !pool_balances.contains_key(pool_id))
+            .collect::<BTreeSet<_>>();
+        for pool_id in other_pool_ids {
+            let balance = self
+                .rpc_client
+                .get_stake_pool_balance(pool_id)
+                .await
+                .map_err(ControllerError::NodeCallError)?;
+            // Decommissioned pools don't hold any stake anymore
+            if let Some(balance) = balance {
+                pool_balances.insert(pool_id, balance);
+            }
+        }
+
+        Ok(calculate_pools_staking_status(
+            pools.into_iter().map(|(pool_id, _, _, _)| pool_id),
+            &pool_balances,
+            &blocks,
+        ))
+    }
+
     /// Get all pools that can be decommissioned by this account
     pub async fn get_pools_for_decommission(
         &self,
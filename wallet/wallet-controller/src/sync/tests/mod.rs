@@ -20,7 +20,7 @@ use std::{
 };
 
 use blockprod::TimestampSearchData;
-use chainstate::ChainInfo;
+use chainstate::{rpc::BlockSubmissionError, ChainInfo, ProofOfReservesReport, StakePoolInfo};
 use chainstate_test_framework::TestFramework;
 use common::{
     chain::{
@@ -33,7 +33,7 @@ use consensus::GenerateBlockInputData;
 use crypto::ephemeral_e2e::EndToEndPublicKey;
 use futures::executor::block_on;
 use logging::log;
-use mempool::{tx_accumulator::PackingStrategy, FeeRate};
+use mempool::{tx_accumulator::PackingStrategy, FeeRate, MempoolConfig, PackageLimits};
 use mempool_types::tx_options::TxOptionsOverrides;
 use node_comm::{
     node_traits::{ConnectedPeer, PeerId},
@@ -275,6 +275,17 @@ impl NodeInterface for MockNode {
         unreachable!()
     }
 
+    async fn get_all_pools(&self) -> Result<Vec<StakePoolInfo>, Self::Error> {
+        unreachable!()
+    }
+
+    async fn verify_proof_of_reserves(
+        &self,
+        _proof_hex: String,
+    ) -> Result<ProofOfReservesReport, Self::Error> {
+        unreachable!()
+    }
+
     async fn get_delegation_share(
         &self,
         _pool_id: PoolId,
@@ -332,7 +343,10 @@ impl NodeInterface for MockNode {
     ) -> Result<Block, Self::Error> {
         unreachable!()
     }
-    async fn submit_block(&self, _block: Block) -> Result<(), Self::Error> {
+    async fn submit_block(
+        &self,
+        _block: Block,
+    ) -> Result<Result<(), BlockSubmissionError>, Self::Error> {
         unreachable!()
     }
     async fn submit_transaction(
@@ -378,6 +392,12 @@ impl NodeInterface for MockNode {
     async fn p2p_get_peer_count(&self) -> Result<usize, Self::Error> {
         unreachable!()
     }
+    async fn get_peer_count(&self) -> Result<u32, Self::Error> {
+        unreachable!()
+    }
+    async fn is_node_synced(&self) -> Result<bool, Self::Error> {
+        unreachable!()
+    }
     async fn p2p_get_connected_peers(&self) -> Result<Vec<ConnectedPeer>, Self::Error> {
         unreachable!()
     }
@@ -404,6 +424,21 @@ impl NodeInterface for MockNode {
             FeeRate::from_amount_per_kb(Amount::from_atoms(1)),
         )])
     }
+
+    async fn mempool_get_package_limits(&self) -> Result<PackageLimits, Self::Error> {
+        Ok(MempoolConfig::new().package_limits())
+    }
+
+    async fn mempool_contains_tx(&self, _tx_id: Id<Transaction>) -> Result<bool, Self::Error> {
+        unreachable!()
+    }
+
+    async fn mempool_contains_orphan_tx(
+        &self,
+        _tx_id: Id<Transaction>,
+    ) -> Result<bool, Self::Error> {
+        unreachable!()
+    }
 }
 
 fn create_chain(node: &MockNode, rng: &mut (impl Rng + CryptoRng), parent: u64, count: usize) {
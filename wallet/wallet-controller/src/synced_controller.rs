@@ -13,11 +13,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{BTreeMap, BTreeSet};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
 
 use common::{
     address::{pubkeyhash::PublicKeyHash, Address},
     chain::{
+        block::timestamp::BlockTimestamp,
         classic_multisig::ClassicMultisigChallenge,
         htlc::HashedTimelockContract,
         output_value::OutputValue,
@@ -27,10 +32,10 @@ use common::{
             IsTokenFreezable, IsTokenUnfreezable, Metadata, RPCFungibleTokenInfo, RPCTokenInfo,
             TokenId, TokenIssuance, TokenIssuanceV1, TokenTotalSupply,
         },
-        ChainConfig, DelegationId, Destination, PoolId, SignedTransaction, Transaction, TxOutput,
-        UtxoOutPoint,
+        ChainConfig, DelegationId, Destination, PoolId, SignedTransaction, Transaction, TxInput,
+        TxOutput, UtxoOutPoint,
     },
-    primitives::{per_thousand::PerThousand, Amount, Id},
+    primitives::{per_thousand::PerThousand, time::get_time, Amount, Id, Idable},
 };
 use crypto::{
     key::{
@@ -43,11 +48,10 @@ use futures::{stream::FuturesUnordered, TryStreamExt};
 use logging::log;
 use mempool::FeeRate;
 use node_comm::node_traits::NodeInterface;
+use serialization::DecodeAll;
 use utils::ensure;
 use wallet::{
-    account::{
-        currency_grouper::Currency, CoinSelectionAlgo, TransactionToSign, UnconfirmedTokenInfo,
-    },
+    account::{CoinSelectionAlgo, TransactionToSign, UnconfirmedTokenInfo},
     destination_getters::{get_tx_output_destination, HtlcSpendingCondition},
     send_request::{
         make_address_output, make_address_output_token, make_create_delegation_output,
@@ -58,15 +62,18 @@ use wallet::{
     DefaultWallet, WalletError, WalletResult,
 };
 use wallet_types::{
+    address_set::{ImportedAddressSet, SignedAddressSet},
+    currency::Currency,
+    label::Label,
     signature_status::SignatureStatus,
     utxo_types::{UtxoState, UtxoType},
     with_locked::WithLocked,
 };
 
 use crate::{
-    into_balances,
-    types::{Balances, GenericCurrencyTransfer},
-    ControllerConfig, ControllerError,
+    calculate_fees, into_balances,
+    types::{Balances, DuplicateOutputs, GenericCurrencyTransfer, RpcAmountOut, SendToManyResult},
+    ControllerConfig, ControllerError, UtxoSelectionStrategy,
 };
 
 pub struct SyncedController<'a, T, W> {
@@ -156,6 +163,42 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
         Ok(())
     }
 
+    /// The UTXOs to spend and the coin selection algorithm to choose among them, according to
+    /// the configured UTXO selection strategy.
+    /// The UTXOs explicitly selected for a transaction take precedence over the strategy.
+    fn utxo_selection(
+        &self,
+        selected_utxos: Vec<UtxoOutPoint>,
+    ) -> Result<(Vec<UtxoOutPoint>, Option<CoinSelectionAlgo>), ControllerError<T>> {
+        if !selected_utxos.is_empty() {
+            return Ok((selected_utxos, None));
+        }
+
+        let selection = match &self.config.utxo_selection_strategy {
+            UtxoSelectionStrategy::MinimizeFee => (vec![], Some(CoinSelectionAlgo::LargestFirst)),
+            UtxoSelectionStrategy::MaximizePrivacy => {
+                (vec![], Some(CoinSelectionAlgo::SingleRandomDraw))
+            }
+            UtxoSelectionStrategy::MinimizeChange => (vec![], Some(CoinSelectionAlgo::LeastChange)),
+            UtxoSelectionStrategy::Manual(utxos) => {
+                ensure!(!utxos.is_empty(), ControllerError::ExpectingNonEmptyInputs);
+                (utxos.clone(), Some(CoinSelectionAlgo::UsePreselected))
+            }
+        };
+        Ok(selection)
+    }
+
+    /// The inputs of a transaction paid by the UTXOs chosen by `utxo_selection`.
+    /// If none are chosen, the UTXOs below the wallet's confirmation threshold are only spent
+    /// if the config allows it.
+    fn selected_inputs(&self, selected_utxos: Vec<UtxoOutPoint>) -> SelectedInputs {
+        if selected_utxos.is_empty() && self.config.spend_pending_utxos {
+            SelectedInputs::IncludingPending
+        } else {
+            SelectedInputs::Utxos(selected_utxos)
+        }
+    }
+
     /// Filter out utxos that contain tokens that are frozen and can't be used
     async fn filter_out_utxos_with_frozen_tokens(
         &self,
@@ -194,6 +237,26 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
             .map_err(ControllerError::WalletError)
     }
 
+    pub fn set_transaction_label(
+        &mut self,
+        tx_id: Id<Transaction>,
+        label: Option<Label>,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .set_transaction_label(self.account_index, tx_id, label)
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub fn set_address_label(
+        &mut self,
+        address: Destination,
+        label: Option<Label>,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .set_address_label(&address, label)
+            .map_err(ControllerError::WalletError)
+    }
+
     pub fn standalone_address_label_rename(
         &mut self,
         address: Destination,
@@ -214,6 +277,22 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
             .map_err(ControllerError::WalletError)
     }
 
+    /// Watch the addresses from a file written by `export_address_set` of a cold wallet
+    pub fn import_address_set(
+        &mut self,
+        file_path: impl AsRef<Path>,
+    ) -> Result<ImportedAddressSet, ControllerError<T>> {
+        let file_error =
+            |e: String| ControllerError::WalletFileError(file_path.as_ref().to_owned(), e);
+        let file_contents = fs::read(file_path.as_ref()).map_err(|e| file_error(e.to_string()))?;
+        let signed_address_set = SignedAddressSet::decode_all(&mut file_contents.as_slice())
+            .map_err(|_| file_error("Invalid address set file".to_owned()))?;
+
+        self.wallet
+            .import_address_set(self.account_index, signed_address_set)
+            .map_err(ControllerError::WalletError)
+    }
+
     pub fn add_standalone_private_key(
         &mut self,
         private_key: PrivateKey,
@@ -234,12 +313,18 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
             .map_err(ControllerError::WalletError)
     }
 
+    /// Get a receive address that has never received funds.
+    /// If allow_reuse is set, the last issued address is returned even if it has been used.
     pub fn new_address(
         &mut self,
+        allow_reuse: bool,
     ) -> Result<(ChildNumber, Address<Destination>), ControllerError<T>> {
-        self.wallet
-            .get_new_address(self.account_index)
-            .map_err(ControllerError::WalletError)
+        if allow_reuse {
+            self.wallet.get_reusable_address(self.account_index)
+        } else {
+            self.wallet.get_new_address(self.account_index)
+        }
+        .map_err(ControllerError::WalletError)
     }
 
     pub fn find_public_key(
@@ -502,6 +587,7 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
                     account_index,
                     outputs,
                     SelectedInputs::Utxos(vec![]),
+                    None,
                     BTreeMap::new(),
                     current_fee_rate,
                     consolidate_fee_rate,
@@ -521,7 +607,9 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
         amount: Amount,
         selected_utxos: Vec<UtxoOutPoint>,
     ) -> Result<SignedTransaction, ControllerError<T>> {
+        let (selected_utxos, selection_algo) = self.utxo_selection(selected_utxos)?;
         self.check_tokens_in_selected_utxo(&selected_utxos).await?;
+        let selected_inputs = self.selected_inputs(selected_utxos);
 
         let output = make_address_output(address, amount);
         self.create_and_send_tx(
@@ -532,7 +620,8 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
                 wallet.create_transaction_to_addresses(
                     account_index,
                     [output],
-                    SelectedInputs::Utxos(selected_utxos),
+                    selected_inputs,
+                    selection_algo,
                     BTreeMap::new(),
                     current_fee_rate,
                     consolidate_fee_rate,
@@ -542,6 +631,138 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
         .await
     }
 
+    /// Create a single transaction that transfers coins or tokens to all the destination addresses
+    /// with the specified amounts and broadcast it to the mempool.
+    /// The payments of the same currency to the same address are handled as specified by
+    /// `duplicate_outputs`; the addresses whose payments were merged are returned in the result.
+    pub async fn send_to_many_addresses(
+        &mut self,
+        outputs: Vec<(Address<Destination>, Currency, Amount)>,
+        duplicate_outputs: DuplicateOutputs,
+    ) -> Result<SendToManyResult, ControllerError<T>> {
+        let (payments, merged_addresses) =
+            collect_send_to_many_payments(outputs, duplicate_outputs)?;
+
+        let max_outputs = self.chain_config.max_tx_outputs();
+        ensure!(
+            payments.len() <= max_outputs,
+            ControllerError::TooManyOutputs(payments.len(), max_outputs)
+        );
+
+        // Check before sending, as the new transaction marks the addresses as used
+        let reused_addresses = self
+            .wallet
+            .find_used_own_addresses(payments.iter().map(|(address, _, _)| address));
+
+        let token_ids = payments
+            .iter()
+            .filter_map(|(_, currency, _)| match currency {
+                Currency::Coin => None,
+                Currency::Token(token_id) => Some(*token_id),
+            })
+            .collect();
+        for token_info in self.fetch_token_infos(token_ids).await? {
+            match token_info {
+                RPCTokenInfo::FungibleToken(token_info) => {
+                    self.check_fungible_token_is_usable(&token_info)?
+                }
+                RPCTokenInfo::NonFungibleToken(_) => {}
+            }
+        }
+
+        let (selected_utxos, selection_algo) = self.utxo_selection(vec![])?;
+        self.check_tokens_in_selected_utxo(&selected_utxos).await?;
+        let selected_inputs = self.selected_inputs(selected_utxos);
+
+        let outputs: Vec<_> = payments
+            .into_iter()
+            .map(|(address, currency, amount)| match currency {
+                Currency::Coin => make_address_output(address, amount),
+                Currency::Token(token_id) => make_address_output_token(address, amount, token_id),
+            })
+            .collect();
+        let recipient_count = outputs.len();
+        let tx = self
+            .create_and_send_tx(
+                move |current_fee_rate: FeeRate,
+                      consolidate_fee_rate: FeeRate,
+                      wallet: &mut DefaultWallet,
+                      account_index: U31| {
+                    wallet.create_transaction_to_addresses(
+                        account_index,
+                        outputs,
+                        selected_inputs,
+                        selection_algo,
+                        BTreeMap::new(),
+                        current_fee_rate,
+                        consolidate_fee_rate,
+                    )
+                },
+            )
+            .await?;
+
+        let (fees, fee_per_recipient) = self.send_to_many_fees(&tx, recipient_count).await?;
+
+        Ok(SendToManyResult {
+            tx,
+            fees,
+            fee_per_recipient,
+            merged_addresses,
+            reused_addresses,
+        })
+    }
+
+    /// Calculate the fees paid by a transaction of this account paying to many addresses,
+    /// along with the coin fee divided between the recipients
+    pub async fn send_to_many_fees(
+        &self,
+        tx: &SignedTransaction,
+        recipient_count: usize,
+    ) -> Result<(Balances, RpcAmountOut), ControllerError<T>> {
+        let mut input_utxos = Vec::with_capacity(tx.inputs().len());
+        for input in tx.inputs() {
+            match input {
+                TxInput::Utxo(outpoint) => {
+                    // The inputs are usually the outputs of this account, which are already
+                    // spent by the transaction
+                    let utxo = match self
+                        .wallet
+                        .find_account_txo(self.account_index, outpoint)
+                        .map_err(ControllerError::WalletError)?
+                    {
+                        Some(utxo) => utxo,
+                        None => self.fetch_utxo(outpoint).await?,
+                    };
+                    input_utxos.push(utxo);
+                }
+                TxInput::Account(_) | TxInput::AccountCommand(_, _) => {}
+            }
+        }
+
+        let best_block_height = self
+            .wallet
+            .get_best_block_for_account(self.account_index)
+            .map_err(ControllerError::WalletError)?
+            .1;
+        let fees = calculate_fees(
+            self.chain_config,
+            best_block_height,
+            &input_utxos,
+            tx.outputs(),
+        )?;
+        let coin_fee = fees.get(&Currency::Coin).copied().unwrap_or(Amount::ZERO);
+
+        let fee_per_recipient =
+            (coin_fee / recipient_count as u128).expect("non-zero recipient count");
+        let fee_per_recipient = RpcAmountOut::from_amount_no_padding(
+            fee_per_recipient,
+            self.chain_config.coin_decimals(),
+        );
+        let fees = into_balances(&self.rpc_client, self.chain_config, fees.into()).await?;
+
+        Ok((fees, fee_per_recipient))
+    }
+
     /// Create a transaction that transfers all the coins and tokens to the destination address
     /// and broadcast it to the mempool.
     pub async fn sweep_addresses(
@@ -848,6 +1069,32 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
         .await
     }
 
+    /// Create a transaction that creates a new delegation for the specified pool, owned by a new
+    /// address of the selected account, and stakes the specified amount to it in the same
+    /// transaction, and broadcasts it to the mempool.
+    /// Returns the new transaction and the newly created Delegation ID
+    pub async fn create_delegation_and_stake(
+        &mut self,
+        pool_id: PoolId,
+        amount: Amount,
+    ) -> Result<(SignedTransaction, DelegationId), ControllerError<T>> {
+        self.create_and_send_tx_with_id(
+            move |current_fee_rate: FeeRate,
+                  consolidate_fee_rate: FeeRate,
+                  wallet: &mut DefaultWallet,
+                  account_index: U31| {
+                wallet.create_delegation_and_stake(
+                    account_index,
+                    pool_id,
+                    amount,
+                    current_fee_rate,
+                    consolidate_fee_rate,
+                )
+            },
+        )
+        .await
+    }
+
     /// Create a transaction to stake to the specified delegation ID and broadcasts it to the
     /// mempool.
     pub async fn delegate_staking(
@@ -865,6 +1112,7 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
                     account_index,
                     [output],
                     SelectedInputs::Utxos(vec![]),
+                    None,
                     BTreeMap::new(),
                     current_fee_rate,
                     consolidate_fee_rate,
@@ -936,6 +1184,7 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
                     account_index,
                     [output],
                     SelectedInputs::Utxos(vec![]),
+                    None,
                     BTreeMap::new(),
                     current_fee_rate,
                     consolidate_fee_rate,
@@ -1006,6 +1255,52 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
         .await
     }
 
+    /// Creates a transaction that decommissions a stake pool, returning the staker's balance to the
+    /// pool's decommission destination. The transaction is not broadcast.
+    /// Also returns the decommission destination and the balance delegated to the pool.
+    pub async fn create_decommission_pool_transaction(
+        &mut self,
+        pool_id: PoolId,
+    ) -> Result<(SignedTransaction, Destination, Amount), ControllerError<T>> {
+        let staker_balance = self
+            .rpc_client
+            .get_staker_balance(pool_id)
+            .await
+            .map_err(ControllerError::NodeCallError)?
+            .ok_or(ControllerError::WalletError(WalletError::UnknownPoolId(
+                pool_id,
+            )))?;
+        let pool_balance = self
+            .rpc_client
+            .get_stake_pool_balance(pool_id)
+            .await
+            .map_err(ControllerError::NodeCallError)?
+            .ok_or(ControllerError::WalletError(WalletError::UnknownPoolId(
+                pool_id,
+            )))?;
+        let delegated_balance = (pool_balance - staker_balance).unwrap_or(Amount::ZERO);
+
+        let (current_fee_rate, _) = self.get_current_and_consolidation_fee_rate().await?;
+
+        let decommission_destination = self
+            .wallet
+            .get_pool_ids(self.account_index, WalletPoolsFilter::Decommission)?
+            .into_iter()
+            .find_map(|(id, data)| (id == pool_id).then_some(data.decommission_key))
+            .ok_or(ControllerError::WalletError(
+                WalletError::PoolDecommissionKeyNotOwned(pool_id),
+            ))?;
+
+        let tx = self.wallet.create_decommission_pool_transaction(
+            self.account_index,
+            pool_id,
+            staker_balance,
+            current_fee_rate,
+        )?;
+
+        Ok((tx, decommission_destination, delegated_balance))
+    }
+
     /// Creates a partially signed transaction that decommissions a stake pool.
     pub async fn decommission_stake_pool_request(
         &mut self,
@@ -1049,6 +1344,12 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
             current_fee_rate,
             consolidate_fee_rate,
         )?;
+        // The signed transaction is returned to be broadcast by the caller
+        self.wallet.check_and_record_outflow(
+            self.account_index,
+            &result,
+            BlockTimestamp::from_time(get_time()),
+        )?;
         Ok(result)
     }
 
@@ -1084,6 +1385,24 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
             .map_err(ControllerError::WalletError)
     }
 
+    /// Signs the inputs of a raw transaction that belong to this account with the private keys
+    /// in this wallet, leaving the rest of the inputs unsigned.
+    pub fn sign_raw_transaction_own_inputs(
+        &mut self,
+        tx: Transaction,
+    ) -> Result<
+        (
+            PartiallySignedTransaction,
+            Vec<SignatureStatus>,
+            Vec<SignatureStatus>,
+        ),
+        ControllerError<T>,
+    > {
+        self.wallet
+            .sign_raw_transaction_own_inputs(self.account_index, tx)
+            .map_err(ControllerError::WalletError)
+    }
+
     pub fn sign_challenge(
         &mut self,
         challenge: Vec<u8>,
@@ -1112,16 +1431,64 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
         Ok((current_fee_rate, consolidate_fee_rate))
     }
 
+    /// Warn if the transaction is likely to be rejected by the mempool because, together with
+    /// the unconfirmed transactions of the account, it would exceed the node's limits on chains
+    /// of unconfirmed transactions
+    async fn check_package_limits(&self, tx: &SignedTransaction) -> Result<(), ControllerError<T>> {
+        let limits = self
+            .rpc_client
+            .mempool_get_package_limits()
+            .await
+            .map_err(ControllerError::NodeCallError)?;
+
+        if let Some(err) = self
+            .wallet
+            .check_package_limits(self.account_index, tx, &limits)
+            .map_err(ControllerError::WalletError)?
+        {
+            log::warn!(
+                "Transaction {} is likely to be rejected by the mempool: {err}",
+                tx.transaction().get_id()
+            );
+        }
+
+        Ok(())
+    }
+
     /// Broadcast a singed transaction to the mempool and update the wallets state if the
     /// transaction has been added to the mempool
     pub async fn broadcast_to_mempool(
         &mut self,
         tx: SignedTransaction,
     ) -> Result<SignedTransaction, ControllerError<T>> {
+        self.check_package_limits(&tx).await?;
+
         self.wallet
-            .add_account_unconfirmed_tx(self.account_index, tx.clone(), self.wallet_events)
+            .check_and_record_outflow(
+                self.account_index,
+                &tx,
+                BlockTimestamp::from_time(get_time()),
+            )
             .map_err(ControllerError::WalletError)?;
 
+        // The request creates a single transaction, so the key is recorded only once
+        match self.config.idempotent_request.take() {
+            Some(request) => self.wallet.add_account_unconfirmed_tx_with_idempotency_key(
+                self.account_index,
+                tx.clone(),
+                &request.key,
+                request.request_hash,
+                BlockTimestamp::from_time(get_time()),
+                self.wallet_events,
+            ),
+            None => self.wallet.add_account_unconfirmed_tx(
+                self.account_index,
+                tx.clone(),
+                self.wallet_events,
+            ),
+        }
+        .map_err(ControllerError::WalletError)?;
+
         self.rpc_client
             .submit_transaction(tx.clone(), Default::default())
             .await
@@ -1241,3 +1608,54 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
         )))
     }
 }
+
+/// Collect the payments of a transaction paying to many addresses, handling the payments of the
+/// same currency to the same address as specified by `duplicate_outputs`.
+/// Returns the payments along with the addresses whose payments were merged.
+#[allow(clippy::type_complexity)]
+pub fn collect_send_to_many_payments<T: NodeInterface>(
+    outputs: Vec<(Address<Destination>, Currency, Amount)>,
+    duplicate_outputs: DuplicateOutputs,
+) -> Result<
+    (
+        Vec<(Address<Destination>, Currency, Amount)>,
+        Vec<Address<Destination>>,
+    ),
+    ControllerError<T>,
+> {
+    ensure!(
+        !outputs.is_empty(),
+        ControllerError::ExpectingNonEmptyOutputs
+    );
+
+    let mut payments: Vec<(Address<Destination>, Currency, Amount)> = Vec::new();
+    let mut payment_indexes = BTreeMap::new();
+    let mut merged_addresses = Vec::new();
+    for (address, currency, amount) in outputs {
+        match payment_indexes.entry((address.as_object().clone(), currency.clone())) {
+            std::collections::btree_map::Entry::Vacant(e) => {
+                e.insert(payments.len());
+                payments.push((address, currency, amount));
+            }
+            std::collections::btree_map::Entry::Occupied(e) => match duplicate_outputs {
+                DuplicateOutputs::Merge => {
+                    let (_, _, total) = &mut payments[*e.get()];
+                    *total = (*total + amount).ok_or(ControllerError::WalletError(
+                        WalletError::OutputAmountOverflow,
+                    ))?;
+                    if !merged_addresses.contains(&address) {
+                        merged_addresses.push(address);
+                    }
+                }
+                DuplicateOutputs::Allow => payments.push((address, currency, amount)),
+                DuplicateOutputs::Reject => {
+                    return Err(ControllerError::DuplicateOutputAddress(
+                        address.as_str().to_owned(),
+                    ))
+                }
+            },
+        }
+    }
+
+    Ok((payments, merged_addresses))
+}
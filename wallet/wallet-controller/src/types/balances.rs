@@ -44,3 +44,16 @@ impl Balances {
         (coins, tokens)
     }
 }
+
+/// Balances split by the number of confirmations of the transactions that created the outputs
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
+pub struct BalancesByConfirmations {
+    /// The number of confirmations after which the funds are considered confirmed
+    pub confirmation_threshold: u64,
+    /// The funds with at least the threshold number of confirmations
+    pub confirmed: Balances,
+    /// The funds that are in the chain but don't have enough confirmations yet
+    pub pending: Balances,
+    /// The funds of the transactions in the mempool
+    pub unconfirmed: Balances,
+}
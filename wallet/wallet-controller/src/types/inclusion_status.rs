@@ -0,0 +1,228 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    chain::{block::BlockMerkleTreeError, Block, Transaction},
+    primitives::{BlockHeight, Id, Idable, H256},
+};
+use rpc_description::HasValueHint;
+
+/// Whether a transaction has been included in the mainchain or is still waiting for it
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, HasValueHint)]
+#[serde(tag = "type", content = "content")]
+pub enum InclusionStatus {
+    Confirmed {
+        block_id: Id<Block>,
+        block_height: BlockHeight,
+        /// The number of blocks on top of the block plus one
+        confirmations: u64,
+        /// The position of the transaction in the block
+        tx_index: u32,
+        /// The hashes needed to calculate the merkle root of the block from the transaction id,
+        /// starting from the bottom of the tree. The block reward is the first leaf of the tree,
+        /// so the leaf index of the transaction is `tx_index + 1`.
+        merkle_proof: Vec<H256>,
+    },
+    InMempool,
+    InOrphanPool,
+    NotFound,
+}
+
+impl InclusionStatus {
+    /// Find the transaction in a mainchain block and prove its inclusion.
+    ///
+    /// Returns `None` if the block doesn't contain the transaction.
+    pub fn from_mainchain_block(
+        block: &Block,
+        block_height: BlockHeight,
+        best_block_height: BlockHeight,
+        tx_id: &Id<Transaction>,
+    ) -> Result<Option<Self>, BlockMerkleTreeError> {
+        let tx_index =
+            match block.transactions().iter().position(|tx| tx.transaction().get_id() == *tx_id) {
+                Some(tx_index) => tx_index as u32,
+                None => return Ok(None),
+            };
+
+        let merkle_proof = block
+            .body()
+            .merkle_tree_proxy()?
+            .merkle_tree()
+            .transaction_inclusion_proof(tx_index)?
+            .into_hashes();
+
+        let confirmations = (best_block_height - block_height)
+            .and_then(|distance| u64::try_from(distance.to_int()).ok())
+            .map_or(0, |distance| distance + 1);
+
+        Ok(Some(Self::Confirmed {
+            block_id: block.get_id(),
+            block_height,
+            confirmations,
+            tx_index,
+            merkle_proof,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{
+        chain::{
+            block::{timestamp::BlockTimestamp, BlockReward, ConsensusData},
+            GenBlock, SignedTransaction,
+        },
+        primitives::id::DefaultHashAlgoStream,
+    };
+    use crypto::hash::StreamHasher;
+    use randomness::Rng;
+    use rstest::rstest;
+    use test_utils::random::{make_seedable_rng, Seed};
+
+    use super::*;
+
+    fn make_tx(rng: &mut impl Rng) -> SignedTransaction {
+        let tx = Transaction::new(rng.gen(), vec![], vec![]).unwrap();
+        SignedTransaction::new(tx, vec![]).unwrap()
+    }
+
+    // Hash the leaf up to the root, the same way `MerkleHasher` does
+    fn merkle_root_from_proof(leaf: H256, mut leaf_index: u32, proof: &[H256]) -> H256 {
+        proof.iter().fold(leaf, |node, sibling| {
+            let (left, right) = if leaf_index % 2 == 0 {
+                (node, *sibling)
+            } else {
+                (*sibling, node)
+            };
+            leaf_index /= 2;
+
+            let mut hasher = DefaultHashAlgoStream::new();
+            hasher.write(&left);
+            hasher.write(&right);
+            hasher.finalize().into()
+        })
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn confirmed(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+
+        // Together with the block reward the tree has 4 leaves
+        let txs = (0..3).map(|_| make_tx(&mut rng)).collect::<Vec<_>>();
+        let block = Block::new(
+            txs.clone(),
+            Id::<GenBlock>::new(H256::random_using(&mut rng)),
+            BlockTimestamp::from_int_seconds(rng.gen()),
+            ConsensusData::None,
+            BlockReward::new(vec![]),
+        )
+        .unwrap();
+        let block_height = BlockHeight::new(rng.gen_range(1..1000));
+        let best_block_height = block_height.checked_add(5).unwrap();
+
+        for (index, tx) in txs.iter().enumerate() {
+            let tx_id = tx.transaction().get_id();
+            let status = InclusionStatus::from_mainchain_block(
+                &block,
+                block_height,
+                best_block_height,
+                &tx_id,
+            )
+            .unwrap()
+            .unwrap();
+
+            let InclusionStatus::Confirmed {
+                block_id,
+                block_height: status_block_height,
+                confirmations,
+                tx_index,
+                merkle_proof,
+            } = status
+            else {
+                panic!("unexpected status: {status:?}");
+            };
+            assert_eq!(block_id, block.get_id());
+            assert_eq!(status_block_height, block_height);
+            assert_eq!(confirmations, 6);
+            assert_eq!(tx_index, index as u32);
+            assert_eq!(merkle_proof.len(), 2);
+            assert_eq!(
+                merkle_root_from_proof(tx_id.to_hash(), tx_index + 1, &merkle_proof),
+                block.merkle_root()
+            );
+        }
+
+        // The tip itself has a single confirmation
+        let tx_id = txs[0].transaction().get_id();
+        let status =
+            InclusionStatus::from_mainchain_block(&block, block_height, block_height, &tx_id)
+                .unwrap()
+                .unwrap();
+        assert!(matches!(
+            status,
+            InclusionStatus::Confirmed {
+                confirmations: 1,
+                ..
+            }
+        ));
+
+        // A transaction from another block
+        let other_tx_id = make_tx(&mut rng).transaction().get_id();
+        assert_eq!(
+            InclusionStatus::from_mainchain_block(
+                &block,
+                block_height,
+                best_block_height,
+                &other_tx_id
+            )
+            .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let block_id = Id::<Block>::new(H256::from_low_u64_be(1));
+        let status = InclusionStatus::Confirmed {
+            block_id,
+            block_height: BlockHeight::new(10),
+            confirmations: 3,
+            tx_index: 0,
+            merkle_proof: vec![H256::from_low_u64_be(2)],
+        };
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["type"], "Confirmed");
+        assert_eq!(json["content"]["confirmations"], 3);
+        assert_eq!(
+            serde_json::from_value::<InclusionStatus>(json).unwrap(),
+            status
+        );
+
+        for (status, name) in [
+            (InclusionStatus::InMempool, "InMempool"),
+            (InclusionStatus::InOrphanPool, "InOrphanPool"),
+            (InclusionStatus::NotFound, "NotFound"),
+        ] {
+            let json = serde_json::to_value(&status).unwrap();
+            assert_eq!(json, serde_json::json!({ "type": name }));
+            assert_eq!(
+                serde_json::from_value::<InclusionStatus>(json).unwrap(),
+                status
+            );
+        }
+    }
+}
@@ -17,27 +17,36 @@
 
 mod balances;
 mod block_info;
+mod inclusion_status;
 mod seed_phrase;
+mod staking_status;
 mod standalone_key;
 mod transaction;
+mod watch_event;
 
-pub use balances::Balances;
+pub use balances::{Balances, BalancesByConfirmations};
 pub use block_info::{BlockInfo, CreatedBlockInfo};
 pub use common::primitives::amount::RpcAmountOut;
 use common::{
+    address::Address,
     chain::{
         output_value::OutputValue,
         tokens::{RPCTokenInfo, TokenId},
-        ChainConfig, Destination, TxOutput,
+        ChainConfig, Destination, SignedTransaction, TxOutput,
     },
     primitives::{DecimalAmount, H256},
 };
+pub use inclusion_status::InclusionStatus;
 pub use seed_phrase::SeedWithPassPhrase;
+pub use staking_status::{
+    calculate_pools_staking_status, PoolStakingStatus, STAKING_STATUS_PERIOD,
+};
 pub use standalone_key::AccountStandaloneKeyDetails;
 pub use transaction::{
     InspectTransaction, SignatureStats, TransactionToInspect, ValidatedSignatures,
 };
 use utils::ensure;
+pub use watch_event::{WatchEvent, WatchEventTracker};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
 pub struct WalletInfo {
@@ -45,6 +54,81 @@ pub struct WalletInfo {
     pub account_names: Vec<Option<String>>,
 }
 
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
+pub struct WalletFingerprintInfo {
+    /// Hex-encoded fingerprint of the wallet's root public key
+    pub fingerprint: String,
+    /// Whether a BIP39 passphrase was used to derive the root key; unknown for wallets
+    /// created by older versions
+    pub passphrase_used: Option<bool>,
+    /// The number of unused addresses the wallet looks ahead for when scanning the blockchain
+    pub lookahead_size: u32,
+    /// The highest index of a used change address over all the accounts.
+    /// If a restored wallet shows a lower value than the original one, the lookahead size
+    /// was too small to find all the change outputs.
+    pub last_used_change_index: Option<u32>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
+pub struct WalletVersionInfo {
+    /// The version of the wallet software
+    pub software_version: String,
+    /// The DB schema version of the open wallet file
+    pub wallet_schema_version: u32,
+    /// The oldest schema version this software can open, older wallet files can't be migrated
+    pub min_compatible_schema_version: u32,
+    /// The chain the wallet software is running on
+    pub chain_type: String,
+    /// The path of the open wallet file, if the wallet was opened from a file
+    pub open_wallet_path: Option<String>,
+}
+
+/// A transaction paying to one or more addresses
+#[derive(Debug, Clone)]
+pub struct SendResult {
+    pub tx: SignedTransaction,
+    /// The addresses of this wallet that have already received funds before
+    pub reused_addresses: Vec<Address<Destination>>,
+}
+
+/// How to handle the payments of the same currency to the same address in a transaction paying
+/// to many addresses at once
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    rpc_description::HasValueHint,
+)]
+pub enum DuplicateOutputs {
+    /// Merge such payments into a single output and report the address
+    #[default]
+    Merge,
+    /// Make a separate output for each payment
+    Allow,
+    /// Refuse to make the transaction
+    Reject,
+}
+
+/// A transaction paying to many addresses at once
+#[derive(Debug, Clone)]
+pub struct SendToManyResult {
+    pub tx: SignedTransaction,
+    /// The total fees paid by the transaction
+    pub fees: Balances,
+    /// The coin fee divided between the recipients
+    pub fee_per_recipient: RpcAmountOut,
+    /// The addresses that were paid more than once in the same currency, when such payments
+    /// are merged into a single output
+    pub merged_addresses: Vec<Address<Destination>>,
+    /// The addresses of this wallet that have already received funds before
+    pub reused_addresses: Vec<Address<Destination>>,
+}
+
 // A struct that represents sending a particular amount of unspecified currency.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct GenericCurrencyTransfer {
@@ -0,0 +1,201 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use common::{
+    chain::{block::ConsensusData, Block, PoolId},
+    primitives::Amount,
+};
+
+/// The period over which the block production statistics of the pools are collected.
+pub const STAKING_STATUS_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Block production statistics of a stake pool over the last `STAKING_STATUS_PERIOD`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolStakingStatus {
+    pub pool_id: PoolId,
+    pub balance: Amount,
+    pub blocks_produced: u64,
+    /// The number of blocks the pool was expected to produce given its share of the stake.
+    pub expected_blocks: u64,
+}
+
+/// Collect the block production statistics of the specified pools from the recent `blocks`.
+///
+/// A pool's share of the stake is its balance divided by the total balance of all the pools
+/// in `pool_balances`, which should contain the specified pools and the pools that produced
+/// the blocks.
+pub fn calculate_pools_staking_status(
+    pool_ids: impl IntoIterator<Item = PoolId>,
+    pool_balances: &BTreeMap<PoolId, Amount>,
+    blocks: &[Block],
+) -> Vec<PoolStakingStatus> {
+    let mut blocks_produced = BTreeMap::<PoolId, u64>::new();
+    for block in blocks {
+        match block.consensus_data() {
+            ConsensusData::PoS(pos_data) => {
+                *blocks_produced.entry(*pos_data.stake_pool_id()).or_default() += 1;
+            }
+            ConsensusData::None | ConsensusData::PoW(_) => {}
+        }
+    }
+
+    let total_balance = pool_balances.values().fold(0u128, |total, balance| {
+        total.saturating_add(balance.into_atoms())
+    });
+
+    pool_ids
+        .into_iter()
+        .map(|pool_id| {
+            let balance = pool_balances.get(&pool_id).copied().unwrap_or(Amount::ZERO);
+            let expected_blocks = (blocks.len() as u128)
+                .saturating_mul(balance.into_atoms())
+                .checked_div(total_balance)
+                .unwrap_or(0);
+
+            PoolStakingStatus {
+                pool_id,
+                balance,
+                blocks_produced: blocks_produced.get(&pool_id).copied().unwrap_or(0),
+                expected_blocks: expected_blocks.try_into().unwrap_or(u64::MAX),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{
+        chain::{
+            block::{consensus_data::PoSData, timestamp::BlockTimestamp, BlockReward},
+            GenBlock,
+        },
+        primitives::{Compact, Id, H256},
+    };
+    use crypto::vrf::{transcript::no_rng::VRFTranscript, VRFKeyKind, VRFPrivateKey};
+    use randomness::{CryptoRng, Rng, SliceRandom};
+    use rstest::rstest;
+    use test_utils::random::{make_seedable_rng, Seed};
+
+    use super::*;
+
+    fn make_block(rng: &mut (impl Rng + CryptoRng), pool_id: PoolId) -> Block {
+        let (sk, _) = VRFPrivateKey::new_from_rng(rng, VRFKeyKind::Schnorrkel);
+        let vrf_data = sk.produce_vrf_data(VRFTranscript::new(b"abc"));
+        Block::new(
+            vec![],
+            Id::<GenBlock>::new(H256::random_using(rng)),
+            BlockTimestamp::from_int_seconds(rng.gen()),
+            ConsensusData::PoS(Box::new(PoSData::new(
+                vec![],
+                vec![],
+                pool_id,
+                vrf_data,
+                Compact(1),
+            ))),
+            BlockReward::new(vec![]),
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn produced_and_expected_blocks(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let own_pool_1: PoolId = H256::random_using(&mut rng).into();
+        let own_pool_2: PoolId = H256::random_using(&mut rng).into();
+        let idle_pool: PoolId = H256::random_using(&mut rng).into();
+        let other_pool: PoolId = H256::random_using(&mut rng).into();
+
+        // The own pools hold 50% and 10% of the stake and the other pool holds the rest
+        let pool_balances = BTreeMap::from([
+            (own_pool_1, Amount::from_atoms(5000)),
+            (own_pool_2, Amount::from_atoms(1000)),
+            (idle_pool, Amount::ZERO),
+            (other_pool, Amount::from_atoms(4000)),
+        ]);
+
+        let mut blocks = std::iter::repeat(own_pool_1)
+            .take(45)
+            .chain(std::iter::repeat(own_pool_2).take(15))
+            .chain(std::iter::repeat(other_pool).take(40))
+            .map(|pool_id| make_block(&mut rng, pool_id))
+            .collect::<Vec<_>>();
+        blocks.shuffle(&mut rng);
+
+        let status = calculate_pools_staking_status(
+            [own_pool_1, own_pool_2, idle_pool],
+            &pool_balances,
+            &blocks,
+        );
+        assert_eq!(
+            status,
+            vec![
+                PoolStakingStatus {
+                    pool_id: own_pool_1,
+                    balance: Amount::from_atoms(5000),
+                    blocks_produced: 45,
+                    expected_blocks: 50,
+                },
+                PoolStakingStatus {
+                    pool_id: own_pool_2,
+                    balance: Amount::from_atoms(1000),
+                    blocks_produced: 15,
+                    expected_blocks: 10,
+                },
+                PoolStakingStatus {
+                    pool_id: idle_pool,
+                    balance: Amount::ZERO,
+                    blocks_produced: 0,
+                    expected_blocks: 0,
+                },
+            ]
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn no_blocks_or_stake(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let pool_id: PoolId = H256::random_using(&mut rng).into();
+
+        // No blocks were produced recently
+        let pool_balances = BTreeMap::from([(pool_id, Amount::from_atoms(1000))]);
+        assert_eq!(
+            calculate_pools_staking_status([pool_id], &pool_balances, &[]),
+            vec![PoolStakingStatus {
+                pool_id,
+                balance: Amount::from_atoms(1000),
+                blocks_produced: 0,
+                expected_blocks: 0,
+            }]
+        );
+
+        // The pool's balance is unknown
+        let blocks = [make_block(&mut rng, pool_id)];
+        assert_eq!(
+            calculate_pools_staking_status([pool_id], &BTreeMap::new(), &blocks),
+            vec![PoolStakingStatus {
+                pool_id,
+                balance: Amount::ZERO,
+                blocks_produced: 1,
+                expected_blocks: 0,
+            }]
+        );
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+
+use common::{
+    chain::{GenBlock, Transaction},
+    primitives::{BlockHeight, Id},
+};
+use crypto::key::hdkd::u31::U31;
+use wallet::account::transaction_list::TransactionInfo;
+use wallet_types::wallet_tx::TxState;
+
+/// An event shown to the user who is watching the wallet activity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchEvent {
+    /// The wallet has scanned a new chain tip
+    NewTip {
+        block_height: BlockHeight,
+        block_id: Id<GenBlock>,
+        /// The number of the wallet transactions confirmed since the previous tip
+        confirmed_txs: usize,
+    },
+    /// A wallet transaction has been added or its state has changed
+    Transaction {
+        account_index: U31,
+        info: TransactionInfo,
+    },
+    /// A block produced by one of the wallet's pools has been added
+    BlockStaked {
+        account_index: U31,
+        block_height: BlockHeight,
+        block_id: Id<GenBlock>,
+    },
+}
+
+/// Turns the notifications of the wallet sync into [WatchEvent]s.
+///
+/// The wallet reports every scanned batch of blocks once per account, so the tip is only
+/// reported when it changes.
+#[derive(Debug, Default)]
+pub struct WatchEventTracker {
+    last_tip: Option<Id<GenBlock>>,
+    confirmed_txs: BTreeSet<Id<Transaction>>,
+}
+
+impl WatchEventTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn transaction_updated(&mut self, account_index: U31, info: TransactionInfo) -> WatchEvent {
+        match info.state {
+            TxState::Confirmed(_, _, _) => {
+                self.confirmed_txs.insert(info.txid);
+            }
+            TxState::InMempool(_)
+            | TxState::Conflicted(_)
+            | TxState::Inactive(_)
+            | TxState::Abandoned => {
+                self.confirmed_txs.remove(&info.txid);
+            }
+        }
+
+        WatchEvent::Transaction {
+            account_index,
+            info,
+        }
+    }
+
+    /// Called when the wallet has scanned new blocks and its best block is `block_id`
+    pub fn new_block(
+        &mut self,
+        block_height: BlockHeight,
+        block_id: Id<GenBlock>,
+    ) -> Option<WatchEvent> {
+        if self.last_tip == Some(block_id) {
+            return None;
+        }
+        self.last_tip = Some(block_id);
+
+        let confirmed_txs = std::mem::take(&mut self.confirmed_txs).len();
+        Some(WatchEvent::NewTip {
+            block_height,
+            block_id,
+            confirmed_txs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{
+        chain::block::timestamp::BlockTimestamp,
+        primitives::{Amount, H256},
+    };
+    use randomness::Rng;
+    use rstest::rstest;
+    use test_utils::random::{make_seedable_rng, Seed};
+    use wallet::account::transaction_list::TxType;
+
+    use super::*;
+
+    fn tx_info(txid: Id<Transaction>, state: TxState) -> TransactionInfo {
+        TransactionInfo {
+            txid,
+            tx_type: TxType::Received {
+                amount: Amount::from_atoms(100),
+            },
+            timestamp: None,
+            state,
+        }
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn event_stream(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let account_index = U31::from_u32(rng.gen_range(0..10)).unwrap();
+        let tx_id_1: Id<Transaction> = Id::new(H256::random_using(&mut rng));
+        let tx_id_2: Id<Transaction> = Id::new(H256::random_using(&mut rng));
+        let tip_1: Id<GenBlock> = Id::new(H256::random_using(&mut rng));
+        let tip_2: Id<GenBlock> = Id::new(H256::random_using(&mut rng));
+        let timestamp = BlockTimestamp::from_int_seconds(rng.gen());
+        let confirmed = TxState::Confirmed(BlockHeight::new(1), timestamp, 0);
+
+        let mut tracker = WatchEventTracker::new();
+
+        // An unconfirmed transaction is reported but not counted
+        let info = tx_info(tx_id_1, TxState::InMempool(0));
+        assert_eq!(
+            tracker.transaction_updated(account_index, info.clone()),
+            WatchEvent::Transaction {
+                account_index,
+                info
+            }
+        );
+
+        // Both transactions are confirmed by the new tip, the first one is reported twice
+        tracker.transaction_updated(account_index, tx_info(tx_id_1, confirmed));
+        tracker.transaction_updated(account_index, tx_info(tx_id_2, confirmed));
+        tracker.transaction_updated(account_index, tx_info(tx_id_1, confirmed));
+        assert_eq!(
+            tracker.new_block(BlockHeight::new(1), tip_1),
+            Some(WatchEvent::NewTip {
+                block_height: BlockHeight::new(1),
+                block_id: tip_1,
+                confirmed_txs: 2,
+            })
+        );
+
+        // The same tip reported for another account is ignored
+        assert_eq!(tracker.new_block(BlockHeight::new(1), tip_1), None);
+
+        // A transaction that was confirmed and then became inactive again (e.g. after a reorg)
+        // is not counted
+        tracker.transaction_updated(account_index, tx_info(tx_id_2, confirmed));
+        tracker.transaction_updated(account_index, tx_info(tx_id_2, TxState::Inactive(0)));
+        assert_eq!(
+            tracker.new_block(BlockHeight::new(2), tip_2),
+            Some(WatchEvent::NewTip {
+                block_height: BlockHeight::new(2),
+                block_id: tip_2,
+                confirmed_txs: 0,
+            })
+        );
+    }
+}
@@ -16,7 +16,10 @@
 use std::{num::NonZeroUsize, time::Duration};
 
 use blockprod::{BlockProductionError, BlockProductionHandle, TimestampSearchData};
-use chainstate::{BlockSource, ChainInfo, ChainstateError, ChainstateHandle};
+use chainstate::{
+    rpc::BlockSubmissionError, BlockSource, ChainInfo, ChainstateError, ChainstateHandle,
+    ProofOfReservesReport, StakePoolInfo,
+};
 use common::{
     chain::{
         tokens::{RPCTokenInfo, TokenId},
@@ -28,6 +31,7 @@ use consensus::GenerateBlockInputData;
 use crypto::ephemeral_e2e::EndToEndPublicKey;
 use mempool::{
     tx_accumulator::PackingStrategy, tx_options::TxOptionsOverrides, FeeRate, MempoolHandle,
+    PackageLimits,
 };
 use p2p::{
     error::P2pError,
@@ -73,6 +77,30 @@ pub enum WalletHandlesClientError {
     AttemptedExit,
 }
 
+impl From<WalletHandlesClientError> for rpc::Error {
+    fn from(e: WalletHandlesClientError) -> Self {
+        match e {
+            WalletHandlesClientError::Chainstate(e) => e.into(),
+            WalletHandlesClientError::P2p(e) => e.into(),
+            WalletHandlesClientError::CallError(_) => {
+                rpc::categorized_error(rpc::SERVICE_UNAVAILABLE_CODE, &e, "CallError")
+            }
+            WalletHandlesClientError::Hex(_) => {
+                rpc::categorized_error(rpc::INVALID_INPUT_CODE, &e, "Hex")
+            }
+            WalletHandlesClientError::BlockProduction(_) => {
+                rpc::categorized_error(rpc::GENERIC_ERROR_CODE, &e, "BlockProduction")
+            }
+            WalletHandlesClientError::MempoolError(_) => {
+                rpc::categorized_error(rpc::GENERIC_ERROR_CODE, &e, "MempoolError")
+            }
+            WalletHandlesClientError::AttemptedExit => {
+                rpc::categorized_error(rpc::GENERIC_ERROR_CODE, &e, "AttemptedExit")
+            }
+        }
+    }
+}
+
 impl WalletHandlesClient {
     pub async fn new(
         chainstate: ChainstateHandle,
@@ -195,6 +223,22 @@ impl NodeInterface for WalletHandlesClient {
         Ok(result)
     }
 
+    async fn get_all_pools(&self) -> Result<Vec<StakePoolInfo>, Self::Error> {
+        let result = self.chainstate.call(move |this| this.get_all_pools()).await??;
+        Ok(result)
+    }
+
+    async fn verify_proof_of_reserves(
+        &self,
+        proof_hex: String,
+    ) -> Result<ProofOfReservesReport, Self::Error> {
+        let result = self
+            .chainstate
+            .call(move |this| this.verify_proof_of_reserves(proof_hex))
+            .await??;
+        Ok(result)
+    }
+
     async fn get_delegation_share(
         &self,
         pool_id: PoolId,
@@ -286,11 +330,18 @@ impl NodeInterface for WalletHandlesClient {
         Ok(block)
     }
 
-    async fn submit_block(&self, block: Block) -> Result<(), Self::Error> {
-        self.chainstate
-            .call_mut(move |this| this.process_block(block, BlockSource::Local))
-            .await??;
-        Ok(())
+    async fn submit_block(
+        &self,
+        block: Block,
+    ) -> Result<Result<(), BlockSubmissionError>, Self::Error> {
+        let result = self
+            .chainstate
+            .call_mut({
+                let block = block.clone();
+                move |this| this.process_block(block, BlockSource::Local)
+            })
+            .await?;
+        Ok(result.map(|_| ()).map_err(|err| BlockSubmissionError::new(&block, &err)))
     }
 
     async fn get_utxo(
@@ -340,9 +391,19 @@ impl NodeInterface for WalletHandlesClient {
         Ok(count)
     }
 
+    async fn get_peer_count(&self) -> Result<u32, Self::Error> {
+        let count = self.p2p_get_peer_count().await?;
+        Ok(count.try_into().unwrap_or(u32::MAX))
+    }
+
+    async fn is_node_synced(&self) -> Result<bool, Self::Error> {
+        let info = self.chainstate_info().await?;
+        Ok(!info.is_initial_block_download)
+    }
+
     async fn p2p_list_banned(&self) -> Result<Vec<(BannableAddress, Time)>, Self::Error> {
         let list = self.p2p.call_async(move |this| this.list_banned()).await??;
-        Ok(list)
+        Ok(list.into_iter().map(|banned| (banned.address, banned.banned_until)).collect())
     }
     async fn p2p_ban(
         &self,
@@ -395,4 +456,22 @@ impl NodeInterface for WalletHandlesClient {
         let res = self.mempool.call(move |this| this.get_fee_rate_points(NUM_POINTS)).await??;
         Ok(res)
     }
+
+    async fn mempool_get_package_limits(&self) -> Result<PackageLimits, Self::Error> {
+        let res = self.mempool.call(|this| this.get_package_limits()).await?;
+        Ok(res)
+    }
+
+    async fn mempool_contains_tx(&self, tx_id: Id<Transaction>) -> Result<bool, Self::Error> {
+        let res = self.mempool.call(move |this| this.contains_transaction(&tx_id)).await?;
+        Ok(res)
+    }
+
+    async fn mempool_contains_orphan_tx(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> Result<bool, Self::Error> {
+        let res = self.mempool.call(move |this| this.contains_orphan_transaction(&tx_id)).await?;
+        Ok(res)
+    }
 }
@@ -15,7 +15,7 @@
 
 use std::{num::NonZeroUsize, time::Duration};
 
-use chainstate::ChainInfo;
+use chainstate::{rpc::BlockSubmissionError, ChainInfo, ProofOfReservesReport, StakePoolInfo};
 use common::{
     chain::{
         tokens::{RPCTokenInfo, TokenId},
@@ -27,7 +27,9 @@ use common::{
 
 use consensus::GenerateBlockInputData;
 use crypto::ephemeral_e2e::EndToEndPublicKey;
-use mempool::{tx_accumulator::PackingStrategy, tx_options::TxOptionsOverrides, FeeRate};
+use mempool::{
+    tx_accumulator::PackingStrategy, tx_options::TxOptionsOverrides, FeeRate, PackageLimits,
+};
 use p2p::types::{bannable_address::BannableAddress, socket_address::SocketAddress};
 pub use p2p::{interface::types::ConnectedPeer, types::peer_id::PeerId};
 use utils_networking::IpOrSocketAddress;
@@ -35,10 +37,18 @@ use wallet_types::wallet_type::WalletType;
 
 #[async_trait::async_trait]
 pub trait NodeInterface {
-    type Error: std::error::Error + Send + Sync + 'static;
+    /// The errors are converted to RPC errors with a code and a type telling the category
+    /// of the failure when they are returned by the wallet RPC
+    type Error: std::error::Error + Into<rpc::Error> + Send + Sync + 'static;
 
     fn is_cold_wallet_node(&self) -> WalletType;
 
+    /// Whether the error means that the node couldn't be reached (e.g. it's restarting),
+    /// so the request may succeed later without any action from the caller
+    fn is_node_unavailable(&self, _error: &Self::Error) -> bool {
+        false
+    }
+
     async fn chainstate_info(&self) -> Result<ChainInfo, Self::Error>;
     async fn get_best_block_id(&self) -> Result<Id<GenBlock>, Self::Error>;
     async fn get_block(&self, block_id: Id<Block>) -> Result<Option<Block>, Self::Error>;
@@ -65,6 +75,13 @@ pub trait NodeInterface {
     ) -> Result<Option<(Id<GenBlock>, BlockHeight)>, Self::Error>;
     async fn get_stake_pool_balance(&self, pool_id: PoolId) -> Result<Option<Amount>, Self::Error>;
     async fn get_staker_balance(&self, pool_id: PoolId) -> Result<Option<Amount>, Self::Error>;
+    /// All the stake pools with their current data and the number of blocks they produced recently
+    async fn get_all_pools(&self) -> Result<Vec<StakePoolInfo>, Self::Error>;
+    /// Check a hex-encoded proof of reserves against the node's mainchain
+    async fn verify_proof_of_reserves(
+        &self,
+        proof_hex: String,
+    ) -> Result<ProofOfReservesReport, Self::Error>;
     async fn get_delegation_share(
         &self,
         pool_id: PoolId,
@@ -95,7 +112,11 @@ pub trait NodeInterface {
         seconds_to_check_for_height: u64,
         check_all_timestamps_between_blocks: bool,
     ) -> Result<blockprod::TimestampSearchData, Self::Error>;
-    async fn submit_block(&self, block: Block) -> Result<(), Self::Error>;
+    /// The inner error is returned if the block was rejected by the node.
+    async fn submit_block(
+        &self,
+        block: Block,
+    ) -> Result<Result<(), BlockSubmissionError>, Self::Error>;
     async fn submit_transaction(
         &self,
         tx: SignedTransaction,
@@ -117,6 +138,10 @@ pub trait NodeInterface {
     async fn p2p_unban(&self, address: BannableAddress) -> Result<(), Self::Error>;
     async fn p2p_list_discouraged(&self) -> Result<Vec<(BannableAddress, Time)>, Self::Error>;
     async fn p2p_get_peer_count(&self) -> Result<usize, Self::Error>;
+    /// The number of peers the node is connected to, 0 if there is no node
+    async fn get_peer_count(&self) -> Result<u32, Self::Error>;
+    /// Whether the node has finished the initial block download
+    async fn is_node_synced(&self) -> Result<bool, Self::Error>;
     async fn p2p_get_connected_peers(&self) -> Result<Vec<ConnectedPeer>, Self::Error>;
     async fn p2p_get_reserved_nodes(&self) -> Result<Vec<SocketAddress>, Self::Error>;
     async fn p2p_add_reserved_node(&self, address: IpOrSocketAddress) -> Result<(), Self::Error>;
@@ -125,6 +150,10 @@ pub trait NodeInterface {
 
     async fn mempool_get_fee_rate(&self, in_top_x_mb: usize) -> Result<FeeRate, Self::Error>;
     async fn mempool_get_fee_rate_points(&self) -> Result<Vec<(usize, FeeRate)>, Self::Error>;
+    async fn mempool_get_package_limits(&self) -> Result<PackageLimits, Self::Error>;
+    async fn mempool_contains_tx(&self, tx_id: Id<Transaction>) -> Result<bool, Self::Error>;
+    async fn mempool_contains_orphan_tx(&self, tx_id: Id<Transaction>)
+        -> Result<bool, Self::Error>;
 
     async fn get_utxo(&self, outpoint: UtxoOutPoint) -> Result<Option<TxOutput>, Self::Error>;
 }
@@ -16,7 +16,10 @@
 use std::{num::NonZeroUsize, time::Duration};
 
 use blockprod::{rpc::BlockProductionRpcClient, TimestampSearchData};
-use chainstate::{rpc::ChainstateRpcClient, ChainInfo};
+use chainstate::{
+    rpc::{BlockSubmissionError, ChainstateRpcClient},
+    ChainInfo, ProofOfReservesReport, StakePoolInfo,
+};
 use common::{
     address::Address,
     chain::{
@@ -29,7 +32,8 @@ use common::{
 use consensus::GenerateBlockInputData;
 use crypto::ephemeral_e2e::EndToEndPublicKey;
 use mempool::{
-    rpc::MempoolRpcClient, tx_accumulator::PackingStrategy, tx_options::TxOptionsOverrides, FeeRate,
+    rpc::MempoolRpcClient, tx_accumulator::PackingStrategy, tx_options::TxOptionsOverrides,
+    FeeRate, PackageLimits,
 };
 use p2p::{
     interface::types::ConnectedPeer,
@@ -52,16 +56,17 @@ impl NodeInterface for NodeRpcClient {
         WalletType::Hot
     }
 
+    fn is_node_unavailable(&self, error: &Self::Error) -> bool {
+        error.is_node_unavailable()
+    }
+
     async fn chainstate_info(&self) -> Result<ChainInfo, Self::Error> {
-        ChainstateRpcClient::info(&self.http_client)
-            .await
-            .map_err(NodeRpcError::ResponseError)
+        self.read_request(|| ChainstateRpcClient::info(&self.http_client)).await
     }
 
     async fn get_block(&self, block_id: Id<Block>) -> Result<Option<Block>, Self::Error> {
-        ChainstateRpcClient::get_block(&self.http_client, block_id)
+        self.read_request(|| ChainstateRpcClient::get_block(&self.http_client, block_id))
             .await
-            .map_err(NodeRpcError::ResponseError)
             .map(|block_opt| block_opt.map(HexEncoded::take))
     }
 
@@ -70,10 +75,11 @@ impl NodeInterface for NodeRpcClient {
         from: BlockHeight,
         max_count: usize,
     ) -> Result<Vec<Block>, Self::Error> {
-        ChainstateRpcClient::get_mainchain_blocks(&self.http_client, from, max_count)
-            .await
-            .map_err(NodeRpcError::ResponseError)
-            .map(|blocks| blocks.into_iter().map(HexEncoded::take).collect())
+        self.read_request(|| {
+            ChainstateRpcClient::get_mainchain_blocks(&self.http_client, from, max_count)
+        })
+        .await
+        .map(|blocks| blocks.into_iter().map(HexEncoded::take).collect())
     }
 
     async fn get_block_ids_as_checkpoints(
@@ -82,35 +88,33 @@ impl NodeInterface for NodeRpcClient {
         end_height: BlockHeight,
         step: NonZeroUsize,
     ) -> Result<Vec<(BlockHeight, Id<GenBlock>)>, Self::Error> {
-        ChainstateRpcClient::get_block_ids_as_checkpoints(
-            &self.http_client,
-            start_height,
-            end_height,
-            step,
-        )
+        self.read_request(|| {
+            ChainstateRpcClient::get_block_ids_as_checkpoints(
+                &self.http_client,
+                start_height,
+                end_height,
+                step,
+            )
+        })
         .await
-        .map_err(NodeRpcError::ResponseError)
     }
 
     async fn get_best_block_id(&self) -> Result<Id<GenBlock>, Self::Error> {
-        ChainstateRpcClient::best_block_id(&self.http_client)
+        self.read_request(|| ChainstateRpcClient::best_block_id(&self.http_client))
             .await
-            .map_err(NodeRpcError::ResponseError)
     }
 
     async fn get_best_block_height(&self) -> Result<common::primitives::BlockHeight, Self::Error> {
-        ChainstateRpcClient::best_block_height(&self.http_client)
+        self.read_request(|| ChainstateRpcClient::best_block_height(&self.http_client))
             .await
-            .map_err(NodeRpcError::ResponseError)
     }
 
     async fn get_block_id_at_height(
         &self,
         height: BlockHeight,
     ) -> Result<Option<Id<GenBlock>>, Self::Error> {
-        ChainstateRpcClient::block_id_at_height(&self.http_client, height)
+        self.read_request(|| ChainstateRpcClient::block_id_at_height(&self.http_client, height))
             .await
-            .map_err(NodeRpcError::ResponseError)
     }
 
     async fn get_last_common_ancestor(
@@ -118,27 +122,50 @@ impl NodeInterface for NodeRpcClient {
         first_block: Id<GenBlock>,
         second_block: Id<GenBlock>,
     ) -> Result<Option<(Id<GenBlock>, BlockHeight)>, Self::Error> {
-        ChainstateRpcClient::last_common_ancestor_by_id(
-            &self.http_client,
-            first_block,
-            second_block,
-        )
+        self.read_request(|| {
+            ChainstateRpcClient::last_common_ancestor_by_id(
+                &self.http_client,
+                first_block,
+                second_block,
+            )
+        })
         .await
-        .map_err(NodeRpcError::ResponseError)
     }
 
     async fn get_stake_pool_balance(&self, pool_id: PoolId) -> Result<Option<Amount>, Self::Error> {
         let pool_address = Address::new(&self.chain_config, pool_id)?;
-        ChainstateRpcClient::stake_pool_balance(&self.http_client, pool_address.into_string())
-            .await
-            .map_err(NodeRpcError::ResponseError)
+        let pool_address = pool_address.into_string();
+        self.read_request(|| {
+            ChainstateRpcClient::stake_pool_balance(&self.http_client, pool_address.clone())
+        })
+        .await
     }
 
     async fn get_staker_balance(&self, pool_id: PoolId) -> Result<Option<Amount>, Self::Error> {
         let pool_address = Address::new(&self.chain_config, pool_id)?;
-        ChainstateRpcClient::staker_balance(&self.http_client, pool_address.into_string())
-            .await
-            .map_err(NodeRpcError::ResponseError)
+        let pool_address = pool_address.into_string();
+        self.read_request(|| {
+            ChainstateRpcClient::staker_balance(&self.http_client, pool_address.clone())
+        })
+        .await
+    }
+
+    async fn get_all_pools(&self) -> Result<Vec<StakePoolInfo>, Self::Error> {
+        self.read_request(|| ChainstateRpcClient::all_pools(&self.http_client))
+            .await?
+            .into_iter()
+            .map(|info| info.into_stake_pool_info(&self.chain_config).map_err(NodeRpcError::from))
+            .collect()
+    }
+
+    async fn verify_proof_of_reserves(
+        &self,
+        proof_hex: String,
+    ) -> Result<ProofOfReservesReport, Self::Error> {
+        self.read_request(|| {
+            ChainstateRpcClient::verify_proof_of_reserves(&self.http_client, proof_hex.clone())
+        })
+        .await
     }
 
     async fn get_delegation_share(
@@ -148,23 +175,26 @@ impl NodeInterface for NodeRpcClient {
     ) -> Result<Option<Amount>, Self::Error> {
         let pool_address = Address::new(&self.chain_config, pool_id)?.into_string();
         let delegation_address = Address::new(&self.chain_config, delegation_id)?.into_string();
-        ChainstateRpcClient::delegation_share(&self.http_client, pool_address, delegation_address)
-            .await
-            .map_err(NodeRpcError::ResponseError)
+        self.read_request(|| {
+            ChainstateRpcClient::delegation_share(
+                &self.http_client,
+                pool_address.clone(),
+                delegation_address.clone(),
+            )
+        })
+        .await
     }
 
     async fn get_token_info(&self, token_id: TokenId) -> Result<Option<RPCTokenInfo>, Self::Error> {
         let token_id = Address::new(&self.chain_config, token_id)?.into_string();
-        ChainstateRpcClient::token_info(&self.http_client, token_id)
+        self.read_request(|| ChainstateRpcClient::token_info(&self.http_client, token_id.clone()))
             .await
-            .map_err(NodeRpcError::ResponseError)
     }
 
     async fn blockprod_e2e_public_key(&self) -> Result<EndToEndPublicKey, Self::Error> {
-        BlockProductionRpcClient::e2e_public_key(&self.http_client)
+        self.read_request(|| BlockProductionRpcClient::e2e_public_key(&self.http_client))
             .await
             .map(HexEncoded::take)
-            .map_err(NodeRpcError::ResponseError)
     }
 
     async fn generate_block_e2e(
@@ -186,7 +216,7 @@ impl NodeInterface for NodeRpcClient {
         )
         .await
         .map(HexEncoded::take)
-        .map_err(NodeRpcError::ResponseError)
+        .map_err(NodeRpcError::from_response)
     }
 
     async fn collect_timestamp_search_data(
@@ -197,17 +227,18 @@ impl NodeInterface for NodeRpcClient {
         seconds_to_check_for_height: u64,
         all_timestamps_between_blocks: bool,
     ) -> Result<TimestampSearchData, Self::Error> {
-        BlockProductionRpcClient::collect_timestamp_search_data(
-            &self.http_client,
-            pool_id,
-            min_height,
-            max_height,
-            seconds_to_check_for_height,
-            all_timestamps_between_blocks,
-        )
+        self.read_request(|| {
+            BlockProductionRpcClient::collect_timestamp_search_data(
+                &self.http_client,
+                pool_id,
+                min_height,
+                max_height,
+                seconds_to_check_for_height,
+                all_timestamps_between_blocks,
+            )
+        })
         .await
         .map(HexEncoded::take)
-        .map_err(NodeRpcError::ResponseError)
     }
 
     async fn generate_block(
@@ -227,13 +258,20 @@ impl NodeInterface for NodeRpcClient {
         )
         .await
         .map(HexEncoded::take)
-        .map_err(NodeRpcError::ResponseError)
+        .map_err(NodeRpcError::from_response)
     }
 
-    async fn submit_block(&self, block: Block) -> Result<(), Self::Error> {
-        ChainstateRpcClient::submit_block(&self.http_client, block.into())
-            .await
-            .map_err(NodeRpcError::ResponseError)
+    async fn submit_block(
+        &self,
+        block: Block,
+    ) -> Result<Result<(), BlockSubmissionError>, Self::Error> {
+        match ChainstateRpcClient::submit_block(&self.http_client, block.into()).await {
+            Ok(()) => Ok(Ok(())),
+            Err(err) => match rpc::error_data(&err) {
+                Some(rejection) => Ok(Err(rejection)),
+                None => Err(NodeRpcError::from_response(err)),
+            },
+        }
     }
 
     async fn submit_transaction(
@@ -243,41 +281,42 @@ impl NodeInterface for NodeRpcClient {
     ) -> Result<(), Self::Error> {
         let status = P2pRpcClient::submit_transaction(&self.http_client, tx.into(), options)
             .await
-            .map_err(NodeRpcError::ResponseError)?;
+            .map_err(NodeRpcError::from_response)?;
         Ok(status)
     }
 
     async fn node_shutdown(&self) -> Result<(), Self::Error> {
         node_lib::rpc::NodeRpcClient::shutdown(&self.http_client)
             .await
-            .map_err(NodeRpcError::ResponseError)
+            .map_err(NodeRpcError::from_response)
     }
     async fn node_enable_networking(&self, enable: bool) -> Result<(), Self::Error> {
         P2pRpcClient::enable_networking(&self.http_client, enable)
             .await
-            .map_err(NodeRpcError::ResponseError)
+            .map_err(NodeRpcError::from_response)
     }
     async fn node_version(&self) -> Result<String, Self::Error> {
-        node_lib::rpc::NodeRpcClient::version(&self.http_client)
+        self.read_request(|| node_lib::rpc::NodeRpcClient::version(&self.http_client))
             .await
-            .map_err(NodeRpcError::ResponseError)
     }
 
     async fn p2p_connect(&self, address: IpOrSocketAddress) -> Result<(), Self::Error> {
         P2pRpcClient::connect(&self.http_client, address)
             .await
-            .map_err(NodeRpcError::ResponseError)
+            .map_err(NodeRpcError::from_response)
     }
     async fn p2p_disconnect(&self, peer_id: PeerId) -> Result<(), Self::Error> {
         P2pRpcClient::disconnect(&self.http_client, peer_id)
             .await
-            .map_err(NodeRpcError::ResponseError)
+            .map_err(NodeRpcError::from_response)
     }
 
     async fn p2p_list_banned(&self) -> Result<Vec<(BannableAddress, Time)>, Self::Error> {
-        P2pRpcClient::list_banned(&self.http_client)
+        self.read_request(|| P2pRpcClient::list_banned(&self.http_client))
             .await
-            .map_err(NodeRpcError::ResponseError)
+            .map(|list| {
+                list.into_iter().map(|banned| (banned.address, banned.banned_until)).collect()
+            })
     }
     async fn p2p_ban(
         &self,
@@ -286,40 +325,40 @@ impl NodeInterface for NodeRpcClient {
     ) -> Result<(), Self::Error> {
         P2pRpcClient::ban(&self.http_client, address, duration)
             .await
-            .map_err(NodeRpcError::ResponseError)
+            .map_err(NodeRpcError::from_response)
     }
     async fn p2p_unban(&self, address: BannableAddress) -> Result<(), Self::Error> {
         P2pRpcClient::unban(&self.http_client, address)
             .await
-            .map_err(NodeRpcError::ResponseError)
+            .map_err(NodeRpcError::from_response)
     }
 
     async fn p2p_list_discouraged(&self) -> Result<Vec<(BannableAddress, Time)>, Self::Error> {
-        P2pRpcClient::list_discouraged(&self.http_client)
-            .await
-            .map_err(NodeRpcError::ResponseError)
+        self.read_request(|| P2pRpcClient::list_discouraged(&self.http_client)).await
     }
 
     async fn p2p_get_peer_count(&self) -> Result<usize, Self::Error> {
-        P2pRpcClient::get_peer_count(&self.http_client)
-            .await
-            .map_err(NodeRpcError::ResponseError)
+        self.read_request(|| P2pRpcClient::get_peer_count(&self.http_client)).await
+    }
+    async fn get_peer_count(&self) -> Result<u32, Self::Error> {
+        let count = self.p2p_get_peer_count().await?;
+        Ok(count.try_into().unwrap_or(u32::MAX))
+    }
+    async fn is_node_synced(&self) -> Result<bool, Self::Error> {
+        let info = self.chainstate_info().await?;
+        Ok(!info.is_initial_block_download)
     }
     async fn p2p_get_connected_peers(&self) -> Result<Vec<ConnectedPeer>, Self::Error> {
-        P2pRpcClient::get_connected_peers(&self.http_client)
-            .await
-            .map_err(NodeRpcError::ResponseError)
+        self.read_request(|| P2pRpcClient::get_connected_peers(&self.http_client)).await
     }
 
     async fn p2p_get_reserved_nodes(&self) -> Result<Vec<SocketAddress>, Self::Error> {
-        P2pRpcClient::get_reserved_nodes(&self.http_client)
-            .await
-            .map_err(NodeRpcError::ResponseError)
+        self.read_request(|| P2pRpcClient::get_reserved_nodes(&self.http_client)).await
     }
     async fn p2p_add_reserved_node(&self, address: IpOrSocketAddress) -> Result<(), Self::Error> {
         P2pRpcClient::add_reserved_node(&self.http_client, address)
             .await
-            .map_err(NodeRpcError::ResponseError)
+            .map_err(NodeRpcError::from_response)
     }
     async fn p2p_remove_reserved_node(
         &self,
@@ -327,24 +366,41 @@ impl NodeInterface for NodeRpcClient {
     ) -> Result<(), Self::Error> {
         P2pRpcClient::remove_reserved_node(&self.http_client, address)
             .await
-            .map_err(NodeRpcError::ResponseError)
+            .map_err(NodeRpcError::from_response)
     }
 
     async fn mempool_get_fee_rate(&self, in_top_x_mb: usize) -> Result<FeeRate, Self::Error> {
-        MempoolRpcClient::get_fee_rate(&self.http_client, in_top_x_mb)
+        self.read_request(|| MempoolRpcClient::get_fee_rate(&self.http_client, in_top_x_mb))
             .await
-            .map_err(NodeRpcError::ResponseError)
     }
 
     async fn mempool_get_fee_rate_points(&self) -> Result<Vec<(usize, FeeRate)>, Self::Error> {
-        MempoolRpcClient::get_fee_rate_points(&self.http_client)
+        self.read_request(|| MempoolRpcClient::get_fee_rate_points(&self.http_client))
             .await
-            .map_err(NodeRpcError::ResponseError)
     }
 
-    async fn get_utxo(&self, outpoint: UtxoOutPoint) -> Result<Option<TxOutput>, Self::Error> {
-        ChainstateRpcClient::get_utxo(&self.http_client, outpoint.into())
+    async fn mempool_get_package_limits(&self) -> Result<PackageLimits, Self::Error> {
+        self.read_request(|| MempoolRpcClient::get_package_limits(&self.http_client))
             .await
-            .map_err(NodeRpcError::ResponseError)
+    }
+
+    async fn mempool_contains_tx(&self, tx_id: Id<Transaction>) -> Result<bool, Self::Error> {
+        self.read_request(|| MempoolRpcClient::contains_tx(&self.http_client, tx_id))
+            .await
+    }
+
+    async fn mempool_contains_orphan_tx(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> Result<bool, Self::Error> {
+        self.read_request(|| MempoolRpcClient::contains_orphan_tx(&self.http_client, tx_id))
+            .await
+    }
+
+    async fn get_utxo(&self, outpoint: UtxoOutPoint) -> Result<Option<TxOutput>, Self::Error> {
+        self.read_request(|| {
+            ChainstateRpcClient::get_utxo(&self.http_client, outpoint.clone().into())
+        })
+        .await
     }
 }
@@ -16,7 +16,7 @@
 use std::{num::NonZeroUsize, time::Duration};
 
 use blockprod::TimestampSearchData;
-use chainstate::ChainInfo;
+use chainstate::{rpc::BlockSubmissionError, ChainInfo, ProofOfReservesReport, StakePoolInfo};
 use common::{
     chain::{
         tokens::{RPCTokenInfo, TokenId},
@@ -26,7 +26,9 @@ use common::{
 };
 use consensus::GenerateBlockInputData;
 use crypto::ephemeral_e2e::EndToEndPublicKey;
-use mempool::{tx_accumulator::PackingStrategy, tx_options::TxOptionsOverrides, FeeRate};
+use mempool::{
+    tx_accumulator::PackingStrategy, tx_options::TxOptionsOverrides, FeeRate, PackageLimits,
+};
 use p2p::{
     interface::types::ConnectedPeer,
     types::{bannable_address::BannableAddress, socket_address::SocketAddress, PeerId},
@@ -44,6 +46,16 @@ pub enum ColdWalletRpcError {
     NotAvailable,
 }
 
+impl From<ColdWalletRpcError> for rpc::Error {
+    fn from(e: ColdWalletRpcError) -> Self {
+        match e {
+            ColdWalletRpcError::NotAvailable => {
+                rpc::categorized_error(rpc::SERVICE_UNAVAILABLE_CODE, &e, "NotAvailable")
+            }
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl NodeInterface for ColdWalletClient {
     type Error = ColdWalletRpcError;
@@ -60,6 +72,7 @@ impl NodeInterface for ColdWalletClient {
             best_block_timestamp: genesis.timestamp(),
             median_time: genesis.timestamp(),
             is_initial_block_download: false,
+            update_recommended: false,
         })
     }
 
@@ -118,6 +131,17 @@ impl NodeInterface for ColdWalletClient {
         Err(ColdWalletRpcError::NotAvailable)
     }
 
+    async fn get_all_pools(&self) -> Result<Vec<StakePoolInfo>, Self::Error> {
+        Err(ColdWalletRpcError::NotAvailable)
+    }
+
+    async fn verify_proof_of_reserves(
+        &self,
+        _proof_hex: String,
+    ) -> Result<ProofOfReservesReport, Self::Error> {
+        Err(ColdWalletRpcError::NotAvailable)
+    }
+
     async fn get_delegation_share(
         &self,
         _pool_id: PoolId,
@@ -169,7 +193,10 @@ impl NodeInterface for ColdWalletClient {
         Err(ColdWalletRpcError::NotAvailable)
     }
 
-    async fn submit_block(&self, _block: Block) -> Result<(), Self::Error> {
+    async fn submit_block(
+        &self,
+        _block: Block,
+    ) -> Result<Result<(), BlockSubmissionError>, Self::Error> {
         Err(ColdWalletRpcError::NotAvailable)
     }
 
@@ -225,6 +252,14 @@ impl NodeInterface for ColdWalletClient {
         Err(ColdWalletRpcError::NotAvailable)
     }
 
+    async fn get_peer_count(&self) -> Result<u32, Self::Error> {
+        Ok(0)
+    }
+
+    async fn is_node_synced(&self) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
     async fn p2p_get_connected_peers(&self) -> Result<Vec<ConnectedPeer>, Self::Error> {
         Err(ColdWalletRpcError::NotAvailable)
     }
@@ -252,6 +287,21 @@ impl NodeInterface for ColdWalletClient {
         Err(ColdWalletRpcError::NotAvailable)
     }
 
+    async fn mempool_get_package_limits(&self) -> Result<PackageLimits, Self::Error> {
+        Err(ColdWalletRpcError::NotAvailable)
+    }
+
+    async fn mempool_contains_tx(&self, _tx_id: Id<Transaction>) -> Result<bool, Self::Error> {
+        Err(ColdWalletRpcError::NotAvailable)
+    }
+
+    async fn mempool_contains_orphan_tx(
+        &self,
+        _tx_id: Id<Transaction>,
+    ) -> Result<bool, Self::Error> {
+        Err(ColdWalletRpcError::NotAvailable)
+    }
+
     async fn get_utxo(
         &self,
         _outpoint: common::chain::UtxoOutPoint,
@@ -16,16 +16,24 @@
 pub mod client_impl;
 pub mod cold_wallet_client;
 
-use std::sync::Arc;
+use std::{future::Future, sync::Arc, time::Duration};
 
+use chainstate::rpc::ChainstateRpcClient;
 use common::address::AddressError;
 use common::chain::ChainConfig;
+use logging::log;
 use rpc::new_http_client;
 use rpc::ClientError;
 use rpc::RpcAuthData;
 use rpc::RpcHttpClient;
 
-use crate::node_traits::NodeInterface;
+/// The delay before the first replay of a read request that failed because the node
+/// couldn't be reached
+const RECONNECT_INITIAL_DELAY: Duration = Duration::from_millis(100);
+/// The delay between replays doubles after every attempt, up to this limit
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(5);
+/// The number of replays after which the node is reported as unavailable
+const RECONNECT_MAX_ATTEMPTS: u32 = 10;
 
 #[derive(thiserror::Error, Debug)]
 pub enum NodeRpcError {
@@ -37,10 +45,74 @@ pub enum NodeRpcError {
     ClientCreationError(ClientError),
     #[error("Response error: {0}")]
     ResponseError(ClientError),
+    #[error("Node is unavailable, it may be restarting: {0}")]
+    NodeUnavailable(ClientError),
     #[error("Address error: {0}")]
     AddressError(#[from] AddressError),
 }
 
+impl NodeRpcError {
+    /// Wrap an error returned by the node, distinguishing the node being unreachable
+    /// from the node rejecting the request
+    fn from_response(error: ClientError) -> Self {
+        if is_connection_error(&error) {
+            Self::NodeUnavailable(error)
+        } else {
+            Self::ResponseError(error)
+        }
+    }
+
+    /// Whether the node couldn't be reached, so the request may succeed once it's back
+    pub fn is_node_unavailable(&self) -> bool {
+        match self {
+            Self::NodeUnavailable(_) => true,
+            Self::InitializationError(e) => e.is_node_unavailable(),
+            Self::DecodingError(_)
+            | Self::ClientCreationError(_)
+            | Self::ResponseError(_)
+            | Self::AddressError(_) => false,
+        }
+    }
+}
+
+impl From<NodeRpcError> for rpc::Error {
+    fn from(e: NodeRpcError) -> Self {
+        match e {
+            NodeRpcError::InitializationError(e) => (*e).into(),
+            // Keep the code and the type of the error returned by the node
+            NodeRpcError::ResponseError(ClientError::Call(ref err)) => {
+                rpc::Error::owned(err.code(), e.to_string(), err.data())
+            }
+            NodeRpcError::NodeUnavailable(_) => {
+                rpc::categorized_error(rpc::SERVICE_UNAVAILABLE_CODE, &e, "NodeUnavailable")
+            }
+            NodeRpcError::AddressError(_) => {
+                rpc::categorized_error(rpc::INVALID_INPUT_CODE, &e, "AddressError")
+            }
+            NodeRpcError::DecodingError(_) => {
+                rpc::categorized_error(rpc::GENERIC_ERROR_CODE, &e, "DecodingError")
+            }
+            NodeRpcError::ClientCreationError(_) => {
+                rpc::categorized_error(rpc::GENERIC_ERROR_CODE, &e, "ClientCreationError")
+            }
+            NodeRpcError::ResponseError(_) => {
+                rpc::categorized_error(rpc::GENERIC_ERROR_CODE, &e, "ResponseError")
+            }
+        }
+    }
+}
+
+/// Whether the request failed because the node couldn't be reached (e.g. it's restarting)
+/// rather than because of the request itself
+fn is_connection_error(error: &ClientError) -> bool {
+    match error {
+        ClientError::Transport(_) | ClientError::RestartNeeded(_) | ClientError::RequestTimeout => {
+            true
+        }
+        _ => false,
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ColdWalletClient {
     chain_config: Arc<ChainConfig>,
@@ -52,6 +124,14 @@ impl ColdWalletClient {
     }
 }
 
+/// A client of the node's HTTP RPC interface.
+///
+/// Each request opens a connection as needed and the cookie file, if used, is read again on
+/// every request, so the client keeps working after the node is restarted. Read requests that
+/// fail because the node can't be reached are replayed with a capped exponential backoff.
+/// Other requests are never replayed, as they may have reached the node before the connection
+/// was lost, and fail with [NodeRpcError::NodeUnavailable] instead. The client doesn't use
+/// subscriptions, so the callers pick up any changes made while the node was away by polling.
 #[derive(Clone, Debug)]
 pub struct NodeRpcClient {
     http_client: RpcHttpClient,
@@ -74,11 +154,39 @@ impl NodeRpcClient {
             chain_config,
         };
 
-        client
-            .get_best_block_id()
-            .await
-            .map_err(|e| NodeRpcError::InitializationError(Box::new(e)))?;
+        // Not replayed, an unreachable node should be reported right away on startup
+        ChainstateRpcClient::best_block_id(&client.http_client).await.map_err(|e| {
+            NodeRpcError::InitializationError(Box::new(NodeRpcError::from_response(e)))
+        })?;
 
         Ok(client)
     }
+
+    /// Send a request that doesn't change the node state, replaying it while the node can't be
+    /// reached
+    async fn read_request<T, F, Fut>(&self, request: F) -> Result<T, NodeRpcError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let mut delay = RECONNECT_INITIAL_DELAY;
+        let mut attempt = 0;
+        loop {
+            match request().await {
+                Ok(result) => {
+                    if attempt > 0 {
+                        log::info!("Node connection restored after {attempt} attempts");
+                    }
+                    return Ok(result);
+                }
+                Err(e) if is_connection_error(&e) && attempt < RECONNECT_MAX_ATTEMPTS => {
+                    log::debug!("Node connection error, retrying in {delay:?}: {e}");
+                    tokio::time::sleep(delay).await;
+                    delay = std::cmp::min(delay * 2, RECONNECT_MAX_DELAY);
+                    attempt += 1;
+                }
+                Err(e) => return Err(NodeRpcError::from_response(e)),
+            }
+        }
+    }
 }
@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{net::SocketAddr, str::FromStr, sync::Arc};
+use std::{net::SocketAddr, str::FromStr, sync::Arc, time::Duration};
 
 use blockprod::{test_blockprod_config, BlockProductionHandle};
 use chainstate::{
@@ -53,12 +53,17 @@ pub async fn start_subsystems(
         bind_addresses: vec!["127.0.0.1:0".parse().unwrap()],
 
         socks5_proxy: Default::default(),
+        socks5_proxy_auth: Default::default(),
         disable_noise: Default::default(),
+        reserved_nodes_only: Default::default(),
+        enable_mempool_sync: Default::default(),
+        enable_port_mapping: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
         outbound_connection_timeout: Default::default(),
+        inbound_connection_timeout: Default::default(),
         ping_check_period: Default::default(),
         ping_timeout: Default::default(),
         peer_handshake_timeout: Default::default(),
@@ -67,6 +72,13 @@ pub async fn start_subsystems(
         allow_discover_private_ips: Default::default(),
         user_agent: common::primitives::user_agent::mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
+        fork_alert_threshold: Default::default(),
+        fork_alert_duration: Default::default(),
+        max_mempool_tx_broadcasts_per_peer: Default::default(),
+        max_buffered_block_response_bytes: Default::default(),
+        max_in_flight_block_responses_per_peer: Default::default(),
+        max_low_work_blocks_in_flight: Default::default(),
+        peer_admission_config: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
     };
@@ -260,3 +272,84 @@ async fn node_handle_communication() {
     shutdown_trigger.initiate();
     manager_task_handle.await.unwrap();
 }
+
+// The RPC client keeps working after the node's RPC server is restarted
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn node_rpc_reconnect() {
+    let chain_config = Arc::new(common::chain::config::create_unit_test_config());
+
+    let (
+        shutdown_trigger,
+        chainstate,
+        _mempool,
+        _block_prod,
+        _p2p,
+        _rpc_bind_address,
+        manager_task_handle,
+    ) = start_subsystems(chain_config.clone(), "127.0.0.1:0".to_string()).await;
+
+    // A separate RPC server that can be stopped and started again
+    let rpc = rpc::Builder::new("127.0.0.1:0".parse().unwrap(), None)
+        .register(chainstate.clone().into_rpc())
+        .build()
+        .await
+        .unwrap();
+    let rpc_bind_address = *rpc.http_address();
+
+    let rpc_client = make_rpc_client(
+        Arc::clone(&chain_config),
+        rpc_bind_address.to_string(),
+        RpcAuthData::None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        rpc_client.get_best_block_id().await.unwrap(),
+        chain_config.genesis_block_id()
+    );
+
+    rpc.shutdown().await;
+
+    // Requests that change the node state are not replayed
+    let block = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        BlockTimestamp::from_int_seconds(
+            chain_config.genesis_block().timestamp().as_int_seconds() + 1,
+        ),
+        ConsensusData::None,
+        BlockReward::new(Vec::new()),
+    )
+    .unwrap();
+    let error = rpc_client.submit_block(block.clone()).await.unwrap_err();
+    assert!(rpc_client.is_node_unavailable(&error));
+
+    let restart_task = tokio::spawn({
+        let chainstate = chainstate.clone();
+        async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            rpc::Builder::new(rpc_bind_address, None)
+                .register(chainstate.into_rpc())
+                .build()
+                .await
+                .unwrap()
+        }
+    });
+
+    // Read requests are replayed until the node is back
+    assert_eq!(
+        rpc_client.get_best_block_height().await.unwrap().into_int(),
+        0
+    );
+    let rpc = restart_task.await.unwrap();
+
+    rpc_client.submit_block(block.clone()).await.unwrap().unwrap();
+    assert_eq!(
+        rpc_client.get_best_block_id().await.unwrap(),
+        block.get_id()
+    );
+
+    rpc.shutdown().await;
+    shutdown_trigger.initiate();
+    manager_task_handle.await.unwrap();
+}
@@ -15,7 +15,7 @@
 
 use std::{collections::BTreeMap, fmt::Debug, num::NonZeroUsize, path::PathBuf, str::FromStr};
 
-use chainstate::ChainInfo;
+use chainstate::{rpc::BlockSubmissionError, ChainInfo};
 use common::{
     address::{dehexify::dehexify_all_addresses, AddressError},
     chain::{
@@ -30,21 +30,29 @@ use node_comm::node_traits::NodeInterface;
 use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress, PeerId};
 use rpc::types::RpcHexString;
 use serialization::{hex::HexEncode, hex_encoded::HexEncoded, json_encoded::JsonEncoded};
+use tokio::sync::mpsc;
 use utils_networking::IpOrSocketAddress;
 use wallet::{account::TxInfo, version::get_version};
 use wallet_controller::{
-    types::{CreatedBlockInfo, GenericTokenTransfer, SeedWithPassPhrase, WalletInfo},
+    types::{
+        CreatedBlockInfo, GenericTokenTransfer, SeedWithPassPhrase, WalletFingerprintInfo,
+        WalletInfo, WalletVersionInfo, WatchEvent,
+    },
     ConnectedPeer, ControllerConfig, UtxoState, UtxoType,
 };
 use wallet_rpc_lib::{
     types::{
-        AddressInfo, AddressWithUsageInfo, Balances, BlockInfo, ComposedTransaction, CreatedWallet,
-        DelegationInfo, LegacyVrfPublicKeyInfo, NewAccountInfo, NewDelegation, NewTransaction,
-        NftMetadata, NodeVersion, PoolInfo, PublicKeyInfo, RpcHashedTimelockContract,
-        RpcInspectTransaction, RpcStandaloneAddresses, RpcTokenId,
-        SendTokensFromMultisigAddressResult, StakePoolBalance, StakingStatus,
-        StandaloneAddressWithDetails, TokenMetadata, TxOptionsOverrides, UtxoInfo,
-        VrfPublicKeyInfo,
+        AccountStakingStatus, AddressInfo, AddressWithUsageInfo, Balances, BalancesByConfirmations,
+        BlockInfo, ComposedTransaction, CreatedWallet, DecodedPaymentUri,
+        DecommissionPoolTransaction, DelegationInfo, DuplicateOutputs, ExportedAddressSetInfo,
+        ImportedAddressSetInfo, InclusionStatus, LegacyVrfPublicKeyInfo, NewAccountInfo,
+        NewDelegation, NewPaymentUri, NewTransaction, NftMetadata, NodeConnectionStatus,
+        NodeVersion, PoolInfo, ProofOfReservesInfo, ProofOfReservesReport, PublicKeyInfo,
+        RpcAddress, RpcHashedTimelockContract, RpcInspectTransaction, RpcSearchMatch,
+        RpcSpendingPolicy, RpcStakePoolInfo, RpcStandaloneAddresses, RpcTokenId, SendToManyOutput,
+        SendToManyTransaction, SendTokensFromMultisigAddressResult, SendTransaction,
+        StakePoolBalance, StakingStatus, StandaloneAddressWithDetails, TokenMetadata,
+        TxOptionsOverrides, UtxoInfo, VrfPublicKeyInfo, XpubInfo,
     },
     RpcError, WalletRpc,
 };
@@ -149,6 +157,18 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn restore_wallet_from_backup(
+        &self,
+        backup_path: PathBuf,
+        wallet_path: PathBuf,
+        backup_password: Option<String>,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .restore_wallet_from_backup(backup_path, wallet_path, backup_password)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn close_wallet(&self) -> Result<(), Self::Error> {
         self.wallet_rpc
             .close_wallet()
@@ -191,6 +211,31 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn wallet_fingerprint(&self) -> Result<WalletFingerprintInfo, Self::Error> {
+        self.wallet_rpc
+            .wallet_fingerprint()
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn wallet_version(&self) -> Result<WalletVersionInfo, Self::Error> {
+        self.wallet_rpc
+            .wallet_version()
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn backup_wallet(
+        &self,
+        path: PathBuf,
+        password: Option<String>,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .backup_wallet(path, password)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn set_lookahead_size(
         &self,
         lookahead_size: u32,
@@ -237,6 +282,13 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn watch_events(&self) -> Result<mpsc::UnboundedReceiver<WatchEvent>, Self::Error> {
+        self.wallet_rpc
+            .watch_events()
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn create_account(&self, name: Option<String>) -> Result<NewAccountInfo, Self::Error> {
         self.wallet_rpc
             .create_account(name)
@@ -255,6 +307,50 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn set_spending_policy(
+        &self,
+        admin_password: String,
+        account_index: U31,
+        max_per_tx: Option<DecimalAmount>,
+        daily_cap: Option<DecimalAmount>,
+        allowed_addresses: Option<Vec<String>>,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .set_spending_policy(
+                admin_password,
+                account_index,
+                max_per_tx.map(Into::into),
+                daily_cap.map(Into::into),
+                allowed_addresses.map(|addresses| addresses.into_iter().map(Into::into).collect()),
+            )
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn spending_policy(&self, account_index: U31) -> Result<RpcSpendingPolicy, Self::Error> {
+        self.wallet_rpc
+            .spending_policy(account_index)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn set_confirmation_threshold(
+        &self,
+        confirmation_threshold: u64,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .set_confirmation_threshold(confirmation_threshold)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn confirmation_threshold(&self) -> Result<u64, Self::Error> {
+        self.wallet_rpc
+            .confirmation_threshold()
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn standalone_address_label_rename(
         &self,
         account_index: U31,
@@ -323,6 +419,92 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn get_issued_change_addresses(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<AddressWithUsageInfo>, Self::Error> {
+        self.wallet_rpc
+            .get_issued_change_addresses(account_index)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn export_xpub(&self, account_index: U31) -> Result<XpubInfo, Self::Error> {
+        self.wallet_rpc
+            .export_xpub(account_index)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn export_descriptor(
+        &self,
+        account_index: U31,
+        descriptor_type: String,
+    ) -> Result<String, Self::Error> {
+        self.wallet_rpc
+            .export_descriptor(account_index, descriptor_type)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn export_address_set(
+        &self,
+        account_index: U31,
+        path: PathBuf,
+        count: u32,
+    ) -> Result<ExportedAddressSetInfo, Self::Error> {
+        self.wallet_rpc
+            .export_address_set(account_index, path, count)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn import_address_set(
+        &self,
+        account_index: U31,
+        path: PathBuf,
+    ) -> Result<ImportedAddressSetInfo, Self::Error> {
+        self.wallet_rpc
+            .import_address_set(account_index, path)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn generate_proof_of_reserves(
+        &self,
+        account_index: U31,
+        message: String,
+        path: PathBuf,
+    ) -> Result<ProofOfReservesInfo, Self::Error> {
+        self.wallet_rpc
+            .generate_proof_of_reserves(account_index, message.into_bytes(), path)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn set_address_label(
+        &self,
+        account_index: U31,
+        address: String,
+        label: Option<String>,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .set_address_label(account_index, address.into(), label)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn search(
+        &self,
+        account_index: U31,
+        text: String,
+    ) -> Result<Vec<RpcSearchMatch>, Self::Error> {
+        self.wallet_rpc
+            .search(account_index, text)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn get_standalone_addresses(
         &self,
         account_index: U31,
@@ -344,13 +526,35 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
-    async fn issue_address(&self, account_index: U31) -> Result<AddressInfo, Self::Error> {
+    async fn issue_address(
+        &self,
+        account_index: U31,
+        allow_reuse: bool,
+    ) -> Result<AddressInfo, Self::Error> {
         self.wallet_rpc
-            .issue_address(account_index)
+            .issue_address(account_index, allow_reuse)
             .await
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn new_payment_uri(
+        &self,
+        account_index: U31,
+        amount: DecimalAmount,
+        label: Option<String>,
+    ) -> Result<NewPaymentUri, Self::Error> {
+        self.wallet_rpc
+            .new_payment_uri(account_index, amount.into(), label)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn decode_payment_uri(&self, uri: String) -> Result<DecodedPaymentUri, Self::Error> {
+        self.wallet_rpc
+            .decode_payment_uri(&uri)
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn reveal_public_key(
         &self,
         account_index: U31,
@@ -378,6 +582,18 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn get_balance_by_confirmations(
+        &self,
+        account_index: U31,
+        confirmation_threshold: Option<u64>,
+        with_locked: WithLocked,
+    ) -> Result<BalancesByConfirmations, Self::Error> {
+        self.wallet_rpc
+            .get_balance_by_confirmations(account_index, confirmation_threshold, with_locked)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn get_multisig_utxos(
         &self,
         account_index: U31,
@@ -480,9 +696,10 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
                 config,
             )
             .await
-            .map(|(tx, fees)| ComposedTransaction {
+            .map(|(tx, fees, reused_addresses)| ComposedTransaction {
                 hex: HexEncoded::new(tx).to_string(),
                 fees,
+                reused_addresses: reused_addresses.into_iter().map(RpcAddress::from).collect(),
             })
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
@@ -553,9 +770,10 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
         self.wallet_rpc
             .compose_transaction(inputs, outputs, htlc_secrets, only_transaction)
             .await
-            .map(|(tx, fees)| ComposedTransaction {
+            .map(|(tx, fees, reused_addresses)| ComposedTransaction {
                 hex: tx.to_hex(),
                 fees,
+                reused_addresses: reused_addresses.into_iter().map(RpcAddress::from).collect(),
             })
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
@@ -567,7 +785,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
         amount: DecimalAmount,
         selected_utxos: Vec<UtxoOutPoint>,
         config: ControllerConfig,
-    ) -> Result<NewTransaction, Self::Error> {
+    ) -> Result<SendTransaction, Self::Error> {
         self.wallet_rpc
             .send_coins(
                 account_index,
@@ -578,7 +796,29 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             )
             .await
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
-            .map(NewTransaction::new)
+            .map(SendTransaction::new)
+    }
+
+    async fn send_to_many_addresses(
+        &self,
+        account_index: U31,
+        outputs: Vec<(String, DecimalAmount, Option<String>)>,
+        duplicate_outputs: DuplicateOutputs,
+        config: ControllerConfig,
+    ) -> Result<SendToManyTransaction, Self::Error> {
+        let outputs = outputs
+            .into_iter()
+            .map(|(address, amount, token_id)| SendToManyOutput {
+                address: address.into(),
+                amount: amount.into(),
+                token_id: token_id.map(Into::into),
+            })
+            .collect();
+        self.wallet_rpc
+            .send_to_many_addresses(account_index, outputs, duplicate_outputs, config)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+            .map(SendToManyTransaction::new)
     }
 
     async fn sweep_addresses(
@@ -659,6 +899,18 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map(NewTransaction::new)
     }
 
+    async fn create_decommission_pool_transaction(
+        &self,
+        account_index: U31,
+        pool_id: String,
+        config: ControllerConfig,
+    ) -> Result<DecommissionPoolTransaction, Self::Error> {
+        self.wallet_rpc
+            .create_decommission_pool_transaction(account_index, pool_id.into(), config)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn decommission_stake_pool_request(
         &self,
         account_index: U31,
@@ -695,6 +947,23 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             })
     }
 
+    async fn create_delegation_and_stake(
+        &self,
+        account_index: U31,
+        pool_id: String,
+        amount: DecimalAmount,
+        config: ControllerConfig,
+    ) -> Result<NewDelegation, Self::Error> {
+        self.wallet_rpc
+            .create_delegation_and_stake(account_index, pool_id.into(), amount.into(), config)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+            .map(|(tx, delegation_id)| NewDelegation {
+                tx_id: tx.transaction().get_id(),
+                delegation_id,
+            })
+    }
+
     async fn delegate_staking(
         &self,
         account_index: U31,
@@ -751,6 +1020,16 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn staking_pools_status(
+        &self,
+        account_index: U31,
+    ) -> Result<AccountStakingStatus, Self::Error> {
+        self.wallet_rpc
+            .staking_pools_status(account_index)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn list_staking_pools(&self, account_index: U31) -> Result<Vec<PoolInfo>, Self::Error> {
         self.wallet_rpc
             .list_staking_pools(account_index)
@@ -776,6 +1055,13 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn list_all_staking_pools(&self) -> Result<Vec<RpcStakePoolInfo>, Self::Error> {
+        self.wallet_rpc
+            .list_all_staking_pools()
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn list_delegation_ids(
         &self,
         account_index: U31,
@@ -1135,6 +1421,13 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn node_connection_status(&self) -> Result<NodeConnectionStatus, Self::Error> {
+        self.wallet_rpc
+            .node_connection_status()
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn connected_peers(&self) -> Result<Vec<ConnectedPeer>, Self::Error> {
         self.wallet_rpc
             .connected_peers()
@@ -1163,11 +1456,15 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
-    async fn submit_block(&self, block: HexEncoded<Block>) -> Result<(), Self::Error> {
-        self.wallet_rpc
-            .submit_block(block)
-            .await
-            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    async fn submit_block(
+        &self,
+        block: HexEncoded<Block>,
+    ) -> Result<Result<(), BlockSubmissionError>, Self::Error> {
+        match self.wallet_rpc.submit_block(block).await {
+            Ok(()) => Ok(Ok(())),
+            Err(RpcError::BlockRejected(err)) => Ok(Err(err)),
+            Err(err) => Err(WalletRpcHandlesClientError::WalletRpcError(err)),
+        }
     }
 
     async fn chainstate_info(&self) -> Result<ChainInfo, Self::Error> {
@@ -1188,6 +1485,18 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn set_transaction_label(
+        &self,
+        account_index: U31,
+        transaction_id: Id<Transaction>,
+        label: Option<String>,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .set_transaction_label(account_index, transaction_id, label)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn list_pending_transactions(
         &self,
         account_index: U31,
@@ -1252,6 +1561,17 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn verify_transaction_inclusion(
+        &self,
+        account_index: U31,
+        transaction_id: Id<Transaction>,
+    ) -> Result<InclusionStatus, Self::Error> {
+        self.wallet_rpc
+            .verify_transaction_inclusion(account_index, transaction_id)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn sign_raw_transaction(
         &self,
         account_index: U31,
@@ -1306,6 +1626,16 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn node_verify_proof_of_reserves(
+        &self,
+        path: PathBuf,
+    ) -> Result<ProofOfReservesReport, Self::Error> {
+        self.wallet_rpc
+            .node_verify_proof_of_reserves(path)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn node_generate_block(
         &self,
         account_index: U31,
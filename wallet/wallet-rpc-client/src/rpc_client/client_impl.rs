@@ -19,7 +19,7 @@ use crate::wallet_rpc_traits::{PartialOrSignedTx, SignRawTransactionResult, Wall
 
 use super::{ClientWalletRpc, WalletRpcError};
 
-use chainstate::ChainInfo;
+use chainstate::{rpc::BlockSubmissionError, ChainInfo};
 use common::{
     chain::{
         block::timestamp::BlockTimestamp, partially_signed_transaction::PartiallySignedTransaction,
@@ -32,21 +32,28 @@ use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress
 use rpc::types::RpcHexString;
 use serialization::hex_encoded::HexEncoded;
 use serialization::DecodeAll;
+use tokio::sync::mpsc;
 use utils_networking::IpOrSocketAddress;
 use wallet::account::TxInfo;
 use wallet_controller::{
-    types::{Balances, CreatedBlockInfo, GenericTokenTransfer, SeedWithPassPhrase, WalletInfo},
+    types::{
+        Balances, BalancesByConfirmations, CreatedBlockInfo, GenericTokenTransfer,
+        SeedWithPassPhrase, WalletFingerprintInfo, WalletInfo, WalletVersionInfo, WatchEvent,
+    },
     ConnectedPeer, ControllerConfig, UtxoState, UtxoType,
 };
 use wallet_rpc_lib::{
     types::{
-        AddressInfo, AddressWithUsageInfo, BlockInfo, ComposedTransaction, CreatedWallet,
-        DelegationInfo, LegacyVrfPublicKeyInfo, NewAccountInfo, NewDelegation, NewTransaction,
-        NftMetadata, NodeVersion, PoolInfo, PublicKeyInfo, RpcHashedTimelockContract,
-        RpcInspectTransaction, RpcStandaloneAddresses, RpcTokenId,
-        SendTokensFromMultisigAddressResult, StakePoolBalance, StakingStatus,
-        StandaloneAddressWithDetails, TokenMetadata, TransactionOptions, TxOptionsOverrides,
-        VrfPublicKeyInfo,
+        AccountStakingStatus, AddressInfo, AddressWithUsageInfo, BlockInfo, ComposedTransaction,
+        CreatedWallet, DecodedPaymentUri, DecommissionPoolTransaction, DelegationInfo,
+        DuplicateOutputs, ExportedAddressSetInfo, ImportedAddressSetInfo, InclusionStatus,
+        LegacyVrfPublicKeyInfo, NewAccountInfo, NewDelegation, NewPaymentUri, NewTransaction,
+        NftMetadata, NodeConnectionStatus, NodeVersion, PoolInfo, ProofOfReservesInfo,
+        ProofOfReservesReport, PublicKeyInfo, RpcHashedTimelockContract, RpcInspectTransaction,
+        RpcSearchMatch, RpcSpendingPolicy, RpcStakePoolInfo, RpcStandaloneAddresses, RpcTokenId,
+        SendToManyOutput, SendToManyTransaction, SendTokensFromMultisigAddressResult,
+        SendTransaction, StakePoolBalance, StakingStatus, StandaloneAddressWithDetails,
+        TokenMetadata, TransactionOptions, TxOptionsOverrides, VrfPublicKeyInfo, XpubInfo,
     },
     ColdWalletRpcClient, WalletRpcClient,
 };
@@ -110,6 +117,22 @@ impl WalletInterface for ClientWalletRpc {
         .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn restore_wallet_from_backup(
+        &self,
+        backup_path: PathBuf,
+        wallet_path: PathBuf,
+        backup_password: Option<String>,
+    ) -> Result<(), Self::Error> {
+        ColdWalletRpcClient::restore_wallet_from_backup(
+            &self.http_client,
+            backup_path.to_string_lossy().to_string(),
+            wallet_path.to_string_lossy().to_string(),
+            backup_password,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn close_wallet(&self) -> Result<(), Self::Error> {
         ColdWalletRpcClient::close_wallet(&self.http_client)
             .await
@@ -146,6 +169,32 @@ impl WalletInterface for ClientWalletRpc {
             .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn wallet_fingerprint(&self) -> Result<WalletFingerprintInfo, Self::Error> {
+        ColdWalletRpcClient::wallet_fingerprint(&self.http_client)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn wallet_version(&self) -> Result<WalletVersionInfo, Self::Error> {
+        ColdWalletRpcClient::wallet_version(&self.http_client)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn backup_wallet(
+        &self,
+        path: PathBuf,
+        password: Option<String>,
+    ) -> Result<(), Self::Error> {
+        ColdWalletRpcClient::backup_wallet(
+            &self.http_client,
+            path.to_string_lossy().to_string(),
+            password,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn set_lookahead_size(
         &self,
         lookahead_size: u32,
@@ -190,6 +239,11 @@ impl WalletInterface for ClientWalletRpc {
             .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn watch_events(&self) -> Result<mpsc::UnboundedReceiver<WatchEvent>, Self::Error> {
+        // The http client can't receive subscription notifications
+        Err(WalletRpcError::SubscriptionsNotSupported)
+    }
+
     async fn create_account(&self, name: Option<String>) -> Result<NewAccountInfo, Self::Error> {
         WalletRpcClient::create_account(&self.http_client, name)
             .await
@@ -206,6 +260,47 @@ impl WalletInterface for ClientWalletRpc {
             .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn set_spending_policy(
+        &self,
+        admin_password: String,
+        account_index: U31,
+        max_per_tx: Option<DecimalAmount>,
+        daily_cap: Option<DecimalAmount>,
+        allowed_addresses: Option<Vec<String>>,
+    ) -> Result<(), Self::Error> {
+        WalletRpcClient::set_spending_policy(
+            &self.http_client,
+            admin_password,
+            account_index.into(),
+            max_per_tx.map(Into::into),
+            daily_cap.map(Into::into),
+            allowed_addresses.map(|addresses| addresses.into_iter().map(Into::into).collect()),
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn spending_policy(&self, account_index: U31) -> Result<RpcSpendingPolicy, Self::Error> {
+        WalletRpcClient::spending_policy(&self.http_client, account_index.into())
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn set_confirmation_threshold(
+        &self,
+        confirmation_threshold: u64,
+    ) -> Result<(), Self::Error> {
+        WalletRpcClient::set_confirmation_threshold(&self.http_client, confirmation_threshold)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn confirmation_threshold(&self) -> Result<u64, Self::Error> {
+        WalletRpcClient::confirmation_threshold(&self.http_client)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn standalone_address_label_rename(
         &self,
         account_index: U31,
@@ -287,6 +382,107 @@ impl WalletInterface for ClientWalletRpc {
             .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn get_issued_change_addresses(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<AddressWithUsageInfo>, Self::Error> {
+        ColdWalletRpcClient::get_issued_change_addresses(&self.http_client, account_index.into())
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn export_xpub(&self, account_index: U31) -> Result<XpubInfo, Self::Error> {
+        ColdWalletRpcClient::export_xpub(&self.http_client, account_index.into())
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn export_descriptor(
+        &self,
+        account_index: U31,
+        descriptor_type: String,
+    ) -> Result<String, Self::Error> {
+        ColdWalletRpcClient::export_descriptor(
+            &self.http_client,
+            account_index.into(),
+            descriptor_type,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn export_address_set(
+        &self,
+        account_index: U31,
+        path: PathBuf,
+        count: u32,
+    ) -> Result<ExportedAddressSetInfo, Self::Error> {
+        ColdWalletRpcClient::export_address_set(
+            &self.http_client,
+            account_index.into(),
+            path.to_string_lossy().to_string(),
+            count,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn import_address_set(
+        &self,
+        account_index: U31,
+        path: PathBuf,
+    ) -> Result<ImportedAddressSetInfo, Self::Error> {
+        WalletRpcClient::import_address_set(
+            &self.http_client,
+            account_index.into(),
+            path.to_string_lossy().to_string(),
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn generate_proof_of_reserves(
+        &self,
+        account_index: U31,
+        message: String,
+        path: PathBuf,
+    ) -> Result<ProofOfReservesInfo, Self::Error> {
+        WalletRpcClient::generate_proof_of_reserves(
+            &self.http_client,
+            account_index.into(),
+            message,
+            path.to_string_lossy().to_string(),
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn set_address_label(
+        &self,
+        account_index: U31,
+        address: String,
+        label: Option<String>,
+    ) -> Result<(), Self::Error> {
+        ColdWalletRpcClient::set_address_label(
+            &self.http_client,
+            account_index.into(),
+            address.into(),
+            label,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn search(
+        &self,
+        account_index: U31,
+        text: String,
+    ) -> Result<Vec<RpcSearchMatch>, Self::Error> {
+        ColdWalletRpcClient::search(&self.http_client, account_index.into(), text)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn get_standalone_addresses(
         &self,
         account_index: U31,
@@ -310,8 +506,38 @@ impl WalletInterface for ClientWalletRpc {
         .map_err(WalletRpcError::ResponseError)
     }
 
-    async fn issue_address(&self, account_index: U31) -> Result<AddressInfo, Self::Error> {
-        ColdWalletRpcClient::issue_address(&self.http_client, account_index.into())
+    async fn issue_address(
+        &self,
+        account_index: U31,
+        allow_reuse: bool,
+    ) -> Result<AddressInfo, Self::Error> {
+        ColdWalletRpcClient::issue_address(
+            &self.http_client,
+            account_index.into(),
+            Some(allow_reuse),
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn new_payment_uri(
+        &self,
+        account_index: U31,
+        amount: DecimalAmount,
+        label: Option<String>,
+    ) -> Result<NewPaymentUri, Self::Error> {
+        ColdWalletRpcClient::new_payment_uri(
+            &self.http_client,
+            account_index.into(),
+            amount.into(),
+            label,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn decode_payment_uri(&self, uri: String) -> Result<DecodedPaymentUri, Self::Error> {
+        ColdWalletRpcClient::decode_payment_uri(&self.http_client, uri)
             .await
             .map_err(WalletRpcError::ResponseError)
     }
@@ -346,6 +572,22 @@ impl WalletInterface for ClientWalletRpc {
         .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn get_balance_by_confirmations(
+        &self,
+        account_index: U31,
+        confirmation_threshold: Option<u64>,
+        with_locked: WithLocked,
+    ) -> Result<BalancesByConfirmations, Self::Error> {
+        WalletRpcClient::get_balance_by_confirmations(
+            &self.http_client,
+            account_index.into(),
+            confirmation_threshold,
+            Some(with_locked),
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn get_multisig_utxos(
         &self,
         account_index: U31,
@@ -394,7 +636,7 @@ impl WalletInterface for ClientWalletRpc {
         amount: DecimalAmount,
         selected_utxos: Vec<UtxoOutPoint>,
         config: ControllerConfig,
-    ) -> Result<NewTransaction, Self::Error> {
+    ) -> Result<SendTransaction, Self::Error> {
         let options = TransactionOptions::from_controller_config(&config);
         let selected_utxos = selected_utxos.into_iter().map(Into::into).collect();
         WalletRpcClient::send_coins(
@@ -409,6 +651,33 @@ impl WalletInterface for ClientWalletRpc {
         .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn send_to_many_addresses(
+        &self,
+        account_index: U31,
+        outputs: Vec<(String, DecimalAmount, Option<String>)>,
+        duplicate_outputs: DuplicateOutputs,
+        config: ControllerConfig,
+    ) -> Result<SendToManyTransaction, Self::Error> {
+        let options = TransactionOptions::from_controller_config(&config);
+        let outputs = outputs
+            .into_iter()
+            .map(|(address, amount, token_id)| SendToManyOutput {
+                address: address.into(),
+                amount: amount.into(),
+                token_id: token_id.map(Into::into),
+            })
+            .collect();
+        WalletRpcClient::send_to_many_addresses(
+            &self.http_client,
+            account_index.into(),
+            outputs,
+            Some(duplicate_outputs),
+            options,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn sweep_addresses(
         &self,
         account_index: U31,
@@ -521,6 +790,23 @@ impl WalletInterface for ClientWalletRpc {
         .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn create_decommission_pool_transaction(
+        &self,
+        account_index: U31,
+        pool_id: String,
+        config: ControllerConfig,
+    ) -> Result<DecommissionPoolTransaction, Self::Error> {
+        let options = TransactionOptions::from_controller_config(&config);
+        WalletRpcClient::create_decommission_pool_transaction(
+            &self.http_client,
+            account_index.into(),
+            pool_id.into(),
+            options,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn decommission_stake_pool_request(
         &self,
         account_index: U31,
@@ -559,6 +845,25 @@ impl WalletInterface for ClientWalletRpc {
         .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn create_delegation_and_stake(
+        &self,
+        account_index: U31,
+        pool_id: String,
+        amount: DecimalAmount,
+        config: ControllerConfig,
+    ) -> Result<NewDelegation, Self::Error> {
+        let options = TransactionOptions::from_controller_config(&config);
+        WalletRpcClient::create_delegation_and_stake(
+            &self.http_client,
+            account_index.into(),
+            pool_id.into(),
+            amount.into(),
+            options,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn delegate_staking(
         &self,
         account_index: U31,
@@ -617,6 +922,15 @@ impl WalletInterface for ClientWalletRpc {
             .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn staking_pools_status(
+        &self,
+        account_index: U31,
+    ) -> Result<AccountStakingStatus, Self::Error> {
+        WalletRpcClient::staking_pools_status(&self.http_client, account_index.into())
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn list_staking_pools(&self, account_index: U31) -> Result<Vec<PoolInfo>, Self::Error> {
         WalletRpcClient::list_pools(&self.http_client, account_index.into())
             .await
@@ -638,6 +952,12 @@ impl WalletInterface for ClientWalletRpc {
             .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn list_all_staking_pools(&self) -> Result<Vec<RpcStakePoolInfo>, Self::Error> {
+        WalletRpcClient::list_all_staking_pools(&self.http_client)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn list_delegation_ids(
         &self,
         account_index: U31,
@@ -1000,6 +1320,12 @@ impl WalletInterface for ClientWalletRpc {
             .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn node_connection_status(&self) -> Result<NodeConnectionStatus, Self::Error> {
+        WalletRpcClient::node_connection_status(&self.http_client)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn connected_peers(&self) -> Result<Vec<ConnectedPeer>, Self::Error> {
         WalletRpcClient::connected_peers(&self.http_client)
             .await
@@ -1024,10 +1350,17 @@ impl WalletInterface for ClientWalletRpc {
             .map_err(WalletRpcError::ResponseError)
     }
 
-    async fn submit_block(&self, block: HexEncoded<Block>) -> Result<(), Self::Error> {
-        WalletRpcClient::submit_block(&self.http_client, block)
-            .await
-            .map_err(WalletRpcError::ResponseError)
+    async fn submit_block(
+        &self,
+        block: HexEncoded<Block>,
+    ) -> Result<Result<(), BlockSubmissionError>, Self::Error> {
+        match WalletRpcClient::submit_block(&self.http_client, block).await {
+            Ok(()) => Ok(Ok(())),
+            Err(err) => match rpc::error_data(&err) {
+                Some(rejection) => Ok(Err(rejection)),
+                None => Err(WalletRpcError::ResponseError(err)),
+            },
+        }
     }
 
     async fn chainstate_info(&self) -> Result<ChainInfo, Self::Error> {
@@ -1050,6 +1383,22 @@ impl WalletInterface for ClientWalletRpc {
         .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn set_transaction_label(
+        &self,
+        account_index: U31,
+        transaction_id: Id<Transaction>,
+        label: Option<String>,
+    ) -> Result<(), Self::Error> {
+        WalletRpcClient::set_transaction_label(
+            &self.http_client,
+            account_index.into(),
+            HexEncoded::new(transaction_id),
+            label,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn list_pending_transactions(
         &self,
         account_index: U31,
@@ -1115,6 +1464,20 @@ impl WalletInterface for ClientWalletRpc {
         .map(|obj| obj.to_string())
     }
 
+    async fn verify_transaction_inclusion(
+        &self,
+        account_index: U31,
+        transaction_id: Id<Transaction>,
+    ) -> Result<InclusionStatus, Self::Error> {
+        WalletRpcClient::verify_transaction_inclusion(
+            &self.http_client,
+            account_index.into(),
+            transaction_id,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn sign_raw_transaction(
         &self,
         account_index: U31,
@@ -1260,6 +1623,18 @@ impl WalletInterface for ClientWalletRpc {
             .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn node_verify_proof_of_reserves(
+        &self,
+        path: PathBuf,
+    ) -> Result<ProofOfReservesReport, Self::Error> {
+        WalletRpcClient::node_verify_proof_of_reserves(
+            &self.http_client,
+            path.to_string_lossy().to_string(),
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn node_generate_block(
         &self,
         account_index: U31,
@@ -32,6 +32,8 @@ pub enum WalletRpcError {
     ClientCreationError(ClientError),
     #[error("Response error: {0}")]
     ResponseError(ClientError),
+    #[error("Wallet events are not available when connected to a remote wallet")]
+    SubscriptionsNotSupported,
 }
 
 impl From<hex::FromHexError> for WalletRpcError {
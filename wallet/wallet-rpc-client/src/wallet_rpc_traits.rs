@@ -15,7 +15,7 @@
 
 use std::{collections::BTreeMap, num::NonZeroUsize, path::PathBuf};
 
-use chainstate::ChainInfo;
+use chainstate::{rpc::BlockSubmissionError, ChainInfo};
 use common::{
     chain::{
         block::timestamp::BlockTimestamp, partially_signed_transaction::PartiallySignedTransaction,
@@ -26,19 +26,27 @@ use common::{
 use crypto::key::{hdkd::u31::U31, PrivateKey};
 use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress, PeerId};
 use serialization::hex_encoded::HexEncoded;
+use tokio::sync::mpsc;
 use utils_networking::IpOrSocketAddress;
 use wallet::account::TxInfo;
 use wallet_controller::{
-    types::{CreatedBlockInfo, GenericTokenTransfer, SeedWithPassPhrase, WalletInfo},
+    types::{
+        CreatedBlockInfo, GenericTokenTransfer, SeedWithPassPhrase, WalletFingerprintInfo,
+        WalletInfo, WalletVersionInfo, WatchEvent,
+    },
     ConnectedPeer, ControllerConfig, UtxoState, UtxoType,
 };
 use wallet_rpc_lib::types::{
-    AddressInfo, AddressWithUsageInfo, Balances, BlockInfo, ComposedTransaction, CreatedWallet,
-    DelegationInfo, LegacyVrfPublicKeyInfo, NewAccountInfo, NewDelegation, NewTransaction,
-    NftMetadata, NodeVersion, PoolInfo, PublicKeyInfo, RpcHashedTimelockContract,
-    RpcInspectTransaction, RpcSignatureStatus, RpcStandaloneAddresses, RpcTokenId,
-    SendTokensFromMultisigAddressResult, StakePoolBalance, StakingStatus,
-    StandaloneAddressWithDetails, TokenMetadata, TxOptionsOverrides, VrfPublicKeyInfo,
+    AccountStakingStatus, AddressInfo, AddressWithUsageInfo, Balances, BalancesByConfirmations,
+    BlockInfo, ComposedTransaction, CreatedWallet, DecodedPaymentUri, DecommissionPoolTransaction,
+    DelegationInfo, DuplicateOutputs, ExportedAddressSetInfo, ImportedAddressSetInfo,
+    InclusionStatus, LegacyVrfPublicKeyInfo, NewAccountInfo, NewDelegation, NewPaymentUri,
+    NewTransaction, NftMetadata, NodeConnectionStatus, NodeVersion, PoolInfo, ProofOfReservesInfo,
+    ProofOfReservesReport, PublicKeyInfo, RpcHashedTimelockContract, RpcInspectTransaction,
+    RpcSearchMatch, RpcSignatureStatus, RpcSpendingPolicy, RpcStakePoolInfo,
+    RpcStandaloneAddresses, RpcTokenId, SendToManyTransaction, SendTokensFromMultisigAddressResult,
+    SendTransaction, StakePoolBalance, StakingStatus, StandaloneAddressWithDetails, TokenMetadata,
+    TxOptionsOverrides, VrfPublicKeyInfo, XpubInfo,
 };
 use wallet_types::with_locked::WithLocked;
 
@@ -80,6 +88,13 @@ pub trait WalletInterface {
         force_migrate_wallet_type: Option<bool>,
     ) -> Result<(), Self::Error>;
 
+    async fn restore_wallet_from_backup(
+        &self,
+        backup_path: PathBuf,
+        wallet_path: PathBuf,
+        backup_password: Option<String>,
+    ) -> Result<(), Self::Error>;
+
     async fn close_wallet(&self) -> Result<(), Self::Error>;
 
     async fn wallet_info(&self) -> Result<WalletInfo, Self::Error>;
@@ -92,6 +107,16 @@ pub trait WalletInterface {
 
     async fn purge_seed_phrase(&self) -> Result<Option<SeedWithPassPhrase>, Self::Error>;
 
+    async fn wallet_fingerprint(&self) -> Result<WalletFingerprintInfo, Self::Error>;
+
+    async fn wallet_version(&self) -> Result<WalletVersionInfo, Self::Error>;
+
+    async fn backup_wallet(
+        &self,
+        path: PathBuf,
+        password: Option<String>,
+    ) -> Result<(), Self::Error>;
+
     async fn set_lookahead_size(
         &self,
         lookahead_size: u32,
@@ -108,6 +133,10 @@ pub trait WalletInterface {
 
     async fn best_block(&self) -> Result<BlockInfo, Self::Error>;
 
+    /// Subscribe to the wallet events, e.g. new chain tips and transaction updates;
+    /// the events are produced until the receiver is dropped
+    async fn watch_events(&self) -> Result<mpsc::UnboundedReceiver<WatchEvent>, Self::Error>;
+
     async fn create_account(&self, name: Option<String>) -> Result<NewAccountInfo, Self::Error>;
 
     async fn rename_account(
@@ -116,6 +145,24 @@ pub trait WalletInterface {
         name: Option<String>,
     ) -> Result<NewAccountInfo, Self::Error>;
 
+    async fn set_spending_policy(
+        &self,
+        admin_password: String,
+        account_index: U31,
+        max_per_tx: Option<DecimalAmount>,
+        daily_cap: Option<DecimalAmount>,
+        allowed_addresses: Option<Vec<String>>,
+    ) -> Result<(), Self::Error>;
+
+    async fn spending_policy(&self, account_index: U31) -> Result<RpcSpendingPolicy, Self::Error>;
+
+    async fn set_confirmation_threshold(
+        &self,
+        confirmation_threshold: u64,
+    ) -> Result<(), Self::Error>;
+
+    async fn confirmation_threshold(&self) -> Result<u64, Self::Error>;
+
     async fn standalone_address_label_rename(
         &self,
         account_index: U31,
@@ -153,6 +200,52 @@ pub trait WalletInterface {
         options: U31,
     ) -> Result<Vec<AddressWithUsageInfo>, Self::Error>;
 
+    async fn get_issued_change_addresses(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<AddressWithUsageInfo>, Self::Error>;
+
+    async fn export_xpub(&self, account_index: U31) -> Result<XpubInfo, Self::Error>;
+
+    async fn export_descriptor(
+        &self,
+        account_index: U31,
+        descriptor_type: String,
+    ) -> Result<String, Self::Error>;
+
+    async fn export_address_set(
+        &self,
+        account_index: U31,
+        path: PathBuf,
+        count: u32,
+    ) -> Result<ExportedAddressSetInfo, Self::Error>;
+
+    async fn import_address_set(
+        &self,
+        account_index: U31,
+        path: PathBuf,
+    ) -> Result<ImportedAddressSetInfo, Self::Error>;
+
+    async fn generate_proof_of_reserves(
+        &self,
+        account_index: U31,
+        message: String,
+        path: PathBuf,
+    ) -> Result<ProofOfReservesInfo, Self::Error>;
+
+    async fn set_address_label(
+        &self,
+        account_index: U31,
+        address: String,
+        label: Option<String>,
+    ) -> Result<(), Self::Error>;
+
+    async fn search(
+        &self,
+        account_index: U31,
+        text: String,
+    ) -> Result<Vec<RpcSearchMatch>, Self::Error>;
+
     async fn get_standalone_addresses(
         &self,
         account_index: U31,
@@ -164,7 +257,20 @@ pub trait WalletInterface {
         address: String,
     ) -> Result<StandaloneAddressWithDetails, Self::Error>;
 
-    async fn issue_address(&self, account_index: U31) -> Result<AddressInfo, Self::Error>;
+    async fn issue_address(
+        &self,
+        account_index: U31,
+        allow_reuse: bool,
+    ) -> Result<AddressInfo, Self::Error>;
+
+    async fn new_payment_uri(
+        &self,
+        account_index: U31,
+        amount: DecimalAmount,
+        label: Option<String>,
+    ) -> Result<NewPaymentUri, Self::Error>;
+
+    async fn decode_payment_uri(&self, uri: String) -> Result<DecodedPaymentUri, Self::Error>;
 
     async fn reveal_public_key(
         &self,
@@ -179,6 +285,13 @@ pub trait WalletInterface {
         with_locked: WithLocked,
     ) -> Result<Balances, Self::Error>;
 
+    async fn get_balance_by_confirmations(
+        &self,
+        account_index: U31,
+        confirmation_threshold: Option<u64>,
+        with_locked: WithLocked,
+    ) -> Result<BalancesByConfirmations, Self::Error>;
+
     async fn get_multisig_utxos(
         &self,
         account_index: U31,
@@ -245,7 +358,15 @@ pub trait WalletInterface {
         amount: DecimalAmount,
         selected_utxos: Vec<UtxoOutPoint>,
         config: ControllerConfig,
-    ) -> Result<NewTransaction, Self::Error>;
+    ) -> Result<SendTransaction, Self::Error>;
+
+    async fn send_to_many_addresses(
+        &self,
+        account_index: U31,
+        outputs: Vec<(String, DecimalAmount, Option<String>)>,
+        duplicate_outputs: DuplicateOutputs,
+        config: ControllerConfig,
+    ) -> Result<SendToManyTransaction, Self::Error>;
 
     async fn sweep_addresses(
         &self,
@@ -296,6 +417,13 @@ pub trait WalletInterface {
         config: ControllerConfig,
     ) -> Result<NewTransaction, Self::Error>;
 
+    async fn create_decommission_pool_transaction(
+        &self,
+        account_index: U31,
+        pool_id: String,
+        config: ControllerConfig,
+    ) -> Result<DecommissionPoolTransaction, Self::Error>;
+
     async fn decommission_stake_pool_request(
         &self,
         account_index: U31,
@@ -312,6 +440,14 @@ pub trait WalletInterface {
         config: ControllerConfig,
     ) -> Result<NewDelegation, Self::Error>;
 
+    async fn create_delegation_and_stake(
+        &self,
+        account_index: U31,
+        pool_id: String,
+        amount: DecimalAmount,
+        config: ControllerConfig,
+    ) -> Result<NewDelegation, Self::Error>;
+
     async fn delegate_staking(
         &self,
         account_index: U31,
@@ -335,6 +471,11 @@ pub trait WalletInterface {
 
     async fn staking_status(&self, account_index: U31) -> Result<StakingStatus, Self::Error>;
 
+    async fn staking_pools_status(
+        &self,
+        account_index: U31,
+    ) -> Result<AccountStakingStatus, Self::Error>;
+
     async fn list_staking_pools(&self, account_index: U31) -> Result<Vec<PoolInfo>, Self::Error>;
 
     async fn list_pools_for_decommission(
@@ -344,6 +485,8 @@ pub trait WalletInterface {
 
     async fn stake_pool_balance(&self, pool_id: String) -> Result<StakePoolBalance, Self::Error>;
 
+    async fn list_all_staking_pools(&self) -> Result<Vec<RpcStakePoolInfo>, Self::Error>;
+
     async fn list_delegation_ids(
         &self,
         account_index: U31,
@@ -500,6 +643,8 @@ pub trait WalletInterface {
 
     async fn peer_count(&self) -> Result<usize, Self::Error>;
 
+    async fn node_connection_status(&self) -> Result<NodeConnectionStatus, Self::Error>;
+
     async fn connected_peers(&self) -> Result<Vec<ConnectedPeer>, Self::Error>;
 
     async fn reserved_peers(&self) -> Result<Vec<SocketAddress>, Self::Error>;
@@ -508,7 +653,11 @@ pub trait WalletInterface {
 
     async fn remove_reserved_peer(&self, address: IpOrSocketAddress) -> Result<(), Self::Error>;
 
-    async fn submit_block(&self, block: HexEncoded<Block>) -> Result<(), Self::Error>;
+    /// The inner error is returned if the block was rejected by the node.
+    async fn submit_block(
+        &self,
+        block: HexEncoded<Block>,
+    ) -> Result<Result<(), BlockSubmissionError>, Self::Error>;
 
     async fn chainstate_info(&self) -> Result<ChainInfo, Self::Error>;
 
@@ -518,6 +667,13 @@ pub trait WalletInterface {
         transaction_id: Id<Transaction>,
     ) -> Result<(), Self::Error>;
 
+    async fn set_transaction_label(
+        &self,
+        account_index: U31,
+        transaction_id: Id<Transaction>,
+        label: Option<String>,
+    ) -> Result<(), Self::Error>;
+
     async fn list_pending_transactions(
         &self,
         account_index: U31,
@@ -548,6 +704,12 @@ pub trait WalletInterface {
         transaction_id: Id<Transaction>,
     ) -> Result<String, Self::Error>;
 
+    async fn verify_transaction_inclusion(
+        &self,
+        account_index: U31,
+        transaction_id: Id<Transaction>,
+    ) -> Result<InclusionStatus, Self::Error>;
+
     async fn sign_raw_transaction(
         &self,
         account_index: U31,
@@ -564,6 +726,11 @@ pub trait WalletInterface {
         block_height: BlockHeight,
     ) -> Result<Option<Id<GenBlock>>, Self::Error>;
 
+    async fn node_verify_proof_of_reserves(
+        &self,
+        path: PathBuf,
+    ) -> Result<ProofOfReservesReport, Self::Error>;
+
     async fn node_generate_block(
         &self,
         account_index: U31,
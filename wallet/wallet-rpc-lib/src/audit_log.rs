@@ -0,0 +1,469 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tamper-evident log of the wallet RPC calls changing the wallet state.
+//!
+//! Each entry is a JSON line containing the hash of the previous entry, so any edit, removal
+//! or reordering of the entries breaks the chain. When the log file grows over the size limit,
+//! it's renamed and a new file is started with a header entry continuing the chain.
+
+use std::{
+    collections::BTreeSet,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+};
+
+use crypto::hash::{hash, Sha256};
+use serde::{Deserialize, Serialize};
+use utils::{ensure, sync::Mutex};
+
+use serde_json::Value as JsonValue;
+
+/// The hash the first entry of the chain points to
+const GENESIS_HASH: [u8; 32] = [0; 32];
+
+#[derive(Debug, Clone)]
+pub struct AuditLogConfig {
+    /// The file the entries are appended to
+    pub path: PathBuf,
+
+    /// The size after which the file is rotated
+    pub max_file_size: u64,
+}
+
+impl AuditLogConfig {
+    pub const DEFAULT_MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            max_file_size: Self::DEFAULT_MAX_FILE_SIZE,
+        }
+    }
+}
+
+/// The outcome of an audited call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditResult {
+    Ok,
+    TxId(String),
+    Error(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AuditEvent {
+    /// The first entry of each file, names the rotated file that the chain continues from
+    Header { previous_file: Option<String> },
+    /// An RPC call, the parameters never include key material or passwords
+    Call {
+        method: String,
+        params: JsonValue,
+        result: AuditResult,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// The position of the entry in the chain, spanning the rotated files
+    pub seq: u64,
+    /// Seconds since the epoch
+    pub timestamp: u64,
+    pub event: AuditEvent,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditEntry {
+    fn new(seq: u64, timestamp: u64, event: AuditEvent, prev_hash: String) -> Self {
+        let mut entry = Self {
+            seq,
+            timestamp,
+            event,
+            prev_hash,
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+        entry
+    }
+
+    fn compute_hash(&self) -> String {
+        let contents =
+            serde_json::to_vec(&(self.seq, self.timestamp, &self.event, &self.prev_hash))
+                .expect("Serialization of the audit entry cannot fail");
+        hex::encode(hash::<Sha256, _>(contents))
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum BrokenEntryReason {
+    #[error("Malformed entry: {0}")]
+    Malformed(String),
+    #[error("The entry hash doesn't match its contents")]
+    HashMismatch,
+    #[error("The entry doesn't follow the previous one")]
+    ChainMismatch,
+    #[error("The file doesn't start with a header")]
+    MissingHeader,
+    #[error("Header in the middle of the file")]
+    UnexpectedHeader,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AuditLogError {
+    #[error("Audit log file {} IO error: {error}", .file.display())]
+    Io {
+        file: PathBuf,
+        error: std::io::Error,
+    },
+    #[error("Audit log file {} is broken at line {line}: {reason}", .file.display())]
+    BrokenEntry {
+        file: PathBuf,
+        line: usize,
+        reason: BrokenEntryReason,
+    },
+}
+
+/// The result of a successful verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditLogSummary {
+    /// The verified files, starting with the oldest one
+    pub files: Vec<PathBuf>,
+    /// The number of entries in all the files, including the headers
+    pub entries: u64,
+    /// The hash of the last entry, can be stored elsewhere to detect the truncation of the log
+    pub last_hash: String,
+}
+
+struct AuditLogState {
+    file: File,
+    file_size: u64,
+    next_seq: u64,
+    last_hash: String,
+}
+
+/// Appends the entries to the audit log file, rotating it when it grows too big
+pub struct AuditLog {
+    config: AuditLogConfig,
+    state: Mutex<AuditLogState>,
+}
+
+impl AuditLog {
+    /// Open the log file, continuing the chain of its last entry if it already exists
+    pub fn open(config: AuditLogConfig) -> Result<Self, AuditLogError> {
+        let io_error = |error| AuditLogError::Io {
+            file: config.path.clone(),
+            error,
+        };
+
+        let last_entry = if config.path.exists() {
+            read_entries(&config.path)?.pop().transpose()?
+        } else {
+            None
+        };
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&config.path)
+            .map_err(io_error)?;
+        let file_size = file.metadata().map_err(io_error)?.len();
+
+        let mut state = match last_entry {
+            Some((_, entry)) => AuditLogState {
+                file,
+                file_size,
+                next_seq: entry.seq + 1,
+                last_hash: entry.hash,
+            },
+            None => AuditLogState {
+                file,
+                file_size,
+                next_seq: 0,
+                last_hash: hex::encode(GENESIS_HASH),
+            },
+        };
+        if state.next_seq == 0 {
+            Self::append(
+                &config.path,
+                &mut state,
+                AuditEvent::Header {
+                    previous_file: None,
+                },
+            )?;
+        }
+
+        Ok(Self {
+            config,
+            state: Mutex::new(state),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.config.path
+    }
+
+    /// Record a call, rotating the file first if it's over the size limit
+    pub fn record(
+        &self,
+        method: &str,
+        params: JsonValue,
+        result: AuditResult,
+    ) -> Result<(), AuditLogError> {
+        let mut state = self.state.lock().expect("poisoned mutex");
+
+        if state.file_size >= self.config.max_file_size {
+            self.rotate(&mut state)?;
+        }
+
+        let event = AuditEvent::Call {
+            method: method.to_owned(),
+            params,
+            result,
+        };
+        Self::append(&self.config.path, &mut state, event)
+    }
+
+    /// Rename the current file after the sequence number of the new header, and start a new
+    /// file with a header pointing to it
+    fn rotate(&self, state: &mut AuditLogState) -> Result<(), AuditLogError> {
+        let path = &self.config.path;
+        let io_error = |error| AuditLogError::Io {
+            file: path.clone(),
+            error,
+        };
+
+        let mut rotated_name = path.file_name().unwrap_or_default().to_owned();
+        rotated_name.push(format!(".{}", state.next_seq));
+        let rotated_path = path.with_file_name(&rotated_name);
+
+        std::fs::rename(path, &rotated_path).map_err(io_error)?;
+        state.file =
+            OpenOptions::new().create_new(true).append(true).open(path).map_err(io_error)?;
+        state.file_size = 0;
+
+        let event = AuditEvent::Header {
+            previous_file: Some(rotated_name.to_string_lossy().into_owned()),
+        };
+        Self::append(path, state, event)
+    }
+
+    fn append(
+        path: &Path,
+        state: &mut AuditLogState,
+        event: AuditEvent,
+    ) -> Result<(), AuditLogError> {
+        let timestamp = common::primitives::time::get_time().as_secs_since_epoch();
+        let entry = AuditEntry::new(state.next_seq, timestamp, event, state.last_hash.clone());
+
+        let mut line =
+            serde_json::to_string(&entry).expect("Serialization of the audit entry cannot fail");
+        line.push('\n');
+        state
+            .file
+            .write_all(line.as_bytes())
+            .and_then(|()| state.file.sync_data())
+            .map_err(|error| AuditLogError::Io {
+                file: path.to_owned(),
+                error,
+            })?;
+
+        state.file_size += line.len() as u64;
+        state.next_seq += 1;
+        state.last_hash = entry.hash;
+        Ok(())
+    }
+}
+
+type LineResult = Result<(usize, AuditEntry), AuditLogError>;
+
+/// Parse the lines of the file, the line numbers start from 1
+fn read_entries(path: &Path) -> Result<Vec<LineResult>, AuditLogError> {
+    let io_error = |error| AuditLogError::Io {
+        file: path.to_owned(),
+        error,
+    };
+
+    let file = File::open(path).map_err(io_error)?;
+    BufReader::new(file)
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let line_num = index + 1;
+            let line = line.map_err(io_error)?;
+            let entry = serde_json::from_str::<AuditEntry>(&line).map_err(|err| {
+                AuditLogError::BrokenEntry {
+                    file: path.to_owned(),
+                    line: line_num,
+                    reason: BrokenEntryReason::Malformed(err.to_string()),
+                }
+            });
+            Ok(entry.map(|entry| (line_num, entry)))
+        })
+        .collect()
+}
+
+/// Check the hash chain of the audit log file and of the rotated files preceding it.
+///
+/// Returns the first broken entry in the chain, if any.
+pub fn verify_audit_log(path: &Path) -> Result<AuditLogSummary, AuditLogError> {
+    // Follow the headers back to the first file of the chain
+    let mut files = vec![path.to_owned()];
+    let mut visited = BTreeSet::new();
+    loop {
+        let file = files.last().expect("not empty");
+        let broken_header = |reason| AuditLogError::BrokenEntry {
+            file: file.clone(),
+            line: 1,
+            reason,
+        };
+
+        let first_entry = read_entries(file)?.into_iter().next().transpose()?;
+        let previous_file = match first_entry.map(|(_, entry)| entry.event) {
+            Some(AuditEvent::Header { previous_file }) => previous_file,
+            Some(AuditEvent::Call { .. }) | None => {
+                return Err(broken_header(BrokenEntryReason::MissingHeader))
+            }
+        };
+        let previous_file = match previous_file {
+            Some(previous_file) => file.with_file_name(previous_file),
+            None => break,
+        };
+        ensure!(
+            visited.insert(previous_file.clone()),
+            broken_header(BrokenEntryReason::ChainMismatch)
+        );
+        files.push(previous_file);
+    }
+    files.reverse();
+
+    let mut entries = 0;
+    let mut last_hash = hex::encode(GENESIS_HASH);
+    for file in &files {
+        for line_result in read_entries(file)? {
+            let (line, entry) = line_result?;
+            let broken_entry = |reason| AuditLogError::BrokenEntry {
+                file: file.clone(),
+                line,
+                reason,
+            };
+
+            ensure!(
+                entry.hash == entry.compute_hash(),
+                broken_entry(BrokenEntryReason::HashMismatch)
+            );
+            ensure!(
+                entry.seq == entries && entry.prev_hash == last_hash,
+                broken_entry(BrokenEntryReason::ChainMismatch)
+            );
+            let is_header = matches!(entry.event, AuditEvent::Header { .. });
+            ensure!(
+                is_header == (line == 1),
+                broken_entry(if is_header {
+                    BrokenEntryReason::UnexpectedHeader
+                } else {
+                    BrokenEntryReason::MissingHeader
+                })
+            );
+
+            entries += 1;
+            last_hash = entry.hash;
+        }
+    }
+
+    Ok(AuditLogSummary {
+        files,
+        entries,
+        last_hash,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record_calls(audit_log: &AuditLog, count: u64) {
+        for i in 0..count {
+            audit_log
+                .record(
+                    "send_coins",
+                    serde_json::json!({ "account": 0, "amount": i.to_string() }),
+                    AuditResult::TxId(format!("{i:064x}")),
+                )
+                .unwrap();
+        }
+    }
+
+    fn edit_line(path: &Path, line: usize, f: impl FnOnce(&str) -> String) {
+        let contents = std::fs::read_to_string(path).unwrap();
+        let mut lines = contents.lines().map(str::to_owned).collect::<Vec<_>>();
+        lines[line - 1] = f(&lines[line - 1]);
+        std::fs::write(path, lines.join("\n") + "\n").unwrap();
+    }
+
+    #[test]
+    fn rotation_and_tampering() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("audit.log");
+        let config = AuditLogConfig {
+            path: path.clone(),
+            max_file_size: 1000,
+        };
+
+        let audit_log = AuditLog::open(config.clone()).unwrap();
+        record_calls(&audit_log, 10);
+        drop(audit_log);
+
+        // The chain continues after reopening
+        let audit_log = AuditLog::open(config).unwrap();
+        record_calls(&audit_log, 10);
+        drop(audit_log);
+
+        let summary = verify_audit_log(&path).unwrap();
+        assert!(summary.files.len() > 2);
+        assert_eq!(summary.files.last(), Some(&path));
+        assert_eq!(summary.entries, 20 + summary.files.len() as u64);
+
+        // Edit an entry in the middle of the first file
+        let first_file = summary.files[0].clone();
+        edit_line(&first_file, 3, |line| line.replace("\"1\"", "\"10\""));
+        let err = verify_audit_log(&path).unwrap_err();
+        assert!(matches!(
+            err,
+            AuditLogError::BrokenEntry { file, line: 3, reason: BrokenEntryReason::HashMismatch }
+                if file == first_file
+        ));
+
+        // Recomputing the hash of the edited entry breaks the link to the next one
+        edit_line(&first_file, 3, |line| {
+            let mut entry: AuditEntry = serde_json::from_str(line).unwrap();
+            entry.hash = entry.compute_hash();
+            serde_json::to_string(&entry).unwrap()
+        });
+        let err = verify_audit_log(&path).unwrap_err();
+        assert!(matches!(
+            err,
+            AuditLogError::BrokenEntry { file, line: 4, reason: BrokenEntryReason::ChainMismatch }
+                if file == first_file
+        ));
+
+        // Removing the rotated file breaks the chain too
+        std::fs::remove_file(&first_file).unwrap();
+        let err = verify_audit_log(&path).unwrap_err();
+        assert!(matches!(err, AuditLogError::Io { file, .. } if file == first_file));
+    }
+}
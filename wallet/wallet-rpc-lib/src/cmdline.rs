@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 use common::chain::config::{regtest_options::ChainConfigOptions, ChainType};
 use crypto::key::hdkd::u31::U31;
@@ -26,7 +26,10 @@ use utils::{
 };
 use utils_networking::NetworkAddressWithPort;
 
-use crate::config::{WalletRpcConfig, WalletServiceConfig};
+use crate::{
+    audit_log::AuditLogConfig,
+    config::{WalletRpcConfig, WalletServiceConfig},
+};
 
 /// Service providing an RPC interface to a wallet
 #[derive(clap::Parser)]
@@ -36,12 +39,21 @@ pub struct WalletRpcDaemonArgs {
 }
 
 impl WalletRpcDaemonArgs {
-    pub fn into_config(self) -> Result<(WalletServiceConfig, WalletRpcConfig), ConfigError> {
+    pub fn into_action(self) -> Result<WalletRpcDaemonAction, ConfigError> {
         let Self { command } = self;
-        command.into_config()
+        command.into_action()
     }
 }
 
+/// What the daemon is requested to do
+pub enum WalletRpcDaemonAction {
+    /// Run the wallet service with the RPC interface
+    Run(WalletServiceConfig, WalletRpcConfig),
+
+    /// Check the hash chain of the audit log file
+    VerifyAuditLog(PathBuf),
+}
+
 #[derive(clap::Subcommand)]
 pub enum WalletRpcDaemonCommand {
     /// Run the mainnet wallet.
@@ -60,21 +72,32 @@ pub enum WalletRpcDaemonCommand {
         #[command(flatten)]
         regtest_opts: Box<ChainConfigOptions>,
     },
+
+    /// Check the hash chain of an audit log file and of the rotated files preceding it.
+    VerifyAuditLog {
+        /// The audit log file
+        #[arg(value_name("PATH"))]
+        path: PathBuf,
+    },
 }
 
 impl WalletRpcDaemonCommand {
-    fn into_config(self) -> Result<(WalletServiceConfig, WalletRpcConfig), ConfigError> {
-        match self {
-            Self::Mainnet(args) => args.into_config(ChainType::Mainnet),
-            Self::Testnet(args) => args.into_config(ChainType::Testnet),
+    fn into_action(self) -> Result<WalletRpcDaemonAction, ConfigError> {
+        let (ws_config, rpc_config) = match self {
+            Self::Mainnet(args) => args.into_config(ChainType::Mainnet)?,
+            Self::Testnet(args) => args.into_config(ChainType::Testnet)?,
             Self::Regtest { args, regtest_opts } => {
                 let (ws_config, rpc_config) = args.into_config(ChainType::Regtest)?;
                 let ws_config = ws_config
                     .with_regtest_options(*regtest_opts)
                     .map_err(ConfigError::InvalidRegtestOptions)?;
-                Ok((ws_config, rpc_config))
+                (ws_config, rpc_config)
             }
-        }
+            Self::VerifyAuditLog { path } => {
+                return Ok(WalletRpcDaemonAction::VerifyAuditLog(path))
+            }
+        };
+        Ok(WalletRpcDaemonAction::Run(ws_config, rpc_config))
     }
 }
 
@@ -149,6 +172,24 @@ pub struct WalletRpcDaemonChainArgs {
     #[arg(long, conflicts_with_all(["rpc_password", "rpc_username", "rpc_cookie_file"]))]
     rpc_no_authentication: bool,
 
+    /// How long, in seconds, the idempotency keys of the sent transactions are remembered.
+    /// Default is 24 hours.
+    #[arg(long, value_name("SECS"))]
+    idempotency_key_retention_secs: Option<u64>,
+
+    /// Password required by the RPC methods changing the spending policies of the accounts,
+    /// in addition to the RPC authorization. If not set, the policies can't be changed over RPC.
+    #[arg(long, value_name("PASS"))]
+    rpc_admin_password: Option<String>,
+
+    /// Append the RPC calls changing the wallet state to this hash-chained audit log file
+    #[arg(long, value_name("PATH"))]
+    audit_log_file: Option<PathBuf>,
+
+    /// The size in bytes after which the audit log file is rotated. Default is 64 MiB.
+    #[arg(long, value_name("BYTES"), requires("audit_log_file"))]
+    audit_log_max_file_size: Option<u64>,
+
     #[clap(flatten)]
     force_allow_run_as_root: utils::root_user::ForceRunAsRootOptions,
 }
@@ -171,6 +212,10 @@ impl WalletRpcDaemonChainArgs {
             rpc_username,
             rpc_password,
             rpc_no_authentication,
+            idempotency_key_retention_secs,
+            rpc_admin_password,
+            audit_log_file,
+            audit_log_max_file_size,
             cold_wallet,
             force_allow_run_as_root,
         } = self;
@@ -224,6 +269,22 @@ impl WalletRpcDaemonChainArgs {
             rpc_bind_address,
             *ws_config.chain_config.chain_type(),
         )?;
+        let rpc_config = match idempotency_key_retention_secs {
+            Some(secs) => rpc_config.with_idempotency_key_retention(Duration::from_secs(secs)),
+            None => rpc_config,
+        };
+        let rpc_config = match rpc_admin_password {
+            Some(password) => rpc_config.with_admin_password(password),
+            None => rpc_config,
+        };
+        let rpc_config = match audit_log_file {
+            Some(path) => rpc_config.with_audit_log(AuditLogConfig {
+                path,
+                max_file_size: audit_log_max_file_size
+                    .unwrap_or(AuditLogConfig::DEFAULT_MAX_FILE_SIZE),
+            }),
+            None => rpc_config,
+        };
 
         Ok((ws_config, rpc_config))
     }
@@ -260,6 +321,9 @@ pub fn make_wallet_config(
         WalletRpcConfig {
             bind_addr,
             auth_credentials,
+            idempotency_key_retention: WalletRpcConfig::DEFAULT_IDEMPOTENCY_KEY_RETENTION,
+            admin_password: None,
+            audit_log: None,
         }
     };
     Ok(rpc_config)
@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{net::SocketAddr, path::PathBuf, sync::Arc};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
 
 use common::chain::config::{
     regtest_options::{regtest_chain_config, ChainConfigOptions},
@@ -22,6 +22,8 @@ use common::chain::config::{
 use crypto::key::hdkd::u31::U31;
 use rpc::{rpc_creds::RpcCreds, RpcAuthData};
 
+use crate::audit_log::AuditLogConfig;
+
 #[derive(Clone)]
 pub enum NodeRpc {
     ColdWallet,
@@ -134,9 +136,21 @@ pub struct WalletRpcConfig {
 
     /// Authentication credentials needed to use the interface
     pub auth_credentials: Option<RpcCreds>,
+
+    /// How long the idempotency keys of the sent transactions are remembered
+    pub idempotency_key_retention: Duration,
+
+    /// The password required to change the spending policies of the accounts, separate from
+    /// the authentication credentials. The policies can't be changed over RPC if not set.
+    pub admin_password: Option<String>,
+
+    /// The log of the calls changing the wallet state, disabled if not set
+    pub audit_log: Option<AuditLogConfig>,
 }
 
 impl WalletRpcConfig {
+    pub const DEFAULT_IDEMPOTENCY_KEY_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
     pub fn default_port(chain_type: ChainType) -> u16 {
         match chain_type {
             ChainType::Mainnet => 3034,
@@ -145,4 +159,19 @@ impl WalletRpcConfig {
             ChainType::Signet => 33034,
         }
     }
+
+    pub fn with_idempotency_key_retention(mut self, retention: Duration) -> Self {
+        self.idempotency_key_retention = retention;
+        self
+    }
+
+    pub fn with_admin_password(mut self, admin_password: String) -> Self {
+        self.admin_password = Some(admin_password);
+        self
+    }
+
+    pub fn with_audit_log(mut self, audit_log: AuditLogConfig) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
 }
@@ -13,6 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod audit_log;
 pub mod cmdline;
 pub mod config;
 mod rpc;
@@ -30,6 +31,7 @@ use wallet_controller::{NodeInterface, NodeRpcClient};
 
 use std::{fmt::Debug, time::Duration};
 
+use cmdline::WalletRpcDaemonAction;
 pub use config::WalletServiceConfig;
 use config::{NodeRpc, WalletRpcConfig};
 use logging::log;
@@ -47,6 +49,25 @@ pub enum StartupError<N: NodeInterface> {
     Rpc(anyhow::Error),
 }
 
+/// Run the action requested on the wallet daemon command line
+pub async fn run_action(action: WalletRpcDaemonAction) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        WalletRpcDaemonAction::Run(wallet_config, rpc_config) => {
+            run(wallet_config, rpc_config).await
+        }
+        WalletRpcDaemonAction::VerifyAuditLog(path) => {
+            let summary = audit_log::verify_audit_log(&path)?;
+            println!(
+                "The audit log is intact: {} entries in {} files, last hash {}",
+                summary.entries,
+                summary.files.len(),
+                summary.last_hash
+            );
+            Ok(())
+        }
+    }
+}
+
 /// Run a wallet daemon with RPC interface
 pub async fn run(
     wallet_config: WalletServiceConfig,
@@ -29,20 +29,27 @@ use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress
 use rpc::types::RpcHexString;
 use wallet::account::TxInfo;
 use wallet_controller::{
-    types::{BlockInfo, CreatedBlockInfo, GenericTokenTransfer, SeedWithPassPhrase, WalletInfo},
+    types::{
+        BlockInfo, CreatedBlockInfo, GenericTokenTransfer, SeedWithPassPhrase,
+        WalletFingerprintInfo, WalletInfo, WalletVersionInfo,
+    },
     ConnectedPeer,
 };
 use wallet_types::with_locked::WithLocked;
 
 use crate::types::{
-    AccountArg, AddressInfo, AddressWithUsageInfo, Balances, ChainInfo, ComposedTransaction,
-    CreatedWallet, DelegationInfo, HexEncoded, JsonValue, LegacyVrfPublicKeyInfo,
-    MaybeSignedTransaction, NewAccountInfo, NewDelegation, NewTransaction, NftMetadata,
-    NodeVersion, PoolInfo, PublicKeyInfo, RpcAmountIn, RpcHashedTimelockContract,
-    RpcInspectTransaction, RpcStandaloneAddresses, RpcTokenId, RpcUtxoOutpoint, RpcUtxoState,
-    RpcUtxoType, SendTokensFromMultisigAddressResult, StakePoolBalance, StakingStatus,
-    StandaloneAddressWithDetails, TokenMetadata, TransactionOptions, TxOptionsOverrides,
-    VrfPublicKeyInfo,
+    AccountArg, AccountStakingStatus, AddressInfo, AddressWithUsageInfo, Balances,
+    BalancesByConfirmations, ChainInfo, ComposedTransaction, CreatedWallet, DecodedPaymentUri,
+    DecommissionPoolTransaction, DelegationInfo, DuplicateOutputs, ExportedAddressSetInfo,
+    HexEncoded, ImportedAddressSetInfo, InclusionStatus, JsonValue, LegacyVrfPublicKeyInfo,
+    MaybeSignedTransaction, NewAccountInfo, NewDelegation, NewPaymentUri, NewTransaction,
+    NftMetadata, NodeConnectionStatus, NodeVersion, PoolInfo, ProofOfReservesInfo,
+    ProofOfReservesReport, PublicKeyInfo, RpcAmountIn, RpcHashedTimelockContract,
+    RpcInspectTransaction, RpcSearchMatch, RpcSpendingPolicy, RpcStakePoolInfo,
+    RpcStandaloneAddresses, RpcTokenId, RpcUtxoOutpoint, RpcUtxoState, RpcUtxoType,
+    SendToManyOutput, SendToManyTransaction, SendTokensFromMultisigAddressResult, SendTransaction,
+    StakePoolBalance, StakingStatus, StandaloneAddressWithDetails, TokenMetadata,
+    TransactionOptions, TxOptionsOverrides, VrfPublicKeyInfo, XpubInfo,
 };
 
 #[rpc::rpc(server)]
@@ -81,6 +88,16 @@ trait ColdWalletRpc {
         force_migrate_wallet_type: Option<bool>,
     ) -> rpc::RpcResult<()>;
 
+    /// Create a new wallet file from an encrypted backup made with `wallet_backup` and open it.
+    /// The wallet rescans the blockchain to rebuild its transaction history and balances.
+    #[method(name = "wallet_restore_from_backup")]
+    async fn restore_wallet_from_backup(
+        &self,
+        backup_path: String,
+        wallet_path: String,
+        backup_password: Option<String>,
+    ) -> rpc::RpcResult<()>;
+
     /// Close the currently open wallet file
     #[method(name = "wallet_close")]
     async fn close_wallet(&self) -> rpc::RpcResult<()>;
@@ -114,6 +131,24 @@ trait ColdWalletRpc {
     #[method(name = "wallet_purge_seed_phrase")]
     async fn purge_seed_phrase(&self) -> rpc::RpcResult<Option<SeedWithPassPhrase>>;
 
+    /// Show the fingerprint of the loaded wallet's root key and whether a passphrase was used.
+    /// Restoring from the same seed phrase with a different passphrase gives a different fingerprint.
+    #[method(name = "wallet_fingerprint")]
+    async fn wallet_fingerprint(&self) -> rpc::RpcResult<WalletFingerprintInfo>;
+
+    /// Show the version of the wallet software and the schema version of the open wallet file,
+    /// to check whether the wallet file can be opened by another version of the software.
+    /// Fails with the error code 503 if no wallet is open.
+    #[method(name = "wallet_version")]
+    async fn wallet_version(&self) -> rpc::RpcResult<WalletVersionInfo>;
+
+    /// Write an encrypted backup of the loaded wallet to a new file.
+    /// The backup contains the keys, accounts, labels, wallet created transactions and settings,
+    /// but not the data that can be recovered by rescanning the blockchain.
+    /// Without a password, the private keys are only protected by the wallet's own encryption.
+    #[method(name = "wallet_backup")]
+    async fn backup_wallet(&self, path: String, password: Option<String>) -> rpc::RpcResult<()>;
+
     /// Set the lookahead size for key generation.
     ///
     /// The lookahead size, also known as the gap limit, determines the number of addresses
@@ -138,6 +173,62 @@ trait ColdWalletRpc {
         account: AccountArg,
     ) -> rpc::RpcResult<Vec<AddressWithUsageInfo>>;
 
+    /// Show the change addresses issued by the wallet with their usage state.
+    /// The change addresses are recovered by scanning the blockchain with the same lookahead
+    /// as the receive addresses, so the wallet's lookahead size must be larger than the gap
+    /// between the used change addresses.
+    #[method(name = "change_address_show")]
+    async fn get_issued_change_addresses(
+        &self,
+        account: AccountArg,
+    ) -> rpc::RpcResult<Vec<AddressWithUsageInfo>>;
+
+    /// Export the selected account's extended public key, serialized according to BIP32.
+    /// It can be used to derive the account's addresses without the private keys.
+    #[method(name = "account_export_xpub")]
+    async fn export_xpub(&self, account: AccountArg) -> rpc::RpcResult<XpubInfo>;
+
+    /// Export the output descriptor of the selected account's receiving addresses,
+    /// e.g. `pkh(xpub.../0/*)`. The supported descriptor types are `pkh` and `pk`.
+    #[method(name = "account_export_descriptor")]
+    async fn export_descriptor(
+        &self,
+        account: AccountArg,
+        descriptor_type: String,
+    ) -> rpc::RpcResult<String>;
+
+    /// Write the first `count` receive and change public keys of the selected account to a new file,
+    /// signed with the account's private key, so that a hot wallet can watch their addresses
+    /// with `account_import_address_set`. The private keys never leave the wallet,
+    /// but the wallet must be unlocked to sign the file.
+    #[method(name = "account_export_address_set")]
+    async fn export_address_set(
+        &self,
+        account: AccountArg,
+        path: String,
+        count: u32,
+    ) -> rpc::RpcResult<ExportedAddressSetInfo>;
+
+    /// Add, replace or delete the label of an address.
+    /// The address doesn't have to belong to the wallet.
+    /// Not specifying a label will remove the existing one.
+    #[method(name = "address_set_label")]
+    async fn set_address_label(
+        &self,
+        account: AccountArg,
+        address: RpcAddress<Destination>,
+        label: Option<String>,
+    ) -> rpc::RpcResult<()>;
+
+    /// Search the transactions and the addresses of the selected account
+    /// by their label, transaction ID or address.
+    #[method(name = "wallet_search")]
+    async fn search(
+        &self,
+        account: AccountArg,
+        text: String,
+    ) -> rpc::RpcResult<Vec<RpcSearchMatch>>;
+
     /// Show standalone added addresses with their labels.
     #[method(name = "standalone_address_show")]
     async fn get_standalone_addresses(
@@ -153,9 +244,29 @@ trait ColdWalletRpc {
         address: RpcAddress<Destination>,
     ) -> rpc::RpcResult<StandaloneAddressWithDetails>;
 
-    /// Generate a new unused address
+    /// Generate a new address that has never received any funds, skipping over the already used ones.
+    /// If allow_reuse is set, the last issued address is returned even if it has already been used.
     #[method(name = "address_new")]
-    async fn issue_address(&self, account: AccountArg) -> rpc::RpcResult<AddressInfo>;
+    async fn issue_address(
+        &self,
+        account: AccountArg,
+        allow_reuse: Option<bool>,
+    ) -> rpc::RpcResult<AddressInfo>;
+
+    /// Generate a new unused address and return a payment URI requesting the given amount of coins
+    /// to be sent to it, optionally with a label describing the recipient.
+    #[method(name = "payment_uri_new")]
+    async fn new_payment_uri(
+        &self,
+        account: AccountArg,
+        amount: RpcAmountIn,
+        label: Option<String>,
+    ) -> rpc::RpcResult<NewPaymentUri>;
+
+    /// Decode a payment URI, checking that its address belongs to the current network
+    /// and that the amount doesn't have more decimal places than the coin supports.
+    #[method(name = "payment_uri_decode")]
+    async fn decode_payment_uri(&self, uri: String) -> rpc::RpcResult<DecodedPaymentUri>;
 
     /// Reveal the public key behind this address in hex encoding and address encoding.
     /// Note that this isn't a normal address to be used in transactions.
@@ -198,6 +309,8 @@ trait ColdWalletRpc {
     /// The input is a special format of the transaction serialized to hex. This format is automatically used in this wallet
     /// in functions such as staking-decommission-pool-request. Once all signatures are complete, the result can be broadcast
     /// to the network.
+    /// If the input is a plain transaction, only the inputs owned by the account are signed and it is an error
+    /// if none of them belong to the account.
     async fn sign_raw_transaction(
         &self,
         account: AccountArg,
@@ -271,6 +384,38 @@ trait WalletRpc {
         name: Option<String>,
     ) -> rpc::RpcResult<NewAccountInfo>;
 
+    /// Sets the spending policy of the selected account, replacing the existing one.
+    /// The limits apply to the coins sent out of the account: `max_per_tx` to each transaction
+    /// and `daily_cap` to all the transactions broadcast within the last 24 hours.
+    /// If `allowed_addresses` is specified, coins and tokens can only be sent to those addresses.
+    /// Not specifying any of the limits removes the policy.
+    /// Requires the admin password that the wallet RPC server was started with.
+    #[method(name = "account_set_spending_policy")]
+    async fn set_spending_policy(
+        &self,
+        admin_password: String,
+        account: AccountArg,
+        max_per_tx: Option<RpcAmountIn>,
+        daily_cap: Option<RpcAmountIn>,
+        allowed_addresses: Option<Vec<RpcAddress<Destination>>>,
+    ) -> rpc::RpcResult<()>;
+
+    /// Returns the spending policy of the selected account along with the amount of coins
+    /// sent within the last 24 hours and the remaining daily allowance.
+    #[method(name = "account_spending_policy")]
+    async fn spending_policy(&self, account: AccountArg) -> rpc::RpcResult<RpcSpendingPolicy>;
+
+    /// Set the number of confirmations after which the received funds are considered confirmed.
+    ///
+    /// Once set, the transactions only spend the outputs that have at least this number of
+    /// confirmations, unless the `spend_pending_utxos` option is given.
+    #[method(name = "wallet_set_confirmation_threshold")]
+    async fn set_confirmation_threshold(&self, confirmation_threshold: u64) -> rpc::RpcResult<()>;
+
+    /// Returns the number of confirmations after which the received funds are considered confirmed.
+    #[method(name = "wallet_confirmation_threshold")]
+    async fn confirmation_threshold(&self) -> rpc::RpcResult<u64>;
+
     /// Add, rename or delete a label to an already added standalone address.
     /// Specifying a label will add or replace the existing one,
     /// and not specifying a label will remove the existing one.
@@ -292,6 +437,30 @@ trait WalletRpc {
         no_rescan: Option<bool>,
     ) -> rpc::RpcResult<()>;
 
+    /// Watch the addresses from a file written by `account_export_address_set` of a cold wallet
+    /// with the selected account, after checking the file's signature.
+    /// The wallet can't derive any addresses beyond the imported ones, so more addresses should
+    /// be exported when few unused ones remain.
+    /// The wallet is rescanned if any new addresses are added.
+    #[method(name = "account_import_address_set")]
+    async fn import_address_set(
+        &self,
+        account: AccountArg,
+        path: String,
+    ) -> rpc::RpcResult<ImportedAddressSetInfo>;
+
+    /// Write a proof that the selected account controls its confirmed transfer utxos, including
+    /// the multisig ones, at the current best block to a new file, signing the given message
+    /// with each utxo. The file can be checked by any node with `node_verify_proof_of_reserves`.
+    /// Multisig utxos are signed with the keys this wallet has, which may be fewer than required.
+    #[method(name = "account_generate_proof_of_reserves")]
+    async fn generate_proof_of_reserves(
+        &self,
+        account: AccountArg,
+        message: String,
+        path: String,
+    ) -> rpc::RpcResult<ProofOfReservesInfo>;
+
     /// Add a new standalone private key not derived from the selected account's key chain to be watched
     #[method(name = "standalone_add_private_key_from_hex")]
     async fn add_standalone_private_key(
@@ -333,6 +502,17 @@ trait WalletRpc {
         with_locked: Option<WithLocked>,
     ) -> rpc::RpcResult<Balances>;
 
+    /// Get the balance in the selected account split into the confirmed funds, the pending funds
+    /// that are in the chain but don't have enough confirmations yet, and the unconfirmed funds
+    /// in the mempool. The wallet's confirmation threshold is used unless one is given.
+    #[method(name = "account_balance_by_confirmations")]
+    async fn get_balance_by_confirmations(
+        &self,
+        account: AccountArg,
+        confirmation_threshold: Option<u64>,
+        with_locked: Option<WithLocked>,
+    ) -> rpc::RpcResult<BalancesByConfirmations>;
+
     /// Lists all the utxos owned by this account
     #[method(name = "account_utxos")]
     async fn get_utxos(&self, account: AccountArg) -> rpc::RpcResult<Vec<JsonValue>>;
@@ -348,6 +528,7 @@ trait WalletRpc {
 
     /// Send a given coin amount to a given address. The wallet will automatically calculate the required information
     /// Optionally, one can also mention the utxos to be used.
+    /// The result lists the addresses of this wallet that had already received funds before.
     #[method(name = "address_send")]
     async fn send_coins(
         &self,
@@ -356,7 +537,24 @@ trait WalletRpc {
         amount: RpcAmountIn,
         selected_utxos: Vec<RpcUtxoOutpoint>,
         options: TransactionOptions,
-    ) -> rpc::RpcResult<NewTransaction>;
+    ) -> rpc::RpcResult<SendTransaction>;
+
+    /// Send coins or tokens to many addresses in a single transaction, each with its own amount.
+    /// An output pays in coins, or in the token if the token id is specified.
+    /// By default, the payments of the same currency to the same address are merged into a single
+    /// output and such addresses are listed in the result; duplicate_outputs can be set to Allow
+    /// to make a separate output for each payment, or to Reject to refuse them instead.
+    /// Returns the first invalid address in case of an error.
+    /// The result shows the total fee and the coin fee per recipient, and lists the addresses
+    /// of this wallet that had already received funds before.
+    #[method(name = "address_send_to_many")]
+    async fn send_to_many_addresses(
+        &self,
+        account: AccountArg,
+        outputs: Vec<SendToManyOutput>,
+        duplicate_outputs: Option<DuplicateOutputs>,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<SendToManyTransaction>;
 
     /// Sweep all spendable coins or tokens from an address or addresses to a given address.
     /// Spendable coins are any coins that are not locked, and tokens that are not frozen or locked.
@@ -437,6 +635,19 @@ trait WalletRpc {
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction>;
 
+    /// Create a transaction that decommissions a staking pool, given its id, returning the pledge
+    /// and the staking proceeds to the pool's decommission key, which must be owned by the selected
+    /// account in this wallet. The created transaction is not broadcast by this function.
+    /// The balance delegated to the pool is returned as well; after the pool is decommissioned,
+    /// the delegators can only withdraw their coins subject to the maturity timelock.
+    #[method(name = "staking_create_decommission_pool_transaction")]
+    async fn create_decommission_pool_transaction(
+        &self,
+        account: AccountArg,
+        pool_id: RpcAddress<PoolId>,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<DecommissionPoolTransaction>;
+
     /// Create a request to decommission a pool. This assumes that the decommission key is owned
     /// by another wallet. The output of this command should be passed to account-sign-raw-transaction
     /// in the wallet that owns the decommission key. The result from signing, assuming success, can
@@ -463,6 +674,17 @@ trait WalletRpc {
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewDelegation>;
 
+    /// Create a delegation to a given pool id, owned by a new address of the account,
+    /// and send the given amount of coins to it to be staked, all in a single transaction.
+    #[method(name = "delegation_create_and_stake")]
+    async fn create_delegation_and_stake(
+        &self,
+        account: AccountArg,
+        pool_id: RpcAddress<PoolId>,
+        amount: RpcAmountIn,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<NewDelegation>;
+
     /// Send coins to a delegation id to be staked
     #[method(name = "delegation_stake")]
     async fn delegate_staking(
@@ -497,6 +719,15 @@ trait WalletRpc {
     #[method(name = "staking_status")]
     async fn staking_status(&self, account: AccountArg) -> rpc::RpcResult<StakingStatus>;
 
+    /// Show the health of the pools owned by the selected account in this wallet: the number
+    /// of blocks each of them produced over the last 24 hours compared to the number of blocks
+    /// expected from its share of the stake.
+    #[method(name = "staking_pools_status")]
+    async fn staking_pools_status(
+        &self,
+        account: AccountArg,
+    ) -> rpc::RpcResult<AccountStakingStatus>;
+
     /// List ids of pools that are controlled by the selected account in this wallet
     #[method(name = "staking_list_pools")]
     async fn list_pools(&self, account: AccountArg) -> rpc::RpcResult<Vec<PoolInfo>>;
@@ -515,6 +746,12 @@ trait WalletRpc {
         pool_id: RpcAddress<PoolId>,
     ) -> rpc::RpcResult<StakePoolBalance>;
 
+    /// List all the staking pools known to the node with their pledge, total stake,
+    /// cost per block, margin ratio and the number of blocks each of them produced
+    /// among the latest 1000 blocks
+    #[method(name = "staking_list_all_pools")]
+    async fn list_all_staking_pools(&self) -> rpc::RpcResult<Vec<RpcStakePoolInfo>>;
+
     /// List delegation ids controlled by the selected account in this wallet with their balances
     #[method(name = "delegation_list_ids")]
     async fn list_delegation_ids(&self, account: AccountArg)
@@ -724,6 +961,10 @@ trait WalletRpc {
     #[method(name = "node_peer_count")]
     async fn peer_count(&self) -> rpc::RpcResult<usize>;
 
+    /// Get the number of connected peers and whether the node has finished the initial block download
+    #[method(name = "node_connection_status")]
+    async fn node_connection_status(&self) -> rpc::RpcResult<NodeConnectionStatus>;
+
     /// Get connected peers in the node
     #[method(name = "node_list_connected_peers")]
     async fn connected_peers(&self) -> rpc::RpcResult<Vec<ConnectedPeer>>;
@@ -741,6 +982,9 @@ trait WalletRpc {
     async fn remove_reserved_peer(&self, address: String) -> rpc::RpcResult<()>;
 
     /// Submit a block to be included in the chain
+    ///
+    /// If the block is rejected, the error data contains the rejection category,
+    /// the offending transaction id (if known) and a hint on how to proceed.
     #[method(name = "node_submit_block")]
     async fn submit_block(&self, block: HexEncoded<Block>) -> rpc::RpcResult<()>;
 
@@ -758,6 +1002,17 @@ trait WalletRpc {
         transaction_id: HexEncoded<Id<Transaction>>,
     ) -> rpc::RpcResult<()>;
 
+    /// Add, replace or delete the label of a transaction of the selected account.
+    /// Not specifying a label will remove the existing one.
+    /// Labels are only stored in the wallet and are kept when the wallet is rescanned.
+    #[method(name = "transaction_set_label")]
+    async fn set_transaction_label(
+        &self,
+        account: AccountArg,
+        transaction_id: HexEncoded<Id<Transaction>>,
+        label: Option<String>,
+    ) -> rpc::RpcResult<()>;
+
     /// List the pending transactions that can be abandoned
     #[method(name = "transaction_list_pending")]
     async fn list_pending_transactions(
@@ -798,6 +1053,16 @@ trait WalletRpc {
         transaction_id: Id<Transaction>,
     ) -> rpc::RpcResult<HexEncoded<SignedTransaction>>;
 
+    /// Check whether a transaction of the selected account has been included in the mainchain
+    /// or is still waiting in the mempool. For a confirmed transaction, the merkle proof of its
+    /// inclusion in the block is provided.
+    #[method(name = "transaction_verify_inclusion")]
+    async fn verify_transaction_inclusion(
+        &self,
+        account: AccountArg,
+        transaction_id: Id<Transaction>,
+    ) -> rpc::RpcResult<InclusionStatus>;
+
     /// Compose a new transaction from the specified outputs and selected utxos
     /// The transaction is returned in a hex encoded form that can be passed to account-sign-raw-transaction
     /// and also prints the fees that will be paid by the transaction
@@ -825,6 +1090,15 @@ trait WalletRpc {
         block_height: BlockHeight,
     ) -> rpc::RpcResult<Option<Id<GenBlock>>>;
 
+    /// Check a proof of reserves file written by `account_generate_proof_of_reserves` against
+    /// the node's mainchain, reporting the status of each utxo, whether it was spent since,
+    /// and the total provable coins and tokens.
+    #[method(name = "node_verify_proof_of_reserves")]
+    async fn node_verify_proof_of_reserves(
+        &self,
+        path: String,
+    ) -> rpc::RpcResult<ProofOfReservesReport>;
+
     /// Generate a block with the given transactions to the specified
     /// reward destination. If transactions are None, the block will be
     /// generated with available transactions in the mempool
@@ -27,24 +27,29 @@ use std::{
 };
 
 use chainstate::{tx_verifier::check_transaction, ChainInfo, TokenIssuanceError};
-use crypto::key::{hdkd::u31::U31, PrivateKey, PublicKey};
+use crypto::{
+    key::{
+        hdkd::{child_number::ChildNumber, u31::U31},
+        PrivateKey, PublicKey,
+    },
+    util::eq::SliceEqualityCheckMethod,
+};
+use logging::log;
 use mempool::tx_accumulator::PackingStrategy;
 use mempool_types::tx_options::TxOptionsOverrides;
 use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress, PeerId};
 use serialization::{hex_encoded::HexEncoded, Decode, DecodeAll};
+use tokio::sync::mpsc;
 use types::RpcHashedTimelockContract;
 use utils::{ensure, shallow_clone::ShallowClone};
 use utils_networking::IpOrSocketAddress;
 use wallet::{
-    account::{
-        currency_grouper::Currency, transaction_list::TransactionList, PoolData, TransactionToSign,
-        TxInfo,
-    },
+    account::{transaction_list::TransactionList, PoolData, TransactionToSign, TxInfo},
     WalletError,
 };
 
 use common::{
-    address::Address,
+    address::{payment_uri::PaymentUri, Address},
     chain::{
         block::timestamp::BlockTimestamp,
         classic_multisig::ClassicMultisigChallenge,
@@ -68,27 +73,34 @@ pub use interface::{
 };
 pub use rpc::{rpc_creds::RpcCreds, Rpc};
 use wallet_controller::{
+    synced_controller::collect_send_to_many_payments,
     types::{
-        Balances, BlockInfo, CreatedBlockInfo, GenericTokenTransfer, InspectTransaction,
-        SeedWithPassPhrase, TransactionToInspect, WalletInfo,
+        Balances, BalancesByConfirmations, BlockInfo, CreatedBlockInfo, DuplicateOutputs,
+        GenericTokenTransfer, InclusionStatus, InspectTransaction, SeedWithPassPhrase,
+        TransactionToInspect, WalletFingerprintInfo, WalletInfo, WalletVersionInfo, WatchEvent,
+        WatchEventTracker,
     },
-    ConnectedPeer, ControllerConfig, ControllerError, NodeInterface, UtxoState, UtxoStates,
-    UtxoType, UtxoTypes, DEFAULT_ACCOUNT_INDEX,
+    ConnectedPeer, ControllerConfig, ControllerError, NodeInterface, UtxoSelectionStrategy,
+    UtxoState, UtxoStates, UtxoType, UtxoTypes, DEFAULT_ACCOUNT_INDEX,
 };
 use wallet_types::{
-    account_info::StandaloneAddressDetails, seed_phrase::StoreSeedPhrase,
-    signature_status::SignatureStatus, wallet_tx::TxData, with_locked::WithLocked,
+    account_info::StandaloneAddressDetails, currency::Currency, label::Label,
+    seed_phrase::StoreSeedPhrase, signature_status::SignatureStatus,
+    spending_policy::SpendingPolicy, wallet_tx::TxData, with_locked::WithLocked,
 };
 
-use crate::{service::CreatedWallet, WalletHandle, WalletRpcConfig};
+use crate::{audit_log::AuditLog, service::CreatedWallet, Event, WalletHandle, WalletRpcConfig};
 
 pub use self::types::RpcError;
 use self::types::{
-    AddressInfo, AddressWithUsageInfo, DelegationInfo, LegacyVrfPublicKeyInfo, NewAccountInfo,
-    NewTransaction, PoolInfo, PublicKeyInfo, RpcAddress, RpcAmountIn, RpcHexString,
-    RpcStandaloneAddress, RpcStandaloneAddressDetails, RpcStandaloneAddresses,
-    RpcStandalonePrivateKeyAddress, RpcTokenId, RpcUtxoOutpoint, StakingStatus,
-    StandaloneAddressWithDetails, VrfPublicKeyInfo,
+    AccountStakingStatus, AddressInfo, AddressWithUsageInfo, DecodedPaymentUri,
+    DecommissionPoolTransaction, DelegationInfo, ExportedAddressSetInfo, ImportedAddressSetInfo,
+    LegacyVrfPublicKeyInfo, NewAccountInfo, NewPaymentUri, NewTransaction, NodeConnectionStatus,
+    PoolInfo, ProofOfReservesInfo, ProofOfReservesReport, PublicKeyInfo, RpcAddress, RpcAmountIn,
+    RpcHexString, RpcSearchMatch, RpcSpendingPolicy, RpcStakePoolInfo, RpcStandaloneAddress,
+    RpcStandaloneAddressDetails, RpcStandaloneAddresses, RpcStandalonePrivateKeyAddress,
+    RpcTokenId, RpcUtxoOutpoint, SendResult, SendToManyOutput, SendToManyResult, StakingStatus,
+    StandaloneAddressWithDetails, VrfPublicKeyInfo, XpubInfo,
 };
 
 #[derive(Clone)]
@@ -96,6 +108,9 @@ pub struct WalletRpc<N: Clone> {
     wallet: WalletHandle<N>,
     node: N,
     chain_config: Arc<ChainConfig>,
+    idempotency_key_retention: Duration,
+    admin_password: Option<String>,
+    audit_log: Option<Arc<AuditLog>>,
 }
 
 type WRpcResult<T, N> = Result<T, RpcError<N>>;
@@ -106,9 +121,39 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             wallet,
             node,
             chain_config,
+            idempotency_key_retention: WalletRpcConfig::DEFAULT_IDEMPOTENCY_KEY_RETENTION,
+            admin_password: None,
+            audit_log: None,
         }
     }
 
+    pub fn with_idempotency_key_retention(mut self, retention: Duration) -> Self {
+        self.idempotency_key_retention = retention;
+        self
+    }
+
+    pub fn with_admin_password(mut self, admin_password: Option<String>) -> Self {
+        self.admin_password = admin_password;
+        self
+    }
+
+    pub fn with_audit_log(mut self, audit_log: Option<Arc<AuditLog>>) -> Self {
+        self.audit_log = audit_log;
+        self
+    }
+
+    fn check_admin_password(&self, password: &str) -> WRpcResult<(), N> {
+        let admin_password = self.admin_password.as_ref().ok_or(RpcError::AdminMethodsDisabled)?;
+        ensure!(
+            SliceEqualityCheckMethod::timing_resistant_equal(
+                admin_password.as_bytes(),
+                password.as_bytes()
+            ),
+            RpcError::InvalidAdminPassword
+        );
+        Ok(())
+    }
+
     pub async fn closed(&self) {
         self.wallet.closed().await
     }
@@ -158,6 +203,24 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             .await??)
     }
 
+    pub async fn restore_wallet_from_backup(
+        &self,
+        backup_path: PathBuf,
+        wallet_path: PathBuf,
+        backup_password: Option<String>,
+    ) -> WRpcResult<(), N> {
+        Ok(self
+            .wallet
+            .manage_async(move |wallet_manager| {
+                Box::pin(async move {
+                    wallet_manager
+                        .restore_wallet_from_backup(backup_path, wallet_path, backup_password)
+                        .await
+                })
+            })
+            .await??)
+    }
+
     pub async fn close_wallet(&self) -> WRpcResult<(), N> {
         Ok(self
             .wallet
@@ -177,6 +240,14 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             .await?
     }
 
+    pub async fn confirmation_threshold(&self) -> WRpcResult<u64, N> {
+        self.wallet.call(|w| w.get_confirmation_threshold()).await?
+    }
+
+    pub async fn set_confirmation_threshold(&self, threshold: u64) -> WRpcResult<(), N> {
+        self.wallet.call(move |w| w.set_confirmation_threshold(threshold)).await?
+    }
+
     pub async fn encrypt_private_keys(&self, password: String) -> WRpcResult<(), N> {
         self.wallet.call(|w| w.encrypt_wallet(&Some(password))).await?
     }
@@ -198,6 +269,80 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         Ok(BlockInfo::from_tuple(res))
     }
 
+    /// Subscribe to the wallet events in the form shown to a user watching the wallet activity.
+    ///
+    /// The events are produced until the returned receiver is dropped or the wallet service
+    /// stops.
+    pub async fn watch_events(&self) -> WRpcResult<mpsc::UnboundedReceiver<WatchEvent>, N> {
+        let mut wallet_events = self.wallet.subscribe().await?;
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        let this = self.clone();
+
+        tokio::spawn(async move {
+            let mut tracker = WatchEventTracker::new();
+            loop {
+                let event = tokio::select! {
+                    event = wallet_events.recv() => match event {
+                        Some(event) => event,
+                        None => break,
+                    },
+                    () = event_tx.closed() => break,
+                };
+
+                match this.make_watch_event(&mut tracker, event).await {
+                    Ok(Some(watch_event)) => {
+                        if event_tx.send(watch_event).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(err) => log::warn!("Failed to process a wallet event: {err}"),
+                }
+            }
+        });
+
+        Ok(event_rx)
+    }
+
+    async fn make_watch_event(
+        &self,
+        tracker: &mut WatchEventTracker,
+        event: Event,
+    ) -> WRpcResult<Option<WatchEvent>, N> {
+        let to_account_index =
+            |account_idx| U31::from_u32(account_idx).expect("the event has a valid account index");
+
+        let watch_event = match event {
+            Event::NewBlock {} => {
+                let (block_id, block_height) =
+                    self.wallet.call(|w| Ok::<_, RpcError<N>>(w.best_block())).await??;
+                tracker.new_block(block_height, block_id)
+            }
+            Event::TxUpdated {
+                account_idx,
+                tx_id,
+                state: _,
+            } => {
+                let account_index = to_account_index(account_idx);
+                let info = self
+                    .wallet
+                    .call(move |controller| {
+                        controller.readonly_controller(account_index).get_transaction_info(tx_id)
+                    })
+                    .await??;
+                Some(tracker.transaction_updated(account_index, info))
+            }
+            Event::RewardAdded { account_idx, data } => Some(WatchEvent::BlockStaked {
+                account_index: to_account_index(account_idx),
+                block_height: data.height(),
+                block_id: *data.block_id(),
+            }),
+            Event::TxDropped { .. } | Event::RewardDropped { .. } => None,
+        };
+
+        Ok(watch_event)
+    }
+
     pub async fn generate_block(
         &self,
         account_index: U31,
@@ -268,6 +413,53 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         Ok(NewAccountInfo::new(num, name))
     }
 
+    pub async fn set_spending_policy(
+        &self,
+        admin_password: String,
+        account_index: U31,
+        max_per_tx: Option<RpcAmountIn>,
+        daily_cap: Option<RpcAmountIn>,
+        allowed_addresses: Option<Vec<RpcAddress<Destination>>>,
+    ) -> WRpcResult<(), N> {
+        self.check_admin_password(&admin_password)?;
+
+        let decimals = self.chain_config.coin_decimals();
+        let to_amount =
+            |amount: RpcAmountIn| amount.to_amount(decimals).ok_or(RpcError::InvalidCoinAmount);
+        let allowed_destinations = allowed_addresses
+            .map(|addresses| {
+                addresses
+                    .into_iter()
+                    .map(|address| {
+                        address
+                            .decode_object(&self.chain_config)
+                            .map_err(|_| RpcError::InvalidAddress)
+                    })
+                    .collect::<WRpcResult<BTreeSet<_>, N>>()
+            })
+            .transpose()?;
+        let policy = SpendingPolicy {
+            max_per_tx: max_per_tx.map(to_amount).transpose()?,
+            daily_cap: daily_cap.map(to_amount).transpose()?,
+            allowed_destinations,
+        };
+
+        self.wallet
+            .call(move |w| w.set_spending_policy(account_index, policy))
+            .await??;
+        Ok(())
+    }
+
+    pub async fn spending_policy(&self, account_index: U31) -> WRpcResult<RpcSpendingPolicy, N> {
+        let (policy, sent_in_period) =
+            self.wallet.call(move |w| w.get_spending_policy(account_index)).await??;
+        Ok(RpcSpendingPolicy::new(
+            policy,
+            sent_in_period,
+            &self.chain_config,
+        )?)
+    }
+
     pub async fn standalone_address_label_rename(
         &self,
         account_index: U31,
@@ -280,6 +472,9 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: false,
+            idempotent_request: None,
         }; // irrelevant for issuing addresses
         self.wallet
             .call_async(move |w| {
@@ -314,6 +509,9 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: false,
+            idempotent_request: None,
         }; // irrelevant for issuing addresses
         self.wallet
             .call_async(move |w| {
@@ -344,6 +542,9 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: false,
+            idempotent_request: None,
         }; // irrelevant for issuing addresses
         self.wallet
             .call_async(move |w| {
@@ -375,6 +576,9 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: false,
+            idempotent_request: None,
         }; // irrelevant for issuing addresses
         let min_required_signatures =
             NonZeroU8::new(min_required_signatures).ok_or(RpcError::InvalidMultisigMinSignature)?;
@@ -429,20 +633,54 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         Ok(address.to_string())
     }
 
-    pub async fn issue_address(&self, account_index: U31) -> WRpcResult<AddressInfo, N> {
+    pub async fn issue_address(
+        &self,
+        account_index: U31,
+        allow_reuse: bool,
+    ) -> WRpcResult<AddressInfo, N> {
+        let (child_number, destination) = self.new_address(account_index, allow_reuse).await?;
+        Ok(AddressInfo::new(child_number, destination))
+    }
+
+    pub async fn new_payment_uri(
+        &self,
+        account_index: U31,
+        amount: RpcAmountIn,
+        label: Option<String>,
+    ) -> WRpcResult<NewPaymentUri, N> {
+        let decimals = self.chain_config.coin_decimals();
+        let amount = amount.to_amount(decimals).ok_or(RpcError::InvalidCoinAmount)?;
+        let (child_number, address) = self.new_address(account_index, false).await?;
+        let uri = PaymentUri::new(&self.chain_config, address, Some(amount), label, None);
+        Ok(NewPaymentUri::new(child_number, uri))
+    }
+
+    pub fn decode_payment_uri(&self, uri: &str) -> WRpcResult<DecodedPaymentUri, N> {
+        let uri = PaymentUri::from_string(&self.chain_config, uri)?;
+        Ok(DecodedPaymentUri::new(uri, &self.chain_config))
+    }
+
+    async fn new_address(
+        &self,
+        account_index: U31,
+        allow_reuse: bool,
+    ) -> WRpcResult<(ChildNumber, Address<Destination>), N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: false,
+            idempotent_request: None,
         }; // irrelevant for issuing addresses
-        let (child_number, destination) = self
+        let (child_number, address) = self
             .wallet
             .call_async(move |w| {
-                Box::pin(
-                    async move { w.synced_controller(account_index, config).await?.new_address() },
-                )
+                Box::pin(async move {
+                    w.synced_controller(account_index, config).await?.new_address(allow_reuse)
+                })
             })
             .await??;
-        Ok(AddressInfo::new(child_number, destination))
+        Ok((child_number, address))
     }
 
     pub async fn find_public_key(
@@ -453,6 +691,9 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: false,
+            idempotent_request: None,
         }; // irrelevant for issuing addresses
         let address = address
             .decode_object(&self.chain_config)
@@ -485,10 +726,108 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             })
     }
 
+    pub async fn export_xpub(&self, account_index: U31) -> WRpcResult<XpubInfo, N> {
+        self.wallet
+            .call(move |controller| controller.readonly_controller(account_index).export_xpub())
+            .await?
+            .map(|(xpub, derivation_path)| XpubInfo {
+                xpub,
+                derivation_path: derivation_path.to_string(),
+            })
+    }
+
+    pub async fn export_descriptor(
+        &self,
+        account_index: U31,
+        descriptor_type: String,
+    ) -> WRpcResult<String, N> {
+        let descriptor_type = descriptor_type.parse()?;
+        self.wallet
+            .call(move |controller| {
+                controller.readonly_controller(account_index).export_descriptor(descriptor_type)
+            })
+            .await?
+    }
+
+    pub async fn export_address_set(
+        &self,
+        account_index: U31,
+        file_path: PathBuf,
+        count: u32,
+    ) -> WRpcResult<ExportedAddressSetInfo, N> {
+        let address_count = U31::from_u32(count)
+            .filter(|count| count.into_u32() > 0)
+            .ok_or(RpcError::InvalidAddressCount)?;
+        self.wallet
+            .call(move |controller| {
+                controller
+                    .readonly_controller(account_index)
+                    .export_address_set(file_path, address_count)
+            })
+            .await?
+            .map(|account_fingerprint| ExportedAddressSetInfo {
+                account_fingerprint: account_fingerprint.to_string(),
+                count,
+            })
+    }
+
+    pub async fn generate_proof_of_reserves(
+        &self,
+        account_index: U31,
+        message: Vec<u8>,
+        file_path: PathBuf,
+    ) -> WRpcResult<ProofOfReservesInfo, N> {
+        self.wallet
+            .call(move |controller| {
+                controller
+                    .readonly_controller(account_index)
+                    .generate_proof_of_reserves(file_path, message)
+            })
+            .await?
+            .map(|proof| ProofOfReservesInfo {
+                block_id: *proof.block_id(),
+                utxo_count: proof.entries().len(),
+            })
+    }
+
+    pub async fn import_address_set(
+        &self,
+        account_index: U31,
+        file_path: PathBuf,
+    ) -> WRpcResult<ImportedAddressSetInfo, N> {
+        let config = ControllerConfig {
+            in_top_x_mb: 5,
+            broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: false,
+            idempotent_request: None,
+        }; // irrelevant for issuing addresses
+        self.wallet
+            .call_async(move |w| {
+                Box::pin(async move {
+                    let imported = w
+                        .synced_controller(account_index, config)
+                        .await?
+                        .import_address_set(file_path)?;
+
+                    // The imported addresses may have been used before
+                    if imported.added_addresses > 0 {
+                        w.reset_wallet_to_genesis()?;
+                    }
+
+                    Ok::<ImportedAddressSetInfo, RpcError<N>>(imported.into())
+                })
+            })
+            .await?
+    }
+
     pub async fn issue_vrf_key(&self, account_index: U31) -> WRpcResult<VrfPublicKeyInfo, N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: false,
+            idempotent_request: None,
         }; // irrelevant for issuing addresses
         self.wallet
             .call_async(move |w| {
@@ -539,19 +878,92 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         &self,
         account_index: U31,
     ) -> WRpcResult<Vec<AddressWithUsageInfo>, N> {
-        let addresses: BTreeMap<_, _> = self
+        let (addresses, labels) = self
+            .wallet
+            .call(move |controller| {
+                let controller = controller.readonly_controller(account_index);
+                Ok::<_, RpcError<N>>((
+                    controller.get_addresses_with_usage()?,
+                    controller.get_address_labels()?,
+                ))
+            })
+            .await??;
+        let result = addresses
+            .into_iter()
+            .map(|(num, (addr, used))| {
+                let label = labels.get(addr.as_object()).map(|label| label.as_str().to_owned());
+                AddressWithUsageInfo::new(num, addr, used, label)
+            })
+            .collect();
+        Ok(result)
+    }
+
+    pub async fn get_issued_change_addresses(
+        &self,
+        account_index: U31,
+    ) -> WRpcResult<Vec<AddressWithUsageInfo>, N> {
+        let (addresses, labels) = self
             .wallet
             .call(move |controller| {
-                controller.readonly_controller(account_index).get_addresses_with_usage()
+                let controller = controller.readonly_controller(account_index);
+                Ok::<_, RpcError<N>>((
+                    controller.get_change_addresses_with_usage()?,
+                    controller.get_address_labels()?,
+                ))
             })
             .await??;
         let result = addresses
             .into_iter()
-            .map(|(num, (addr, used))| AddressWithUsageInfo::new(num, addr, used))
+            .map(|(num, (addr, used))| {
+                let label = labels.get(addr.as_object()).map(|label| label.as_str().to_owned());
+                AddressWithUsageInfo::new(num, addr, used, label)
+            })
             .collect();
         Ok(result)
     }
 
+    pub async fn set_address_label(
+        &self,
+        account_index: U31,
+        address: RpcAddress<Destination>,
+        label: Option<String>,
+    ) -> WRpcResult<(), N> {
+        let dest = address
+            .decode_object(&self.chain_config)
+            .map_err(|_| RpcError::InvalidAddress)?;
+        let label = label.map(Label::new).transpose()?;
+        let config = ControllerConfig {
+            in_top_x_mb: 5,
+            broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: false,
+            idempotent_request: None,
+        }; // irrelevant for setting labels
+        self.wallet
+            .call_async(move |w| {
+                Box::pin(async move {
+                    w.synced_controller(account_index, config).await?.set_address_label(dest, label)
+                })
+            })
+            .await??;
+        Ok(())
+    }
+
+    pub async fn search(
+        &self,
+        account_index: U31,
+        text: String,
+    ) -> WRpcResult<Vec<RpcSearchMatch>, N> {
+        let matches = self
+            .wallet
+            .call(move |controller| controller.readonly_controller(account_index).search(&text))
+            .await??;
+        Ok(matches
+            .into_iter()
+            .map(|search_match| RpcSearchMatch::new(search_match, &self.chain_config))
+            .collect())
+    }
+
     pub async fn get_standalone_addresses(
         &self,
         account_index: U31,
@@ -674,6 +1086,25 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         Ok(balances)
     }
 
+    pub async fn get_balance_by_confirmations(
+        &self,
+        account_index: U31,
+        confirmation_threshold: Option<u64>,
+        with_locked: WithLocked,
+    ) -> WRpcResult<BalancesByConfirmations, N> {
+        let balances = self
+            .wallet
+            .call_async(move |w| {
+                Box::pin(async move {
+                    let c = w.readonly_controller(account_index);
+                    c.get_decimal_balance_by_confirmations(confirmation_threshold, with_locked)
+                        .await
+                })
+            })
+            .await??;
+        Ok(balances)
+    }
+
     pub async fn get_multisig_utxos(
         &self,
         account_index: U31,
@@ -722,6 +1153,23 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             .await?
     }
 
+    pub async fn verify_transaction_inclusion(
+        &self,
+        account_index: U31,
+        transaction_id: Id<Transaction>,
+    ) -> WRpcResult<InclusionStatus, N> {
+        self.wallet
+            .call_async(move |controller| {
+                Box::pin(async move {
+                    controller
+                        .readonly_controller(account_index)
+                        .verify_transaction_inclusion(transaction_id)
+                        .await
+                })
+            })
+            .await?
+    }
+
     pub async fn pending_transactions(
         &self,
         account_index: U31,
@@ -777,6 +1225,9 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             let config = ControllerConfig {
                 in_top_x_mb: 5,
                 broadcast_to_mempool: true,
+                utxo_selection_strategy: UtxoSelectionStrategy::default(),
+                spend_pending_utxos: false,
+                idempotent_request: None,
             }; // irrelevant for issuing addresses
             self.wallet
                 .call_async(move |controller| {
@@ -821,11 +1272,19 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         self.wallet
             .call_async(move |controller| {
                 Box::pin(async move {
-                    controller
-                        .synced_controller(account_index, config)
-                        .await?
-                        .sign_raw_transaction(tx_to_sign)
-                        .map_err(RpcError::Controller)
+                    let mut synced_controller =
+                        controller.synced_controller(account_index, config).await?;
+                    match tx_to_sign {
+                        // only the inputs of a raw transaction that belong to the account
+                        // are signed, the rest are left for their owners
+                        TransactionToSign::Tx(tx) => {
+                            synced_controller.sign_raw_transaction_own_inputs(tx)
+                        }
+                        TransactionToSign::Partial(ptx) => {
+                            synced_controller.sign_raw_transaction(TransactionToSign::Partial(ptx))
+                        }
+                    }
+                    .map_err(RpcError::Controller)
                 })
             })
             .await?
@@ -840,6 +1299,9 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: false,
+            idempotent_request: None,
         }; // irrelevant for issuing addresses
         let destination = address
             .decode_object(&self.chain_config)
@@ -891,9 +1353,18 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             .map(|a| a.decode_object(&self.chain_config).map_err(|_| RpcError::InvalidAddress))
             .collect::<Result<BTreeSet<Destination>, _>>()?;
 
+        let retention = self.idempotency_key_retention;
         self.wallet
             .call_async(move |controller| {
                 Box::pin(async move {
+                    if let Some(tx) = controller.find_idempotent_transaction(
+                        account_index,
+                        config.idempotent_request.as_ref(),
+                        retention,
+                    )? {
+                        return Ok(NewTransaction::new(tx));
+                    }
+
                     controller
                         .synced_controller(account_index, config)
                         .await?
@@ -942,20 +1413,127 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         amount: RpcAmountIn,
         selected_utxos: Vec<UtxoOutPoint>,
         config: ControllerConfig,
-    ) -> WRpcResult<SignedTransaction, N> {
+    ) -> WRpcResult<SendResult, N> {
         let decimals = self.chain_config.coin_decimals();
         let amount = amount.to_amount(decimals).ok_or(RpcError::InvalidCoinAmount)?;
         let address =
             address.into_address(&self.chain_config).map_err(|_| RpcError::InvalidAddress)?;
 
+        let retention = self.idempotency_key_retention;
         self.wallet
             .call_async(move |controller| {
                 Box::pin(async move {
-                    controller
+                    if let Some(tx) = controller.find_idempotent_transaction(
+                        account_index,
+                        config.idempotent_request.as_ref(),
+                        retention,
+                    )? {
+                        return Ok(SendResult {
+                            tx,
+                            reused_addresses: Vec::new(),
+                        });
+                    }
+
+                    // Check before sending, as the new transaction marks the address as used
+                    let reused_addresses = controller.find_reused_addresses([&address]);
+                    let tx = controller
                         .synced_controller(account_index, config)
                         .await?
                         .send_to_address(address, amount, selected_utxos)
                         .await
+                        .map_err(RpcError::Controller)?;
+                    Ok::<_, RpcError<N>>(SendResult {
+                        tx,
+                        reused_addresses,
+                    })
+                })
+            })
+            .await?
+    }
+
+    pub async fn send_to_many_addresses(
+        &self,
+        account_index: U31,
+        outputs: Vec<SendToManyOutput>,
+        duplicate_outputs: DuplicateOutputs,
+        config: ControllerConfig,
+    ) -> WRpcResult<SendToManyResult, N> {
+        let coin_decimals = self.chain_config.coin_decimals();
+        let outputs = outputs
+            .into_iter()
+            .map(|output| {
+                let address =
+                    output.address.clone().into_address(&self.chain_config).map_err(|_| {
+                        RpcError::InvalidAddressWithAddr(output.address.into_string())
+                    })?;
+                let token_id = output
+                    .token_id
+                    .map(|token_id| {
+                        token_id
+                            .decode_object(&self.chain_config)
+                            .map_err(|_| RpcError::InvalidTokenId)
+                    })
+                    .transpose()?;
+                Ok((address, token_id, output.amount))
+            })
+            .collect::<WRpcResult<Vec<_>, N>>()?;
+
+        let retention = self.idempotency_key_retention;
+        self.wallet
+            .call_async(move |controller| {
+                Box::pin(async move {
+                    let mut token_decimals = BTreeMap::new();
+                    for token_id in outputs.iter().filter_map(|(_, token_id, _)| *token_id) {
+                        if let std::collections::btree_map::Entry::Vacant(e) =
+                            token_decimals.entry(token_id)
+                        {
+                            let token_info = controller.get_token_info(token_id).await?;
+                            e.insert(token_info.token_number_of_decimals());
+                        }
+                    }
+
+                    let outputs = outputs
+                        .into_iter()
+                        .map(|(address, token_id, amount)| {
+                            let (currency, decimals) = match token_id {
+                                Some(token_id) => {
+                                    (Currency::Token(token_id), token_decimals[&token_id])
+                                }
+                                None => (Currency::Coin, coin_decimals),
+                            };
+                            let amount =
+                                amount.to_amount(decimals).ok_or(RpcError::InvalidCoinAmount)?;
+                            Ok((address, currency, amount))
+                        })
+                        .collect::<WRpcResult<Vec<_>, N>>()?;
+
+                    if let Some(tx) = controller.find_idempotent_transaction(
+                        account_index,
+                        config.idempotent_request.as_ref(),
+                        retention,
+                    )? {
+                        // The same request made the same payments, so report them the same way
+                        let (payments, merged_addresses) =
+                            collect_send_to_many_payments::<N>(outputs, duplicate_outputs)?;
+                        let (fees, fee_per_recipient) = controller
+                            .synced_controller(account_index, config)
+                            .await?
+                            .send_to_many_fees(&tx, payments.len())
+                            .await?;
+                        return Ok(SendToManyResult {
+                            tx,
+                            fees,
+                            fee_per_recipient,
+                            merged_addresses,
+                            reused_addresses: Vec::new(),
+                        });
+                    }
+
+                    controller
+                        .synced_controller(account_index, config)
+                        .await?
+                        .send_to_many_addresses(outputs, duplicate_outputs)
+                        .await
                         .map_err(RpcError::Controller)
                 })
             })
@@ -970,7 +1548,14 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         selected_utxo: UtxoOutPoint,
         change_address: Option<RpcAddress<Destination>>,
         config: ControllerConfig,
-    ) -> WRpcResult<(PartiallySignedTransaction, Balances), N> {
+    ) -> WRpcResult<
+        (
+            PartiallySignedTransaction,
+            Balances,
+            Vec<Address<Destination>>,
+        ),
+        N,
+    > {
         let decimals = self.chain_config.coin_decimals();
         let amount = amount.to_amount(decimals).ok_or(RpcError::InvalidCoinAmount)?;
         let address =
@@ -983,12 +1568,14 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         self.wallet
             .call_async(move |controller| {
                 Box::pin(async move {
-                    controller
+                    let reused_addresses = controller.find_reused_addresses([&address]);
+                    let (tx, fees) = controller
                         .synced_controller(account_index, config)
                         .await?
                         .request_send_to_address(address, amount, selected_utxo, change_address)
                         .await
-                        .map_err(RpcError::Controller)
+                        .map_err(RpcError::Controller)?;
+                    Ok::<_, RpcError<N>>((tx, fees, reused_addresses))
                 })
             })
             .await?
@@ -1038,9 +1625,18 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         let address =
             address.into_address(&self.chain_config).map_err(|_| RpcError::InvalidAddress)?;
 
+        let retention = self.idempotency_key_retention;
         self.wallet
             .call_async(move |controller| {
                 Box::pin(async move {
+                    if let Some(tx) = controller.find_idempotent_transaction(
+                        account_index,
+                        config.idempotent_request.as_ref(),
+                        retention,
+                    )? {
+                        return Ok(NewTransaction::new(tx));
+                    }
+
                     let token_info = controller.get_token_info(token_id).await?;
                     let amount = amount
                         .to_amount(token_info.token_number_of_decimals())
@@ -1245,6 +1841,37 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             .await?
     }
 
+    pub async fn create_decommission_pool_transaction(
+        &self,
+        account_index: U31,
+        pool_id: RpcAddress<PoolId>,
+        config: ControllerConfig,
+    ) -> WRpcResult<DecommissionPoolTransaction, N> {
+        let pool_id =
+            pool_id.decode_object(&self.chain_config).map_err(|_| RpcError::InvalidPoolId)?;
+
+        self.wallet
+            .call_async(move |controller| {
+                Box::pin(async move {
+                    controller
+                        .synced_controller(account_index, config)
+                        .await?
+                        .create_decommission_pool_transaction(pool_id)
+                        .await
+                        .map_err(RpcError::Controller)
+                })
+            })
+            .await?
+            .map(|(tx, decommission_key, delegated_balance)| {
+                DecommissionPoolTransaction::new(
+                    tx,
+                    decommission_key,
+                    delegated_balance,
+                    &self.chain_config,
+                )
+            })
+    }
+
     pub async fn decommission_stake_pool_request(
         &self,
         account_index: U31,
@@ -1307,6 +1934,40 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             })
     }
 
+    pub async fn create_delegation_and_stake(
+        &self,
+        account_index: U31,
+        pool_id: RpcAddress<PoolId>,
+        amount: RpcAmountIn,
+        config: ControllerConfig,
+    ) -> WRpcResult<(SignedTransaction, RpcAddress<DelegationId>), N> {
+        let decimals = self.chain_config.coin_decimals();
+        let amount = amount.to_amount(decimals).ok_or(RpcError::InvalidCoinAmount)?;
+
+        let pool_id =
+            pool_id.decode_object(&self.chain_config).map_err(|_| RpcError::InvalidPoolId)?;
+
+        self.wallet
+            .call_async(move |controller| {
+                Box::pin(async move {
+                    controller
+                        .synced_controller(account_index, config)
+                        .await?
+                        .create_delegation_and_stake(pool_id, amount)
+                        .await
+                        .map_err(RpcError::Controller)
+                })
+            })
+            .await?
+            .map(|(tx, delegation_id)| {
+                (
+                    tx,
+                    RpcAddress::new(&self.chain_config, delegation_id)
+                        .expect("addressable delegation id"),
+                )
+            })
+    }
+
     pub async fn delegate_staking(
         &self,
         account_index: U31,
@@ -1369,6 +2030,9 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: false,
+            idempotent_request: None,
         }; // irrelevant for issuing addresses
 
         self.wallet
@@ -1399,6 +2063,25 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             .await?
     }
 
+    pub async fn staking_pools_status(
+        &self,
+        account_index: U31,
+    ) -> WRpcResult<AccountStakingStatus, N> {
+        self.wallet
+            .call_async(move |controller| {
+                Box::pin(async move {
+                    let is_staking = controller.is_staking(account_index);
+                    let pools =
+                        controller.readonly_controller(account_index).get_staking_status().await?;
+                    Ok::<_, ControllerError<_>>((pools, is_staking))
+                })
+            })
+            .await?
+            .map(|(pools, is_staking)| {
+                AccountStakingStatus::new(pools, is_staking, &self.chain_config)
+            })
+    }
+
     pub async fn create_htlc_transaction(
         &self,
         account_index: U31,
@@ -1468,7 +2151,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         outputs: Vec<TxOutput>,
         htlc_secrets: Option<Vec<Option<RpcHexString>>>,
         only_transaction: bool,
-    ) -> WRpcResult<(TransactionToSign, Balances), N> {
+    ) -> WRpcResult<(TransactionToSign, Balances, Vec<Address<Destination>>), N> {
         ensure!(!inputs.is_empty(), RpcError::ComposeTransactionEmptyInputs);
         let inputs = inputs.into_iter().map(|o| o.into_outpoint()).collect();
 
@@ -1490,10 +2173,33 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             })
             .transpose()?;
 
+        let destinations: Vec<_> = outputs
+            .iter()
+            .filter_map(|output| match output {
+                TxOutput::Transfer(_, dest) | TxOutput::LockThenTransfer(_, dest, _) => {
+                    Some(Address::new(&self.chain_config, dest.clone()).expect("addressable"))
+                }
+                TxOutput::Burn(_)
+                | TxOutput::CreateStakePool(_, _)
+                | TxOutput::ProduceBlockFromStake(_, _)
+                | TxOutput::CreateDelegationId(_, _)
+                | TxOutput::DelegateStaking(_, _)
+                | TxOutput::IssueFungibleToken(_)
+                | TxOutput::IssueNft(_, _, _)
+                | TxOutput::DataDeposit(_)
+                | TxOutput::Htlc(_, _)
+                | TxOutput::AnyoneCanTake(_) => None,
+            })
+            .collect();
+
         self.wallet
             .call_async(move |w| {
                 Box::pin(async move {
-                    w.compose_transaction(inputs, outputs, htlc_secrets, only_transaction).await
+                    let reused_addresses = w.find_reused_addresses(&destinations);
+                    let (tx, fees) = w
+                        .compose_transaction(inputs, outputs, htlc_secrets, only_transaction)
+                        .await?;
+                    Ok::<_, RpcError<N>>((tx, fees, reused_addresses))
                 })
             })
             .await?
@@ -1507,6 +2213,9 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: false,
+            idempotent_request: None,
         }; // irrelevant for issuing addresses
         self.wallet
             .call_async(move |w| {
@@ -1519,6 +2228,31 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             .await?
     }
 
+    pub async fn set_transaction_label(
+        &self,
+        account_index: U31,
+        transaction_id: Id<Transaction>,
+        label: Option<String>,
+    ) -> WRpcResult<(), N> {
+        let label = label.map(Label::new).transpose()?;
+        let config = ControllerConfig {
+            in_top_x_mb: 5,
+            broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: false,
+            idempotent_request: None,
+        }; // irrelevant for setting labels
+        self.wallet
+            .call_async(move |w| {
+                Box::pin(async move {
+                    w.synced_controller(account_index, config)
+                        .await?
+                        .set_transaction_label(transaction_id, label)
+                })
+            })
+            .await?
+    }
+
     pub async fn deposit_data(
         &self,
         account_index: U31,
@@ -1904,6 +2638,28 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         self.wallet.call(move |controller| controller.delete_seed_phrase()).await?
     }
 
+    pub async fn wallet_fingerprint(&self) -> WRpcResult<WalletFingerprintInfo, N> {
+        self.wallet.call(move |controller| controller.fingerprint()).await?
+    }
+
+    pub async fn wallet_version(&self) -> WRpcResult<WalletVersionInfo, N> {
+        self.wallet
+            .manage_async(move |wallet_manager| {
+                Box::pin(async move { wallet_manager.wallet_version() })
+            })
+            .await?
+    }
+
+    pub async fn backup_wallet(
+        &self,
+        backup_path: PathBuf,
+        password: Option<String>,
+    ) -> WRpcResult<(), N> {
+        self.wallet
+            .call(move |controller| controller.backup_wallet(backup_path, password))
+            .await?
+    }
+
     pub async fn wallet_info(&self) -> WRpcResult<WalletInfo, N> {
         self.wallet
             .call(move |controller| Ok::<_, RpcError<N>>(controller.wallet_info()))
@@ -1924,6 +2680,15 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             .map(|balance| balance.into_fixedpoint_str(self.chain_config.coin_decimals())))
     }
 
+    pub async fn list_all_staking_pools(&self) -> WRpcResult<Vec<RpcStakePoolInfo>, N> {
+        let pools = self.node.get_all_pools().await.map_err(RpcError::RpcError)?;
+        let pools = pools
+            .iter()
+            .map(|info| RpcStakePoolInfo::new(&self.chain_config, info))
+            .collect::<Result<_, _>>()?;
+        Ok(pools)
+    }
+
     pub async fn node_version(&self) -> WRpcResult<String, N> {
         self.node.node_version().await.map_err(RpcError::RpcError)
     }
@@ -1968,6 +2733,15 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         self.node.p2p_get_peer_count().await.map_err(RpcError::RpcError)
     }
 
+    pub async fn node_connection_status(&self) -> WRpcResult<NodeConnectionStatus, N> {
+        let peer_count = self.node.get_peer_count().await.map_err(RpcError::RpcError)?;
+        let is_synced = self.node.is_node_synced().await.map_err(RpcError::RpcError)?;
+        Ok(NodeConnectionStatus {
+            peer_count,
+            is_synced,
+        })
+    }
+
     pub async fn connected_peers(&self) -> WRpcResult<Vec<ConnectedPeer>, N> {
         self.node.p2p_get_connected_peers().await.map_err(RpcError::RpcError)
     }
@@ -1985,7 +2759,11 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
     }
 
     pub async fn submit_block(&self, block: HexEncoded<Block>) -> WRpcResult<(), N> {
-        self.node.submit_block(block.take()).await.map_err(RpcError::RpcError)
+        self.node
+            .submit_block(block.take())
+            .await
+            .map_err(RpcError::RpcError)?
+            .map_err(RpcError::BlockRejected)
     }
 
     pub async fn chainstate_info(&self) -> WRpcResult<ChainInfo, N> {
@@ -2007,6 +2785,19 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         self.node.get_block_id_at_height(block_height).await.map_err(RpcError::RpcError)
     }
 
+    pub async fn node_verify_proof_of_reserves(
+        &self,
+        file_path: PathBuf,
+    ) -> WRpcResult<ProofOfReservesReport, N> {
+        let proof = std::fs::read(&file_path).map_err(|e| {
+            RpcError::Controller(ControllerError::WalletFileError(file_path, e.to_string()))
+        })?;
+        self.node
+            .verify_proof_of_reserves(hex::encode(proof))
+            .await
+            .map_err(RpcError::RpcError)
+    }
+
     pub async fn get_node_block(&self, block_id: Id<Block>) -> WRpcResult<Option<Block>, N> {
         self.node.get_block(block_id).await.map_err(RpcError::RpcError)
     }
@@ -2034,9 +2825,17 @@ pub async fn start<N: NodeInterface + Clone + Send + Sync + Debug + 'static>(
     let WalletRpcConfig {
         bind_addr,
         auth_credentials,
+        idempotency_key_retention,
+        admin_password,
+        audit_log,
     } = config;
 
-    let wallet_rpc = WalletRpc::new(wallet_handle, node_rpc, chain_config);
+    let audit_log = audit_log.map(AuditLog::open).transpose()?.map(Arc::new);
+
+    let wallet_rpc = WalletRpc::new(wallet_handle, node_rpc, chain_config)
+        .with_idempotency_key_retention(idempotency_key_retention)
+        .with_admin_password(admin_password)
+        .with_audit_log(audit_log);
     let builder = rpc::Builder::new(bind_addr, auth_credentials)
         .with_method_list("list_methods")
         .register(ColdWalletRpcServer::into_rpc(wallet_rpc.clone()));
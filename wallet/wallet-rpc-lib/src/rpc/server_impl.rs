@@ -27,34 +27,119 @@ use common::{
     primitives::{time::Time, BlockHeight, Id, Idable},
 };
 use crypto::key::PrivateKey;
+use logging::log;
 use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress, PeerId};
 use serialization::{hex::HexEncode, json_encoded::JsonEncoded};
 use utils_networking::IpOrSocketAddress;
-use wallet::{account::TxInfo, version::get_version};
+use wallet::{account::TxInfo, version::get_version, WalletError};
 use wallet_controller::{
-    types::{BlockInfo, CreatedBlockInfo, GenericTokenTransfer, SeedWithPassPhrase, WalletInfo},
-    ConnectedPeer, ControllerConfig, NodeInterface, UtxoState, UtxoStates, UtxoType, UtxoTypes,
+    types::{
+        BlockInfo, CreatedBlockInfo, GenericTokenTransfer, SeedWithPassPhrase,
+        WalletFingerprintInfo, WalletInfo, WalletVersionInfo,
+    },
+    ConnectedPeer, ControllerConfig, ControllerError, NodeInterface, UtxoSelectionStrategy,
+    UtxoState, UtxoStates, UtxoType, UtxoTypes,
 };
 use wallet_types::{
     seed_phrase::StoreSeedPhrase, signature_status::SignatureStatus, with_locked::WithLocked,
 };
 
 use crate::{
+    audit_log::AuditResult,
     rpc::{ColdWalletRpcServer, WalletEventsRpcServer, WalletRpc, WalletRpcServer},
     types::{
-        AccountArg, AddressInfo, AddressWithUsageInfo, Balances, ChainInfo, ComposedTransaction,
-        CreatedWallet, DelegationInfo, HexEncoded, JsonValue, LegacyVrfPublicKeyInfo,
-        MaybeSignedTransaction, NewAccountInfo, NewDelegation, NewTransaction, NftMetadata,
-        NodeVersion, PoolInfo, PublicKeyInfo, RpcAddress, RpcAmountIn, RpcHexString,
-        RpcInspectTransaction, RpcStandaloneAddresses, RpcTokenId, RpcUtxoOutpoint, RpcUtxoState,
-        RpcUtxoType, SendTokensFromMultisigAddressResult, StakePoolBalance, StakingStatus,
-        StandaloneAddressWithDetails, TokenMetadata, TransactionOptions, TxOptionsOverrides,
-        UtxoInfo, VrfPublicKeyInfo,
+        AccountArg, AccountStakingStatus, AddressInfo, AddressWithUsageInfo, Balances,
+        BalancesByConfirmations, ChainInfo, ComposedTransaction, CreatedWallet, DecodedPaymentUri,
+        DecommissionPoolTransaction, DelegationInfo, DuplicateOutputs, ExportedAddressSetInfo,
+        HexEncoded, ImportedAddressSetInfo, InclusionStatus, JsonValue, LegacyVrfPublicKeyInfo,
+        MaybeSignedTransaction, NewAccountInfo, NewDelegation, NewPaymentUri, NewTransaction,
+        NftMetadata, NodeConnectionStatus, NodeVersion, PoolInfo, ProofOfReservesInfo,
+        ProofOfReservesReport, PublicKeyInfo, RpcAddress, RpcAmountIn, RpcHexString,
+        RpcInspectTransaction, RpcSearchMatch, RpcSpendingPolicy, RpcSpendingPolicyViolation,
+        RpcStakePoolInfo, RpcStandaloneAddresses, RpcTokenId, RpcUtxoOutpoint, RpcUtxoState,
+        RpcUtxoType, SendToManyOutput, SendToManyTransaction, SendTokensFromMultisigAddressResult,
+        SendTransaction, StakePoolBalance, StakingStatus, StandaloneAddressWithDetails,
+        TokenMetadata, TransactionOptions, TxOptionsOverrides, UtxoInfo, VrfPublicKeyInfo,
+        XpubInfo,
     },
     RpcError,
 };
 
-use super::types::RpcHashedTimelockContract;
+use super::{types::RpcHashedTimelockContract, WRpcResult};
+
+impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpc<N> {
+    /// Attach the violated rule and the remaining allowance to the spending policy errors
+    fn handle_spending_result<T>(&self, res: WRpcResult<T, N>) -> rpc::RpcResult<T> {
+        match res {
+            Err(RpcError::Controller(ControllerError::WalletError(
+                WalletError::SpendingPolicyViolation(violation),
+            ))) => Err(rpc::error_with_data(
+                &violation,
+                RpcSpendingPolicyViolation::new(&violation, &self.chain_config),
+            )),
+            res => rpc::handle_result(res),
+        }
+    }
+
+    /// Return the errors of the node calls with the code and the type given by the node client,
+    /// so that the clients can tell e.g. an unavailable node from an unknown peer
+    fn handle_node_result<T>(res: WRpcResult<T, N>) -> rpc::RpcResult<T> {
+        match res {
+            Err(RpcError::RpcError(err)) => Err(err.into()),
+            res => rpc::handle_result(res),
+        }
+    }
+
+    /// Record the call in the audit log if it's enabled, the params must not contain
+    /// key material or passwords
+    fn audit<T: AuditedResult>(
+        &self,
+        method: &str,
+        params: JsonValue,
+        res: rpc::RpcResult<T>,
+    ) -> rpc::RpcResult<T> {
+        if let Some(audit_log) = &self.audit_log {
+            let result = match &res {
+                Ok(value) => value.audit_result(),
+                Err(err) => AuditResult::Error(err.message().to_owned()),
+            };
+            if let Err(err) = audit_log.record(method, params, result) {
+                log::error!("Failed to record the {method} call in the audit log: {err}");
+            }
+        }
+        res
+    }
+}
+
+/// The summary of a successful call recorded in the audit log
+trait AuditedResult {
+    fn audit_result(&self) -> AuditResult;
+}
+
+impl AuditedResult for () {
+    fn audit_result(&self) -> AuditResult {
+        AuditResult::Ok
+    }
+}
+
+macro_rules! impl_audited_tx_result {
+    ($($type:ty),*) => {
+        $(
+            impl AuditedResult for $type {
+                fn audit_result(&self) -> AuditResult {
+                    AuditResult::TxId(format!("{:x}", self.tx_id))
+                }
+            }
+        )*
+    };
+}
+
+impl_audited_tx_result!(
+    NewTransaction,
+    SendTransaction,
+    SendToManyTransaction,
+    NewDelegation
+);
 
 #[async_trait::async_trait]
 impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletEventsRpcServer
@@ -122,6 +207,22 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> ColdWalletRpcServ
         )
     }
 
+    async fn restore_wallet_from_backup(
+        &self,
+        backup_path: String,
+        wallet_path: String,
+        backup_password: Option<String>,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(
+            self.restore_wallet_from_backup(
+                backup_path.into(),
+                wallet_path.into(),
+                backup_password,
+            )
+            .await,
+        )
+    }
+
     async fn close_wallet(&self) -> rpc::RpcResult<()> {
         rpc::handle_result(self.close_wallet().await)
     }
@@ -138,6 +239,23 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> ColdWalletRpcServ
         rpc::handle_result(self.purge_seed_phrase().await)
     }
 
+    async fn wallet_fingerprint(&self) -> rpc::RpcResult<WalletFingerprintInfo> {
+        rpc::handle_result(self.wallet_fingerprint().await)
+    }
+
+    async fn wallet_version(&self) -> rpc::RpcResult<WalletVersionInfo> {
+        match self.wallet_version().await {
+            Err(RpcError::NoWalletOpened) => Err(rpc::service_unavailable_error(
+                RpcError::<N>::NoWalletOpened,
+            )),
+            res => rpc::handle_result(res),
+        }
+    }
+
+    async fn backup_wallet(&self, path: String, password: Option<String>) -> rpc::RpcResult<()> {
+        rpc::handle_result(self.backup_wallet(path.into(), password).await)
+    }
+
     async fn set_lookahead_size(
         &self,
         lookahead_size: u32,
@@ -162,8 +280,28 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> ColdWalletRpcServ
         rpc::handle_result(self.lock_private_keys().await)
     }
 
-    async fn issue_address(&self, account_arg: AccountArg) -> rpc::RpcResult<AddressInfo> {
-        rpc::handle_result(self.issue_address(account_arg.index::<N>()?).await)
+    async fn issue_address(
+        &self,
+        account_arg: AccountArg,
+        allow_reuse: Option<bool>,
+    ) -> rpc::RpcResult<AddressInfo> {
+        rpc::handle_result(
+            self.issue_address(account_arg.index::<N>()?, allow_reuse.unwrap_or(false))
+                .await,
+        )
+    }
+
+    async fn new_payment_uri(
+        &self,
+        account_arg: AccountArg,
+        amount: RpcAmountIn,
+        label: Option<String>,
+    ) -> rpc::RpcResult<NewPaymentUri> {
+        rpc::handle_result(self.new_payment_uri(account_arg.index::<N>()?, amount, label).await)
+    }
+
+    async fn decode_payment_uri(&self, uri: String) -> rpc::RpcResult<DecodedPaymentUri> {
+        rpc::handle_result(self.decode_payment_uri(&uri))
     }
 
     async fn reveal_public_key(
@@ -198,6 +336,53 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> ColdWalletRpcServ
         rpc::handle_result(self.get_issued_addresses(account_arg.index::<N>()?).await)
     }
 
+    async fn get_issued_change_addresses(
+        &self,
+        account_arg: AccountArg,
+    ) -> rpc::RpcResult<Vec<AddressWithUsageInfo>> {
+        rpc::handle_result(self.get_issued_change_addresses(account_arg.index::<N>()?).await)
+    }
+
+    async fn export_xpub(&self, account_arg: AccountArg) -> rpc::RpcResult<XpubInfo> {
+        rpc::handle_result(self.export_xpub(account_arg.index::<N>()?).await)
+    }
+
+    async fn export_descriptor(
+        &self,
+        account_arg: AccountArg,
+        descriptor_type: String,
+    ) -> rpc::RpcResult<String> {
+        rpc::handle_result(self.export_descriptor(account_arg.index::<N>()?, descriptor_type).await)
+    }
+
+    async fn export_address_set(
+        &self,
+        account_arg: AccountArg,
+        path: String,
+        count: u32,
+    ) -> rpc::RpcResult<ExportedAddressSetInfo> {
+        rpc::handle_result(
+            self.export_address_set(account_arg.index::<N>()?, path.into(), count).await,
+        )
+    }
+
+    async fn set_address_label(
+        &self,
+        account_arg: AccountArg,
+        address: RpcAddress<Destination>,
+        label: Option<String>,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(self.set_address_label(account_arg.index::<N>()?, address, label).await)
+    }
+
+    async fn search(
+        &self,
+        account_arg: AccountArg,
+        text: String,
+    ) -> rpc::RpcResult<Vec<RpcSearchMatch>> {
+        rpc::handle_result(self.search(account_arg.index::<N>()?, text).await)
+    }
+
     async fn new_vrf_public_key(
         &self,
         account_arg: AccountArg,
@@ -225,9 +410,13 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> ColdWalletRpcServ
         raw_tx: RpcHexString,
         options: TransactionOptions,
     ) -> rpc::RpcResult<MaybeSignedTransaction> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
         rpc::handle_result(
             self.sign_raw_transaction(account_arg.index::<N>()?, raw_tx, config).await.map(
@@ -328,6 +517,48 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         rpc::handle_result(self.update_account_name(account_arg.index::<N>()?, name).await)
     }
 
+    async fn set_spending_policy(
+        &self,
+        admin_password: String,
+        account_arg: AccountArg,
+        max_per_tx: Option<RpcAmountIn>,
+        daily_cap: Option<RpcAmountIn>,
+        allowed_addresses: Option<Vec<RpcAddress<Destination>>>,
+    ) -> rpc::RpcResult<()> {
+        let params = serde_json::json!({
+            "account": account_arg,
+            "max_per_tx": max_per_tx,
+            "daily_cap": daily_cap,
+            "allowed_addresses": allowed_addresses,
+        });
+        self.audit(
+            "account_set_spending_policy",
+            params,
+            rpc::handle_result(
+                self.set_spending_policy(
+                    admin_password,
+                    account_arg.index::<N>()?,
+                    max_per_tx,
+                    daily_cap,
+                    allowed_addresses,
+                )
+                .await,
+            ),
+        )
+    }
+
+    async fn spending_policy(&self, account_arg: AccountArg) -> rpc::RpcResult<RpcSpendingPolicy> {
+        rpc::handle_result(self.spending_policy(account_arg.index::<N>()?).await)
+    }
+
+    async fn set_confirmation_threshold(&self, confirmation_threshold: u64) -> rpc::RpcResult<()> {
+        rpc::handle_result(self.set_confirmation_threshold(confirmation_threshold).await)
+    }
+
+    async fn confirmation_threshold(&self) -> rpc::RpcResult<u64> {
+        rpc::handle_result(self.confirmation_threshold().await)
+    }
+
     async fn standalone_address_label_rename(
         &self,
         account_arg: AccountArg,
@@ -358,6 +589,30 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         )
     }
 
+    async fn import_address_set(
+        &self,
+        account_arg: AccountArg,
+        path: String,
+    ) -> rpc::RpcResult<ImportedAddressSetInfo> {
+        rpc::handle_result(self.import_address_set(account_arg.index::<N>()?, path.into()).await)
+    }
+
+    async fn generate_proof_of_reserves(
+        &self,
+        account_arg: AccountArg,
+        message: String,
+        path: String,
+    ) -> rpc::RpcResult<ProofOfReservesInfo> {
+        rpc::handle_result(
+            self.generate_proof_of_reserves(
+                account_arg.index::<N>()?,
+                message.into_bytes(),
+                path.into(),
+            )
+            .await,
+        )
+    }
+
     async fn add_standalone_private_key(
         &self,
         account_arg: AccountArg,
@@ -365,14 +620,23 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         label: Option<String>,
         no_rescan: Option<bool>,
     ) -> rpc::RpcResult<()> {
-        rpc::handle_result(
-            self.add_standalone_private_key(
-                account_arg.index::<N>()?,
-                private_key.take(),
-                label,
-                no_rescan.unwrap_or(false),
-            )
-            .await,
+        let params = serde_json::json!({
+            "account": account_arg,
+            "label": label,
+            "no_rescan": no_rescan,
+        });
+        self.audit(
+            "standalone_add_private_key_from_hex",
+            params,
+            rpc::handle_result(
+                self.add_standalone_private_key(
+                    account_arg.index::<N>()?,
+                    private_key.take(),
+                    label,
+                    no_rescan.unwrap_or(false),
+                )
+                .await,
+            ),
         )
     }
 
@@ -416,6 +680,22 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         )
     }
 
+    async fn get_balance_by_confirmations(
+        &self,
+        account_arg: AccountArg,
+        confirmation_threshold: Option<u64>,
+        with_locked: Option<WithLocked>,
+    ) -> rpc::RpcResult<BalancesByConfirmations> {
+        rpc::handle_result(
+            self.get_balance_by_confirmations(
+                account_arg.index::<N>()?,
+                confirmation_threshold,
+                with_locked.unwrap_or(WithLocked::Unlocked),
+            )
+            .await,
+        )
+    }
+
     async fn get_multisig_utxos(
         &self,
         account_arg: AccountArg,
@@ -480,7 +760,12 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         do_not_store: bool,
         options: TxOptionsOverrides,
     ) -> rpc::RpcResult<NewTransaction> {
-        rpc::handle_result(self.submit_raw_transaction(tx, do_not_store, options).await)
+        let params = serde_json::json!({ "do_not_store": do_not_store });
+        self.audit(
+            "node_submit_transaction",
+            params,
+            rpc::handle_result(self.submit_raw_transaction(tx, do_not_store, options).await),
+        )
     }
 
     async fn send_coins(
@@ -490,21 +775,75 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         amount: RpcAmountIn,
         selected_utxos: Vec<RpcUtxoOutpoint>,
         options: TransactionOptions,
-    ) -> rpc::RpcResult<NewTransaction> {
+    ) -> rpc::RpcResult<SendTransaction> {
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: options.idempotent_request::<N>(
+                "send_coins",
+                &(&account_arg, &address, &amount, &selected_utxos),
+            )?,
         };
-        rpc::handle_result(
-            self.send_coins(
-                account_arg.index::<N>()?,
-                address,
-                amount,
-                selected_utxos.into_iter().map(|o| o.into_outpoint()).collect(),
-                config,
-            )
-            .await
-            .map(NewTransaction::new),
+        let params = serde_json::json!({
+            "account": account_arg,
+            "address": address,
+            "amount": amount,
+            "selected_utxos": selected_utxos,
+        });
+        self.audit(
+            "address_send",
+            params,
+            self.handle_spending_result(
+                self.send_coins(
+                    account_arg.index::<N>()?,
+                    address,
+                    amount,
+                    selected_utxos.into_iter().map(|o| o.into_outpoint()).collect(),
+                    config,
+                )
+                .await
+                .map(SendTransaction::new),
+            ),
+        )
+    }
+
+    async fn send_to_many_addresses(
+        &self,
+        account_arg: AccountArg,
+        outputs: Vec<SendToManyOutput>,
+        duplicate_outputs: Option<DuplicateOutputs>,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<SendToManyTransaction> {
+        let config = ControllerConfig {
+            in_top_x_mb: options.in_top_x_mb(),
+            broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: options.idempotent_request::<N>(
+                "send_to_many_addresses",
+                &(&account_arg, &outputs, duplicate_outputs),
+            )?,
+        };
+        let params = serde_json::json!({
+            "account": account_arg,
+            "outputs": outputs,
+            "duplicate_outputs": duplicate_outputs,
+        });
+        self.audit(
+            "address_send_to_many",
+            params,
+            self.handle_spending_result(
+                self.send_to_many_addresses(
+                    account_arg.index::<N>()?,
+                    outputs,
+                    duplicate_outputs.unwrap_or_default(),
+                    config,
+                )
+                .await
+                .map(SendToManyTransaction::new),
+            ),
         )
     }
 
@@ -518,15 +857,30 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: options.idempotent_request::<N>(
+                "sweep_addresses",
+                &(&account, &destination_address, &from_addresses),
+            )?,
         };
-        rpc::handle_result(
-            self.sweep_addresses(
-                account.index::<N>()?,
-                destination_address,
-                from_addresses,
-                config,
-            )
-            .await,
+        let params = serde_json::json!({
+            "account": account,
+            "destination_address": destination_address,
+            "from_addresses": from_addresses,
+        });
+        self.audit(
+            "address_sweep_spendable",
+            params,
+            self.handle_spending_result(
+                self.sweep_addresses(
+                    account.index::<N>()?,
+                    destination_address,
+                    from_addresses,
+                    config,
+                )
+                .await,
+            ),
         )
     }
 
@@ -537,18 +891,31 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         delegation_id: RpcAddress<DelegationId>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
-        rpc::handle_result(
-            self.sweep_delegation(
-                account.index::<N>()?,
-                destination_address,
-                delegation_id,
-                config,
-            )
-            .await,
+        let params = serde_json::json!({
+            "account": account,
+            "destination_address": destination_address,
+            "delegation_id": delegation_id,
+        });
+        self.audit(
+            "staking_sweep_delegation",
+            params,
+            self.handle_spending_result(
+                self.sweep_delegation(
+                    account.index::<N>()?,
+                    destination_address,
+                    delegation_id,
+                    config,
+                )
+                .await,
+            ),
         )
     }
 
@@ -561,9 +928,13 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         change_address: Option<RpcAddress<Destination>>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<ComposedTransaction> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
         rpc::handle_result(
             self.request_send_coins(
@@ -575,9 +946,10 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
                 config,
             )
             .await
-            .map(|(tx, fees)| ComposedTransaction {
+            .map(|(tx, fees, reused_addresses)| ComposedTransaction {
                 hex: HexEncoded::new(tx).to_string(),
                 fees,
+                reused_addresses: reused_addresses.into_iter().map(RpcAddress::from).collect(),
             }),
         )
     }
@@ -600,21 +972,36 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         decommission_address: RpcAddress<Destination>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
-        rpc::handle_result(
-            self.create_stake_pool(
-                account_arg.index::<N>()?,
-                amount,
-                cost_per_block,
-                margin_ratio_per_thousand,
-                decommission_address,
-                config,
-            )
-            .await
-            .map(NewTransaction::new),
+        let params = serde_json::json!({
+            "account": account_arg,
+            "amount": amount,
+            "cost_per_block": cost_per_block,
+            "margin_ratio_per_thousand": margin_ratio_per_thousand,
+            "decommission_address": decommission_address,
+        });
+        self.audit(
+            "staking_create_pool",
+            params,
+            self.handle_spending_result(
+                self.create_stake_pool(
+                    account_arg.index::<N>()?,
+                    amount,
+                    cost_per_block,
+                    margin_ratio_per_thousand,
+                    decommission_address,
+                    config,
+                )
+                .await
+                .map(NewTransaction::new),
+            ),
         )
     }
 
@@ -625,19 +1012,52 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         output_address: Option<RpcAddress<Destination>>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
+        };
+        let params = serde_json::json!({
+            "account": account_arg,
+            "pool_id": pool_id,
+            "output_address": output_address,
+        });
+        self.audit(
+            "staking_decommission_pool",
+            params,
+            rpc::handle_result(
+                self.decommission_stake_pool(
+                    account_arg.index::<N>()?,
+                    pool_id,
+                    output_address,
+                    config,
+                )
+                .await
+                .map(NewTransaction::new),
+            ),
+        )
+    }
+
+    async fn create_decommission_pool_transaction(
+        &self,
+        account_arg: AccountArg,
+        pool_id: RpcAddress<PoolId>,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<DecommissionPoolTransaction> {
+        options.ensure_no_idempotency_key::<N>()?;
+        let config = ControllerConfig {
+            in_top_x_mb: options.in_top_x_mb(),
+            broadcast_to_mempool: false,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
         rpc::handle_result(
-            self.decommission_stake_pool(
-                account_arg.index::<N>()?,
-                pool_id,
-                output_address,
-                config,
-            )
-            .await
-            .map(NewTransaction::new),
+            self.create_decommission_pool_transaction(account_arg.index::<N>()?, pool_id, config)
+                .await,
         )
     }
 
@@ -648,9 +1068,13 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         output_address: Option<RpcAddress<Destination>>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<HexEncoded<PartiallySignedTransaction>> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
         rpc::handle_result(
             self.decommission_stake_pool_request(
@@ -671,17 +1095,69 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         pool_id: RpcAddress<PoolId>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewDelegation> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
-        rpc::handle_result(
-            self.create_delegation(account_arg.index::<N>()?, address, pool_id, config)
+        let params = serde_json::json!({
+            "account": account_arg,
+            "address": address,
+            "pool_id": pool_id,
+        });
+        self.audit(
+            "delegation_create",
+            params,
+            rpc::handle_result(
+                self.create_delegation(account_arg.index::<N>()?, address, pool_id, config)
+                    .await
+                    .map(|(tx, delegation_id)| NewDelegation {
+                        tx_id: tx.transaction().get_id(),
+                        delegation_id,
+                    }),
+            ),
+        )
+    }
+
+    async fn create_delegation_and_stake(
+        &self,
+        account_arg: AccountArg,
+        pool_id: RpcAddress<PoolId>,
+        amount: RpcAmountIn,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<NewDelegation> {
+        options.ensure_no_idempotency_key::<N>()?;
+        let config = ControllerConfig {
+            in_top_x_mb: options.in_top_x_mb(),
+            broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
+        };
+        let params = serde_json::json!({
+            "account": account_arg,
+            "pool_id": pool_id,
+            "amount": amount,
+        });
+        self.audit(
+            "delegation_create_and_stake",
+            params,
+            rpc::handle_result(
+                self.create_delegation_and_stake(
+                    account_arg.index::<N>()?,
+                    pool_id,
+                    amount,
+                    config,
+                )
                 .await
                 .map(|(tx, delegation_id)| NewDelegation {
                     tx_id: tx.transaction().get_id(),
                     delegation_id,
                 }),
+            ),
         )
     }
 
@@ -692,14 +1168,27 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         delegation_id: RpcAddress<DelegationId>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
-        rpc::handle_result(
-            self.delegate_staking(account_arg.index::<N>()?, amount, delegation_id, config)
-                .await
-                .map(NewTransaction::new),
+        let params = serde_json::json!({
+            "account": account_arg,
+            "amount": amount,
+            "delegation_id": delegation_id,
+        });
+        self.audit(
+            "delegation_stake",
+            params,
+            self.handle_spending_result(
+                self.delegate_staking(account_arg.index::<N>()?, amount, delegation_id, config)
+                    .await
+                    .map(NewTransaction::new),
+            ),
         )
     }
 
@@ -711,35 +1200,66 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         delegation_id: RpcAddress<DelegationId>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
-        rpc::handle_result(
-            self.withdraw_from_delegation(
-                account_arg.index::<N>()?,
-                address,
-                amount,
-                delegation_id,
-                config,
-            )
-            .await
-            .map(NewTransaction::new),
+        let params = serde_json::json!({
+            "account": account_arg,
+            "address": address,
+            "amount": amount,
+            "delegation_id": delegation_id,
+        });
+        self.audit(
+            "delegation_withdraw",
+            params,
+            self.handle_spending_result(
+                self.withdraw_from_delegation(
+                    account_arg.index::<N>()?,
+                    address,
+                    amount,
+                    delegation_id,
+                    config,
+                )
+                .await
+                .map(NewTransaction::new),
+            ),
         )
     }
 
     async fn start_staking(&self, account_arg: AccountArg) -> rpc::RpcResult<()> {
-        rpc::handle_result(self.start_staking(account_arg.index::<N>()?).await)
+        let params = serde_json::json!({ "account": account_arg });
+        self.audit(
+            "staking_start",
+            params,
+            rpc::handle_result(self.start_staking(account_arg.index::<N>()?).await),
+        )
     }
 
     async fn stop_staking(&self, account_arg: AccountArg) -> rpc::RpcResult<()> {
-        rpc::handle_result(self.stop_staking(account_arg.index::<N>()?).await)
+        let params = serde_json::json!({ "account": account_arg });
+        self.audit(
+            "staking_stop",
+            params,
+            rpc::handle_result(self.stop_staking(account_arg.index::<N>()?).await),
+        )
     }
 
     async fn staking_status(&self, account_arg: AccountArg) -> rpc::RpcResult<StakingStatus> {
         rpc::handle_result(self.staking_status(account_arg.index::<N>()?).await)
     }
 
+    async fn staking_pools_status(
+        &self,
+        account_arg: AccountArg,
+    ) -> rpc::RpcResult<AccountStakingStatus> {
+        rpc::handle_result(self.staking_pools_status(account_arg.index::<N>()?).await)
+    }
+
     async fn list_pools(&self, account_arg: AccountArg) -> rpc::RpcResult<Vec<PoolInfo>> {
         rpc::handle_result(self.list_staking_pools(account_arg.index::<N>()?).await)
     }
@@ -772,9 +1292,13 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         metadata: NftMetadata,
         options: TransactionOptions,
     ) -> rpc::RpcResult<RpcTokenId> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
 
         rpc::handle_result(
@@ -795,9 +1319,13 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         metadata: TokenMetadata,
         options: TransactionOptions,
     ) -> rpc::RpcResult<RpcTokenId> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
 
         let token_supply = metadata.token_supply::<N>()?;
@@ -824,9 +1352,13 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         address: RpcAddress<Destination>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
 
         rpc::handle_result(
@@ -842,9 +1374,13 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         metadata_uri: RpcHexString,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
 
         rpc::handle_result(
@@ -866,9 +1402,13 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         amount: RpcAmountIn,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
 
         rpc::handle_result(
@@ -884,9 +1424,13 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         amount: RpcAmountIn,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
 
         rpc::handle_result(
@@ -900,9 +1444,13 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         token_id: RpcAddress<TokenId>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
 
         rpc::handle_result(
@@ -917,9 +1465,13 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         is_unfreezable: bool,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
 
         let is_unfreezable = if is_unfreezable {
@@ -940,9 +1492,13 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         token_id: RpcAddress<TokenId>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
 
         rpc::handle_result(self.unfreeze_token(account_arg.index::<N>()?, token_id, config).await)
@@ -959,11 +1515,27 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: options.idempotent_request::<N>(
+                "send_tokens",
+                &(&account_arg, &token_id, &address, &amount),
+            )?,
         };
 
-        rpc::handle_result(
-            self.send_tokens(account_arg.index::<N>()?, token_id, address, amount, config)
-                .await,
+        let params = serde_json::json!({
+            "account": account_arg,
+            "token_id": token_id,
+            "address": address,
+            "amount": amount,
+        });
+        self.audit(
+            "token_send",
+            params,
+            self.handle_spending_result(
+                self.send_tokens(account_arg.index::<N>()?, token_id, address, amount, config)
+                    .await,
+            ),
         )
     }
 
@@ -975,9 +1547,13 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         outputs: Vec<GenericTokenTransfer>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<SendTokensFromMultisigAddressResult> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
         rpc::handle_result(
             self.make_tx_to_send_tokens_from_multisig_address(
@@ -1004,9 +1580,13 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         data: RpcHexString,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
 
         rpc::handle_result(
@@ -1022,12 +1602,16 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         htlc: RpcHashedTimelockContract,
         options: TransactionOptions,
     ) -> rpc::RpcResult<HexEncoded<SignedTransaction>> {
+        options.ensure_no_idempotency_key::<N>()?;
         let config = ControllerConfig {
             in_top_x_mb: options.in_top_x_mb(),
             broadcast_to_mempool: true,
+            utxo_selection_strategy: UtxoSelectionStrategy::default(),
+            spend_pending_utxos: options.spend_pending_utxos(),
+            idempotent_request: None,
         };
 
-        rpc::handle_result(
+        self.handle_spending_result(
             self.create_htlc_transaction(account_arg.index::<N>()?, amount, token_id, htlc, config)
                 .await
                 .map(HexEncoded::new),
@@ -1045,30 +1629,34 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         )
     }
 
+    async fn list_all_staking_pools(&self) -> rpc::RpcResult<Vec<RpcStakePoolInfo>> {
+        Self::handle_node_result(self.list_all_staking_pools().await)
+    }
+
     async fn node_version(&self) -> rpc::RpcResult<NodeVersion> {
-        rpc::handle_result(self.node_version().await.map(|version| NodeVersion { version }))
+        Self::handle_node_result(self.node_version().await.map(|version| NodeVersion { version }))
     }
 
     async fn node_shutdown(&self) -> rpc::RpcResult<()> {
-        rpc::handle_result(self.node_shutdown().await)
+        Self::handle_node_result(self.node_shutdown().await)
     }
 
     async fn node_enable_networking(&self, enable: bool) -> rpc::RpcResult<()> {
-        rpc::handle_result(self.node_enable_networking(enable).await)
+        Self::handle_node_result(self.node_enable_networking(enable).await)
     }
 
     async fn connect_to_peer(&self, address: String) -> rpc::RpcResult<()> {
         let address =
             IpOrSocketAddress::from_str(&address).map_err(|_| RpcError::<N>::InvalidIpAddress)?;
-        rpc::handle_result(self.connect_to_peer(address).await)
+        Self::handle_node_result(self.connect_to_peer(address).await)
     }
 
     async fn disconnect_peer(&self, peer_id: u64) -> rpc::RpcResult<()> {
-        rpc::handle_result(self.disconnect_peer(PeerId::from_u64(peer_id)).await)
+        Self::handle_node_result(self.disconnect_peer(PeerId::from_u64(peer_id)).await)
     }
 
     async fn list_banned(&self) -> rpc::RpcResult<Vec<(BannableAddress, Time)>> {
-        rpc::handle_result(self.list_banned().await)
+        Self::handle_node_result(self.list_banned().await)
     }
 
     async fn ban_address(
@@ -1076,47 +1664,54 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         address: BannableAddress,
         duration: Duration,
     ) -> rpc::RpcResult<()> {
-        rpc::handle_result(self.ban_address(address, duration).await)
+        Self::handle_node_result(self.ban_address(address, duration).await)
     }
 
     async fn unban_address(&self, address: BannableAddress) -> rpc::RpcResult<()> {
-        rpc::handle_result(self.unban_address(address).await)
+        Self::handle_node_result(self.unban_address(address).await)
     }
 
     async fn list_discouraged(&self) -> rpc::RpcResult<Vec<(BannableAddress, Time)>> {
-        rpc::handle_result(self.list_discouraged().await)
+        Self::handle_node_result(self.list_discouraged().await)
     }
 
     async fn peer_count(&self) -> rpc::RpcResult<usize> {
-        rpc::handle_result(self.peer_count().await)
+        Self::handle_node_result(self.peer_count().await)
+    }
+
+    async fn node_connection_status(&self) -> rpc::RpcResult<NodeConnectionStatus> {
+        Self::handle_node_result(self.node_connection_status().await)
     }
 
     async fn connected_peers(&self) -> rpc::RpcResult<Vec<ConnectedPeer>> {
-        rpc::handle_result(self.connected_peers().await)
+        Self::handle_node_result(self.connected_peers().await)
     }
 
     async fn reserved_peers(&self) -> rpc::RpcResult<Vec<SocketAddress>> {
-        rpc::handle_result(self.reserved_peers().await)
+        Self::handle_node_result(self.reserved_peers().await)
     }
 
     async fn add_reserved_peer(&self, address: String) -> rpc::RpcResult<()> {
         let address =
             IpOrSocketAddress::from_str(&address).map_err(|_| RpcError::<N>::InvalidIpAddress)?;
-        rpc::handle_result(self.add_reserved_peer(address).await)
+        Self::handle_node_result(self.add_reserved_peer(address).await)
     }
 
     async fn remove_reserved_peer(&self, address: String) -> rpc::RpcResult<()> {
         let address =
             IpOrSocketAddress::from_str(&address).map_err(|_| RpcError::<N>::InvalidIpAddress)?;
-        rpc::handle_result(self.remove_reserved_peer(address).await)
+        Self::handle_node_result(self.remove_reserved_peer(address).await)
     }
 
     async fn submit_block(&self, block: HexEncoded<Block>) -> rpc::RpcResult<()> {
-        rpc::handle_result(self.submit_block(block).await)
+        match self.submit_block(block).await {
+            Err(RpcError::BlockRejected(err)) => Err(rpc::error_with_data(&err, &err)),
+            res => rpc::handle_result(res),
+        }
     }
 
     async fn chainstate_info(&self) -> rpc::RpcResult<ChainInfo> {
-        rpc::handle_result(self.chainstate_info().await)
+        Self::handle_node_result(self.chainstate_info().await)
     }
 
     async fn abandon_transaction(
@@ -1129,6 +1724,18 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         )
     }
 
+    async fn set_transaction_label(
+        &self,
+        account_arg: AccountArg,
+        transaction_id: HexEncoded<Id<Transaction>>,
+        label: Option<String>,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(
+            self.set_transaction_label(account_arg.index::<N>()?, transaction_id.take(), label)
+                .await,
+        )
+    }
+
     async fn list_pending_transactions(
         &self,
         account_arg: AccountArg,
@@ -1189,6 +1796,17 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         )
     }
 
+    async fn verify_transaction_inclusion(
+        &self,
+        account_arg: AccountArg,
+        transaction_id: Id<Transaction>,
+    ) -> rpc::RpcResult<InclusionStatus> {
+        rpc::handle_result(
+            self.verify_transaction_inclusion(account_arg.index::<N>()?, transaction_id)
+                .await,
+        )
+    }
+
     async fn compose_transaction(
         &self,
         inputs: Vec<RpcUtxoOutpoint>,
@@ -1199,26 +1817,34 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         rpc::handle_result(
             self.compose_transaction(inputs, outputs, htlc_secrets, only_transaction)
                 .await
-                .map(|(tx, fees)| ComposedTransaction {
+                .map(|(tx, fees, reused_addresses)| ComposedTransaction {
                     hex: tx.to_hex(),
                     fees,
+                    reused_addresses: reused_addresses.into_iter().map(RpcAddress::from).collect(),
                 }),
         )
     }
 
     async fn node_best_block_id(&self) -> rpc::RpcResult<Id<GenBlock>> {
-        rpc::handle_result(self.node_best_block_id().await)
+        Self::handle_node_result(self.node_best_block_id().await)
     }
 
     async fn node_best_block_height(&self) -> rpc::RpcResult<BlockHeight> {
-        rpc::handle_result(self.node_best_block_height().await)
+        Self::handle_node_result(self.node_best_block_height().await)
     }
 
     async fn node_block_id(
         &self,
         block_height: BlockHeight,
     ) -> rpc::RpcResult<Option<Id<GenBlock>>> {
-        rpc::handle_result(self.node_block_id(block_height).await)
+        Self::handle_node_result(self.node_block_id(block_height).await)
+    }
+
+    async fn node_verify_proof_of_reserves(
+        &self,
+        path: String,
+    ) -> rpc::RpcResult<ProofOfReservesReport> {
+        Self::handle_node_result(self.node_verify_proof_of_reserves(path.into()).await)
     }
 
     async fn node_generate_block(
@@ -1263,7 +1889,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
     }
 
     async fn node_block(&self, block_id: Id<Block>) -> rpc::RpcResult<Option<HexEncoded<Block>>> {
-        rpc::handle_result(
+        Self::handle_node_result(
             self.get_node_block(block_id)
                 .await
                 .map(|block_opt| block_opt.map(HexEncoded::new)),
@@ -16,7 +16,11 @@
 //! Types supporting the RPC interface
 
 use common::{
-    address::{pubkeyhash::PublicKeyHash, Address, AddressError},
+    address::{
+        payment_uri::{PaymentUri, PaymentUriError},
+        pubkeyhash::PublicKeyHash,
+        Address, AddressError,
+    },
     chain::{
         block::timestamp::BlockTimestamp,
         classic_multisig::ClassicMultisigChallengeError,
@@ -24,10 +28,10 @@ use common::{
         signature::DestinationSigError,
         timelock::OutputTimeLock,
         tokens::{self, IsTokenFreezable, Metadata, TokenCreator, TokenId},
-        ChainConfig, DelegationId, Destination, PoolId, SignedTransaction, Transaction, TxOutput,
-        UtxoOutPoint,
+        ChainConfig, DelegationId, Destination, GenBlock, PoolId, SignedTransaction, Transaction,
+        TxOutput, UtxoOutPoint,
     },
-    primitives::{per_thousand::PerThousand, Amount, BlockHeight, Id, Idable},
+    primitives::{id::hash_encoded, per_thousand::PerThousand, Amount, BlockHeight, Id, Idable},
 };
 use crypto::{
     key::{
@@ -40,8 +44,10 @@ use rpc::description::HasValueHint;
 use wallet::account::PoolData;
 
 pub use chainstate::{
-    rpc::{RpcSignedTransaction, RpcTxOutput, RpcUtxoOutpoint},
-    ChainInfo,
+    rpc::{
+        BlockSubmissionError, RpcSignedTransaction, RpcStakePoolInfo, RpcTxOutput, RpcUtxoOutpoint,
+    },
+    ChainInfo, ProofOfReservesReport,
 };
 pub use common::{
     address::RpcAddress,
@@ -52,11 +58,23 @@ pub use rpc::types::{RpcHexString, RpcString};
 pub use serde_json::Value as JsonValue;
 pub use serialization::hex_encoded::HexEncoded;
 pub use wallet_controller::types::{
-    Balances, BlockInfo, InspectTransaction, SignatureStats, ValidatedSignatures,
+    Balances, BalancesByConfirmations, BlockInfo, DuplicateOutputs, InclusionStatus,
+    InspectTransaction, SendResult, SendToManyResult, SignatureStats, ValidatedSignatures,
+    WalletVersionInfo,
+};
+use wallet_controller::{types::PoolStakingStatus, UtxoState, UtxoType};
+pub use wallet_controller::{
+    ControllerConfig, IdempotentRequest, NodeInterface, UtxoSelectionStrategy,
+};
+pub use wallet_types::spending_policy::SpendingRule;
+use wallet_types::{
+    address_set::ImportedAddressSet,
+    idempotency::{IdempotencyKey, IdempotencyKeyError},
+    keys::DescriptorTypeError,
+    label::{Label, LabelError, SearchMatch},
+    signature_status::SignatureStatus,
+    spending_policy::{SpendingPolicy, SpendingPolicyViolation},
 };
-pub use wallet_controller::{ControllerConfig, NodeInterface};
-use wallet_controller::{UtxoState, UtxoType};
-use wallet_types::signature_status::SignatureStatus;
 
 use crate::service::SubmitError;
 
@@ -71,6 +89,9 @@ pub enum RpcError<N: NodeInterface> {
     #[error("Invalid address")]
     InvalidAddress,
 
+    #[error("{0}")]
+    InvalidPaymentUri(#[from] PaymentUriError),
+
     // Same as InvalidAddress, but for cases when it's not clear which address is invalid.
     #[error("Invalid address: {0}")]
     InvalidAddressWithAddr(String),
@@ -102,6 +123,9 @@ pub enum RpcError<N: NodeInterface> {
     #[error("RPC error: {0}")]
     RpcError(N::Error),
 
+    #[error("{0}")]
+    BlockRejected(BlockSubmissionError),
+
     #[error("No wallet opened")]
     NoWalletOpened,
 
@@ -151,11 +175,35 @@ pub enum RpcError<N: NodeInterface> {
 
     #[error("Invalid HTLC secret hash")]
     InvalidHtlcSecretHash,
+
+    #[error("Invalid idempotency key: {0}")]
+    InvalidIdempotencyKey(#[from] IdempotencyKeyError),
+
+    #[error("Idempotency keys are not supported by this method")]
+    IdempotencyKeyNotSupported,
+
+    #[error("Invalid label: {0}")]
+    InvalidLabel(#[from] LabelError),
+
+    #[error("{0}")]
+    InvalidDescriptorType(#[from] DescriptorTypeError),
+
+    #[error("Invalid number of addresses to export")]
+    InvalidAddressCount,
+
+    #[error("Invalid admin password")]
+    InvalidAdminPassword,
+
+    #[error("Spending policy changes are disabled, no admin password was configured")]
+    AdminMethodsDisabled,
 }
 
 impl<N: NodeInterface> From<RpcError<N>> for rpc::Error {
     fn from(e: RpcError<N>) -> Self {
-        Self::owned::<()>(-1, e.to_string(), None)
+        match e {
+            RpcError::RpcError(e) => e.into(),
+            e => Self::owned::<()>(-1, e.to_string(), None),
+        }
     }
 }
 
@@ -189,6 +237,45 @@ impl AddressInfo {
     }
 }
 
+#[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct NewPaymentUri {
+    pub uri: String,
+    pub address: String,
+    pub index: String,
+}
+
+impl NewPaymentUri {
+    pub fn new(child_number: ChildNumber, uri: PaymentUri) -> Self {
+        Self {
+            address: uri.address().to_string(),
+            uri: uri.to_string(),
+            index: child_number.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct DecodedPaymentUri {
+    pub address: RpcAddress<Destination>,
+    pub amount: Option<RpcAmountOut>,
+    pub label: Option<String>,
+    pub message: Option<String>,
+}
+
+impl DecodedPaymentUri {
+    pub fn new(uri: PaymentUri, chain_config: &ChainConfig) -> Self {
+        let decimals = chain_config.coin_decimals();
+        Self {
+            address: uri.address().clone().into(),
+            amount: uri
+                .amount()
+                .map(|amount| RpcAmountOut::from_amount_no_padding(amount, decimals)),
+            label: uri.label().map(str::to_owned),
+            message: uri.message().map(str::to_owned),
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
 #[serde(tag = "type", content = "content")]
 pub enum RpcStandaloneAddressDetails {
@@ -264,14 +351,49 @@ pub struct AddressWithUsageInfo {
     pub address: RpcAddress<Destination>,
     pub index: String,
     pub used: bool,
+    pub label: Option<String>,
 }
 
 impl AddressWithUsageInfo {
-    pub fn new(child_number: ChildNumber, address: Address<Destination>, used: bool) -> Self {
+    pub fn new(
+        child_number: ChildNumber,
+        address: Address<Destination>,
+        used: bool,
+        label: Option<String>,
+    ) -> Self {
         Self {
             address: address.into(),
             index: child_number.to_string(),
             used,
+            label,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+#[serde(tag = "type", content = "content")]
+pub enum RpcSearchMatch {
+    Transaction {
+        tx_id: Id<Transaction>,
+        label: Option<String>,
+    },
+    Address {
+        address: RpcAddress<Destination>,
+        label: Option<String>,
+    },
+}
+
+impl RpcSearchMatch {
+    pub fn new(search_match: SearchMatch, chain_config: &ChainConfig) -> Self {
+        match search_match {
+            SearchMatch::Transaction { id, label } => Self::Transaction {
+                tx_id: id,
+                label: label.map(Label::into_string),
+            },
+            SearchMatch::Address { address, label } => Self::Address {
+                address: RpcAddress::new(chain_config, address).expect("addressable"),
+                label: label.map(Label::into_string),
+            },
         }
     }
 }
@@ -299,6 +421,54 @@ pub struct LegacyVrfPublicKeyInfo {
     pub vrf_public_key: String,
 }
 
+#[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct XpubInfo {
+    /// The account's extended public key, serialized according to BIP32
+    pub xpub: String,
+    /// The derivation path of the account's key from the wallet's root key
+    pub derivation_path: String,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct ExportedAddressSetInfo {
+    /// Hex-encoded fingerprint of the account's public key
+    pub account_fingerprint: String,
+    /// The number of the exported receive addresses, the same number of change addresses
+    /// is exported too
+    pub count: u32,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct ProofOfReservesInfo {
+    /// The block the proof was made at, the signatures are only valid for this block
+    pub block_id: Id<GenBlock>,
+    /// The number of the utxos included in the proof
+    pub utxo_count: usize,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct ImportedAddressSetInfo {
+    /// Hex-encoded fingerprint of the public key of the account the addresses belong to
+    pub account_fingerprint: String,
+    /// The number of the addresses that weren't watched before
+    pub added_addresses: usize,
+    /// The number of the imported receive addresses that haven't received funds yet
+    pub unused_receive_addresses: usize,
+    /// Whether few unused receive addresses remain, so more should be exported
+    pub low_on_unused_addresses: bool,
+}
+
+impl From<ImportedAddressSet> for ImportedAddressSetInfo {
+    fn from(imported: ImportedAddressSet) -> Self {
+        Self {
+            account_fingerprint: imported.account_fingerprint.to_string(),
+            added_addresses: imported.added_addresses,
+            unused_receive_addresses: imported.unused_receive_addresses,
+            low_on_unused_addresses: imported.is_low_on_unused_addresses(),
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
 pub struct VrfPublicKeyInfo {
     pub vrf_public_key: RpcAddress<VRFPublicKey>,
@@ -316,7 +486,7 @@ impl VrfPublicKeyInfo {
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct UtxoInfo {
     pub outpoint: RpcUtxoOutpoint,
     pub output: RpcTxOutput,
@@ -351,6 +521,12 @@ impl NewAccountInfo {
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
 pub struct TransactionOptions {
     pub in_top_x_mb: Option<usize>,
+    /// A client-supplied key identifying the request, up to 128 bytes long. Retrying a request
+    /// with the same key returns the transaction created by the first attempt instead of
+    /// creating a new one. Only supported by the methods sending coins or tokens.
+    pub idempotency_key: Option<String>,
+    /// Also spend the outputs that haven't reached the wallet's confirmation threshold yet
+    pub spend_pending_utxos: Option<bool>,
 }
 
 impl TransactionOptions {
@@ -358,12 +534,50 @@ impl TransactionOptions {
 
     pub fn from_controller_config(config: &ControllerConfig) -> Self {
         let in_top_x_mb = Some(config.in_top_x_mb);
-        Self { in_top_x_mb }
+        Self {
+            in_top_x_mb,
+            idempotency_key: None,
+            spend_pending_utxos: Some(config.spend_pending_utxos),
+        }
     }
 
     pub fn in_top_x_mb(&self) -> usize {
         self.in_top_x_mb.unwrap_or(Self::DEFAULT_IN_TOP_X_MB)
     }
+
+    pub fn spend_pending_utxos(&self) -> bool {
+        self.spend_pending_utxos.unwrap_or(false)
+    }
+
+    pub fn idempotency_key(&self) -> Result<Option<IdempotencyKey>, IdempotencyKeyError> {
+        self.idempotency_key.clone().map(IdempotencyKey::new).transpose()
+    }
+
+    /// Identify the request by its idempotency key, if one is given, and the hash of the method
+    /// name and its parameters
+    pub fn idempotent_request<N: NodeInterface>(
+        &self,
+        method: &str,
+        params: &impl serde::Serialize,
+    ) -> Result<Option<IdempotentRequest>, RpcError<N>> {
+        let request = self.idempotency_key()?.map(|key| {
+            let params = serde_json::to_string(&(method, params))
+                .expect("RPC parameters to be serializable");
+            IdempotentRequest {
+                key,
+                request_hash: hash_encoded(&params),
+            }
+        });
+        Ok(request)
+    }
+
+    /// Fail if an idempotency key is given to a method that doesn't support it
+    pub fn ensure_no_idempotency_key<N: NodeInterface>(&self) -> Result<(), RpcError<N>> {
+        match self.idempotency_key {
+            Some(_) => Err(RpcError::IdempotencyKeyNotSupported),
+            None => Ok(()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
@@ -418,6 +632,34 @@ pub struct NewDelegation {
     pub delegation_id: RpcAddress<DelegationId>,
 }
 
+/// A signed transaction that decommissions a pool, not broadcast yet
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct DecommissionPoolTransaction {
+    pub transaction: HexEncoded<SignedTransaction>,
+    /// The destination receiving the pledge and the staking proceeds
+    pub decommission_key: RpcAddress<Destination>,
+    /// The balance delegated to the pool by its delegators
+    pub delegated_balance: RpcAmountOut,
+}
+
+impl DecommissionPoolTransaction {
+    pub fn new(
+        tx: SignedTransaction,
+        decommission_key: Destination,
+        delegated_balance: Amount,
+        chain_config: &ChainConfig,
+    ) -> Self {
+        Self {
+            transaction: HexEncoded::new(tx),
+            decommission_key: RpcAddress::new(chain_config, decommission_key).expect("addressable"),
+            delegated_balance: RpcAmountOut::from_amount_no_padding(
+                delegated_balance,
+                chain_config.coin_decimals(),
+            ),
+        }
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
 pub struct DelegationInfo {
     pub delegation_id: RpcAddress<DelegationId>,
@@ -610,11 +852,84 @@ impl NewTransaction {
     }
 }
 
+/// A new transaction paying to one or more addresses
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct SendTransaction {
+    pub tx_id: Id<Transaction>,
+    /// The addresses of this wallet that had already received funds before,
+    /// paying them again reuses an address
+    pub reused_addresses: Vec<RpcAddress<Destination>>,
+}
+
+impl SendTransaction {
+    pub fn new(result: SendResult) -> Self {
+        let SendResult {
+            tx,
+            reused_addresses,
+        } = result;
+
+        Self {
+            tx_id: tx.transaction().get_id(),
+            reused_addresses: reused_addresses.into_iter().map(RpcAddress::from).collect(),
+        }
+    }
+}
+
+/// A payment of coins, or of a token if the token id is specified, to an address
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct SendToManyOutput {
+    pub address: RpcAddress<Destination>,
+    pub amount: RpcAmountIn,
+    pub token_id: Option<RpcAddress<TokenId>>,
+}
+
+/// A new transaction paying to many addresses
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct SendToManyTransaction {
+    pub tx_id: Id<Transaction>,
+    /// The total fees paid by the transaction
+    pub fees: Balances,
+    /// The coin fee divided between the recipients
+    pub fee_per_recipient: RpcAmountOut,
+    /// The addresses that were paid more than once in the same currency,
+    /// if their payments were merged into a single output
+    pub merged_addresses: Vec<RpcAddress<Destination>>,
+    /// The addresses of this wallet that had already received funds before,
+    /// paying them again reuses an address
+    pub reused_addresses: Vec<RpcAddress<Destination>>,
+}
+
+impl SendToManyTransaction {
+    pub fn new(result: SendToManyResult) -> Self {
+        let SendToManyResult {
+            tx,
+            fees,
+            fee_per_recipient,
+            merged_addresses,
+            reused_addresses,
+        } = result;
+
+        Self {
+            tx_id: tx.transaction().get_id(),
+            fees,
+            fee_per_recipient,
+            merged_addresses: merged_addresses.into_iter().map(RpcAddress::from).collect(),
+            reused_addresses: reused_addresses.into_iter().map(RpcAddress::from).collect(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
 pub struct NodeVersion {
     pub version: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct NodeConnectionStatus {
+    pub peer_count: u32,
+    pub is_synced: bool,
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
 pub enum StakingStatus {
     Staking,
@@ -630,6 +945,43 @@ impl StakingStatus {
         }
     }
 }
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct StakingPoolStatus {
+    pub pool_id: RpcAddress<PoolId>,
+    pub balance: RpcAmountOut,
+    pub blocks_produced_last_24h: u64,
+    /// The number of blocks the pool was expected to produce given its share of the stake
+    pub expected_blocks_last_24h: u64,
+    pub is_staking: bool,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct AccountStakingStatus {
+    pub pools: Vec<StakingPoolStatus>,
+}
+
+impl AccountStakingStatus {
+    pub fn new(
+        pools: Vec<PoolStakingStatus>,
+        is_staking: bool,
+        chain_config: &ChainConfig,
+    ) -> Self {
+        let decimals = chain_config.coin_decimals();
+        let pools = pools
+            .into_iter()
+            .map(|pool| StakingPoolStatus {
+                pool_id: RpcAddress::new(chain_config, pool.pool_id).expect("addressable"),
+                balance: RpcAmountOut::from_amount_no_padding(pool.balance, decimals),
+                blocks_produced_last_24h: pool.blocks_produced,
+                expected_blocks_last_24h: pool.expected_blocks,
+                is_staking,
+            })
+            .collect();
+
+        Self { pools }
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
 #[serde(tag = "type", content = "content")]
 pub enum MnemonicInfo {
@@ -664,6 +1016,9 @@ impl From<crate::CreatedWallet> for CreatedWallet {
 pub struct ComposedTransaction {
     pub hex: String,
     pub fees: Balances,
+    /// The addresses of this wallet that had already received funds before,
+    /// paying them again reuses an address
+    pub reused_addresses: Vec<RpcAddress<Destination>>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize, HasValueHint)]
@@ -771,6 +1126,75 @@ pub struct RpcHashedTimelockContract {
     pub refund_timelock: OutputTimeLock,
 }
 
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct RpcSpendingPolicy {
+    /// The maximum amount of coins that a single transaction can send
+    pub max_per_tx: Option<RpcAmountOut>,
+    /// The maximum amount of coins that can be sent within 24 hours
+    pub daily_cap: Option<RpcAmountOut>,
+    /// If set, coins and tokens can only be sent to these addresses
+    pub allowed_addresses: Option<Vec<RpcAddress<Destination>>>,
+    /// The coins sent within the last 24 hours
+    pub sent_in_last_24h: RpcAmountOut,
+    /// The coins that can still be sent under the daily cap
+    pub daily_remaining: Option<RpcAmountOut>,
+}
+
+impl RpcSpendingPolicy {
+    pub fn new(
+        policy: SpendingPolicy,
+        sent_in_period: Amount,
+        chain_config: &ChainConfig,
+    ) -> Result<Self, AddressError> {
+        let decimals = chain_config.coin_decimals();
+        let allowed_addresses = policy
+            .allowed_destinations
+            .map(|destinations| {
+                destinations
+                    .into_iter()
+                    .map(|dest| RpcAddress::new(chain_config, dest))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+        let daily_remaining =
+            policy.daily_cap.map(|cap| (cap - sent_in_period).unwrap_or(Amount::ZERO));
+
+        Ok(Self {
+            max_per_tx: policy
+                .max_per_tx
+                .map(|amount| RpcAmountOut::from_amount_no_padding(amount, decimals)),
+            daily_cap: policy
+                .daily_cap
+                .map(|amount| RpcAmountOut::from_amount_no_padding(amount, decimals)),
+            allowed_addresses,
+            sent_in_last_24h: RpcAmountOut::from_amount_no_padding(sent_in_period, decimals),
+            daily_remaining: daily_remaining
+                .map(|amount| RpcAmountOut::from_amount_no_padding(amount, decimals)),
+        })
+    }
+}
+
+/// The data of the error returned when a transaction violates the spending policy of the account
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct RpcSpendingPolicyViolation {
+    /// The violated rule
+    pub rule: SpendingRule,
+    /// The amount that can still be sent under the violated rule, if it limits the amount
+    pub remaining_allowance: Option<RpcAmountOut>,
+}
+
+impl RpcSpendingPolicyViolation {
+    pub fn new(violation: &SpendingPolicyViolation, chain_config: &ChainConfig) -> Self {
+        let decimals = chain_config.coin_decimals();
+        Self {
+            rule: violation.rule(),
+            remaining_allowance: violation
+                .remaining_allowance()
+                .map(|amount| RpcAmountOut::from_amount_no_padding(amount, decimals)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -780,5 +1204,28 @@ mod test {
         let empty_obj = serde_json::Value::Object(Default::default());
         let opts = serde_json::from_value::<TransactionOptions>(empty_obj).unwrap();
         assert_eq!(opts.in_top_x_mb(), 5);
+        assert_eq!(opts.idempotency_key(), Ok(None));
+    }
+
+    #[test]
+    fn balances_output() {
+        let chain_config = common::chain::config::create_unit_test_config();
+        let token_id = TokenId::new(common::primitives::H256([0x50; 32]));
+        let balances = Balances::new(
+            RpcAmountOut::from_amount_no_padding(Amount::from_atoms(150_000_000_000), 11),
+            [(
+                RpcAddress::new(&chain_config, token_id).unwrap(),
+                RpcAmountOut::from_amount_no_padding(Amount::from_atoms(12345), 2),
+            )]
+            .into(),
+        );
+        assert_eq!(
+            serde_json::to_string(&balances).unwrap(),
+            concat!(
+                r#"{"coins":{"atoms":"150000000000","decimal":"1.5"},"#,
+                r#""tokens":{"rmltk12pg9q5zs2pg9q5zs2pg9q5zs2pg9q5zs2pg9q5zs2pg9q5zs2pgqvj94dh":"#,
+                r#"{"atoms":"12345","decimal":"123.45"}}}"#,
+            )
+        );
     }
 }
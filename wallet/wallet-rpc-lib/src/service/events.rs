@@ -41,6 +41,13 @@ pub enum Event {
         tx_id: Id<Transaction>,
     },
 
+    /// Transaction has reached the number of confirmations required by the wallet
+    TxConfirmationThresholdReached {
+        account_idx: u32,
+        tx_id: Id<Transaction>,
+        confirmations: u64,
+    },
+
     /// Added a reward from given block
     RewardAdded {
         account_idx: u32,
@@ -153,4 +160,17 @@ impl wallet::wallet_events::WalletEvents for WalletServiceEvents {
 
         self.emit(event);
     }
+
+    fn tx_confirmation_threshold_reached(
+        &self,
+        account_idx: U31,
+        tx_id: Id<Transaction>,
+        confirmations: u64,
+    ) {
+        self.emit(Event::TxConfirmationThresholdReached {
+            account_idx: account_idx.into_u32(),
+            tx_id,
+            confirmations,
+        })
+    }
 }
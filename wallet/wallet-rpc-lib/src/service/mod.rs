@@ -25,7 +25,7 @@ use utils::shallow_clone::ShallowClone;
 
 pub use events::{Event, TxState};
 pub use handle::{EventStream, SubmitError, WalletHandle};
-use wallet_controller::{ControllerConfig, NodeInterface};
+use wallet_controller::{ControllerConfig, NodeInterface, UtxoSelectionStrategy};
 pub use worker::{CreatedWallet, WalletController, WalletControllerError};
 
 use events::WalletServiceEvents;
@@ -87,6 +87,9 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletService<N> {
                 let config = ControllerConfig {
                     in_top_x_mb: 5,
                     broadcast_to_mempool: true,
+                    utxo_selection_strategy: UtxoSelectionStrategy::default(),
+                    spend_pending_utxos: false,
+                    idempotent_request: None,
                 };
                 controller.synced_controller(account_index, config).await?.start_staking()?;
             }
@@ -98,6 +101,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletService<N> {
 
         let task = worker::WalletWorker::spawn(
             controller,
+            wallet_file,
             chain_config.clone(),
             node_rpc.clone(),
             command_rx,
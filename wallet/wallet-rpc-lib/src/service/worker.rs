@@ -22,7 +22,7 @@ use tokio::{sync::mpsc, task::JoinHandle};
 use logging::log;
 use utils_networking::broadcaster::Broadcaster;
 use wallet::wallet::Mnemonic;
-use wallet_controller::{ControllerError, NodeInterface};
+use wallet_controller::{types::WalletVersionInfo, ControllerError, NodeInterface};
 use wallet_types::seed_phrase::StoreSeedPhrase;
 
 use crate::types::RpcError;
@@ -60,6 +60,8 @@ pub enum CreatedWallet {
 /// Represents the wallet worker task. It handles external commands and keeps the wallet in sync.
 pub struct WalletWorker<N> {
     controller: Option<WalletController<N>>,
+    /// The file of the open wallet
+    wallet_path: Option<PathBuf>,
     command_rx: CommandReceiver<N>,
     chain_config: Arc<ChainConfig>,
     node_rpc: N,
@@ -71,6 +73,7 @@ pub struct WalletWorker<N> {
 impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
     fn new(
         controller: Option<WalletController<N>>,
+        wallet_path: Option<PathBuf>,
         chain_config: Arc<ChainConfig>,
         node_rpc: N,
         command_rx: CommandReceiver<N>,
@@ -80,6 +83,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
         let events_bcast = Broadcaster::new();
         Self {
             controller,
+            wallet_path,
             command_rx,
             chain_config,
             node_rpc,
@@ -91,6 +95,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
 
     pub fn spawn(
         controller: Option<WalletController<N>>,
+        wallet_path: Option<PathBuf>,
         chain_config: Arc<ChainConfig>,
         node_rpc: N,
         command_rx: CommandReceiver<N>,
@@ -99,6 +104,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
     ) -> JoinHandle<()> {
         let worker = Self::new(
             controller,
+            wallet_path,
             chain_config,
             node_rpc,
             command_rx,
@@ -165,6 +171,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
     pub fn close_wallet(&mut self) -> Result<(), ControllerError<N>> {
         utils::ensure!(self.controller.is_some(), ControllerError::NoWallet);
         self.controller = None;
+        self.wallet_path = None;
         Ok(())
     }
 
@@ -181,7 +188,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
 
         let wallet = WalletController::open_wallet(
             self.chain_config.clone(),
-            wallet_path,
+            &wallet_path,
             password,
             self.node_rpc.is_cold_wallet_node(),
             force_migrate_wallet_type,
@@ -195,6 +202,39 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
         )
         .await?;
         self.controller.replace(controller);
+        self.wallet_path = Some(wallet_path);
+
+        Ok(())
+    }
+
+    pub async fn restore_wallet_from_backup(
+        &mut self,
+        backup_path: PathBuf,
+        wallet_path: PathBuf,
+        backup_password: Option<String>,
+    ) -> Result<(), ControllerError<N>> {
+        utils::ensure!(
+            self.controller.is_none(),
+            ControllerError::WalletFileAlreadyOpen
+        );
+
+        let wallet = WalletController::restore_wallet_from_backup(
+            self.chain_config.clone(),
+            backup_path,
+            &wallet_path,
+            backup_password,
+            self.node_rpc.is_cold_wallet_node(),
+        )?;
+
+        let controller = WalletController::new(
+            self.chain_config.clone(),
+            self.node_rpc.clone(),
+            wallet,
+            self.wallet_events.clone(),
+        )
+        .await?;
+        self.controller.replace(controller);
+        self.wallet_path = Some(wallet_path);
 
         Ok(())
     }
@@ -225,7 +265,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
             let info = self.node_rpc.chainstate_info().await.map_err(RpcError::RpcError)?;
             WalletController::create_wallet(
                 self.chain_config.clone(),
-                wallet_path,
+                &wallet_path,
                 mnemonic.clone(),
                 passphrase_ref,
                 whether_to_store_seed_phrase,
@@ -235,7 +275,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
         } else {
             WalletController::recover_wallet(
                 self.chain_config.clone(),
-                wallet_path,
+                &wallet_path,
                 mnemonic.clone(),
                 passphrase_ref,
                 whether_to_store_seed_phrase,
@@ -254,6 +294,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
         .map_err(RpcError::Controller)?;
 
         self.controller.replace(controller);
+        self.wallet_path = Some(wallet_path);
 
         let result = match newly_generated_mnemonic {
             true => CreatedWallet::NewlyGeneratedMnemonic(mnemonic, passphrase),
@@ -262,6 +303,11 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
         Ok(result)
     }
 
+    pub fn wallet_version(&self) -> Result<WalletVersionInfo, RpcError<N>> {
+        let controller = self.controller.as_ref().ok_or(RpcError::NoWalletOpened)?;
+        Ok(controller.version_info(self.wallet_path.as_deref())?)
+    }
+
     pub fn subscribe(&mut self) -> EventStream {
         self.events_bcast.subscribe()
     }
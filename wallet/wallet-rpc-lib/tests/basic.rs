@@ -23,13 +23,17 @@ use common::{
     primitives::{Amount, BlockHeight, Id},
 };
 use utils::{
-    make_seedable_rng, ClientT, JsonValue, Seed, Subscription, SubscriptionClientT, ACCOUNT0_ARG,
-    ACCOUNT1_ARG,
+    make_seedable_rng, ClientT, JsonValue, Rng, Seed, Subscription, SubscriptionClientT,
+    ACCOUNT0_ARG, ACCOUNT1_ARG,
 };
 use wallet_rpc_lib::{
+    audit_log::{
+        verify_audit_log, AuditEntry, AuditEvent, AuditLogError, AuditResult, BrokenEntryReason,
+    },
     types::{
         AddressInfo, Balances, BlockInfo, NewAccountInfo, NewTransaction, RpcAmountIn,
-        RpcUtxoState, TransactionOptions,
+        RpcSpendingPolicy, RpcSpendingPolicyViolation, RpcUtxoState, SpendingRule,
+        TransactionOptions, WalletVersionInfo,
     },
     TxState,
 };
@@ -168,6 +172,8 @@ async fn stake_and_send_coins_to_acct1(#[case] seed: Seed) {
         let send_to_addr = acct1_addr.address;
         let options = TransactionOptions {
             in_top_x_mb: Some(3),
+            idempotency_key: None,
+            spend_pending_utxos: None,
         };
         let params = (
             ACCOUNT0_ARG,
@@ -232,6 +238,311 @@ async fn stake_and_send_coins_to_acct1(#[case] seed: Seed) {
     tf.stop().await;
 }
 
+#[rstest]
+#[trace]
+#[case(test_utils::random::Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn send_coins_with_idempotency_key(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = utils::TestFramework::start(&mut rng).await;
+
+    let wallet_rpc = tf.rpc_client_http();
+
+    let all_utxo_states = enum_iterator::all::<RpcUtxoState>().collect::<Vec<_>>();
+    let send_to_addr: AddressInfo =
+        wallet_rpc.request("address_new", [ACCOUNT0_ARG]).await.unwrap();
+    let options = TransactionOptions {
+        in_top_x_mb: None,
+        idempotency_key: Some("payment-1".to_owned()),
+        spend_pending_utxos: None,
+    };
+    let send_params = |amount: Amount| {
+        (
+            ACCOUNT0_ARG,
+            send_to_addr.address.clone(),
+            RpcAmountIn::from_atoms(amount),
+            Vec::<UtxoOutPoint>::new(),
+            options.clone(),
+        )
+    };
+
+    let tx1: NewTransaction = wallet_rpc
+        .request("address_send", send_params(Amount::from_atoms(1000)))
+        .await
+        .unwrap();
+    let balances: Balances = wallet_rpc
+        .request("account_balance", (ACCOUNT0_ARG, &all_utxo_states))
+        .await
+        .unwrap();
+
+    // Retrying the request returns the original transaction instead of creating a new one
+    let tx2: NewTransaction = wallet_rpc
+        .request("address_send", send_params(Amount::from_atoms(1000)))
+        .await
+        .unwrap();
+    assert_eq!(tx1.tx_id, tx2.tx_id);
+    let balances_after_retry: Balances = wallet_rpc
+        .request("account_balance", (ACCOUNT0_ARG, &all_utxo_states))
+        .await
+        .unwrap();
+    assert_eq!(
+        balances_after_retry.coins().amount(),
+        balances.coins().amount()
+    );
+
+    // The key can't be reused for a different request
+    let result: Result<NewTransaction, _> =
+        wallet_rpc.request("address_send", send_params(Amount::from_atoms(2000))).await;
+    assert!(result.is_err());
+
+    // The key is rejected by the methods that don't support it
+    let result: Result<JsonValue, _> =
+        wallet_rpc.request("address_deposit_data", (ACCOUNT0_ARG, "00", options)).await;
+    assert!(result.unwrap_err().to_string().contains("not supported"));
+
+    tf.stop().await;
+}
+
+#[rstest]
+#[trace]
+#[case(test_utils::random::Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn send_coins_with_spending_policy(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = utils::TestFramework::start(&mut rng).await;
+
+    let wallet_rpc = tf.rpc_client_http();
+
+    let _: NewAccountInfo = wallet_rpc.request("account_create", Vec::<u32>::new()).await.unwrap();
+    let send_to_addr: AddressInfo =
+        wallet_rpc.request("address_new", [ACCOUNT1_ARG]).await.unwrap();
+    let send_params = |atoms: u128| {
+        (
+            ACCOUNT0_ARG,
+            send_to_addr.address.clone(),
+            RpcAmountIn::from_atoms(Amount::from_atoms(atoms)),
+            Vec::<UtxoOutPoint>::new(),
+            TransactionOptions {
+                in_top_x_mb: None,
+                idempotency_key: None,
+                spend_pending_utxos: None,
+            },
+        )
+    };
+    let policy_params = |password: &str| {
+        (
+            password.to_owned(),
+            ACCOUNT0_ARG,
+            Some(RpcAmountIn::from_atoms(Amount::from_atoms(1000))),
+            Some(RpcAmountIn::from_atoms(Amount::from_atoms(1500))),
+            None::<Vec<String>>,
+        )
+    };
+
+    // Only the admin can set the policy
+    let result: Result<(), _> =
+        wallet_rpc.request("account_set_spending_policy", policy_params("wrong")).await;
+    assert!(result.is_err());
+    let _: () = wallet_rpc
+        .request(
+            "account_set_spending_policy",
+            policy_params(utils::ADMIN_PASSWORD),
+        )
+        .await
+        .unwrap();
+
+    let _: NewTransaction = wallet_rpc.request("address_send", send_params(1000)).await.unwrap();
+
+    // The violations report the rule and the remaining allowance
+    let err = wallet_rpc
+        .request::<NewTransaction, _>("address_send", send_params(1001))
+        .await
+        .unwrap_err();
+    let violation: RpcSpendingPolicyViolation = rpc::error_data(&err).unwrap();
+    assert_eq!(violation.rule, SpendingRule::MaxPerTx);
+    assert_eq!(
+        violation.remaining_allowance.map(|amount| amount.amount()),
+        Some(Amount::from_atoms(1000))
+    );
+
+    let err = wallet_rpc
+        .request::<NewTransaction, _>("address_send", send_params(600))
+        .await
+        .unwrap_err();
+    let violation: RpcSpendingPolicyViolation = rpc::error_data(&err).unwrap();
+    assert_eq!(violation.rule, SpendingRule::DailyCap);
+    assert_eq!(
+        violation.remaining_allowance.map(|amount| amount.amount()),
+        Some(Amount::from_atoms(500))
+    );
+
+    let policy: RpcSpendingPolicy =
+        wallet_rpc.request("account_spending_policy", [ACCOUNT0_ARG]).await.unwrap();
+    assert_eq!(policy.sent_in_last_24h.amount(), Amount::from_atoms(1000));
+    assert_eq!(
+        policy.daily_remaining.map(|amount| amount.amount()),
+        Some(Amount::from_atoms(500))
+    );
+
+    tf.stop().await;
+}
+
+#[rstest]
+#[trace]
+#[case(test_utils::random::Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn wallet_version(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = utils::TestFramework::start(&mut rng).await;
+
+    let wallet_rpc = tf.rpc_client_http();
+
+    let check_version_info = |info: &WalletVersionInfo| {
+        assert_eq!(
+            info.software_version,
+            wallet::wallet::WALLET_SOFTWARE_VERSION
+        );
+        assert_eq!(
+            info.wallet_schema_version,
+            wallet::wallet::CURRENT_WALLET_VERSION
+        );
+        assert_eq!(
+            info.min_compatible_schema_version,
+            wallet::wallet::MIN_SUPPORTED_WALLET_VERSION
+        );
+        assert_eq!(info.chain_type, tf.chain_config().chain_type().name());
+    };
+
+    // The wallet opened on startup
+    let info: WalletVersionInfo =
+        wallet_rpc.request("wallet_version", Vec::<u32>::new()).await.unwrap();
+    check_version_info(&info);
+    let wallet_path = info.open_wallet_path.unwrap();
+    assert!(wallet_path.ends_with("wallet.sqlite"));
+
+    let _: () = wallet_rpc.request("wallet_close", Vec::<u32>::new()).await.unwrap();
+    let err = wallet_rpc
+        .request::<WalletVersionInfo, _>("wallet_version", Vec::<u32>::new())
+        .await
+        .unwrap_err();
+    match err {
+        rpc::ClientError::Call(err) => assert_eq!(err.code(), rpc::SERVICE_UNAVAILABLE_CODE),
+        err => panic!("unexpected error: {err}"),
+    }
+
+    // The wallet opened by the RPC call
+    let _: () = wallet_rpc
+        .request("wallet_open", (&wallet_path, None::<String>, None::<bool>))
+        .await
+        .unwrap();
+    let info: WalletVersionInfo =
+        wallet_rpc.request("wallet_version", Vec::<u32>::new()).await.unwrap();
+    check_version_info(&info);
+    assert_eq!(info.open_wallet_path, Some(wallet_path));
+
+    tf.stop().await;
+}
+
+#[rstest]
+#[trace]
+#[case(test_utils::random::Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn audit_log(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = utils::TestFramework::start(&mut rng).await;
+
+    let wallet_rpc = tf.rpc_client_http();
+
+    let send_to_addr: AddressInfo =
+        wallet_rpc.request("address_new", [ACCOUNT0_ARG]).await.unwrap();
+    let send_params = |atoms: u128| {
+        (
+            ACCOUNT0_ARG,
+            send_to_addr.address.clone(),
+            RpcAmountIn::from_atoms(Amount::from_atoms(atoms)),
+            Vec::<UtxoOutPoint>::new(),
+            TransactionOptions {
+                in_top_x_mb: None,
+                idempotency_key: None,
+                spend_pending_utxos: None,
+            },
+        )
+    };
+
+    let _: () = wallet_rpc
+        .request(
+            "account_set_spending_policy",
+            (
+                utils::ADMIN_PASSWORD,
+                ACCOUNT0_ARG,
+                Some(RpcAmountIn::from_atoms(Amount::from_atoms(1000))),
+                None::<RpcAmountIn>,
+                None::<Vec<String>>,
+            ),
+        )
+        .await
+        .unwrap();
+    let tx: NewTransaction = wallet_rpc.request("address_send", send_params(1000)).await.unwrap();
+    let result: Result<NewTransaction, _> =
+        wallet_rpc.request("address_send", send_params(1001)).await;
+    assert!(result.is_err());
+    let _: () = wallet_rpc.request("staking_start", [ACCOUNT0_ARG]).await.unwrap();
+    let _: () = wallet_rpc.request("staking_stop", [ACCOUNT0_ARG]).await.unwrap();
+
+    // The header and the calls changing the wallet state are recorded
+    let summary = verify_audit_log(&tf.audit_log_path).unwrap();
+    assert_eq!(summary.entries, 6);
+
+    let contents = std::fs::read_to_string(&tf.audit_log_path).unwrap();
+    assert!(!contents.contains(utils::ADMIN_PASSWORD));
+    let entries = contents
+        .lines()
+        .map(|line| serde_json::from_str::<AuditEntry>(line).unwrap())
+        .collect::<Vec<_>>();
+    let calls = entries
+        .iter()
+        .filter_map(|entry| match &entry.event {
+            AuditEvent::Header { .. } => None,
+            AuditEvent::Call { method, result, .. } => Some((method.as_str(), result.clone())),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(calls[0], ("account_set_spending_policy", AuditResult::Ok));
+    assert_eq!(
+        calls[1],
+        ("address_send", AuditResult::TxId(format!("{:x}", tx.tx_id)))
+    );
+    assert!(matches!(calls[2], ("address_send", AuditResult::Error(_))));
+    assert_eq!(calls[3], ("staking_start", AuditResult::Ok));
+    assert_eq!(calls[4], ("staking_stop", AuditResult::Ok));
+
+    // Changing the amount of the sent coins is detected
+    let tampered = contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            if index == 2 {
+                line.replace("\"1000\"", "\"100\"")
+            } else {
+                line.to_owned()
+            }
+        })
+        .collect::<Vec<_>>();
+    assert_ne!(tampered.join("\n") + "\n", contents);
+    std::fs::write(&tf.audit_log_path, tampered.join("\n") + "\n").unwrap();
+
+    let err = verify_audit_log(&tf.audit_log_path).unwrap_err();
+    assert!(matches!(
+        err,
+        AuditLogError::BrokenEntry {
+            line: 3,
+            reason: BrokenEntryReason::HashMismatch,
+            ..
+        }
+    ));
+
+    tf.stop().await;
+}
+
 #[rstest]
 #[trace]
 #[case(test_utils::random::Seed::from_entropy())]
@@ -252,3 +563,69 @@ async fn no_hexified_destination(#[case] seed: Seed) {
 
     tf.stop().await;
 }
+
+/// The code and the type of an error returned by a call
+fn error_code_and_type(err: rpc::ClientError) -> (i32, String) {
+    let category: rpc::ErrorCategory = rpc::error_data(&err).unwrap();
+    match err {
+        rpc::ClientError::Call(err) => (err.code(), category.error_type),
+        err => panic!("unexpected error: {err}"),
+    }
+}
+
+#[rstest]
+#[trace]
+#[case(test_utils::random::Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn node_error_codes(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = utils::TestFramework::start(&mut rng).await;
+
+    let wallet_rpc = tf.rpc_client_http();
+
+    // Unknown peer
+    let err = wallet_rpc
+        .request::<(), _>("node_disconnect_peer", [rng.gen::<u64>()])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error_code_and_type(err),
+        (rpc::NOT_FOUND_CODE, "PeerError".to_owned())
+    );
+
+    // Banned address
+    let _: () = wallet_rpc
+        .request(
+            "node_ban_peer_address",
+            ("127.0.0.1", std::time::Duration::from_secs(3600)),
+        )
+        .await
+        .unwrap();
+    let err = wallet_rpc
+        .request::<(), _>("node_connect_to_peer", ["127.0.0.1:1"])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error_code_and_type(err),
+        (
+            rpc::UNPROCESSABLE_CODE,
+            "ConnectionValidationFailed".to_owned()
+        )
+    );
+
+    // Networking disabled
+    let _: () = wallet_rpc.request("node_enable_networking", [false]).await.unwrap();
+    let err = wallet_rpc
+        .request::<(), _>("node_connect_to_peer", ["127.0.0.1:1"])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error_code_and_type(err),
+        (
+            rpc::SERVICE_UNAVAILABLE_CODE,
+            "ConnectionValidationFailed".to_owned()
+        )
+    );
+
+    tf.stop().await;
+}
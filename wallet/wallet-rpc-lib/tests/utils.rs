@@ -15,7 +15,7 @@
 
 //! Wallet RPC testing utilities
 
-use std::{sync::Arc, time::Duration};
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
 use common::{
     chain::config::{
@@ -26,7 +26,10 @@ use common::{
 use rpc::RpcAuthData;
 use test_utils::{test_dir::TestRoot, test_root};
 use wallet_controller::NodeRpcClient;
-use wallet_rpc_lib::{config::WalletServiceConfig, types::AccountArg, WalletHandle, WalletService};
+use wallet_rpc_lib::{
+    audit_log::AuditLogConfig, config::WalletServiceConfig, types::AccountArg, WalletHandle,
+    WalletService,
+};
 use wallet_test_node::{RPC_PASSWORD, RPC_USERNAME};
 
 pub use randomness::Rng;
@@ -37,6 +40,7 @@ use wallet_types::wallet_type::WalletType;
 
 pub const ACCOUNT0_ARG: AccountArg = AccountArg(0);
 pub const ACCOUNT1_ARG: AccountArg = AccountArg(1);
+pub const ADMIN_PASSWORD: &str = "admin";
 
 pub struct TestFramework {
     pub wallet_service: WalletService<NodeRpcClient>,
@@ -44,6 +48,7 @@ pub struct TestFramework {
     pub node_manager_task: subsystem::ManagerJoinHandle,
     pub test_root: TestRoot,
     pub rpc_server: rpc::Rpc,
+    pub audit_log_path: PathBuf,
 }
 
 impl TestFramework {
@@ -77,6 +82,7 @@ impl TestFramework {
 
             wallet_path
         };
+        let audit_log_path = test_root.fresh_test_dir("audit_log").as_ref().join("audit.log");
 
         // Start the node
         let (manager, node_rpc_addr) =
@@ -111,6 +117,10 @@ impl TestFramework {
             let rpc_config = wallet_rpc_lib::config::WalletRpcConfig {
                 bind_addr,
                 auth_credentials: None,
+                idempotency_key_retention:
+                    wallet_rpc_lib::config::WalletRpcConfig::DEFAULT_IDEMPOTENCY_KEY_RETENTION,
+                admin_password: Some(ADMIN_PASSWORD.to_owned()),
+                audit_log: Some(AuditLogConfig::new(audit_log_path.clone())),
             };
 
             let rpc_address = node_rpc_addr.to_string();
@@ -136,6 +146,7 @@ impl TestFramework {
             node_manager_task,
             test_root,
             rpc_server,
+            audit_log_path,
         }
     }
 
@@ -170,6 +181,7 @@ impl TestFramework {
             node_manager_task,
             test_root,
             rpc_server,
+            audit_log_path: _,
         } = self;
 
         let wallet_handle = wallet_service.handle();